@@ -0,0 +1,46 @@
+//! Benchmarks of building virtual trees with and without recycling
+//! collections of previous trees. Run them with `cargo bench --bench build`.
+
+#[macro_use]
+extern crate yew;
+
+use std::time::{Duration, Instant};
+use yew::virtual_dom::VTag;
+
+const ROWS: usize = 1000;
+
+fn table(label: &str) -> VTag<()> {
+    html! {
+        <table class="table",>
+            { for (0..ROWS).map(|row| html! {
+                <tr class="row", data-row=row, onclick=|_| (),>
+                    <td class="id",>{ row }</td>
+                    <td class="label", title=label,>{ label }</td>
+                </tr>
+            }) }
+        </table>
+    }
+}
+
+fn bench<F: FnMut()>(name: &str, mut routine: F) {
+    let iterations = 100;
+    let started = Instant::now();
+    for _ in 0..iterations {
+        routine();
+    }
+    let elapsed = started.elapsed() / iterations;
+    println!("{:<24} {:>10.3} ms/iter", name, millis(elapsed));
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs() as f64 * 1000.0 + f64::from(duration.subsec_nanos()) / 1_000_000.0
+}
+
+fn main() {
+    bench("build and drop", || {
+        drop(table("label"));
+    });
+    bench("build and recycle", || {
+        table("label").recycle();
+    });
+}
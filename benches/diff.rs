@@ -0,0 +1,69 @@
+//! Benchmarks of `VTag::diff` which don't need a browser.
+//! Run them with `cargo bench --bench diff`.
+
+#[macro_use]
+extern crate yew;
+
+use std::time::{Duration, Instant};
+use yew::virtual_dom::{VNode, VTag};
+
+const ROWS: usize = 1000;
+
+fn table(selected: usize, label: &str) -> VTag<()> {
+    html! {
+        <table class="table",>
+            { for (0..ROWS).map(|row| {
+                let class = if row == selected { "row selected" } else { "row" };
+                html! {
+                    <tr class=class, data-row=row,>
+                        <td class="id",>{ row }</td>
+                        <td class="label", title=label,>{ label }</td>
+                    </tr>
+                }
+            }) }
+        </table>
+    }
+}
+
+/// Diffs every tag of the tree with the tag at the same position of the ancestor
+/// and returns the count of patches.
+fn diff_tree(left: &VTag<()>, right: Option<&VTag<()>>) -> usize {
+    let mut patches = left.diff(right).len();
+    for (index, child) in left.childs.iter().enumerate() {
+        if let VNode::VTag { ref vtag, .. } = *child {
+            let ancestor = right.and_then(|right| match right.childs.get(index) {
+                Some(VNode::VTag { vtag, .. }) => Some(vtag),
+                _ => None,
+            });
+            patches += diff_tree(vtag, ancestor);
+        }
+    }
+    patches
+}
+
+fn bench<F: FnMut() -> usize>(name: &str, mut routine: F) {
+    let iterations = 100;
+    let started = Instant::now();
+    let mut patches = 0;
+    for _ in 0..iterations {
+        patches = routine();
+    }
+    let elapsed = started.elapsed() / iterations;
+    println!("{:<24} {:>10.3} ms/iter {:>6} patches", name, millis(elapsed), patches);
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs() as f64 * 1000.0 + f64::from(duration.subsec_nanos()) / 1_000_000.0
+}
+
+fn main() {
+    let base = table(1, "label");
+    let same = table(1, "label");
+    let selected = table(2, "label");
+    let relabeled = table(1, "other");
+
+    bench("diff new rows", || diff_tree(&base, None));
+    bench("diff unchanged rows", || diff_tree(&same, Some(&base)));
+    bench("diff selected row", || diff_tree(&selected, Some(&base)));
+    bench("diff all labels", || diff_tree(&relabeled, Some(&base)));
+}
@@ -0,0 +1,63 @@
+//! `cargo yew` scaffolds projects and components of Yew apps and serves
+//! them while they're developed:
+//!
+//! ```sh
+//! cargo install --path cargo-yew
+//! cargo yew new todos
+//! cd todos
+//! cargo yew component TodoList
+//! cargo yew serve
+//! ```
+//!
+//! A project keeps components in a library, so tests in `tests` render
+//! them with `TestRenderer`. Yew is taken from the checkout which `cargo-yew`
+//! was installed from. The server is `cargo web start`, which rebuilds
+//! the app and reloads the page when sources change.
+
+use std::env;
+use std::path::Path;
+use std::process::{self, Command};
+
+mod scaffold;
+mod templates;
+
+const USAGE: &str = "Usage:
+    cargo yew new <path>          Creates a project with a component and its test
+    cargo yew component <Name>    Adds a component and its test to the project
+    cargo yew serve [options]     Serves the project, rebuilds and reloads it on changes;
+                                  options are passed to `cargo web start`";
+
+/// Runs `cargo web start` with the automatic reload of the page.
+fn serve(options: &[String]) -> Result<(), String> {
+    let status = Command::new("cargo")
+        .args(["web", "start", "--auto-reload"])
+        .args(options)
+        .status()
+        .map_err(|err| format!("can't run cargo: {}", err))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err("the server stopped, is cargo-web installed? Run `cargo install cargo-web`".to_owned())
+    }
+}
+
+fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    // Cargo passes the name of the subcommand as the first argument
+    if args.first().map(String::as_str) == Some("yew") {
+        args.remove(0);
+    }
+    let result = match args.first().map(String::as_str) {
+        Some("new") if args.len() == 2 => scaffold::project(Path::new(&args[1])),
+        Some("component") if args.len() == 2 => scaffold::component(Path::new("."), &args[1]),
+        Some("serve") => serve(&args[1..]),
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+    if let Err(err) = result {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
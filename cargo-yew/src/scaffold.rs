@@ -0,0 +1,112 @@
+//! Generators of projects and components.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use templates::{self, render};
+
+/// The component which is generated with a project.
+const ROOT_COMPONENT: &str = "Home";
+
+/// Creates a project in the directory, which mustn't exist.
+/// The name of the project is the name of the directory.
+pub fn project(dir: &Path) -> Result<(), String> {
+    let name = dir.file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| format!("can't take a name of the project from {}", dir.display()))?;
+    let valid = name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(format!("`{}` isn't a name of a crate", name));
+    }
+    if dir.exists() {
+        return Err(format!("{} already exists", dir.display()));
+    }
+    let values = [("name", name), ("crate", &name.replace('-', "_")), ("yew", &yew_path())];
+    write(&dir.join("Cargo.toml"), &render(templates::MANIFEST, &values))?;
+    write(&dir.join(".gitignore"), templates::GITIGNORE)?;
+    write(&dir.join("src/lib.rs"), templates::LIB)?;
+    write(&dir.join("src/main.rs"), &render(templates::MAIN, &values))?;
+    write(&dir.join("src/components/mod.rs"), templates::COMPONENTS)?;
+    write(&dir.join("static/index.html"), &render(templates::INDEX, &values))?;
+    component(dir, ROOT_COMPONENT)
+}
+
+/// Returns the path of the checkout of Yew which `cargo-yew` was built from.
+/// Slashes are used, because backslashes are escapes in the manifest.
+fn yew_path() -> String {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let checkout = manifest.parent().unwrap_or(manifest);
+    checkout.display().to_string().replace('\\', "/")
+}
+
+/// Adds a component with a test to the project in the directory.
+/// The name is a name of a type, like `TodoList`.
+pub fn component(dir: &Path, name: &str) -> Result<(), String> {
+    let valid = name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name.chars().all(|c| c.is_ascii_alphanumeric());
+    if !valid {
+        return Err(format!("`{}` isn't a name of a type, like `TodoList`", name));
+    }
+    let krate = crate_of(dir)?.replace('-', "_");
+    let snake = snake_case(name);
+    let values = [
+        ("crate", krate.as_str()),
+        ("Component", name),
+        ("snake", &snake),
+        ("class", &snake.replace('_', "-")),
+    ];
+    let module = dir.join("src/components").join(format!("{}.rs", snake));
+    if module.exists() {
+        return Err(format!("{} already exists", module.display()));
+    }
+    write(&module, &render(templates::COMPONENT, &values))?;
+    write(&dir.join("tests").join(format!("{}_test.rs", snake)), &render(templates::COMPONENT_TEST, &values))?;
+    let components = dir.join("src/components/mod.rs");
+    OpenOptions::new().create(true).append(true).open(&components)
+        .and_then(|mut file| file.write_all(render(templates::DECLARATION, &values).as_bytes()))
+        .map_err(|err| format!("can't update {}: {}", components.display(), err))
+}
+
+/// Returns the name of the package of the manifest in the directory.
+fn crate_of(dir: &Path) -> Result<String, String> {
+    let path = dir.join("Cargo.toml");
+    let manifest = fs::read_to_string(&path)
+        .map_err(|err| format!("can't read {}, run it in a project: {}", path.display(), err))?;
+    manifest.lines()
+        .skip_while(|line| line.trim() != "[package]")
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some(key), Some(value)) if key.trim() == "name" => Some(value.trim().trim_matches('"').to_owned()),
+                _ => None,
+            }
+        })
+        .next()
+        .ok_or_else(|| format!("{} doesn't have a name of a package", path.display()))
+}
+
+/// Converts a name of a type to a name of a module: `TodoList` is `todo_list`.
+pub fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| format!("can't create {}: {}", parent.display(), err))?;
+    }
+    fs::write(path, contents).map_err(|err| format!("can't write {}: {}", path.display(), err))?;
+    println!("     Created {}", path.display());
+    Ok(())
+}
@@ -0,0 +1,140 @@
+//! Templates of generated files. Placeholders like `__Component__` are
+//! replaced with names by `render`.
+
+/// The manifest of a project. Yew is taken from the checkout of `__yew__`,
+/// because releases don't have the APIs which generated files use.
+pub const MANIFEST: &str = r#"[package]
+name = "__name__"
+version = "0.1.0"
+
+[dependencies]
+yew = { path = "__yew__" }
+
+[dev-dependencies]
+yew = { path = "__yew__", features = ["testing"] }
+"#;
+
+/// Files ignored by git.
+pub const GITIGNORE: &str = "target/\nCargo.lock\n";
+
+/// The library of a project, which keeps components, so tests could use them.
+pub const LIB: &str = r#"#[macro_use]
+extern crate yew;
+
+pub mod components;
+"#;
+
+/// The binary of a project which mounts the root component.
+pub const MAIN: &str = r#"#[macro_use]
+extern crate yew;
+extern crate __crate__;
+
+use yew::html::App;
+use __crate__::components::Home;
+
+fn main() {
+    yew::initialize();
+    let mut app = App::<()>::new();
+    app.mount((), (), |_, _, _| {}, |_| html! { <Home: title="__name__", /> });
+    yew::run_loop();
+}
+"#;
+
+/// The module of components. Generated components are appended to it.
+pub const COMPONENTS: &str = "//! Components of the app.\n\n";
+
+/// Declarations of a component in the module of components.
+pub const DECLARATION: &str = "pub mod __snake__;\npub use self::__snake__::__Component__;\n";
+
+/// The page which loads the app.
+pub const INDEX: &str = r#"<!doctype html>
+<html lang="en">
+    <head>
+        <meta charset="utf-8">
+        <title>__name__</title>
+    </head>
+    <body>
+        <script src="__name__.js"></script>
+    </body>
+</html>
+"#;
+
+/// A component with properties, messages and a view.
+pub const COMPONENT: &str = r#"//! This module contains the `__Component__` component.
+
+use yew::html::{AppSender, Component, Html, ShouldRender};
+
+/// Properties of the `__Component__` component.
+#[derive(Default)]
+pub struct __Component__Props {
+    /// The title of the component.
+    pub title: String,
+}
+
+/// Messages of the `__Component__` component.
+pub enum Msg {
+    /// The button was clicked.
+    Click,
+}
+
+/// The `__Component__` component.
+pub struct __Component__ {
+    props: __Component__Props,
+    clicks: u32,
+}
+
+impl Component for __Component__ {
+    type Msg = Msg;
+    type Properties = __Component__Props;
+
+    fn create(props: Self::Properties, _: AppSender<Msg>) -> Self {
+        __Component__ { props, clicks: 0 }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Click => self.clicks += 1,
+        }
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        html! {
+            <div class="__class__",>
+                <h2>{ &self.props.title }</h2>
+                <button onclick=|_| Msg::Click,>{ format!("Clicked {} times", self.clicks) }</button>
+            </div>
+        }
+    }
+}
+"#;
+
+/// Tests of a component with the test renderer.
+pub const COMPONENT_TEST: &str = r#"extern crate yew;
+extern crate __crate__;
+
+use yew::testing::TestRenderer;
+use __crate__::components::__snake__::{__Component__, __Component__Props};
+
+#[test]
+fn it_counts_clicks() {
+    let props = __Component__Props { title: "Title".into() };
+    let mut component = TestRenderer::<__Component__>::new(props);
+    assert_eq!(component.root().find("h2").unwrap().text(), "Title");
+
+    component.click("button");
+    assert_eq!(component.root().find("button").unwrap().text(), "Clicked 1 times");
+}
+"#;
+
+/// Replaces placeholders of the template with values.
+pub fn render(template: &str, values: &[(&str, &str)]) -> String {
+    values.iter().fold(template.to_owned(), |text, &(placeholder, value)| {
+        text.replace(&format!("__{}__", placeholder), value)
+    })
+}
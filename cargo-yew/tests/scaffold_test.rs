@@ -0,0 +1,86 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn yew(dir: &PathBuf, args: &[&str]) -> bool {
+    Command::new(env!("CARGO_BIN_EXE_cargo-yew"))
+        .arg("yew")
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .expect("can't run cargo-yew")
+        .success()
+}
+
+/// Runs a command of cargo-web with `CARGO_WEB_ARGS`, which choose a target like in `ci.sh`.
+fn cargo_web(dir: &PathBuf, args: &[&str]) -> bool {
+    let target = env::var("CARGO_WEB_ARGS").unwrap_or_default();
+    Command::new("cargo")
+        .arg("web")
+        .args(args)
+        .args(target.split_whitespace())
+        .current_dir(dir)
+        .status()
+        .expect("can't run cargo, is cargo-web installed?")
+        .success()
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("cargo-yew-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn it_scaffolds_a_project_and_components() {
+    let dir = temp_dir("project");
+    assert!(yew(&dir, &["new", "todo-app"]));
+    let project = dir.join("todo-app");
+    let main = fs::read_to_string(project.join("src/main.rs")).unwrap();
+    assert!(main.contains("extern crate todo_app;"));
+    assert!(main.contains("<Home: title=\"todo-app\", />"));
+    assert!(project.join("tests/home_test.rs").exists());
+
+    assert!(yew(&project, &["component", "TodoList"]));
+    let module = fs::read_to_string(project.join("src/components/todo_list.rs")).unwrap();
+    assert!(module.contains("pub struct TodoListProps"));
+    assert!(module.contains("<div class=\"todo-list\",>"));
+    let test = fs::read_to_string(project.join("tests/todo_list_test.rs")).unwrap();
+    assert!(test.contains("use todo_app::components::todo_list::{TodoList, TodoListProps};"));
+    let components = fs::read_to_string(project.join("src/components/mod.rs")).unwrap();
+    assert!(components.contains("pub mod home;\npub use self::home::Home;\n"));
+    assert!(components.contains("pub mod todo_list;\npub use self::todo_list::TodoList;\n"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn it_refuses_to_overwrite_files() {
+    let dir = temp_dir("existing");
+    assert!(yew(&dir, &["new", "app"]));
+    assert!(!yew(&dir, &["new", "app"]));
+    let project = dir.join("app");
+    assert!(!yew(&project, &["component", "Home"]));
+    assert!(!yew(&project, &["component", "todo_list"]));
+    assert!(!yew(&dir, &["component", "Other"]));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn it_builds_a_scaffolded_project() {
+    let dir = temp_dir("build");
+    assert!(yew(&dir, &["new", "counter"]));
+    let project = dir.join("counter");
+    let manifest = fs::read_to_string(project.join("Cargo.toml")).unwrap();
+    let checkout = PathBuf::from(env!("CARGO_MANIFEST_DIR")).parent().unwrap().display().to_string();
+    assert!(manifest.contains(&format!("yew = {{ path = \"{}\" }}", checkout.replace('\\', "/"))));
+
+    // Apps run in browsers, so they're built like the examples
+    assert!(cargo_web(&project, &["build"]));
+    assert!(cargo_web(&project, &["test", "--no-run"]));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -42,8 +42,16 @@ if [ "$TARGET" = "wasm32-unknown-unknown" ]; then
     rustup target add wasm32-unknown-unknown
     export CARGO_WEB_ARGS="--target-webasm"
     cargo web test --nodejs $CARGO_WEB_ARGS
+    # Backends of services which use web-sys
+    cargo web build --features web_sys $CARGO_WEB_ARGS
 fi
 
+# Server-side rendering runs in native binaries without JS
+cargo test --features ssr --test ssr_test
+
+# Projects of `cargo yew new` are built with the same target
+cargo test -p cargo-yew
+
 check_example() {
     echo "Checking example [$1]"
     cd $1
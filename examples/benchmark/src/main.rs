@@ -0,0 +1,336 @@
+//! A benchmark of rendering in the style of js-framework-benchmark:
+//! creates, updates, swaps and removes rows of a table with listeners
+//! of every row, and renders a deep tree.
+//!
+//! Buttons run single operations. Run the whole suite with the `?auto`
+//! query or the "Run all" button. Results are printed to the console as JSON
+//! and to the `#results` element, and the title changes to `done` when
+//! the suite finishes, so it could run in a headless browser:
+//!
+//! ```sh
+//! cargo web start --release
+//! chrome --headless --virtual-time-budget=120000 --dump-dom "http://localhost:8000/?auto"
+//! ```
+
+#[macro_use]
+extern crate stdweb;
+#[macro_use]
+extern crate yew;
+
+use std::collections::VecDeque;
+use stdweb::Value;
+use stdweb::unstable::TryInto;
+use yew::html::*;
+use yew::services::console::ConsoleService;
+
+/// Runs of every benchmark which aren't measured.
+const WARMUP: usize = 2;
+/// Measured runs of every benchmark.
+const ITERATIONS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Create(usize),
+    Append(usize),
+    UpdateEvery(usize),
+    Select(usize),
+    Swap,
+    RemoveRow,
+    Clear,
+    Deep(usize),
+}
+
+/// A name, operations which prepare the benchmark and the measured operation.
+const BENCHMARKS: &[(&str, &[Op], Op)] = &[
+    ("create 1,000 rows", &[], Op::Create(1_000)),
+    ("replace 1,000 rows", &[Op::Create(1_000)], Op::Create(1_000)),
+    ("update every 10th row", &[Op::Create(1_000)], Op::UpdateEvery(10)),
+    ("select row", &[Op::Create(1_000)], Op::Select(1)),
+    ("swap rows", &[Op::Create(1_000)], Op::Swap),
+    ("remove row", &[Op::Create(1_000)], Op::RemoveRow),
+    ("create 10,000 rows", &[], Op::Create(10_000)),
+    ("append 1,000 rows", &[Op::Create(1_000)], Op::Append(1_000)),
+    ("clear 1,000 rows", &[Op::Create(1_000)], Op::Clear),
+    ("deep tree of 300 levels", &[], Op::Deep(300)),
+];
+
+const ADJECTIVES: &[&str] = &[
+    "pretty", "large", "big", "small", "tall", "short", "long", "handsome", "plain",
+    "quaint", "clean", "elegant", "easy", "angry", "crazy", "helpful", "mushy",
+];
+const COLOURS: &[&str] = &[
+    "red", "yellow", "blue", "green", "pink", "brown", "purple", "white", "black", "orange",
+];
+const NOUNS: &[&str] = &[
+    "table", "chair", "house", "bbq", "desk", "car", "pony", "cookie", "sandwich",
+    "burger", "pizza", "mouse", "keyboard",
+];
+
+struct Row {
+    id: usize,
+    label: String,
+}
+
+enum Step {
+    Prepare(Op),
+    Measure(usize, Op),
+}
+
+struct Context {
+    sender: AppSender<Msg>,
+    console: ConsoleService,
+}
+
+struct Model {
+    rows: Vec<Row>,
+    next_id: usize,
+    seed: u32,
+    selected: Option<usize>,
+    depth: usize,
+    queue: VecDeque<Step>,
+    timing: Option<(usize, f64)>,
+    samples: Vec<Vec<f64>>,
+    report: String,
+}
+
+enum Msg {
+    Run(Op),
+    Select(usize),
+    Remove(usize),
+    RunAll,
+    Step,
+    Rendered(f64),
+}
+
+fn now() -> f64 {
+    let now: Value = js! {
+        return performance.now();
+    };
+    now.try_into().unwrap_or(0.0)
+}
+
+impl Model {
+    fn random(&mut self, max: usize) -> usize {
+        // A linear congruential generator is enough for labels
+        self.seed = self.seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        (self.seed >> 16) as usize % max
+    }
+
+    fn label(&mut self) -> String {
+        let adjective = ADJECTIVES[self.random(ADJECTIVES.len())];
+        let colour = COLOURS[self.random(COLOURS.len())];
+        let noun = NOUNS[self.random(NOUNS.len())];
+        format!("{} {} {}", adjective, colour, noun)
+    }
+
+    fn build(&mut self, count: usize) -> Vec<Row> {
+        (0..count).map(|_| {
+            self.next_id += 1;
+            Row { id: self.next_id, label: self.label() }
+        }).collect()
+    }
+
+    fn apply(&mut self, op: Op) {
+        match op {
+            Op::Create(count) => {
+                self.rows = self.build(count);
+                self.selected = None;
+            }
+            Op::Append(count) => {
+                let rows = self.build(count);
+                self.rows.extend(rows);
+            }
+            Op::UpdateEvery(step) => {
+                for row in self.rows.iter_mut().step_by(step) {
+                    row.label.push_str(" !!!");
+                }
+            }
+            Op::Select(index) => {
+                self.selected = self.rows.get(index).map(|row| row.id);
+            }
+            Op::Swap => {
+                if self.rows.len() > 998 {
+                    self.rows.swap(1, 998);
+                }
+            }
+            Op::RemoveRow => {
+                if self.rows.len() > 1 {
+                    self.rows.remove(1);
+                }
+            }
+            Op::Clear => {
+                self.rows.clear();
+                self.selected = None;
+                self.depth = 0;
+            }
+            Op::Deep(depth) => {
+                self.depth = depth;
+            }
+        }
+    }
+
+    fn plan(&mut self) {
+        self.queue.clear();
+        self.samples = BENCHMARKS.iter().map(|_| Vec::new()).collect();
+        for (index, &(_, prepare, op)) in BENCHMARKS.iter().enumerate() {
+            for _ in 0..WARMUP + ITERATIONS {
+                self.queue.push_back(Step::Prepare(Op::Clear));
+                for op in prepare {
+                    self.queue.push_back(Step::Prepare(*op));
+                }
+                self.queue.push_back(Step::Measure(index, op));
+            }
+        }
+    }
+
+    fn report(&self) -> String {
+        let lines: Vec<String> = BENCHMARKS.iter().zip(self.samples.iter())
+            .map(|(&(name, _, _), samples)| {
+                let mut measured: Vec<f64> = samples.iter().skip(WARMUP).cloned().collect();
+                measured.sort_by(|a, b| a.partial_cmp(b).expect("a sample is not a number"));
+                let count = measured.len().max(1) as f64;
+                let mean = measured.iter().sum::<f64>() / count;
+                let median = measured.get(measured.len() / 2).cloned().unwrap_or(0.0);
+                let min = measured.first().cloned().unwrap_or(0.0);
+                format!("  {{ \"name\": {:?}, \"mean\": {:.2}, \"median\": {:.2}, \"min\": {:.2} }}",
+                        name, mean, median, min)
+            })
+            .collect();
+        format!("[\n{}\n]", lines.join(",\n"))
+    }
+}
+
+/// Sends the time when the current render finished: the microtask is called
+/// after the loop renders the update which is handled now.
+fn notify_rendered(sender: &AppSender<Msg>) {
+    let mut sender = sender.clone();
+    let callback = move || {
+        sender.send(Msg::Rendered(now()));
+    };
+    js! { @(no_return)
+        var callback = @{callback};
+        Promise.resolve().then(function() {
+            callback();
+            callback.drop();
+        });
+    }
+}
+
+fn update(context: &mut Context, model: &mut Model, msg: Msg) {
+    match msg {
+        Msg::Run(op) => {
+            model.apply(op);
+        }
+        Msg::Select(id) => {
+            model.selected = Some(id);
+        }
+        Msg::Remove(id) => {
+            model.rows.retain(|row| row.id != id);
+        }
+        Msg::RunAll => {
+            model.report = "Running...".into();
+            model.plan();
+            context.sender.send_soon(Msg::Step);
+        }
+        Msg::Rendered(finished) => {
+            if let Some((index, started)) = model.timing.take() {
+                model.samples[index].push(finished - started);
+            }
+            // Let the browser paint and collect garbage between steps
+            context.sender.send_soon(Msg::Step);
+        }
+        Msg::Step => {
+            match model.queue.pop_front() {
+                Some(Step::Prepare(op)) => {
+                    model.apply(op);
+                    context.sender.send_soon(Msg::Step);
+                }
+                Some(Step::Measure(index, op)) => {
+                    model.timing = Some((index, now()));
+                    model.apply(op);
+                    notify_rendered(&context.sender);
+                }
+                None => {
+                    model.apply(Op::Clear);
+                    model.report = model.report();
+                    context.console.log(&model.report);
+                    js! { @(no_return) document.title = "done"; }
+                }
+            }
+        }
+    }
+}
+
+fn view_row(row: &Row, selected: Option<usize>) -> Html<Msg> {
+    let id = row.id;
+    let class = if selected == Some(id) { "danger" } else { "" };
+    html! {
+        <tr class=class,>
+            <td class="col-md-1",>{ id }</td>
+            <td class="col-md-4",><a onclick=move |_| Msg::Select(id),>{ &row.label }</a></td>
+            <td class="col-md-1",><a onclick=move |_| Msg::Remove(id),>{ "×" }</a></td>
+            <td class="col-md-6",></td>
+        </tr>
+    }
+}
+
+fn view_deep(depth: usize) -> Html<Msg> {
+    if depth == 0 {
+        html! { <span>{ "leaf" }</span> }
+    } else {
+        html! { <div class="level",>{ view_deep(depth - 1) }</div> }
+    }
+}
+
+fn view(model: &Model) -> Html<Msg> {
+    let selected = model.selected;
+    html! {
+        <div class="container",>
+            <nav>
+                <button onclick=|_| Msg::Run(Op::Create(1_000)),>{ "Create 1,000 rows" }</button>
+                <button onclick=|_| Msg::Run(Op::Create(10_000)),>{ "Create 10,000 rows" }</button>
+                <button onclick=|_| Msg::Run(Op::Append(1_000)),>{ "Append 1,000 rows" }</button>
+                <button onclick=|_| Msg::Run(Op::UpdateEvery(10)),>{ "Update every 10th row" }</button>
+                <button onclick=|_| Msg::Run(Op::Swap),>{ "Swap rows" }</button>
+                <button onclick=|_| Msg::Run(Op::Clear),>{ "Clear" }</button>
+                <button onclick=|_| Msg::Run(Op::Deep(300)),>{ "Deep tree" }</button>
+                <button onclick=|_| Msg::RunAll,>{ "Run all" }</button>
+            </nav>
+            <pre id="results",>{ &model.report }</pre>
+            <table class="table",>
+                <tbody>
+                    { for model.rows.iter().map(|row| view_row(row, selected)) }
+                </tbody>
+            </table>
+            { if model.depth > 0 { view_deep(model.depth) } else { html! { <div></div> } } }
+        </div>
+    }
+}
+
+fn main() {
+    yew::initialize();
+    let mut app = App::new();
+    let context = Context {
+        sender: app.sender(),
+        console: ConsoleService,
+    };
+    let model = Model {
+        rows: Vec::new(),
+        next_id: 0,
+        seed: 42,
+        selected: None,
+        depth: 0,
+        queue: VecDeque::new(),
+        timing: None,
+        samples: Vec::new(),
+        report: String::new(),
+    };
+    let auto: Value = js! {
+        return window.location.search.indexOf("auto") >= 0;
+    };
+    if auto == Value::Bool(true) {
+        app.sender().send(Msg::RunAll);
+    }
+    app.mount(context, model, update, view);
+    yew::run_loop();
+}
@@ -183,8 +183,7 @@ fn view_first_name_input(client: &Client) -> Html<Msg> {
     html! {
         <input class=("new-client", "firstname"),
                placeholder="First name",
-               value=&client.first_name,
-               oninput=|e: InputData| Msg::UpdateFirstName(e.value),
+               bind=(&client.first_name, Msg::UpdateFirstName),
                />
     }
 }
@@ -193,8 +192,7 @@ fn view_last_name_input(client: &Client) -> Html<Msg> {
     html! {
         <input class=("new-client", "lastname"),
                placeholder="Last name",
-               value=&client.last_name,
-               oninput=|e: InputData| Msg::UpdateLastName(e.value),
+               bind=(&client.last_name, Msg::UpdateLastName),
                />
     }
 }
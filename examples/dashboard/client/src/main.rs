@@ -6,7 +6,7 @@ extern crate yew;
 use yew::html::*;
 use yew::format::{Nothing, Json};
 use yew::services::Task;
-use yew::services::fetch::{FetchService, Request};
+use yew::services::fetch::{FetchService, FetchHandle, Request};
 use yew::services::websocket::{WebSocketService, WebSocketHandle, WebSocketStatus};
 
 struct Context {
@@ -16,6 +16,7 @@ struct Context {
 
 struct Model {
     fetching: bool,
+    fetch: Option<FetchHandle>,
     data: Option<u32>,
     ws: Option<WebSocketHandle>,
 }
@@ -71,7 +72,7 @@ fn update(context: &mut Context, model: &mut Model, msg: Msg) {
     match msg {
         Msg::FetchData => {
             model.fetching = true;
-            context.web.fetch(
+            let handle = context.web.fetch(
                 Request::get("/data.json").body(Nothing).unwrap(),
                 |response| {
                     let (meta, Json(data)) = response.into_parts();
@@ -82,6 +83,8 @@ fn update(context: &mut Context, model: &mut Model, msg: Msg) {
                     }
                 }
             );
+            // The request is canceled if the handle is dropped
+            model.fetch = Some(handle);
         }
         Msg::WsAction(action) => {
             match action {
@@ -109,6 +112,7 @@ fn update(context: &mut Context, model: &mut Model, msg: Msg) {
         }
         Msg::FetchReady(response) => {
             model.fetching = false;
+            model.fetch = None;
             model.data = response.map(|data| data.value).ok();
         }
         Msg::WsReady(response) => {
@@ -157,6 +161,7 @@ fn main() {
     };
     let model = Model {
         fetching: false,
+        fetch: None,
         data: None,
         ws: None,
     };
@@ -3,7 +3,7 @@ extern crate yew;
 #[macro_use]
 extern crate stdweb;
 
-use yew::html::{App, Html, InputData};
+use yew::html::{App, Html};
 use stdweb::web::{IElement, document, INode};
 
 struct Context {}
@@ -27,7 +27,7 @@ fn update(_: &mut Context, model: &mut Model, msg: Msg) {
 fn view(model: &Model) -> Html<Msg> {
     html! {
         <div>
-            <input value=&model.name, oninput=|e: InputData| Msg::UpdateName(e.value), />
+            <input bind=(&model.name, Msg::UpdateName), />
             <p>{ model.name.chars().rev().collect::<String>() }</p>
         </div>
     }
@@ -61,7 +61,6 @@ fn main() {
         name: "Reversed".to_owned(),
     };
 
-    let mount_point = format!(".{}", mount_class);
-    app.mount_to(&mount_point, context, model, update, view);
+    app.mount_to_element(mount_point, context, model, update, view);
     yew::run_loop();
 }
@@ -6,6 +6,7 @@ extern crate stdweb;
 extern crate yew;
 
 use yew::html::*;
+use yew::services::TaskBag;
 
 // Own services implementation
 mod gravatar;
@@ -16,6 +17,7 @@ use ccxt::CcxtService;
 struct Context {
     gravatar: GravatarService<Msg>,
     ccxt: CcxtService,
+    tasks: TaskBag,
 }
 
 struct Model {
@@ -32,7 +34,8 @@ enum Msg {
 fn update(context: &mut Context, model: &mut Model, msg: Msg) {
     match msg {
         Msg::Gravatar => {
-            context.gravatar.profile("205e460b479e2e5b48aec07710c08d50", Msg::GravatarReady);
+            let task = context.gravatar.profile("205e460b479e2e5b48aec07710c08d50", Msg::GravatarReady);
+            context.tasks.push(task);
         }
         Msg::GravatarReady(Ok(profile)) => {
             model.profile = Some(profile);
@@ -67,6 +70,7 @@ fn main() {
     let context = Context {
         gravatar: GravatarService::new(app.sender()),
         ccxt: CcxtService::new(),
+        tasks: TaskBag::new(),
     };
     let model = Model {
         profile: None,
@@ -0,0 +1,298 @@
+//! This module contains an audit of accessibility which is compiled with
+//! the `a11y` feature. In debug builds it walks the rendered trees of apps
+//! and their components after renders and warns about common issues:
+//!
+//! * images without `alt`;
+//! * buttons without an accessible name;
+//! * fields without labels;
+//! * duplicate ids.
+//!
+//! New issues are printed to the console and all current issues are listed
+//! in an overlay at the corner of the page. Release builds skip audits.
+//! Trees could be audited explicitly too:
+//!
+//! ```rust,ignore
+//! let issues = a11y::audit(&VNode::from(view(&model)));
+//! assert!(issues.is_empty(), "{:?}", issues);
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::mem;
+use std::rc::Weak;
+use virtual_dom::{VNode, VTag};
+
+/// A rule which an element breaks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rule {
+    /// An image doesn't have the `alt` attribute.
+    MissingAlt,
+    /// A button doesn't have text, `aria-label` or `title`.
+    UnnamedButton,
+    /// A field is not inside a `label`, no `label` refers to it and it doesn't have `aria-label`.
+    UnlabeledField,
+    /// Elements use the same id.
+    DuplicateId,
+}
+
+/// An issue of an element found by an audit.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Issue {
+    /// A broken rule.
+    pub rule: Rule,
+    /// A short selector of the element, like `input#name.wide`.
+    pub element: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let problem = match self.rule {
+            Rule::MissingAlt => "image without alt text",
+            Rule::UnnamedButton => "button without an accessible name",
+            Rule::UnlabeledField => "field without a label",
+            Rule::DuplicateId => "duplicate id",
+        };
+        write!(f, "{}: {}", problem, self.element)
+    }
+}
+
+/// A walk through trees which collects issues.
+pub(crate) struct Audit {
+    issues: Vec<Issue>,
+    ids: BTreeMap<String, usize>,
+    /// Values of `for` attributes of labels.
+    labels: HashSet<String>,
+    /// Fields outside of labels which need a `label` referring to them.
+    fields: Vec<(Option<String>, String)>,
+    /// The depth of labels around the current node.
+    in_label: usize,
+}
+
+impl Audit {
+    fn new() -> Self {
+        Audit {
+            issues: Vec::new(),
+            ids: BTreeMap::new(),
+            labels: HashSet::new(),
+            fields: Vec::new(),
+            in_label: 0,
+        }
+    }
+
+    /// Walks the node and subtrees of components mounted inside it.
+    pub(crate) fn node<MSG>(&mut self, node: &VNode<MSG>) {
+        match *node {
+            VNode::VTag { ref vtag, .. } => self.tag(vtag),
+            VNode::VPortal { ref vportal, .. } => {
+                for child in &vportal.childs {
+                    self.node(child);
+                }
+            }
+            VNode::VComp { ref vcomp } => vcomp.audit(self),
+            VNode::VText { .. } | VNode::VRaw { .. } => {}
+        }
+    }
+
+    fn tag<MSG>(&mut self, vtag: &VTag<MSG>) {
+        let id = vtag.attributes.get("id").cloned();
+        if let Some(ref id) = id {
+            *self.ids.entry(id.clone()).or_insert(0) += 1;
+        }
+        let tag = vtag.tag().to_lowercase();
+        let kind = vtag.kind.as_ref().map(|kind| kind.to_lowercase());
+        let labelled = has_label(vtag);
+        match (tag.as_str(), kind.as_deref()) {
+            ("img", _) | ("input", Some("image"))
+                if !vtag.attributes.contains_key("alt") && !labelled && !is_hidden(vtag) =>
+            {
+                self.report(Rule::MissingAlt, vtag);
+            }
+            ("button", _) if !labelled && !vtag.childs.iter().any(has_name) => {
+                self.report(Rule::UnnamedButton, vtag);
+            }
+            ("input", Some("button")) => {
+                let named = vtag.value.as_ref().map_or(false, |value| !value.trim().is_empty());
+                if !labelled && !named {
+                    self.report(Rule::UnnamedButton, vtag);
+                }
+            }
+            // Browsers name these buttons by themselves, images are checked above
+            ("input", Some("image")) | ("input", Some("submit"))
+                | ("input", Some("reset")) | ("input", Some("hidden")) => {}
+            ("input", _) | ("select", _) | ("textarea", _) if !labelled && self.in_label == 0 => {
+                self.fields.push((id.clone(), describe(vtag)));
+            }
+            ("label", _) => {
+                if let Some(target) = vtag.attributes.get("for") {
+                    self.labels.insert(target.clone());
+                }
+            }
+            _ => {}
+        }
+        let label = tag == "label";
+        if label {
+            self.in_label += 1;
+        }
+        for child in &vtag.childs {
+            self.node(child);
+        }
+        if label {
+            self.in_label -= 1;
+        }
+    }
+
+    fn report<MSG>(&mut self, rule: Rule, vtag: &VTag<MSG>) {
+        self.issues.push(Issue { rule, element: describe(vtag) });
+    }
+
+    /// Checks rules which need the whole tree and returns all issues.
+    fn finish(mut self) -> Vec<Issue> {
+        let fields = mem::take(&mut self.fields);
+        for (id, element) in fields {
+            let referred = id.map_or(false, |id| self.labels.contains(&id));
+            if !referred {
+                self.issues.push(Issue { rule: Rule::UnlabeledField, element });
+            }
+        }
+        for (id, count) in &self.ids {
+            if *count > 1 {
+                self.issues.push(Issue { rule: Rule::DuplicateId, element: format!("#{}", id) });
+            }
+        }
+        self.issues
+    }
+}
+
+fn has_label<MSG>(vtag: &VTag<MSG>) -> bool {
+    ["aria-label", "aria-labelledby", "title"].iter().any(|name| {
+        vtag.attributes.get(*name).map_or(false, |value| !value.trim().is_empty())
+    })
+}
+
+fn is_hidden<MSG>(vtag: &VTag<MSG>) -> bool {
+    let role = vtag.attributes.get("role").map(String::as_str);
+    role == Some("presentation") || role == Some("none")
+        || vtag.attributes.get("aria-hidden").map(String::as_str) == Some("true")
+}
+
+/// Checks that the node has text which names a control.
+/// A nested component is expected to render a name.
+fn has_name<MSG>(node: &VNode<MSG>) -> bool {
+    match *node {
+        VNode::VText { ref vtext, .. } => !vtext.text.trim().is_empty(),
+        VNode::VTag { ref vtag, .. } => {
+            if is_hidden(vtag) {
+                return false;
+            }
+            let alt = vtag.attributes.get("alt").map_or(false, |alt| !alt.trim().is_empty());
+            alt || has_label(vtag) || vtag.childs.iter().any(has_name)
+        }
+        VNode::VPortal { .. } => false,
+        VNode::VRaw { .. } | VNode::VComp { .. } => true,
+    }
+}
+
+fn describe<MSG>(vtag: &VTag<MSG>) -> String {
+    let mut selector = vtag.tag().to_lowercase();
+    if let Some(id) = vtag.attributes.get("id") {
+        selector.push('#');
+        selector.push_str(id);
+    }
+    let mut classes = vtag.classes.iter().collect::<Vec<_>>();
+    classes.sort();
+    for class in classes {
+        selector.push('.');
+        selector.push_str(class);
+    }
+    selector
+}
+
+/// Returns issues of the tree and subtrees of mounted components inside it.
+pub fn audit<MSG>(node: &VNode<MSG>) -> Vec<Issue> {
+    let mut audit = Audit::new();
+    audit.node(node);
+    audit.finish()
+}
+
+type Root = Box<Fn(&mut Audit) -> bool>;
+
+thread_local! {
+    /// Frames of running apps. A root returns `false` if its app was dropped.
+    static ROOTS: RefCell<Vec<Root>> = RefCell::new(Vec::new());
+    static SCHEDULED: Cell<bool> = Cell::new(false);
+    /// Issues printed to the console by the last audit.
+    static REPORTED: RefCell<HashSet<Issue>> = RefCell::new(HashSet::new());
+}
+
+/// Adds the frame of an app to audits.
+pub(crate) fn register<MSG: 'static>(frame: Weak<RefCell<Option<VNode<MSG>>>>) {
+    let root = move |audit: &mut Audit| {
+        match frame.upgrade() {
+            Some(frame) => {
+                if let Ok(frame) = frame.try_borrow() {
+                    if let Some(ref node) = *frame {
+                        audit.node(node);
+                    }
+                }
+                true
+            }
+            None => false,
+        }
+    };
+    ROOTS.with(|roots| roots.borrow_mut().push(Box::new(root)));
+}
+
+/// Audits all apps after the current task, once for all renders of it.
+pub(crate) fn schedule() {
+    if !cfg!(debug_assertions) || SCHEDULED.with(|scheduled| scheduled.replace(true)) {
+        return;
+    }
+    let callback = || {
+        SCHEDULED.with(|scheduled| scheduled.set(false));
+        run();
+    };
+    js! { @(no_return)
+        var callback = @{callback};
+        setTimeout(function() {
+            callback();
+            callback.drop();
+        });
+    }
+}
+
+fn run() {
+    let mut audit = Audit::new();
+    ROOTS.with(|roots| roots.borrow_mut().retain(|root| root(&mut audit)));
+    let issues = audit.finish();
+    REPORTED.with(|reported| {
+        let mut reported = reported.borrow_mut();
+        for issue in &issues {
+            if !reported.contains(issue) {
+                warn!("accessibility: {}", issue);
+            }
+        }
+        *reported = issues.iter().cloned().collect();
+    });
+    let lines = issues.iter().map(ToString::to_string).collect::<Vec<_>>();
+    js! { @(no_return)
+        var lines = @{lines};
+        var overlay = document.getElementById("yew-a11y-overlay");
+        if (lines.length === 0) {
+            if (overlay) {
+                overlay.remove();
+            }
+            return;
+        }
+        if (!overlay) {
+            overlay = document.createElement("pre");
+            overlay.id = "yew-a11y-overlay";
+            overlay.style.cssText = "position: fixed; bottom: 0; right: 0; z-index: 2147483647; " +
+                "margin: 0; padding: 8px; max-height: 30%; overflow: auto; " +
+                "background: rgba(255, 240, 200, 0.95); color: #600; font: 12px monospace; " +
+                "border: 1px solid #c60; pointer-events: none;";
+            document.body.appendChild(overlay);
+        }
+        overlay.textContent = "Accessibility issues:\n" + lines.join("\n");
+    }
+}
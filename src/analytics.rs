@@ -0,0 +1,439 @@
+//! This module contains a bus of analytics events. Components track events,
+//! middleware enriches them and sinks which are added once send them:
+//!
+//! ```rust,ignore
+//! fn main() {
+//!     yew::initialize();
+//!     analytics::add_middleware(Session::new());
+//!     analytics::add_middleware(CurrentRoute);
+//!     analytics::add_sink(BeaconSink::new("/api/events"));
+//!     // ...
+//! }
+//!
+//! let onclick = analytics::tracked(Event::interaction("add_to_cart").property("id", id),
+//!                                  self.link.callback(|_| Msg::Add));
+//! analytics::track(Event::custom("checkout").property("total", total));
+//! ```
+//!
+//! A `Router` tracks a page view of every visited location. Apps could track
+//! own types which implement `Into<Event>`.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::mem;
+use std::rc::{Rc, Weak};
+use std::time::Duration;
+use serde_json::{self, Map, Value};
+use callback::Callback;
+use services::Task;
+use services::backend::{self, BeaconBackend, ConnectivityBackend, FetchBackend, RandomBackend,
+                        StorageBackend, TimerBackend, VisibilityBackend};
+use services::fetch::{Request, Response};
+use services::interceptor;
+use services::storage::Scope;
+use format::Restorable;
+
+/// A kind of an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    /// A user opened a page.
+    PageView,
+    /// A user interacted with an element, like clicked a button.
+    Interaction,
+    /// An event of the app, like a finished checkout.
+    Custom,
+}
+
+impl EventKind {
+    /// Returns the name of the kind, like `page_view`.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            EventKind::PageView => "page_view",
+            EventKind::Interaction => "interaction",
+            EventKind::Custom => "custom",
+        }
+    }
+}
+
+/// An analytics event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    /// The kind of the event.
+    pub kind: EventKind,
+    /// The name of the event, like `add_to_cart`.
+    pub name: String,
+    /// Properties of the event, including ones of middleware.
+    pub properties: BTreeMap<String, Value>,
+}
+
+impl Event {
+    /// Creates an event without properties.
+    pub fn new<T: Into<String>>(kind: EventKind, name: T) -> Self {
+        Event {
+            kind,
+            name: name.into(),
+            properties: BTreeMap::new(),
+        }
+    }
+
+    /// Creates a page view of the path with the `path` property.
+    pub fn page_view(path: &str) -> Self {
+        Event::new(EventKind::PageView, "page_view").property("path", path)
+    }
+
+    /// Creates an event of the `Interaction` kind.
+    pub fn interaction<T: Into<String>>(name: T) -> Self {
+        Event::new(EventKind::Interaction, name)
+    }
+
+    /// Creates an event of the `Custom` kind.
+    pub fn custom<T: Into<String>>(name: T) -> Self {
+        Event::new(EventKind::Custom, name)
+    }
+
+    /// Sets a property of the event.
+    pub fn property<K: Into<String>, V: Into<Value>>(mut self, key: K, value: V) -> Self {
+        self.properties.insert(key.into(), value.into());
+        self
+    }
+
+    fn to_json(&self) -> Value {
+        let mut event = Map::new();
+        event.insert("type".into(), Value::String(self.kind.name().into()));
+        event.insert("name".into(), Value::String(self.name.clone()));
+        let properties = self.properties.iter().map(|(key, value)| (key.clone(), value.clone())).collect();
+        event.insert("properties".into(), Value::Object(properties));
+        Value::Object(event)
+    }
+}
+
+/// Returns a JSON payload of events: `{"events": [{"type", "name", "properties"}]}`.
+pub fn payload(events: &[Event]) -> String {
+    let mut body = Map::new();
+    body.insert("events".into(), Value::Array(events.iter().map(Event::to_json).collect()));
+    serde_json::to_string(&Value::Object(body)).expect("can't serialize events")
+}
+
+/// A step which enriches tracked events before sinks get them.
+pub trait Enricher {
+    /// Changes the event. Returns `false` to drop it, like without a consent of a user.
+    fn enrich(&mut self, event: &mut Event) -> bool;
+}
+
+impl<F: FnMut(&mut Event) -> bool> Enricher for F {
+    fn enrich(&mut self, event: &mut Event) -> bool {
+        self(event)
+    }
+}
+
+/// A destination of tracked events.
+pub trait AnalyticsSink {
+    /// Receives an enriched event.
+    fn send(&mut self, event: &Event);
+
+    /// Sends events which are kept to batch them.
+    fn flush(&mut self) {
+    }
+}
+
+impl AnalyticsSink for Callback<Event> {
+    fn send(&mut self, event: &Event) {
+        self.emit(event.clone());
+    }
+}
+
+#[derive(Default)]
+struct Bus {
+    middleware: Vec<Rc<RefCell<Box<Enricher>>>>,
+    sinks: Vec<Rc<RefCell<Box<AnalyticsSink>>>>,
+    route: Option<String>,
+}
+
+thread_local! {
+    static BUS: RefCell<Bus> = RefCell::new(Bus::default());
+}
+
+/// Adds middleware which enriches events tracked later, after the middleware added before.
+pub fn add_middleware<E: Enricher + 'static>(enricher: E) {
+    BUS.with(|bus| bus.borrow_mut().middleware.push(Rc::new(RefCell::new(Box::new(enricher)))));
+}
+
+/// Adds a sink which receives all events tracked later.
+pub fn add_sink<S: AnalyticsSink + 'static>(sink: S) {
+    BUS.with(|bus| bus.borrow_mut().sinks.push(Rc::new(RefCell::new(Box::new(sink)))));
+}
+
+/// Removes all middleware and sinks.
+pub fn clear() {
+    BUS.with(|bus| *bus.borrow_mut() = Bus::default());
+}
+
+/// Returns the path of the last page view.
+pub fn current_route() -> Option<String> {
+    BUS.with(|bus| bus.borrow().route.clone())
+}
+
+/// Enriches the event and sends it to all sinks.
+pub fn track<E: Into<Event>>(event: E) {
+    let mut event = event.into();
+    let (middleware, sinks) = BUS.with(|bus| {
+        let mut bus = bus.borrow_mut();
+        if event.kind == EventKind::PageView {
+            if let Some(path) = event.properties.get("path").and_then(Value::as_str) {
+                bus.route = Some(path.to_owned());
+            }
+        }
+        (bus.middleware.clone(), bus.sinks.clone())
+    });
+    for enricher in middleware {
+        if !enricher.borrow_mut().enrich(&mut event) {
+            return;
+        }
+    }
+    for sink in sinks {
+        // A sink which tracks an event itself doesn't receive it
+        if let Ok(mut sink) = sink.try_borrow_mut() {
+            sink.send(&event);
+        }
+    }
+}
+
+/// Sends events which sinks keep to batch them.
+pub fn flush() {
+    let sinks = BUS.with(|bus| bus.borrow().sinks.clone());
+    for sink in sinks {
+        if let Ok(mut sink) = sink.try_borrow_mut() {
+            sink.flush();
+        }
+    }
+}
+
+/// Returns a callback which tracks the event every time before it calls the `callback`.
+pub fn tracked<IN: 'static>(event: Event, callback: Callback<IN>) -> Callback<IN> {
+    Callback::from(move |value| {
+        track(event.clone());
+        callback.emit(value);
+    })
+}
+
+const SESSION_KEY: &str = "yew-analytics-session";
+
+/// Middleware which adds the `session` property with a random id. The id
+/// is kept in the session storage, so it's the same until the tab is closed.
+pub struct Session {
+    id: String,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Session::new()
+    }
+}
+
+impl Session {
+    /// Restores the id of the session or starts a new one.
+    pub fn new() -> Self {
+        Session::with_backends(backend::storage(), backend::random())
+    }
+
+    /// Same as `new`, but with the backends.
+    pub fn with_backends(storage: Rc<StorageBackend>, random: Rc<RandomBackend>) -> Self {
+        let id = storage.get(Scope::Session, SESSION_KEY).unwrap_or_else(|| {
+            let mut bytes = [0; 8];
+            random.fill(&mut bytes);
+            let id: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+            storage.set(Scope::Session, SESSION_KEY, &id);
+            id
+        });
+        Session { id }
+    }
+
+    /// Returns the id of the session.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl Enricher for Session {
+    fn enrich(&mut self, event: &mut Event) -> bool {
+        event.properties.insert("session".into(), Value::String(self.id.clone()));
+        true
+    }
+}
+
+/// Middleware which adds the `route` property with the path of the last page view.
+pub struct CurrentRoute;
+
+impl Enricher for CurrentRoute {
+    fn enrich(&mut self, event: &mut Event) -> bool {
+        if let Some(route) = current_route() {
+            event.properties.insert("route".into(), Value::String(route));
+        }
+        true
+    }
+}
+
+struct Outbox {
+    endpoint: String,
+    max: usize,
+    delay: Duration,
+    capacity: usize,
+    events: Vec<Event>,
+    online: bool,
+    timer: Option<Box<Task>>,
+    beacon: Rc<BeaconBackend>,
+    fetch: Rc<FetchBackend>,
+    timers: Rc<TimerBackend>,
+}
+
+impl Outbox {
+    fn keep(&mut self, events: Vec<Event>) {
+        self.events.extend(events);
+        if self.events.len() > self.capacity {
+            // The oldest events are dropped when a browser is offline for long
+            let excess = self.events.len() - self.capacity;
+            self.events.drain(..excess);
+        }
+    }
+
+    fn send(outbox: &Rc<RefCell<Outbox>>) {
+        let mut this = outbox.borrow_mut();
+        if let Some(mut timer) = this.timer.take() {
+            if timer.is_active() {
+                timer.cancel();
+            }
+        }
+        if !this.online || this.events.is_empty() {
+            return;
+        }
+        let events: Vec<Event> = this.events.drain(..).collect();
+        let body = payload(&events);
+        if this.beacon.send(&this.endpoint, &body) {
+            return;
+        }
+        let mut request = Request::post(this.endpoint.as_str());
+        request.header("Content-Type", "application/json");
+        let request = request.body(Some(body)).expect("wrong endpoint of events");
+        let weak = Rc::downgrade(outbox);
+        let mut events = Some(events);
+        let callback = move |response: Response<Restorable>| {
+            let failed = response.body().is_err() || response.status().is_server_error();
+            if let (true, Some(outbox), Some(events)) = (failed, weak.upgrade(), events.take()) {
+                // Events are sent again with the next batch
+                let mut outbox = outbox.borrow_mut();
+                let queued = mem::take(&mut outbox.events);
+                outbox.keep(events);
+                outbox.keep(queued);
+            }
+        };
+        this.fetch.fetch(interceptor::fetch(request), Box::new(callback));
+    }
+
+    fn schedule(outbox: &Rc<RefCell<Outbox>>) {
+        let mut this = outbox.borrow_mut();
+        if this.timer.is_some() || this.events.is_empty() {
+            return;
+        }
+        let weak: Weak<RefCell<Outbox>> = Rc::downgrade(outbox);
+        let callback = move || {
+            if let Some(outbox) = weak.upgrade() {
+                outbox.borrow_mut().timer = None;
+                Outbox::send(&outbox);
+            }
+        };
+        let timer = this.timers.timeout(this.delay, Box::new(callback));
+        this.timer = Some(timer);
+    }
+}
+
+/// A sink which sends batches of events to an endpoint with `sendBeacon`,
+/// or with the backend of the fetch service if a browser refused a beacon.
+/// A batch is sent when it's full, a while after its first event and when
+/// the page is hidden. Events are kept while a browser is offline and
+/// after failed fetches, and sent when it's online again.
+pub struct BeaconSink {
+    outbox: Rc<RefCell<Outbox>>,
+    _subscriptions: Vec<Box<Task>>,
+}
+
+impl BeaconSink {
+    /// Creates a sink which sends up to 20 events a request, 10 seconds after
+    /// the first one, and keeps up to 500 events while offline.
+    pub fn new(endpoint: &str) -> Self {
+        BeaconSink::with_backends(endpoint, backend::beacon(), backend::fetch(), backend::timers(),
+                                  backend::connectivity(), backend::visibility())
+    }
+
+    /// Same as `new`, but with the backends.
+    pub fn with_backends(endpoint: &str, beacon: Rc<BeaconBackend>, fetch: Rc<FetchBackend>,
+                         timers: Rc<TimerBackend>, connectivity: Rc<ConnectivityBackend>,
+                         visibility: Rc<VisibilityBackend>) -> Self {
+        let outbox = Rc::new(RefCell::new(Outbox {
+            endpoint: endpoint.to_owned(),
+            max: 20,
+            delay: Duration::from_secs(10),
+            capacity: 500,
+            events: Vec::new(),
+            online: connectivity.is_online(),
+            timer: None,
+            beacon,
+            fetch,
+            timers,
+        }));
+        let weak = Rc::downgrade(&outbox);
+        let online = connectivity.listen(Box::new(move |online| {
+            if let Some(outbox) = weak.upgrade() {
+                outbox.borrow_mut().online = online;
+                if online {
+                    Outbox::send(&outbox);
+                }
+            }
+        }));
+        let weak = Rc::downgrade(&outbox);
+        let hidden = visibility.listen(Box::new(move |hidden| {
+            // A hidden page could be closed without other events
+            if let (true, Some(outbox)) = (hidden, weak.upgrade()) {
+                Outbox::send(&outbox);
+            }
+        }));
+        BeaconSink {
+            outbox,
+            _subscriptions: vec![online, hidden],
+        }
+    }
+
+    /// Sets the maximal size of a batch and how long the first event waits for others.
+    pub fn batch(self, max: usize, delay: Duration) -> Self {
+        {
+            let mut outbox = self.outbox.borrow_mut();
+            outbox.max = max.max(1);
+            outbox.delay = delay;
+        }
+        self
+    }
+
+    /// Sets how many events are kept while a browser is offline.
+    pub fn capacity(self, capacity: usize) -> Self {
+        self.outbox.borrow_mut().capacity = capacity.max(1);
+        self
+    }
+}
+
+impl AnalyticsSink for BeaconSink {
+    fn send(&mut self, event: &Event) {
+        let full = {
+            let mut outbox = self.outbox.borrow_mut();
+            outbox.keep(vec![event.clone()]);
+            outbox.events.len() >= outbox.max
+        };
+        if full {
+            Outbox::send(&self.outbox);
+        } else {
+            Outbox::schedule(&self.outbox);
+        }
+    }
+
+    fn flush(&mut self) {
+        Outbox::send(&self.outbox);
+    }
+}
@@ -0,0 +1,557 @@
+//! This module contains typed roles and states of WAI-ARIA widgets, so
+//! a view doesn't misspell a role or forget a state the role requires.
+//! `Role` is a value of the `role` attribute and `Aria` collects a role
+//! with its states, which are spread onto an element with `..`:
+//!
+//! ```rust,ignore
+//! html! {
+//!     <input ..Aria::combobox(self.open)
+//!                .controls("suggestions")
+//!                .has_popup(Popup::Listbox)
+//!                .active_descendant(self.highlighted.as_ref()),/>
+//!     <ul id="suggestions", role=Role::Listbox,>
+//!         { for self.items.iter().map(|item| html! {
+//!             <li ..Aria::option(item.id == self.selected).label(&item.name),>{ &item.name }</li>
+//!         }) }
+//!     </ul>
+//! }
+//! ```
+//!
+//! Constructors of widget roles take the states which the role requires,
+//! like `aria-checked` of a checkbox or `aria-valuenow` of a slider.
+
+use std::fmt;
+use std::vec;
+
+/// A role of an element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Role {
+    /// An important message which is announced at once.
+    Alert,
+    /// A dialog with an alert which needs a response.
+    AlertDialog,
+    /// A banner of a page, like a header.
+    Banner,
+    /// A button.
+    Button,
+    /// A checkbox with `aria-checked`.
+    Checkbox,
+    /// An input with a popup of suggestions and `aria-expanded`.
+    Combobox,
+    /// A dialog.
+    Dialog,
+    /// A grid of cells which are navigated by arrows.
+    Grid,
+    /// A cell of a grid.
+    GridCell,
+    /// A heading with `aria-level`.
+    Heading,
+    /// A link.
+    Link,
+    /// A list of options to select from.
+    Listbox,
+    /// A log of messages, like a chat.
+    Log,
+    /// The main content of a page.
+    Main,
+    /// A menu.
+    Menu,
+    /// A bar of menus.
+    Menubar,
+    /// An item of a menu.
+    MenuItem,
+    /// A checkable item of a menu with `aria-checked`.
+    MenuItemCheckbox,
+    /// A radio item of a menu with `aria-checked`.
+    MenuItemRadio,
+    /// Navigation links.
+    Navigation,
+    /// An option of a listbox with `aria-selected`.
+    Option,
+    /// An element without semantics, like a layout table.
+    Presentation,
+    /// A progress bar.
+    Progressbar,
+    /// A radio button with `aria-checked`.
+    Radio,
+    /// A group of radio buttons.
+    RadioGroup,
+    /// A landmark region with a label.
+    Region,
+    /// A row of a grid or a table.
+    Row,
+    /// A search landmark.
+    Search,
+    /// A separator.
+    Separator,
+    /// A slider with `aria-valuenow`.
+    Slider,
+    /// A spin button with `aria-valuenow`.
+    SpinButton,
+    /// A status message which is announced politely.
+    Status,
+    /// A switch with `aria-checked`.
+    Switch,
+    /// A tab of a tab list with `aria-selected`.
+    Tab,
+    /// A list of tabs.
+    TabList,
+    /// A panel of a tab.
+    TabPanel,
+    /// A text field.
+    Textbox,
+    /// A toolbar.
+    Toolbar,
+    /// A tooltip.
+    Tooltip,
+    /// A tree.
+    Tree,
+    /// An item of a tree.
+    TreeItem,
+}
+
+impl Role {
+    /// Returns the value of the `role` attribute.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Role::Alert => "alert",
+            Role::AlertDialog => "alertdialog",
+            Role::Banner => "banner",
+            Role::Button => "button",
+            Role::Checkbox => "checkbox",
+            Role::Combobox => "combobox",
+            Role::Dialog => "dialog",
+            Role::Grid => "grid",
+            Role::GridCell => "gridcell",
+            Role::Heading => "heading",
+            Role::Link => "link",
+            Role::Listbox => "listbox",
+            Role::Log => "log",
+            Role::Main => "main",
+            Role::Menu => "menu",
+            Role::Menubar => "menubar",
+            Role::MenuItem => "menuitem",
+            Role::MenuItemCheckbox => "menuitemcheckbox",
+            Role::MenuItemRadio => "menuitemradio",
+            Role::Navigation => "navigation",
+            Role::Option => "option",
+            Role::Presentation => "presentation",
+            Role::Progressbar => "progressbar",
+            Role::Radio => "radio",
+            Role::RadioGroup => "radiogroup",
+            Role::Region => "region",
+            Role::Row => "row",
+            Role::Search => "search",
+            Role::Separator => "separator",
+            Role::Slider => "slider",
+            Role::SpinButton => "spinbutton",
+            Role::Status => "status",
+            Role::Switch => "switch",
+            Role::Tab => "tab",
+            Role::TabList => "tablist",
+            Role::TabPanel => "tabpanel",
+            Role::Textbox => "textbox",
+            Role::Toolbar => "toolbar",
+            Role::Tooltip => "tooltip",
+            Role::Tree => "tree",
+            Role::TreeItem => "treeitem",
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A state which could be mixed, like a checkbox of a partly checked group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tristate {
+    /// It's on.
+    True,
+    /// It's off.
+    False,
+    /// Some of the children are on.
+    Mixed,
+}
+
+impl From<bool> for Tristate {
+    fn from(value: bool) -> Self {
+        if value {
+            Tristate::True
+        } else {
+            Tristate::False
+        }
+    }
+}
+
+impl fmt::Display for Tristate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Tristate::True => "true",
+            Tristate::False => "false",
+            Tristate::Mixed => "mixed",
+        })
+    }
+}
+
+/// A kind of a popup of `aria-haspopup`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Popup {
+    /// A menu.
+    Menu,
+    /// A listbox.
+    Listbox,
+    /// A tree.
+    Tree,
+    /// A grid.
+    Grid,
+    /// A dialog.
+    Dialog,
+}
+
+impl fmt::Display for Popup {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Popup::Menu => "menu",
+            Popup::Listbox => "listbox",
+            Popup::Tree => "tree",
+            Popup::Grid => "grid",
+            Popup::Dialog => "dialog",
+        })
+    }
+}
+
+/// How changes of a live region are announced, the `aria-live` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Live {
+    /// Changes aren't announced.
+    Off,
+    /// Changes are announced when a user is idle.
+    Polite,
+    /// Changes are announced at once.
+    Assertive,
+}
+
+impl fmt::Display for Live {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Live::Off => "off",
+            Live::Polite => "polite",
+            Live::Assertive => "assertive",
+        })
+    }
+}
+
+/// A current item of a set, the `aria-current` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Current {
+    /// The current page of navigation links.
+    Page,
+    /// The current step of a process.
+    Step,
+    /// The current location of a chart.
+    Location,
+    /// The current date of a calendar.
+    Date,
+    /// The current time of a timetable.
+    Time,
+    /// The current item of other sets.
+    True,
+}
+
+impl fmt::Display for Current {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Current::Page => "page",
+            Current::Step => "step",
+            Current::Location => "location",
+            Current::Date => "date",
+            Current::Time => "time",
+            Current::True => "true",
+        })
+    }
+}
+
+/// An order of a sorted column, the `aria-sort` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sort {
+    /// From the smallest value.
+    Ascending,
+    /// From the largest value.
+    Descending,
+    /// Another order.
+    Other,
+}
+
+impl fmt::Display for Sort {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Sort::Ascending => "ascending",
+            Sort::Descending => "descending",
+            Sort::Other => "other",
+        })
+    }
+}
+
+/// An orientation of a widget, the `aria-orientation` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    /// Items go from left to right.
+    Horizontal,
+    /// Items go from top to bottom.
+    Vertical,
+}
+
+impl fmt::Display for Orientation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Orientation::Horizontal => "horizontal",
+            Orientation::Vertical => "vertical",
+        })
+    }
+}
+
+/// A role with states, which is spread onto an element with `..`.
+/// A state which is set twice keeps the last value.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Aria {
+    attributes: Vec<(&'static str, String)>,
+}
+
+impl Aria {
+    /// Creates states without a role, for elements with a native role.
+    pub fn new() -> Self {
+        Aria::default()
+    }
+
+    /// Creates states of an element with the role. Use a constructor of
+    /// the role if there is one, it takes states the role requires.
+    pub fn role(role: Role) -> Self {
+        Aria::new().set("role", role)
+    }
+
+    /// A button which toggles, with `aria-pressed`.
+    pub fn toggle_button<T: Into<Tristate>>(pressed: T) -> Self {
+        Aria::role(Role::Button).set("aria-pressed", pressed.into())
+    }
+
+    /// A checkbox with `aria-checked`.
+    pub fn checkbox<T: Into<Tristate>>(checked: T) -> Self {
+        Aria::role(Role::Checkbox).checked(checked)
+    }
+
+    /// A switch with `aria-checked`.
+    pub fn switch(on: bool) -> Self {
+        Aria::role(Role::Switch).checked(on)
+    }
+
+    /// A radio button with `aria-checked`.
+    pub fn radio(checked: bool) -> Self {
+        Aria::role(Role::Radio).checked(checked)
+    }
+
+    /// A checkable item of a menu with `aria-checked`.
+    pub fn menu_item_checkbox<T: Into<Tristate>>(checked: T) -> Self {
+        Aria::role(Role::MenuItemCheckbox).checked(checked)
+    }
+
+    /// A radio item of a menu with `aria-checked`.
+    pub fn menu_item_radio(checked: bool) -> Self {
+        Aria::role(Role::MenuItemRadio).checked(checked)
+    }
+
+    /// An input with a popup of suggestions with `aria-expanded`.
+    pub fn combobox(expanded: bool) -> Self {
+        Aria::role(Role::Combobox).expanded(expanded)
+    }
+
+    /// An option of a listbox with `aria-selected`.
+    pub fn option(selected: bool) -> Self {
+        Aria::role(Role::Option).selected(selected)
+    }
+
+    /// A tab with `aria-selected` which controls the panel with the id.
+    pub fn tab(selected: bool, panel: &str) -> Self {
+        Aria::role(Role::Tab).selected(selected).controls(panel)
+    }
+
+    /// A heading with `aria-level`, from 1.
+    pub fn heading(level: u32) -> Self {
+        Aria::role(Role::Heading).set("aria-level", level.max(1))
+    }
+
+    /// A slider with `aria-valuenow` of the range.
+    pub fn slider(value: f64, min: f64, max: f64) -> Self {
+        Aria::role(Role::Slider).range(value, min, max)
+    }
+
+    /// A spin button with `aria-valuenow` of the range.
+    pub fn spin_button(value: f64, min: f64, max: f64) -> Self {
+        Aria::role(Role::SpinButton).range(value, min, max)
+    }
+
+    /// A progress bar. The value is `None` while the progress isn't known.
+    pub fn progressbar(value: Option<f64>, min: f64, max: f64) -> Self {
+        let aria = Aria::role(Role::Progressbar)
+            .set("aria-valuemin", min)
+            .set("aria-valuemax", max);
+        match value {
+            Some(value) => aria.set("aria-valuenow", value),
+            None => aria,
+        }
+    }
+
+    fn range(self, value: f64, min: f64, max: f64) -> Self {
+        self.set("aria-valuenow", value)
+            .set("aria-valuemin", min)
+            .set("aria-valuemax", max)
+    }
+
+    fn set<T: ToString>(mut self, name: &'static str, value: T) -> Self {
+        let value = value.to_string();
+        match self.attributes.iter_mut().find(|item| item.0 == name) {
+            Some(item) => item.1 = value,
+            None => self.attributes.push((name, value)),
+        }
+        self
+    }
+
+    /// Sets `aria-label`, a name of an element without a visible text.
+    pub fn label(self, label: &str) -> Self {
+        self.set("aria-label", label)
+    }
+
+    /// Sets `aria-labelledby`, an id of an element with the name.
+    pub fn labelled_by(self, id: &str) -> Self {
+        self.set("aria-labelledby", id)
+    }
+
+    /// Sets `aria-describedby`, an id of an element with a description.
+    pub fn described_by(self, id: &str) -> Self {
+        self.set("aria-describedby", id)
+    }
+
+    /// Sets `aria-controls`, an id of an element which the widget changes.
+    pub fn controls(self, id: &str) -> Self {
+        self.set("aria-controls", id)
+    }
+
+    /// Sets `aria-owns`, an id of a child element which is rendered elsewhere.
+    pub fn owns(self, id: &str) -> Self {
+        self.set("aria-owns", id)
+    }
+
+    /// Sets `aria-activedescendant`, an id of the focused item of a composite
+    /// widget. It's omitted if there is no such item.
+    pub fn active_descendant<T: AsRef<str>>(mut self, id: Option<T>) -> Self {
+        match id {
+            Some(id) => self.set("aria-activedescendant", id.as_ref()),
+            None => {
+                self.attributes.retain(|item| item.0 != "aria-activedescendant");
+                self
+            }
+        }
+    }
+
+    /// Sets `aria-expanded` of a widget with a popup or a collapsible section.
+    pub fn expanded(self, expanded: bool) -> Self {
+        self.set("aria-expanded", expanded)
+    }
+
+    /// Sets `aria-selected`.
+    pub fn selected(self, selected: bool) -> Self {
+        self.set("aria-selected", selected)
+    }
+
+    /// Sets `aria-checked`.
+    pub fn checked<T: Into<Tristate>>(self, checked: T) -> Self {
+        self.set("aria-checked", checked.into())
+    }
+
+    /// Sets `aria-disabled`.
+    pub fn disabled(self, disabled: bool) -> Self {
+        self.set("aria-disabled", disabled)
+    }
+
+    /// Sets `aria-hidden`, which hides an element from assistive technologies.
+    pub fn hidden(self, hidden: bool) -> Self {
+        self.set("aria-hidden", hidden)
+    }
+
+    /// Sets `aria-invalid` of a field.
+    pub fn invalid(self, invalid: bool) -> Self {
+        self.set("aria-invalid", invalid)
+    }
+
+    /// Sets `aria-required` of a field.
+    pub fn required(self, required: bool) -> Self {
+        self.set("aria-required", required)
+    }
+
+    /// Sets `aria-readonly` of a field.
+    pub fn readonly(self, readonly: bool) -> Self {
+        self.set("aria-readonly", readonly)
+    }
+
+    /// Sets `aria-busy` while a region is updated.
+    pub fn busy(self, busy: bool) -> Self {
+        self.set("aria-busy", busy)
+    }
+
+    /// Sets `aria-multiselectable` of a listbox, a grid or a tree.
+    pub fn multiselectable(self, multiselectable: bool) -> Self {
+        self.set("aria-multiselectable", multiselectable)
+    }
+
+    /// Sets `aria-haspopup`.
+    pub fn has_popup(self, popup: Popup) -> Self {
+        self.set("aria-haspopup", popup)
+    }
+
+    /// Sets `aria-live`.
+    pub fn live(self, live: Live) -> Self {
+        self.set("aria-live", live)
+    }
+
+    /// Sets `aria-current`.
+    pub fn current(self, current: Current) -> Self {
+        self.set("aria-current", current)
+    }
+
+    /// Sets `aria-sort` of a header of a column.
+    pub fn sort(self, sort: Sort) -> Self {
+        self.set("aria-sort", sort)
+    }
+
+    /// Sets `aria-orientation`.
+    pub fn orientation(self, orientation: Orientation) -> Self {
+        self.set("aria-orientation", orientation)
+    }
+
+    /// Sets `aria-valuetext`, a readable value of a range, like `"3 of 5 stars"`.
+    pub fn value_text(self, text: &str) -> Self {
+        self.set("aria-valuetext", text)
+    }
+
+    /// Sets `aria-posinset` and `aria-setsize` of an item of a set which isn't
+    /// rendered completely, like a virtual list. The position is from 1.
+    pub fn position(self, position: usize, size: usize) -> Self {
+        self.set("aria-posinset", position).set("aria-setsize", size)
+    }
+
+    /// Returns the value of an attribute.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|item| item.0 == name).map(|item| item.1.as_str())
+    }
+}
+
+impl IntoIterator for Aria {
+    type Item = (&'static str, String);
+    type IntoIter = vec::IntoIter<(&'static str, String)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.attributes.into_iter()
+    }
+}
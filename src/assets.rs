@@ -0,0 +1,169 @@
+//! This module contains static assets which are embedded into an app at
+//! build time, so components don't concatenate paths of images and styles.
+//! `asset!` hashes a file of the `static` directory of the crate, which
+//! `cargo web` serves, and a build fails if the file is missing:
+//!
+//! ```rust,ignore
+//! const LOGO: Asset = asset!("images/logo.png");
+//!
+//! html! {
+//!     <img src=LOGO.url(), alt="Logo",/>
+//! }
+//! ```
+//!
+//! A URL ends with a hash of the content, like `/images/logo.png?v=5d41402a`,
+//! so browsers don't keep stale copies after a deploy. Apps which serve
+//! assets from another place, like a CDN, set it once with `assets::set_base`.
+//! The hash is computed by the compiler, so the content isn't a part of an app.
+//! Small files could be embedded with `asset!(inline "images/icon.svg")`
+//! to use them with `data_url` or `text` instead.
+
+use std::cell::RefCell;
+use std::path::Path;
+
+thread_local! {
+    static BASE: RefCell<String> = RefCell::new("/".to_owned());
+}
+
+/// Sets the URL which paths of assets are relative to, like
+/// `https://cdn.example.com/app/`. It's `/` by default.
+pub fn set_base(base: &str) {
+    let mut base = base.to_owned();
+    if !base.ends_with('/') {
+        base.push('/');
+    }
+    BASE.with(|current| *current.borrow_mut() = base);
+}
+
+/// Returns the URL which paths of assets are relative to.
+pub fn base() -> String {
+    BASE.with(|base| base.borrow().clone())
+}
+
+/// Returns a hash of the content which `asset!` computes at build time.
+pub const fn content_hash(bytes: &[u8]) -> u32 {
+    // FNV-1a, which is enough to notice changes of files
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    let mut index = 0;
+    while index < bytes.len() {
+        hash = (hash ^ bytes[index] as u64).wrapping_mul(0x0000_0100_0000_01b3);
+        index += 1;
+    }
+    (hash >> 32) as u32 ^ hash as u32
+}
+
+/// A file of the `static` directory hashed with `asset!`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Asset {
+    path: &'static str,
+    hash: u32,
+    bytes: Option<&'static [u8]>,
+}
+
+impl Asset {
+    /// Creates an asset of the path relative to the base with the content,
+    /// which is embedded into an app. Use `asset!(inline ...)` to include the file.
+    pub const fn new(path: &'static str, bytes: &'static [u8]) -> Self {
+        Asset { path, hash: content_hash(bytes), bytes: Some(bytes) }
+    }
+
+    /// Creates an asset of the path relative to the base with the hash
+    /// of its content. Use `asset!` to hash the file.
+    pub const fn hashed(path: &'static str, hash: u32) -> Self {
+        Asset { path, hash, bytes: None }
+    }
+
+    /// Returns the path relative to the `static` directory.
+    pub fn path(&self) -> &'static str {
+        self.path
+    }
+
+    /// Returns the content of an embedded file.
+    pub fn bytes(&self) -> Option<&'static [u8]> {
+        self.bytes
+    }
+
+    /// Returns the content of an embedded text file, like a style sheet.
+    /// It's `None` if the file isn't embedded or isn't UTF-8.
+    pub fn text(&self) -> Option<&'static str> {
+        self.bytes.and_then(|bytes| ::std::str::from_utf8(bytes).ok())
+    }
+
+    /// Returns a hash of the content, 8 hexadecimal digits.
+    pub fn hash(&self) -> String {
+        format!("{:08x}", self.hash)
+    }
+
+    /// Returns the URL of the file with the hash of its content.
+    pub fn url(&self) -> String {
+        format!("{}{}?v={:08x}", base(), self.path.trim_start_matches('/'), self.hash)
+    }
+
+    /// Returns the type of the content by the extension of the file.
+    pub fn mime(&self) -> &'static str {
+        let extension = Path::new(self.path).extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_lowercase);
+        match extension.as_deref() {
+            Some("png") => "image/png",
+            Some("jpg") | Some("jpeg") => "image/jpeg",
+            Some("gif") => "image/gif",
+            Some("svg") => "image/svg+xml",
+            Some("webp") => "image/webp",
+            Some("ico") => "image/x-icon",
+            Some("css") => "text/css",
+            Some("js") => "text/javascript",
+            Some("json") => "application/json",
+            Some("html") => "text/html",
+            Some("txt") => "text/plain",
+            Some("woff") => "font/woff",
+            Some("woff2") => "font/woff2",
+            Some("mp3") => "audio/mpeg",
+            Some("mp4") => "video/mp4",
+            _ => "application/octet-stream",
+        }
+    }
+
+    /// Returns a `data:` URL with the content of an embedded file,
+    /// which doesn't need a request.
+    pub fn data_url(&self) -> Option<String> {
+        self.bytes.map(|bytes| format!("data:{};base64,{}", self.mime(), base64(bytes)))
+    }
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64(bytes: &[u8]) -> String {
+    let mut text = String::new();
+    for chunk in bytes.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0u32, |bits, (index, &byte)| bits | u32::from(byte) << (16 - index * 8));
+        for index in 0..4 {
+            if index <= chunk.len() {
+                text.push(BASE64[(bits >> (18 - index * 6)) as usize & 63] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
+
+/// Hashes a file of the `static` directory of the crate as an `Asset`.
+/// The path is relative to the directory, like `images/logo.png`.
+/// With `inline` before the path the content is embedded too.
+#[macro_export]
+macro_rules! asset {
+    (inline $path:expr) => {{
+        const ASSET: $crate::assets::Asset = $crate::assets::Asset::new(
+            $path,
+            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/static/", $path)),
+        );
+        ASSET
+    }};
+    ($path:expr) => {{
+        const HASH: u32 = $crate::assets::content_hash(
+            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/static/", $path)),
+        );
+        $crate::assets::Asset::hashed($path, HASH)
+    }};
+}
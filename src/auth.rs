@@ -0,0 +1,430 @@
+//! This module keeps a session of a logged in user. The session is kept
+//! in a storage between visits, its token is attached to requests of
+//! services, and it's refreshed before it expires:
+//!
+//! ```rust,ignore
+//! auth::configure(AuthConfig::new()
+//!     .attach_to("https://api.example/")
+//!     .refresh(Duration::from_secs(60), |session: &Session| {
+//!         Request::post("/auth/refresh").body(Some(session.refresh_token.clone().unwrap_or_default())).unwrap()
+//!     }, |response: Response<Json<Result<Tokens, Error>>>| {
+//!         response.into_body().0.ok().map(Tokens::into_session)
+//!     }));
+//!
+//! // A response of a login form
+//! Msg::LoggedIn(tokens) => auth::login(tokens.into_session()),
+//!
+//! // A component which shows a login page when the session ends
+//! let handle = AuthService::new(sender).listen(Msg::Auth);
+//! ```
+//!
+//! Requests to relative urls and urls with the prefixes of `attach_to` get
+//! an `Authorization: Bearer` header, and websockets to the prefixes get
+//! the token in the query, since browsers can't set headers of them.
+
+use std::cell::RefCell;
+use std::mem;
+use std::rc::Rc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use http::header::{HeaderValue, AUTHORIZATION};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use format::{Json, Restorable, Storable};
+use html::AppSender;
+use services::Task;
+use services::backend;
+use services::fetch::{Request, Response};
+use services::interceptor::{self, Interceptor, InterceptorHandle};
+use services::storage::{Scope, StorageService};
+
+/// A session of a logged in user.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    /// The token which is attached to requests.
+    pub token: String,
+    /// The token to get a new session when this one expires.
+    pub refresh_token: Option<String>,
+    /// The time when the token expires. A session without it doesn't expire.
+    pub expires_at: Option<SystemTime>,
+}
+
+impl Session {
+    /// Creates a session which doesn't expire.
+    pub fn new<T: Into<String>>(token: T) -> Self {
+        Session {
+            token: token.into(),
+            refresh_token: None,
+            expires_at: None,
+        }
+    }
+
+    /// Sets the time when the session expires from now, like `expires_in` of OAuth.
+    pub fn expires_in(mut self, duration: Duration) -> Self {
+        self.expires_at = Some(now() + duration);
+        self
+    }
+
+    /// Sets the refresh token.
+    pub fn refresh_token<T: Into<String>>(mut self, token: T) -> Self {
+        self.refresh_token = Some(token.into());
+        self
+    }
+
+    /// Returns `true` if the session expired.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.map_or(false, |expires_at| expires_at <= now())
+    }
+}
+
+impl Serialize for Session {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let expires_at = self.expires_at.map(|time| millis(time.duration_since(UNIX_EPOCH).unwrap_or_default()));
+        (&self.token, &self.refresh_token, expires_at).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Session {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (token, refresh_token, expires_at) = <(String, Option<String>, Option<u64>)>::deserialize(deserializer)?;
+        Ok(Session {
+            token,
+            refresh_token,
+            expires_at: expires_at.map(|ms| UNIX_EPOCH + Duration::from_millis(ms)),
+        })
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_secs() * 1000 + u64::from(duration.subsec_millis())
+}
+
+fn now() -> SystemTime {
+    backend::intl().now()
+}
+
+/// Changes of a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthEvent {
+    /// A user logged in.
+    LoggedIn,
+    /// The session was refreshed with a new token.
+    Refreshed,
+    /// A user logged out.
+    LoggedOut,
+    /// The session expired and couldn't be refreshed.
+    Expired,
+}
+
+type Refresh = Rc<Fn(&Session, Box<Fn(Option<Session>)>) -> Box<Task>>;
+
+/// Options of sessions.
+pub struct AuthConfig {
+    /// The storage which keeps a session between visits.
+    pub scope: Scope,
+    /// The key of a session in the storage.
+    pub key: String,
+    /// Prefixes of absolute urls which get the token, like `https://api.example/`.
+    pub attach_to: Vec<String>,
+    /// The name of the query parameter with the token of websockets.
+    /// Tokens aren't attached to websockets without it.
+    pub socket_param: Option<String>,
+    /// How long before the expiry a session is refreshed.
+    pub refresh_before: Duration,
+    refresh: Option<Refresh>,
+}
+
+impl AuthConfig {
+    /// Keeps a session in the local storage and attaches it to relative urls only.
+    pub fn new() -> Self {
+        AuthConfig {
+            scope: Scope::Local,
+            key: "session".into(),
+            attach_to: Vec::new(),
+            socket_param: Some("token".into()),
+            refresh_before: Duration::from_secs(60),
+            refresh: None,
+        }
+    }
+
+    /// Keeps a session in the storage by the key.
+    pub fn storage(mut self, scope: Scope, key: &str) -> Self {
+        self.scope = scope;
+        self.key = key.to_owned();
+        self
+    }
+
+    /// Attaches the token to urls with the prefix too.
+    pub fn attach_to(mut self, prefix: &str) -> Self {
+        self.attach_to.push(prefix.to_owned());
+        self
+    }
+
+    /// Sets the name of the query parameter of websockets, `None` doesn't attach tokens to them.
+    pub fn socket_param(mut self, name: Option<&str>) -> Self {
+        self.socket_param = name.map(str::to_owned);
+        self
+    }
+
+    /// Refreshes a session `before` it expires with a request built by `request`.
+    /// The response is turned into a new session by `parse`, and `None` ends
+    /// the session as expired. A new session without a refresh token keeps the old one.
+    pub fn refresh<R, IN, OUT, P>(mut self, before: Duration, request: R, parse: P) -> Self
+    where
+        R: Fn(&Session) -> Request<IN> + 'static,
+        IN: Into<Storable>,
+        OUT: From<Restorable> + 'static,
+        P: Fn(Response<OUT>) -> Option<Session> + 'static,
+    {
+        let parse = Rc::new(parse);
+        let refresh: Refresh = Rc::new(move |session: &Session, done: Box<Fn(Option<Session>)>| {
+            let (parts, body) = request(session).into_parts();
+            let parse = parse.clone();
+            let callback = move |response: Response<Restorable>| done(parse(response.map(OUT::from)));
+            backend::fetch().fetch(Request::from_parts(parts, body.into()), Box::new(callback))
+        });
+        self.refresh_before = before;
+        self.refresh = Some(refresh);
+        self
+    }
+
+    fn is_attached(&self, url: &str) -> bool {
+        let relative = url.starts_with('/') && !url.starts_with("//");
+        relative || self.attach_to.iter().any(|prefix| url.starts_with(prefix.as_str()))
+    }
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        AuthConfig::new()
+    }
+}
+
+type Listener = Rc<Fn(AuthEvent)>;
+
+#[derive(Default)]
+struct Auth {
+    config: Option<Rc<AuthConfig>>,
+    session: Option<Rc<Session>>,
+    // A refresh or an expiry which is due
+    timer: Option<Box<Task>>,
+    refresh: Option<Box<Task>>,
+    interceptor: Option<InterceptorHandle>,
+    next_id: usize,
+    listeners: Vec<(usize, Listener)>,
+}
+
+thread_local! {
+    static AUTH: RefCell<Auth> = RefCell::new(Auth::default());
+}
+
+struct Attach(Rc<AuthConfig>);
+
+impl Interceptor for Attach {
+    fn fetch(&self, request: &mut Request<Storable>) {
+        if let Some(token) = token() {
+            if self.0.is_attached(&request.uri().to_string()) {
+                if let Ok(value) = HeaderValue::from_str(&format!("Bearer {}", token)) {
+                    request.headers_mut().insert(AUTHORIZATION, value);
+                }
+            }
+        }
+    }
+
+    fn connect(&self, url: &str) -> Option<String> {
+        let param = self.0.socket_param.as_ref()?;
+        let token = token()?;
+        if !self.0.is_attached(url) {
+            return None;
+        }
+        let separator = if url.contains('?') { '&' } else { '?' };
+        Some(format!("{}{}{}={}", url, separator, param, token))
+    }
+}
+
+/// Sets options of sessions and restores a session from the storage.
+/// Call it once before the app is mounted.
+pub fn configure(config: AuthConfig) {
+    let config = Rc::new(config);
+    let stored = StorageService::new(config.scope).restore::<Json<Result<Session, ()>>>(&config.key).0.ok();
+    let handle = interceptor::add(Rc::new(Attach(config.clone())));
+    let previous = AUTH.with(|auth| {
+        let mut auth = auth.borrow_mut();
+        auth.config = Some(config);
+        auth.interceptor.replace(handle)
+    });
+    drop(previous);
+    if let Some(session) = stored {
+        set(session);
+    }
+}
+
+fn config() -> Rc<AuthConfig> {
+    AUTH.with(|auth| auth.borrow().config.clone()).expect("auth::configure wasn't called")
+}
+
+/// Starts a session and keeps it in the storage.
+pub fn login(session: Session) {
+    set(session);
+    notify(AuthEvent::LoggedIn);
+}
+
+/// Ends the session and removes it from the storage.
+pub fn logout() {
+    if session().is_some() {
+        end(AuthEvent::LoggedOut);
+    }
+}
+
+/// Returns the current session.
+pub fn session() -> Option<Rc<Session>> {
+    AUTH.with(|auth| auth.borrow().session.clone())
+}
+
+/// Returns the token of the current session.
+pub fn token() -> Option<String> {
+    session().map(|session| session.token.clone())
+}
+
+/// Returns `true` if a user is logged in.
+pub fn is_logged_in() -> bool {
+    session().is_some()
+}
+
+/// Refreshes the session now, like after a response with the 401 status.
+/// Returns `false` if there is no session or refreshes aren't configured.
+pub fn refresh() -> bool {
+    let session = match session() {
+        Some(session) => session,
+        None => return false,
+    };
+    let refresh = match config().refresh.clone() {
+        Some(refresh) => refresh,
+        None => return false,
+    };
+    if AUTH.with(|auth| auth.borrow().refresh.is_some()) {
+        return true;
+    }
+    let task = refresh(&session, Box::new(refreshed));
+    AUTH.with(|auth| auth.borrow_mut().refresh = Some(task));
+    true
+}
+
+fn refreshed(session: Option<Session>) {
+    AUTH.with(|auth| auth.borrow_mut().refresh.take());
+    match session {
+        Some(mut session) => {
+            if session.refresh_token.is_none() {
+                session.refresh_token = self::session().and_then(|previous| previous.refresh_token.clone());
+            }
+            set(session);
+            notify(AuthEvent::Refreshed);
+        }
+        None => end(AuthEvent::Expired),
+    }
+}
+
+/// Keeps the session and schedules its refresh or expiry.
+fn set(session: Session) {
+    let config = config();
+    if session.is_expired() && config.refresh.is_none() {
+        StorageService::new(config.scope).remove(&config.key);
+        return;
+    }
+    StorageService::new(config.scope).store(&config.key, Json(&session));
+    let timer = session.expires_at.map(|expires_at| {
+        let before = if config.refresh.is_some() { config.refresh_before } else { Duration::from_secs(0) };
+        let delay = expires_at.duration_since(now()).unwrap_or_default();
+        let delay = delay.checked_sub(before).unwrap_or_default();
+        backend::timers().timeout(delay, Box::new(due))
+    });
+    let previous = AUTH.with(|auth| {
+        let mut auth = auth.borrow_mut();
+        auth.session = Some(Rc::new(session));
+        (auth.refresh.take(), mem::replace(&mut auth.timer, timer))
+    });
+    cancel(previous.0.into_iter().chain(previous.1));
+}
+
+fn due() {
+    if !refresh() {
+        end(AuthEvent::Expired);
+    }
+}
+
+fn end(event: AuthEvent) {
+    let config = config();
+    StorageService::new(config.scope).remove(&config.key);
+    let tasks = AUTH.with(|auth| {
+        let mut auth = auth.borrow_mut();
+        auth.session = None;
+        (auth.timer.take(), auth.refresh.take())
+    });
+    cancel(tasks.0.into_iter().chain(tasks.1));
+    notify(event);
+}
+
+fn cancel<I: IntoIterator<Item = Box<Task>>>(tasks: I) {
+    for mut task in tasks {
+        if task.is_active() {
+            task.cancel();
+        }
+    }
+}
+
+fn notify(event: AuthEvent) {
+    let listeners: Vec<Listener> = AUTH.with(|auth| {
+        auth.borrow().listeners.iter().map(|(_, listener)| listener.clone()).collect()
+    });
+    for listener in listeners {
+        listener(event);
+    }
+}
+
+/// A service to get changes of the session as messages.
+pub struct AuthService<MSG> {
+    sender: AppSender<MSG>,
+}
+
+impl<MSG: 'static> AuthService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        AuthService { sender }
+    }
+
+    /// Sends a message from the `converter` every time the session changes.
+    pub fn listen<F>(&mut self, converter: F) -> AuthHandle
+    where
+        F: Fn(AuthEvent) -> MSG + 'static,
+    {
+        let tx = self.sender.clone();
+        let listener = move |event| tx.clone().send(converter(event));
+        AUTH.with(|auth| {
+            let mut auth = auth.borrow_mut();
+            let id = auth.next_id;
+            auth.next_id += 1;
+            auth.listeners.push((id, Rc::new(listener)));
+            AuthHandle(Some(id))
+        })
+    }
+}
+
+/// A handle of a subscription to changes of the session.
+pub struct AuthHandle(Option<usize>);
+
+impl Task for AuthHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let id = self.0.take().expect("tried to cancel a subscription to a session twice");
+        AUTH.with(|auth| auth.borrow_mut().listeners.retain(|&(other, _)| other != id));
+    }
+}
+
+impl Drop for AuthHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
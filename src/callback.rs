@@ -0,0 +1,59 @@
+//! This module contains the implementation of a `Callback` which
+//! components use to notify parents about changes.
+
+use std::fmt;
+use std::rc::Rc;
+
+/// A universal callback which can be passed to a component as a property.
+/// It's cheap to clone and two callbacks are equal if they point
+/// to the same function.
+pub struct Callback<IN>(Option<Rc<Fn(IN)>>);
+
+impl<IN> Callback<IN> {
+    /// Calls the callback with a value.
+    pub fn emit(&self, value: IN) {
+        if let Some(ref callback) = self.0 {
+            (callback)(value);
+        }
+    }
+
+    /// Returns `true` if the callback does nothing.
+    pub fn is_noop(&self) -> bool {
+        self.0.is_none()
+    }
+}
+
+impl<IN, F: Fn(IN) + 'static> From<F> for Callback<IN> {
+    fn from(func: F) -> Self {
+        Callback(Some(Rc::new(func)))
+    }
+}
+
+impl<IN> Clone for Callback<IN> {
+    fn clone(&self) -> Self {
+        Callback(self.0.clone())
+    }
+}
+
+/// Creates a callback which does nothing.
+impl<IN> Default for Callback<IN> {
+    fn default() -> Self {
+        Callback(None)
+    }
+}
+
+impl<IN> PartialEq for Callback<IN> {
+    fn eq(&self, other: &Callback<IN>) -> bool {
+        match (self.0.as_ref(), other.0.as_ref()) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<IN> fmt::Debug for Callback<IN> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Callback {{ noop: {} }}", self.is_noop())
+    }
+}
@@ -0,0 +1,123 @@
+//! This module contains a `Classes` type which merges class names
+//! from strings, options and conditional pairs.
+//!
+//! ```rust,ignore
+//! html! {
+//!     <li class=classes!("entry", ("completed", entry.completed), model.extra_class.clone()),></li>
+//! }
+//! ```
+
+use std::fmt;
+
+/// An ordered set of class names without duplicates.
+/// Strings with spaces are split to separate classes.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Classes {
+    set: Vec<String>,
+}
+
+impl Classes {
+    /// Creates an empty set of classes.
+    pub fn new() -> Self {
+        Classes {
+            set: Vec::new(),
+        }
+    }
+
+    /// Adds classes to the set. Classes which are in the set already are skipped.
+    pub fn push<T: Into<Classes>>(&mut self, classes: T) {
+        for class in classes.into().set {
+            if !self.set.contains(&class) {
+                self.set.push(class);
+            }
+        }
+    }
+
+    /// Returns `true` if the set contains the class.
+    pub fn contains(&self, class: &str) -> bool {
+        self.set.iter().any(|item| item == class)
+    }
+
+    /// Returns `true` if there are no classes in the set.
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    /// Iterates over classes in the order they were added.
+    pub fn iter<'a>(&'a self) -> ::std::slice::Iter<'a, String> {
+        self.set.iter()
+    }
+}
+
+impl<'a> From<&'a str> for Classes {
+    fn from(classes: &'a str) -> Self {
+        let set = classes.split_whitespace().map(String::from).collect();
+        let mut result = Classes::new();
+        result.push(Classes { set });
+        result
+    }
+}
+
+impl From<String> for Classes {
+    fn from(classes: String) -> Self {
+        Classes::from(classes.as_str())
+    }
+}
+
+impl<'a> From<&'a String> for Classes {
+    fn from(classes: &'a String) -> Self {
+        Classes::from(classes.as_str())
+    }
+}
+
+impl<'a> From<&'a Classes> for Classes {
+    fn from(classes: &'a Classes) -> Self {
+        classes.clone()
+    }
+}
+
+/// Adds classes if the value is `Some`.
+impl<T: Into<Classes>> From<Option<T>> for Classes {
+    fn from(classes: Option<T>) -> Self {
+        classes.map(Into::into).unwrap_or_default()
+    }
+}
+
+/// Adds classes if the flag is `true`.
+impl<T: Into<Classes>> From<(T, bool)> for Classes {
+    fn from((classes, flag): (T, bool)) -> Self {
+        if flag {
+            classes.into()
+        } else {
+            Classes::new()
+        }
+    }
+}
+
+impl<T: Into<Classes>> From<Vec<T>> for Classes {
+    fn from(list: Vec<T>) -> Self {
+        let mut classes = Classes::new();
+        for item in list {
+            classes.push(item);
+        }
+        classes
+    }
+}
+
+impl fmt::Display for Classes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.set.join(" "))
+    }
+}
+
+/// Merges strings, `Option`s, `(class, flag)` pairs and other `Classes`
+/// into a single `Classes` value.
+#[macro_export]
+macro_rules! classes {
+    ($($class:expr),* $(,)*) => {{
+        #[allow(unused_mut)]
+        let mut classes = $crate::classes::Classes::new();
+        $( classes.push($class); )*
+        classes
+    }};
+}
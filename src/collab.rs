@@ -0,0 +1,520 @@
+//! This module contains replicated data types which merge concurrent edits
+//! of several clients without conflicts, and `Shared` which syncs them over
+//! a websocket. Every client has a unique replica id, like one assigned by
+//! a server, and changes are sent as deltas which are applied in any order:
+//!
+//! ```rust,ignore
+//! fn create(_: Self::Properties, sender: AppSender<Msg>) -> Self {
+//!     let mut sockets = WebSocketService::new(sender);
+//!     let mut text = Shared::new(Text::new(replica));
+//!     text.connect(&mut sockets, "wss://docs.example/42", Msg::Sync);
+//!     Editor { text, sockets }
+//! }
+//!
+//! fn update(&mut self, msg: Msg) -> ShouldRender {
+//!     match msg {
+//!         Msg::Typed(index, chunk) => self.text.edit(|text| text.insert_str(index, &chunk)),
+//!         Msg::Sync(msg) => self.text.handle(msg),
+//!     }
+//! }
+//! ```
+//!
+//! A server relays deltas to other clients. The whole state is sent when
+//! a connection opens, so edits made offline are merged after a reconnect.
+
+use std::cmp;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::iter::FromIterator;
+use std::rc::Rc;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use component::ShouldRender;
+use format::Json;
+use services::Task;
+use services::websocket::{WebSocketHandle, WebSocketService, WebSocketStatus};
+
+/// A data type which merges deltas from other replicas.
+pub trait Crdt {
+    /// A change which is sent to other replicas.
+    type Delta;
+
+    /// Merges a delta. Returns `true` if the value changed.
+    /// Deltas are idempotent and could come in any order.
+    fn apply(&mut self, delta: Self::Delta) -> bool;
+
+    /// Returns a delta with the whole state for replicas which missed changes.
+    fn state(&self) -> Self::Delta;
+}
+
+/// A Lamport timestamp of a change. Changes of different replicas
+/// made at the same time are ordered by replica ids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Stamp {
+    /// The count of changes known to the replica when the change was made.
+    pub counter: u64,
+    /// The id of the replica which made the change.
+    pub replica: u64,
+}
+
+impl Serialize for Stamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.counter, self.replica).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Stamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (counter, replica) = <(u64, u64)>::deserialize(deserializer)?;
+        Ok(Stamp { counter, replica })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Clock {
+    replica: u64,
+    counter: u64,
+}
+
+impl Clock {
+    fn new(replica: u64) -> Self {
+        Clock { replica, counter: 0 }
+    }
+
+    fn tick(&mut self) -> Stamp {
+        self.counter += 1;
+        Stamp {
+            counter: self.counter,
+            replica: self.replica,
+        }
+    }
+
+    fn observe(&mut self, stamp: Stamp) {
+        self.counter = cmp::max(self.counter, stamp.counter);
+    }
+}
+
+/// A map where the latest write of a key wins.
+#[derive(Debug, Clone)]
+pub struct LwwMap<K, V> {
+    clock: Clock,
+    // Removed keys are kept as `None`, so older writes don't revive them
+    entries: HashMap<K, (Stamp, Option<V>)>,
+}
+
+/// Changes of keys of a `LwwMap`. A removed key has no value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LwwDelta<K, V> {
+    /// Keys with timestamps of the changes and new values.
+    pub entries: Vec<(K, Stamp, Option<V>)>,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> LwwMap<K, V> {
+    /// Creates an empty map of the replica.
+    pub fn new(replica: u64) -> Self {
+        LwwMap {
+            clock: Clock::new(replica),
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Returns the value of the key.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.entries.get(key).and_then(|(_, value)| value.as_ref())
+    }
+
+    /// Returns keys with values in an arbitrary order.
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a K, &'a V)> + 'a {
+        self.entries.iter().filter_map(|(key, (_, value))| value.as_ref().map(|value| (key, value)))
+    }
+
+    /// Returns the count of keys with values.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Returns `true` if no key has a value.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Sets the value of the key.
+    pub fn set(&mut self, key: K, value: V) -> LwwDelta<K, V> {
+        self.write(key, Some(value))
+    }
+
+    /// Removes the value of the key.
+    pub fn remove(&mut self, key: K) -> LwwDelta<K, V> {
+        self.write(key, None)
+    }
+
+    fn write(&mut self, key: K, value: Option<V>) -> LwwDelta<K, V> {
+        let stamp = self.clock.tick();
+        self.entries.insert(key.clone(), (stamp, value.clone()));
+        LwwDelta {
+            entries: vec![(key, stamp, value)],
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Crdt for LwwMap<K, V> {
+    type Delta = LwwDelta<K, V>;
+
+    fn apply(&mut self, delta: LwwDelta<K, V>) -> bool {
+        let mut changed = false;
+        for (key, stamp, value) in delta.entries {
+            self.clock.observe(stamp);
+            let newer = self.entries.get(&key).map_or(true, |&(current, _)| stamp > current);
+            if newer {
+                self.entries.insert(key, (stamp, value));
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    fn state(&self) -> LwwDelta<K, V> {
+        let entries = self.entries.iter()
+            .map(|(key, (stamp, value))| (key.clone(), *stamp, value.clone()))
+            .collect();
+        LwwDelta { entries }
+    }
+}
+
+impl<K: Serialize, V: Serialize> Serialize for LwwDelta<K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.entries.serialize(serializer)
+    }
+}
+
+impl<'de, K: Deserialize<'de>, V: Deserialize<'de>> Deserialize<'de> for LwwDelta<K, V> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::deserialize(deserializer).map(|entries| LwwDelta { entries })
+    }
+}
+
+/// A change of a `Sequence`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SequenceOp<T> {
+    /// A value was inserted after an element, or at the start.
+    Insert {
+        /// The id of the new element.
+        id: Stamp,
+        /// The id of the element on the left when the value was inserted.
+        after: Option<Stamp>,
+        /// The inserted value.
+        value: T,
+    },
+    /// An element was removed.
+    Remove {
+        /// The id of the removed element.
+        id: Stamp,
+    },
+}
+
+impl<T: Serialize> Serialize for SequenceOp<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Removals are sent as an id without a value
+        match *self {
+            SequenceOp::Insert { id, after, ref value } => (id, after, Some(value)).serialize(serializer),
+            SequenceOp::Remove { id } => (id, None::<Stamp>, None::<&T>).serialize(serializer),
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for SequenceOp<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (id, after, value) = <(Stamp, Option<Stamp>, Option<T>)>::deserialize(deserializer)?;
+        Ok(match value {
+            Some(value) => SequenceOp::Insert { id, after, value },
+            None => SequenceOp::Remove { id },
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Element<T> {
+    id: Stamp,
+    after: Option<Stamp>,
+    value: T,
+    removed: bool,
+}
+
+/// A list which merges concurrent inserts and removals (a replicated
+/// growable array). Removed elements are kept as tombstones.
+#[derive(Debug, Clone)]
+pub struct Sequence<T> {
+    clock: Clock,
+    elements: Vec<Element<T>>,
+    // Changes which refer to elements which didn't come yet
+    pending: Vec<SequenceOp<T>>,
+}
+
+/// A sequence of characters.
+pub type Text = Sequence<char>;
+
+impl<T: Clone> Sequence<T> {
+    /// Creates an empty sequence of the replica.
+    pub fn new(replica: u64) -> Self {
+        Sequence {
+            clock: Clock::new(replica),
+            elements: Vec::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Returns values which aren't removed.
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = &'a T> + 'a {
+        self.elements.iter().filter(|element| !element.removed).map(|element| &element.value)
+    }
+
+    /// Returns the count of values.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Returns `true` if there are no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Inserts the value at the index. Panics if the index is out of bounds.
+    pub fn insert(&mut self, index: usize, value: T) -> Vec<SequenceOp<T>> {
+        let after = match index {
+            0 => None,
+            _ => Some(self.elements[self.position(index - 1)].id),
+        };
+        let op = SequenceOp::Insert {
+            id: self.clock.tick(),
+            after,
+            value,
+        };
+        if self.integrate(op.clone()).is_err() {
+            unreachable!("an element of a local insert is missing");
+        }
+        vec![op]
+    }
+
+    /// Removes the value at the index. Panics if the index is out of bounds.
+    pub fn remove(&mut self, index: usize) -> Vec<SequenceOp<T>> {
+        let position = self.position(index);
+        self.elements[position].removed = true;
+        vec![SequenceOp::Remove { id: self.elements[position].id }]
+    }
+
+    /// Returns the position of an element by the index of its value.
+    fn position(&self, index: usize) -> usize {
+        self.elements.iter()
+            .enumerate()
+            .filter(|(_, element)| !element.removed)
+            .nth(index)
+            .map(|(position, _)| position)
+            .unwrap_or_else(|| panic!("index {} is out of bounds of a sequence", index))
+    }
+
+    fn find(&self, id: Stamp) -> Option<usize> {
+        self.elements.iter().position(|element| element.id == id)
+    }
+
+    /// Applies a change. Returns it back if an element it refers to is missing.
+    fn integrate(&mut self, op: SequenceOp<T>) -> Result<bool, SequenceOp<T>> {
+        match op {
+            SequenceOp::Insert { id, after, value } => {
+                if self.find(id).is_some() {
+                    return Ok(false);
+                }
+                let mut position = match after {
+                    None => 0,
+                    Some(after) => match self.find(after) {
+                        Some(position) => position + 1,
+                        None => return Err(SequenceOp::Insert { id, after: Some(after), value }),
+                    },
+                };
+                // Later inserts at the same place go first, so every replica ends in the same order
+                while position < self.elements.len() && self.elements[position].id > id {
+                    position += 1;
+                }
+                self.clock.observe(id);
+                self.elements.insert(position, Element { id, after, value, removed: false });
+                Ok(true)
+            }
+            SequenceOp::Remove { id } => match self.find(id) {
+                Some(position) => Ok(!::std::mem::replace(&mut self.elements[position].removed, true)),
+                None => Err(SequenceOp::Remove { id }),
+            },
+        }
+    }
+}
+
+impl Sequence<char> {
+    /// Inserts the characters of the string at the index.
+    pub fn insert_str(&mut self, index: usize, text: &str) -> Vec<SequenceOp<char>> {
+        let mut ops = Vec::new();
+        for (offset, c) in text.chars().enumerate() {
+            ops.extend(self.insert(index + offset, c));
+        }
+        ops
+    }
+
+    /// Removes `count` characters from the index.
+    pub fn remove_range(&mut self, index: usize, count: usize) -> Vec<SequenceOp<char>> {
+        (0..count).flat_map(|_| self.remove(index)).collect()
+    }
+
+    /// Returns the text.
+    pub fn text(&self) -> String {
+        String::from_iter(self.iter())
+    }
+}
+
+impl<T: Clone> Crdt for Sequence<T> {
+    type Delta = Vec<SequenceOp<T>>;
+
+    fn apply(&mut self, delta: Vec<SequenceOp<T>>) -> bool {
+        let mut changed = false;
+        let mut waiting = ::std::mem::take(&mut self.pending);
+        waiting.extend(delta);
+        // Retries waiting changes while some of them are applied
+        loop {
+            let count = waiting.len();
+            let mut missing = Vec::new();
+            for op in waiting {
+                match self.integrate(op) {
+                    Ok(applied) => changed |= applied,
+                    Err(op) => missing.push(op),
+                }
+            }
+            waiting = missing;
+            if waiting.is_empty() || waiting.len() == count {
+                break;
+            }
+        }
+        self.pending = waiting;
+        changed
+    }
+
+    fn state(&self) -> Vec<SequenceOp<T>> {
+        let mut elements: Vec<&Element<T>> = self.elements.iter().collect();
+        // Every element comes after the one it was inserted after
+        elements.sort_by_key(|element| element.id);
+        let inserts = elements.iter().map(|element| SequenceOp::Insert {
+            id: element.id,
+            after: element.after,
+            value: element.value.clone(),
+        });
+        let removals = elements.iter()
+            .filter(|element| element.removed)
+            .map(|element| SequenceOp::Remove { id: element.id });
+        inserts.chain(removals).collect()
+    }
+}
+
+/// Messages of a connection of `Shared`.
+pub enum SyncMsg<D> {
+    /// A delta from other replicas.
+    Remote(D),
+    /// Data from a server which isn't a delta.
+    Malformed,
+    /// The connection was opened or closed.
+    Status(WebSocketStatus),
+}
+
+/// A replicated value which sends local changes over a websocket
+/// and merges remote ones.
+pub struct Shared<C: Crdt> {
+    value: C,
+    socket: Option<WebSocketHandle>,
+    opened: bool,
+}
+
+impl<C> Shared<C>
+where
+    C: Crdt,
+    C::Delta: Serialize + for<'de> Deserialize<'de> + 'static,
+{
+    /// Wraps a value which isn't connected yet.
+    pub fn new(value: C) -> Self {
+        Shared {
+            value,
+            socket: None,
+            opened: false,
+        }
+    }
+
+    /// Returns the current value.
+    pub fn get(&self) -> &C {
+        &self.value
+    }
+
+    /// Returns `true` if changes are sent to a server now.
+    pub fn is_online(&self) -> bool {
+        self.opened
+    }
+
+    /// Opens a connection which sends messages from the `converter`
+    /// to the loop. Pass them to `handle`. A previous connection is closed.
+    pub fn connect<MSG, F>(&mut self, service: &mut WebSocketService<MSG>, url: &str, converter: F)
+    where
+        MSG: 'static,
+        F: Fn(SyncMsg<C::Delta>) -> MSG + 'static,
+    {
+        self.disconnect();
+        let converter = Rc::new(converter);
+        let notify = converter.clone();
+        let socket = service.connect(url, move |Json(delta): Json<Result<C::Delta, ()>>| match delta {
+            Ok(delta) => converter(SyncMsg::Remote(delta)),
+            Err(()) => converter(SyncMsg::Malformed),
+        }, move |status| notify(SyncMsg::Status(status)));
+        self.socket = Some(socket);
+    }
+
+    /// Closes the connection. Local changes are kept and sent after `connect`.
+    pub fn disconnect(&mut self) {
+        self.opened = false;
+        if let Some(mut socket) = self.socket.take() {
+            if socket.is_active() {
+                socket.cancel();
+            }
+        }
+    }
+
+    /// Changes the value with `edit` which returns a delta of the change
+    /// and sends it if the connection is opened.
+    pub fn edit<F>(&mut self, edit: F) -> ShouldRender
+    where
+        F: FnOnce(&mut C) -> C::Delta,
+    {
+        let delta = edit(&mut self.value);
+        self.send(&delta);
+        true
+    }
+
+    /// Handles a message of the connection. Returns `true` if the value changed.
+    pub fn handle(&mut self, msg: SyncMsg<C::Delta>) -> ShouldRender {
+        match msg {
+            SyncMsg::Remote(delta) => self.value.apply(delta),
+            SyncMsg::Malformed => {
+                warn!("can't parse a delta of a shared value");
+                false
+            }
+            SyncMsg::Status(WebSocketStatus::Opened) => {
+                self.opened = true;
+                let state = self.value.state();
+                self.send(&state);
+                false
+            }
+            SyncMsg::Status(WebSocketStatus::Closed) => {
+                self.opened = false;
+                false
+            }
+        }
+    }
+
+    fn send(&mut self, delta: &C::Delta) {
+        if !self.opened {
+            return;
+        }
+        if let Some(ref mut socket) = self.socket {
+            socket.send(Json(delta));
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! This module contains commands which an `update` function returns
+//! instead of calling services. A runtime executes them, so the function
+//! only changes the model and could be tested without a browser.
+//!
+//! ```rust,ignore
+//! fn update(model: &mut Model, msg: Msg) -> Vec<Command<Msg>> {
+//!     match msg {
+//!         Msg::Fire => {
+//!             vec![Command::delay(Duration::from_secs(5), Msg::Timeout)]
+//!         }
+//!         Msg::Timeout => {
+//!             model.fired = true;
+//!             vec![Command::Navigate("/done".into())]
+//!         }
+//!     }
+//! }
+//!
+//! fn main() {
+//!     yew::initialize();
+//!     let mut app = App::new();
+//!     let runtime = Runtime::new(app.sender());
+//!     app.mount(runtime, Model::default(), command::program(update), view);
+//!     yew::run_loop();
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+use format::{Storable, Restorable};
+use html::AppSender;
+use services::fetch::{FetchService, Request, Response};
+use services::TaskBag;
+use services::history;
+use services::timeout::TimeoutService;
+use subscription::{Subscription, Subscriptions};
+
+/// An effect which is executed by a `Runtime`.
+pub enum Command<MSG> {
+    /// Sends the message to the loop.
+    Send(MSG),
+    /// Sends the message after the duration.
+    Delay(Duration, MSG),
+    /// Sends the request and converts the response to a message.
+    Fetch(Box<Request<Storable>>, Box<Fn(Response<Restorable>) -> MSG>),
+    /// Pushes the path to the history.
+    Navigate(String),
+    /// Replaces the current entry of the history with the path.
+    Redirect(String),
+    /// Runs the future and sends its output to the loop.
+    Spawn(Pin<Box<Future<Output = MSG>>>),
+}
+
+impl<MSG> Command<MSG> {
+    /// Creates a command which sends the message after the duration.
+    pub fn delay(duration: Duration, msg: MSG) -> Self {
+        Command::Delay(duration, msg)
+    }
+
+    /// Creates a command which fetches a resource like `FetchService::fetch` does.
+    pub fn fetch<F, IN, OUT>(request: Request<IN>, converter: F) -> Self
+    where
+        IN: Into<Storable>,
+        OUT: From<Restorable>,
+        F: Fn(Response<OUT>) -> MSG + 'static,
+    {
+        let request = request.map(Into::into);
+        let converter = move |response: Response<Restorable>| {
+            converter(response.map(OUT::from))
+        };
+        Command::Fetch(Box::new(request), Box::new(converter))
+    }
+
+    /// Creates a command which runs the future.
+    pub fn spawn<F>(future: F) -> Self
+    where
+        F: Future<Output = MSG> + 'static,
+    {
+        Command::Spawn(Box::pin(future))
+    }
+}
+
+impl<MSG: fmt::Debug> fmt::Debug for Command<MSG> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Command::Send(ref msg) => write!(f, "Send({:?})", msg),
+            Command::Delay(ref duration, ref msg) => write!(f, "Delay({:?}, {:?})", duration, msg),
+            Command::Fetch(ref request, _) => write!(f, "Fetch({} {})", request.method(), request.uri()),
+            Command::Navigate(ref path) => write!(f, "Navigate({:?})", path),
+            Command::Redirect(ref path) => write!(f, "Redirect({:?})", path),
+            Command::Spawn(_) => write!(f, "Spawn"),
+        }
+    }
+}
+
+/// A context of a program which executes commands with services.
+pub struct Runtime<MSG> {
+    sender: AppSender<MSG>,
+    timeout: TimeoutService<MSG>,
+    fetch: FetchService<MSG>,
+    subscriptions: Subscriptions<MSG>,
+    tasks: TaskBag,
+}
+
+impl<MSG: 'static> Runtime<MSG> {
+    /// Creates a runtime connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Runtime {
+            timeout: TimeoutService::new(sender.clone()),
+            fetch: FetchService::new(sender.clone()),
+            subscriptions: Subscriptions::new(sender.clone()),
+            tasks: TaskBag::new(),
+            sender,
+        }
+    }
+
+    /// Executes the commands in order. Tasks started by commands run
+    /// until they complete or the runtime is dropped.
+    pub fn execute<I>(&mut self, commands: I)
+    where
+        I: IntoIterator<Item = Command<MSG>>,
+    {
+        for command in commands {
+            self.execute_one(command);
+        }
+    }
+
+    /// Updates running subscriptions to the declared ones.
+    pub fn subscribe<I>(&mut self, subscriptions: I)
+    where
+        I: IntoIterator<Item = Subscription<MSG>>,
+    {
+        self.subscriptions.update(subscriptions);
+    }
+
+    fn execute_one(&mut self, command: Command<MSG>) {
+        match command {
+            Command::Send(msg) => {
+                self.sender.send(msg);
+            }
+            Command::Delay(duration, msg) => {
+                let msg = RefCell::new(Some(msg));
+                let handle = self.timeout.spawn(duration, move || {
+                    msg.borrow_mut().take().expect("delayed message sent twice")
+                });
+                self.tasks.push(handle);
+            }
+            Command::Fetch(request, converter) => {
+                let handle = self.fetch.fetch(*request, converter);
+                self.tasks.push(handle);
+            }
+            Command::Navigate(path) => {
+                history::push_state(&path);
+            }
+            Command::Redirect(path) => {
+                history::replace_state(&path);
+            }
+            Command::Spawn(future) => {
+                let handle = self.sender.spawn(future);
+                self.tasks.push(handle);
+            }
+        }
+    }
+}
+
+/// Converts an `update` function which returns commands to a function
+/// which `App::mount` expects with a `Runtime` as a context.
+pub fn program<MOD, MSG, U>(update: U) -> impl Fn(&mut Runtime<MSG>, &mut MOD, MSG)
+where
+    MSG: 'static,
+    U: Fn(&mut MOD, MSG) -> Vec<Command<MSG>>,
+{
+    move |runtime: &mut Runtime<MSG>, model: &mut MOD, msg: MSG| {
+        let commands = update(model, msg);
+        runtime.execute(commands);
+    }
+}
+
+/// Same as `program`, but also updates subscriptions of a runtime
+/// to subscriptions of the model after every message.
+/// Call `Runtime::subscribe` with subscriptions of the initial model
+/// before the program is mounted.
+pub fn program_with<MOD, MSG, U, S>(update: U, subscriptions: S) -> impl Fn(&mut Runtime<MSG>, &mut MOD, MSG)
+where
+    MSG: 'static,
+    U: Fn(&mut MOD, MSG) -> Vec<Command<MSG>>,
+    S: Fn(&MOD) -> Vec<Subscription<MSG>>,
+{
+    move |runtime: &mut Runtime<MSG>, model: &mut MOD, msg: MSG| {
+        let commands = update(model, msg);
+        runtime.execute(commands);
+        runtime.subscribe(subscriptions(model));
+    }
+}
@@ -0,0 +1,1025 @@
+//! This module contains the implementation of components.
+//!
+//! A component is a part of a view which has an own state and handles
+//! own messages with a separate loop. Parents pass properties to a component
+//! and receive notifications from it with callbacks.
+//!
+//! ```rust,ignore
+//! struct Counter {
+//!     value: u32,
+//!     onchange: Callback<u32>,
+//! }
+//!
+//! enum Msg {
+//!     Increment,
+//! }
+//!
+//! #[derive(Default)]
+//! struct Props {
+//!     onchange: Callback<u32>,
+//! }
+//!
+//! impl Component for Counter {
+//!     type Msg = Msg;
+//!     type Properties = Props;
+//!
+//!     fn create(props: Props, _: AppSender<Msg>) -> Self {
+//!         Counter { value: 0, onchange: props.onchange }
+//!     }
+//!
+//!     fn update(&mut self, msg: Msg) -> ShouldRender {
+//!         match msg {
+//!             Msg::Increment => {
+//!                 self.value += 1;
+//!                 self.onchange.emit(self.value);
+//!             }
+//!         }
+//!         true
+//!     }
+//!
+//!     fn view(&self) -> Html<Msg> {
+//!         html! {
+//!             <button onclick=|_| Msg::Increment,>{ self.value }</button>
+//!         }
+//!     }
+//! }
+//!
+//! fn view(model: &Model) -> Html<ParentMsg> {
+//!     html! {
+//!         <div>
+//!             <Counter: onchange=ParentMsg::CounterChanged, />
+//!         </div>
+//!     }
+//! }
+//! ```
+//!
+//! The component tag is a shorthand for:
+//!
+//! ```rust,ignore
+//! let (mut props, link) = VComp::lazy::<Counter>();
+//! props.onchange = link.callback(ParentMsg::CounterChanged);
+//! VComp::new::<Counter>(props, link)
+//! ```
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use std::slice;
+use std::vec;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::web::{INode, Node, document};
+use html::{AppSender, Html};
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use html::App;
+use callback::Callback;
+use virtual_dom::{VNode, VText, VComp};
+use virtual_dom::vcomp::Mount;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use virtual_dom::slicing;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use trace;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use direction;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use css;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use theme;
+
+/// A flag which tells the loop to render a component again.
+pub type ShouldRender = bool;
+
+/// An interface of a component with own state and messages.
+pub trait Component: Sized + 'static {
+    /// Messages which the component handles in the `update` method.
+    type Msg: 'static;
+    /// Properties which the component receives from a parent.
+    type Properties: Default + 'static;
+
+    /// Creates a new instance with the initial properties. The `sender`
+    /// sends messages to the own loop of the component and could be used
+    /// to create services.
+    fn create(props: Self::Properties, sender: AppSender<Self::Msg>) -> Self;
+
+    /// Updates the state of the component on every message.
+    fn update(&mut self, msg: Self::Msg) -> ShouldRender;
+
+    /// Called when a parent renders the component with new properties.
+    /// Renders the component again by default.
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        true
+    }
+
+    /// Renders the state of the component to a virtual DOM tree.
+    fn view(&self) -> Html<Self::Msg>;
+
+    /// Called before the component will be removed from the tree.
+    fn destroy(&mut self) { }
+
+    /// Describes the state of the component for debugging tools.
+    /// Nothing is shown by default, return `Some(format!("{:?}", self))`
+    /// to inspect the component with the `devtools` feature.
+    fn inspect(&self) -> Option<String> {
+        None
+    }
+}
+
+/// A link to a loop of a parent. It isn't bound until a node which uses it
+/// rendered, but it's possible to create callbacks for properties with it.
+pub struct Link<MSG> {
+    sender: Rc<RefCell<Option<AppSender<MSG>>>>,
+}
+
+impl<MSG> Clone for Link<MSG> {
+    fn clone(&self) -> Self {
+        Link {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<MSG> Default for Link<MSG> {
+    fn default() -> Self {
+        Link::new()
+    }
+}
+
+impl<MSG> Link<MSG> {
+    /// Creates an unbound link.
+    pub fn new() -> Self {
+        Link {
+            sender: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    /// Binds the link to a loop.
+    pub(crate) fn bind(&self, sender: AppSender<MSG>) {
+        *self.sender.borrow_mut() = Some(sender);
+    }
+
+    /// Returns a sender of the bound loop.
+    pub(crate) fn sender(&self) -> Option<AppSender<MSG>> {
+        self.sender.borrow().clone()
+    }
+
+    /// Sends the message to the bound loop.
+    pub fn send(&self, msg: MSG) {
+        if let Some(ref mut sender) = *self.sender.borrow_mut() {
+            sender.send(msg);
+        } else {
+            warn!("message sent through a link which wasn't bound to a loop");
+        }
+    }
+
+    /// Sends all messages to the bound loop with a single update.
+    pub fn send_all<I>(&self, messages: I)
+    where
+        I: IntoIterator<Item = MSG>,
+    {
+        if let Some(ref mut sender) = *self.sender.borrow_mut() {
+            sender.send_all(messages);
+        } else {
+            warn!("messages sent through a link which wasn't bound to a loop");
+        }
+    }
+}
+
+impl<MSG: 'static> Link<MSG> {
+    /// Creates a callback which converts a value to a message of the bound loop.
+    pub fn callback<IN, F>(&self, converter: F) -> Callback<IN>
+    where
+        F: Fn(IN) -> MSG + 'static,
+    {
+        let link = self.clone();
+        Callback::from(move |value| {
+            let msg = converter(value);
+            link.send(msg);
+        })
+    }
+
+    /// Wraps nodes of a parent to pass them to a component as `Children`.
+    pub fn children<I>(&self, nodes: I) -> Children
+    where
+        I: IntoIterator<Item = VNode<MSG>>,
+    {
+        let link = self.clone();
+        let nodes = nodes.into_iter().map(|node| {
+            let island = Island {
+                node: Some(node),
+                link: link.clone(),
+            };
+            Some(Box::new(island) as Box<Mount>)
+        }).collect();
+        Children {
+            kind: TypeId::of::<Island<MSG>>(),
+            nodes: Rc::new(RefCell::new(nodes)),
+        }
+    }
+
+    /// Creates a render property from a function which renders a view of a parent.
+    pub fn render<ARGS, F>(&self, render: F) -> Render<ARGS>
+    where
+        F: Fn(ARGS) -> Html<MSG> + 'static,
+    {
+        let link = self.clone();
+        let render = move |args| {
+            let island = Island {
+                node: Some(VNode::from(render(args))),
+                link: link.clone(),
+            };
+            Box::new(island) as Box<Mount>
+        };
+        Render(Some(Rc::new(render)))
+    }
+}
+
+/// Converts values of properties which are set by `html!` macro.
+/// Closures become callbacks and renders bound to the link of a parent,
+/// references are cloned and other values are passed as is.
+pub trait Transformer<FROM, TO> {
+    /// Converts a value to a type of the property.
+    fn transform(&self, from: FROM) -> TO;
+}
+
+impl<MSG, T> Transformer<T, T> for Link<MSG> {
+    fn transform(&self, from: T) -> T {
+        from
+    }
+}
+
+impl<'a, MSG, T: Clone> Transformer<&'a T, T> for Link<MSG> {
+    fn transform(&self, from: &'a T) -> T {
+        from.clone()
+    }
+}
+
+impl<MSG, T> Transformer<T, Option<T>> for Link<MSG> {
+    fn transform(&self, from: T) -> Option<T> {
+        Some(from)
+    }
+}
+
+impl<MSG: 'static> Transformer<Vec<VNode<MSG>>, Children> for Link<MSG> {
+    fn transform(&self, from: Vec<VNode<MSG>>) -> Children {
+        self.children(from)
+    }
+}
+
+impl<MSG: 'static, COMP: Component> Transformer<Vec<VNode<MSG>>, ChildrenOf<COMP>> for Link<MSG> {
+    fn transform(&self, from: Vec<VNode<MSG>>) -> ChildrenOf<COMP> {
+        let items = from.into_iter().map(|node| ChildOf::from_node(node, self)).collect();
+        ChildrenOf {
+            items: Rc::new(items),
+        }
+    }
+}
+
+impl<'a, MSG> Transformer<&'a str, String> for Link<MSG> {
+    fn transform(&self, from: &'a str) -> String {
+        from.to_owned()
+    }
+}
+
+impl<MSG, IN, F> Transformer<F, Callback<IN>> for Link<MSG>
+where
+    MSG: 'static,
+    F: Fn(IN) -> MSG + 'static,
+{
+    fn transform(&self, from: F) -> Callback<IN> {
+        self.callback(from)
+    }
+}
+
+impl<MSG, ARGS, F> Transformer<F, Render<ARGS>> for Link<MSG>
+where
+    MSG: 'static,
+    F: Fn(ARGS) -> Html<MSG> + 'static,
+{
+    fn transform(&self, from: F) -> Render<ARGS> {
+        self.render(from)
+    }
+}
+
+type Nodes = Rc<RefCell<Vec<Option<Box<Mount>>>>>;
+
+/// Nodes of a parent passed to a component as a property.
+/// Listeners of these nodes send messages to the parent's loop.
+///
+/// Nodes rendered once per an update of the parent. If the component
+/// renders again, it keeps nodes which were rendered before.
+pub struct Children {
+    kind: TypeId,
+    nodes: Nodes,
+}
+
+impl Default for Children {
+    fn default() -> Self {
+        Children {
+            kind: TypeId::of::<Island<()>>(),
+            nodes: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+}
+
+impl Clone for Children {
+    fn clone(&self) -> Self {
+        Children {
+            kind: self.kind,
+            nodes: self.nodes.clone(),
+        }
+    }
+}
+
+impl PartialEq for Children {
+    fn eq(&self, other: &Children) -> bool {
+        Rc::ptr_eq(&self.nodes, &other.nodes)
+    }
+}
+
+impl fmt::Debug for Children {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Children {{ len: {} }}", self.len())
+    }
+}
+
+impl Children {
+    /// Returns the count of children.
+    pub fn len(&self) -> usize {
+        self.nodes.borrow().len()
+    }
+
+    /// Returns `true` if there are no children.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.borrow().is_empty()
+    }
+
+    /// Returns nodes to put them into a view of a component:
+    ///
+    /// ```rust,ignore
+    /// html! {
+    ///     <div class="layout",>{ for self.props.children.view() }</div>
+    /// }
+    /// ```
+    pub fn view<MSG>(&self) -> vec::IntoIter<VNode<MSG>> {
+        let kind = self.kind;
+        let nodes = self.nodes.borrow_mut().iter_mut().map(|slot| {
+            let mount = slot.take().unwrap_or_else(|| Box::new(Kept::new(kind)));
+            VNode::from(VComp::from_mount(mount))
+        }).collect::<Vec<_>>();
+        nodes.into_iter()
+    }
+}
+
+/// Children of a parent which are all `COMP` components, like tabs of `<Tabs>`.
+/// A layout reads properties of children and renders them where it needs,
+/// as many times as it needs. Other nodes in children are a panic:
+///
+/// ```rust,ignore
+/// html! {
+///     <nav>{ for self.props.children.iter().map(|tab| html! { <a>{ &tab.props().title }</a> }) }</nav>
+///     <section>{ self.props.children.get(self.active).map(ChildOf::view) }</section>
+/// }
+/// ```
+pub struct ChildrenOf<COMP: Component> {
+    items: Rc<Vec<ChildOf<COMP>>>,
+}
+
+impl<COMP: Component> Default for ChildrenOf<COMP> {
+    fn default() -> Self {
+        ChildrenOf {
+            items: Rc::new(Vec::new()),
+        }
+    }
+}
+
+impl<COMP: Component> Clone for ChildrenOf<COMP> {
+    fn clone(&self) -> Self {
+        ChildrenOf {
+            items: self.items.clone(),
+        }
+    }
+}
+
+impl<COMP: Component> PartialEq for ChildrenOf<COMP> {
+    fn eq(&self, other: &ChildrenOf<COMP>) -> bool {
+        Rc::ptr_eq(&self.items, &other.items)
+    }
+}
+
+impl<COMP: Component> fmt::Debug for ChildrenOf<COMP> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ChildrenOf {{ len: {} }}", self.len())
+    }
+}
+
+impl<COMP: Component> ChildrenOf<COMP> {
+    /// Returns the count of children.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if there are no children.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns a child by its index.
+    pub fn get(&self, index: usize) -> Option<&ChildOf<COMP>> {
+        self.items.get(index)
+    }
+
+    /// Returns an iterator over children.
+    pub fn iter(&self) -> slice::Iter<'_, ChildOf<COMP>> {
+        self.items.iter()
+    }
+}
+
+impl<COMP: Component> ChildrenOf<COMP>
+where
+    COMP::Properties: Clone,
+{
+    /// Returns nodes of all children with their properties.
+    pub fn view<MSG>(&self) -> vec::IntoIter<VNode<MSG>> {
+        self.iter().map(ChildOf::view).collect::<Vec<_>>().into_iter()
+    }
+
+    /// Returns nodes of all children with properties which were changed by `update`,
+    /// which receives the index of a child. It's a way to pass state of a layout,
+    /// like whether a tab is active.
+    pub fn view_with<MSG, F>(&self, update: F) -> vec::IntoIter<VNode<MSG>>
+    where
+        F: Fn(usize, &mut COMP::Properties),
+    {
+        self.iter().enumerate()
+            .map(|(index, child)| child.view_with(|props| update(index, props)))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+/// A child component of `ChildrenOf`.
+pub struct ChildOf<COMP: Component> {
+    props: COMP::Properties,
+    render: Rc<Fn(COMP::Properties) -> Box<Mount>>,
+}
+
+impl<COMP: Component> ChildOf<COMP> {
+    fn from_node<MSG: 'static>(node: VNode<MSG>, parent: &Link<MSG>) -> Self {
+        let (link, mount) = match node {
+            VNode::VComp { vcomp } => vcomp.into_parts(),
+            other => panic!("children have to be {} components, not {:?}", ::std::any::type_name::<COMP>(), other),
+        };
+        let mut mount = mount.into_any().downcast::<ComponentMount<COMP>>()
+            .unwrap_or_else(|_| panic!("children have to be {} components", ::std::any::type_name::<COMP>()));
+        let props = mount.props.take().expect("a child component was rendered before");
+        let parent = parent.clone();
+        let render = move |props| {
+            let vcomp = VComp::from_parts(link.clone(), Box::new(ComponentMount::<COMP>::new(props)));
+            let island = Island {
+                node: Some(VNode::from(vcomp)),
+                link: parent.clone(),
+            };
+            Box::new(island) as Box<Mount>
+        };
+        ChildOf {
+            props,
+            render: Rc::new(render),
+        }
+    }
+
+    /// Returns properties which a parent set.
+    pub fn props(&self) -> &COMP::Properties {
+        &self.props
+    }
+}
+
+impl<COMP: Component> ChildOf<COMP>
+where
+    COMP::Properties: Clone,
+{
+    /// Returns a node of the child.
+    pub fn view<MSG>(&self) -> VNode<MSG> {
+        VNode::from(VComp::from_mount((self.render)(self.props.clone())))
+    }
+
+    /// Returns a node of the child with properties which were changed by `update`.
+    pub fn view_with<MSG, F>(&self, update: F) -> VNode<MSG>
+    where
+        F: FnOnce(&mut COMP::Properties),
+    {
+        let mut props = self.props.clone();
+        update(&mut props);
+        VNode::from(VComp::from_mount((self.render)(props)))
+    }
+}
+
+type RenderFn<ARGS> = Rc<Fn(ARGS) -> Box<Mount>>;
+
+/// A property which renders a part of a component's view by a parent.
+pub struct Render<ARGS>(Option<RenderFn<ARGS>>);
+
+impl<ARGS> Default for Render<ARGS> {
+    fn default() -> Self {
+        Render(None)
+    }
+}
+
+impl<ARGS> Clone for Render<ARGS> {
+    fn clone(&self) -> Self {
+        Render(self.0.clone())
+    }
+}
+
+impl<ARGS> PartialEq for Render<ARGS> {
+    fn eq(&self, other: &Render<ARGS>) -> bool {
+        match (self.0.as_ref(), other.0.as_ref()) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<ARGS> Render<ARGS> {
+    /// Renders a node of the parent with arguments. Renders an empty
+    /// text node if a parent hadn't set the property.
+    pub fn view<MSG>(&self, args: ARGS) -> VNode<MSG> {
+        match self.0 {
+            Some(ref render) => VNode::from(VComp::from_mount(render(args))),
+            None => VNode::from(VText::new("")),
+        }
+    }
+}
+
+/// A node of a parent which rendered inside a component.
+struct Island<MSG> {
+    node: Option<VNode<MSG>>,
+    link: Link<MSG>,
+}
+
+impl<MSG: 'static> Mount for Island<MSG> {
+    fn kind(&self) -> TypeId {
+        TypeId::of::<Island<MSG>>()
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn mount(&mut self, parent: &Node) {
+        let sender = self.link.sender().expect("children rendered before a parent");
+        if let Some(ref mut node) = self.node {
+            node.apply(parent, None, sender);
+        }
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn patch(&mut self, parent: &Node, mut last: Box<Mount>) {
+        let mut placeholder = None;
+        let last = last.take_kept().unwrap_or(last);
+        let last_node = {
+            match last.into_any().downcast::<Island<MSG>>() {
+                Ok(mut last) => last.node.take(),
+                Err(last) => {
+                    // It's a kept island which wasn't rendered
+                    placeholder = last.downcast::<Kept>().ok().and_then(|mut kept| kept.placeholder.take());
+                    None
+                }
+            }
+        };
+        let sender = self.link.sender().expect("children rendered before a parent");
+        if let Some(ref mut node) = self.node {
+            node.apply(parent, last_node, sender);
+            if let (Some(wrong), Some(reference)) = (placeholder, node.reference()) {
+                parent.replace_child(&reference, &wrong);
+            }
+        }
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn hydrate(&mut self, parent: &Node, next: &mut Option<Node>) -> Result<(), String> {
+        let sender = self.link.sender().expect("children hydrated before a parent");
+        match self.node {
+            Some(ref mut node) => node.hydrate(parent, next, sender),
+            None => Ok(()),
+        }
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn reference(&self) -> Option<Node> {
+        self.node.as_ref().and_then(VNode::reference)
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn unmount(&mut self) -> Option<Node> {
+        self.node.take().and_then(VNode::into_reference)
+    }
+
+    #[cfg(feature = "ssr")]
+    fn render_to_string(&mut self, out: &mut String) {
+        if let Some(ref mut node) = self.node {
+            ::ssr::write_node(node, out);
+        }
+    }
+
+    #[cfg(feature = "testing")]
+    fn render_to_test(&mut self) -> ::testing::TestNode {
+        match self.node {
+            Some(ref mut node) => ::testing::convert(node),
+            None => ::testing::TestNode::Text(String::new()),
+        }
+    }
+
+    #[cfg(feature = "a11y")]
+    fn audit(&self, audit: &mut ::a11y::Audit) {
+        if let Some(ref node) = self.node {
+            audit.node(node);
+        }
+    }
+
+    fn into_any(self: Box<Self>) -> Box<Any> {
+        self
+    }
+}
+
+/// A placeholder of an island which was rendered before and
+/// is kept as is when a component renders again.
+struct Kept {
+    kind: TypeId,
+    inner: Option<Box<Mount>>,
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    placeholder: Option<Node>,
+}
+
+impl Kept {
+    fn new(kind: TypeId) -> Self {
+        Kept {
+            kind,
+            inner: None,
+            #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+            placeholder: None,
+        }
+    }
+}
+
+impl Mount for Kept {
+    fn kind(&self) -> TypeId {
+        self.kind
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn mount(&mut self, parent: &Node) {
+        // Nodes were taken by an unmounted view, render nothing
+        let placeholder = Node::from(document().create_text_node(""));
+        parent.append_child(&placeholder);
+        self.placeholder = Some(placeholder);
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn patch(&mut self, _: &Node, mut last: Box<Mount>) {
+        let last = last.take_kept().unwrap_or(last);
+        self.inner = Some(last);
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn hydrate(&mut self, _: &Node, _: &mut Option<Node>) -> Result<(), String> {
+        Err("kept children can't be hydrated".to_owned())
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn reference(&self) -> Option<Node> {
+        match self.inner {
+            Some(ref inner) => inner.reference(),
+            None => self.placeholder.clone(),
+        }
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn unmount(&mut self) -> Option<Node> {
+        match self.inner.take() {
+            Some(mut inner) => inner.unmount(),
+            None => self.placeholder.take(),
+        }
+    }
+
+    fn take_kept(&mut self) -> Option<Box<Mount>> {
+        self.inner.take()
+    }
+
+    #[cfg(feature = "ssr")]
+    fn render_to_string(&mut self, out: &mut String) {
+        if let Some(ref mut inner) = self.inner {
+            inner.render_to_string(out);
+        }
+    }
+
+    #[cfg(feature = "testing")]
+    fn render_to_test(&mut self) -> ::testing::TestNode {
+        match self.inner {
+            Some(ref mut inner) => inner.render_to_test(),
+            None => ::testing::TestNode::Text(String::new()),
+        }
+    }
+
+    #[cfg(feature = "a11y")]
+    fn audit(&self, audit: &mut ::a11y::Audit) {
+        if let Some(ref inner) = self.inner {
+            inner.audit(audit);
+        }
+    }
+
+    fn into_any(self: Box<Self>) -> Box<Any> {
+        self
+    }
+}
+
+/// A state of a mounted component.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+struct ComponentState<COMP: Component> {
+    component: COMP,
+    parent: Node,
+    last_frame: Option<VNode<COMP::Msg>>,
+    sender: AppSender<COMP::Msg>,
+    /// A copy of the last properties kept by a memoized component.
+    memo_props: Option<COMP::Properties>,
+    destroyed: bool,
+    /// An id of the component in the inspector.
+    #[cfg(feature = "devtools")]
+    id: usize,
+}
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+impl<COMP: Component> ComponentState<COMP> {
+    fn render(&mut self) {
+        #[cfg(feature = "devtools")]
+        let _scope = ::devtools::enter(self.id);
+        let name = ::std::any::type_name::<COMP>();
+        let mut next_frame = {
+            let _span = trace::span("view", name);
+            #[cfg(feature = "profiler")]
+            let _measure = ::profiler::measure(name, ::profiler::Phase::View);
+            #[cfg(feature = "strict")]
+            let _viewing = ::strict::viewing(name);
+            VNode::from(self.component.view())
+        };
+        #[cfg(feature = "strict")]
+        ::strict::check(name, &next_frame, || VNode::from(self.component.view()));
+        let _span = trace::span("diff", name);
+        let _slice = slicing::begin();
+        {
+            #[cfg(feature = "profiler")]
+            let _measure = ::profiler::measure(name, ::profiler::Phase::Patch);
+            next_frame.apply(&self.parent, self.last_frame.take(), self.sender.clone());
+        }
+        self.last_frame = Some(next_frame);
+        direction::sync_document();
+        css::sync();
+        theme::sync();
+        #[cfg(feature = "a11y")]
+        ::a11y::schedule();
+    }
+
+    fn hydrate(&mut self, next: &mut Option<Node>) -> Result<(), String> {
+        #[cfg(feature = "devtools")]
+        let _scope = ::devtools::enter(self.id);
+        let mut frame = VNode::from(self.component.view());
+        let result = frame.hydrate(&self.parent, next, self.sender.clone());
+        // Keep the frame anyway to clean it up when the component is unmounted
+        self.last_frame = Some(frame);
+        result
+    }
+}
+
+/// Functions to compare properties of a memoized component.
+struct Memo<T> {
+    eq: fn(&T, &T) -> bool,
+    clone: fn(&T) -> T,
+}
+
+/// A subtree of a component which is rendered with the own loop.
+pub(crate) struct ComponentMount<COMP: Component> {
+    props: Option<COMP::Properties>,
+    memo: Option<Memo<COMP::Properties>>,
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    state: Option<Rc<RefCell<ComponentState<COMP>>>>,
+    /// The own loop of the component which is stopped when it's unmounted.
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    app: Option<App<COMP::Msg>>,
+}
+
+impl<COMP: Component> ComponentMount<COMP> {
+    pub(crate) fn new(props: COMP::Properties) -> Self {
+        ComponentMount {
+            props: Some(props),
+            memo: None,
+            #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+            state: None,
+            #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+            app: None,
+        }
+    }
+}
+
+impl<COMP> ComponentMount<COMP>
+where
+    COMP: Component,
+    COMP::Properties: Clone + PartialEq,
+{
+    /// Creates a subtree which skips `change` calls of the component
+    /// if properties are equal to the properties of the last render.
+    pub(crate) fn memo(props: COMP::Properties) -> Self {
+        let memo = Memo {
+            eq: <COMP::Properties as PartialEq>::eq,
+            clone: <COMP::Properties as Clone>::clone,
+        };
+        ComponentMount {
+            props: Some(props),
+            memo: Some(memo),
+            #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+            state: None,
+            #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+            app: None,
+        }
+    }
+}
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+impl<COMP: Component> ComponentMount<COMP> {
+    /// Creates an instance of the component with an own loop.
+    fn create(&mut self, parent: &Node) -> (App<COMP::Msg>, Rc<RefCell<ComponentState<COMP>>>) {
+        let props = self.props.take().expect("tried to mount a component twice");
+        let memo_props = self.memo.as_ref().map(|memo| (memo.clone)(&props));
+        let mut app = App::<COMP::Msg>::new();
+        let sender = app.sender();
+        let component = COMP::create(props, sender.clone());
+        #[cfg(feature = "devtools")]
+        let id = ::devtools::mounted(::std::any::type_name::<COMP>(), component.inspect());
+        let state = ComponentState {
+            component,
+            parent: parent.clone(),
+            last_frame: None,
+            sender,
+            memo_props,
+            destroyed: false,
+            #[cfg(feature = "devtools")]
+            id,
+        };
+        (app, Rc::new(RefCell::new(state)))
+    }
+
+    /// Starts the loop of the rendered component.
+    fn run(&mut self, mut app: App<COMP::Msg>, state: Rc<RefCell<ComponentState<COMP>>>) {
+        let handle = state.clone();
+        app.handle(move |messages| {
+            let mut state = handle.borrow_mut();
+            if state.destroyed {
+                return;
+            }
+            let mut should_render = false;
+            #[cfg(feature = "devtools")]
+            let mut count = 0;
+            let span = trace::span("update", ::std::any::type_name::<COMP>());
+            for msg in messages {
+                should_render |= state.component.update(msg);
+                #[cfg(feature = "devtools")]
+                {
+                    count += 1;
+                }
+            }
+            drop(span);
+            if should_render {
+                state.render();
+            }
+            #[cfg(feature = "devtools")]
+            ::devtools::updated(state.id, count, should_render, state.component.inspect());
+        });
+        self.state = Some(state);
+        self.app = Some(app);
+    }
+}
+
+impl<COMP: Component> Mount for ComponentMount<COMP> {
+    fn kind(&self) -> TypeId {
+        TypeId::of::<COMP>()
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn mount(&mut self, parent: &Node) {
+        let (app, state) = self.create(parent);
+        state.borrow_mut().render();
+        self.run(app, state);
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn hydrate(&mut self, parent: &Node, next: &mut Option<Node>) -> Result<(), String> {
+        let (app, state) = self.create(parent);
+        let result = state.borrow_mut().hydrate(next);
+        self.run(app, state);
+        result
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn patch(&mut self, parent: &Node, last: Box<Mount>) {
+        let mut last = last.into_any().downcast::<ComponentMount<COMP>>()
+            .expect("tried to patch a component of other type");
+        let props = self.props.take().expect("tried to patch a component twice");
+        let state = last.state.take().expect("tried to patch an unmounted component");
+        self.app = last.app.take();
+        {
+            let mut state = state.borrow_mut();
+            state.parent = parent.clone();
+            let unchanged = {
+                match (self.memo.as_ref(), state.memo_props.as_ref()) {
+                    (Some(memo), Some(last_props)) => (memo.eq)(last_props, &props),
+                    _ => false,
+                }
+            };
+            if !unchanged {
+                if let Some(ref memo) = self.memo {
+                    state.memo_props = Some((memo.clone)(&props));
+                }
+                let should_render = state.component.change(props);
+                if should_render {
+                    state.render();
+                }
+                #[cfg(feature = "devtools")]
+                ::devtools::updated(state.id, 0, should_render, state.component.inspect());
+            }
+        }
+        self.state = Some(state);
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn reference(&self) -> Option<Node> {
+        self.state.as_ref().and_then(|state| {
+            state.borrow().last_frame.as_ref().and_then(VNode::reference)
+        })
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn unmount(&mut self) -> Option<Node> {
+        if let Some(mut app) = self.app.take() {
+            app.unmount();
+        }
+        self.state.take().and_then(|state| {
+            let mut state = state.borrow_mut();
+            state.destroyed = true;
+            state.component.destroy();
+            #[cfg(feature = "devtools")]
+            ::devtools::unmounted(state.id);
+            state.last_frame.take().and_then(VNode::into_reference)
+        })
+    }
+
+    #[cfg(feature = "ssr")]
+    fn render_to_string(&mut self, out: &mut String) {
+        let props = self.props.take().expect("tried to render a component twice");
+        let component = COMP::create(props, AppSender::detached());
+        let mut frame = VNode::from(component.view());
+        ::ssr::write_node(&mut frame, out);
+    }
+
+    #[cfg(feature = "testing")]
+    fn render_to_test(&mut self) -> ::testing::TestNode {
+        let props = self.props.take().expect("tried to render a component twice");
+        let mut component = COMP::create(props, AppSender::detached());
+        let mut frame = {
+            #[cfg(feature = "profiler")]
+            let _measure = ::profiler::measure(::std::any::type_name::<COMP>(), ::profiler::Phase::View);
+            VNode::from(component.view())
+        };
+        let node = {
+            #[cfg(feature = "profiler")]
+            let _measure = ::profiler::measure(::std::any::type_name::<COMP>(), ::profiler::Phase::Patch);
+            ::testing::component::<COMP>(&mut frame)
+        };
+        component.destroy();
+        node
+    }
+
+    #[cfg(feature = "a11y")]
+    fn audit(&self, audit: &mut ::a11y::Audit) {
+        #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+        {
+            if let Some(ref state) = self.state {
+                // The component is rendering now if it's borrowed, it will be audited later
+                if let Ok(state) = state.try_borrow() {
+                    if let Some(ref frame) = state.last_frame {
+                        audit.node(frame);
+                    }
+                }
+            }
+        }
+        // Native builds never mount components
+        #[cfg(not(any(target_arch = "wasm32", target_os = "emscripten")))]
+        let _ = audit;
+    }
+
+    fn into_any(self: Box<Self>) -> Box<Any> {
+        self
+    }
+}
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+impl<COMP: Component> Drop for ComponentMount<COMP> {
+    fn drop(&mut self) {
+        self.unmount();
+    }
+}
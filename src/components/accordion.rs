@@ -0,0 +1,201 @@
+//! This module contains a headless accordion which follows the ARIA
+//! accordion pattern.
+
+use callback::Callback;
+use component::{Component, ShouldRender, Render};
+use focus::{roving_index, Orientation};
+use html::{AppSender, Html, KeyData};
+use virtual_dom::{NodeRef, VNode, VText};
+
+/// A state of a section for rendering its header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Section {
+    /// The index of the section.
+    pub index: usize,
+    /// The panel of the section is shown.
+    pub expanded: bool,
+    /// The section can't be expanded or collapsed.
+    pub disabled: bool,
+}
+
+/// Properties of the `Accordion` component.
+#[derive(Clone, PartialEq)]
+pub struct AccordionProps {
+    /// A prefix of ids of headers and panels, unique on the page.
+    pub id: String,
+    /// The count of sections.
+    pub count: usize,
+    /// Renders the header of a section inside of its button.
+    pub header: Render<Section>,
+    /// Renders the panel of an expanded section.
+    pub panel: Render<usize>,
+    /// Expanded sections, which makes the accordion controlled: a click
+    /// only calls `onchange` and a parent changes them.
+    pub expanded: Option<Vec<usize>>,
+    /// Sections which are expanded first if the accordion is uncontrolled.
+    pub default_expanded: Vec<usize>,
+    /// More than one section can be expanded.
+    pub multiple: bool,
+    /// The only expanded section can be collapsed.
+    pub collapsible: bool,
+    /// Indexes of sections which can't be toggled.
+    pub disabled: Vec<usize>,
+    /// A level of headings of headers.
+    pub level: u8,
+    /// Called with indexes of sections which are expanded after a user toggled one.
+    pub onchange: Callback<Vec<usize>>,
+}
+
+impl Default for AccordionProps {
+    fn default() -> Self {
+        AccordionProps {
+            id: "accordion".into(),
+            count: 0,
+            header: Render::default(),
+            panel: Render::default(),
+            expanded: None,
+            default_expanded: Vec::new(),
+            multiple: false,
+            collapsible: true,
+            disabled: Vec::new(),
+            level: 3,
+            onchange: Callback::default(),
+        }
+    }
+}
+
+/// A message of the `Accordion` component.
+pub enum Msg {
+    /// A user clicked the header.
+    Toggle(usize),
+    /// A key was pressed on the header.
+    Key(usize, String),
+}
+
+/// Sections with headers which expand and collapse their panels. It renders
+/// buttons inside of headings with `aria-expanded` and regions of panels,
+/// and a parent renders headers and panels with its own markup and styles.
+/// Up and down arrows and `Home`/`End` move between headers.
+///
+/// ```rust,ignore
+/// html! {
+///     <Accordion: id="faq", count=self.questions.len(), multiple=true,
+///                 header=|section: Section| html! { <span>{ &questions[section.index].title }</span> },
+///                 panel=|index| view_answer(index), />
+/// }
+/// ```
+pub struct Accordion {
+    props: AccordionProps,
+    // Expanded sections of an uncontrolled accordion
+    current: Vec<usize>,
+    refs: Vec<NodeRef>,
+}
+
+impl Accordion {
+    fn expanded(&self) -> &[usize] {
+        match self.props.expanded {
+            Some(ref expanded) => expanded,
+            None => &self.current,
+        }
+    }
+
+    fn toggle(&mut self, index: usize) -> ShouldRender {
+        if index >= self.props.count || self.props.disabled.contains(&index) {
+            return false;
+        }
+        let mut expanded = self.expanded().to_vec();
+        if expanded.contains(&index) {
+            if !self.props.collapsible && expanded.len() == 1 {
+                return false;
+            }
+            expanded.retain(|item| *item != index);
+        } else if self.props.multiple {
+            expanded.push(index);
+        } else {
+            expanded = vec![index];
+        }
+        if self.props.expanded.is_none() {
+            self.current = expanded.clone();
+        }
+        self.props.onchange.emit(expanded);
+        true
+    }
+
+    fn view_section(&self, index: usize) -> Html<Msg> {
+        let expanded = self.expanded().contains(&index);
+        let section = Section {
+            index,
+            expanded,
+            // An only expanded section of a not collapsible accordion can't be collapsed
+            disabled: self.props.disabled.contains(&index)
+                || (expanded && !self.props.collapsible && self.expanded().len() == 1),
+        };
+        let header = format!("{}-header-{}", self.props.id, index);
+        let panel = format!("{}-panel-{}", self.props.id, index);
+        let content = if expanded { self.props.panel.view(index) } else { VNode::from(VText::new("")) };
+        html! {
+            <div class="accordion-section",>
+                <div role="heading", class="accordion-heading", aria-level=self.props.level,>
+                    <button type="button", class="accordion-header", id=&header, ref=&self.refs[index],
+                            aria-expanded=if expanded { "true" } else { "false" },
+                            aria-controls=&panel,
+                            aria-disabled=if section.disabled { Some("true") } else { None },
+                            onclick=move |_| Msg::Toggle(index),
+                            onkeydown=move |e: KeyData| Msg::Key(index, e.key),>
+                        { self.props.header.view(section) }
+                    </button>
+                </div>
+                <div role="region", class="accordion-panel", id=&panel, aria-labelledby=&header, hidden=!expanded,>
+                    { content }
+                </div>
+            </div>
+        }
+    }
+}
+
+impl Component for Accordion {
+    type Msg = Msg;
+    type Properties = AccordionProps;
+
+    fn create(props: Self::Properties, _: AppSender<Msg>) -> Self {
+        let mut current = props.default_expanded.clone();
+        if !props.multiple {
+            current.truncate(1);
+        }
+        let mut refs = Vec::new();
+        refs.resize(props.count, NodeRef::default());
+        Accordion { props, current, refs }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Toggle(index) => self.toggle(index),
+            Msg::Key(index, key) => {
+                // Headers are buttons, so Enter and Space click them
+                if let Some(next) = roving_index(&key, index, self.props.count, Orientation::Vertical, &[]) {
+                    self.refs[next].focus();
+                }
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        self.refs.resize(self.props.count, NodeRef::default());
+        let count = self.props.count;
+        self.current.retain(|index| *index < count);
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        html! {
+            <div class="accordion",>
+                { for (0..self.props.count).map(|index| self.view_section(index)) }
+            </div>
+        }
+    }
+}
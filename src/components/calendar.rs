@@ -0,0 +1,314 @@
+//! This module contains a calendar of a month with a grid of days.
+
+use callback::Callback;
+use component::{Component, ShouldRender};
+use date::{Date, DateRange};
+use direction;
+use html::{AppSender, Html, KeyData};
+use services::intl::{CalendarField, DateStyle, IntlService};
+use virtual_dom::NodeRef;
+
+/// Days in the grid: six weeks fit every month.
+const GRID_DAYS: usize = 42;
+
+/// Properties of the `Calendar` component.
+#[derive(Clone, PartialEq)]
+pub struct CalendarProps {
+    /// A prefix of ids of the heading and the days, unique on the page.
+    /// The heading gets the `{id}-heading` id and days get `{id}-2024-01-02`.
+    pub id: String,
+    /// The selected days, a range of one day unless `range` is set.
+    pub selected: Option<DateRange>,
+    /// The earliest day which could be selected.
+    pub min: Option<Date>,
+    /// The latest day which could be selected.
+    pub max: Option<Date>,
+    /// Selects a range with two clicks or presses of `Enter` instead of a day.
+    pub range: bool,
+    /// Moves focus to the selected day or today when the calendar is created.
+    pub autofocus: bool,
+    /// A label of the button which shows the previous month.
+    pub previous_label: String,
+    /// A label of the button which shows the next month.
+    pub next_label: String,
+    /// Called with the days which a user selected.
+    pub onselect: Callback<DateRange>,
+    /// Called when `Escape` was pressed on a day.
+    pub onclose: Callback<()>,
+}
+
+impl Default for CalendarProps {
+    fn default() -> Self {
+        CalendarProps {
+            id: "calendar".into(),
+            selected: None,
+            min: None,
+            max: None,
+            range: false,
+            autofocus: false,
+            previous_label: "Previous month".into(),
+            next_label: "Next month".into(),
+            onselect: Callback::default(),
+            onclose: Callback::default(),
+        }
+    }
+}
+
+/// A message of the `Calendar` component.
+pub enum Msg {
+    /// A key was pressed on the focused day.
+    Key(KeyData),
+    /// A day was clicked.
+    Select(Date),
+    /// The pointer entered a day or left the grid, which previews a range.
+    Hover(Option<Date>),
+    /// Shows a month which is that many months later.
+    Month(i32),
+    /// Moves focus to the focused day after it's rendered.
+    FocusDay,
+}
+
+/// A grid of days of a month with weekdays of the locale of `IntlService`.
+/// Days have a roving `tabindex`: arrows move by days and weeks, `Home` and
+/// `End` to the edges of the week, `PageUp` and `PageDown` by months and
+/// with `Shift` by years. Days are buttons, so `Enter` and `Space` click
+/// them, and days out of `min` and `max` can't be selected.
+///
+/// ```rust,ignore
+/// html! {
+///     <Calendar: id="due", selected=self.due.map(DateRange::day), min=Some(self.today),
+///                onselect=|range: DateRange| Msg::Due(range.start), />
+/// }
+/// ```
+pub struct Calendar {
+    props: CalendarProps,
+    sender: AppSender<Msg>,
+    intl: IntlService,
+    // The day with `tabindex="0"`, which shows its month
+    focused: Date,
+    // The first day of a range which is being selected
+    pending: Option<Date>,
+    hovered: Option<Date>,
+    refs: Vec<NodeRef>,
+}
+
+impl Calendar {
+    fn initial(&self) -> Date {
+        let date = match self.props.selected {
+            Some(range) => range.start,
+            None => self.intl.today(),
+        };
+        date.clamp_to(self.props.min, self.props.max)
+    }
+
+    /// Returns the first day of the grid of the focused month.
+    fn grid_start(&self) -> Date {
+        self.focused.first_of_month().start_of_week(self.intl.first_weekday())
+    }
+
+    fn move_focus(&mut self, date: Date) -> ShouldRender {
+        let date = date.clamp_to(self.props.min, self.props.max);
+        if date == self.focused {
+            return false;
+        }
+        self.focused = date;
+        self.sender.send_soon(Msg::FocusDay);
+        true
+    }
+
+    fn select(&mut self, date: Date) -> ShouldRender {
+        if !date.is_within(self.props.min, self.props.max) {
+            return false;
+        }
+        self.focused = date;
+        if !self.props.range {
+            self.props.onselect.emit(DateRange::day(date));
+            return true;
+        }
+        match self.pending.take() {
+            Some(start) => self.props.onselect.emit(DateRange::new(start, date)),
+            None => self.pending = Some(date),
+        }
+        true
+    }
+
+    /// Returns the highlighted days: a range which is being selected
+    /// or the selected days.
+    fn highlighted(&self) -> Option<DateRange> {
+        match self.pending {
+            Some(start) => Some(DateRange::new(start, self.hovered.unwrap_or(start))),
+            None => self.props.selected,
+        }
+    }
+
+    fn view_day(&self, index: usize, date: Date, today: Date) -> Html<Msg> {
+        let highlighted = self.highlighted();
+        let selected = highlighted.map_or(false, |range| range.contains(date));
+        let disabled = !date.is_within(self.props.min, self.props.max);
+        let mut class = vec!["calendar-day"];
+        if date.month() != self.focused.month() {
+            class.push("outside");
+        }
+        if let Some(range) = highlighted {
+            if self.props.range && range.start == date {
+                class.push("range-start");
+            }
+            if self.props.range && range.end == date {
+                class.push("range-end");
+            }
+        }
+        html! {
+            <td role="gridcell", aria-selected=if selected { "true" } else { "false" },>
+                <button type="button", class=class.join(" "), id=format!("{}-{}", self.props.id, date),
+                        ref=&self.refs[index],
+                        tabindex=if date == self.focused { "0" } else { "-1" },
+                        aria-label=self.intl.calendar_date(date, DateStyle::Full),
+                        aria-current=if date == today { Some("date") } else { None },
+                        aria-disabled=if disabled { Some("true") } else { None },
+                        onclick=move |_| Msg::Select(date),
+                        onmouseenter=move |_| Msg::Hover(Some(date)),
+                        onkeydown=|e: KeyData| Msg::Key(e),>
+                    { self.intl.number(f64::from(date.day())) }
+                </button>
+            </td>
+        }
+    }
+
+    fn view_week(&self, week: usize, start: Date, today: Date) -> Html<Msg> {
+        html! {
+            <tr>
+                { for (0..7).map(|day| {
+                    let index = week * 7 + day;
+                    self.view_day(index, start.add_days(index as i64), today)
+                }) }
+            </tr>
+        }
+    }
+}
+
+impl Component for Calendar {
+    type Msg = Msg;
+    type Properties = CalendarProps;
+
+    fn create(props: Self::Properties, sender: AppSender<Msg>) -> Self {
+        let mut calendar = Calendar {
+            props,
+            sender,
+            intl: IntlService::new(),
+            focused: Date::from_days(0),
+            pending: None,
+            hovered: None,
+            refs: vec![NodeRef::default(); GRID_DAYS],
+        };
+        calendar.focused = calendar.initial();
+        if calendar.props.autofocus {
+            calendar.sender.send_soon(Msg::FocusDay);
+        }
+        calendar
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Key(data) => {
+                // Arrows follow the reading direction
+                let forward = if direction::direction().is_rtl() { -1 } else { 1 };
+                let shift = data.modifiers.shift;
+                let focused = self.focused;
+                let target = match data.key.as_str() {
+                    "ArrowRight" => focused.add_days(forward),
+                    "ArrowLeft" => focused.add_days(-forward),
+                    "ArrowDown" => focused.add_days(7),
+                    "ArrowUp" => focused.add_days(-7),
+                    "Home" => focused.start_of_week(self.intl.first_weekday()),
+                    "End" => focused.start_of_week(self.intl.first_weekday()).add_days(6),
+                    "PageDown" if shift => focused.add_years(1),
+                    "PageUp" if shift => focused.add_years(-1),
+                    "PageDown" => focused.add_months(1),
+                    "PageUp" => focused.add_months(-1),
+                    "Escape" => {
+                        self.pending = None;
+                        self.props.onclose.emit(());
+                        return true;
+                    }
+                    _ => return false,
+                };
+                self.move_focus(target)
+            }
+            Msg::Select(date) => self.select(date),
+            Msg::Hover(date) => {
+                self.hovered = date;
+                self.pending.is_some()
+            }
+            Msg::Month(months) => {
+                let target = self.focused.add_months(months);
+                let target = target.clamp_to(self.props.min, self.props.max);
+                let changed = target != self.focused;
+                self.focused = target;
+                changed
+            }
+            Msg::FocusDay => {
+                let index = self.focused.days() - self.grid_start().days();
+                if let Some(day) = self.refs.get(index as usize) {
+                    day.focus();
+                }
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        let reselected = self.props.selected != props.selected;
+        self.props = props;
+        if reselected {
+            self.pending = None;
+            self.focused = self.initial();
+        } else {
+            self.focused = self.focused.clamp_to(self.props.min, self.props.max);
+        }
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let start = self.grid_start();
+        let today = self.intl.today();
+        let month = self.focused.first_of_month();
+        let previous_disabled = self.props.min.map_or(false, |min| month <= min);
+        let next_disabled = self.props.max.map_or(false, |max| month.last_of_month() >= max);
+        let heading = format!("{}-heading", self.props.id);
+        html! {
+            <div class="calendar", id=&self.props.id,>
+                <div class="calendar-header",>
+                    <button type="button", class="calendar-previous", aria-label=&self.props.previous_label,
+                            disabled=previous_disabled, onclick=|_| Msg::Month(-1),>{ "‹" }</button>
+                    <h2 class="calendar-heading", id=&heading, aria-live="polite",>
+                        { self.intl.calendar_field(month, CalendarField::MonthYear) }
+                    </h2>
+                    <button type="button", class="calendar-next", aria-label=&self.props.next_label,
+                            disabled=next_disabled, onclick=|_| Msg::Month(1),>{ "›" }</button>
+                </div>
+                <table role="grid", class="calendar-grid", aria-labelledby=&heading,
+                       aria-multiselectable=if self.props.range { Some("true") } else { None },
+                       onmouseleave=|_| Msg::Hover(None),>
+                    <thead>
+                        <tr>
+                            { for (0..7).map(|day| {
+                                let date = start.add_days(day);
+                                html! {
+                                    <th scope="col", ["abbr"]=self.intl.calendar_field(date, CalendarField::Weekday),>
+                                        { self.intl.calendar_field(date, CalendarField::ShortWeekday) }
+                                    </th>
+                                }
+                            }) }
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { for (0..GRID_DAYS / 7).map(|week| self.view_week(week, start, today)) }
+                    </tbody>
+                </table>
+            </div>
+        }
+    }
+}
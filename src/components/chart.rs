@@ -0,0 +1,705 @@
+//! This module contains SVG charts: lines, bars, pies and sparklines.
+//! Scales and ticks are computed in Rust, so dashboards don't need
+//! a JS charting library and render as any other view:
+//!
+//! ```rust,ignore
+//! Msg::Received(sample) => {
+//!     self.latency.push(sample.time, sample.latency);
+//!     self.latency.keep_last(120);
+//! }
+//!
+//! html! { <LineChart: series=vec![self.latency.clone()], title="Latency", /> }
+//! ```
+
+use std::f64::consts::PI;
+use std::rc::Rc;
+use component::{Component, ShouldRender};
+use html::{AppSender, Html};
+use virtual_dom::{VNode, VText};
+
+/// Colors of series and slices which don't have a color.
+pub const PALETTE: &[&str] = &[
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f",
+    "#edc948", "#b07aa1", "#ff9da7", "#9c755f", "#bab0ac",
+];
+
+// Space around the plot for ticks: top, right, bottom and left
+const MARGIN: (f64, f64, f64, f64) = (12.0, 16.0, 28.0, 48.0);
+
+fn color_of(color: &str, index: usize) -> String {
+    if color.is_empty() {
+        PALETTE[index % PALETTE.len()].to_owned()
+    } else {
+        color.to_owned()
+    }
+}
+
+/// Formats a coordinate with at most two decimals.
+fn num(value: f64) -> String {
+    let text = format!("{:.2}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        "0".into()
+    } else {
+        text.into()
+    }
+}
+
+fn extent<I: Iterator<Item = f64>>(values: I) -> Option<(f64, f64)> {
+    values.filter(|value| value.is_finite()).fold(None, |extent, value| match extent {
+        Some((min, max)) => Some((value.min(min), value.max(max))),
+        None => Some((value, value)),
+    })
+}
+
+/// Returns a round step of about `count` ticks between the values: 1, 2 or 5
+/// multiplied by a power of 10. Returns `0` for an empty span.
+pub fn tick_step(min: f64, max: f64, count: usize) -> f64 {
+    let span = (max - min).abs();
+    if count == 0 || span == 0.0 || !span.is_finite() {
+        return 0.0;
+    }
+    let raw = span / count as f64;
+    let power = 10f64.powf(raw.log10().floor());
+    let error = raw / power;
+    let factor = if error >= 50f64.sqrt() {
+        10.0
+    } else if error >= 10f64.sqrt() {
+        5.0
+    } else if error >= 2f64.sqrt() {
+        2.0
+    } else {
+        1.0
+    };
+    factor * power
+}
+
+/// Formats a tick with as many decimals as its step needs.
+pub fn format_tick(value: f64, step: f64) -> String {
+    let decimals = if step > 0.0 && step < 1.0 { (-step.log10().floor()) as usize } else { 0 };
+    let text = format!("{:.*}", decimals, value);
+    // Rounded negative values like `-0.00` are zero
+    if text.starts_with('-') && text.bytes().all(|byte| byte == b'-' || byte == b'0' || byte == b'.') {
+        text[1..].to_owned()
+    } else {
+        text
+    }
+}
+
+/// Formats values of ticks with their step, like times. Ticks are
+/// formatted with `format_tick` by default.
+#[derive(Clone, Default)]
+pub struct TickFormat(Option<Rc<Fn(f64, f64) -> String>>);
+
+impl TickFormat {
+    /// Creates a format from a function of a value and a step.
+    pub fn new<F>(format: F) -> Self
+    where
+        F: Fn(f64, f64) -> String + 'static,
+    {
+        TickFormat(Some(Rc::new(format)))
+    }
+
+    /// Formats the value of a tick.
+    pub fn format(&self, value: f64, step: f64) -> String {
+        match self.0 {
+            Some(ref format) => format(value, step),
+            None => format_tick(value, step),
+        }
+    }
+}
+
+impl PartialEq for TickFormat {
+    fn eq(&self, other: &TickFormat) -> bool {
+        match (&self.0, &other.0) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+/// A linear mapping of values of a domain to coordinates of a range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearScale {
+    /// The smallest and the largest values.
+    pub domain: (f64, f64),
+    /// Coordinates of the values. The start is larger for vertical axes,
+    /// which grow up while SVG coordinates grow down.
+    pub range: (f64, f64),
+}
+
+impl LinearScale {
+    /// Creates a scale which maps the domain to the range.
+    pub fn new(domain: (f64, f64), range: (f64, f64)) -> Self {
+        LinearScale { domain, range }
+    }
+
+    /// Extends the domain to round values of about `count` ticks.
+    pub fn nice(mut self, count: usize) -> Self {
+        let (min, max) = self.domain;
+        let step = tick_step(min, max, count);
+        if step > 0.0 {
+            self.domain = ((min / step).floor() * step, (max / step).ceil() * step);
+        }
+        self
+    }
+
+    /// Returns the coordinate of the value. Values of an empty domain
+    /// are in the middle of the range.
+    pub fn map(&self, value: f64) -> f64 {
+        let (min, max) = self.domain;
+        let (start, end) = self.range;
+        if max == min {
+            return (start + end) / 2.0;
+        }
+        start + (value - min) / (max - min) * (end - start)
+    }
+
+    /// Returns the step of ticks.
+    pub fn step(&self, count: usize) -> f64 {
+        tick_step(self.domain.0, self.domain.1, count)
+    }
+
+    /// Returns round values of about `count` ticks inside the domain.
+    pub fn ticks(&self, count: usize) -> Vec<f64> {
+        let (min, max) = (self.domain.0.min(self.domain.1), self.domain.0.max(self.domain.1));
+        let step = self.step(count);
+        if step == 0.0 {
+            return vec![min];
+        }
+        let (first, last) = ((min / step).ceil() as i64, (max / step).floor() as i64);
+        (first..=last).map(|index| index as f64 * step).collect()
+    }
+}
+
+/// A mapping of categories to bands of the same width, like bars.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BandScale {
+    /// The count of categories.
+    pub count: usize,
+    /// Coordinates of the start and the end of all bands.
+    pub range: (f64, f64),
+    /// A part of a band from 0 to 1 which is empty, split between its sides.
+    pub padding: f64,
+}
+
+impl BandScale {
+    /// Creates bands with `0.2` of padding.
+    pub fn new(count: usize, range: (f64, f64)) -> Self {
+        BandScale { count, range, padding: 0.2 }
+    }
+
+    /// Returns the distance between starts of bands.
+    pub fn step(&self) -> f64 {
+        (self.range.1 - self.range.0) / self.count.max(1) as f64
+    }
+
+    /// Returns the width of a band without padding.
+    pub fn bandwidth(&self) -> f64 {
+        self.step() * (1.0 - self.padding)
+    }
+
+    /// Returns the start of the band of the category with the index.
+    pub fn start(&self, index: usize) -> f64 {
+        let step = self.step();
+        self.range.0 + step * index as f64 + step * self.padding / 2.0
+    }
+}
+
+/// Returns a path through points which are already mapped to coordinates.
+pub fn line_path(points: &[(f64, f64)]) -> String {
+    let mut path = String::new();
+    for (index, &(x, y)) in points.iter().enumerate() {
+        path.push(if index == 0 { 'M' } else { 'L' });
+        path.push_str(&num(x));
+        path.push(',');
+        path.push_str(&num(y));
+    }
+    path
+}
+
+/// Returns a path of a slice of a pie between angles in radians, clockwise
+/// from the top. A slice of a donut has an `inner` radius larger than `0`.
+pub fn arc_path(center: (f64, f64), outer: f64, inner: f64, start: f64, end: f64) -> String {
+    // A full circle has the same start and end, so it's drawn a bit shorter
+    let end = start + (end - start).min(2.0 * PI - 1e-6);
+    let large = if end - start > PI { 1 } else { 0 };
+    let point = |angle: f64, radius: f64| {
+        format!("{},{}", num(center.0 + radius * angle.sin()), num(center.1 - radius * angle.cos()))
+    };
+    let mut path = format!("M{}A{},{} 0 {} 1 {}", point(start, outer), num(outer), num(outer), large, point(end, outer));
+    if inner > 0.0 {
+        path.push_str(&format!("L{}A{},{} 0 {} 0 {}", point(end, inner), num(inner), num(inner), large, point(start, inner)));
+    } else {
+        path.push_str(&format!("L{},{}", num(center.0), num(center.1)));
+    }
+    path.push('Z');
+    path
+}
+
+/// A series of points of a line chart.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Series {
+    /// A name of the series for the legend.
+    pub name: String,
+    /// A CSS color of the line, a color of the palette if it's empty.
+    pub color: String,
+    /// Points of the series in order of the line.
+    pub points: Vec<(f64, f64)>,
+}
+
+impl Series {
+    /// Creates an empty series.
+    pub fn new(name: &str) -> Self {
+        Series {
+            name: name.to_owned(),
+            ..Series::default()
+        }
+    }
+
+    /// Creates a series of values with their indexes as `x`.
+    pub fn values(name: &str, values: &[f64]) -> Self {
+        Series {
+            points: values.iter().enumerate().map(|(index, value)| (index as f64, *value)).collect(),
+            ..Series::new(name)
+        }
+    }
+
+    /// Sets the color of the line.
+    pub fn color(mut self, color: &str) -> Self {
+        self.color = color.to_owned();
+        self
+    }
+
+    /// Adds a point to the end, like a sample which came with a websocket.
+    pub fn push(&mut self, x: f64, y: f64) {
+        self.points.push((x, y));
+    }
+
+    /// Removes the oldest points and keeps the last `count` ones.
+    pub fn keep_last(&mut self, count: usize) {
+        let extra = self.points.len().saturating_sub(count);
+        self.points.drain(..extra);
+    }
+}
+
+/// A labeled value of a bar or a slice of a pie.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Datum {
+    /// A label of the value.
+    pub label: String,
+    /// The value.
+    pub value: f64,
+    /// A CSS color, a color of the palette if it's empty.
+    pub color: String,
+}
+
+impl Datum {
+    /// Creates a value with a label.
+    pub fn new(label: &str, value: f64) -> Self {
+        Datum {
+            label: label.to_owned(),
+            value,
+            color: String::new(),
+        }
+    }
+
+    /// Sets the color of the value.
+    pub fn color(mut self, color: &str) -> Self {
+        self.color = color.to_owned();
+        self
+    }
+}
+
+fn view_legend(items: Vec<(String, String)>) -> VNode<()> {
+    html! {
+        <ul class="chart-legend",>
+            { for items.into_iter().map(|(label, color)| html! {
+                <li>
+                    <span class="chart-swatch", style=format!("background: {};", color),></span>
+                    { label }
+                </li>
+            }) }
+        </ul>
+    }.into()
+}
+
+fn view_y_axis(scale: &LinearScale, count: usize, format: &TickFormat, width: f64) -> Vec<VNode<()>> {
+    let step = scale.step(count);
+    scale.ticks(count).into_iter().map(|tick| {
+        let y = num(scale.map(tick));
+        html! {
+            <g class="chart-tick",>
+                <line x1=num(MARGIN.3), x2=num(width - MARGIN.1), y1=&y, y2=&y,
+                      stroke="currentColor", stroke-opacity="0.15", />
+                <text x=num(MARGIN.3 - 6.0), y=&y, dy="0.32em", text-anchor="end", font-size="11",>
+                    { format.format(tick, step) }
+                </text>
+            </g>
+        }.into()
+    }).collect()
+}
+
+/// Properties of the `LineChart` component.
+#[derive(Clone, PartialEq)]
+pub struct LineChartProps {
+    /// Series of points. A legend is shown for more than one.
+    pub series: Vec<Series>,
+    /// A title of the chart for screen readers.
+    pub title: String,
+    /// The width of the chart in pixels.
+    pub width: f64,
+    /// The height of the chart in pixels.
+    pub height: f64,
+    /// The count of ticks of axes, about.
+    pub ticks: usize,
+    /// The vertical axis starts at zero.
+    pub zero: bool,
+    /// Formats values of the horizontal axis.
+    pub format_x: TickFormat,
+    /// Formats values of the vertical axis.
+    pub format_y: TickFormat,
+}
+
+impl Default for LineChartProps {
+    fn default() -> Self {
+        LineChartProps {
+            series: Vec::new(),
+            title: String::new(),
+            width: 640.0,
+            height: 320.0,
+            ticks: 5,
+            zero: false,
+            format_x: TickFormat::default(),
+            format_y: TickFormat::default(),
+        }
+    }
+}
+
+/// A chart of lines of series with axes.
+pub struct LineChart {
+    props: LineChartProps,
+}
+
+impl LineChart {
+    fn scales(&self) -> (LinearScale, LinearScale) {
+        let props = &self.props;
+        let points = || props.series.iter().flat_map(|series| series.points.iter());
+        let x = extent(points().map(|point| point.0)).unwrap_or((0.0, 1.0));
+        let mut y = extent(points().map(|point| point.1)).unwrap_or((0.0, 1.0));
+        if props.zero {
+            y = (y.0.min(0.0), y.1.max(0.0));
+        }
+        let x = LinearScale::new(x, (MARGIN.3, props.width - MARGIN.1));
+        let y = LinearScale::new(y, (props.height - MARGIN.2, MARGIN.0)).nice(props.ticks);
+        (x, y)
+    }
+}
+
+impl Component for LineChart {
+    type Msg = ();
+    type Properties = LineChartProps;
+
+    fn create(props: Self::Properties, _: AppSender<()>) -> Self {
+        LineChart { props }
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<()> {
+        let props = &self.props;
+        let (x, y) = self.scales();
+        let step = x.step(props.ticks);
+        let legend = if props.series.len() > 1 {
+            view_legend(props.series.iter().enumerate().map(|(index, series)| (series.name.clone(), color_of(&series.color, index))).collect())
+        } else {
+            VNode::from(VText::new(""))
+        };
+        html! {
+            <figure class="chart line-chart",>
+                <svg viewBox=format!("0 0 {} {}", num(props.width), num(props.height)),
+                     width=num(props.width), height=num(props.height), role="img", aria-label=&props.title,>
+                    { for view_y_axis(&y, props.ticks, &props.format_y, props.width) }
+                    { for x.ticks(props.ticks).into_iter().map(|tick| html! {
+                        <text class="chart-tick", x=num(x.map(tick)), y=num(props.height - MARGIN.2 + 18.0),
+                              text-anchor="middle", font-size="11",>
+                            { props.format_x.format(tick, step) }
+                        </text>
+                    }) }
+                    { for props.series.iter().enumerate().map(|(index, series)| {
+                        let points: Vec<(f64, f64)> = series.points.iter().map(|&(px, py)| (x.map(px), y.map(py))).collect();
+                        html! {
+                            <path class="chart-series", d=line_path(&points), fill="none",
+                                  stroke=color_of(&series.color, index), stroke-width="2",
+                                  stroke-linejoin="round",>
+                                <title>{ &series.name }</title>
+                            </path>
+                        }
+                    }) }
+                </svg>
+                { legend }
+            </figure>
+        }
+    }
+}
+
+/// Properties of the `BarChart` component.
+#[derive(Clone, PartialEq)]
+pub struct BarChartProps {
+    /// Values of bars in order. Negative values go down from zero.
+    pub data: Vec<Datum>,
+    /// A title of the chart for screen readers.
+    pub title: String,
+    /// The width of the chart in pixels.
+    pub width: f64,
+    /// The height of the chart in pixels.
+    pub height: f64,
+    /// The count of ticks of the vertical axis, about.
+    pub ticks: usize,
+    /// Formats values of the vertical axis.
+    pub format_y: TickFormat,
+}
+
+impl Default for BarChartProps {
+    fn default() -> Self {
+        BarChartProps {
+            data: Vec::new(),
+            title: String::new(),
+            width: 640.0,
+            height: 320.0,
+            ticks: 5,
+            format_y: TickFormat::default(),
+        }
+    }
+}
+
+/// A chart of bars of labeled values which start at zero.
+pub struct BarChart {
+    props: BarChartProps,
+}
+
+impl Component for BarChart {
+    type Msg = ();
+    type Properties = BarChartProps;
+
+    fn create(props: Self::Properties, _: AppSender<()>) -> Self {
+        BarChart { props }
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<()> {
+        let props = &self.props;
+        let (min, max) = extent(props.data.iter().map(|datum| datum.value)).unwrap_or((0.0, 1.0));
+        let y = LinearScale::new((min.min(0.0), max.max(0.0)), (props.height - MARGIN.2, MARGIN.0)).nice(props.ticks);
+        let bands = BandScale::new(props.data.len(), (MARGIN.3, props.width - MARGIN.1));
+        let zero = y.map(0.0);
+        html! {
+            <figure class="chart bar-chart",>
+                <svg viewBox=format!("0 0 {} {}", num(props.width), num(props.height)),
+                     width=num(props.width), height=num(props.height), role="img", aria-label=&props.title,>
+                    { for view_y_axis(&y, props.ticks, &props.format_y, props.width) }
+                    { for props.data.iter().enumerate().map(|(index, datum)| {
+                        let top = y.map(datum.value);
+                        let start = bands.start(index);
+                        html! {
+                            <g class="chart-bar",>
+                                <rect x=num(start), y=num(top.min(zero)), width=num(bands.bandwidth()),
+                                      height=num((top - zero).abs()), fill=color_of(&datum.color, index),>
+                                    <title>{ format!("{}: {}", datum.label, datum.value) }</title>
+                                </rect>
+                                <text x=num(start + bands.bandwidth() / 2.0), y=num(props.height - MARGIN.2 + 18.0),
+                                      text-anchor="middle", font-size="11",>
+                                    { &datum.label }
+                                </text>
+                            </g>
+                        }
+                    }) }
+                </svg>
+            </figure>
+        }
+    }
+}
+
+/// Properties of the `PieChart` component.
+#[derive(Clone, PartialEq)]
+pub struct PieChartProps {
+    /// Values of slices in order, clockwise from the top. Values which
+    /// aren't positive are skipped.
+    pub data: Vec<Datum>,
+    /// A title of the chart for screen readers.
+    pub title: String,
+    /// The width and the height of the chart in pixels.
+    pub size: f64,
+    /// A part of the radius from 0 to 1 which is empty, for donuts.
+    pub donut: f64,
+}
+
+impl Default for PieChartProps {
+    fn default() -> Self {
+        PieChartProps {
+            data: Vec::new(),
+            title: String::new(),
+            size: 240.0,
+            donut: 0.0,
+        }
+    }
+}
+
+/// A pie or a donut of labeled values with a legend.
+pub struct PieChart {
+    props: PieChartProps,
+}
+
+impl Component for PieChart {
+    type Msg = ();
+    type Properties = PieChartProps;
+
+    fn create(props: Self::Properties, _: AppSender<()>) -> Self {
+        PieChart { props }
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<()> {
+        let props = &self.props;
+        let total: f64 = props.data.iter().map(|datum| datum.value).filter(|value| *value > 0.0).sum();
+        let radius = props.size / 2.0;
+        let inner = radius * props.donut.clamp(0.0, 1.0);
+        let mut angle = 0.0;
+        let mut slices = Vec::new();
+        for (index, datum) in props.data.iter().enumerate() {
+            if datum.value <= 0.0 || !datum.value.is_finite() {
+                continue;
+            }
+            let end = angle + datum.value / total * 2.0 * PI;
+            let path = arc_path((radius, radius), radius, inner, angle, end);
+            angle = end;
+            slices.push(html! {
+                <path class="chart-slice", d=path, fill=color_of(&datum.color, index),>
+                    <title>{ format!("{}: {}", datum.label, datum.value) }</title>
+                </path>
+            });
+        }
+        let legend = props.data.iter().enumerate()
+            .map(|(index, datum)| (datum.label.clone(), color_of(&datum.color, index)))
+            .collect();
+        html! {
+            <figure class="chart pie-chart",>
+                <svg viewBox=format!("0 0 {} {}", num(props.size), num(props.size)),
+                     width=num(props.size), height=num(props.size), role="img", aria-label=&props.title,>
+                    { for slices }
+                </svg>
+                { view_legend(legend) }
+            </figure>
+        }
+    }
+}
+
+/// Properties of the `Sparkline` component.
+#[derive(Clone, PartialEq)]
+pub struct SparklineProps {
+    /// Values in order.
+    pub values: Vec<f64>,
+    /// A title of the line for screen readers, like the last value.
+    pub title: String,
+    /// The width of the line in pixels.
+    pub width: f64,
+    /// The height of the line in pixels.
+    pub height: f64,
+    /// A CSS color of the line.
+    pub color: String,
+}
+
+impl Default for SparklineProps {
+    fn default() -> Self {
+        SparklineProps {
+            values: Vec::new(),
+            title: String::new(),
+            width: 100.0,
+            height: 24.0,
+            color: "currentColor".into(),
+        }
+    }
+}
+
+/// A small line of values without axes which fits into text or a table cell.
+/// The last value is marked with a dot.
+pub struct Sparkline {
+    props: SparklineProps,
+}
+
+impl Component for Sparkline {
+    type Msg = ();
+    type Properties = SparklineProps;
+
+    fn create(props: Self::Properties, _: AppSender<()>) -> Self {
+        Sparkline { props }
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<()> {
+        let props = &self.props;
+        // Leave space for the dot
+        let x = LinearScale::new((0.0, props.values.len().saturating_sub(1) as f64), (2.0, props.width - 2.0));
+        let domain = extent(props.values.iter().cloned()).unwrap_or((0.0, 1.0));
+        let y = LinearScale::new(domain, (props.height - 2.0, 2.0));
+        let points: Vec<(f64, f64)> = props.values.iter().enumerate()
+            .map(|(index, value)| (x.map(index as f64), y.map(*value)))
+            .collect();
+        let dot: VNode<()> = match points.last() {
+            Some(&(cx, cy)) => html! { <circle cx=num(cx), cy=num(cy), r="2", fill=&props.color, /> }.into(),
+            None => VNode::from(VText::new("")),
+        };
+        html! {
+            <svg class="sparkline", viewBox=format!("0 0 {} {}", num(props.width), num(props.height)),
+                 width=num(props.width), height=num(props.height), role="img", aria-label=&props.title,>
+                <path d=line_path(&points), fill="none", stroke=&props.color, stroke-width="1.5",
+                      stroke-linejoin="round", stroke-linecap="round", />
+                { dot }
+            </svg>
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! This module contains a controlled checkbox component.
+
+use callback::Callback;
+use component::{Component, ShouldRender};
+use html::{AppSender, Html, ChangeData};
+
+/// Properties of the `Checkbox` component.
+#[derive(Default, Clone, PartialEq)]
+pub struct CheckboxProps {
+    /// The state of the checkbox.
+    pub checked: bool,
+    /// Disables the checkbox.
+    pub disabled: bool,
+    /// Called with a new state when a user toggles the checkbox.
+    pub onchange: Callback<bool>,
+}
+
+/// A message of the `Checkbox` component.
+pub enum Msg {
+    /// A user toggled the checkbox.
+    Toggled(bool),
+}
+
+/// A controlled checkbox. It sets `checked` property of the element
+/// instead of the attribute which controls the default state only.
+pub struct Checkbox {
+    props: CheckboxProps,
+}
+
+impl Component for Checkbox {
+    type Msg = Msg;
+    type Properties = CheckboxProps;
+
+    fn create(props: Self::Properties, _: AppSender<Msg>) -> Self {
+        Checkbox { props }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Toggled(checked) => {
+                self.props.onchange.emit(checked);
+            }
+        }
+        // Render the state which the parent has to make the element consistent
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        html! {
+            <input type="checkbox",
+                   checked=self.props.checked,
+                   disabled=self.props.disabled,
+                   onchange=|e: ChangeData| Msg::Toggled(e.checked), />
+        }
+    }
+}
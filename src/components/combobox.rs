@@ -0,0 +1,256 @@
+//! This module contains an accessible autocomplete input which
+//! suggests options while a user types.
+
+use std::time::Duration;
+use callback::Callback;
+use component::{Component, ShouldRender};
+use html::{AppSender, Html, InputData, KeyData};
+use services::rate_limit::RateLimitService;
+use virtual_dom::VNode;
+
+/// Properties of the `Combobox` component.
+#[derive(Clone, PartialEq)]
+pub struct ComboboxProps<T> {
+    /// A prefix of ids of the listbox and its options, unique on the page.
+    pub id: String,
+    /// Suggestions for the current query, labels are made with `ToString`.
+    pub suggestions: Vec<T>,
+    /// Suggestions for the query are loading.
+    pub loading: bool,
+    /// A placeholder of the empty input.
+    pub placeholder: String,
+    /// A label of the input for screen readers.
+    pub label: String,
+    /// A pause of typing after which a query is sent.
+    pub delay: Duration,
+    /// Queries shorter than this aren't sent.
+    pub min_length: usize,
+    /// Called with a query when typing pauses, to load suggestions.
+    pub onquery: Callback<String>,
+    /// Called with a suggestion which a user picked.
+    pub onselect: Callback<T>,
+}
+
+impl<T> Default for ComboboxProps<T> {
+    fn default() -> Self {
+        ComboboxProps {
+            id: "combobox".into(),
+            suggestions: Vec::new(),
+            loading: false,
+            placeholder: String::new(),
+            label: String::new(),
+            delay: Duration::from_millis(300),
+            min_length: 1,
+            onquery: Callback::default(),
+            onselect: Callback::default(),
+        }
+    }
+}
+
+/// A message of the `Combobox` component.
+pub enum Msg {
+    /// A user typed.
+    Input(String),
+    /// Typing paused.
+    Search(String),
+    /// A key was pressed in the input.
+    Key(String),
+    /// A user picked the suggestion with the index.
+    Pick(usize),
+    /// The input lost focus.
+    Blur,
+}
+
+/// Splits the text into parts which match the query and parts which don't,
+/// ignoring case, to highlight matches in suggestions: `(part, matched)`.
+pub fn highlight(text: &str, query: &str) -> Vec<(String, bool)> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut parts = Vec::new();
+    let (mut start, mut index) = (0, 0);
+    while !query.is_empty() && index < chars.len() {
+        let matched = chars.len() - index >= query.len()
+            && chars[index..index + query.len()].iter().zip(&query)
+                .all(|(&(_, c), q)| c.to_lowercase().eq(Some(*q)));
+        if !matched {
+            index += 1;
+            continue;
+        }
+        let from = chars[index].0;
+        let to = chars.get(index + query.len()).map_or(text.len(), |&(offset, _)| offset);
+        if from > start {
+            parts.push((text[start..from].to_owned(), false));
+        }
+        parts.push((text[from..to].to_owned(), true));
+        start = to;
+        index += query.len();
+    }
+    if start < text.len() {
+        parts.push((text[start..].to_owned(), false));
+    }
+    parts
+}
+
+/// An input with a list of suggestions which follows the ARIA combobox pattern.
+/// It asks a parent for suggestions with a debounced `onquery`, moves
+/// between suggestions with arrows, picks one with Enter or a click and
+/// closes the list with Escape. Matches of the query are wrapped with `<mark>`.
+///
+/// ```rust,ignore
+/// html! {
+///     <Combobox<City>: id="city", suggestions=self.cities.clone(), loading=self.loading,
+///                      onquery=Msg::Search, onselect=Msg::Picked, />
+/// }
+/// ```
+pub struct Combobox<T: 'static> {
+    props: ComboboxProps<T>,
+    query: String,
+    open: bool,
+    active: Option<usize>,
+    limits: RateLimitService<Msg>,
+    search: Callback<String>,
+}
+
+impl<T> Combobox<T>
+where
+    T: ToString + Clone + PartialEq + 'static,
+{
+    fn option_id(&self, index: usize) -> String {
+        format!("{}-option-{}", self.props.id, index)
+    }
+
+    fn pick(&mut self, index: usize) {
+        if let Some(suggestion) = self.props.suggestions.get(index).cloned() {
+            self.query = suggestion.to_string();
+            self.open = false;
+            self.active = None;
+            self.props.onselect.emit(suggestion);
+        }
+    }
+
+    fn view_option(&self, index: usize, suggestion: &T) -> Html<Msg> {
+        let selected = self.active == Some(index);
+        let class = if selected { "combobox-option active" } else { "combobox-option" };
+        let label = suggestion.to_string();
+        html! {
+            <li id=self.option_id(index), class=class, role="option",
+                aria-selected=if selected { "true" } else { "false" },
+                onmousedown=move |_| Msg::Pick(index),>
+                { for highlight(&label, &self.query).into_iter().map(|(part, matched)| -> VNode<Msg> {
+                    if matched {
+                        html! { <mark>{ part }</mark> }.into()
+                    } else {
+                        part.into()
+                    }
+                }) }
+            </li>
+        }
+    }
+}
+
+impl<T> Component for Combobox<T>
+where
+    T: ToString + Clone + PartialEq + 'static,
+{
+    type Msg = Msg;
+    type Properties = ComboboxProps<T>;
+
+    fn create(props: Self::Properties, sender: AppSender<Msg>) -> Self {
+        let mut limits = RateLimitService::new(sender);
+        let search = limits.debounce(props.delay, Msg::Search);
+        Combobox {
+            props,
+            query: String::new(),
+            open: false,
+            active: None,
+            limits,
+            search,
+        }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Input(query) => {
+                self.open = query.chars().count() >= self.props.min_length;
+                self.active = None;
+                self.query = query.clone();
+                self.search.emit(query);
+            }
+            Msg::Search(query) => {
+                // A query which was typed over isn't sent
+                if query == self.query && query.chars().count() >= self.props.min_length {
+                    self.props.onquery.emit(query);
+                }
+                return false;
+            }
+            Msg::Key(key) => {
+                let count = self.props.suggestions.len();
+                match key.as_str() {
+                    "ArrowDown" if count > 0 => {
+                        self.active = Some(self.active.map_or(0, |index| (index + 1) % count));
+                        self.open = true;
+                    }
+                    "ArrowUp" if count > 0 => {
+                        self.active = Some(self.active.map_or(count - 1, |index| (index + count - 1) % count));
+                        self.open = true;
+                    }
+                    "Enter" if self.open => match self.active {
+                        Some(index) => self.pick(index),
+                        None => return false,
+                    },
+                    "Escape" if self.open => {
+                        self.open = false;
+                        self.active = None;
+                    }
+                    // The second Escape clears the input like native search inputs
+                    "Escape" => self.query.clear(),
+                    _ => return false,
+                }
+            }
+            Msg::Pick(index) => self.pick(index),
+            Msg::Blur => {
+                self.open = false;
+                self.active = None;
+            }
+        }
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        if self.props.delay != props.delay {
+            self.search = self.limits.debounce(props.delay, Msg::Search);
+        }
+        if self.props.suggestions != props.suggestions {
+            self.active = None;
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let listbox = format!("{}-listbox", self.props.id);
+        let expanded = self.open && (!self.props.suggestions.is_empty() || self.props.loading);
+        let active = self.active.map(|index| self.option_id(index));
+        html! {
+            <div class="combobox",>
+                <input type="text", role="combobox", value=&self.query,
+                       placeholder=&self.props.placeholder,
+                       aria-label=&self.props.label,
+                       aria-autocomplete="list",
+                       aria-controls=&listbox,
+                       aria-expanded=if expanded { "true" } else { "false" },
+                       aria-activedescendant=active,
+                       aria-busy=if self.props.loading { "true" } else { "false" },
+                       oninput=|e: InputData| Msg::Input(e.value),
+                       onkeydown=|e: KeyData| Msg::Key(e.key),
+                       onblur=|_| Msg::Blur, />
+                <ul id=listbox, class="combobox-listbox", role="listbox", hidden=!expanded,>
+                    { for self.props.suggestions.iter().enumerate().map(|(index, suggestion)| self.view_option(index, suggestion)) }
+                </ul>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! This module contains a menu which opens at the pointer on a right click.
+
+use stdweb::Value;
+use stdweb::unstable::TryInto;
+use callback::Callback;
+use component::{Component, ShouldRender, Render};
+use focus;
+use host::{self, HostAttributes};
+use html::{AppSender, Html};
+use position::{self, Align, Layout, Placement, Position, PositionOptions, Rect, Side};
+use services::layout::{LayoutEvent, LayoutHandle, LayoutService};
+use virtual_dom::{NodeRef, VNode, VPortal, VText};
+use super::menu::{self, Edge, MenuItem};
+
+/// Properties of the `ContextMenu` component.
+#[derive(Clone, PartialEq)]
+pub struct ContextMenuProps<T> {
+    /// A prefix of ids of the target and the menu, unique on the page.
+    /// The target gets the `{id}-target` id and the menu gets `{id}-menu`.
+    pub id: String,
+    /// Items of the menu.
+    pub items: Vec<MenuItem<T>>,
+    /// A label of the menu for screen readers.
+    pub label: String,
+    /// Renders the element which is right clicked with attributes and
+    /// listeners which it spreads onto itself.
+    pub target: Render<HostAttributes>,
+    /// Called with the value of an item which a user selected.
+    pub onselect: Callback<T>,
+}
+
+impl<T> Default for ContextMenuProps<T> {
+    fn default() -> Self {
+        ContextMenuProps {
+            id: "context-menu".into(),
+            items: Vec::new(),
+            label: String::new(),
+            target: Render::default(),
+            onselect: Callback::default(),
+        }
+    }
+}
+
+/// A message of the `ContextMenu` component.
+pub enum Msg<T> {
+    /// The target was right clicked at the point relative to the viewport.
+    Open(f64, f64),
+    /// A user selected an item.
+    Select(T),
+    /// A user closed the menu.
+    Close,
+    /// The menu was measured or a pointer was pressed outside of it.
+    Layout(LayoutEvent),
+}
+
+/// Replaces the menu of the browser on the target with a menu at the pointer.
+/// The menu flips above the pointer and shifts to the left near edges of
+/// the viewport. The `ContextMenu` key and `Shift+F10` open it too, because
+/// browsers send `contextmenu` events for them.
+///
+/// ```rust,ignore
+/// html! {
+///     <ContextMenu<FileAction>: id="file", label="File",
+///                               items=vec![MenuItem::action("Rename", FileAction::Rename)],
+///                               target=|host: HostAttributes| html! { <div tabindex="0", ..&host,>{ &file.name }</div> },
+///                               onselect=|action| Msg::File(action), />
+/// }
+/// ```
+pub struct ContextMenu<T: 'static> {
+    props: ContextMenuProps<T>,
+    sender: AppSender<Msg<T>>,
+    layout: LayoutService<Msg<T>>,
+    tracker: Option<LayoutHandle>,
+    floating: NodeRef,
+    position: Option<Position>,
+    point: Option<(f64, f64)>,
+    // The last layout, which is used again when the open menu moves
+    measured: Option<Layout>,
+}
+
+/// Prevents the menu of the browser and returns the point of the event.
+fn open_at(event: Value) -> (f64, f64) {
+    let point: Vec<f64> = js! {
+        var event = @{event};
+        event.preventDefault();
+        return [event.clientX, event.clientY];
+    }.try_into().unwrap_or_default();
+    match point[..] {
+        [x, y] => (x, y),
+        _ => (0.0, 0.0),
+    }
+}
+
+impl<T> ContextMenu<T>
+where
+    T: Clone + PartialEq + 'static,
+{
+    fn target_id(&self) -> String {
+        format!("{}-target", self.props.id)
+    }
+
+    fn place(&mut self) {
+        self.position = match (self.point, self.measured) {
+            (Some((x, y)), Some(mut layout)) => {
+                layout.anchor = Rect::new(x, y, 0.0, 0.0);
+                let options = PositionOptions {
+                    offset: 0.0,
+                    ..PositionOptions::new(Placement::new(Side::Bottom, Align::Start))
+                };
+                Some(position::compute(&layout, &options))
+            }
+            _ => None,
+        };
+    }
+
+    fn close(&mut self) -> ShouldRender {
+        self.tracker = None;
+        self.measured = None;
+        self.position = None;
+        self.point.take().is_some()
+    }
+}
+
+impl<T> Component for ContextMenu<T>
+where
+    T: Clone + PartialEq + 'static,
+{
+    type Msg = Msg<T>;
+    type Properties = ContextMenuProps<T>;
+
+    fn create(props: Self::Properties, sender: AppSender<Msg<T>>) -> Self {
+        ContextMenu {
+            props,
+            layout: LayoutService::new(sender.clone()),
+            sender,
+            tracker: None,
+            floating: NodeRef::default(),
+            position: None,
+            point: None,
+            measured: None,
+        }
+    }
+
+    fn update(&mut self, msg: Msg<T>) -> ShouldRender {
+        match msg {
+            Msg::Open(x, y) => {
+                // Another right click moves the open menu
+                if self.tracker.is_none() {
+                    let target = self.target_id();
+                    self.tracker = Some(self.layout.track(&target, &self.floating, Msg::Layout));
+                }
+                self.point = Some((x, y));
+                self.place();
+                true
+            }
+            Msg::Select(value) => {
+                self.props.onselect.emit(value);
+                false
+            }
+            Msg::Close => {
+                focus::focus_id(&self.target_id());
+                self.close()
+            }
+            Msg::Layout(LayoutEvent::Measured(layout)) => {
+                self.measured = Some(layout);
+                self.place();
+                true
+            }
+            Msg::Layout(LayoutEvent::Outside) => self.close(),
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<Msg<T>> {
+        let style = match self.position {
+            Some(ref position) => position.style(),
+            None => position::UNMEASURED_STYLE.to_owned(),
+        };
+        let menu: VNode<Msg<T>> = match self.point {
+            Some(_) => menu::popup(format!("{}-menu", self.props.id), self.props.items.clone(),
+                                   self.props.label.clone(), Edge::First, Msg::Select, |_| Msg::Close),
+            None => VNode::from(VText::new("")),
+        };
+        let mut portal = VPortal::body();
+        portal.add_child(html! {
+            <div class="menu-popup context-menu", ref=&self.floating, style=style, hidden=self.point.is_none(),>
+                { menu }
+            </div>
+        }.into());
+        let target = HostAttributes::new()
+            .attr("id", self.target_id())
+            .listen("contextmenu", host::forward(&self.sender, |event| {
+                let (x, y) = open_at(event);
+                Msg::Open(x, y)
+            }));
+        html! {
+            <span class="context-menu-target",>
+                { self.props.target.view(target) }
+                { portal }
+            </span>
+        }
+    }
+}
@@ -0,0 +1,599 @@
+//! This module contains a table component with typed columns,
+//! sorting, selection and pages.
+
+use std::cmp::Ordering;
+use std::rc::Rc;
+use callback::Callback;
+use component::{Component, ShouldRender, Render};
+use html::{AppSender, Html};
+use router::{decode, encode};
+use virtual_dom::{VNode, VText};
+
+/// A direction of sorting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// From the smallest value.
+    Ascending,
+    /// From the largest value.
+    Descending,
+}
+
+/// Sorting of a table by a column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sort {
+    /// The key of the column.
+    pub column: String,
+    /// The direction of sorting.
+    pub direction: Direction,
+}
+
+impl Sort {
+    /// Sorts by the column from the smallest value.
+    pub fn ascending(column: &str) -> Self {
+        Sort { column: column.to_owned(), direction: Direction::Ascending }
+    }
+
+    /// Sorts by the column from the largest value.
+    pub fn descending(column: &str) -> Self {
+        Sort { column: column.to_owned(), direction: Direction::Descending }
+    }
+
+    /// Returns sorting after a click on the header of the column: ascending,
+    /// then descending, then unsorted.
+    pub fn next(current: Option<&Sort>, column: &str) -> Option<Sort> {
+        match current {
+            Some(sort) if sort.column == column => match sort.direction {
+                Direction::Ascending => Some(Sort::descending(column)),
+                Direction::Descending => None,
+            },
+            _ => Some(Sort::ascending(column)),
+        }
+    }
+}
+
+type Text<T> = Fn(&T) -> String;
+type Compare<T> = Fn(&T, &T) -> Ordering;
+
+/// A column of a table of `T` rows.
+///
+/// ```rust,ignore
+/// Column::new("name", "Name").text(|user: &User| user.name.clone()).sort_by_key(|user| user.name.clone())
+/// Column::new("actions", "").render(link.render(|user: User| html! { <button onclick=move |_| Msg::Edit(user.id),>{ "Edit" }</button> }))
+/// ```
+pub struct Column<T> {
+    key: String,
+    header: String,
+    class: String,
+    text: Option<Rc<Text<T>>>,
+    render: Option<Render<T>>,
+    compare: Option<Rc<Compare<T>>>,
+    sortable: bool,
+}
+
+impl<T> Column<T> {
+    /// Creates a column with a unique key and a header.
+    pub fn new(key: &str, header: &str) -> Self {
+        Column {
+            key: key.to_owned(),
+            header: header.to_owned(),
+            class: String::new(),
+            text: None,
+            render: None,
+            compare: None,
+            sortable: false,
+        }
+    }
+
+    /// Renders cells as text of rows.
+    pub fn text<F>(mut self, text: F) -> Self
+    where
+        F: Fn(&T) -> String + 'static,
+    {
+        self.text = Some(Rc::new(text));
+        self
+    }
+
+    /// Renders cells by a parent, like with buttons or links. Rows are cloned for it.
+    pub fn render(mut self, render: Render<T>) -> Self {
+        self.render = Some(render);
+        self
+    }
+
+    /// Sets a class of cells of the column.
+    pub fn class(mut self, class: &str) -> Self {
+        self.class = class.to_owned();
+        self
+    }
+
+    /// Sorts rows by the key in a browser.
+    pub fn sort_by_key<K, F>(self, key: F) -> Self
+    where
+        K: Ord,
+        F: Fn(&T) -> K + 'static,
+    {
+        self.sort_by(move |a, b| key(a).cmp(&key(b)))
+    }
+
+    /// Sorts rows with the comparison in a browser.
+    pub fn sort_by<F>(mut self, compare: F) -> Self
+    where
+        F: Fn(&T, &T) -> Ordering + 'static,
+    {
+        self.compare = Some(Rc::new(compare));
+        self.sortable = true;
+        self
+    }
+
+    /// Lets users sort by the column which a server sorts.
+    pub fn sortable(mut self) -> Self {
+        self.sortable = true;
+        self
+    }
+
+    /// Returns the key of the column.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+impl<T> Clone for Column<T> {
+    fn clone(&self) -> Self {
+        Column {
+            key: self.key.clone(),
+            header: self.header.clone(),
+            class: self.class.clone(),
+            text: self.text.clone(),
+            render: self.render.clone(),
+            compare: self.compare.clone(),
+            sortable: self.sortable,
+        }
+    }
+}
+
+impl<T> PartialEq for Column<T> {
+    fn eq(&self, other: &Column<T>) -> bool {
+        fn same<F: ?Sized>(a: &Option<Rc<F>>, b: &Option<Rc<F>>) -> bool {
+            match (a, b) {
+                (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+        }
+        self.key == other.key && self.header == other.header && self.class == other.class
+            && self.render == other.render && self.sortable == other.sortable
+            && same(&self.text, &other.text) && same(&self.compare, &other.compare)
+    }
+}
+
+/// Rows which can be selected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    /// Rows aren't selected.
+    None,
+    /// A click on a row selects it.
+    Single,
+    /// Checkboxes of rows and the header select rows.
+    Multiple,
+}
+
+/// A page of a table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pagination {
+    /// The index of the page from 0.
+    pub page: usize,
+    /// The count of rows of a page.
+    pub per_page: usize,
+    /// The count of rows of all pages.
+    pub total: usize,
+}
+
+impl Pagination {
+    /// Returns the count of pages, at least one.
+    pub fn pages(&self) -> usize {
+        if self.per_page == 0 {
+            return 1;
+        }
+        let pages = self.total / self.per_page + if self.total % self.per_page == 0 { 0 } else { 1 };
+        pages.max(1)
+    }
+}
+
+/// Sorting and a page which a server should return, for tables with
+/// `server=true`. It's written to and read from a query:
+///
+/// ```rust,ignore
+/// Msg::Sort(sort) => self.query.sort(sort),
+/// Msg::Page(page) => self.query.page = page,
+/// // then
+/// let request = Request::get(router::with_query("/users", &self.query.to_query())).body(Nothing).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableQuery {
+    /// Sorting of rows.
+    pub sort: Option<Sort>,
+    /// The index of the page from 0.
+    pub page: usize,
+    /// The count of rows of a page.
+    pub per_page: usize,
+}
+
+impl TableQuery {
+    /// Creates a query of the first unsorted page.
+    pub fn new(per_page: usize) -> Self {
+        TableQuery { sort: None, page: 0, per_page }
+    }
+
+    /// Changes sorting and returns to the first page.
+    pub fn sort(&mut self, sort: Option<Sort>) {
+        self.sort = sort;
+        self.page = 0;
+    }
+
+    /// Returns the pagination property for the total count of rows.
+    pub fn pagination(&self, total: usize) -> Pagination {
+        Pagination { page: self.page, per_page: self.per_page, total }
+    }
+
+    /// Converts it to a query (without `?`) like `sort=name&order=desc&page=2&per_page=20`.
+    /// Pages of the query are counted from 1.
+    pub fn to_query(&self) -> String {
+        let mut pairs = Vec::new();
+        if let Some(ref sort) = self.sort {
+            let order = match sort.direction {
+                Direction::Ascending => "asc",
+                Direction::Descending => "desc",
+            };
+            pairs.push(format!("sort={}&order={}", encode(&sort.column), order));
+        }
+        pairs.push(format!("page={}&per_page={}", self.page + 1, self.per_page));
+        pairs.join("&")
+    }
+
+    /// Reads a query of `to_query`. Missing or invalid parameters keep values of `self`.
+    pub fn parse(mut self, query: &str) -> Self {
+        let mut order = Direction::Ascending;
+        let mut column = None;
+        for pair in query.trim_start_matches('?').split('&') {
+            let mut parts = pair.splitn(2, '=');
+            let key = parts.next().unwrap_or_default();
+            let value = match parts.next().and_then(decode) {
+                Some(value) => value,
+                None => continue,
+            };
+            match key {
+                "sort" if !value.is_empty() => column = Some(value),
+                "order" if value == "desc" => order = Direction::Descending,
+                "page" => {
+                    if let Some(page) = value.parse::<usize>().ok().filter(|page| *page > 0) {
+                        self.page = page - 1;
+                    }
+                }
+                "per_page" => {
+                    if let Some(per_page) = value.parse().ok().filter(|per_page| *per_page > 0) {
+                        self.per_page = per_page;
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let Some(column) = column {
+            self.sort = Some(Sort { column, direction: order });
+        }
+        self
+    }
+}
+
+/// Properties of the `DataTable` component.
+#[derive(Clone, PartialEq)]
+pub struct DataTableProps<T> {
+    /// Rows of the table, or rows of the page if a server sorts them.
+    pub rows: Vec<T>,
+    /// Columns of the table.
+    pub columns: Vec<Column<T>>,
+    /// Initial sorting, or sorting of rows which a server returned.
+    pub sort: Option<Sort>,
+    /// Rows are sorted and paged by a server, so the table only calls
+    /// `onsort` and `onpage`.
+    pub server: bool,
+    /// Rows which could be selected.
+    pub selection: Selection,
+    /// Indexes of selected rows in `rows`.
+    pub selected: Vec<usize>,
+    /// Keeps the header at the top of a scrolled container.
+    pub sticky: bool,
+    /// The page of rows to show with buttons to change it.
+    pub pagination: Option<Pagination>,
+    /// Rendered instead of rows if there are none.
+    pub empty: Render<()>,
+    /// Called when a user changed sorting.
+    pub onsort: Callback<Option<Sort>>,
+    /// Called with indexes of rows which a user selected.
+    pub onselect: Callback<Vec<usize>>,
+    /// Called with the index of the page a user opened.
+    pub onpage: Callback<usize>,
+}
+
+impl<T> Default for DataTableProps<T> {
+    fn default() -> Self {
+        DataTableProps {
+            rows: Vec::new(),
+            columns: Vec::new(),
+            sort: None,
+            server: false,
+            selection: Selection::None,
+            selected: Vec::new(),
+            sticky: false,
+            pagination: None,
+            empty: Render::default(),
+            onsort: Callback::default(),
+            onselect: Callback::default(),
+            onpage: Callback::default(),
+        }
+    }
+}
+
+/// A message of the `DataTable` component.
+pub enum Msg {
+    /// A user clicked the header of the column with the index.
+    Sort(usize),
+    /// A user clicked the row with the index in `rows`.
+    Select(usize),
+    /// A user toggled the checkbox of the row with the index in `rows`.
+    Toggle(usize),
+    /// A user toggled the checkbox of the header.
+    ToggleAll,
+    /// A user opened the page.
+    Page(usize),
+}
+
+/// A table of `T` rows with columns which render cells. Clicks on headers
+/// of sortable columns sort rows in a browser, or ask a server for sorted
+/// rows with `server=true` and `TableQuery`. Selection and pages are
+/// controlled by a parent like in `Checkbox`.
+///
+/// ```rust,ignore
+/// let (mut props, link) = VComp::lazy::<DataTable<User>>();
+/// props.rows = model.users.clone();
+/// props.columns = vec![
+///     Column::new("name", "Name").text(|user: &User| user.name.clone()).sort_by_key(|user| user.name.clone()),
+///     Column::new("age", "Age").text(|user: &User| user.age.to_string()).sort_by_key(|user| user.age),
+/// ];
+/// props.selection = Selection::Multiple;
+/// props.selected = model.selected.clone();
+/// props.onselect = link.callback(Msg::Selected);
+/// let table = VComp::new::<DataTable<User>>(props, link);
+/// ```
+pub struct DataTable<T: 'static> {
+    props: DataTableProps<T>,
+    sort: Option<Sort>,
+    // Indexes of rows in order of sorting
+    order: Vec<usize>,
+}
+
+impl<T> DataTable<T>
+where
+    T: Clone + PartialEq + 'static,
+{
+    fn arrange(&mut self) {
+        let mut order: Vec<usize> = (0..self.props.rows.len()).collect();
+        if !self.props.server {
+            let compare = self.sort.as_ref().and_then(|sort| {
+                let column = self.props.columns.iter().find(|column| column.key == sort.column)?;
+                column.compare.clone().map(|compare| (compare, sort.direction))
+            });
+            if let Some((compare, direction)) = compare {
+                let rows = &self.props.rows;
+                order.sort_by(|&a, &b| {
+                    let ordering = compare(&rows[a], &rows[b]);
+                    match direction {
+                        Direction::Ascending => ordering,
+                        Direction::Descending => ordering.reverse(),
+                    }
+                });
+            }
+            if let Some(ref pagination) = self.props.pagination {
+                let start = pagination.page.saturating_mul(pagination.per_page).min(order.len());
+                let end = start.saturating_add(pagination.per_page).min(order.len());
+                order = order[start..end].to_vec();
+            }
+        }
+        self.order = order;
+    }
+
+    fn is_selected(&self, index: usize) -> bool {
+        self.props.selected.contains(&index)
+    }
+
+    fn view_header(&self, index: usize, column: &Column<T>) -> Html<Msg> {
+        let style = if self.props.sticky { Some("position: sticky; top: 0;") } else { None };
+        if !column.sortable {
+            return html! { <th scope="col", class=&column.class, style=style,>{ &column.header }</th> };
+        }
+        let direction = self.sort.as_ref()
+            .filter(|sort| sort.column == column.key)
+            .map(|sort| sort.direction);
+        let (aria, class) = match direction {
+            Some(Direction::Ascending) => ("ascending", "data-table-sort ascending"),
+            Some(Direction::Descending) => ("descending", "data-table-sort descending"),
+            None => ("none", "data-table-sort"),
+        };
+        html! {
+            <th scope="col", class=&column.class, style=style, aria-sort=aria,>
+                <button type="button", class=class, onclick=move |_| Msg::Sort(index),>{ &column.header }</button>
+            </th>
+        }
+    }
+
+    fn view_row(&self, index: usize) -> Html<Msg> {
+        let row = &self.props.rows[index];
+        let selected = self.is_selected(index);
+        let class = if selected { "data-table-row selected" } else { "data-table-row" };
+        let (aria, check): (Option<&str>, VNode<Msg>) = match self.props.selection {
+            Selection::None => (None, VNode::from(VText::new(""))),
+            Selection::Single => (Some(if selected { "true" } else { "false" }), VNode::from(VText::new(""))),
+            Selection::Multiple => {
+                let check = html! {
+                    <td class="data-table-check",>
+                        <input type="checkbox", aria-label="Select row", checked=selected,
+                               onclick=move |_| Msg::Toggle(index), />
+                    </td>
+                };
+                (Some(if selected { "true" } else { "false" }), check.into())
+            }
+        };
+        html! {
+            <tr class=class, aria-selected=aria, onclick=move |_| Msg::Select(index),>
+                { check }
+                { for self.props.columns.iter().map(|column| html! {
+                    <td class=&column.class,>{ self.view_cell(column, row) }</td>
+                }) }
+            </tr>
+        }
+    }
+
+    fn view_cell(&self, column: &Column<T>, row: &T) -> VNode<Msg> {
+        if let Some(ref render) = column.render {
+            render.view(row.clone())
+        } else if let Some(ref text) = column.text {
+            text(row).into()
+        } else {
+            VNode::from(VText::new(""))
+        }
+    }
+
+    fn view_pages(&self) -> VNode<Msg> {
+        let pagination = match self.props.pagination {
+            Some(ref pagination) => pagination,
+            None => return VNode::from(VText::new("")),
+        };
+        let page = pagination.page;
+        let pages = pagination.pages();
+        html! {
+            <nav class="data-table-pages", aria-label="Pages",>
+                <button type="button", class="data-table-previous", disabled=page == 0, onclick=move |_| Msg::Page(page - 1),>{ "Previous" }</button>
+                <span class="data-table-page",>{ format!("{} / {}", page + 1, pages) }</span>
+                <button type="button", class="data-table-next", disabled=page + 1 >= pages, onclick=move |_| Msg::Page(page + 1),>{ "Next" }</button>
+            </nav>
+        }.into()
+    }
+}
+
+impl<T> Component for DataTable<T>
+where
+    T: Clone + PartialEq + 'static,
+{
+    type Msg = Msg;
+    type Properties = DataTableProps<T>;
+
+    fn create(props: Self::Properties, _: AppSender<Msg>) -> Self {
+        let mut table = DataTable {
+            sort: props.sort.clone(),
+            props,
+            order: Vec::new(),
+        };
+        table.arrange();
+        table
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Sort(index) => {
+                let key = match self.props.columns.get(index) {
+                    Some(column) => column.key.clone(),
+                    None => return false,
+                };
+                self.sort = Sort::next(self.sort.as_ref(), &key);
+                self.arrange();
+                self.props.onsort.emit(self.sort.clone());
+            }
+            Msg::Select(index) => {
+                if self.props.selection == Selection::Single && !self.is_selected(index) {
+                    self.props.onselect.emit(vec![index]);
+                }
+                return false;
+            }
+            Msg::Toggle(index) => {
+                let mut selected = self.props.selected.clone();
+                if self.is_selected(index) {
+                    selected.retain(|item| *item != index);
+                } else {
+                    selected.push(index);
+                }
+                self.props.onselect.emit(selected);
+            }
+            Msg::ToggleAll => {
+                // Only shown rows are selected, like the rows of the current page
+                let all = self.order.iter().all(|index| self.is_selected(*index));
+                let mut selected: Vec<usize> = self.props.selected.iter().cloned()
+                    .filter(|index| !self.order.contains(index))
+                    .collect();
+                if !all {
+                    selected.extend(self.order.iter().cloned());
+                }
+                self.props.onselect.emit(selected);
+            }
+            Msg::Page(page) => {
+                let pages = self.props.pagination.as_ref().map_or(0, Pagination::pages);
+                if page < pages {
+                    self.props.onpage.emit(page);
+                }
+                return false;
+            }
+        }
+        // Render the selection which the parent has
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        if self.props.sort != props.sort {
+            self.sort = props.sort.clone();
+        }
+        self.props = props;
+        self.arrange();
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let class = if self.props.sticky { "data-table sticky" } else { "data-table" };
+        let all = !self.order.is_empty() && self.order.iter().all(|index| self.is_selected(*index));
+        let check: VNode<Msg> = if self.props.selection == Selection::Multiple {
+            let style = if self.props.sticky { Some("position: sticky; top: 0;") } else { None };
+            html! {
+                <th scope="col", class="data-table-check", style=style,>
+                    <input type="checkbox", aria-label="Select all rows", checked=all,
+                           onclick=|_| Msg::ToggleAll, />
+                </th>
+            }.into()
+        } else {
+            VNode::from(VText::new(""))
+        };
+        let empty: VNode<Msg> = if self.order.is_empty() {
+            let span = self.props.columns.len() + if self.props.selection == Selection::Multiple { 1 } else { 0 };
+            html! {
+                <tr class="data-table-empty",><td colspan=span,>{ self.props.empty.view(()) }</td></tr>
+            }.into()
+        } else {
+            VNode::from(VText::new(""))
+        };
+        html! {
+            <div class="data-table-container",>
+                <table class=class,>
+                    <thead>
+                        <tr>
+                            { check }
+                            { for self.props.columns.iter().enumerate().map(|(index, column)| self.view_header(index, column)) }
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { empty }
+                        { for self.order.iter().map(|index| self.view_row(*index)) }
+                    </tbody>
+                </table>
+                { self.view_pages() }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,372 @@
+//! This module contains buttons which open a calendar to pick a date
+//! or a range of dates.
+
+use callback::Callback;
+use component::{Component, ShouldRender};
+use date::{Date, DateRange};
+use html::{AppSender, Html, KeyData};
+use position::{self, Align, Layout, Placement, Position, PositionOptions, Side};
+use services::intl::{DateStyle, IntlService};
+use services::layout::{LayoutEvent, LayoutHandle, LayoutService};
+use virtual_dom::{NodeRef, VComp, VNode, VPortal, VText};
+use super::calendar::Calendar;
+
+/// Creates a calendar which takes focus, with the `{id}-calendar` id.
+fn calendar<MSG: 'static>(id: &str, selected: Option<DateRange>, min: Option<Date>, max: Option<Date>,
+                          range: bool, onselect: fn(DateRange) -> MSG) -> VNode<MSG> {
+    let (mut props, link) = VComp::lazy::<Calendar>();
+    props.id = format!("{}-calendar", id);
+    props.selected = selected;
+    props.min = min;
+    props.max = max;
+    props.range = range;
+    props.autofocus = true;
+    props.onselect = link.callback(onselect);
+    VComp::new::<Calendar>(props, link).into()
+}
+
+/// A calendar in a dialog which is rendered into the body under a button.
+struct Dropdown<MSG: 'static> {
+    layout: LayoutService<MSG>,
+    tracker: Option<LayoutHandle>,
+    floating: NodeRef,
+    button: NodeRef,
+    position: Option<Position>,
+    open: bool,
+}
+
+impl<MSG: 'static> Dropdown<MSG> {
+    fn new(sender: AppSender<MSG>) -> Self {
+        Dropdown {
+            layout: LayoutService::new(sender),
+            tracker: None,
+            floating: NodeRef::default(),
+            button: NodeRef::default(),
+            position: None,
+            open: false,
+        }
+    }
+
+    fn open<F>(&mut self, id: &str, converter: F) -> ShouldRender
+    where
+        F: Fn(LayoutEvent) -> MSG + 'static,
+    {
+        if self.open {
+            return false;
+        }
+        self.open = true;
+        self.tracker = Some(self.layout.track(&format!("{}-button", id), &self.floating, converter));
+        true
+    }
+
+    /// Closes the dialog and returns focus to the button if it was closed
+    /// with keys or a day was picked.
+    fn close(&mut self, refocus: bool) -> ShouldRender {
+        if refocus {
+            self.button.focus();
+        }
+        self.tracker = None;
+        self.position = None;
+        let open = self.open;
+        self.open = false;
+        open
+    }
+
+    fn measured(&mut self, layout: &Layout, options: &PositionOptions) -> ShouldRender {
+        self.position = Some(position::compute(layout, options));
+        true
+    }
+
+    fn view_button(&self, id: &str, label: &str, text: String, disabled: bool,
+                   onclick: fn(()) -> MSG) -> Html<MSG> {
+        let aria_label = if label.is_empty() { text.clone() } else { format!("{}, {}", label, text) };
+        html! {
+            <button type="button", class="date-picker-button", id=format!("{}-button", id), ref=&self.button,
+                    aria-haspopup="dialog", aria-expanded=self.open,
+                    aria-controls=format!("{}-dialog", id), aria-label=aria_label,
+                    disabled=disabled, onclick=move |_| onclick(()),>
+                { text }
+            </button>
+        }
+    }
+
+    fn view_dialog<F>(&self, id: &str, label: &str, options: &PositionOptions, calendar: F,
+                      onkeydown: fn(String) -> MSG) -> VNode<MSG>
+    where
+        F: FnOnce() -> VNode<MSG>,
+    {
+        let (style, side) = match self.position {
+            Some(ref position) => (position.style(), position.placement.side),
+            None => (position::UNMEASURED_STYLE.to_owned(), options.placement.side),
+        };
+        let content = if self.open { calendar() } else { VNode::from(VText::new("")) };
+        let label = if label.is_empty() { None } else { Some(label) };
+        let mut portal = VPortal::body();
+        portal.add_child(html! {
+            <div role="dialog", class="date-picker-dialog", id=format!("{}-dialog", id), ref=&self.floating,
+                 style=style, data-side=side.name(), aria-label=label, hidden=!self.open,
+                 onkeydown=move |e: KeyData| onkeydown(e.key),>
+                { content }
+            </div>
+        }.into());
+        portal.into()
+    }
+}
+
+/// Properties of the `DatePicker` component.
+#[derive(Clone, PartialEq)]
+pub struct DatePickerProps {
+    /// A prefix of ids of the button and the dialog, unique on the page.
+    /// The button gets the `{id}-button` id and the dialog gets `{id}-dialog`.
+    pub id: String,
+    /// The picked date.
+    pub value: Option<Date>,
+    /// The earliest date which could be picked.
+    pub min: Option<Date>,
+    /// The latest date which could be picked.
+    pub max: Option<Date>,
+    /// A label of the button and the dialog for screen readers, like `Due date`.
+    pub label: String,
+    /// A text of the button without a date.
+    pub placeholder: String,
+    /// The style of the date on the button.
+    pub style: DateStyle,
+    /// Where the dialog is placed.
+    pub position: PositionOptions,
+    /// Disables the button.
+    pub disabled: bool,
+    /// Called with a date which a user picked.
+    pub onchange: Callback<Date>,
+}
+
+impl Default for DatePickerProps {
+    fn default() -> Self {
+        DatePickerProps {
+            id: "date-picker".into(),
+            value: None,
+            min: None,
+            max: None,
+            label: String::new(),
+            placeholder: "Choose a date".into(),
+            style: DateStyle::Medium,
+            position: PositionOptions::new(Placement::new(Side::Bottom, Align::Start)),
+            disabled: false,
+            onchange: Callback::default(),
+        }
+    }
+}
+
+/// A message of the `DatePicker` component.
+pub enum Msg {
+    /// The button was clicked.
+    Toggle,
+    /// A user picked a date in the calendar.
+    Select(Date),
+    /// A key was pressed in the dialog.
+    Key(String),
+    /// The dialog was measured or a pointer was pressed outside of it.
+    Layout(LayoutEvent),
+}
+
+/// A button with the date formatted for the locale of `IntlService`,
+/// which opens a `Calendar` in a dialog with focus on the picked date.
+/// `Escape` closes the dialog and returns focus to the button.
+///
+/// ```rust,ignore
+/// html! {
+///     <DatePicker: id="due", label="Due date", value=self.due, min=Some(self.today),
+///                  onchange=|date| Msg::Due(date), />
+/// }
+/// ```
+pub struct DatePicker {
+    props: DatePickerProps,
+    intl: IntlService,
+    dropdown: Dropdown<Msg>,
+}
+
+impl Component for DatePicker {
+    type Msg = Msg;
+    type Properties = DatePickerProps;
+
+    fn create(props: Self::Properties, sender: AppSender<Msg>) -> Self {
+        DatePicker {
+            props,
+            intl: IntlService::new(),
+            dropdown: Dropdown::new(sender),
+        }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Toggle => {
+                if self.dropdown.open {
+                    self.dropdown.close(false)
+                } else {
+                    self.dropdown.open(&self.props.id, Msg::Layout)
+                }
+            }
+            Msg::Select(date) => {
+                self.props.onchange.emit(date);
+                self.dropdown.close(true)
+            }
+            Msg::Key(ref key) if key == "Escape" => self.dropdown.close(true),
+            Msg::Key(_) => false,
+            Msg::Layout(LayoutEvent::Measured(layout)) => self.dropdown.measured(&layout, &self.props.position),
+            Msg::Layout(LayoutEvent::Outside) => self.dropdown.close(false),
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let text = match self.props.value {
+            Some(date) => self.intl.calendar_date(date, self.props.style),
+            None => self.props.placeholder.clone(),
+        };
+        let props = &self.props;
+        let calendar = || {
+            calendar(&props.id, props.value.map(DateRange::day), props.min, props.max, false,
+                     |range| Msg::Select(range.start))
+        };
+        html! {
+            <span class="date-picker",>
+                { self.dropdown.view_button(&self.props.id, &self.props.label, text, self.props.disabled, |_| Msg::Toggle) }
+                { self.dropdown.view_dialog(&self.props.id, &self.props.label, &self.props.position, calendar, Msg::Key) }
+            </span>
+        }
+    }
+}
+
+/// Properties of the `DateRangePicker` component.
+#[derive(Clone, PartialEq)]
+pub struct DateRangePickerProps {
+    /// A prefix of ids of the button and the dialog, unique on the page.
+    /// The button gets the `{id}-button` id and the dialog gets `{id}-dialog`.
+    pub id: String,
+    /// The picked range.
+    pub value: Option<DateRange>,
+    /// The earliest date of ranges.
+    pub min: Option<Date>,
+    /// The latest date of ranges.
+    pub max: Option<Date>,
+    /// A label of the button and the dialog for screen readers, like `Stay`.
+    pub label: String,
+    /// A text of the button without a range.
+    pub placeholder: String,
+    /// The style of dates on the button.
+    pub style: DateStyle,
+    /// Where the dialog is placed.
+    pub position: PositionOptions,
+    /// Disables the button.
+    pub disabled: bool,
+    /// Called with a range which a user picked.
+    pub onchange: Callback<DateRange>,
+}
+
+impl Default for DateRangePickerProps {
+    fn default() -> Self {
+        DateRangePickerProps {
+            id: "date-range-picker".into(),
+            value: None,
+            min: None,
+            max: None,
+            label: String::new(),
+            placeholder: "Choose dates".into(),
+            style: DateStyle::Medium,
+            position: PositionOptions::new(Placement::new(Side::Bottom, Align::Start)),
+            disabled: false,
+            onchange: Callback::default(),
+        }
+    }
+}
+
+/// A message of the `DateRangePicker` component.
+pub enum RangeMsg {
+    /// The button was clicked.
+    Toggle,
+    /// A user picked the last date of a range in the calendar.
+    Select(DateRange),
+    /// A key was pressed in the dialog.
+    Key(String),
+    /// The dialog was measured or a pointer was pressed outside of it.
+    Layout(LayoutEvent),
+}
+
+/// A button with a range of dates which opens a `Calendar` where the first
+/// and the last day are picked one after another. Days between them are
+/// highlighted while the pointer moves.
+///
+/// ```rust,ignore
+/// html! {
+///     <DateRangePicker: id="stay", label="Stay", value=self.stay, min=Some(self.today),
+///                       onchange=|range| Msg::Stay(range), />
+/// }
+/// ```
+pub struct DateRangePicker {
+    props: DateRangePickerProps,
+    intl: IntlService,
+    dropdown: Dropdown<RangeMsg>,
+}
+
+impl Component for DateRangePicker {
+    type Msg = RangeMsg;
+    type Properties = DateRangePickerProps;
+
+    fn create(props: Self::Properties, sender: AppSender<RangeMsg>) -> Self {
+        DateRangePicker {
+            props,
+            intl: IntlService::new(),
+            dropdown: Dropdown::new(sender),
+        }
+    }
+
+    fn update(&mut self, msg: RangeMsg) -> ShouldRender {
+        match msg {
+            RangeMsg::Toggle => {
+                if self.dropdown.open {
+                    self.dropdown.close(false)
+                } else {
+                    self.dropdown.open(&self.props.id, RangeMsg::Layout)
+                }
+            }
+            RangeMsg::Select(range) => {
+                self.props.onchange.emit(range);
+                self.dropdown.close(true)
+            }
+            RangeMsg::Key(ref key) if key == "Escape" => self.dropdown.close(true),
+            RangeMsg::Key(_) => false,
+            RangeMsg::Layout(LayoutEvent::Measured(layout)) => self.dropdown.measured(&layout, &self.props.position),
+            RangeMsg::Layout(LayoutEvent::Outside) => self.dropdown.close(false),
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<RangeMsg> {
+        let text = match self.props.value {
+            Some(range) => format!("{} – {}", self.intl.calendar_date(range.start, self.props.style),
+                                   self.intl.calendar_date(range.end, self.props.style)),
+            None => self.props.placeholder.clone(),
+        };
+        let props = &self.props;
+        let calendar = || calendar(&props.id, props.value, props.min, props.max, true, RangeMsg::Select);
+        html! {
+            <span class="date-picker date-range-picker",>
+                { self.dropdown.view_button(&self.props.id, &self.props.label, text, self.props.disabled, |_| RangeMsg::Toggle) }
+                { self.dropdown.view_dialog(&self.props.id, &self.props.label, &self.props.position, calendar, RangeMsg::Key) }
+            </span>
+        }
+    }
+}
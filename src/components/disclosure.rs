@@ -0,0 +1,113 @@
+//! This module contains a headless disclosure which shows and hides
+//! a section with a button.
+
+use callback::Callback;
+use component::{Component, ShouldRender, Render};
+use html::{AppSender, Html};
+use virtual_dom::{VNode, VText};
+
+/// Properties of the `Disclosure` component.
+#[derive(Clone, PartialEq)]
+pub struct DisclosureProps {
+    /// An id of the panel, unique on the page.
+    pub id: String,
+    /// Renders the label of the button with the state of the panel.
+    pub button: Render<bool>,
+    /// Renders the content of the shown panel.
+    pub panel: Render<()>,
+    /// The state of the panel, which makes the disclosure controlled:
+    /// a click only calls `onchange` and a parent changes it.
+    pub open: Option<bool>,
+    /// The state of the panel first if the disclosure is uncontrolled.
+    pub default_open: bool,
+    /// Called with a new state when a user clicked the button.
+    pub onchange: Callback<bool>,
+}
+
+impl Default for DisclosureProps {
+    fn default() -> Self {
+        DisclosureProps {
+            id: "disclosure".into(),
+            button: Render::default(),
+            panel: Render::default(),
+            open: None,
+            default_open: false,
+            onchange: Callback::default(),
+        }
+    }
+}
+
+/// A message of the `Disclosure` component.
+pub enum Msg {
+    /// A user clicked the button.
+    Toggle,
+}
+
+/// A button with `aria-expanded` which shows and hides a panel, like
+/// details of an item. A parent renders the label and the panel.
+///
+/// ```rust,ignore
+/// html! {
+///     <Disclosure: id="shipping",
+///                  button=|open| html! { <span>{ if open { "Hide shipping" } else { "Show shipping" } }</span> },
+///                  panel=|_| view_shipping(), />
+/// }
+/// ```
+pub struct Disclosure {
+    props: DisclosureProps,
+    // The state of an uncontrolled disclosure
+    current: bool,
+}
+
+impl Disclosure {
+    fn is_open(&self) -> bool {
+        self.props.open.unwrap_or(self.current)
+    }
+}
+
+impl Component for Disclosure {
+    type Msg = Msg;
+    type Properties = DisclosureProps;
+
+    fn create(props: Self::Properties, _: AppSender<Msg>) -> Self {
+        let current = props.default_open;
+        Disclosure { props, current }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Toggle => {
+                let open = !self.is_open();
+                self.current = open;
+                self.props.onchange.emit(open);
+            }
+        }
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let open = self.is_open();
+        let panel = if open { self.props.panel.view(()) } else { VNode::from(VText::new("")) };
+        html! {
+            <div class="disclosure",>
+                <button type="button", class="disclosure-button",
+                        aria-expanded=if open { "true" } else { "false" },
+                        aria-controls=&self.props.id,
+                        onclick=|_| Msg::Toggle,>
+                    { self.props.button.view(open) }
+                </button>
+                <div class="disclosure-panel", id=&self.props.id, hidden=!open,>
+                    { panel }
+                </div>
+            </div>
+        }
+    }
+}
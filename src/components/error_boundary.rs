@@ -0,0 +1,122 @@
+//! This module contains a boundary which replaces a failed part of a view
+//! with a fallback and publishes its error.
+
+use callback::Callback;
+use component::{Component, Render, ShouldRender};
+use errors::{self, ErrorKind, ErrorReport};
+use html::{AppSender, Html};
+use virtual_dom::VNode;
+
+/// Properties of the `ErrorBoundary` component.
+#[derive(Clone, PartialEq)]
+pub struct ErrorBoundaryProps {
+    /// A name of the part, the origin of published errors.
+    pub name: String,
+    /// Renders the content with a callback which fails it with a message.
+    pub render: Render<Callback<String>>,
+    /// Renders the failure instead of the message of the error.
+    pub fallback: Render<ErrorReport>,
+    /// The text of the button which renders the content again.
+    pub retry_label: String,
+}
+
+impl Default for ErrorBoundaryProps {
+    fn default() -> Self {
+        ErrorBoundaryProps {
+            name: "view".into(),
+            render: Render::default(),
+            fallback: Render::default(),
+            retry_label: "Try again".into(),
+        }
+    }
+}
+
+/// A message of the `ErrorBoundary` component.
+pub enum Msg {
+    /// The content failed.
+    Fail(String),
+    /// A user asked to render the content again.
+    Retry,
+}
+
+/// A part of a view which could fail without breaking the rest of a page.
+/// The content gets a callback, passes it to its components and calls it
+/// when it can't render, like after a failed fetch. The boundary publishes
+/// a `Component` error to `errors` and shows an alert with a retry button.
+///
+/// ```rust,ignore
+/// html! {
+///     <ErrorBoundary: name="recommendations",
+///                     render=|fail| html! { <Recommendations: onerror=fail, /> }, />
+/// }
+/// ```
+pub struct ErrorBoundary {
+    props: ErrorBoundaryProps,
+    fail: Callback<String>,
+    error: Option<ErrorReport>,
+}
+
+impl Component for ErrorBoundary {
+    type Msg = Msg;
+    type Properties = ErrorBoundaryProps;
+
+    fn create(props: Self::Properties, sender: AppSender<Msg>) -> Self {
+        let fail = Callback::from(move |message| sender.clone().send(Msg::Fail(message)));
+        ErrorBoundary {
+            props,
+            fail,
+            error: None,
+        }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Fail(message) => {
+                if self.error.is_some() {
+                    // The content failed again before it was removed
+                    return false;
+                }
+                let error = ErrorReport::new(ErrorKind::Component, self.props.name.as_str(), message);
+                errors::report(error.clone());
+                self.error = Some(error);
+            }
+            Msg::Retry => {
+                self.error = None;
+            }
+        }
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        match self.error {
+            Some(ref error) => {
+                let fallback: VNode<Msg> = if self.props.fallback == Render::default() {
+                    html! { <p class="error-boundary-message",>{ &error.message }</p> }.into()
+                } else {
+                    self.props.fallback.view(error.clone())
+                };
+                html! {
+                    <div class="error-boundary", role="alert",>
+                        { fallback }
+                        <button type="button", class="error-boundary-retry", onclick=|_| Msg::Retry,>
+                            { &self.props.retry_label }
+                        </button>
+                    </div>
+                }
+            }
+            None => html! {
+                <div class="error-boundary",>
+                    { self.props.render.view(self.fail.clone()) }
+                </div>
+            },
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! This module contains a component which keeps focus inside of a dialog.
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::Value;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::unstable::TryInto;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::web::Element;
+use callback::Callback;
+use component::{Component, ShouldRender, Children};
+use focus::{self, FocusRestore};
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use focus::FOCUSABLE;
+use html::{AppSender, Html};
+use virtual_dom::{Listener, NodeRef, VTag};
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use virtual_dom::{ListenerHandle, ListenerOptions};
+
+/// Properties of the `FocusTrap` component.
+#[derive(Default)]
+pub struct FocusTrapProps {
+    /// Nodes inside of the trap.
+    pub children: Children,
+    /// Classes of the wrapper element.
+    pub class: String,
+    /// Lets focus leave the trap and returns it to the element focused before.
+    pub paused: bool,
+    /// Called when the Escape key is pressed inside of the trap.
+    pub onescape: Callback<()>,
+}
+
+/// Messages of the `FocusTrap` component.
+pub enum Msg {
+    /// Children were rendered and could take focus.
+    Activate,
+}
+
+/// A wrapper which moves focus to its first focusable element when it's mounted,
+/// cycles Tab and Shift+Tab within its subtree and returns focus to the element
+/// which was focused before when it's unmounted or paused. Use it for modals:
+///
+/// ```rust,ignore
+/// html! {
+///     <FocusTrap: class="modal", onescape=|_| Msg::Close,
+///                 children=self.link.children(vec![self.view_form().into()]), />
+/// }
+/// ```
+pub struct FocusTrap {
+    props: FocusTrapProps,
+    sender: AppSender<Msg>,
+    node: NodeRef,
+    restore: Option<FocusRestore>,
+}
+
+impl FocusTrap {
+    fn activate(&mut self) {
+        if self.props.paused || self.restore.is_some() {
+            return;
+        }
+        self.restore = Some(FocusRestore::save());
+        if !focus::focus_first(&self.node) {
+            // The wrapper has `tabindex` to take focus without focusable children
+            self.node.focus();
+        }
+    }
+
+    fn release(&mut self) {
+        if let Some(restore) = self.restore.take() {
+            restore.restore();
+        }
+    }
+}
+
+impl Component for FocusTrap {
+    type Msg = Msg;
+    type Properties = FocusTrapProps;
+
+    fn create(props: Self::Properties, sender: AppSender<Msg>) -> Self {
+        sender.send_soon(Msg::Activate);
+        FocusTrap {
+            props,
+            sender,
+            node: NodeRef::default(),
+            restore: None,
+        }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Activate => self.activate(),
+        }
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let resumed = self.props.paused && !props.paused;
+        self.props = props;
+        if self.props.paused {
+            self.release();
+        } else if resumed {
+            self.sender.send_soon(Msg::Activate);
+        }
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        VTag::new("div")
+            .class(&self.props.class)
+            .attr("tabindex", "-1")
+            .node_ref(&self.node)
+            .listener(TrapListener {
+                paused: self.props.paused,
+                onescape: self.props.onescape.clone(),
+            })
+            .children(self.props.children.view())
+    }
+
+    fn destroy(&mut self) {
+        self.release();
+    }
+}
+
+/// A listener of keys which moves focus from the last focusable element
+/// to the first one and back.
+struct TrapListener {
+    paused: bool,
+    onescape: Callback<()>,
+}
+
+impl<MSG> Listener<MSG> for TrapListener {
+    fn kind(&self) -> &'static str {
+        "onkeydown"
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn attach(&mut self, element: &Element, _: AppSender<MSG>) -> ListenerHandle {
+        let container = element.clone();
+        let paused = self.paused;
+        let onescape = self.onescape.clone();
+        let listener = move |event: Value| {
+            let key: String = js! { return @{&event}.key; }.try_into().unwrap_or_default();
+            if key == "Escape" {
+                onescape.emit(());
+            } else if key == "Tab" && !paused {
+                js! { @(no_return)
+                    var event = @{&event};
+                    var container = @{&container};
+                    var items = container.querySelectorAll(@{FOCUSABLE});
+                    if (items.length === 0) {
+                        event.preventDefault();
+                        return;
+                    }
+                    var first = items[0];
+                    var last = items[items.length - 1];
+                    var active = document.activeElement;
+                    var outside = !container.contains(active) || active === container;
+                    if (event.shiftKey && (active === first || outside)) {
+                        event.preventDefault();
+                        last.focus();
+                    } else if (!event.shiftKey && (active === last || outside)) {
+                        event.preventDefault();
+                        first.focus();
+                    }
+                }
+            }
+        };
+        ListenerHandle::attach_named(element, "keydown", ListenerOptions::default(), listener)
+    }
+}
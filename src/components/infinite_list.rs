@@ -0,0 +1,394 @@
+//! This module contains a component which loads pages of a list
+//! when a user scrolls to its ends.
+
+use callback::Callback;
+use component::{Component, ShouldRender, Render};
+use html::{AppSender, Html};
+use services::Task;
+use services::intersection::{IntersectionService, IntersectionHandle, IntersectionOptions};
+use suspense::{Resource, State};
+use virtual_dom::{NodeRef, VNode, VText};
+
+/// An end of a list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    /// The top of a list, like older messages of a chat.
+    Start,
+    /// The bottom of a list.
+    End,
+}
+
+/// A state of loading at an end of a list.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeState {
+    /// The next page is loaded when the end is scrolled to.
+    #[default]
+    Idle,
+    /// The next page is loading.
+    Loading,
+    /// The last page wasn't loaded. It's loaded again when the state
+    /// is `Idle` again, like after a user pressed a retry button.
+    Failed,
+    /// There are no more pages.
+    Done,
+}
+
+/// A loaded page of items.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    /// Items of the page in order of the list.
+    pub items: Vec<T>,
+    /// `false` if it's the last page in its direction.
+    pub more: bool,
+}
+
+struct Side<E> {
+    resource: Resource<(), E>,
+    done: bool,
+}
+
+impl<E> Side<E> {
+    fn new() -> Self {
+        Side {
+            resource: Resource::new(),
+            done: false,
+        }
+    }
+}
+
+/// Items of a list which are loaded by pages at both ends. Keep it in
+/// a model of `InfiniteList` and pass its states to the properties:
+///
+/// ```rust,ignore
+/// Msg::Load(edge) => {
+///     let task = self.fetch.fetch(page_request(&self.pages, edge), move |response| Msg::Loaded(edge, parse(response)));
+///     self.pages.load(edge, task);
+/// }
+/// Msg::Loaded(edge, page) => self.pages.resolve(edge, page),
+/// ```
+pub struct Pages<T, E = ()> {
+    items: Vec<T>,
+    start: Side<E>,
+    end: Side<E>,
+}
+
+impl<T, E> Default for Pages<T, E> {
+    fn default() -> Self {
+        Pages::new()
+    }
+}
+
+impl<T, E> Pages<T, E> {
+    /// Creates an empty list.
+    pub fn new() -> Self {
+        Pages {
+            items: Vec::new(),
+            start: Side::new(),
+            end: Side::new(),
+        }
+    }
+
+    /// Returns loaded items.
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Returns the count of loaded items.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if nothing is loaded.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Adds an item to the end, like a new message of a chat.
+    pub fn push(&mut self, item: T) {
+        self.items.push(item);
+    }
+
+    /// Returns the state of the end for the `start` and `end` properties.
+    pub fn state(&self, edge: Edge) -> EdgeState {
+        let side = self.side(edge);
+        if side.done {
+            return EdgeState::Done;
+        }
+        match *side.resource.state() {
+            State::Idle | State::Ready(_) => EdgeState::Idle,
+            State::Pending => EdgeState::Loading,
+            State::Failed(_) | State::TimedOut => EdgeState::Failed,
+        }
+    }
+
+    /// Returns the error of the last page of the end if it failed.
+    pub fn error(&self, edge: Edge) -> Option<&E> {
+        match *self.side(edge).resource.state() {
+            State::Failed(ref error) => Some(error),
+            _ => None,
+        }
+    }
+
+    /// Keeps the task which loads the next page of the end. It will
+    /// send a message to call `resolve`.
+    pub fn load<TSK: Task + 'static>(&mut self, edge: Edge, task: TSK) {
+        self.side_mut(edge).resource.load(task);
+    }
+
+    /// Adds the loaded page to the end or keeps the error. Does nothing
+    /// if the end doesn't wait for a page, like after `reset`.
+    pub fn resolve(&mut self, edge: Edge, page: Result<Page<T>, E>) {
+        if self.state(edge) != EdgeState::Loading {
+            return;
+        }
+        let page = match page {
+            Ok(page) => page,
+            Err(error) => {
+                self.side_mut(edge).resource.resolve(Err(error));
+                return;
+            }
+        };
+        match edge {
+            Edge::Start => {
+                self.items.splice(0..0, page.items);
+            }
+            Edge::End => {
+                self.items.extend(page.items);
+            }
+        }
+        let side = self.side_mut(edge);
+        side.resource.resolve(Ok(()));
+        side.done = !page.more;
+    }
+
+    /// Lets a failed end load its page again.
+    pub fn retry(&mut self, edge: Edge) {
+        if self.state(edge) == EdgeState::Failed {
+            self.side_mut(edge).resource.cancel();
+        }
+    }
+
+    /// Marks that there are no more pages at the end.
+    pub fn finish(&mut self, edge: Edge) {
+        let side = self.side_mut(edge);
+        side.resource.cancel();
+        side.done = true;
+    }
+
+    /// Removes all items and cancels loading, like when a filter changed.
+    pub fn reset(&mut self) {
+        self.items.clear();
+        self.start = Side::new();
+        self.end = Side::new();
+    }
+
+    fn side(&self, edge: Edge) -> &Side<E> {
+        match edge {
+            Edge::Start => &self.start,
+            Edge::End => &self.end,
+        }
+    }
+
+    fn side_mut(&mut self, edge: Edge) -> &mut Side<E> {
+        match edge {
+            Edge::Start => &mut self.start,
+            Edge::End => &mut self.end,
+        }
+    }
+}
+
+/// Properties of the `InfiniteList` component.
+#[derive(Clone, PartialEq)]
+pub struct InfiniteListProps {
+    /// The count of loaded items.
+    pub count: usize,
+    /// Renders the item with the index.
+    pub item: Render<usize>,
+    /// The state of the top of the list.
+    pub start: EdgeState,
+    /// The state of the bottom of the list.
+    pub end: EdgeState,
+    /// Loads pages at the top too, like a history of a chat.
+    pub bidirectional: bool,
+    /// A margin around the viewport, so pages are loaded before a user reaches an end.
+    pub margin: String,
+    /// Called when the next page of the end should be loaded.
+    pub onload: Callback<Edge>,
+    /// Rendered at an end while it's loading.
+    pub loading: Render<Edge>,
+    /// Rendered at an end if it failed, like with a retry button.
+    pub failed: Render<Edge>,
+    /// Rendered at an end without more pages.
+    pub finished: Render<Edge>,
+}
+
+impl Default for InfiniteListProps {
+    fn default() -> Self {
+        InfiniteListProps {
+            count: 0,
+            item: Render::default(),
+            start: EdgeState::Idle,
+            end: EdgeState::Idle,
+            bidirectional: false,
+            margin: "200px".into(),
+            onload: Callback::default(),
+            loading: Render::default(),
+            failed: Render::default(),
+            finished: Render::default(),
+        }
+    }
+}
+
+/// A message of the `InfiniteList` component.
+pub enum Msg {
+    /// A sentinel of the end entered or left the viewport.
+    Visible(Edge, bool),
+}
+
+struct Sentinel {
+    node: NodeRef,
+    visible: bool,
+    // A page was asked for and the state of the end isn't changed yet
+    requested: bool,
+    observer: Option<IntersectionHandle>,
+}
+
+impl Sentinel {
+    fn request(&mut self, edge: Edge, state: EdgeState, onload: &Callback<Edge>) {
+        if state != EdgeState::Idle {
+            self.requested = false;
+        } else if self.visible && !self.requested {
+            self.requested = true;
+            onload.emit(edge);
+        }
+    }
+}
+
+/// A list which asks a parent for the next page when an invisible sentinel
+/// at its end is scrolled into the viewport. It asks again while the sentinel
+/// stays visible, so short pages fill the screen. Browsers keep the scroll
+/// position when pages are added above with scroll anchoring.
+///
+/// ```rust,ignore
+/// let (mut props, link) = VComp::lazy::<InfiniteList>();
+/// props.count = model.pages.len();
+/// props.end = model.pages.state(Edge::End);
+/// props.item = link.render(move |index| view_item(&items[index]));
+/// props.onload = link.callback(Msg::Load);
+/// props.failed = link.render(|edge| html! { <button onclick=move |_| Msg::Retry(edge),>{ "Retry" }</button> });
+/// let list = VComp::new::<InfiniteList>(props, link);
+/// ```
+pub struct InfiniteList {
+    props: InfiniteListProps,
+    start: Sentinel,
+    end: Sentinel,
+    intersection: IntersectionService<Msg>,
+}
+
+impl InfiniteList {
+    fn observe(&mut self, edge: Edge) {
+        let options = IntersectionOptions::margin(&self.props.margin);
+        let sentinel = match edge {
+            Edge::Start => &mut self.start,
+            Edge::End => &mut self.end,
+        };
+        let observer = self.intersection.observe(&sentinel.node, options, move |visible| Msg::Visible(edge, visible));
+        sentinel.observer = Some(observer);
+    }
+
+    /// Asks for pages of visible ends which could load them.
+    fn request(&mut self) {
+        if self.props.bidirectional {
+            self.start.request(Edge::Start, self.props.start, &self.props.onload);
+        }
+        self.end.request(Edge::End, self.props.end, &self.props.onload);
+    }
+
+    fn view_edge(&self, edge: Edge) -> VNode<Msg> {
+        let state = match edge {
+            Edge::Start => self.props.start,
+            Edge::End => self.props.end,
+        };
+        match state {
+            EdgeState::Idle => VNode::from(VText::new("")),
+            EdgeState::Loading => self.props.loading.view(edge),
+            EdgeState::Failed => self.props.failed.view(edge),
+            EdgeState::Done => self.props.finished.view(edge),
+        }
+    }
+}
+
+impl Component for InfiniteList {
+    type Msg = Msg;
+    type Properties = InfiniteListProps;
+
+    fn create(props: Self::Properties, sender: AppSender<Msg>) -> Self {
+        let sentinel = || Sentinel { node: NodeRef::default(), visible: false, requested: false, observer: None };
+        let mut list = InfiniteList {
+            props,
+            start: sentinel(),
+            end: sentinel(),
+            intersection: IntersectionService::new(sender),
+        };
+        list.observe(Edge::End);
+        if list.props.bidirectional {
+            list.observe(Edge::Start);
+        }
+        list
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Visible(Edge::Start, visible) => self.start.visible = visible,
+            Msg::Visible(Edge::End, visible) => self.end.visible = visible,
+        }
+        self.request();
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        let margin = self.props.margin != props.margin;
+        let bidirectional = props.bidirectional && (!self.props.bidirectional || margin);
+        self.props = props;
+        if margin {
+            self.observe(Edge::End);
+        }
+        if bidirectional {
+            self.observe(Edge::Start);
+        } else if !self.props.bidirectional {
+            self.start.observer = None;
+            self.start.visible = false;
+        }
+        self.request();
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let start: VNode<Msg> = if self.props.bidirectional {
+            html! {
+                <div class="infinite-list-start",>
+                    <div class="infinite-list-sentinel", ref=&self.start.node,></div>
+                    { self.view_edge(Edge::Start) }
+                </div>
+            }.into()
+        } else {
+            VNode::from(VText::new(""))
+        };
+        html! {
+            <div class="infinite-list",>
+                { start }
+                { for (0..self.props.count).map(|index| self.props.item.view(index)) }
+                { self.view_edge(Edge::End) }
+                <div class="infinite-list-sentinel", ref=&self.end.node,></div>
+            </div>
+        }
+    }
+
+    fn destroy(&mut self) {
+        self.start.observer = None;
+        self.end.observer = None;
+    }
+}
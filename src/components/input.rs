@@ -0,0 +1,68 @@
+//! This module contains a controlled `input` component.
+
+use callback::Callback;
+use component::{Component, ShouldRender};
+use html::{AppSender, Html, InputData};
+
+/// Properties of the `Input` component.
+#[derive(Default, Clone, PartialEq)]
+pub struct InputProps {
+    /// The value of the input. The element always shows this value.
+    pub value: String,
+    /// A type of the input (`text` if empty).
+    pub kind: String,
+    /// A placeholder of the empty input.
+    pub placeholder: String,
+    /// Disables the input.
+    pub disabled: bool,
+    /// Called with a new value on every change by a user.
+    pub onchange: Callback<String>,
+}
+
+/// A message of the `Input` component.
+pub enum Msg {
+    /// A user changed the value.
+    Changed(String),
+}
+
+/// A controlled `input` element. It shows the value passed by a parent
+/// and notifies the parent about changes. If the parent doesn't accept a change
+/// and renders the old value, the element resets to the old value as well.
+pub struct Input {
+    props: InputProps,
+}
+
+impl Component for Input {
+    type Msg = Msg;
+    type Properties = InputProps;
+
+    fn create(props: Self::Properties, _: AppSender<Msg>) -> Self {
+        Input { props }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Changed(value) => {
+                self.props.onchange.emit(value);
+            }
+        }
+        // A parent decides what value to render
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let kind = if self.props.kind.is_empty() { "text" } else { &self.props.kind };
+        html! {
+            <input type=kind,
+                   value=&self.props.value,
+                   placeholder=&self.props.placeholder,
+                   disabled=self.props.disabled,
+                   oninput=|e: InputData| Msg::Changed(e.value), />
+        }
+    }
+}
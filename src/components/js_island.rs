@@ -0,0 +1,224 @@
+//! This module contains a component which hosts a widget of a JS library,
+//! like a map, a chart or an editor, inside of a view.
+
+use serde_json::{self, Value as Json};
+use stdweb::Value;
+use callback::Callback;
+use component::{Component, ShouldRender};
+use html::{AppSender, Html};
+use virtual_dom::{NodeRef, VTag};
+
+/// Registers an adapter of a JS widget by a name. The `source` is an expression
+/// which returns an object with functions:
+///
+/// * `mount(element, props, emit)` creates the widget inside of the element,
+///   `emit(detail)` sends JSON data to `onevent` of the component;
+/// * `update(element, props)` applies new properties. If it's missing, the
+///   widget is unmounted and mounted again;
+/// * `unmount(element)` destroys the widget.
+///
+/// ```rust,ignore
+/// js_island::register("chart", r#"{
+///     mount: function(element, props, emit) {
+///         element.chart = new Chart(element, props);
+///         element.chart.on("click", function(point) { emit(point.index); });
+///     },
+///     update: function(element, props) { element.chart.setData(props.data); },
+///     unmount: function(element) { element.chart.destroy(); },
+/// }"#);
+/// ```
+///
+/// With the `csp` feature it panics, because the source is evaluated.
+pub fn register(name: &str, source: &str) {
+    #[cfg(feature = "csp")]
+    ::csp::forbid("js_island::register", "js_island::register_global");
+    js! { @(no_return)
+        var adapters = window.__yew_islands || (window.__yew_islands = {});
+        var adapter = new Function("return (" + @{source} + ");")();
+        if (!adapter || typeof adapter.mount !== "function") {
+            throw new Error("the adapter " + @{name} + " has no mount function");
+        }
+        adapters[@{name}] = adapter;
+    }
+}
+
+/// Registers an adapter which a script of the page defined as a global
+/// object, the `path` is a path of properties from `window`, like
+/// `widgets.chart`. It doesn't evaluate code, so it works under a Content
+/// Security Policy.
+pub fn register_global(name: &str, path: &str) {
+    js! { @(no_return)
+        var adapters = window.__yew_islands || (window.__yew_islands = {});
+        var adapter = @{path}.split(".").reduce(function(object, key) {
+            return object == null ? undefined : object[key];
+        }, window);
+        if (!adapter || typeof adapter.mount !== "function") {
+            throw new Error("the adapter " + @{name} + " at " + @{path} + " has no mount function");
+        }
+        adapters[@{name}] = adapter;
+    }
+}
+
+/// Properties of the `JsIsland` component.
+#[derive(Default)]
+pub struct JsIslandProps {
+    /// The name of a registered adapter.
+    pub adapter: String,
+    /// Properties of the widget. They're passed again when they change.
+    pub props: Json,
+    /// The tag of the element of the widget, `div` by default.
+    pub tag: String,
+    /// Classes of the element.
+    pub class: String,
+    /// Called with data which the widget emitted.
+    pub onevent: Callback<Json>,
+}
+
+/// Messages of the `JsIsland` component.
+pub enum Msg {
+    /// The element is rendered and the widget could be mounted.
+    Mount,
+    /// The widget emitted data.
+    Event(Json),
+}
+
+/// A component which hands its element and properties to a JS adapter and
+/// keeps away from children of the element, so a widget renders them itself.
+/// The widget is mounted after the first render and unmounted when the
+/// component is destroyed:
+///
+/// ```rust,ignore
+/// html! {
+///     <JsIsland: adapter="chart", class="sales",
+///                props=json!({ "data": self.sales }), onevent=|index| Msg::Pick(index),/>
+/// }
+/// ```
+pub struct JsIsland {
+    props: JsIslandProps,
+    sender: AppSender<Msg>,
+    node: NodeRef,
+    // A JS object with the element, the adapter and the emit callback
+    mounted: Option<Value>,
+}
+
+impl JsIsland {
+    fn mount(&mut self) {
+        let element = match self.node.get() {
+            Some(element) => element,
+            None => return,
+        };
+        let props = serde_json::to_string(&self.props.props).unwrap_or_else(|_| "null".into());
+        let mut sender = self.sender.clone();
+        let emit = move |detail: String| {
+            let data = serde_json::from_str(&detail).unwrap_or(Json::Null);
+            sender.send(Msg::Event(data));
+        };
+        let mounted: Value = js! {
+            var name = @{&self.props.adapter};
+            var adapter = (window.__yew_islands || {})[name];
+            var emit = @{emit};
+            if (!adapter) {
+                emit.drop();
+                console.warn("the island adapter " + name + " isn't registered");
+                return null;
+            }
+            var element = @{element};
+            var mounted = { element: element, adapter: adapter, emit: emit };
+            adapter.mount(element, JSON.parse(@{props}), function(detail) {
+                emit(JSON.stringify(detail === undefined ? null : detail));
+            });
+            return mounted;
+        };
+        if mounted != Value::Null {
+            self.mounted = Some(mounted);
+        }
+    }
+
+    fn update_widget(&mut self) {
+        let props = serde_json::to_string(&self.props.props).unwrap_or_else(|_| "null".into());
+        if let Some(ref mounted) = self.mounted {
+            let updated: Value = js! {
+                var mounted = @{mounted};
+                if (typeof mounted.adapter.update !== "function") {
+                    return false;
+                }
+                mounted.adapter.update(mounted.element, JSON.parse(@{props}));
+                return true;
+            };
+            if updated == Value::Bool(true) {
+                return;
+            }
+        }
+        self.unmount();
+        self.mount();
+    }
+
+    fn unmount(&mut self) {
+        if let Some(mounted) = self.mounted.take() {
+            js! { @(no_return)
+                var mounted = @{mounted};
+                if (typeof mounted.adapter.unmount === "function") {
+                    mounted.adapter.unmount(mounted.element);
+                }
+                mounted.emit.drop();
+            }
+        }
+    }
+}
+
+impl Component for JsIsland {
+    type Msg = Msg;
+    type Properties = JsIslandProps;
+
+    fn create(props: Self::Properties, sender: AppSender<Msg>) -> Self {
+        sender.send_soon(Msg::Mount);
+        JsIsland {
+            props,
+            sender,
+            node: NodeRef::default(),
+            mounted: None,
+        }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Mount => {
+                if self.mounted.is_none() {
+                    self.mount();
+                }
+            }
+            Msg::Event(data) => {
+                self.props.onevent.emit(data);
+            }
+        }
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let remount = props.adapter != self.props.adapter || props.tag != self.props.tag;
+        let changed = props.props != self.props.props;
+        let restyled = props.class != self.props.class;
+        self.props = props;
+        if remount {
+            // The element could be replaced by the render
+            self.unmount();
+            self.sender.send_soon(Msg::Mount);
+            return true;
+        }
+        if changed && self.mounted.is_some() {
+            self.update_widget();
+        }
+        restyled
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let tag = if self.props.tag.is_empty() { "div" } else { &self.props.tag };
+        VTag::new(tag.to_owned())
+            .class(&self.props.class)
+            .node_ref(&self.node)
+    }
+
+    fn destroy(&mut self) {
+        self.unmount();
+    }
+}
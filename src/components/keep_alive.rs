@@ -0,0 +1,126 @@
+//! This module contains a component which keeps views of recent keys
+//! mounted while another one is shown.
+
+use component::{Component, Render, ShouldRender};
+use html::{AppSender, Html};
+use virtual_dom::{VNode, VTag};
+
+/// Properties of the `KeepAlive` component.
+pub struct KeepAliveProps<K> {
+    /// A key of the shown view, or none to hide all of them.
+    pub current: Option<K>,
+    /// Renders a view of a key.
+    pub render: Render<K>,
+    /// Views which stay alive, including the shown one. The least recently
+    /// shown view is destroyed when a new key doesn't fit.
+    pub max: usize,
+}
+
+impl<K> Default for KeepAliveProps<K> {
+    fn default() -> Self {
+        KeepAliveProps {
+            current: None,
+            render: Render::default(),
+            max: 5,
+        }
+    }
+}
+
+/// A kept view.
+struct Slot<K> {
+    key: K,
+    shown: usize,
+}
+
+/// A component which renders views of the last `max` keys and hides all
+/// but the current one with the `hidden` attribute. Components of a hidden
+/// view keep their state and DOM, like values of a half-filled form, and
+/// the view is shown as it was when its key becomes current again. Every
+/// distinct key has its own view.
+///
+/// ```rust,ignore
+/// html! {
+///     <KeepAlive<Tab>: current=Some(self.tab), max=3, render=|tab| self.view_tab(tab), />
+/// }
+/// ```
+///
+/// `Router` keeps views of visited routes with its `keep_alive` property.
+pub struct KeepAlive<K: 'static> {
+    props: KeepAliveProps<K>,
+    // Views keep their places, because children are patched by position
+    slots: Vec<Option<Slot<K>>>,
+    clock: usize,
+}
+
+impl<K: Clone + PartialEq + 'static> KeepAlive<K> {
+    fn show(&mut self) {
+        while let Some(&None) = self.slots.last() {
+            self.slots.pop();
+        }
+        let key = match self.props.current {
+            Some(ref key) => key.clone(),
+            None => return,
+        };
+        self.clock += 1;
+        let clock = self.clock;
+        if let Some(slot) = self.slots.iter_mut().flatten().find(|slot| slot.key == key) {
+            slot.shown = clock;
+            return;
+        }
+        // An empty place was rendered empty before, so the new view is mounted
+        // anew instead of patching a destroyed one
+        let slot = Some(Slot { key, shown: clock });
+        match self.slots.iter().position(Option::is_none) {
+            Some(index) => self.slots[index] = slot,
+            None => self.slots.push(slot),
+        }
+        while self.slots.iter().flatten().count() > self.props.max.max(1) {
+            let oldest = self.slots.iter().enumerate()
+                .filter_map(|(index, slot)| slot.as_ref().map(|slot| (index, slot.shown)))
+                .min_by_key(|&(_, shown)| shown)
+                .map(|(index, _)| index)
+                .expect("a kept view");
+            self.slots[oldest] = None;
+        }
+    }
+}
+
+impl<K: Clone + PartialEq + 'static> Component for KeepAlive<K> {
+    type Msg = ();
+    type Properties = KeepAliveProps<K>;
+
+    fn create(props: Self::Properties, _: AppSender<()>) -> Self {
+        let mut keep = KeepAlive {
+            props,
+            slots: Vec::new(),
+            clock: 0,
+        };
+        keep.show();
+        keep
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        self.show();
+        true
+    }
+
+    fn view(&self) -> Html<()> {
+        let views = self.slots.iter().map(|slot| match *slot {
+            Some(ref slot) => {
+                let shown = self.props.current.as_ref() == Some(&slot.key);
+                VTag::new("div")
+                    .class("keep-alive-view")
+                    .attr("hidden", !shown)
+                    .child(self.props.render.view(slot.key.clone()))
+                    .into()
+            }
+            None => VNode::from(""),
+        });
+        VTag::new("div").class("keep-alive").children(views)
+    }
+}
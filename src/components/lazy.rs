@@ -0,0 +1,155 @@
+//! This module contains a component which loads a chunk before
+//! rendering a wrapped component.
+//!
+//! A chunk is a separate script, like a JS library or a widget which a view
+//! needs. It doesn't split the code of the app: the wrapped component is
+//! compiled into the app like any other one, because a WebAssembly module
+//! of `stdweb` can't be split. So `Lazy` defers the download of the chunk
+//! and the render of the component, but the app isn't smaller with it.
+
+use component::{Component, ShouldRender, Link, Render};
+use html::{AppSender, Html};
+use services::Task;
+use services::script::{ScriptService, ScriptHandle};
+use virtual_dom::VComp;
+
+/// Properties of the `Lazy` component.
+pub struct LazyProps<COMP: Component> {
+    /// URL of a chunk which have to be loaded before the first render.
+    /// The component renders immediately if it's empty.
+    pub chunk: String,
+    /// Properties which will be passed to the wrapped component.
+    pub props: COMP::Properties,
+    /// Rendered while the chunk is loading.
+    pub fallback: Render<()>,
+    /// Rendered if the chunk can't be loaded.
+    pub failed: Render<String>,
+}
+
+impl<COMP: Component> Default for LazyProps<COMP> {
+    fn default() -> Self {
+        LazyProps {
+            chunk: String::new(),
+            props: Default::default(),
+            fallback: Render::default(),
+            failed: Render::default(),
+        }
+    }
+}
+
+/// A state of the chunk loading.
+enum Chunk {
+    Loading(ScriptHandle),
+    Loaded,
+    Failed(String),
+}
+
+/// A message of the `Lazy` component.
+pub enum Msg {
+    /// The chunk loaded or failed.
+    Loaded(Result<(), String>),
+}
+
+/// A component which loads a chunk (a script which the component needs)
+/// on the first render and renders the wrapped component `COMP` after that.
+/// Properties of the wrapped component have to be cloneable, because
+/// they are passed to the wrapped component on every render.
+///
+/// ```rust,ignore
+/// let (mut props, link) = VComp::lazy::<Lazy<Reports>>();
+/// props.chunk = "/chunks/reports.js".into();
+/// props.props.onselect = link.callback(Msg::ReportSelected);
+/// props.fallback = link.render(|_| html! { <p>{ "Loading..." }</p> });
+/// let reports = VComp::new::<Lazy<Reports>>(props, link);
+/// ```
+pub struct Lazy<COMP: Component> {
+    props: LazyProps<COMP>,
+    chunk: Chunk,
+    script: ScriptService<Msg>,
+}
+
+impl<COMP> Lazy<COMP>
+where
+    COMP: Component,
+    COMP::Properties: Clone,
+{
+    fn load(&mut self) {
+        self.chunk = {
+            if self.props.chunk.is_empty() {
+                Chunk::Loaded
+            } else {
+                let handle = self.script.load(&self.props.chunk, Msg::Loaded);
+                Chunk::Loading(handle)
+            }
+        };
+    }
+}
+
+impl<COMP> Component for Lazy<COMP>
+where
+    COMP: Component,
+    COMP::Properties: Clone,
+{
+    type Msg = Msg;
+    type Properties = LazyProps<COMP>;
+
+    fn create(props: Self::Properties, sender: AppSender<Msg>) -> Self {
+        let mut lazy = Lazy {
+            props,
+            chunk: Chunk::Loaded,
+            script: ScriptService::new(sender),
+        };
+        lazy.load();
+        lazy
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Loaded(Ok(())) => {
+                self.chunk = Chunk::Loaded;
+            }
+            Msg::Loaded(Err(reason)) => {
+                warn!("{}", reason);
+                self.chunk = Chunk::Failed(reason);
+            }
+        }
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let reload = self.props.chunk != props.chunk;
+        self.props = props;
+        if reload {
+            if let Chunk::Loading(ref mut handle) = self.chunk {
+                handle.cancel();
+            }
+            self.load();
+        }
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        match self.chunk {
+            Chunk::Loading(_) => html! {
+                <div>{ self.props.fallback.view(()) }</div>
+            },
+            Chunk::Loaded => {
+                // Callbacks of properties are bound by the link of a parent
+                let props = self.props.props.clone();
+                html! {
+                    <div>{ VComp::new::<COMP>(props, Link::new()) }</div>
+                }
+            }
+            Chunk::Failed(ref reason) => html! {
+                <div>{ self.props.failed.view(reason.clone()) }</div>
+            },
+        }
+    }
+
+    fn destroy(&mut self) {
+        if let Chunk::Loading(ref mut handle) = self.chunk {
+            handle.cancel();
+        }
+        self.chunk = Chunk::Loaded;
+    }
+}
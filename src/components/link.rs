@@ -0,0 +1,221 @@
+//! This module contains a component which renders an anchor to a route.
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::Value;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::unstable::TryInto;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::web::Element;
+use component::{Component, ShouldRender, Children};
+use html::{onmouseenter, AppSender, Html};
+use prefetch;
+use router::{self, Routable};
+use services::Task;
+use services::history::{self, HistoryService, HistoryHandle};
+use services::intersection::{IntersectionService, IntersectionHandle, IntersectionOptions};
+use virtual_dom::{Listener, NodeRef, VTag};
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use virtual_dom::{ListenerHandle, ListenerOptions};
+
+/// Properties of the `Link` component.
+pub struct LinkProps<R> {
+    /// The route of the link.
+    pub to: Option<R>,
+    /// A path of the scope of nested routes. The route is relative to it.
+    pub base: String,
+    /// A text of the link which is rendered before children.
+    pub label: String,
+    /// Nodes inside of the anchor.
+    pub children: Children,
+    /// Classes of the anchor.
+    pub class: String,
+    /// A class which is added when the current location matches the route.
+    /// It's `active` by default.
+    pub active_class: String,
+    /// Doesn't prefetch a chunk and data of the route when a pointer enters the link.
+    pub no_prefetch: bool,
+    /// Prefetches the route when the link is scrolled near the viewport instead,
+    /// like links of a menu on touch screens.
+    pub prefetch_visible: bool,
+}
+
+impl<R> Default for LinkProps<R> {
+    fn default() -> Self {
+        LinkProps {
+            to: None,
+            base: String::new(),
+            label: String::new(),
+            children: Children::default(),
+            class: String::new(),
+            active_class: "active".into(),
+            no_prefetch: false,
+            prefetch_visible: false,
+        }
+    }
+}
+
+/// Messages of the `Link` component.
+pub enum Msg {
+    /// The location was changed.
+    Changed(String),
+    /// A pointer entered the link.
+    Hovered,
+    /// The link was scrolled into the viewport or out of it.
+    Visible(bool),
+}
+
+/// An anchor which navigates to a route without reloading of a page.
+/// Clicks with modifier keys or with a button other than the main one
+/// are left to a browser, so a route could be opened in a new tab.
+pub struct Link<R: Routable> {
+    props: LinkProps<R>,
+    active: bool,
+    handle: HistoryHandle,
+    anchor: NodeRef,
+    intersection: IntersectionService<Msg>,
+    observer: Option<IntersectionHandle>,
+    prefetched: bool,
+}
+
+impl<R: Routable> Link<R> {
+    fn is_active(&self, location: &str) -> bool {
+        match self.props.to {
+            Some(ref to) => router::route_of::<R>(&self.props.base, location).as_ref() == Some(to),
+            None => false,
+        }
+    }
+
+    fn is_prefetchable(&self) -> bool {
+        !self.props.no_prefetch && self.props.to.as_ref().map_or(false, prefetch::is_prefetchable)
+    }
+
+    fn observe(&mut self) {
+        if self.props.prefetch_visible && self.is_prefetchable() && !self.prefetched {
+            if self.observer.is_none() {
+                let options = IntersectionOptions::margin("200px");
+                self.observer = Some(self.intersection.observe(&self.anchor, options, Msg::Visible));
+            }
+        } else {
+            self.observer = None;
+        }
+    }
+}
+
+impl<R: Routable> Component for Link<R> {
+    type Msg = Msg;
+    type Properties = LinkProps<R>;
+
+    fn create(props: Self::Properties, sender: AppSender<Msg>) -> Self {
+        let mut history = HistoryService::new(sender.clone());
+        let handle = history.listen(Msg::Changed);
+        let mut link = Link {
+            props,
+            active: false,
+            handle,
+            anchor: NodeRef::default(),
+            intersection: IntersectionService::new(sender),
+            observer: None,
+            prefetched: false,
+        };
+        link.active = link.is_active(&history.location());
+        link.observe();
+        link
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Changed(location) => {
+                let active = self.is_active(&location);
+                let changed = self.active != active;
+                self.active = active;
+                changed
+            }
+            Msg::Hovered => {
+                if let Some(ref to) = self.props.to {
+                    prefetch::route(to);
+                }
+                false
+            }
+            Msg::Visible(visible) => {
+                if visible {
+                    if let Some(ref to) = self.props.to {
+                        prefetch::route(to);
+                    }
+                    // A route is prefetched once, the query cache keeps its data
+                    self.prefetched = true;
+                    self.observer = None;
+                }
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let to = self.props.to.take();
+        self.props = props;
+        if self.props.to != to {
+            self.prefetched = false;
+            self.observer = None;
+        }
+        self.observe();
+        self.active = self.is_active(&history::location());
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let mut anchor = VTag::new("a")
+            .class(&self.props.class)
+            .class((&self.props.active_class, self.active))
+            .node_ref(&self.anchor);
+        if let Some(ref to) = self.props.to {
+            let path = router::join(&self.props.base, &to.to_path());
+            anchor = anchor
+                .attr("href", history::href(&path))
+                .listener(NavigateListener { path });
+            if !self.props.prefetch_visible && self.is_prefetchable() {
+                let listener = onmouseenter::Wrapper::from(|_| Msg::Hovered);
+                anchor = anchor.listener(listener);
+            }
+        }
+        if !self.props.label.is_empty() {
+            anchor = anchor.child(&self.props.label);
+        }
+        anchor.children(self.props.children.view())
+    }
+
+    fn destroy(&mut self) {
+        self.handle.cancel();
+        self.observer = None;
+    }
+}
+
+/// A listener of clicks which pushes the path to the history instead of
+/// loading of a page.
+struct NavigateListener {
+    path: String,
+}
+
+impl<MSG> Listener<MSG> for NavigateListener {
+    fn kind(&self) -> &'static str {
+        "onclick"
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn attach(&mut self, element: &Element, _: AppSender<MSG>) -> ListenerHandle {
+        let path = self.path.clone();
+        let listener = move |event: Value| {
+            let plain: bool = js! {
+                var event = @{&event};
+                var target = event.currentTarget.getAttribute("target");
+                return event.button === 0 && !event.defaultPrevented
+                    && !event.altKey && !event.ctrlKey && !event.shiftKey && !event.metaKey
+                    && (!target || target === "_self");
+            }.try_into().unwrap_or(false);
+            if plain {
+                js! { @(no_return) @{&event}.preventDefault(); }
+                history::push_state(&path);
+            }
+        };
+        ListenerHandle::attach_named(element, "click", ListenerOptions::default(), listener)
+    }
+}
@@ -0,0 +1,43 @@
+//! This module contains a component which renders Markdown.
+
+use component::{Component, ShouldRender};
+use html::{AppSender, Html};
+use markdown;
+
+/// Properties of the `Markdown` component.
+#[derive(Default, Clone, PartialEq)]
+pub struct MarkdownProps {
+    /// A Markdown source to render.
+    pub source: String,
+}
+
+/// A component which renders a Markdown source to safe virtual nodes.
+/// It renders again only when the source changed.
+pub struct Markdown {
+    props: MarkdownProps,
+}
+
+impl Component for Markdown {
+    type Msg = ();
+    type Properties = MarkdownProps;
+
+    fn create(props: Self::Properties, _: AppSender<()>) -> Self {
+        Markdown { props }
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<()> {
+        markdown::render(&self.props.source)
+    }
+}
@@ -0,0 +1,161 @@
+//! This module contains an input which formats its text with a mask.
+
+use stdweb::unstable::TryInto;
+use callback::Callback;
+use component::{Component, ShouldRender};
+use html::{AppSender, Html, InputData};
+use mask::{Mask, Masked};
+use virtual_dom::NodeRef;
+
+/// Properties of the `MaskedInput` component.
+#[derive(Default, Clone, PartialEq)]
+pub struct MaskedInputProps {
+    /// An id of the input, for its `label`.
+    pub id: String,
+    /// The raw value of the input. The element shows it formatted.
+    pub value: String,
+    /// The mask of the text.
+    pub mask: Mask,
+    /// A placeholder of the empty input, like `(555) 123-4567`.
+    pub placeholder: String,
+    /// A label for screen readers if there is no `label` element.
+    pub label: String,
+    /// An `autocomplete` hint of browsers, like `tel` or `cc-number`.
+    pub autocomplete: String,
+    /// Disables the input.
+    pub disabled: bool,
+    /// Called with raw and formatted values on every change by a user.
+    pub onchange: Callback<Masked>,
+}
+
+/// A message of the `MaskedInput` component.
+pub enum Msg {
+    /// A user changed the text.
+    Input(String),
+    /// The formatted text is rendered and the caret could be moved.
+    Caret,
+}
+
+/// Converts between UTF-16 offsets of the DOM and char offsets.
+fn chars_before(text: &str, utf16: usize) -> usize {
+    let mut units = 0;
+    text.chars().take_while(|c| {
+        units += c.len_utf16();
+        units <= utf16
+    }).count()
+}
+
+fn utf16_before(text: &str, chars: usize) -> usize {
+    text.chars().take(chars).map(char::len_utf16).sum()
+}
+
+/// A controlled input which formats the text with a `Mask` on every
+/// keystroke, like `(555) 123-4567` of typed digits, and keeps the caret
+/// after the same digit. A parent gets `Masked` values with the raw
+/// value to store and renders the raw value back. Characters which the mask
+/// doesn't accept are dropped.
+///
+/// ```rust,ignore
+/// html! {
+///     <MaskedInput: id="phone", mask=Mask::phone(), value=&self.phone, autocomplete="tel",
+///                   onchange=|masked: Masked| Msg::Phone(masked.raw), />
+///     <MaskedInput: id="amount", mask=Mask::Number(NumberMask::currency(&self.intl, "EUR")),
+///                   value=&self.amount, onchange=|masked: Masked| Msg::Amount(masked.raw), />
+/// }
+/// ```
+pub struct MaskedInput {
+    props: MaskedInputProps,
+    sender: AppSender<Msg>,
+    node: NodeRef,
+    text: String,
+    caret: usize,
+}
+
+impl MaskedInput {
+    /// Returns the caret of the element in chars of the text, or the end
+    /// if the element isn't rendered.
+    fn read_caret(&self, text: &str) -> usize {
+        let element = match self.node.get() {
+            Some(element) => element,
+            None => return text.chars().count(),
+        };
+        let caret: Option<u32> = js! {
+            var start = @{element}.selectionStart;
+            return typeof start === "number" ? start : null;
+        }.try_into().unwrap_or_default();
+        caret.map_or(text.chars().count(), |caret| chars_before(text, caret as usize))
+    }
+}
+
+impl Component for MaskedInput {
+    type Msg = Msg;
+    type Properties = MaskedInputProps;
+
+    fn create(props: Self::Properties, sender: AppSender<Msg>) -> Self {
+        let text = props.mask.format(&props.value);
+        MaskedInput {
+            caret: text.chars().count(),
+            props,
+            sender,
+            node: NodeRef::default(),
+            text,
+        }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Input(text) => {
+                let caret = self.read_caret(&text);
+                let masked = self.props.mask.edit(&self.text, &text, caret);
+                self.text = masked.formatted.clone();
+                self.caret = masked.caret;
+                self.props.onchange.emit(masked);
+                // Browsers move the caret to the end when the value is replaced
+                self.sender.send_soon(Msg::Caret);
+                true
+            }
+            Msg::Caret => {
+                if let Some(element) = self.node.get() {
+                    let caret = utf16_before(&self.text, self.caret) as u32;
+                    js! { @(no_return)
+                        var element = @{element};
+                        if (document.activeElement === element) {
+                            element.setSelectionRange(@{caret}, @{caret});
+                        }
+                    }
+                }
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        let text = props.mask.format(&props.value);
+        if text != self.text {
+            // A parent changed or rejected the value
+            self.caret = text.chars().count();
+            self.text = text;
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let id = if self.props.id.is_empty() { None } else { Some(&self.props.id) };
+        let label = if self.props.label.is_empty() { None } else { Some(&self.props.label) };
+        let autocomplete = if self.props.autocomplete.is_empty() { None } else { Some(&self.props.autocomplete) };
+        html! {
+            <input type="text", class="masked-input", id=id, ref=&self.node,
+                   inputmode=self.props.mask.input_mode(),
+                   autocomplete=autocomplete,
+                   value=&self.text,
+                   placeholder=&self.props.placeholder,
+                   aria-label=label,
+                   disabled=self.props.disabled,
+                   oninput=|e: InputData| Msg::Input(e.value), />
+        }
+    }
+}
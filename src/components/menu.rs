@@ -0,0 +1,519 @@
+//! This module contains a menu and a menubar which follow the ARIA
+//! menu pattern, with typed items and nested submenus.
+
+use callback::Callback;
+use component::{Component, ShouldRender};
+use focus::{roving_index, Orientation};
+use html::{AppSender, Html, KeyData};
+use virtual_dom::{NodeRef, VComp, VNode, VText};
+
+/// What an item of a menu does.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemKind<T> {
+    /// Selects the value and closes the menu.
+    Action(T),
+    /// Selects the value which toggles an option, shown with a check mark.
+    Check(T, bool),
+    /// Opens a nested menu.
+    Submenu(Vec<MenuItem<T>>),
+    /// A line between groups of items, which can't take focus.
+    Separator,
+}
+
+/// An item of a menu.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MenuItem<T> {
+    /// The label, which is also used to find the item by its first letter.
+    pub label: String,
+    /// What the item does.
+    pub kind: ItemKind<T>,
+    /// The item can't be selected, but it's shown and takes focus.
+    pub disabled: bool,
+    /// A keyboard shortcut which is shown next to the label, like `Ctrl+S`.
+    pub shortcut: Option<String>,
+}
+
+impl<T> MenuItem<T> {
+    fn new<L: Into<String>>(label: L, kind: ItemKind<T>) -> Self {
+        MenuItem {
+            label: label.into(),
+            kind,
+            disabled: false,
+            shortcut: None,
+        }
+    }
+
+    /// Creates an item which selects the value.
+    pub fn action<L: Into<String>>(label: L, value: T) -> Self {
+        MenuItem::new(label, ItemKind::Action(value))
+    }
+
+    /// Creates an item of an option which is checked or not.
+    pub fn check<L: Into<String>>(label: L, value: T, checked: bool) -> Self {
+        MenuItem::new(label, ItemKind::Check(value, checked))
+    }
+
+    /// Creates an item which opens a nested menu.
+    pub fn submenu<L: Into<String>>(label: L, items: Vec<MenuItem<T>>) -> Self {
+        MenuItem::new(label, ItemKind::Submenu(items))
+    }
+
+    /// Creates a separator.
+    pub fn separator() -> Self {
+        MenuItem::new("", ItemKind::Separator)
+    }
+
+    /// Disables the item.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    /// Sets a keyboard shortcut which is shown with the item.
+    pub fn shortcut<S: Into<String>>(mut self, shortcut: S) -> Self {
+        self.shortcut = Some(shortcut.into());
+        self
+    }
+
+    /// Returns items of the nested menu or an empty slice.
+    pub fn items(&self) -> &[MenuItem<T>] {
+        match self.kind {
+            ItemKind::Submenu(ref items) => items,
+            _ => &[],
+        }
+    }
+
+    fn is_separator(&self) -> bool {
+        matches!(self.kind, ItemKind::Separator)
+    }
+
+    fn has_submenu(&self) -> bool {
+        match self.kind {
+            ItemKind::Submenu(_) => !self.disabled,
+            _ => false,
+        }
+    }
+}
+
+/// Which item of a menu is active when it's opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    /// The first item, like after `ArrowDown` on a menu button.
+    First,
+    /// The last item, like after `ArrowUp`.
+    Last,
+}
+
+/// Properties of the `Menu` component.
+#[derive(Clone, PartialEq)]
+pub struct MenuProps<T> {
+    /// A prefix of ids of items, unique on the page.
+    pub id: String,
+    /// Items of the menu.
+    pub items: Vec<MenuItem<T>>,
+    /// A label of the menu for screen readers.
+    pub label: String,
+    /// `Horizontal` renders a menubar whose items open submenus below them.
+    pub orientation: Orientation,
+    /// Takes focus and activates the item when it's created, for menus
+    /// which are opened by a button or a right click.
+    pub autofocus: Option<Edge>,
+    /// Called with the value of an item which a user selected.
+    pub onselect: Callback<T>,
+    /// Called when a user closed the menu with `Escape` or `Tab`
+    /// or selected an item.
+    pub onclose: Callback<()>,
+}
+
+impl<T> Default for MenuProps<T> {
+    fn default() -> Self {
+        MenuProps {
+            id: "menu".into(),
+            items: Vec::new(),
+            label: String::new(),
+            orientation: Orientation::Vertical,
+            autofocus: None,
+            onselect: Callback::default(),
+            onclose: Callback::default(),
+        }
+    }
+}
+
+/// A message of the `Menu` component.
+pub enum Msg {
+    /// A key was pressed in the menu.
+    Key(String),
+    /// The pointer entered the item at the path.
+    Hover(Vec<usize>),
+    /// The item at the path was clicked.
+    Activate(Vec<usize>),
+    /// The menu got focus.
+    Focus,
+    /// The menu lost focus.
+    Blur,
+    /// The menu takes focus after it's rendered.
+    Autofocus,
+}
+
+/// A menu of actions with nested submenus. The menu keeps focus and points
+/// to the active item with `aria-activedescendant`. Arrows move between
+/// items and open and close submenus, `Enter` selects an item, `Escape`
+/// closes a submenu or the menu and a letter finds an item by its label.
+///
+/// ```rust,ignore
+/// html! {
+///     <Menu<Command>: id="editor", label="Editor", orientation=Orientation::Horizontal,
+///                     items=vec![
+///                         MenuItem::submenu("File", vec![
+///                             MenuItem::action("Save", Command::Save).shortcut("Ctrl+S"),
+///                             MenuItem::separator(),
+///                             MenuItem::check("Autosave", Command::Autosave, self.autosave),
+///                         ]),
+///                     ],
+///                     onselect=|command| Msg::Run(command), />
+/// }
+/// ```
+pub struct Menu<T: 'static> {
+    props: MenuProps<T>,
+    node: NodeRef,
+    // Indexes of the active item and items whose submenus are open
+    path: Vec<usize>,
+    // The submenu of the active item is open, like after hovering it
+    expanded: bool,
+}
+
+/// Creates a menu which takes focus in a popup of `MenuButton` or `ContextMenu`.
+pub(crate) fn popup<T, MSG>(id: String, items: Vec<MenuItem<T>>, label: String, edge: Edge,
+                            onselect: fn(T) -> MSG, onclose: fn(()) -> MSG) -> VNode<MSG>
+where
+    T: Clone + PartialEq + 'static,
+    MSG: 'static,
+{
+    let (mut props, link) = VComp::lazy::<Menu<T>>();
+    props.id = id;
+    props.items = items;
+    props.label = label;
+    props.autofocus = Some(edge);
+    props.onselect = link.callback(onselect);
+    props.onclose = link.callback(onclose);
+    VComp::new::<Menu<T>>(props, link).into()
+}
+
+fn skipped<T>(items: &[MenuItem<T>]) -> Vec<usize> {
+    items.iter().enumerate()
+        .filter(|&(_, item)| item.is_separator())
+        .map(|(index, _)| index)
+        .collect()
+}
+
+impl<T> Menu<T>
+where
+    T: Clone + PartialEq + 'static,
+{
+    fn is_bar(&self) -> bool {
+        self.props.orientation == Orientation::Horizontal
+    }
+
+    /// Returns items of the menu at the depth of the path.
+    fn items_at(&self, depth: usize) -> &[MenuItem<T>] {
+        self.path[..depth].iter().fold(&self.props.items[..], |items, index| items[*index].items())
+    }
+
+    fn active(&self) -> Option<&MenuItem<T>> {
+        let depth = self.path.len().checked_sub(1)?;
+        self.items_at(depth).get(self.path[depth])
+    }
+
+    fn is_valid(&self) -> bool {
+        (0..self.path.len()).all(|depth| {
+            let items = self.items_at(depth);
+            match items.get(self.path[depth]) {
+                Some(item) => depth + 1 == self.path.len() || item.has_submenu(),
+                None => false,
+            }
+        })
+    }
+
+    fn edge(items: &[MenuItem<T>], edge: Edge) -> Option<usize> {
+        let key = if edge == Edge::First { "Home" } else { "End" };
+        roving_index(key, 0, items.len(), Orientation::Vertical, &skipped(items))
+    }
+
+    /// Moves into the submenu of the active item.
+    fn open_submenu(&mut self, edge: Edge) -> bool {
+        let next = match self.active() {
+            Some(item) if item.has_submenu() => Menu::edge(item.items(), edge),
+            _ => None,
+        };
+        match next {
+            Some(next) => {
+                self.path.push(next);
+                self.expanded = false;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves between items of a menubar and keeps a submenu open.
+    fn move_bar(&mut self, key: &str) -> bool {
+        let open = self.path.len() > 1 || self.expanded;
+        let items = &self.props.items;
+        let current = self.path.first().cloned().unwrap_or(0);
+        match roving_index(key, current, items.len(), Orientation::Horizontal, &skipped(items)) {
+            Some(next) => {
+                self.path = vec![next];
+                self.expanded = false;
+                if open {
+                    self.open_submenu(Edge::First);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn close(&mut self) -> ShouldRender {
+        self.path.clear();
+        self.expanded = false;
+        self.props.onclose.emit(());
+        true
+    }
+
+    fn activate(&mut self, path: Vec<usize>) -> ShouldRender {
+        self.path = path;
+        self.expanded = false;
+        let value = match self.active() {
+            Some(item) if item.disabled => return true,
+            Some(&MenuItem { kind: ItemKind::Action(ref value), .. })
+            | Some(&MenuItem { kind: ItemKind::Check(ref value, _), .. }) => value.clone(),
+            Some(&MenuItem { kind: ItemKind::Submenu(_), .. }) => {
+                self.open_submenu(Edge::First);
+                return true;
+            }
+            _ => return false,
+        };
+        self.props.onselect.emit(value);
+        self.close()
+    }
+
+    /// Activates the next item of the current menu which starts with the letter.
+    fn find(&mut self, letter: &str) -> ShouldRender {
+        let depth = match self.path.len().checked_sub(1) {
+            Some(depth) => depth,
+            None => return false,
+        };
+        let letter = letter.to_lowercase();
+        let current = self.path[depth];
+        let found = {
+            let items = self.items_at(depth);
+            (current + 1..items.len()).chain(0..current + 1).find(|index| {
+                let item = &items[*index];
+                !item.is_separator() && item.label.to_lowercase().starts_with(&letter)
+            })
+        };
+        match found {
+            Some(index) => {
+                self.path[depth] = index;
+                self.expanded = false;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn key(&mut self, key: &str) -> ShouldRender {
+        let depth = self.path.len();
+        let bar = self.is_bar();
+        if depth == 0 {
+            let edge = match key {
+                "ArrowDown" | "Home" => Edge::First,
+                "ArrowRight" if bar => Edge::First,
+                "ArrowUp" | "End" => Edge::Last,
+                "ArrowLeft" if bar => Edge::Last,
+                "Escape" | "Tab" => return self.close(),
+                _ => return false,
+            };
+            self.path = Menu::edge(&self.props.items, edge).into_iter().collect();
+            return true;
+        }
+        // Items of the menubar are in a row, items of menus are in a column
+        let row = bar && depth == 1;
+        match key {
+            "ArrowDown" | "ArrowUp" if row => {
+                self.open_submenu(if key == "ArrowDown" { Edge::First } else { Edge::Last })
+            }
+            "ArrowLeft" | "ArrowRight" if row => self.move_bar(key),
+            "ArrowDown" | "ArrowUp" | "Home" | "End" => {
+                let orientation = if row { Orientation::Horizontal } else { Orientation::Vertical };
+                let next = {
+                    let items = self.items_at(depth - 1);
+                    roving_index(key, self.path[depth - 1], items.len(), orientation, &skipped(items))
+                };
+                match next {
+                    Some(next) => {
+                        self.path[depth - 1] = next;
+                        self.expanded = false;
+                        true
+                    }
+                    None => false,
+                }
+            }
+            "ArrowRight" => self.open_submenu(Edge::First) || (bar && self.move_bar(key)),
+            "ArrowLeft" if bar && depth == 2 => self.move_bar(key),
+            "ArrowLeft" | "Escape" if depth > 1 => {
+                self.path.pop();
+                self.expanded = false;
+                true
+            }
+            "Escape" | "Tab" => self.close(),
+            "Enter" | " " => {
+                let path = self.path.clone();
+                self.activate(path)
+            }
+            _ if key.chars().count() == 1 => self.find(key),
+            _ => false,
+        }
+    }
+
+    fn item_id(&self, path: &[usize]) -> String {
+        let indexes: Vec<String> = path.iter().map(usize::to_string).collect();
+        format!("{}-item-{}", self.props.id, indexes.join("-"))
+    }
+
+    /// Returns `true` if the submenu of the item at the path is open.
+    fn is_open(&self, path: &[usize]) -> bool {
+        self.path.starts_with(path) && (self.path.len() > path.len() || self.expanded)
+    }
+
+    fn view_submenu(&self, items: &[MenuItem<T>], parent: &[usize]) -> Html<Msg> {
+        html! {
+            <ul role="menu", class="menu-submenu", aria-labelledby=self.item_id(parent), hidden=!self.is_open(parent),>
+                { for items.iter().enumerate().map(|(index, item)| self.view_item(item, parent, index)) }
+            </ul>
+        }
+    }
+
+    fn view_item(&self, item: &MenuItem<T>, prefix: &[usize], index: usize) -> Html<Msg> {
+        if item.is_separator() {
+            return html! { <li role="separator", class="menu-separator",></li> };
+        }
+        let mut path = prefix.to_vec();
+        path.push(index);
+        let active = self.path == path;
+        let (role, checked) = match item.kind {
+            ItemKind::Check(_, checked) => ("menuitemcheckbox", Some(if checked { "true" } else { "false" })),
+            _ => ("menuitem", None),
+        };
+        let (popup, expanded, submenu) = match item.kind {
+            ItemKind::Submenu(ref items) => {
+                let open = if self.is_open(&path) { "true" } else { "false" };
+                (Some("menu"), Some(open), self.view_submenu(items, &path).into())
+            }
+            _ => (None, None, VNode::from(VText::new(""))),
+        };
+        let shortcut = match item.shortcut {
+            Some(ref shortcut) => html! { <kbd class="menu-shortcut",>{ shortcut }</kbd> },
+            None => html! { <span class="menu-shortcut",></span> },
+        };
+        let (hover, click) = (path.clone(), path.clone());
+        html! {
+            <li role=role, id=self.item_id(&path), class=if active { "menu-item active" } else { "menu-item" },
+                aria-checked=checked, aria-disabled=if item.disabled { Some("true") } else { None },
+                aria-haspopup=popup, aria-expanded=expanded, aria-keyshortcuts=item.shortcut.as_ref(),
+                onmouseenter=move |_| Msg::Hover(hover.clone()),
+                onclick=move |_| Msg::Activate(click.clone()),>
+                <span class="menu-label",>{ &item.label }</span>
+                { shortcut }
+                { submenu }
+            </li>
+        }
+    }
+}
+
+impl<T> Component for Menu<T>
+where
+    T: Clone + PartialEq + 'static,
+{
+    type Msg = Msg;
+    type Properties = MenuProps<T>;
+
+    fn create(props: Self::Properties, sender: AppSender<Msg>) -> Self {
+        let path = match props.autofocus {
+            Some(edge) => {
+                sender.send_soon(Msg::Autofocus);
+                Menu::edge(&props.items, edge).into_iter().collect()
+            }
+            None => Vec::new(),
+        };
+        Menu {
+            props,
+            node: NodeRef::default(),
+            path,
+            expanded: false,
+        }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Key(key) => self.key(&key),
+            Msg::Hover(path) => {
+                if self.path == path {
+                    return false;
+                }
+                self.path = path;
+                self.expanded = self.active().map_or(false, MenuItem::has_submenu);
+                true
+            }
+            Msg::Activate(path) => self.activate(path),
+            Msg::Focus => {
+                if self.path.is_empty() {
+                    self.path = Menu::edge(&self.props.items, Edge::First).into_iter().collect();
+                    return true;
+                }
+                false
+            }
+            Msg::Blur => {
+                // A menubar stays on the page, so only its submenus are closed
+                if self.is_bar() && !self.path.is_empty() {
+                    self.path.clear();
+                    self.expanded = false;
+                    return true;
+                }
+                false
+            }
+            Msg::Autofocus => {
+                self.node.focus();
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        if !self.is_valid() {
+            self.path.clear();
+            self.expanded = false;
+        }
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let bar = self.is_bar();
+        let active = if self.path.is_empty() { None } else { Some(self.item_id(&self.path)) };
+        html! {
+            <ul role=if bar { "menubar" } else { "menu" }, class=if bar { "menubar" } else { "menu" },
+                id=&self.props.id, ref=&self.node, tabindex="0",
+                aria-label=&self.props.label,
+                aria-orientation=if bar { "horizontal" } else { "vertical" },
+                aria-activedescendant=active,
+                onkeydown=|e: KeyData| Msg::Key(e.key),
+                onfocus=|_| Msg::Focus, onblur=|_| Msg::Blur,>
+                { for self.props.items.iter().enumerate().map(|(index, item)| self.view_item(item, &[], index)) }
+            </ul>
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! This module contains a button which opens a menu next to it.
+
+use callback::Callback;
+use component::{Component, ShouldRender, Render};
+use focus;
+use host::{self, HostAttributes};
+use html::{AppSender, Html};
+use position::{self, Align, Placement, Position, PositionOptions, Side};
+use services::layout::{LayoutEvent, LayoutHandle, LayoutService};
+use virtual_dom::{NodeRef, VNode, VPortal, VText};
+use super::menu::{self, Edge, MenuItem};
+
+/// Properties of the `MenuButton` component.
+#[derive(Clone, PartialEq)]
+pub struct MenuButtonProps<T> {
+    /// A prefix of ids of the button and the menu, unique on the page.
+    /// The button gets the `{id}-button` id and the menu gets `{id}-menu`.
+    pub id: String,
+    /// Items of the menu.
+    pub items: Vec<MenuItem<T>>,
+    /// A label of the menu for screen readers.
+    pub label: String,
+    /// Renders the button with attributes and listeners which it spreads
+    /// onto its `button` element.
+    pub anchor: Render<HostAttributes>,
+    /// Where the menu is placed.
+    pub position: PositionOptions,
+    /// Called with the value of an item which a user selected.
+    pub onselect: Callback<T>,
+}
+
+impl<T> Default for MenuButtonProps<T> {
+    fn default() -> Self {
+        MenuButtonProps {
+            id: "menu-button".into(),
+            items: Vec::new(),
+            label: String::new(),
+            anchor: Render::default(),
+            position: PositionOptions::new(Placement::new(Side::Bottom, Align::Start)),
+            onselect: Callback::default(),
+        }
+    }
+}
+
+/// A message of the `MenuButton` component.
+pub enum Msg<T> {
+    /// The button was clicked.
+    Toggle,
+    /// A key was pressed on the button.
+    Key(String),
+    /// Opens the menu with the item active.
+    Open(Edge),
+    /// A user selected an item.
+    Select(T),
+    /// A user closed the menu.
+    Close,
+    /// The menu was measured or a pointer was pressed outside of it.
+    Layout(LayoutEvent),
+}
+
+/// A button with `aria-haspopup` which opens a menu in the body next to it.
+/// A click or `ArrowDown` opens the menu with the first item active and
+/// `ArrowUp` with the last one. Focus returns to the button when the menu
+/// is closed with keys or an item was selected.
+///
+/// ```rust,ignore
+/// html! {
+///     <MenuButton<Action>: id="row-actions", label="Actions",
+///                          items=vec![MenuItem::action("Edit", Action::Edit), MenuItem::action("Delete", Action::Delete)],
+///                          anchor=|host: HostAttributes| html! { <button ..&host,>{ "Actions" }</button> },
+///                          onselect=|action| Msg::Row(action), />
+/// }
+/// ```
+pub struct MenuButton<T: 'static> {
+    props: MenuButtonProps<T>,
+    sender: AppSender<Msg<T>>,
+    layout: LayoutService<Msg<T>>,
+    tracker: Option<LayoutHandle>,
+    floating: NodeRef,
+    position: Option<Position>,
+    open: Option<Edge>,
+}
+
+impl<T> MenuButton<T>
+where
+    T: Clone + PartialEq + 'static,
+{
+    fn open(&mut self, edge: Edge) -> ShouldRender {
+        if self.open.is_some() {
+            return false;
+        }
+        self.open = Some(edge);
+        let anchor = format!("{}-button", self.props.id);
+        self.tracker = Some(self.layout.track(&anchor, &self.floating, Msg::Layout));
+        true
+    }
+
+    fn close(&mut self) -> ShouldRender {
+        self.tracker = None;
+        self.position = None;
+        self.open.take().is_some()
+    }
+
+    fn anchor(&self) -> HostAttributes {
+        HostAttributes::new()
+            .attr("id", format!("{}-button", self.props.id))
+            .attr("aria-haspopup", "menu")
+            .attr("aria-expanded", self.open.is_some())
+            .attr("aria-controls", format!("{}-menu", self.props.id))
+            .listen("click", host::forward(&self.sender, |_| Msg::Toggle))
+            .listen("keydown", host::forward(&self.sender, |event| Msg::Key(host::event_key(&event))))
+    }
+}
+
+impl<T> Component for MenuButton<T>
+where
+    T: Clone + PartialEq + 'static,
+{
+    type Msg = Msg<T>;
+    type Properties = MenuButtonProps<T>;
+
+    fn create(props: Self::Properties, sender: AppSender<Msg<T>>) -> Self {
+        MenuButton {
+            props,
+            layout: LayoutService::new(sender.clone()),
+            sender,
+            tracker: None,
+            floating: NodeRef::default(),
+            position: None,
+            open: None,
+        }
+    }
+
+    fn update(&mut self, msg: Msg<T>) -> ShouldRender {
+        match msg {
+            Msg::Toggle => {
+                if self.open.is_some() {
+                    self.close()
+                } else {
+                    self.open(Edge::First)
+                }
+            }
+            Msg::Key(key) => match key.as_str() {
+                "ArrowDown" => self.open(Edge::First),
+                "ArrowUp" => self.open(Edge::Last),
+                _ => false,
+            },
+            Msg::Open(edge) => self.open(edge),
+            Msg::Select(value) => {
+                self.props.onselect.emit(value);
+                false
+            }
+            Msg::Close => {
+                focus::focus_id(&format!("{}-button", self.props.id));
+                self.close()
+            }
+            Msg::Layout(LayoutEvent::Measured(layout)) => {
+                self.position = Some(position::compute(&layout, &self.props.position));
+                true
+            }
+            Msg::Layout(LayoutEvent::Outside) => self.close(),
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<Msg<T>> {
+        let (style, side) = match self.position {
+            Some(ref position) => (position.style(), position.placement.side),
+            None => (position::UNMEASURED_STYLE.to_owned(), self.props.position.placement.side),
+        };
+        let menu: VNode<Msg<T>> = match self.open {
+            Some(edge) => menu::popup(format!("{}-menu", self.props.id), self.props.items.clone(),
+                                      self.props.label.clone(), edge, Msg::Select, |_| Msg::Close),
+            None => VNode::from(VText::new("")),
+        };
+        let mut portal = VPortal::body();
+        portal.add_child(html! {
+            <div class="menu-popup", ref=&self.floating, style=style, data-side=side.name(), hidden=self.open.is_none(),>
+                { menu }
+            </div>
+        }.into());
+        html! {
+            <span class="menu-button",>
+                { self.props.anchor.view(self.anchor()) }
+                { portal }
+            </span>
+        }
+    }
+}
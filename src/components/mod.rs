@@ -0,0 +1,77 @@
+//! This module contains built-in components.
+
+pub mod lazy;
+pub mod focus_trap;
+pub mod link;
+pub mod input;
+pub mod textarea;
+pub mod select;
+pub mod checkbox;
+pub mod radio;
+pub mod redirect;
+pub mod transition;
+pub mod toaster;
+pub mod progress_bar;
+pub mod modal_host;
+pub mod js_island;
+pub mod router;
+pub mod keep_alive;
+pub mod error_boundary;
+pub mod virtual_list;
+pub mod infinite_list;
+pub mod combobox;
+pub mod data_table;
+pub mod chart;
+pub mod tabs;
+pub mod accordion;
+pub mod disclosure;
+pub mod tooltip;
+pub mod popover;
+pub mod menu;
+pub mod menu_button;
+pub mod context_menu;
+pub mod calendar;
+pub mod date_picker;
+pub mod time_picker;
+pub mod rich_text_editor;
+pub mod masked_input;
+#[cfg(feature = "markdown")]
+pub mod markdown;
+
+pub use self::lazy::Lazy;
+pub use self::focus_trap::FocusTrap;
+pub use self::link::Link;
+pub use self::input::Input;
+pub use self::textarea::TextArea;
+pub use self::select::Select;
+pub use self::checkbox::Checkbox;
+pub use self::radio::Radio;
+pub use self::redirect::Redirect;
+pub use self::transition::Transition;
+pub use self::toaster::Toaster;
+pub use self::progress_bar::ProgressBar;
+pub use self::modal_host::ModalHost;
+pub use self::js_island::JsIsland;
+pub use self::router::Router;
+pub use self::keep_alive::KeepAlive;
+pub use self::error_boundary::ErrorBoundary;
+pub use self::virtual_list::VirtualList;
+pub use self::infinite_list::InfiniteList;
+pub use self::combobox::Combobox;
+pub use self::data_table::DataTable;
+pub use self::chart::{BarChart, LineChart, PieChart, Sparkline};
+pub use self::tabs::Tabs;
+pub use self::accordion::Accordion;
+pub use self::disclosure::Disclosure;
+pub use self::tooltip::Tooltip;
+pub use self::popover::Popover;
+pub use self::menu::Menu;
+pub use self::menu_button::MenuButton;
+pub use self::context_menu::ContextMenu;
+pub use self::calendar::Calendar;
+pub use self::date_picker::{DatePicker, DateRangePicker};
+pub use self::time_picker::TimePicker;
+pub use self::rich_text_editor::RichTextEditor;
+pub use self::masked_input::MaskedInput;
+#[cfg(feature = "markdown")]
+pub use self::markdown::Markdown;
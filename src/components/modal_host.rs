@@ -0,0 +1,135 @@
+//! This module contains a component which shows the stack of modals.
+
+use std::rc::Rc;
+use stdweb::Value;
+use stdweb::unstable::TryInto;
+use component::{Component, Link, ShouldRender};
+use components::FocusTrap;
+use html::{AppSender, Html};
+use modal::{self, Entry, ModalHandle, ModalId, ModalService};
+use virtual_dom::{VComp, VPortal};
+
+/// Messages of the `ModalHost` component.
+pub enum Msg {
+    /// A modal was opened or closed.
+    Changed,
+    /// The backdrop of the modal was clicked.
+    Backdrop(ModalId),
+    /// The Escape key was pressed inside of the modal.
+    Escape(ModalId),
+}
+
+/// A host of modals. Render it once in an app, it renders modals into
+/// `body` with a `div.modal-layer` per modal which has a `div.modal-backdrop`
+/// and a `div.modal` with the component inside of a `div[role=dialog]`.
+/// Focus is trapped in the top modal and returned back when it's closed,
+/// the page isn't scrolled while any modal is opened.
+pub struct ModalHost {
+    entries: Vec<Rc<Entry>>,
+    _listener: ModalHandle,
+}
+
+impl ModalHost {
+    fn dismiss(&self, id: ModalId) {
+        let dismissible = self.entries.last()
+            .map(|entry| entry.id == id && entry.options.dismissible)
+            .unwrap_or(false);
+        if dismissible {
+            modal::dismiss(id);
+        }
+    }
+
+    fn view_entry(&self, entry: &Entry) -> Html<Msg> {
+        let id = entry.id;
+        let top = modal::top() == Some(id);
+        let content = VComp::from_parts(None, (entry.render)());
+        html! {
+            <div class=("modal-layer", entry.options.class.as_str()),>
+                <div class="modal-backdrop", onclick=move |_| Msg::Backdrop(id),></div>
+                <FocusTrap: class="modal", paused=!top, onescape=move |_| Msg::Escape(id),>
+                    <div class="modal-dialog", role="dialog", aria-modal="true",>{ content }</div>
+                </FocusTrap>
+            </div>
+        }
+    }
+}
+
+impl Component for ModalHost {
+    type Msg = Msg;
+    type Properties = ();
+
+    fn create(_: Self::Properties, sender: AppSender<Msg>) -> Self {
+        let listener = ModalService::new(sender).listen(|| Msg::Changed);
+        ModalHost {
+            entries: modal::entries(),
+            _listener: listener,
+        }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Changed => {
+                self.entries = modal::entries();
+                true
+            }
+            Msg::Backdrop(id) | Msg::Escape(id) => {
+                self.dismiss(id);
+                false
+            }
+        }
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let mut portal = VPortal::body();
+        for entry in &self.entries {
+            portal.add_child(self.view_entry(entry).into());
+        }
+        if !self.entries.is_empty() {
+            portal.add_child(VComp::new::<ScrollLock>((), Link::new()).into());
+        }
+        html! {
+            <div class="modal-host",>{ portal }</div>
+        }
+    }
+}
+
+/// Hides the overflow of `body` while it's mounted.
+struct ScrollLock {
+    previous: Option<String>,
+}
+
+impl Component for ScrollLock {
+    type Msg = ();
+    type Properties = ();
+
+    fn create(_: Self::Properties, sender: AppSender<()>) -> Self {
+        // Locks after the mount, so trees which aren't mounted don't touch the page
+        sender.send_soon(());
+        ScrollLock { previous: None }
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        if self.previous.is_none() {
+            let previous: Value = js! {
+                var style = document.body.style;
+                var previous = style.overflow;
+                style.overflow = "hidden";
+                return previous;
+            };
+            self.previous = Some(previous.try_into().unwrap_or_default());
+        }
+        false
+    }
+
+    fn view(&self) -> Html<()> {
+        html! { <span class="modal-scroll-lock",></span> }
+    }
+
+    fn destroy(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            js! { @(no_return)
+                document.body.style.overflow = @{previous};
+            }
+        }
+    }
+}
@@ -0,0 +1,264 @@
+//! This module contains a popover which shows a floating dialog next
+//! to an anchor.
+
+use std::time::Duration;
+use callback::Callback;
+use component::{Component, ShouldRender, Render};
+use focus;
+use host::{self, HostAttributes};
+use html::{AppSender, Html, KeyData};
+use position::{self, Align, Placement, Position, PositionOptions, Side};
+use services::layout::{LayoutEvent, LayoutHandle, LayoutService};
+use services::timeout::{TimeoutHandle, TimeoutService};
+use virtual_dom::{NodeRef, VNode, VPortal, VText};
+use super::tooltip::HIDE_DELAY;
+
+/// What opens a popover.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+    /// A click on the anchor toggles it.
+    Click,
+    /// Hovering the anchor opens it after a delay.
+    Hover,
+    /// Focus of the anchor opens it, for content without focusable elements.
+    Focus,
+}
+
+/// Properties of the `Popover` component.
+#[derive(Clone, PartialEq)]
+pub struct PopoverProps {
+    /// An id of the dialog, unique on the page. The anchor gets
+    /// the `{id}-anchor` id.
+    pub id: String,
+    /// Renders the anchor with attributes and listeners which it spreads
+    /// onto its button.
+    pub anchor: Render<HostAttributes>,
+    /// Renders the content of the open popover.
+    pub content: Render<()>,
+    /// Where the popover is placed.
+    pub position: PositionOptions,
+    /// What opens the popover.
+    pub trigger: Trigger,
+    /// A pause of hovering before the popover is opened by `Trigger::Hover`.
+    pub delay: Duration,
+    /// The state of the popover, which makes it controlled: triggers
+    /// only call `onchange` and a parent changes it.
+    pub open: Option<bool>,
+    /// A label of the dialog for screen readers.
+    pub label: String,
+    /// Called with a new state when the popover was opened or closed.
+    pub onchange: Callback<bool>,
+}
+
+impl Default for PopoverProps {
+    fn default() -> Self {
+        PopoverProps {
+            id: "popover".into(),
+            anchor: Render::default(),
+            content: Render::default(),
+            position: PositionOptions::new(Placement::new(Side::Bottom, Align::Start)),
+            trigger: Trigger::Click,
+            delay: Duration::from_millis(300),
+            open: None,
+            label: String::new(),
+            onchange: Callback::default(),
+        }
+    }
+}
+
+/// A message of the `Popover` component.
+pub enum Msg {
+    /// The anchor was clicked.
+    Toggle,
+    /// The pointer entered the anchor or the popover.
+    Enter,
+    /// The pointer left the anchor or the popover.
+    Leave,
+    /// The anchor got focus.
+    Focus,
+    /// The anchor lost focus.
+    Blur,
+    /// A key was pressed on the anchor.
+    Key(String),
+    /// A key was pressed inside of the popover.
+    ContentKey(String),
+    /// The delay elapsed.
+    Open,
+    /// The pause after leaving elapsed.
+    Close,
+    /// The popover was measured or a pointer was pressed outside of it.
+    Layout(LayoutEvent),
+}
+
+/// A dialog which is rendered into the body next to its anchor. It flips
+/// and shifts to stay in the viewport and follows the anchor when the page
+/// is scrolled. The anchor gets `aria-haspopup`, `aria-expanded` and
+/// `aria-controls`, and `Escape` or a press outside closes the popover.
+///
+/// ```rust,ignore
+/// html! {
+///     <Popover: id="filters", label="Filters",
+///               anchor=|host: HostAttributes| html! { <button ..&host,>{ "Filters" }</button> },
+///               content=|_| view_filters(), />
+/// }
+/// ```
+pub struct Popover {
+    props: PopoverProps,
+    sender: AppSender<Msg>,
+    timeout: TimeoutService<Msg>,
+    layout: LayoutService<Msg>,
+    timer: Option<TimeoutHandle>,
+    tracker: Option<LayoutHandle>,
+    floating: NodeRef,
+    position: Option<Position>,
+    // The state of an uncontrolled popover
+    current: bool,
+}
+
+impl Popover {
+    fn is_open(&self) -> bool {
+        self.props.open.unwrap_or(self.current)
+    }
+
+    fn set_open(&mut self, open: bool) -> ShouldRender {
+        self.timer = None;
+        if open == self.is_open() {
+            return false;
+        }
+        self.current = open;
+        self.props.onchange.emit(open);
+        self.track();
+        true
+    }
+
+    /// Tracks the layout of the open popover.
+    fn track(&mut self) {
+        if !self.is_open() {
+            self.tracker = None;
+            self.position = None;
+        } else if self.tracker.is_none() {
+            let anchor = format!("{}-anchor", self.props.id);
+            self.tracker = Some(self.layout.track(&anchor, &self.floating, Msg::Layout));
+        }
+    }
+
+    fn close_later(&mut self) {
+        if self.is_open() {
+            self.timer = Some(self.timeout.spawn(HIDE_DELAY, || Msg::Close));
+        }
+    }
+
+    fn anchor(&self) -> HostAttributes {
+        let host = HostAttributes::new()
+            .attr("id", format!("{}-anchor", self.props.id))
+            .attr("aria-haspopup", "dialog")
+            .attr("aria-expanded", self.is_open())
+            .attr("aria-controls", &self.props.id)
+            .listen("keydown", host::forward(&self.sender, |event| Msg::Key(host::event_key(&event))));
+        match self.props.trigger {
+            Trigger::Click => host.listen("click", host::forward(&self.sender, |_| Msg::Toggle)),
+            Trigger::Hover => host
+                .listen("mouseenter", host::forward(&self.sender, |_| Msg::Enter))
+                .listen("mouseleave", host::forward(&self.sender, |_| Msg::Leave)),
+            Trigger::Focus => host
+                .listen("focus", host::forward(&self.sender, |_| Msg::Focus))
+                .listen("blur", host::forward(&self.sender, |_| Msg::Blur)),
+        }
+    }
+}
+
+impl Component for Popover {
+    type Msg = Msg;
+    type Properties = PopoverProps;
+
+    fn create(props: Self::Properties, sender: AppSender<Msg>) -> Self {
+        let mut popover = Popover {
+            props,
+            timeout: TimeoutService::new(sender.clone()),
+            layout: LayoutService::new(sender.clone()),
+            sender,
+            timer: None,
+            tracker: None,
+            floating: NodeRef::default(),
+            position: None,
+            current: false,
+        };
+        popover.track();
+        popover
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        let hover = self.props.trigger == Trigger::Hover;
+        match msg {
+            Msg::Toggle => {
+                let open = !self.is_open();
+                self.set_open(open)
+            }
+            Msg::Enter if hover => {
+                self.timer = None;
+                if !self.is_open() {
+                    self.timer = Some(self.timeout.spawn(self.props.delay, || Msg::Open));
+                }
+                false
+            }
+            Msg::Leave if hover => {
+                self.timer = None;
+                self.close_later();
+                false
+            }
+            Msg::Enter | Msg::Leave => false,
+            Msg::Focus => self.set_open(true),
+            Msg::Blur => {
+                self.close_later();
+                false
+            }
+            Msg::Key(ref key) if key == "Escape" => self.set_open(false),
+            Msg::ContentKey(ref key) if key == "Escape" => {
+                // The anchor is rendered by the parent
+                focus::focus_id(&format!("{}-anchor", self.props.id));
+                self.set_open(false)
+            }
+            Msg::Key(_) | Msg::ContentKey(_) => false,
+            Msg::Open => self.set_open(true),
+            Msg::Close | Msg::Layout(LayoutEvent::Outside) => self.set_open(false),
+            Msg::Layout(LayoutEvent::Measured(layout)) => {
+                self.position = Some(position::compute(&layout, &self.props.position));
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        self.track();
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let open = self.is_open();
+        let (style, side) = match self.position {
+            Some(ref position) => (position.style(), position.placement.side),
+            None => (position::UNMEASURED_STYLE.to_owned(), self.props.position.placement.side),
+        };
+        let content = if open { self.props.content.view(()) } else { VNode::from(VText::new("")) };
+        let label = if self.props.label.is_empty() { None } else { Some(&self.props.label) };
+        let mut portal = VPortal::body();
+        portal.add_child(html! {
+            <div role="dialog", class="popover", id=&self.props.id, ref=&self.floating,
+                 style=style, data-side=side.name(), aria-label=label, hidden=!open,
+                 onkeydown=|e: KeyData| Msg::ContentKey(e.key),
+                 onmouseenter=|_| Msg::Enter, onmouseleave=|_| Msg::Leave,>
+                { content }
+            </div>
+        }.into());
+        html! {
+            <span class="popover-anchor",>
+                { self.props.anchor.view(self.anchor()) }
+                { portal }
+            </span>
+        }
+    }
+}
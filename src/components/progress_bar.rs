@@ -0,0 +1,114 @@
+//! This module contains a loading bar of in-flight work of the `progress` registry.
+
+use std::time::Duration;
+use component::{Component, ShouldRender};
+use html::{AppSender, Html};
+use progress::{self, ProgressHandle, ProgressService};
+use services::interval::{IntervalHandle, IntervalService};
+use services::timeout::{TimeoutHandle, TimeoutService};
+
+/// How often the bar moves while work is in flight.
+const TICK: Duration = Duration::from_millis(200);
+/// How long the full bar is shown after work is finished.
+const FINISH: Duration = Duration::from_millis(300);
+
+/// Properties of the `ProgressBar` component.
+#[derive(Default)]
+pub struct ProgressBarProps {
+    /// Classes of the bar.
+    pub class: String,
+}
+
+/// Messages of the `ProgressBar` component.
+pub enum Msg {
+    /// The count of in-flight work changed.
+    Changed(usize),
+    /// The bar moves forward.
+    Tick,
+    /// The full bar is hidden.
+    Hide,
+}
+
+/// A bar at the top of a page which is shown while work is in flight.
+/// It renders a `div.progress-bar` with the `progressbar` role and a
+/// `div.progress-bar-value` with the width of the progress. The bar moves
+/// slower as it gets closer to the end, since the real amount of work
+/// isn't known, and fills up when all work is finished.
+pub struct ProgressBar {
+    props: ProgressBarProps,
+    value: f64,
+    visible: bool,
+    interval: IntervalService<Msg>,
+    timeout: TimeoutService<Msg>,
+    ticker: Option<IntervalHandle>,
+    hiding: Option<TimeoutHandle>,
+    _listener: ProgressHandle,
+}
+
+impl ProgressBar {
+    fn sync(&mut self, count: usize) {
+        if count > 0 {
+            self.hiding = None;
+            if !self.visible || self.ticker.is_none() {
+                if !self.visible {
+                    self.value = 0.1;
+                }
+                self.visible = true;
+                self.ticker = Some(self.interval.spawn(TICK, || Msg::Tick));
+            }
+        } else if self.visible && self.ticker.is_some() {
+            self.value = 1.0;
+            self.ticker = None;
+            self.hiding = Some(self.timeout.spawn(FINISH, || Msg::Hide));
+        }
+    }
+}
+
+impl Component for ProgressBar {
+    type Msg = Msg;
+    type Properties = ProgressBarProps;
+
+    fn create(props: Self::Properties, sender: AppSender<Msg>) -> Self {
+        let listener = ProgressService::new(sender.clone()).listen(Msg::Changed);
+        let mut bar = ProgressBar {
+            props,
+            value: 0.0,
+            visible: false,
+            interval: IntervalService::new(sender.clone()),
+            timeout: TimeoutService::new(sender),
+            ticker: None,
+            hiding: None,
+            _listener: listener,
+        };
+        bar.sync(progress::in_flight());
+        bar
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Changed(count) => self.sync(count),
+            Msg::Tick => self.value += (0.9 - self.value) * 0.1,
+            Msg::Hide => {
+                self.hiding = None;
+                self.visible = false;
+                self.value = 0.0;
+            }
+        }
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let percent = (self.value * 100.0).round();
+        html! {
+            <div class=("progress-bar", self.props.class.as_str()), role="progressbar", aria-label="Loading",
+                 aria-valuemin="0", aria-valuemax="100", aria-valuenow=percent, hidden=!self.visible,>
+                <div class="progress-bar-value", style=format!("width: {}%;", percent),></div>
+            </div>
+        }
+    }
+}
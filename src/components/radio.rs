@@ -0,0 +1,67 @@
+//! This module contains a controlled radio button component.
+
+use callback::Callback;
+use component::{Component, ShouldRender};
+use html::{AppSender, Html, ChangeData};
+
+/// Properties of the `Radio` component.
+#[derive(Default, Clone, PartialEq)]
+pub struct RadioProps<T> {
+    /// A name of the group of radio buttons.
+    pub name: String,
+    /// A value which this button represents.
+    pub value: T,
+    /// The state of the button.
+    pub checked: bool,
+    /// Disables the button.
+    pub disabled: bool,
+    /// Called with the value of the button when a user selects it.
+    pub onselect: Callback<T>,
+}
+
+/// A message of the `Radio` component.
+pub enum Msg {
+    /// A user selected the button.
+    Selected,
+}
+
+/// A controlled radio button which represents a typed value.
+pub struct Radio<T: 'static> {
+    props: RadioProps<T>,
+}
+
+impl<T> Component for Radio<T>
+where
+    T: Clone + Default + 'static,
+{
+    type Msg = Msg;
+    type Properties = RadioProps<T>;
+
+    fn create(props: Self::Properties, _: AppSender<Msg>) -> Self {
+        Radio { props }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Selected => {
+                self.props.onselect.emit(self.props.value.clone());
+            }
+        }
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        html! {
+            <input type="radio",
+                   name=&self.props.name,
+                   checked=self.props.checked,
+                   disabled=self.props.disabled,
+                   onchange=|_: ChangeData| Msg::Selected, />
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! This module contains a component which redirects to a route when it's rendered.
+
+use component::{Component, ShouldRender};
+use html::{AppSender, Html};
+use router::{self, Routable};
+
+/// Properties of the `Redirect` component.
+pub struct RedirectProps<R> {
+    /// The route to redirect to.
+    pub to: Option<R>,
+    /// A path of the scope of nested routes. The route is relative to it.
+    pub base: String,
+}
+
+impl<R> Default for RedirectProps<R> {
+    fn default() -> Self {
+        RedirectProps {
+            to: None,
+            base: String::new(),
+        }
+    }
+}
+
+/// A node which replaces the current location with the route.
+/// Put it into a view of a route to redirect declaratively:
+///
+/// ```rust,ignore
+/// Route::Old => html! { <Redirect<Route>: to=Route::Home, /> },
+/// ```
+pub struct Redirect<R: Routable> {
+    props: RedirectProps<R>,
+}
+
+impl<R: Routable> Redirect<R> {
+    fn redirect(&self) {
+        if let Some(ref to) = self.props.to {
+            router::redirect_in(&self.props.base, to);
+        }
+    }
+}
+
+impl<R: Routable> Component for Redirect<R> {
+    type Msg = ();
+    type Properties = RedirectProps<R>;
+
+    fn create(props: Self::Properties, _: AppSender<()>) -> Self {
+        let redirect = Redirect { props };
+        redirect.redirect();
+        redirect
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let changed = self.props.to != props.to || self.props.base != props.base;
+        self.props = props;
+        if changed {
+            self.redirect();
+        }
+        false
+    }
+
+    fn view(&self) -> Html<()> {
+        html! {
+            <div></div>
+        }
+    }
+}
@@ -0,0 +1,460 @@
+//! This module contains a controlled editor of rich text on a
+//! `contenteditable` element.
+
+use serde_json::{self, Value as Json};
+use stdweb::Value;
+use callback::Callback;
+use component::{Component, ShouldRender};
+use html::{AppSender, Html};
+use rich_text::{Delta, Document, Mark, Selection};
+use virtual_dom::{NodeRef, VTag};
+
+/// Properties of the `RichTextEditor` component.
+#[derive(Clone, Default, PartialEq)]
+pub struct RichTextEditorProps {
+    /// The edited document. A parent applies deltas of `onchange` to it.
+    pub document: Document,
+    /// A label for screen readers.
+    pub label: String,
+    /// A text which is shown in the empty editor.
+    pub placeholder: String,
+    /// Disables editing.
+    pub readonly: bool,
+    /// Classes of the element.
+    pub class: String,
+    /// Called with a delta of a change which a user made.
+    pub onchange: Callback<Delta>,
+    /// Called when a user moved the caret or selected text.
+    pub onselect: Callback<Selection>,
+}
+
+/// A message of the `RichTextEditor` component.
+pub enum Msg {
+    /// The element is rendered and listeners could be added.
+    Mount,
+    /// A user changed the element to the document.
+    Input(Document),
+    /// A user selected text.
+    Select(Selection),
+    /// A shortcut toggled the mark of the selection.
+    Command(Mark),
+}
+
+/// Returns functions which read documents and offsets from elements and render
+/// documents back. Blocks are children of the root and items of its lists,
+/// and offsets count code points like chars of Rust.
+fn bridge() -> Value {
+    let bridge: Value = js! {
+        if (window.__yew_rich_text) {
+            return window.__yew_rich_text;
+        }
+        function heading(tag) {
+            return tag.length === 2 && tag[0] === "H" && "123456".indexOf(tag[1]) >= 0 ? parseInt(tag[1], 10) : 0;
+        }
+        function segments(root) {
+            var blocks = [];
+            var current = null;
+            function open(type, level) {
+                current = { type: type, level: level, nodes: [] };
+                blocks.push(current);
+                return current;
+            }
+            Array.prototype.forEach.call(root.childNodes, function(node) {
+                var tag = node.nodeType === 1 ? node.tagName : "";
+                var level = heading(tag);
+                if (tag === "UL" || tag === "OL") {
+                    Array.prototype.forEach.call(node.childNodes, function(item) {
+                        open(tag === "UL" ? "bullet" : "numbered").nodes.push(item);
+                    });
+                    current = null;
+                } else if (level || tag === "P" || tag === "DIV" || tag === "BLOCKQUOTE" || tag === "PRE") {
+                    var type = level ? "heading" : tag === "BLOCKQUOTE" ? "quote" : "paragraph";
+                    open(type, level || undefined).nodes.push(node);
+                    current = null;
+                } else {
+                    (current || open("paragraph")).nodes.push(node);
+                }
+            });
+            return blocks.length ? blocks : [{ type: "paragraph", nodes: [] }];
+        }
+        function length(text) {
+            return Array.from(text).length;
+        }
+        // Calls the visitor with text nodes of the nodes in order until it returns true
+        function texts(nodes, visit) {
+            for (var i = 0; i < nodes.length; i++) {
+                var node = nodes[i];
+                if (node.nodeType === 3 ? visit(node) : texts(node.childNodes, visit)) {
+                    return true;
+                }
+            }
+            return false;
+        }
+        function size(nodes) {
+            var total = 0;
+            texts(nodes, function(text) { total += length(text.data); return false; });
+            return total;
+        }
+        function contains(nodes, target) {
+            return nodes.some(function(node) { return node === target || node.contains(target); });
+        }
+        function parse(root) {
+            var space = [String.fromCharCode(10), String.fromCharCode(160)];
+            return JSON.stringify(segments(root).map(function(segment) {
+                var spans = [];
+                function inline(node, marks) {
+                    if (node.nodeType === 3) {
+                        var text = space.reduce(function(text, c) { return text.split(c).join(" "); }, node.data);
+                        if (text) {
+                            spans.push(Object.assign({ text: text }, marks));
+                        }
+                        return;
+                    }
+                    if (node.nodeType !== 1) {
+                        return;
+                    }
+                    var next = Object.assign({}, marks);
+                    switch (node.tagName) {
+                        case "B": case "STRONG": next.bold = true; break;
+                        case "I": case "EM": next.italic = true; break;
+                        case "U": next.underline = true; break;
+                        case "CODE": next.code = true; break;
+                        case "A": next.link = node.getAttribute("href") || ""; break;
+                    }
+                    Array.prototype.forEach.call(node.childNodes, function(child) { inline(child, next); });
+                }
+                segment.nodes.forEach(function(node) { inline(node, {}); });
+                return { type: segment.type, level: segment.level, spans: spans };
+            }));
+        }
+        function offset(root, container, position) {
+            var blocks = segments(root);
+            var child = container === root ? root.childNodes[position] : null;
+            var start = 0;
+            for (var i = 0; i < blocks.length; i++) {
+                var nodes = blocks[i].nodes;
+                if (child && contains(nodes, child)) {
+                    return start;
+                }
+                if (container !== root && contains(nodes, container)) {
+                    var count = 0;
+                    var boundary = container.nodeType === 3 ? null : container.childNodes[position];
+                    texts(nodes, function(text) {
+                        if (text === container) {
+                            count += length(text.data.slice(0, position));
+                            return true;
+                        }
+                        if (boundary && (boundary === text || boundary.contains(text))) {
+                            return true;
+                        }
+                        // The end of an element stops at the first text after it
+                        var after = !container.contains(text) && container.compareDocumentPosition(text) & 4;
+                        if (!boundary && container.nodeType !== 3 && after) {
+                            return true;
+                        }
+                        count += length(text.data);
+                        return false;
+                    });
+                    return start + count;
+                }
+                start += size(nodes) + 1;
+            }
+            return Math.max(start - 1, 0);
+        }
+        function point(root, target) {
+            var blocks = segments(root);
+            var start = 0;
+            for (var i = 0; i < blocks.length; i++) {
+                var nodes = blocks[i].nodes;
+                var found = null;
+                var count = start;
+                texts(nodes, function(text) {
+                    var size = length(text.data);
+                    if (target <= count + size) {
+                        found = { node: text, offset: Array.from(text.data).slice(0, target - count).join("").length };
+                        return true;
+                    }
+                    count += size;
+                    return false;
+                });
+                if (found) {
+                    return found;
+                }
+                if (target <= count || i === blocks.length - 1) {
+                    return nodes.length ? { node: nodes[0], offset: 0 } : { node: root, offset: 0 };
+                }
+                start = count + 1;
+            }
+            return { node: root, offset: 0 };
+        }
+        function wrap(tag, node) {
+            var wrapper = document.createElement(tag);
+            wrapper.appendChild(node);
+            return wrapper;
+        }
+        function render(root, blocks) {
+            while (root.firstChild) {
+                root.removeChild(root.firstChild);
+            }
+            var list = null;
+            blocks.forEach(function(block) {
+                var element;
+                if (block.type === "bullet" || block.type === "numbered") {
+                    var tag = block.type === "bullet" ? "UL" : "OL";
+                    if (!list || list.tagName !== tag) {
+                        list = root.appendChild(document.createElement(tag));
+                    }
+                    element = list.appendChild(document.createElement("li"));
+                } else {
+                    list = null;
+                    var name = block.type === "heading" ? "h" + block.level : block.type === "quote" ? "blockquote" : "p";
+                    element = root.appendChild(document.createElement(name));
+                }
+                block.spans.forEach(function(span) {
+                    var node = document.createTextNode(span.text);
+                    node = span.code ? wrap("code", node) : node;
+                    node = span.underline ? wrap("u", node) : node;
+                    node = span.italic ? wrap("em", node) : node;
+                    node = span.bold ? wrap("strong", node) : node;
+                    if (typeof span.link === "string") {
+                        node = wrap("a", node);
+                        node.setAttribute("href", span.link);
+                    }
+                    element.appendChild(node);
+                });
+                if (!block.spans.length) {
+                    element.appendChild(document.createElement("br"));
+                }
+            });
+        }
+        function select(root, anchor, focus) {
+            var start = point(root, anchor);
+            var end = point(root, focus);
+            window.getSelection().setBaseAndExtent(start.node, start.offset, end.node, end.offset);
+        }
+        return window.__yew_rich_text = { parse: parse, offset: offset, render: render, select: select };
+    };
+    bridge
+}
+
+/// An editor which keeps the DOM of a `contenteditable` element and maps it
+/// to a `Document`. Edits of a user are sent as deltas to `onchange` and a
+/// parent applies them, which makes the editor controlled: the element is
+/// rendered again with the caret in place when its document differs from
+/// `document`, like after an edit of another user. `Ctrl+B`, `Ctrl+I` and
+/// `Ctrl+U` toggle marks and pasted text loses its formatting.
+///
+/// ```rust,ignore
+/// html! {
+///     <RichTextEditor: document=&self.note, label="Note", placeholder="Write a note",
+///                      onchange=|delta| Msg::Edit(delta), onselect=|selection| Msg::Select(selection), />
+/// }
+/// ```
+///
+/// Buttons of a toolbar make deltas with `Document::toggle_mark` and
+/// `Document::toggle_block` for the last selection. They should prevent
+/// default actions of `mousedown` to keep the selection in the editor.
+pub struct RichTextEditor {
+    props: RichTextEditorProps,
+    sender: AppSender<Msg>,
+    node: NodeRef,
+    // A JS object with the element, listeners and callbacks
+    mounted: Option<Value>,
+    // The document in the element, which is ahead of props until a parent applies a change
+    current: Document,
+    selection: Selection,
+}
+
+impl RichTextEditor {
+    fn mount(&mut self) {
+        let element = match self.node.get() {
+            Some(element) => element,
+            None => return,
+        };
+        let mut sender = self.sender.clone();
+        let input = move |json: String| {
+            let json: Json = serde_json::from_str(&json).unwrap_or(Json::Null);
+            sender.send(Msg::Input(Document::from_json(&json)));
+        };
+        let mut sender = self.sender.clone();
+        let select = move |anchor: f64, focus: f64| {
+            sender.send(Msg::Select(Selection { anchor: anchor as usize, focus: focus as usize }));
+        };
+        let mut sender = self.sender.clone();
+        let command = move |key: String| {
+            let mark = match key.as_str() {
+                "b" => Mark::Bold,
+                "i" => Mark::Italic,
+                _ => Mark::Underline,
+            };
+            sender.send(Msg::Command(mark));
+        };
+        let mounted: Value = js! {
+            var bridge = @{bridge()};
+            var element = @{element};
+            var mounted = { element: element, input: @{input}, select: @{select}, command: @{command} };
+            mounted.onInput = function() {
+                mounted.input(bridge.parse(element));
+            };
+            mounted.onKey = function(event) {
+                var key = event.key.toLowerCase();
+                if ((event.ctrlKey || event.metaKey) && !event.altKey && (key === "b" || key === "i" || key === "u")) {
+                    event.preventDefault();
+                    mounted.command(key);
+                }
+            };
+            mounted.onPaste = function(event) {
+                event.preventDefault();
+                var text = (event.clipboardData || window.clipboardData).getData("text/plain");
+                document.execCommand("insertText", false, text);
+            };
+            mounted.onSelection = function() {
+                var selection = window.getSelection();
+                if (!selection || !selection.rangeCount
+                    || !element.contains(selection.anchorNode) || !element.contains(selection.focusNode)) {
+                    return;
+                }
+                mounted.select(bridge.offset(element, selection.anchorNode, selection.anchorOffset),
+                               bridge.offset(element, selection.focusNode, selection.focusOffset));
+            };
+            element.addEventListener("input", mounted.onInput);
+            element.addEventListener("keydown", mounted.onKey);
+            element.addEventListener("paste", mounted.onPaste);
+            document.addEventListener("selectionchange", mounted.onSelection);
+            return mounted;
+        };
+        self.mounted = Some(mounted);
+        self.render_element(false);
+    }
+
+    /// Renders the current document into the element and restores the selection
+    /// if the element has focus.
+    fn render_element(&self, restore: bool) {
+        if let Some(ref mounted) = self.mounted {
+            let json = self.current.to_json().to_string();
+            let last = self.current.len() - 1;
+            let anchor = self.selection.anchor.min(last) as u32;
+            let focus = self.selection.focus.min(last) as u32;
+            js! { @(no_return)
+                var bridge = @{bridge()};
+                var element = @{mounted}.element;
+                bridge.render(element, JSON.parse(@{json}));
+                if (@{restore} && document.activeElement === element) {
+                    bridge.select(element, @{anchor}, @{focus});
+                }
+            }
+        }
+    }
+
+    fn unmount(&mut self) {
+        if let Some(mounted) = self.mounted.take() {
+            js! { @(no_return)
+                var mounted = @{mounted};
+                mounted.element.removeEventListener("input", mounted.onInput);
+                mounted.element.removeEventListener("keydown", mounted.onKey);
+                mounted.element.removeEventListener("paste", mounted.onPaste);
+                document.removeEventListener("selectionchange", mounted.onSelection);
+                mounted.input.drop();
+                mounted.select.drop();
+                mounted.command.drop();
+            }
+        }
+    }
+}
+
+impl Component for RichTextEditor {
+    type Msg = Msg;
+    type Properties = RichTextEditorProps;
+
+    fn create(props: Self::Properties, sender: AppSender<Msg>) -> Self {
+        sender.send_soon(Msg::Mount);
+        RichTextEditor {
+            current: props.document.clone(),
+            props,
+            sender,
+            node: NodeRef::default(),
+            mounted: None,
+            selection: Selection::default(),
+        }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Mount => {
+                if self.mounted.is_none() {
+                    self.mount();
+                }
+                false
+            }
+            Msg::Input(document) => {
+                if document == self.current {
+                    return false;
+                }
+                let delta = self.current.diff(&document);
+                let emptied = document.is_empty() != self.current.is_empty();
+                self.current = document;
+                self.props.onchange.emit(delta);
+                // The placeholder depends on the emptiness
+                emptied
+            }
+            Msg::Select(selection) => {
+                if selection != self.selection {
+                    self.selection = selection;
+                    self.props.onselect.emit(selection);
+                }
+                false
+            }
+            Msg::Command(mark) => {
+                // The element shows the mark when a parent applies the delta
+                let delta = self.current.toggle_mark(self.selection, mark);
+                if !delta.is_empty() {
+                    self.props.onchange.emit(delta);
+                }
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        if props.document != self.current {
+            let delta = self.current.diff(&props.document);
+            self.selection = self.selection.transform(&delta);
+            self.current = props.document.clone();
+            self.render_element(true);
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let mut classes = vec!["rich-text-editor", self.props.class.as_str()];
+        if self.current.is_empty() {
+            classes.push("empty");
+        }
+        let mut tag = VTag::new("div")
+            .class(classes.join(" ").trim())
+            .attr("contenteditable", !self.props.readonly)
+            .attr("role", "textbox")
+            .attr("aria-multiline", "true")
+            .attr("style", "white-space: pre-wrap;")
+            .node_ref(&self.node);
+        if self.props.readonly {
+            tag.add_attribute("aria-readonly", "true");
+        }
+        if !self.props.label.is_empty() {
+            tag.add_attribute("aria-label", &self.props.label);
+        }
+        if !self.props.placeholder.is_empty() {
+            tag.add_attribute("aria-placeholder", &self.props.placeholder);
+            tag.add_attribute("data-placeholder", &self.props.placeholder);
+        }
+        tag
+    }
+
+    fn destroy(&mut self) {
+        self.unmount();
+    }
+}
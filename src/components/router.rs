@@ -0,0 +1,401 @@
+//! This module contains a component which renders a view of the current route.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::mem;
+use std::time::Duration;
+use analytics::{self, Event};
+use callback::Callback;
+use component::{Component, ShouldRender, Render};
+use components::keep_alive::{KeepAlive, KeepAliveProps};
+use focus;
+use html::{AppSender, Html};
+use router::{self, Routable, Guard, Navigation, Decision};
+use services::Task;
+use services::history::{self, HistoryService, HistoryHandle};
+use services::script::{ScriptService, ScriptHandle};
+use services::scroll::{ScrollService, ScrollPosition};
+use services::timeout::{TimeoutService, TimeoutHandle};
+use virtual_dom::{NodeRef, VComp, VTag};
+
+/// Properties of the `Router` component.
+pub struct RouterProps<R> {
+    /// A path of the scope of nested routes. Routes are matched with the rest
+    /// of the location and paths out of the scope are not found.
+    pub base: String,
+    /// Renders a view of the matched route.
+    pub render: Render<R>,
+    /// Renders a view for a path which doesn't match any route.
+    pub not_found: Render<String>,
+    /// Renders a view of a route while its chunk is loading.
+    pub loading: Render<R>,
+    /// Renders a view if a chunk of a route can't be loaded.
+    pub failed: Render<String>,
+    /// Checks navigations before the destination is mounted.
+    /// The current route stays rendered while the guard decides.
+    pub guard: Guard<R>,
+    /// Moves focus to the view of a new route after a navigation, so a screen
+    /// reader starts from the new content (see `focus::focus_content`).
+    pub focus: bool,
+    /// Views of this many recently visited routes stay mounted and hidden
+    /// with `KeepAlive`, so their state is restored when a user returns.
+    /// Views are destroyed at once by default.
+    pub keep_alive: usize,
+}
+
+impl<R> Default for RouterProps<R> {
+    fn default() -> Self {
+        RouterProps {
+            base: String::new(),
+            render: Render::default(),
+            not_found: Render::default(),
+            loading: Render::default(),
+            failed: Render::default(),
+            guard: Guard::default(),
+            focus: false,
+            keep_alive: 0,
+        }
+    }
+}
+
+/// Messages of the `Router` component.
+pub enum Msg {
+    /// The location was changed.
+    Changed(String),
+    /// A guard decided about the navigation with the serial number.
+    Decided(usize, Decision),
+    /// A new route was rendered and the page has to be scrolled.
+    Scroll,
+    /// A chunk of the route loaded or failed.
+    Loaded(Result<(), String>),
+    /// A new route was rendered and focus has to be moved to it.
+    Focus,
+}
+
+/// A state of the chunk of the current route.
+enum Chunk {
+    Ready,
+    Loading(ScriptHandle),
+    Failed(String),
+}
+
+/// Where to scroll after a route was rendered.
+enum ScrollTarget {
+    /// A saved position of an entry of the history.
+    Position(ScrollPosition),
+    /// An element with an id from the hash or the top of the page.
+    Fragment(String),
+}
+
+/// A navigation which waits for a decision of the guard.
+struct Pending<R> {
+    serial: usize,
+    location: String,
+    route: R,
+}
+
+/// A component which listens of the location and renders the matched route.
+///
+/// A router without a `base` path also restores the scroll position of
+/// entries of the history when a user goes back or forward. New entries
+/// are scrolled to the top or to an element from the hash of the location.
+/// Every visited location is tracked as a page view of `analytics`.
+pub struct Router<R: Routable> {
+    props: RouterProps<R>,
+    location: String,
+    path: String,
+    route: Option<R>,
+    pending: Option<Pending<R>>,
+    serial: usize,
+    sender: AppSender<Msg>,
+    handle: HistoryHandle,
+    entry: String,
+    positions: HashMap<String, ScrollPosition>,
+    scroll: ScrollService,
+    timeout: TimeoutService<Msg>,
+    scrolling: Option<TimeoutHandle>,
+    scroll_target: Option<ScrollTarget>,
+    script: ScriptService<Msg>,
+    chunk: Chunk,
+    node: NodeRef,
+    focusing: bool,
+    focus_timeout: Option<TimeoutHandle>,
+}
+
+impl<R: Routable> Router<R> {
+    fn set_location(&mut self, location: &str) -> bool {
+        let path = router::target_of(location).to_owned();
+        if path == self.path && self.pending.is_none() {
+            self.location = location.to_owned();
+            return false;
+        }
+        let route = router::route_of(&self.props.base, &path);
+        match route {
+            Some(route) => {
+                if self.props.guard.is_noop() {
+                    self.apply(location, path, Some(route))
+                } else {
+                    self.check(location, route)
+                }
+            }
+            None => {
+                self.pending = None;
+                self.apply(location, path, None)
+            }
+        }
+    }
+
+    fn check(&mut self, location: &str, route: R) -> bool {
+        self.serial += 1;
+        let serial = self.serial;
+        let sender = RefCell::new(self.sender.clone());
+        let decide = Callback::from(move |decision| {
+            sender.borrow_mut().send(Msg::Decided(serial, decision));
+        });
+        self.props.guard.check(&route, Navigation::new(decide));
+        self.pending = Some(Pending {
+            serial,
+            location: location.to_owned(),
+            route,
+        });
+        // Nothing is rendered before the first decision
+        self.route.is_none()
+    }
+
+    fn apply(&mut self, location: &str, path: String, route: Option<R>) -> bool {
+        // The first page is focused by a browser
+        let navigated = !self.location.is_empty();
+        analytics::track(Event::page_view(location));
+        self.location = location.to_owned();
+        self.path = path;
+        self.route = route;
+        self.load_chunk();
+        if self.props.base.is_empty() {
+            self.track_scroll();
+        }
+        if self.props.focus && navigated {
+            self.focusing = true;
+            if let Chunk::Ready = self.chunk {
+                self.schedule_focus();
+            }
+        }
+        true
+    }
+
+    fn schedule_focus(&mut self) {
+        if let Some(mut handle) = self.focus_timeout.take() {
+            handle.cancel();
+        }
+        if self.focusing {
+            let handle = self.timeout.spawn(Duration::from_millis(0), || Msg::Focus);
+            self.focus_timeout = Some(handle);
+        }
+    }
+
+    fn load_chunk(&mut self) {
+        if let Chunk::Loading(ref mut handle) = self.chunk {
+            handle.cancel();
+        }
+        self.chunk = match self.route.as_ref().and_then(|route| route.chunk()) {
+            Some(url) if !self.script.is_loaded(url) => {
+                Chunk::Loading(self.script.load(url, Msg::Loaded))
+            }
+            _ => Chunk::Ready,
+        };
+    }
+
+    fn track_scroll(&mut self) {
+        let entry = history::entry_key();
+        if entry == self.entry {
+            return;
+        }
+        if self.entry.is_empty() {
+            // A browser scrolls the first page itself
+            self.entry = entry;
+            return;
+        }
+        let position = self.scroll.position();
+        let last = mem::replace(&mut self.entry, entry);
+        self.positions.insert(last, position);
+        let target = match self.positions.get(&self.entry) {
+            Some(position) => ScrollTarget::Position(*position),
+            None => ScrollTarget::Fragment(router::hash_of(&self.location).to_owned()),
+        };
+        self.scroll_target = Some(target);
+        if let Chunk::Loading(_) = self.chunk {
+            // Scroll when the chunk is loaded, because a fallback is shorter
+            return;
+        }
+        self.schedule_scroll();
+    }
+
+    fn schedule_scroll(&mut self) {
+        if let Some(mut handle) = self.scrolling.take() {
+            handle.cancel();
+        }
+        if self.scroll_target.is_some() {
+            // Scroll when the view of the route is rendered
+            let handle = self.timeout.spawn(Duration::from_millis(0), || Msg::Scroll);
+            self.scrolling = Some(handle);
+        }
+    }
+
+    fn scroll(&mut self) {
+        self.scrolling = None;
+        match self.scroll_target.take() {
+            Some(ScrollTarget::Position(position)) => {
+                self.scroll.scroll_to(position);
+            }
+            Some(ScrollTarget::Fragment(ref id)) if id.is_empty() || !self.scroll.scroll_to_fragment(id) => {
+                self.scroll.scroll_to_top();
+            }
+            _ => {}
+        }
+    }
+
+    fn decide(&mut self, serial: usize, decision: Decision) -> bool {
+        if self.pending.as_ref().map(|pending| pending.serial) != Some(serial) {
+            debug!("ignore a decision about a stale navigation: {}", serial);
+            return false;
+        }
+        let pending = self.pending.take().expect("pending navigation");
+        match decision {
+            Decision::Allow => {
+                let path = router::target_of(&pending.location).to_owned();
+                self.apply(&pending.location, path, Some(pending.route))
+            }
+            Decision::Redirect(path) => {
+                history::replace_state(&router::join(&self.props.base, &path));
+                false
+            }
+            Decision::Block => {
+                // The first location has nothing to restore
+                if !self.location.is_empty() {
+                    history::restore(&self.location);
+                }
+                self.route.is_none()
+            }
+        }
+    }
+}
+
+impl<R: Routable> Component for Router<R> {
+    type Msg = Msg;
+    type Properties = RouterProps<R>;
+
+    fn create(props: Self::Properties, sender: AppSender<Msg>) -> Self {
+        let mut history = HistoryService::new(sender.clone());
+        let handle = history.listen(Msg::Changed);
+        let mut scroll = ScrollService;
+        if props.base.is_empty() {
+            scroll.set_manual_restoration();
+        }
+        let mut router = Router {
+            props,
+            location: String::new(),
+            path: String::new(),
+            route: None,
+            pending: None,
+            serial: 0,
+            timeout: TimeoutService::new(sender.clone()),
+            script: ScriptService::new(sender.clone()),
+            sender,
+            handle,
+            entry: String::new(),
+            positions: HashMap::new(),
+            scroll,
+            scrolling: None,
+            scroll_target: None,
+            chunk: Chunk::Ready,
+            node: NodeRef::default(),
+            focusing: false,
+            focus_timeout: None,
+        };
+        router.set_location(&history.location());
+        router
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Changed(location) => self.set_location(&location),
+            Msg::Decided(serial, decision) => self.decide(serial, decision),
+            Msg::Scroll => {
+                self.scroll();
+                false
+            }
+            Msg::Loaded(result) => {
+                self.chunk = match result {
+                    Ok(()) => Chunk::Ready,
+                    Err(reason) => {
+                        warn!("{}", reason);
+                        Chunk::Failed(reason)
+                    }
+                };
+                self.schedule_scroll();
+                self.schedule_focus();
+                true
+            }
+            Msg::Focus => {
+                self.focus_timeout = None;
+                self.focusing = false;
+                focus::focus_content(&self.node);
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let rebase = self.props.base != props.base;
+        self.props = props;
+        if rebase {
+            self.route = router::route_of(&self.props.base, &self.path);
+        }
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let mut wrapper = VTag::new("div").node_ref(&self.node);
+        if self.props.focus {
+            // The wrapper takes focus if the view has no heading
+            wrapper = wrapper.attr("tabindex", "-1");
+        }
+        if self.props.keep_alive > 0 {
+            let (_, link) = VComp::lazy::<KeepAlive<R>>();
+            let current = match self.chunk {
+                Chunk::Ready => self.route.clone(),
+                _ => None,
+            };
+            let props = KeepAliveProps {
+                current: current.clone(),
+                render: self.props.render.clone(),
+                max: self.props.keep_alive,
+            };
+            wrapper = wrapper.child(VComp::new::<KeepAlive<R>>(props, link));
+            if current.is_some() {
+                return wrapper;
+            }
+        }
+        let content = match (&self.route, &self.chunk) {
+            (Some(route), Chunk::Loading(_)) => Some(self.props.loading.view(route.clone())),
+            (Some(_), Chunk::Failed(reason)) => Some(self.props.failed.view(reason.clone())),
+            (Some(route), Chunk::Ready) => Some(self.props.render.view(route.clone())),
+            (None, _) if self.pending.is_some() => None,
+            (None, _) => Some(self.props.not_found.view(self.path.clone())),
+        };
+        wrapper.children(content)
+    }
+
+    fn destroy(&mut self) {
+        self.handle.cancel();
+        if let Some(mut handle) = self.scrolling.take() {
+            handle.cancel();
+        }
+        if let Some(mut handle) = self.focus_timeout.take() {
+            handle.cancel();
+        }
+        if let Chunk::Loading(ref mut handle) = self.chunk {
+            handle.cancel();
+        }
+        self.chunk = Chunk::Ready;
+    }
+}
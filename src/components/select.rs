@@ -0,0 +1,93 @@
+//! This module contains a controlled `select` component.
+
+use callback::Callback;
+use component::{Component, ShouldRender};
+use html::{AppSender, Html, ChangeData};
+use virtual_dom::{VNode, VTag};
+
+/// Properties of the `Select` component.
+#[derive(Clone, PartialEq)]
+pub struct SelectProps<T> {
+    /// Options to select. Labels are made with `ToString`.
+    pub options: Vec<T>,
+    /// The selected option. Nothing selected if it's `None`
+    /// or it doesn't exist in `options`.
+    pub selected: Option<T>,
+    /// Disables the element.
+    pub disabled: bool,
+    /// Called with an option which a user selected.
+    pub onchange: Callback<T>,
+}
+
+impl<T> Default for SelectProps<T> {
+    fn default() -> Self {
+        SelectProps {
+            options: Vec::new(),
+            selected: None,
+            disabled: false,
+            onchange: Callback::default(),
+        }
+    }
+}
+
+/// A message of the `Select` component.
+pub enum Msg {
+    /// A user selected an option with the index.
+    Selected(String),
+}
+
+/// A controlled `select` element which works with typed options.
+pub struct Select<T: 'static> {
+    props: SelectProps<T>,
+}
+
+impl<T> Component for Select<T>
+where
+    T: ToString + Clone + PartialEq + 'static,
+{
+    type Msg = Msg;
+    type Properties = SelectProps<T>;
+
+    fn create(props: Self::Properties, _: AppSender<Msg>) -> Self {
+        Select { props }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Selected(value) => {
+                let option = value.parse::<usize>().ok()
+                    .and_then(|idx| self.props.options.get(idx));
+                if let Some(option) = option {
+                    self.props.onchange.emit(option.clone());
+                }
+            }
+        }
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let selected = self.props.selected.as_ref().and_then(|selected| {
+            self.props.options.iter().position(|option| option == selected)
+        });
+        // Options use indices as values, because they have to be matched with typed options
+        let value = selected.map(|idx| idx.to_string()).unwrap_or_default();
+        let options = self.props.options.iter().enumerate().map(|(idx, option)| {
+            let mut tag = VTag::new("option");
+            tag.add_attribute("value", idx);
+            tag.add_child(VNode::from(option.to_string()));
+            tag
+        });
+        html! {
+            <select value=value,
+                    disabled=self.props.disabled,
+                    onchange=|e: ChangeData| Msg::Selected(e.value),>
+                { for options }
+            </select>
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! This module contains headless tabs which follow the ARIA tabs pattern.
+
+use callback::Callback;
+use component::{Component, ShouldRender, Render};
+use focus::{roving_index, Orientation};
+use html::{AppSender, Html, KeyData};
+use virtual_dom::NodeRef;
+
+/// A state of a tab for rendering its label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tab {
+    /// The index of the tab.
+    pub index: usize,
+    /// The panel of the tab is shown.
+    pub selected: bool,
+    /// The tab can't be selected.
+    pub disabled: bool,
+}
+
+/// When a tab which got focus with arrows is selected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Activation {
+    /// Tabs are selected when they get focus.
+    Automatic,
+    /// Tabs are selected with Enter or Space, for panels which are slow to render.
+    Manual,
+}
+
+/// Properties of the `Tabs` component.
+#[derive(Clone, PartialEq)]
+pub struct TabsProps {
+    /// A prefix of ids of tabs and panels, unique on the page.
+    pub id: String,
+    /// The count of tabs.
+    pub count: usize,
+    /// Renders the label of a tab inside of its button.
+    pub tab: Render<Tab>,
+    /// Renders the panel of the selected tab.
+    pub panel: Render<usize>,
+    /// The selected tab, which makes tabs controlled: a click only calls
+    /// `onchange` and a parent changes it.
+    pub selected: Option<usize>,
+    /// The tab which is selected first if tabs are uncontrolled.
+    pub default_selected: usize,
+    /// Indexes of tabs which can't be selected.
+    pub disabled: Vec<usize>,
+    /// A direction of the tab list.
+    pub orientation: Orientation,
+    /// When tabs are selected with arrows.
+    pub activation: Activation,
+    /// A label of the tab list for screen readers.
+    pub label: String,
+    /// Called with the index of a tab which a user selected.
+    pub onchange: Callback<usize>,
+}
+
+impl Default for TabsProps {
+    fn default() -> Self {
+        TabsProps {
+            id: "tabs".into(),
+            count: 0,
+            tab: Render::default(),
+            panel: Render::default(),
+            selected: None,
+            default_selected: 0,
+            disabled: Vec::new(),
+            orientation: Orientation::Horizontal,
+            activation: Activation::Automatic,
+            label: String::new(),
+            onchange: Callback::default(),
+        }
+    }
+}
+
+/// A message of the `Tabs` component.
+pub enum Msg {
+    /// A user clicked the tab.
+    Select(usize),
+    /// A key was pressed on the tab.
+    Key(usize, String),
+}
+
+/// A tab list with a panel of the selected tab. It renders elements with
+/// roles, ids and a roving `tabindex`, and a parent renders labels and
+/// panels with its own markup and styles. Arrows and `Home`/`End` move
+/// between tabs.
+///
+/// ```rust,ignore
+/// html! {
+///     <Tabs: id="settings", count=2, label="Settings",
+///            tab=|tab: Tab| html! { <span>{ ["Profile", "Security"][tab.index] }</span> },
+///            panel=|index| view_settings(index), />
+/// }
+/// ```
+pub struct Tabs {
+    props: TabsProps,
+    // The selected tab of uncontrolled tabs
+    current: usize,
+    // The tab which takes focus with Tab
+    focused: usize,
+    refs: Vec<NodeRef>,
+}
+
+impl Tabs {
+    fn selected(&self) -> usize {
+        self.props.selected.unwrap_or(self.current)
+    }
+
+    fn select(&mut self, index: usize) -> ShouldRender {
+        if index >= self.props.count || self.props.disabled.contains(&index) {
+            return false;
+        }
+        self.focused = index;
+        if index != self.selected() {
+            self.current = index;
+            self.props.onchange.emit(index);
+        }
+        true
+    }
+
+    fn tab_id(&self, index: usize) -> String {
+        format!("{}-tab-{}", self.props.id, index)
+    }
+
+    fn panel_id(&self, index: usize) -> String {
+        format!("{}-panel-{}", self.props.id, index)
+    }
+
+    fn view_tab(&self, index: usize) -> Html<Msg> {
+        let tab = Tab {
+            index,
+            selected: index == self.selected(),
+            disabled: self.props.disabled.contains(&index),
+        };
+        html! {
+            <button type="button", role="tab", class="tab", id=self.tab_id(index), ref=&self.refs[index],
+                    aria-selected=if tab.selected { "true" } else { "false" },
+                    aria-controls=self.panel_id(index),
+                    aria-disabled=if tab.disabled { Some("true") } else { None },
+                    tabindex=if index == self.focused { "0" } else { "-1" },
+                    onclick=move |_| Msg::Select(index),
+                    onkeydown=move |e: KeyData| Msg::Key(index, e.key),>
+                { self.props.tab.view(tab) }
+            </button>
+        }
+    }
+}
+
+impl Component for Tabs {
+    type Msg = Msg;
+    type Properties = TabsProps;
+
+    fn create(props: Self::Properties, _: AppSender<Msg>) -> Self {
+        let current = props.default_selected;
+        let mut tabs = Tabs {
+            props,
+            current,
+            focused: 0,
+            refs: Vec::new(),
+        };
+        tabs.focused = tabs.selected();
+        tabs.refs.resize(tabs.props.count, NodeRef::default());
+        tabs
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Select(index) => self.select(index),
+            Msg::Key(index, key) => {
+                let manual = self.props.activation == Activation::Manual;
+                if manual && (key == "Enter" || key == " ") {
+                    return self.select(index);
+                }
+                let next = roving_index(&key, index, self.props.count, self.props.orientation, &self.props.disabled);
+                match next {
+                    Some(next) => {
+                        self.refs[next].focus();
+                        if manual {
+                            self.focused = next;
+                            true
+                        } else {
+                            self.select(next)
+                        }
+                    }
+                    None => false,
+                }
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        self.refs.resize(self.props.count, NodeRef::default());
+        if self.focused >= self.props.count {
+            self.focused = self.selected();
+        }
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let selected = self.selected();
+        let orientation = match self.props.orientation {
+            Orientation::Horizontal => "horizontal",
+            Orientation::Vertical => "vertical",
+        };
+        html! {
+            <div class="tabs",>
+                <div role="tablist", class="tab-list", aria-label=&self.props.label, aria-orientation=orientation,>
+                    { for (0..self.props.count).map(|index| self.view_tab(index)) }
+                </div>
+                <div role="tabpanel", class="tab-panel", id=self.panel_id(selected),
+                     aria-labelledby=self.tab_id(selected), tabindex="0",>
+                    { self.props.panel.view(selected) }
+                </div>
+            </div>
+        }
+    }
+}
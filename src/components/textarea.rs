@@ -0,0 +1,62 @@
+//! This module contains a controlled `textarea` component.
+
+use callback::Callback;
+use component::{Component, ShouldRender};
+use html::{AppSender, Html, InputData};
+
+/// Properties of the `TextArea` component.
+#[derive(Default, Clone, PartialEq)]
+pub struct TextAreaProps {
+    /// The text of the area. The element always shows this text.
+    pub value: String,
+    /// A placeholder of the empty area.
+    pub placeholder: String,
+    /// Disables the area.
+    pub disabled: bool,
+    /// Called with a new text on every change by a user.
+    pub onchange: Callback<String>,
+}
+
+/// A message of the `TextArea` component.
+pub enum Msg {
+    /// A user changed the text.
+    Changed(String),
+}
+
+/// A controlled `textarea` element. The text is set as `value` property,
+/// because children of a `textarea` set its default value only.
+pub struct TextArea {
+    props: TextAreaProps,
+}
+
+impl Component for TextArea {
+    type Msg = Msg;
+    type Properties = TextAreaProps;
+
+    fn create(props: Self::Properties, _: AppSender<Msg>) -> Self {
+        TextArea { props }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Changed(value) => {
+                self.props.onchange.emit(value);
+            }
+        }
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        html! {
+            <textarea value=&self.props.value,
+                      placeholder=&self.props.placeholder,
+                      disabled=self.props.disabled,
+                      oninput=|e: InputData| Msg::Changed(e.value),></textarea>
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! This module contains a `select` of times of the day.
+
+use callback::Callback;
+use component::{Component, ShouldRender};
+use date::Time;
+use html::{AppSender, Html, ChangeData};
+use services::intl::IntlService;
+use virtual_dom::{VNode, VTag};
+
+/// Properties of the `TimePicker` component.
+#[derive(Clone, PartialEq)]
+pub struct TimePickerProps {
+    /// An id of the `select` element, for its `label`.
+    pub id: String,
+    /// The picked time. A time between steps is listed too.
+    pub value: Option<Time>,
+    /// The earliest time of the list.
+    pub min: Time,
+    /// The latest time of the list.
+    pub max: Time,
+    /// Minutes between listed times.
+    pub step: u32,
+    /// A label for screen readers if there is no `label` element.
+    pub label: String,
+    /// A text of the empty option which is shown without a time.
+    pub placeholder: String,
+    /// Disables the element.
+    pub disabled: bool,
+    /// Called with a time which a user picked.
+    pub onchange: Callback<Time>,
+}
+
+impl Default for TimePickerProps {
+    fn default() -> Self {
+        TimePickerProps {
+            id: "time-picker".into(),
+            value: None,
+            min: Time::MIDNIGHT,
+            max: Time::LAST_MINUTE,
+            step: 15,
+            label: String::new(),
+            placeholder: "Choose a time".into(),
+            disabled: false,
+            onchange: Callback::default(),
+        }
+    }
+}
+
+/// A message of the `TimePicker` component.
+pub enum Msg {
+    /// A user picked an option with the value.
+    Selected(String),
+}
+
+/// A `select` of times from `min` to `max` formatted for the locale of
+/// `IntlService`, like `2:30 PM`. Browsers make it accessible with keys
+/// and typing.
+///
+/// ```rust,ignore
+/// html! {
+///     <TimePicker: id="start", value=self.start, min=Time::new(9, 0).unwrap(),
+///                  max=Time::new(17, 0).unwrap(), step=30,
+///                  onchange=|time| Msg::Start(time), />
+/// }
+/// ```
+pub struct TimePicker {
+    props: TimePickerProps,
+    intl: IntlService,
+}
+
+impl TimePicker {
+    /// Returns listed times in order.
+    fn times(&self) -> Vec<Time> {
+        let step = self.props.step.max(1) as usize;
+        let (min, max) = (self.props.min.minutes(), self.props.max.minutes());
+        let mut times: Vec<Time> = (min..=max).step_by(step).map(Time::from_minutes).collect();
+        if let Some(value) = self.props.value {
+            if let Err(index) = times.binary_search(&value) {
+                times.insert(index, value);
+            }
+        }
+        times
+    }
+}
+
+impl Component for TimePicker {
+    type Msg = Msg;
+    type Properties = TimePickerProps;
+
+    fn create(props: Self::Properties, _: AppSender<Msg>) -> Self {
+        TimePicker {
+            props,
+            intl: IntlService::new(),
+        }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Selected(value) => {
+                if let Some(time) = Time::parse(&value) {
+                    self.props.onchange.emit(time);
+                }
+            }
+        }
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let value = self.props.value.map(|time| time.to_string()).unwrap_or_default();
+        let mut placeholder = VTag::new("option");
+        placeholder.add_attribute("value", "");
+        placeholder.add_attribute("disabled", "");
+        placeholder.add_child(VNode::from(self.props.placeholder.clone()));
+        let options = self.times().into_iter().map(|time| {
+            let mut tag = VTag::new("option");
+            tag.add_attribute("value", time);
+            tag.add_child(VNode::from(self.intl.time_of_day(time)));
+            tag
+        });
+        let label = if self.props.label.is_empty() { None } else { Some(&self.props.label) };
+        html! {
+            <select class="time-picker", id=&self.props.id, value=value, aria-label=label,
+                    disabled=self.props.disabled,
+                    onchange=|e: ChangeData| Msg::Selected(e.value),>
+                { placeholder }
+                { for options }
+            </select>
+        }
+    }
+}
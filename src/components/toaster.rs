@@ -0,0 +1,138 @@
+//! This module contains a component which shows toasts of the queue.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+use component::{Component, ShouldRender};
+use html::{AppSender, Html};
+use services::timeout::{TimeoutHandle, TimeoutService};
+use toast::{self, Level, Toast, ToastHandle, ToastId, ToastService};
+
+/// Properties of the `Toaster` component.
+#[derive(Default)]
+pub struct ToasterProps {
+    /// Shows only the newest toasts, `0` shows all of them.
+    /// Timers of hidden toasts start when they're shown.
+    pub max: usize,
+    /// Classes of the wrapper element.
+    pub class: String,
+}
+
+/// Messages of the `Toaster` component.
+pub enum Msg {
+    /// A toast was pushed or dismissed.
+    Changed,
+    /// The time of the toast is out.
+    Expire(ToastId),
+    /// The close button of the toast was clicked.
+    Dismiss(ToastId),
+    /// The action button of the toast was clicked.
+    Act(ToastId),
+}
+
+/// A host of toasts. Render it once in an app, it renders a `div.toaster`
+/// with a `div.toast.toast-{level}` per toast which has a `span.toast-message`,
+/// an optional `button.toast-action` and a `button.toast-close`.
+/// Errors have the `alert` role and other toasts have the `status` role,
+/// so screen readers read them.
+pub struct Toaster {
+    props: ToasterProps,
+    toasts: Vec<(ToastId, Rc<Toast>)>,
+    timeout: TimeoutService<Msg>,
+    timers: HashMap<ToastId, TimeoutHandle>,
+    _listener: ToastHandle,
+}
+
+impl Toaster {
+    fn visible(&self) -> &[(ToastId, Rc<Toast>)] {
+        let skip = match self.props.max {
+            0 => 0,
+            max => self.toasts.len().saturating_sub(max),
+        };
+        &self.toasts[skip..]
+    }
+
+    /// Takes toasts of the queue and starts timers of shown toasts.
+    fn sync(&mut self) {
+        self.toasts = toast::toasts();
+        let visible: Vec<(ToastId, Rc<Toast>)> = self.visible().to_vec();
+        self.timers.retain(|id, _| visible.iter().any(|(toast, _)| toast == id));
+        for (id, toast) in visible {
+            if let Some(timeout) = toast.timeout {
+                if !self.timers.contains_key(&id) {
+                    let handle = self.timeout.spawn(timeout, move || Msg::Expire(id));
+                    self.timers.insert(id, handle);
+                }
+            }
+        }
+    }
+
+    fn view_toast(&self, id: ToastId, toast: &Toast) -> Html<Msg> {
+        let role = if toast.level == Level::Error { "alert" } else { "status" };
+        html! {
+            <div class=("toast", format!("toast-{}", toast.level.name())), role=role,>
+                <span class="toast-message",>{ &toast.message }</span>
+                if let (Some(ref action) = toast.action) {
+                    <button class="toast-action", type="button", onclick=move |_| Msg::Act(id),>{ &action.label }</button>
+                }
+                <button class="toast-close", type="button", aria-label="Dismiss", onclick=move |_| Msg::Dismiss(id),>
+                    { "×" }
+                </button>
+            </div>
+        }
+    }
+}
+
+impl Component for Toaster {
+    type Msg = Msg;
+    type Properties = ToasterProps;
+
+    fn create(props: Self::Properties, sender: AppSender<Msg>) -> Self {
+        let listener = ToastService::new(sender.clone()).listen(|| Msg::Changed);
+        let mut toaster = Toaster {
+            props,
+            toasts: Vec::new(),
+            timeout: TimeoutService::new(sender),
+            timers: HashMap::new(),
+            _listener: listener,
+        };
+        toaster.sync();
+        toaster
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Changed => {
+                self.sync();
+                true
+            }
+            Msg::Expire(id) | Msg::Dismiss(id) => {
+                toast::dismiss(id);
+                false
+            }
+            Msg::Act(id) => {
+                let action = self.toasts.iter()
+                    .find(|&&(toast, _)| toast == id)
+                    .and_then(|(_, toast)| toast.action.clone());
+                if let Some(action) = action {
+                    action.callback.emit(());
+                }
+                toast::dismiss(id);
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        self.sync();
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        html! {
+            <div class=("toaster", self.props.class.as_str()), role="region", aria-label="Notifications",>
+                { for self.visible().iter().map(|(id, toast)| self.view_toast(*id, toast)) }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,227 @@
+//! This module contains a tooltip which describes an element when
+//! it's hovered or focused.
+
+use std::time::Duration;
+use component::{Component, ShouldRender, Render};
+use host::{self, HostAttributes};
+use html::{AppSender, Html};
+use position::{self, Align, Placement, Position, PositionOptions, Side};
+use services::layout::{LayoutEvent, LayoutHandle, LayoutService};
+use services::timeout::{TimeoutHandle, TimeoutService};
+use virtual_dom::{NodeRef, VPortal};
+
+/// A pause after the pointer left an anchor before its floating element
+/// is hidden, so the pointer could move onto the element.
+pub(crate) const HIDE_DELAY: Duration = Duration::from_millis(100);
+
+/// Properties of the `Tooltip` component.
+#[derive(Clone, PartialEq)]
+pub struct TooltipProps {
+    /// An id of the tooltip, unique on the page. The anchor gets
+    /// the `{id}-anchor` id.
+    pub id: String,
+    /// The text of the tooltip.
+    pub text: String,
+    /// Renders the anchor with attributes and listeners which it spreads
+    /// onto its focusable element.
+    pub anchor: Render<HostAttributes>,
+    /// Where the tooltip is placed.
+    pub position: PositionOptions,
+    /// A pause of hovering before the tooltip is shown. It's shown
+    /// at once when the anchor gets focus.
+    pub delay: Duration,
+}
+
+impl Default for TooltipProps {
+    fn default() -> Self {
+        TooltipProps {
+            id: "tooltip".into(),
+            text: String::new(),
+            anchor: Render::default(),
+            position: PositionOptions::new(Placement::new(Side::Top, Align::Center)),
+            delay: Duration::from_millis(300),
+        }
+    }
+}
+
+/// A message of the `Tooltip` component.
+pub enum Msg {
+    /// The pointer entered the anchor or the tooltip.
+    Enter,
+    /// The pointer left the anchor or the tooltip.
+    Leave,
+    /// The anchor got focus.
+    Focus,
+    /// The anchor lost focus.
+    Blur,
+    /// A key was pressed on the anchor.
+    Key(String),
+    /// The delay elapsed.
+    Show,
+    /// The pause after leaving elapsed.
+    Hide,
+    /// The tooltip was measured.
+    Layout(LayoutEvent),
+}
+
+/// A tooltip which is rendered into the body next to its anchor, so
+/// containers with `overflow: hidden` don't clip it. It flips and shifts
+/// to stay in the viewport, and it follows the anchor when the page
+/// is scrolled. The anchor is described by the tooltip with
+/// `aria-describedby` and `Escape` hides it.
+///
+/// ```rust,ignore
+/// html! {
+///     <Tooltip: id="save-hint", text="Saves the draft",
+///               anchor=|host: HostAttributes| html! { <button ..&host,>{ "Save" }</button> }, />
+/// }
+/// ```
+pub struct Tooltip {
+    props: TooltipProps,
+    sender: AppSender<Msg>,
+    timeout: TimeoutService<Msg>,
+    layout: LayoutService<Msg>,
+    timer: Option<TimeoutHandle>,
+    tracker: Option<LayoutHandle>,
+    floating: NodeRef,
+    position: Option<Position>,
+    open: bool,
+    hovered: bool,
+    focused: bool,
+    // Escape hides the tooltip until the pointer leaves or focus moves
+    dismissed: bool,
+}
+
+impl Tooltip {
+    fn show(&mut self) -> ShouldRender {
+        self.timer = None;
+        if self.open || self.dismissed {
+            return false;
+        }
+        self.open = true;
+        let anchor = format!("{}-anchor", self.props.id);
+        self.tracker = Some(self.layout.track(&anchor, &self.floating, Msg::Layout));
+        true
+    }
+
+    fn hide(&mut self) -> ShouldRender {
+        self.timer = None;
+        self.tracker = None;
+        self.position = None;
+        let open = self.open;
+        self.open = false;
+        open
+    }
+
+    fn anchor(&self) -> HostAttributes {
+        HostAttributes::new()
+            .attr("id", format!("{}-anchor", self.props.id))
+            .attr("aria-describedby", &self.props.id)
+            .listen("mouseenter", host::forward(&self.sender, |_| Msg::Enter))
+            .listen("mouseleave", host::forward(&self.sender, |_| Msg::Leave))
+            .listen("focus", host::forward(&self.sender, |_| Msg::Focus))
+            .listen("blur", host::forward(&self.sender, |_| Msg::Blur))
+            .listen("keydown", host::forward(&self.sender, |event| Msg::Key(host::event_key(&event))))
+    }
+}
+
+impl Component for Tooltip {
+    type Msg = Msg;
+    type Properties = TooltipProps;
+
+    fn create(props: Self::Properties, sender: AppSender<Msg>) -> Self {
+        Tooltip {
+            props,
+            timeout: TimeoutService::new(sender.clone()),
+            layout: LayoutService::new(sender.clone()),
+            sender,
+            timer: None,
+            tracker: None,
+            floating: NodeRef::default(),
+            position: None,
+            open: false,
+            hovered: false,
+            focused: false,
+            dismissed: false,
+        }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Enter => {
+                self.hovered = true;
+                self.timer = None;
+                if !self.open && !self.dismissed {
+                    self.timer = Some(self.timeout.spawn(self.props.delay, || Msg::Show));
+                }
+                false
+            }
+            Msg::Leave => {
+                self.hovered = false;
+                self.dismissed = false;
+                self.timer = None;
+                if self.open && !self.focused {
+                    self.timer = Some(self.timeout.spawn(HIDE_DELAY, || Msg::Hide));
+                }
+                false
+            }
+            Msg::Focus => {
+                self.focused = true;
+                self.show()
+            }
+            Msg::Blur => {
+                self.focused = false;
+                self.dismissed = false;
+                if self.hovered {
+                    false
+                } else {
+                    self.hide()
+                }
+            }
+            Msg::Key(key) => {
+                if key == "Escape" && self.open {
+                    self.dismissed = true;
+                    self.hide()
+                } else {
+                    false
+                }
+            }
+            Msg::Show => self.show(),
+            Msg::Hide => self.hide(),
+            Msg::Layout(LayoutEvent::Measured(layout)) => {
+                self.position = Some(position::compute(&layout, &self.props.position));
+                true
+            }
+            Msg::Layout(LayoutEvent::Outside) => false,
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        self.props = props;
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let (style, side) = match self.position {
+            Some(ref position) => (position.style(), position.placement.side),
+            None => (position::UNMEASURED_STYLE.to_owned(), self.props.position.placement.side),
+        };
+        let mut portal = VPortal::body();
+        portal.add_child(html! {
+            <div role="tooltip", class="tooltip", id=&self.props.id, ref=&self.floating,
+                 style=style, data-side=side.name(), hidden=!self.open,
+                 onmouseenter=|_| Msg::Enter, onmouseleave=|_| Msg::Leave,>
+                { &self.props.text }
+            </div>
+        }.into());
+        html! {
+            <span class="tooltip-anchor",>
+                { self.props.anchor.view(self.anchor()) }
+                { portal }
+            </span>
+        }
+    }
+}
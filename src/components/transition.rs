@@ -0,0 +1,252 @@
+//! This module contains a component which animates its children in and out.
+
+use std::time::Duration;
+use stdweb::Value;
+use stdweb::unstable::TryInto;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::web::Element;
+use callback::Callback;
+use component::{Component, ShouldRender, Children};
+use html::{AppSender, Html};
+use services::timeout::{TimeoutHandle, TimeoutService};
+use virtual_dom::{Listener, NodeRef, VTag};
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use virtual_dom::{ListenerHandle, ListenerOptions};
+#[cfg(feature = "testing")]
+use std::any::Any;
+#[cfg(feature = "testing")]
+use virtual_dom::Simulated;
+
+/// Properties of the `Transition` component.
+#[derive(Default)]
+pub struct TransitionProps {
+    /// Shows the children. Changes of the flag start transitions.
+    pub show: bool,
+    /// A prefix of transition classes, `transition` by default.
+    pub name: String,
+    /// Animates the children in when the component is created with `show`.
+    pub appear: bool,
+    /// Classes of the wrapper element.
+    pub class: String,
+    /// Nodes inside of the wrapper.
+    pub children: Children,
+    /// Called when an enter transition ends.
+    pub onentered: Callback<()>,
+    /// Called when a leave transition ends and the children are removed.
+    pub onleft: Callback<()>,
+}
+
+/// Messages of the `Transition` component.
+pub enum Msg {
+    /// Initial classes were rendered, the transition could start.
+    Start,
+    /// The transition of the wrapper ended.
+    End,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Phase {
+    Hidden,
+    EnterFrom,
+    EnterTo,
+    Shown,
+    LeaveFrom,
+    LeaveTo,
+}
+
+/// A wrapper which adds classes to its element when the children are inserted
+/// and removed, and removes them after the transition or the animation of the
+/// wrapper ends. Hidden children are removed and the wrapper gets `hidden`. For the `fade` name the classes are:
+///
+/// * `fade-enter fade-enter-active` when the children are inserted,
+/// * `fade-enter-active fade-enter-to` in the next frame until the transition ends,
+/// * `fade-leave fade-leave-active` when `show` is turned off,
+/// * `fade-leave-active fade-leave-to` in the next frame until the transition ends.
+///
+/// ```rust,ignore
+/// html! {
+///     <Transition: name="fade", show=self.open,
+///                  children=self.link.children(vec![self.view_modal().into()]), />
+/// }
+/// ```
+///
+/// Items of lists are wrapped one by one and kept with `show=false` until
+/// `onleft` is called, then a parent removes them from its model.
+pub struct Transition {
+    props: TransitionProps,
+    sender: AppSender<Msg>,
+    timeout: TimeoutService<Msg>,
+    node: NodeRef,
+    phase: Phase,
+    fallback: Option<TimeoutHandle>,
+}
+
+impl Transition {
+    fn name(&self) -> &str {
+        if self.props.name.is_empty() {
+            "transition"
+        } else {
+            &self.props.name
+        }
+    }
+
+    fn begin(&mut self, phase: Phase) {
+        self.phase = phase;
+        self.fallback = None;
+        self.sender.send_soon(Msg::Start);
+    }
+
+    fn finish(&mut self) {
+        self.fallback = None;
+        match self.phase {
+            Phase::EnterTo => {
+                self.phase = Phase::Shown;
+                self.props.onentered.emit(());
+            }
+            Phase::LeaveTo => {
+                self.phase = Phase::Hidden;
+                self.props.onleft.emit(());
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the longest transition or animation of the wrapper,
+    /// or `None` if it isn't rendered.
+    fn duration(&self) -> Option<Duration> {
+        let element = self.node.get()?;
+        let ms: Value = js! {
+            var element = @{element};
+            // Reading the layout applies initial classes before the next ones
+            element.offsetWidth;
+            var style = window.getComputedStyle(element);
+            var longest = function(durations, delays) {
+                var parse = function(value) { return parseFloat(value) * (value.indexOf("ms") >= 0 ? 1 : 1000) || 0; };
+                var times = durations.split(",").map(parse);
+                var waits = delays.split(",").map(parse);
+                return Math.max.apply(null, times.map(function(time, i) { return time + (waits[i] || waits[0] || 0); }));
+            };
+            return Math.max(longest(style.transitionDuration, style.transitionDelay),
+                longest(style.animationDuration, style.animationDelay));
+        };
+        let ms: f64 = ms.try_into().unwrap_or(0.0);
+        Some(Duration::from_millis(ms.max(0.0) as u64))
+    }
+}
+
+impl Component for Transition {
+    type Msg = Msg;
+    type Properties = TransitionProps;
+
+    fn create(props: Self::Properties, sender: AppSender<Msg>) -> Self {
+        let phase = if props.show { Phase::Shown } else { Phase::Hidden };
+        let appear = props.show && props.appear;
+        let mut transition = Transition {
+            props,
+            timeout: TimeoutService::new(sender.clone()),
+            sender,
+            node: NodeRef::default(),
+            phase,
+            fallback: None,
+        };
+        if appear {
+            transition.begin(Phase::EnterFrom);
+        }
+        transition
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Start => {
+                let next = match self.phase {
+                    Phase::EnterFrom => Phase::EnterTo,
+                    Phase::LeaveFrom => Phase::LeaveTo,
+                    _ => return false,
+                };
+                self.phase = next;
+                match self.duration() {
+                    Some(duration) if duration == Duration::from_millis(0) => self.finish(),
+                    Some(duration) => {
+                        // The end event doesn't come if the transition is interrupted
+                        let handle = self.timeout.spawn(duration + Duration::from_millis(50), || Msg::End);
+                        self.fallback = Some(handle);
+                    }
+                    None => {}
+                }
+                true
+            }
+            Msg::End => {
+                let phase = self.phase;
+                self.finish();
+                phase != self.phase
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let was_shown = self.props.show;
+        self.props = props;
+        if self.props.show && !was_shown {
+            self.begin(Phase::EnterFrom);
+        } else if !self.props.show && was_shown {
+            if self.phase == Phase::Hidden {
+                return true;
+            }
+            self.begin(Phase::LeaveFrom);
+        }
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let name = self.name();
+        let classes = match self.phase {
+            Phase::Hidden | Phase::Shown => String::new(),
+            Phase::EnterFrom => format!("{0}-enter {0}-enter-active", name),
+            Phase::EnterTo => format!("{0}-enter-active {0}-enter-to", name),
+            Phase::LeaveFrom => format!("{0}-leave {0}-leave-active", name),
+            Phase::LeaveTo => format!("{0}-leave-active {0}-leave-to", name),
+        };
+        let class = format!("{} {}", self.props.class, classes);
+        let tag = VTag::new("div")
+            .class(class.trim())
+            .node_ref(&self.node)
+            .listener(EndListener("ontransitionend"))
+            .listener(EndListener("onanimationend"));
+        if self.phase == Phase::Hidden {
+            // The wrapper stays in place of removed children
+            tag.attr("hidden", "")
+        } else {
+            tag.children(self.props.children.view())
+        }
+    }
+}
+
+/// A listener of ends of transitions and animations of the wrapper itself.
+/// Ends of transitions of children are skipped.
+struct EndListener(&'static str);
+
+impl Listener<Msg> for EndListener {
+    fn kind(&self) -> &'static str {
+        self.0
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn attach(&mut self, element: &Element, sender: AppSender<Msg>) -> ListenerHandle {
+        let wrapper = element.clone();
+        let listener = move |event: Value| {
+            let own: bool = js! { return @{&event}.target === @{&wrapper}; }.try_into().unwrap_or(false);
+            if own {
+                sender.clone().send(Msg::End);
+            }
+        };
+        ListenerHandle::attach_named(element, &self.0[2..], ListenerOptions::default(), listener)
+    }
+
+    #[cfg(feature = "testing")]
+    fn simulate(&mut self, _: &Any) -> Option<Simulated<Msg>> {
+        Some(Simulated {
+            msg: Msg::End,
+            stop_propagation: true,
+        })
+    }
+}
@@ -0,0 +1,260 @@
+//! This module contains a component which renders only visible rows
+//! of a long list.
+
+use std::ops::Range;
+use std::rc::Rc;
+use component::{Component, ShouldRender, Render};
+use html::{AppSender, Html, ScrollData};
+use services::resize::{ResizeService, ResizeHandle, WindowSize};
+
+/// Heights of rows of a list in pixels.
+#[derive(Clone)]
+pub enum RowHeights {
+    /// All rows have the same height.
+    Fixed(f64),
+    /// The function returns a height of the row with the index.
+    /// It's called for every row when the count of rows changes.
+    Variable(Rc<Fn(usize) -> f64>),
+}
+
+impl Default for RowHeights {
+    fn default() -> Self {
+        RowHeights::Fixed(20.0)
+    }
+}
+
+impl PartialEq for RowHeights {
+    fn eq(&self, other: &RowHeights) -> bool {
+        match (self, other) {
+            (RowHeights::Fixed(a), RowHeights::Fixed(b)) => a == b,
+            (RowHeights::Variable(a), RowHeights::Variable(b)) => Rc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+/// Offsets of rows which find visible rows for a scroll position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layout {
+    count: usize,
+    fixed: Option<f64>,
+    /// Offsets of the top of every row and the end of the list.
+    offsets: Vec<f64>,
+}
+
+impl Layout {
+    /// Computes offsets of `count` rows.
+    pub fn new(count: usize, heights: &RowHeights) -> Self {
+        match *heights {
+            RowHeights::Fixed(height) => Layout {
+                count,
+                fixed: Some(height.max(1.0)),
+                offsets: Vec::new(),
+            },
+            RowHeights::Variable(ref height) => {
+                let mut offsets = Vec::with_capacity(count + 1);
+                let mut offset = 0.0;
+                offsets.push(offset);
+                for index in 0..count {
+                    offset += height(index).max(0.0);
+                    offsets.push(offset);
+                }
+                Layout {
+                    count,
+                    fixed: None,
+                    offsets,
+                }
+            }
+        }
+    }
+
+    /// Returns the height of all rows.
+    pub fn total_height(&self) -> f64 {
+        self.offset(self.count)
+    }
+
+    /// Returns the offset of the top of the row with the index.
+    pub fn offset(&self, index: usize) -> f64 {
+        let index = index.min(self.count);
+        match self.fixed {
+            Some(height) => height * index as f64,
+            None => self.offsets[index],
+        }
+    }
+
+    /// Returns the index of the row at the offset.
+    fn row_at(&self, offset: f64) -> usize {
+        let row = match self.fixed {
+            Some(height) => (offset.max(0.0) / height) as usize,
+            None => self.offsets.partition_point(|top| *top <= offset).saturating_sub(1),
+        };
+        row.min(self.count)
+    }
+
+    /// Returns indexes of rows which are visible in the `viewport` height
+    /// scrolled to the `top` with `overscan` extra rows at both sides.
+    pub fn visible(&self, top: f64, viewport: f64, overscan: usize) -> Range<usize> {
+        if self.count == 0 {
+            return 0..0;
+        }
+        let first = self.row_at(top);
+        let last = self.row_at(top + viewport.max(0.0));
+        let start = first.saturating_sub(overscan);
+        let end = (last + 1 + overscan).min(self.count);
+        start..end
+    }
+}
+
+/// Properties of the `VirtualList` component.
+#[derive(Clone, PartialEq)]
+pub struct VirtualListProps {
+    /// The count of rows.
+    pub count: usize,
+    /// Heights of rows.
+    pub heights: RowHeights,
+    /// The height of the list in pixels. The list fills its parent
+    /// if it's `None` and renders rows for the height of the window.
+    pub height: Option<f64>,
+    /// Extra rows which are rendered above and below visible ones,
+    /// so fast scrolling doesn't show blank space.
+    pub overscan: usize,
+    /// Renders the row with the index.
+    pub row: Render<usize>,
+}
+
+impl Default for VirtualListProps {
+    fn default() -> Self {
+        VirtualListProps {
+            count: 0,
+            heights: RowHeights::default(),
+            height: None,
+            overscan: 3,
+            row: Render::default(),
+        }
+    }
+}
+
+/// A message of the `VirtualList` component.
+pub enum Msg {
+    /// The list was scrolled.
+    Scrolled(ScrollData),
+    /// The window was resized.
+    Resized(WindowSize),
+}
+
+/// A list which renders only visible rows, so rows of a huge collection
+/// aren't created until they're scrolled to. Rows are positioned by
+/// offsets computed from `heights`, so heights have to be known without
+/// a render.
+///
+/// ```rust,ignore
+/// let (mut props, link) = VComp::lazy::<VirtualList>();
+/// props.count = model.rows.len();
+/// props.heights = RowHeights::Fixed(32.0);
+/// props.height = Some(600.0);
+/// props.row = link.render(move |index| view_row(index));
+/// let list = VComp::new::<VirtualList>(props, link);
+/// ```
+pub struct VirtualList {
+    props: VirtualListProps,
+    layout: Layout,
+    top: f64,
+    viewport: f64,
+    rows: Range<usize>,
+    resize: ResizeService<Msg>,
+    listener: Option<ResizeHandle>,
+}
+
+impl VirtualList {
+    /// Updates visible rows and returns `true` if they've changed.
+    fn refresh(&mut self) -> bool {
+        let rows = self.layout.visible(self.top, self.viewport, self.props.overscan);
+        let changed = rows != self.rows;
+        self.rows = rows;
+        changed
+    }
+
+    fn listen(&mut self) {
+        if self.props.height.is_some() {
+            self.listener = None;
+            self.viewport = self.props.height.unwrap_or_default();
+        } else {
+            if self.listener.is_none() {
+                self.listener = Some(self.resize.register(Msg::Resized));
+            }
+            self.viewport = self.resize.size().height;
+        }
+    }
+}
+
+impl Component for VirtualList {
+    type Msg = Msg;
+    type Properties = VirtualListProps;
+
+    fn create(props: Self::Properties, sender: AppSender<Msg>) -> Self {
+        let mut list = VirtualList {
+            layout: Layout::new(props.count, &props.heights),
+            props,
+            top: 0.0,
+            viewport: 0.0,
+            rows: 0..0,
+            resize: ResizeService::new(sender),
+            listener: None,
+        };
+        list.listen();
+        list.refresh();
+        list
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Scrolled(data) => {
+                self.top = data.top;
+                if self.props.height.is_none() {
+                    self.viewport = data.height;
+                }
+            }
+            Msg::Resized(size) => {
+                self.viewport = size.height;
+            }
+        }
+        self.refresh()
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props == props {
+            return false;
+        }
+        if self.props.count != props.count || self.props.heights != props.heights {
+            self.layout = Layout::new(props.count, &props.heights);
+        }
+        self.props = props;
+        self.listen();
+        self.refresh();
+        // Rows could render other data with the same indexes
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let height = match self.props.height {
+            Some(height) => format!("{}px", height),
+            None => "100%".into(),
+        };
+        let container = format!("overflow-y:auto;height:{};", height);
+        let content = format!("position:relative;height:{}px;", self.layout.total_height());
+        let rows = format!("position:absolute;top:{}px;left:0;right:0;", self.layout.offset(self.rows.start));
+        html! {
+            <div class="virtual-list", style=container, onscroll.passive=|data| Msg::Scrolled(data),>
+                <div style=content,>
+                    <div style=rows,>
+                        { for self.rows.clone().map(|index| self.props.row.view(index)) }
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    fn destroy(&mut self) {
+        self.listener = None;
+    }
+}
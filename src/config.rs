@@ -0,0 +1,290 @@
+//! This module contains feature flags and remote config of an app. Flags are
+//! typed constants with defaults, values are loaded from a JSON object of an
+//! endpoint or a websocket stream and cached in the local storage:
+//!
+//! ```rust,ignore
+//! const NEW_CHECKOUT: Flag<bool> = Flag::new("new-checkout", false);
+//! const PAGE_SIZE: Flag<u32> = Flag::new("page-size", 20);
+//!
+//! self.config = ConfigService::new(sender);
+//! self.loading = Some(self.config.load("/api/flags"));
+//! self.listening = Some(self.config.listen(|| Msg::FlagsChanged));
+//!
+//! if self.config.get(&NEW_CHECKOUT) { ... }
+//! ```
+//!
+//! Tests set values with `config::set_overrides`, then loaded and cached
+//! values are ignored and every lookup is deterministic.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use serde::de::DeserializeOwned;
+use serde_json::{self, Map, Value};
+use format::Restorable;
+use html::AppSender;
+use services::Task;
+use services::backend::{self, Connection, FetchBackend, StorageBackend, WebSocketBackend};
+use services::fetch::{Request, Response};
+use services::interceptor;
+use services::storage::Scope;
+
+/// A key of the local storage with cached values.
+pub const CACHE_KEY: &str = "yew-config";
+
+/// A typed flag with a default which is used until a value is loaded
+/// or if a loaded value has another type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Flag<T> {
+    /// The name of the flag in a JSON object of values.
+    pub name: &'static str,
+    /// The value if none is loaded.
+    pub default: T,
+}
+
+impl<T> Flag<T> {
+    /// Creates a flag.
+    pub const fn new(name: &'static str, default: T) -> Self {
+        Flag { name, default }
+    }
+}
+
+impl<T: DeserializeOwned + Clone> Flag<T> {
+    /// Returns the current value of the flag.
+    pub fn get(&self) -> T {
+        get(self)
+    }
+}
+
+type Listener = Rc<Fn()>;
+
+#[derive(Default)]
+struct Store {
+    values: Map<String, Value>,
+    loaded: bool,
+    overrides: Option<Map<String, Value>>,
+    listeners: Vec<(usize, Listener)>,
+    next_listener: usize,
+}
+
+impl Store {
+    fn effective(&self) -> &Map<String, Value> {
+        self.overrides.as_ref().unwrap_or(&self.values)
+    }
+}
+
+thread_local! {
+    static STORE: RefCell<Store> = RefCell::new(Store::default());
+}
+
+/// Returns the current value of the flag.
+pub fn get<T: DeserializeOwned + Clone>(flag: &Flag<T>) -> T {
+    value(flag.name)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_else(|| flag.default.clone())
+}
+
+/// Returns a raw value by its name.
+pub fn value(name: &str) -> Option<Value> {
+    STORE.with(|store| store.borrow().effective().get(name).cloned())
+}
+
+/// Returns `true` if values were loaded or restored from the cache.
+pub fn is_loaded() -> bool {
+    STORE.with(|store| store.borrow().loaded)
+}
+
+/// Replaces all values, like after a response of an endpoint.
+pub fn set_values(values: Map<String, Value>) {
+    change(|store| {
+        store.values = values;
+        store.loaded = true;
+    });
+}
+
+/// Changes some values and keeps others, like after a message of a stream.
+pub fn update(values: Map<String, Value>) {
+    change(|store| {
+        store.values.extend(values);
+        store.loaded = true;
+    });
+}
+
+/// Turns on the override mode: lookups return only these values or defaults
+/// of flags, whatever is loaded. Values are replaced by later calls.
+pub fn set_overrides(values: Vec<(&str, Value)>) {
+    let values = values.into_iter().map(|(name, value)| (name.to_owned(), value)).collect();
+    change(|store| store.overrides = Some(values));
+}
+
+/// Turns off the override mode.
+pub fn clear_overrides() {
+    change(|store| store.overrides = None);
+}
+
+fn change<F: FnOnce(&mut Store)>(apply: F) {
+    let listeners = STORE.with(|store| {
+        let mut store = store.borrow_mut();
+        let before = store.effective().clone();
+        apply(&mut store);
+        if *store.effective() == before {
+            return Vec::new();
+        }
+        store.listeners.iter().map(|(_, listener)| listener.clone()).collect()
+    });
+    for listener in listeners {
+        listener();
+    }
+}
+
+fn parse(data: &str) -> Option<Map<String, Value>> {
+    match serde_json::from_str(data) {
+        Ok(Value::Object(values)) => Some(values),
+        _ => None,
+    }
+}
+
+fn cache(storage: &StorageBackend) {
+    let values = STORE.with(|store| Value::Object(store.borrow().values.clone()));
+    storage.set(Scope::Local, CACHE_KEY, &values.to_string());
+}
+
+/// A handle of a load, a stream or a listener of values. It's canceled when dropped.
+pub struct ConfigHandle(Option<Box<Task>>);
+
+impl Task for ConfigHandle {
+    fn is_active(&self) -> bool {
+        self.0.as_ref().map_or(false, |task| task.is_active())
+    }
+
+    fn cancel(&mut self) {
+        let mut task = self.0.take().expect("tried to cancel a config handle twice");
+        if task.is_active() {
+            task.cancel();
+        }
+    }
+}
+
+impl Drop for ConfigHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+struct Listening(Option<usize>);
+
+impl Task for Listening {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let id = self.0.take().expect("tried to cancel a config listener twice");
+        STORE.with(|store| store.borrow_mut().listeners.retain(|&(listener, _)| listener != id));
+    }
+}
+
+struct Stream(Box<Connection>);
+
+impl Task for Stream {
+    fn is_active(&self) -> bool {
+        self.0.is_active()
+    }
+
+    fn cancel(&mut self) {
+        self.0.cancel();
+    }
+}
+
+/// A service which loads values and renders components again when they change.
+pub struct ConfigService<MSG> {
+    sender: AppSender<MSG>,
+    fetch: Rc<FetchBackend>,
+    storage: Rc<StorageBackend>,
+    websocket: Rc<WebSocketBackend>,
+}
+
+impl<MSG: 'static> ConfigService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    /// Cached values are restored if nothing is loaded yet.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self::with_backends(sender, backend::fetch(), backend::storage(), backend::websocket())
+    }
+
+    /// Same as `new`, but with the backends.
+    pub fn with_backends(sender: AppSender<MSG>, fetch: Rc<FetchBackend>, storage: Rc<StorageBackend>,
+                         websocket: Rc<WebSocketBackend>) -> Self {
+        if !is_loaded() {
+            if let Some(values) = storage.get(Scope::Local, CACHE_KEY).as_ref().and_then(|data| parse(data)) {
+                set_values(values);
+            }
+        }
+        Self { sender, fetch, storage, websocket }
+    }
+
+    /// Returns the current value of the flag.
+    pub fn get<T: DeserializeOwned + Clone>(&self, flag: &Flag<T>) -> T {
+        get(flag)
+    }
+
+    /// Loads a JSON object of values from the url and replaces all values with it.
+    /// Values stay as they are if the request failed.
+    pub fn load(&mut self, url: &str) -> ConfigHandle {
+        let request = Request::get(url).body(None).expect("wrong url of config");
+        let storage = self.storage.clone();
+        let callback = move |response: Response<Restorable>| {
+            let values = match *response.body() {
+                Ok(ref data) if response.status().is_success() => parse(data),
+                _ => None,
+            };
+            match values {
+                Some(values) => {
+                    set_values(values);
+                    cache(&*storage);
+                }
+                None => warn!("can't load config from {}", response.status()),
+            }
+        };
+        let task = self.fetch.fetch(interceptor::fetch(request), Box::new(callback));
+        ConfigHandle(Some(task))
+    }
+
+    /// Connects to a websocket which sends JSON objects of changed values.
+    pub fn stream(&mut self, url: &str) -> ConfigHandle {
+        let storage = self.storage.clone();
+        let on_message = move |data: String| {
+            match parse(&data) {
+                Some(values) => {
+                    update(values);
+                    cache(&*storage);
+                }
+                None => warn!("can't parse a message of config"),
+            }
+        };
+        let url = interceptor::connect(url);
+        let connection = self.websocket.connect(&url, Box::new(on_message), Box::new(|_| ()));
+        ConfigHandle(Some(Box::new(Stream(connection))))
+    }
+
+    /// Sends a message from the `converter` every time values change.
+    /// Return `true` from `update` to render with the new values.
+    pub fn listen<F>(&mut self, converter: F) -> ConfigHandle
+    where
+        F: Fn() -> MSG + 'static,
+    {
+        let tx = self.sender.clone();
+        let listener = move || {
+            let msg = converter();
+            tx.clone().send(msg);
+        };
+        let id = STORE.with(|store| {
+            let mut store = store.borrow_mut();
+            let id = store.next_listener;
+            store.next_listener += 1;
+            store.listeners.push((id, Rc::new(listener)));
+            id
+        });
+        ConfigHandle(Some(Box::new(Listening(Some(id)))))
+    }
+}
@@ -0,0 +1,56 @@
+//! This module contains the rendering mode for pages with a strict
+//! Content Security Policy, which is enabled with the `csp` feature:
+//!
+//! ```text
+//! Content-Security-Policy: script-src 'self' 'wasm-unsafe-eval' 'nonce-r4nd0m'; style-src 'self' 'nonce-r4nd0m'
+//! ```
+//!
+//! Snippets of `js!` are put to the JS file of the app at build time, so they
+//! don't need `unsafe-eval`, only the compilation of WebAssembly needs
+//! `wasm-unsafe-eval`. In the mode the framework doesn't use inline code:
+//!
+//! * `style` attributes of tags are applied with CSSOM, which a policy allows;
+//! * `<style>` elements of `css!` and printing and inline scripts of
+//!   streamed pages carry the nonce of `set_nonce`, they're rendered
+//!   without one if it isn't set, like on a page without a policy;
+//! * `interop::register` and `js_island::register` panic, because they
+//!   evaluate sources, use `register_global` with functions of a script file;
+//! * attributes of inline handlers, like `onclick="..."`, and `javascript:`
+//!   URLs panic, they would be blocked silently.
+//!
+//! Markup of raw nodes, like rendered Markdown, isn't checked.
+
+use std::cell::RefCell;
+
+thread_local! {
+    static NONCE: RefCell<Option<String>> = RefCell::new(None);
+}
+
+/// Sets the nonce of the policy of the page, which is put to elements with
+/// inline styles and scripts. A server generates it for every response and
+/// passes it to the app, like in a `<meta>` tag of the page.
+pub fn set_nonce(nonce: &str) {
+    NONCE.with(|current| *current.borrow_mut() = Some(nonce.to_owned()));
+}
+
+/// Returns the nonce of the policy if it's set.
+pub fn nonce() -> Option<String> {
+    NONCE.with(|current| current.borrow().clone())
+}
+
+/// Panics with the API which evaluates code and its replacement.
+pub(crate) fn forbid(api: &str, instead: &str) {
+    panic!("{} evaluates code, which a Content Security Policy blocks without 'unsafe-eval', use {} instead", api, instead)
+}
+
+/// Panics if the attribute would run inline code.
+pub(crate) fn check_attribute(name: &str, value: &str) {
+    let name = name.to_lowercase();
+    if name.starts_with("on") {
+        panic!("the `{}` attribute is an inline handler, which a Content Security Policy blocks, use a listener of html! instead", name);
+    }
+    let url = value.trim_start().to_lowercase();
+    if (name == "href" || name == "src" || name == "action" || name == "formaction") && url.starts_with("javascript:") {
+        panic!("the `{}` attribute has a `javascript:` URL, which a Content Security Policy blocks", name);
+    }
+}
@@ -0,0 +1,242 @@
+//! This module contains scoped styles of components. A component declares
+//! rules with a builder where `&` stands for a generated class, and keeps
+//! the handle of the class while it's mounted:
+//!
+//! ```rust,ignore
+//! fn create(_: Self::Properties, _: AppSender<Msg>) -> Self {
+//!     let css = Css::new()
+//!         .rule("&", Style::new().set("padding", "8px"))
+//!         .rule("&:hover", Style::new().set("background", "#eee"))
+//!         .rule("& > .title", Style::new().set("font-weight", "bold"));
+//!     Card { styles: Scoped::new("card", &css) }
+//! }
+//!
+//! fn view(&self) -> Html<Msg> {
+//!     html! {
+//!         <div class=self.styles.class(),>{ "..." }</div>
+//!     }
+//! }
+//! ```
+//!
+//! Rules are put to a managed `<style>` element after a render, components
+//! with the same rules share a class, and rules are removed when the last
+//! handle of the class is dropped.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use stdweb::Value;
+use style::Style;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Rule {
+    Style(String, Style),
+    Media(String, Css),
+}
+
+/// Rules of a scoped class. Selectors use `&` for the class,
+/// selectors without it are applied to descendants of the class.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Css {
+    rules: Vec<Rule>,
+}
+
+impl Css {
+    /// Creates empty rules.
+    pub fn new() -> Self {
+        Css::default()
+    }
+
+    /// Adds a rule of the selector, like `&:hover` or `& > li`.
+    pub fn rule<S: Into<String>>(mut self, selector: S, style: Style) -> Self {
+        self.rules.push(Rule::Style(selector.into(), style));
+        self
+    }
+
+    /// Adds rules which apply when the media query matches, like `(max-width: 600px)`.
+    pub fn media<S: Into<String>>(mut self, query: S, css: Css) -> Self {
+        self.rules.push(Rule::Media(query.into(), css));
+        self
+    }
+
+    /// Returns the text of a style sheet with `&` replaced by the class.
+    pub fn to_css(&self, class: &str) -> String {
+        let mut out = String::new();
+        self.write(class, &mut out);
+        out
+    }
+
+    fn write(&self, class: &str, out: &mut String) {
+        let scope = format!(".{}", class);
+        for rule in &self.rules {
+            match *rule {
+                Rule::Style(ref selector, ref style) => {
+                    let selectors: Vec<String> = selector.split(',')
+                        .map(|part| {
+                            let part = part.trim();
+                            if part.contains('&') {
+                                part.replace('&', &scope)
+                            } else {
+                                format!("{} {}", scope, part)
+                            }
+                        })
+                        .collect();
+                    out.push_str(&format!("{} {{ {} }}\n", selectors.join(", "), style));
+                }
+                Rule::Media(ref query, ref css) => {
+                    out.push_str(&format!("@media {} {{\n", query));
+                    css.write(class, out);
+                    out.push_str("}\n");
+                }
+            }
+        }
+    }
+}
+
+struct Entry {
+    class: String,
+    text: String,
+    consumers: usize,
+}
+
+#[derive(Default)]
+struct Registry {
+    // Rules keep the order they were added in to keep the cascade stable
+    entries: Vec<Entry>,
+    // Text nodes of classes in the managed style element
+    applied: HashMap<String, Value>,
+    element: Option<Value>,
+}
+
+thread_local! {
+    static REGISTRY: RefCell<Registry> = RefCell::new(Registry::default());
+}
+
+/// A handle of a scoped class. Clones share the class, its rules
+/// are removed when the last handle is dropped.
+pub struct Scoped {
+    class: String,
+}
+
+impl Scoped {
+    /// Registers the rules and returns a handle of a class. The class
+    /// starts with the `name` and ends with a hash of the rules.
+    pub fn new(name: &str, css: &Css) -> Self {
+        let mut hasher = DefaultHasher::new();
+        css.to_css("&").hash(&mut hasher);
+        let class = format!("{}-{:06x}", name, hasher.finish() & 0xff_ffff);
+        REGISTRY.with(|registry| {
+            let mut registry = registry.borrow_mut();
+            if let Some(entry) = registry.entries.iter_mut().find(|entry| entry.class == class) {
+                entry.consumers += 1;
+                return;
+            }
+            let text = css.to_css(&class);
+            registry.entries.push(Entry { class: class.clone(), text, consumers: 1 });
+        });
+        Scoped { class }
+    }
+
+    /// Returns the generated class.
+    pub fn class(&self) -> &str {
+        &self.class
+    }
+}
+
+impl Clone for Scoped {
+    fn clone(&self) -> Self {
+        REGISTRY.with(|registry| {
+            let mut registry = registry.borrow_mut();
+            if let Some(entry) = registry.entries.iter_mut().find(|entry| entry.class == self.class) {
+                entry.consumers += 1;
+            }
+        });
+        Scoped { class: self.class.clone() }
+    }
+}
+
+impl Drop for Scoped {
+    fn drop(&mut self) {
+        REGISTRY.with(|registry| {
+            let mut registry = registry.borrow_mut();
+            if let Some(entry) = registry.entries.iter_mut().find(|entry| entry.class == self.class) {
+                entry.consumers -= 1;
+            }
+            registry.entries.retain(|entry| entry.consumers > 0);
+        });
+    }
+}
+
+impl fmt::Display for Scoped {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.class)
+    }
+}
+
+/// Returns the text of all registered rules. Put it to a `<style>` of a page
+/// rendered on a server.
+pub fn sheet() -> String {
+    REGISTRY.with(|registry| {
+        registry.borrow().entries.iter().map(|entry| entry.text.as_str()).collect()
+    })
+}
+
+/// Returns registered classes in the order they were added.
+pub fn classes() -> Vec<String> {
+    REGISTRY.with(|registry| {
+        registry.borrow().entries.iter().map(|entry| entry.class.clone()).collect()
+    })
+}
+
+/// Adds rules of new classes to the managed style element and
+/// removes rules of dropped ones.
+pub(crate) fn sync() {
+    REGISTRY.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        let registry = &mut *registry;
+        let stale: Vec<String> = registry.applied.keys()
+            .filter(|class| !registry.entries.iter().any(|entry| &entry.class == *class))
+            .cloned()
+            .collect();
+        for class in stale {
+            if let Some(node) = registry.applied.remove(&class) {
+                js! { @(no_return)
+                    var node = @{node};
+                    if (node.parentNode) {
+                        node.parentNode.removeChild(node);
+                    }
+                }
+            }
+        }
+        for entry in &registry.entries {
+            if registry.applied.contains_key(&entry.class) {
+                continue;
+            }
+            let element = registry.element.get_or_insert_with(|| {
+                #[cfg(feature = "csp")]
+                let nonce = ::csp::nonce();
+                #[cfg(not(feature = "csp"))]
+                let nonce: Option<String> = None;
+                let element: Value = js! {
+                    var element = document.createElement("style");
+                    element.setAttribute("data-yew", "scoped");
+                    var nonce = @{nonce};
+                    if (nonce) {
+                        element.setAttribute("nonce", nonce);
+                    }
+                    document.head.appendChild(element);
+                    return element;
+                };
+                element
+            }).clone();
+            let node = js! {
+                var node = document.createTextNode(@{&entry.text});
+                @{element}.appendChild(node);
+                return node;
+            };
+            registry.applied.insert(entry.class.clone(), node);
+        }
+    });
+}
@@ -0,0 +1,193 @@
+//! This module contains an adapter which registers a component as
+//! a custom element of a browser. It lets to use components of `yew`
+//! inside of applications written with any JS framework.
+//!
+//! Attributes of the element are mapped to properties of the component
+//! and callbacks of the component dispatch custom events:
+//!
+//! ```rust,ignore
+//! impl CustomElement for Counter {
+//!     fn observed_attributes() -> &'static [&'static str] {
+//!         &["step"]
+//!     }
+//!
+//!     fn properties(attributes: &Attributes, host: &Host) -> Props {
+//!         Props {
+//!             step: attributes.get("step").and_then(|step| step.parse().ok()).unwrap_or(1),
+//!             onchange: host.callback("counter-change"),
+//!         }
+//!     }
+//! }
+//!
+//! custom_element::define::<Counter>("yew-counter");
+//! ```
+//!
+//! ```html
+//! <yew-counter step="2"></yew-counter>
+//! <script>
+//!     document.querySelector("yew-counter")
+//!         .addEventListener("counter-change", e => console.log(e.detail));
+//! </script>
+//! ```
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use std::cell::RefCell;
+use std::collections::HashMap;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use std::rc::Rc;
+use stdweb::Value;
+use stdweb::web::Element;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::web::{INode, Node};
+use callback::Callback;
+use component::Component;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use component::ComponentMount;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use virtual_dom;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use virtual_dom::vcomp::Mount;
+
+/// Values of observed attributes of a custom element.
+pub type Attributes = HashMap<String, String>;
+
+/// A component which could be registered as a custom element.
+pub trait CustomElement: Component {
+    /// Returns names of attributes which change properties of the component.
+    fn observed_attributes() -> &'static [&'static str];
+
+    /// Creates properties from the values of attributes. Use the `host`
+    /// to create callbacks which dispatch custom events.
+    fn properties(attributes: &Attributes, host: &Host) -> Self::Properties;
+}
+
+/// The element which hosts a component.
+#[derive(Clone)]
+pub struct Host {
+    element: Element,
+}
+
+impl Host {
+    /// Returns the custom element.
+    pub fn element(&self) -> &Element {
+        &self.element
+    }
+
+    /// Creates a callback which dispatches a bubbling `CustomEvent`
+    /// with the value as a `detail` of the event.
+    pub fn callback<T: Into<Value>>(&self, kind: &'static str) -> Callback<T> {
+        let element = self.element.clone();
+        Callback::from(move |value: T| {
+            let detail = value.into();
+            js! { @(no_return)
+                var event = new CustomEvent(@{kind}, { detail: @{detail}, bubbles: true });
+                @{&element}.dispatchEvent(event);
+            }
+        })
+    }
+}
+
+/// A component mounted to a connected element.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+struct Instance<COMP: Component> {
+    host: Host,
+    attributes: Attributes,
+    mount: Option<ComponentMount<COMP>>,
+}
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+impl<COMP: CustomElement> Instance<COMP> {
+    fn connect(&mut self) {
+        let props = COMP::properties(&self.attributes, &self.host);
+        let mut mount = ComponentMount::<COMP>::new(props);
+        virtual_dom::register_root(&self.host.element);
+        mount.mount(&Node::from(self.host.element.clone()));
+        self.mount = Some(mount);
+    }
+
+    fn change(&mut self, name: String, value: Option<String>) {
+        match value {
+            Some(value) => self.attributes.insert(name, value),
+            None => self.attributes.remove(&name),
+        };
+        if let Some(last) = self.mount.take() {
+            let props = COMP::properties(&self.attributes, &self.host);
+            let mut mount = ComponentMount::<COMP>::new(props);
+            mount.patch(&Node::from(self.host.element.clone()), Box::new(last));
+            self.mount = Some(mount);
+        }
+    }
+
+    fn disconnect(&mut self) {
+        if let Some(mut mount) = self.mount.take() {
+            if let Some(node) = mount.unmount() {
+                if self.host.element.remove_child(&node).is_err() {
+                    warn!("custom element lost the root node of the component");
+                }
+            }
+            virtual_dom::unregister_root(&self.host.element);
+        }
+    }
+}
+
+/// Registers the component as a custom element with the `name`.
+/// The name has to contain a dash. The component renders into the
+/// element when it's connected to a document and it's destroyed
+/// when the element is disconnected.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+pub fn define<COMP: CustomElement>(name: &str) {
+    if !name.contains('-') {
+        panic!("name of a custom element has to contain a dash: {}", name);
+    }
+    let attributes = COMP::observed_attributes().iter()
+        .map(|name| name.to_string())
+        .collect::<Vec<_>>();
+    let create = |element: Element| {
+        let instance = Instance::<COMP> {
+            host: Host { element: element.clone() },
+            attributes: Attributes::new(),
+            mount: None,
+        };
+        let instance = Rc::new(RefCell::new(instance));
+        let connect = {
+            let instance = instance.clone();
+            move || instance.borrow_mut().connect()
+        };
+        let change = {
+            let instance = instance.clone();
+            move |name: String, value: Option<String>| instance.borrow_mut().change(name, value)
+        };
+        let disconnect = move || instance.borrow_mut().disconnect();
+        js! { @(no_return)
+            var element = @{element};
+            element.__yew = {
+                connect: @{connect},
+                change: @{change},
+                disconnect: @{disconnect},
+            };
+        }
+    };
+    js! { @(no_return)
+        var create = @{create};
+        var attributes = @{attributes};
+        class YewElement extends HTMLElement {
+            static get observedAttributes() {
+                return attributes;
+            }
+            constructor() {
+                super();
+                create(this);
+            }
+            connectedCallback() {
+                this.__yew.connect();
+            }
+            attributeChangedCallback(name, _, value) {
+                this.__yew.change(name, value);
+            }
+            disconnectedCallback() {
+                this.__yew.disconnect();
+            }
+        }
+        customElements.define(@{name}, YewElement);
+    }
+}
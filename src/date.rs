@@ -0,0 +1,298 @@
+//! This module contains dates of the calendar and times of the day without
+//! time zones, which pickers and forms use as values:
+//!
+//! ```rust,ignore
+//! let due = Date::parse("2024-01-31").expect("a date");
+//! assert_eq!(due.add_months(1), Date::new(2024, 2, 29).unwrap());
+//! assert_eq!(due.weekday(), 3);
+//! assert_eq!(Time::new(9, 30).unwrap().to_string(), "09:30");
+//! ```
+//!
+//! They're shown to users with `IntlService::calendar_date` and
+//! `IntlService::time_of_day`.
+
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const DAY_SECONDS: u64 = 86_400;
+
+fn is_leap(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Returns the number of days of the month from 1 to 12.
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// A day of the proleptic Gregorian calendar. Dates are ordered
+/// chronologically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    /// Creates a date of the month from 1 to 12. Returns `None` if the day
+    /// doesn't exist, like February 30.
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Date> {
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Date { year, month, day })
+    }
+
+    /// Parses a date in the ISO 8601 format `YYYY-MM-DD`, which `input`
+    /// elements and JSON use.
+    pub fn parse(text: &str) -> Option<Date> {
+        let mut parts = text.splitn(3, '-');
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        Date::new(year, month, day)
+    }
+
+    /// Returns the date which is `days` days after the Unix epoch, negative
+    /// for earlier dates.
+    pub fn from_days(days: i64) -> Date {
+        // See http://howardhinnant.github.io/date_algorithms.html
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+        Date { year: year as i32, month: month as u32, day: day as u32 }
+    }
+
+    /// Returns the date of the time in UTC.
+    pub fn from_time(time: SystemTime) -> Date {
+        let days = match time.duration_since(UNIX_EPOCH) {
+            Ok(duration) => (duration.as_secs() / DAY_SECONDS) as i64,
+            Err(err) => {
+                let duration = err.duration();
+                let days = (duration.as_secs() / DAY_SECONDS) as i64;
+                if duration.as_secs() % DAY_SECONDS == 0 && duration.subsec_nanos() == 0 {
+                    -days
+                } else {
+                    -days - 1
+                }
+            }
+        };
+        Date::from_days(days)
+    }
+
+    /// Returns the year.
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// Returns the month from 1 to 12.
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    /// Returns the day of the month from 1.
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    /// Returns the number of days since the Unix epoch.
+    pub fn days(&self) -> i64 {
+        let year = i64::from(self.year) - if self.month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let yoe = year - era * 400;
+        let month = i64::from(self.month);
+        let mp = if month > 2 { month - 3 } else { month + 9 };
+        let doy = (153 * mp + 2) / 5 + i64::from(self.day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    /// Returns the midnight of the date in UTC.
+    pub fn to_time(&self) -> SystemTime {
+        let days = self.days();
+        let duration = Duration::from_secs(days.unsigned_abs() * DAY_SECONDS);
+        if days < 0 {
+            UNIX_EPOCH - duration
+        } else {
+            UNIX_EPOCH + duration
+        }
+    }
+
+    /// Returns the ISO 8601 weekday: 1 is Monday and 7 is Sunday.
+    pub fn weekday(&self) -> u32 {
+        // The epoch was a Thursday
+        ((self.days() + 3).rem_euclid(7) + 1) as u32
+    }
+
+    /// Returns the date `days` days later, or earlier if it's negative.
+    pub fn add_days(&self, days: i64) -> Date {
+        Date::from_days(self.days() + days)
+    }
+
+    /// Returns the same day `months` months later, or earlier if it's negative.
+    /// The day is clamped to the end of a shorter month: January 31 plus
+    /// a month is the last day of February.
+    pub fn add_months(&self, months: i32) -> Date {
+        let index = self.year * 12 + self.month as i32 - 1 + months;
+        let year = index.div_euclid(12);
+        let month = index.rem_euclid(12) as u32 + 1;
+        let day = self.day.min(days_in_month(year, month));
+        Date { year, month, day }
+    }
+
+    /// Returns the same day `years` years later, or earlier if it's negative.
+    pub fn add_years(&self, years: i32) -> Date {
+        self.add_months(years * 12)
+    }
+
+    /// Returns the first day of the month of the date.
+    pub fn first_of_month(&self) -> Date {
+        Date { day: 1, ..*self }
+    }
+
+    /// Returns the last day of the month of the date.
+    pub fn last_of_month(&self) -> Date {
+        Date { day: days_in_month(self.year, self.month), ..*self }
+    }
+
+    /// Returns the first day of the week of the date, where weeks start
+    /// on the ISO 8601 weekday `first_weekday`.
+    pub fn start_of_week(&self, first_weekday: u32) -> Date {
+        let back = (i64::from(self.weekday()) - i64::from(first_weekday)).rem_euclid(7);
+        self.add_days(-back)
+    }
+
+    /// Returns the nearest date between the bounds.
+    pub fn clamp_to(&self, min: Option<Date>, max: Option<Date>) -> Date {
+        match (min, max) {
+            (Some(min), _) if *self < min => min,
+            (_, Some(max)) if *self > max => max,
+            _ => *self,
+        }
+    }
+
+    /// Returns `true` if the date is between the bounds.
+    pub fn is_within(&self, min: Option<Date>, max: Option<Date>) -> bool {
+        self.clamp_to(min, max) == *self
+    }
+}
+
+impl fmt::Display for Date {
+    /// Writes the date in the ISO 8601 format `YYYY-MM-DD`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Days from the start to the end, both included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DateRange {
+    /// The first day.
+    pub start: Date,
+    /// The last day, which isn't before the start.
+    pub end: Date,
+}
+
+impl DateRange {
+    /// Creates a range between two dates in any order.
+    pub fn new(a: Date, b: Date) -> Self {
+        if a <= b {
+            DateRange { start: a, end: b }
+        } else {
+            DateRange { start: b, end: a }
+        }
+    }
+
+    /// Creates a range of one day.
+    pub fn day(date: Date) -> Self {
+        DateRange { start: date, end: date }
+    }
+
+    /// Returns `true` if the date is in the range.
+    pub fn contains(&self, date: Date) -> bool {
+        self.start <= date && date <= self.end
+    }
+
+    /// Returns the number of days in the range.
+    pub fn days(&self) -> i64 {
+        self.end.days() - self.start.days() + 1
+    }
+}
+
+/// A time of the day with minutes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time {
+    hour: u32,
+    minute: u32,
+}
+
+impl Time {
+    /// The start of the day.
+    pub const MIDNIGHT: Time = Time { hour: 0, minute: 0 };
+    /// The last minute of the day.
+    pub const LAST_MINUTE: Time = Time { hour: 23, minute: 59 };
+
+    /// Creates a time of the hour from 0 to 23. Returns `None` if it
+    /// doesn't exist.
+    pub fn new(hour: u32, minute: u32) -> Option<Time> {
+        if hour > 23 || minute > 59 {
+            return None;
+        }
+        Some(Time { hour, minute })
+    }
+
+    /// Parses a time in the format `HH:MM` of `input` elements. Seconds
+    /// are ignored.
+    pub fn parse(text: &str) -> Option<Time> {
+        let mut parts = text.splitn(3, ':');
+        let hour = parts.next()?.parse().ok()?;
+        let minute = parts.next()?.parse().ok()?;
+        Time::new(hour, minute)
+    }
+
+    /// Returns the time `minutes` minutes after midnight, wrapped around
+    /// the day.
+    pub fn from_minutes(minutes: u32) -> Time {
+        let minutes = minutes % (24 * 60);
+        Time { hour: minutes / 60, minute: minutes % 60 }
+    }
+
+    /// Returns the hour from 0 to 23.
+    pub fn hour(&self) -> u32 {
+        self.hour
+    }
+
+    /// Returns the minute from 0 to 59.
+    pub fn minute(&self) -> u32 {
+        self.minute
+    }
+
+    /// Returns minutes since midnight.
+    pub fn minutes(&self) -> u32 {
+        self.hour * 60 + self.minute
+    }
+
+    /// Returns the time of the date in UTC.
+    pub fn on(&self, date: Date) -> SystemTime {
+        date.to_time() + Duration::from_secs(u64::from(self.minutes()) * 60)
+    }
+}
+
+impl fmt::Display for Time {
+    /// Writes the time in the format `HH:MM`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour, self.minute)
+    }
+}
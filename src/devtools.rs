@@ -0,0 +1,167 @@
+//! This module contains a bridge to an external inspector like a browser
+//! extension. It's compiled with the `devtools` feature only.
+//!
+//! Events are posted to the window as messages with `source: "yew-devtools"`:
+//!
+//! * `mount` with `id`, `name`, `parent` and `state` of a component;
+//! * `update` with `id`, the count of handled `messages`, `rendered` flag and `state`;
+//! * `unmount` with `id`;
+//! * `message` with `app`, `msg`, `model` and `elapsed` milliseconds of an update
+//!   of an app which uses the `DevTools` middleware;
+//! * `tree` with all mounted components, in reply to a message
+//!   `{ source: "yew-devtools-extension", type: "inspect" }`;
+//! * `profile` with times of renders of component types, in reply to a message
+//!   of `type: "profile"` if the `profiler` feature is enabled.
+//!
+//! The state of a component is published if it implements `Component::inspect`.
+
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use serde_json::{Map, Value};
+use html;
+use middleware::Middleware;
+use services::Task;
+use services::backend;
+
+/// A mounted component known by the inspector.
+struct Node {
+    name: &'static str,
+    parent: Option<usize>,
+    state: Option<String>,
+}
+
+thread_local! {
+    static NODES: RefCell<BTreeMap<usize, Node>> = RefCell::new(BTreeMap::new());
+    /// Components which are rendering now. The last one is a parent of mounted components.
+    static RENDERING: RefCell<Vec<usize>> = RefCell::new(Vec::new());
+    static NEXT_ID: Cell<usize> = Cell::new(1);
+    /// A listener of requests of an inspector.
+    static CONNECTION: RefCell<Option<Box<Task>>> = RefCell::new(None);
+}
+
+fn object(fields: Vec<(&str, Value)>) -> Value {
+    let mut object = Map::new();
+    for (key, value) in fields {
+        object.insert(key.to_owned(), value);
+    }
+    Value::Object(object)
+}
+
+fn optional(value: Option<String>) -> Value {
+    value.map(Value::String).unwrap_or(Value::Null)
+}
+
+/// Posts an event to an inspector.
+pub(crate) fn post(kind: &str, payload: Value) {
+    connect();
+    backend::devtools().post(kind, &payload);
+}
+
+/// Starts listening for requests of an inspector.
+fn connect() {
+    if CONNECTION.with(|connection| connection.borrow().is_some()) {
+        return;
+    }
+    let listener = backend::devtools().listen(Box::new(|kind: String| match kind.as_str() {
+        "inspect" => post("tree", tree()),
+        #[cfg(feature = "profiler")]
+        "profile" => post("profile", ::profiler::rows()),
+        _ => {}
+    }));
+    CONNECTION.with(|connection| *connection.borrow_mut() = Some(listener));
+}
+
+/// Returns all mounted components.
+fn tree() -> Value {
+    NODES.with(|nodes| {
+        let nodes = nodes.borrow().iter().map(|(id, node)| {
+            object(vec![
+                ("id", Value::from(*id)),
+                ("name", Value::from(node.name)),
+                ("parent", node.parent.map(Value::from).unwrap_or(Value::Null)),
+                ("state", optional(node.state.clone())),
+            ])
+        }).collect();
+        Value::Array(nodes)
+    })
+}
+
+/// Registers a created component and returns its id.
+pub(crate) fn mounted(name: &'static str, state: Option<String>) -> usize {
+    let id = NEXT_ID.with(|next| next.replace(next.get() + 1));
+    let parent = RENDERING.with(|rendering| rendering.borrow().last().cloned());
+    post("mount", object(vec![
+        ("id", Value::from(id)),
+        ("name", Value::from(name)),
+        ("parent", parent.map(Value::from).unwrap_or(Value::Null)),
+        ("state", optional(state.clone())),
+    ]));
+    NODES.with(|nodes| nodes.borrow_mut().insert(id, Node { name, parent, state }));
+    id
+}
+
+/// Publishes an update of a component.
+pub(crate) fn updated(id: usize, messages: usize, rendered: bool, state: Option<String>) {
+    post("update", object(vec![
+        ("id", Value::from(id)),
+        ("messages", Value::from(messages)),
+        ("rendered", Value::from(rendered)),
+        ("state", optional(state.clone())),
+    ]));
+    NODES.with(|nodes| {
+        if let Some(node) = nodes.borrow_mut().get_mut(&id) {
+            node.state = state;
+        }
+    });
+}
+
+/// Forgets a removed component.
+pub(crate) fn unmounted(id: usize) {
+    NODES.with(|nodes| nodes.borrow_mut().remove(&id));
+    post("unmount", object(vec![("id", Value::from(id))]));
+}
+
+/// Marks the component as rendering until the scope is dropped.
+pub(crate) fn enter(id: usize) -> Scope {
+    RENDERING.with(|rendering| rendering.borrow_mut().push(id));
+    Scope
+}
+
+/// A scope of a rendering component.
+pub(crate) struct Scope;
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        RENDERING.with(|rendering| rendering.borrow_mut().pop());
+    }
+}
+
+/// A middleware which publishes messages and models of an app.
+pub struct DevTools {
+    app: String,
+}
+
+impl DevTools {
+    /// Creates a middleware for the app with the name.
+    pub fn new(app: &str) -> Self {
+        DevTools {
+            app: app.to_owned(),
+        }
+    }
+}
+
+impl<MOD: Debug, MSG: Debug> Middleware<MOD, MSG> for DevTools {
+    fn update(&mut self, model: &mut MOD, msg: MSG, next: &mut FnMut(&mut MOD, MSG)) {
+        let message = format!("{:?}", msg);
+        let started = html::now();
+        next(model, msg);
+        let elapsed = html::now() - started;
+        post("message", object(vec![
+            ("app", Value::from(self.app.as_str())),
+            ("msg", Value::String(message)),
+            ("model", Value::String(format!("{:?}", model))),
+            ("elapsed", Value::from(elapsed)),
+        ]));
+    }
+}
@@ -0,0 +1,194 @@
+//! This module contains the direction of text for apps in right-to-left
+//! languages, like Arabic or Hebrew.
+//!
+//! The direction of the thread is set explicitly with `set_direction` or follows
+//! the current locale of the `i18n` feature. Apps put it to the `dir` attribute
+//! of the document after renders, and styles use logical sides instead of left
+//! and right:
+//!
+//! ```rust,ignore
+//! let style = Style::new()
+//!     .set_logical("margin-inline-start", "8px")
+//!     .set_logical("text-align", "end");
+//! ```
+//!
+//! A subtree of another direction is built with `with_direction` and put to
+//! a tag with the `dir` attribute:
+//!
+//! ```rust,ignore
+//! html! {
+//!     <blockquote dir=Direction::Rtl,>
+//!         { direction::with_direction(Direction::Rtl, || self.view_quote()) }
+//!     </blockquote>
+//! }
+//! ```
+
+use std::cell::Cell;
+use std::fmt;
+
+/// A direction of text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Left to right, the default.
+    Ltr,
+    /// Right to left.
+    Rtl,
+}
+
+/// Languages which are written from right to left.
+const RTL_LANGUAGES: &[&str] = &["ar", "arc", "ckb", "dv", "fa", "he", "iw", "ks", "ku", "ps", "sd", "ug", "ur", "yi"];
+
+impl Direction {
+    /// Returns the direction of the language of a locale, like `ar-EG`.
+    pub fn of_locale(locale: &str) -> Direction {
+        let language = locale.split(['-', '_']).next().unwrap_or("").to_lowercase();
+        if RTL_LANGUAGES.contains(&language.as_str()) {
+            Direction::Rtl
+        } else {
+            Direction::Ltr
+        }
+    }
+
+    /// Returns the value of the `dir` attribute.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Direction::Ltr => "ltr",
+            Direction::Rtl => "rtl",
+        }
+    }
+
+    /// Checks the direction is right to left.
+    pub fn is_rtl(&self) -> bool {
+        *self == Direction::Rtl
+    }
+
+    /// Returns the opposite direction.
+    pub fn reverse(&self) -> Direction {
+        match *self {
+            Direction::Ltr => Direction::Rtl,
+            Direction::Rtl => Direction::Ltr,
+        }
+    }
+
+    /// Returns the physical side where lines start: `left` or `right`.
+    pub fn start(&self) -> &'static str {
+        match *self {
+            Direction::Ltr => "left",
+            Direction::Rtl => "right",
+        }
+    }
+
+    /// Returns the physical side where lines end: `right` or `left`.
+    pub fn end(&self) -> &'static str {
+        self.reverse().start()
+    }
+
+    /// Replaces logical sides in a CSS property with physical ones:
+    /// `margin-inline-start` is `margin-left` for left-to-right text,
+    /// `inset-inline-end` is `left` for right-to-left text.
+    pub fn physical_property(&self, property: &str) -> String {
+        let sides = [("-inline-start", self.start()), ("-inline-end", self.end())];
+        for &(logical, side) in sides.iter() {
+            if let Some(position) = property.find(logical) {
+                let (prefix, rest) = property.split_at(position);
+                let suffix = &rest[logical.len()..];
+                return if prefix == "inset" {
+                    format!("{}{}", side, suffix)
+                } else {
+                    format!("{}-{}{}", prefix, side, suffix)
+                };
+            }
+        }
+        property.to_owned()
+    }
+
+    /// Replaces logical values `start` and `end` of properties like
+    /// `text-align`, `float` and `clear` with physical ones.
+    pub fn physical_value<'a>(&self, property: &str, value: &'a str) -> &'a str {
+        match property {
+            "text-align" | "float" | "clear" => match value {
+                "start" => self.start(),
+                "end" => self.end(),
+                _ => value,
+            },
+            _ => value,
+        }
+    }
+}
+
+impl Default for Direction {
+    fn default() -> Self {
+        Direction::Ltr
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+thread_local! {
+    static DIRECTION: Cell<Option<Direction>> = Cell::new(None);
+    static APPLIED: Cell<Option<Direction>> = Cell::new(None);
+}
+
+/// Sets the direction of the thread. It's put to the document
+/// after the next render of an app.
+pub fn set_direction(direction: Direction) {
+    DIRECTION.with(|current| current.set(Some(direction)));
+}
+
+/// Returns the direction which was set, the direction of the current locale
+/// with the `i18n` feature or `Ltr`.
+pub fn direction() -> Direction {
+    managed().unwrap_or_default()
+}
+
+/// Returns the direction if an app chose it and it should be put to the document.
+fn managed() -> Option<Direction> {
+    if let Some(direction) = DIRECTION.with(Cell::get) {
+        return Some(direction);
+    }
+    #[cfg(feature = "i18n")]
+    {
+        if !::i18n::locales().is_empty() {
+            return Some(Direction::of_locale(&::i18n::locale()));
+        }
+    }
+    None
+}
+
+/// Calls the function with another direction, for a subtree of a view
+/// in another language.
+pub fn with_direction<F, T>(direction: Direction, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let previous = DIRECTION.with(|current| current.replace(Some(direction)));
+    let result = f();
+    DIRECTION.with(|current| current.set(previous));
+    result
+}
+
+/// Returns the physical side where lines start in the current direction.
+pub fn start() -> &'static str {
+    direction().start()
+}
+
+/// Returns the physical side where lines end in the current direction.
+pub fn end() -> &'static str {
+    direction().end()
+}
+
+/// Puts the direction to the `dir` attribute of the document element if
+/// it's changed. The document isn't touched until an app chooses a direction.
+pub(crate) fn sync_document() {
+    if let Some(direction) = managed() {
+        if APPLIED.with(|applied| applied.replace(Some(direction))) != Some(direction) {
+            js! { @(no_return)
+                document.documentElement.setAttribute("dir", @{direction.as_str()});
+            }
+        }
+    }
+}
@@ -0,0 +1,397 @@
+//! This module contains listeners to drag items between lists. A drag source
+//! keeps a typed payload and a drop target gets messages with the payload and
+//! the index where it would be inserted:
+//!
+//! ```rust,ignore
+//! html! {
+//!     <ul listener=dnd::target(|event| Msg::Board(column, event)),>
+//!         { for self.cards(column).map(|card| html! {
+//!             <li listener=dnd::source(card.id, Msg::Drag),>{ &card.title }</li>
+//!         }) }
+//!     </ul>
+//! }
+//! ```
+//!
+//! Browsers drag with the native drag and drop of HTML5. Touch pointers don't
+//! support it, so they drag after a short move instead. Put `touch-action: none`
+//! to styles of sources to keep the page from scrolling under a finger.
+//!
+//! An index is counted among element children of a target along the vertical
+//! axis, or the horizontal one if the target has `data-drop-axis="x"`.
+//! Children with `data-drop-placeholder` aren't counted, so a placeholder
+//! could be rendered at the hover index without moving it.
+
+use std::any::Any;
+use std::marker::PhantomData;
+use std::cell::RefCell;
+use std::rc::Rc;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::{Reference, Value};
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::unstable::TryInto;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::web::Element;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use html::AppSender;
+use virtual_dom::Listener;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use virtual_dom::{ListenerHandle, ListenerOptions};
+#[cfg(feature = "testing")]
+use virtual_dom::Simulated;
+
+/// An event of a drag source.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DragEvent {
+    /// A user started to drag the source.
+    Start,
+    /// The drag ended. `dropped` is `true` if a target took the payload.
+    End {
+        /// The payload was dropped to a target.
+        dropped: bool,
+    },
+}
+
+/// An action of a drag over a target without a payload. Tests simulate
+/// `ondrop` events of targets with it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DropAction {
+    /// A payload is over the target before the child with the index.
+    Over(usize),
+    /// The payload left the target.
+    Leave,
+    /// The payload is dropped before the child with the index.
+    Drop(usize),
+}
+
+/// An event of a drop target with the dragged payload.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DropEvent<P> {
+    /// The payload entered the target or moved to another index.
+    Over {
+        /// The dragged payload.
+        payload: P,
+        /// The index of a child before which the payload would be inserted.
+        index: usize,
+    },
+    /// The payload left the target without a drop.
+    Leave,
+    /// The payload is dropped.
+    Drop {
+        /// The dropped payload.
+        payload: P,
+        /// The index of a child before which the payload is inserted.
+        index: usize,
+    },
+}
+
+thread_local! {
+    static DRAGGED: RefCell<Option<Rc<Any>>> = RefCell::new(None);
+}
+
+/// Returns the payload which is dragged now if it has the type `P`.
+pub fn dragged<P: Clone + 'static>() -> Option<P> {
+    DRAGGED.with(|dragged| {
+        dragged.borrow().as_ref().and_then(|payload| payload.downcast_ref::<P>().cloned())
+    })
+}
+
+/// Returns an event of a target or `None` if the dragged payload has another type.
+fn drop_event<P: Clone + 'static>(action: DropAction) -> Option<DropEvent<P>> {
+    let payload = dragged::<P>()?;
+    match action {
+        DropAction::Over(index) => Some(DropEvent::Over { payload, index }),
+        DropAction::Leave => Some(DropEvent::Leave),
+        DropAction::Drop(index) => {
+            // The source could be removed before the drag ends
+            DRAGGED.with(|dragged| dragged.borrow_mut().take());
+            Some(DropEvent::Drop { payload, index })
+        }
+    }
+}
+
+fn drag_event<P: Clone + 'static>(payload: &P, event: DragEvent) {
+    DRAGGED.with(|dragged| {
+        *dragged.borrow_mut() = match event {
+            DragEvent::Start => Some(Rc::new(payload.clone())),
+            DragEvent::End { .. } => None,
+        };
+    });
+}
+
+/// A listener which makes an element draggable with the payload.
+pub struct DragSource<P, F> {
+    payload: P,
+    handler: Option<F>,
+}
+
+/// Creates a listener of a draggable element with the payload.
+pub fn source<P, F>(payload: P, handler: F) -> DragSource<P, F> {
+    DragSource {
+        payload,
+        handler: Some(handler),
+    }
+}
+
+impl<P, F, MSG> Listener<MSG> for DragSource<P, F>
+where
+    MSG: 'static,
+    P: Clone + 'static,
+    F: Fn(DragEvent) -> MSG + 'static,
+{
+    fn kind(&self) -> &'static str {
+        "ondrag"
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn attach(&mut self, element: &Element, mut sender: AppSender<MSG>) -> ListenerHandle {
+        let handler = self.handler.take().expect("tried to attach listener twice");
+        let payload = self.payload.clone();
+        install_driver();
+        js! { @(no_return)
+            var element = @{element};
+            element.__yew_drag_source = true;
+            element.draggable = true;
+        }
+        let listener = move |event: Reference| {
+            let detail: Value = js! {
+                var detail = @{event}.detail;
+                detail.accepted = true;
+                return detail;
+            };
+            let dropped: Value = js!( return @{&detail}.dropped; );
+            let event = match js!( return @{&detail}.phase; ).into_string() {
+                Some(ref phase) if phase == "start" => DragEvent::Start,
+                _ => DragEvent::End { dropped: dropped == Value::Bool(true) },
+            };
+            drag_event(&payload, event);
+            sender.send(handler(event));
+        };
+        ListenerHandle::attach_named(element, "yew-drag", ListenerOptions::default(), listener)
+    }
+
+    #[cfg(feature = "testing")]
+    fn simulate(&mut self, data: &Any) -> Option<Simulated<MSG>> {
+        let event = *data.downcast_ref::<DragEvent>()?;
+        let handler = self.handler.as_ref()?;
+        drag_event(&self.payload, event);
+        Some(Simulated {
+            msg: handler(event),
+            stop_propagation: true,
+        })
+    }
+}
+
+/// A listener which takes dragged payloads of the type `P`.
+pub struct DropTarget<P, F> {
+    handler: Option<F>,
+    _payload: PhantomData<P>,
+}
+
+/// Creates a listener of an element which takes payloads of the type `P`.
+/// Payloads of other types can't be dropped to it.
+pub fn target<P, F>(handler: F) -> DropTarget<P, F> {
+    DropTarget {
+        handler: Some(handler),
+        _payload: PhantomData,
+    }
+}
+
+impl<P, F, MSG> Listener<MSG> for DropTarget<P, F>
+where
+    MSG: 'static,
+    P: Clone + 'static,
+    F: Fn(DropEvent<P>) -> MSG + 'static,
+{
+    fn kind(&self) -> &'static str {
+        "ondrop"
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn attach(&mut self, element: &Element, mut sender: AppSender<MSG>) -> ListenerHandle {
+        let handler = self.handler.take().expect("tried to attach listener twice");
+        install_driver();
+        js! { @(no_return)
+            @{element}.__yew_drop_target = true;
+        }
+        let listener = move |event: Reference| {
+            let detail: Value = js!( return @{event}.detail; );
+            let index: Value = js!( return @{&detail}.index; );
+            let index: f64 = index.try_into().unwrap_or(0.0);
+            let index = index as usize;
+            let action = match js!( return @{&detail}.phase; ).into_string() {
+                Some(ref phase) if phase == "over" => DropAction::Over(index),
+                Some(ref phase) if phase == "drop" => DropAction::Drop(index),
+                _ => DropAction::Leave,
+            };
+            if let Some(event) = drop_event(action) {
+                js! { @(no_return)
+                    @{detail}.accepted = true;
+                }
+                sender.send(handler(event));
+            }
+        };
+        ListenerHandle::attach_named(element, "yew-drop", ListenerOptions::default(), listener)
+    }
+
+    #[cfg(feature = "testing")]
+    fn simulate(&mut self, data: &Any) -> Option<Simulated<MSG>> {
+        let action = *data.downcast_ref::<DropAction>()?;
+        let handler = self.handler.as_ref()?;
+        Some(Simulated {
+            msg: handler(drop_event(action)?),
+            stop_propagation: true,
+        })
+    }
+}
+
+/// Adds listeners of the document which turn native drags and moves of
+/// touch pointers to `yew-drag` events of sources and `yew-drop` events
+/// of targets. They're added once per page.
+fn install_driver() {
+    js! { @(no_return)
+        if (window.__yew_dnd) {
+            return;
+        }
+        var state = window.__yew_dnd = {
+            source: null, target: null, index: -1, accepted: false, dropped: false, pointer: null,
+        };
+        var closest = function(node, flag) {
+            while (node && !node[flag]) {
+                node = node.parentNode;
+            }
+            return node;
+        };
+        var send = function(element, kind, detail) {
+            element.dispatchEvent(new CustomEvent(kind, { detail: detail }));
+            return detail.accepted === true;
+        };
+        var indexOf = function(target, x, y) {
+            var horizontal = target.getAttribute("data-drop-axis") === "x";
+            var index = 0;
+            var children = target.children;
+            for (var i = 0; i < children.length; i++) {
+                var child = children[i];
+                if (child.hasAttribute("data-drop-placeholder")) {
+                    continue;
+                }
+                var rect = child.getBoundingClientRect();
+                var middle = horizontal ? rect.left + rect.width / 2 : rect.top + rect.height / 2;
+                if ((horizontal ? x : y) < middle) {
+                    return index;
+                }
+                index++;
+            }
+            return index;
+        };
+        var leave = function() {
+            if (state.target) {
+                send(state.target, "yew-drop", { phase: "leave" });
+            }
+            state.target = null;
+            state.index = -1;
+            state.accepted = false;
+        };
+        var start = function(node) {
+            var source = closest(node, "__yew_drag_source");
+            if (!source || !send(source, "yew-drag", { phase: "start" })) {
+                return false;
+            }
+            state.source = source;
+            state.dropped = false;
+            return true;
+        };
+        // Returns `true` if the target under the point takes the payload
+        var hover = function(node, x, y) {
+            var target = closest(node, "__yew_drop_target");
+            if (target !== state.target) {
+                leave();
+                state.target = target;
+            }
+            if (target) {
+                var index = indexOf(target, x, y);
+                if (index !== state.index) {
+                    state.index = index;
+                    state.accepted = send(target, "yew-drop", { phase: "over", index: index });
+                }
+            }
+            return state.accepted;
+        };
+        var drop = function() {
+            if (state.target && state.accepted) {
+                send(state.target, "yew-drop", { phase: "drop", index: state.index });
+                state.dropped = true;
+                state.target = null;
+            }
+        };
+        var end = function() {
+            if (!state.source) {
+                return;
+            }
+            leave();
+            send(state.source, "yew-drag", { phase: "end", dropped: state.dropped });
+            state.source = null;
+            state.pointer = null;
+        };
+        document.addEventListener("dragstart", function(event) {
+            if (start(event.target)) {
+                event.dataTransfer.effectAllowed = "move";
+                // Firefox doesn't drag without data
+                event.dataTransfer.setData("text/plain", "");
+            }
+        });
+        document.addEventListener("dragover", function(event) {
+            if (state.source && hover(event.target, event.clientX, event.clientY)) {
+                event.preventDefault();
+                event.dataTransfer.dropEffect = "move";
+            }
+        });
+        document.addEventListener("drop", function(event) {
+            if (state.source && state.accepted) {
+                event.preventDefault();
+                drop();
+                end();
+            }
+        });
+        document.addEventListener("dragend", end);
+        document.addEventListener("pointerdown", function(event) {
+            if (event.pointerType === "mouse" || !closest(event.target, "__yew_drag_source")) {
+                return;
+            }
+            state.pointer = { id: event.pointerId, node: event.target, x: event.clientX, y: event.clientY, active: false };
+        });
+        document.addEventListener("pointermove", function(event) {
+            var pointer = state.pointer;
+            if (!pointer || pointer.id !== event.pointerId) {
+                return;
+            }
+            if (!pointer.active) {
+                if (Math.abs(event.clientX - pointer.x) + Math.abs(event.clientY - pointer.y) < 8) {
+                    return;
+                }
+                if (!start(pointer.node)) {
+                    state.pointer = null;
+                    return;
+                }
+                pointer.active = true;
+            }
+            event.preventDefault();
+            var node = document.elementFromPoint(event.clientX, event.clientY);
+            hover(node, event.clientX, event.clientY);
+        });
+        document.addEventListener("pointerup", function(event) {
+            if (state.pointer && state.pointer.id === event.pointerId) {
+                if (state.pointer.active) {
+                    drop();
+                    end();
+                }
+                state.pointer = null;
+            }
+        });
+        document.addEventListener("pointercancel", function(event) {
+            if (state.pointer && state.pointer.id === event.pointerId) {
+                end();
+                state.pointer = null;
+            }
+        });
+    }
+}
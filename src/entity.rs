@@ -0,0 +1,320 @@
+//! This module contains a normalized store of entities. Every type of records
+//! has a table of records by their ids, and records refer to each other with
+//! typed `Ref`s instead of nested copies, so an update of a record is seen
+//! by every view which uses it:
+//!
+//! ```rust,ignore
+//! struct Post { id: u64, title: String, author: Ref<User>, comments: Vec<Ref<Comment>> }
+//!
+//! impl Entity for Post {
+//!     type Id = u64;
+//!
+//!     fn id(&self) -> u64 { self.id }
+//! }
+//!
+//! // A message from the websocket
+//! Msg::Synced(posts, users) => {
+//!     self.store.insert_all(posts);
+//!     self.store.insert_all(users);
+//! }
+//!
+//! // A view model which is computed again only when the store changes
+//! let feed = Selector::new(|store: &Store| {
+//!     store.all::<Post>().into_iter()
+//!         .map(|post| (store.resolve(&post.author), post))
+//!         .collect::<Vec<_>>()
+//! });
+//! let posts = feed.get(&self.store);
+//! ```
+
+use std::any::{Any, TypeId};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::rc::Rc;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A record which is kept in a table of a `Store` by its id.
+pub trait Entity: 'static {
+    /// The type of ids of the records.
+    type Id: Eq + Hash + Clone + 'static;
+
+    /// Returns the id of the record.
+    fn id(&self) -> Self::Id;
+}
+
+/// A typed reference to a record by its id. It's serialized as the id.
+pub struct Ref<E: Entity> {
+    /// The id of the referred record.
+    pub id: E::Id,
+    _entity: PhantomData<E>,
+}
+
+impl<E: Entity> Ref<E> {
+    /// Creates a reference to a record by its id.
+    pub fn new(id: E::Id) -> Self {
+        Ref {
+            id,
+            _entity: PhantomData,
+        }
+    }
+
+    /// Creates a reference to the record.
+    pub fn to(record: &E) -> Self {
+        Ref::new(record.id())
+    }
+}
+
+impl<E: Entity> Clone for Ref<E> {
+    fn clone(&self) -> Self {
+        Ref::new(self.id.clone())
+    }
+}
+
+impl<E: Entity> PartialEq for Ref<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<E: Entity> Eq for Ref<E> {}
+
+impl<E: Entity> Hash for Ref<E> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<E: Entity> fmt::Debug for Ref<E>
+where
+    E::Id: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Ref({:?})", self.id)
+    }
+}
+
+impl<E: Entity> Serialize for Ref<E>
+where
+    E::Id: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.id.serialize(serializer)
+    }
+}
+
+impl<'de, E: Entity> Deserialize<'de> for Ref<E>
+where
+    E::Id: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        E::Id::deserialize(deserializer).map(Ref::new)
+    }
+}
+
+/// Records of a type in the order they were inserted first.
+pub struct Table<E: Entity> {
+    ids: Vec<E::Id>,
+    records: HashMap<E::Id, Rc<E>>,
+}
+
+impl<E: Entity> Table<E> {
+    fn new() -> Self {
+        Table {
+            ids: Vec::new(),
+            records: HashMap::new(),
+        }
+    }
+
+    /// Returns a record by its id.
+    pub fn get(&self, id: &E::Id) -> Option<Rc<E>> {
+        self.records.get(id).cloned()
+    }
+
+    /// Returns all records of the table.
+    pub fn all(&self) -> Vec<Rc<E>> {
+        self.ids.iter().filter_map(|id| self.records.get(id).cloned()).collect()
+    }
+
+    /// Returns the count of records.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns `true` if there are no records.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+}
+
+thread_local! {
+    static NEXT_STORE: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Tables of entities of all types.
+pub struct Store {
+    id: usize,
+    revision: u64,
+    tables: HashMap<TypeId, Box<Any>>,
+}
+
+impl Store {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        let id = NEXT_STORE.with(|next| next.replace(next.get() + 1));
+        Store {
+            id,
+            revision: 0,
+            tables: HashMap::new(),
+        }
+    }
+
+    /// Returns a number which grows on every change of the store.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Returns the table of the type. It's empty if nothing was inserted yet.
+    pub fn table<E: Entity>(&self) -> Option<&Table<E>> {
+        self.tables.get(&TypeId::of::<E>()).map(|table| {
+            table.downcast_ref().expect("a table of another type")
+        })
+    }
+
+    fn table_mut<E: Entity>(&mut self) -> &mut Table<E> {
+        self.revision += 1;
+        self.tables.entry(TypeId::of::<E>())
+            .or_insert_with(|| Box::new(Table::<E>::new()))
+            .downcast_mut()
+            .expect("a table of another type")
+    }
+
+    /// Inserts a record or replaces a record with the same id.
+    pub fn insert<E: Entity>(&mut self, record: E) -> Ref<E> {
+        let id = record.id();
+        let table = self.table_mut::<E>();
+        if table.records.insert(id.clone(), Rc::new(record)).is_none() {
+            table.ids.push(id.clone());
+        }
+        Ref::new(id)
+    }
+
+    /// Inserts all records, like a page of records from a server.
+    pub fn insert_all<E: Entity, I: IntoIterator<Item = E>>(&mut self, records: I) {
+        for record in records {
+            self.insert(record);
+        }
+    }
+
+    /// Changes a copy of a record. Returns `false` if there is no record with the id.
+    pub fn update<E, F>(&mut self, id: &E::Id, change: F) -> bool
+    where
+        E: Entity + Clone,
+        F: FnOnce(&mut E),
+    {
+        let record = match self.get::<E>(id) {
+            Some(record) => record,
+            None => return false,
+        };
+        let mut record = (*record).clone();
+        change(&mut record);
+        let table = self.table_mut::<E>();
+        table.records.insert(id.clone(), Rc::new(record));
+        true
+    }
+
+    /// Removes a record. References to it aren't resolved after that.
+    pub fn remove<E: Entity>(&mut self, id: &E::Id) -> Option<Rc<E>> {
+        self.get::<E>(id)?;
+        let table = self.table_mut::<E>();
+        table.ids.retain(|other| other != id);
+        table.records.remove(id)
+    }
+
+    /// Returns a record by its id.
+    pub fn get<E: Entity>(&self, id: &E::Id) -> Option<Rc<E>> {
+        self.table::<E>().and_then(|table| table.get(id))
+    }
+
+    /// Returns the record of the reference.
+    pub fn resolve<E: Entity>(&self, reference: &Ref<E>) -> Option<Rc<E>> {
+        self.get(&reference.id)
+    }
+
+    /// Returns records of the references which exist in the order of the references.
+    pub fn resolve_all<'a, E, I>(&self, references: I) -> Vec<Rc<E>>
+    where
+        E: Entity,
+        I: IntoIterator<Item = &'a Ref<E>>,
+    {
+        references.into_iter().filter_map(|reference| self.resolve(reference)).collect()
+    }
+
+    /// Returns all records of the type.
+    pub fn all<E: Entity>(&self) -> Vec<Rc<E>> {
+        self.table::<E>().map(Table::all).unwrap_or_default()
+    }
+
+    /// Returns records which match the predicate, like comments which refer to a post.
+    pub fn filter<E, F>(&self, predicate: F) -> Vec<Rc<E>>
+    where
+        E: Entity,
+        F: Fn(&E) -> bool,
+    {
+        self.all::<E>().into_iter().filter(|record| predicate(record)).collect()
+    }
+
+    /// Removes all records.
+    pub fn clear(&mut self) {
+        self.revision += 1;
+        self.tables.clear();
+    }
+}
+
+impl Default for Store {
+    fn default() -> Self {
+        Store::new()
+    }
+}
+
+impl fmt::Debug for Store {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Store")
+            .field("revision", &self.revision)
+            .field("tables", &self.tables.len())
+            .finish()
+    }
+}
+
+/// A view model which is computed from a store and kept until the store changes.
+pub struct Selector<T> {
+    select: Box<Fn(&Store) -> T>,
+    cached: RefCell<Option<(usize, u64, Rc<T>)>>,
+}
+
+impl<T> Selector<T> {
+    /// Creates a selector which computes a view model with `select`.
+    pub fn new<F>(select: F) -> Self
+    where
+        F: Fn(&Store) -> T + 'static,
+    {
+        Selector {
+            select: Box::new(select),
+            cached: RefCell::new(None),
+        }
+    }
+
+    /// Returns the view model of the store.
+    pub fn get(&self, store: &Store) -> Rc<T> {
+        if let Some((id, revision, ref value)) = *self.cached.borrow() {
+            if id == store.id && revision == store.revision {
+                return value.clone();
+            }
+        }
+        let value = Rc::new((self.select)(store));
+        *self.cached.borrow_mut() = Some((store.id, store.revision, value.clone()));
+        value
+    }
+}
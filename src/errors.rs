@@ -0,0 +1,316 @@
+//! This module contains a bus of errors of an app. Services, decoders of
+//! formats, error boundaries and the panic hook publish errors to it, and
+//! sinks which are added once report them:
+//!
+//! ```rust,ignore
+//! fn main() {
+//!     yew::initialize();
+//!     errors::add_sink(ConsoleSink);
+//!     errors::add_sink(HttpSink::new("https://errors.example.com/api/1/store/")
+//!         .header("X-Sentry-Auth", SENTRY_AUTH)
+//!         .release(env!("CARGO_PKG_VERSION")));
+//!     // ...
+//! }
+//!
+//! errors::report(ErrorReport::new(ErrorKind::App, "cart", "a product is gone").tag("id", id));
+//! ```
+//!
+//! Failed fetches are published as `Service` errors of `fetch`, and JSON
+//! which doesn't match a type as `Decode` errors of `json`.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::{Rc, Weak};
+use std::time::Duration;
+use serde_json::{self, Map, Value};
+use callback::Callback;
+use services::Task;
+use services::backend::{self, FetchBackend, TimerBackend};
+use services::fetch::Request;
+use services::interceptor;
+
+/// A part of an app where an error happened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// A service failed, like a fetch without a response.
+    Service,
+    /// Data wasn't decoded from a format.
+    Decode,
+    /// A component failed and its error boundary shows a fallback.
+    Component,
+    /// The app panicked. Sinks are flushed at once after a panic.
+    Panic,
+    /// An error which an app reported itself.
+    App,
+}
+
+impl ErrorKind {
+    /// Returns the name of the kind, like `decode`.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            ErrorKind::Service => "service",
+            ErrorKind::Decode => "decode",
+            ErrorKind::Component => "component",
+            ErrorKind::Panic => "panic",
+            ErrorKind::App => "app",
+        }
+    }
+}
+
+/// A structured error which is published to the bus.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorReport {
+    /// A part of the app where the error happened.
+    pub kind: ErrorKind,
+    /// What published the error, like `fetch` or a name of a boundary.
+    pub origin: String,
+    /// A description of the error.
+    pub message: String,
+    /// Details to group and search errors by, like a route.
+    pub tags: Vec<(String, String)>,
+}
+
+impl ErrorReport {
+    /// Creates a report without tags.
+    pub fn new<O: Into<String>, M: Into<String>>(kind: ErrorKind, origin: O, message: M) -> Self {
+        ErrorReport {
+            kind,
+            origin: origin.into(),
+            message: message.into(),
+            tags: Vec::new(),
+        }
+    }
+
+    /// Adds a tag to the report.
+    pub fn tag<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.tags.push((key.into(), value.into()));
+        self
+    }
+}
+
+impl fmt::Display for ErrorReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} error of {}: {}", self.kind.name(), self.origin, self.message)
+    }
+}
+
+/// A destination of published errors.
+pub trait ErrorSink {
+    /// Receives a published error.
+    fn report(&mut self, error: &ErrorReport);
+
+    /// Sends errors which are kept to batch them.
+    fn flush(&mut self) {
+    }
+}
+
+impl ErrorSink for Callback<ErrorReport> {
+    fn report(&mut self, error: &ErrorReport) {
+        self.emit(error.clone());
+    }
+}
+
+type Sink = Rc<RefCell<Box<ErrorSink>>>;
+
+thread_local! {
+    static SINKS: RefCell<Vec<Sink>> = RefCell::new(Vec::new());
+}
+
+fn sinks() -> Vec<Sink> {
+    SINKS.try_with(|sinks| sinks.try_borrow().map(|sinks| sinks.clone()).unwrap_or_default())
+        .unwrap_or_default()
+}
+
+/// Adds a sink which receives all errors published later.
+pub fn add_sink<S: ErrorSink + 'static>(sink: S) {
+    SINKS.with(|sinks| sinks.borrow_mut().push(Rc::new(RefCell::new(Box::new(sink)))));
+}
+
+/// Removes all sinks. Errors are dropped until a sink is added.
+pub fn clear_sinks() {
+    SINKS.with(|sinks| sinks.borrow_mut().clear());
+}
+
+/// Publishes an error to all sinks. A sink which publishes an error itself
+/// doesn't receive it, so a failing sink can't loop.
+pub fn report(error: ErrorReport) {
+    let panic = error.kind == ErrorKind::Panic;
+    for sink in sinks() {
+        if let Ok(mut sink) = sink.try_borrow_mut() {
+            sink.report(&error);
+            if panic {
+                sink.flush();
+            }
+        }
+    }
+}
+
+/// Sends errors which sinks keep to batch them, like before a page is left.
+pub fn flush() {
+    for sink in sinks() {
+        if let Ok(mut sink) = sink.try_borrow_mut() {
+            sink.flush();
+        }
+    }
+}
+
+/// A sink which writes errors to the browser's console.
+pub struct ConsoleSink;
+
+impl ErrorSink for ConsoleSink {
+    fn report(&mut self, error: &ErrorReport) {
+        let text = error.to_string();
+        let tags: Map<String, Value> = error.tags.iter()
+            .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+            .collect();
+        let tags = Value::Object(tags).to_string();
+        js! { @(no_return)
+            console.error(@{text}, JSON.parse(@{tags}));
+        }
+    }
+}
+
+/// Returns a JSON payload of errors, like events of Sentry.
+pub fn payload(errors: &[ErrorReport], release: Option<&str>, environment: Option<&str>) -> String {
+    let events = errors.iter().map(|error| {
+        let mut tags = Map::new();
+        tags.insert("kind".into(), Value::String(error.kind.name().into()));
+        for (key, value) in &error.tags {
+            tags.insert(key.clone(), Value::String(value.clone()));
+        }
+        let level = if error.kind == ErrorKind::Panic { "fatal" } else { "error" };
+        let mut event = Map::new();
+        event.insert("level".into(), Value::String(level.into()));
+        event.insert("logger".into(), Value::String(error.origin.clone()));
+        event.insert("message".into(), Value::String(error.message.clone()));
+        event.insert("tags".into(), Value::Object(tags));
+        event.insert("platform".into(), Value::String("javascript".into()));
+        if let Some(release) = release {
+            event.insert("release".into(), Value::String(release.into()));
+        }
+        if let Some(environment) = environment {
+            event.insert("environment".into(), Value::String(environment.into()));
+        }
+        Value::Object(event)
+    }).collect();
+    let mut body = Map::new();
+    body.insert("events".into(), Value::Array(events));
+    serde_json::to_string(&Value::Object(body)).expect("can't serialize errors")
+}
+
+struct Batch {
+    endpoint: String,
+    headers: Vec<(String, String)>,
+    release: Option<String>,
+    environment: Option<String>,
+    max: usize,
+    delay: Duration,
+    errors: Vec<ErrorReport>,
+    timer: Option<Box<Task>>,
+    fetch: Rc<FetchBackend>,
+    timers: Rc<TimerBackend>,
+}
+
+impl Batch {
+    fn send(&mut self) {
+        if let Some(mut timer) = self.timer.take() {
+            if timer.is_active() {
+                timer.cancel();
+            }
+        }
+        if self.errors.is_empty() {
+            return;
+        }
+        let body = payload(&self.errors, self.release.as_deref(), self.environment.as_deref());
+        self.errors.clear();
+        let mut request = Request::post(self.endpoint.as_str());
+        request.header("Content-Type", "application/json");
+        for (name, value) in &self.headers {
+            request.header(name.as_str(), value.as_str());
+        }
+        let request = request.body(Some(body)).expect("wrong endpoint of errors");
+        // A failed report is dropped: publishing it would report it again
+        self.fetch.fetch(interceptor::fetch(request), Box::new(|_| ()));
+    }
+}
+
+/// A sink which posts errors to an endpoint in batches with the backend
+/// of the fetch service. A batch is sent when it's full, a while after its
+/// first error, or at once after a panic.
+pub struct HttpSink {
+    batch: Rc<RefCell<Batch>>,
+}
+
+impl HttpSink {
+    /// Creates a sink which sends up to 10 errors a request, 5 seconds after the first one.
+    pub fn new(endpoint: &str) -> Self {
+        let batch = Batch {
+            endpoint: endpoint.to_owned(),
+            headers: Vec::new(),
+            release: None,
+            environment: None,
+            max: 10,
+            delay: Duration::from_secs(5),
+            errors: Vec::new(),
+            timer: None,
+            fetch: backend::fetch(),
+            timers: backend::timers(),
+        };
+        HttpSink {
+            batch: Rc::new(RefCell::new(batch)),
+        }
+    }
+
+    /// Adds a header to requests, like credentials of the endpoint.
+    pub fn header(self, name: &str, value: &str) -> Self {
+        self.batch.borrow_mut().headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Sets a version of the app which is sent with errors.
+    pub fn release(self, release: &str) -> Self {
+        self.batch.borrow_mut().release = Some(release.to_owned());
+        self
+    }
+
+    /// Sets an environment of the app, like `staging`.
+    pub fn environment(self, environment: &str) -> Self {
+        self.batch.borrow_mut().environment = Some(environment.to_owned());
+        self
+    }
+
+    /// Sets the maximal size of a batch and how long the first error waits for others.
+    pub fn batch(self, max: usize, delay: Duration) -> Self {
+        {
+            let mut batch = self.batch.borrow_mut();
+            batch.max = max.max(1);
+            batch.delay = delay;
+        }
+        self
+    }
+}
+
+impl ErrorSink for HttpSink {
+    fn report(&mut self, error: &ErrorReport) {
+        let mut batch = self.batch.borrow_mut();
+        batch.errors.push(error.clone());
+        if batch.errors.len() >= batch.max {
+            batch.send();
+        } else if batch.timer.is_none() {
+            let weak: Weak<RefCell<Batch>> = Rc::downgrade(&self.batch);
+            let callback = move || {
+                if let Some(batch) = weak.upgrade() {
+                    let mut batch = batch.borrow_mut();
+                    batch.timer = None;
+                    batch.send();
+                }
+            };
+            let timer = batch.timers.timeout(batch.delay, Box::new(callback));
+            batch.timer = Some(timer);
+        }
+    }
+
+    fn flush(&mut self) {
+        self.batch.borrow_mut().send();
+    }
+}
@@ -0,0 +1,82 @@
+//! This module contains DOM events which `stdweb` doesn't provide yet.
+//! They implement the same traits as events of `stdweb` and could be
+//! used with `add_event_listener` of any element.
+
+use stdweb::{Reference, Value};
+use stdweb::unstable::TryFrom;
+use stdweb::web::event::{IEvent, IUiEvent, IMouseEvent, ConcreteEvent};
+
+macro_rules! impl_event {
+    ($($(#[$attr:meta])* $name:ident($kind:expr) : $($iface:ident),*;)*) => {$(
+        $(#[$attr])*
+        pub struct $name(Reference);
+
+        impl AsRef<Reference> for $name {
+            fn as_ref(&self) -> &Reference {
+                &self.0
+            }
+        }
+
+        impl TryFrom<Value> for $name {
+            type Error = <Reference as TryFrom<Value>>::Error;
+
+            fn try_from(value: Value) -> Result<Self, Self::Error> {
+                Reference::try_from(value).map($name)
+            }
+        }
+
+        impl IEvent for $name {}
+        $( impl $iface for $name {} )*
+
+        impl ConcreteEvent for $name {
+            const EVENT_TYPE: &'static str = $kind;
+        }
+    )*};
+}
+
+impl_event! {
+    /// Fired when a form is submitted.
+    SubmitEvent("submit"): ;
+    /// Fired when a button of a pointing device is pressed on an element.
+    MouseDownEvent("mousedown"): IUiEvent, IMouseEvent;
+    /// Fired when a button of a pointing device is released over an element.
+    MouseUpEvent("mouseup"): IUiEvent, IMouseEvent;
+    /// Fired when a pointing device is moved over an element.
+    MouseMoveEvent("mousemove"): IUiEvent, IMouseEvent;
+    /// Fired when a pointing device is moved onto an element. Doesn't bubble.
+    MouseEnterEvent("mouseenter"): IUiEvent, IMouseEvent;
+    /// Fired when a pointing device is moved off an element. Doesn't bubble.
+    MouseLeaveEvent("mouseleave"): IUiEvent, IMouseEvent;
+    /// Fired when a pointing device is moved onto an element or its children.
+    MouseOverEvent("mouseover"): IUiEvent, IMouseEvent;
+    /// Fired when a pointing device is moved off an element or its children.
+    MouseOutEvent("mouseout"): IUiEvent, IMouseEvent;
+    /// Fired when a user requests a context menu (usually with the right button).
+    ContextMenuEvent("contextmenu"): IUiEvent, IMouseEvent;
+    /// Fired when a wheel of a pointing device is rotated.
+    WheelEvent("wheel"): IUiEvent, IMouseEvent;
+    /// Fired when a touch point is placed on the touch surface.
+    TouchStartEvent("touchstart"): IUiEvent;
+    /// Fired when a touch point is removed from the touch surface.
+    TouchEndEvent("touchend"): IUiEvent;
+    /// Fired when a touch point is moved along the touch surface.
+    TouchMoveEvent("touchmove"): IUiEvent;
+    /// Fired when a touch point has been disrupted.
+    TouchCancelEvent("touchcancel"): IUiEvent;
+    /// Fired when a pointer becomes active.
+    PointerDownEvent("pointerdown"): IUiEvent, IMouseEvent;
+    /// Fired when a pointer is no longer active.
+    PointerUpEvent("pointerup"): IUiEvent, IMouseEvent;
+    /// Fired when a pointer changes coordinates.
+    PointerMoveEvent("pointermove"): IUiEvent, IMouseEvent;
+    /// Fired when a pointer is moved into the hit test boundaries of an element.
+    PointerEnterEvent("pointerenter"): IUiEvent, IMouseEvent;
+    /// Fired when a pointer is moved out of the hit test boundaries of an element.
+    PointerLeaveEvent("pointerleave"): IUiEvent, IMouseEvent;
+    /// Fired when a browser decides that a pointer won't produce events anymore.
+    PointerCancelEvent("pointercancel"): IUiEvent, IMouseEvent;
+    /// Fired when an element is scrolled. Doesn't bubble.
+    ScrollEvent("scroll"): ;
+    /// Fired when a user pastes from the clipboard.
+    PasteEvent("paste"): ;
+}
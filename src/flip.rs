@@ -0,0 +1,215 @@
+//! This module contains FLIP animations of reordered lists: positions of items
+//! are measured before a change (First) and after a render (Last), moved items
+//! are shifted back with a transform (Invert) and the transform is transitioned
+//! away (Play).
+//!
+//! Items are matched by the `data-flip-key` attribute, not by elements, so
+//! items whose content moved to another reused element animate correctly:
+//!
+//! ```rust,ignore
+//! fn update(&mut self, msg: Msg) -> ShouldRender {
+//!     match msg {
+//!         Msg::Sort => {
+//!             self.flip.first();
+//!             self.items.sort_by_key(|item| item.rank);
+//!             self.sender.send_soon(Msg::Animate);
+//!             true
+//!         }
+//!         Msg::Animate => {
+//!             self.flip.play();
+//!             false
+//!         }
+//!     }
+//! }
+//!
+//! fn view(&self) -> Html<Msg> {
+//!     html! {
+//!         <ul ref=self.flip.node(),>
+//!             { for self.items.iter().map(|item| html! {
+//!                 <li data-flip-key=item.id,>{ &item.title }</li>
+//!             }) }
+//!         </ul>
+//!     }
+//! }
+//! ```
+//!
+//! Nothing is animated if the user prefers reduced motion.
+
+use std::time::Duration;
+use stdweb::Value;
+use stdweb::unstable::TryInto;
+use services::to_ms;
+use virtual_dom::NodeRef;
+
+/// The attribute which identifies items of a list between renders.
+pub const KEY_ATTRIBUTE: &str = "data-flip-key";
+
+/// A position of an item on a page in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    /// The distance from the left side of the viewport.
+    pub left: f64,
+    /// The distance from the top side of the viewport.
+    pub top: f64,
+}
+
+/// A shift of an item which is applied as a transform before it's played.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Move {
+    /// The key of the item.
+    pub key: String,
+    /// The horizontal shift from the new position back to the old one.
+    pub dx: f64,
+    /// The vertical shift from the new position back to the old one.
+    pub dy: f64,
+}
+
+/// Returns shifts of items which are in both measurements and moved by
+/// at least a pixel. New and removed items aren't moved.
+pub fn moves(first: &[(String, Position)], last: &[(String, Position)]) -> Vec<Move> {
+    last.iter()
+        .filter_map(|(key, after)| {
+            let before = first.iter().find(|(old, _)| old == key)?.1;
+            let dx = before.left - after.left;
+            let dy = before.top - after.top;
+            if dx.abs() < 1.0 && dy.abs() < 1.0 {
+                return None;
+            }
+            Some(Move { key: key.clone(), dx, dy })
+        })
+        .collect()
+}
+
+/// A coordinator of FLIP animations of items inside of a container.
+pub struct Flip {
+    node: NodeRef,
+    duration: Duration,
+    easing: String,
+    first: Option<Vec<(String, Position)>>,
+}
+
+impl Default for Flip {
+    fn default() -> Self {
+        Flip {
+            node: NodeRef::default(),
+            duration: Duration::from_millis(250),
+            easing: "ease".into(),
+            first: None,
+        }
+    }
+}
+
+impl Flip {
+    /// Creates a coordinator which animates items for 250 milliseconds.
+    pub fn new() -> Self {
+        Flip::default()
+    }
+
+    /// Sets the duration of animations.
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Sets the timing function of animations, like `ease-out`.
+    pub fn easing<S: Into<String>>(mut self, easing: S) -> Self {
+        self.easing = easing.into();
+        self
+    }
+
+    /// Returns the reference which is put to the container of items.
+    pub fn node(&self) -> &NodeRef {
+        &self.node
+    }
+
+    /// Measures positions of items before a change of the list.
+    pub fn first(&mut self) {
+        self.first = Some(self.measure());
+    }
+
+    /// Measures positions of items after a render and animates moved items
+    /// from their first positions. Does nothing if `first` wasn't called.
+    pub fn play(&mut self) {
+        let first = match self.first.take() {
+            Some(first) => first,
+            None => return,
+        };
+        let container = match self.node.get() {
+            Some(container) => container,
+            None => return,
+        };
+        let moves = moves(&first, &self.measure());
+        if moves.is_empty() {
+            return;
+        }
+        let keys: Vec<String> = moves.iter().map(|item| item.key.clone()).collect();
+        let shifts: Vec<f64> = moves.iter().flat_map(|item| vec![item.dx, item.dy]).collect();
+        let transition = format!("transform {}ms {}", to_ms(self.duration), self.easing);
+        js! { @(no_return)
+            var container = @{container};
+            var keys = @{keys};
+            var shifts = @{shifts};
+            var transition = @{transition};
+            var reduced = window.matchMedia && window.matchMedia("(prefers-reduced-motion: reduce)").matches;
+            if (reduced) {
+                return;
+            }
+            var elements = keys.map(function(key) {
+                return container.querySelector("[" + @{KEY_ATTRIBUTE} + "=\"" + CSS.escape(key) + "\"]");
+            });
+            elements.forEach(function(element, i) {
+                if (!element) {
+                    return;
+                }
+                element.style.transition = "none";
+                element.style.transform = "translate(" + shifts[i * 2] + "px, " + shifts[i * 2 + 1] + "px)";
+            });
+            // Reading the layout applies inverted positions before the transition
+            container.offsetWidth;
+            elements.forEach(function(element) {
+                if (!element) {
+                    return;
+                }
+                element.style.transition = transition;
+                element.style.transform = "";
+                var done = function(event) {
+                    if (event.target === element && event.propertyName === "transform") {
+                        element.style.transition = "";
+                        element.removeEventListener("transitionend", done);
+                    }
+                };
+                element.addEventListener("transitionend", done);
+            });
+        }
+    }
+
+    /// Returns positions of items in the container with transforms of running
+    /// animations, so an interrupted animation continues from where it is.
+    fn measure(&self) -> Vec<(String, Position)> {
+        let container = match self.node.get() {
+            Some(container) => container,
+            None => return Vec::new(),
+        };
+        let keys: Value = js! {
+            var items = @{&container}.querySelectorAll("[" + @{KEY_ATTRIBUTE} + "]");
+            return Array.prototype.map.call(items, function(item) {
+                return item.getAttribute(@{KEY_ATTRIBUTE});
+            });
+        };
+        let positions: Value = js! {
+            var items = @{&container}.querySelectorAll("[" + @{KEY_ATTRIBUTE} + "]");
+            var positions = [];
+            Array.prototype.forEach.call(items, function(item) {
+                var rect = item.getBoundingClientRect();
+                positions.push(rect.left, rect.top);
+            });
+            return positions;
+        };
+        let keys: Vec<String> = keys.try_into().unwrap_or_default();
+        let positions: Vec<f64> = positions.try_into().unwrap_or_default();
+        keys.into_iter()
+            .zip(positions.chunks(2))
+            .map(|(key, position)| (key, Position { left: position[0], top: position[1] }))
+            .collect()
+    }
+}
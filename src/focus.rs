@@ -0,0 +1,154 @@
+//! This module contains functions to move focus programmatically.
+//! Elements are found with a `NodeRef` which is put to a tag:
+//!
+//! ```rust,ignore
+//! html! {
+//!     <input ref=&self.search, />
+//! }
+//!
+//! fn update(&mut self, msg: Msg) -> ShouldRender {
+//!     match msg {
+//!         Msg::StartSearch => {
+//!             self.search.focus();
+//!         }
+//!     }
+//!     false
+//! }
+//! ```
+//!
+//! Use `FocusTrap` component to keep focus inside of a dialog and
+//! `roving_index` to move focus between items of a group with arrows.
+
+use stdweb::Value;
+use stdweb::unstable::TryInto;
+use stdweb::web::Element;
+use virtual_dom::NodeRef;
+
+/// A selector of elements which take focus with the Tab key.
+pub const FOCUSABLE: &str = "a[href], area[href], button:not([disabled]), \
+    input:not([disabled]):not([type=hidden]), select:not([disabled]), textarea:not([disabled]), \
+    iframe, [contenteditable], [tabindex]:not([tabindex='-1'])";
+
+/// Returns focusable elements inside of the container in the order of the document.
+pub fn focusable(container: &Element) -> Vec<Element> {
+    let elements: Value = js! {
+        return Array.prototype.slice.call(@{container}.querySelectorAll(@{FOCUSABLE}));
+    };
+    let elements: Vec<Value> = elements.try_into().unwrap_or_default();
+    elements.into_iter().filter_map(|element| element.try_into().ok()).collect()
+}
+
+/// Moves focus to the first focusable element inside of the container.
+/// Returns `false` if there is no such element.
+pub fn focus_first(container: &NodeRef) -> bool {
+    let first = container.get().and_then(|container| focusable(&container).into_iter().next());
+    match first {
+        Some(element) => focus_element(&element),
+        None => false,
+    }
+}
+
+/// Moves focus to the content of a new page: to an element with `autofocus`,
+/// to the first heading or to the container itself. The container needs
+/// `tabindex="-1"` to take focus. The page isn't scrolled.
+pub fn focus_content(container: &NodeRef) -> bool {
+    match container.get() {
+        Some(container) => {
+            let target: Value = js! {
+                var container = @{container};
+                // Hidden elements, like views of `KeepAlive`, can't take focus
+                var targets = container.querySelectorAll("[autofocus], h1");
+                return Array.prototype.find.call(targets, function(target) {
+                    return !target.closest("[hidden]");
+                }) || container;
+            };
+            match target.try_into() {
+                Ok(element) => focus_element(&element),
+                Err(_) => false,
+            }
+        }
+        None => false,
+    }
+}
+
+/// Moves focus to the element with the id, like a button which a parent
+/// rendered. Returns `false` if there is no such element.
+pub fn focus_id(id: &str) -> bool {
+    let element: Value = js! { return document.getElementById(@{id}); };
+    match element.try_into() {
+        Ok(element) => focus_element(&element),
+        Err(_) => false,
+    }
+}
+
+/// Returns the element which has focus now.
+pub fn active_element() -> Option<Element> {
+    let active: Value = js! {
+        var active = document.activeElement;
+        return active === document.body ? null : active;
+    };
+    active.try_into().ok()
+}
+
+fn focus_element(element: &Element) -> bool {
+    let focused: Value = js! {
+        var element = @{element};
+        element.focus({ preventScroll: true });
+        return document.activeElement === element;
+    };
+    focused == Value::Bool(true)
+}
+
+/// Remembers the focused element to return focus to it later,
+/// for example when a dialog which took focus is closed.
+pub struct FocusRestore(Option<Element>);
+
+impl FocusRestore {
+    /// Remembers the element which has focus now.
+    pub fn save() -> Self {
+        FocusRestore(active_element())
+    }
+
+    /// Returns focus to the remembered element if it's still in the document.
+    pub fn restore(self) {
+        if let Some(element) = self.0 {
+            js! { @(no_return)
+                var element = @{element};
+                if (document.contains(element)) {
+                    element.focus({ preventScroll: true });
+                }
+            }
+        }
+    }
+}
+
+/// A direction in which arrow keys move between items of a group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    /// Left and right arrows move focus, like in a tab list.
+    Horizontal,
+    /// Up and down arrows move focus, like in an accordion.
+    Vertical,
+}
+
+/// Returns the index of the item which takes focus after the key was pressed
+/// on the `current` item of a group with a roving `tabindex`: arrows move to
+/// the previous or the next item with a wrap, `Home` and `End` move to the
+/// first and the last one. `skip` items, like disabled ones, are skipped.
+/// Returns `None` if the key doesn't move focus.
+pub fn roving_index(key: &str, current: usize, count: usize, orientation: Orientation, skip: &[usize]) -> Option<usize> {
+    let (previous, next) = match orientation {
+        Orientation::Horizontal => ("ArrowLeft", "ArrowRight"),
+        Orientation::Vertical => ("ArrowUp", "ArrowDown"),
+    };
+    let enabled = |index: &usize| !skip.contains(index);
+    let mut order = (current + 1..count).chain(0..current.min(count));
+    match key {
+        _ if count == 0 => None,
+        "Home" => (0..count).find(enabled),
+        "End" => (0..count).rev().find(enabled),
+        _ if key == next => order.find(enabled),
+        _ if key == previous => order.collect::<Vec<_>>().into_iter().rev().find(enabled),
+        _ => None,
+    }
+}
@@ -0,0 +1,286 @@
+//! This module contains a state of forms: values of fields,
+//! dirty and touched flags and validation errors.
+//!
+//! Describe fields of a model with `form_fields!` macro and keep
+//! a `Form` in a model of an app:
+//!
+//! ```rust,ignore
+//! #[derive(Default)]
+//! struct Signup {
+//!     email: String,
+//!     age: u32,
+//! }
+//!
+//! form_fields!(Signup { email, age });
+//!
+//! let form = Form::new(Signup::default())
+//!     .validator("email", |signup| {
+//!         if signup.email.contains('@') { Ok(()) } else { Err("wrong email".into()) }
+//!     });
+//!
+//! fn update(context: &mut Context<Msg>, model: &mut Model, msg: Msg) {
+//!     match msg {
+//!         Msg::Edit(field, value) => {
+//!             model.form.set(field, value);
+//!         }
+//!         Msg::Blur(field) => {
+//!             model.form.touch(field);
+//!         }
+//!         Msg::Submit => {
+//!             if model.form.validate() {
+//!                 // Send `model.form.model()`
+//!             }
+//!         }
+//!     }
+//! }
+//!
+//! fn view(model: &Model) -> Html<Msg> {
+//!     html! {
+//!         <form>
+//!             <input value=model.form.value("email"),
+//!                    oninput=|e: InputData| Msg::Edit("email", e.value),
+//!                    onblur=|_| Msg::Blur("email"), />
+//!             { model.form.view_error("email") }
+//!         </form>
+//!     }
+//! }
+//! ```
+
+use std::collections::HashMap;
+use html::Html;
+use services::Task;
+
+/// Maps fields of a struct to names. Use `form_fields!` macro to implement it.
+pub trait Fields {
+    /// Returns names of all fields.
+    fn names() -> &'static [&'static str];
+    /// Returns a value of the field as a string or `None` if there is no field with the name.
+    fn get(&self, name: &str) -> Option<String>;
+    /// Parses and sets a value of the field.
+    fn set(&mut self, name: &str, value: String) -> Result<(), String>;
+}
+
+/// Implements `Fields` trait for a struct. Fields have to implement
+/// `ToString` and `FromStr` traits.
+///
+/// ```rust,ignore
+/// form_fields!(Signup { email, age });
+/// ```
+#[macro_export]
+macro_rules! form_fields {
+    ($model:ident { $($field:ident),* $(,)* }) => {
+        impl $crate::form::Fields for $model {
+            fn names() -> &'static [&'static str] {
+                &[$(stringify!($field)),*]
+            }
+
+            fn get(&self, name: &str) -> Option<String> {
+                match name {
+                    $(stringify!($field) => Some(self.$field.to_string()),)*
+                    _ => None,
+                }
+            }
+
+            fn set(&mut self, name: &str, value: String) -> Result<(), String> {
+                match name {
+                    $(stringify!($field) => {
+                        self.$field = value.parse()
+                            .map_err(|_| format!("wrong value of {}", name))?;
+                        Ok(())
+                    })*
+                    _ => Err(format!("unknown field {}", name)),
+                }
+            }
+        }
+    };
+}
+
+type Validator<T> = Box<Fn(&T) -> Result<(), String>>;
+
+/// A state of a single field of a form.
+#[derive(Default)]
+struct FieldState {
+    /// The field was changed by a user.
+    dirty: bool,
+    /// The field lost focus at least once.
+    touched: bool,
+    /// The last raw value which can't be parsed to the model.
+    raw: Option<String>,
+    /// The error of the last validation.
+    error: Option<String>,
+    /// An unfinished asynchronous validation.
+    task: Option<Box<Task>>,
+}
+
+/// A form which keeps a model and states of its fields.
+pub struct Form<T: Fields> {
+    model: T,
+    fields: HashMap<&'static str, FieldState>,
+    validators: Vec<(&'static str, Validator<T>)>,
+}
+
+impl<T: Fields> Form<T> {
+    /// Creates a pristine form for the model.
+    pub fn new(model: T) -> Self {
+        let fields = T::names().iter()
+            .map(|name| (*name, FieldState::default()))
+            .collect();
+        Form {
+            model,
+            fields,
+            validators: Vec::new(),
+        }
+    }
+
+    /// Adds a synchronous validator for the field. The first error
+    /// of validators of the field will be kept.
+    pub fn validator<F>(mut self, name: &'static str, validator: F) -> Self
+    where
+        F: Fn(&T) -> Result<(), String> + 'static,
+    {
+        self.validators.push((name, Box::new(validator)));
+        self
+    }
+
+    /// Returns the model of the form.
+    pub fn model(&self) -> &T {
+        &self.model
+    }
+
+    /// Returns the value of the field which is shown to a user.
+    /// It's the raw value if it wasn't parsed.
+    pub fn value(&self, name: &str) -> String {
+        self.field(name).raw.clone()
+            .or_else(|| self.model.get(name))
+            .unwrap_or_default()
+    }
+
+    /// Sets the value of the field changed by a user and validates the field.
+    pub fn set(&mut self, name: &str, value: String) {
+        let result = self.model.set(name, value.clone());
+        {
+            let field = self.field_mut(name);
+            field.dirty = true;
+            if let Some(mut task) = field.task.take() {
+                task.cancel();
+            }
+            match result {
+                Ok(()) => {
+                    field.raw = None;
+                }
+                Err(err) => {
+                    field.raw = Some(value);
+                    field.error = Some(err);
+                    return;
+                }
+            }
+        }
+        self.validate_field(name);
+    }
+
+    /// Marks the field as touched. Call it when the field loses focus.
+    pub fn touch(&mut self, name: &str) {
+        self.field_mut(name).touched = true;
+    }
+
+    /// Starts asynchronous validation of the field with a task which will
+    /// send a message to call `resolve`. It replaces an unfinished validation.
+    pub fn validate_async<TSK: Task + 'static>(&mut self, name: &str, task: TSK) {
+        let field = self.field_mut(name);
+        if let Some(mut task) = field.task.take() {
+            task.cancel();
+        }
+        field.task = Some(Box::new(task));
+    }
+
+    /// Sets the result of asynchronous validation of the field.
+    /// Does nothing if there is an error of synchronous validator already.
+    pub fn resolve(&mut self, name: &str, result: Result<(), String>) {
+        let field = self.field_mut(name);
+        if field.task.take().is_none() || field.error.is_some() {
+            return;
+        }
+        field.error = result.err();
+    }
+
+    /// Validates all fields and marks them as touched to show errors.
+    /// Returns `true` if the form is valid.
+    pub fn validate(&mut self) -> bool {
+        for name in T::names() {
+            self.field_mut(name).touched = true;
+            if self.field(name).raw.is_none() {
+                self.validate_field(name);
+            }
+        }
+        self.is_valid()
+    }
+
+    /// Returns `true` if there are no errors and asynchronous validations.
+    pub fn is_valid(&self) -> bool {
+        self.fields.values().all(|field| field.error.is_none() && field.task.is_none())
+    }
+
+    /// Returns `true` if any field was changed by a user.
+    pub fn is_dirty(&self) -> bool {
+        self.fields.values().any(|field| field.dirty)
+    }
+
+    /// Returns `true` if the field was changed by a user.
+    pub fn is_field_dirty(&self, name: &str) -> bool {
+        self.field(name).dirty
+    }
+
+    /// Returns `true` if the field lost focus at least once.
+    pub fn is_touched(&self, name: &str) -> bool {
+        self.field(name).touched
+    }
+
+    /// Returns `true` if the field waits for asynchronous validation.
+    pub fn is_pending(&self, name: &str) -> bool {
+        self.field(name).task.is_some()
+    }
+
+    /// Returns an error of the field.
+    pub fn error(&self, name: &str) -> Option<&str> {
+        self.field(name).error.as_deref()
+    }
+
+    /// Renders an error of the field as `<span class="error">` if the field
+    /// was touched. The span is empty if there is nothing to show.
+    pub fn view_error<MSG: 'static>(&self, name: &str) -> Html<MSG> {
+        let field = self.field(name);
+        let error = if field.touched { field.error.clone() } else { None };
+        html! {
+            <span class="error",>{ error.unwrap_or_default() }</span>
+        }
+    }
+
+    /// Replaces the model and resets states of all fields.
+    pub fn reset(&mut self, model: T) {
+        for field in self.fields.values_mut() {
+            if let Some(mut task) = field.task.take() {
+                task.cancel();
+            }
+            *field = FieldState::default();
+        }
+        self.model = model;
+    }
+
+    fn validate_field(&mut self, name: &str) {
+        let error = self.validators.iter()
+            .filter(|&&(field, _)| field == name)
+            .filter_map(|(_, validator)| validator(&self.model).err())
+            .next();
+        self.field_mut(name).error = error;
+    }
+
+    fn field(&self, name: &str) -> &FieldState {
+        self.fields.get(name)
+            .unwrap_or_else(|| panic!("form has no field {}", name))
+    }
+
+    fn field_mut(&mut self, name: &str) -> &mut FieldState {
+        self.fields.get_mut(name)
+            .unwrap_or_else(|| panic!("form has no field {}", name))
+    }
+}
@@ -6,6 +6,7 @@
 
 use serde::{Serialize, Deserialize};
 use serde_json;
+use errors::{self, ErrorKind, ErrorReport};
 
 /// A representation of a value which can be stored.
 pub type Storable = Option<String>;
@@ -31,7 +32,7 @@ impl From<Restorable> for Nothing {
 /// A representation of a JSON data. Use it as wrapper to
 /// set a format you want to use for conversion:
 ///
-/// ```rust
+/// ```rust,ignore
 /// // Converts (lazy) data to a Json
 /// let dump = Json(&data);
 ///
@@ -56,7 +57,9 @@ where
     fn from(value: Restorable) -> Self {
         match value {
             Ok(data) => {
-                Json(serde_json::from_str(&data).map_err(drop))
+                Json(serde_json::from_str(&data).map_err(|error| {
+                    errors::report(ErrorReport::new(ErrorKind::Decode, "json", error.to_string()));
+                }))
             }
             Err(_reason) => {
                 Json(Err(()))
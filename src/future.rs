@@ -0,0 +1,332 @@
+//! This module contains a local executor of futures. Resolved values
+//! of futures are sent to a loop as messages, so `async` code could be
+//! written instead of threading callbacks through services:
+//!
+//! ```rust,ignore
+//! fn update(context: &mut Context, model: &mut Model, msg: Msg) {
+//!     match msg {
+//!         Msg::Load => {
+//!             let task = context.sender.spawn(async {
+//!                 future::sleep(Duration::from_millis(300)).await;
+//!                 Msg::Loaded(load_user().await)
+//!             });
+//!             model.user.load(task);
+//!         }
+//!         Msg::Loaded(user) => {
+//!             model.user.resolve(user);
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Futures are polled on the main thread of a browser. Wakers could be
+//! called from the same thread only.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::time::Duration;
+use callback::Callback;
+use html::AppSender;
+use services::Task;
+use services::to_ms;
+use stdweb::Value;
+
+type LocalFuture = Pin<Box<Future<Output = ()>>>;
+
+/// Futures which are spawned and not completed yet.
+#[derive(Default)]
+struct Executor {
+    tasks: HashMap<usize, LocalFuture>,
+    ready: Vec<usize>,
+    next_id: usize,
+    scheduled: bool,
+    /// The task which is polled now, it's out of `tasks` during a poll.
+    polling: Option<usize>,
+    /// The polled task was canceled by its handle and isn't put back.
+    canceled: bool,
+}
+
+thread_local! {
+    static EXECUTOR: RefCell<Executor> = RefCell::new(Executor::default());
+}
+
+/// Wakes a task by its id.
+struct TaskWaker(usize);
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        wake(self.0);
+    }
+}
+
+/// Puts the task to the ready queue and schedules polling of the queue.
+fn wake(id: usize) {
+    let schedule = EXECUTOR.with(|executor| {
+        let mut executor = executor.borrow_mut();
+        if !executor.ready.contains(&id) {
+            executor.ready.push(id);
+        }
+        !::std::mem::replace(&mut executor.scheduled, true)
+    });
+    if schedule {
+        js! { @(no_return)
+            var callback = @{poll_ready};
+            Promise.resolve().then(function() {
+                callback();
+                callback.drop();
+            });
+        }
+    }
+}
+
+/// Polls all tasks which were woken up.
+fn poll_ready() {
+    loop {
+        let ready = EXECUTOR.with(|executor| {
+            let mut executor = executor.borrow_mut();
+            if executor.ready.is_empty() {
+                executor.scheduled = false;
+            }
+            ::std::mem::take(&mut executor.ready)
+        });
+        if ready.is_empty() {
+            break;
+        }
+        for id in ready {
+            // The task is taken out, because it could spawn other tasks
+            let task = EXECUTOR.with(|executor| {
+                let mut executor = executor.borrow_mut();
+                let task = executor.tasks.remove(&id);
+                if task.is_some() {
+                    executor.polling = Some(id);
+                    executor.canceled = false;
+                }
+                task
+            });
+            if let Some(mut task) = task {
+                let waker = Waker::from(Arc::new(TaskWaker(id)));
+                let mut context = Context::from_waker(&waker);
+                let pending = task.as_mut().poll(&mut context).is_pending();
+                let canceled = EXECUTOR.with(|executor| {
+                    let mut executor = executor.borrow_mut();
+                    executor.polling = None;
+                    ::std::mem::replace(&mut executor.canceled, false)
+                });
+                if pending && !canceled {
+                    EXECUTOR.with(|executor| executor.borrow_mut().tasks.insert(id, task));
+                }
+            }
+        }
+    }
+}
+
+/// A handle to cancel a spawned future. The future is dropped when it's canceled.
+pub struct SpawnHandle(Option<usize>);
+
+impl Task for SpawnHandle {
+    fn is_active(&self) -> bool {
+        self.0.map_or(false, |id| {
+            EXECUTOR.with(|executor| {
+                let executor = executor.borrow();
+                executor.tasks.contains_key(&id) || (executor.polling == Some(id) && !executor.canceled)
+            })
+        })
+    }
+
+    fn cancel(&mut self) {
+        let id = self.0.take().expect("tried to cancel a future twice");
+        let task = EXECUTOR.with(|executor| {
+            let mut executor = executor.borrow_mut();
+            executor.ready.retain(|ready| *ready != id);
+            if executor.polling == Some(id) {
+                // The task is polled now, it's dropped after the poll
+                executor.canceled = true;
+            }
+            executor.tasks.remove(&id)
+        });
+        // The future could drop handles of other futures
+        drop(task);
+    }
+}
+
+impl Drop for SpawnHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+/// Runs the future on the main thread until it completes.
+pub fn spawn_local<F>(future: F) -> SpawnHandle
+where
+    F: Future<Output = ()> + 'static,
+{
+    let id = EXECUTOR.with(|executor| {
+        let mut executor = executor.borrow_mut();
+        let id = executor.next_id;
+        executor.next_id += 1;
+        executor.tasks.insert(id, Box::pin(future));
+        id
+    });
+    wake(id);
+    SpawnHandle(Some(id))
+}
+
+/// A future which sends its output to a loop.
+struct SendOutput<MSG> {
+    future: Pin<Box<Future<Output = MSG>>>,
+    sender: AppSender<MSG>,
+}
+
+impl<MSG> Future for SendOutput<MSG> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<()> {
+        match self.future.as_mut().poll(context) {
+            Poll::Ready(msg) => {
+                self.sender.send(msg);
+                Poll::Ready(())
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<MSG: 'static> AppSender<MSG> {
+    /// Runs the future and sends its output to the loop as a message.
+    pub fn spawn<F>(&self, future: F) -> SpawnHandle
+    where
+        F: Future<Output = MSG> + 'static,
+    {
+        spawn_local(SendOutput {
+            future: Box::pin(future),
+            sender: self.clone(),
+        })
+    }
+}
+
+/// A state shared by a `Deferred` future and its `Resolver`.
+struct Slot<T> {
+    value: Option<T>,
+    waker: Option<Waker>,
+}
+
+/// A future which is completed by a `Resolver`. It adapts callbacks
+/// of components and services to `async` code.
+pub struct Deferred<T> {
+    slot: Rc<RefCell<Slot<T>>>,
+}
+
+/// Completes a `Deferred` future.
+pub struct Resolver<T> {
+    slot: Rc<RefCell<Slot<T>>>,
+}
+
+/// Creates a future and a resolver which completes it.
+pub fn deferred<T>() -> (Resolver<T>, Deferred<T>) {
+    let slot = Rc::new(RefCell::new(Slot {
+        value: None,
+        waker: None,
+    }));
+    (Resolver { slot: slot.clone() }, Deferred { slot })
+}
+
+impl<T> Resolver<T> {
+    /// Completes the future with the value. Only the first value is kept.
+    pub fn resolve(&self, value: T) {
+        let waker = {
+            let mut slot = self.slot.borrow_mut();
+            if slot.value.is_some() {
+                return;
+            }
+            slot.value = Some(value);
+            slot.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T: 'static> Resolver<T> {
+    /// Converts the resolver to a callback which completes the future.
+    pub fn into_callback(self) -> Callback<T> {
+        Callback::from(move |value| self.resolve(value))
+    }
+}
+
+impl<T> Future for Deferred<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context) -> Poll<T> {
+        let mut slot = self.slot.borrow_mut();
+        match slot.value.take() {
+            Some(value) => Poll::Ready(value),
+            None => {
+                slot.waker = Some(context.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Returns a future which completes after the `duration`.
+pub fn sleep(duration: Duration) -> Sleep {
+    let (resolver, deferred) = deferred();
+    let callback = move || resolver.resolve(());
+    let ms = to_ms(duration);
+    let handle = js! {
+        var callback = @{callback};
+        let action = function() {
+            callback();
+            callback.drop();
+        };
+        let delay = @{ms};
+        return {
+            timeout_id: setTimeout(action, delay),
+            callback,
+        };
+    };
+    Sleep {
+        deferred,
+        handle: Some(handle),
+    }
+}
+
+/// A future of the `sleep` function. The timeout is cleared if it's
+/// dropped before completion.
+pub struct Sleep {
+    deferred: Deferred<()>,
+    handle: Option<Value>,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<()> {
+        let poll = Pin::new(&mut self.deferred).poll(context);
+        if poll.is_ready() {
+            // The callback was dropped by the timeout itself
+            self.handle = None;
+        }
+        poll
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            js! { @(no_return)
+                var handle = @{handle};
+                clearTimeout(handle.timeout_id);
+                handle.callback.drop();
+            }
+        }
+    }
+}
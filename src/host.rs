@@ -0,0 +1,191 @@
+//! This module contains `HostAttributes` which wrapper components take
+//! as a property and spread onto their element with `..`, so a parent
+//! sets `id`, classes, `aria-*` attributes or listeners of the element
+//! without a property for every one:
+//!
+//! ```rust,ignore
+//! let host = HostAttributes::new()
+//!     .attr("id", "save")
+//!     .attr("aria-describedby", "save-hint")
+//!     .class("primary")
+//!     .listen("focus", link.send_back(|_| Msg::Focused));
+//! html! { <Button: label="Save", host=host,/> }
+//!
+//! // The view of `Button`
+//! html! { <button class="button", ..&self.props.host,>{ &self.props.label }</button> }
+//! ```
+
+#[cfg(feature = "testing")]
+use std::any::Any;
+use std::cell::RefCell;
+use stdweb::Value;
+use stdweb::unstable::TryInto;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::web::Element;
+use callback::Callback;
+use classes::Classes;
+use html::AppSender;
+use virtual_dom::{VTag, Listener};
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use virtual_dom::{ListenerHandle, ListenerOptions};
+#[cfg(feature = "testing")]
+use virtual_dom::Simulated;
+
+/// Attributes, classes and listeners which are added to an element by `..`.
+/// Classes are added to classes of the element and attributes replace
+/// attributes which were set before the spread.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct HostAttributes {
+    attributes: Vec<(String, String)>,
+    classes: Classes,
+    listeners: Vec<(&'static str, Callback<Value>)>,
+}
+
+impl HostAttributes {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        HostAttributes::default()
+    }
+
+    /// Adds an attribute. A value of the same attribute is replaced.
+    pub fn attr<T: ToString>(mut self, name: &str, value: T) -> Self {
+        let value = value.to_string();
+        match self.attributes.iter_mut().find(|item| item.0 == name) {
+            Some(item) => item.1 = value,
+            None => self.attributes.push((name.to_owned(), value)),
+        }
+        self
+    }
+
+    /// Adds classes.
+    pub fn class<T: Into<Classes>>(mut self, classes: T) -> Self {
+        self.classes.push(classes);
+        self
+    }
+
+    /// Adds a listener of DOM events with the `kind` name, like `click`
+    /// or `keydown`. The callback gets the event object.
+    pub fn listen(mut self, kind: &'static str, callback: Callback<Value>) -> Self {
+        self.listeners.push((kind, callback));
+        self
+    }
+
+    /// Returns the value of an attribute.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|item| item.0 == name).map(|item| item.1.as_str())
+    }
+
+    /// Returns the classes.
+    pub fn classes(&self) -> &Classes {
+        &self.classes
+    }
+
+    /// Returns `true` if there is nothing to add.
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty() && self.classes.is_empty() && self.listeners.is_empty()
+    }
+}
+
+/// Something which could be spread onto an element with `..` in `html!`:
+/// `HostAttributes` or pairs of names and values of attributes.
+pub trait Spread<MSG> {
+    /// Adds everything to the tag.
+    fn spread(self, tag: &mut VTag<MSG>);
+}
+
+impl<MSG: 'static> Spread<MSG> for HostAttributes {
+    fn spread(self, tag: &mut VTag<MSG>) {
+        (&self).spread(tag);
+    }
+}
+
+impl<MSG: 'static> Spread<MSG> for &HostAttributes {
+    fn spread(self, tag: &mut VTag<MSG>) {
+        self.attributes.iter().map(|(name, value)| (name, value)).spread(tag);
+        for class in self.classes.iter() {
+            tag.add_classes(class);
+        }
+        for &(kind, ref callback) in &self.listeners {
+            tag.add_listener(Box::new(ForwardedListener {
+                kind,
+                callback: callback.clone(),
+            }));
+        }
+    }
+}
+
+impl<MSG, I, K, V> Spread<MSG> for I
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: ToString,
+{
+    fn spread(self, tag: &mut VTag<MSG>) {
+        for (name, value) in self {
+            let name = name.as_ref();
+            if is_attribute_name(name) {
+                tag.add_attribute(name, value);
+            } else {
+                warn!("skipped attribute with a wrong name: {:?}", name);
+            }
+        }
+    }
+}
+
+/// Creates a callback for `HostAttributes::listen` which sends a message
+/// to a component, which renders its anchor through a parent.
+pub(crate) fn forward<MSG, F>(sender: &AppSender<MSG>, converter: F) -> Callback<Value>
+where
+    MSG: 'static,
+    F: Fn(Value) -> MSG + 'static,
+{
+    let sender = RefCell::new(sender.clone());
+    Callback::from(move |event| sender.borrow_mut().send(converter(event)))
+}
+
+/// Returns the `key` of a keyboard event which a forwarded listener got.
+pub(crate) fn event_key(event: &Value) -> String {
+    js! { return @{event}.key; }.try_into().unwrap_or_default()
+}
+
+/// Checks a computed name of an attribute, because browsers throw
+/// an exception if the name contains spaces, quotes or `/ = >`.
+fn is_attribute_name(name: &str) -> bool {
+    !name.is_empty() && !name.chars().any(|c| {
+        c.is_whitespace() || c.is_control() || "\"'/=>".contains(c)
+    })
+}
+
+/// A listener which calls a callback of a parent instead of sending
+/// a message to the component which renders the element.
+struct ForwardedListener {
+    kind: &'static str,
+    callback: Callback<Value>,
+}
+
+impl<MSG> Listener<MSG> for ForwardedListener {
+    fn kind(&self) -> &'static str {
+        self.kind
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn attach(&mut self, element: &Element, _: AppSender<MSG>) -> ListenerHandle {
+        let callback = self.callback.clone();
+        let kind = self.kind;
+        let listener = move |event: Value| {
+            debug!("Forwarded event handler: {}", kind);
+            callback.emit(event);
+        };
+        ListenerHandle::attach_named(element, kind, ListenerOptions::default(), listener)
+    }
+
+    /// Calls the callback with a `Value` of the simulated event. There is no
+    /// message of the component to return, so other data are ignored.
+    #[cfg(feature = "testing")]
+    fn simulate(&mut self, data: &Any) -> Option<Simulated<MSG>> {
+        if let Some(event) = data.downcast_ref::<Value>() {
+            self.callback.emit(event.clone());
+        }
+        None
+    }
+}
@@ -0,0 +1,104 @@
+//! This module contains a middleware which keeps the model of an app
+//! across reloads of the page, so a rebuild during development doesn't
+//! reset the state of the app. It's compiled with the `hot_reload` feature:
+//!
+//! ```rust,ignore
+//! let mut app = App::new();
+//! let mut hot = HotReload::new("todomvc");
+//! let model = hot.restore(Model::new());
+//! app.middleware(hot);
+//! app.mount(context, model, update, view);
+//! ```
+//!
+//! The model is serialized after every update and written to the session
+//! storage before the page is unloaded. It's restored once by the next page.
+//! If the model of a new build can't be parsed from the snapshot, because
+//! its fields changed, the app starts with the given model.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json;
+use middleware::Middleware;
+use services::Task;
+use services::backend::{self, StorageBackend};
+use services::storage::Scope;
+
+/// A prefix of keys of snapshots in the session storage.
+const PREFIX: &str = "yew-hot-reload:";
+
+/// A middleware which restores the model after reloads of the page.
+pub struct HotReload {
+    key: String,
+    storage: Rc<StorageBackend>,
+    /// The snapshot of the latest model.
+    snapshot: Rc<RefCell<Option<String>>>,
+    _unload: Box<Task>,
+}
+
+impl HotReload {
+    /// Creates a middleware which keeps the model with the key, so
+    /// a few apps of a page keep their models apart.
+    pub fn new(key: &str) -> Self {
+        let key = format!("{}{}", PREFIX, key);
+        let storage = backend::storage();
+        let snapshot: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
+        let unload = {
+            let key = key.clone();
+            let storage = storage.clone();
+            let snapshot = snapshot.clone();
+            backend::unload().listen(Box::new(move || {
+                if let Some(ref snapshot) = *snapshot.borrow() {
+                    storage.set(Scope::Session, &key, snapshot);
+                }
+                false
+            }))
+        };
+        HotReload {
+            key,
+            storage,
+            snapshot,
+            _unload: unload,
+        }
+    }
+
+    /// Returns the model of the previous page, or the `model` if there
+    /// is no snapshot or it doesn't fit the model anymore.
+    pub fn restore<MOD>(&mut self, model: MOD) -> MOD
+    where
+        MOD: Serialize + DeserializeOwned,
+    {
+        let restored = self.storage.get(Scope::Session, &self.key).and_then(|snapshot| {
+            self.storage.remove(Scope::Session, &self.key);
+            match serde_json::from_str(&snapshot) {
+                Ok(model) => Some(model),
+                Err(err) => {
+                    warn!("can't restore the model after a reload, it starts over: {}", err);
+                    None
+                }
+            }
+        });
+        let model = restored.unwrap_or(model);
+        self.keep(&model);
+        model
+    }
+
+    fn keep<MOD: Serialize>(&self, model: &MOD) {
+        match serde_json::to_string(model) {
+            Ok(snapshot) => *self.snapshot.borrow_mut() = Some(snapshot),
+            Err(err) => warn!("can't take a snapshot of the model: {}", err),
+        }
+    }
+}
+
+impl<MOD, MSG> Middleware<MOD, MSG> for HotReload
+where
+    MOD: Serialize,
+{
+    fn should_render(&mut self, model: &MOD) -> bool {
+        // It's called once after every batch of messages
+        self.keep(model);
+        true
+    }
+}
@@ -1,13 +1,55 @@
 //! The main module which contents aliases to necessary items
 //! to create a template and implement `update` and `view` functions.
 
-use std::sync::mpsc::{Sender, Receiver, channel};
-use stdweb::Value;
-use stdweb::web::{Element, INode, EventListenerHandle, document};
-use stdweb::web::event::{IMouseEvent, IKeyboardEvent};
-use virtual_dom::{VNode, VTag, Listener};
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten", feature = "testing"))]
+use std::any::Any;
+use std::sync::Mutex;
+use std::sync::mpsc::{Sender, Receiver, TryRecvError, channel};
+use std::marker::PhantomData;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use std::cell::RefCell;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use std::rc::Rc;
+use std::time::Duration;
+use stdweb::{Value, Reference};
+use stdweb::unstable::{TryFrom, TryInto};
+use stdweb::web::Element;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::web::{INode, document};
+use stdweb::web::event::{IEvent, IMouseEvent, IKeyboardEvent};
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::web::event::{ClickEvent, DoubleClickEvent, KeypressEvent, KeydownEvent, KeyupEvent};
+use stdweb::web::event::{FocusEvent, BlurEvent};
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::web::event::{InputEvent, ChangeEvent};
+use events::*;
+use virtual_dom::{VTag, Listener, ListenerOptions};
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use virtual_dom::{self, VNode, ListenerHandle};
+#[cfg(feature = "testing")]
+use virtual_dom::Simulated;
+use virtual_dom::slicing;
+use services::backend;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use middleware::{self, Middleware};
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use trace;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use direction;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use css;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use theme;
+
+pub use callback::Callback;
+pub use classes::Classes;
+pub use style::Style;
+pub use host::HostAttributes;
+pub use component::{Component, ShouldRender, Link, Children, ChildrenOf, ChildOf, Render};
+pub use virtual_dom::{VComp, NodeRef};
 
 /// Removes anything from the given element.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
 fn clear_element(element: &Element) {
     while let Some(child) = element.last_child() {
         element.remove_child(&child).expect("can't remove a child");
@@ -18,42 +60,461 @@ fn clear_element(element: &Element) {
 /// and to schedule the next update call.
 pub struct AppSender<MSG> {
     tx: Sender<MSG>,
+    low_tx: Sender<MSG>,
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
     bind: Value,
+    /// The sender doesn't have a loop, its messages are ignored silently.
+    detached: bool,
 }
 
 impl<MSG> Clone for AppSender<MSG> {
     fn clone(&self) -> Self {
         AppSender {
             tx: self.tx.clone(),
+            low_tx: self.low_tx.clone(),
+            #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
             bind: self.bind.clone(),
+            detached: self.detached,
         }
     }
 }
 
+/// A lane of a message. A loop processes all messages of the high lane
+/// first and then messages of the low lane in slices of a few milliseconds,
+/// so streamed updates don't block user input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Priority {
+    /// Messages of user input. It's the lane of `send`.
+    High,
+    /// Messages of background work like a synchronization with a server.
+    Low,
+}
+
+/// How long a loop processes messages of the low lane before
+/// it lets a browser handle other events.
+const SLICE_MS: f64 = 5.0;
+
 impl<MSG> AppSender<MSG> {
+    /// Creates a sender without a loop for server-side rendering.
+    /// Messages sent with it are ignored like messages of an unmounted app.
+    #[cfg(any(feature = "ssr", feature = "testing"))]
+    pub(crate) fn detached() -> Self {
+        let (tx, _) = channel();
+        let (low_tx, _) = channel();
+        AppSender {
+            tx,
+            low_tx,
+            #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+            bind: Value::Null,
+            detached: true,
+        }
+    }
+
+    /// Creates a sender of a loop which is driven by a test renderer.
+    /// Messages are kept in the returned receivers of both lanes and
+    /// nothing is scheduled.
+    #[cfg(feature = "testing")]
+    pub(crate) fn captured() -> (Self, Receiver<MSG>, Receiver<MSG>) {
+        let (tx, rx) = channel();
+        let (low_tx, low_rx) = channel();
+        let sender = AppSender {
+            tx,
+            low_tx,
+            #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+            bind: Value::Null,
+            detached: false,
+        };
+        (sender, rx, low_rx)
+    }
+
     /// Send the message and schedule an update.
     pub fn send(&mut self, msg: MSG) {
-        self.tx.send(msg).expect("App lost the receiver!");
-        let bind = &self.bind;
+        self.send_with(Priority::High, msg);
+    }
+
+    /// Send the message to the lane and schedule an update.
+    pub fn send_with(&mut self, priority: Priority, msg: MSG) {
+        #[cfg(feature = "strict")]
+        ::strict::sent::<MSG>();
+        let tx = match priority {
+            Priority::High => &self.tx,
+            Priority::Low => &self.low_tx,
+        };
+        if tx.send(msg).is_err() {
+            self.unmounted();
+            return;
+        }
+        self.schedule();
+    }
+
+    /// Returns `false` for senders of test renderers and servers.
+    /// Native builds don't have loops at all.
+    fn has_loop(&self) -> bool {
+        #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+        {
+            self.bind != Value::Null
+        }
+        #[cfg(not(any(target_arch = "wasm32", target_os = "emscripten")))]
+        {
+            false
+        }
+    }
+
+    /// Puts the loop of the sender to the queue of the scheduler.
+    fn schedule(&self) {
+        #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+        schedule(&self.bind);
+    }
+
+    /// Schedules a run of the loop without messages, so middlewares
+    /// could change the model and the app is rendered again.
+    pub(crate) fn wake(&self) {
+        // A test renderer runs middlewares on every flush
+        if self.has_loop() {
+            #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+            {
+                js! { @(no_return)
+                    @{&self.bind}.woken = true;
+                }
+                schedule(&self.bind);
+            }
+        }
+    }
+
+    /// Sends all messages and schedules a single update for them,
+    /// so a burst of messages is rendered once.
+    pub fn send_all<I>(&mut self, messages: I)
+    where
+        I: IntoIterator<Item = MSG>,
+    {
+        #[cfg(feature = "strict")]
+        ::strict::sent::<MSG>();
+        let mut sent = false;
+        for msg in messages {
+            if self.tx.send(msg).is_err() {
+                self.unmounted();
+                return;
+            }
+            sent = true;
+        }
+        if sent {
+            self.schedule();
+        }
+    }
+
+    /// Reports a message which was sent to a destroyed loop.
+    fn unmounted(&self) {
+        if self.detached {
+            return;
+        }
+        debug!("message sent to an unmounted app");
+        #[cfg(feature = "strict")]
+        ::strict::leaked::<MSG>();
+    }
+}
+
+impl<MSG: 'static> AppSender<MSG> {
+    /// Sends the message after the current update. Unlike `send` called
+    /// from `update` the message isn't processed by the running loop,
+    /// so the current state is rendered first.
+    pub fn send_soon(&self, msg: MSG) {
+        #[cfg(feature = "strict")]
+        ::strict::sent::<MSG>();
+        if self.has_loop() {
+            #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+            {
+                let mut sender = self.clone();
+                let mut msg = Some(msg);
+                let callback = move || {
+                    if let Some(msg) = msg.take() {
+                        sender.send(msg);
+                    }
+                };
+                js! { @(no_return)
+                    var callback = @{callback};
+                    setTimeout(function() {
+                        callback();
+                        callback.drop();
+                    });
+                }
+            }
+        } else if self.low_tx.send(msg).is_err() {
+            // A sender of a test renderer handles the low lane after the render
+            self.unmounted();
+        }
+    }
+}
+
+impl<MSG: Send + 'static> AppSender<MSG> {
+    /// Creates a sender which could be moved to other threads. Its messages
+    /// are collected by the main thread every few milliseconds until
+    /// all clones of the sender are dropped.
+    pub fn thread_sender(&self) -> ThreadSender<MSG> {
+        let (tx, rx) = channel();
+        let mut sender = self.clone();
+        let pump = move || -> bool {
+            let mut messages = Vec::new();
+            let connected = loop {
+                match rx.try_recv() {
+                    Ok(msg) => messages.push(msg),
+                    Err(TryRecvError::Empty) => break true,
+                    Err(TryRecvError::Disconnected) => break false,
+                }
+            };
+            sender.send_all(messages);
+            connected
+        };
         js! { @(no_return)
-            // Schedule to call the loop handler
+            var pump = @{pump};
+            var id = setInterval(function() {
+                if (!pump()) {
+                    clearInterval(id);
+                    pump.drop();
+                }
+            }, @{PUMP_MS});
+        }
+        ThreadSender {
+            tx: Mutex::new(tx),
+        }
+    }
+}
+
+/// Returns `true` once after the loop was woken by `AppSender::wake`.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+fn take_woken(bind: &Value) -> bool {
+    let woken: Value = js! {
+        var bind = @{bind};
+        var woken = bind.woken === true;
+        bind.woken = false;
+        return woken;
+    };
+    woken == Value::Bool(true)
+}
+
+/// How often the main thread collects messages of thread senders.
+const PUMP_MS: u32 = 10;
+
+/// A sender which is `Send` and `Sync`, so workers, threads and callbacks
+/// of other contexts could send messages to a loop.
+/// Get it with `AppSender::thread_sender`.
+pub struct ThreadSender<MSG> {
+    tx: Mutex<Sender<MSG>>,
+}
+
+impl<MSG> ThreadSender<MSG> {
+    /// Sends the message. It's delivered by the main thread.
+    pub fn send(&self, msg: MSG) {
+        let tx = self.tx.lock().expect("thread sender poisoned");
+        if tx.send(msg).is_err() {
+            debug!("message sent to an unmounted app");
+        }
+    }
+}
+
+impl<MSG> Clone for ThreadSender<MSG> {
+    fn clone(&self) -> Self {
+        let tx = self.tx.lock().expect("thread sender poisoned");
+        ThreadSender {
+            tx: Mutex::new(tx.clone()),
+        }
+    }
+}
+
+/// Receivers of both lanes of a loop and the message of the low lane
+/// which didn't fit in the last slice.
+pub(crate) struct Lanes<MSG> {
+    rx: Receiver<MSG>,
+    low_rx: Receiver<MSG>,
+    carry: Option<MSG>,
+}
+
+impl<MSG> Lanes<MSG> {
+    pub(crate) fn new(rx: Receiver<MSG>, low_rx: Receiver<MSG>) -> Self {
+        Lanes { rx, low_rx, carry: None }
+    }
+
+    /// Starts a batch of queued messages. The carried message stays
+    /// before other messages of the low lane, even if the high lane goes first.
+    pub(crate) fn batch(&mut self) -> Batch<'_, MSG> {
+        let first = self.rx.try_recv().ok()
+            .or_else(|| self.carry.take())
+            .or_else(|| self.low_rx.try_recv().ok());
+        Batch {
+            first,
+            rx: &self.rx,
+            low_rx: &self.low_rx,
+            carry: &mut self.carry,
+            started: now(),
+            sliced: false,
+        }
+    }
+}
+
+/// Messages which a loop processes at once: all messages of the high lane
+/// and messages of the low lane until the slice elapsed.
+pub(crate) struct Batch<'a, MSG: 'a> {
+    first: Option<MSG>,
+    rx: &'a Receiver<MSG>,
+    low_rx: &'a Receiver<MSG>,
+    carry: &'a mut Option<MSG>,
+    started: f64,
+    sliced: bool,
+}
+
+impl<'a, MSG> Batch<'a, MSG> {
+    /// Returns `true` if no messages were queued.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.first.is_none()
+    }
+}
+
+impl<'a, MSG> Iterator for Batch<'a, MSG> {
+    type Item = MSG;
+
+    fn next(&mut self) -> Option<MSG> {
+        if let Some(msg) = self.first.take() {
+            return Some(msg);
+        }
+        if let Ok(msg) = self.rx.try_recv() {
+            return Some(msg);
+        }
+        if self.sliced {
+            return None;
+        }
+        let msg = self.carry.take().or_else(|| self.low_rx.try_recv().ok())?;
+        if now() - self.started > SLICE_MS {
+            // Keep the message for the next slice
+            *self.carry = Some(msg);
+            self.sliced = true;
+            return None;
+        }
+        Some(msg)
+    }
+}
+
+/// Returns a time in milliseconds to measure slices. It's the time of
+/// the backend of timers, so `MockClock` controls slices in tests.
+pub(crate) fn now() -> f64 {
+    let now = backend::timers().monotonic();
+    now.as_secs() as f64 * 1000.0 + f64::from(now.subsec_micros()) / 1000.0
+}
+
+/// When the scheduler processes queued messages.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Flush {
+    /// Right after the current task (an event handler or a callback of a service).
+    /// It's the default.
+    Microtask,
+    /// Before the next repaint. Messages of many tasks (like frequent messages
+    /// of a websocket) are processed together and views are rendered once per frame.
+    AnimationFrame,
+}
+
+/// Sets when the scheduler processes queued messages of all apps and components.
+/// Native builds don't have a scheduler, so it does nothing there.
+pub fn set_flush(flush: Flush) {
+    let frame = flush == Flush::AnimationFrame;
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    js! { @(no_return)
+        @{scheduler()}.flush = @{frame} ? "frame" : "microtask";
+    }
+    #[cfg(not(any(target_arch = "wasm32", target_os = "emscripten")))]
+    let _ = frame;
+}
+
+/// Sets the budget of a render. If a render takes longer, new nodes which
+/// are left are created in the next animation frames, so a huge update doesn't
+/// block input events. Renders are never sliced with `None`, it's the default.
+pub fn set_time_slicing(budget: Option<Duration>) {
+    slicing::set_budget(budget);
+}
+
+/// Puts the loop to the queue of the scheduler. Loops are called
+/// in batches and every loop processes all its messages and renders once,
+/// so many sends in a row cause a single update of the DOM.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+fn schedule(bind: &Value) {
+    if let Value::Null = *bind {
+        // A sender without a loop
+        return;
+    }
+    js! { @(no_return)
+        @{scheduler()}.push(@{bind});
+    }
+}
+
+/// Returns the scheduler shared by all loops of a page.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+fn scheduler() -> Value {
+    let scheduler: Value = js! {
+        if (window.__yew_scheduler) {
+            return window.__yew_scheduler;
+        }
+        var scheduler = {
+            queue: [],
+            scheduled: false,
+            flush: "microtask",
+        };
+        var run = function() {
+            scheduler.scheduled = false;
             // IMPORTANT! If call loop function immediately
             // it stops handling other messages and the first
             // one will be fired.
-            var bind = @{bind};
-            setTimeout(bind.loop);
-        }
-    }
+            // Loops queued by other loops (like components which got
+            // new properties) are called in the same batch, but a loop
+            // which always sends messages to itself can't block a page.
+            // The loop is taken on call, because it could be unmounted.
+            var budget = 1000;
+            while (scheduler.queue.length > 0 && budget > 0) {
+                var bind = scheduler.queue.shift();
+                bind.queued = false;
+                if (bind.loop() === true) {
+                    // Messages of the low lane are left: let a browser
+                    // handle input and continue them in the next task
+                    setTimeout(scheduler.push.bind(null, bind));
+                }
+                budget -= 1;
+            }
+            if (scheduler.queue.length > 0) {
+                scheduler.scheduled = true;
+                setTimeout(run);
+            }
+        };
+        scheduler.push = function(bind) {
+            if (bind.queued !== true) {
+                bind.queued = true;
+                scheduler.queue.push(bind);
+            }
+            if (scheduler.scheduled !== true) {
+                scheduler.scheduled = true;
+                if (scheduler.flush === "frame") {
+                    requestAnimationFrame(run);
+                } else {
+                    Promise.resolve().then(run);
+                }
+            }
+        };
+        window.__yew_scheduler = scheduler;
+        return scheduler;
+    };
+    scheduler
 }
 
 /// A context which contains a bridge to send a messages to a loop.
 /// Mostly services uses it.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
 pub struct App<MSG> {
     tx: Sender<MSG>,
     rx: Option<Receiver<MSG>>,
+    low_tx: Sender<MSG>,
+    low_rx: Option<Receiver<MSG>>,
     bind: Value,
+    teardown: Option<Box<FnMut()>>,
+    /// Middlewares of the model which will be mounted.
+    middleware: Option<Box<Any>>,
 }
 
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
 impl<MSG: 'static> App<MSG> {
     /// Creates a context with connected sender and receiver.
     pub fn new() -> Self {
@@ -61,10 +522,15 @@ impl<MSG: 'static> App<MSG> {
             return { "loop": function() { } };
         };
         let (tx, rx) = channel();
+        let (low_tx, low_rx) = channel();
         App {
             tx,
             rx: Some(rx),
+            low_tx,
+            low_rx: Some(low_rx),
             bind,
+            teardown: None,
+            middleware: None,
         }
     }
 
@@ -72,10 +538,27 @@ impl<MSG: 'static> App<MSG> {
     pub fn sender(&mut self) -> AppSender<MSG> {
         AppSender {
             tx: self.tx.clone(),
+            low_tx: self.low_tx.clone(),
             bind: self.bind.clone(),
+            detached: false,
         }
     }
 
+    /// Adds a middleware which wraps updates of the model.
+    /// Add middlewares before the app is mounted.
+    pub fn middleware<MOD, M>(&mut self, middleware: M)
+    where
+        MOD: 'static,
+        M: Middleware<MOD, MSG> + 'static,
+    {
+        let chain = self.middleware.get_or_insert_with(|| {
+            Box::new(Vec::<Box<Middleware<MOD, MSG>>>::new())
+        });
+        chain.downcast_mut::<Vec<Box<Middleware<MOD, MSG>>>>()
+            .expect("middlewares were added for another model")
+            .push(Box::new(middleware));
+    }
+
     /// Alias to `mount_to("body", ...)`.
     pub fn mount<CTX, MOD, U, V>(&mut self, context: CTX, model: MOD, update: U, view: V)
     where
@@ -91,7 +574,7 @@ impl<MSG: 'static> App<MSG> {
     /// function in Elm. You should provide an initial model, `update` function
     /// which will update the state of the model and a `view` function which
     /// will render the model to a virtual DOM tree.
-    pub fn mount_to<CTX, MOD, U, V>(&mut self, selector: &str, mut context: CTX, mut model: MOD, update: U, view: V)
+    pub fn mount_to<CTX, MOD, U, V>(&mut self, selector: &str, context: CTX, model: MOD, update: U, view: V)
     where
         CTX: 'static,
         MOD: 'static,
@@ -100,32 +583,178 @@ impl<MSG: 'static> App<MSG> {
     {
         let element = document().query_selector(selector)
             .expect(format!("can't get node with selector `{}` for rendering", selector).as_str());
+        self.mount_to_element(element, context, model, update, view)
+    }
+
+    /// Mounts the app to the element. Every app owns its element and has
+    /// an own loop, so a page could contain many independent apps
+    /// (widgets of a server-rendered page, for example).
+    pub fn mount_to_element<CTX, MOD, U, V>(&mut self, element: Element, context: CTX, model: MOD, update: U, view: V)
+    where
+        CTX: 'static,
+        MOD: 'static,
+        U: Fn(&mut CTX, &mut MOD, MSG) + 'static,
+        V: Fn(&MOD) -> Html<MSG> + 'static,
+    {
         clear_element(&element);
-        // No messages at start
-        let mut messages = Vec::new();
-        let mut last_frame = VNode::from(view(&model));
-        last_frame.apply(&element, None, self.sender());
-        let mut last_frame = Some(last_frame);
+        let mut first_frame = VNode::from(view(&model));
+        first_frame.apply(&element, None, self.sender());
+        self.run(element, first_frame, context, model, update, view);
+    }
+
+    /// Same as `mount_to`, but adopts the markup rendered by a server
+    /// (see `ssr` module) instead of rendering it again.
+    pub fn hydrate_to<CTX, MOD, U, V>(&mut self, selector: &str, context: CTX, model: MOD, update: U, view: V)
+    where
+        CTX: 'static,
+        MOD: 'static,
+        U: Fn(&mut CTX, &mut MOD, MSG) + 'static,
+        V: Fn(&MOD) -> Html<MSG> + 'static,
+    {
+        let element = document().query_selector(selector)
+            .unwrap_or_else(|| panic!("can't get node with selector `{}` for hydration", selector));
+        self.hydrate_to_element(element, context, model, update, view)
+    }
+
+    /// Adopts the existing nodes of the element and attaches listeners to them.
+    /// The model has to be the same as the model which was rendered by a server.
+    /// If the markup doesn't match the view, it prints a warning and renders the view again.
+    pub fn hydrate_to_element<CTX, MOD, U, V>(&mut self, element: Element, context: CTX, model: MOD, update: U, view: V)
+    where
+        CTX: 'static,
+        MOD: 'static,
+        U: Fn(&mut CTX, &mut MOD, MSG) + 'static,
+        V: Fn(&MOD) -> Html<MSG> + 'static,
+    {
+        let mut first_frame = VNode::from(view(&model));
+        if let Err(err) = first_frame.hydrate_root(&element, self.sender()) {
+            warn!("can't hydrate the markup, render it again: {}", err);
+            first_frame.into_reference();
+            clear_element(&element);
+            first_frame = VNode::from(view(&model));
+            first_frame.apply(&element, None, self.sender());
+        }
+        self.run(element, first_frame, context, model, update, view);
+    }
+
+    /// Starts the loop of the rendered app.
+    fn run<CTX, MOD, U, V>(&mut self, element: Element, first_frame: VNode<MSG>, mut context: CTX, mut model: MOD, update: U, view: V)
+    where
+        CTX: 'static,
+        MOD: 'static,
+        U: Fn(&mut CTX, &mut MOD, MSG) + 'static,
+        V: Fn(&MOD) -> Html<MSG> + 'static,
+    {
+        let last_frame = Rc::new(RefCell::new(Some(first_frame)));
+        let sender = self.sender();
+        let frame = last_frame.clone();
+        let parent = element.clone();
+        let mut chain: Vec<Box<Middleware<MOD, MSG>>> = self.middleware.take()
+            .map(|chain| *chain.downcast().expect("middlewares were added for another model"))
+            .unwrap_or_default();
+        self.handle(move |messages| {
+            {
+                let _span = trace::span("update", "app");
+                middleware::before_batch(&mut chain, &mut model);
+                for msg in messages {
+                    middleware::dispatch(&mut chain, &mut model, msg, &mut |model, msg| {
+                        update(&mut context, model, msg);
+                    });
+                }
+            }
+            if !middleware::should_render(&mut chain, &model) {
+                return;
+            }
+            let mut last_frame = frame.borrow_mut();
+            let mut next_frame = {
+                let _span = trace::span("view", "app");
+                #[cfg(feature = "profiler")]
+                let _measure = ::profiler::measure("app", ::profiler::Phase::View);
+                #[cfg(feature = "strict")]
+                let _viewing = ::strict::viewing("app");
+                VNode::from(view(&model))
+            };
+            #[cfg(feature = "strict")]
+            ::strict::check("app", &next_frame, || VNode::from(view(&model)));
+            let _span = trace::span("diff", "app");
+            let _slice = slicing::begin();
+            {
+                #[cfg(feature = "profiler")]
+                let _measure = ::profiler::measure("app", ::profiler::Phase::Patch);
+                next_frame.apply(&parent, last_frame.take(), sender.clone());
+            }
+            *last_frame = Some(next_frame);
+            direction::sync_document();
+            css::sync();
+            theme::sync();
+            #[cfg(feature = "a11y")]
+            ::a11y::schedule();
+        });
+        direction::sync_document();
+        css::sync();
+        theme::register_root(&element);
+        virtual_dom::register_root(&element);
+        #[cfg(feature = "a11y")]
+        {
+            ::a11y::register(Rc::downgrade(&last_frame));
+            ::a11y::schedule();
+        }
+        self.teardown = Some(Box::new(move || {
+            theme::unregister_root(&element);
+            virtual_dom::unregister_root(&element);
+            if let Some(frame) = last_frame.borrow_mut().take() {
+                frame.remove(&element);
+            }
+        }));
+    }
+
+    /// Unmounts the app: removes its nodes from the element, detaches listeners
+    /// and destroys components, then drops the loop with the context and the model,
+    /// so the handles of tasks kept by them are dropped too.
+    /// Messages sent to the app later are ignored.
+    ///
+    /// Call it outside of the `update` function of the app.
+    pub fn unmount(&mut self) {
+        if let Some(mut teardown) = self.teardown.take() {
+            teardown();
+        }
+        let bind = &self.bind;
+        js! { @(no_return)
+            var bind = @{bind};
+            var callback = bind.loop;
+            bind.loop = function() { };
+            if (callback.drop) {
+                // The loop could be running now, drop it later
+                setTimeout(function() { callback.drop(); });
+            }
+        }
+    }
+
+    /// Sets a handler which will be called with a batch of messages
+    /// received by the loop since the last call. The loop returns `true`
+    /// if messages of the low lane are left for the next slice.
+    pub(crate) fn handle<F>(&mut self, mut handler: F)
+    where
+        F: FnMut(&mut Batch<MSG>) + 'static,
+    {
         let rx = self.rx.take().expect("application runned without a receiver");
+        let low_rx = self.low_rx.take().expect("application runned without a receiver");
         let bind = self.bind.clone();
-        let sender = self.sender();
-        let mut callback = move || {
-            messages.extend(rx.try_iter());
-            for msg in messages.drain(..) {
-                update(&mut context, &mut model, msg);
+        let woken = self.bind.clone();
+        let mut lanes = Lanes::new(rx, low_rx);
+        let callback = move || -> bool {
+            let mut batch = lanes.batch();
+            if batch.is_empty() && !take_woken(&woken) {
+                return false;
             }
-            let mut next_frame = VNode::from(view(&model));
-            next_frame.apply(&element, last_frame.take(), sender.clone());
-            last_frame = Some(next_frame);
+            handler(&mut batch);
+            batch.sliced
         };
-        // Initial call for first rendering
-        callback();
         js! { @(no_return)
             var bind = @{bind};
             var callback = @{callback};
             bind.loop = callback;
         }
-        // TODO `Drop` should drop the callback
     }
 }
 
@@ -136,13 +765,22 @@ macro_rules! impl_action {
     ($($action:ident($event:ident : $type:ident) -> $ret:ty => $convert:expr)*) => {$(
         /// An abstract implementation of a listener.
         pub mod $action {
-            use stdweb::web::{IEventTarget, Element};
-            use stdweb::web::event::{IEvent, $type};
+            #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+            use stdweb::web::Element;
+            #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+            use stdweb::web::event::IEvent;
+            #[allow(unused_imports)]
+            use stdweb::unstable::TryInto;
             use super::*;
 
             /// A wrapper for a callback.
             /// Listener extracted from here when attached.
-            pub struct Wrapper<F>(Option<F>);
+            pub struct Wrapper<F> {
+                handler: Option<F>,
+                prevent_default: bool,
+                stop_propagation: bool,
+                options: ListenerOptions,
+            }
 
             /// And event type which keeps the returned type.
             pub type Event = $ret;
@@ -153,7 +791,44 @@ macro_rules! impl_action {
                 F: Fn($ret) -> MSG + 'static,
             {
                 fn from(handler: F) -> Self {
-                    Wrapper(Some(handler))
+                    Wrapper {
+                        handler: Some(handler),
+                        prevent_default: false,
+                        stop_propagation: true,
+                        options: ListenerOptions::default(),
+                    }
+                }
+            }
+
+            impl<F> Wrapper<F> {
+                /// Cancels the default action of the browser for the event.
+                pub fn prevent_default(&mut self) {
+                    self.prevent_default = true;
+                }
+
+                /// Stops propagation of the event to parent elements. It's the default.
+                pub fn stop_propagation(&mut self) {
+                    self.stop_propagation = true;
+                }
+
+                /// Lets the event bubble up to listeners of parent elements.
+                pub fn propagate(&mut self) {
+                    self.stop_propagation = false;
+                }
+
+                /// Promises that the listener never prevents the default action.
+                pub fn passive(&mut self) {
+                    self.options.passive = true;
+                }
+
+                /// Calls the listener in the capture phase.
+                pub fn capture(&mut self) {
+                    self.options.capture = true;
+                }
+
+                /// Removes the listener after the first event.
+                pub fn once(&mut self) {
+                    self.options.once = true;
                 }
             }
 
@@ -166,52 +841,210 @@ macro_rules! impl_action {
                     stringify!($action)
                 }
 
+                #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
                 fn attach(&mut self, element: &Element, mut sender: AppSender<MSG>)
-                    -> EventListenerHandle {
-                    let handler = self.0.take().expect("tried to attach listener twice");
+                    -> ListenerHandle {
+                    let handler = self.handler.take().expect("tried to attach listener twice");
+                    let prevent_default = self.prevent_default;
+                    let stop_propagation = self.stop_propagation;
                     let this = element.clone();
                     let listener = move |event: $type| {
                         debug!("Event handler: {}", stringify!($type));
-                        event.stop_propagation();
+                        if prevent_default {
+                            event.prevent_default();
+                        }
+                        if stop_propagation {
+                            event.stop_propagation();
+                        }
                         let handy_event: $ret = $convert(&this, event);
                         let msg = handler(handy_event);
                         sender.send(msg);
                     };
-                    element.add_event_listener(listener)
+                    ListenerHandle::attach(element, self.options, listener)
+                }
+
+                #[cfg(feature = "testing")]
+                fn simulate(&mut self, data: &Any) -> Option<Simulated<MSG>> {
+                    let data = data.downcast_ref::<$ret>()?.clone();
+                    let msg = match self.handler {
+                        Some(ref handler) => handler(data),
+                        None => return None,
+                    };
+                    if self.options.once {
+                        self.handler = None;
+                    }
+                    Some(Simulated {
+                        msg,
+                        stop_propagation: self.stop_propagation,
+                    })
                 }
             }
         }
     )*};
 }
 
+/// A listener of custom events (like events of web components) with a name
+/// which is not known to `yew`. The handler gets `detail` of the event.
+pub struct CustomListener<T, F> {
+    kind: &'static str,
+    handler: Option<F>,
+    _detail: PhantomData<T>,
+}
+
+impl<T, F> CustomListener<T, F> {
+    /// Creates a listener of events with the `kind` name.
+    pub fn new(kind: &'static str, handler: F) -> Self {
+        CustomListener {
+            kind,
+            handler: Some(handler),
+            _detail: PhantomData,
+        }
+    }
+}
+
+impl<T, F, MSG> Listener<MSG> for CustomListener<T, F>
+where
+    MSG: 'static,
+    T: TryFrom<Value> + 'static,
+    F: Fn(T) -> MSG + 'static,
+{
+    fn kind(&self) -> &'static str {
+        self.kind
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn attach(&mut self, element: &Element, mut sender: AppSender<MSG>) -> ListenerHandle {
+        let handler = self.handler.take().expect("tried to attach listener twice");
+        let kind = self.kind;
+        let listener = move |event: Reference| {
+            debug!("Custom event handler: {}", kind);
+            let detail: Value = js!( return @{event}.detail; );
+            match T::try_from(detail) {
+                Ok(detail) => {
+                    let msg = handler(detail);
+                    sender.send(msg);
+                }
+                Err(_) => {
+                    warn!("can't convert detail of custom event: {}", kind);
+                }
+            }
+        };
+        ListenerHandle::attach_named(element, kind, ListenerOptions::default(), listener)
+    }
+
+    #[cfg(feature = "testing")]
+    fn simulate(&mut self, data: &Any) -> Option<Simulated<MSG>> {
+        let detail = data.downcast_ref::<Value>()?.clone();
+        let detail = T::try_from(detail).ok()?;
+        let handler = self.handler.as_ref()?;
+        Some(Simulated {
+            msg: handler(detail),
+            stop_propagation: false,
+        })
+    }
+}
+
 // Inspired by: http://package.elm-lang.org/packages/elm-lang/html/2.0.0/Html-Events
 impl_action! {
     onclick(event: ClickEvent) -> MouseData => |_, event| { MouseData::from(event) }
     ondoubleclick(event: DoubleClickEvent) -> MouseData => |_, event| { MouseData::from(event) }
+    onmousedown(event: MouseDownEvent) -> MouseData => |_, event| { MouseData::from(event) }
+    onmouseup(event: MouseUpEvent) -> MouseData => |_, event| { MouseData::from(event) }
+    onmousemove(event: MouseMoveEvent) -> MouseData => |_, event| { MouseData::from(event) }
+    onmouseenter(event: MouseEnterEvent) -> MouseData => |_, event| { MouseData::from(event) }
+    onmouseleave(event: MouseLeaveEvent) -> MouseData => |_, event| { MouseData::from(event) }
+    onmouseover(event: MouseOverEvent) -> MouseData => |_, event| { MouseData::from(event) }
+    onmouseout(event: MouseOutEvent) -> MouseData => |_, event| { MouseData::from(event) }
+    oncontextmenu(event: ContextMenuEvent) -> MouseData => |_, event| { MouseData::from(event) }
+    onwheel(event: WheelEvent) -> WheelData => |_, event| { WheelData::from(event) }
+    onscroll(event: ScrollEvent) -> ScrollData => |this: &Element, _| { ScrollData::of(this) }
+    onpointerdown(event: PointerDownEvent) -> PointerData => |_, event| { PointerData::from(event) }
+    onpointerup(event: PointerUpEvent) -> PointerData => |_, event| { PointerData::from(event) }
+    onpointermove(event: PointerMoveEvent) -> PointerData => |_, event| { PointerData::from(event) }
+    onpointerenter(event: PointerEnterEvent) -> PointerData => |_, event| { PointerData::from(event) }
+    onpointerleave(event: PointerLeaveEvent) -> PointerData => |_, event| { PointerData::from(event) }
+    onpointercancel(event: PointerCancelEvent) -> PointerData => |_, event| { PointerData::from(event) }
+    ontouchstart(event: TouchStartEvent) -> TouchData => |_, event| { TouchData::from(event) }
+    ontouchend(event: TouchEndEvent) -> TouchData => |_, event| { TouchData::from(event) }
+    ontouchmove(event: TouchMoveEvent) -> TouchData => |_, event| { TouchData::from(event) }
+    ontouchcancel(event: TouchCancelEvent) -> TouchData => |_, event| { TouchData::from(event) }
     onkeypress(event: KeypressEvent) -> KeyData => |_, event| { KeyData::from(event) }
-    /* TODO Add PR to https://github.com/koute/stdweb
-    onmousedown(event: MouseDownEvent) -> () => |_, _| { () }
-    onmouseup(event: MouseUpEvent) -> () => |_, _| { () }
-    onmouseenter(event: MouseEnterEvent) -> () => |_, _| { () }
-    onmouseleave(event: MouseLeaveEvent) -> () => |_, _| { () }
-    onmouseover(event: MouseOverEvent) -> () => |_, _| { () }
-    onmouseout(event: MouseOutEvent) -> () => |_, _| { () }
-    */
+    onkeydown(event: KeydownEvent) -> KeyData => |_, event| { KeyData::from(event) }
+    onkeyup(event: KeyupEvent) -> KeyData => |_, event| { KeyData::from(event) }
+    onsubmit(event: SubmitEvent) -> () => |_, _| {}
+    onfocus(event: FocusEvent) -> FocusEvent => |_, event| { event }
     onblur(event: BlurEvent) -> BlurEvent => |_, event| {
         use stdweb::web::event::BlurEvent;
         BlurEvent::from(event)
     }
     oninput(event: InputEvent) -> InputData => |this: &Element, _| {
-        use stdweb::web::html_element::InputElement;
-        use stdweb::unstable::TryInto;
-        let input: InputElement = this.clone().try_into().expect("only an InputElement can have an oninput event listener");
-        let value = input.value().into_string().unwrap_or_else(|| "".into());
+        // Works for `input` and `textarea` elements
+        let value: Value = js!( return @{this}.value; );
+        let value = value.into_string().unwrap_or_else(|| "".into());
         InputData { value }
     }
+    onchange(event: ChangeEvent) -> ChangeData => |this: &Element, _| {
+        let value: Value = js!( return @{this}.value; );
+        let value = value.into_string().unwrap_or_else(|| "".into());
+        let checked: Value = js!( return @{this}.checked === true; );
+        let checked = checked.try_into().unwrap_or(false);
+        let selected: Value = js! {
+            var options = @{this}.selectedOptions || [];
+            return Array.prototype.map.call(options, function(option) { return option.value; });
+        };
+        let selected = selected.try_into().unwrap_or_default();
+        let names: Value = js! {
+            var files = @{this}.files || [];
+            return Array.prototype.map.call(files, function(file) { return file.name; });
+        };
+        let names: Vec<String> = names.try_into().unwrap_or_default();
+        let sizes: Value = js! {
+            var files = @{this}.files || [];
+            return Array.prototype.map.call(files, function(file) { return file.size; });
+        };
+        let sizes: Vec<f64> = sizes.try_into().unwrap_or_default();
+        let files = names.into_iter().zip(sizes)
+            .map(|(name, size)| FileData { name, size: size as u64 })
+            .collect();
+        ChangeData { value, checked, selected, files }
+    }
+    onpaste(event: PasteEvent) -> PasteData => |_, event| { PasteData::from(event) }
 }
 
-/// A type representing data from `onclick` and `ondoubleclick` event.
-#[derive(Debug)]
+/// States of modifier keys during an event.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Modifiers {
+    /// The Alt (Option on Mac) key was down.
+    pub alt: bool,
+    /// The Control key was down.
+    pub ctrl: bool,
+    /// The Shift key was down.
+    pub shift: bool,
+    /// The Meta (Command on Mac, Windows on PC) key was down.
+    pub meta: bool,
+}
+
+impl Modifiers {
+    fn of<T: IEvent>(event: &T) -> Self {
+        let event = event.as_ref();
+        let value: Value = js! {
+            var event = @{event};
+            return [event.altKey === true, event.ctrlKey === true,
+                    event.shiftKey === true, event.metaKey === true];
+        };
+        let flags: Vec<bool> = value.try_into().unwrap_or_default();
+        let flag = |idx: usize| flags.get(idx).cloned().unwrap_or(false);
+        Modifiers {
+            alt: flag(0),
+            ctrl: flag(1),
+            shift: flag(2),
+            meta: flag(3),
+        }
+    }
+}
+
+/// A type representing data from mouse events like `onclick` and `onmousemove`.
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct MouseData {
     /// The screenX is a read-only property of the
     /// [MouseEvent](https://developer.mozilla.org/en-US/docs/Web/API/MouseEvent/screenX)
@@ -233,38 +1066,363 @@ pub struct MouseData {
     /// interface which provides the vertical coordinate within
     /// the application's client area at which the event occurred
     pub client_y: f64,
+    /// The button which fired the event as a number from
+    /// [MouseEvent](https://developer.mozilla.org/en-US/docs/Web/API/MouseEvent/button).
+    /// It's `-1` if no button changed its state.
+    pub button: i32,
+    /// Buttons which were down when the event was fired as a bitmask from
+    /// [MouseEvent](https://developer.mozilla.org/en-US/docs/Web/API/MouseEvent/buttons).
+    pub buttons: u16,
+    /// States of modifier keys.
+    pub modifiers: Modifiers,
 }
 
 impl<T: IMouseEvent> From<T> for MouseData {
     fn from(event: T) -> Self {
+        // `button` of `stdweb` panics for pointer events without a button
+        let button: Value = js!( return @{event.as_ref()}.button; );
+        let buttons: Value = js!( return @{event.as_ref()}.buttons; );
         MouseData {
             screen_x: event.screen_x(),
             screen_y: event.screen_y(),
             client_x: event.client_x(),
             client_y: event.client_y(),
+            button: button.try_into().unwrap_or(-1),
+            buttons: buttons.try_into().unwrap_or(0),
+            modifiers: Modifiers::of(&event),
+        }
+    }
+}
+
+/// A type representing data from `onscroll` event.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ScrollData {
+    /// The vertical offset of the content.
+    pub top: f64,
+    /// The horizontal offset of the content.
+    pub left: f64,
+    /// The visible width of the element.
+    pub width: f64,
+    /// The visible height of the element.
+    pub height: f64,
+}
+
+impl ScrollData {
+    fn of(element: &Element) -> Self {
+        let value: Value = js! {
+            var element = @{element};
+            return [element.scrollTop, element.scrollLeft, element.clientWidth, element.clientHeight];
+        };
+        let values: Vec<f64> = value.try_into().unwrap_or_default();
+        let value = |idx: usize| values.get(idx).cloned().unwrap_or(0.0);
+        ScrollData {
+            top: value(0),
+            left: value(1),
+            width: value(2),
+            height: value(3),
+        }
+    }
+}
+
+/// A type representing data from `onwheel` event.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct WheelData {
+    /// Coordinates, buttons and modifiers of the pointing device.
+    pub mouse: MouseData,
+    /// Horizontal scroll amount.
+    pub delta_x: f64,
+    /// Vertical scroll amount.
+    pub delta_y: f64,
+    /// Scroll amount for the z-axis.
+    pub delta_z: f64,
+    /// A unit of deltas from
+    /// [WheelEvent](https://developer.mozilla.org/en-US/docs/Web/API/WheelEvent/deltaMode):
+    /// `0` - pixels, `1` - lines, `2` - pages.
+    pub delta_mode: u32,
+}
+
+impl From<WheelEvent> for WheelData {
+    fn from(event: WheelEvent) -> Self {
+        let deltas: Value = js! {
+            var event = @{event.as_ref()};
+            return [event.deltaX, event.deltaY, event.deltaZ, event.deltaMode];
+        };
+        let deltas: Vec<f64> = deltas.try_into().unwrap_or_default();
+        let delta = |idx: usize| deltas.get(idx).cloned().unwrap_or(0.0);
+        WheelData {
+            delta_x: delta(0),
+            delta_y: delta(1),
+            delta_z: delta(2),
+            delta_mode: delta(3) as u32,
+            mouse: MouseData::from(event),
+        }
+    }
+}
+
+/// A type representing data from pointer events like `onpointerdown`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PointerData {
+    /// Coordinates, buttons and modifiers of the pointer.
+    pub mouse: MouseData,
+    /// A unique identifier of the pointer.
+    pub pointer_id: i32,
+    /// A type of the device: `mouse`, `pen` or `touch`.
+    pub pointer_type: String,
+    /// Normalized pressure of the pointer in the range `0` to `1`.
+    pub pressure: f64,
+    /// Width of the contact geometry of the pointer.
+    pub width: f64,
+    /// Height of the contact geometry of the pointer.
+    pub height: f64,
+    /// The pointer is the primary pointer of its type.
+    pub is_primary: bool,
+}
+
+impl<T: IMouseEvent> From<T> for PointerData {
+    fn from(event: T) -> Self {
+        let values: Value = js! {
+            var event = @{event.as_ref()};
+            return [event.pointerId, event.pressure, event.width, event.height];
+        };
+        let values: Vec<f64> = values.try_into().unwrap_or_default();
+        let value = |idx: usize| values.get(idx).cloned().unwrap_or(0.0);
+        let pointer_type: Value = js!( return @{event.as_ref()}.pointerType; );
+        let is_primary: Value = js!( return @{event.as_ref()}.isPrimary === true; );
+        PointerData {
+            pointer_id: value(0) as i32,
+            pointer_type: pointer_type.into_string().unwrap_or_default(),
+            pressure: value(1),
+            width: value(2),
+            height: value(3),
+            is_primary: is_primary.try_into().unwrap_or(false),
+            mouse: MouseData::from(event),
+        }
+    }
+}
+
+/// A single point of contact with a touch surface.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TouchPoint {
+    /// A unique identifier of the point for the duration of the contact.
+    pub identifier: i32,
+    /// The horizontal coordinate within the client area.
+    pub client_x: f64,
+    /// The vertical coordinate within the client area.
+    pub client_y: f64,
+    /// The horizontal coordinate in screen coordinates.
+    pub screen_x: f64,
+    /// The vertical coordinate in screen coordinates.
+    pub screen_y: f64,
+}
+
+/// A type representing data from touch events like `ontouchstart`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TouchData {
+    /// All current points of contact with the surface.
+    pub touches: Vec<TouchPoint>,
+    /// Points which changed with this event.
+    pub changed_touches: Vec<TouchPoint>,
+    /// States of modifier keys.
+    pub modifiers: Modifiers,
+}
+
+fn touch_points(event: &Reference, list: &str) -> Vec<TouchPoint> {
+    let values: Value = js! {
+        var list = @{event}[@{list}] || [];
+        var values = [];
+        for (var i = 0; i < list.length; i++) {
+            var touch = list[i];
+            values.push(touch.identifier, touch.clientX, touch.clientY, touch.screenX, touch.screenY);
+        }
+        return values;
+    };
+    let values: Vec<f64> = values.try_into().unwrap_or_default();
+    values.chunks(5).filter(|chunk| chunk.len() == 5).map(|chunk| {
+        TouchPoint {
+            identifier: chunk[0] as i32,
+            client_x: chunk[1],
+            client_y: chunk[2],
+            screen_x: chunk[3],
+            screen_y: chunk[4],
+        }
+    }).collect()
+}
+
+impl<T: IEvent> From<T> for TouchData {
+    fn from(event: T) -> Self {
+        TouchData {
+            touches: touch_points(event.as_ref(), "touches"),
+            changed_touches: touch_points(event.as_ref(), "changedTouches"),
+            modifiers: Modifiers::of(&event),
         }
     }
 }
 
 /// A type representing data from `oninput` event.
-#[derive(Debug)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct InputData {
     /// Inserted characters. Contains value from
     /// [InputEvent](https://developer.mozilla.org/en-US/docs/Web/API/InputEvent/data).
     pub value: String,
 }
 
-/// A type representing data from `onkeypress` event.
-#[derive(Debug)]
+/// A type representing data from `onchange` event.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ChangeData {
+    /// A value of an `input`, `textarea` or `select` element.
+    pub value: String,
+    /// Contains `checked` property of a checkbox or a radio button.
+    pub checked: bool,
+    /// Values of selected options of a `select` element (multiple if it has `multiple` attribute).
+    pub selected: Vec<String>,
+    /// Files selected with `<input type="file">`.
+    pub files: Vec<FileData>,
+}
+
+/// A description of a file selected by a user.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileData {
+    /// The name of the file without a path.
+    pub name: String,
+    /// The size of the file in bytes.
+    pub size: u64,
+}
+
+/// A type representing data from `onpaste` event. Use `onpaste.prevent_default=`
+/// to insert the content by the handler, like an image to a message.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PasteData {
+    /// The plain text, empty if there's none.
+    pub text: String,
+    /// The markup, like of a selection copied from a page.
+    pub html: Option<String>,
+    /// Files, like a screenshot or images copied in a file manager.
+    pub files: Vec<ClipboardFile>,
+}
+
+impl PasteData {
+    /// Returns pasted images.
+    pub fn images(&self) -> impl Iterator<Item = &ClipboardFile> {
+        self.files.iter().filter(|file| file.is_image())
+    }
+}
+
+impl From<PasteEvent> for PasteData {
+    fn from(event: PasteEvent) -> Self {
+        let data: Value = js! {
+            var data = @{event.as_ref()}.clipboardData;
+            if (!data) {
+                return null;
+            }
+            var files = [];
+            if (data.items) {
+                for (var i = 0; i < data.items.length; i++) {
+                    var file = data.items[i].kind === "file" ? data.items[i].getAsFile() : null;
+                    if (file) {
+                        files.push(file);
+                    }
+                }
+            } else {
+                files = Array.prototype.slice.call(data.files || []);
+            }
+            return { text: data.getData("text/plain"), html: data.getData("text/html") || null, files: files };
+        };
+        if data == Value::Null {
+            return PasteData::default();
+        }
+        let text: Value = js!( return @{&data}.text; );
+        let html: Value = js!( return @{&data}.html; );
+        let count: Value = js!( return @{&data}.files.length; );
+        let count: u32 = count.try_into().unwrap_or(0);
+        let files = (0..count).map(|index| {
+            let blob: Value = js!( return @{&data}.files[@{index}]; );
+            ClipboardFile::of(blob)
+        }).collect();
+        PasteData {
+            text: text.into_string().unwrap_or_default(),
+            html: html.into_string(),
+            files,
+        }
+    }
+}
+
+/// A file of the clipboard. `ImageSource::Clipboard` makes a thumbnail of an image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClipboardFile {
+    /// The name of the file, browsers name screenshots like `image.png`.
+    pub name: String,
+    /// The MIME type of the file, like `image/png`.
+    pub mime: String,
+    /// The size of the file in bytes.
+    pub size: u64,
+    blob: Option<Value>,
+}
+
+impl ClipboardFile {
+    /// Creates a description of a file without content, like for simulated pastes in tests.
+    pub fn new(name: &str, mime: &str, size: u64) -> Self {
+        ClipboardFile {
+            name: name.to_owned(),
+            mime: mime.to_owned(),
+            size,
+            blob: None,
+        }
+    }
+
+    fn of(blob: Value) -> Self {
+        let name: Value = js!( return @{&blob}.name || ""; );
+        let mime: Value = js!( return @{&blob}.type || ""; );
+        let size: Value = js!( return @{&blob}.size || 0; );
+        let size: f64 = size.try_into().unwrap_or(0.0);
+        ClipboardFile {
+            name: name.into_string().unwrap_or_default(),
+            mime: mime.into_string().unwrap_or_default(),
+            size: size as u64,
+            blob: Some(blob),
+        }
+    }
+
+    /// Returns `true` if the file is an image.
+    pub fn is_image(&self) -> bool {
+        self.mime.starts_with("image/")
+    }
+
+    /// Returns the `Blob` of the file, like to upload it with `FormData`,
+    /// or `None` if it was created without content.
+    #[doc(hidden)]
+    pub fn raw(&self) -> Option<&Value> {
+        self.blob.as_ref()
+    }
+}
+
+/// A type representing data from keyboard events like `onkeypress` and `onkeydown`.
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct KeyData {
     /// Value of a pressed key. Contains key name from
     /// [KeyboardEvent](https://developer.mozilla.org/en-US/docs/Web/API/KeyboardEvent/key).
     pub key: String,
+    /// A physical key on the keyboard from
+    /// [KeyboardEvent](https://developer.mozilla.org/en-US/docs/Web/API/KeyboardEvent/code).
+    pub code: String,
+    /// The key is being held down such that it is automatically repeating.
+    pub repeat: bool,
+    /// States of modifier keys.
+    pub modifiers: Modifiers,
 }
 
 impl<T: IKeyboardEvent> From<T> for KeyData {
     fn from(event: T) -> Self {
-        KeyData { key: event.key() }
+        KeyData {
+            key: event.key(),
+            code: event.code(),
+            repeat: event.repeat(),
+            modifiers: Modifiers {
+                alt: event.alt_key(),
+                ctrl: event.ctrl_key(),
+                shift: event.shift_key(),
+                meta: event.meta_key(),
+            },
+        }
     }
 }
 
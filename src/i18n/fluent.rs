@@ -0,0 +1,673 @@
+//! This module contains a parser and a formatter of the Fluent syntax.
+//!
+//! It supports messages, attributes, terms, comments, multiline patterns,
+//! string and number literals, variables, references of messages and terms,
+//! select expressions with plural categories and the `NUMBER` function.
+
+use std::collections::HashMap;
+use std::fmt;
+use super::plural;
+
+/// A value of an argument of a message.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Arg {
+    /// A string which is selected by variant keys as is.
+    Str(String),
+    /// A number which is selected by exact keys or by plural categories.
+    Number(f64),
+}
+
+impl fmt::Display for Arg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Arg::Str(ref value) => f.write_str(value),
+            Arg::Number(value) if value.fract() == 0.0 && value.abs() < 1e15 => {
+                write!(f, "{}", value as i64)
+            }
+            Arg::Number(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Arg {
+    fn from(value: &'a str) -> Self {
+        Arg::Str(value.to_owned())
+    }
+}
+
+impl From<String> for Arg {
+    fn from(value: String) -> Self {
+        Arg::Str(value)
+    }
+}
+
+impl<'a> From<&'a String> for Arg {
+    fn from(value: &'a String) -> Self {
+        Arg::Str(value.clone())
+    }
+}
+
+macro_rules! number_args {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Arg {
+                fn from(value: $ty) -> Self {
+                    Arg::Number(value as f64)
+                }
+            }
+        )*
+    };
+}
+
+number_args!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+/// Named arguments of a message.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Args(Vec<(String, Arg)>);
+
+impl Args {
+    /// Creates an empty set of arguments.
+    pub fn new() -> Self {
+        Args::default()
+    }
+
+    /// Adds an argument and returns the set.
+    pub fn with<T: Into<Arg>>(mut self, name: &str, value: T) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    /// Adds an argument or replaces the value of an existing one.
+    pub fn set<T: Into<Arg>>(&mut self, name: &str, value: T) {
+        let value = value.into();
+        match self.0.iter_mut().find(|(key, _)| key == name) {
+            Some((_, old)) => *old = value,
+            None => self.0.push((name.to_owned(), value)),
+        }
+    }
+
+    /// Returns the value of an argument.
+    pub fn get(&self, name: &str) -> Option<&Arg> {
+        self.0.iter().find(|(key, _)| key == name).map(|(_, value)| value)
+    }
+}
+
+/// An error in a Fluent source.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    /// The line of the error starting from 1.
+    pub line: usize,
+    /// The description of the error.
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+type Pattern = Vec<Element>;
+
+#[derive(Debug)]
+enum Element {
+    Text(String),
+    Placeable(Expr),
+}
+
+#[derive(Debug)]
+enum Expr {
+    Str(String),
+    Number(f64),
+    Var(String),
+    Message(String, Option<String>),
+    Term(String, Option<String>),
+    Select(Box<Expr>, Vec<Variant>),
+}
+
+#[derive(Debug)]
+struct Variant {
+    key: String,
+    default: bool,
+    value: Pattern,
+}
+
+#[derive(Debug, Default)]
+struct Entry {
+    value: Option<Pattern>,
+    attributes: Vec<(String, Pattern)>,
+}
+
+/// Messages of a single locale. Load it from a Fluent source:
+///
+/// ```rust,ignore
+/// let bundle = Bundle::parse("en", include_str!("../locales/en.ftl"))?;
+/// let text = bundle.format("greeting", &Args::new().with("name", "Ann"));
+/// ```
+pub struct Bundle {
+    locale: String,
+    messages: HashMap<String, Entry>,
+    terms: HashMap<String, Entry>,
+}
+
+impl Bundle {
+    /// Creates an empty bundle of the locale, like `en-US`.
+    pub fn new(locale: &str) -> Self {
+        Bundle {
+            locale: locale.to_owned(),
+            messages: HashMap::new(),
+            terms: HashMap::new(),
+        }
+    }
+
+    /// Creates a bundle of the locale with messages of the source.
+    pub fn parse(locale: &str, source: &str) -> Result<Self, ParseError> {
+        let mut bundle = Bundle::new(locale);
+        bundle.add_source(source)?;
+        Ok(bundle)
+    }
+
+    /// Adds messages of the source. Messages with existing ids replace the old ones.
+    /// Nothing is added if the source has an error.
+    pub fn add_source(&mut self, source: &str) -> Result<(), ParseError> {
+        let entries = Parser::new(source).entries()?;
+        for (id, entry) in entries {
+            if let Some(term) = id.strip_prefix('-') {
+                self.terms.insert(term.to_owned(), entry);
+            } else {
+                self.messages.insert(id, entry);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the locale of the bundle.
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Checks the bundle has the message or the attribute `message.attribute`.
+    pub fn has_message(&self, id: &str) -> bool {
+        self.pattern(&self.messages, id).is_some()
+    }
+
+    /// Formats the message or the attribute `message.attribute` with the arguments.
+    /// Returns `None` if the bundle doesn't have it. Missing arguments and references
+    /// are rendered in braces, like `{$name}`.
+    pub fn format(&self, id: &str, args: &Args) -> Option<String> {
+        self.pattern(&self.messages, id).map(|pattern| {
+            let mut out = String::new();
+            Scope { bundle: self, args, depth: 0 }.pattern(pattern, &mut out);
+            out
+        })
+    }
+
+    fn pattern<'a>(&self, entries: &'a HashMap<String, Entry>, id: &str) -> Option<&'a Pattern> {
+        let mut parts = id.splitn(2, '.');
+        let entry = entries.get(parts.next().unwrap_or(""))?;
+        match parts.next() {
+            Some(attribute) => entry.attributes.iter()
+                .find(|(name, _)| name == attribute)
+                .map(|(_, pattern)| pattern),
+            None => entry.value.as_ref(),
+        }
+    }
+}
+
+/// Nested references deeper than this are considered cyclic.
+const MAX_DEPTH: usize = 16;
+
+struct Scope<'a> {
+    bundle: &'a Bundle,
+    args: &'a Args,
+    depth: usize,
+}
+
+impl<'a> Scope<'a> {
+    fn pattern(&self, pattern: &Pattern, out: &mut String) {
+        for element in pattern {
+            match *element {
+                Element::Text(ref text) => out.push_str(text),
+                Element::Placeable(ref expr) => self.expr(expr, out),
+            }
+        }
+    }
+
+    fn expr(&self, expr: &Expr, out: &mut String) {
+        match *expr {
+            Expr::Select(ref selector, ref variants) => {
+                let variant = self.select(selector, variants);
+                self.pattern(&variant.value, out);
+            }
+            ref expr => out.push_str(&self.value(expr).to_string()),
+        }
+    }
+
+    fn value(&self, expr: &Expr) -> Arg {
+        match *expr {
+            Expr::Str(ref value) => Arg::Str(value.clone()),
+            Expr::Number(value) => Arg::Number(value),
+            Expr::Var(ref name) => match self.args.get(name) {
+                Some(value) => value.clone(),
+                None => {
+                    warn!("the argument ${} of a message isn't set", name);
+                    Arg::Str(format!("{{${}}}", name))
+                }
+            },
+            Expr::Message(ref id, ref attribute) => {
+                self.reference(&self.bundle.messages, id, attribute, "")
+            }
+            Expr::Term(ref id, ref attribute) => {
+                self.reference(&self.bundle.terms, id, attribute, "-")
+            }
+            Expr::Select(..) => {
+                let mut out = String::new();
+                self.expr(expr, &mut out);
+                Arg::Str(out)
+            }
+        }
+    }
+
+    fn reference(&self, entries: &HashMap<String, Entry>, id: &str,
+                 attribute: &Option<String>, prefix: &str) -> Arg {
+        let id = match *attribute {
+            Some(ref attribute) => format!("{}.{}", id, attribute),
+            None => id.to_owned(),
+        };
+        match self.bundle.pattern(entries, &id) {
+            Some(pattern) if self.depth < MAX_DEPTH => {
+                let scope = Scope { depth: self.depth + 1, ..*self };
+                let mut out = String::new();
+                scope.pattern(pattern, &mut out);
+                Arg::Str(out)
+            }
+            Some(_) => {
+                warn!("the message {}{} has cyclic references", prefix, id);
+                Arg::Str(format!("{{{}{}}}", prefix, id))
+            }
+            None => {
+                warn!("the message {}{} isn't found", prefix, id);
+                Arg::Str(format!("{{{}{}}}", prefix, id))
+            }
+        }
+    }
+
+    fn select<'v>(&self, selector: &Expr, variants: &'v [Variant]) -> &'v Variant {
+        let found = match self.value(selector) {
+            Arg::Number(number) => {
+                let category = plural::category(&self.bundle.locale, number);
+                variants.iter()
+                    .find(|variant| variant.key.parse::<f64>().ok() == Some(number))
+                    .or_else(|| variants.iter().find(|variant| variant.key == category))
+            }
+            Arg::Str(value) => variants.iter().find(|variant| variant.key == value),
+        };
+        found.or_else(|| variants.iter().find(|variant| variant.default))
+            .expect("select expressions are parsed with a default variant")
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Context {
+    Entry,
+    Variant,
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(source: &str) -> Self {
+        Parser { chars: source.chars().collect(), pos: 0 }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).cloned()
+    }
+
+    fn peek_at(&self, pos: usize) -> Option<char> {
+        self.chars.get(pos).cloned()
+    }
+
+    fn error<T>(&self, message: &str) -> Result<T, ParseError> {
+        let line = self.chars[..self.pos.min(self.chars.len())].iter().filter(|&&c| c == '\n').count();
+        Err(ParseError { line: line + 1, message: message.to_owned() })
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            self.error(&format!("expected `{}`", expected))
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek() == Some(' ') {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().map_or(false, char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_line(&mut self) {
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            if c == '\n' {
+                break;
+            }
+        }
+    }
+
+    fn entries(mut self) -> Result<Vec<(String, Entry)>, ParseError> {
+        let mut entries = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None => break,
+                Some('#') => self.skip_line(),
+                Some('-') => {
+                    self.pos += 1;
+                    let id = format!("-{}", self.identifier()?);
+                    let entry = self.entry()?;
+                    if entry.value.is_none() {
+                        return self.error("a term needs a value");
+                    }
+                    entries.push((id, entry));
+                }
+                Some(c) if c.is_alphabetic() => {
+                    let id = self.identifier()?;
+                    entries.push((id, self.entry()?));
+                }
+                Some(_) => return self.error("expected a message"),
+            }
+        }
+        Ok(entries)
+    }
+
+    fn identifier(&mut self) -> Result<String, ParseError> {
+        let start = self.pos;
+        match self.peek() {
+            Some(c) if c.is_alphabetic() => self.pos += 1,
+            _ => return self.error("expected an identifier"),
+        }
+        while self.peek().map_or(false, |c| c.is_alphanumeric() || c == '-' || c == '_') {
+            self.pos += 1;
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+
+    fn entry(&mut self) -> Result<Entry, ParseError> {
+        self.skip_spaces();
+        self.expect('=')?;
+        let mut entry = Entry::default();
+        let value = self.pattern(Context::Entry)?;
+        if !value.is_empty() {
+            entry.value = Some(value);
+        }
+        loop {
+            let start = self.pos;
+            self.skip_whitespace();
+            if self.peek() == Some('.') && start != self.pos {
+                self.pos += 1;
+                let name = self.identifier()?;
+                self.skip_spaces();
+                self.expect('=')?;
+                let value = self.pattern(Context::Entry)?;
+                if value.is_empty() {
+                    return self.error("an attribute needs a value");
+                }
+                entry.attributes.push((name, value));
+            } else {
+                self.pos = start;
+                break;
+            }
+        }
+        if entry.value.is_none() && entry.attributes.is_empty() {
+            return self.error("a message needs a value or an attribute");
+        }
+        Ok(entry)
+    }
+
+    /// Returns the number of line breaks before an indented continuation of a pattern,
+    /// or `None` if the pattern ends at the line break.
+    fn continuation(&self, context: Context) -> Option<usize> {
+        let mut pos = self.pos;
+        let mut breaks = 0;
+        loop {
+            if self.peek_at(pos) == Some('\r') {
+                pos += 1;
+            }
+            if self.peek_at(pos) != Some('\n') {
+                return None;
+            }
+            pos += 1;
+            breaks += 1;
+            let start = pos;
+            while self.peek_at(pos) == Some(' ') {
+                pos += 1;
+            }
+            match self.peek_at(pos) {
+                Some('\r') | Some('\n') => continue,
+                None => return None,
+                Some(_) if pos == start => return None,
+                Some('.') if context == Context::Entry => return None,
+                Some('[') | Some('*') | Some('}') if context == Context::Variant => return None,
+                Some(_) => {
+                    return Some(breaks);
+                }
+            }
+        }
+    }
+
+    fn pattern(&mut self, context: Context) -> Result<Pattern, ParseError> {
+        let mut elements = Vec::new();
+        let mut text = String::new();
+        let mut first_line = true;
+        self.skip_spaces();
+        loop {
+            match self.peek() {
+                None => break,
+                Some('{') => {
+                    if !text.is_empty() {
+                        elements.push(Element::Text(text));
+                        text = String::new();
+                    }
+                    elements.push(Element::Placeable(self.placeable()?));
+                }
+                Some('}') if context == Context::Variant => break,
+                Some('}') => return self.error("unbalanced `}` in a pattern"),
+                Some('\r') | Some('\n') => match self.continuation(context) {
+                    Some(breaks) => {
+                        // A value which starts on the next line has no leading break
+                        if !(first_line && elements.is_empty() && text.is_empty()) {
+                            let trimmed = text.trim_end().len();
+                            text.truncate(trimmed);
+                            for _ in 0..breaks {
+                                text.push('\n');
+                            }
+                        }
+                        self.skip_whitespace();
+                        first_line = false;
+                    }
+                    None => break,
+                },
+                Some(c) => {
+                    text.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+        let trimmed = text.trim_end().len();
+        text.truncate(trimmed);
+        if !text.is_empty() {
+            elements.push(Element::Text(text));
+        }
+        Ok(elements)
+    }
+
+    fn placeable(&mut self) -> Result<Expr, ParseError> {
+        self.expect('{')?;
+        self.skip_whitespace();
+        let expr = self.inline_expr()?;
+        self.skip_whitespace();
+        let expr = if self.peek() == Some('-') && self.peek_at(self.pos + 1) == Some('>') {
+            self.pos += 2;
+            if let Expr::Message(..) = expr {
+                return self.error("messages can't be selectors");
+            }
+            let variants = self.variants()?;
+            Expr::Select(Box::new(expr), variants)
+        } else {
+            expr
+        };
+        self.skip_whitespace();
+        self.expect('}')?;
+        Ok(expr)
+    }
+
+    fn inline_expr(&mut self) -> Result<Expr, ParseError> {
+        match self.peek() {
+            Some('"') => self.string(),
+            Some('$') => {
+                self.pos += 1;
+                Ok(Expr::Var(self.identifier()?))
+            }
+            Some(c) if c.is_ascii_digit() => self.number(),
+            Some('-') if self.peek_at(self.pos + 1).map_or(false, |c| c.is_ascii_digit()) => self.number(),
+            Some('-') => {
+                self.pos += 1;
+                let id = self.identifier()?;
+                let attribute = self.attribute()?;
+                if self.peek() == Some('(') {
+                    return self.error("arguments of terms aren't supported");
+                }
+                Ok(Expr::Term(id, attribute))
+            }
+            Some('{') => self.placeable(),
+            Some(c) if c.is_alphabetic() => {
+                let id = self.identifier()?;
+                if self.peek() == Some('(') {
+                    return self.function(&id);
+                }
+                let attribute = self.attribute()?;
+                Ok(Expr::Message(id, attribute))
+            }
+            _ => self.error("expected an expression"),
+        }
+    }
+
+    fn attribute(&mut self) -> Result<Option<String>, ParseError> {
+        if self.peek() == Some('.') {
+            self.pos += 1;
+            self.identifier().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Parses a call of `NUMBER` which passes its first argument through.
+    /// Named options of the call are skipped.
+    fn function(&mut self, name: &str) -> Result<Expr, ParseError> {
+        if name != "NUMBER" {
+            return self.error(&format!("the function {} isn't supported", name));
+        }
+        self.expect('(')?;
+        self.skip_whitespace();
+        let expr = self.inline_expr()?;
+        while let Some(c) = self.peek() {
+            if c == ')' {
+                break;
+            }
+            self.pos += 1;
+        }
+        self.expect(')')?;
+        Ok(expr)
+    }
+
+    fn string(&mut self) -> Result<Expr, ParseError> {
+        self.expect('"')?;
+        let mut value = String::new();
+        loop {
+            match self.peek() {
+                Some('"') => {
+                    self.pos += 1;
+                    return Ok(Expr::Str(value));
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some('"') => value.push('"'),
+                        Some('\\') => value.push('\\'),
+                        Some('u') => {
+                            let digits: String = self.chars.iter().skip(self.pos + 1).take(4).collect();
+                            match u32::from_str_radix(&digits, 16).ok().and_then(::std::char::from_u32) {
+                                Some(c) if digits.len() == 4 => value.push(c),
+                                _ => return self.error("invalid unicode escape"),
+                            }
+                            self.pos += 4;
+                        }
+                        _ => return self.error("invalid escape in a string"),
+                    }
+                    self.pos += 1;
+                }
+                Some('\n') | None => return self.error("unterminated string"),
+                Some(c) => {
+                    value.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Expr, ParseError> {
+        let start = self.pos;
+        if self.peek() == Some('-') {
+            self.pos += 1;
+        }
+        while self.peek().map_or(false, |c| c.is_ascii_digit() || c == '.') {
+            self.pos += 1;
+        }
+        let literal: String = self.chars[start..self.pos].iter().collect();
+        match literal.parse() {
+            Ok(number) => Ok(Expr::Number(number)),
+            Err(_) => self.error("invalid number"),
+        }
+    }
+
+    fn variants(&mut self) -> Result<Vec<Variant>, ParseError> {
+        let mut variants = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let default = match self.peek() {
+                Some('}') => break,
+                Some('*') => {
+                    self.pos += 1;
+                    true
+                }
+                Some('[') => false,
+                _ => return self.error("expected a variant"),
+            };
+            self.expect('[')?;
+            let start = self.pos;
+            while self.peek().map_or(false, |c| c != ']' && c != '\n') {
+                self.pos += 1;
+            }
+            let key: String = self.chars[start..self.pos].iter().collect();
+            self.expect(']')?;
+            let value = self.pattern(Context::Variant)?;
+            variants.push(Variant { key: key.trim().to_owned(), default, value });
+        }
+        if variants.iter().filter(|variant| variant.default).count() != 1 {
+            return self.error("a select expression needs one default variant `*[...]`");
+        }
+        Ok(variants)
+    }
+}
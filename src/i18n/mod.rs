@@ -0,0 +1,298 @@
+//! This module contains localization of apps with
+//! [Fluent](https://projectfluent.org/) bundles.
+//!
+//! Bundles of locales are added to the context of the thread and views
+//! look messages up in the current locale with the `t!` macro:
+//!
+//! ```rust,ignore
+//! i18n::add_bundle(Bundle::parse("en", include_str!("../locales/en.ftl")).unwrap());
+//! i18n::add_bundle(Bundle::parse("de", include_str!("../locales/de.ftl")).unwrap());
+//! i18n::set_locale("de");
+//!
+//! html! {
+//!     <p>{ t!("unread-emails", count = self.unread) }</p>
+//! }
+//! ```
+//!
+//! Components which listen of the locale with `LocaleService` are
+//! rendered again when it's changed.
+
+mod fluent;
+pub mod plural;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use stdweb::Value;
+use stdweb::unstable::TryInto;
+use html::AppSender;
+use services::Task;
+use services::fetch::{FetchHandle, FetchService, Request, Response};
+use format::{Nothing, Restorable};
+
+pub use self::fluent::{Arg, Args, Bundle, ParseError};
+
+/// Looks a message up in the current locale. Arguments are passed by names:
+///
+/// ```rust,ignore
+/// t!("greeting", name = &self.user, count = self.unread)
+/// ```
+///
+/// The id itself is returned if no bundle has the message.
+#[macro_export]
+macro_rules! t {
+    ($id:expr) => {
+        $crate::i18n::translate($id, &$crate::i18n::Args::new())
+    };
+    ($id:expr, $($name:ident = $value:expr),+ $(,)*) => {
+        $crate::i18n::translate($id, &$crate::i18n::Args::new()$(.with(stringify!($name), $value))+)
+    };
+}
+
+type Listener = Rc<Fn(&str)>;
+
+struct Localizer {
+    bundles: Vec<Rc<Bundle>>,
+    locale: String,
+    fallback: Option<String>,
+    listeners: Vec<(usize, Listener)>,
+    next_listener: usize,
+}
+
+thread_local! {
+    static LOCALIZER: RefCell<Localizer> = RefCell::new(Localizer {
+        bundles: Vec::new(),
+        locale: "en".to_owned(),
+        fallback: None,
+        listeners: Vec::new(),
+        next_listener: 0,
+    });
+}
+
+fn language(locale: &str) -> String {
+    locale.split(['-', '_']).next().unwrap_or("").to_lowercase()
+}
+
+impl Localizer {
+    /// Returns bundles to look messages up in: the exact locale, the same
+    /// language and then the fallback locale.
+    fn chain(&self) -> Vec<Rc<Bundle>> {
+        let mut requested = vec![self.locale.clone()];
+        requested.extend(self.fallback.clone());
+        let mut chain: Vec<Rc<Bundle>> = Vec::new();
+        for locale in requested {
+            let exact = self.bundles.iter()
+                .filter(|bundle| bundle.locale().eq_ignore_ascii_case(&locale));
+            let similar = self.bundles.iter()
+                .filter(|bundle| language(bundle.locale()) == language(&locale));
+            for bundle in exact.chain(similar) {
+                if !chain.iter().any(|added| Rc::ptr_eq(added, bundle)) {
+                    chain.push(bundle.clone());
+                }
+            }
+        }
+        chain
+    }
+}
+
+fn notify() {
+    let (locale, listeners) = LOCALIZER.with(|localizer| {
+        let localizer = localizer.borrow();
+        let listeners: Vec<_> = localizer.listeners.iter()
+            .map(|(_, listener)| listener.clone())
+            .collect();
+        (localizer.locale.clone(), listeners)
+    });
+    for listener in listeners {
+        listener(&locale);
+    }
+}
+
+/// Adds the bundle to the context. It replaces a bundle of the same locale
+/// and listeners are notified to render messages again.
+pub fn add_bundle(bundle: Bundle) {
+    LOCALIZER.with(|localizer| {
+        let mut localizer = localizer.borrow_mut();
+        localizer.bundles.retain(|old| old.locale() != bundle.locale());
+        localizer.bundles.push(Rc::new(bundle));
+    });
+    notify();
+}
+
+/// Returns locales of added bundles.
+pub fn locales() -> Vec<String> {
+    LOCALIZER.with(|localizer| {
+        localizer.borrow().bundles.iter().map(|bundle| bundle.locale().to_owned()).collect()
+    })
+}
+
+/// Returns the current locale. It's `en` by default.
+pub fn locale() -> String {
+    LOCALIZER.with(|localizer| localizer.borrow().locale.clone())
+}
+
+/// Changes the current locale and notifies listeners if it's changed.
+pub fn set_locale(locale: &str) {
+    let changed = LOCALIZER.with(|localizer| {
+        let mut localizer = localizer.borrow_mut();
+        if localizer.locale == locale {
+            false
+        } else {
+            localizer.locale = locale.to_owned();
+            true
+        }
+    });
+    if changed {
+        notify();
+    }
+}
+
+/// Sets a locale to look messages up in when the current one doesn't have them.
+pub fn set_fallback(locale: &str) {
+    LOCALIZER.with(|localizer| localizer.borrow_mut().fallback = Some(locale.to_owned()));
+}
+
+/// Calls the function with another current locale and restores the previous one
+/// without notifications. Use it to render a page for a request on a server.
+pub fn with_locale<F, T>(locale: &str, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let previous = LOCALIZER.with(|localizer| {
+        ::std::mem::replace(&mut localizer.borrow_mut().locale, locale.to_owned())
+    });
+    let result = f();
+    LOCALIZER.with(|localizer| localizer.borrow_mut().locale = previous);
+    result
+}
+
+/// Returns the first locale of added bundles which matches requested ones,
+/// exactly or by the language.
+pub fn negotiate<S: AsRef<str>>(requested: &[S]) -> Option<String> {
+    let available = locales();
+    let exact = requested.iter().filter_map(|locale| {
+        available.iter().find(|available| available.eq_ignore_ascii_case(locale.as_ref()))
+    }).next();
+    let similar = || requested.iter().filter_map(|locale| {
+        available.iter().find(|available| language(available) == language(locale.as_ref()))
+    }).next();
+    exact.or_else(similar).cloned()
+}
+
+/// Returns locales which are preferred by the user in a browser.
+pub fn browser_locales() -> Vec<String> {
+    let locales: Value = js! {
+        return Array.prototype.slice.call(navigator.languages || [navigator.language]);
+    };
+    locales.try_into().unwrap_or_default()
+}
+
+/// Returns the message or the attribute `message.attribute` formatted in the
+/// current locale. The id is returned if no bundle has it.
+pub fn translate(id: &str, args: &Args) -> String {
+    let chain = LOCALIZER.with(|localizer| localizer.borrow().chain());
+    match chain.iter().filter_map(|bundle| bundle.format(id, args)).next() {
+        Some(message) => message,
+        None => {
+            warn!("the message {} isn't found in the locale {}", id, locale());
+            id.to_owned()
+        }
+    }
+}
+
+/// Checks a bundle of the current locale or the fallback has the message.
+pub fn has_message(id: &str) -> bool {
+    let chain = LOCALIZER.with(|localizer| localizer.borrow().chain());
+    chain.iter().any(|bundle| bundle.has_message(id))
+}
+
+/// A handle to stop listening of the locale.
+pub struct LocaleHandle(Option<usize>);
+
+impl Task for LocaleHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let id = self.0.take().expect("tried to cancel locale listener twice");
+        LOCALIZER.with(|localizer| {
+            localizer.borrow_mut().listeners.retain(|&(listener, _)| listener != id);
+        });
+    }
+}
+
+impl Drop for LocaleHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+/// A service to switch the locale at runtime, to load bundles
+/// and to listen of changes.
+pub struct LocaleService<MSG> {
+    sender: AppSender<MSG>,
+}
+
+impl<MSG: 'static> LocaleService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self { sender }
+    }
+
+    /// Sends a message from the `converter` with the locale every time
+    /// it's changed or bundles are added. Return `true` from `update` to
+    /// render messages again.
+    pub fn listen<F>(&mut self, converter: F) -> LocaleHandle
+    where
+        F: Fn(String) -> MSG + 'static,
+    {
+        let tx = self.sender.clone();
+        let listener = move |locale: &str| {
+            let msg = converter(locale.to_owned());
+            tx.clone().send(msg);
+        };
+        let id = LOCALIZER.with(|localizer| {
+            let mut localizer = localizer.borrow_mut();
+            let id = localizer.next_listener;
+            localizer.next_listener += 1;
+            localizer.listeners.push((id, Rc::new(listener)));
+            id
+        });
+        LocaleHandle(Some(id))
+    }
+
+    /// Returns the current locale.
+    pub fn locale(&self) -> String {
+        locale()
+    }
+
+    /// Changes the current locale for all components.
+    pub fn set_locale(&mut self, locale: &str) {
+        set_locale(locale);
+    }
+
+    /// Fetches a Fluent source from the `url` and adds it as a bundle of the `locale`.
+    /// The `converter` gets an error if the request fails or the source is invalid.
+    pub fn load<F>(&mut self, locale: &str, url: &str, converter: F) -> FetchHandle
+    where
+        F: Fn(Result<(), String>) -> MSG + 'static,
+    {
+        let request = Request::get(url).body(Nothing).expect("can't build a request of a bundle");
+        let locale = locale.to_owned();
+        let url = url.to_owned();
+        FetchService::new(self.sender.clone()).fetch(request, move |response: Response<Restorable>| {
+            let (meta, body) = response.into_parts();
+            let result = match body {
+                Ok(ref source) if meta.status.is_success() => {
+                    Bundle::parse(&locale, source)
+                        .map(add_bundle)
+                        .map_err(|err| format!("{}: {}", url, err))
+                }
+                _ => Err(format!("can't load {}: {}", url, meta.status)),
+            };
+            converter(result)
+        })
+    }
+}
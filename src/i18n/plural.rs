@@ -0,0 +1,83 @@
+//! This module contains cardinal plural rules of CLDR for common languages.
+
+/// Returns the plural category of the number in the locale:
+/// `zero`, `one`, `two`, `few`, `many` or `other`.
+/// Languages without known rules use rules of English.
+pub fn category(locale: &str, n: f64) -> &'static str {
+    let language = locale.split(['-', '_']).next().unwrap_or("").to_lowercase();
+    let n = n.abs();
+    // Integer digits and a flag of visible fraction digits
+    let i = n.trunc() as u64;
+    let fraction = n.fract() != 0.0;
+    match language.as_str() {
+        "ja" | "zh" | "ko" | "vi" | "th" | "id" | "ms" | "lo" | "my" | "km" => "other",
+        "fr" | "hy" | "kab" | "ff" => if i <= 1 { "one" } else { "other" },
+        // Portugal uses rules of English
+        "pt" if locale.to_lowercase().replace('_', "-") != "pt-pt" => {
+            if i <= 1 && !fraction { "one" } else { "other" }
+        }
+        "es" => if n == 1.0 { "one" } else { "other" },
+        "ru" | "uk" | "be" => {
+            if fraction {
+                "other"
+            } else if i % 10 == 1 && i % 100 != 11 {
+                "one"
+            } else if (2..5).contains(&(i % 10)) && !(12..15).contains(&(i % 100)) {
+                "few"
+            } else {
+                "many"
+            }
+        }
+        "pl" => {
+            if fraction {
+                "other"
+            } else if i == 1 {
+                "one"
+            } else if (2..5).contains(&(i % 10)) && !(12..15).contains(&(i % 100)) {
+                "few"
+            } else {
+                "many"
+            }
+        }
+        "cs" | "sk" => {
+            if fraction {
+                "many"
+            } else if i == 1 {
+                "one"
+            } else if (2..5).contains(&i) {
+                "few"
+            } else {
+                "other"
+            }
+        }
+        "ar" => {
+            if fraction {
+                "other"
+            } else if i == 0 {
+                "zero"
+            } else if i == 1 {
+                "one"
+            } else if i == 2 {
+                "two"
+            } else if (3..11).contains(&(i % 100)) {
+                "few"
+            } else if (11..100).contains(&(i % 100)) {
+                "many"
+            } else {
+                "other"
+            }
+        }
+        "he" => {
+            if fraction {
+                "other"
+            } else if i == 1 {
+                "one"
+            } else if i == 2 {
+                "two"
+            } else {
+                "other"
+            }
+        }
+        _ => if i == 1 && !fraction { "one" } else { "other" },
+    }
+}
@@ -49,19 +49,116 @@
     missing_docs,
 )]
 #![recursion_limit="256"]
+// Native builds only render to strings and test trees, the parts which
+// render to a DOM are compiled for browsers and are checked there.
+#![cfg_attr(not(any(target_arch = "wasm32", target_os = "emscripten")), allow(dead_code))]
 
 extern crate http;
+#[macro_use]
 extern crate serde;
 extern crate serde_json;
+#[cfg(feature = "log")]
+extern crate log;
+#[cfg(feature = "tracing")]
+extern crate tracing;
 #[macro_use]
 extern crate stdweb;
+extern crate yew_derive;
+#[cfg(feature = "web_sys")]
+extern crate wasm_bindgen;
+#[cfg(feature = "web_sys")]
+extern crate web_sys;
 
 #[macro_use]
 pub mod macros;
+#[doc(hidden)]
+pub mod spec;
 pub mod html;
+pub mod classes;
+pub mod style;
+pub mod host;
+pub mod css;
+pub mod theme;
+pub mod toast;
+pub mod progress;
+pub mod entity;
+pub mod modal;
+pub mod query;
+pub mod room;
+pub mod auth;
+pub mod oauth;
+pub mod flip;
+pub mod dnd;
+pub mod callback;
+pub mod component;
+pub mod properties;
+pub mod registry;
+pub mod components;
 pub mod services;
 pub mod format;
 pub mod virtual_dom;
+pub mod events;
+pub mod suspense;
+pub mod future;
+pub mod command;
+pub mod subscription;
+pub mod middleware;
+pub mod time_travel;
+pub mod undo;
+pub mod panic_hook;
+pub mod errors;
+pub mod analytics;
+pub mod config;
+#[macro_use]
+pub mod settings;
+mod trace;
+pub mod form;
+pub mod focus;
+pub mod aria;
+pub mod date;
+pub mod rich_text;
+pub mod mask;
+pub mod position;
+pub mod direction;
+pub mod custom_element;
+#[macro_use]
+pub mod router;
+pub mod url;
+pub mod prefetch;
+#[macro_use]
+pub mod assets;
+#[cfg(feature = "markdown")]
+pub mod markdown;
+#[cfg(feature = "sanitize")]
+pub mod sanitize;
+#[cfg(feature = "ssr")]
+pub mod ssr;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "devtools")]
+pub mod devtools;
+#[cfg(feature = "profiler")]
+pub mod profiler;
+#[cfg(feature = "strict")]
+pub mod strict;
+#[cfg(feature = "hot_reload")]
+pub mod hot_reload;
+#[cfg(feature = "csp")]
+pub mod csp;
+#[cfg(feature = "collab")]
+pub mod collab;
+#[cfg(feature = "a11y")]
+pub mod a11y;
+#[cfg(feature = "i18n")]
+#[macro_use]
+pub mod i18n;
+#[cfg(feature = "typescript")]
+#[macro_use]
+pub mod typescript;
+#[cfg(feature = "log")]
+pub mod logger;
+
+pub use yew_derive::{Properties, Routable};
 
 /// Initializes yew framework. It should be called first.
 /// No it actually initializes `stdweb` dependency only, but later it could
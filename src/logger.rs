@@ -0,0 +1,65 @@
+//! This module contains a logger of the `log` facade which writes to
+//! the browser's console. It's compiled with the `log` feature.
+//!
+//! ```rust,ignore
+//! fn main() {
+//!     yew::initialize();
+//!     logger::init(LevelFilter::Info).expect("logger installed twice");
+//!     info!("the app started");
+//!     // ...
+//! }
+//! ```
+//!
+//! Warnings and debug messages of the framework are logged with the `yew` target.
+
+use std::fmt;
+use log::{self, Log, Metadata, Record, LevelFilter, SetLoggerError};
+
+pub use log::Level;
+
+/// A logger which writes records to the console with methods of their levels.
+pub struct ConsoleLogger;
+
+static LOGGER: ConsoleLogger = ConsoleLogger;
+
+impl Log for ConsoleLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let message = format!("[{}] {}", record.target(), record.args());
+        match record.level() {
+            Level::Error => {
+                js! { @(no_return) console.error(@{message}); }
+            }
+            Level::Warn => {
+                js! { @(no_return) console.warn(@{message}); }
+            }
+            Level::Info => {
+                js! { @(no_return) console.info(@{message}); }
+            }
+            Level::Debug | Level::Trace => {
+                js! { @(no_return) console.debug(@{message}); }
+            }
+        }
+    }
+
+    fn flush(&self) {
+    }
+}
+
+/// Installs the console logger and sets the maximal level of records.
+pub fn init(level: LevelFilter) -> Result<(), SetLoggerError> {
+    log::set_logger(&LOGGER)?;
+    log::set_max_level(level);
+    Ok(())
+}
+
+#[doc(hidden)]
+pub fn __log(level: Level, args: fmt::Arguments) {
+    log::log!(target: "yew", level, "{}", args);
+}
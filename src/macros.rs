@@ -1,16 +1,161 @@
 //! This module contains macros which implements `html!` macro
 //! and JSX-like templates.
 
-use virtual_dom::{VTag, VNode, Listener};
+use std::borrow::Borrow;
+use std::ops::{Deref, DerefMut};
+use virtual_dom::{VTag, VText, VNode, Listener, NodeRef};
+use classes::Classes;
+use spec;
+use host::Spread;
+use stdweb::Value;
+
+#[cfg(not(feature = "log"))]
+#[macro_export]
+macro_rules! debug {
+    ($($e:expr),*) => {
+        if cfg!(debug) {
+            println!($($e,)*);
+        }
+    };
+}
+
+#[cfg(not(feature = "log"))]
+#[macro_export]
+macro_rules! warn {
+    ($($e:expr),*) => {
+        eprintln!($($e,)*);
+    };
+}
+
+// Diagnostics of the framework go to the `log` facade with the `log` feature
+#[cfg(feature = "log")]
+#[macro_export]
+macro_rules! debug {
+    ($($e:expr),*) => {
+        $crate::logger::__log($crate::logger::Level::Debug, format_args!($($e,)*));
+    };
+}
+
+#[cfg(feature = "log")]
+#[macro_export]
+macro_rules! warn {
+    ($($e:expr),*) => {
+        $crate::logger::__log($crate::logger::Level::Warn, format_args!($($e,)*));
+    };
+}
 
 #[macro_export]
 macro_rules! html_impl {
+    // PATTERN: <Component: property=value, />
+    // Unknown properties and values of wrong types are compile-time errors,
+    // as well as missing required properties of structures which derive `Properties`.
+    ($stack:ident (< $comp:ty : $($tail:tt)*)) => {
+        let (mut props, link) = $crate::virtual_dom::VComp::lazy::<$comp>();
+        let probe = $crate::properties::Probe::<<$comp as $crate::component::Component>::Properties>::new();
+        let fields = {
+            #[allow(unused_imports)]
+            use $crate::properties::{Checked, Unchecked};
+            (&probe).fields()
+        };
+        let set = $crate::properties::Nil;
+        html_impl! { @comp $stack ($comp) props link (probe fields set) ($($tail)*) }
+    };
+    (@comp $stack:ident ($comp:ty) $props:ident $link:ident ($probe:ident $fields:ident $set:ident)
+        ($attr:ident = $val:expr, $($tail:tt)*)) => {
+        $props.$attr = $crate::component::Transformer::transform(&$link, $val);
+        let $set = $crate::properties::set($set, &$fields, |fields| &fields.$attr);
+        html_impl! { @comp $stack ($comp) $props $link ($probe $fields $set) ($($tail)*) }
+    };
+    (@comp $stack:ident ($comp:ty) $props:ident $link:ident ($probe:ident $fields:ident $set:ident)
+        (/ > $($tail:tt)*)) => {
+        {
+            #[allow(unused_imports)]
+            use $crate::properties::{Checked, Unchecked};
+            (&$probe).verify($set);
+        }
+        let vcomp = $crate::virtual_dom::VComp::new::<$comp>($props, $link);
+        $crate::macros::add_child(&mut $stack, $crate::virtual_dom::VNode::from(vcomp));
+        html_impl! { $stack ($($tail)*) }
+    };
+    // PATTERN: <Component: property=value,> children </Component>
+    // Nodes until the closing tag are set to the `children` property.
+    (@comp $stack:ident ($comp:ty) $props:ident $link:ident ($probe:ident $fields:ident $set:ident)
+        (> $($tail:tt)*)) => {
+        let $set = $crate::properties::set($set, &$fields, |fields| &fields.children);
+        {
+            #[allow(unused_imports)]
+            use $crate::properties::{Checked, Unchecked};
+            (&$probe).verify($set);
+        }
+        $crate::macros::open_component(&mut $stack, stringify!($comp), move |nodes| {
+            $props.children = $crate::component::Transformer::transform(&$link, nodes);
+            let vcomp = $crate::virtual_dom::VComp::new::<$comp>($props, $link);
+            $crate::virtual_dom::VNode::from(vcomp)
+        });
+        html_impl! { $stack ($($tail)*) }
+    };
     // Start of openging tag
     ($stack:ident (< $starttag:ident $($tail:tt)*)) => {
-        let node = $crate::virtual_dom::VTag::new(stringify!($starttag));
+        html_impl! { @tag $stack ($starttag) ($($tail)*) }
+    };
+    // Tags of custom elements contain dashes: <my-element>
+    (@tag $stack:ident ($($name:ident)-+) (- $part:ident $($tail:tt)*)) => {
+        html_impl! { @tag $stack ($($name)-+ - $part) ($($tail)*) }
+    };
+    (@tag $stack:ident ($($name:ident)-+) ($($tail:tt)*)) => {
+        html_impl! { @check_tag ($($name)-+) ($($tail)*) }
+        let tag = concat!($(stringify!($name), "-"),+);
+        let node = $crate::virtual_dom::VTag::new(&tag[..tag.len() - 1]);
         $stack.push(node);
         html_impl! { $stack ($($tail)*) }
     };
+    // Names of elements and their attributes are checked at compile time.
+    // Custom elements have dashes and any attributes.
+    (@check_tag ($name:ident) ($($tail:tt)*)) => {
+        const _: () = assert!($crate::spec::is_element(stringify!($name)),
+            concat!("unknown element <", stringify!($name), ">, custom elements need a dash"));
+        html_impl! { @check_attrs ($($tail)*) }
+    };
+    (@check_tag ($($name:ident)-+) ($($tail:tt)*)) => {};
+    (@check_attrs ($attr:ident = $val:expr, $($tail:tt)*)) => {
+        const _: () = assert!($crate::macros::is_known_attribute(stringify!($attr)),
+            concat!("unknown attribute `", stringify!($attr), "`, set it with [\"", stringify!($attr), "\"]=value"));
+        html_impl! { @check_attrs ($($tail)*) }
+    };
+    (@check_attrs (> $($tail:tt)*)) => {};
+    (@check_attrs (/ > $($tail:tt)*)) => {};
+    (@check_attrs ($($tail:tt)*)) => {
+        html_impl! { @check_attrs_next ($($tail)*) }
+    };
+    // Skips an attribute which has another form, like `.value=` or `on("event")=`
+    (@check_attrs_next ($skip:tt $($tail:tt)*)) => {
+        html_impl! { @check_attrs_skip ($($tail)*) }
+    };
+    (@check_attrs_next ()) => {};
+    (@check_attrs_skip (, $($tail:tt)*)) => {
+        html_impl! { @check_attrs ($($tail)*) }
+    };
+    (@check_attrs_skip (> $($tail:tt)*)) => {};
+    (@check_attrs_skip ($skip:tt $($tail:tt)*)) => {
+        html_impl! { @check_attrs_skip ($($tail)*) }
+    };
+    (@check_attrs_skip ()) => {};
+    // PATTERN: .property=value, sets a JS property instead of an attribute
+    ($stack:ident (. $prop:ident = $val:expr, $($tail:tt)*)) => {
+        $crate::macros::add_property(&mut $stack, stringify!($prop), $val);
+        html_impl! { $stack ($($tail)*) }
+    };
+    // PATTERN: on("custom-event")=|detail: T| Msg::Event(detail),
+    ($stack:ident (on ($kind:expr) = $handler:expr, $($tail:tt)*)) => {
+        let listener = $crate::html::CustomListener::new($kind, $handler);
+        $crate::macros::attach_listener(&mut $stack, Box::new(listener));
+        html_impl! { $stack ($($tail)*) }
+    };
+    // PATTERN: listener=expression, attaches a listener like `dnd::source(id, Msg::Drag)`
+    ($stack:ident (listener = $listener:expr, $($tail:tt)*)) => {
+        $crate::macros::attach_listener(&mut $stack, Box::new($listener));
+        html_impl! { $stack ($($tail)*) }
+    };
     // PATTERN: class=("class-1", "class-2"),
     ($stack:ident (class = ($($class:expr),*), $($tail:tt)*)) => {
         $( $crate::macros::attach_class(&mut $stack, $class); )*
@@ -20,15 +165,29 @@ macro_rules! html_impl {
         $crate::macros::attach_class(&mut $stack, $class);
         html_impl! { $stack ($($tail)*) }
     };
+    // PATTERN: bind=(model.value, Msg::Update),
+    // Sets the value and sends the message with a new value on every input.
+    ($stack:ident (bind = ($value:expr, $update:expr), $($tail:tt)*)) => {
+        html_impl! { $stack (value = $value, oninput = |e: $crate::html::InputData| ($update)(e.value), $($tail)*) }
+    };
     // PATTERN: value="",
     ($stack:ident (value = $value:expr, $($tail:tt)*)) => {
-        $crate::macros::set_value(&mut $stack, $value);
+        if let Some(value) = html_impl!(@value $value) {
+            $crate::macros::set_value(&mut $stack, value);
+        }
         html_impl! { $stack ($($tail)*) }
     };
     // PATTERN: attribute=value, - workaround for `type` attribute
     // because `type` is a keyword in Rust
     ($stack:ident (type = $kind:expr, $($tail:tt)*)) => {
-        $crate::macros::set_kind(&mut $stack, $kind);
+        if let Some(kind) = html_impl!(@value $kind) {
+            $crate::macros::set_kind(&mut $stack, kind);
+        }
+        html_impl! { $stack ($($tail)*) }
+    };
+    // PATTERN: ref=&self.node_ref,
+    ($stack:ident (ref = $node_ref:expr, $($tail:tt)*)) => {
+        $crate::macros::set_node_ref(&mut $stack, $node_ref);
         html_impl! { $stack ($($tail)*) }
     };
     ($stack:ident (checked = $kind:expr, $($tail:tt)*)) => {
@@ -36,7 +195,7 @@ macro_rules! html_impl {
         html_impl! { $stack ($($tail)*) }
     };
     ($stack:ident (disabled = $kind:expr, $($tail:tt)*)) => {
-        if $kind {
+        if html_impl!(@value $kind).as_ref().map(String::as_str) == Some("true") {
             $crate::macros::add_attribute(&mut $stack, "disabled", "true");
         }
         html_impl! { $stack ($($tail)*) }
@@ -48,15 +207,101 @@ macro_rules! html_impl {
     ($stack:ident (ondoubleclick = $handler:expr, $($tail:tt)*)) => {
         html_impl! { $stack ((ondoubleclick) = $handler, $($tail)*) }
     };
+    ($stack:ident (onmousedown = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((onmousedown) = $handler, $($tail)*) }
+    };
+    ($stack:ident (onmouseup = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((onmouseup) = $handler, $($tail)*) }
+    };
+    ($stack:ident (onmousemove = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((onmousemove) = $handler, $($tail)*) }
+    };
+    ($stack:ident (onmouseenter = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((onmouseenter) = $handler, $($tail)*) }
+    };
+    ($stack:ident (onmouseleave = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((onmouseleave) = $handler, $($tail)*) }
+    };
+    ($stack:ident (onmouseover = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((onmouseover) = $handler, $($tail)*) }
+    };
+    ($stack:ident (onmouseout = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((onmouseout) = $handler, $($tail)*) }
+    };
+    ($stack:ident (oncontextmenu = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((oncontextmenu) = $handler, $($tail)*) }
+    };
+    ($stack:ident (onwheel = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((onwheel) = $handler, $($tail)*) }
+    };
+    ($stack:ident (onscroll = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((onscroll) = $handler, $($tail)*) }
+    };
+    ($stack:ident (onpointerdown = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((onpointerdown) = $handler, $($tail)*) }
+    };
+    ($stack:ident (onpointerup = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((onpointerup) = $handler, $($tail)*) }
+    };
+    ($stack:ident (onpointermove = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((onpointermove) = $handler, $($tail)*) }
+    };
+    ($stack:ident (onpointerenter = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((onpointerenter) = $handler, $($tail)*) }
+    };
+    ($stack:ident (onpointerleave = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((onpointerleave) = $handler, $($tail)*) }
+    };
+    ($stack:ident (onpointercancel = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((onpointercancel) = $handler, $($tail)*) }
+    };
+    ($stack:ident (ontouchstart = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((ontouchstart) = $handler, $($tail)*) }
+    };
+    ($stack:ident (ontouchend = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((ontouchend) = $handler, $($tail)*) }
+    };
+    ($stack:ident (ontouchmove = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((ontouchmove) = $handler, $($tail)*) }
+    };
+    ($stack:ident (ontouchcancel = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((ontouchcancel) = $handler, $($tail)*) }
+    };
     ($stack:ident (onkeypress = $handler:expr, $($tail:tt)*)) => {
         html_impl! { $stack ((onkeypress) = $handler, $($tail)*) }
     };
+    ($stack:ident (onkeydown = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((onkeydown) = $handler, $($tail)*) }
+    };
+    ($stack:ident (onkeyup = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((onkeyup) = $handler, $($tail)*) }
+    };
     ($stack:ident (oninput = $handler:expr, $($tail:tt)*)) => {
         html_impl! { $stack ((oninput) = $handler, $($tail)*) }
     };
+    ($stack:ident (onfocus = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((onfocus) = $handler, $($tail)*) }
+    };
     ($stack:ident (onblur = $handler:expr, $($tail:tt)*)) => {
         html_impl! { $stack ((onblur) = $handler, $($tail)*) }
     };
+    ($stack:ident (onchange = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((onchange) = $handler, $($tail)*) }
+    };
+    ($stack:ident (onsubmit = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((onsubmit) = $handler, $($tail)*) }
+    };
+    ($stack:ident (onpaste = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { $stack ((onpaste) = $handler, $($tail)*) }
+    };
+    // PATTERN: action.modifier=expression, like `onsubmit.prevent_default=`
+    ($stack:ident ($action:ident $(. $modifier:ident)+ = $handler:expr, $($tail:tt)*)) => {
+        let handler = $handler;
+        let mut listener = $crate::html::$action::Wrapper::from(handler);
+        $( listener.$modifier(); )+
+        $crate::macros::attach_listener(&mut $stack, Box::new(listener));
+        html_impl! { $stack ($($tail)*) }
+    };
     // PATTERN: (action)=expression,
     ($stack:ident (($action:ident) = $handler:expr, $($tail:tt)*)) => {
         // Catch value to a separate variable for clear error messages
@@ -71,20 +316,81 @@ macro_rules! html_impl {
         $crate::macros::add_attribute(&mut $stack, "href", href);
         html_impl! { $stack ($($tail)*) }
     };
+    // Attributes with `None` values are omitted.
     ($stack:ident ($attr:ident = $val:expr, $($tail:tt)*)) => {
-        $crate::macros::add_attribute(&mut $stack, stringify!($attr), $val);
+        if let Some(value) = html_impl!(@value $val) {
+            $crate::macros::add_attribute(&mut $stack, stringify!($attr), value);
+        }
         html_impl! { $stack ($($tail)*) }
     };
     ($stack:ident ($($attr:ident)-+ = $val:expr, $($tail:tt)*)) => {
-        let attr = vec![$(stringify!($attr).to_string()),+].join("-");
-        $crate::macros::add_attribute(&mut $stack, &attr, $val);
+        if let Some(value) = html_impl!(@value $val) {
+            let attr = vec![$(stringify!($attr).to_string()),+].join("-");
+            $crate::macros::add_attribute(&mut $stack, &attr, value);
+        }
+        html_impl! { $stack ($($tail)*) }
+    };
+    // PATTERN: [name]=value, with a computed name of the attribute
+    ($stack:ident ([ $name:expr ] = $val:expr, $($tail:tt)*)) => {
+        if let Some(value) = html_impl!(@value $val) {
+            $crate::macros::spread(&mut $stack, Some(($name, value)));
+        }
+        html_impl! { $stack ($($tail)*) }
+    };
+    (@value $val:expr) => {{
+        #[allow(unused_imports)]
+        use $crate::macros::{OptionalValue, PlainValue};
+        $crate::macros::AttributeValue($val).value()
+    }};
+    // PATTERN: ..attributes, where attributes are `HostAttributes` or pairs of names and values
+    ($stack:ident (.. $attrs:expr, $($tail:tt)*)) => {
+        $crate::macros::spread(&mut $stack, $attrs);
+        html_impl! { $stack ($($tail)*) }
+    };
+    // PATTERN: if (condition) { <tag> } else if (condition) { <tag> } else { <tag> }
+    // Branches are collected until the last `else` and rendered as an optional node.
+    ($stack:ident (if $($tail:tt)*)) => {
+        html_impl! { @if $stack () (if $($tail)*) }
+    };
+    (@if $stack:ident ($($branches:tt)*) (if let ($pat:pat = $eval:expr) { $($then:tt)* } else if $($tail:tt)*)) => {
+        html_impl! { @if $stack ($($branches)* if let ($pat = $eval) { $($then)* } else) (if $($tail)*) }
+    };
+    (@if $stack:ident ($($branches:tt)*) (if ($cond:expr) { $($then:tt)* } else if $($tail:tt)*)) => {
+        html_impl! { @if $stack ($($branches)* if ($cond) { $($then)* } else) (if $($tail)*) }
+    };
+    (@if $stack:ident ($($branches:tt)*) (if let ($pat:pat = $eval:expr) { $($then:tt)* } else { $($other:tt)* } $($tail:tt)*)) => {
+        let node = html_branch! { $($branches)* if let ($pat = $eval) { $($then)* } else { $($other)* } };
+        $crate::macros::add_optional_child(&mut $stack, node);
+        html_impl! { $stack ($($tail)*) }
+    };
+    (@if $stack:ident ($($branches:tt)*) (if ($cond:expr) { $($then:tt)* } else { $($other:tt)* } $($tail:tt)*)) => {
+        let node = html_branch! { $($branches)* if ($cond) { $($then)* } else { $($other)* } };
+        $crate::macros::add_optional_child(&mut $stack, node);
+        html_impl! { $stack ($($tail)*) }
+    };
+    (@if $stack:ident ($($branches:tt)*) (if let ($pat:pat = $eval:expr) { $($then:tt)* } $($tail:tt)*)) => {
+        let node = html_branch! { $($branches)* if let ($pat = $eval) { $($then)* } };
+        $crate::macros::add_optional_child(&mut $stack, node);
+        html_impl! { $stack ($($tail)*) }
+    };
+    (@if $stack:ident ($($branches:tt)*) (if ($cond:expr) { $($then:tt)* } $($tail:tt)*)) => {
+        let node = html_branch! { $($branches)* if ($cond) { $($then)* } };
+        $crate::macros::add_optional_child(&mut $stack, node);
+        html_impl! { $stack ($($tail)*) }
+    };
+    // PATTERN: for item in (iterable) { <tag> }
+    ($stack:ident (for $item:pat in ($iter:expr) { $($body:tt)* } $($tail:tt)*)) => {
+        for $item in $iter {
+            let node = html_node! { $($body)* };
+            $crate::macros::add_child(&mut $stack, node);
+        }
         html_impl! { $stack ($($tail)*) }
     };
-    // PATTERN: { for expression }
+    // PATTERN: { for iterable }
     ($stack:ident ({ for $eval:expr } $($tail:tt)*)) => {
         let nodes = $eval;
-        for node in nodes.map($crate::virtual_dom::VNode::from) {
-            $crate::macros::add_child(&mut $stack, node);
+        for node in nodes {
+            $crate::macros::add_child(&mut $stack, $crate::virtual_dom::VNode::from(node));
         }
         html_impl! { $stack ($($tail)*) }
     };
@@ -104,9 +410,9 @@ macro_rules! html_impl {
         html_impl! { $stack ($($tail)*) }
     };
     // Traditional tag closing
-    ($stack:ident (< / $endtag:ident > $($tail:tt)*)) => {
-        let endtag = stringify!($endtag);
-        $crate::macros::child_to_parent(&mut $stack, Some(endtag));
+    ($stack:ident (< / $($endtag:ident)-+ > $($tail:tt)*)) => {
+        let endtag = concat!($(stringify!($endtag), "-"),+);
+        $crate::macros::child_to_parent(&mut $stack, Some(&endtag[..endtag.len() - 1]));
         html_impl! { $stack ($($tail)*) }
     };
     // "End of paring" rule
@@ -115,16 +421,121 @@ macro_rules! html_impl {
     };
 }
 
+// Converts a chain of branches to an expression which returns `Option<VNode>`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! html_branch {
+    (if let ($pat:pat = $eval:expr) { $($then:tt)* } else if $($tail:tt)*) => {
+        if let $pat = $eval { Some(html_node! { $($then)* }) } else { html_branch! { if $($tail)* } }
+    };
+    (if ($cond:expr) { $($then:tt)* } else if $($tail:tt)*) => {
+        if $cond { Some(html_node! { $($then)* }) } else { html_branch! { if $($tail)* } }
+    };
+    (if let ($pat:pat = $eval:expr) { $($then:tt)* } else { $($other:tt)* }) => {
+        if let $pat = $eval { Some(html_node! { $($then)* }) } else { Some(html_node! { $($other)* }) }
+    };
+    (if ($cond:expr) { $($then:tt)* } else { $($other:tt)* }) => {
+        if $cond { Some(html_node! { $($then)* }) } else { Some(html_node! { $($other)* }) }
+    };
+    (if let ($pat:pat = $eval:expr) { $($then:tt)* }) => {
+        if let $pat = $eval { Some(html_node! { $($then)* }) } else { None }
+    };
+    (if ($cond:expr) { $($then:tt)* }) => {
+        if $cond { Some(html_node! { $($then)* }) } else { None }
+    };
+}
+
+// A body of a branch is a single tag or an expression in braces.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! html_node {
+    ({ $eval:expr }) => {
+        $crate::virtual_dom::VNode::from($eval)
+    };
+    ($($tail:tt)*) => {
+        $crate::virtual_dom::VNode::from(html! { $($tail)* })
+    };
+}
+
 // This entrypoint and implementation had separated to prevent infinite recursion.
 #[macro_export]
 macro_rules! html {
     ($($tail:tt)*) => {{
-        let mut stack = Vec::new();
+        let mut stack = $crate::macros::Stack::new();
         html_impl! { stack ($($tail)*) }
     }};
 }
 
-type Stack<MSG> = Vec<VTag<MSG>>;
+/// Listeners which `html!` attaches by names like `onclick=`.
+const LISTENERS: &[&str] = &[
+    "onclick", "ondoubleclick", "onmousedown", "onmouseup", "onmousemove", "onmouseenter",
+    "onmouseleave", "onmouseover", "onmouseout", "oncontextmenu", "onwheel", "onscroll",
+    "onpointerdown", "onpointerup", "onpointermove", "onpointerenter", "onpointerleave",
+    "onpointercancel", "ontouchstart", "ontouchend", "ontouchmove", "ontouchcancel",
+    "onkeypress", "onkeydown", "onkeyup", "oninput", "onfocus", "onblur", "onchange", "onsubmit",
+    "onpaste",
+];
+
+/// Returns `true` if `html!` sets an attribute with the name or handles it itself,
+/// like `ref=`, `bind=` or listeners.
+#[doc(hidden)]
+pub const fn is_known_attribute(name: &str) -> bool {
+    spec::is_attribute(name) || spec::contains(LISTENERS, name)
+        || spec::contains(&["ref", "bind", "listener"], name)
+}
+
+/// Tags which are opened by `html!`. Components with children are kept
+/// with their wrappers until their closing tags.
+#[doc(hidden)]
+pub struct Stack<MSG> {
+    tags: Vec<VTag<MSG>>,
+    components: Vec<(usize, Finish<MSG>)>,
+}
+
+/// Creates a component with its children.
+type Finish<MSG> = Box<FnOnce(Vec<VNode<MSG>>) -> VNode<MSG>>;
+
+impl<MSG> Stack<MSG> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Stack {
+            tags: Vec::new(),
+            components: Vec::new(),
+        }
+    }
+}
+
+impl<MSG> Deref for Stack<MSG> {
+    type Target = Vec<VTag<MSG>>;
+
+    fn deref(&self) -> &Vec<VTag<MSG>> {
+        &self.tags
+    }
+}
+
+impl<MSG> DerefMut for Stack<MSG> {
+    fn deref_mut(&mut self) -> &mut Vec<VTag<MSG>> {
+        &mut self.tags
+    }
+}
+
+/// Opens a component with children. The `finish` function creates the component
+/// with nodes which were rendered until its closing tag.
+#[doc(hidden)]
+pub fn open_component<MSG: 'static, F>(stack: &mut Stack<MSG>, name: &'static str, finish: F)
+where
+    F: FnOnce(Vec<VNode<MSG>>) -> VNode<MSG> + 'static,
+{
+    stack.tags.push(VTag::new(name));
+    let depth = stack.tags.len();
+    stack.components.push((depth, Box::new(finish)));
+}
+
+/// Returns the name of a component in its closing tag: `Select` for `form::Select<u32>`.
+fn closing_name(name: &str) -> &str {
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name).trim()
+}
 
 #[doc(hidden)]
 pub fn unpack<MSG>(mut stack: Stack<MSG>) -> VTag<MSG> {
@@ -152,6 +563,15 @@ pub fn set_kind<MSG, T: ToString>(stack: &mut Stack<MSG>, value: T) {
     }
 }
 
+#[doc(hidden)]
+pub fn set_node_ref<MSG, R: Borrow<NodeRef>>(stack: &mut Stack<MSG>, node_ref: R) {
+    if let Some(node) = stack.last_mut() {
+        node.set_node_ref(node_ref.borrow().clone());
+    } else {
+        panic!("no tag to set ref");
+    }
+}
+
 #[doc(hidden)]
 pub fn set_checked<MSG>(stack: &mut Stack<MSG>, value: bool) {
     if let Some(node) = stack.last_mut() {
@@ -170,12 +590,60 @@ pub fn add_attribute<MSG, T: ToString>(stack: &mut Stack<MSG>, name: &str, value
     }
 }
 
+/// A value of an attribute. Method calls prefer `OptionalValue` for options
+/// and fall back to `PlainValue` for other types.
+#[doc(hidden)]
+pub struct AttributeValue<T>(pub T);
+
+#[doc(hidden)]
+pub trait OptionalValue {
+    fn value(self) -> Option<String>;
+}
+
+impl<T: ToString> OptionalValue for AttributeValue<Option<T>> {
+    fn value(self) -> Option<String> {
+        self.0.map(|value| value.to_string())
+    }
+}
+
+#[doc(hidden)]
+pub trait PlainValue {
+    fn value(self) -> Option<String>;
+}
+
+impl<T: ToString> PlainValue for &AttributeValue<T> {
+    fn value(self) -> Option<String> {
+        Some(self.0.to_string())
+    }
+}
+
 #[doc(hidden)]
-pub fn attach_class<MSG>(stack: &mut Stack<MSG>, class: &'static str) {
+pub fn spread<MSG, S: Spread<MSG>>(stack: &mut Stack<MSG>, attrs: S) {
     if let Some(node) = stack.last_mut() {
-        node.add_classes(class);
+        attrs.spread(node);
     } else {
-        panic!("no tag to attach class: {}", class);
+        panic!("no tag to set attributes");
+    }
+}
+
+#[doc(hidden)]
+pub fn add_property<MSG, T: Into<Value>>(stack: &mut Stack<MSG>, name: &str, value: T) {
+    if let Some(node) = stack.last_mut() {
+        node.add_property(name, value);
+    } else {
+        panic!("no tag to set property: {}", name);
+    }
+}
+
+#[doc(hidden)]
+pub fn attach_class<MSG, T: Into<Classes>>(stack: &mut Stack<MSG>, class: T) {
+    let classes = class.into();
+    if let Some(node) = stack.last_mut() {
+        for class in classes.iter() {
+            node.add_classes(class);
+        }
+    } else {
+        panic!("no tag to attach class: {}", classes);
     }
 }
 
@@ -197,41 +665,48 @@ pub fn add_child<MSG>(stack: &mut Stack<MSG>, child: VNode<MSG>) {
     }
 }
 
+#[doc(hidden)]
+pub fn add_optional_child<MSG>(stack: &mut Stack<MSG>, child: Option<VNode<MSG>>) {
+    // An empty text keeps the position of the node, because children compared by indices
+    let child = child.unwrap_or_else(|| VNode::from(VText::new("")));
+    add_child(stack, child);
+}
+
 #[doc(hidden)]
 pub fn child_to_parent<MSG>(stack: &mut Stack<MSG>, endtag: Option<&'static str>) {
+    let depth = stack.len();
     if let Some(node) = stack.pop() {
+        let component = match stack.components.last() {
+            Some(&(opened, _)) if opened == depth => stack.components.pop().map(|(_, finish)| finish),
+            _ => None,
+        };
         if let Some(endtag) = endtag {
             let starttag = node.tag();
-            if starttag != endtag {
+            let matched = match component {
+                Some(_) => closing_name(starttag) == endtag,
+                None => starttag == endtag,
+            };
+            if !matched {
                 panic!("wrong closing tag: <{}> -> </{}>", starttag, endtag);
             }
         }
 
-        if !stack.is_empty() {
-            stack.last_mut()
-                .expect("stack lost the last element")
-                .add_child(VNode::from(node));
-        } else {
-            // Keep the last node in the stack
-            stack.push(node);
+        match component {
+            Some(finish) => {
+                let node = finish(node.childs);
+                add_child(stack, node);
+            }
+            None if !stack.is_empty() => {
+                stack.last_mut()
+                    .expect("stack lost the last element")
+                    .add_child(VNode::from(node));
+            }
+            None => {
+                // Keep the last node in the stack
+                stack.push(node);
+            }
         }
     } else {
         panic!("redundant closing tag: {:?}", endtag);
     }
 }
-
-#[macro_export]
-macro_rules! debug {
-    ($($e:expr),*) => {
-        if cfg!(debug) {
-            println!($($e,)*);
-        }
-    };
-}
-
-#[macro_export]
-macro_rules! warn {
-    ($($e:expr),*) => {
-        eprintln!($($e,)*);
-    };
-}
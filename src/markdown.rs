@@ -0,0 +1,575 @@
+//! This module contains a renderer of Markdown to a virtual DOM.
+//!
+//! It supports the common subset of CommonMark: headings, paragraphs,
+//! emphasis, strong emphasis, inline code, fenced and indented code blocks,
+//! block quotes, ordered and unordered lists, thematic breaks, hard line breaks,
+//! links, autolinks and images.
+//!
+//! The markup is never inserted as HTML. Every element is created as a virtual
+//! node, raw HTML of the source is rendered as text and links with schemes
+//! other than `http`, `https` and `mailto` are dropped, so the output is safe
+//! for content provided by users. `render_with_html` inserts HTML blocks
+//! of the source instead, which are cleaned by a sanitizer.
+
+use virtual_dom::{VNode, VTag, VText, VRaw, Sanitizer};
+use html::Html;
+
+/// A block of a document.
+#[derive(Debug, PartialEq)]
+enum Block {
+    Heading(usize, String),
+    Paragraph(String),
+    Code(Option<String>, String),
+    Quote(Vec<Block>),
+    List(Option<u32>, Vec<Vec<Block>>),
+    Rule,
+    Html(VRaw),
+}
+
+/// An inline element of a block.
+#[derive(Debug, PartialEq)]
+enum Inline {
+    Text(String),
+    Code(String),
+    Emphasis(Vec<Inline>),
+    Strong(Vec<Inline>),
+    Link(String, Vec<Inline>),
+    Image(String, String),
+    Break,
+}
+
+/// Renders the Markdown `source` to a `div` with the `markdown` class.
+pub fn render<MSG: 'static>(source: &str) -> Html<MSG> {
+    render_blocks(source, None)
+}
+
+/// Renders the Markdown `source` like `render`, but blocks of HTML, which
+/// start with a tag on a line and end with a blank line, are cleaned by
+/// the `sanitizer` and inserted as raw nodes. HTML inside of lines is text.
+pub fn render_with_html<MSG: 'static, S: Sanitizer>(source: &str, sanitizer: &S) -> Html<MSG> {
+    render_blocks(source, Some(sanitizer))
+}
+
+fn render_blocks<MSG: 'static>(source: &str, sanitizer: Option<&Sanitizer>) -> Html<MSG> {
+    let lines = source.lines().map(|line| line.replace('\t', "    ")).collect::<Vec<_>>();
+    let blocks = parse_blocks(&lines, sanitizer);
+    let mut root = VTag::new("div");
+    root.add_classes("markdown");
+    for block in blocks {
+        root.add_child(render_block(block));
+    }
+    root
+}
+
+// Only spaces and tabs indent, other whitespace is text and could be multibyte
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(|c| c == ' ' || c == '\t').len()
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+fn heading(line: &str) -> Option<(usize, String)> {
+    let line = line.trim();
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    let text = rest.trim().trim_end_matches('#').trim();
+    Some((level, text.to_owned()))
+}
+
+/// Returns `true` if the line starts a block of HTML, like `<div>` or `</p>`.
+fn is_html(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    let mut chars = trimmed.chars();
+    indent_of(line) < 4 && chars.next() == Some('<') && match chars.next() {
+        Some('/') | Some('!') => true,
+        Some(c) => c.is_ascii_alphabetic(),
+        None => false,
+    }
+}
+
+fn is_rule(line: &str) -> bool {
+    let line = line.trim();
+    let mut chars = line.chars().filter(|c| !c.is_whitespace());
+    match chars.next() {
+        Some(first) if first == '-' || first == '*' || first == '_' => {
+            let rest = chars.collect::<Vec<_>>();
+            rest.len() >= 2 && rest.iter().all(|c| *c == first)
+        }
+        _ => false,
+    }
+}
+
+fn fence(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    if indent_of(line) < 4 && trimmed.starts_with("```") {
+        Some(trimmed.trim_start_matches('`').trim())
+    } else {
+        None
+    }
+}
+
+/// Returns a number of an ordered item (`None` for bullets)
+/// and the offset of the content.
+fn list_marker(line: &str) -> Option<(Option<u32>, usize)> {
+    let indent = indent_of(line);
+    if indent >= 4 {
+        return None;
+    }
+    let trimmed = &line[indent..];
+    let mut chars = trimmed.chars();
+    match chars.next() {
+        Some('-') | Some('*') | Some('+') => {
+            match chars.next() {
+                Some(' ') => Some((None, indent + 2)),
+                None => Some((None, indent + 1)),
+                _ => None,
+            }
+        }
+        Some(c) if c.is_ascii_digit() => {
+            let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+            if digits > 9 {
+                return None;
+            }
+            let rest = &trimmed[digits..];
+            if rest.starts_with(". ") || rest.starts_with(") ") {
+                let number = trimmed[..digits].parse().ok()?;
+                Some((Some(number), indent + digits + 2))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+fn strip_quote(line: &str) -> Option<&str> {
+    if indent_of(line) >= 4 {
+        return None;
+    }
+    let rest = line.trim_start().strip_prefix('>')?;
+    Some(rest.strip_prefix(' ').unwrap_or(rest))
+}
+
+fn dedent(line: &str, width: usize) -> String {
+    let indent = indent_of(line).min(width);
+    line[indent..].to_owned()
+}
+
+fn parse_blocks(lines: &[String], sanitizer: Option<&Sanitizer>) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut i = 0;
+
+    macro_rules! flush {
+        () => {
+            if !paragraph.is_empty() {
+                blocks.push(Block::Paragraph(paragraph.join("\n").trim_end().to_owned()));
+                paragraph.clear();
+            }
+        };
+    }
+
+    while i < lines.len() {
+        let line = lines[i].as_str();
+        if is_blank(line) {
+            flush!();
+            i += 1;
+        } else if let Some(lang) = fence(line) {
+            flush!();
+            let lang = if lang.is_empty() { None } else { Some(lang.to_owned()) };
+            let mut code = Vec::new();
+            i += 1;
+            while i < lines.len() && fence(&lines[i]).is_none() {
+                code.push(lines[i].as_str());
+                i += 1;
+            }
+            // Skip the closing fence
+            i += 1;
+            blocks.push(Block::Code(lang, code.join("\n")));
+        } else if indent_of(line) >= 4 && paragraph.is_empty() {
+            let mut code = Vec::new();
+            while i < lines.len() && (indent_of(&lines[i]) >= 4 || is_blank(&lines[i])) {
+                code.push(dedent(&lines[i], 4));
+                i += 1;
+            }
+            while code.last().map(|line| is_blank(line)).unwrap_or(false) {
+                code.pop();
+            }
+            blocks.push(Block::Code(None, code.join("\n")));
+        } else if let Some(sanitizer) = sanitizer.filter(|_| is_html(line)) {
+            flush!();
+            let mut html = Vec::new();
+            while i < lines.len() && !is_blank(&lines[i]) {
+                html.push(lines[i].as_str());
+                i += 1;
+            }
+            blocks.push(Block::Html(VRaw::sanitized(&html.join("\n"), sanitizer)));
+        } else if let Some((level, text)) = heading(line) {
+            flush!();
+            blocks.push(Block::Heading(level, text));
+            i += 1;
+        } else if is_rule(line) {
+            flush!();
+            blocks.push(Block::Rule);
+            i += 1;
+        } else if strip_quote(line).is_some() {
+            flush!();
+            let mut quoted = Vec::new();
+            while i < lines.len() {
+                match strip_quote(&lines[i]) {
+                    Some(rest) => quoted.push(rest.to_owned()),
+                    None => break,
+                }
+                i += 1;
+            }
+            blocks.push(Block::Quote(parse_blocks(&quoted, sanitizer)));
+        } else if let Some((start, _)) = list_marker(line) {
+            flush!();
+            let ordered = start.is_some();
+            let mut items = Vec::new();
+            while i < lines.len() {
+                let offset = match list_marker(&lines[i]) {
+                    Some((number, offset)) if number.is_some() == ordered => offset,
+                    _ => break,
+                };
+                let mut content = vec![lines[i].get(offset..).unwrap_or("").to_owned()];
+                i += 1;
+                while i < lines.len() {
+                    let line = lines[i].as_str();
+                    if is_blank(line) {
+                        let continues = lines.get(i + 1)
+                            .map(|next| !is_blank(next) && indent_of(next) >= offset)
+                            .unwrap_or(false);
+                        if !continues {
+                            break;
+                        }
+                        content.push(String::new());
+                    } else if indent_of(line) >= offset {
+                        content.push(dedent(line, offset));
+                    } else if list_marker(line).is_none() && !is_rule(line) && heading(line).is_none() {
+                        // A lazy continuation of the paragraph
+                        content.push(line.trim().to_owned());
+                    } else {
+                        break;
+                    }
+                    i += 1;
+                }
+                items.push(parse_blocks(&content, sanitizer));
+                // A blank line between items
+                if i < lines.len() && is_blank(&lines[i]) {
+                    let next_is_item = lines.get(i + 1)
+                        .and_then(|next| list_marker(next))
+                        .map(|(number, _)| number.is_some() == ordered)
+                        .unwrap_or(false);
+                    if next_is_item {
+                        i += 1;
+                    }
+                }
+            }
+            blocks.push(Block::List(start, items));
+        } else {
+            // Trailing spaces are kept for hard line breaks
+            paragraph.push(line.trim_start());
+            i += 1;
+        }
+    }
+    flush!();
+    blocks
+}
+
+/// Returns `true` for links which can't execute scripts.
+fn is_safe_url(url: &str) -> bool {
+    let scheme_end = url.find(|c: char| c == ':' || c == '/' || c == '?' || c == '#');
+    match scheme_end {
+        Some(idx) if url[idx..].starts_with(':') => {
+            let scheme = url[..idx].to_lowercase();
+            scheme == "http" || scheme == "https" || scheme == "mailto"
+        }
+        _ => true,
+    }
+}
+
+/// Finds the closing `delimiter` which isn't preceded by a whitespace.
+fn find_closing(chars: &[char], from: usize, delimiter: &[char]) -> Option<usize> {
+    let mut i = from;
+    while i + delimiter.len() <= chars.len() {
+        if chars[i] == '\\' {
+            i += 2;
+            continue;
+        }
+        if &chars[i..i + delimiter.len()] == delimiter && i > from && !chars[i - 1].is_whitespace() {
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Finds the closing bracket which matches the opening one before `from`.
+fn find_bracket(chars: &[char], from: usize, open: char, close: char) -> Option<usize> {
+    let mut depth = 1;
+    let mut i = from;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            c if c == open => depth += 1,
+            c if c == close => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Parses `[text](url "title")` or `![alt](src)` starting from `[`.
+/// Returns the text, the url and the index after the closing parenthesis.
+fn parse_link(chars: &[char], from: usize) -> Option<(String, String, usize)> {
+    let close = find_bracket(chars, from + 1, '[', ']')?;
+    if chars.get(close + 1) != Some(&'(') {
+        return None;
+    }
+    let end = find_bracket(chars, close + 2, '(', ')')?;
+    let text = chars[from + 1..close].iter().collect::<String>();
+    let target = chars[close + 2..end].iter().collect::<String>();
+    let url = target.split_whitespace().next().unwrap_or("")
+        .trim_start_matches('<').trim_end_matches('>').to_owned();
+    Some((text, url, end + 1))
+}
+
+fn parse_inlines(text: &str) -> Vec<Inline> {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut inlines = Vec::new();
+    let mut buffer = String::new();
+    let mut i = 0;
+
+    macro_rules! flush {
+        () => {
+            if !buffer.is_empty() {
+                inlines.push(Inline::Text(buffer.clone()));
+                buffer.clear();
+            }
+        };
+    }
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\\' if i + 1 < chars.len() && chars[i + 1] == '\n' => {
+                flush!();
+                inlines.push(Inline::Break);
+                i += 2;
+            }
+            '\\' if i + 1 < chars.len() && chars[i + 1].is_ascii_punctuation() => {
+                buffer.push(chars[i + 1]);
+                i += 2;
+            }
+            '\n' => {
+                let hard = buffer.ends_with("  ");
+                let trimmed = buffer.trim_end().len();
+                buffer.truncate(trimmed);
+                if hard {
+                    flush!();
+                    inlines.push(Inline::Break);
+                } else {
+                    buffer.push(' ');
+                }
+                i += 1;
+            }
+            '`' => {
+                let ticks = chars[i..].iter().take_while(|c| **c == '`').count();
+                let delimiter = vec!['`'; ticks];
+                let mut j = i + ticks;
+                let mut found = None;
+                while j + ticks <= chars.len() {
+                    if chars[j..j + ticks] == delimiter[..] && chars.get(j + ticks) != Some(&'`') {
+                        found = Some(j);
+                        break;
+                    }
+                    j += 1;
+                }
+                match found {
+                    Some(end) => {
+                        flush!();
+                        let code = chars[i + ticks..end].iter().collect::<String>();
+                        inlines.push(Inline::Code(code.trim().replace('\n', " ")));
+                        i = end + ticks;
+                    }
+                    None => {
+                        buffer.extend(&delimiter);
+                        i += ticks;
+                    }
+                }
+            }
+            '*' | '_' => {
+                let double = chars.get(i + 1) == Some(&c);
+                let delimiter = if double { vec![c, c] } else { vec![c] };
+                let start = i + delimiter.len();
+                let opens = chars.get(start).map(|c| !c.is_whitespace()).unwrap_or(false);
+                match find_closing(&chars, start, &delimiter) {
+                    Some(end) if opens => {
+                        flush!();
+                        let inner = chars[start..end].iter().collect::<String>();
+                        let children = parse_inlines(&inner);
+                        inlines.push(if double { Inline::Strong(children) } else { Inline::Emphasis(children) });
+                        i = end + delimiter.len();
+                    }
+                    _ => {
+                        buffer.extend(&delimiter);
+                        i = start;
+                    }
+                }
+            }
+            '!' if chars.get(i + 1) == Some(&'[') => {
+                match parse_link(&chars, i + 1) {
+                    Some((alt, src, next)) => {
+                        flush!();
+                        if is_safe_url(&src) {
+                            inlines.push(Inline::Image(src, alt));
+                        }
+                        i = next;
+                    }
+                    None => {
+                        buffer.push(c);
+                        i += 1;
+                    }
+                }
+            }
+            '[' => {
+                match parse_link(&chars, i) {
+                    Some((text, href, next)) => {
+                        flush!();
+                        let children = parse_inlines(&text);
+                        if is_safe_url(&href) {
+                            inlines.push(Inline::Link(href, children));
+                        } else {
+                            inlines.extend(children);
+                        }
+                        i = next;
+                    }
+                    None => {
+                        buffer.push(c);
+                        i += 1;
+                    }
+                }
+            }
+            '<' => {
+                let end = chars[i..].iter().position(|c| *c == '>' || c.is_whitespace());
+                let url = end.filter(|end| chars[i + end] == '>')
+                    .map(|end| chars[i + 1..i + end].iter().collect::<String>())
+                    .filter(|url| url.starts_with("http://") || url.starts_with("https://"));
+                match url {
+                    Some(url) => {
+                        flush!();
+                        let len = url.chars().count();
+                        inlines.push(Inline::Link(url.clone(), vec![Inline::Text(url)]));
+                        i += len + 2;
+                    }
+                    None => {
+                        buffer.push(c);
+                        i += 1;
+                    }
+                }
+            }
+            _ => {
+                buffer.push(c);
+                i += 1;
+            }
+        }
+    }
+    flush!();
+    inlines
+}
+
+fn tag<MSG: 'static>(name: &'static str, children: Vec<VNode<MSG>>) -> VNode<MSG> {
+    let mut tag = VTag::new(name);
+    for child in children {
+        tag.add_child(child);
+    }
+    VNode::from(tag)
+}
+
+fn render_inlines<MSG: 'static>(inlines: Vec<Inline>) -> Vec<VNode<MSG>> {
+    inlines.into_iter().map(|inline| {
+        match inline {
+            Inline::Text(text) => VNode::from(VText::new(text)),
+            Inline::Code(code) => tag("code", vec![VNode::from(VText::new(code))]),
+            Inline::Emphasis(children) => tag("em", render_inlines(children)),
+            Inline::Strong(children) => tag("strong", render_inlines(children)),
+            Inline::Link(href, children) => {
+                let mut link = VTag::new("a");
+                link.add_attribute("href", href);
+                for child in render_inlines(children) {
+                    link.add_child(child);
+                }
+                VNode::from(link)
+            }
+            Inline::Image(src, alt) => {
+                let mut image = VTag::new("img");
+                image.add_attribute("src", src);
+                image.add_attribute("alt", alt);
+                VNode::from(image)
+            }
+            Inline::Break => tag("br", Vec::new()),
+        }
+    }).collect()
+}
+
+fn render_block<MSG: 'static>(block: Block) -> VNode<MSG> {
+    match block {
+        Block::Heading(level, text) => {
+            let name = ["h1", "h2", "h3", "h4", "h5", "h6"][level - 1];
+            tag(name, render_inlines(parse_inlines(&text)))
+        }
+        Block::Paragraph(text) => tag("p", render_inlines(parse_inlines(&text))),
+        Block::Code(lang, code) => {
+            let mut inner = VTag::new("code");
+            if let Some(lang) = lang {
+                inner.add_classes(&format!("language-{}", lang));
+            }
+            inner.add_child(VNode::from(VText::new(code)));
+            tag("pre", vec![VNode::from(inner)])
+        }
+        Block::Quote(blocks) => tag("blockquote", blocks.into_iter().map(render_block).collect()),
+        Block::Html(raw) => VNode::from(raw),
+        Block::List(start, items) => {
+            let tight = items.iter().all(|item| item.len() <= 1);
+            let items = items.into_iter().map(|blocks| {
+                let children = blocks.into_iter().flat_map(|block| {
+                    match block {
+                        // Tight lists don't wrap items with paragraphs
+                        Block::Paragraph(ref text) if tight => render_inlines(parse_inlines(text)),
+                        block => vec![render_block(block)],
+                    }
+                }).collect();
+                tag("li", children)
+            }).collect();
+            match start {
+                Some(start) => {
+                    let mut list = VTag::new("ol");
+                    if start != 1 {
+                        list.add_attribute("start", start);
+                    }
+                    for item in items {
+                        list.add_child(item);
+                    }
+                    VNode::from(list)
+                }
+                None => tag("ul", items),
+            }
+        }
+        Block::Rule => tag("hr", Vec::new()),
+    }
+}
@@ -0,0 +1,332 @@
+//! This module contains masks which format text of inputs while users type,
+//! like phone numbers, card numbers and amounts. A mask splits text into
+//! the raw value, which apps store, and the formatted text, which users see:
+//!
+//! ```rust,ignore
+//! let phone = Mask::phone();
+//! assert_eq!(phone.format("5551234"), "(555) 123-4");
+//! assert_eq!(phone.raw("(555) 123-4"), "5551234");
+//! ```
+//!
+//! `MaskedInput` applies a mask to every edit and keeps the caret after
+//! the same raw character.
+
+use std::cmp;
+use services::intl::IntlService;
+
+/// An edit of a masked input.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Masked {
+    /// The value without formatting.
+    pub raw: String,
+    /// The text of the input.
+    pub formatted: String,
+    /// The caret in chars of the formatted text.
+    pub caret: usize,
+    /// The raw value has all which the mask expects, like every digit of
+    /// a phone number.
+    pub complete: bool,
+}
+
+/// A format of numbers with separators of a locale.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberMask {
+    /// Separates the fraction.
+    pub decimal: char,
+    /// Separates groups of thousands, if there are groups.
+    pub group: Option<char>,
+    /// Digits after the decimal separator at most, none for integers.
+    pub fraction_digits: u8,
+    /// Allows a leading minus sign.
+    pub negative: bool,
+    /// A text before the number, like a currency symbol.
+    pub prefix: String,
+    /// A text after the number.
+    pub suffix: String,
+}
+
+impl Default for NumberMask {
+    fn default() -> Self {
+        NumberMask {
+            decimal: '.',
+            group: Some(','),
+            fraction_digits: 0,
+            negative: false,
+            prefix: String::new(),
+            suffix: String::new(),
+        }
+    }
+}
+
+impl NumberMask {
+    /// Creates a mask of decimal numbers with separators of the locale
+    /// of `IntlService`.
+    pub fn new(intl: &IntlService, fraction_digits: u8) -> Self {
+        NumberMask {
+            fraction_digits,
+            ..NumberMask::from_sample(&intl.number(1234.5))
+        }
+    }
+
+    /// Creates a mask of amounts of the currency with an ISO 4217 code,
+    /// with its symbol and digits of the fraction in the locale of
+    /// `IntlService`: `$1,234.50` or `1.234,50 €`.
+    pub fn currency(intl: &IntlService, currency: &str) -> Self {
+        NumberMask::from_sample(&intl.currency(1234.5, currency))
+    }
+
+    /// Reads separators and affixes from a formatted `1234.5`.
+    fn from_sample(sample: &str) -> Self {
+        let first = sample.find(|c: char| c.is_ascii_digit()).unwrap_or(0);
+        let last = sample.rfind(|c: char| c.is_ascii_digit()).map_or(sample.len(), |index| index + 1);
+        let number: Vec<char> = sample[first..last].chars().collect();
+        // The integer part is `1234`, with a group after the first digit
+        let mut chars = number.iter().skip(1);
+        let group = match chars.next() {
+            Some(&c) if !c.is_ascii_digit() => Some(c),
+            _ => None,
+        };
+        let integer = if group.is_some() { 5 } else { 4 };
+        let decimal = number.get(integer).cloned().filter(|c| !c.is_ascii_digit());
+        NumberMask {
+            decimal: decimal.unwrap_or('.'),
+            group,
+            fraction_digits: decimal.map_or(0, |_| (number.len() - integer - 1) as u8),
+            negative: false,
+            prefix: sample[..first].to_owned(),
+            suffix: sample[last..].to_owned(),
+        }
+    }
+
+    fn raw(&self, text: &str) -> String {
+        let text = text.strip_prefix(self.prefix.as_str()).unwrap_or(text);
+        let mut raw = String::new();
+        let mut fraction = None;
+        for c in text.chars() {
+            if c == '-' && self.negative && raw.is_empty() {
+                raw.push('-');
+            } else if c.is_ascii_digit() {
+                match fraction {
+                    Some(digits) if digits >= self.fraction_digits => continue,
+                    Some(digits) => fraction = Some(digits + 1),
+                    None => (),
+                }
+                raw.push(c);
+            } else if c == self.decimal && self.fraction_digits > 0 && fraction.is_none() {
+                raw.push('.');
+                fraction = Some(0);
+            }
+        }
+        raw
+    }
+
+    fn format(&self, raw: &str) -> String {
+        if raw.is_empty() {
+            return String::new();
+        }
+        let (sign, number) = match raw.strip_prefix('-') {
+            Some(number) => ("-", number),
+            None => ("", raw),
+        };
+        let mut parts = number.splitn(2, '.');
+        let integer = parts.next().unwrap_or_default();
+        let mut text = format!("{}{}", sign, self.prefix);
+        for (index, digit) in integer.chars().enumerate() {
+            let left = integer.len() - index;
+            if index > 0 && left % 3 == 0 {
+                if let Some(group) = self.group {
+                    text.push(group);
+                }
+            }
+            text.push(digit);
+        }
+        if let Some(fraction) = parts.next() {
+            text.push(self.decimal);
+            text.push_str(fraction);
+        }
+        text.push_str(&self.suffix);
+        text
+    }
+
+    /// Returns the number of the raw value.
+    pub fn value(&self, raw: &str) -> Option<f64> {
+        raw.parse().ok()
+    }
+}
+
+/// A mask of inputs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Mask {
+    /// A pattern where `#` is a digit, `A` is a letter, `*` is a digit or
+    /// a letter and other characters are added between them, like
+    /// `(###) ###-####`. Letters are typed in upper case.
+    Pattern(String),
+    /// A payment card number in groups of four digits, or four, six and
+    /// five digits of American Express.
+    CardNumber,
+    /// A number with separators.
+    Number(NumberMask),
+}
+
+impl Default for Mask {
+    fn default() -> Self {
+        Mask::Number(NumberMask::default())
+    }
+}
+
+fn is_slot(c: char) -> bool {
+    c == '#' || c == 'A' || c == '*'
+}
+
+fn fits(slot: char, c: char) -> bool {
+    match slot {
+        '#' => c.is_ascii_digit(),
+        'A' => c.is_alphabetic(),
+        _ => c.is_alphanumeric(),
+    }
+}
+
+fn is_amex(raw: &str) -> bool {
+    raw.starts_with("34") || raw.starts_with("37")
+}
+
+/// Checks digits with the Luhn algorithm of card numbers.
+fn luhn(digits: &str) -> bool {
+    let sum: u32 = digits.chars().rev().filter_map(|c| c.to_digit(10)).enumerate()
+        .map(|(index, digit)| match (index % 2, digit * 2) {
+            (0, _) => digit,
+            (_, double) if double > 9 => double - 9,
+            (_, double) => double,
+        })
+        .sum();
+    sum % 10 == 0
+}
+
+impl Mask {
+    /// A mask of North American phone numbers: `(555) 123-4567`. Other
+    /// numbers use their own patterns, like `+44 #### ######`.
+    pub fn phone() -> Mask {
+        Mask::Pattern("(###) ###-####".into())
+    }
+
+    /// Returns the raw value of a text: characters which the mask keeps.
+    pub fn raw(&self, text: &str) -> String {
+        match *self {
+            Mask::Pattern(ref pattern) => {
+                let slots: Vec<char> = pattern.chars().filter(|c| is_slot(*c)).collect();
+                // A typed prefix of literals, like `+1 `, isn't a value
+                let skip = text.chars().zip(pattern.chars().take_while(|c| !is_slot(*c)))
+                    .take_while(|&(a, b)| a == b)
+                    .count();
+                let mut raw = String::new();
+                let mut count = 0;
+                for c in text.chars().skip(skip) {
+                    match slots.get(count) {
+                        Some(&slot) if fits(slot, c) => {
+                            raw.extend(c.to_uppercase());
+                            count += 1;
+                        }
+                        Some(_) => (),
+                        None => break,
+                    }
+                }
+                raw
+            }
+            Mask::CardNumber => text.chars().filter(char::is_ascii_digit).take(19).collect(),
+            Mask::Number(ref number) => number.raw(text),
+        }
+    }
+
+    /// Formats a raw value. Literals of patterns are added up to the last
+    /// raw character.
+    pub fn format(&self, raw: &str) -> String {
+        match *self {
+            Mask::Pattern(ref pattern) => {
+                let mut chars = raw.chars().peekable();
+                let mut text = String::new();
+                for c in pattern.chars() {
+                    if chars.peek().is_none() {
+                        break;
+                    }
+                    if is_slot(c) {
+                        text.extend(chars.next());
+                    } else {
+                        text.push(c);
+                    }
+                }
+                text
+            }
+            Mask::CardNumber => {
+                let groups: &[usize] = if is_amex(raw) { &[4, 6, 5] } else { &[4, 4, 4, 4, 3] };
+                let mut text = String::new();
+                let mut rest = raw;
+                for &size in groups {
+                    if rest.is_empty() {
+                        break;
+                    }
+                    if !text.is_empty() {
+                        text.push(' ');
+                    }
+                    let (group, next) = rest.split_at(cmp::min(size, rest.len()));
+                    text.push_str(group);
+                    rest = next;
+                }
+                text
+            }
+            Mask::Number(ref number) => number.format(raw),
+        }
+    }
+
+    /// Returns `true` if the raw value has all which the mask expects:
+    /// every slot of a pattern, a card number which passes the Luhn
+    /// check or a number.
+    pub fn is_complete(&self, raw: &str) -> bool {
+        match *self {
+            Mask::Pattern(ref pattern) => raw.chars().count() == pattern.chars().filter(|c| is_slot(*c)).count(),
+            Mask::CardNumber => {
+                let length = if is_amex(raw) { 15..=15 } else { 13..=19 };
+                length.contains(&raw.len()) && luhn(raw)
+            }
+            Mask::Number(ref number) => number.value(raw).is_some(),
+        }
+    }
+
+    /// Returns the `inputmode` of inputs, which picks a keyboard of phones.
+    pub fn input_mode(&self) -> &'static str {
+        match *self {
+            Mask::Pattern(ref pattern) if pattern.chars().all(|c| c != 'A' && c != '*') => "numeric",
+            Mask::Pattern(_) => "text",
+            Mask::CardNumber => "numeric",
+            Mask::Number(ref number) if number.fraction_digits > 0 => "decimal",
+            Mask::Number(_) => "numeric",
+        }
+    }
+
+    /// Formats a text which a user changed from the old one with the caret
+    /// at a char offset. The caret stays after the same raw character,
+    /// and deleting a literal deletes the raw character before it.
+    pub fn edit(&self, old: &str, text: &str, caret: usize) -> Masked {
+        let before: String = text.chars().take(caret).collect();
+        let mut raw: Vec<char> = self.raw(text).chars().collect();
+        let mut count = cmp::min(self.raw(&before).chars().count(), raw.len());
+        let deleted = text.chars().count() < old.chars().count();
+        if deleted && count > 0 && raw.iter().cloned().eq(self.raw(old).chars()) {
+            raw.remove(count - 1);
+            count -= 1;
+        }
+        let raw: String = raw.into_iter().collect();
+        let formatted = self.format(&raw);
+        let caret = (0..=formatted.chars().count())
+            .find(|&offset| {
+                let prefix: String = formatted.chars().take(offset).collect();
+                self.raw(&prefix).chars().count() >= count
+            })
+            .unwrap_or(0);
+        Masked {
+            complete: self.is_complete(&raw),
+            raw,
+            formatted,
+            caret,
+        }
+    }
+}
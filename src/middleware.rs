@@ -0,0 +1,121 @@
+//! This module contains hooks which wrap every update of an app.
+//! Middlewares could observe and replace messages, measure updates,
+//! log changes of a model or skip renders:
+//!
+//! ```rust,ignore
+//! struct Analytics;
+//!
+//! impl Middleware<Model, Msg> for Analytics {
+//!     fn update(&mut self, model: &mut Model, msg: Msg, next: &mut FnMut(&mut Model, Msg)) {
+//!         if let Msg::Checkout = msg {
+//!             track("checkout");
+//!         }
+//!         next(model, msg);
+//!     }
+//! }
+//!
+//! let mut app = App::new();
+//! app.middleware(Logger::new());
+//! app.middleware(Analytics);
+//! app.mount(context, model, update, view);
+//! ```
+//!
+//! Middlewares are called in the order they were added: the first one
+//! wraps all others and the `update` function.
+
+use std::fmt::Debug;
+use html;
+use services::console::ConsoleService;
+
+/// A hook which wraps updates of an app.
+pub trait Middleware<MOD, MSG> {
+    /// Called before every batch of messages. The batch is empty
+    /// if the loop was woken to render a model changed by a middleware.
+    fn before_batch(&mut self, _model: &mut MOD) {
+    }
+
+    /// Handles a message. Call `next` to pass the message (or another one)
+    /// to the next middleware and finally to `update`. A message which
+    /// isn't passed is dropped.
+    fn update(&mut self, model: &mut MOD, msg: MSG, next: &mut FnMut(&mut MOD, MSG)) {
+        next(model, msg);
+    }
+
+    /// Returns `false` to skip a render of the model after a batch of messages.
+    /// All middlewares are asked every time.
+    fn should_render(&mut self, _model: &MOD) -> bool {
+        true
+    }
+}
+
+/// Calls `before_batch` of every middleware.
+pub(crate) fn before_batch<MOD, MSG>(chain: &mut [Box<Middleware<MOD, MSG>>], model: &mut MOD) {
+    for middleware in chain.iter_mut() {
+        middleware.before_batch(model);
+    }
+}
+
+/// Passes the message through the middlewares to the `update` function.
+pub(crate) fn dispatch<MOD, MSG>(
+    chain: &mut [Box<Middleware<MOD, MSG>>],
+    model: &mut MOD,
+    msg: MSG,
+    update: &mut FnMut(&mut MOD, MSG),
+) {
+    match chain.split_first_mut() {
+        Some((first, rest)) => {
+            first.update(model, msg, &mut |model, msg| dispatch(rest, model, msg, update));
+        }
+        None => update(model, msg),
+    }
+}
+
+/// Asks every middleware whether the model has to be rendered.
+pub(crate) fn should_render<MOD, MSG>(chain: &mut [Box<Middleware<MOD, MSG>>], model: &MOD) -> bool {
+    let mut render = true;
+    for middleware in chain {
+        render &= middleware.should_render(model);
+    }
+    render
+}
+
+/// A middleware which logs messages and durations of updates to the console.
+pub struct Logger {
+    console: ConsoleService,
+    model: bool,
+}
+
+impl Logger {
+    /// Creates a logger of messages.
+    pub fn new() -> Self {
+        Logger {
+            console: ConsoleService,
+            model: false,
+        }
+    }
+
+    /// Also logs the model after every update.
+    pub fn with_model(mut self) -> Self {
+        self.model = true;
+        self
+    }
+}
+
+impl Default for Logger {
+    fn default() -> Self {
+        Logger::new()
+    }
+}
+
+impl<MOD: Debug, MSG: Debug> Middleware<MOD, MSG> for Logger {
+    fn update(&mut self, model: &mut MOD, msg: MSG, next: &mut FnMut(&mut MOD, MSG)) {
+        let message = format!("{:?}", msg);
+        let started = html::now();
+        next(model, msg);
+        let elapsed = html::now() - started;
+        self.console.log(&format!("{} ({:.1} ms)", message, elapsed));
+        if self.model {
+            self.console.log(&format!("{:#?}", model));
+        }
+    }
+}
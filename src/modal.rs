@@ -0,0 +1,244 @@
+//! This module contains a stack of modals which are opened imperatively.
+//! A modal is a component which gets a callback to close itself with a result.
+//! A `ModalHost` which is rendered once in an app shows the stack in a portal,
+//! traps focus inside of the top modal and locks scrolling of the page:
+//!
+//! ```rust,ignore
+//! impl Modal for Confirm {
+//!     type Output = bool;
+//!
+//!     fn set_close(props: &mut ConfirmProps, close: Callback<bool>) {
+//!         props.onclose = close;
+//!     }
+//! }
+//!
+//! modal::open::<Confirm>(props, self.link.callback(|answer| Msg::Confirmed(answer)));
+//!
+//! html! { <ModalHost: /> }
+//! ```
+//!
+//! Callbacks get `None` when a modal is dismissed with the Escape key, a click
+//! on the backdrop or `modal::dismiss`.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use callback::Callback;
+use component::{Component, ComponentMount};
+use future::{self, Deferred};
+use html::AppSender;
+use services::Task;
+use virtual_dom::vcomp::Mount;
+
+/// A component which is opened as a modal.
+pub trait Modal: Component {
+    /// A result of the modal, like a chosen option.
+    type Output: 'static;
+
+    /// Puts a callback which closes the modal with a result to the properties.
+    fn set_close(props: &mut Self::Properties, close: Callback<Self::Output>);
+}
+
+/// Options of an opened modal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModalOptions {
+    /// The modal is dismissed with the Escape key or a click on the backdrop.
+    pub dismissible: bool,
+    /// Classes of the layer of the modal.
+    pub class: String,
+}
+
+impl Default for ModalOptions {
+    fn default() -> Self {
+        ModalOptions {
+            dismissible: true,
+            class: String::new(),
+        }
+    }
+}
+
+/// An id of an opened modal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ModalId(usize);
+
+/// An opened modal.
+pub(crate) struct Entry {
+    pub(crate) id: ModalId,
+    pub(crate) options: ModalOptions,
+    /// Creates a subtree of the component with its properties.
+    pub(crate) render: Box<Fn() -> Box<Mount>>,
+    on_dismiss: Callback<()>,
+}
+
+type Listener = Rc<Fn()>;
+
+#[derive(Default)]
+struct Stack {
+    entries: Vec<Rc<Entry>>,
+    next_id: usize,
+    listeners: Vec<(usize, Listener)>,
+    next_listener: usize,
+}
+
+thread_local! {
+    static STACK: RefCell<Stack> = RefCell::new(Stack::default());
+}
+
+/// Opens a modal over the others. The `callback` gets the result
+/// or `None` if the modal was dismissed.
+pub fn open<M>(props: M::Properties, callback: Callback<Option<M::Output>>) -> ModalId
+where
+    M: Modal,
+    M::Properties: Clone,
+{
+    open_with::<M>(ModalOptions::default(), props, callback)
+}
+
+/// Opens a modal with the options.
+pub fn open_with<M>(options: ModalOptions, props: M::Properties, callback: Callback<Option<M::Output>>) -> ModalId
+where
+    M: Modal,
+    M::Properties: Clone,
+{
+    let id = STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        let id = ModalId(stack.next_id);
+        stack.next_id += 1;
+        id
+    });
+    let mut props = props;
+    let closed = callback.clone();
+    M::set_close(&mut props, Callback::from(move |output| {
+        // A modal could be closed twice before it's unmounted
+        if remove(id).is_some() {
+            closed.emit(Some(output));
+        }
+    }));
+    let entry = Entry {
+        id,
+        options,
+        render: Box::new(move || Box::new(ComponentMount::<M>::new(props.clone())) as Box<Mount>),
+        on_dismiss: Callback::from(move |_| callback.emit(None)),
+    };
+    STACK.with(|stack| stack.borrow_mut().entries.push(Rc::new(entry)));
+    changed();
+    id
+}
+
+/// Opens a modal and returns a future of its result.
+pub fn open_async<M>(props: M::Properties) -> Deferred<Option<M::Output>>
+where
+    M: Modal,
+    M::Properties: Clone,
+{
+    let (resolver, deferred) = future::deferred();
+    open::<M>(props, resolver.into_callback());
+    deferred
+}
+
+/// Closes a modal without a result. Returns `false` if it was closed before.
+pub fn dismiss(id: ModalId) -> bool {
+    match remove(id) {
+        Some(entry) => {
+            entry.on_dismiss.emit(());
+            true
+        }
+        None => false,
+    }
+}
+
+/// Closes the top modal without a result.
+pub fn dismiss_top() -> bool {
+    top().map(dismiss).unwrap_or(false)
+}
+
+/// Returns the id of the top modal.
+pub fn top() -> Option<ModalId> {
+    STACK.with(|stack| stack.borrow().entries.last().map(|entry| entry.id))
+}
+
+/// Returns the number of opened modals.
+pub fn count() -> usize {
+    STACK.with(|stack| stack.borrow().entries.len())
+}
+
+/// Returns opened modals from the bottom to the top.
+pub(crate) fn entries() -> Vec<Rc<Entry>> {
+    STACK.with(|stack| stack.borrow().entries.clone())
+}
+
+fn remove(id: ModalId) -> Option<Rc<Entry>> {
+    let removed = STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        let position = stack.entries.iter().position(|entry| entry.id == id);
+        position.map(|position| stack.entries.remove(position))
+    });
+    if removed.is_some() {
+        changed();
+    }
+    removed
+}
+
+fn changed() {
+    let listeners: Vec<Listener> = STACK.with(|stack| {
+        stack.borrow().listeners.iter().map(|(_, listener)| listener.clone()).collect()
+    });
+    for listener in listeners {
+        listener();
+    }
+}
+
+/// A handle to stop listening of the stack.
+pub struct ModalHandle(Option<usize>);
+
+impl Task for ModalHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let id = self.0.take().expect("tried to cancel modal listener twice");
+        STACK.with(|stack| {
+            stack.borrow_mut().listeners.retain(|&(listener, _)| listener != id);
+        });
+    }
+}
+
+impl Drop for ModalHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+/// A service to listen of changes of the stack of modals.
+pub struct ModalService<MSG> {
+    sender: AppSender<MSG>,
+}
+
+impl<MSG: 'static> ModalService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self { sender }
+    }
+
+    /// Sends a message from the `converter` every time a modal is opened or closed.
+    pub fn listen<F>(&mut self, converter: F) -> ModalHandle
+    where
+        F: Fn() -> MSG + 'static,
+    {
+        let tx = self.sender.clone();
+        let listener = move || {
+            let msg = converter();
+            tx.clone().send(msg);
+        };
+        let id = STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            let id = stack.next_listener;
+            stack.next_listener += 1;
+            stack.listeners.push((id, Rc::new(listener)));
+            id
+        });
+        ModalHandle(Some(id))
+    }
+}
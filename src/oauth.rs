@@ -0,0 +1,422 @@
+//! This module contains the authorization code flow of OAuth 2.0 with PKCE
+//! for apps in a browser, which can't keep a client secret. An app sends
+//! a user to the authorization server and handles the redirect back with
+//! a route of the router:
+//!
+//! ```rust,ignore
+//! let config = OAuthConfig::new(
+//!     "https://id.example/authorize",
+//!     "https://id.example/token",
+//!     "my-app",
+//!     "https://app.example/callback",
+//! ).scope("openid").scope("profile");
+//!
+//! // A login button
+//! let url = self.oauth.authorize_url(&config);
+//! html! { <a href=url,>{ "Log in" }</a> }
+//!
+//! // A component of the `Route::Callback => "/callback"` route
+//! let handle = oauth.callback(&config, &history.location(), Msg::Tokens);
+//!
+//! Msg::Tokens(Ok(tokens)) => auth::login(tokens.into_session()),
+//! Msg::Tokens(Err(error)) => self.error = Some(error),
+//! ```
+//!
+//! The verifier, the state and the nonce of a flow are kept in the session
+//! storage until the redirect comes back, and a flow is completed once.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+use serde_json::{self, Value};
+use auth::Session;
+use format::{Json, Restorable, Storable};
+use html::AppSender;
+use router;
+use services::Task;
+use services::backend::{self, FetchBackend, RandomBackend, StorageBackend};
+use services::fetch::{Request, Response};
+use services::storage::Scope;
+
+const FLOW_KEY: &str = "oauth.flow";
+
+/// Options of an authorization server and a client.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OAuthConfig {
+    /// The authorization endpoint, a user is sent there.
+    pub authorize_url: String,
+    /// The token endpoint, a code is exchanged there.
+    pub token_url: String,
+    /// The id of the client.
+    pub client_id: String,
+    /// The url of the app which the server redirects back to.
+    pub redirect_uri: String,
+    /// Requested scopes. A nonce is checked for the `openid` scope.
+    pub scopes: Vec<String>,
+    /// Other parameters of the authorization url, like `audience` or `prompt`.
+    pub params: Vec<(String, String)>,
+}
+
+impl OAuthConfig {
+    /// Creates options without scopes.
+    pub fn new(authorize_url: &str, token_url: &str, client_id: &str, redirect_uri: &str) -> Self {
+        OAuthConfig {
+            authorize_url: authorize_url.to_owned(),
+            token_url: token_url.to_owned(),
+            client_id: client_id.to_owned(),
+            redirect_uri: redirect_uri.to_owned(),
+            scopes: Vec::new(),
+            params: Vec::new(),
+        }
+    }
+
+    /// Requests the scope too.
+    pub fn scope(mut self, scope: &str) -> Self {
+        self.scopes.push(scope.to_owned());
+        self
+    }
+
+    /// Adds a parameter to the authorization url.
+    pub fn param(mut self, name: &str, value: &str) -> Self {
+        self.params.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    fn is_openid(&self) -> bool {
+        self.scopes.iter().any(|scope| scope == "openid")
+    }
+}
+
+/// Tokens of a response of the token endpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tokens {
+    /// The token to access resources.
+    pub access_token: String,
+    /// The type of the token, usually `Bearer`.
+    pub token_type: String,
+    /// Seconds until the access token expires.
+    pub expires_in: Option<u64>,
+    /// The token to get new tokens.
+    pub refresh_token: Option<String>,
+    /// The ID token of OpenID Connect.
+    pub id_token: Option<String>,
+    /// Granted scopes if they differ from requested ones.
+    pub scope: Option<String>,
+}
+
+impl Tokens {
+    /// Returns a session of the tokens for the `auth` module.
+    pub fn into_session(self) -> Session {
+        let mut session = Session::new(self.access_token);
+        session.refresh_token = self.refresh_token;
+        match self.expires_in {
+            Some(seconds) => session.expires_in(::std::time::Duration::from_secs(seconds)),
+            None => session,
+        }
+    }
+
+    fn parse(value: &Value) -> Option<Self> {
+        let text = |name: &str| value[name].as_str().map(str::to_owned);
+        Some(Tokens {
+            access_token: text("access_token")?,
+            token_type: text("token_type").unwrap_or_else(|| "Bearer".into()),
+            expires_in: value["expires_in"].as_u64(),
+            refresh_token: text("refresh_token"),
+            id_token: text("id_token"),
+            scope: text("scope"),
+        })
+    }
+}
+
+/// Errors of a flow.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OAuthError {
+    /// The server or a user refused the authorization.
+    Denied {
+        /// The code of the error, like `access_denied`.
+        error: String,
+        /// A description for developers.
+        description: Option<String>,
+    },
+    /// There is no flow which waits for the redirect, it was completed
+    /// or started in another tab.
+    UnknownFlow,
+    /// The state of the redirect isn't the state of the flow.
+    StateMismatch,
+    /// The nonce of the ID token isn't the nonce of the flow.
+    NonceMismatch,
+    /// The redirect has no code.
+    MissingCode,
+    /// The token endpoint refused the code, with the status and the error code of the response.
+    Exchange(u16, Option<String>),
+    /// A response of the token endpoint isn't a valid response.
+    Malformed,
+}
+
+// The state, the code verifier and the nonce of a flow
+type Flow = (String, String, Option<String>);
+
+/// A handle of an exchange of a code. Implements `Task` and could be canceled.
+pub struct OAuthHandle(Option<Box<Task>>);
+
+/// A service to sign in with an authorization server.
+pub struct OAuthService<MSG> {
+    sender: AppSender<MSG>,
+    fetch: Rc<FetchBackend>,
+    storage: Rc<StorageBackend>,
+    random: Rc<RandomBackend>,
+}
+
+impl<MSG: 'static> OAuthService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self::with_backends(sender, backend::fetch(), backend::storage(), backend::random())
+    }
+
+    /// Creates a new service instance which uses the backends.
+    pub fn with_backends(
+        sender: AppSender<MSG>,
+        fetch: Rc<FetchBackend>,
+        storage: Rc<StorageBackend>,
+        random: Rc<RandomBackend>,
+    ) -> Self {
+        Self { sender, fetch, storage, random }
+    }
+
+    /// Starts a flow and returns the url of the authorization endpoint to send
+    /// a user to. A new flow replaces the previous one.
+    pub fn authorize_url(&mut self, config: &OAuthConfig) -> String {
+        let verifier = self.random_text(32);
+        let state = self.random_text(16);
+        let nonce = if config.is_openid() { Some(self.random_text(16)) } else { None };
+        let mut params = vec![
+            ("response_type", "code".to_owned()),
+            ("client_id", config.client_id.clone()),
+            ("redirect_uri", config.redirect_uri.clone()),
+            ("scope", config.scopes.join(" ")),
+            ("state", state.clone()),
+            ("code_challenge", code_challenge(&verifier)),
+            ("code_challenge_method", "S256".to_owned()),
+        ];
+        if let Some(ref nonce) = nonce {
+            params.push(("nonce", nonce.clone()));
+        }
+        let flow: Flow = (state, verifier, nonce);
+        let data = serde_json::to_string(&flow).expect("can't serialize a flow");
+        self.storage.set(Scope::Session, FLOW_KEY, &data);
+        let mut query = form(params.iter().map(|(name, value)| (*name, value.as_str())));
+        for (name, value) in &config.params {
+            query.push('&');
+            query.push_str(&form(vec![(name.as_str(), value.as_str())]));
+        }
+        let separator = if config.authorize_url.contains('?') { '&' } else { '?' };
+        format!("{}{}{}", config.authorize_url, separator, query)
+    }
+
+    /// Completes a flow with the location of the redirect: checks the state,
+    /// exchanges the code for tokens and sends a message from the `converter`
+    /// with them. Errors are sent too, a flow isn't reused after an error.
+    pub fn callback<F>(&mut self, config: &OAuthConfig, location: &str, converter: F) -> OAuthHandle
+    where
+        F: Fn(Result<Tokens, OAuthError>) -> MSG + 'static,
+    {
+        match self.exchange(config, location) {
+            Ok((request, nonce)) => {
+                let mut tx = self.sender.clone();
+                let callback = move |response: Response<Restorable>| {
+                    tx.send(converter(finish(response, nonce.as_ref())));
+                };
+                OAuthHandle(Some(self.fetch.fetch(request, Box::new(callback))))
+            }
+            Err(error) => {
+                self.sender.send(converter(Err(error)));
+                OAuthHandle(None)
+            }
+        }
+    }
+
+    /// Checks the redirect and builds a request of tokens.
+    fn exchange(&mut self, config: &OAuthConfig, location: &str) -> Result<(Request<Storable>, Option<String>), OAuthError> {
+        let flow = self.storage.get(Scope::Session, FLOW_KEY);
+        self.storage.remove(Scope::Session, FLOW_KEY);
+        let query: HashMap<String, String> = router::parse_query(router::query_of(location)).unwrap_or_default();
+        if let Some(error) = query.get("error") {
+            return Err(OAuthError::Denied {
+                error: error.clone(),
+                description: query.get("error_description").cloned(),
+            });
+        }
+        let Json(flow) = Json::<Result<Flow, ()>>::from(flow.ok_or_else(|| "no flow".into()));
+        let (state, verifier, nonce) = flow.map_err(|_| OAuthError::UnknownFlow)?;
+        if query.get("state") != Some(&state) {
+            return Err(OAuthError::StateMismatch);
+        }
+        let code = query.get("code").ok_or(OAuthError::MissingCode)?;
+        let body = form(vec![
+            ("grant_type", "authorization_code"),
+            ("code", code.as_str()),
+            ("redirect_uri", config.redirect_uri.as_str()),
+            ("client_id", config.client_id.as_str()),
+            ("code_verifier", verifier.as_str()),
+        ]);
+        // Tokens of a session aren't attached, so interceptors are skipped
+        let request = Request::post(config.token_url.as_str())
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(Some(body))
+            .expect("can't build a request of tokens");
+        Ok((request, nonce))
+    }
+
+    fn random_text(&self, length: usize) -> String {
+        let mut bytes = vec![0; length];
+        self.random.fill(&mut bytes);
+        base64url(&bytes)
+    }
+}
+
+/// Returns `true` if the location is the redirect of the config, like
+/// `/callback?code=...` for `https://app.example/callback`.
+pub fn is_callback(config: &OAuthConfig, location: &str) -> bool {
+    let uri = &config.redirect_uri;
+    let path = match uri.find("://") {
+        Some(scheme) => uri[scheme + 3..].find('/').map_or("/", |start| &uri[scheme + 3 + start..]),
+        None => uri.as_str(),
+    };
+    router::path_of(location) == router::path_of(path)
+}
+
+/// Returns the `S256` code challenge of a code verifier.
+pub fn code_challenge(verifier: &str) -> String {
+    base64url(&sha256(verifier.as_bytes()))
+}
+
+fn finish(response: Response<Restorable>, nonce: Option<&String>) -> Result<Tokens, OAuthError> {
+    let status = response.status().as_u16();
+    let body: Option<Value> = response.into_body().ok().and_then(|body| serde_json::from_str(&body).ok());
+    if !(200..300).contains(&status) {
+        let error = body.and_then(|body| body["error"].as_str().map(str::to_owned));
+        return Err(OAuthError::Exchange(status, error));
+    }
+    let tokens = body.as_ref().and_then(Tokens::parse).ok_or(OAuthError::Malformed)?;
+    if let Some(nonce) = nonce {
+        // The token comes from the token endpoint over TLS, so claims are read without its signature
+        let claims = tokens.id_token.as_ref()
+            .and_then(|token| token.split('.').nth(1))
+            .and_then(decode_base64url)
+            .and_then(|payload| serde_json::from_slice::<Value>(&payload).ok());
+        match claims {
+            Some(ref claims) if claims["nonce"].as_str() == Some(nonce.as_str()) => {}
+            Some(_) => return Err(OAuthError::NonceMismatch),
+            None if tokens.id_token.is_some() => return Err(OAuthError::Malformed),
+            None => {}
+        }
+    }
+    Ok(tokens)
+}
+
+fn form<'a, I: IntoIterator<Item = (&'a str, &'a str)>>(pairs: I) -> String {
+    let pairs: Vec<String> = pairs.into_iter()
+        .map(|(name, value)| format!("{}={}", router::encode(name), router::encode(value)))
+        .collect();
+    pairs.join("&")
+}
+
+const BASE64URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+fn base64url(bytes: &[u8]) -> String {
+    let mut text = String::new();
+    for chunk in bytes.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0u32, |bits, (index, &byte)| bits | u32::from(byte) << (16 - index * 8));
+        for index in 0..=chunk.len() {
+            text.push(BASE64URL[(bits >> (18 - index * 6)) as usize & 63] as char);
+        }
+    }
+    text
+}
+
+fn decode_base64url(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let (mut bits, mut count) = (0u32, 0);
+    for c in text.bytes().filter(|&c| c != b'=') {
+        let value = BASE64URL.iter().position(|&other| other == c)? as u32;
+        bits = (bits << 6 | value) & 0xFFFF;
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            bytes.push((bits >> count) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+const ROUNDS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Returns the SHA-256 digest of the data, browsers compute it only asynchronously.
+fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+    ];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+    for block in message.chunks(64) {
+        let mut words = [0u32; 64];
+        for (word, bytes) in words.iter_mut().zip(block.chunks(4)) {
+            *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        for index in 16..64 {
+            let (early, late) = (words[index - 15], words[index - 2]);
+            let s0 = early.rotate_right(7) ^ early.rotate_right(18) ^ (early >> 3);
+            let s1 = late.rotate_right(17) ^ late.rotate_right(19) ^ (late >> 10);
+            words[index] = words[index - 16].wrapping_add(s0).wrapping_add(words[index - 7]).wrapping_add(s1);
+        }
+        let mut v = state;
+        for (round, word) in ROUNDS.iter().zip(words.iter()) {
+            let s1 = v[4].rotate_right(6) ^ v[4].rotate_right(11) ^ v[4].rotate_right(25);
+            let choice = (v[4] & v[5]) ^ (!v[4] & v[6]);
+            let first = v[7].wrapping_add(s1).wrapping_add(choice).wrapping_add(*round).wrapping_add(*word);
+            let s0 = v[0].rotate_right(2) ^ v[0].rotate_right(13) ^ v[0].rotate_right(22);
+            let majority = (v[0] & v[1]) ^ (v[0] & v[2]) ^ (v[1] & v[2]);
+            let second = s0.wrapping_add(majority);
+            v = [first.wrapping_add(second), v[0], v[1], v[2], v[3].wrapping_add(first), v[4], v[5], v[6]];
+        }
+        for (value, add) in state.iter_mut().zip(v.iter()) {
+            *value = value.wrapping_add(*add);
+        }
+    }
+    let mut digest = [0; 32];
+    for (bytes, value) in digest.chunks_mut(4).zip(state.iter()) {
+        bytes.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
+
+impl Task for OAuthHandle {
+    fn is_active(&self) -> bool {
+        self.0.as_ref().map_or(false, |task| task.is_active())
+    }
+
+    fn cancel(&mut self) {
+        let mut task = self.0.take().expect("tried to cancel an exchange of a code twice");
+        task.cancel();
+    }
+}
+
+impl Drop for OAuthHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! This module contains a panic hook which shows a panic in the page.
+//! An app stops responding after a panic, so the hook renders an overlay
+//! with the message instead of leaving the last frame frozen. Panics are
+//! published to sinks of `errors` too:
+//!
+//! ```rust,ignore
+//! fn main() {
+//!     yew::initialize();
+//!     panic_hook::install_with(Callback::from(|report: PanicReport| {
+//!         send_to_server(report.to_string());
+//!     }));
+//!     // ...
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::fmt;
+use std::panic;
+use callback::Callback;
+use errors::{self, ErrorKind, ErrorReport};
+
+/// A description of a panic.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PanicReport {
+    /// A message of the panic.
+    pub message: String,
+    /// A file and a line where the panic happened.
+    pub location: Option<String>,
+    /// A backtrace if it was captured. Browsers could capture nothing.
+    pub backtrace: Option<String>,
+}
+
+impl fmt::Display for PanicReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "panicked")?;
+        if let Some(ref location) = self.location {
+            write!(f, " at {}", location)?;
+        }
+        write!(f, ": {}", self.message)?;
+        if let Some(ref backtrace) = self.backtrace {
+            write!(f, "\n\n{}", backtrace)?;
+        }
+        Ok(())
+    }
+}
+
+thread_local! {
+    static REPORTER: RefCell<Option<Callback<PanicReport>>> = RefCell::new(None);
+}
+
+/// Installs the hook which shows an overlay with a panic.
+/// The previous hook is called too, so the panic is printed to the console.
+pub fn install() {
+    set_hook();
+}
+
+/// Same as `install`, but also sends reports of panics to the `callback`.
+pub fn install_with(callback: Callback<PanicReport>) {
+    REPORTER.with(|reporter| *reporter.borrow_mut() = Some(callback));
+    set_hook();
+}
+
+fn set_hook() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        previous(info);
+        let payload = info.payload();
+        let message = payload.downcast_ref::<&str>().map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".into());
+        let location = info.location()
+            .map(|location| format!("{}:{}:{}", location.file(), location.line(), location.column()));
+        // The stack of JS has frames of WebAssembly functions too
+        let backtrace = js! {
+            var stack = new Error().stack;
+            return stack ? stack : null;
+        };
+        let backtrace = backtrace.into_string();
+        let report = PanicReport {
+            message,
+            location,
+            backtrace,
+        };
+        show_overlay(&report);
+        let origin = report.location.clone().unwrap_or_else(|| "unknown".into());
+        errors::report(ErrorReport::new(ErrorKind::Panic, origin, report.message.clone()));
+        let callback = REPORTER.with(|reporter| {
+            reporter.try_borrow().ok().and_then(|reporter| reporter.clone())
+        });
+        if let Some(callback) = callback {
+            callback.emit(report);
+        }
+    }));
+}
+
+/// Renders the report over the page. The overlay of a previous panic is replaced.
+fn show_overlay(report: &PanicReport) {
+    let text = report.to_string();
+    js! { @(no_return)
+        var overlay = document.getElementById("yew-panic-overlay");
+        if (overlay) {
+            overlay.remove();
+        }
+        overlay = document.createElement("div");
+        overlay.id = "yew-panic-overlay";
+        overlay.style.cssText = "position:fixed;top:0;left:0;right:0;bottom:0;z-index:2147483647;"
+            + "overflow:auto;padding:2em;background:rgba(20,0,0,0.92);color:#ffd6d6;"
+            + "font:14px/1.5 monospace;";
+        var title = document.createElement("h2");
+        title.textContent = "The app crashed";
+        var details = document.createElement("pre");
+        details.style.whiteSpace = "pre-wrap";
+        details.textContent = @{text};
+        overlay.appendChild(title);
+        overlay.appendChild(details);
+        (document.body || document.documentElement).appendChild(overlay);
+    }
+}
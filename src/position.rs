@@ -0,0 +1,276 @@
+//! This module contains a positioning engine for floating elements like
+//! tooltips, popovers and menus. It places an element at a side of an anchor,
+//! flips it to the opposite side if it doesn't fit and shifts it along the
+//! side to keep it inside of a boundary of the viewport and scroll containers:
+//!
+//! ```rust,ignore
+//! let options = PositionOptions::new(Placement::new(Side::Bottom, Align::Start));
+//! let position = compute(&layout, &options);
+//! html! { <div class="menu", style=position.style(),>{ "..." }</div> }
+//! ```
+//!
+//! Layouts are measured by `LayoutService`, and `Tooltip` and `Popover`
+//! components use both.
+
+/// A rectangle in pixels relative to the viewport.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Rect {
+    /// The left edge.
+    pub x: f64,
+    /// The top edge.
+    pub y: f64,
+    /// The width.
+    pub width: f64,
+    /// The height.
+    pub height: f64,
+}
+
+impl Rect {
+    /// Creates a rectangle.
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Rect { x, y, width, height }
+    }
+
+    /// Returns the right edge.
+    pub fn right(&self) -> f64 {
+        self.x + self.width
+    }
+
+    /// Returns the bottom edge.
+    pub fn bottom(&self) -> f64 {
+        self.y + self.height
+    }
+
+    /// Returns the part which is inside of both rectangles. It's empty
+    /// if they don't overlap.
+    pub fn intersect(&self, other: &Rect) -> Rect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        Rect {
+            x,
+            y,
+            width: (self.right().min(other.right()) - x).max(0.0),
+            height: (self.bottom().min(other.bottom()) - y).max(0.0),
+        }
+    }
+}
+
+/// A side of an anchor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// Above the anchor.
+    Top,
+    /// To the right of the anchor.
+    Right,
+    /// Below the anchor.
+    Bottom,
+    /// To the left of the anchor.
+    Left,
+}
+
+impl Side {
+    /// Returns the opposite side.
+    pub fn opposite(self) -> Side {
+        match self {
+            Side::Top => Side::Bottom,
+            Side::Right => Side::Left,
+            Side::Bottom => Side::Top,
+            Side::Left => Side::Right,
+        }
+    }
+
+    /// Returns the name of the side for `data-side` attributes of styles.
+    pub fn name(self) -> &'static str {
+        match self {
+            Side::Top => "top",
+            Side::Right => "right",
+            Side::Bottom => "bottom",
+            Side::Left => "left",
+        }
+    }
+
+    fn is_vertical(self) -> bool {
+        self == Side::Top || self == Side::Bottom
+    }
+}
+
+/// An alignment of an element along a side of an anchor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    /// Edges of starts are aligned, like left edges below an anchor.
+    Start,
+    /// Centers are aligned.
+    Center,
+    /// Edges of ends are aligned.
+    End,
+}
+
+/// A place of an element next to an anchor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    /// The side of the anchor.
+    pub side: Side,
+    /// The alignment along the side.
+    pub align: Align,
+}
+
+impl Placement {
+    /// Creates a placement.
+    pub fn new(side: Side, align: Align) -> Self {
+        Placement { side, align }
+    }
+}
+
+/// Options of positioning.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionOptions {
+    /// The preferred placement.
+    pub placement: Placement,
+    /// A gap between the anchor and the element.
+    pub offset: f64,
+    /// A distance which is kept from edges of the boundary.
+    pub padding: f64,
+    /// Moves the element to the opposite side if it doesn't fit.
+    pub flip: bool,
+    /// Moves the element along the side to keep it inside of the boundary.
+    pub shift: bool,
+}
+
+impl PositionOptions {
+    /// Creates options with a gap of 8 pixels which flip and shift the element.
+    pub fn new(placement: Placement) -> Self {
+        PositionOptions {
+            placement,
+            offset: 8.0,
+            padding: 8.0,
+            flip: true,
+            shift: true,
+        }
+    }
+}
+
+/// Rectangles which an element is positioned with.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Layout {
+    /// The anchor.
+    pub anchor: Rect,
+    /// The floating element, only its size is used.
+    pub floating: Rect,
+    /// The visible area: the viewport clipped by scroll containers of the anchor.
+    pub boundary: Rect,
+}
+
+/// A computed position of an element.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    /// The left edge relative to the viewport, for `position: fixed`.
+    pub x: f64,
+    /// The top edge relative to the viewport.
+    pub y: f64,
+    /// The placement after flipping.
+    pub placement: Placement,
+    /// The offset of an arrow along the side from the start of the element,
+    /// which points to the center of the anchor.
+    pub arrow: f64,
+    /// The anchor is outside of the boundary, like after a container was
+    /// scrolled, so the element could be hidden.
+    pub detached: bool,
+}
+
+/// A style of a floating element which isn't measured yet: it has its size,
+/// but it isn't seen.
+pub const UNMEASURED_STYLE: &str = "position: fixed; left: 0; top: 0; visibility: hidden;";
+
+impl Position {
+    /// Returns an inline style which puts a fixed element at the position.
+    /// The offset of an arrow is set to the `--arrow-offset` variable
+    /// and a detached element is hidden.
+    pub fn style(&self) -> String {
+        let mut style = format!(
+            "position: fixed; left: {}px; top: {}px; --arrow-offset: {}px;",
+            self.x.round(), self.y.round(), self.arrow.round(),
+        );
+        if self.detached {
+            style.push_str(" visibility: hidden;");
+        }
+        style
+    }
+}
+
+fn place(layout: &Layout, placement: Placement, offset: f64) -> (f64, f64) {
+    let (anchor, floating) = (&layout.anchor, &layout.floating);
+    let cross = |start: f64, size: f64, own: f64| match placement.align {
+        Align::Start => start,
+        Align::Center => start + (size - own) / 2.0,
+        Align::End => start + size - own,
+    };
+    match placement.side {
+        Side::Top => (cross(anchor.x, anchor.width, floating.width), anchor.y - floating.height - offset),
+        Side::Bottom => (cross(anchor.x, anchor.width, floating.width), anchor.bottom() + offset),
+        Side::Left => (anchor.x - floating.width - offset, cross(anchor.y, anchor.height, floating.height)),
+        Side::Right => (anchor.right() + offset, cross(anchor.y, anchor.height, floating.height)),
+    }
+}
+
+/// Returns how many pixels of the element at the point are outside of the
+/// boundary at the side, or a negative free space.
+fn overflow(layout: &Layout, side: Side, point: (f64, f64), padding: f64) -> f64 {
+    let (boundary, floating) = (&layout.boundary, &layout.floating);
+    match side {
+        Side::Top => boundary.y + padding - point.1,
+        Side::Bottom => point.1 + floating.height - (boundary.bottom() - padding),
+        Side::Left => boundary.x + padding - point.0,
+        Side::Right => point.0 + floating.width - (boundary.right() - padding),
+    }
+}
+
+fn clamp(value: f64, min: f64, max: f64) -> f64 {
+    // The element is larger than the space, so its start is kept visible
+    if max < min {
+        min
+    } else {
+        value.max(min).min(max)
+    }
+}
+
+/// Computes the position of the floating element of the layout.
+pub fn compute(layout: &Layout, options: &PositionOptions) -> Position {
+    let mut placement = options.placement;
+    let mut point = place(layout, placement, options.offset);
+    if options.flip {
+        let wanted = overflow(layout, placement.side, point, options.padding);
+        if wanted > 0.0 {
+            let opposite = Placement { side: placement.side.opposite(), ..placement };
+            let other = place(layout, opposite, options.offset);
+            // The side with more space wins if neither fits
+            if overflow(layout, opposite.side, other, options.padding) < wanted {
+                placement = opposite;
+                point = other;
+            }
+        }
+    }
+    let (anchor, floating, boundary) = (&layout.anchor, &layout.floating, &layout.boundary);
+    if options.shift {
+        let padding = options.padding;
+        if placement.side.is_vertical() {
+            point.0 = clamp(point.0, boundary.x + padding, boundary.right() - padding - floating.width);
+        } else {
+            point.1 = clamp(point.1, boundary.y + padding, boundary.bottom() - padding - floating.height);
+        }
+    }
+    let arrow = if placement.side.is_vertical() {
+        clamp(anchor.x + anchor.width / 2.0 - point.0, 0.0, floating.width)
+    } else {
+        clamp(anchor.y + anchor.height / 2.0 - point.1, 0.0, floating.height)
+    };
+    // Edges are compared, because anchors could be points without a size
+    let detached = anchor.bottom() < boundary.y || anchor.y > boundary.bottom()
+        || anchor.right() < boundary.x || anchor.x > boundary.right();
+    Position {
+        x: point.0,
+        y: point.1,
+        placement,
+        arrow,
+        detached,
+    }
+}
@@ -0,0 +1,156 @@
+//! This module contains hints which fetch resources and data of a page
+//! before a user opens it, so navigation feels instant. `Link` prefetches
+//! its route when a pointer enters it, or when it's scrolled into view with
+//! `prefetch_visible`: the chunk of the route and the data which is
+//! registered for it with `register`:
+//!
+//! ```rust,ignore
+//! prefetch::register(|route: &Route| {
+//!     if let Route::Post { id } = *route {
+//!         query::prefetch_fetch(format!("posts/{}", id), move || post_request(id), parse_post);
+//!     }
+//! });
+//!
+//! prefetch::preload("/fonts/inter.woff2", Destination::Font);
+//! ```
+//!
+//! Data goes to the query cache, so the page gets it at once or shares
+//! the running request. Hints are `<link>` tags of `head`, every URL is
+//! hinted once.
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use router::Routable;
+use services::backend;
+
+/// A kind of a hint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rel {
+    /// A resource of the current page, which is fetched at once.
+    Preload,
+    /// A resource of a next page, which is fetched when a browser is idle.
+    Prefetch,
+}
+
+impl Rel {
+    /// Returns the value of the `rel` attribute.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Rel::Preload => "preload",
+            Rel::Prefetch => "prefetch",
+        }
+    }
+}
+
+/// A kind of a hinted resource, a browser requests it like the tag which uses it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Destination {
+    /// A script, like a chunk of a route.
+    Script,
+    /// A stylesheet.
+    Style,
+    /// A font, which is requested with CORS.
+    Font,
+    /// An image.
+    Image,
+    /// A response of a fetch, which is requested with CORS.
+    Fetch,
+    /// A page.
+    Document,
+}
+
+impl Destination {
+    /// Returns the value of the `as` attribute.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Destination::Script => "script",
+            Destination::Style => "style",
+            Destination::Font => "font",
+            Destination::Image => "image",
+            Destination::Fetch => "fetch",
+            Destination::Document => "document",
+        }
+    }
+
+    /// Returns `true` if a browser requests the resource with CORS, so
+    /// the hint needs the `crossorigin` attribute to be used.
+    pub fn is_cors(self) -> bool {
+        self == Destination::Font || self == Destination::Fetch
+    }
+}
+
+/// A hint which a `HeadBackend` inserts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hint {
+    /// The kind of the hint.
+    pub rel: Rel,
+    /// The URL of the resource.
+    pub href: String,
+    /// The kind of the resource.
+    pub destination: Destination,
+}
+
+type Prefetcher<R> = Rc<Fn(&R)>;
+
+thread_local! {
+    static HINTED: RefCell<HashSet<(Rel, String)>> = RefCell::new(HashSet::new());
+    static PREFETCHERS: RefCell<HashMap<TypeId, Rc<Any>>> = RefCell::new(HashMap::new());
+}
+
+/// Asks a browser to fetch a resource of the current page at once,
+/// like a font of a stylesheet which would be found late.
+pub fn preload(url: &str, destination: Destination) {
+    hint(Rel::Preload, url, destination);
+}
+
+/// Asks a browser to fetch a resource of a next page when it's idle.
+pub fn prefetch(url: &str, destination: Destination) {
+    hint(Rel::Prefetch, url, destination);
+}
+
+fn hint(rel: Rel, url: &str, destination: Destination) {
+    let new = HINTED.with(|hinted| hinted.borrow_mut().insert((rel, url.to_owned())));
+    if new {
+        backend::head().insert_hint(&Hint {
+            rel,
+            href: url.to_owned(),
+            destination,
+        });
+    }
+}
+
+/// Registers a function which prefetches data of routes, like queries of
+/// their pages. It replaces a function registered before for the type.
+pub fn register<R, F>(prefetcher: F)
+where
+    R: Routable,
+    F: Fn(&R) + 'static,
+{
+    let prefetcher: Prefetcher<R> = Rc::new(prefetcher);
+    PREFETCHERS.with(|prefetchers| {
+        prefetchers.borrow_mut().insert(TypeId::of::<R>(), Rc::new(prefetcher));
+    });
+}
+
+/// Prefetches the chunk of the route and calls the registered function
+/// for its data. A loaded chunk is got from the cache of a browser.
+pub fn route<R: Routable>(route: &R) {
+    if let Some(url) = route.chunk() {
+        prefetch(url, Destination::Script);
+    }
+    if let Some(prefetcher) = prefetcher::<R>() {
+        prefetcher(route);
+    }
+}
+
+/// Returns `true` if `route` has anything to prefetch.
+pub(crate) fn is_prefetchable<R: Routable>(route: &R) -> bool {
+    route.chunk().is_some() || prefetcher::<R>().is_some()
+}
+
+fn prefetcher<R: Routable>() -> Option<Prefetcher<R>> {
+    let prefetcher = PREFETCHERS.with(|prefetchers| prefetchers.borrow().get(&TypeId::of::<R>()).cloned())?;
+    prefetcher.downcast::<Prefetcher<R>>().ok().map(|prefetcher| (*prefetcher).clone())
+}
@@ -0,0 +1,176 @@
+//! This module contains a profiler of renders. It's compiled with the
+//! `profiler` feature only, which is meant for debug builds.
+//!
+//! Every render of an app or a component is measured in two phases: `view`
+//! builds a virtual tree and `patch` diffs it with the previous one and
+//! changes the DOM, which happens in one pass. Times are aggregated by
+//! component types and don't include nested components, so a slow child
+//! doesn't make its parent look slow:
+//!
+//! ```rust,ignore
+//! // After using a slow page for a while
+//! profiler::print();
+//! let hottest = &profiler::report()[0];
+//! profiler::reset();
+//! ```
+//!
+//! With the `devtools` feature `profiler::publish()` posts the report to
+//! an inspector, and it replies to `{ type: "profile" }` requests too.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::Duration;
+use serde_json::{Map, Value};
+use services::backend;
+
+/// A phase of a render.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Phase {
+    View,
+    Patch,
+}
+
+/// Aggregated times of renders of a component type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    /// The type name of the component, or `app` for the root of an app.
+    pub name: &'static str,
+    /// How many times views were built.
+    pub renders: usize,
+    /// The time spent in `view`.
+    pub view: Duration,
+    /// The time spent in diffs and patches of the DOM.
+    pub patch: Duration,
+    /// The longest render, both phases together.
+    pub slowest: Duration,
+}
+
+impl Profile {
+    /// Returns the time of both phases.
+    pub fn total(&self) -> Duration {
+        self.view + self.patch
+    }
+
+    /// Returns the average time of a render.
+    pub fn average(&self) -> Duration {
+        match self.renders {
+            0 => Duration::from_secs(0),
+            renders => self.total() / renders as u32,
+        }
+    }
+}
+
+struct Open {
+    start: Duration,
+    nested: Duration,
+}
+
+#[derive(Default)]
+struct Profiler {
+    profiles: HashMap<&'static str, Profile>,
+    open: Vec<Open>,
+    /// Times of views of renders which aren't patched yet.
+    rendering: HashMap<&'static str, Duration>,
+}
+
+thread_local! {
+    static PROFILER: RefCell<Profiler> = RefCell::new(Profiler::default());
+}
+
+/// Measures the phase of a render until the guard is dropped.
+pub(crate) fn measure(name: &'static str, phase: Phase) -> Measure {
+    let start = backend::timers().monotonic();
+    PROFILER.with(|profiler| profiler.borrow_mut().open.push(Open { start, nested: Duration::from_secs(0) }));
+    Measure { name, phase }
+}
+
+/// A guard of a measured phase.
+pub(crate) struct Measure {
+    name: &'static str,
+    phase: Phase,
+}
+
+impl Drop for Measure {
+    fn drop(&mut self) {
+        let now = backend::timers().monotonic();
+        PROFILER.with(|profiler| {
+            let profiler = &mut *profiler.borrow_mut();
+            let open = match profiler.open.pop() {
+                Some(open) => open,
+                None => return,
+            };
+            let elapsed = now.checked_sub(open.start).unwrap_or_default();
+            // A parent doesn't count the time of nested components
+            if let Some(parent) = profiler.open.last_mut() {
+                parent.nested += elapsed;
+            }
+            let own = elapsed.checked_sub(open.nested).unwrap_or_default();
+            let name = self.name;
+            let profile = profiler.profiles.entry(name).or_insert_with(|| Profile {
+                name,
+                renders: 0,
+                view: Duration::from_secs(0),
+                patch: Duration::from_secs(0),
+                slowest: Duration::from_secs(0),
+            });
+            match self.phase {
+                Phase::View => {
+                    profile.renders += 1;
+                    profile.view += own;
+                    profiler.rendering.insert(name, own);
+                }
+                Phase::Patch => {
+                    profile.patch += own;
+                    let render = profiler.rendering.remove(name).unwrap_or_default() + own;
+                    profile.slowest = profile.slowest.max(render);
+                }
+            }
+        });
+    }
+}
+
+/// Returns profiles of component types from the slowest in total.
+pub fn report() -> Vec<Profile> {
+    let mut profiles: Vec<Profile> = PROFILER.with(|profiler| profiler.borrow().profiles.values().cloned().collect());
+    profiles.sort_by(|a, b| b.total().cmp(&a.total()).then_with(|| a.name.cmp(b.name)));
+    profiles
+}
+
+/// Forgets measured renders, to profile an interaction alone.
+pub fn reset() {
+    PROFILER.with(|profiler| profiler.borrow_mut().profiles.clear());
+}
+
+fn ms(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 1_000_000.0).round() / 1000.0
+}
+
+/// Returns the report as JSON rows with times in milliseconds.
+pub(crate) fn rows() -> Value {
+    let rows = report().into_iter().map(|profile| {
+        let mut row = Map::new();
+        row.insert("component".into(), Value::from(profile.name));
+        row.insert("renders".into(), Value::from(profile.renders));
+        row.insert("total".into(), Value::from(ms(profile.total())));
+        row.insert("view".into(), Value::from(ms(profile.view)));
+        row.insert("patch".into(), Value::from(ms(profile.patch)));
+        row.insert("average".into(), Value::from(ms(profile.average())));
+        row.insert("slowest".into(), Value::from(ms(profile.slowest)));
+        Value::Object(row)
+    }).collect();
+    Value::Array(rows)
+}
+
+/// Prints the report to the console as a table.
+pub fn print() {
+    let rows = rows().to_string();
+    js! { @(no_return)
+        console.table(JSON.parse(@{rows}));
+    }
+}
+
+/// Posts the report to an inspector as a `profile` event.
+#[cfg(feature = "devtools")]
+pub fn publish() {
+    ::devtools::post("profile", rows());
+}
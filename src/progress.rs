@@ -0,0 +1,168 @@
+//! This module contains a registry of in-flight work, so an app shows one
+//! loading bar for all of it. Fetches, websocket connections and timeouts
+//! are counted while they're pending if they're started inside of `track`,
+//! or every fetch is counted after `track_fetches(true)`:
+//!
+//! ```rust,ignore
+//! progress::track_fetches(true);
+//! html! { <ProgressBar: /> }
+//!
+//! let handle = progress::track(|| self.timeout.spawn(Duration::from_secs(2), || Msg::Retry));
+//! ```
+//!
+//! Other work, like a promise, holds a `Pending` of `progress::begin()`
+//! until it's finished. Canceled tasks stop being counted.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use html::AppSender;
+use services::Task;
+
+type Listener = Rc<Fn(usize)>;
+
+#[derive(Default)]
+struct Registry {
+    in_flight: Cell<usize>,
+    tracking: Cell<usize>,
+    fetches: Cell<bool>,
+    listeners: RefCell<Vec<(usize, Listener)>>,
+    next_listener: Cell<usize>,
+}
+
+thread_local! {
+    static REGISTRY: Registry = Registry::default();
+}
+
+/// A kind of tracked work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    Fetch,
+    WebSocket,
+    Timeout,
+}
+
+/// A piece of in-flight work. It's counted until it's dropped.
+pub struct Pending(());
+
+impl Drop for Pending {
+    fn drop(&mut self) {
+        let count = REGISTRY.with(|registry| {
+            let count = registry.in_flight.get().saturating_sub(1);
+            registry.in_flight.set(count);
+            count
+        });
+        changed(count);
+    }
+}
+
+/// Starts counting work until the returned `Pending` is dropped.
+pub fn begin() -> Pending {
+    let count = REGISTRY.with(|registry| {
+        let count = registry.in_flight.get() + 1;
+        registry.in_flight.set(count);
+        count
+    });
+    changed(count);
+    Pending(())
+}
+
+/// Counts fetches, websocket connections and timeouts which are started
+/// by the function while they're pending.
+pub fn track<T, F: FnOnce() -> T>(start: F) -> T {
+    REGISTRY.with(|registry| registry.tracking.set(registry.tracking.get() + 1));
+    // A guard restores the flag if the function panics
+    struct Restore;
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            REGISTRY.with(|registry| registry.tracking.set(registry.tracking.get() - 1));
+        }
+    }
+    let _restore = Restore;
+    start()
+}
+
+/// Counts every fetch if it's `true`, so a loading bar needs no code per request.
+pub fn track_fetches(enabled: bool) {
+    REGISTRY.with(|registry| registry.fetches.set(enabled));
+}
+
+/// Returns how much work is in flight.
+pub fn in_flight() -> usize {
+    REGISTRY.with(|registry| registry.in_flight.get())
+}
+
+/// Returns a `Pending` if work of the kind which is started now is tracked.
+pub(crate) fn pending(kind: Kind) -> Option<Pending> {
+    let tracked = REGISTRY.with(|registry| {
+        registry.tracking.get() > 0 || (kind == Kind::Fetch && registry.fetches.get())
+    });
+    if tracked {
+        Some(begin())
+    } else {
+        None
+    }
+}
+
+fn changed(count: usize) {
+    let listeners: Vec<Listener> = REGISTRY.with(|registry| {
+        registry.listeners.borrow().iter().map(|(_, listener)| listener.clone()).collect()
+    });
+    for listener in listeners {
+        listener(count);
+    }
+}
+
+/// A handle to stop listening of in-flight work.
+pub struct ProgressHandle(Option<usize>);
+
+impl Task for ProgressHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let id = self.0.take().expect("tried to cancel progress listener twice");
+        REGISTRY.with(|registry| {
+            registry.listeners.borrow_mut().retain(|&(listener, _)| listener != id);
+        });
+    }
+}
+
+impl Drop for ProgressHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+/// A service to listen of the count of in-flight work.
+pub struct ProgressService<MSG> {
+    sender: AppSender<MSG>,
+}
+
+impl<MSG: 'static> ProgressService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self { sender }
+    }
+
+    /// Sends a message with the count every time work starts or finishes.
+    pub fn listen<F>(&mut self, converter: F) -> ProgressHandle
+    where
+        F: Fn(usize) -> MSG + 'static,
+    {
+        let tx = self.sender.clone();
+        let listener = move |count| {
+            let msg = converter(count);
+            tx.clone().send(msg);
+        };
+        let id = REGISTRY.with(|registry| {
+            let id = registry.next_listener.get();
+            registry.next_listener.set(id + 1);
+            registry.listeners.borrow_mut().push((id, Rc::new(listener)));
+            id
+        });
+        ProgressHandle(Some(id))
+    }
+}
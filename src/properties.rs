@@ -0,0 +1,131 @@
+//! This module contains checks of properties which derive `Properties`.
+//! Fields of such properties are required unless they have defaults, and
+//! `html!` fails to compile if a required property isn't set:
+//!
+//! ```rust,ignore
+//! /// Properties of a dialog.
+//! #[derive(Properties)]
+//! pub struct DialogProps {
+//!     /// The title is required.
+//!     pub title: String,
+//!     #[prop_or(true)]
+//!     pub closable: bool,
+//!     #[prop_or_default]
+//!     pub onclose: Option<Callback<()>>,
+//! }
+//!
+//! html! { <Dialog: title="Delete the file?", /> }
+//! html! { <Dialog: closable=false, /> } // error: `Contains<title, _>` isn't implemented
+//! ```
+//!
+//! The derive implements `Default` with defaults of optional fields, and types of
+//! required fields have to implement `Default` too, because a component receives
+//! default properties which `html!` changes field by field.
+//! Properties of other components aren't checked.
+
+use std::marker::PhantomData;
+
+/// Properties which derive `Properties`.
+#[doc(hidden)]
+pub trait Declared {
+    /// A structure with fields of properties which have types of markers.
+    type Fields;
+    /// A list of markers of required fields.
+    type Required;
+}
+
+/// An empty list of markers.
+#[doc(hidden)]
+pub struct Nil;
+
+/// A list of markers with a head.
+#[doc(hidden)]
+pub struct Cons<H, T>(PhantomData<(H, T)>);
+
+/// The marker of optional fields.
+#[doc(hidden)]
+pub struct Optional;
+
+/// The index of a marker in the head of a list.
+#[doc(hidden)]
+pub struct Here;
+
+/// The index of a marker in the tail of a list.
+#[doc(hidden)]
+pub struct There<I>(PhantomData<I>);
+
+/// A list of set fields which contains the marker of a field. Markers are
+/// named like fields, so errors name the required property which isn't set.
+#[doc(hidden)]
+pub trait Contains<T, I> {}
+
+impl<T, Tail> Contains<T, Here> for Cons<T, Tail> {}
+
+impl<T, H, Tail, I> Contains<T, There<I>> for Cons<H, Tail> where Tail: Contains<T, I> {}
+
+/// A list of set fields which contains all markers of a list of required fields.
+#[doc(hidden)]
+pub trait ContainsAll<L, I> {}
+
+impl<S> ContainsAll<Nil, Here> for S {}
+
+impl<S, H, T, I, Is> ContainsAll<Cons<H, T>, (I, Is)> for S where S: Contains<H, I> + ContainsAll<T, Is> {}
+
+/// A probe of properties of a component which `html!` uses to record set fields.
+#[doc(hidden)]
+pub struct Probe<P>(PhantomData<P>);
+
+impl<P> Probe<P> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Probe(PhantomData)
+    }
+}
+
+/// Checks properties which derive `Properties`.
+#[doc(hidden)]
+pub trait Checked {
+    type Fields;
+    type Required;
+
+    fn fields(&self) -> Probe<Self::Fields>;
+
+    fn verify<S, I>(&self, set: S) where S: ContainsAll<Self::Required, I>;
+}
+
+impl<P: Declared> Checked for Probe<P> {
+    type Fields = P::Fields;
+    type Required = P::Required;
+
+    fn fields(&self) -> Probe<P::Fields> {
+        Probe(PhantomData)
+    }
+
+    fn verify<S, I>(&self, _: S) where S: ContainsAll<P::Required, I> {}
+}
+
+/// Accepts properties of other components. Method calls prefer `Checked`
+/// and fall back to it for types which don't derive `Properties`.
+#[doc(hidden)]
+pub trait Unchecked {
+    type Fields;
+
+    fn fields(&self) -> Probe<Self::Fields>;
+
+    fn verify<S>(&self, _: S) {}
+}
+
+impl<P> Unchecked for &Probe<P> {
+    type Fields = P;
+
+    fn fields(&self) -> Probe<P> {
+        Probe(PhantomData)
+    }
+}
+
+/// Adds the marker of a field to a list of set fields.
+#[doc(hidden)]
+pub fn set<S, F, T>(set: S, _: &Probe<F>, _: fn(&F) -> &T) -> Cons<T, S> {
+    drop(set);
+    Cons(PhantomData)
+}
@@ -0,0 +1,740 @@
+//! This module contains a cache of async data which is shared by components.
+//! A query is declared with a key and a source, like a fetch or a future.
+//! Components which use the same key share one request and one cached value,
+//! and they're rendered again when the value changes:
+//!
+//! ```rust,ignore
+//! fn create(props: Self::Properties, sender: AppSender<Msg>) -> Self {
+//!     let mut queries = QueryService::new(sender);
+//!     let user = queries.fetch(format!("users/{}", props.id), QueryOptions::default(), move || {
+//!         Request::get(format!("/users/{}", props.id)).body(Nothing).unwrap()
+//!     }, |response: Response<Json<Result<User, Error>>>| response.into_body().0, || Msg::User);
+//!     Profile { user }
+//! }
+//!
+//! fn view(&self) -> Html<Msg> {
+//!     let user = self.user.state();
+//!     ...
+//! }
+//!
+//! // After the user was saved
+//! query::invalidate("users/42");
+//! ```
+//!
+//! Values are kept while they're fetched again, so stale data is shown
+//! instead of a spinner. Queries are fetched again when they're invalidated,
+//! when the page is shown again and when the browser is online again.
+//! `prefetch` fills the cache before a page which uses a query is opened.
+//!
+//! Mutations change a cached value optimistically and roll it back if
+//! the request fails:
+//!
+//! ```rust,ignore
+//! self.rename = Some(self.queries.mutate("users/42", |user: Option<&User>| {
+//!     User { name: name.clone(), ..user.cloned().unwrap_or_default() }
+//! }, request, |response: Response<Json<Result<User, Error>>>| {
+//!     response.into_body().0.map(Some)
+//! }, Msg::Renamed));
+//! ```
+
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+use callback::Callback;
+use format::{Storable, Restorable};
+use future;
+use html::AppSender;
+use services::{interceptor, Task};
+use services::backend::{self, ConnectivityBackend, FetchBackend, VisibilityBackend};
+use services::fetch::{Request, Response};
+
+/// A state of a query.
+pub struct QueryState<T, E> {
+    /// The last fetched value. It's kept while the query is fetched again.
+    pub data: Option<Rc<T>>,
+    /// The error of the last fetch if it failed.
+    pub error: Option<Rc<E>>,
+    /// A request of the query is running.
+    pub fetching: bool,
+}
+
+impl<T, E> QueryState<T, E> {
+    /// Returns `true` if the query is fetched first time.
+    pub fn is_loading(&self) -> bool {
+        self.fetching && self.data.is_none() && self.error.is_none()
+    }
+}
+
+/// Options of a query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryOptions {
+    /// Fetches the query again when the page is shown again.
+    pub refetch_on_focus: bool,
+    /// Fetches the query again when the browser is online again.
+    pub refetch_on_reconnect: bool,
+}
+
+impl Default for QueryOptions {
+    fn default() -> Self {
+        QueryOptions {
+            refetch_on_focus: true,
+            refetch_on_reconnect: true,
+        }
+    }
+}
+
+type Listener = Rc<Fn()>;
+type Outcome = Result<Rc<Any>, Rc<Any>>;
+type Start = Rc<Fn(Box<Fn(Outcome)>) -> Box<Task>>;
+
+#[derive(Default)]
+struct Entry {
+    data: Option<Rc<Any>>,
+    error: Option<Rc<Any>>,
+    stale: bool,
+    // The source of the last subscriber, queries of a key are expected to be equal
+    start: Option<Start>,
+    request: Option<usize>,
+    task: Option<Box<Task>>,
+    // Requests aren't started while mutations of the query are running
+    mutations: usize,
+    subscribers: Vec<(usize, Listener)>,
+}
+
+#[derive(Default)]
+struct Cache {
+    entries: HashMap<String, Entry>,
+    next_id: usize,
+}
+
+thread_local! {
+    static CACHE: RefCell<Cache> = RefCell::new(Cache::default());
+}
+
+fn next_id() -> usize {
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let id = cache.next_id;
+        cache.next_id += 1;
+        id
+    })
+}
+
+/// Returns the state of the query by its key.
+pub fn state<T: 'static, E: 'static>(key: &str) -> QueryState<T, E> {
+    CACHE.with(|cache| {
+        let cache = cache.borrow();
+        let entry = cache.entries.get(key);
+        QueryState {
+            data: entry.and_then(|entry| entry.data.clone()).map(|data| downcast(key, data)),
+            error: entry.and_then(|entry| entry.error.clone()).map(|error| downcast(key, error)),
+            fetching: entry.map_or(false, |entry| entry.request.is_some()),
+        }
+    })
+}
+
+/// Returns the cached value of the query.
+pub fn data<T: 'static>(key: &str) -> Option<Rc<T>> {
+    let data = CACHE.with(|cache| {
+        cache.borrow().entries.get(key).and_then(|entry| entry.data.clone())
+    });
+    data.map(|data| downcast(key, data))
+}
+
+fn downcast<T: 'static>(key: &str, value: Rc<Any>) -> Rc<T> {
+    value.downcast().unwrap_or_else(|_| panic!("query {} holds a value of another type", key))
+}
+
+/// Puts a value to the cache, like a response of a mutation.
+pub fn set_data<T: 'static>(key: &str, data: T) {
+    put(key, Rc::new(data), true);
+}
+
+/// Puts a value to the cache. Optimistic values aren't `fresh`,
+/// they don't cancel fetches which are due.
+fn put(key: &str, data: Rc<Any>, fresh: bool) {
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let entry = cache.entries.entry(key.to_owned()).or_insert_with(Entry::default);
+        entry.data = Some(data);
+        entry.error = None;
+        entry.stale &= !fresh;
+    });
+    changed(key);
+}
+
+/// Marks the query as stale. It's fetched again at once if it's used
+/// or when it's used next time.
+pub fn invalidate(key: &str) {
+    let used = CACHE.with(|cache| {
+        cache.borrow_mut().entries.get_mut(key).map(|entry| {
+            entry.stale = true;
+            !entry.subscribers.is_empty()
+        })
+    });
+    if used == Some(true) {
+        start(key);
+    }
+}
+
+/// Invalidates all queries which keys start with the `prefix`, like `users/`.
+pub fn invalidate_prefix(prefix: &str) {
+    let keys: Vec<String> = CACHE.with(|cache| {
+        cache.borrow().entries.keys().filter(|key| key.starts_with(prefix)).cloned().collect()
+    });
+    for key in keys {
+        invalidate(&key);
+    }
+}
+
+/// Removes the cached value and the error of the query.
+/// It's fetched again at once if it's used.
+pub fn remove(key: &str) {
+    let used = CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let used = match cache.entries.get_mut(key) {
+            Some(entry) => {
+                entry.data = None;
+                entry.error = None;
+                !entry.subscribers.is_empty()
+            }
+            None => return false,
+        };
+        if !used {
+            cache.entries.remove(key);
+        }
+        used
+    });
+    if used {
+        changed(key);
+        start(key);
+    }
+}
+
+/// Fetches a query into the cache before a component uses it, like when
+/// a link to its page is hovered. The task is started like with
+/// `QueryService::spawn` unless the cached value is fresh or a request is
+/// running. The value is kept for a component which uses the key later.
+pub fn prefetch<K, T, E, S, R>(key: K, start: S)
+where
+    K: Into<String>,
+    T: 'static,
+    E: 'static,
+    S: Fn(Callback<Result<T, E>>) -> R + 'static,
+    R: Task + 'static,
+{
+    warm(&key.into(), spawn_source(start));
+}
+
+/// Fetches a query which sends a request into the cache, like `QueryService::fetch`.
+pub fn prefetch_fetch<K, T, E, R, IN, OUT, P>(key: K, request: R, parse: P)
+where
+    K: Into<String>,
+    T: 'static,
+    E: 'static,
+    R: Fn() -> Request<IN> + 'static,
+    IN: Into<Storable>,
+    OUT: From<Restorable> + 'static,
+    P: Fn(Response<OUT>) -> Result<T, E> + 'static,
+{
+    warm(&key.into(), fetch_source(backend::fetch(), request, parse));
+}
+
+fn warm(key: &str, source: Start) {
+    let fetch = CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let entry = cache.entries.entry(key.to_owned()).or_insert_with(Entry::default);
+        if entry.start.is_none() {
+            entry.start = Some(source);
+        }
+        entry.data.is_none() || entry.stale
+    });
+    if fetch {
+        start(key);
+    }
+}
+
+fn spawn_source<T, E, S, R>(start: S) -> Start
+where
+    T: 'static,
+    E: 'static,
+    S: Fn(Callback<Result<T, E>>) -> R + 'static,
+    R: Task + 'static,
+{
+    Rc::new(move |done: Box<Fn(Outcome)>| {
+        let callback = Callback::from(move |result: Result<T, E>| {
+            done(result.map(|data| Rc::new(data) as Rc<Any>).map_err(|error| Rc::new(error) as Rc<Any>));
+        });
+        Box::new(start(callback)) as Box<Task>
+    })
+}
+
+fn fetch_source<T, E, R, IN, OUT, P>(backend: Rc<FetchBackend>, request: R, parse: P) -> Start
+where
+    T: 'static,
+    E: 'static,
+    R: Fn() -> Request<IN> + 'static,
+    IN: Into<Storable>,
+    OUT: From<Restorable> + 'static,
+    P: Fn(Response<OUT>) -> Result<T, E> + 'static,
+{
+    let parse = Rc::new(parse);
+    Rc::new(move |done: Box<Fn(Outcome)>| {
+        let (parts, body) = request().into_parts();
+        let parse = parse.clone();
+        let callback = move |response: Response<Restorable>| {
+            let result = (*parse)(response.map(OUT::from));
+            done(result.map(|data| Rc::new(data) as Rc<Any>).map_err(|error| Rc::new(error) as Rc<Any>));
+        };
+        backend.fetch(interceptor::fetch(Request::from_parts(parts, body.into())), Box::new(callback))
+    })
+}
+
+/// Starts a request of the query unless one is running.
+fn start(key: &str) {
+    let id = next_id();
+    let source = CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let entry = cache.entries.get_mut(key)?;
+        if entry.request.is_some() {
+            return None;
+        }
+        if entry.mutations > 0 {
+            entry.stale = true;
+            return None;
+        }
+        let source = entry.start.clone()?;
+        entry.request = Some(id);
+        Some(source)
+    });
+    let source = match source {
+        Some(source) => source,
+        None => return,
+    };
+    changed(key);
+    let owned = key.to_owned();
+    let task = source(Box::new(move |outcome| finish(&owned, id, outcome)));
+    // The request could be finished or canceled while it was started
+    let unused = CACHE.with(|cache| {
+        match cache.borrow_mut().entries.get_mut(key) {
+            Some(ref mut entry) if entry.request == Some(id) => {
+                entry.task = Some(task);
+                None
+            }
+            _ => Some(task),
+        }
+    });
+    drop(unused);
+}
+
+fn finish(key: &str, id: usize, outcome: Outcome) {
+    let task = CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let entry = cache.entries.get_mut(key).filter(|entry| entry.request == Some(id))?;
+        entry.request = None;
+        entry.stale = false;
+        match outcome {
+            Ok(data) => {
+                entry.data = Some(data);
+                entry.error = None;
+            }
+            Err(error) => {
+                entry.error = Some(error);
+            }
+        }
+        Some(entry.task.take())
+    });
+    if let Some(task) = task {
+        drop(task);
+        changed(key);
+    }
+}
+
+fn subscribe(key: &str, source: Start, listener: Listener) -> usize {
+    let id = next_id();
+    let fetch = CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let entry = cache.entries.entry(key.to_owned()).or_insert_with(Entry::default);
+        entry.start = Some(source);
+        entry.subscribers.push((id, listener));
+        entry.data.is_none() || entry.stale
+    });
+    if fetch {
+        start(key);
+    }
+    id
+}
+
+fn unsubscribe(key: &str, id: usize) {
+    let task = CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let entry = cache.entries.get_mut(key)?;
+        entry.subscribers.retain(|&(subscriber, _)| subscriber != id);
+        if !entry.subscribers.is_empty() || entry.request.is_none() {
+            return None;
+        }
+        // Nobody waits for the request, it's started again by the next subscriber
+        entry.request = None;
+        entry.stale = true;
+        entry.task.take()
+    });
+    if let Some(mut task) = task {
+        if task.is_active() {
+            task.cancel();
+        }
+    }
+}
+
+/// Cancels a running request of the query, so it doesn't overwrite
+/// an optimistic value, and holds new requests until the mutation ends.
+fn begin_mutation(key: &str) {
+    let task = CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let entry = cache.entries.entry(key.to_owned()).or_insert_with(Entry::default);
+        entry.mutations += 1;
+        entry.request.take().map(|_| {
+            entry.stale = true;
+            entry.task.take()
+        })
+    });
+    if let Some(Some(mut task)) = task {
+        if task.is_active() {
+            task.cancel();
+        }
+    }
+}
+
+/// Fetches the query if it was invalidated while mutations were running.
+fn end_mutation(key: &str) {
+    let fetch = CACHE.with(|cache| {
+        cache.borrow_mut().entries.get_mut(key).map_or(false, |entry| {
+            entry.mutations -= 1;
+            entry.mutations == 0 && entry.stale && !entry.subscribers.is_empty()
+        })
+    });
+    if fetch {
+        start(key);
+    }
+}
+
+/// Restores the value which the query had before an optimistic update.
+/// If another mutation changed it since, the query is fetched again instead.
+fn roll_back(key: &str, optimistic: &Rc<Any>, previous: Option<Rc<Any>>) {
+    let restored = CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let entry = cache.entries.get_mut(key)?;
+        let current = entry.data.as_ref().map_or(false, |data| Rc::ptr_eq(data, optimistic));
+        if current {
+            entry.data = previous;
+        } else {
+            entry.stale = true;
+        }
+        Some(current)
+    });
+    if restored == Some(true) {
+        changed(key);
+    }
+}
+
+fn changed(key: &str) {
+    let listeners: Vec<Listener> = CACHE.with(|cache| {
+        cache.borrow().entries.get(key)
+            .map(|entry| entry.subscribers.iter().map(|(_, listener)| listener.clone()).collect())
+            .unwrap_or_default()
+    });
+    for listener in listeners {
+        listener();
+    }
+}
+
+/// A subscription to a query. The query is used until the handle is dropped.
+pub struct QueryHandle<T, E> {
+    key: String,
+    id: Option<usize>,
+    subscriptions: Vec<Box<Task>>,
+    _types: PhantomData<(T, E)>,
+}
+
+impl<T: 'static, E: 'static> QueryHandle<T, E> {
+    /// Returns the key of the query.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Returns the current state of the query.
+    pub fn state(&self) -> QueryState<T, E> {
+        state(&self.key)
+    }
+
+    /// Fetches the query again, the cached value is kept until it's fetched.
+    pub fn refetch(&self) {
+        invalidate(&self.key);
+    }
+}
+
+impl<T, E> Task for QueryHandle<T, E> {
+    fn is_active(&self) -> bool {
+        self.id.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let id = self.id.take().expect("tried to cancel a query twice");
+        for mut subscription in self.subscriptions.drain(..) {
+            if subscription.is_active() {
+                subscription.cancel();
+            }
+        }
+        unsubscribe(&self.key, id);
+    }
+}
+
+impl<T, E> Drop for QueryHandle<T, E> {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+/// A service which subscribes components to queries.
+pub struct QueryService<MSG> {
+    sender: AppSender<MSG>,
+    fetch: Rc<FetchBackend>,
+    visibility: Rc<VisibilityBackend>,
+    connectivity: Rc<ConnectivityBackend>,
+}
+
+impl<MSG: 'static> QueryService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self::with_backends(sender, backend::fetch(), backend::visibility(), backend::connectivity())
+    }
+
+    /// Creates a new service instance which uses the backends.
+    pub fn with_backends(
+        sender: AppSender<MSG>,
+        fetch: Rc<FetchBackend>,
+        visibility: Rc<VisibilityBackend>,
+        connectivity: Rc<ConnectivityBackend>,
+    ) -> Self {
+        Self { sender, fetch, visibility, connectivity }
+    }
+
+    /// Uses a query which is fetched by a task returned by `start`. The task
+    /// passes the result to the callback. A message from the `converter`
+    /// is sent every time the state of the query changes.
+    pub fn spawn<K, T, E, S, R, F>(&mut self, key: K, options: QueryOptions, start: S, converter: F) -> QueryHandle<T, E>
+    where
+        K: Into<String>,
+        T: 'static,
+        E: 'static,
+        S: Fn(Callback<Result<T, E>>) -> R + 'static,
+        R: Task + 'static,
+        F: Fn() -> MSG + 'static,
+    {
+        self.subscribe(key.into(), options, spawn_source(start), converter)
+    }
+
+    /// Uses a query which sends a request built by `request`.
+    /// The response is turned into the result by `parse`.
+    pub fn fetch<K, T, E, R, IN, OUT, P, F>(
+        &mut self,
+        key: K,
+        options: QueryOptions,
+        request: R,
+        parse: P,
+        converter: F,
+    ) -> QueryHandle<T, E>
+    where
+        K: Into<String>,
+        T: 'static,
+        E: 'static,
+        R: Fn() -> Request<IN> + 'static,
+        IN: Into<Storable>,
+        OUT: From<Restorable> + 'static,
+        P: Fn(Response<OUT>) -> Result<T, E> + 'static,
+        F: Fn() -> MSG + 'static,
+    {
+        let source = fetch_source(self.fetch.clone(), request, parse);
+        self.subscribe(key.into(), options, source, converter)
+    }
+
+    /// Uses a query which is resolved by a future returned by `start`.
+    pub fn future<K, T, E, S, FUT, F>(&mut self, key: K, options: QueryOptions, start: S, converter: F) -> QueryHandle<T, E>
+    where
+        K: Into<String>,
+        T: 'static,
+        E: 'static,
+        S: Fn() -> FUT + 'static,
+        FUT: Future<Output = Result<T, E>> + 'static,
+        F: Fn() -> MSG + 'static,
+    {
+        self.spawn(key, options, move |done| {
+            future::spawn_local(Finish {
+                future: Box::pin(start()),
+                done,
+            })
+        }, converter)
+    }
+
+    /// Changes the cached value of the query with `update` at once and sends
+    /// the `request`. The response is turned into a result by `parse`: a value
+    /// confirmed by a server replaces the optimistic one and `None` fetches
+    /// the query again. An error rolls the value back, and a message from
+    /// the `converter` is sent with the result, so a conflict could be shown.
+    pub fn mutate<T, E, U, IN, OUT, P, F>(
+        &mut self,
+        key: &str,
+        update: U,
+        request: Request<IN>,
+        parse: P,
+        converter: F,
+    ) -> MutationHandle
+    where
+        T: 'static,
+        E: 'static,
+        U: FnOnce(Option<&T>) -> T,
+        IN: Into<Storable>,
+        OUT: From<Restorable> + 'static,
+        P: Fn(Response<OUT>) -> Result<Option<T>, E> + 'static,
+        F: Fn(Result<(), E>) -> MSG + 'static,
+    {
+        let key = key.to_owned();
+        begin_mutation(&key);
+        let previous = data::<T>(&key);
+        let optimistic: Rc<Any> = Rc::new(update(previous.as_deref()));
+        let mut previous = previous.map(|data| data as Rc<Any>);
+        put(&key, optimistic.clone(), false);
+
+        let settled = Rc::new(Cell::new(false));
+        let mut tx = self.sender.clone();
+        let callback = {
+            let key = key.clone();
+            let settled = settled.clone();
+            move |response: Response<Restorable>| {
+                if settled.replace(true) {
+                    return;
+                }
+                let result = match parse(response.map(OUT::from)) {
+                    Ok(Some(confirmed)) => {
+                        put(&key, Rc::new(confirmed), true);
+                        Ok(())
+                    }
+                    Ok(None) => {
+                        invalidate(&key);
+                        Ok(())
+                    }
+                    Err(error) => {
+                        roll_back(&key, &optimistic, previous.take());
+                        Err(error)
+                    }
+                };
+                end_mutation(&key);
+                tx.send(converter(result));
+            }
+        };
+        let (parts, body) = request.into_parts();
+        let request = interceptor::fetch(Request::from_parts(parts, body.into()));
+        let task = self.fetch.fetch(request, Box::new(callback));
+        MutationHandle {
+            key,
+            task: Some(task),
+            settled,
+        }
+    }
+
+    fn subscribe<T, E, F>(&mut self, key: String, options: QueryOptions, source: Start, converter: F) -> QueryHandle<T, E>
+    where
+        F: Fn() -> MSG + 'static,
+    {
+        let tx = self.sender.clone();
+        let listener = move || {
+            let msg = converter();
+            tx.clone().send(msg);
+        };
+        let mut subscriptions = Vec::new();
+        if options.refetch_on_focus {
+            let key = key.clone();
+            let callback = move |hidden: bool| {
+                if !hidden {
+                    invalidate(&key);
+                }
+            };
+            subscriptions.push(self.visibility.listen(Box::new(callback)));
+        }
+        if options.refetch_on_reconnect {
+            let key = key.clone();
+            let callback = move |online: bool| {
+                if online {
+                    invalidate(&key);
+                }
+            };
+            subscriptions.push(self.connectivity.listen(Box::new(callback)));
+        }
+        let id = subscribe(&key, source, Rc::new(listener));
+        QueryHandle {
+            key,
+            id: Some(id),
+            subscriptions,
+            _types: PhantomData,
+        }
+    }
+}
+
+/// A handle of a running mutation. If it's canceled before the response comes,
+/// the optimistic value is kept and the query is fetched again.
+pub struct MutationHandle {
+    key: String,
+    task: Option<Box<Task>>,
+    settled: Rc<Cell<bool>>,
+}
+
+impl Task for MutationHandle {
+    fn is_active(&self) -> bool {
+        self.task.is_some() && !self.settled.get()
+    }
+
+    fn cancel(&mut self) {
+        let mut task = self.task.take().expect("tried to cancel a mutation twice");
+        if !self.settled.replace(true) {
+            if task.is_active() {
+                task.cancel();
+            }
+            invalidate(&self.key);
+            end_mutation(&self.key);
+        }
+    }
+}
+
+impl Drop for MutationHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+/// A future which passes the output of another one to a callback.
+struct Finish<FUT: Future> {
+    future: Pin<Box<FUT>>,
+    done: Callback<FUT::Output>,
+}
+
+impl<FUT: Future> Future for Finish<FUT> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<()> {
+        match self.future.as_mut().poll(context) {
+            Poll::Ready(output) => {
+                self.done.emit(output);
+                Poll::Ready(())
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
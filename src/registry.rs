@@ -0,0 +1,86 @@
+//! This module contains a registry of components which helps
+//! to instantiate components chosen at runtime.
+//!
+//! It's useful for plugin-style dashboards or page builders
+//! which compose views from data:
+//!
+//! ```rust,ignore
+//! let mut registry = Registry::new();
+//! registry.register::<Chart>("chart");
+//! registry.register::<Table>("table");
+//!
+//! fn view_widget(registry: &Registry<&'static str, Msg>, widget: &Widget) -> Html<Msg> {
+//!     let link = Link::new();
+//!     let props = widget.props(&link);
+//!     html! {
+//!         <div class="widget",>
+//!             { for registry.create(&widget.kind, props, link).into_iter() }
+//!         </div>
+//!     }
+//! }
+//! ```
+
+use std::any::Any;
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+use component::{Component, Link};
+use virtual_dom::VComp;
+
+type Factory<MSG> = Box<Fn(Box<Any>, Link<MSG>) -> Option<VComp<MSG>>>;
+
+/// A registry of components keyed by a runtime value.
+pub struct Registry<K, MSG> {
+    factories: HashMap<K, Factory<MSG>>,
+}
+
+impl<K: Eq + Hash, MSG: 'static> Default for Registry<K, MSG> {
+    fn default() -> Self {
+        Registry::new()
+    }
+}
+
+impl<K: Eq + Hash, MSG: 'static> Registry<K, MSG> {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Registry {
+            factories: HashMap::new(),
+        }
+    }
+
+    /// Registers a component with a key. Replaces a component
+    /// registered with the same key before.
+    pub fn register<COMP: Component>(&mut self, key: K) {
+        let factory = |props: Box<Any>, link: Link<MSG>| {
+            match props.downcast::<COMP::Properties>() {
+                Ok(props) => Some(VComp::new::<COMP>(*props, link)),
+                Err(_) => {
+                    warn!("wrong type of properties to create a component");
+                    None
+                }
+            }
+        };
+        self.factories.insert(key, Box::new(factory));
+    }
+
+    /// Returns `true` if a component registered with the key.
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.factories.contains_key(key)
+    }
+
+    /// Creates a component registered with the key. Properties have to be
+    /// the same type as `Properties` of the registered component.
+    /// Returns `None` if there is no component for the key or
+    /// properties have a wrong type.
+    pub fn create<Q>(&self, key: &Q, props: Box<Any>, link: Link<MSG>) -> Option<VComp<MSG>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.factories.get(key).and_then(|factory| factory(props, link))
+    }
+}
@@ -0,0 +1,638 @@
+//! This module contains a document model of rich text and deltas which
+//! describe its changes. `RichTextEditor` maps a `contenteditable` element
+//! to the model, and apps keep documents in their state and apply deltas:
+//!
+//! ```rust,ignore
+//! let mut note = Document::from_text("Hello world");
+//! note.apply(&Delta::new().retain(6).format(5, Mark::Bold, true));
+//! assert!(note.is_marked(6, 11, &Mark::Bold));
+//! ```
+//!
+//! Offsets count characters of blocks and a break after every block, so
+//! they're the same as offsets of `text()` where blocks end with `\n`.
+//! Documents are stored with `Json` like other values.
+
+use std::cmp;
+use std::mem;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value as Json};
+use html::Html;
+use virtual_dom::{VNode, VTag};
+
+/// A mark of text which is toggled by commands.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Mark {
+    /// Strong text.
+    Bold,
+    /// Emphasized text.
+    Italic,
+    /// Underlined text.
+    Underline,
+    /// Inline code.
+    Code,
+    /// A link to the URL.
+    Link(String),
+}
+
+/// Marks of a span of text.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Marks {
+    /// Strong text.
+    pub bold: bool,
+    /// Emphasized text.
+    pub italic: bool,
+    /// Underlined text.
+    pub underline: bool,
+    /// Inline code.
+    pub code: bool,
+    /// The URL of a link.
+    pub link: Option<String>,
+}
+
+impl Marks {
+    /// Returns `true` if the mark is set, a link to the same URL for links.
+    pub fn has(&self, mark: &Mark) -> bool {
+        match *mark {
+            Mark::Bold => self.bold,
+            Mark::Italic => self.italic,
+            Mark::Underline => self.underline,
+            Mark::Code => self.code,
+            Mark::Link(ref href) => self.link.as_ref() == Some(href),
+        }
+    }
+
+    /// Sets or clears the mark.
+    pub fn set(&mut self, mark: &Mark, on: bool) {
+        match *mark {
+            Mark::Bold => self.bold = on,
+            Mark::Italic => self.italic = on,
+            Mark::Underline => self.underline = on,
+            Mark::Code => self.code = on,
+            Mark::Link(ref href) => self.link = if on { Some(href.clone()) } else { None },
+        }
+    }
+}
+
+/// A kind of blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlockKind {
+    /// A paragraph.
+    Paragraph,
+    /// A heading of the level from 1 to 6.
+    Heading(u8),
+    /// A quote.
+    Quote,
+    /// An item of a bulleted list. Adjacent items make one list.
+    Bullet,
+    /// An item of a numbered list.
+    Numbered,
+}
+
+/// Text with the same marks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    /// The text without breaks.
+    pub text: String,
+    /// Marks of the text.
+    pub marks: Marks,
+}
+
+/// A block of spans, like a paragraph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    /// The kind of the block.
+    pub kind: BlockKind,
+    /// Spans of the block, empty for an empty line.
+    pub spans: Vec<Span>,
+}
+
+impl Block {
+    /// Creates a block of the kind with plain text.
+    pub fn new(kind: BlockKind, text: &str) -> Self {
+        let spans = if text.is_empty() {
+            Vec::new()
+        } else {
+            vec![Span { text: text.to_owned(), marks: Marks::default() }]
+        };
+        Block { kind, spans }
+    }
+
+    /// Returns the text of spans.
+    pub fn text(&self) -> String {
+        self.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+
+    /// Returns the number of characters without the break.
+    pub fn len(&self) -> usize {
+        self.spans.iter().map(|span| span.text.chars().count()).sum()
+    }
+
+    /// Returns `true` if the block has no text.
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+}
+
+/// A selection between offsets of a document. The anchor stays where it
+/// started and the focus moves, so the focus is before the anchor when it
+/// was selected backwards.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Selection {
+    /// The offset where the selection started.
+    pub anchor: usize,
+    /// The offset of the caret.
+    pub focus: usize,
+}
+
+impl Selection {
+    /// Creates a caret at the offset.
+    pub fn caret(offset: usize) -> Self {
+        Selection { anchor: offset, focus: offset }
+    }
+
+    /// Returns the first offset.
+    pub fn start(&self) -> usize {
+        cmp::min(self.anchor, self.focus)
+    }
+
+    /// Returns the last offset.
+    pub fn end(&self) -> usize {
+        cmp::max(self.anchor, self.focus)
+    }
+
+    /// Returns `true` if nothing is selected.
+    pub fn is_collapsed(&self) -> bool {
+        self.anchor == self.focus
+    }
+
+    /// Returns the selection at offsets after the delta.
+    pub fn transform(&self, delta: &Delta) -> Selection {
+        Selection {
+            anchor: delta.transform_offset(self.anchor),
+            focus: delta.transform_offset(self.focus),
+        }
+    }
+}
+
+/// An operation of a delta, which reads a document from the start.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Op {
+    /// Keeps that many characters and breaks.
+    Retain(usize),
+    /// Inserts text with the marks.
+    Insert(String, Marks),
+    /// Inserts a break which ends a block of the kind.
+    Break(BlockKind),
+    /// Removes that many characters and breaks.
+    Delete(usize),
+    /// Sets or clears the mark of characters in the next that many offsets.
+    Format(usize, Mark, bool),
+    /// Changes the kind of blocks whose breaks are in the next that many offsets.
+    SetBlock(usize, BlockKind),
+}
+
+/// Operations which change a document. Offsets after the last operation
+/// are kept.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Delta {
+    ops: Vec<Op>,
+}
+
+impl Delta {
+    /// Creates a delta without changes.
+    pub fn new() -> Self {
+        Delta::default()
+    }
+
+    /// Keeps `count` characters and breaks.
+    pub fn retain(mut self, count: usize) -> Self {
+        self.push(Op::Retain(count));
+        self
+    }
+
+    /// Inserts text with the marks. Every `\n` inserts a break which ends
+    /// a block of the kind of the block where it's inserted.
+    pub fn insert(mut self, text: &str, marks: Marks) -> Self {
+        self.push(Op::Insert(text.to_owned(), marks));
+        self
+    }
+
+    /// Inserts a break which ends a block of the kind.
+    pub fn insert_break(mut self, kind: BlockKind) -> Self {
+        self.push(Op::Break(kind));
+        self
+    }
+
+    /// Removes `count` characters and breaks.
+    pub fn delete(mut self, count: usize) -> Self {
+        self.push(Op::Delete(count));
+        self
+    }
+
+    /// Sets or clears the mark of `count` characters.
+    pub fn format(mut self, count: usize, mark: Mark, on: bool) -> Self {
+        self.push(Op::Format(count, mark, on));
+        self
+    }
+
+    /// Changes the kind of blocks whose breaks are in the next `count` offsets.
+    pub fn set_block(mut self, count: usize, kind: BlockKind) -> Self {
+        self.push(Op::SetBlock(count, kind));
+        self
+    }
+
+    /// Returns operations of the delta.
+    pub fn ops(&self) -> &[Op] {
+        &self.ops
+    }
+
+    /// Returns `true` if the delta changes nothing.
+    pub fn is_empty(&self) -> bool {
+        self.ops.iter().all(|op| matches!(*op, Op::Retain(_)))
+    }
+
+    /// Returns the offset after the delta. Text inserted at the offset
+    /// stays after it.
+    pub fn transform_offset(&self, offset: usize) -> usize {
+        let mut index = 0;
+        let mut result = offset;
+        for op in &self.ops {
+            match *op {
+                Op::Retain(count) | Op::Format(count, _, _) | Op::SetBlock(count, _) => index += count,
+                Op::Insert(ref text, _) if index < offset => result += text.chars().count(),
+                Op::Break(_) if index < offset => result += 1,
+                Op::Insert(..) | Op::Break(_) => {}
+                Op::Delete(count) => {
+                    if index < offset {
+                        result -= cmp::min(count, offset - index);
+                    }
+                    index += count;
+                }
+            }
+        }
+        result
+    }
+
+    fn push(&mut self, op: Op) {
+        match op {
+            Op::Retain(0) | Op::Delete(0) | Op::Format(0, _, _) | Op::SetBlock(0, _) => return,
+            Op::Insert(ref text, _) if text.is_empty() => return,
+            _ => {}
+        }
+        match (self.ops.last_mut(), op) {
+            (Some(&mut Op::Retain(ref mut last)), Op::Retain(count)) => *last += count,
+            (Some(&mut Op::Delete(ref mut last)), Op::Delete(count)) => *last += count,
+            (Some(&mut Op::Insert(ref mut last, ref marks)), Op::Insert(ref text, ref other)) if marks == other => {
+                last.push_str(text);
+            }
+            (_, op) => self.ops.push(op),
+        }
+    }
+}
+
+/// A character or a break of a flat document.
+#[derive(Debug, Clone, PartialEq)]
+enum Unit {
+    Char(char, Marks),
+    Break(BlockKind),
+}
+
+/// A document of blocks, which always has one block at least.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Document {
+    blocks: Vec<Block>,
+}
+
+impl Default for Document {
+    fn default() -> Self {
+        Document::new()
+    }
+}
+
+impl Document {
+    /// Creates a document with an empty paragraph.
+    pub fn new() -> Self {
+        Document {
+            blocks: vec![Block::new(BlockKind::Paragraph, "")],
+        }
+    }
+
+    /// Creates a document with a paragraph of every line.
+    pub fn from_text(text: &str) -> Self {
+        Document::from_blocks(text.split('\n').map(|line| Block::new(BlockKind::Paragraph, line)).collect())
+    }
+
+    /// Creates a document of blocks. Adjacent spans with the same marks
+    /// are merged and empty spans are dropped.
+    pub fn from_blocks(blocks: Vec<Block>) -> Self {
+        let mut units = Vec::new();
+        for block in blocks {
+            for span in block.spans {
+                units.extend(span.text.chars().filter(|c| *c != '\n').map(|c| Unit::Char(c, span.marks.clone())));
+            }
+            units.push(Unit::Break(block.kind));
+        }
+        Document::from_units(units)
+    }
+
+    fn from_units(mut units: Vec<Unit>) -> Self {
+        if !matches!(units.last(), Some(&Unit::Break(_))) {
+            units.push(Unit::Break(BlockKind::Paragraph));
+        }
+        let mut blocks = Vec::new();
+        let mut spans: Vec<Span> = Vec::new();
+        for unit in units {
+            match unit {
+                Unit::Char(c, marks) => match spans.last_mut() {
+                    Some(ref mut span) if span.marks == marks => span.text.push(c),
+                    _ => spans.push(Span { text: c.to_string(), marks }),
+                },
+                Unit::Break(kind) => blocks.push(Block { kind, spans: mem::take(&mut spans) }),
+            }
+        }
+        Document { blocks }
+    }
+
+    fn units(&self) -> Vec<Unit> {
+        let mut units = Vec::with_capacity(self.len());
+        for block in &self.blocks {
+            for span in &block.spans {
+                units.extend(span.text.chars().map(|c| Unit::Char(c, span.marks.clone())));
+            }
+            units.push(Unit::Break(block.kind));
+        }
+        units
+    }
+
+    /// Returns blocks of the document.
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    /// Returns the text of blocks with `\n` between them.
+    pub fn text(&self) -> String {
+        let lines: Vec<String> = self.blocks.iter().map(Block::text).collect();
+        lines.join("\n")
+    }
+
+    /// Returns the number of characters and breaks. The last offset
+    /// before the final break is one less.
+    pub fn len(&self) -> usize {
+        self.blocks.iter().map(|block| block.len() + 1).sum()
+    }
+
+    /// Returns `true` if the document is a single block without text.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.len() == 1 && self.blocks[0].is_empty()
+    }
+
+    /// Returns marks which typed text gets at the offset: marks of
+    /// the character before it or after it at the start of a block.
+    pub fn marks_at(&self, offset: usize) -> Marks {
+        let units = self.units();
+        let before = offset.checked_sub(1).and_then(|index| units.get(index));
+        match (before, units.get(offset)) {
+            (Some(&Unit::Char(_, ref marks)), _) | (_, Some(&Unit::Char(_, ref marks))) => marks.clone(),
+            _ => Marks::default(),
+        }
+    }
+
+    /// Returns `true` if all characters between offsets have the mark,
+    /// or typed text gets it at an empty range.
+    pub fn is_marked(&self, start: usize, end: usize, mark: &Mark) -> bool {
+        if start >= end {
+            return self.marks_at(start).has(mark);
+        }
+        let units = self.units();
+        let mut chars = units.iter().take(end).skip(start).filter_map(|unit| match *unit {
+            Unit::Char(_, ref marks) => Some(marks),
+            Unit::Break(_) => None,
+        }).peekable();
+        chars.peek().is_some() && chars.all(|marks| marks.has(mark))
+    }
+
+    /// Returns a delta which sets the mark on the selection, or clears it
+    /// if all of the selection has it already.
+    pub fn toggle_mark(&self, selection: Selection, mark: Mark) -> Delta {
+        let (start, end) = (selection.start(), cmp::min(selection.end(), self.len()));
+        if start >= end {
+            return Delta::new();
+        }
+        let on = !self.is_marked(start, end, &mark);
+        Delta::new().retain(start).format(end - start, mark, on)
+    }
+
+    /// Returns a delta which changes blocks of the selection to the kind,
+    /// or to paragraphs if all of them have the kind already.
+    pub fn toggle_block(&self, selection: Selection, kind: BlockKind) -> Delta {
+        // Breaks of blocks which the selection touches
+        let mut breaks = Vec::new();
+        let mut start = 0;
+        for block in &self.blocks {
+            let end = start + block.len();
+            if end >= selection.start() && start <= selection.end() {
+                breaks.push((end, block.kind));
+            }
+            start = end + 1;
+        }
+        let first = match breaks.first() {
+            Some(&(first, _)) => first,
+            None => return Delta::new(),
+        };
+        let last = breaks.last().map_or(first, |&(last, _)| last);
+        let kind = if breaks.iter().all(|&(_, block)| block == kind) { BlockKind::Paragraph } else { kind };
+        Delta::new().retain(first).set_block(last - first + 1, kind)
+    }
+
+    /// Applies the delta. Returns `true` if the document changed.
+    pub fn apply(&mut self, delta: &Delta) -> bool {
+        let old = self.units();
+        let mut units = Vec::with_capacity(old.len());
+        let mut index = 0;
+        for op in delta.ops() {
+            match *op {
+                Op::Retain(count) => {
+                    let end = cmp::min(index + count, old.len());
+                    units.extend_from_slice(&old[index..end]);
+                    index = end;
+                }
+                Op::Insert(ref text, ref marks) => {
+                    // Split blocks keep their kind
+                    let kind = old[index..].iter().find_map(|unit| match *unit {
+                        Unit::Break(kind) => Some(kind),
+                        Unit::Char(..) => None,
+                    }).unwrap_or(BlockKind::Paragraph);
+                    units.extend(text.chars().map(|c| match c {
+                        '\n' => Unit::Break(kind),
+                        c => Unit::Char(c, marks.clone()),
+                    }));
+                }
+                Op::Break(kind) => units.push(Unit::Break(kind)),
+                Op::Delete(count) => index = cmp::min(index + count, old.len()),
+                Op::Format(count, ref mark, on) => {
+                    let end = cmp::min(index + count, old.len());
+                    units.extend(old[index..end].iter().cloned().map(|unit| match unit {
+                        Unit::Char(c, mut marks) => {
+                            marks.set(mark, on);
+                            Unit::Char(c, marks)
+                        }
+                        unit => unit,
+                    }));
+                    index = end;
+                }
+                Op::SetBlock(count, kind) => {
+                    let end = cmp::min(index + count, old.len());
+                    units.extend(old[index..end].iter().cloned().map(|unit| match unit {
+                        Unit::Break(_) => Unit::Break(kind),
+                        unit => unit,
+                    }));
+                    index = end;
+                }
+            }
+        }
+        units.extend_from_slice(&old[index..]);
+        let document = Document::from_units(units);
+        let changed = document != *self;
+        *self = document;
+        changed
+    }
+
+    /// Returns a delta which changes this document to the other one.
+    /// It replaces everything between the common start and the common end.
+    pub fn diff(&self, other: &Document) -> Delta {
+        let (old, new) = (self.units(), other.units());
+        let prefix = old.iter().zip(&new).take_while(|&(a, b)| a == b).count();
+        let longest = cmp::min(old.len(), new.len()) - prefix;
+        let suffix = old.iter().rev().zip(new.iter().rev()).take(longest).take_while(|&(a, b)| a == b).count();
+        let mut delta = Delta::new().retain(prefix);
+        for unit in &new[prefix..new.len() - suffix] {
+            delta = match *unit {
+                Unit::Char(c, ref marks) => delta.insert(&c.to_string(), marks.clone()),
+                Unit::Break(kind) => delta.insert_break(kind),
+            };
+        }
+        delta.delete(old.len() - prefix - suffix)
+    }
+
+    /// Renders the document for reading, like a posted comment.
+    pub fn view<MSG: 'static>(&self) -> Html<MSG> {
+        let mut root = VTag::new("div").class("rich-text");
+        let mut list: Option<(BlockKind, VTag<MSG>)> = None;
+        for block in &self.blocks {
+            let mut element = VTag::new(match block.kind {
+                BlockKind::Paragraph => "p".to_owned(),
+                BlockKind::Heading(level) => format!("h{}", level.clamp(1, 6)),
+                BlockKind::Quote => "blockquote".to_owned(),
+                BlockKind::Bullet | BlockKind::Numbered => "li".to_owned(),
+            });
+            for span in &block.spans {
+                element.add_child(view_span(span));
+            }
+            let item = block.kind == BlockKind::Bullet || block.kind == BlockKind::Numbered;
+            if list.as_ref().map_or(false, |&(kind, _)| kind != block.kind) || !item {
+                if let Some((_, tag)) = list.take() {
+                    root.add_child(tag.into());
+                }
+            }
+            if item {
+                let tag = if block.kind == BlockKind::Bullet { "ul" } else { "ol" };
+                let (_, ref mut parent) = *list.get_or_insert_with(|| (block.kind, VTag::new(tag)));
+                parent.add_child(element.into());
+            } else {
+                root.add_child(element.into());
+            }
+        }
+        if let Some((_, tag)) = list {
+            root.add_child(tag.into());
+        }
+        root
+    }
+
+    /// Returns JSON of blocks, which editors render and read.
+    pub(crate) fn to_json(&self) -> Json {
+        let blocks = self.blocks.iter().map(|block| {
+            let mut object = Map::new();
+            let kind = match block.kind {
+                BlockKind::Paragraph => "paragraph",
+                BlockKind::Heading(level) => {
+                    object.insert("level".into(), Json::from(level));
+                    "heading"
+                }
+                BlockKind::Quote => "quote",
+                BlockKind::Bullet => "bullet",
+                BlockKind::Numbered => "numbered",
+            };
+            object.insert("type".into(), Json::from(kind));
+            let spans = block.spans.iter().map(|span| {
+                let mut object = Map::new();
+                object.insert("text".into(), Json::from(span.text.as_str()));
+                let flags = [("bold", span.marks.bold), ("italic", span.marks.italic),
+                             ("underline", span.marks.underline), ("code", span.marks.code)];
+                for &(name, on) in flags.iter().filter(|&&(_, on)| on) {
+                    object.insert(name.into(), Json::Bool(on));
+                }
+                if let Some(ref link) = span.marks.link {
+                    object.insert("link".into(), Json::from(link.as_str()));
+                }
+                Json::Object(object)
+            }).collect();
+            object.insert("spans".into(), Json::Array(spans));
+            Json::Object(object)
+        }).collect();
+        Json::Array(blocks)
+    }
+
+    /// Reads JSON of blocks. Unknown blocks are paragraphs and missing fields are empty.
+    pub(crate) fn from_json(json: &Json) -> Document {
+        let blocks = json.as_array().map(Vec::as_slice).unwrap_or_default().iter().map(|block| {
+            let level = block["level"].as_u64().unwrap_or(1);
+            let kind = match block["type"].as_str().unwrap_or_default() {
+                "heading" => BlockKind::Heading(level.clamp(1, 6) as u8),
+                "quote" => BlockKind::Quote,
+                "bullet" => BlockKind::Bullet,
+                "numbered" => BlockKind::Numbered,
+                _ => BlockKind::Paragraph,
+            };
+            let spans = block["spans"].as_array().map(Vec::as_slice).unwrap_or_default().iter().map(|span| {
+                let flag = |name: &str| span[name].as_bool().unwrap_or(false);
+                Span {
+                    text: span["text"].as_str().unwrap_or_default().to_owned(),
+                    marks: Marks {
+                        bold: flag("bold"),
+                        italic: flag("italic"),
+                        underline: flag("underline"),
+                        code: flag("code"),
+                        link: span["link"].as_str().map(str::to_owned),
+                    },
+                }
+            }).collect();
+            Block { kind, spans }
+        }).collect();
+        Document::from_blocks(blocks)
+    }
+}
+
+fn view_span<MSG: 'static>(span: &Span) -> VNode<MSG> {
+    let mut node: VNode<MSG> = VNode::from(span.text.clone());
+    let tags = [("code", span.marks.code), ("u", span.marks.underline),
+                ("em", span.marks.italic), ("strong", span.marks.bold)];
+    for &(tag, _) in tags.iter().filter(|&&(_, on)| on) {
+        node = VTag::new(tag).child(node).into();
+    }
+    if let Some(ref link) = span.marks.link {
+        node = VTag::new("a").attr("href", link).child(node).into();
+    }
+    node
+}
+
+impl Serialize for Document {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Document {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Json::deserialize(deserializer).map(|json| Document::from_json(&json))
+    }
+}
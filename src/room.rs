@@ -0,0 +1,636 @@
+//! This module contains rooms over websockets, like channels of Phoenix.
+//! Components join rooms by topics and rooms of the same url share one
+//! connection. A room receives broadcasts of its topic and diffs of who's
+//! in the room now:
+//!
+//! ```rust,ignore
+//! fn create(props: Self::Properties, sender: AppSender<Msg>) -> Self {
+//!     let mut rooms = RoomService::new(sender);
+//!     let room = rooms.join("wss://chat.example/socket/websocket", "room:lobby", &props.token, Msg::Room);
+//!     Chat { room, presence: Presence::new(), messages: Vec::new() }
+//! }
+//!
+//! fn update(&mut self, msg: Msg) -> ShouldRender {
+//!     match msg {
+//!         Msg::Send(text) => self.room.push("new_msg", &text),
+//!         Msg::Room(RoomMsg::Message { payload, .. }) => self.messages.push(payload),
+//!         Msg::Room(RoomMsg::Presence(diff)) => return self.presence.apply(diff),
+//!         Msg::Room(_) => return false,
+//!     }
+//!     true
+//! }
+//! ```
+//!
+//! Frames are arrays `[join_ref, ref, topic, event, payload]` of the JSON
+//! serializer of Phoenix. Rooms are joined again after a reconnect, and
+//! pushes are kept until a room is joined.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Duration;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{self, Map, Value};
+use html::AppSender;
+use services::{interceptor, Task};
+use services::backend::{self, Connection, TimerBackend, WebSocketBackend};
+use services::websocket::WebSocketStatus;
+
+const HEARTBEAT: Duration = Duration::from_secs(30);
+// Delays of reconnects in seconds, the last one is repeated
+const RECONNECTS: [u64; 4] = [1, 2, 5, 10];
+
+/// Messages of a room.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RoomMsg<T, M> {
+    /// The server accepted the join. It's sent again after every rejoin.
+    Joined,
+    /// The server refused to join, with the `reason` of the response.
+    Rejected(String),
+    /// A broadcast or a push of the server to the room.
+    Message {
+        /// The name of the event, like `new_msg`.
+        event: String,
+        /// The payload of the event.
+        payload: T,
+    },
+    /// Changes of members of the room.
+    Presence(PresenceDiff<M>),
+    /// The connection was lost, the room is joined again after a reconnect.
+    Disconnected,
+    /// The server closed the room, it isn't joined again.
+    Left,
+}
+
+/// A connection of a member, a member could be in a room from several devices.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Member<M> {
+    /// The key of the member, like a user id.
+    pub key: String,
+    /// The `phx_ref` of the connection.
+    pub reference: String,
+    /// The metadata of the connection, like a status.
+    pub meta: M,
+}
+
+/// Members who joined and left a room.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PresenceDiff<M> {
+    /// New connections of members.
+    pub joins: Vec<Member<M>>,
+    /// Closed connections of members.
+    pub leaves: Vec<Member<M>>,
+}
+
+/// Members who are in a room now. It's kept by a component which applies diffs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Presence<M> {
+    members: Vec<Member<M>>,
+}
+
+impl<M> Presence<M> {
+    /// Creates a presence without members.
+    pub fn new() -> Self {
+        Presence {
+            members: Vec::new(),
+        }
+    }
+
+    /// Applies a diff. Returns `true` if members changed.
+    pub fn apply(&mut self, diff: PresenceDiff<M>) -> bool {
+        let count = self.members.len();
+        let leaves = diff.leaves;
+        self.members.retain(|member| {
+            !leaves.iter().any(|left| left.key == member.key && left.reference == member.reference)
+        });
+        let changed = self.members.len() != count;
+        for member in diff.joins {
+            let known = self.members.iter_mut()
+                .find(|other| other.key == member.key && other.reference == member.reference);
+            match known {
+                Some(known) => known.meta = member.meta,
+                None => self.members.push(member),
+            }
+        }
+        changed || self.members.len() != count
+    }
+
+    /// Returns keys of members in the order they joined.
+    pub fn keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = Vec::new();
+        for member in &self.members {
+            if !keys.contains(&member.key.as_str()) {
+                keys.push(&member.key);
+            }
+        }
+        keys
+    }
+
+    /// Returns metadata of all connections of the member.
+    pub fn get(&self, key: &str) -> Vec<&M> {
+        self.members.iter().filter(|member| member.key == key).map(|member| &member.meta).collect()
+    }
+
+    /// Returns `true` if the member is in the room.
+    pub fn contains(&self, key: &str) -> bool {
+        self.members.iter().any(|member| member.key == key)
+    }
+
+    /// Returns the count of members.
+    pub fn len(&self) -> usize {
+        self.keys().len()
+    }
+
+    /// Returns `true` if nobody is in the room.
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+}
+
+impl<M> Default for Presence<M> {
+    fn default() -> Self {
+        Presence::new()
+    }
+}
+
+// A key, a `phx_ref` and metadata of a connection
+type RawMember = (String, String, Value);
+
+enum Event {
+    Joined,
+    Rejected(String),
+    Message(String, Value),
+    Presence(Vec<RawMember>, Vec<RawMember>),
+    Disconnected,
+    Left,
+}
+
+type Listener = Rc<Fn(Event)>;
+
+// A join ref, a ref, a topic, an event and a payload
+type Frame = (Option<String>, Option<String>, String, String, Value);
+
+struct Room {
+    id: usize,
+    topic: String,
+    params: Value,
+    // The ref of the last join, messages of older joins are ignored
+    join_ref: Option<String>,
+    joined: bool,
+    // Rejected and closed rooms aren't joined again
+    active: bool,
+    buffer: Vec<(String, Value)>,
+    presence: Vec<RawMember>,
+    listener: Listener,
+}
+
+struct Socket {
+    // The id of the current connection, statuses of older ones are ignored
+    connection_id: usize,
+    connection: Option<Box<Connection>>,
+    open: bool,
+    next_ref: u64,
+    attempts: usize,
+    // A heartbeat while the connection is open or a reconnect while it's closed
+    timer: Option<Box<Task>>,
+    rooms: Vec<Room>,
+    websocket: Rc<WebSocketBackend>,
+    timers: Rc<TimerBackend>,
+}
+
+impl Socket {
+    fn next_ref(&mut self) -> String {
+        self.next_ref += 1;
+        self.next_ref.to_string()
+    }
+
+    fn send(&mut self, join_ref: Option<&str>, reference: &str, topic: &str, event: &str, payload: &Value) {
+        if let Some(ref mut connection) = self.connection {
+            let frame = (join_ref, reference, topic, event, payload);
+            let data = serde_json::to_string(&frame).expect("can't serialize a frame of a room");
+            connection.send(data);
+        }
+    }
+
+    fn push(&mut self, index: usize, event: &str, payload: &Value) {
+        let reference = self.next_ref();
+        let (join_ref, topic) = (self.rooms[index].join_ref.clone(), self.rooms[index].topic.clone());
+        self.send(join_ref.as_deref(), &reference, &topic, event, payload);
+    }
+
+    fn join(&mut self, index: usize) {
+        // A join starts a join ref, so both refs of it are the same
+        let reference = self.next_ref();
+        let (topic, params) = (self.rooms[index].topic.clone(), self.rooms[index].params.clone());
+        self.send(Some(&reference), &reference, &topic, "phx_join", &params);
+        let room = &mut self.rooms[index];
+        room.join_ref = Some(reference);
+        room.joined = false;
+    }
+}
+
+#[derive(Default)]
+struct Sockets {
+    sockets: HashMap<String, Socket>,
+    next_id: usize,
+}
+
+thread_local! {
+    static SOCKETS: RefCell<Sockets> = RefCell::new(Sockets::default());
+}
+
+fn next_id() -> usize {
+    SOCKETS.with(|sockets| {
+        let mut sockets = sockets.borrow_mut();
+        sockets.next_id += 1;
+        sockets.next_id
+    })
+}
+
+fn connect(url: &str) {
+    let id = next_id();
+    let websocket = SOCKETS.with(|sockets| {
+        let mut sockets = sockets.borrow_mut();
+        let socket = sockets.sockets.get_mut(url)?;
+        socket.connection_id = id;
+        socket.timer = None;
+        Some(socket.websocket.clone())
+    });
+    let websocket = match websocket {
+        Some(websocket) => websocket,
+        None => return,
+    };
+    let (message_url, status_url) = (url.to_owned(), url.to_owned());
+    let on_message = move |data: String| receive(&message_url, id, &data);
+    let on_status = move |status| changed(&status_url, id, status);
+    let connection = websocket.connect(&interceptor::connect(url), Box::new(on_message), Box::new(on_status));
+    let unused = SOCKETS.with(|sockets| {
+        match sockets.borrow_mut().sockets.get_mut(url) {
+            Some(ref mut socket) if socket.connection_id == id => {
+                socket.connection = Some(connection);
+                None
+            }
+            _ => Some(connection),
+        }
+    });
+    close(unused);
+}
+
+fn close(connection: Option<Box<Connection>>) {
+    if let Some(mut connection) = connection {
+        if connection.is_active() {
+            connection.cancel();
+        }
+    }
+}
+
+fn changed(url: &str, id: usize, status: WebSocketStatus) {
+    let listeners = SOCKETS.with(|sockets| {
+        let mut sockets = sockets.borrow_mut();
+        let socket = match sockets.sockets.get_mut(url) {
+            Some(socket) if socket.connection_id == id => socket,
+            _ => return Vec::new(),
+        };
+        match status {
+            WebSocketStatus::Opened => {
+                socket.open = true;
+                socket.attempts = 0;
+                for index in 0..socket.rooms.len() {
+                    if socket.rooms[index].active {
+                        socket.join(index);
+                    }
+                }
+                let owned = url.to_owned();
+                let heartbeat = move || heartbeat(&owned, id);
+                socket.timer = Some(socket.timers.interval(HEARTBEAT, Box::new(heartbeat)));
+                Vec::new()
+            }
+            WebSocketStatus::Closed => {
+                socket.open = false;
+                socket.connection = None;
+                let delay = RECONNECTS[socket.attempts.min(RECONNECTS.len() - 1)];
+                socket.attempts += 1;
+                let owned = url.to_owned();
+                let reconnect = move || connect(&owned);
+                socket.timer = Some(socket.timers.timeout(Duration::from_secs(delay), Box::new(reconnect)));
+                socket.rooms.iter_mut()
+                    .filter(|room| room.active)
+                    .map(|room| {
+                        room.joined = false;
+                        (room.listener.clone(), Event::Disconnected)
+                    })
+                    .collect()
+            }
+        }
+    });
+    notify(listeners);
+}
+
+fn heartbeat(url: &str, id: usize) {
+    SOCKETS.with(|sockets| {
+        if let Some(socket) = sockets.borrow_mut().sockets.get_mut(url) {
+            if socket.connection_id == id && socket.open {
+                let reference = socket.next_ref();
+                socket.send(None, &reference, "phoenix", "heartbeat", &Value::Object(Map::new()));
+            }
+        }
+    });
+}
+
+fn receive(url: &str, id: usize, data: &str) {
+    let (join_ref, reference, topic, event, payload): Frame = match serde_json::from_str(data) {
+        Ok(frame) => frame,
+        Err(error) => {
+            warn!("can't parse a frame of a room: {}", error);
+            return;
+        }
+    };
+    let listener = SOCKETS.with(|sockets| {
+        let mut sockets = sockets.borrow_mut();
+        let socket = sockets.sockets.get_mut(url).filter(|socket| socket.connection_id == id)?;
+        let index = socket.rooms.iter().position(|room| room.topic == topic)?;
+        if join_ref.is_some() && join_ref != socket.rooms[index].join_ref {
+            return None;
+        }
+        let event = match event.as_str() {
+            "phx_reply" => {
+                let room = &mut socket.rooms[index];
+                if room.joined || reference.is_none() || reference != room.join_ref {
+                    return None;
+                }
+                if payload["status"] == "ok" {
+                    room.joined = true;
+                    let buffer: Vec<_> = room.buffer.drain(..).collect();
+                    for (event, payload) in buffer {
+                        socket.push(index, &event, &payload);
+                    }
+                    Event::Joined
+                } else {
+                    room.active = false;
+                    let response = &payload["response"];
+                    let reason = match response["reason"].as_str() {
+                        Some(reason) => reason.to_owned(),
+                        None => response.to_string(),
+                    };
+                    Event::Rejected(reason)
+                }
+            }
+            "phx_error" => {
+                // The process of the room crashed on the server
+                socket.join(index);
+                Event::Disconnected
+            }
+            "phx_close" => {
+                let room = &mut socket.rooms[index];
+                room.active = false;
+                room.joined = false;
+                Event::Left
+            }
+            "presence_state" => {
+                let room = &mut socket.rooms[index];
+                let state = members(&payload);
+                let joins = state.iter().filter(|member| !contains(&room.presence, member)).cloned().collect();
+                let leaves = room.presence.iter().filter(|member| !contains(&state, member)).cloned().collect();
+                room.presence = state;
+                Event::Presence(joins, leaves)
+            }
+            "presence_diff" => {
+                let room = &mut socket.rooms[index];
+                let (joins, leaves) = (members(&payload["joins"]), members(&payload["leaves"]));
+                room.presence.retain(|member| !contains(&leaves, member));
+                for member in &joins {
+                    if !contains(&room.presence, member) {
+                        room.presence.push(member.clone());
+                    }
+                }
+                Event::Presence(joins, leaves)
+            }
+            _ => Event::Message(event, payload),
+        };
+        Some((socket.rooms[index].listener.clone(), event))
+    });
+    notify(listener);
+}
+
+/// Returns connections of a map of members to `{ metas: [...] }`.
+fn members(value: &Value) -> Vec<RawMember> {
+    let mut members = Vec::new();
+    if let Some(map) = value.as_object() {
+        for (key, entry) in map {
+            for meta in entry["metas"].as_array().into_iter().flatten() {
+                let reference = meta["phx_ref"].as_str().unwrap_or_default().to_owned();
+                members.push((key.clone(), reference, meta.clone()));
+            }
+        }
+    }
+    members
+}
+
+fn contains(members: &[RawMember], member: &RawMember) -> bool {
+    members.iter().any(|other| other.0 == member.0 && other.1 == member.1)
+}
+
+fn notify<I: IntoIterator<Item = (Listener, Event)>>(listeners: I) {
+    for (listener, event) in listeners {
+        listener(event);
+    }
+}
+
+/// A service to join rooms.
+pub struct RoomService<MSG> {
+    sender: AppSender<MSG>,
+    websocket: Rc<WebSocketBackend>,
+    timers: Rc<TimerBackend>,
+}
+
+impl<MSG: 'static> RoomService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self::with_backends(sender, backend::websocket(), backend::timers())
+    }
+
+    /// Creates a new service instance which uses the backends. A connection
+    /// uses backends of the service which opened it.
+    pub fn with_backends(sender: AppSender<MSG>, websocket: Rc<WebSocketBackend>, timers: Rc<TimerBackend>) -> Self {
+        Self { sender, websocket, timers }
+    }
+
+    /// Joins the room of the `topic` by a connection to the `url` with `params`,
+    /// like a token. Messages from the `converter` are sent for events of the room:
+    /// payloads of broadcasts are parsed as `T` and metadata of members as `M`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the room was already joined by this connection, the server
+    /// allows one join of a topic.
+    pub fn join<P, T, M, F>(&mut self, url: &str, topic: &str, params: &P, converter: F) -> RoomHandle
+    where
+        P: Serialize,
+        T: DeserializeOwned + 'static,
+        M: DeserializeOwned + 'static,
+        F: Fn(RoomMsg<T, M>) -> MSG + 'static,
+    {
+        let params = serde_json::to_value(params).expect("can't serialize params of a room");
+        let tx = self.sender.clone();
+        let listener = move |event: Event| {
+            let msg = match event {
+                Event::Joined => RoomMsg::Joined,
+                Event::Rejected(reason) => RoomMsg::Rejected(reason),
+                Event::Message(event, payload) => match serde_json::from_value(payload) {
+                    Ok(payload) => RoomMsg::Message { event, payload },
+                    Err(error) => {
+                        warn!("can't parse a payload of {} in a room: {}", event, error);
+                        return;
+                    }
+                },
+                Event::Presence(joins, leaves) => RoomMsg::Presence(PresenceDiff {
+                    joins: parse_members(joins),
+                    leaves: parse_members(leaves),
+                }),
+                Event::Disconnected => RoomMsg::Disconnected,
+                Event::Left => RoomMsg::Left,
+            };
+            tx.clone().send(converter(msg));
+        };
+        let id = next_id();
+        let room = Room {
+            id,
+            topic: topic.to_owned(),
+            params,
+            join_ref: None,
+            joined: false,
+            active: true,
+            buffer: Vec::new(),
+            presence: Vec::new(),
+            listener: Rc::new(listener),
+        };
+        let (websocket, timers) = (self.websocket.clone(), self.timers.clone());
+        let fresh = SOCKETS.with(|sockets| {
+            let mut sockets = sockets.borrow_mut();
+            let socket = sockets.sockets.entry(url.to_owned()).or_insert_with(|| Socket {
+                connection_id: 0,
+                connection: None,
+                open: false,
+                next_ref: 0,
+                attempts: 0,
+                timer: None,
+                rooms: Vec::new(),
+                websocket,
+                timers,
+            });
+            if socket.rooms.iter().any(|room| room.topic == topic) {
+                panic!("room {} is already joined by a connection to {}", topic, url);
+            }
+            socket.rooms.push(room);
+            if socket.open {
+                let index = socket.rooms.len() - 1;
+                socket.join(index);
+            }
+            socket.connection.is_none() && socket.timer.is_none()
+        });
+        if fresh {
+            connect(url);
+        }
+        RoomHandle {
+            url: url.to_owned(),
+            topic: topic.to_owned(),
+            id: Some(id),
+        }
+    }
+}
+
+fn parse_members<M: DeserializeOwned>(members: Vec<RawMember>) -> Vec<Member<M>> {
+    members.into_iter()
+        .filter_map(|(key, reference, meta)| match serde_json::from_value(meta) {
+            Ok(meta) => Some(Member { key, reference, meta }),
+            Err(error) => {
+                warn!("can't parse metadata of {} in a room: {}", key, error);
+                None
+            }
+        })
+        .collect()
+}
+
+/// A handle of a joined room. The room is left when it's canceled or dropped,
+/// and the connection is closed when it has no rooms.
+pub struct RoomHandle {
+    url: String,
+    topic: String,
+    id: Option<usize>,
+}
+
+impl RoomHandle {
+    /// Returns the topic of the room.
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    /// Returns `true` if the server accepted the join and the connection is open.
+    pub fn is_joined(&self) -> bool {
+        self.with_room(|socket, index| socket.open && socket.rooms[index].joined).unwrap_or(false)
+    }
+
+    /// Pushes an event to the room. It's sent when the room is joined.
+    pub fn push<P: Serialize>(&mut self, event: &str, payload: &P) {
+        let payload = serde_json::to_value(payload).expect("can't serialize a payload of a room");
+        self.with_room(move |socket, index| {
+            if socket.open && socket.rooms[index].joined {
+                socket.push(index, event, &payload);
+            } else {
+                socket.rooms[index].buffer.push((event.to_owned(), payload));
+            }
+        });
+    }
+
+    fn with_room<F, R>(&self, action: F) -> Option<R>
+    where
+        F: FnOnce(&mut Socket, usize) -> R,
+    {
+        let id = self.id?;
+        SOCKETS.with(|sockets| {
+            let mut sockets = sockets.borrow_mut();
+            let socket = sockets.sockets.get_mut(&self.url)?;
+            let index = socket.rooms.iter().position(|room| room.id == id)?;
+            Some(action(socket, index))
+        })
+    }
+}
+
+impl Task for RoomHandle {
+    fn is_active(&self) -> bool {
+        self.id.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let id = self.id.take().expect("tried to leave a room twice");
+        let closed = SOCKETS.with(|sockets| {
+            let mut sockets = sockets.borrow_mut();
+            {
+                let socket = sockets.sockets.get_mut(&self.url)?;
+                let index = socket.rooms.iter().position(|room| room.id == id)?;
+                if socket.open && socket.rooms[index].active {
+                    socket.push(index, "phx_leave", &Value::Object(Map::new()));
+                }
+                socket.rooms.remove(index);
+                if !socket.rooms.is_empty() {
+                    return None;
+                }
+            }
+            sockets.sockets.remove(&self.url)
+        });
+        // Closing a connection notifies its status, so it's done without a borrow
+        if let Some(mut socket) = closed {
+            drop(socket.timer.take());
+            close(socket.connection.take());
+        }
+    }
+}
+
+impl Drop for RoomHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
@@ -0,0 +1,512 @@
+//! This module contains typed routes of an app.
+//!
+//! Derive `Routable` for an enum and describe path patterns of its variants
+//! with `#[at(..)]`. Parameters in braces are parsed to fields of variants:
+//!
+//! ```rust,ignore
+//! #[derive(Clone, PartialEq, Routable)]
+//! enum Route {
+//!     #[at("/")]
+//!     Home,
+//!     #[at("/posts/{id}")]
+//!     Post { id: u32 },
+//!     #[at("*")]
+//!     NotFound,
+//! }
+//!
+//! fn view(model: &Model) -> Html<Msg> {
+//!     html! {
+//!         <Router<Route>: render=|route| match route {
+//!             Route::Home => html! { <h1>{ "Home" }</h1> },
+//!             Route::Post { id } => html! { <Post: id=id, /> },
+//!             Route::NotFound => html! { <h1>{ "Not found" }</h1> },
+//!         }, />
+//!     }
+//! }
+//!
+//! router::navigate(&Route::Post { id: 42 });
+//! ```
+//!
+//! Variants are matched in their order and the `*` segment matches the rest
+//! of a path. Fields have to implement `ToString` and `FromStr` traits.
+//! A field with `#[query]` gets parameters of a query, its type has to
+//! implement `Serialize`, `Deserialize` and `Default` traits.
+//! A chunk of `#[chunk(..)]` is loaded on the first visit of a route.
+//!
+//! Nested routes split big apps into scopes. A parent route renders a layout
+//! and a `Router` with a `base` path inside of it is an outlet for child routes:
+//!
+//! ```rust,ignore
+//! #[derive(Clone, PartialEq, Routable)]
+//! enum Route {
+//!     #[at("/")]
+//!     Home,
+//!     #[at("/admin")]
+//!     #[chunk("/chunks/admin.js")]
+//!     Admin(AdminRoute),
+//! }
+//!
+//! #[derive(Clone, PartialEq, Routable)]
+//! enum AdminRoute {
+//!     #[at("/")]
+//!     Dashboard,
+//!     #[at("/users/{id}")]
+//!     User { id: u32 },
+//! }
+//!
+//! html! {
+//!     <div class="admin",>
+//!         <nav>
+//!             <Link<AdminRoute>: base="/admin", to=AdminRoute::Dashboard, label="Dashboard", />
+//!         </nav>
+//!         <Router<AdminRoute>: base="/admin", render=|route| view_admin(route), />
+//!     </div>
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::rc::Rc;
+use serde::{de, Serialize, Deserializer};
+use serde::de::{DeserializeOwned, IntoDeserializer, Visitor};
+use serde::de::value::{Error as ValueError, MapDeserializer};
+use serde_json::{self, Value as JsonValue};
+use callback::Callback;
+use component::{Link, Transformer};
+use services::history;
+use url::Url;
+
+/// A route which can be parsed from a path and converted back.
+/// Derive it for an enum with `#[derive(Routable)]`.
+pub trait Routable: Sized + Clone + PartialEq + 'static {
+    /// Parses a path with an optional query (but without a hash) to a route.
+    fn from_path(path: &str) -> Option<Self>;
+    /// Builds a path of the route with a query if the route has parameters of it.
+    fn to_path(&self) -> String;
+    /// Returns a URL of a chunk which has to be loaded before a view
+    /// of the route is rendered. Like chunks of `Lazy`, it's a separate script:
+    /// the code of the view is a part of the app anyway.
+    fn chunk(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+/// A decision of a guard about a navigation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decision {
+    /// Mount the destination route.
+    Allow,
+    /// Replace the destination with a path of another route.
+    Redirect(String),
+    /// Stay on the current route and restore its location.
+    Block,
+}
+
+/// A pending navigation which a guard has to decide about.
+/// It could be decided later, for example, when a response of
+/// a fetch call is received. A dropped navigation is blocked.
+pub struct Navigation {
+    decide: Option<Callback<Decision>>,
+}
+
+impl Navigation {
+    /// Creates a navigation which emits a decision to the callback once.
+    pub fn new(decide: Callback<Decision>) -> Self {
+        Navigation {
+            decide: Some(decide),
+        }
+    }
+
+    fn decide(&mut self, decision: Decision) {
+        if let Some(decide) = self.decide.take() {
+            decide.emit(decision);
+        }
+    }
+
+    /// Lets the router mount the destination.
+    pub fn allow(mut self) {
+        self.decide(Decision::Allow);
+    }
+
+    /// Redirects to another route instead of the destination.
+    pub fn redirect<R: Routable>(mut self, route: &R) {
+        self.decide(Decision::Redirect(route.to_path()));
+    }
+
+    /// Cancels the navigation.
+    pub fn block(mut self) {
+        self.decide(Decision::Block);
+    }
+}
+
+impl Drop for Navigation {
+    fn drop(&mut self) {
+        self.decide(Decision::Block);
+    }
+}
+
+type Check<R> = Rc<Fn(&R, Navigation)>;
+
+/// A guard which checks every navigation to a route before
+/// the router mounts the destination.
+pub struct Guard<R>(Option<Check<R>>);
+
+impl<R> Guard<R> {
+    /// Calls the guard or allows the navigation if there is no guard.
+    pub fn check(&self, route: &R, navigation: Navigation) {
+        match self.0 {
+            Some(ref guard) => (guard)(route, navigation),
+            None => navigation.allow(),
+        }
+    }
+
+    /// Returns `true` if the guard allows every navigation.
+    pub fn is_noop(&self) -> bool {
+        self.0.is_none()
+    }
+}
+
+impl<R> Default for Guard<R> {
+    fn default() -> Self {
+        Guard(None)
+    }
+}
+
+impl<R> Clone for Guard<R> {
+    fn clone(&self) -> Self {
+        Guard(self.0.clone())
+    }
+}
+
+impl<R, F: Fn(&R, Navigation) + 'static> From<F> for Guard<R> {
+    fn from(guard: F) -> Self {
+        Guard(Some(Rc::new(guard)))
+    }
+}
+
+impl<MSG, R, F> Transformer<F, Guard<R>> for Link<MSG>
+where
+    F: Fn(&R, Navigation) + 'static,
+{
+    fn transform(&self, from: F) -> Guard<R> {
+        Guard::from(from)
+    }
+}
+
+/// Navigates to the route: adds an entry to the history and notifies routers.
+pub fn navigate<R: Routable>(route: &R) {
+    history::push_state(&route.to_path());
+}
+
+/// Replaces the current entry of the history with the route.
+pub fn redirect<R: Routable>(route: &R) {
+    history::replace_state(&route.to_path());
+}
+
+/// Navigates to the URL of the same origin, like a route with a hash.
+pub fn navigate_url(url: &Url) {
+    history::push_state(&url.to_string());
+}
+
+/// Returns the current location as a `Url`.
+pub fn current_url() -> Url {
+    Url::parse(&history::location()).unwrap_or_default()
+}
+
+/// Navigates to the route nested under the `base` path.
+/// Use it inside of a layout to navigate relatively to its scope.
+pub fn navigate_in<R: Routable>(base: &str, route: &R) {
+    history::push_state(&join(base, &route.to_path()));
+}
+
+/// Replaces the current entry of the history with the route nested under the `base` path.
+pub fn redirect_in<R: Routable>(base: &str, route: &R) {
+    history::replace_state(&join(base, &route.to_path()));
+}
+
+/// Returns the rest of the path if it starts with segments of the `base`.
+/// The rest of `/admin/users` under `/admin` is `/users`.
+pub fn strip_base<'a>(base: &str, path: &'a str) -> Option<&'a str> {
+    let base = base.trim_end_matches('/');
+    if !path.starts_with(base) {
+        return None;
+    }
+    let rest = &path[base.len()..];
+    if rest.is_empty() {
+        Some("/")
+    } else if rest.starts_with('/') {
+        Some(rest)
+    } else {
+        None
+    }
+}
+
+/// Parses a route from the location nested under the `base` path.
+/// A hash of the location is ignored.
+pub fn route_of<R: Routable>(base: &str, location: &str) -> Option<R> {
+    let rest = strip_base(base, path_of(location))?;
+    R::from_path(&with_query(rest, query_of(location)))
+}
+
+/// Returns the location without a hash.
+pub fn target_of(location: &str) -> &str {
+    &location[..location.find('#').unwrap_or(location.len())]
+}
+
+/// Joins the `base` path with a nested path which could have a query.
+pub fn join(base: &str, path: &str) -> String {
+    let base = base.trim_end_matches('/');
+    let query = query_of(path);
+    let path = path_of(path).trim_start_matches('/');
+    let joined = {
+        if path.is_empty() {
+            if base.is_empty() {
+                "/".into()
+            } else {
+                base.into()
+            }
+        } else {
+            format!("{}/{}", base, path)
+        }
+    };
+    with_query(&joined, query)
+}
+
+/// Returns the path of a location without a query and a hash.
+pub fn path_of(location: &str) -> &str {
+    let end = location.find(|c| c == '?' || c == '#').unwrap_or(location.len());
+    &location[..end]
+}
+
+/// Returns the query of a location without `?` and a hash.
+pub fn query_of(location: &str) -> &str {
+    let location = &location[..location.find('#').unwrap_or(location.len())];
+    match location.find('?') {
+        Some(start) => &location[start + 1..],
+        None => "",
+    }
+}
+
+/// Returns the hash of a location without `#`.
+pub fn hash_of(location: &str) -> &str {
+    match location.find('#') {
+        Some(start) => &location[start + 1..],
+        None => "",
+    }
+}
+
+/// Appends the query to the path if it's not empty.
+pub fn with_query(path: &str, query: &str) -> String {
+    if query.is_empty() {
+        path.to_owned()
+    } else {
+        format!("{}?{}", path, query)
+    }
+}
+
+/// Parses a query (without `?`) to a value. Values of a query are strings,
+/// they are parsed when the value needs numbers or flags.
+/// The last value wins if a parameter is repeated.
+pub fn parse_query<T: DeserializeOwned>(query: &str) -> Result<T, String> {
+    let mut pairs: Vec<(String, Part)> = Vec::new();
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let mut parts = pair.splitn(2, '=');
+        let key = parts.next().unwrap_or_default().replace('+', " ");
+        let value = parts.next().unwrap_or_default().replace('+', " ");
+        let key = decode(&key).ok_or_else(|| format!("can't decode query parameter: {}", key))?;
+        let value = decode(&value).ok_or_else(|| format!("can't decode query value: {}", value))?;
+        pairs.retain(|(existing, _)| *existing != key);
+        pairs.push((key, Part(value)));
+    }
+    T::deserialize(MapDeserializer::new(pairs.into_iter())).map_err(|err| err.to_string())
+}
+
+/// Converts a value with fields to a query (without `?`). `None` fields are skipped
+/// and sequences become repeated parameters.
+pub fn to_query<T: Serialize>(value: &T) -> String {
+    let fields = match serde_json::to_value(value) {
+        Ok(JsonValue::Object(fields)) => fields,
+        Ok(JsonValue::Null) => return String::new(),
+        Ok(_) | Err(_) => {
+            warn!("query parameters have to be a struct or a map");
+            return String::new();
+        }
+    };
+    let mut pairs = Vec::new();
+    for (key, value) in fields {
+        let values = match value {
+            JsonValue::Array(values) => values,
+            value => vec![value],
+        };
+        for value in values {
+            let value = match value {
+                JsonValue::Null => continue,
+                JsonValue::String(value) => value,
+                JsonValue::Bool(value) => value.to_string(),
+                JsonValue::Number(value) => value.to_string(),
+                JsonValue::Array(_) | JsonValue::Object(_) => {
+                    warn!("nested values are not supported by queries: {}", key);
+                    continue;
+                }
+            };
+            pairs.push(format!("{}={}", encode(&key), encode(&value)));
+        }
+    }
+    pairs.join("&")
+}
+
+/// Replaces parameters of the query of the current location
+/// and notifies routers without adding an entry to the history.
+pub fn set_query<T: Serialize>(value: &T) {
+    let location = history::location();
+    let path = with_query(path_of(&location), &to_query(value));
+    let hash = hash_of(&location);
+    if hash.is_empty() {
+        history::replace_state(&path);
+    } else {
+        history::replace_state(&format!("{}#{}", path, hash));
+    }
+}
+
+/// A value of a query which could be parsed to a type a field needs.
+struct Part(String);
+
+impl<'de> IntoDeserializer<'de, ValueError> for Part {
+    type Deserializer = Part;
+
+    fn into_deserializer(self) -> Part {
+        self
+    }
+}
+
+macro_rules! parse_part {
+    ($($method:ident => $visit:ident,)*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+            match self.0.parse() {
+                Ok(value) => visitor.$visit(value),
+                Err(_) => Err(de::Error::custom(format!("can't parse query value: {}", self.0))),
+            }
+        }
+    )*};
+}
+
+impl<'de> Deserializer<'de> for Part {
+    type Error = ValueError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        visitor.visit_string(self.0)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, _: &'static str, _: &'static [&'static str], visitor: V)
+        -> Result<V::Value, ValueError> {
+        visitor.visit_enum(self.0.into_deserializer())
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _: &'static str, visitor: V)
+        -> Result<V::Value, ValueError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    parse_part! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// Matches the path with the pattern and returns decoded values of parameters.
+pub fn match_path(pattern: &str, path: &str) -> Option<HashMap<String, String>> {
+    let mut params = HashMap::new();
+    let mut parts = path.split('/').filter(|part| !part.is_empty());
+    for segment in pattern.split('/').filter(|segment| !segment.is_empty()) {
+        if segment == "*" {
+            return Some(params);
+        }
+        let part = parts.next()?;
+        if segment.starts_with('{') && segment.ends_with('}') {
+            let name = &segment[1..segment.len() - 1];
+            params.insert(name.to_owned(), decode(part)?);
+        } else if segment != part {
+            return None;
+        }
+    }
+    if parts.next().is_some() {
+        None
+    } else {
+        Some(params)
+    }
+}
+
+/// Builds a path from the pattern with encoded values of parameters.
+pub fn build_path(pattern: &str, params: &[(&str, String)]) -> String {
+    let mut path = String::new();
+    for segment in pattern.split('/').filter(|segment| !segment.is_empty()) {
+        if segment == "*" {
+            break;
+        }
+        path.push('/');
+        if segment.starts_with('{') && segment.ends_with('}') {
+            let name = &segment[1..segment.len() - 1];
+            match params.iter().find(|&&(param, _)| param == name) {
+                Some((_, value)) => path.push_str(&encode(value)),
+                None => panic!("no value for parameter {} of route {}", name, pattern),
+            }
+        } else {
+            path.push_str(segment);
+        }
+    }
+    if path.is_empty() {
+        path.push('/');
+    }
+    path
+}
+
+/// Encodes a value of a path segment or a query.
+pub fn encode(value: &str) -> String {
+    let mut encoded = String::new();
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char);
+            }
+            _ => {
+                encoded.push_str(&format!("%{:02X}", byte));
+            }
+        }
+    }
+    encoded
+}
+
+/// Decodes a value of a path segment or a query.
+/// Returns `None` if the value is not a valid encoded UTF-8 string.
+pub fn decode(value: &str) -> Option<String> {
+    let mut bytes = Vec::new();
+    let mut iter = value.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let high = iter.next().and_then(|b| (b as char).to_digit(16))?;
+            let low = iter.next().and_then(|b| (b as char).to_digit(16))?;
+            bytes.push((high * 16 + low) as u8);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
@@ -0,0 +1,429 @@
+//! This module contains an allowlist sanitizer of HTML, which is enabled with
+//! the `sanitize` feature. It keeps known formatting tags and attributes,
+//! drops scripts, styles and event handlers and links with schemes which
+//! could run code, so HTML of users could be rendered with `VRaw`:
+//!
+//! ```rust,ignore
+//! html! {
+//!     <div class="comment",>{ VRaw::sanitized(&comment.html, &HtmlSanitizer::new()) }</div>
+//! }
+//! ```
+//!
+//! The markup is parsed and written again, so the output doesn't depend on
+//! how a browser would parse invalid input. After `enforce` every `VRaw`
+//! which wasn't sanitized, like `VRaw::trusted`, is cleaned with the default
+//! sanitizer before it's inserted.
+
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use virtual_dom::Sanitizer;
+
+const TAGS: &[&str] = &[
+    "a", "abbr", "acronym", "article", "aside", "b", "bdi", "bdo", "blockquote", "br", "caption",
+    "center", "cite", "code", "col", "colgroup", "data", "dd", "del", "details", "dfn", "div", "dl",
+    "dt", "em", "figcaption", "figure", "footer", "h1", "h2", "h3", "h4", "h5", "h6", "header",
+    "hgroup", "hr", "i", "img", "ins", "kbd", "li", "mark", "nav", "ol", "p", "pre", "q", "rp", "rt",
+    "rtc", "ruby", "s", "samp", "small", "span", "strike", "strong", "sub", "summary", "sup", "table",
+    "tbody", "td", "th", "thead", "time", "tfoot", "tr", "tt", "u", "ul", "var", "wbr",
+];
+
+const GENERIC_ATTRIBUTES: &[&str] = &["dir", "lang", "title"];
+
+const TAG_ATTRIBUTES: &[(&str, &[&str])] = &[
+    ("a", &["href", "hreflang"]),
+    ("bdo", &["dir"]),
+    ("blockquote", &["cite"]),
+    ("col", &["align", "char", "charoff", "span"]),
+    ("colgroup", &["align", "char", "charoff", "span"]),
+    ("del", &["cite", "datetime"]),
+    ("hr", &["align", "size", "width"]),
+    ("img", &["align", "alt", "height", "src", "width"]),
+    ("ins", &["cite", "datetime"]),
+    ("ol", &["start"]),
+    ("q", &["cite"]),
+    ("table", &["align", "char", "charoff", "summary"]),
+    ("tbody", &["align", "char", "charoff"]),
+    ("td", &["align", "char", "charoff", "colspan", "headers", "rowspan"]),
+    ("tfoot", &["align", "char", "charoff"]),
+    ("th", &["align", "char", "charoff", "colspan", "headers", "rowspan", "scope"]),
+    ("thead", &["align", "char", "charoff"]),
+    ("time", &["datetime"]),
+    ("tr", &["align", "char", "charoff"]),
+];
+
+const SCHEMES: &[&str] = &["http", "https", "mailto", "tel"];
+
+/// Attributes which have URLs, their schemes are checked.
+const URL_ATTRIBUTES: &[&str] = &["href", "src", "cite"];
+
+/// Elements which are dropped with their contents, because their content
+/// isn't markup or runs code.
+const CLEAN_CONTENT: &[&str] = &[
+    "script", "style", "template", "noscript", "textarea", "title", "xmp", "iframe", "noembed",
+    "noframes", "plaintext",
+];
+
+const VOID_ELEMENTS: &[&str] = &["area", "br", "col", "hr", "img", "wbr"];
+
+thread_local! {
+    static ENFORCED: Cell<bool> = Cell::new(false);
+}
+
+/// Makes every `VRaw` which wasn't created with a sanitizer be cleaned
+/// with the default `HtmlSanitizer` before it's inserted into the DOM.
+pub fn enforce() {
+    ENFORCED.with(|enforced| enforced.set(true));
+}
+
+/// Returns `true` if unsanitized HTML is cleaned before it's inserted.
+pub fn is_enforced() -> bool {
+    ENFORCED.with(|enforced| enforced.get())
+}
+
+/// Cleans the HTML with the default `HtmlSanitizer`.
+pub fn clean(html: &str) -> String {
+    HtmlSanitizer::new().clean(html)
+}
+
+/// An allowlist sanitizer of HTML.
+#[derive(Debug, Clone)]
+pub struct HtmlSanitizer {
+    tags: HashSet<String>,
+    attributes: HashMap<String, HashSet<String>>,
+    schemes: HashSet<String>,
+    link_rel: Option<String>,
+}
+
+impl Default for HtmlSanitizer {
+    fn default() -> Self {
+        HtmlSanitizer::new()
+    }
+}
+
+impl HtmlSanitizer {
+    /// Creates a sanitizer which allows common formatting tags, links with
+    /// `http`, `https`, `mailto` and `tel` schemes and images. Links get
+    /// `rel="noopener noreferrer"`.
+    pub fn new() -> Self {
+        let mut attributes = HashMap::new();
+        attributes.insert("*".to_owned(), GENERIC_ATTRIBUTES.iter().map(|name| name.to_string()).collect());
+        for (tag, names) in TAG_ATTRIBUTES {
+            attributes.insert(tag.to_string(), names.iter().map(|name| name.to_string()).collect());
+        }
+        HtmlSanitizer {
+            tags: TAGS.iter().map(|tag| tag.to_string()).collect(),
+            attributes,
+            schemes: SCHEMES.iter().map(|scheme| scheme.to_string()).collect(),
+            link_rel: Some("noopener noreferrer".to_owned()),
+        }
+    }
+
+    /// Allows more tags.
+    pub fn allow_tags(mut self, tags: &[&str]) -> Self {
+        self.tags.extend(tags.iter().map(|tag| tag.to_lowercase()));
+        self
+    }
+
+    /// Removes tags, their contents are kept.
+    pub fn remove_tags(mut self, tags: &[&str]) -> Self {
+        for tag in tags {
+            self.tags.remove(&tag.to_lowercase());
+        }
+        self
+    }
+
+    /// Allows more attributes of the tag, `*` is any tag. Attributes of
+    /// inline handlers and `style` are never kept.
+    pub fn allow_attributes(mut self, tag: &str, attributes: &[&str]) -> Self {
+        self.attributes.entry(tag.to_lowercase()).or_default()
+            .extend(attributes.iter().map(|name| name.to_lowercase()));
+        self
+    }
+
+    /// Allows more schemes of URLs, like `ftp`. Relative URLs are always allowed.
+    pub fn allow_schemes(mut self, schemes: &[&str]) -> Self {
+        self.schemes.extend(schemes.iter().map(|scheme| scheme.to_lowercase()));
+        self
+    }
+
+    /// Sets the `rel` of links, `None` keeps `rel` of the source if it's allowed.
+    pub fn link_rel(mut self, rel: Option<&str>) -> Self {
+        self.link_rel = rel.map(str::to_owned);
+        self
+    }
+
+    /// Returns the HTML with allowed tags and attributes only.
+    pub fn clean(&self, html: &str) -> String {
+        let mut out = String::with_capacity(html.len());
+        let mut open: Vec<String> = Vec::new();
+        let mut rest = html;
+        while !rest.is_empty() {
+            if rest.starts_with("<!--") {
+                rest = rest[4..].find("-->").map(|end| &rest[4 + end + 3..]).unwrap_or("");
+            } else if rest.starts_with("<!") || rest.starts_with("<?") {
+                // Doctypes, CDATA and processing instructions
+                rest = rest.find('>').map(|end| &rest[end + 1..]).unwrap_or("");
+            } else if rest.starts_with('<') {
+                match parse_tag(rest) {
+                    Some((tag, len)) => {
+                        rest = &rest[len..];
+                        if !tag.closing && CLEAN_CONTENT.contains(&tag.name.as_str()) {
+                            rest = skip_content(rest, &tag.name);
+                        } else {
+                            self.write_tag(tag, &mut open, &mut out);
+                        }
+                    }
+                    None => {
+                        out.push_str("&lt;");
+                        rest = &rest[1..];
+                    }
+                }
+            } else {
+                let end = rest.find('<').unwrap_or(rest.len());
+                escape_text(&rest[..end], &mut out);
+                rest = &rest[end..];
+            }
+        }
+        while let Some(tag) = open.pop() {
+            out.push_str("</");
+            out.push_str(&tag);
+            out.push('>');
+        }
+        out
+    }
+
+    fn write_tag(&self, tag: Tag, open: &mut Vec<String>, out: &mut String) {
+        if !self.tags.contains(&tag.name) {
+            return;
+        }
+        if tag.closing {
+            if let Some(index) = open.iter().rposition(|name| *name == tag.name) {
+                for name in open.drain(index..).rev() {
+                    out.push_str("</");
+                    out.push_str(&name);
+                    out.push('>');
+                }
+            }
+            return;
+        }
+        out.push('<');
+        out.push_str(&tag.name);
+        let mut seen = HashSet::new();
+        let mut link = false;
+        for (name, value) in tag.attributes {
+            if !self.is_allowed(&tag.name, &name) || !seen.insert(name.clone()) {
+                continue;
+            }
+            if name == "rel" && tag.name == "a" && self.link_rel.is_some() {
+                continue;
+            }
+            if URL_ATTRIBUTES.contains(&name.as_str()) && !self.is_safe_url(&value) {
+                continue;
+            }
+            link = link || name == "href";
+            write_attribute(&name, &value, out);
+        }
+        if tag.name == "a" && link {
+            if let Some(ref rel) = self.link_rel {
+                write_attribute("rel", rel, out);
+            }
+        }
+        out.push('>');
+        if !VOID_ELEMENTS.contains(&tag.name.as_str()) {
+            open.push(tag.name);
+        }
+    }
+
+    fn is_allowed(&self, tag: &str, attribute: &str) -> bool {
+        if attribute.starts_with("on") || attribute == "style" {
+            return false;
+        }
+        let allowed = |key: &str| self.attributes.get(key).map(|names| names.contains(attribute)).unwrap_or(false);
+        allowed("*") || allowed(tag)
+    }
+
+    fn is_safe_url(&self, url: &str) -> bool {
+        // Browsers ignore these characters in URLs, like in `java\tscript:`
+        let url: String = url.chars()
+            .filter(|c| *c != '\t' && *c != '\n' && *c != '\r')
+            .skip_while(|c| c.is_whitespace() || c.is_control())
+            .collect();
+        match url.find([':', '/', '?', '#']) {
+            Some(index) if url[index..].starts_with(':') => self.schemes.contains(&url[..index].to_lowercase()),
+            _ => true,
+        }
+    }
+}
+
+impl Sanitizer for HtmlSanitizer {
+    fn sanitize(&self, html: &str) -> String {
+        self.clean(html)
+    }
+}
+
+struct Tag {
+    name: String,
+    closing: bool,
+    attributes: Vec<(String, String)>,
+}
+
+/// Parses a tag at the start of the text. Returns the tag and its length,
+/// or `None` if it isn't a tag, like `a < b`.
+fn parse_tag(text: &str) -> Option<(Tag, usize)> {
+    let bytes = text.as_bytes();
+    let mut i = 1;
+    let closing = bytes.get(i) == Some(&b'/');
+    if closing {
+        i += 1;
+    }
+    if !bytes.get(i).map(u8::is_ascii_alphabetic).unwrap_or(false) {
+        return None;
+    }
+    let start = i;
+    while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'-') {
+        i += 1;
+    }
+    let name = text[start..i].to_ascii_lowercase();
+    let mut attributes = Vec::new();
+    loop {
+        while i < bytes.len() && (bytes[i].is_ascii_whitespace() || bytes[i] == b'/') {
+            i += 1;
+        }
+        match bytes.get(i) {
+            None => return None,
+            Some(b'>') => break,
+            _ => {}
+        }
+        let start = i;
+        while i < bytes.len() && !bytes[i].is_ascii_whitespace() && !b"=>/".contains(&bytes[i]) {
+            i += 1;
+        }
+        // A name starts with `=` or `/` only in broken markup
+        if i == start {
+            i += 1;
+            continue;
+        }
+        let attribute = text[start..i].to_ascii_lowercase();
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        let mut value = String::new();
+        if bytes.get(i) == Some(&b'=') {
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            let raw = match bytes.get(i) {
+                Some(&quote) if quote == b'"' || quote == b'\'' => {
+                    let end = text[i + 1..].find(quote as char)? + i + 1;
+                    let raw = &text[i + 1..end];
+                    i = end + 1;
+                    raw
+                }
+                _ => {
+                    let start = i;
+                    while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'>' {
+                        i += 1;
+                    }
+                    &text[start..i]
+                }
+            };
+            value = decode_entities(raw);
+        }
+        attributes.push((attribute, value));
+    }
+    Some((Tag { name, closing, attributes }, i + 1))
+}
+
+/// Skips the content of the element up to its closing tag.
+fn skip_content<'a>(text: &'a str, name: &str) -> &'a str {
+    let closing = format!("</{}", name);
+    let lower = text.to_ascii_lowercase();
+    match lower.find(&closing) {
+        Some(start) => text[start..].find('>').map(|end| &text[start + end + 1..]).unwrap_or(""),
+        None => "",
+    }
+}
+
+/// Decodes character references of an attribute value, so `&#106;avascript:`
+/// is checked as `javascript:`.
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let (decoded, len) = decode_entity(rest).unwrap_or(('&', 1));
+        out.push(decoded);
+        rest = &rest[len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Decodes a reference at the start of the text, the semicolon of numeric
+/// ones is optional like in browsers.
+fn decode_entity(text: &str) -> Option<(char, usize)> {
+    let body = &text[1..];
+    if let Some(number) = body.strip_prefix('#') {
+        let (digits, radix, offset) = if number.starts_with('x') || number.starts_with('X') {
+            (number[1..].chars().take_while(char::is_ascii_hexdigit).count(), 16, 3)
+        } else {
+            (number.chars().take_while(char::is_ascii_digit).count(), 10, 2)
+        };
+        if digits == 0 {
+            return None;
+        }
+        let code = u32::from_str_radix(&text[offset..offset + digits], radix).unwrap_or(0xfffd);
+        let decoded = ::std::char::from_u32(code).filter(|c| *c != '\0').unwrap_or('\u{fffd}');
+        let semicolon = if text[offset + digits..].starts_with(';') { 1 } else { 0 };
+        return Some((decoded, offset + digits + semicolon));
+    }
+    let end = body.find(';')?;
+    let decoded = match &body[..end] {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "colon" => ':',
+        "Tab" => '\t',
+        "NewLine" => '\n',
+        "nbsp" => '\u{a0}',
+        _ => return None,
+    };
+    Some((decoded, end + 2))
+}
+
+fn write_attribute(name: &str, value: &str, out: &mut String) {
+    out.push(' ');
+    out.push_str(name);
+    out.push_str("=\"");
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Escapes text, but keeps character references which are already there.
+fn escape_text(text: &str, out: &mut String) {
+    for (index, c) in text.char_indices() {
+        match c {
+            '&' if !is_reference(&text[index..]) => out.push_str("&amp;"),
+            '>' => out.push_str("&gt;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+fn is_reference(text: &str) -> bool {
+    let body = &text[1..];
+    let len = body.chars().take_while(|c| c.is_ascii_alphanumeric() || *c == '#').count();
+    len > 0 && body[len..].starts_with(';')
+}
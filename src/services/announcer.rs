@@ -0,0 +1,55 @@
+//! This module contains a service which announces status messages
+//! to screen readers with ARIA live regions.
+
+use std::rc::Rc;
+use super::backend::{self, AnnouncerBackend};
+
+/// How urgent an announcement is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Politeness {
+    /// The message is read when a user is idle, like "3 results loaded".
+    Polite,
+    /// The message interrupts a user, like "Saving failed".
+    Assertive,
+}
+
+/// A service which reads messages to users of screen readers.
+/// Live regions are added to the end of the body when they're used first,
+/// and both regions are shared by all components.
+pub struct AnnouncerService {
+    backend: Rc<AnnouncerBackend>,
+}
+
+impl AnnouncerService {
+    /// Creates a new service instance.
+    pub fn new() -> Self {
+        AnnouncerService::with_backend(backend::announcer())
+    }
+
+    /// Creates a new service instance which announces with the `backend`.
+    pub fn with_backend(backend: Rc<AnnouncerBackend>) -> Self {
+        AnnouncerService { backend }
+    }
+
+    /// Announces a status message politely.
+    pub fn announce(&mut self, text: &str) {
+        self.announce_with(Politeness::Polite, text);
+    }
+
+    /// Announces an urgent message which interrupts a user.
+    pub fn alert(&mut self, text: &str) {
+        self.announce_with(Politeness::Assertive, text);
+    }
+
+    /// Announces a message with the politeness. The same message announced
+    /// twice is read twice.
+    pub fn announce_with(&mut self, politeness: Politeness, text: &str) {
+        self.backend.announce(politeness, text);
+    }
+}
+
+impl Default for AnnouncerService {
+    fn default() -> Self {
+        AnnouncerService::new()
+    }
+}
@@ -0,0 +1,2185 @@
+//! This module contains interfaces of backends which services use to reach
+//! a browser: timers, storages, fetches, websockets, live regions, formatters, messages of windows,
+//! the visibility of the page, intersections of elements, layouts of floating elements, random bytes, unloads of the page, beacons, activity of a user, the install prompt of an app, media elements, measurements of text, printing, the selection, hints in the head, long tasks, shared listeners of the document and inspectors of `devtools`. Services get the
+//! backends which are set for the thread when they're created, so tests
+//! could replace them with mocks which control time and responses:
+//!
+//! ```rust,ignore
+//! let clock = MockClock::new();
+//! let _installed = clock.install();
+//! let mut timeout = TimeoutService::new(sender);
+//! ```
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::rc::Rc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+#[cfg(not(any(target_arch = "wasm32", target_os = "emscripten")))]
+use std::time::Instant;
+use stdweb::Value;
+use stdweb::web::Element;
+use stdweb::unstable::TryFrom;
+use format::{Storable, Restorable};
+use virtual_dom::NodeRef;
+use super::{Task, is_done, to_ms};
+use super::fetch::{Request, Response};
+use super::storage::Scope;
+use super::websocket::WebSocketStatus;
+use super::announcer::Politeness;
+use super::messaging::{Channel, Target};
+use super::intersection::IntersectionOptions;
+use super::layout::LayoutEvent;
+use super::install::{InstallChoice, InstallEvent};
+use super::media::{MediaCommand, MediaError, MediaEvent, MediaState};
+use super::text::TextMetrics;
+use super::print::{PrintEvent, PrintJob};
+use super::selection::Selection;
+use super::long_task::LongTask;
+use super::document::DocumentEvent;
+use position::{Layout, Rect};
+use prefetch::Hint;
+use super::intl::{self as intl_service, NumberOptions, NumberStyle, DateOptions, CalendarField, RelativeUnit};
+use date::Date;
+
+/// A backend of timeouts and intervals.
+pub trait TimerBackend {
+    /// Calls the callback once after the `delay`.
+    fn timeout(&self, delay: Duration, callback: Box<FnMut()>) -> Box<Task>;
+    /// Calls the callback after every `period` until the task is canceled.
+    fn interval(&self, period: Duration, callback: Box<FnMut()>) -> Box<Task>;
+    /// Returns a monotonic time to measure how long work takes. It doesn't move by default.
+    fn monotonic(&self) -> Duration {
+        Duration::from_secs(0)
+    }
+}
+
+/// A backend of key-value storages.
+pub trait StorageBackend {
+    /// Returns a value of the key.
+    fn get(&self, scope: Scope, key: &str) -> Option<String>;
+    /// Sets a value of the key.
+    fn set(&self, scope: Scope, key: &str, value: &str);
+    /// Removes the key.
+    fn remove(&self, scope: Scope, key: &str);
+}
+
+/// A backend of HTTP requests.
+pub trait FetchBackend {
+    /// Sends the request and calls the callback with the response. The body of
+    /// the response is `Err` with the text if the body wasn't read completely.
+    fn fetch(&self, request: Request<Storable>, callback: Box<FnMut(Response<Restorable>)>) -> Box<Task>;
+}
+
+/// A backend of websocket connections.
+pub trait WebSocketBackend {
+    /// Connects to the `url` and calls callbacks with received data and
+    /// changes of the status.
+    fn connect(&self, url: &str, on_message: Box<FnMut(String)>, on_status: Box<FnMut(WebSocketStatus)>)
+        -> Box<Connection>;
+}
+
+/// A backend of announcements for screen readers.
+pub trait AnnouncerBackend {
+    /// Reads the text to a user with the politeness.
+    fn announce(&self, politeness: Politeness, text: &str);
+}
+
+/// A backend of formatters of numbers, dates and relative times.
+/// The locale is `None` for the locale of a user.
+pub trait IntlBackend {
+    /// Formats a number with the options.
+    fn format_number(&self, locale: Option<&str>, number: f64, options: &NumberOptions) -> String;
+    /// Formats a time with the options.
+    fn format_date(&self, locale: Option<&str>, time: SystemTime, options: &DateOptions) -> String;
+    /// Formats a part of a calendar date.
+    fn format_calendar(&self, locale: Option<&str>, date: Date, field: CalendarField) -> String;
+    /// Formats a value of the unit relatively to now.
+    fn format_relative(&self, locale: Option<&str>, value: f64, unit: RelativeUnit) -> String;
+    /// Returns the ISO 8601 weekday which weeks start on, Monday by default.
+    fn first_weekday(&self, _locale: Option<&str>) -> u32 {
+        1
+    }
+    /// Returns the current time to measure relative times from.
+    fn now(&self) -> SystemTime;
+    /// Returns the date of today, in UTC by default.
+    fn today(&self) -> Date {
+        Date::from_time(self.now())
+    }
+}
+
+/// A backend of messages between windows.
+pub trait MessagingBackend {
+    /// Posts an envelope with data to the window of the channel.
+    fn post(&self, channel: &Channel, data: String);
+    /// Calls the callback with data of envelopes which the window of the channel
+    /// sent from its origin, until the task is canceled.
+    fn listen(&self, channel: &Channel, callback: Box<FnMut(String)>) -> Box<Task>;
+}
+
+/// A backend of the visibility of the page.
+pub trait VisibilityBackend {
+    /// Returns `true` if the page is hidden, like a tab in the background.
+    fn is_hidden(&self) -> bool;
+    /// Calls the callback with `true` when the page is hidden or unloaded and with
+    /// `false` when it's shown again, until the task is canceled.
+    fn listen(&self, callback: Box<FnMut(bool)>) -> Box<Task>;
+}
+
+/// A backend of the connectivity of the browser.
+pub trait ConnectivityBackend {
+    /// Returns `false` if the browser is offline.
+    fn is_online(&self) -> bool;
+    /// Calls the callback with `false` when the browser goes offline and with
+    /// `true` when it's online again, until the task is canceled.
+    fn listen(&self, callback: Box<FnMut(bool)>) -> Box<Task>;
+}
+
+/// A backend of observers of intersections of elements with the viewport.
+pub trait IntersectionBackend {
+    /// Calls the callback with `true` when the element of the reference enters
+    /// the viewport and with `false` when it leaves it, until the task is canceled.
+    /// The element is observed when it's rendered.
+    fn observe(&self, target: NodeRef, options: &IntersectionOptions, callback: Box<FnMut(bool)>) -> Box<Task>;
+}
+
+/// A backend which measures floating elements and their anchors.
+pub trait LayoutBackend {
+    /// Calls the callback with layouts of the element of the reference and
+    /// the anchor with the id when they're rendered, resized or scrolled,
+    /// and when a pointer is pressed outside of them, until the task is canceled.
+    fn track(&self, anchor: String, floating: NodeRef, callback: Box<FnMut(LayoutEvent)>) -> Box<Task>;
+}
+
+/// A backend of secure random bytes, like keys of sign-in flows.
+pub trait RandomBackend {
+    /// Fills the buffer with random bytes.
+    fn fill(&self, bytes: &mut [u8]);
+}
+
+/// A backend of the `beforeunload` event of the page.
+pub trait UnloadBackend {
+    /// Calls the callback before the page is reloaded or closed, until the task
+    /// is canceled. A browser asks a user to confirm leaving if it returns `true`.
+    fn listen(&self, callback: Box<FnMut() -> bool>) -> Box<Task>;
+}
+
+/// A backend of `navigator.sendBeacon`, which sends data even while the page is unloaded.
+pub trait BeaconBackend {
+    /// Queues a `POST` of the JSON data to the url. Returns `false` if the
+    /// browser refused it, like when the data is too large.
+    fn send(&self, url: &str, data: &str) -> bool;
+}
+
+/// A backend of input events of a user on the page.
+pub trait ActivityBackend {
+    /// Calls the callback when a user moves a pointer, presses a key, touches
+    /// the screen or scrolls, at most once a second, until the task is canceled.
+    fn listen(&self, callback: Box<FnMut()>) -> Box<Task>;
+}
+
+/// A backend of the install prompt of a Progressive Web App.
+pub trait InstallBackend {
+    /// Returns `true` if a browser offered to install the app and its prompt wasn't shown yet.
+    fn is_installable(&self) -> bool;
+    /// Calls the callback with `Installable` when a browser offers to install the app
+    /// and with `Installed` when it's installed, until the task is canceled.
+    fn listen(&self, callback: Box<FnMut(InstallEvent)>) -> Box<Task>;
+    /// Shows the offered prompt and calls the callback with the choice of a user.
+    /// Returns `false` if nothing is offered, a prompt is shown once.
+    fn prompt(&self, callback: Box<FnMut(InstallChoice)>) -> bool;
+}
+
+/// A backend of `<audio>` and `<video>` elements.
+pub trait MediaBackend {
+    /// Calls the callback with events of the media element of the reference
+    /// until the task is canceled. The element is attached when it's rendered.
+    fn attach(&self, target: NodeRef, callback: Box<FnMut(MediaEvent)>) -> Box<MediaControl>;
+}
+
+/// An attached media element of a `MediaBackend`. Canceling the task detaches it.
+pub trait MediaControl: Task {
+    /// Applies the command to the element. Returns `false` if it isn't rendered.
+    fn control(&mut self, command: MediaCommand) -> bool;
+    /// Returns the state of the element or `None` if it isn't rendered.
+    fn state(&self) -> Option<MediaState>;
+}
+
+/// A backend of measurements of text.
+pub trait TextBackend {
+    /// Measures the text on one line with the CSS font.
+    fn measure(&self, font: &str, text: &str) -> TextMetrics;
+    /// Returns the rectangle of the rendered contents of the element.
+    fn content_rect(&self, target: &NodeRef) -> Option<Rect>;
+}
+
+/// A backend of printing.
+pub trait PrintBackend {
+    /// Opens the print dialog for the job. Returns `false` if there's
+    /// nothing to print, like an element which isn't rendered.
+    fn print(&self, job: PrintJob) -> bool;
+    /// Calls the callback before and after printing.
+    fn listen(&self, callback: Box<FnMut(PrintEvent)>) -> Box<Task>;
+}
+
+/// A backend of the selection of the document.
+pub trait SelectionBackend {
+    /// Returns the selection with nodes mapped to the targets.
+    fn selection(&self, targets: &[NodeRef]) -> Option<Selection>;
+    /// Selects characters of the text of the target.
+    fn select(&self, target: &NodeRef, offsets: Range<usize>) -> bool;
+    /// Removes the selection.
+    fn clear(&self);
+    /// Calls the callback when the selection is changed.
+    fn listen(&self, callback: Box<FnMut()>) -> Box<Task>;
+}
+
+/// A backend of hints in the head of the document.
+pub trait HeadBackend {
+    /// Inserts a `<link>` tag of the hint.
+    fn insert_hint(&self, hint: &Hint);
+}
+
+/// A backend of long tasks of the main thread.
+pub trait LongTaskBackend {
+    /// Calls the callback with every long task, without activities.
+    fn observe(&self, callback: Box<FnMut(LongTask)>) -> Box<Task>;
+}
+
+/// A backend of listeners of the document.
+pub trait DocumentBackend {
+    /// Calls the callback with every event of the kind which the document
+    /// captures, like `keydown`.
+    fn listen(&self, kind: &'static str, callback: Box<FnMut(DocumentEvent)>) -> Box<Task>;
+}
+
+/// A backend of the bridge to an inspector of the `devtools` feature.
+#[cfg(feature = "devtools")]
+pub trait DevtoolsBackend {
+    /// Posts an event to an inspector.
+    fn post(&self, kind: &str, payload: &::serde_json::Value);
+    /// Calls the callback with the type of every request of an inspector, like `inspect`.
+    fn listen(&self, callback: Box<FnMut(String)>) -> Box<Task>;
+}
+
+/// An open connection of a `WebSocketBackend`. Canceling the task closes it.
+pub trait Connection: Task {
+    /// Sends data to a server.
+    fn send(&mut self, data: String);
+}
+
+thread_local! {
+    static TIMERS: RefCell<Rc<TimerBackend>> = RefCell::new(Rc::new(Browser));
+    static STORAGE: RefCell<Rc<StorageBackend>> = RefCell::new(Rc::new(Browser));
+    static FETCH: RefCell<Rc<FetchBackend>> = RefCell::new(Rc::new(Browser));
+    static WEBSOCKET: RefCell<Rc<WebSocketBackend>> = RefCell::new(Rc::new(Browser));
+    static ANNOUNCER: RefCell<Rc<AnnouncerBackend>> = RefCell::new(Rc::new(Browser));
+    static INTL: RefCell<Rc<IntlBackend>> = RefCell::new(Rc::new(Browser));
+    static MESSAGING: RefCell<Rc<MessagingBackend>> = RefCell::new(Rc::new(Browser));
+    static VISIBILITY: RefCell<Rc<VisibilityBackend>> = RefCell::new(Rc::new(Browser));
+    static CONNECTIVITY: RefCell<Rc<ConnectivityBackend>> = RefCell::new(Rc::new(Browser));
+    static INTERSECTION: RefCell<Rc<IntersectionBackend>> = RefCell::new(Rc::new(Browser));
+    static LAYOUT: RefCell<Rc<LayoutBackend>> = RefCell::new(Rc::new(Browser));
+    static RANDOM: RefCell<Rc<RandomBackend>> = RefCell::new(Rc::new(Browser));
+    static UNLOAD: RefCell<Rc<UnloadBackend>> = RefCell::new(Rc::new(Browser));
+    static BEACON: RefCell<Rc<BeaconBackend>> = RefCell::new(Rc::new(Browser));
+    static ACTIVITY: RefCell<Rc<ActivityBackend>> = RefCell::new(Rc::new(Browser));
+    static INSTALL: RefCell<Rc<InstallBackend>> = RefCell::new(Rc::new(Browser));
+    static MEDIA: RefCell<Rc<MediaBackend>> = RefCell::new(Rc::new(Browser));
+    static TEXT: RefCell<Rc<TextBackend>> = RefCell::new(Rc::new(Browser));
+    static PRINT: RefCell<Rc<PrintBackend>> = RefCell::new(Rc::new(Browser));
+    static SELECTION: RefCell<Rc<SelectionBackend>> = RefCell::new(Rc::new(Browser));
+    static HEAD: RefCell<Rc<HeadBackend>> = RefCell::new(Rc::new(Browser));
+    static LONG_TASKS: RefCell<Rc<LongTaskBackend>> = RefCell::new(Rc::new(Browser));
+    static DOCUMENT: RefCell<Rc<DocumentBackend>> = RefCell::new(Rc::new(Browser));
+    #[cfg(feature = "devtools")]
+    static DEVTOOLS: RefCell<Rc<DevtoolsBackend>> = RefCell::new(Rc::new(Browser));
+}
+
+/// Sets the backend of timers for services created later and returns the previous one.
+pub fn set_timers(backend: Rc<TimerBackend>) -> Rc<TimerBackend> {
+    TIMERS.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of storages for services created later and returns the previous one.
+pub fn set_storage(backend: Rc<StorageBackend>) -> Rc<StorageBackend> {
+    STORAGE.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of fetches for services created later and returns the previous one.
+pub fn set_fetch(backend: Rc<FetchBackend>) -> Rc<FetchBackend> {
+    FETCH.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of websockets for services created later and returns the previous one.
+pub fn set_websocket(backend: Rc<WebSocketBackend>) -> Rc<WebSocketBackend> {
+    WEBSOCKET.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of announcers for services created later and returns the previous one.
+pub fn set_announcer(backend: Rc<AnnouncerBackend>) -> Rc<AnnouncerBackend> {
+    ANNOUNCER.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of formatters for services created later and returns the previous one.
+pub fn set_intl(backend: Rc<IntlBackend>) -> Rc<IntlBackend> {
+    INTL.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of messages for services created later and returns the previous one.
+pub fn set_messaging(backend: Rc<MessagingBackend>) -> Rc<MessagingBackend> {
+    MESSAGING.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of the visibility for services created later and returns the previous one.
+pub fn set_visibility(backend: Rc<VisibilityBackend>) -> Rc<VisibilityBackend> {
+    VISIBILITY.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of the connectivity for services created later and returns the previous one.
+pub fn set_connectivity(backend: Rc<ConnectivityBackend>) -> Rc<ConnectivityBackend> {
+    CONNECTIVITY.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of intersections for services created later and returns the previous one.
+pub fn set_intersection(backend: Rc<IntersectionBackend>) -> Rc<IntersectionBackend> {
+    INTERSECTION.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of layouts for services created later and returns the previous one.
+pub fn set_layout(backend: Rc<LayoutBackend>) -> Rc<LayoutBackend> {
+    LAYOUT.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of random bytes for services created later and returns the previous one.
+pub fn set_random(backend: Rc<RandomBackend>) -> Rc<RandomBackend> {
+    RANDOM.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of unloads for services created later and returns the previous one.
+pub fn set_unload(backend: Rc<UnloadBackend>) -> Rc<UnloadBackend> {
+    UNLOAD.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of beacons for services created later and returns the previous one.
+pub fn set_beacon(backend: Rc<BeaconBackend>) -> Rc<BeaconBackend> {
+    BEACON.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of activity for services created later and returns the previous one.
+pub fn set_activity(backend: Rc<ActivityBackend>) -> Rc<ActivityBackend> {
+    ACTIVITY.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of the install prompt for services created later and returns the previous one.
+pub fn set_install(backend: Rc<InstallBackend>) -> Rc<InstallBackend> {
+    INSTALL.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of media elements for services created later and returns the previous one.
+pub fn set_media(backend: Rc<MediaBackend>) -> Rc<MediaBackend> {
+    MEDIA.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of measurements of text for services created later and returns the previous one.
+pub fn set_text(backend: Rc<TextBackend>) -> Rc<TextBackend> {
+    TEXT.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of printing for services created later and returns the previous one.
+pub fn set_print(backend: Rc<PrintBackend>) -> Rc<PrintBackend> {
+    PRINT.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of the selection for services created later and returns the previous one.
+pub fn set_selection(backend: Rc<SelectionBackend>) -> Rc<SelectionBackend> {
+    SELECTION.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of hints in the head for hints inserted later and returns the previous one.
+pub fn set_head(backend: Rc<HeadBackend>) -> Rc<HeadBackend> {
+    HEAD.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of long tasks for services created later and returns the previous one.
+pub fn set_long_tasks(backend: Rc<LongTaskBackend>) -> Rc<LongTaskBackend> {
+    LONG_TASKS.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of listeners of the document for services created later and returns the previous one.
+pub fn set_document(backend: Rc<DocumentBackend>) -> Rc<DocumentBackend> {
+    DOCUMENT.with(|current| current.replace(backend))
+}
+
+/// Sets the backend of inspectors for the thread and returns the previous one.
+/// The bridge listens for requests with the backend which is set when it posts the first event.
+#[cfg(feature = "devtools")]
+pub fn set_devtools(backend: Rc<DevtoolsBackend>) -> Rc<DevtoolsBackend> {
+    DEVTOOLS.with(|current| current.replace(backend))
+}
+
+pub(crate) fn timers() -> Rc<TimerBackend> {
+    TIMERS.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn storage() -> Rc<StorageBackend> {
+    STORAGE.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn fetch() -> Rc<FetchBackend> {
+    FETCH.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn websocket() -> Rc<WebSocketBackend> {
+    WEBSOCKET.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn announcer() -> Rc<AnnouncerBackend> {
+    ANNOUNCER.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn intl() -> Rc<IntlBackend> {
+    INTL.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn messaging() -> Rc<MessagingBackend> {
+    MESSAGING.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn visibility() -> Rc<VisibilityBackend> {
+    VISIBILITY.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn connectivity() -> Rc<ConnectivityBackend> {
+    CONNECTIVITY.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn intersection() -> Rc<IntersectionBackend> {
+    INTERSECTION.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn layout() -> Rc<LayoutBackend> {
+    LAYOUT.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn random() -> Rc<RandomBackend> {
+    RANDOM.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn unload() -> Rc<UnloadBackend> {
+    UNLOAD.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn beacon() -> Rc<BeaconBackend> {
+    BEACON.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn activity() -> Rc<ActivityBackend> {
+    ACTIVITY.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn install() -> Rc<InstallBackend> {
+    INSTALL.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn media() -> Rc<MediaBackend> {
+    MEDIA.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn text() -> Rc<TextBackend> {
+    TEXT.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn print() -> Rc<PrintBackend> {
+    PRINT.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn selection() -> Rc<SelectionBackend> {
+    SELECTION.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn head() -> Rc<HeadBackend> {
+    HEAD.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn long_tasks() -> Rc<LongTaskBackend> {
+    LONG_TASKS.with(|current| current.borrow().clone())
+}
+
+pub(crate) fn document() -> Rc<DocumentBackend> {
+    DOCUMENT.with(|current| current.borrow().clone())
+}
+
+#[cfg(feature = "devtools")]
+pub(crate) fn devtools() -> Rc<DevtoolsBackend> {
+    DEVTOOLS.with(|current| current.borrow().clone())
+}
+
+/// Backends which use APIs of a browser. They're used by default.
+pub struct Browser;
+
+impl TimerBackend for Browser {
+    fn timeout(&self, delay: Duration, callback: Box<FnMut()>) -> Box<Task> {
+        let mut callback = callback;
+        let callback = move || callback();
+        let ms = to_ms(delay);
+        let handle = js! {
+            var callback = @{callback};
+            var handle = {
+                done: false,
+                callback,
+            };
+            let action = function() {
+                handle.done = true;
+                callback();
+                callback.drop();
+            };
+            let delay = @{ms};
+            handle.timeout_id = setTimeout(action, delay);
+            return handle;
+        };
+        Box::new(BrowserTimeout(Some(handle)))
+    }
+
+    fn interval(&self, period: Duration, callback: Box<FnMut()>) -> Box<Task> {
+        let mut callback = callback;
+        let callback = move || callback();
+        let ms = to_ms(period);
+        let handle = js! {
+            var callback = @{callback};
+            let action = function() {
+                callback();
+            };
+            let delay = @{ms};
+            return {
+                interval_id: setInterval(action, delay),
+                callback,
+            };
+        };
+        Box::new(BrowserInterval(Some(handle)))
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn monotonic(&self) -> Duration {
+        let now: Value = js! { return performance.now(); };
+        let ms = f64::try_from(now).unwrap_or(0.0);
+        Duration::from_micros((ms * 1000.0) as u64)
+    }
+
+    // Native builds, like tests of components without `MockClock`, have no browser
+    #[cfg(not(any(target_arch = "wasm32", target_os = "emscripten")))]
+    fn monotonic(&self) -> Duration {
+        thread_local! {
+            static ORIGIN: Instant = Instant::now();
+        }
+        ORIGIN.with(|origin| origin.elapsed())
+    }
+}
+
+struct BrowserTimeout(Option<Value>);
+
+impl Task for BrowserTimeout {
+    fn is_active(&self) -> bool {
+        self.0.as_ref().map_or(false, |handle| !is_done(handle))
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel timeout twice");
+        js! { @(no_return)
+            var handle = @{handle};
+            clearTimeout(handle.timeout_id);
+            handle.callback.drop();
+        }
+    }
+}
+
+struct BrowserInterval(Option<Value>);
+
+impl Task for BrowserInterval {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel interval twice");
+        js! { @(no_return)
+            var handle = @{handle};
+            clearInterval(handle.interval_id);
+            handle.callback.drop();
+        }
+    }
+}
+
+impl StorageBackend for Browser {
+    fn get(&self, scope: Scope, key: &str) -> Option<String> {
+        let value: Value = {
+            match scope {
+                Scope::Local => js! { return localStorage.getItem(@{key}); },
+                Scope::Session => js! { return sessionStorage.getItem(@{key}); },
+            }
+        };
+        value.into_string()
+    }
+
+    fn set(&self, scope: Scope, key: &str, value: &str) {
+        match scope {
+            Scope::Local => { js! { @(no_return)
+                localStorage.setItem(@{key}, @{value});
+            } },
+            Scope::Session => { js! { @(no_return)
+                sessionStorage.setItem(@{key}, @{value});
+            } },
+        }
+    }
+
+    fn remove(&self, scope: Scope, key: &str) {
+        match scope {
+            Scope::Local => { js! { @(no_return)
+                localStorage.removeItem(@{key});
+            } },
+            Scope::Session => { js! { @(no_return)
+                sessionStorage.removeItem(@{key});
+            } },
+        }
+    }
+}
+
+impl FetchBackend for Browser {
+    fn fetch(&self, request: Request<Storable>, callback: Box<FnMut(Response<Restorable>)>) -> Box<Task> {
+        // Consume request as parts and body.
+        let (parts, body) = request.into_parts();
+
+        // Map headers into a Js serializable HashMap.
+        let header_map: HashMap<&str, &str> = parts.headers.iter().map(
+            |(k, v)| (k.as_str(), v.to_str().expect(
+                format!("Unparsable request header {}: {:?}", k.as_str(), v).as_str()
+            ))
+        ).collect();
+
+        // Formats URI.
+        let uri = format!("{}", parts.uri);
+
+        // Prepare the response callback.
+        // Notice that the callback signature must match the call from the javascript
+        // side. There is no static check at this point.
+        let mut callback = callback;
+        let callback = move |success: bool, response: Value, body: String| {
+            let mut response_builder = Response::builder();
+
+            // Deserialize response status.
+            let status = u16::try_from(js!{
+                return @{&response}.status;
+            });
+
+            if let Ok(code) = status {
+                response_builder.status(code);
+            }
+
+            // Deserialize response headers.
+            let headers: HashMap<String, String> = HashMap::try_from(js!{
+                var map = {};
+                @{&response}.headers.forEach(function(value, key) {
+                    map[key] = value;
+                });
+                return map;
+            }).unwrap_or(HashMap::new());
+
+            for (key, values) in &headers {
+                response_builder.header(key.as_str(), values.as_str());
+            }
+
+            // Wrap response body into a Restorable object.
+            let data = if success { Ok(body) } else { Err(body) };
+            let response = response_builder.body(data).unwrap();
+            callback(response);
+        };
+
+        let handle = js! {
+            var data = {
+                method: @{parts.method.as_str()},
+                body: @{body},
+                headers: @{header_map},
+            };
+            var request = new Request(@{uri}, data);
+            var callback = @{callback};
+            var handle = {
+                interrupt: false,
+                callback,
+            };
+            fetch(request).then(function(response) {
+                response.text().then(function(data) {
+                    if (handle.interrupted != true) {
+                        handle.done = true;
+                        callback(true, response, data);
+                        callback.drop();
+                    }
+                }).catch(function(err) {
+                    if (handle.interrupted != true) {
+                        handle.done = true;
+                        callback(false, response, data);
+                        callback.drop();
+                    }
+                });
+            });
+            return handle;
+        };
+        Box::new(BrowserFetch(Some(handle)))
+    }
+}
+
+struct BrowserFetch(Option<Value>);
+
+impl Task for BrowserFetch {
+    fn is_active(&self) -> bool {
+        self.0.as_ref().map_or(false, |handle| !is_done(handle))
+    }
+
+    fn cancel(&mut self) {
+        // Fetch API doesn't support request cancelling
+        // and we should use this workaround with a flag.
+        // In fact, request not canceled, but callback won't be called.
+        let handle = self.0.take().expect("tried to cancel request fetching twice");
+        js! {  @(no_return)
+            var handle = @{handle};
+            handle.interrupted = true;
+            handle.callback.drop();
+        }
+    }
+}
+
+impl WebSocketBackend for Browser {
+    fn connect(&self, url: &str, on_message: Box<FnMut(String)>, on_status: Box<FnMut(WebSocketStatus)>)
+        -> Box<Connection>
+    {
+        let mut on_message = on_message;
+        let callback = move |data: String| on_message(data);
+        let mut on_status = on_status;
+        let notify_callback = move |code: u32| {
+            let status = {
+                match code {
+                    1 => WebSocketStatus::Opened,
+                    0 => WebSocketStatus::Closed,
+                    x => panic!("unknown code of websocket notification: {}", x),
+                }
+            };
+            on_status(status);
+        };
+        let handle = js! {
+            var socket = new WebSocket(@{url});
+            var callback = @{callback};
+            var notify_callback = @{notify_callback};
+            var handle = {
+                done: false,
+                socket,
+            };
+            socket.onopen = function(event) {
+                notify_callback(1);
+            };
+            socket.onclose = function(event) {
+                handle.done = true;
+                callback.drop();
+                notify_callback(0);
+                notify_callback.drop();
+            };
+            socket.onerror = function(event) {
+            };
+            socket.onmessage = function(event) {
+                callback(event.data);
+            };
+            return handle;
+        };
+        Box::new(BrowserSocket(Some(handle)))
+    }
+}
+
+struct BrowserSocket(Option<Value>);
+
+impl Task for BrowserSocket {
+    fn is_active(&self) -> bool {
+        self.0.as_ref().map_or(false, |handle| !is_done(handle))
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to close websocket twice");
+        js! { @(no_return)
+            var handle = @{handle};
+            handle.socket.close();
+        }
+    }
+}
+
+impl Connection for BrowserSocket {
+    fn send(&mut self, data: String) {
+        let handle = self.0.as_ref().expect("can't send data to the closed websocket connection");
+        js! { @(no_return)
+            var handle = @{handle};
+            handle.socket.send(@{data});
+        }
+    }
+}
+
+impl AnnouncerBackend for Browser {
+    fn announce(&self, politeness: Politeness, text: &str) {
+        let (live, role) = match politeness {
+            Politeness::Polite => ("polite", "status"),
+            Politeness::Assertive => ("assertive", "alert"),
+        };
+        js! { @(no_return)
+            var live = @{live};
+            var id = "yew-live-" + live;
+            var region = document.getElementById(id);
+            if (!region) {
+                region = document.createElement("div");
+                region.id = id;
+                region.setAttribute("aria-live", live);
+                region.setAttribute("aria-atomic", "true");
+                region.setAttribute("role", @{role});
+                // Hidden visually, but not from screen readers
+                region.style.cssText = "position: absolute; width: 1px; height: 1px; margin: -1px; " +
+                    "padding: 0; overflow: hidden; clip: rect(0, 0, 0, 0); white-space: nowrap; border: 0;";
+                document.body.appendChild(region);
+            }
+            // Readers announce changes of regions which exist already,
+            // and the same text is read again only after it was cleared
+            region.textContent = "";
+            clearTimeout(region.yewTimeout);
+            var text = @{text};
+            region.yewTimeout = setTimeout(function() {
+                region.textContent = text;
+            }, 100);
+        }
+    }
+}
+
+thread_local! {
+    // Formatters of `Intl` are slow to create, so they're kept by locales and options
+    static FORMATTERS: RefCell<HashMap<String, Value>> = RefCell::new(HashMap::new());
+}
+
+fn formatter<F>(key: String, create: F) -> Value
+where
+    F: FnOnce() -> Value,
+{
+    if let Some(formatter) = FORMATTERS.with(|formatters| formatters.borrow().get(&key).cloned()) {
+        return formatter;
+    }
+    let formatter = create();
+    FORMATTERS.with(|formatters| formatters.borrow_mut().insert(key, formatter.clone()));
+    formatter
+}
+
+impl IntlBackend for Browser {
+    fn format_number(&self, locale: Option<&str>, number: f64, options: &NumberOptions) -> String {
+        let key = format!("number {:?} {:?}", locale, options);
+        let formatter = formatter(key, || {
+            let (style, currency) = match options.style {
+                NumberStyle::Decimal => ("decimal", None),
+                NumberStyle::Percent => ("percent", None),
+                NumberStyle::Currency(ref currency) => ("currency", Some(currency.as_str())),
+            };
+            let minimum = options.minimum_fraction_digits.map(u32::from);
+            let maximum = options.maximum_fraction_digits.map(u32::from);
+            let formatter: Value = js! {
+                var options = { style: @{style}, useGrouping: @{options.grouping} };
+                var currency = @{currency};
+                var minimum = @{minimum};
+                var maximum = @{maximum};
+                if (currency !== null) {
+                    options.currency = currency;
+                }
+                if (minimum !== null) {
+                    options.minimumFractionDigits = minimum;
+                }
+                if (maximum !== null) {
+                    options.maximumFractionDigits = maximum;
+                }
+                return new Intl.NumberFormat(@{locale} || undefined, options);
+            };
+            formatter
+        });
+        let formatted = js! {
+            return @{formatter}.format(@{number});
+        };
+        String::try_from(formatted).unwrap_or_default()
+    }
+
+    fn format_date(&self, locale: Option<&str>, time: SystemTime, options: &DateOptions) -> String {
+        let key = format!("date {:?} {:?}", locale, options);
+        let formatter = formatter(key, || {
+            let date = options.date.map(|style| style.as_str());
+            let time = options.time.map(|style| style.as_str());
+            let zone = options.time_zone.as_deref();
+            let formatter: Value = js! {
+                var options = {};
+                var date = @{date};
+                var time = @{time};
+                var zone = @{zone};
+                if (date !== null) {
+                    options.dateStyle = date;
+                }
+                if (time !== null) {
+                    options.timeStyle = time;
+                }
+                if (zone !== null) {
+                    options.timeZone = zone;
+                }
+                return new Intl.DateTimeFormat(@{locale} || undefined, options);
+            };
+            formatter
+        });
+        let formatted = js! {
+            return @{formatter}.format(new Date(@{intl_service::to_timestamp(time)}));
+        };
+        String::try_from(formatted).unwrap_or_default()
+    }
+
+    fn format_calendar(&self, locale: Option<&str>, date: Date, field: CalendarField) -> String {
+        let key = format!("calendar {:?} {:?}", locale, field);
+        let formatter = formatter(key, || {
+            let (month, weekday) = match field {
+                CalendarField::MonthYear => (Some("long"), None),
+                CalendarField::Weekday => (None, Some("long")),
+                CalendarField::ShortWeekday => (None, Some("short")),
+            };
+            let formatter: Value = js! {
+                var options = { timeZone: "UTC" };
+                var month = @{month};
+                var weekday = @{weekday};
+                if (month !== null) {
+                    options.month = month;
+                    options.year = "numeric";
+                }
+                if (weekday !== null) {
+                    options.weekday = weekday;
+                }
+                return new Intl.DateTimeFormat(@{locale} || undefined, options);
+            };
+            formatter
+        });
+        let formatted = js! {
+            return @{formatter}.format(new Date(@{intl_service::to_timestamp(date.to_time())}));
+        };
+        String::try_from(formatted).unwrap_or_default()
+    }
+
+    fn format_relative(&self, locale: Option<&str>, value: f64, unit: RelativeUnit) -> String {
+        let key = format!("relative {:?}", locale);
+        let formatter = formatter(key, || {
+            let formatter: Value = js! {
+                return new Intl.RelativeTimeFormat(@{locale} || undefined, { numeric: "auto" });
+            };
+            formatter
+        });
+        let formatted = js! {
+            return @{formatter}.format(@{value}, @{unit.as_str()});
+        };
+        String::try_from(formatted).unwrap_or_default()
+    }
+
+    fn first_weekday(&self, locale: Option<&str>) -> u32 {
+        let first: Value = js! {
+            try {
+                var locale = new Intl.Locale(@{locale} || navigator.language);
+                var info = locale.getWeekInfo ? locale.getWeekInfo() : locale.weekInfo;
+                return info ? info.firstDay : 1;
+            } catch (e) {
+                return 1;
+            }
+        };
+        u32::try_from(first).unwrap_or(1)
+    }
+
+    fn now(&self) -> SystemTime {
+        let now: Value = js! { return Date.now(); };
+        let ms = f64::try_from(now).unwrap_or_default();
+        UNIX_EPOCH + Duration::from_millis(ms as u64)
+    }
+
+    fn today(&self) -> Date {
+        let today: Value = js! {
+            var today = new Date();
+            return [today.getFullYear(), today.getMonth() + 1, today.getDate()];
+        };
+        let parts = Vec::<i32>::try_from(today).unwrap_or_default();
+        match parts[..] {
+            [year, month, day] => Date::new(year, month as u32, day as u32),
+            _ => None,
+        }.unwrap_or_else(|| Date::from_time(self.now()))
+    }
+}
+
+/// Returns the window of the target, or `null` if the app isn't embedded
+/// or the frame isn't rendered.
+fn target_window(target: &Target) -> Value {
+    match *target {
+        Target::Parent => js! {
+            return window.parent !== window ? window.parent : null;
+        },
+        Target::Frame(ref node) => match node.get() {
+            Some(element) => js! {
+                return @{element}.contentWindow || null;
+            },
+            None => Value::Null,
+        },
+    }
+}
+
+impl MessagingBackend for Browser {
+    fn post(&self, channel: &Channel, data: String) {
+        let target = target_window(&channel.target);
+        if target == Value::Null {
+            warn!("can't post a message of the channel {}: the window isn't available", channel.name);
+            return;
+        }
+        js! { @(no_return)
+            @{target}.postMessage({ yew: @{&channel.name}, data: @{data} }, @{&channel.origin});
+        }
+    }
+
+    fn listen(&self, channel: &Channel, callback: Box<FnMut(String)>) -> Box<Task> {
+        let mut callback = callback;
+        let target = channel.target.clone();
+        // A frame could be rendered again, so its window is compared when a message comes
+        let callback = move |source: Value, data: String| {
+            let window = target_window(&target);
+            let same: Value = js! {
+                return @{window} === @{source};
+            };
+            if same == Value::Bool(true) {
+                callback(data);
+            }
+        };
+        let handle = js! {
+            var origin = @{&channel.origin};
+            var name = @{&channel.name};
+            var callback = @{callback};
+            var listener = function(event) {
+                var envelope = event.data;
+                if (event.origin !== origin || !envelope || envelope.yew !== name
+                    || typeof envelope.data !== "string") {
+                    return;
+                }
+                callback(event.source, envelope.data);
+            };
+            window.addEventListener("message", listener);
+            return { listener, callback };
+        };
+        Box::new(BrowserSubscription(Some(handle)))
+    }
+}
+
+struct BrowserSubscription(Option<Value>);
+
+impl Task for BrowserSubscription {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel a subscription to a channel twice");
+        js! { @(no_return)
+            var handle = @{handle};
+            window.removeEventListener("message", handle.listener);
+            handle.callback.drop();
+        }
+    }
+}
+
+impl VisibilityBackend for Browser {
+    fn is_hidden(&self) -> bool {
+        let hidden: Value = js! {
+            return document.hidden === true;
+        };
+        hidden == Value::Bool(true)
+    }
+
+    fn listen(&self, callback: Box<FnMut(bool)>) -> Box<Task> {
+        let mut callback = callback;
+        let callback = move |hidden: bool| callback(hidden);
+        let handle = js! {
+            var callback = @{callback};
+            var listener = function() {
+                callback(document.hidden === true);
+            };
+            // Some browsers don't change the visibility when a tab is closed
+            var pagehide = function() {
+                callback(true);
+            };
+            document.addEventListener("visibilitychange", listener);
+            window.addEventListener("pagehide", pagehide);
+            return { listener, pagehide, callback };
+        };
+        Box::new(BrowserVisibility(Some(handle)))
+    }
+}
+
+struct BrowserVisibility(Option<Value>);
+
+impl Task for BrowserVisibility {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel a subscription to the visibility twice");
+        js! { @(no_return)
+            var handle = @{handle};
+            document.removeEventListener("visibilitychange", handle.listener);
+            window.removeEventListener("pagehide", handle.pagehide);
+            handle.callback.drop();
+        }
+    }
+}
+
+impl ConnectivityBackend for Browser {
+    fn is_online(&self) -> bool {
+        let online: Value = js! {
+            return navigator.onLine !== false;
+        };
+        online == Value::Bool(true)
+    }
+
+    fn listen(&self, callback: Box<FnMut(bool)>) -> Box<Task> {
+        let mut callback = callback;
+        let callback = move |online: bool| callback(online);
+        let handle = js! {
+            var callback = @{callback};
+            var online = function() {
+                callback(true);
+            };
+            var offline = function() {
+                callback(false);
+            };
+            window.addEventListener("online", online);
+            window.addEventListener("offline", offline);
+            return { online, offline, callback };
+        };
+        Box::new(BrowserConnectivity(Some(handle)))
+    }
+}
+
+struct BrowserConnectivity(Option<Value>);
+
+impl Task for BrowserConnectivity {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel a subscription to the connectivity twice");
+        js! { @(no_return)
+            var handle = @{handle};
+            window.removeEventListener("online", handle.online);
+            window.removeEventListener("offline", handle.offline);
+            handle.callback.drop();
+        }
+    }
+}
+
+impl IntersectionBackend for Browser {
+    fn observe(&self, target: NodeRef, options: &IntersectionOptions, callback: Box<FnMut(bool)>) -> Box<Task> {
+        let mut callback = callback;
+        let callback = move |visible: bool| callback(visible);
+        let element = move || target.get();
+        let handle = js! {
+            var callback = @{callback};
+            var element = @{element};
+            var handle = {
+                frame: null,
+                observer: new IntersectionObserver(function(entries) {
+                    entries.forEach(function(entry) {
+                        callback(entry.isIntersecting);
+                    });
+                }, { rootMargin: @{&options.root_margin}, threshold: @{options.threshold} }),
+                callback,
+                element,
+            };
+            // The reference is set when the element is rendered
+            var attach = function() {
+                var target = element();
+                if (target) {
+                    handle.frame = null;
+                    handle.observer.observe(target);
+                } else {
+                    handle.frame = requestAnimationFrame(attach);
+                }
+            };
+            attach();
+            return handle;
+        };
+        Box::new(BrowserObserver(Some(handle)))
+    }
+}
+
+struct BrowserObserver(Option<Value>);
+
+impl Task for BrowserObserver {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel an observer of an element twice");
+        js! { @(no_return)
+            var handle = @{handle};
+            if (handle.frame !== null) {
+                cancelAnimationFrame(handle.frame);
+            }
+            handle.observer.disconnect();
+            handle.detached = true;
+            handle.callback.drop();
+            handle.element.drop();
+        }
+    }
+}
+
+impl LayoutBackend for Browser {
+    fn track(&self, anchor: String, floating: NodeRef, callback: Box<FnMut(LayoutEvent)>) -> Box<Task> {
+        let mut callback = callback;
+        // Rectangles come as groups of 4 numbers: the anchor, the element,
+        // the viewport and containers which clip the anchor
+        let callback = move |outside: bool, rects: Vec<f64>| {
+            if outside {
+                callback(LayoutEvent::Outside);
+                return;
+            }
+            let mut rects = rects.chunks(4).map(|rect| Rect::new(rect[0], rect[1], rect[2], rect[3]));
+            let anchor = rects.next().expect("a rectangle of an anchor");
+            let floating = rects.next().expect("a rectangle of a floating element");
+            let viewport = rects.next().expect("a rectangle of the viewport");
+            let boundary = rects.fold(viewport, |boundary, clip| boundary.intersect(&clip));
+            callback(LayoutEvent::Measured(Layout { anchor, floating, boundary }));
+        };
+        let element = move || floating.get();
+        let handle = js! {
+            var callback = @{callback};
+            var element = @{element};
+            var id = @{anchor};
+            var handle = { frame: null, observer: null, callback, element };
+            var rect = function(node) {
+                var rect = node.getBoundingClientRect();
+                return [rect.left, rect.top, rect.width, rect.height];
+            };
+            var measure = function() {
+                handle.frame = null;
+                var anchor = document.getElementById(id);
+                var floating = element();
+                // Both are measured when they're rendered
+                if (!anchor || !floating) {
+                    handle.frame = requestAnimationFrame(measure);
+                    return;
+                }
+                if (handle.observer === null && typeof ResizeObserver === "function") {
+                    handle.observer = new ResizeObserver(schedule);
+                    handle.observer.observe(anchor);
+                    handle.observer.observe(floating);
+                }
+                var root = document.documentElement;
+                var rects = rect(anchor).concat(rect(floating), [0, 0, root.clientWidth, root.clientHeight]);
+                for (var node = anchor.parentElement; node && node !== document.body && node !== root; node = node.parentElement) {
+                    var style = getComputedStyle(node);
+                    if (/auto|scroll|hidden|clip/.test(style.overflowX + style.overflowY)) {
+                        rects = rects.concat(rect(node));
+                    }
+                }
+                callback(false, rects);
+            };
+            // Many events of resizing and scrolling are measured once in a frame
+            var schedule = function() {
+                if (handle.frame === null) {
+                    handle.frame = requestAnimationFrame(measure);
+                }
+            };
+            handle.schedule = schedule;
+            handle.pointer = function(event) {
+                var anchor = document.getElementById(id);
+                var floating = element();
+                if ((anchor && anchor.contains(event.target)) || (floating && floating.contains(event.target))) {
+                    return;
+                }
+                callback(true, []);
+            };
+            window.addEventListener("resize", schedule);
+            // Scrolling of containers doesn't bubble, so it's captured
+            window.addEventListener("scroll", schedule, true);
+            document.addEventListener("pointerdown", handle.pointer, true);
+            schedule();
+            return handle;
+        };
+        Box::new(BrowserTracker(Some(handle)))
+    }
+}
+
+struct BrowserTracker(Option<Value>);
+
+impl Task for BrowserTracker {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel tracking of a layout twice");
+        js! { @(no_return)
+            var handle = @{handle};
+            if (handle.frame !== null) {
+                cancelAnimationFrame(handle.frame);
+            }
+            if (handle.observer !== null) {
+                handle.observer.disconnect();
+            }
+            window.removeEventListener("resize", handle.schedule);
+            window.removeEventListener("scroll", handle.schedule, true);
+            document.removeEventListener("pointerdown", handle.pointer, true);
+            handle.detached = true;
+            handle.callback.drop();
+            handle.element.drop();
+        }
+    }
+}
+
+impl RandomBackend for Browser {
+    fn fill(&self, bytes: &mut [u8]) {
+        let length = bytes.len() as u32;
+        let hex: Value = js! {
+            var bytes = new Uint8Array(@{length});
+            window.crypto.getRandomValues(bytes);
+            return Array.prototype.map.call(bytes, function(byte) {
+                return ("0" + byte.toString(16)).slice(-2);
+            }).join("");
+        };
+        let hex = hex.into_string().expect("crypto.getRandomValues isn't available");
+        for (index, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16).expect("a random byte isn't hex");
+        }
+    }
+}
+
+impl UnloadBackend for Browser {
+    fn listen(&self, callback: Box<FnMut() -> bool>) -> Box<Task> {
+        let mut callback = callback;
+        let callback = move || -> bool { callback() };
+        let handle = js! {
+            var callback = @{callback};
+            var listener = function(event) {
+                if (callback()) {
+                    event.preventDefault();
+                    // Older browsers need a value to show the prompt
+                    event.returnValue = "";
+                    return "";
+                }
+            };
+            window.addEventListener("beforeunload", listener);
+            return { listener, callback };
+        };
+        Box::new(BrowserUnload(Some(handle)))
+    }
+}
+
+struct BrowserUnload(Option<Value>);
+
+impl Task for BrowserUnload {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel a subscription to unloads twice");
+        js! { @(no_return)
+            var handle = @{handle};
+            window.removeEventListener("beforeunload", handle.listener);
+            handle.callback.drop();
+        }
+    }
+}
+
+impl BeaconBackend for Browser {
+    fn send(&self, url: &str, data: &str) -> bool {
+        let queued: Value = js! {
+            if (!navigator.sendBeacon) {
+                return false;
+            }
+            var blob = new Blob([@{data}], { type: "application/json" });
+            return navigator.sendBeacon(@{url}, blob);
+        };
+        queued == Value::Bool(true)
+    }
+}
+
+impl ActivityBackend for Browser {
+    fn listen(&self, callback: Box<FnMut()>) -> Box<Task> {
+        let mut callback = callback;
+        let callback = move || callback();
+        let handle = js! {
+            var callback = @{callback};
+            var last = 0;
+            // One set of listeners of the document sees events of all elements
+            var listener = function() {
+                var now = Date.now();
+                if (now - last >= 1000) {
+                    last = now;
+                    callback();
+                }
+            };
+            var kinds = ["pointermove", "pointerdown", "keydown", "touchstart", "wheel", "scroll"];
+            var options = { capture: true, passive: true };
+            kinds.forEach(function(kind) {
+                document.addEventListener(kind, listener, options);
+            });
+            return { kinds, listener, options, callback };
+        };
+        Box::new(BrowserActivity(Some(handle)))
+    }
+}
+
+struct BrowserActivity(Option<Value>);
+
+impl Task for BrowserActivity {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel a subscription to activity twice");
+        js! { @(no_return)
+            var handle = @{handle};
+            handle.kinds.forEach(function(kind) {
+                document.removeEventListener(kind, handle.listener, handle.options);
+            });
+            handle.callback.drop();
+        }
+    }
+}
+
+thread_local! {
+    // A browser sends the event of the prompt once, it's kept until the prompt is shown
+    static INSTALL_PROMPT: RefCell<Option<Value>> = RefCell::new(None);
+}
+
+impl InstallBackend for Browser {
+    fn is_installable(&self) -> bool {
+        INSTALL_PROMPT.with(|prompt| prompt.borrow().is_some())
+    }
+
+    fn listen(&self, callback: Box<FnMut(InstallEvent)>) -> Box<Task> {
+        let mut callback = callback;
+        let callback = move |prompt: Value| {
+            if prompt == Value::Null {
+                INSTALL_PROMPT.with(|current| current.borrow_mut().take());
+                callback(InstallEvent::Installed);
+            } else {
+                INSTALL_PROMPT.with(|current| *current.borrow_mut() = Some(prompt));
+                callback(InstallEvent::Installable);
+            }
+        };
+        let handle = js! {
+            var callback = @{callback};
+            var offered = function(event) {
+                // Keeps the mini-infobar of a browser hidden, the app shows the prompt
+                event.preventDefault();
+                callback(event);
+            };
+            var installed = function() {
+                callback(null);
+            };
+            window.addEventListener("beforeinstallprompt", offered);
+            window.addEventListener("appinstalled", installed);
+            return { offered, installed, callback };
+        };
+        Box::new(BrowserInstall(Some(handle)))
+    }
+
+    fn prompt(&self, callback: Box<FnMut(InstallChoice)>) -> bool {
+        let prompt = match INSTALL_PROMPT.with(|current| current.borrow_mut().take()) {
+            Some(prompt) => prompt,
+            None => return false,
+        };
+        let mut callback = callback;
+        let callback = move |accepted: bool| {
+            callback(if accepted { InstallChoice::Accepted } else { InstallChoice::Dismissed });
+        };
+        js! { @(no_return)
+            var prompt = @{prompt};
+            var callback = @{callback};
+            prompt.prompt();
+            prompt.userChoice.then(function(choice) {
+                callback(choice.outcome === "accepted");
+                callback.drop();
+            }).catch(function() {
+                callback(false);
+                callback.drop();
+            });
+        }
+        true
+    }
+}
+
+struct BrowserInstall(Option<Value>);
+
+impl Task for BrowserInstall {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel a subscription to installs twice");
+        js! { @(no_return)
+            var handle = @{handle};
+            window.removeEventListener("beforeinstallprompt", handle.offered);
+            window.removeEventListener("appinstalled", handle.installed);
+            handle.callback.drop();
+        }
+    }
+}
+
+fn seconds(value: Value) -> Option<Duration> {
+    let seconds = f64::try_from(value).ok()?;
+    if seconds.is_finite() && seconds >= 0.0 {
+        Some(Duration::from_millis((seconds * 1000.0) as u64))
+    } else {
+        None
+    }
+}
+
+impl MediaBackend for Browser {
+    fn attach(&self, target: NodeRef, callback: Box<FnMut(MediaEvent)>) -> Box<MediaControl> {
+        let mut callback = callback;
+        let callback = move |kind: String, value: Value| {
+            let event = match kind.as_str() {
+                "loadedmetadata" => MediaEvent::Metadata(seconds(value)),
+                "timeupdate" => MediaEvent::Time(seconds(value).unwrap_or_default()),
+                "playing" => MediaEvent::Playing,
+                "pause" => MediaEvent::Paused,
+                "waiting" => MediaEvent::Buffering,
+                "progress" => MediaEvent::Buffered(seconds(value).unwrap_or_default()),
+                "ended" => MediaEvent::Ended,
+                "volumechange" => {
+                    let volume = f64::try_from(js!( return @{&value}.volume; )).unwrap_or(1.0);
+                    let muted = js!( return @{&value}.muted; ) == Value::Bool(true);
+                    MediaEvent::Volume(volume, muted)
+                }
+                "blocked" => MediaEvent::Failed(MediaError::Blocked),
+                _ => {
+                    let code = f64::try_from(value).unwrap_or(0.0);
+                    let error = match code as u32 {
+                        1 => MediaError::Aborted,
+                        2 => MediaError::Network,
+                        3 => MediaError::Decode,
+                        _ => MediaError::Unsupported,
+                    };
+                    MediaEvent::Failed(error)
+                }
+            };
+            callback(event);
+        };
+        let element = {
+            let target = target.clone();
+            move || target.get()
+        };
+        let handle = js! {
+            var callback = @{callback};
+            var element = @{element};
+            var handle = {
+                frame: null,
+                target: null,
+                callback,
+                element,
+            };
+            function buffered(target) {
+                var ranges = target.buffered;
+                return ranges.length > 0 ? ranges.end(ranges.length - 1) : 0;
+            }
+            handle.listeners = {
+                loadedmetadata: function(event) { callback("loadedmetadata", event.target.duration); },
+                timeupdate: function(event) { callback("timeupdate", event.target.currentTime); },
+                playing: function() { callback("playing", null); },
+                pause: function() { callback("pause", null); },
+                waiting: function() { callback("waiting", null); },
+                progress: function(event) { callback("progress", buffered(event.target)); },
+                ended: function() { callback("ended", null); },
+                volumechange: function(event) { callback("volumechange", event.target); },
+                error: function(event) {
+                    var error = event.target.error;
+                    callback("error", error ? error.code : 4);
+                },
+            };
+            // A rejected playback could settle after the element is detached
+            handle.blocked = function() {
+                if (handle.detached !== true) {
+                    callback("blocked", null);
+                }
+            };
+            // The reference is set when the element is rendered
+            var attach = function() {
+                var target = element();
+                if (!target) {
+                    handle.frame = requestAnimationFrame(attach);
+                    return;
+                }
+                handle.frame = null;
+                handle.target = target;
+                Object.keys(handle.listeners).forEach(function(kind) {
+                    target.addEventListener(kind, handle.listeners[kind]);
+                });
+                if (target.readyState >= 1) {
+                    callback("loadedmetadata", target.duration);
+                }
+            };
+            attach();
+            return handle;
+        };
+        Box::new(BrowserMedia { handle: Some(handle), target })
+    }
+}
+
+struct BrowserMedia {
+    handle: Option<Value>,
+    target: NodeRef,
+}
+
+impl Task for BrowserMedia {
+    fn is_active(&self) -> bool {
+        self.handle.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.handle.take().expect("tried to detach a media element twice");
+        js! { @(no_return)
+            var handle = @{handle};
+            if (handle.frame !== null) {
+                cancelAnimationFrame(handle.frame);
+            }
+            if (handle.target !== null) {
+                var target = handle.target;
+                Object.keys(handle.listeners).forEach(function(kind) {
+                    target.removeEventListener(kind, handle.listeners[kind]);
+                });
+            }
+            handle.detached = true;
+            handle.callback.drop();
+            handle.element.drop();
+        }
+    }
+}
+
+impl MediaControl for BrowserMedia {
+    fn control(&mut self, command: MediaCommand) -> bool {
+        let (handle, target) = match (self.handle.as_ref(), self.target.get()) {
+            (Some(handle), Some(target)) => (handle, target),
+            _ => return false,
+        };
+        match command {
+            MediaCommand::Play => {
+                js! { @(no_return)
+                    var handle = @{handle};
+                    var promise = @{target}.play();
+                    if (promise) {
+                        promise.catch(function(error) {
+                            // A pause before playback started rejects with `AbortError`
+                            if (error && error.name === "NotAllowedError") {
+                                handle.blocked();
+                            }
+                        });
+                    }
+                }
+            }
+            MediaCommand::Pause => {
+                js! { @(no_return) @{target}.pause(); }
+            }
+            MediaCommand::Seek(time) => {
+                let seconds = time.as_secs() as f64 + f64::from(time.subsec_millis()) / 1000.0;
+                js! { @(no_return) @{target}.currentTime = @{seconds}; }
+            }
+            MediaCommand::Volume(volume) => {
+                js! { @(no_return) @{target}.volume = @{volume}; }
+            }
+            MediaCommand::Muted(muted) => {
+                js! { @(no_return) @{target}.muted = @{muted}; }
+            }
+            MediaCommand::Rate(rate) => {
+                js! { @(no_return) @{target}.playbackRate = @{rate}; }
+            }
+        }
+        true
+    }
+
+    fn state(&self) -> Option<MediaState> {
+        let target = self.target.get()?;
+        let state: Value = js! {
+            var target = @{target};
+            var ranges = target.buffered;
+            return {
+                time: target.currentTime,
+                duration: target.duration,
+                buffered: ranges.length > 0 ? ranges.end(ranges.length - 1) : 0,
+                paused: target.paused,
+                ended: target.ended,
+                volume: target.volume,
+                muted: target.muted,
+                rate: target.playbackRate,
+            };
+        };
+        let field = |name: &str| -> Value { js!( return @{&state}[@{name}]; ) };
+        Some(MediaState {
+            time: seconds(field("time")).unwrap_or_default(),
+            duration: seconds(field("duration")),
+            buffered: seconds(field("buffered")).unwrap_or_default(),
+            paused: field("paused") == Value::Bool(true),
+            ended: field("ended") == Value::Bool(true),
+            volume: f64::try_from(field("volume")).unwrap_or(1.0),
+            muted: field("muted") == Value::Bool(true),
+            rate: f64::try_from(field("rate")).unwrap_or(1.0),
+        })
+    }
+}
+
+impl TextBackend for Browser {
+    fn measure(&self, font: &str, text: &str) -> TextMetrics {
+        let metrics: Value = js! {
+            // One canvas is kept for all measurements
+            var context = window.__yew_text || (window.__yew_text = document.createElement("canvas").getContext("2d"));
+            context.font = @{font};
+            var metrics = context.measureText(@{text});
+            var ascent = metrics.fontBoundingBoxAscent;
+            var descent = metrics.fontBoundingBoxDescent;
+            return [
+                metrics.width,
+                ascent === undefined ? metrics.actualBoundingBoxAscent || 0 : ascent,
+                descent === undefined ? metrics.actualBoundingBoxDescent || 0 : descent,
+            ];
+        };
+        let metrics = Vec::<f64>::try_from(metrics).unwrap_or_default();
+        match metrics.as_slice() {
+            [width, ascent, descent] => TextMetrics { width: *width, ascent: *ascent, descent: *descent },
+            _ => TextMetrics::default(),
+        }
+    }
+
+    fn content_rect(&self, target: &NodeRef) -> Option<Rect> {
+        let target = target.get()?;
+        let rect: Value = js! {
+            var range = document.createRange();
+            range.selectNodeContents(@{target});
+            var rect = range.getBoundingClientRect();
+            return [rect.left, rect.top, rect.width, rect.height];
+        };
+        let rect = Vec::<f64>::try_from(rect).ok()?;
+        match rect.as_slice() {
+            [x, y, width, height] => Some(Rect::new(*x, *y, *width, *height)),
+            _ => None,
+        }
+    }
+}
+
+/// Hides everything but the marked element on paper.
+const PRINT_STYLE: &str = "@media print {\
+    html.yew-printing body * { visibility: hidden; }\
+    html.yew-printing [data-yew-print], html.yew-printing [data-yew-print] * { visibility: visible; }\
+    html.yew-printing [data-yew-print] { position: absolute; left: 0; top: 0; width: 100%; }\
+}";
+
+impl PrintBackend for Browser {
+    fn print(&self, job: PrintJob) -> bool {
+        match job {
+            PrintJob::Page => {
+                js! { @(no_return)
+                    window.print();
+                }
+            }
+            PrintJob::Element(target) => {
+                let target = match target.get() {
+                    Some(target) => target,
+                    None => return false,
+                };
+                #[cfg(feature = "csp")]
+                let nonce = ::csp::nonce();
+                #[cfg(not(feature = "csp"))]
+                let nonce: Option<String> = None;
+                js! { @(no_return)
+                    var target = @{target};
+                    var root = document.documentElement;
+                    if (!document.getElementById("yew-print-style")) {
+                        var style = document.createElement("style");
+                        style.id = "yew-print-style";
+                        var nonce = @{nonce};
+                        if (nonce) {
+                            style.setAttribute("nonce", nonce);
+                        }
+                        style.textContent = @{PRINT_STYLE};
+                        document.head.appendChild(style);
+                    }
+                    var done = function() {
+                        window.removeEventListener("afterprint", done);
+                        target.removeAttribute("data-yew-print");
+                        root.classList.remove("yew-printing");
+                    };
+                    target.setAttribute("data-yew-print", "");
+                    root.classList.add("yew-printing");
+                    window.addEventListener("afterprint", done);
+                    window.print();
+                }
+            }
+            PrintJob::Document(html) => {
+                js! { @(no_return)
+                    var previous = document.getElementById("yew-print-frame");
+                    if (previous) {
+                        previous.remove();
+                    }
+                    var frame = document.createElement("iframe");
+                    frame.id = "yew-print-frame";
+                    frame.setAttribute("aria-hidden", "true");
+                    frame.style.cssText = "position: fixed; right: 0; bottom: 0; width: 0; height: 0; border: 0;";
+                    frame.onload = function() {
+                        var view = frame.contentWindow;
+                        // Events of the frame are repeated on the page for listeners of the service
+                        view.addEventListener("beforeprint", function() {
+                            window.dispatchEvent(new Event("beforeprint"));
+                        });
+                        view.addEventListener("afterprint", function() {
+                            window.dispatchEvent(new Event("afterprint"));
+                            setTimeout(function() { frame.remove(); }, 0);
+                        });
+                        view.focus();
+                        view.print();
+                    };
+                    frame.srcdoc = @{html};
+                    document.body.appendChild(frame);
+                }
+            }
+        }
+        true
+    }
+
+    fn listen(&self, callback: Box<FnMut(PrintEvent)>) -> Box<Task> {
+        let mut callback = callback;
+        let callback = move |before: bool| callback(if before { PrintEvent::Before } else { PrintEvent::After });
+        let handle = js! {
+            var callback = @{callback};
+            var before = function() {
+                callback(true);
+            };
+            var after = function() {
+                callback(false);
+            };
+            window.addEventListener("beforeprint", before);
+            window.addEventListener("afterprint", after);
+            return { before, after, callback };
+        };
+        Box::new(BrowserPrint(Some(handle)))
+    }
+}
+
+struct BrowserPrint(Option<Value>);
+
+impl Task for BrowserPrint {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel a subscription to printing twice");
+        js! { @(no_return)
+            var handle = @{handle};
+            window.removeEventListener("beforeprint", handle.before);
+            window.removeEventListener("afterprint", handle.after);
+            handle.callback.drop();
+        }
+    }
+}
+
+impl SelectionBackend for Browser {
+    fn selection(&self, targets: &[NodeRef]) -> Option<Selection> {
+        let elements: Vec<Option<Element>> = targets.iter().map(NodeRef::get).collect();
+        let selection: Value = js! {
+            var targets = @{elements};
+            var selection = window.getSelection();
+            if (!selection || selection.rangeCount === 0) {
+                return null;
+            }
+            // The innermost target which contains the node
+            var owner = function(node) {
+                var found = -1;
+                targets.forEach(function(target, index) {
+                    if (target && target.contains(node) && (found < 0 || targets[found].contains(target))) {
+                        found = index;
+                    }
+                });
+                return found;
+            };
+            var anchor = owner(selection.anchorNode);
+            var focus = owner(selection.focusNode);
+            var range = selection.getRangeAt(0);
+            var start = -1;
+            var end = -1;
+            if (anchor >= 0 && anchor === focus) {
+                var before = document.createRange();
+                before.selectNodeContents(targets[anchor]);
+                before.setEnd(range.startContainer, range.startOffset);
+                // Offsets are counted in code points like chars of Rust
+                start = Array.from(before.toString()).length;
+                end = start + Array.from(range.toString()).length;
+            }
+            var rect = range.getBoundingClientRect();
+            return [selection.toString(), selection.isCollapsed, anchor, focus, start, end,
+                    rect.left, rect.top, rect.width, rect.height];
+        };
+        let values = Vec::<Value>::try_from(selection).ok()?;
+        if values.len() != 10 {
+            return None;
+        }
+        let number = |index: usize| f64::try_from(values[index].clone()).unwrap_or(-1.0);
+        let target = |index: usize| if number(index) >= 0.0 { targets.get(number(index) as usize).cloned() } else { None };
+        let offsets = if number(4) >= 0.0 { Some(number(4) as usize..number(5) as usize) } else { None };
+        Some(Selection {
+            text: String::try_from(values[0].clone()).unwrap_or_default(),
+            collapsed: values[1] == Value::Bool(true),
+            anchor: target(2),
+            focus: target(3),
+            offsets,
+            rect: Some(Rect::new(number(6), number(7), number(8), number(9))),
+        })
+    }
+
+    fn select(&self, target: &NodeRef, offsets: Range<usize>) -> bool {
+        let target = match target.get() {
+            Some(target) => target,
+            None => return false,
+        };
+        let selected: Value = js! {
+            var target = @{target};
+            // Finds the text node and its offset in UTF-16 units of the character offset
+            var point = function(offset) {
+                var walker = document.createTreeWalker(target, NodeFilter.SHOW_TEXT);
+                var node;
+                while ((node = walker.nextNode())) {
+                    var chars = Array.from(node.data);
+                    if (offset <= chars.length) {
+                        return [node, chars.slice(0, offset).join("").length];
+                    }
+                    offset -= chars.length;
+                }
+                return offset === 0 ? [target, target.childNodes.length] : null;
+            };
+            var start = point(@{offsets.start as u32});
+            var end = point(@{offsets.end as u32});
+            if (!start || !end) {
+                return false;
+            }
+            var range = document.createRange();
+            range.setStart(start[0], start[1]);
+            range.setEnd(end[0], end[1]);
+            var selection = window.getSelection();
+            selection.removeAllRanges();
+            selection.addRange(range);
+            return true;
+        };
+        selected == Value::Bool(true)
+    }
+
+    fn clear(&self) {
+        js! { @(no_return)
+            var selection = window.getSelection();
+            if (selection) {
+                selection.removeAllRanges();
+            }
+        }
+    }
+
+    fn listen(&self, callback: Box<FnMut()>) -> Box<Task> {
+        let mut callback = callback;
+        let callback = move || callback();
+        let handle = js! {
+            var callback = @{callback};
+            var listener = function() {
+                callback();
+            };
+            document.addEventListener("selectionchange", listener);
+            return { listener, callback };
+        };
+        Box::new(BrowserSelection(Some(handle)))
+    }
+}
+
+struct BrowserSelection(Option<Value>);
+
+impl Task for BrowserSelection {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel a subscription to the selection twice");
+        js! { @(no_return)
+            var handle = @{handle};
+            document.removeEventListener("selectionchange", handle.listener);
+            handle.callback.drop();
+        }
+    }
+}
+
+impl HeadBackend for Browser {
+    fn insert_hint(&self, hint: &Hint) {
+        js! { @(no_return)
+            var link = document.createElement("link");
+            link.rel = @{hint.rel.as_str()};
+            link.as = @{hint.destination.as_str()};
+            link.href = @{&hint.href};
+            if (@{hint.destination.is_cors()}) {
+                link.crossOrigin = "anonymous";
+            }
+            document.head.appendChild(link);
+        }
+    }
+}
+
+impl LongTaskBackend for Browser {
+    fn observe(&self, callback: Box<FnMut(LongTask)>) -> Box<Task> {
+        let mut callback = callback;
+        let callback = move |start: f64, duration: f64, source: String, container: Option<String>| {
+            callback(LongTask {
+                start: Duration::from_micros((start * 1000.0) as u64),
+                duration: Duration::from_micros((duration * 1000.0) as u64),
+                source,
+                container,
+                activities: Vec::new(),
+            });
+        };
+        let handle = js! {
+            var callback = @{callback};
+            var types = typeof PerformanceObserver === "function" ? PerformanceObserver.supportedEntryTypes || [] : [];
+            if (types.indexOf("longtask") < 0) {
+                return { observer: null, callback };
+            }
+            var observer = new PerformanceObserver(function(list) {
+                list.getEntries().forEach(function(entry) {
+                    var attribution = entry.attribution && entry.attribution[0];
+                    var container = attribution ? attribution.containerSrc || attribution.containerName || null : null;
+                    callback(entry.startTime, entry.duration, entry.name, container);
+                });
+            });
+            observer.observe({ type: "longtask", buffered: true });
+            return { observer, callback };
+        };
+        Box::new(BrowserLongTasks(Some(handle)))
+    }
+}
+
+struct BrowserLongTasks(Option<Value>);
+
+impl Task for BrowserLongTasks {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel an observer of long tasks twice");
+        js! { @(no_return)
+            var handle = @{handle};
+            if (handle.observer) {
+                handle.observer.disconnect();
+            }
+            handle.callback.drop();
+        }
+    }
+}
+
+impl DocumentBackend for Browser {
+    fn listen(&self, kind: &'static str, callback: Box<FnMut(DocumentEvent)>) -> Box<Task> {
+        let mut callback = callback;
+        let callback = move |key: String, target: Value| {
+            let inside = move |node: &NodeRef| match node.get() {
+                Some(element) => js! { return @{element}.contains(@{&target}); } == true,
+                None => false,
+            };
+            callback(DocumentEvent::new(kind, &key, inside));
+        };
+        let handle = js! {
+            var callback = @{callback};
+            var handle = {
+                kind: @{kind},
+                callback,
+            };
+            handle.listener = function(event) {
+                callback(event.key || "", event.target);
+            };
+            document.addEventListener(handle.kind, handle.listener, true);
+            return handle;
+        };
+        Box::new(BrowserDocumentListener(Some(handle)))
+    }
+}
+
+struct BrowserDocumentListener(Option<Value>);
+
+impl Task for BrowserDocumentListener {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel a listener of the document twice");
+        js! { @(no_return)
+            var handle = @{handle};
+            document.removeEventListener(handle.kind, handle.listener, true);
+            handle.callback.drop();
+        }
+    }
+}
+
+#[cfg(feature = "devtools")]
+impl DevtoolsBackend for Browser {
+    fn post(&self, kind: &str, payload: &::serde_json::Value) {
+        // Native builds, like tests of components, have no window to post to
+        if cfg!(not(any(target_arch = "wasm32", target_os = "emscripten"))) {
+            return;
+        }
+        let payload = payload.to_string();
+        js! { @(no_return)
+            window.postMessage({
+                source: "yew-devtools",
+                type: @{kind},
+                payload: JSON.parse(@{payload}),
+            }, "*");
+        }
+    }
+
+    fn listen(&self, callback: Box<FnMut(String)>) -> Box<Task> {
+        if cfg!(not(any(target_arch = "wasm32", target_os = "emscripten"))) {
+            return Box::new(BrowserInspector(None));
+        }
+        let mut callback = callback;
+        let callback = move |kind: String| callback(kind);
+        let handle = js! {
+            var callback = @{callback};
+            var handle = { callback };
+            handle.listener = function(event) {
+                var data = event.data;
+                if (event.source === window && data && data.source === "yew-devtools-extension"
+                        && typeof data.type === "string") {
+                    callback(data.type);
+                }
+            };
+            window.addEventListener("message", handle.listener);
+            return handle;
+        };
+        Box::new(BrowserInspector(Some(handle)))
+    }
+}
+
+#[cfg(feature = "devtools")]
+struct BrowserInspector(Option<Value>);
+
+#[cfg(feature = "devtools")]
+impl Task for BrowserInspector {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel a listener of an inspector twice");
+        js! { @(no_return)
+            var handle = @{handle};
+            window.removeEventListener("message", handle.listener);
+            handle.callback.drop();
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! This module contains a service which sends data with `navigator.sendBeacon`
+//! and calls hooks when the page is left, so queued data reaches a server
+//! even when a user closes the tab:
+//!
+//! ```rust,ignore
+//! self.beacon.send("/api/session-end", Json(&summary));
+//! self.exit = Some(self.beacon.flush_on_exit());
+//! ```
+
+use std::rc::Rc;
+use analytics;
+use errors;
+use format::Storable;
+use super::Task;
+use super::backend::{self, BeaconBackend, VisibilityBackend};
+
+/// A handle of a hook which is called when the page is left.
+pub struct ExitHandle(Option<Box<Task>>);
+
+impl Task for ExitHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let mut listener = self.0.take().expect("tried to cancel an exit hook twice");
+        listener.cancel();
+    }
+}
+
+impl Drop for ExitHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+/// A service of beacons and hooks of leaving the page.
+pub struct BeaconService {
+    beacon: Rc<BeaconBackend>,
+    visibility: Rc<VisibilityBackend>,
+}
+
+impl Default for BeaconService {
+    fn default() -> Self {
+        BeaconService::new()
+    }
+}
+
+impl BeaconService {
+    /// Creates a new service instance.
+    pub fn new() -> Self {
+        BeaconService::with_backends(backend::beacon(), backend::visibility())
+    }
+
+    /// Creates a new service instance which uses the backends.
+    pub fn with_backends(beacon: Rc<BeaconBackend>, visibility: Rc<VisibilityBackend>) -> Self {
+        BeaconService { beacon, visibility }
+    }
+
+    /// Queues a `POST` of the data to the url. A browser sends it even
+    /// after the page is closed. Returns `false` if the data wasn't serialized
+    /// or the browser refused it, so it could be sent with a fetch.
+    pub fn send<IN: Into<Storable>>(&mut self, url: &str, data: IN) -> bool {
+        match data.into() {
+            Some(data) => self.beacon.send(url, &data),
+            None => false,
+        }
+    }
+
+    /// Calls the callback every time the page is hidden, which is the last
+    /// reliable moment before a tab is closed, and when it's unloaded.
+    pub fn on_exit<F: FnMut() + 'static>(&mut self, callback: F) -> ExitHandle {
+        let mut callback = callback;
+        let listener = self.visibility.listen(Box::new(move |hidden| {
+            if hidden {
+                callback();
+            }
+        }));
+        ExitHandle(Some(listener))
+    }
+
+    /// Flushes sinks of `errors` and `analytics` when the page is left.
+    pub fn flush_on_exit(&mut self) -> ExitHandle {
+        self.on_exit(|| {
+            errors::flush();
+            analytics::flush();
+        })
+    }
+}
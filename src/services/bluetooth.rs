@@ -0,0 +1,359 @@
+//! This module contains a service of Web Bluetooth, so dashboards and
+//! configurators read and write characteristics of devices nearby:
+//!
+//! ```rust,ignore
+//! let options = BluetoothOptions::service("heart_rate");
+//! self.task = Some(self.bluetooth.request(&options, Msg::Chosen));
+//!
+//! Msg::Chosen(Ok(device)) => {
+//!     self.notify = Some(self.bluetooth.notify(&device, "heart_rate", "heart_rate_measurement", Msg::Beat));
+//! }
+//! Msg::Beat(GattEvent::Value(data)) => self.bpm = data.get(1).cloned(),
+//! ```
+//!
+//! Services and characteristics are names of the Bluetooth registry, like
+//! `battery_service`, or full UUIDs. A chooser needs a user gesture, so it's
+//! opened from messages of click events. Enable the `bluetooth` feature.
+
+use std::fmt;
+use stdweb::Value;
+use stdweb::unstable::TryFrom;
+use html::AppSender;
+use super::{Task, is_done};
+use super::promise::{self, PromiseHandle};
+
+/// An error of a Bluetooth device.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BluetoothError {
+    /// The browser doesn't have Web Bluetooth.
+    Unsupported,
+    /// A user or a policy of the page didn't allow Bluetooth.
+    Denied,
+    /// A user closed the chooser, or the device doesn't have the service
+    /// or the characteristic.
+    NotFound,
+    /// The device is out of range or disconnected.
+    Disconnected,
+    /// The characteristic doesn't support the operation, like a write.
+    NotSupported,
+    /// Another error with its message.
+    Failed(String),
+}
+
+impl fmt::Display for BluetoothError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BluetoothError::Unsupported => write!(f, "Web Bluetooth isn't supported"),
+            BluetoothError::Denied => write!(f, "an access to Bluetooth was denied"),
+            BluetoothError::NotFound => write!(f, "a device, a service or a characteristic wasn't found"),
+            BluetoothError::Disconnected => write!(f, "a device is disconnected"),
+            BluetoothError::NotSupported => write!(f, "a characteristic doesn't support the operation"),
+            BluetoothError::Failed(ref reason) => write!(f, "Bluetooth error: {}", reason),
+        }
+    }
+}
+
+/// Options of a chooser of devices.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BluetoothOptions {
+    /// Services which a device has to advertise.
+    pub services: Vec<String>,
+    /// A prefix of the name of a device.
+    pub name_prefix: Option<String>,
+    /// Other services which are used after a device is chosen.
+    pub optional_services: Vec<String>,
+    /// Shows all devices nearby, filters are ignored.
+    pub accept_all: bool,
+}
+
+impl BluetoothOptions {
+    /// Creates options which show devices with the service.
+    pub fn service(service: &str) -> Self {
+        BluetoothOptions {
+            services: vec![service.to_owned()],
+            ..BluetoothOptions::default()
+        }
+    }
+
+    /// Creates options which show devices with the prefix of a name.
+    pub fn name_prefix(prefix: &str) -> Self {
+        BluetoothOptions {
+            name_prefix: Some(prefix.to_owned()),
+            ..BluetoothOptions::default()
+        }
+    }
+
+    /// Creates options which show all devices nearby.
+    pub fn accept_all() -> Self {
+        BluetoothOptions {
+            accept_all: true,
+            ..BluetoothOptions::default()
+        }
+    }
+
+    /// Allows to use the service too, a device doesn't have to advertise it.
+    pub fn optional_service(mut self, service: &str) -> Self {
+        self.optional_services.push(service.to_owned());
+        self
+    }
+}
+
+/// A device which a user chose.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BluetoothDevice(Value);
+
+impl BluetoothDevice {
+    /// Returns an id of the device, which is stable for the origin.
+    pub fn id(&self) -> String {
+        let id: Value = js! { return @{&self.0}.id; };
+        id.into_string().unwrap_or_default()
+    }
+
+    /// Returns the name of the device.
+    pub fn name(&self) -> Option<String> {
+        let name: Value = js! { return @{&self.0}.name || null; };
+        name.into_string()
+    }
+
+    /// Returns `true` if the device is connected.
+    pub fn is_connected(&self) -> bool {
+        let connected: Value = js! { return @{&self.0}.gatt.connected; };
+        connected == Value::Bool(true)
+    }
+
+    /// Disconnects from the device.
+    pub fn disconnect(&self) {
+        js! { @(no_return)
+            @{&self.0}.gatt.disconnect();
+        }
+    }
+}
+
+/// Events of notifications of a characteristic.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GattEvent {
+    /// Notifications started.
+    Started,
+    /// The characteristic changed to the value.
+    Value(Vec<u8>),
+    /// The device was disconnected, notifications stopped.
+    Disconnected,
+    /// Notifications didn't start with the error.
+    Failed(BluetoothError),
+}
+
+/// A handle of notifications. They're stopped when it's canceled.
+pub struct GattHandle(Option<Value>);
+
+/// Returns `true` if the browser has Web Bluetooth.
+pub fn is_supported() -> bool {
+    let supported: Value = js! {
+        return !!(navigator.bluetooth && navigator.bluetooth.requestDevice);
+    };
+    supported == Value::Bool(true)
+}
+
+/// A service of Bluetooth devices.
+pub struct BluetoothService<MSG> {
+    sender: AppSender<MSG>,
+}
+
+impl<MSG: 'static> BluetoothService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self { sender }
+    }
+
+    /// Shows a chooser of devices and sends the chosen one.
+    pub fn request<F>(&mut self, options: &BluetoothOptions, converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<BluetoothDevice, BluetoothError>) -> MSG + 'static,
+    {
+        let promise = js! {
+            if (!(navigator.bluetooth && navigator.bluetooth.requestDevice)) {
+                return Promise.reject(new DOMException("", "UnsupportedError"));
+            }
+            var services = @{&options.services};
+            var prefix = @{&options.name_prefix};
+            var request = { optionalServices: @{&options.optional_services} };
+            if (@{options.accept_all}) {
+                request.acceptAllDevices = true;
+            } else {
+                var filter = {};
+                if (services.length > 0) {
+                    filter.services = services;
+                }
+                if (prefix !== null) {
+                    filter.namePrefix = prefix;
+                }
+                request.filters = [filter];
+            }
+            return navigator.bluetooth.requestDevice(request);
+        };
+        self.spawn(promise, |device| Some(BluetoothDevice(device)), converter)
+    }
+
+    /// Reads the value of the characteristic, it connects to the device if it's needed.
+    pub fn read<F>(&mut self, device: &BluetoothDevice, service: &str, characteristic: &str, converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<Vec<u8>, BluetoothError>) -> MSG + 'static,
+    {
+        let promise = js! {
+            return @{characteristic_of(device, service, characteristic)}.then(function(characteristic) {
+                return characteristic.readValue();
+            }).then(function(view) {
+                return Array.from(new Uint8Array(view.buffer, view.byteOffset, view.byteLength));
+            });
+        };
+        self.spawn(promise, |data| Vec::<u8>::try_from(data).ok(), converter)
+    }
+
+    /// Writes the value to the characteristic, it connects to the device if it's needed.
+    pub fn write<F>(&mut self, device: &BluetoothDevice, service: &str, characteristic: &str, data: &[u8],
+                    converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<(), BluetoothError>) -> MSG + 'static,
+    {
+        let promise = js! {
+            var data = new Uint8Array(@{data.to_vec()});
+            return @{characteristic_of(device, service, characteristic)}.then(function(characteristic) {
+                return characteristic.writeValue(data);
+            });
+        };
+        self.spawn(promise, |_| Some(()), converter)
+    }
+
+    /// Sends every new value of the characteristic until the handle is canceled
+    /// or the device is disconnected.
+    pub fn notify<F>(&mut self, device: &BluetoothDevice, service: &str, characteristic: &str, converter: F) -> GattHandle
+    where
+        F: Fn(GattEvent) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let callback = move |kind: String, value: Value| {
+            let event = match kind.as_str() {
+                "started" => GattEvent::Started,
+                "value" => GattEvent::Value(Vec::<u8>::try_from(value).unwrap_or_default()),
+                "disconnected" => GattEvent::Disconnected,
+                _ => GattEvent::Failed(error_of(&kind, value.into_string().unwrap_or_default())),
+            };
+            tx.send(converter(event));
+        };
+        let handle = js! {
+            var callback = @{callback};
+            var device = @{&device.0};
+            var handle = {
+                characteristic: null,
+                callback,
+            };
+            handle.changed = function(event) {
+                var view = event.target.value;
+                callback("value", Array.from(new Uint8Array(view.buffer, view.byteOffset, view.byteLength)));
+            };
+            handle.disconnected = function() {
+                if (handle.done !== true) {
+                    callback("disconnected", null);
+                    handle.stop();
+                }
+            };
+            handle.stop = function() {
+                handle.done = true;
+                device.removeEventListener("gattserverdisconnected", handle.disconnected);
+                if (handle.characteristic !== null) {
+                    var characteristic = handle.characteristic;
+                    characteristic.removeEventListener("characteristicvaluechanged", handle.changed);
+                    if (device.gatt.connected) {
+                        characteristic.stopNotifications().catch(function() {});
+                    }
+                }
+                callback.drop();
+            };
+            device.addEventListener("gattserverdisconnected", handle.disconnected);
+            @{characteristic_of(device, service, characteristic)}.then(function(characteristic) {
+                return characteristic.startNotifications();
+            }).then(function(characteristic) {
+                if (handle.done === true) {
+                    characteristic.stopNotifications().catch(function() {});
+                    return;
+                }
+                handle.characteristic = characteristic;
+                characteristic.addEventListener("characteristicvaluechanged", handle.changed);
+                callback("started", null);
+            }).catch(function(error) {
+                if (handle.done !== true) {
+                    callback(error && error.name || "failed", String(error && error.message || error));
+                    handle.stop();
+                }
+            });
+            return handle;
+        };
+        GattHandle(Some(handle))
+    }
+
+    fn spawn<T, C, F>(&mut self, promise: Value, convert: C, converter: F) -> PromiseHandle
+    where
+        C: Fn(Value) -> Option<T> + 'static,
+        F: Fn(Result<T, BluetoothError>) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        promise::settle(promise, move |resolved, value| {
+            let result = if resolved {
+                convert(value).ok_or_else(|| BluetoothError::Failed("unexpected value".into()))
+            } else {
+                let name: Value = js! {
+                    var reason = @{&value};
+                    return reason && reason.name || "";
+                };
+                Err(error_of(&name.into_string().unwrap_or_default(), promise::reason(value)))
+            };
+            tx.send(converter(result));
+        })
+    }
+}
+
+/// Returns a promise of the characteristic, it connects to the device if it's needed.
+fn characteristic_of(device: &BluetoothDevice, service: &str, characteristic: &str) -> Value {
+    let promise: Value = js! {
+        var gatt = @{&device.0}.gatt;
+        var service = @{service};
+        var characteristic = @{characteristic};
+        return (gatt.connected ? Promise.resolve(gatt) : gatt.connect()).then(function(server) {
+            return server.getPrimaryService(service);
+        }).then(function(service) {
+            return service.getCharacteristic(characteristic);
+        });
+    };
+    promise
+}
+
+fn error_of(name: &str, message: String) -> BluetoothError {
+    match name {
+        "UnsupportedError" => BluetoothError::Unsupported,
+        "NotSupportedError" | "InvalidModificationError" => BluetoothError::NotSupported,
+        "NotAllowedError" | "SecurityError" => BluetoothError::Denied,
+        "NotFoundError" => BluetoothError::NotFound,
+        "NetworkError" => BluetoothError::Disconnected,
+        _ => BluetoothError::Failed(message),
+    }
+}
+
+impl Task for GattHandle {
+    fn is_active(&self) -> bool {
+        self.0.as_ref().map_or(false, |handle| !is_done(handle))
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel notifications twice");
+        js! { @(no_return)
+            @{handle}.stop();
+        }
+    }
+}
+
+impl Drop for GattHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
@@ -0,0 +1,443 @@
+//! This module contains a service which captures the screen with
+//! `getDisplayMedia`, the camera and the microphone with `getUserMedia`, and
+//! makes images of rendered elements, like a screen share in a call or
+//! a screenshot which is attached to a bug report:
+//!
+//! ```rust,ignore
+//! html! { <video ref=&self.preview, muted=true, playsinline=true,/> }
+//!
+//! self.share = Some(self.capture.share(ShareOptions::new(), Msg::Share));
+//!
+//! Msg::Share(ShareEvent::Started(stream)) => { stream.play(&self.preview); }
+//! Msg::Share(ShareEvent::Failed(CaptureError::Denied)) => self.error = Some("Screen sharing is blocked".into()),
+//! Msg::Attach => self.task = Some(self.capture.screenshot(ThumbnailOptions::fit(1280, 1280), Msg::Screenshot)),
+//! ```
+//!
+//! Images are `Thumbnail`s of the image service, so they're encoded and
+//! fit the same way. Capturing the screen needs a user gesture, so it's
+//! started from messages of click events.
+
+use std::fmt;
+use stdweb::Value;
+use html::AppSender;
+use virtual_dom::NodeRef;
+use super::{Task, is_done};
+use super::image::{self, Thumbnail, ThumbnailOptions};
+use super::promise::{self, PromiseHandle};
+
+/// An error of a capture.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CaptureError {
+    /// The browser can't capture the screen or devices.
+    Unsupported,
+    /// A user or a policy of the page didn't allow to capture the screen
+    /// or devices, or a user closed the picker.
+    Denied,
+    /// There is no camera or microphone, or the element isn't rendered or
+    /// isn't a `<video>`, an `<img>` or a `<canvas>` with a frame to draw.
+    Missing,
+    /// The element shows an image of another origin which isn't allowed by CORS
+    /// and can't be read.
+    Tainted,
+    /// Another error with its message.
+    Failed(String),
+}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CaptureError::Unsupported => write!(f, "the browser can't capture media"),
+            CaptureError::Denied => write!(f, "a permission to capture media wasn't granted"),
+            CaptureError::Missing => write!(f, "there is nothing to capture"),
+            CaptureError::Tainted => write!(f, "an image of another origin can't be captured"),
+            CaptureError::Failed(ref reason) => write!(f, "capture error: {}", reason),
+        }
+    }
+}
+
+/// Options of a screen share.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShareOptions {
+    /// Captures the sound of a tab or of the system too, where a browser can.
+    pub audio: bool,
+    /// The highest frame rate, the browser chooses if it's `None`.
+    pub frame_rate: Option<u32>,
+}
+
+impl ShareOptions {
+    /// Creates options which capture the video only.
+    pub fn new() -> Self {
+        ShareOptions::default()
+    }
+
+    /// Captures the sound too.
+    pub fn audio(mut self) -> Self {
+        self.audio = true;
+        self
+    }
+
+    /// Limits the frame rate.
+    pub fn frame_rate(mut self, frame_rate: u32) -> Self {
+        self.frame_rate = Some(frame_rate);
+        self
+    }
+}
+
+/// Options of a stream of the camera and the microphone.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceOptions {
+    /// Captures the camera.
+    pub video: bool,
+    /// Captures the microphone.
+    pub audio: bool,
+}
+
+impl DeviceOptions {
+    /// Creates options which capture the microphone only, like for voice memos.
+    pub fn microphone() -> Self {
+        DeviceOptions { video: false, audio: true }
+    }
+
+    /// Creates options which capture the camera and the microphone.
+    pub fn camera() -> Self {
+        DeviceOptions { video: true, audio: true }
+    }
+}
+
+/// A stream of a capture. It's stopped by its `ShareHandle`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaStream(Value);
+
+impl MediaStream {
+    /// Plays the stream in the video element. Returns `false` if the
+    /// element isn't rendered.
+    pub fn play(&self, video: &NodeRef) -> bool {
+        let video = match video.get() {
+            Some(video) => video,
+            None => return false,
+        };
+        js! { @(no_return)
+            var video = @{video};
+            video.srcObject = @{&self.0};
+            video.play().catch(function() {});
+        }
+        true
+    }
+
+    /// Returns `false` when all tracks of the stream are stopped.
+    pub fn is_active(&self) -> bool {
+        let active: Value = js! { return @{&self.0}.active === true; };
+        active == Value::Bool(true)
+    }
+
+    #[doc(hidden)]
+    pub fn raw(&self) -> &Value {
+        &self.0
+    }
+}
+
+/// Events of a screen share.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShareEvent {
+    /// A user chose what to share or allowed devices and the stream runs.
+    Started(MediaStream),
+    /// A user stopped sharing with a control of the browser or devices are gone.
+    Ended,
+    /// Sharing didn't start with the error.
+    Failed(CaptureError),
+}
+
+/// A handle of a screen share. The stream is stopped when it's canceled.
+pub struct ShareHandle(Option<Value>);
+
+/// Returns `true` if the browser can capture the screen.
+pub fn is_supported() -> bool {
+    let supported: Value = js! {
+        return !!(navigator.mediaDevices && navigator.mediaDevices.getDisplayMedia);
+    };
+    supported == Value::Bool(true)
+}
+
+/// A service to capture the screen and elements.
+pub struct CaptureService<MSG> {
+    sender: AppSender<MSG>,
+}
+
+impl<MSG: 'static> CaptureService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self { sender }
+    }
+
+    /// Asks a user to choose a screen, a window or a tab and sends events
+    /// of the stream until the handle is canceled or sharing ends.
+    pub fn share<F>(&mut self, options: ShareOptions, converter: F) -> ShareHandle
+    where
+        F: Fn(ShareEvent) -> MSG + 'static,
+    {
+        let frame_rate = options.frame_rate.map(f64::from);
+        let request = js! {
+            if (!(navigator.mediaDevices && navigator.mediaDevices.getDisplayMedia)) {
+                return Promise.reject(new DOMException("", "NotSupportedError"));
+            }
+            var frameRate = @{frame_rate};
+            var video = frameRate === null ? true : { frameRate: { max: frameRate } };
+            return navigator.mediaDevices.getDisplayMedia({ video: video, audio: @{options.audio} });
+        };
+        self.open(request, converter)
+    }
+
+    /// Asks a user to allow the camera or the microphone and sends events
+    /// of the stream until the handle is canceled or a device is gone.
+    pub fn devices<F>(&mut self, options: DeviceOptions, converter: F) -> ShareHandle
+    where
+        F: Fn(ShareEvent) -> MSG + 'static,
+    {
+        let request = js! {
+            if (!(navigator.mediaDevices && navigator.mediaDevices.getUserMedia)) {
+                return Promise.reject(new DOMException("", "NotSupportedError"));
+            }
+            return navigator.mediaDevices.getUserMedia({ video: @{options.video}, audio: @{options.audio} });
+        };
+        self.open(request, converter)
+    }
+
+    /// Sends events of the stream of a promise until the handle is canceled.
+    fn open<F>(&mut self, request: Value, converter: F) -> ShareHandle
+    where
+        F: Fn(ShareEvent) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let callback = move |kind: String, message: String, stream: Value| {
+            let event = match kind.as_str() {
+                "started" => ShareEvent::Started(MediaStream(stream)),
+                "ended" => ShareEvent::Ended,
+                _ => ShareEvent::Failed(error_of(&kind, message)),
+            };
+            tx.send(converter(event));
+        };
+        let handle = js! {
+            var callback = @{callback};
+            var handle = {
+                stream: null,
+                callback,
+            };
+            handle.stop = function() {
+                handle.done = true;
+                if (handle.stream !== null) {
+                    handle.stream.getTracks().forEach(function(track) { track.stop(); });
+                }
+                callback.drop();
+            };
+            function finish(kind, message) {
+                if (handle.done !== true) {
+                    callback(kind, message, null);
+                    handle.stop();
+                }
+            }
+            @{request}.then(function(stream) {
+                if (handle.done === true) {
+                    stream.getTracks().forEach(function(track) { track.stop(); });
+                    return;
+                }
+                handle.stream = stream;
+                // A browser ends tracks when a user presses its stop button or a device is unplugged
+                stream.getTracks().forEach(function(track) {
+                    track.addEventListener("ended", function() {
+                        if (stream.getTracks().every(function(track) { return track.readyState === "ended"; })) {
+                            finish("ended", "");
+                        }
+                    });
+                });
+                callback("started", "", stream);
+            }).catch(function(error) {
+                finish(error && error.name || "failed", String(error && error.message || error));
+            });
+            return handle;
+        };
+        ShareHandle(Some(handle))
+    }
+
+    /// Asks a user to choose a screen, a window or a tab, captures one frame
+    /// and sends it as an image. The stream is stopped after the frame.
+    pub fn screenshot<F>(&mut self, options: ThumbnailOptions, converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<Thumbnail, CaptureError>) -> MSG + 'static,
+    {
+        start_helpers();
+        let stream = js! {
+            if (!(navigator.mediaDevices && navigator.mediaDevices.getDisplayMedia)) {
+                return Promise.reject(new DOMException("", "NotSupportedError"));
+            }
+            return navigator.mediaDevices.getDisplayMedia({ video: true, audio: false });
+        };
+        let frame = js! {
+            return @{stream}.then(function(stream) {
+                return window.__yew_capture.frame(stream).then(function(video) {
+                    return [video, stream];
+                }, function(error) {
+                    stream.getTracks().forEach(function(track) { track.stop(); });
+                    throw error;
+                });
+            });
+        };
+        self.draw(frame, options, converter)
+    }
+
+    /// Captures the current frame of a stream as an image, like of a running share.
+    pub fn snapshot<F>(&mut self, stream: &MediaStream, options: ThumbnailOptions, converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<Thumbnail, CaptureError>) -> MSG + 'static,
+    {
+        start_helpers();
+        let frame = js! {
+            return window.__yew_capture.frame(@{&stream.0}).then(function(video) {
+                return [video, null];
+            });
+        };
+        self.draw(frame, options, converter)
+    }
+
+    /// Captures the image of a rendered `<video>`, `<img>` or `<canvas>` element.
+    pub fn element<F>(&mut self, target: &NodeRef, options: ThumbnailOptions, converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<Thumbnail, CaptureError>) -> MSG + 'static,
+    {
+        start_helpers();
+        let frame = match target.get() {
+            Some(element) => js! { return Promise.resolve([@{element}, null]); },
+            None => js! { return Promise.reject(null); },
+        };
+        self.draw(frame, options, converter)
+    }
+
+    /// Draws the element of a promise of `[element, stream]` and sends its image.
+    /// The stream is stopped after the element is drawn.
+    fn draw<F>(&mut self, frame: Value, options: ThumbnailOptions, converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<Thumbnail, CaptureError>) -> MSG + 'static,
+    {
+        let drawn = js! {
+            var job = {
+                maxWidth: @{options.max_width},
+                maxHeight: @{options.max_height},
+                type: @{&options.mime},
+                quality: @{options.quality},
+            };
+            return @{frame}.then(function(frame) {
+                try {
+                    return window.__yew_capture.draw(frame[0], job);
+                } finally {
+                    if (frame[1] !== null) {
+                        frame[1].getTracks().forEach(function(track) { track.stop(); });
+                    }
+                }
+            });
+        };
+        let published = image::publish(drawn, options.data_url);
+        let mut tx = self.sender.clone();
+        promise::settle(published, move |resolved, value| {
+            let result = if resolved {
+                Ok(image::thumbnail_of(&value))
+            } else if value == Value::Null {
+                Err(CaptureError::Missing)
+            } else {
+                let name: Value = js! {
+                    var reason = @{&value};
+                    return reason && reason.name || "";
+                };
+                Err(error_of(&name.into_string().unwrap_or_default(), promise::reason(value)))
+            };
+            tx.send(converter(result));
+        })
+    }
+}
+
+/// Starts helpers which wait for frames of streams and draw elements.
+fn start_helpers() {
+    js! { @(no_return)
+        if (window.__yew_capture !== undefined) {
+            return;
+        }
+        window.__yew_capture = {
+            // Resolves with a playing video of the stream which has a frame to draw
+            frame: function(stream) {
+                var video = document.createElement("video");
+                video.muted = true;
+                video.playsInline = true;
+                video.srcObject = stream;
+                return video.play().then(function() {
+                    if (video.readyState >= 2) {
+                        return video;
+                    }
+                    return new Promise(function(resolve) {
+                        video.addEventListener("loadeddata", function() { resolve(video); }, { once: true });
+                    });
+                });
+            },
+            // Resolves with a description of an encoded image of the element
+            draw: function(element, job) {
+                var width = element.videoWidth || element.naturalWidth || element.width || 0;
+                var height = element.videoHeight || element.naturalHeight || element.height || 0;
+                var drawable = element instanceof HTMLVideoElement || element instanceof HTMLImageElement
+                    || element instanceof HTMLCanvasElement;
+                if (!drawable || width === 0 || height === 0) {
+                    return Promise.reject(null);
+                }
+                var scale = Math.min(1, job.maxWidth / width, job.maxHeight / height);
+                var canvas = document.createElement("canvas");
+                canvas.width = Math.max(1, Math.round(width * scale));
+                canvas.height = Math.max(1, Math.round(height * scale));
+                canvas.getContext("2d").drawImage(element, 0, 0, canvas.width, canvas.height);
+                var size = { width: canvas.width, height: canvas.height, originalWidth: width, originalHeight: height };
+                return new Promise(function(resolve, reject) {
+                    try {
+                        canvas.toBlob(function(blob) {
+                            if (blob === null) {
+                                reject(new Error("the image wasn't encoded"));
+                            } else {
+                                size.blob = blob;
+                                resolve(size);
+                            }
+                        }, job.type, job.quality);
+                    } catch (error) {
+                        // A canvas with an image of another origin can't be read, it's told
+                        // apart from a `SecurityError` of a policy of the page here
+                        reject(new DOMException(String(error && error.message || error), "TaintedError"));
+                    }
+                });
+            },
+        };
+    }
+}
+
+fn error_of(name: &str, message: String) -> CaptureError {
+    match name {
+        "NotSupportedError" => CaptureError::Unsupported,
+        "NotAllowedError" | "AbortError" | "SecurityError" => CaptureError::Denied,
+        "TaintedError" => CaptureError::Tainted,
+        "NotFoundError" | "OverconstrainedError" => CaptureError::Missing,
+        _ => CaptureError::Failed(message),
+    }
+}
+
+impl Task for ShareHandle {
+    fn is_active(&self) -> bool {
+        self.0.as_ref().map_or(false, |handle| !is_done(handle))
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel a screen share twice");
+        js! { @(no_return)
+            @{handle}.stop();
+        }
+    }
+}
+
+impl Drop for ShareHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
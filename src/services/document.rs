@@ -0,0 +1,212 @@
+//! This module contains a service of shared listeners of the document, which
+//! dropdowns, dialogs and menus request to close on Escape or a press outside:
+//!
+//! ```rust,ignore
+//! let inside = [self.button.clone(), self.menu.clone()];
+//! self.escape = Some(self.document.on_escape(|| Msg::Close));
+//! self.outside = Some(self.document.on_outside_press(&inside, || Msg::Close));
+//!
+//! Msg::Close => { self.escape = None; self.outside = None; }
+//! ```
+//!
+//! Requests of all components of the app are multiplexed by one real listener
+//! of the document for a kind of events, which is added with the first request
+//! and removed with the last one. Listeners capture events, so a listener of
+//! `html!` which stops propagation doesn't hide them. Escape is sent to the
+//! latest request only, so the innermost of nested popups closes first.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use html::AppSender;
+use virtual_dom::NodeRef;
+use super::Task;
+use super::backend::{self, DocumentBackend};
+
+const KEYDOWN: &str = "keydown";
+const POINTERDOWN: &str = "pointerdown";
+
+/// An event of the document which a `DocumentBackend` reports.
+pub struct DocumentEvent {
+    /// The kind, like `keydown` or `pointerdown`.
+    pub kind: &'static str,
+    /// The key of a keyboard event, like `Escape`, empty for other events.
+    pub key: String,
+    inside: Box<Fn(&NodeRef) -> bool>,
+}
+
+impl DocumentEvent {
+    /// Creates an event with a check if its target is within the element of a reference.
+    pub fn new<F>(kind: &'static str, key: &str, inside: F) -> Self
+    where
+        F: Fn(&NodeRef) -> bool + 'static,
+    {
+        DocumentEvent {
+            kind,
+            key: key.to_owned(),
+            inside: Box::new(inside),
+        }
+    }
+
+    /// Returns `true` if the target is within the rendered element of the reference.
+    pub fn is_inside(&self, node: &NodeRef) -> bool {
+        (self.inside)(node)
+    }
+}
+
+type Callback = Rc<RefCell<Box<FnMut(&DocumentEvent)>>>;
+
+enum Request {
+    Escape,
+    Key,
+    Outside(Vec<NodeRef>),
+}
+
+struct Shared {
+    listener: Box<Task>,
+    requests: Vec<(usize, Request, Callback)>,
+}
+
+#[derive(Default)]
+struct Registry {
+    next_id: usize,
+    shared: HashMap<&'static str, Shared>,
+}
+
+thread_local! {
+    static REGISTRY: RefCell<Registry> = RefCell::new(Registry::default());
+}
+
+fn add(backend: &Rc<DocumentBackend>, kind: &'static str, request: Request, callback: Callback) -> DocumentHandle {
+    REGISTRY.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        let id = registry.next_id;
+        registry.next_id += 1;
+        let shared = registry.shared.entry(kind).or_insert_with(|| Shared {
+            listener: backend.listen(kind, Box::new(|event| dispatch(&event))),
+            requests: Vec::new(),
+        });
+        shared.requests.push((id, request, callback));
+        DocumentHandle(Some((kind, id)))
+    })
+}
+
+fn dispatch(event: &DocumentEvent) {
+    // Callbacks are called out of the borrow, because updates cancel requests
+    let callbacks: Vec<Callback> = REGISTRY.with(|registry| {
+        let registry = registry.borrow();
+        let requests = match registry.shared.get(event.kind) {
+            Some(shared) => &shared.requests,
+            None => return Vec::new(),
+        };
+        let mut callbacks: Vec<Callback> = requests.iter()
+            .filter(|(_, request, _)| match *request {
+                Request::Escape => false,
+                Request::Key => true,
+                Request::Outside(ref inside) => !inside.iter().any(|node| event.is_inside(node)),
+            })
+            .map(|(_, _, callback)| callback.clone())
+            .collect();
+        if event.key == "Escape" {
+            let latest = requests.iter().rev().find(|(_, request, _)| matches!(*request, Request::Escape));
+            if let Some((_, _, callback)) = latest {
+                callbacks.push(callback.clone());
+            }
+        }
+        callbacks
+    });
+    for callback in callbacks {
+        (*callback.borrow_mut())(event);
+    }
+}
+
+/// A handle of a request of a shared listener. It's canceled when dropped.
+pub struct DocumentHandle(Option<(&'static str, usize)>);
+
+impl Task for DocumentHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let (kind, id) = self.0.take().expect("tried to cancel a document listener twice");
+        let unused = REGISTRY.with(|registry| {
+            let mut registry = registry.borrow_mut();
+            let empty = match registry.shared.get_mut(kind) {
+                Some(shared) => {
+                    shared.requests.retain(|&(request, _, _)| request != id);
+                    shared.requests.is_empty()
+                }
+                None => false,
+            };
+            if empty {
+                registry.shared.remove(kind)
+            } else {
+                None
+            }
+        });
+        if let Some(mut shared) = unused {
+            shared.listener.cancel();
+        }
+    }
+}
+
+impl Drop for DocumentHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+/// A service of shared listeners of the document.
+pub struct DocumentService<MSG> {
+    sender: AppSender<MSG>,
+    backend: Rc<DocumentBackend>,
+}
+
+impl<MSG: 'static> DocumentService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self::with_backend(sender, backend::document())
+    }
+
+    /// Creates a new service instance which uses the backend. The backend
+    /// of the first request adds the shared listener.
+    pub fn with_backend(sender: AppSender<MSG>, backend: Rc<DocumentBackend>) -> Self {
+        Self { sender, backend }
+    }
+
+    /// Sends a message when Escape is pressed anywhere in the document and
+    /// the request is the latest one of Escape.
+    pub fn on_escape<F>(&mut self, converter: F) -> DocumentHandle
+    where
+        F: Fn() -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let callback: Box<FnMut(&DocumentEvent)> = Box::new(move |_| tx.send(converter()));
+        add(&self.backend, KEYDOWN, Request::Escape, Rc::new(RefCell::new(callback)))
+    }
+
+    /// Sends a message with the key of every key pressed in the document,
+    /// like shortcuts of an open menu.
+    pub fn on_key<F>(&mut self, converter: F) -> DocumentHandle
+    where
+        F: Fn(String) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let callback: Box<FnMut(&DocumentEvent)> = Box::new(move |event| tx.send(converter(event.key.clone())));
+        add(&self.backend, KEYDOWN, Request::Key, Rc::new(RefCell::new(callback)))
+    }
+
+    /// Sends a message when a pointer is pressed outside of all elements of
+    /// the references, like a trigger and a popup rendered to a portal.
+    pub fn on_outside_press<F>(&mut self, inside: &[NodeRef], converter: F) -> DocumentHandle
+    where
+        F: Fn() -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let callback: Box<FnMut(&DocumentEvent)> = Box::new(move |_| tx.send(converter()));
+        add(&self.backend, POINTERDOWN, Request::Outside(inside.to_vec()), Rc::new(RefCell::new(callback)))
+    }
+}
@@ -0,0 +1,204 @@
+//! This module contains a service of the EyeDropper API, so design tools
+//! let users pick a color of any pixel of the screen:
+//!
+//! ```rust,ignore
+//! Msg::Pick => self.pick = Some(self.eyedropper.pick(Msg::Picked)),
+//! Msg::Picked(Ok(color)) => self.swatches.push(color),
+//! Msg::Picked(Err(PickError::Canceled)) => (),
+//! ```
+//!
+//! A picker needs a user gesture, so it's opened from messages of click
+//! events. Check `is_supported` to hide the button in other browsers.
+
+use std::fmt;
+use stdweb::Value;
+use super::Task;
+use super::promise::{self, PromiseHandle};
+use html::AppSender;
+
+/// A color of the sRGB space with 8 bits a channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Srgb {
+    /// The red channel.
+    pub r: u8,
+    /// The green channel.
+    pub g: u8,
+    /// The blue channel.
+    pub b: u8,
+}
+
+impl Srgb {
+    /// Creates a color of the channels.
+    pub fn new(r: u8, g: u8, b: u8) -> Self {
+        Srgb { r, g, b }
+    }
+
+    /// Parses a hex color like `#1e90ff` or `#fff`. The `#` is optional.
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.trim_start_matches('#');
+        if !hex.is_ascii() {
+            return None;
+        }
+        let channel = |from: usize, len: usize| {
+            let value = u8::from_str_radix(&hex[from..from + len], 16).ok()?;
+            Some(if len == 1 { value * 17 } else { value })
+        };
+        match hex.len() {
+            3 => Some(Srgb::new(channel(0, 1)?, channel(1, 1)?, channel(2, 1)?)),
+            6 => Some(Srgb::new(channel(0, 2)?, channel(2, 2)?, channel(4, 2)?)),
+            _ => None,
+        }
+    }
+
+    /// Returns the color like `#1e90ff`, to use in styles.
+    pub fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+
+    /// Returns the relative luminance from 0 for black to 1 for white.
+    pub fn luminance(&self) -> f64 {
+        let linear = |channel: u8| {
+            let value = f64::from(channel) / 255.0;
+            if value <= 0.039_28 {
+                value / 12.92
+            } else {
+                ((value + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linear(self.r) + 0.7152 * linear(self.g) + 0.0722 * linear(self.b)
+    }
+
+    /// Returns the contrast ratio with another color from 1 to 21,
+    /// text needs at least 4.5 to be readable.
+    pub fn contrast(&self, other: &Srgb) -> f64 {
+        let (a, b) = (self.luminance(), other.luminance());
+        let (light, dark) = if a > b { (a, b) } else { (b, a) };
+        (light + 0.05) / (dark + 0.05)
+    }
+}
+
+impl fmt::Display for Srgb {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_hex())
+    }
+}
+
+/// An error of a picker.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PickError {
+    /// The browser doesn't have the EyeDropper API.
+    Unsupported,
+    /// A user closed the picker without a color.
+    Canceled,
+    /// Another error with its message.
+    Failed(String),
+}
+
+impl fmt::Display for PickError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PickError::Unsupported => write!(f, "the EyeDropper API isn't supported"),
+            PickError::Canceled => write!(f, "a color wasn't picked"),
+            PickError::Failed(ref reason) => write!(f, "picker error: {}", reason),
+        }
+    }
+}
+
+/// A handle of an opened picker. The picker is closed when it's canceled.
+pub struct PickHandle {
+    promise: PromiseHandle,
+    controller: Value,
+}
+
+/// Returns `true` if the browser has the EyeDropper API.
+pub fn is_supported() -> bool {
+    let supported: Value = js! {
+        return typeof EyeDropper === "function";
+    };
+    supported == Value::Bool(true)
+}
+
+/// A service to pick colors of the screen.
+pub struct EyeDropperService<MSG> {
+    sender: AppSender<MSG>,
+}
+
+impl<MSG: 'static> EyeDropperService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self { sender }
+    }
+
+    /// Opens a picker and sends the color which a user clicked.
+    pub fn pick<F>(&mut self, converter: F) -> PickHandle
+    where
+        F: Fn(Result<Srgb, PickError>) -> MSG + 'static,
+    {
+        let controller: Value = js! {
+            return typeof AbortController === "function" ? new AbortController() : null;
+        };
+        let promise = js! {
+            if (typeof EyeDropper !== "function") {
+                return Promise.reject(new DOMException("", "UnsupportedError"));
+            }
+            var controller = @{&controller};
+            var options = controller !== null ? { signal: controller.signal } : {};
+            return new EyeDropper().open(options).then(function(result) {
+                return result.sRGBHex;
+            });
+        };
+        let mut tx = self.sender.clone();
+        let promise = promise::settle(promise, move |resolved, value| {
+            let result = if resolved {
+                let color = value.into_string().unwrap_or_default();
+                color_of(&color).ok_or_else(|| PickError::Failed(format!("unexpected color: {}", color)))
+            } else {
+                let name: Value = js! {
+                    var reason = @{&value};
+                    return reason && reason.name || "";
+                };
+                match name.into_string().unwrap_or_default().as_str() {
+                    "UnsupportedError" => Err(PickError::Unsupported),
+                    "AbortError" => Err(PickError::Canceled),
+                    _ => Err(PickError::Failed(promise::reason(value))),
+                }
+            };
+            tx.send(converter(result));
+        });
+        PickHandle { promise, controller }
+    }
+}
+
+/// Parses a hex color or `rgb(...)`, which some browsers send instead.
+fn color_of(color: &str) -> Option<Srgb> {
+    if !color.starts_with("rgb") {
+        return Srgb::from_hex(color);
+    }
+    let inner = color.split(&['(', ')'][..]).nth(1)?;
+    let mut channels = inner.split(',').map(|channel| channel.trim().parse::<u8>().ok());
+    Some(Srgb::new(channels.next()??, channels.next()??, channels.next()??))
+}
+
+impl Task for PickHandle {
+    fn is_active(&self) -> bool {
+        self.promise.is_active()
+    }
+
+    fn cancel(&mut self) {
+        self.promise.cancel();
+        js! { @(no_return)
+            var controller = @{&self.controller};
+            if (controller !== null) {
+                controller.abort();
+            }
+        }
+    }
+}
+
+impl Drop for PickHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
@@ -1,13 +1,13 @@
 //! Service to send HTTP-request to a server.
 
-use std::collections::HashMap;
-
-use stdweb::Value;
-use stdweb::unstable::TryFrom;
+use std::rc::Rc;
 
+use errors::{self, ErrorKind, ErrorReport};
+use progress::{self, Kind};
 use html::AppSender;
 use format::{Storable, Restorable};
-use super::Task;
+use super::{interceptor, Task};
+use super::backend::{self, FetchBackend};
 
 pub use http::{
     HeaderMap,
@@ -17,22 +17,29 @@ pub use http::{
     StatusCode,
     Uri
 };
+pub use url::Url;
 
 
 /// A handle to control sent requests. Can be canceled with a `Task::cancel` call.
-pub struct FetchHandle(Option<Value>);
+pub struct FetchHandle(Box<Task>);
 
 
 /// A service to fetch resources.
 pub struct FetchService<MSG> {
     sender: AppSender<MSG>,
+    backend: Rc<FetchBackend>,
 }
 
 impl<MSG: 'static> FetchService<MSG> {
 
     /// Creates a new service instance connected to an `App` by the provided `sender`.
     pub fn new(sender: AppSender<MSG>) -> Self {
-        Self { sender }
+        Self::with_backend(sender, backend::fetch())
+    }
+
+    /// Creates a new service instance which sends requests with the `backend`.
+    pub fn with_backend(sender: AppSender<MSG>, backend: Rc<FetchBackend>) -> Self {
+        Self { sender, backend }
     }
 
     /// Sends a request to a remote server given a Request object and a callback
@@ -89,98 +96,39 @@ impl<MSG: 'static> FetchService<MSG> {
         OUT: From<Restorable>,
         F: Fn(Response<OUT>) -> MSG + 'static
     {
-        // Consume request as parts and body.
         let (parts, body) = request.into_parts();
-
-        // Map headers into a Js serializable HashMap.
-        let header_map: HashMap<&str, &str> = parts.headers.iter().map(
-            |(k, v)| (k.as_str(), v.to_str().expect(
-                format!("Unparsable request header {}: {:?}", k.as_str(), v).as_str()
-            ))
-        ).collect();
-
-        // Formats URI.
-        let uri = format!("{}", parts.uri);
-
-        // Prepare the response callback.
-        // Notice that the callback signature must match the call from the javascript
-        // side. There is no static check at this point.
+        let request = interceptor::fetch(Request::from_parts(parts, body.into()));
+        let target = format!("{} {}", request.method(), request.uri());
         let mut tx = self.sender.clone();
-        let callback = move |success: bool, response: Value, body: String| {
-            let mut response_builder = Response::builder();
-
-            // Deserialize response status.
-            let status = u16::try_from(js!{
-                return @{&response}.status;
-            });
-
-            if let Ok(code) = status {
-                response_builder.status(code);
-            }
-
-            // Deserialize response headers.
-            let headers: HashMap<String, String> = HashMap::try_from(js!{
-                var map = {};
-                @{&response}.headers.forEach(function(value, key) {
-                    map[key] = value;
-                });
-                return map;
-            }).unwrap_or(HashMap::new());
-
-            for (key, values) in &headers {
-                response_builder.header(key.as_str(), values.as_str());
+        let mut pending = progress::pending(Kind::Fetch);
+        let callback = move |response: Response<Restorable>| {
+            pending.take();
+            if let Err(ref reason) = *response.body() {
+                let message = format!("{} failed: {}", target, reason);
+                errors::report(ErrorReport::new(ErrorKind::Service, "fetch", message)
+                    .tag("status", response.status().as_str()));
             }
-
-            // Deserialize and wrap response body into a Restorable object.
-            let data = if success { Ok(body) } else { Err(body) };
-            let out = OUT::from(data);
-            let response = response_builder.body(out).unwrap();
-
-            let msg = converter(response);
+            let msg = converter(response.map(OUT::from));
             tx.send(msg);
         };
-
-        let handle = js! {
-            var data = {
-                method: @{parts.method.as_str()},
-                body: @{body.into()},
-                headers: @{header_map},
-            };
-            var request = new Request(@{uri}, data);
-            var callback = @{callback};
-            var handle = {
-                interrupt: false,
-                callback,
-            };
-            fetch(request).then(function(response) {
-                response.text().then(function(data) {
-                    if (handle.interrupted != true) {
-                        callback(true, response, data);
-                        callback.drop();
-                    }
-                }).catch(function(err) {
-                    if (handle.interrupted != true) {
-                        callback(false, response, data);
-                        callback.drop();
-                    }
-                });
-            });
-            return handle;
-        };
-        FetchHandle(Some(handle))
+        FetchHandle(self.backend.fetch(request, Box::new(callback)))
     }
 }
 
 impl Task for FetchHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_active()
+    }
+
     fn cancel(&mut self) {
-        // Fetch API doesn't support request cancelling
-        // and we should use this workaround with a flag.
-        // In fact, request not canceled, but callback won't be called.
-        let handle = self.0.take().expect("tried to cancel request fetching twice");
-        js! {  @(no_return)
-            var handle = @{handle};
-            handle.interrupted = true;
-            handle.callback.drop();
+        self.0.cancel();
+    }
+}
+
+impl Drop for FetchHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
         }
     }
 }
@@ -0,0 +1,499 @@
+//! This module contains a service to open and save local files with the
+//! File System Access API, so an editor keeps handles of files and writes
+//! them back in place:
+//!
+//! ```rust,ignore
+//! let options = PickerOptions::new().accept("Markdown", "text/markdown", &[".md"]);
+//! let task = self.files.open(&options, Msg::Opened);
+//!
+//! Msg::Opened(Ok(handles)) => self.task = self.files.read(&handles[0], Msg::Loaded),
+//! Msg::Save => self.task = self.files.write(&self.handle, self.text.clone(), Msg::Saved),
+//! ```
+//!
+//! The API exists in Chromium based browsers only, check `is_supported()` to
+//! offer a download and an `<input type="file">` in others. Pickers need a user
+//! gesture, so they're opened from messages of click events.
+
+use std::fmt;
+use serde_json::{self, Map, Value as Json};
+use stdweb::Value;
+use stdweb::unstable::TryFrom;
+use format::{Restorable, Storable};
+use html::AppSender;
+use super::promise::{self, PromiseHandle};
+
+/// An error of an operation with files.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileSystemError {
+    /// The browser doesn't have the File System Access API.
+    Unsupported,
+    /// A user closed a picker without choosing anything.
+    Aborted,
+    /// A permission to access a file wasn't granted.
+    Denied,
+    /// A file or a directory doesn't exist.
+    NotFound,
+    /// Another error with its message.
+    Failed(String),
+}
+
+impl fmt::Display for FileSystemError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FileSystemError::Unsupported => write!(f, "the file system access API isn't supported"),
+            FileSystemError::Aborted => write!(f, "a picker was closed"),
+            FileSystemError::Denied => write!(f, "an access to a file was denied"),
+            FileSystemError::NotFound => write!(f, "a file wasn't found"),
+            FileSystemError::Failed(ref reason) => write!(f, "file system error: {}", reason),
+        }
+    }
+}
+
+/// A mode of an access to a handle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Reading only.
+    Read,
+    /// Reading and writing.
+    ReadWrite,
+}
+
+/// A state of a permission to access a handle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    /// The access is allowed.
+    Granted,
+    /// The access was refused.
+    Denied,
+    /// A user will be asked with `request_permission`.
+    Prompt,
+}
+
+/// A handle of a file or of a directory.
+pub trait Handle {
+    /// Returns the name of the file or of the directory.
+    fn name(&self) -> String;
+
+    #[doc(hidden)]
+    fn raw(&self) -> &Value;
+}
+
+/// A handle of a file which was picked by a user or found in a directory.
+#[derive(Debug, Clone)]
+pub struct FileHandle(Value);
+
+/// A handle of a directory which was picked by a user or found in a directory.
+#[derive(Debug, Clone)]
+pub struct DirectoryHandle(Value);
+
+/// An entry of a directory.
+#[derive(Debug, Clone)]
+pub enum Entry {
+    /// A file.
+    File(FileHandle),
+    /// A nested directory.
+    Directory(DirectoryHandle),
+}
+
+/// Options of open, save and directory pickers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PickerOptions {
+    /// Types of files which could be chosen: descriptions, MIME types and extensions.
+    pub types: Vec<(String, String, Vec<String>)>,
+    /// Lets a user choose files of other types too.
+    pub any_type: bool,
+    /// Lets a user choose a few files in an open picker.
+    pub multiple: bool,
+    /// A name which a save picker suggests.
+    pub suggested_name: Option<String>,
+    /// A well-known directory to start in, like `documents` or `downloads`.
+    pub start_in: Option<String>,
+    /// An id of the picker, a browser remembers the last directory of every id.
+    pub id: Option<String>,
+}
+
+impl PickerOptions {
+    /// Creates options which accept files of any type.
+    pub fn new() -> Self {
+        PickerOptions {
+            any_type: true,
+            ..PickerOptions::default()
+        }
+    }
+
+    /// Accepts files of the MIME type and the extensions, like `.md`,
+    /// and hides files of other types.
+    pub fn accept(mut self, description: &str, mime: &str, extensions: &[&str]) -> Self {
+        let extensions = extensions.iter().map(|extension| extension.to_string()).collect();
+        self.types.push((description.to_owned(), mime.to_owned(), extensions));
+        self.any_type = false;
+        self
+    }
+
+    /// Lets a user choose a few files.
+    pub fn multiple(mut self) -> Self {
+        self.multiple = true;
+        self
+    }
+
+    /// Suggests the name of a saved file.
+    pub fn suggested_name(mut self, name: &str) -> Self {
+        self.suggested_name = Some(name.to_owned());
+        self
+    }
+
+    /// Starts in the well-known directory.
+    pub fn start_in(mut self, directory: &str) -> Self {
+        self.start_in = Some(directory.to_owned());
+        self
+    }
+
+    /// Sets an id of the picker.
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_owned());
+        self
+    }
+
+    fn to_json(&self) -> String {
+        let mut options = Map::new();
+        let types = self.types.iter().map(|(description, mime, extensions)| {
+            let mut accept = Map::new();
+            accept.insert(mime.clone(), Json::from(extensions.clone()));
+            let mut kind = Map::new();
+            kind.insert("description".into(), Json::from(description.clone()));
+            kind.insert("accept".into(), Json::Object(accept));
+            Json::Object(kind)
+        }).collect::<Vec<_>>();
+        options.insert("types".into(), Json::Array(types));
+        options.insert("excludeAcceptAllOption".into(), Json::Bool(!self.any_type));
+        options.insert("multiple".into(), Json::Bool(self.multiple));
+        let names = [("suggestedName", &self.suggested_name), ("startIn", &self.start_in), ("id", &self.id)];
+        for &(name, value) in &names {
+            if let Some(value) = value {
+                options.insert(name.into(), Json::from(value.clone()));
+            }
+        }
+        serde_json::to_string(&options).expect("can't serialize options of a picker")
+    }
+}
+
+/// A stream which writes a file in parts. Written data is applied to the file
+/// when the writer is closed with `FileSystemService::close`, a dropped writer
+/// leaves the file unchanged.
+pub struct FileWriter(Option<Value>);
+
+impl FileWriter {
+    /// Appends the data to the file. Writes are done in order.
+    pub fn write<IN: Into<Storable>>(&mut self, data: IN) {
+        let data = data.into().unwrap_or_default();
+        let writer = self.0.as_ref().expect("tried to write to a closed writer");
+        js! { @(no_return)
+            var writer = @{writer};
+            var data = @{data};
+            writer.queue = writer.queue.then(function() {
+                return writer.stream.write(data);
+            });
+        }
+    }
+}
+
+impl Drop for FileWriter {
+    fn drop(&mut self) {
+        if let Some(writer) = self.0.take() {
+            js! { @(no_return)
+                var writer = @{writer};
+                writer.queue.then(function() {
+                    return writer.stream.abort();
+                }).catch(function() {});
+            }
+        }
+    }
+}
+
+/// Returns `true` if the browser has the File System Access API.
+pub fn is_supported() -> bool {
+    let supported: Value = js! {
+        return typeof window.showOpenFilePicker === "function";
+    };
+    supported == Value::Bool(true)
+}
+
+/// A service to read and write local files.
+pub struct FileSystemService<MSG> {
+    sender: AppSender<MSG>,
+}
+
+impl<MSG: 'static> FileSystemService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self { sender }
+    }
+
+    /// Shows an open picker and sends handles of chosen files.
+    pub fn open<F>(&mut self, options: &PickerOptions, converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<Vec<FileHandle>, FileSystemError>) -> MSG + 'static,
+    {
+        let promise = js! {
+            if (typeof window.showOpenFilePicker !== "function") {
+                return Promise.reject(new DOMException("", "NotSupportedError"));
+            }
+            return window.showOpenFilePicker(JSON.parse(@{options.to_json()}));
+        };
+        self.spawn(promise, |handles| {
+            let handles = Vec::<Value>::try_from(handles).ok()?;
+            Some(handles.into_iter().map(FileHandle).collect())
+        }, converter)
+    }
+
+    /// Shows a save picker and sends a handle of the chosen file, which could be new.
+    pub fn save<F>(&mut self, options: &PickerOptions, converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<FileHandle, FileSystemError>) -> MSG + 'static,
+    {
+        let promise = js! {
+            if (typeof window.showSaveFilePicker !== "function") {
+                return Promise.reject(new DOMException("", "NotSupportedError"));
+            }
+            return window.showSaveFilePicker(JSON.parse(@{options.to_json()}));
+        };
+        self.spawn(promise, |handle| Some(FileHandle(handle)), converter)
+    }
+
+    /// Shows a directory picker and sends a handle of the chosen directory.
+    /// The `mode` is asked at once, so writing doesn't need another prompt.
+    pub fn directory<F>(&mut self, options: &PickerOptions, mode: Mode, converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<DirectoryHandle, FileSystemError>) -> MSG + 'static,
+    {
+        let promise = js! {
+            if (typeof window.showDirectoryPicker !== "function") {
+                return Promise.reject(new DOMException("", "NotSupportedError"));
+            }
+            var options = JSON.parse(@{options.to_json()});
+            options.mode = @{mode_name(mode)};
+            return window.showDirectoryPicker(options);
+        };
+        self.spawn(promise, |handle| Some(DirectoryHandle(handle)), converter)
+    }
+
+    /// Reads the contents of the file as text and converts them with the format layer.
+    pub fn read<OUT, F>(&mut self, file: &FileHandle, converter: F) -> PromiseHandle
+    where
+        OUT: From<Restorable> + 'static,
+        F: Fn(Result<OUT, FileSystemError>) -> MSG + 'static,
+    {
+        let promise = js! {
+            return @{&file.0}.getFile().then(function(file) {
+                return file.text();
+            });
+        };
+        self.spawn(promise, |text| text.into_string().map(|text| OUT::from(Ok(text))), converter)
+    }
+
+    /// Replaces the contents of the file with the data. A browser asks for
+    /// a permission to write if it wasn't granted yet.
+    pub fn write<IN, F>(&mut self, file: &FileHandle, data: IN, converter: F) -> PromiseHandle
+    where
+        IN: Into<Storable>,
+        F: Fn(Result<(), FileSystemError>) -> MSG + 'static,
+    {
+        let data = data.into().unwrap_or_default();
+        let promise = js! {
+            var data = @{data};
+            return @{&file.0}.createWritable().then(function(stream) {
+                return stream.write(data).then(function() {
+                    return stream.close();
+                }, function(error) {
+                    stream.abort();
+                    throw error;
+                });
+            });
+        };
+        self.spawn(promise, |_| Some(()), converter)
+    }
+
+    /// Opens a stream to write the file in parts, for big files which aren't
+    /// kept in memory. It starts with the current contents if `keep` is `true`
+    /// and with an empty file otherwise.
+    pub fn writer<F>(&mut self, file: &FileHandle, keep: bool, converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<FileWriter, FileSystemError>) -> MSG + 'static,
+    {
+        let promise = js! {
+            return @{&file.0}.createWritable({ keepExistingData: @{keep} }).then(function(stream) {
+                return { stream: stream, queue: Promise.resolve() };
+            });
+        };
+        self.spawn(promise, |writer| Some(FileWriter(Some(writer))), converter)
+    }
+
+    /// Waits for writes of the writer, applies them to the file and sends a message.
+    pub fn close<F>(&mut self, mut writer: FileWriter, converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<(), FileSystemError>) -> MSG + 'static,
+    {
+        let writer = writer.0.take().expect("tried to close a closed writer");
+        let promise = js! {
+            var writer = @{writer};
+            return writer.queue.then(function() {
+                return writer.stream.close();
+            }, function(error) {
+                writer.stream.abort();
+                throw error;
+            });
+        };
+        self.spawn(promise, |_| Some(()), converter)
+    }
+
+    /// Sends entries of the directory.
+    pub fn entries<F>(&mut self, directory: &DirectoryHandle, converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<Vec<Entry>, FileSystemError>) -> MSG + 'static,
+    {
+        let promise = js! {
+            var iterator = @{&directory.0}.values();
+            var entries = [];
+            function next() {
+                return iterator.next().then(function(step) {
+                    if (step.done) {
+                        return entries;
+                    }
+                    entries.push(step.value);
+                    return next();
+                });
+            }
+            return next();
+        };
+        self.spawn(promise, |entries| {
+            let entries = Vec::<Value>::try_from(entries).ok()?;
+            let entries = entries.into_iter().map(|entry| {
+                let directory: Value = js! { return @{&entry}.kind === "directory"; };
+                if directory == Value::Bool(true) {
+                    Entry::Directory(DirectoryHandle(entry))
+                } else {
+                    Entry::File(FileHandle(entry))
+                }
+            }).collect();
+            Some(entries)
+        }, converter)
+    }
+
+    /// Sends a handle of the file of the directory, creates it if `create` is `true`.
+    pub fn file<F>(&mut self, directory: &DirectoryHandle, name: &str, create: bool, converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<FileHandle, FileSystemError>) -> MSG + 'static,
+    {
+        let promise = js! {
+            return @{&directory.0}.getFileHandle(@{name}, { create: @{create} });
+        };
+        self.spawn(promise, |handle| Some(FileHandle(handle)), converter)
+    }
+
+    /// Sends a handle of the nested directory, creates it if `create` is `true`.
+    pub fn subdirectory<F>(&mut self, directory: &DirectoryHandle, name: &str, create: bool, converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<DirectoryHandle, FileSystemError>) -> MSG + 'static,
+    {
+        let promise = js! {
+            return @{&directory.0}.getDirectoryHandle(@{name}, { create: @{create} });
+        };
+        self.spawn(promise, |handle| Some(DirectoryHandle(handle)), converter)
+    }
+
+    /// Sends the state of a permission to access the handle without asking a user.
+    pub fn permission<H, F>(&mut self, handle: &H, mode: Mode, converter: F) -> PromiseHandle
+    where
+        H: Handle,
+        F: Fn(Result<Permission, FileSystemError>) -> MSG + 'static,
+    {
+        let promise = js! {
+            return @{handle.raw()}.queryPermission({ mode: @{mode_name(mode)} });
+        };
+        self.spawn(promise, permission_of, converter)
+    }
+
+    /// Asks a user again for a permission to access the handle, like a handle
+    /// of a recent file after a reload. It needs a user gesture.
+    pub fn request_permission<H, F>(&mut self, handle: &H, mode: Mode, converter: F) -> PromiseHandle
+    where
+        H: Handle,
+        F: Fn(Result<Permission, FileSystemError>) -> MSG + 'static,
+    {
+        let promise = js! {
+            return @{handle.raw()}.requestPermission({ mode: @{mode_name(mode)} });
+        };
+        self.spawn(promise, permission_of, converter)
+    }
+
+    fn spawn<T, C, F>(&mut self, promise: Value, convert: C, converter: F) -> PromiseHandle
+    where
+        C: Fn(Value) -> Option<T> + 'static,
+        F: Fn(Result<T, FileSystemError>) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        promise::settle(promise, move |resolved, value| {
+            let result = if resolved {
+                convert(value).ok_or_else(|| FileSystemError::Failed("unexpected value".into()))
+            } else {
+                Err(error_of(value))
+            };
+            tx.send(converter(result));
+        })
+    }
+}
+
+impl Handle for FileHandle {
+    fn name(&self) -> String {
+        name_of(&self.0)
+    }
+
+    fn raw(&self) -> &Value {
+        &self.0
+    }
+}
+
+impl Handle for DirectoryHandle {
+    fn name(&self) -> String {
+        name_of(&self.0)
+    }
+
+    fn raw(&self) -> &Value {
+        &self.0
+    }
+}
+
+fn name_of(handle: &Value) -> String {
+    let name: Value = js! { return @{handle}.name; };
+    name.into_string().unwrap_or_default()
+}
+
+fn mode_name(mode: Mode) -> &'static str {
+    match mode {
+        Mode::Read => "read",
+        Mode::ReadWrite => "readwrite",
+    }
+}
+
+fn permission_of(state: Value) -> Option<Permission> {
+    match state.into_string()?.as_str() {
+        "granted" => Some(Permission::Granted),
+        "denied" => Some(Permission::Denied),
+        "prompt" => Some(Permission::Prompt),
+        _ => None,
+    }
+}
+
+fn error_of(reason: Value) -> FileSystemError {
+    let name: Value = js! {
+        var reason = @{&reason};
+        return reason && reason.name || "";
+    };
+    match name.into_string().unwrap_or_default().as_str() {
+        "NotSupportedError" => FileSystemError::Unsupported,
+        "AbortError" => FileSystemError::Aborted,
+        "NotAllowedError" | "SecurityError" => FileSystemError::Denied,
+        "NotFoundError" => FileSystemError::NotFound,
+        _ => FileSystemError::Failed(promise::reason(reason)),
+    }
+}
@@ -0,0 +1,190 @@
+//! This module contains the implementation of a service to
+//! navigate with the [History API](https://developer.mozilla.org/en-US/docs/Web/API/History).
+//!
+//! Locations are kept in paths of URLs by default. It needs a server which
+//! responds with the app to every path. Use the hash mode if a server
+//! can't be configured, locations look like `/#/posts/1?tab=2` then:
+//!
+//! ```rust,ignore
+//! history::set_mode(HistoryMode::Hash);
+//! ```
+
+use std::cell::Cell;
+use stdweb::Value;
+use html::AppSender;
+use super::Task;
+
+/// A way to keep locations of an app in URLs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HistoryMode {
+    /// A location is the path of a URL: `/posts/1`.
+    Path,
+    /// A location is in the hash of a URL: `/#/posts/1`.
+    Hash,
+}
+
+thread_local! {
+    static MODE: Cell<HistoryMode> = Cell::new(HistoryMode::Path);
+}
+
+/// Sets the mode for all services, routers and links. Call it before
+/// an app is mounted.
+pub fn set_mode(mode: HistoryMode) {
+    MODE.with(|current| current.set(mode));
+}
+
+/// Returns the current mode.
+pub fn mode() -> HistoryMode {
+    MODE.with(|current| current.get())
+}
+
+/// Returns a URL of the location for the `href` attribute of an anchor.
+pub fn href(location: &str) -> String {
+    match mode() {
+        HistoryMode::Path => location.to_owned(),
+        HistoryMode::Hash => format!("#{}", location),
+    }
+}
+
+/// A handle to stop listening of navigation.
+pub struct HistoryHandle(Option<Value>);
+
+/// A service to change a location of a page without reloading
+/// and to listen of changes of the location.
+pub struct HistoryService<MSG> {
+    sender: AppSender<MSG>,
+}
+
+impl<MSG: 'static> HistoryService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self { sender }
+    }
+
+    /// Sends a message from the `converter` with a new location (path, query and hash)
+    /// every time it's changed by the service or by buttons of a browser.
+    pub fn listen<F>(&mut self, converter: F) -> HistoryHandle
+    where
+        F: Fn(String) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let callback = move || {
+            let msg = converter(location());
+            tx.send(msg);
+        };
+        let handle = js! {
+            var callback = @{callback};
+            var listener = function() {
+                callback();
+            };
+            window.addEventListener("popstate", listener);
+            return {
+                listener,
+                callback,
+            };
+        };
+        HistoryHandle(Some(handle))
+    }
+
+    /// Returns the current location: path, query and hash.
+    pub fn location(&self) -> String {
+        location()
+    }
+
+    /// Adds an entry to the history and notifies listeners.
+    pub fn push(&mut self, location: &str) {
+        push_state(location);
+    }
+
+    /// Replaces the current entry of the history and notifies listeners.
+    pub fn replace(&mut self, location: &str) {
+        replace_state(location);
+    }
+
+    /// Goes to the previous entry of the history.
+    pub fn back(&mut self) {
+        js! { @(no_return) history.back(); }
+    }
+
+    /// Goes to the next entry of the history.
+    pub fn forward(&mut self) {
+        js! { @(no_return) history.forward(); }
+    }
+}
+
+impl Task for HistoryHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel history listener twice");
+        js! { @(no_return)
+            var handle = @{handle};
+            window.removeEventListener("popstate", handle.listener);
+            handle.callback.drop();
+        }
+    }
+}
+
+impl Drop for HistoryHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+/// Returns the current location: path, query and hash.
+pub(crate) fn location() -> String {
+    let location: Value = match mode() {
+        HistoryMode::Path => js! {
+            return location.pathname + location.search + location.hash;
+        },
+        HistoryMode::Hash => js! {
+            return location.hash.substring(1) || "/";
+        },
+    };
+    location.into_string().unwrap_or_default()
+}
+
+/// Returns a key of the current entry of the history. The key is unique
+/// for every entry added by the service and it survives a reload of a page.
+pub(crate) fn entry_key() -> String {
+    let key: Value = js! {
+        var state = history.state;
+        if (state && state.yew) {
+            return state.yew;
+        }
+        var key = Date.now() + "-" + Math.random();
+        history.replaceState({ yew: key }, "");
+        return key;
+    };
+    key.into_string().unwrap_or_default()
+}
+
+/// Adds an entry to the history. Browsers don't fire `popstate` for
+/// `pushState`, so the event is dispatched to notify listeners.
+pub(crate) fn push_state(location: &str) {
+    js! { @(no_return)
+        var key = Date.now() + "-" + Math.random();
+        history.pushState({ yew: key }, "", @{href(location)});
+        window.dispatchEvent(new PopStateEvent("popstate", { state: history.state }));
+    }
+}
+
+/// Replaces the current entry of the history and notifies listeners.
+/// The entry keeps its key.
+pub(crate) fn replace_state(location: &str) {
+    js! { @(no_return)
+        history.replaceState(history.state, "", @{href(location)});
+        window.dispatchEvent(new PopStateEvent("popstate", { state: history.state }));
+    }
+}
+
+/// Replaces the current entry of the history without notifying listeners.
+pub(crate) fn restore(location: &str) {
+    js! { @(no_return)
+        history.replaceState(history.state, "", @{href(location)});
+    }
+}
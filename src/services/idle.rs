@@ -0,0 +1,158 @@
+//! This module contains a service which detects idle users, like to log out
+//! after a while or to show a user as away:
+//!
+//! ```rust,ignore
+//! let thresholds = [Duration::from_secs(60), Duration::from_secs(15 * 60)];
+//! let handle = self.idle.watch(&thresholds, Msg::Idle);
+//!
+//! Msg::Idle(IdleEvent::Idle(after)) if after >= Duration::from_secs(15 * 60) => self.auth.logout(),
+//! Msg::Idle(IdleEvent::Idle(_)) => self.presence = Presence::Away,
+//! Msg::Idle(IdleEvent::Active) => self.presence = Presence::Online,
+//! ```
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+use std::time::Duration;
+use html::AppSender;
+use super::Task;
+use super::backend::{self, ActivityBackend, TimerBackend};
+
+/// A transition of the activity of a user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdleEvent {
+    /// A user didn't do anything for the threshold.
+    Idle(Duration),
+    /// A user returned after an `Idle` event.
+    Active,
+}
+
+struct Watch {
+    thresholds: Vec<Duration>,
+    passed: usize,
+    timer: Option<Box<Task>>,
+    timers: Rc<TimerBackend>,
+    emit: Rc<Fn(IdleEvent)>,
+}
+
+impl Watch {
+    fn schedule(watch: &Rc<RefCell<Watch>>) {
+        let mut this = watch.borrow_mut();
+        if let Some(mut timer) = this.timer.take() {
+            if timer.is_active() {
+                timer.cancel();
+            }
+        }
+        let next = match this.thresholds.get(this.passed) {
+            Some(next) => *next,
+            None => return,
+        };
+        let since = if this.passed == 0 { Duration::from_secs(0) } else { this.thresholds[this.passed - 1] };
+        let weak: Weak<RefCell<Watch>> = Rc::downgrade(watch);
+        let callback = move || {
+            if let Some(watch) = weak.upgrade() {
+                let emit = {
+                    let mut this = watch.borrow_mut();
+                    this.timer = None;
+                    this.passed += 1;
+                    this.emit.clone()
+                };
+                emit(IdleEvent::Idle(next));
+                Watch::schedule(&watch);
+            }
+        };
+        this.timer = Some(this.timers.timeout(next - since, Box::new(callback)));
+    }
+
+    fn act(watch: &Rc<RefCell<Watch>>) {
+        let (returned, emit) = {
+            let mut this = watch.borrow_mut();
+            let returned = this.passed > 0;
+            this.passed = 0;
+            (returned, this.emit.clone())
+        };
+        if returned {
+            emit(IdleEvent::Active);
+        }
+        Watch::schedule(watch);
+    }
+}
+
+struct Watching {
+    watch: Rc<RefCell<Watch>>,
+    listener: Box<Task>,
+}
+
+/// A handle of a watch of activity. Nothing is sent after it's canceled.
+pub struct IdleHandle(Option<Watching>);
+
+impl Task for IdleHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let mut watching = self.0.take().expect("tried to cancel an idle watch twice");
+        watching.listener.cancel();
+        let timer = watching.watch.borrow_mut().timer.take();
+        if let Some(mut timer) = timer {
+            if timer.is_active() {
+                timer.cancel();
+            }
+        }
+    }
+}
+
+impl Drop for IdleHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+/// A service to detect when a user is idle.
+pub struct IdleService<MSG> {
+    sender: AppSender<MSG>,
+    activity: Rc<ActivityBackend>,
+    timers: Rc<TimerBackend>,
+}
+
+impl<MSG: 'static> IdleService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self::with_backends(sender, backend::activity(), backend::timers())
+    }
+
+    /// Creates a new service instance which uses the backends.
+    pub fn with_backends(sender: AppSender<MSG>, activity: Rc<ActivityBackend>, timers: Rc<TimerBackend>) -> Self {
+        Self { sender, activity, timers }
+    }
+
+    /// Sends `Idle` with a threshold every time a user didn't move a pointer,
+    /// press a key, touch the screen or scroll for that long, and `Active`
+    /// when the user returns. Time is counted from the call.
+    pub fn watch<F>(&mut self, thresholds: &[Duration], converter: F) -> IdleHandle
+    where
+        F: Fn(IdleEvent) -> MSG + 'static,
+    {
+        let mut thresholds = thresholds.to_vec();
+        thresholds.sort();
+        thresholds.dedup();
+        let tx = self.sender.clone();
+        let watch = Rc::new(RefCell::new(Watch {
+            thresholds,
+            passed: 0,
+            timer: None,
+            timers: self.timers.clone(),
+            emit: Rc::new(move |event| tx.clone().send(converter(event))),
+        }));
+        Watch::schedule(&watch);
+        let weak = Rc::downgrade(&watch);
+        let listener = self.activity.listen(Box::new(move || {
+            if let Some(watch) = weak.upgrade() {
+                Watch::act(&watch);
+            }
+        }));
+        IdleHandle(Some(Watching { watch, listener }))
+    }
+}
@@ -0,0 +1,371 @@
+//! This module contains a service to make thumbnails of images which users
+//! choose for uploads. Images are decoded with `createImageBitmap` and resized
+//! with an `OffscreenCanvas` in a shared worker, so big photos don't block
+//! rendering:
+//!
+//! ```rust,ignore
+//! html! { <input type="file", accept="image/*", multiple=true, ref=&self.input,
+//!                onchange=|data| Msg::Chosen(data.files.len()),/> }
+//!
+//! let sources = (0..count).map(|index| ImageSource::Input(self.input.clone(), index)).collect();
+//! let task = self.images.thumbnails(sources, ThumbnailOptions::fit(200, 200), Msg::Image);
+//!
+//! Msg::Image(ImageEvent::Progress(done, total)) => self.progress = (done, total),
+//! Msg::Image(ImageEvent::Ready(index, Ok(thumbnail))) => self.previews[index] = Some(thumbnail),
+//! ```
+//!
+//! Browsers without workers with canvases resize on the main thread.
+
+use std::cell::Cell;
+use std::fmt;
+use std::rc::Rc;
+use stdweb::Value;
+use stdweb::unstable::TryInto;
+use html::{AppSender, ClipboardFile};
+use virtual_dom::NodeRef;
+use super::Task;
+use super::file_system::{FileHandle, Handle};
+use super::promise::{self, PromiseHandle};
+
+/// An image to make a thumbnail of.
+#[derive(Debug, Clone)]
+pub enum ImageSource {
+    /// A file of an `<input type="file">` element with the index of the file.
+    Input(NodeRef, usize),
+    /// A file of the file system service.
+    File(FileHandle),
+    /// An image which is fetched from the url.
+    Url(String),
+    /// An image which was pasted, see `PasteData`.
+    Clipboard(ClipboardFile),
+}
+
+/// Options of thumbnails.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThumbnailOptions {
+    /// The biggest width of a thumbnail.
+    pub max_width: u32,
+    /// The biggest height of a thumbnail.
+    pub max_height: u32,
+    /// The MIME type of thumbnails, like `image/jpeg` or `image/webp`.
+    pub mime: String,
+    /// The quality of lossy types from 0 to 1.
+    pub quality: f64,
+    /// Returns `data:` urls instead of object urls, so thumbnails are uploaded
+    /// as text or kept in a storage.
+    pub data_url: bool,
+}
+
+impl ThumbnailOptions {
+    /// Creates options of JPEG thumbnails which fit into the size and keep the aspect ratio.
+    pub fn fit(max_width: u32, max_height: u32) -> Self {
+        ThumbnailOptions {
+            max_width,
+            max_height,
+            mime: "image/jpeg".into(),
+            quality: 0.85,
+            data_url: false,
+        }
+    }
+
+    /// Sets the type and the quality of thumbnails.
+    pub fn encode(mut self, mime: &str, quality: f64) -> Self {
+        self.mime = mime.to_owned();
+        self.quality = quality;
+        self
+    }
+
+    /// Returns `data:` urls.
+    pub fn data_url(mut self) -> Self {
+        self.data_url = true;
+        self
+    }
+}
+
+/// A resized image.
+#[derive(Debug, PartialEq)]
+pub struct Thumbnail {
+    /// An object url or a `data:` url to use in `src` of an `<img>`.
+    /// An object url is revoked when the thumbnail is dropped.
+    pub url: String,
+    /// The width of the thumbnail.
+    pub width: u32,
+    /// The height of the thumbnail.
+    pub height: u32,
+    /// The width of the original image.
+    pub original_width: u32,
+    /// The height of the original image.
+    pub original_height: u32,
+    /// The size of the encoded thumbnail in bytes.
+    pub size: u64,
+}
+
+impl Drop for Thumbnail {
+    fn drop(&mut self) {
+        if self.url.starts_with("blob:") {
+            js! { @(no_return)
+                URL.revokeObjectURL(@{&self.url});
+            }
+        }
+    }
+}
+
+/// An error of an image.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImageError {
+    /// There is no file in the input or the input isn't rendered.
+    Missing,
+    /// The file isn't an image which the browser decodes.
+    Undecodable(String),
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ImageError::Missing => write!(f, "there is no image"),
+            ImageError::Undecodable(ref reason) => write!(f, "can't decode an image: {}", reason),
+        }
+    }
+}
+
+/// Events of making thumbnails.
+#[derive(Debug, PartialEq)]
+pub enum ImageEvent {
+    /// A thumbnail of the image with the index of its source was made or failed.
+    Ready(usize, Result<Thumbnail, ImageError>),
+    /// How many images of all were handled, it's sent after every `Ready`.
+    Progress(usize, usize),
+}
+
+/// A handle of thumbnails which are being made. Images aren't stopped,
+/// but no messages are sent after it's canceled.
+pub struct ImageHandle(Vec<PromiseHandle>);
+
+/// A service to decode and resize images.
+pub struct ImageService<MSG> {
+    sender: AppSender<MSG>,
+}
+
+impl<MSG: 'static> ImageService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self { sender }
+    }
+
+    /// Makes thumbnails of the images and sends an event for every image
+    /// and for progress. Thumbnails are ready in any order.
+    pub fn thumbnails<F>(&mut self, sources: Vec<ImageSource>, options: ThumbnailOptions, converter: F) -> ImageHandle
+    where
+        F: Fn(ImageEvent) -> MSG + 'static,
+    {
+        let total = sources.len();
+        let done = Rc::new(Cell::new(0));
+        let converter = Rc::new(converter);
+        let handles = sources.into_iter().enumerate().map(|(index, source)| {
+            let promise = match blob_of(&source) {
+                Some(blob) => thumbnail(blob, &options),
+                None => js! { return Promise.reject(null); },
+            };
+            let mut tx = self.sender.clone();
+            let (done, converter) = (done.clone(), converter.clone());
+            promise::settle(promise, move |resolved, value| {
+                let result = if resolved {
+                    Ok(thumbnail_of(&value))
+                } else if value == Value::Null {
+                    Err(ImageError::Missing)
+                } else {
+                    Err(ImageError::Undecodable(promise::reason(value)))
+                };
+                done.set(done.get() + 1);
+                tx.send(converter(ImageEvent::Ready(index, result)));
+                tx.send(converter(ImageEvent::Progress(done.get(), total)));
+            })
+        }).collect();
+        ImageHandle(handles)
+    }
+}
+
+/// Returns a promise of a blob of the source or `None` if there's no file.
+fn blob_of(source: &ImageSource) -> Option<Value> {
+    match *source {
+        ImageSource::Input(ref input, index) => {
+            let input = input.get()?;
+            let file: Value = js! {
+                var files = @{input}.files || [];
+                return files[@{index as u32}] || null;
+            };
+            if file == Value::Null {
+                None
+            } else {
+                Some(js! { return Promise.resolve(@{file}); })
+            }
+        }
+        ImageSource::File(ref handle) => Some(js! { return @{handle.raw()}.getFile(); }),
+        ImageSource::Clipboard(ref file) => {
+            let blob = file.raw()?;
+            Some(js! { return Promise.resolve(@{blob}); })
+        }
+        ImageSource::Url(ref url) => Some(js! {
+            return fetch(@{url}).then(function(response) {
+                if (!response.ok) {
+                    throw new Error("status " + response.status);
+                }
+                return response.blob();
+            });
+        }),
+    }
+}
+
+/// Starts the shared worker which resizes images if the browser has workers with canvases.
+fn start_worker() {
+    js! { @(no_return)
+        var images = window.__yew_images || (window.__yew_images = { id: 0, jobs: {} });
+        if (images.worker !== undefined) {
+            return;
+        }
+        images.worker = null;
+        images.resize = function(bitmap, canvas, job) {
+            var scale = Math.min(1, job.maxWidth / bitmap.width, job.maxHeight / bitmap.height);
+            canvas.width = Math.max(1, Math.round(bitmap.width * scale));
+            canvas.height = Math.max(1, Math.round(bitmap.height * scale));
+            canvas.getContext("2d").drawImage(bitmap, 0, 0, canvas.width, canvas.height);
+            return { width: canvas.width, height: canvas.height, originalWidth: bitmap.width, originalHeight: bitmap.height };
+        };
+        // The worker draws with the same function
+        if (typeof Worker === "function" && typeof OffscreenCanvas === "function") {
+            var source = "var resize = " + images.resize.toString() + ";" +
+                "self.onmessage = function(event) {" +
+                "  var job = event.data.job;" +
+                "  createImageBitmap(event.data.blob).then(function(bitmap) {" +
+                "    var canvas = new OffscreenCanvas(1, 1);" +
+                "    var size = resize(bitmap, canvas, job);" +
+                "    bitmap.close();" +
+                "    return canvas.convertToBlob({ type: job.type, quality: job.quality }).then(function(blob) {" +
+                "      size.blob = blob;" +
+                "      self.postMessage({ id: event.data.id, ok: true, value: size });" +
+                "    });" +
+                "  }).catch(function(error) {" +
+                "    self.postMessage({ id: event.data.id, ok: false, value: String(error && error.message || error) });" +
+                "  });" +
+                "};";
+            var url = URL.createObjectURL(new Blob([source], { type: "text/javascript" }));
+            images.worker = new Worker(url);
+            URL.revokeObjectURL(url);
+            images.worker.onmessage = function(event) {
+                var settle = images.jobs[event.data.id];
+                delete images.jobs[event.data.id];
+                settle(event.data.ok, event.data.value);
+            };
+        }
+    }
+}
+
+/// Resizes the image of the blob in the worker or on the main thread and
+/// returns a promise of a description of the thumbnail.
+fn thumbnail(blob: Value, options: &ThumbnailOptions) -> Value {
+    start_worker();
+    let resized: Value = js! {
+        var job = {
+            maxWidth: @{options.max_width},
+            maxHeight: @{options.max_height},
+            type: @{&options.mime},
+            quality: @{options.quality},
+        };
+        var images = window.__yew_images;
+        return Promise.resolve(@{blob}).then(function(blob) {
+            if (images.worker) {
+                return new Promise(function(resolve, reject) {
+                    var id = images.id++;
+                    images.jobs[id] = function(ok, value) {
+                        if (ok) {
+                            resolve(value);
+                        } else {
+                            reject(new Error(value));
+                        }
+                    };
+                    images.worker.postMessage({ id: id, blob: blob, job: job });
+                });
+            }
+            return createImageBitmap(blob).then(function(bitmap) {
+                var canvas = document.createElement("canvas");
+                var size = images.resize(bitmap, canvas, job);
+                bitmap.close();
+                return new Promise(function(resolve) {
+                    canvas.toBlob(resolve, job.type, job.quality);
+                }).then(function(blob) {
+                    size.blob = blob;
+                    return size;
+                });
+            });
+        });
+    };
+    publish(resized, options.data_url)
+}
+
+/// Turns a promise of a description with an encoded blob into a promise
+/// of a description with an url of the blob.
+pub(crate) fn publish(resized: Value, data_url: bool) -> Value {
+    let thumbnail: Value = js! {
+        var dataUrl = @{data_url};
+        return @{resized}.then(function(size) {
+            size.size = size.blob.size;
+            if (!dataUrl) {
+                size.url = URL.createObjectURL(size.blob);
+                delete size.blob;
+                return size;
+            }
+            return new Promise(function(resolve, reject) {
+                var reader = new FileReader();
+                reader.onload = function() {
+                    size.url = reader.result;
+                    delete size.blob;
+                    resolve(size);
+                };
+                reader.onerror = function() {
+                    reject(reader.error);
+                };
+                reader.readAsDataURL(size.blob);
+            });
+        });
+    };
+    thumbnail
+}
+
+/// Returns a thumbnail of a published description.
+pub(crate) fn thumbnail_of(value: &Value) -> Thumbnail {
+    Thumbnail {
+        url: js!( return @{value}.url; ).try_into().unwrap_or_default(),
+        width: dimension(js!( return @{value}.width; )),
+        height: dimension(js!( return @{value}.height; )),
+        original_width: dimension(js!( return @{value}.originalWidth; )),
+        original_height: dimension(js!( return @{value}.originalHeight; )),
+        size: u64::from(dimension(js!( return @{value}.size; ))),
+    }
+}
+
+fn dimension(value: Value) -> u32 {
+    let value: f64 = value.try_into().unwrap_or(0.0);
+    value as u32
+}
+
+impl Task for ImageHandle {
+    fn is_active(&self) -> bool {
+        self.0.iter().any(|handle| handle.is_active())
+    }
+
+    fn cancel(&mut self) {
+        for mut handle in self.0.drain(..) {
+            if handle.is_active() {
+                handle.cancel();
+            }
+        }
+    }
+}
+
+impl Drop for ImageHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
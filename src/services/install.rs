@@ -0,0 +1,109 @@
+//! This module contains a service of the install prompt of a Progressive
+//! Web App. A browser offers to install an app with `beforeinstallprompt`,
+//! the service keeps the offer, so the app shows its own install button
+//! and opens the prompt of the browser when it's pressed:
+//!
+//! ```rust,ignore
+//! self.installable = self.install.is_installable();
+//! self.handle = Some(self.install.listen(Msg::Install));
+//!
+//! Msg::Install(InstallEvent::Installable) => self.installable = true,
+//! Msg::Install(InstallEvent::Installed) => self.installable = false,
+//! Msg::InstallPressed => self.installable = !self.install.prompt(Msg::Chosen),
+//! ```
+//!
+//! A browser sends the offer once after the page is loaded, so a service
+//! should listen from the root component.
+
+use std::rc::Rc;
+use html::AppSender;
+use super::Task;
+use super::backend::{self, InstallBackend};
+
+/// A change of installability of the app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallEvent {
+    /// A browser offered to install the app, so it could show the prompt.
+    Installable,
+    /// The app was installed, by the prompt or by a menu of the browser.
+    Installed,
+}
+
+/// A choice of a user in the install prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallChoice {
+    /// A user installed the app.
+    Accepted,
+    /// A user closed the prompt.
+    Dismissed,
+}
+
+/// A handle of a listener of installability. It's canceled when dropped.
+pub struct InstallHandle(Option<Box<Task>>);
+
+impl Task for InstallHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let mut listener = self.0.take().expect("tried to cancel an install listener twice");
+        listener.cancel();
+    }
+}
+
+impl Drop for InstallHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+/// A service of the install prompt.
+pub struct InstallService<MSG> {
+    sender: AppSender<MSG>,
+    backend: Rc<InstallBackend>,
+}
+
+impl<MSG: 'static> InstallService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self::with_backend(sender, backend::install())
+    }
+
+    /// Creates a new service instance which uses the backend.
+    pub fn with_backend(sender: AppSender<MSG>, backend: Rc<InstallBackend>) -> Self {
+        Self { sender, backend }
+    }
+
+    /// Returns `true` if a browser offered to install the app and the prompt
+    /// wasn't shown yet.
+    pub fn is_installable(&self) -> bool {
+        self.backend.is_installable()
+    }
+
+    /// Sends a message when a browser offers to install the app and when
+    /// the app is installed.
+    pub fn listen<F>(&mut self, converter: F) -> InstallHandle
+    where
+        F: Fn(InstallEvent) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let callback = move |event| tx.send(converter(event));
+        InstallHandle(Some(self.backend.listen(Box::new(callback))))
+    }
+
+    /// Shows the prompt of the browser and sends a message with the choice
+    /// of a user. It's shown only after a user's gesture, like in a handler of
+    /// a click. Returns `false` if the app isn't installable, an offer is
+    /// used once and the next one comes only after the page is loaded again.
+    pub fn prompt<F>(&mut self, converter: F) -> bool
+    where
+        F: Fn(InstallChoice) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let callback = move |choice| tx.send(converter(choice));
+        self.backend.prompt(Box::new(callback))
+    }
+}
@@ -0,0 +1,105 @@
+//! This module contains interceptors which change requests of services
+//! before they're sent, like a header with a token of a session:
+//!
+//! ```rust,ignore
+//! struct Version;
+//!
+//! impl Interceptor for Version {
+//!     fn fetch(&self, request: &mut Request<Storable>) {
+//!         request.headers_mut().insert("X-Client", HeaderValue::from_static("web/2"));
+//!     }
+//! }
+//!
+//! let _handle = interceptor::add(Rc::new(Version));
+//! ```
+//!
+//! They're applied to `FetchService`, `WebSocketService`, queries and rooms
+//! in the order they were added. A service created with a backend is intercepted
+//! too, a backend gets requests which are ready to be sent.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use format::Storable;
+use super::Task;
+use super::fetch::Request;
+
+/// Changes requests of services.
+pub trait Interceptor {
+    /// Changes a request before it's fetched.
+    fn fetch(&self, _request: &mut Request<Storable>) {}
+
+    /// Returns another url for a websocket connection, like one with a token in the query.
+    fn connect(&self, _url: &str) -> Option<String> {
+        None
+    }
+}
+
+#[derive(Default)]
+struct Interceptors {
+    next_id: usize,
+    interceptors: Vec<(usize, Rc<Interceptor>)>,
+}
+
+thread_local! {
+    static INTERCEPTORS: RefCell<Interceptors> = RefCell::new(Interceptors::default());
+}
+
+/// A handle of an added interceptor. It's removed when the handle is canceled or dropped.
+pub struct InterceptorHandle(Option<usize>);
+
+/// Adds an interceptor for requests which are sent after that.
+pub fn add(interceptor: Rc<Interceptor>) -> InterceptorHandle {
+    INTERCEPTORS.with(|interceptors| {
+        let mut interceptors = interceptors.borrow_mut();
+        let id = interceptors.next_id;
+        interceptors.next_id += 1;
+        interceptors.interceptors.push((id, interceptor));
+        InterceptorHandle(Some(id))
+    })
+}
+
+fn all() -> Vec<Rc<Interceptor>> {
+    INTERCEPTORS.with(|interceptors| {
+        interceptors.borrow().interceptors.iter().map(|(_, interceptor)| interceptor.clone()).collect()
+    })
+}
+
+/// Applies interceptors to a request.
+pub(crate) fn fetch(mut request: Request<Storable>) -> Request<Storable> {
+    for interceptor in all() {
+        interceptor.fetch(&mut request);
+    }
+    request
+}
+
+/// Applies interceptors to a url of a websocket.
+pub(crate) fn connect(url: &str) -> String {
+    let mut url = url.to_owned();
+    for interceptor in all() {
+        if let Some(changed) = interceptor.connect(&url) {
+            url = changed;
+        }
+    }
+    url
+}
+
+impl Task for InterceptorHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let id = self.0.take().expect("tried to remove an interceptor twice");
+        INTERCEPTORS.with(|interceptors| {
+            interceptors.borrow_mut().interceptors.retain(|&(other, _)| other != id);
+        });
+    }
+}
+
+impl Drop for InterceptorHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! This module contains a service to call JS functions which were registered
+//! by names. Arguments and results are converted with `serde` through JSON,
+//! so components don't contain `js!` blocks:
+//!
+//! ```rust,ignore
+//! interop::register("clipboard.write", "function(text) { return navigator.clipboard.writeText(text); }");
+//! interop::register("chart.size", "function(id) { var r = document.getElementById(id).getBoundingClientRect(); return [r.width, r.height]; }");
+//!
+//! let (width, height): (f64, f64) = self.interop.call("chart.size", ("chart",))?;
+//! self.interop.call_async("clipboard.write", (&self.text,), |result: Result<(), String>| Msg::Copied(result.is_ok()));
+//! ```
+//!
+//! Arguments are a tuple, like `(id, &options)`, a single argument is `(value,)`
+//! and `()` calls without arguments. A result of `undefined` is `null`.
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json;
+use stdweb::Value;
+use stdweb::unstable::TryInto;
+use html::AppSender;
+use super::promise::{PromiseHandle, from_json, reason, settle};
+
+/// Registers a JS function by a name. The `source` is an expression which
+/// returns a function, like `function(a, b) { return a + b; }`. It replaces
+/// a function with the same name.
+///
+/// With the `csp` feature it panics, because the source is evaluated.
+pub fn register(name: &str, source: &str) {
+    #[cfg(feature = "csp")]
+    ::csp::forbid("interop::register", "interop::register_global");
+    js! { @(no_return)
+        var functions = window.__yew_interop || (window.__yew_interop = {});
+        var f = new Function("return (" + @{source} + ");")();
+        if (typeof f !== "function") {
+            throw new Error("the source of " + @{name} + " isn't a function");
+        }
+        functions[@{name}] = f;
+    }
+}
+
+/// Registers a global JS function by a name. The `path` is a path of
+/// properties from `window`, like `navigator.clipboard.writeText`, the
+/// function is called with `this` of its object. It doesn't evaluate code,
+/// so it works under a Content Security Policy.
+pub fn register_global(name: &str, path: &str) {
+    js! { @(no_return)
+        var functions = window.__yew_interop || (window.__yew_interop = {});
+        var owner = window;
+        var f = @{path}.split(".").reduce(function(object, key) {
+            owner = object;
+            return object == null ? undefined : object[key];
+        }, window);
+        if (typeof f !== "function") {
+            throw new Error(@{path} + " of " + @{name} + " isn't a function");
+        }
+        functions[@{name}] = f.bind(owner);
+    }
+}
+
+/// Returns `true` if a function with the name was registered.
+pub fn is_registered(name: &str) -> bool {
+    let registered: Value = js! {
+        var functions = window.__yew_interop || {};
+        return typeof functions[@{name}] === "function";
+    };
+    registered == Value::Bool(true)
+}
+
+/// Converts arguments to JSON or returns an error with the name of a function.
+fn arguments<A: Serialize>(name: &str, args: A) -> Result<String, String> {
+    serde_json::to_string(&args).map_err(|error| format!("can't convert arguments of {}: {}", name, error))
+}
+
+/// Converts a result from JSON. `undefined` comes as an empty string.
+fn result<R: DeserializeOwned>(name: &str, json: &str) -> Result<R, String> {
+    let json = if json.is_empty() { "null" } else { json };
+    serde_json::from_str(json).map_err(|error| format!("can't convert a result of {}: {}", name, error))
+}
+
+/// A handle of an asynchronous call. The call can't be stopped,
+/// but the callback won't be called after it's canceled.
+pub type InteropHandle = PromiseHandle;
+
+/// A service to call registered JS functions.
+pub struct InteropService<MSG> {
+    sender: AppSender<MSG>,
+}
+
+impl<MSG: 'static> InteropService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self { sender }
+    }
+
+    /// Calls the function and returns its result. Returns an error if the function
+    /// isn't registered, throws, returns a promise or the result has another type.
+    pub fn call<A, R>(&self, name: &str, args: A) -> Result<R, String>
+    where
+        A: Serialize,
+        R: DeserializeOwned,
+    {
+        let args = arguments(name, args)?;
+        let outcome: Value = js! {
+            var name = @{name};
+            var functions = window.__yew_interop || {};
+            if (typeof functions[name] !== "function") {
+                return { ok: false, value: "the function " + name + " isn't registered" };
+            }
+            var args = JSON.parse(@{args});
+            args = args === null ? [] : Array.isArray(args) ? args : [args];
+            try {
+                var value = functions[name].apply(null, args);
+                if (value && typeof value.then === "function") {
+                    return { ok: false, value: "the function " + name + " returned a promise, use call_async" };
+                }
+                return { ok: true, value: JSON.stringify(value) || "" };
+            } catch (error) {
+                return { ok: false, value: String(error) };
+            }
+        };
+        let ok: Value = js!( return @{&outcome}.ok; );
+        let value: String = js!( return @{&outcome}.value; ).try_into().unwrap_or_default();
+        if ok == Value::Bool(true) {
+            result(name, &value)
+        } else {
+            Err(value)
+        }
+    }
+
+    /// Calls the function and sends a message returned by a converter when its
+    /// result is ready. Promises are awaited and their rejections are errors.
+    pub fn call_async<A, R, F>(&mut self, name: &str, args: A, converter: F) -> InteropHandle
+    where
+        A: Serialize,
+        R: DeserializeOwned + 'static,
+        F: Fn(Result<R, String>) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let promise = match arguments(name, args) {
+            Ok(args) => js! {
+                var name = @{name};
+                var functions = window.__yew_interop || {};
+                var args = JSON.parse(@{args});
+                args = args === null ? [] : Array.isArray(args) ? args : [args];
+                return new Promise(function(resolve) {
+                    if (typeof functions[name] !== "function") {
+                        throw new Error("the function " + name + " isn't registered");
+                    }
+                    resolve(functions[name].apply(null, args));
+                });
+            },
+            Err(error) => js! { return Promise.reject(@{error}); },
+        };
+        let function = name.to_owned();
+        settle(promise, move |resolved, value| {
+            let outcome = if resolved {
+                from_json(value).map_err(|error| format!("can't convert a result of {}: {}", function, error))
+            } else {
+                Err(reason(value))
+            };
+            let msg = converter(outcome);
+            tx.send(msg);
+        })
+    }
+}
@@ -0,0 +1,93 @@
+//! This module contains a service which notifies when a rendered element
+//! enters or leaves the viewport with `IntersectionObserver`, like a sentinel
+//! at the end of a list or an image which is loaded when it's scrolled to:
+//!
+//! ```rust,ignore
+//! html! { <div ref=&self.sentinel,></div> }
+//!
+//! let handle = self.intersection.observe(&self.sentinel, IntersectionOptions::margin("200px"), Msg::Visible);
+//! ```
+
+use std::rc::Rc;
+use html::AppSender;
+use virtual_dom::NodeRef;
+use super::Task;
+use super::backend::{self, IntersectionBackend};
+
+/// Options of an observer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntersectionOptions {
+    /// A margin around the viewport in the CSS syntax, like `200px 0px`,
+    /// so an element counts as visible before it's scrolled to.
+    pub root_margin: String,
+    /// A part of the element from 0 to 1 which has to be visible.
+    pub threshold: f64,
+}
+
+impl IntersectionOptions {
+    /// Creates options with the margin around the viewport.
+    pub fn margin(root_margin: &str) -> Self {
+        IntersectionOptions {
+            root_margin: root_margin.to_owned(),
+            threshold: 0.0,
+        }
+    }
+}
+
+impl Default for IntersectionOptions {
+    fn default() -> Self {
+        IntersectionOptions::margin("0px")
+    }
+}
+
+/// A handle of an observer. The element isn't observed after it's canceled.
+pub struct IntersectionHandle(Option<Box<Task>>);
+
+/// A service to observe if elements are visible.
+pub struct IntersectionService<MSG> {
+    sender: AppSender<MSG>,
+    backend: Rc<IntersectionBackend>,
+}
+
+impl<MSG: 'static> IntersectionService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self::with_backend(sender, backend::intersection())
+    }
+
+    /// Creates a new service instance which uses the backend.
+    pub fn with_backend(sender: AppSender<MSG>, backend: Rc<IntersectionBackend>) -> Self {
+        Self { sender, backend }
+    }
+
+    /// Sends a message with `true` when the element of the reference becomes
+    /// visible and with `false` when it's hidden. It waits until the element
+    /// is rendered, so it's called in `create`.
+    pub fn observe<F>(&mut self, target: &NodeRef, options: IntersectionOptions, converter: F) -> IntersectionHandle
+    where
+        F: Fn(bool) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let callback = move |visible| tx.send(converter(visible));
+        IntersectionHandle(Some(self.backend.observe(target.clone(), &options, Box::new(callback))))
+    }
+}
+
+impl Task for IntersectionHandle {
+    fn is_active(&self) -> bool {
+        self.0.as_ref().map_or(false, |task| task.is_active())
+    }
+
+    fn cancel(&mut self) {
+        let mut task = self.0.take().expect("tried to cancel an observer twice");
+        task.cancel();
+    }
+}
+
+impl Drop for IntersectionHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
@@ -1,24 +1,31 @@
 //! This module contains the implementation of a service for
 //! periodic sending messages to a loop.
 
+use std::rc::Rc;
 use std::time::Duration;
-use stdweb::Value;
 use html::AppSender;
-use super::{Task, to_ms};
+use super::Task;
+use super::backend::{self, TimerBackend};
 
 /// A handle which helps to cancel interval. Uses
 /// [clearInterval](https://developer.mozilla.org/en-US/docs/Web/API/WindowOrWorkerGlobalScope/clearInterval).
-pub struct IntervalHandle(Option<Value>);
+pub struct IntervalHandle(Box<Task>);
 
 /// A service to send messages on every elapsed interval.
 pub struct IntervalService<MSG> {
     sender: AppSender<MSG>,
+    backend: Rc<TimerBackend>,
 }
 
 impl<MSG: 'static> IntervalService<MSG> {
     /// Creates a new service instance connected to `App` by provided `sender`.
     pub fn new(sender: AppSender<MSG>) -> Self {
-        Self { sender }
+        Self::with_backend(sender, backend::timers())
+    }
+
+    /// Creates a new service instance which uses the `backend` for timers.
+    pub fn with_backend(sender: AppSender<MSG>, backend: Rc<TimerBackend>) -> Self {
+        Self { sender, backend }
     }
 
     /// Sets interval which will call send a messages returned by a converter
@@ -32,29 +39,24 @@ impl<MSG: 'static> IntervalService<MSG> {
             let msg = converter();
             tx.send(msg);
         };
-        let ms = to_ms(duration);
-        let handle = js! {
-            var callback = @{callback};
-            let action = function() {
-                callback();
-            };
-            let delay = @{ms};
-            return {
-                interval_id: setInterval(action, delay),
-                callback,
-            };
-        };
-        IntervalHandle(Some(handle))
+        IntervalHandle(self.backend.interval(duration, Box::new(callback)))
     }
 }
 
 impl Task for IntervalHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_active()
+    }
+
     fn cancel(&mut self) {
-        let handle = self.0.take().expect("tried to cancel interval twice");
-        js! { @(no_return)
-            var handle = @{handle};
-            clearInterval(handle.interval_id);
-            handle.callback.drop();
+        self.0.cancel();
+    }
+}
+
+impl Drop for IntervalHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
         }
     }
 }
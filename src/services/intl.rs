@@ -0,0 +1,315 @@
+//! This module contains a service which formats numbers, dates and
+//! relative times for a locale with the
+//! [Intl API](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Intl).
+//!
+//! Strings are returned synchronously, so the service could be used in `view`:
+//!
+//! ```rust,ignore
+//! html! {
+//!     <span>{ self.intl.currency(self.total, "EUR") }</span>
+//!     <time>{ self.intl.relative(self.posted) }</time>
+//! }
+//! ```
+
+use std::rc::Rc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use date::{Date, Time};
+use super::backend::{self, IntlBackend};
+
+/// A kind of formatted numbers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NumberStyle {
+    /// A plain number: `1,234.5`.
+    Decimal,
+    /// A fraction as percents: `0.25` is `25%`.
+    Percent,
+    /// An amount of the currency with an ISO 4217 code, like `USD`.
+    Currency(String),
+}
+
+/// Options of formatted numbers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberOptions {
+    /// The kind of the number.
+    pub style: NumberStyle,
+    /// Digits after the point which are always shown.
+    pub minimum_fraction_digits: Option<u8>,
+    /// Digits after the point which are shown at most.
+    pub maximum_fraction_digits: Option<u8>,
+    /// Separates groups of thousands.
+    pub grouping: bool,
+}
+
+impl Default for NumberOptions {
+    fn default() -> Self {
+        NumberOptions {
+            style: NumberStyle::Decimal,
+            minimum_fraction_digits: None,
+            maximum_fraction_digits: None,
+            grouping: true,
+        }
+    }
+}
+
+/// A length of formatted dates and times.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateStyle {
+    /// `Tuesday, January 2, 2024`.
+    Full,
+    /// `January 2, 2024`.
+    Long,
+    /// `Jan 2, 2024`.
+    Medium,
+    /// `1/2/24`.
+    Short,
+}
+
+impl DateStyle {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match *self {
+            DateStyle::Full => "full",
+            DateStyle::Long => "long",
+            DateStyle::Medium => "medium",
+            DateStyle::Short => "short",
+        }
+    }
+}
+
+/// Options of formatted dates.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DateOptions {
+    /// The style of the date or `None` to skip it.
+    pub date: Option<DateStyle>,
+    /// The style of the time or `None` to skip it.
+    pub time: Option<DateStyle>,
+    /// An IANA time zone, like `Europe/Berlin`. The zone of a user is used by default.
+    pub time_zone: Option<String>,
+}
+
+/// A part of calendar dates which is formatted alone, for calendars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalendarField {
+    /// The month with the year: `January 2024`.
+    MonthYear,
+    /// The name of the weekday: `Tuesday`.
+    Weekday,
+    /// The short name of the weekday: `Tue`.
+    ShortWeekday,
+}
+
+/// A unit of relative times.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelativeUnit {
+    /// Seconds.
+    Second,
+    /// Minutes.
+    Minute,
+    /// Hours.
+    Hour,
+    /// Days.
+    Day,
+    /// Weeks.
+    Week,
+    /// Months.
+    Month,
+    /// Years.
+    Year,
+}
+
+impl RelativeUnit {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match *self {
+            RelativeUnit::Second => "second",
+            RelativeUnit::Minute => "minute",
+            RelativeUnit::Hour => "hour",
+            RelativeUnit::Day => "day",
+            RelativeUnit::Week => "week",
+            RelativeUnit::Month => "month",
+            RelativeUnit::Year => "year",
+        }
+    }
+}
+
+/// Returns milliseconds since the Unix epoch, negative for earlier times.
+pub(crate) fn to_timestamp(time: SystemTime) -> f64 {
+    let ms = |duration: Duration| duration.as_secs() as f64 * 1000.0 + f64::from(duration.subsec_millis());
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => ms(duration),
+        Err(err) => -ms(err.duration()),
+    }
+}
+
+/// A service which formats values for a locale.
+pub struct IntlService {
+    locale: Option<String>,
+    backend: Rc<IntlBackend>,
+}
+
+impl IntlService {
+    /// Creates a new service instance which formats for the locale of a user.
+    /// With the `i18n` feature it follows the current locale of bundles once
+    /// a bundle is added.
+    pub fn new() -> Self {
+        IntlService::with_backend(backend::intl())
+    }
+
+    /// Creates a new service instance which formats for the locale, like `de-DE`.
+    pub fn with_locale(locale: &str) -> Self {
+        let mut service = IntlService::new();
+        service.set_locale(locale);
+        service
+    }
+
+    /// Creates a new service instance which formats with the `backend`.
+    pub fn with_backend(backend: Rc<IntlBackend>) -> Self {
+        IntlService { locale: None, backend }
+    }
+
+    /// Changes the locale of formatted values.
+    pub fn set_locale(&mut self, locale: &str) {
+        self.locale = Some(locale.to_owned());
+    }
+
+    /// Returns the locale of formatted values or `None` for the locale of a user.
+    pub fn locale(&self) -> Option<String> {
+        #[cfg(feature = "i18n")]
+        {
+            if self.locale.is_none() && !::i18n::locales().is_empty() {
+                return Some(::i18n::locale());
+            }
+        }
+        self.locale.clone()
+    }
+
+    /// Formats a number with separators of the locale.
+    pub fn number(&self, number: f64) -> String {
+        self.number_with(number, &NumberOptions::default())
+    }
+
+    /// Formats an amount of the currency with an ISO 4217 code, like `USD`.
+    pub fn currency(&self, amount: f64, currency: &str) -> String {
+        let options = NumberOptions {
+            style: NumberStyle::Currency(currency.to_owned()),
+            ..NumberOptions::default()
+        };
+        self.number_with(amount, &options)
+    }
+
+    /// Formats a fraction as percents.
+    pub fn percent(&self, fraction: f64) -> String {
+        let options = NumberOptions {
+            style: NumberStyle::Percent,
+            ..NumberOptions::default()
+        };
+        self.number_with(fraction, &options)
+    }
+
+    /// Formats a number with the options.
+    pub fn number_with(&self, number: f64, options: &NumberOptions) -> String {
+        self.backend.format_number(self.locale().as_deref(), number, options)
+    }
+
+    /// Formats the date of the time in the medium style.
+    pub fn date(&self, time: SystemTime) -> String {
+        let options = DateOptions {
+            date: Some(DateStyle::Medium),
+            ..DateOptions::default()
+        };
+        self.date_with(time, &options)
+    }
+
+    /// Formats the date in the medium style and the time in the short style.
+    pub fn date_time(&self, time: SystemTime) -> String {
+        let options = DateOptions {
+            date: Some(DateStyle::Medium),
+            time: Some(DateStyle::Short),
+            ..DateOptions::default()
+        };
+        self.date_with(time, &options)
+    }
+
+    /// Formats the time with the options.
+    pub fn date_with(&self, time: SystemTime, options: &DateOptions) -> String {
+        self.backend.format_date(self.locale().as_deref(), time, options)
+    }
+
+    /// Formats a calendar date in the style. Dates don't have time zones,
+    /// so it's the same day for every user.
+    pub fn calendar_date(&self, date: Date, style: DateStyle) -> String {
+        let options = DateOptions {
+            date: Some(style),
+            time_zone: Some("UTC".into()),
+            ..DateOptions::default()
+        };
+        self.date_with(date.to_time(), &options)
+    }
+
+    /// Formats a part of a calendar date, like the heading of a month.
+    pub fn calendar_field(&self, date: Date, field: CalendarField) -> String {
+        self.backend.format_calendar(self.locale().as_deref(), date, field)
+    }
+
+    /// Formats a time of the day in the short style: `14:30` or `2:30 PM`.
+    pub fn time_of_day(&self, time: Time) -> String {
+        let options = DateOptions {
+            time: Some(DateStyle::Short),
+            time_zone: Some("UTC".into()),
+            ..DateOptions::default()
+        };
+        self.date_with(time.on(Date::from_days(0)), &options)
+    }
+
+    /// Returns the ISO 8601 weekday which weeks start on in the locale:
+    /// 1 is Monday and 7 is Sunday.
+    pub fn first_weekday(&self) -> u32 {
+        self.backend.first_weekday(self.locale().as_deref())
+    }
+
+    /// Returns the date of today in the time zone of a user.
+    pub fn today(&self) -> Date {
+        self.backend.today()
+    }
+
+    /// Formats the time relatively to now, like `3 minutes ago` or `in 2 days`.
+    /// The unit is chosen to be the largest one which keeps the value above one.
+    pub fn relative(&self, time: SystemTime) -> String {
+        let seconds = (to_timestamp(time) - to_timestamp(self.backend.now())) / 1000.0;
+        let (value, unit) = relative_unit(seconds);
+        self.relative_with(value, unit)
+    }
+
+    /// Formats a value of the unit relatively to now, negative for the past.
+    pub fn relative_with(&self, value: f64, unit: RelativeUnit) -> String {
+        self.backend.format_relative(self.locale().as_deref(), value, unit)
+    }
+}
+
+impl Default for IntlService {
+    fn default() -> Self {
+        IntlService::new()
+    }
+}
+
+/// Chooses a unit of a difference in seconds and rounds the value of the unit.
+fn relative_unit(seconds: f64) -> (f64, RelativeUnit) {
+    const MINUTE: f64 = 60.0;
+    const HOUR: f64 = 60.0 * MINUTE;
+    const DAY: f64 = 24.0 * HOUR;
+    const WEEK: f64 = 7.0 * DAY;
+    const MONTH: f64 = 30.0 * DAY;
+    const YEAR: f64 = 365.0 * DAY;
+    let units = [
+        (YEAR, RelativeUnit::Year),
+        (MONTH, RelativeUnit::Month),
+        (WEEK, RelativeUnit::Week),
+        (DAY, RelativeUnit::Day),
+        (HOUR, RelativeUnit::Hour),
+        (MINUTE, RelativeUnit::Minute),
+    ];
+    for &(size, unit) in units.iter() {
+        if seconds.abs() >= size {
+            return ((seconds / size).round(), unit);
+        }
+    }
+    (seconds.round(), RelativeUnit::Second)
+}
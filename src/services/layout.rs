@@ -0,0 +1,82 @@
+//! This module contains a service which measures a floating element and its
+//! anchor to position them with `position::compute`. It measures them again
+//! when the window is resized, the page or a container is scrolled or the
+//! elements change their sizes:
+//!
+//! ```rust,ignore
+//! html! { <div ref=&self.menu, style=&self.style,>{ "..." }</div> }
+//!
+//! self.tracker = Some(self.layout.track("menu-button", &self.menu, Msg::Layout));
+//!
+//! Msg::Layout(LayoutEvent::Measured(layout)) => self.position = Some(compute(&layout, &self.options)),
+//! Msg::Layout(LayoutEvent::Outside) => self.open = false,
+//! ```
+
+use std::rc::Rc;
+use html::AppSender;
+use position::Layout;
+use virtual_dom::NodeRef;
+use super::Task;
+use super::backend::{self, LayoutBackend};
+
+/// An event of a tracked element.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LayoutEvent {
+    /// The elements were measured.
+    Measured(Layout),
+    /// A user pressed a pointer outside of both elements, which usually
+    /// closes popovers and menus.
+    Outside,
+}
+
+/// A handle of tracking. The elements aren't measured after it's canceled.
+pub struct LayoutHandle(Option<Box<Task>>);
+
+/// A service to measure floating elements.
+pub struct LayoutService<MSG> {
+    sender: AppSender<MSG>,
+    backend: Rc<LayoutBackend>,
+}
+
+impl<MSG: 'static> LayoutService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self::with_backend(sender, backend::layout())
+    }
+
+    /// Creates a new service instance which uses the backend.
+    pub fn with_backend(sender: AppSender<MSG>, backend: Rc<LayoutBackend>) -> Self {
+        Self { sender, backend }
+    }
+
+    /// Sends messages with layouts of the element of the reference and an anchor
+    /// with the `id`, which could be rendered by another component. It waits until
+    /// both are rendered, so it's called when the element is shown.
+    pub fn track<F>(&mut self, anchor: &str, floating: &NodeRef, converter: F) -> LayoutHandle
+    where
+        F: Fn(LayoutEvent) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let callback = move |event| tx.send(converter(event));
+        LayoutHandle(Some(self.backend.track(anchor.to_owned(), floating.clone(), Box::new(callback))))
+    }
+}
+
+impl Task for LayoutHandle {
+    fn is_active(&self) -> bool {
+        self.0.as_ref().map_or(false, |task| task.is_active())
+    }
+
+    fn cancel(&mut self) {
+        let mut task = self.0.take().expect("tried to cancel tracking of a layout twice");
+        task.cancel();
+    }
+}
+
+impl Drop for LayoutHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! This module contains a service which reports long tasks, stalls of the main
+//! thread over 50 ms which make a page janky, with `PerformanceObserver`:
+//!
+//! ```rust,ignore
+//! self.handle = Some(self.long_tasks.listen(Msg::Stalled));
+//!
+//! Msg::Stalled(task) => warn!("{:?} blocked for {:?}", task.activities, task.duration),
+//! Msg::Unload => analytics::track(self.long_tasks.report()),
+//! ```
+//!
+//! While a listener is active the loop records which components updated and
+//! rendered, so a task lists the activities which ran during it. A report
+//! aggregates tasks by components, and it's an event of `analytics`.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::time::Duration;
+use serde_json::Value;
+use analytics::Event;
+use html::AppSender;
+use super::Task;
+use super::backend::{self, LongTaskBackend};
+
+/// Tasks which are shorter aren't long, and the time over it blocks a page.
+const THRESHOLD: Duration = Duration::from_millis(50);
+
+/// How many activities are kept to attribute tasks which are reported later.
+const HISTORY: usize = 512;
+
+/// A phase of the loop which ran during a long task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Activity {
+    /// The phase: `update`, `view` or `diff`.
+    pub phase: &'static str,
+    /// The type name of the component, or `app` for the root of an app.
+    pub name: &'static str,
+}
+
+/// A stall of the main thread.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LongTask {
+    /// The start since the time origin of the page.
+    pub start: Duration,
+    /// How long the thread was busy.
+    pub duration: Duration,
+    /// Where the task ran, like `self` or `cross-origin-iframe`.
+    pub source: String,
+    /// The source or the name of the frame of the task, if a browser knows it.
+    pub container: Option<String>,
+    /// Phases of the loop which ran during the task, every phase once.
+    pub activities: Vec<Activity>,
+}
+
+impl LongTask {
+    /// Returns the time over 50 ms, which delays input.
+    pub fn blocking(&self) -> Duration {
+        self.duration.checked_sub(THRESHOLD).unwrap_or_default()
+    }
+}
+
+/// Long tasks of a component.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Culprit {
+    /// The type name of the component.
+    pub name: &'static str,
+    /// How many long tasks it ran in.
+    pub tasks: usize,
+    /// The blocking time of the tasks.
+    pub blocking: Duration,
+}
+
+/// Aggregated long tasks.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JankReport {
+    /// How many long tasks there were.
+    pub tasks: usize,
+    /// The total blocking time.
+    pub blocking: Duration,
+    /// The longest task.
+    pub longest: Duration,
+    /// Components of tasks from the one with the most blocking time.
+    pub culprits: Vec<Culprit>,
+}
+
+impl JankReport {
+    fn add(&mut self, task: &LongTask) {
+        self.tasks += 1;
+        self.blocking += task.blocking();
+        self.longest = self.longest.max(task.duration);
+        let mut names: Vec<&'static str> = task.activities.iter().map(|activity| activity.name).collect();
+        names.sort();
+        names.dedup();
+        for name in names {
+            match self.culprits.iter().position(|culprit| culprit.name == name) {
+                Some(index) => {
+                    self.culprits[index].tasks += 1;
+                    self.culprits[index].blocking += task.blocking();
+                }
+                None => self.culprits.push(Culprit { name, tasks: 1, blocking: task.blocking() }),
+            }
+        }
+        self.culprits.sort_by(|a, b| b.blocking.cmp(&a.blocking).then_with(|| a.name.cmp(b.name)));
+    }
+}
+
+impl From<JankReport> for Event {
+    fn from(report: JankReport) -> Self {
+        let culprits: Vec<Value> = report.culprits.iter().map(|culprit| Value::from(culprit.name)).collect();
+        Event::custom("jank")
+            .property("tasks", report.tasks as u64)
+            .property("blocking_ms", report.blocking.as_millis() as u64)
+            .property("longest_ms", report.longest.as_millis() as u64)
+            .property("culprits", culprits)
+    }
+}
+
+thread_local! {
+    static RECORDING: Cell<usize> = Cell::new(0);
+    static ACTIVITIES: RefCell<VecDeque<(Duration, Activity)>> = RefCell::new(VecDeque::new());
+}
+
+/// Records a phase of the loop if a listener is active.
+pub(crate) fn record(phase: &'static str, name: &'static str) {
+    if RECORDING.with(Cell::get) == 0 {
+        return;
+    }
+    let now = backend::timers().monotonic();
+    ACTIVITIES.with(|activities| {
+        let mut activities = activities.borrow_mut();
+        if activities.len() == HISTORY {
+            activities.pop_front();
+        }
+        activities.push_back((now, Activity { phase, name }));
+    });
+}
+
+fn attribute(task: &mut LongTask) {
+    let end = task.start + task.duration;
+    ACTIVITIES.with(|activities| {
+        for &(time, activity) in activities.borrow().iter() {
+            if time >= task.start && time <= end && !task.activities.contains(&activity) {
+                task.activities.push(activity);
+            }
+        }
+    });
+}
+
+/// A handle of a listener of long tasks. It's canceled when dropped.
+pub struct LongTaskHandle(Option<Box<Task>>);
+
+impl Task for LongTaskHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let mut observer = self.0.take().expect("tried to cancel a long task listener twice");
+        observer.cancel();
+        let recording = RECORDING.with(|recording| {
+            recording.set(recording.get() - 1);
+            recording.get()
+        });
+        if recording == 0 {
+            ACTIVITIES.with(|activities| activities.borrow_mut().clear());
+        }
+    }
+}
+
+impl Drop for LongTaskHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+/// A service of long tasks.
+pub struct LongTaskService<MSG> {
+    sender: AppSender<MSG>,
+    backend: Rc<LongTaskBackend>,
+    report: Rc<RefCell<JankReport>>,
+}
+
+impl<MSG: 'static> LongTaskService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self::with_backend(sender, backend::long_tasks())
+    }
+
+    /// Creates a new service instance which uses the backend.
+    pub fn with_backend(sender: AppSender<MSG>, backend: Rc<LongTaskBackend>) -> Self {
+        Self { sender, backend, report: Rc::new(RefCell::new(JankReport::default())) }
+    }
+
+    /// Sends a message with every long task and adds it to the report.
+    /// Browsers without long tasks don't report any.
+    pub fn listen<F>(&mut self, converter: F) -> LongTaskHandle
+    where
+        F: Fn(LongTask) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let report = self.report.clone();
+        let callback = move |mut task: LongTask| {
+            attribute(&mut task);
+            report.borrow_mut().add(&task);
+            tx.send(converter(task));
+        };
+        RECORDING.with(|recording| recording.set(recording.get() + 1));
+        LongTaskHandle(Some(self.backend.observe(Box::new(callback))))
+    }
+
+    /// Returns long tasks which were reported to listeners of the service.
+    pub fn report(&self) -> JankReport {
+        self.report.borrow().clone()
+    }
+
+    /// Forgets reported tasks, like after the report was sent.
+    pub fn reset(&mut self) {
+        *self.report.borrow_mut() = JankReport::default();
+    }
+}
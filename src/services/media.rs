@@ -0,0 +1,223 @@
+//! This module contains a service which controls `<audio>` and `<video>`
+//! elements of views and sends their events as messages, so a player is
+//! written without `js!`:
+//!
+//! ```rust,ignore
+//! html! { <video ref=&self.video, src="/intro.mp4",/> }
+//!
+//! self.player = self.media.attach(&self.video, Msg::Media);
+//!
+//! Msg::Toggle => if self.state.paused { self.player.play(); } else { self.player.pause(); },
+//! Msg::Seek(position) => self.player.seek(position),
+//! Msg::Media(MediaEvent::Time(time)) => self.position = time,
+//! Msg::Media(MediaEvent::Failed(MediaError::Blocked)) => self.show_play_button = true,
+//! ```
+
+use std::fmt;
+use std::rc::Rc;
+use std::time::Duration;
+use html::AppSender;
+use virtual_dom::NodeRef;
+use super::Task;
+use super::backend::{self, MediaBackend, MediaControl};
+
+/// A command to a media element.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MediaCommand {
+    /// Starts or continues playback.
+    Play,
+    /// Pauses playback.
+    Pause,
+    /// Moves to the time from the start.
+    Seek(Duration),
+    /// Sets the volume from 0 to 1.
+    Volume(f64),
+    /// Mutes or unmutes the sound.
+    Muted(bool),
+    /// Sets the speed of playback, 1 is the normal speed.
+    Rate(f64),
+}
+
+/// An error of a media element.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MediaError {
+    /// The browser didn't allow to play without a gesture of a user,
+    /// like playback with sound before a click.
+    Blocked,
+    /// A user or the app aborted loading.
+    Aborted,
+    /// The media wasn't loaded because of a network error.
+    Network,
+    /// The media was loaded but isn't decoded.
+    Decode,
+    /// The source has a format which the browser doesn't play or isn't found.
+    Unsupported,
+}
+
+impl fmt::Display for MediaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MediaError::Blocked => write!(f, "playback needs a gesture of a user"),
+            MediaError::Aborted => write!(f, "loading of media was aborted"),
+            MediaError::Network => write!(f, "media wasn't loaded because of a network error"),
+            MediaError::Decode => write!(f, "media isn't decoded"),
+            MediaError::Unsupported => write!(f, "a source of media isn't supported"),
+        }
+    }
+}
+
+/// An event of a media element.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MediaEvent {
+    /// Metadata is loaded with the duration, it's `None` for live streams.
+    Metadata(Option<Duration>),
+    /// The current time changed while playing or after a seek.
+    Time(Duration),
+    /// Playback started or continued after buffering.
+    Playing,
+    /// Playback was paused.
+    Paused,
+    /// Playback waits for data to be buffered.
+    Buffering,
+    /// How much is buffered from the start, while it's loaded.
+    Buffered(Duration),
+    /// Playback reached the end.
+    Ended,
+    /// The volume and the muted flag changed.
+    Volume(f64, bool),
+    /// Playback or loading failed.
+    Failed(MediaError),
+}
+
+/// The current state of a media element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaState {
+    /// The current time.
+    pub time: Duration,
+    /// The duration, `None` before metadata is loaded and for live streams.
+    pub duration: Option<Duration>,
+    /// How much is buffered from the start.
+    pub buffered: Duration,
+    /// `true` if playback is paused.
+    pub paused: bool,
+    /// `true` if playback reached the end.
+    pub ended: bool,
+    /// The volume from 0 to 1.
+    pub volume: f64,
+    /// `true` if the sound is muted.
+    pub muted: bool,
+    /// The speed of playback.
+    pub rate: f64,
+}
+
+impl Default for MediaState {
+    fn default() -> Self {
+        MediaState {
+            time: Duration::from_secs(0),
+            duration: None,
+            buffered: Duration::from_secs(0),
+            paused: true,
+            ended: false,
+            volume: 1.0,
+            muted: false,
+            rate: 1.0,
+        }
+    }
+}
+
+/// A handle of an attached media element. It controls the element and
+/// stops sending events when it's canceled.
+pub struct MediaHandle(Option<Box<MediaControl>>);
+
+impl MediaHandle {
+    /// Starts or continues playback. `MediaError::Blocked` is sent if
+    /// the browser doesn't allow it.
+    pub fn play(&mut self) -> bool {
+        self.control(MediaCommand::Play)
+    }
+
+    /// Pauses playback.
+    pub fn pause(&mut self) -> bool {
+        self.control(MediaCommand::Pause)
+    }
+
+    /// Moves to the time from the start.
+    pub fn seek(&mut self, time: Duration) -> bool {
+        self.control(MediaCommand::Seek(time))
+    }
+
+    /// Sets the volume from 0 to 1.
+    pub fn set_volume(&mut self, volume: f64) -> bool {
+        self.control(MediaCommand::Volume(volume.clamp(0.0, 1.0)))
+    }
+
+    /// Mutes or unmutes the sound.
+    pub fn set_muted(&mut self, muted: bool) -> bool {
+        self.control(MediaCommand::Muted(muted))
+    }
+
+    /// Sets the speed of playback.
+    pub fn set_rate(&mut self, rate: f64) -> bool {
+        self.control(MediaCommand::Rate(rate))
+    }
+
+    /// Sends the command to the element. Returns `false` if the element
+    /// isn't rendered or the handle is canceled.
+    pub fn control(&mut self, command: MediaCommand) -> bool {
+        self.0.as_mut().map_or(false, |control| control.control(command))
+    }
+
+    /// Returns the state of the element or `None` if it isn't rendered.
+    pub fn state(&self) -> Option<MediaState> {
+        self.0.as_ref().and_then(|control| control.state())
+    }
+}
+
+impl Task for MediaHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let mut control = self.0.take().expect("tried to cancel a media handle twice");
+        control.cancel();
+    }
+}
+
+impl Drop for MediaHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+/// A service to control media elements.
+pub struct MediaService<MSG> {
+    sender: AppSender<MSG>,
+    backend: Rc<MediaBackend>,
+}
+
+impl<MSG: 'static> MediaService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self::with_backend(sender, backend::media())
+    }
+
+    /// Creates a new service instance which uses the backend.
+    pub fn with_backend(sender: AppSender<MSG>, backend: Rc<MediaBackend>) -> Self {
+        Self { sender, backend }
+    }
+
+    /// Attaches to the `<audio>` or `<video>` element of the reference and
+    /// sends its events. It waits until the element is rendered, so it's
+    /// called in `create`.
+    pub fn attach<F>(&mut self, target: &NodeRef, converter: F) -> MediaHandle
+    where
+        F: Fn(MediaEvent) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let callback = move |event| tx.send(converter(event));
+        MediaHandle(Some(self.backend.attach(target.clone(), Box::new(callback))))
+    }
+}
@@ -0,0 +1,130 @@
+//! This module contains a service to exchange messages with iframes and
+//! with the parent window by `postMessage`. Messages are envelopes of named
+//! channels which are sent to an exact origin and accepted only from the
+//! window and the origin of a channel, so an embedded widget doesn't handle
+//! messages of other pages:
+//!
+//! ```rust,ignore
+//! let host = Channel::new(Target::Parent, "https://shop.example", "cart");
+//! let handle = self.messaging.listen(&host, |Json(data)| Msg::Host(data));
+//! self.messaging.post(&host, Json(&WidgetMsg::Ready));
+//! ```
+//!
+//! An envelope is an object `{ yew: channel, data: text }` where the text of
+//! data is written by the format layer, so a host reads it with
+//! `JSON.parse(event.data.data)` for `Json`.
+
+use std::rc::Rc;
+use html::AppSender;
+use format::{Storable, Restorable};
+use virtual_dom::NodeRef;
+use super::Task;
+use super::backend::{self, MessagingBackend};
+
+/// A window which messages are exchanged with.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Target {
+    /// The parent window of the app which is embedded into an iframe.
+    Parent,
+    /// The window of a rendered iframe, put the reference to it with `ref=`.
+    Frame(NodeRef),
+}
+
+/// A channel of messages with a window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Channel {
+    /// The window of the other side.
+    pub target: Target,
+    /// The origin of the other side, like `https://shop.example`.
+    /// Frames with the `sandbox` attribute without `allow-same-origin`
+    /// have the `null` origin.
+    pub origin: String,
+    /// The name of the channel. Envelopes of other channels are ignored.
+    pub name: String,
+}
+
+impl Channel {
+    /// Creates a channel with the window of the `target`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the origin is `*`, messages are exchanged with an exact origin.
+    pub fn new(target: Target, origin: &str, name: &str) -> Self {
+        if origin == "*" {
+            panic!("a channel of messages needs an exact origin, not *");
+        }
+        Channel {
+            target,
+            origin: origin.to_owned(),
+            name: name.to_owned(),
+        }
+    }
+}
+
+/// A handle of a subscription to a channel. Implements `Task` and could be canceled.
+pub struct MessagingHandle(Option<Box<Task>>);
+
+/// A service to exchange messages with other windows.
+pub struct MessagingService<MSG> {
+    sender: AppSender<MSG>,
+    backend: Rc<MessagingBackend>,
+}
+
+impl<MSG: 'static> MessagingService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self::with_backend(sender, backend::messaging())
+    }
+
+    /// Creates a new service instance which exchanges messages with the `backend`.
+    pub fn with_backend(sender: AppSender<MSG>, backend: Rc<MessagingBackend>) -> Self {
+        Self { sender, backend }
+    }
+
+    /// Posts data to the window of the channel. The browser drops the message
+    /// if the window has another origin now.
+    pub fn post<IN>(&mut self, channel: &Channel, data: IN)
+    where
+        IN: Into<Storable>,
+    {
+        if let Some(data) = data.into() {
+            self.backend.post(channel, data);
+        }
+    }
+
+    /// Sends a message returned by a converter for every envelope
+    /// which comes to the channel.
+    pub fn listen<F, OUT>(&mut self, channel: &Channel, converter: F) -> MessagingHandle
+    where
+        OUT: From<Restorable>,
+        F: Fn(OUT) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let callback = move |data: String| {
+            let out = OUT::from(Ok(data));
+            let msg = converter(out);
+            tx.send(msg);
+        };
+        let task = self.backend.listen(channel, Box::new(callback));
+        MessagingHandle(Some(task))
+    }
+}
+
+impl Task for MessagingHandle {
+    fn is_active(&self) -> bool {
+        self.0.as_ref().map_or(false, |task| task.is_active())
+    }
+
+    fn cancel(&mut self) {
+        let mut task = self.0.take().expect("tried to cancel a subscription to a channel twice");
+        task.cancel();
+    }
+}
+
+impl Drop for MessagingHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
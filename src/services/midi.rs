@@ -0,0 +1,383 @@
+//! This module contains a service of the Web MIDI API, so music tools
+//! receive notes and controls of devices and play to them:
+//!
+//! ```rust,ignore
+//! self.midi = Some(self.service.open(false, Msg::Midi));
+//!
+//! Msg::Midi(MidiEvent::Ready(ports)) => self.ports = ports,
+//! Msg::Midi(MidiEvent::Message(_, MidiMessage::NoteOn { note, velocity, .. })) => self.synth.start(note, velocity),
+//! Msg::Midi(MidiEvent::Message(_, MidiMessage::ControlChange { controller: 7, value, .. })) => self.volume = value,
+//! Msg::Play(note) => if let Some(ref midi) = self.midi {
+//!     midi.send(&self.output, &MidiMessage::NoteOn { channel: 0, note, velocity: 100 });
+//! },
+//! ```
+//!
+//! Browsers ask a user to allow MIDI, and system exclusive messages need
+//! a separate permission.
+
+use std::fmt;
+use stdweb::Value;
+use stdweb::unstable::TryFrom;
+use html::AppSender;
+use super::{Task, is_done};
+
+/// A direction of a port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortKind {
+    /// A device which sends messages, like a keyboard.
+    Input,
+    /// A device which receives messages, like a synthesizer.
+    Output,
+}
+
+/// A port of a MIDI device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MidiPort {
+    /// A stable id of the port to send to.
+    pub id: String,
+    /// The name of the port.
+    pub name: String,
+    /// The manufacturer of the device.
+    pub manufacturer: String,
+    /// The direction of the port.
+    pub kind: PortKind,
+    /// `false` if the device is unplugged.
+    pub connected: bool,
+}
+
+/// A MIDI message. Channels are from 0 to 15, other values are from 0 to 127.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MidiMessage {
+    /// A key is released. A note on with velocity 0 is parsed as it.
+    NoteOff {
+        /// The channel.
+        channel: u8,
+        /// The key, 60 is the middle C.
+        note: u8,
+        /// How fast the key was released.
+        velocity: u8,
+    },
+    /// A key is pressed.
+    NoteOn {
+        /// The channel.
+        channel: u8,
+        /// The key, 60 is the middle C.
+        note: u8,
+        /// How fast the key was pressed.
+        velocity: u8,
+    },
+    /// The pressure of a pressed key changed.
+    PolyAftertouch {
+        /// The channel.
+        channel: u8,
+        /// The key.
+        note: u8,
+        /// The pressure.
+        pressure: u8,
+    },
+    /// A knob, a slider or a pedal moved.
+    ControlChange {
+        /// The channel.
+        channel: u8,
+        /// The number of the control, like 7 for the volume.
+        controller: u8,
+        /// The position of the control.
+        value: u8,
+    },
+    /// An instrument is chosen.
+    ProgramChange {
+        /// The channel.
+        channel: u8,
+        /// The number of the instrument.
+        program: u8,
+    },
+    /// The pressure of all keys of the channel changed.
+    ChannelAftertouch {
+        /// The channel.
+        channel: u8,
+        /// The pressure.
+        pressure: u8,
+    },
+    /// The pitch wheel moved.
+    PitchBend {
+        /// The channel.
+        channel: u8,
+        /// The position from -8192 to 8191, 0 is the middle.
+        value: i16,
+    },
+    /// Another message, like a system exclusive one or a clock, with all its bytes.
+    Other(Vec<u8>),
+}
+
+impl MidiMessage {
+    /// Parses the bytes of a message.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let status = match bytes.first() {
+            Some(&status) if status < 0xF0 && bytes.len() >= 2 => status,
+            _ => return MidiMessage::Other(bytes.to_vec()),
+        };
+        let channel = status & 0x0F;
+        let first = bytes[1] & 0x7F;
+        let second = bytes.get(2).map(|byte| byte & 0x7F);
+        match (status & 0xF0, second) {
+            (0x80, Some(velocity)) => MidiMessage::NoteOff { channel, note: first, velocity },
+            (0x90, Some(0)) => MidiMessage::NoteOff { channel, note: first, velocity: 0 },
+            (0x90, Some(velocity)) => MidiMessage::NoteOn { channel, note: first, velocity },
+            (0xA0, Some(pressure)) => MidiMessage::PolyAftertouch { channel, note: first, pressure },
+            (0xB0, Some(value)) => MidiMessage::ControlChange { channel, controller: first, value },
+            (0xC0, _) => MidiMessage::ProgramChange { channel, program: first },
+            (0xD0, _) => MidiMessage::ChannelAftertouch { channel, pressure: first },
+            (0xE0, Some(high)) => {
+                let value = (i16::from(high) << 7 | i16::from(first)) - 8192;
+                MidiMessage::PitchBend { channel, value }
+            }
+            _ => MidiMessage::Other(bytes.to_vec()),
+        }
+    }
+
+    /// Returns the bytes of the message.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let status = |kind: u8, channel: u8| kind | (channel & 0x0F);
+        match *self {
+            MidiMessage::NoteOff { channel, note, velocity } => vec![status(0x80, channel), note & 0x7F, velocity & 0x7F],
+            MidiMessage::NoteOn { channel, note, velocity } => vec![status(0x90, channel), note & 0x7F, velocity & 0x7F],
+            MidiMessage::PolyAftertouch { channel, note, pressure } => {
+                vec![status(0xA0, channel), note & 0x7F, pressure & 0x7F]
+            }
+            MidiMessage::ControlChange { channel, controller, value } => {
+                vec![status(0xB0, channel), controller & 0x7F, value & 0x7F]
+            }
+            MidiMessage::ProgramChange { channel, program } => vec![status(0xC0, channel), program & 0x7F],
+            MidiMessage::ChannelAftertouch { channel, pressure } => vec![status(0xD0, channel), pressure & 0x7F],
+            MidiMessage::PitchBend { channel, value } => {
+                let value = (i32::from(value) + 8192).clamp(0, 0x3FFF) as u16;
+                vec![status(0xE0, channel), (value & 0x7F) as u8, (value >> 7) as u8]
+            }
+            MidiMessage::Other(ref bytes) => bytes.clone(),
+        }
+    }
+}
+
+/// An error of MIDI access.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MidiError {
+    /// The browser doesn't have the Web MIDI API.
+    Unsupported,
+    /// A user or a policy of the page didn't allow MIDI.
+    Denied,
+    /// Another error with its message.
+    Failed(String),
+}
+
+impl fmt::Display for MidiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MidiError::Unsupported => write!(f, "the Web MIDI API isn't supported"),
+            MidiError::Denied => write!(f, "an access to MIDI devices was denied"),
+            MidiError::Failed(ref reason) => write!(f, "MIDI error: {}", reason),
+        }
+    }
+}
+
+/// Events of MIDI access.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MidiEvent {
+    /// Access is allowed, with all ports.
+    Ready(Vec<MidiPort>),
+    /// A device was plugged or unplugged, with all ports after it.
+    Ports(Vec<MidiPort>),
+    /// An input with the id sent the message.
+    Message(String, MidiMessage),
+    /// Access failed with the error.
+    Failed(MidiError),
+}
+
+/// A handle of MIDI access. Inputs are closed when it's canceled.
+pub struct MidiHandle(Option<Value>);
+
+impl MidiHandle {
+    /// Sends the message to the output with the id. Returns `false` if
+    /// access isn't allowed yet or there's no such output.
+    pub fn send(&self, output: &str, message: &MidiMessage) -> bool {
+        let handle = match self.0 {
+            Some(ref handle) => handle,
+            None => return false,
+        };
+        let sent: Value = js! {
+            var handle = @{handle};
+            var output = handle.access !== null ? handle.access.outputs.get(@{output}) : undefined;
+            if (!output) {
+                return false;
+            }
+            try {
+                output.send(@{message.to_bytes()});
+                return true;
+            } catch (error) {
+                return false;
+            }
+        };
+        sent == Value::Bool(true)
+    }
+
+    /// Returns all ports, it's empty until access is allowed.
+    pub fn ports(&self) -> Vec<MidiPort> {
+        match self.0 {
+            Some(ref handle) => ports_of(js! { return @{handle}.ports(); }),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Returns `true` if the browser has the Web MIDI API.
+pub fn is_supported() -> bool {
+    let supported: Value = js! {
+        return typeof navigator.requestMIDIAccess === "function";
+    };
+    supported == Value::Bool(true)
+}
+
+/// A service of MIDI devices.
+pub struct MidiService<MSG> {
+    sender: AppSender<MSG>,
+}
+
+impl<MSG: 'static> MidiService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self { sender }
+    }
+
+    /// Asks for an access to MIDI devices, with system exclusive messages
+    /// if `sysex` is `true`, and sends events of all inputs until the handle
+    /// is canceled.
+    pub fn open<F>(&mut self, sysex: bool, converter: F) -> MidiHandle
+    where
+        F: Fn(MidiEvent) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let callback = move |kind: String, value: Value| {
+            let event = match kind.as_str() {
+                "ready" => MidiEvent::Ready(ports_of(value)),
+                "ports" => MidiEvent::Ports(ports_of(value)),
+                "message" => {
+                    let port = js!( return @{&value}.port; ).into_string().unwrap_or_default();
+                    let bytes = Vec::<u8>::try_from(js!( return @{&value}.data; )).unwrap_or_default();
+                    MidiEvent::Message(port, MidiMessage::from_bytes(&bytes))
+                }
+                "NotSupportedError" => MidiEvent::Failed(MidiError::Unsupported),
+                "NotAllowedError" | "SecurityError" => MidiEvent::Failed(MidiError::Denied),
+                _ => MidiEvent::Failed(MidiError::Failed(value.into_string().unwrap_or_default())),
+            };
+            tx.send(converter(event));
+        };
+        let handle = js! {
+            var callback = @{callback};
+            var handle = {
+                access: null,
+                callback,
+            };
+            handle.ports = function() {
+                var ports = [];
+                if (handle.access !== null) {
+                    var add = function(port) {
+                        ports.push({
+                            id: port.id,
+                            name: port.name || "",
+                            manufacturer: port.manufacturer || "",
+                            kind: port.type,
+                            connected: port.state === "connected",
+                        });
+                    };
+                    handle.access.inputs.forEach(add);
+                    handle.access.outputs.forEach(add);
+                }
+                return ports;
+            };
+            var listen = function(input) {
+                input.onmidimessage = function(event) {
+                    if (handle.done !== true) {
+                        callback("message", { port: input.id, data: Array.from(event.data) });
+                    }
+                };
+            };
+            handle.stop = function() {
+                handle.done = true;
+                if (handle.access !== null) {
+                    handle.access.onstatechange = null;
+                    handle.access.inputs.forEach(function(input) {
+                        input.onmidimessage = null;
+                    });
+                }
+                callback.drop();
+            };
+            if (typeof navigator.requestMIDIAccess !== "function") {
+                Promise.resolve().then(function() {
+                    if (handle.done !== true) {
+                        callback("NotSupportedError", "");
+                        handle.stop();
+                    }
+                });
+                return handle;
+            }
+            navigator.requestMIDIAccess({ sysex: @{sysex} }).then(function(access) {
+                if (handle.done === true) {
+                    return;
+                }
+                handle.access = access;
+                access.inputs.forEach(listen);
+                access.onstatechange = function(event) {
+                    if (handle.done === true) {
+                        return;
+                    }
+                    if (event.port.type === "input") {
+                        listen(event.port);
+                    }
+                    callback("ports", handle.ports());
+                };
+                callback("ready", handle.ports());
+            }).catch(function(error) {
+                if (handle.done !== true) {
+                    callback(error && error.name || "failed", String(error && error.message || error));
+                    handle.stop();
+                }
+            });
+            return handle;
+        };
+        MidiHandle(Some(handle))
+    }
+}
+
+fn ports_of(value: Value) -> Vec<MidiPort> {
+    let ports = Vec::<Value>::try_from(value).unwrap_or_default();
+    ports.into_iter().map(|port| {
+        let text = |name: &str| js!( return @{&port}[@{name}]; ).into_string().unwrap_or_default();
+        MidiPort {
+            id: text("id"),
+            name: text("name"),
+            manufacturer: text("manufacturer"),
+            kind: if text("kind") == "output" { PortKind::Output } else { PortKind::Input },
+            connected: js!( return @{&port}.connected; ) == Value::Bool(true),
+        }
+    }).collect()
+}
+
+impl Task for MidiHandle {
+    fn is_active(&self) -> bool {
+        self.0.as_ref().map_or(false, |handle| !is_done(handle))
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel MIDI access twice");
+        js! { @(no_return)
+            @{handle}.stop();
+        }
+    }
+}
+
+impl Drop for MidiHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
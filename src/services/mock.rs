@@ -0,0 +1,2101 @@
+//! This module contains mock backends of services for tests. A test moves
+//! the time of timers and answers requests, so debounces, retries and
+//! reconnects are tested instantly:
+//!
+//! ```rust,ignore
+//! let clock = MockClock::new();
+//! let network = MockNetwork::new();
+//! let _clock = clock.install();
+//! let _network = network.install();
+//! let mut search = TestRenderer::<Search>::new(());
+//! search.type_text("input", "yew");
+//! clock.advance(Duration::from_millis(300));
+//! assert!(network.respond("/search?q=yew", 200, "[]"));
+//! search.flush();
+//! ```
+//!
+//! Messages of callbacks are queued to loops as usual, flush a test renderer
+//! to handle them. Mocks are installed for services created later,
+//! and the previous backends return when the guard is dropped.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::mem;
+use std::ops::Range;
+use std::rc::Rc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use format::{Storable, Restorable};
+use virtual_dom::NodeRef;
+use super::Task;
+use super::backend::{self, TimerBackend, StorageBackend, FetchBackend, WebSocketBackend, AnnouncerBackend,
+                    IntlBackend, MessagingBackend, VisibilityBackend, ConnectivityBackend, IntersectionBackend,
+                    LayoutBackend, RandomBackend, UnloadBackend, BeaconBackend, ActivityBackend,
+                    InstallBackend, MediaBackend, MediaControl, TextBackend, PrintBackend, SelectionBackend, HeadBackend, LongTaskBackend, DocumentBackend,
+                    Connection};
+#[cfg(feature = "devtools")]
+use super::backend::DevtoolsBackend;
+use super::announcer::Politeness;
+use super::fetch::{Request, Response};
+use super::messaging::Channel;
+use super::intersection::IntersectionOptions;
+use super::layout::LayoutEvent;
+use super::install::{InstallChoice, InstallEvent};
+use super::media::{MediaCommand, MediaError, MediaEvent, MediaState};
+use super::text::TextMetrics;
+use super::print::{PrintEvent, PrintJob};
+use super::selection::Selection;
+use super::long_task::LongTask;
+use super::document::DocumentEvent;
+use position::{Layout, Rect};
+use prefetch::Hint;
+use super::intl::{self, NumberOptions, NumberStyle, DateOptions, CalendarField, RelativeUnit};
+use date::Date;
+use super::storage::Scope;
+use super::websocket::WebSocketStatus;
+
+/// A guard of installed mocks. Restores previous backends when it's dropped.
+pub struct Installed {
+    restore: Option<Box<FnMut()>>,
+}
+
+impl Drop for Installed {
+    fn drop(&mut self) {
+        if let Some(mut restore) = self.restore.take() {
+            restore();
+        }
+    }
+}
+
+type TimerCallback = Rc<RefCell<Box<FnMut()>>>;
+
+struct Timer {
+    id: usize,
+    due: Duration,
+    period: Option<Duration>,
+    callback: TimerCallback,
+}
+
+#[derive(Default)]
+struct Clock {
+    now: Duration,
+    next_id: usize,
+    timers: Vec<Timer>,
+}
+
+impl Clock {
+    fn add(&mut self, delay: Duration, period: Option<Duration>, callback: Box<FnMut()>) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.timers.push(Timer {
+            id,
+            due: self.now + delay,
+            period,
+            callback: Rc::new(RefCell::new(callback)),
+        });
+        id
+    }
+
+    /// Takes the callback of the earliest timer due by the `until` time.
+    fn next_due(&mut self, until: Duration) -> Option<TimerCallback> {
+        let index = self.timers.iter().enumerate()
+            .filter(|&(_, timer)| timer.due <= until)
+            .min_by_key(|&(_, timer)| (timer.due, timer.id))
+            .map(|(index, _)| index)?;
+        let due = self.timers[index].due;
+        self.now = due;
+        let callback = self.timers[index].callback.clone();
+        match self.timers[index].period {
+            // An interval without a period is called once per millisecond
+            Some(period) => self.timers[index].due = due + period.max(Duration::from_millis(1)),
+            None => {
+                self.timers.remove(index);
+            }
+        }
+        Some(callback)
+    }
+}
+
+/// A clock of timers which moves only when a test advances it.
+#[derive(Clone, Default)]
+pub struct MockClock {
+    clock: Rc<RefCell<Clock>>,
+}
+
+impl MockClock {
+    /// Creates a clock at zero time.
+    pub fn new() -> Self {
+        MockClock::default()
+    }
+
+    /// Sets the clock as the backend of timers for services created later.
+    pub fn install(&self) -> Installed {
+        let previous = RefCell::new(Some(backend::set_timers(Rc::new(self.clone()))));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some(previous) = previous.borrow_mut().take() {
+                    backend::set_timers(previous);
+                }
+            })),
+        }
+    }
+
+    /// Returns the time passed since the clock was created.
+    pub fn now(&self) -> Duration {
+        self.clock.borrow().now
+    }
+
+    /// Returns the count of active timeouts and intervals.
+    pub fn pending(&self) -> usize {
+        self.clock.borrow().timers.len()
+    }
+
+    /// Moves the time forward and calls timers which are due in the order of
+    /// their time. Timers set by callbacks are called too if they're due.
+    pub fn advance(&self, duration: Duration) {
+        let until = self.now() + duration;
+        loop {
+            let callback = self.clock.borrow_mut().next_due(until);
+            match callback {
+                Some(callback) => (*callback.borrow_mut())(),
+                None => break,
+            }
+        }
+        self.clock.borrow_mut().now = until;
+    }
+}
+
+impl TimerBackend for MockClock {
+    fn timeout(&self, delay: Duration, callback: Box<FnMut()>) -> Box<Task> {
+        let id = self.clock.borrow_mut().add(delay, None, callback);
+        Box::new(MockTimer { clock: self.clock.clone(), id })
+    }
+
+    fn interval(&self, period: Duration, callback: Box<FnMut()>) -> Box<Task> {
+        let id = self.clock.borrow_mut().add(period, Some(period), callback);
+        Box::new(MockTimer { clock: self.clock.clone(), id })
+    }
+
+    fn monotonic(&self) -> Duration {
+        self.clock.borrow().now
+    }
+}
+
+struct MockTimer {
+    clock: Rc<RefCell<Clock>>,
+    id: usize,
+}
+
+impl Task for MockTimer {
+    fn is_active(&self) -> bool {
+        self.clock.borrow().timers.iter().any(|timer| timer.id == self.id)
+    }
+
+    fn cancel(&mut self) {
+        let id = self.id;
+        self.clock.borrow_mut().timers.retain(|timer| timer.id != id);
+    }
+}
+
+/// A storage which keeps data in memory.
+#[derive(Clone, Default)]
+pub struct MockStorage {
+    data: Rc<RefCell<HashMap<(Scope, String), String>>>,
+}
+
+impl MockStorage {
+    /// Creates an empty storage.
+    pub fn new() -> Self {
+        MockStorage::default()
+    }
+
+    /// Sets the storage as the backend of storage services created later.
+    pub fn install(&self) -> Installed {
+        let previous = RefCell::new(Some(backend::set_storage(Rc::new(self.clone()))));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some(previous) = previous.borrow_mut().take() {
+                    backend::set_storage(previous);
+                }
+            })),
+        }
+    }
+}
+
+impl StorageBackend for MockStorage {
+    fn get(&self, scope: Scope, key: &str) -> Option<String> {
+        self.data.borrow().get(&(scope, key.to_owned())).cloned()
+    }
+
+    fn set(&self, scope: Scope, key: &str, value: &str) {
+        self.data.borrow_mut().insert((scope, key.to_owned()), value.to_owned());
+    }
+
+    fn remove(&self, scope: Scope, key: &str) {
+        self.data.borrow_mut().remove(&(scope, key.to_owned()));
+    }
+}
+
+/// An announcer which records messages instead of reading them.
+#[derive(Clone, Default)]
+pub struct MockAnnouncer {
+    announcements: Rc<RefCell<Vec<(Politeness, String)>>>,
+}
+
+impl MockAnnouncer {
+    /// Creates an announcer without messages.
+    pub fn new() -> Self {
+        MockAnnouncer::default()
+    }
+
+    /// Sets the announcer as the backend of announcer services created later.
+    pub fn install(&self) -> Installed {
+        let previous = RefCell::new(Some(backend::set_announcer(Rc::new(self.clone()))));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some(previous) = previous.borrow_mut().take() {
+                    backend::set_announcer(previous);
+                }
+            })),
+        }
+    }
+
+    /// Returns announced messages in the order they were announced.
+    pub fn announcements(&self) -> Vec<(Politeness, String)> {
+        self.announcements.borrow().clone()
+    }
+
+    /// Returns the text of the last message.
+    pub fn last(&self) -> Option<String> {
+        self.announcements.borrow().last().map(|(_, text)| text.clone())
+    }
+}
+
+impl AnnouncerBackend for MockAnnouncer {
+    fn announce(&self, politeness: Politeness, text: &str) {
+        self.announcements.borrow_mut().push((politeness, text.to_owned()));
+    }
+}
+
+/// Formatters which ignore locales and format values like `en-US` without
+/// symbols of currencies: `1,234.5`, `USD 1,234.50`, `2024-01-02 13:45` (UTC),
+/// `January 2024`, `Tue` and `3 minutes ago`. Outputs of tests don't depend
+/// on a browser then.
+#[derive(Clone)]
+pub struct MockIntl {
+    now: Rc<Cell<SystemTime>>,
+    first_weekday: Rc<Cell<u32>>,
+}
+
+impl Default for MockIntl {
+    fn default() -> Self {
+        MockIntl {
+            now: Rc::new(Cell::new(UNIX_EPOCH)),
+            first_weekday: Rc::new(Cell::new(1)),
+        }
+    }
+}
+
+const MONTHS: [&str; 12] = ["January", "February", "March", "April", "May", "June", "July",
+                            "August", "September", "October", "November", "December"];
+const WEEKDAYS: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+
+impl MockIntl {
+    /// Creates formatters with the current time at the Unix epoch.
+    pub fn new() -> Self {
+        MockIntl::default()
+    }
+
+    /// Sets the formatters as the backend of intl services created later.
+    pub fn install(&self) -> Installed {
+        let previous = RefCell::new(Some(backend::set_intl(Rc::new(self.clone()))));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some(previous) = previous.borrow_mut().take() {
+                    backend::set_intl(previous);
+                }
+            })),
+        }
+    }
+
+    /// Sets the time which relative times are measured from. Today is
+    /// its date in UTC.
+    pub fn set_now(&self, now: SystemTime) {
+        self.now.set(now);
+    }
+
+    /// Sets the ISO 8601 weekday which weeks start on, Monday by default.
+    pub fn set_first_weekday(&self, weekday: u32) {
+        self.first_weekday.set(weekday);
+    }
+}
+
+impl IntlBackend for MockIntl {
+    fn format_number(&self, _: Option<&str>, number: f64, options: &NumberOptions) -> String {
+        let (value, default_max) = match options.style {
+            NumberStyle::Decimal => (number, 3),
+            NumberStyle::Percent => (number * 100.0, 0),
+            NumberStyle::Currency(_) => (number, 2),
+        };
+        let default_min = if let NumberStyle::Currency(_) = options.style { 2 } else { 0 };
+        let max = options.maximum_fraction_digits.map_or(default_max, usize::from);
+        let min = options.minimum_fraction_digits.map_or(default_min, usize::from).min(max);
+        let fixed = format!("{:.*}", max, value.abs());
+        let mut parts = fixed.splitn(2, '.');
+        let integer = parts.next().unwrap_or("0");
+        let mut fraction = parts.next().unwrap_or("").to_owned();
+        while fraction.len() > min && fraction.ends_with('0') {
+            fraction.pop();
+        }
+        let mut digits = String::new();
+        for (i, digit) in integer.chars().enumerate() {
+            if options.grouping && i > 0 && (integer.len() - i) % 3 == 0 {
+                digits.push(',');
+            }
+            digits.push(digit);
+        }
+        if !fraction.is_empty() {
+            digits.push('.');
+            digits.push_str(&fraction);
+        }
+        let sign = if value < 0.0 && digits.chars().any(|c| c.is_ascii_digit() && c != '0') { "-" } else { "" };
+        match options.style {
+            NumberStyle::Decimal => format!("{}{}", sign, digits),
+            NumberStyle::Percent => format!("{}{}%", sign, digits),
+            NumberStyle::Currency(ref currency) => format!("{}{} {}", sign, currency, digits),
+        }
+    }
+
+    fn format_date(&self, _: Option<&str>, time: SystemTime, options: &DateOptions) -> String {
+        let ms = intl::to_timestamp(time);
+        let days = (ms / 86_400_000.0).floor() as i64;
+        let minutes = ((ms - days as f64 * 86_400_000.0) / 60_000.0) as i64;
+        let date = Date::from_days(days).to_string();
+        let clock = format!("{:02}:{:02}", minutes / 60, minutes % 60);
+        match (options.date.is_some(), options.time.is_some()) {
+            (true, true) => format!("{} {}", date, clock),
+            (false, true) => clock,
+            _ => date,
+        }
+    }
+
+    fn format_calendar(&self, _: Option<&str>, date: Date, field: CalendarField) -> String {
+        let weekday = WEEKDAYS[date.weekday() as usize - 1];
+        match field {
+            CalendarField::MonthYear => format!("{} {}", MONTHS[date.month() as usize - 1], date.year()),
+            CalendarField::Weekday => weekday.to_owned(),
+            CalendarField::ShortWeekday => weekday[..3].to_owned(),
+        }
+    }
+
+    fn format_relative(&self, _: Option<&str>, value: f64, unit: RelativeUnit) -> String {
+        let count = value.abs();
+        let plural = if count == 1.0 { "" } else { "s" };
+        if value == 0.0 {
+            "now".to_owned()
+        } else if value < 0.0 {
+            format!("{} {}{} ago", count, unit.as_str(), plural)
+        } else {
+            format!("in {} {}{}", count, unit.as_str(), plural)
+        }
+    }
+
+    fn first_weekday(&self, _: Option<&str>) -> u32 {
+        self.first_weekday.get()
+    }
+
+    fn now(&self) -> SystemTime {
+        self.now.get()
+    }
+}
+
+/// A request which was sent with a mock network.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockRequest {
+    /// A method, like `GET`.
+    pub method: String,
+    /// The URI of the request.
+    pub uri: String,
+    /// Headers of the request.
+    pub headers: Vec<(String, String)>,
+    /// The body of the request.
+    pub body: Option<String>,
+}
+
+struct PendingRequest {
+    id: usize,
+    request: MockRequest,
+    callback: Box<FnMut(Response<Restorable>)>,
+}
+
+struct Socket {
+    url: String,
+    on_message: Box<FnMut(String)>,
+    on_status: Box<FnMut(WebSocketStatus)>,
+    sent: Vec<String>,
+    closed: bool,
+}
+
+#[derive(Default)]
+struct Network {
+    next_id: usize,
+    requests: Vec<PendingRequest>,
+    sockets: Vec<Rc<RefCell<Socket>>>,
+}
+
+/// A network of fetches and websockets which a test answers.
+#[derive(Clone, Default)]
+pub struct MockNetwork {
+    network: Rc<RefCell<Network>>,
+}
+
+impl MockNetwork {
+    /// Creates a network without requests.
+    pub fn new() -> Self {
+        MockNetwork::default()
+    }
+
+    /// Sets the network as the backend of fetch and websocket services created later.
+    pub fn install(&self) -> Installed {
+        let fetch = backend::set_fetch(Rc::new(self.clone()));
+        let websocket = backend::set_websocket(Rc::new(self.clone()));
+        let previous = RefCell::new(Some((fetch, websocket)));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some((fetch, websocket)) = previous.borrow_mut().take() {
+                    backend::set_fetch(fetch);
+                    backend::set_websocket(websocket);
+                }
+            })),
+        }
+    }
+
+    /// Returns requests which wait for responses in the order they were sent.
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.network.borrow().requests.iter().map(|pending| pending.request.clone()).collect()
+    }
+
+    /// Returns the count of requests which wait for responses.
+    pub fn pending(&self) -> usize {
+        self.network.borrow().requests.len()
+    }
+
+    /// Answers the oldest request to the `uri` with the status and the body.
+    /// Returns `false` if there is no such request.
+    pub fn respond(&self, uri: &str, status: u16, body: &str) -> bool {
+        let response = Response::builder()
+            .status(status)
+            .body(Ok(body.to_owned()))
+            .expect("wrong status of a mock response");
+        self.respond_with(uri, response)
+    }
+
+    /// Answers the oldest request to the `uri` with the response.
+    /// A body with `Err` means that the body wasn't read completely.
+    pub fn respond_with(&self, uri: &str, response: Response<Restorable>) -> bool {
+        let pending = {
+            let mut network = self.network.borrow_mut();
+            let index = network.requests.iter().position(|pending| pending.request.uri == uri);
+            index.map(|index| network.requests.remove(index))
+        };
+        match pending {
+            Some(mut pending) => {
+                (pending.callback)(response);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the count of websocket connections which were opened to the `url`.
+    pub fn connections(&self, url: &str) -> usize {
+        self.network.borrow().sockets.iter().filter(|socket| socket.borrow().url == url).count()
+    }
+
+    /// Returns the last connection to the `url` which wasn't closed.
+    pub fn socket(&self, url: &str) -> Option<MockSocket> {
+        self.network.borrow().sockets.iter().rev()
+            .find(|socket| {
+                let socket = socket.borrow();
+                socket.url == url && !socket.closed
+            })
+            .map(|socket| MockSocket { socket: socket.clone() })
+    }
+}
+
+impl FetchBackend for MockNetwork {
+    fn fetch(&self, request: Request<Storable>, callback: Box<FnMut(Response<Restorable>)>) -> Box<Task> {
+        let (parts, body) = request.into_parts();
+        let headers = parts.headers.iter()
+            .map(|(name, value)| (name.as_str().to_owned(), value.to_str().unwrap_or_default().to_owned()))
+            .collect();
+        let request = MockRequest {
+            method: parts.method.as_str().to_owned(),
+            uri: parts.uri.to_string(),
+            headers,
+            body,
+        };
+        let mut network = self.network.borrow_mut();
+        let id = network.next_id;
+        network.next_id += 1;
+        network.requests.push(PendingRequest { id, request, callback });
+        Box::new(MockFetch { network: self.network.clone(), id })
+    }
+}
+
+struct MockFetch {
+    network: Rc<RefCell<Network>>,
+    id: usize,
+}
+
+impl Task for MockFetch {
+    fn is_active(&self) -> bool {
+        self.network.borrow().requests.iter().any(|pending| pending.id == self.id)
+    }
+
+    fn cancel(&mut self) {
+        let id = self.id;
+        self.network.borrow_mut().requests.retain(|pending| pending.id != id);
+    }
+}
+
+impl WebSocketBackend for MockNetwork {
+    fn connect(&self, url: &str, on_message: Box<FnMut(String)>, on_status: Box<FnMut(WebSocketStatus)>)
+        -> Box<Connection>
+    {
+        let socket = Rc::new(RefCell::new(Socket {
+            url: url.to_owned(),
+            on_message,
+            on_status,
+            sent: Vec::new(),
+            closed: false,
+        }));
+        self.network.borrow_mut().sockets.push(socket.clone());
+        Box::new(MockSocket { socket })
+    }
+}
+
+/// A websocket connection of a mock network. A test plays the server with it.
+#[derive(Clone)]
+pub struct MockSocket {
+    socket: Rc<RefCell<Socket>>,
+}
+
+impl MockSocket {
+    /// Notifies the client that the connection was opened.
+    pub fn open(&self) {
+        self.notify(WebSocketStatus::Opened);
+    }
+
+    /// Sends data from the server to the client.
+    pub fn receive(&self, data: &str) {
+        if self.is_closed() {
+            panic!("data received by a closed websocket connection");
+        }
+        // The client could send data or close the connection while it handles the data
+        let mut on_message = mem::replace(&mut self.socket.borrow_mut().on_message, Box::new(|_| ()));
+        on_message(data.to_owned());
+        self.socket.borrow_mut().on_message = on_message;
+    }
+
+    /// Closes the connection from the server side.
+    pub fn close(&self) {
+        if !self.socket.borrow().closed {
+            self.socket.borrow_mut().closed = true;
+            self.notify(WebSocketStatus::Closed);
+        }
+    }
+
+    fn notify(&self, status: WebSocketStatus) {
+        let mut on_status = mem::replace(&mut self.socket.borrow_mut().on_status, Box::new(|_| ()));
+        on_status(status);
+        self.socket.borrow_mut().on_status = on_status;
+    }
+
+    /// Returns data which the client sent.
+    pub fn sent(&self) -> Vec<String> {
+        self.socket.borrow().sent.clone()
+    }
+
+    /// Returns `true` if the connection was closed.
+    pub fn is_closed(&self) -> bool {
+        self.socket.borrow().closed
+    }
+}
+
+impl Task for MockSocket {
+    fn is_active(&self) -> bool {
+        !self.is_closed()
+    }
+
+    fn cancel(&mut self) {
+        self.close();
+    }
+}
+
+impl Connection for MockSocket {
+    fn send(&mut self, data: String) {
+        let mut socket = self.socket.borrow_mut();
+        if socket.closed {
+            panic!("can't send data to the closed websocket connection");
+        }
+        socket.sent.push(data);
+    }
+}
+
+struct Subscription {
+    id: usize,
+    channel: Channel,
+    callback: Box<FnMut(String)>,
+}
+
+#[derive(Default)]
+struct Windows {
+    next_id: usize,
+    posted: Vec<(Channel, String)>,
+    subscriptions: Vec<Subscription>,
+}
+
+/// Windows which record posted messages and which a test sends messages from.
+#[derive(Clone, Default)]
+pub struct MockMessaging {
+    windows: Rc<RefCell<Windows>>,
+}
+
+impl MockMessaging {
+    /// Creates windows without messages.
+    pub fn new() -> Self {
+        MockMessaging::default()
+    }
+
+    /// Sets the windows as the backend of messaging services created later.
+    pub fn install(&self) -> Installed {
+        let previous = RefCell::new(Some(backend::set_messaging(Rc::new(self.clone()))));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some(previous) = previous.borrow_mut().take() {
+                    backend::set_messaging(previous);
+                }
+            })),
+        }
+    }
+
+    /// Returns posted messages with their channels in the order they were posted.
+    pub fn posted(&self) -> Vec<(Channel, String)> {
+        self.windows.borrow().posted.clone()
+    }
+
+    /// Sends data from the window and the origin of the channel. Returns `false`
+    /// if nobody listens to it, like a browser drops messages of another origin.
+    pub fn receive(&self, channel: &Channel, data: &str) -> bool {
+        let mut windows = self.windows.borrow_mut();
+        let mut received = false;
+        for subscription in windows.subscriptions.iter_mut().filter(|subscription| subscription.channel == *channel) {
+            (subscription.callback)(data.to_owned());
+            received = true;
+        }
+        received
+    }
+}
+
+impl MessagingBackend for MockMessaging {
+    fn post(&self, channel: &Channel, data: String) {
+        self.windows.borrow_mut().posted.push((channel.clone(), data));
+    }
+
+    fn listen(&self, channel: &Channel, callback: Box<FnMut(String)>) -> Box<Task> {
+        let mut windows = self.windows.borrow_mut();
+        let id = windows.next_id;
+        windows.next_id += 1;
+        windows.subscriptions.push(Subscription { id, channel: channel.clone(), callback });
+        Box::new(MockSubscription { windows: self.windows.clone(), id })
+    }
+}
+
+struct MockSubscription {
+    windows: Rc<RefCell<Windows>>,
+    id: usize,
+}
+
+impl Task for MockSubscription {
+    fn is_active(&self) -> bool {
+        self.windows.borrow().subscriptions.iter().any(|subscription| subscription.id == self.id)
+    }
+
+    fn cancel(&mut self) {
+        let id = self.id;
+        self.windows.borrow_mut().subscriptions.retain(|subscription| subscription.id != id);
+    }
+}
+
+type SwitchListener = Rc<RefCell<Box<FnMut(bool)>>>;
+
+#[derive(Default)]
+struct Page {
+    hidden: Cell<bool>,
+    next_id: Cell<usize>,
+    listeners: RefCell<Vec<(usize, SwitchListener)>>,
+}
+
+/// A page which a test hides and shows.
+#[derive(Clone, Default)]
+pub struct MockVisibility {
+    page: Rc<Page>,
+}
+
+impl MockVisibility {
+    /// Creates a visible page.
+    pub fn new() -> Self {
+        MockVisibility::default()
+    }
+
+    /// Sets the page as the backend of the visibility for services created later.
+    pub fn install(&self) -> Installed {
+        let previous = RefCell::new(Some(backend::set_visibility(Rc::new(self.clone()))));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some(previous) = previous.borrow_mut().take() {
+                    backend::set_visibility(previous);
+                }
+            })),
+        }
+    }
+
+    /// Hides or shows the page and calls listeners if the visibility changed.
+    pub fn set_hidden(&self, hidden: bool) {
+        if self.page.hidden.replace(hidden) == hidden {
+            return;
+        }
+        let listeners: Vec<_> = self.page.listeners.borrow().iter()
+            .map(|(_, callback)| callback.clone())
+            .collect();
+        for callback in listeners {
+            (*callback.borrow_mut())(hidden);
+        }
+    }
+}
+
+impl VisibilityBackend for MockVisibility {
+    fn is_hidden(&self) -> bool {
+        self.page.hidden.get()
+    }
+
+    fn listen(&self, callback: Box<FnMut(bool)>) -> Box<Task> {
+        let id = self.page.next_id.get();
+        self.page.next_id.set(id + 1);
+        self.page.listeners.borrow_mut().push((id, Rc::new(RefCell::new(callback))));
+        Box::new(MockVisibilityListener { page: self.page.clone(), id })
+    }
+}
+
+struct MockVisibilityListener {
+    page: Rc<Page>,
+    id: usize,
+}
+
+impl Task for MockVisibilityListener {
+    fn is_active(&self) -> bool {
+        self.page.listeners.borrow().iter().any(|&(id, _)| id == self.id)
+    }
+
+    fn cancel(&mut self) {
+        let id = self.id;
+        self.page.listeners.borrow_mut().retain(|&(listener, _)| listener != id);
+    }
+}
+
+#[derive(Default)]
+struct Uplink {
+    offline: Cell<bool>,
+    next_id: Cell<usize>,
+    listeners: RefCell<Vec<(usize, SwitchListener)>>,
+}
+
+/// A connection of the browser which a test takes down and restores.
+#[derive(Clone, Default)]
+pub struct MockConnectivity {
+    link: Rc<Uplink>,
+}
+
+impl MockConnectivity {
+    /// Creates an online browser.
+    pub fn new() -> Self {
+        MockConnectivity::default()
+    }
+
+    /// Sets the connection as the backend of the connectivity for services created later.
+    pub fn install(&self) -> Installed {
+        let previous = RefCell::new(Some(backend::set_connectivity(Rc::new(self.clone()))));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some(previous) = previous.borrow_mut().take() {
+                    backend::set_connectivity(previous);
+                }
+            })),
+        }
+    }
+
+    /// Takes the browser offline or online and calls listeners if the connectivity changed.
+    pub fn set_online(&self, online: bool) {
+        let was_online = !self.link.offline.replace(!online);
+        if was_online == online {
+            return;
+        }
+        let listeners: Vec<_> = self.link.listeners.borrow().iter()
+            .map(|(_, callback)| callback.clone())
+            .collect();
+        for callback in listeners {
+            (*callback.borrow_mut())(online);
+        }
+    }
+}
+
+impl ConnectivityBackend for MockConnectivity {
+    fn is_online(&self) -> bool {
+        !self.link.offline.get()
+    }
+
+    fn listen(&self, callback: Box<FnMut(bool)>) -> Box<Task> {
+        let id = self.link.next_id.get();
+        self.link.next_id.set(id + 1);
+        self.link.listeners.borrow_mut().push((id, Rc::new(RefCell::new(callback))));
+        Box::new(MockConnectivityListener { link: self.link.clone(), id })
+    }
+}
+
+struct MockConnectivityListener {
+    link: Rc<Uplink>,
+    id: usize,
+}
+
+impl Task for MockConnectivityListener {
+    fn is_active(&self) -> bool {
+        self.link.listeners.borrow().iter().any(|&(id, _)| id == self.id)
+    }
+
+    fn cancel(&mut self) {
+        let id = self.id;
+        self.link.listeners.borrow_mut().retain(|&(listener, _)| listener != id);
+    }
+}
+
+type Observer = Rc<RefCell<Box<FnMut(bool)>>>;
+
+#[derive(Default)]
+struct Viewport {
+    // Observers in order of observation, canceled ones are `None`
+    observers: RefCell<Vec<Option<Observer>>>,
+}
+
+/// A viewport which a test scrolls elements into and out of. Elements are
+/// told apart by the order in which they were observed.
+#[derive(Clone, Default)]
+pub struct MockIntersection {
+    viewport: Rc<Viewport>,
+}
+
+impl MockIntersection {
+    /// Creates a viewport without observed elements.
+    pub fn new() -> Self {
+        MockIntersection::default()
+    }
+
+    /// Sets the viewport as the backend of intersections for services created later.
+    pub fn install(&self) -> Installed {
+        let previous = RefCell::new(Some(backend::set_intersection(Rc::new(self.clone()))));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some(previous) = previous.borrow_mut().take() {
+                    backend::set_intersection(previous);
+                }
+            })),
+        }
+    }
+
+    /// Returns how many elements were observed, including canceled observers.
+    pub fn observed(&self) -> usize {
+        self.viewport.observers.borrow().len()
+    }
+
+    /// Returns `true` if the element with the index is still observed.
+    pub fn is_observed(&self, index: usize) -> bool {
+        self.viewport.observers.borrow().get(index).map_or(false, Option::is_some)
+    }
+
+    /// Scrolls the element with the index in order of observation into
+    /// the viewport or out of it. Returns `false` if it isn't observed.
+    pub fn set_visible(&self, index: usize, visible: bool) -> bool {
+        let observer = self.viewport.observers.borrow().get(index).cloned().and_then(|observer| observer);
+        match observer {
+            Some(callback) => {
+                (*callback.borrow_mut())(visible);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl IntersectionBackend for MockIntersection {
+    fn observe(&self, _: NodeRef, _: &IntersectionOptions, callback: Box<FnMut(bool)>) -> Box<Task> {
+        let mut observers = self.viewport.observers.borrow_mut();
+        observers.push(Some(Rc::new(RefCell::new(callback))));
+        Box::new(MockObserver { viewport: self.viewport.clone(), index: observers.len() - 1 })
+    }
+}
+
+struct MockObserver {
+    viewport: Rc<Viewport>,
+    index: usize,
+}
+
+impl Task for MockObserver {
+    fn is_active(&self) -> bool {
+        self.viewport.observers.borrow()[self.index].is_some()
+    }
+
+    fn cancel(&mut self) {
+        self.viewport.observers.borrow_mut()[self.index] = None;
+    }
+}
+
+type Tracker = (String, Rc<RefCell<Box<FnMut(LayoutEvent)>>>);
+
+#[derive(Default)]
+struct Screen {
+    // Anchors and callbacks in order of tracking, canceled ones are `None`
+    trackers: RefCell<Vec<Option<Tracker>>>,
+}
+
+/// A screen where a test lays out floating elements and presses outside of
+/// them. Elements are told apart by the order in which they were tracked.
+#[derive(Clone, Default)]
+pub struct MockLayout {
+    screen: Rc<Screen>,
+}
+
+impl MockLayout {
+    /// Creates a screen without tracked elements.
+    pub fn new() -> Self {
+        MockLayout::default()
+    }
+
+    /// Sets the screen as the backend of layouts for services created later.
+    pub fn install(&self) -> Installed {
+        let previous = RefCell::new(Some(backend::set_layout(Rc::new(self.clone()))));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some(previous) = previous.borrow_mut().take() {
+                    backend::set_layout(previous);
+                }
+            })),
+        }
+    }
+
+    /// Returns how many elements were tracked, including canceled tracking.
+    pub fn tracked(&self) -> usize {
+        self.screen.trackers.borrow().len()
+    }
+
+    /// Returns `true` if the element with the index is still tracked.
+    pub fn is_tracked(&self, index: usize) -> bool {
+        self.screen.trackers.borrow().get(index).map_or(false, Option::is_some)
+    }
+
+    /// Returns the id of the anchor of the element with the index if it's still tracked.
+    pub fn anchor(&self, index: usize) -> Option<String> {
+        self.screen.trackers.borrow().get(index).and_then(|tracker| tracker.as_ref().map(|tracker| tracker.0.clone()))
+    }
+
+    /// Measures the element with the index in order of tracking with the layout.
+    /// Returns `false` if it isn't tracked.
+    pub fn measure(&self, index: usize, layout: Layout) -> bool {
+        self.send(index, LayoutEvent::Measured(layout))
+    }
+
+    /// Presses a pointer outside of the element with the index and its anchor.
+    /// Returns `false` if it isn't tracked.
+    pub fn press_outside(&self, index: usize) -> bool {
+        self.send(index, LayoutEvent::Outside)
+    }
+
+    fn send(&self, index: usize, event: LayoutEvent) -> bool {
+        let tracker = self.screen.trackers.borrow().get(index).cloned().and_then(|tracker| tracker);
+        match tracker {
+            Some((_, callback)) => {
+                (*callback.borrow_mut())(event);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl LayoutBackend for MockLayout {
+    fn track(&self, anchor: String, _: NodeRef, callback: Box<FnMut(LayoutEvent)>) -> Box<Task> {
+        let mut trackers = self.screen.trackers.borrow_mut();
+        trackers.push(Some((anchor, Rc::new(RefCell::new(callback)))));
+        Box::new(MockTracker { screen: self.screen.clone(), index: trackers.len() - 1 })
+    }
+}
+
+struct MockTracker {
+    screen: Rc<Screen>,
+    index: usize,
+}
+
+impl Task for MockTracker {
+    fn is_active(&self) -> bool {
+        self.screen.trackers.borrow()[self.index].is_some()
+    }
+
+    fn cancel(&mut self) {
+        self.screen.trackers.borrow_mut()[self.index] = None;
+    }
+}
+
+/// A source of random bytes which are predictable: 0, 1, 2 and so on.
+#[derive(Clone, Default)]
+pub struct MockRandom {
+    next: Rc<Cell<u8>>,
+}
+
+impl MockRandom {
+    /// Creates a source which starts with 0.
+    pub fn new() -> Self {
+        MockRandom::default()
+    }
+
+    /// Sets the source as the backend of random bytes for services created later.
+    pub fn install(&self) -> Installed {
+        let previous = RefCell::new(Some(backend::set_random(Rc::new(self.clone()))));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some(previous) = previous.borrow_mut().take() {
+                    backend::set_random(previous);
+                }
+            })),
+        }
+    }
+}
+
+impl RandomBackend for MockRandom {
+    fn fill(&self, bytes: &mut [u8]) {
+        for byte in bytes {
+            *byte = self.next.get();
+            self.next.set(byte.wrapping_add(1));
+        }
+    }
+}
+
+type UnloadListener = Rc<RefCell<Box<FnMut() -> bool>>>;
+
+#[derive(Default)]
+struct Unloads {
+    next_id: Cell<usize>,
+    listeners: RefCell<Vec<(usize, UnloadListener)>>,
+}
+
+/// A page which a test tries to reload.
+#[derive(Clone, Default)]
+pub struct MockUnload {
+    page: Rc<Unloads>,
+}
+
+impl MockUnload {
+    /// Creates a page without listeners.
+    pub fn new() -> Self {
+        MockUnload::default()
+    }
+
+    /// Sets the page as the backend of unloads for services created later.
+    pub fn install(&self) -> Installed {
+        let previous = RefCell::new(Some(backend::set_unload(Rc::new(self.clone()))));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some(previous) = previous.borrow_mut().take() {
+                    backend::set_unload(previous);
+                }
+            })),
+        }
+    }
+
+    /// Calls listeners like a reload of the page. Returns `true` if a browser
+    /// would ask a user to confirm leaving.
+    pub fn unload(&self) -> bool {
+        let listeners: Vec<_> = self.page.listeners.borrow().iter()
+            .map(|(_, callback)| callback.clone())
+            .collect();
+        // Every listener is called, like in a browser
+        let mut prompt = false;
+        for callback in listeners {
+            prompt |= (*callback.borrow_mut())();
+        }
+        prompt
+    }
+
+    /// Returns the number of listeners.
+    pub fn listeners(&self) -> usize {
+        self.page.listeners.borrow().len()
+    }
+}
+
+impl UnloadBackend for MockUnload {
+    fn listen(&self, callback: Box<FnMut() -> bool>) -> Box<Task> {
+        let id = self.page.next_id.get();
+        self.page.next_id.set(id + 1);
+        self.page.listeners.borrow_mut().push((id, Rc::new(RefCell::new(callback))));
+        Box::new(MockUnloadListener { page: self.page.clone(), id })
+    }
+}
+
+struct MockUnloadListener {
+    page: Rc<Unloads>,
+    id: usize,
+}
+
+impl Task for MockUnloadListener {
+    fn is_active(&self) -> bool {
+        self.page.listeners.borrow().iter().any(|&(id, _)| id == self.id)
+    }
+
+    fn cancel(&mut self) {
+        let id = self.id;
+        self.page.listeners.borrow_mut().retain(|&(listener, _)| listener != id);
+    }
+}
+
+#[derive(Default)]
+struct Beacons {
+    sent: Vec<(String, String)>,
+    refused: bool,
+}
+
+/// A browser which records beacons.
+#[derive(Clone, Default)]
+pub struct MockBeacon {
+    beacons: Rc<RefCell<Beacons>>,
+}
+
+impl MockBeacon {
+    /// Creates a browser which queues all beacons.
+    pub fn new() -> Self {
+        MockBeacon::default()
+    }
+
+    /// Sets the browser as the backend of beacons for services created later.
+    pub fn install(&self) -> Installed {
+        let previous = RefCell::new(Some(backend::set_beacon(Rc::new(self.clone()))));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some(previous) = previous.borrow_mut().take() {
+                    backend::set_beacon(previous);
+                }
+            })),
+        }
+    }
+
+    /// Returns urls and data of queued beacons in the order they were sent.
+    pub fn sent(&self) -> Vec<(String, String)> {
+        self.beacons.borrow().sent.clone()
+    }
+
+    /// Refuses beacons like a browser without `sendBeacon` does.
+    pub fn set_refused(&self, refused: bool) {
+        self.beacons.borrow_mut().refused = refused;
+    }
+}
+
+impl BeaconBackend for MockBeacon {
+    fn send(&self, url: &str, data: &str) -> bool {
+        let mut beacons = self.beacons.borrow_mut();
+        if beacons.refused {
+            return false;
+        }
+        beacons.sent.push((url.to_owned(), data.to_owned()));
+        true
+    }
+}
+
+type ActivityListener = Rc<RefCell<Box<FnMut()>>>;
+
+#[derive(Default)]
+struct Activity {
+    next_id: Cell<usize>,
+    listeners: RefCell<Vec<(usize, ActivityListener)>>,
+}
+
+/// A user whose input a test simulates.
+#[derive(Clone, Default)]
+pub struct MockActivity {
+    user: Rc<Activity>,
+}
+
+impl MockActivity {
+    /// Creates a user without listeners.
+    pub fn new() -> Self {
+        MockActivity::default()
+    }
+
+    /// Sets the user as the backend of activity for services created later.
+    pub fn install(&self) -> Installed {
+        let previous = RefCell::new(Some(backend::set_activity(Rc::new(self.clone()))));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some(previous) = previous.borrow_mut().take() {
+                    backend::set_activity(previous);
+                }
+            })),
+        }
+    }
+
+    /// Calls listeners like a user moved the mouse.
+    pub fn act(&self) {
+        let listeners: Vec<_> = self.user.listeners.borrow().iter()
+            .map(|(_, callback)| callback.clone())
+            .collect();
+        for callback in listeners {
+            (*callback.borrow_mut())();
+        }
+    }
+
+    /// Returns the number of listeners.
+    pub fn listeners(&self) -> usize {
+        self.user.listeners.borrow().len()
+    }
+}
+
+impl ActivityBackend for MockActivity {
+    fn listen(&self, callback: Box<FnMut()>) -> Box<Task> {
+        let id = self.user.next_id.get();
+        self.user.next_id.set(id + 1);
+        self.user.listeners.borrow_mut().push((id, Rc::new(RefCell::new(callback))));
+        Box::new(MockActivityListener { user: self.user.clone(), id })
+    }
+}
+
+struct MockActivityListener {
+    user: Rc<Activity>,
+    id: usize,
+}
+
+impl Task for MockActivityListener {
+    fn is_active(&self) -> bool {
+        self.user.listeners.borrow().iter().any(|&(id, _)| id == self.id)
+    }
+
+    fn cancel(&mut self) {
+        let id = self.id;
+        self.user.listeners.borrow_mut().retain(|&(listener, _)| listener != id);
+    }
+}
+
+type InstallListener = Rc<RefCell<Box<FnMut(InstallEvent)>>>;
+type InstallPrompt = Box<FnMut(InstallChoice)>;
+
+#[derive(Default)]
+struct Install {
+    offered: Cell<bool>,
+    next_id: Cell<usize>,
+    listeners: RefCell<Vec<(usize, InstallListener)>>,
+    prompt: RefCell<Option<InstallPrompt>>,
+}
+
+/// A browser which offers to install an app when a test asks it.
+#[derive(Clone, Default)]
+pub struct MockInstall {
+    browser: Rc<Install>,
+}
+
+impl MockInstall {
+    /// Creates a browser which didn't offer anything.
+    pub fn new() -> Self {
+        MockInstall::default()
+    }
+
+    /// Sets the browser as the backend of the install prompt for services created later.
+    pub fn install(&self) -> Installed {
+        let previous = RefCell::new(Some(backend::set_install(Rc::new(self.clone()))));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some(previous) = previous.borrow_mut().take() {
+                    backend::set_install(previous);
+                }
+            })),
+        }
+    }
+
+    /// Offers to install the app like `beforeinstallprompt`.
+    pub fn offer(&self) {
+        self.browser.offered.set(true);
+        self.notify(InstallEvent::Installable);
+    }
+
+    /// Returns `true` if the prompt is shown and waits for a choice.
+    pub fn is_prompted(&self) -> bool {
+        self.browser.prompt.borrow().is_some()
+    }
+
+    /// Answers the shown prompt like a user. The app is installed if the
+    /// choice is `Accepted`. Returns `false` if the prompt isn't shown.
+    pub fn choose(&self, choice: InstallChoice) -> bool {
+        let callback = self.browser.prompt.borrow_mut().take();
+        match callback {
+            Some(mut callback) => {
+                callback(choice);
+                if choice == InstallChoice::Accepted {
+                    self.notify(InstallEvent::Installed);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Installs the app like from a menu of the browser.
+    pub fn complete(&self) {
+        self.browser.offered.set(false);
+        self.notify(InstallEvent::Installed);
+    }
+
+    /// Returns the number of listeners.
+    pub fn listeners(&self) -> usize {
+        self.browser.listeners.borrow().len()
+    }
+
+    fn notify(&self, event: InstallEvent) {
+        let listeners: Vec<_> = self.browser.listeners.borrow().iter()
+            .map(|(_, callback)| callback.clone())
+            .collect();
+        for callback in listeners {
+            (*callback.borrow_mut())(event);
+        }
+    }
+}
+
+impl InstallBackend for MockInstall {
+    fn is_installable(&self) -> bool {
+        self.browser.offered.get()
+    }
+
+    fn listen(&self, callback: Box<FnMut(InstallEvent)>) -> Box<Task> {
+        let id = self.browser.next_id.get();
+        self.browser.next_id.set(id + 1);
+        self.browser.listeners.borrow_mut().push((id, Rc::new(RefCell::new(callback))));
+        Box::new(MockInstallListener { browser: self.browser.clone(), id })
+    }
+
+    fn prompt(&self, callback: Box<FnMut(InstallChoice)>) -> bool {
+        if !self.browser.offered.replace(false) {
+            return false;
+        }
+        *self.browser.prompt.borrow_mut() = Some(callback);
+        true
+    }
+}
+
+struct MockInstallListener {
+    browser: Rc<Install>,
+    id: usize,
+}
+
+impl Task for MockInstallListener {
+    fn is_active(&self) -> bool {
+        self.browser.listeners.borrow().iter().any(|&(id, _)| id == self.id)
+    }
+
+    fn cancel(&mut self) {
+        let id = self.id;
+        self.browser.listeners.borrow_mut().retain(|&(listener, _)| listener != id);
+    }
+}
+
+type MediaListener = Rc<RefCell<Box<FnMut(MediaEvent)>>>;
+
+#[derive(Default)]
+struct Media {
+    state: RefCell<MediaState>,
+    blocked: Cell<bool>,
+    commands: RefCell<Vec<MediaCommand>>,
+    next_id: Cell<usize>,
+    listeners: RefCell<Vec<(usize, MediaListener)>>,
+}
+
+/// A media element which plays when a test asks it.
+#[derive(Clone, Default)]
+pub struct MockMedia {
+    element: Rc<Media>,
+}
+
+impl MockMedia {
+    /// Creates a paused element without metadata.
+    pub fn new() -> Self {
+        MockMedia::default()
+    }
+
+    /// Sets the element as the backend of media for services created later.
+    /// Every reference is attached to it.
+    pub fn install(&self) -> Installed {
+        let previous = RefCell::new(Some(backend::set_media(Rc::new(self.clone()))));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some(previous) = previous.borrow_mut().take() {
+                    backend::set_media(previous);
+                }
+            })),
+        }
+    }
+
+    /// Sends the event to attached services and changes the state like a browser.
+    pub fn emit(&self, event: MediaEvent) {
+        {
+            let mut state = self.element.state.borrow_mut();
+            match event {
+                MediaEvent::Metadata(duration) => state.duration = duration,
+                MediaEvent::Time(time) => state.time = time,
+                MediaEvent::Playing => {
+                    state.paused = false;
+                    state.ended = false;
+                }
+                MediaEvent::Paused => state.paused = true,
+                MediaEvent::Buffered(buffered) => state.buffered = buffered,
+                MediaEvent::Ended => {
+                    state.paused = true;
+                    state.ended = true;
+                }
+                MediaEvent::Volume(volume, muted) => {
+                    state.volume = volume;
+                    state.muted = muted;
+                }
+                MediaEvent::Buffering | MediaEvent::Failed(_) => {}
+            }
+        }
+        let listeners: Vec<_> = self.element.listeners.borrow().iter()
+            .map(|(_, callback)| callback.clone())
+            .collect();
+        for callback in listeners {
+            (*callback.borrow_mut())(event.clone());
+        }
+    }
+
+    /// Rejects playback like a browser which needs a gesture of a user.
+    pub fn set_blocked(&self, blocked: bool) {
+        self.element.blocked.set(blocked);
+    }
+
+    /// Returns all commands to the element.
+    pub fn commands(&self) -> Vec<MediaCommand> {
+        self.element.commands.borrow().clone()
+    }
+
+    /// Returns the number of attached services.
+    pub fn listeners(&self) -> usize {
+        self.element.listeners.borrow().len()
+    }
+}
+
+impl MediaBackend for MockMedia {
+    fn attach(&self, _target: NodeRef, callback: Box<FnMut(MediaEvent)>) -> Box<MediaControl> {
+        let id = self.element.next_id.get();
+        self.element.next_id.set(id + 1);
+        self.element.listeners.borrow_mut().push((id, Rc::new(RefCell::new(callback))));
+        Box::new(MockMediaControl { media: self.clone(), id })
+    }
+}
+
+struct MockMediaControl {
+    media: MockMedia,
+    id: usize,
+}
+
+impl Task for MockMediaControl {
+    fn is_active(&self) -> bool {
+        self.media.element.listeners.borrow().iter().any(|&(id, _)| id == self.id)
+    }
+
+    fn cancel(&mut self) {
+        let id = self.id;
+        self.media.element.listeners.borrow_mut().retain(|&(listener, _)| listener != id);
+    }
+}
+
+impl MediaControl for MockMediaControl {
+    fn control(&mut self, command: MediaCommand) -> bool {
+        self.media.element.commands.borrow_mut().push(command.clone());
+        let (volume, muted) = {
+            let state = self.media.element.state.borrow();
+            (state.volume, state.muted)
+        };
+        match command {
+            MediaCommand::Play if self.media.element.blocked.get() => {
+                self.media.emit(MediaEvent::Failed(MediaError::Blocked));
+            }
+            MediaCommand::Play => self.media.emit(MediaEvent::Playing),
+            MediaCommand::Pause => self.media.emit(MediaEvent::Paused),
+            MediaCommand::Seek(time) => self.media.emit(MediaEvent::Time(time)),
+            MediaCommand::Volume(volume) => self.media.emit(MediaEvent::Volume(volume, muted)),
+            MediaCommand::Muted(muted) => self.media.emit(MediaEvent::Volume(volume, muted)),
+            MediaCommand::Rate(rate) => self.media.element.state.borrow_mut().rate = rate,
+        }
+        true
+    }
+
+    fn state(&self) -> Option<MediaState> {
+        Some(self.media.element.state.borrow().clone())
+    }
+}
+
+struct Font {
+    char_width: Cell<f64>,
+    widths: RefCell<HashMap<char, f64>>,
+    measured: Cell<usize>,
+    content: Cell<Option<Rect>>,
+}
+
+impl Default for Font {
+    fn default() -> Self {
+        Font {
+            char_width: Cell::new(8.0),
+            widths: RefCell::new(HashMap::new()),
+            measured: Cell::new(0),
+            content: Cell::new(None),
+        }
+    }
+}
+
+/// A monospace font which measures every character as 8 pixels wide,
+/// 12 pixels above the baseline and 4 below it, unless a test changes it.
+#[derive(Clone, Default)]
+pub struct MockText {
+    font: Rc<Font>,
+}
+
+impl MockText {
+    /// Creates a font with characters of 8 pixels.
+    pub fn new() -> Self {
+        MockText::default()
+    }
+
+    /// Sets the font as the backend of measurements for services created later.
+    pub fn install(&self) -> Installed {
+        let previous = RefCell::new(Some(backend::set_text(Rc::new(self.clone()))));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some(previous) = previous.borrow_mut().take() {
+                    backend::set_text(previous);
+                }
+            })),
+        }
+    }
+
+    /// Sets the width of characters which don't have their own.
+    pub fn set_char_width(&self, width: f64) {
+        self.font.char_width.set(width);
+    }
+
+    /// Sets the width of the character, like a narrow space.
+    pub fn set_width(&self, c: char, width: f64) {
+        self.font.widths.borrow_mut().insert(c, width);
+    }
+
+    /// Sets the rectangle of contents of every element.
+    pub fn set_content_rect(&self, rect: Option<Rect>) {
+        self.font.content.set(rect);
+    }
+
+    /// Returns how many times text was measured.
+    pub fn measured(&self) -> usize {
+        self.font.measured.get()
+    }
+}
+
+impl TextBackend for MockText {
+    fn measure(&self, _: &str, text: &str) -> TextMetrics {
+        self.font.measured.set(self.font.measured.get() + 1);
+        let widths = self.font.widths.borrow();
+        let width = text.chars().map(|c| widths.get(&c).cloned().unwrap_or_else(|| self.font.char_width.get())).sum();
+        TextMetrics { width, ascent: 12.0, descent: 4.0 }
+    }
+
+    fn content_rect(&self, _: &NodeRef) -> Option<Rect> {
+        self.font.content.get()
+    }
+}
+
+type PrintListener = Rc<RefCell<Box<FnMut(PrintEvent)>>>;
+
+#[derive(Default)]
+struct Printer {
+    next_id: Cell<usize>,
+    listeners: RefCell<Vec<(usize, PrintListener)>>,
+    jobs: RefCell<Vec<PrintJob>>,
+}
+
+/// A printer which keeps printed jobs. Listeners are called before and
+/// after every job like the print dialog was opened and closed at once.
+#[derive(Clone, Default)]
+pub struct MockPrinter {
+    printer: Rc<Printer>,
+}
+
+impl MockPrinter {
+    /// Creates a printer without jobs.
+    pub fn new() -> Self {
+        MockPrinter::default()
+    }
+
+    /// Sets the printer as the backend of printing for services created later.
+    pub fn install(&self) -> Installed {
+        let previous = RefCell::new(Some(backend::set_print(Rc::new(self.clone()))));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some(previous) = previous.borrow_mut().take() {
+                    backend::set_print(previous);
+                }
+            })),
+        }
+    }
+
+    /// Returns printed jobs.
+    pub fn jobs(&self) -> Vec<PrintJob> {
+        self.printer.jobs.borrow().clone()
+    }
+
+    /// Calls listeners like a user printed with a menu of the browser.
+    pub fn notify(&self, event: PrintEvent) {
+        let listeners: Vec<_> = self.printer.listeners.borrow().iter()
+            .map(|(_, callback)| callback.clone())
+            .collect();
+        for callback in listeners {
+            (*callback.borrow_mut())(event);
+        }
+    }
+
+    /// Returns the number of listeners.
+    pub fn listeners(&self) -> usize {
+        self.printer.listeners.borrow().len()
+    }
+}
+
+impl PrintBackend for MockPrinter {
+    fn print(&self, job: PrintJob) -> bool {
+        self.notify(PrintEvent::Before);
+        self.printer.jobs.borrow_mut().push(job);
+        self.notify(PrintEvent::After);
+        true
+    }
+
+    fn listen(&self, callback: Box<FnMut(PrintEvent)>) -> Box<Task> {
+        let id = self.printer.next_id.get();
+        self.printer.next_id.set(id + 1);
+        self.printer.listeners.borrow_mut().push((id, Rc::new(RefCell::new(callback))));
+        Box::new(MockPrintListener { printer: self.printer.clone(), id })
+    }
+}
+
+struct MockPrintListener {
+    printer: Rc<Printer>,
+    id: usize,
+}
+
+impl Task for MockPrintListener {
+    fn is_active(&self) -> bool {
+        self.printer.listeners.borrow().iter().any(|&(id, _)| id == self.id)
+    }
+
+    fn cancel(&mut self) {
+        let id = self.id;
+        self.printer.listeners.borrow_mut().retain(|&(listener, _)| listener != id);
+    }
+}
+
+type SelectionListener = Rc<RefCell<Box<FnMut()>>>;
+
+#[derive(Default)]
+struct Selecting {
+    current: RefCell<Option<Selection>>,
+    next_id: Cell<usize>,
+    listeners: RefCell<Vec<(usize, SelectionListener)>>,
+}
+
+/// A document where a test selects text like a user.
+#[derive(Clone, Default)]
+pub struct MockSelection {
+    document: Rc<Selecting>,
+}
+
+impl MockSelection {
+    /// Creates a document without a selection.
+    pub fn new() -> Self {
+        MockSelection::default()
+    }
+
+    /// Sets the document as the backend of the selection for services created later.
+    pub fn install(&self) -> Installed {
+        let previous = RefCell::new(Some(backend::set_selection(Rc::new(self.clone()))));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some(previous) = previous.borrow_mut().take() {
+                    backend::set_selection(previous);
+                }
+            })),
+        }
+    }
+
+    /// Changes the selection like a user and calls listeners. Nodes should be
+    /// mapped to the targets already, they're returned for any targets.
+    pub fn set(&self, selection: Option<Selection>) {
+        *self.document.current.borrow_mut() = selection;
+        let listeners: Vec<_> = self.document.listeners.borrow().iter()
+            .map(|(_, callback)| callback.clone())
+            .collect();
+        for callback in listeners {
+            (*callback.borrow_mut())();
+        }
+    }
+
+    /// Returns the current selection.
+    pub fn current(&self) -> Option<Selection> {
+        self.document.current.borrow().clone()
+    }
+
+    /// Returns the number of listeners.
+    pub fn listeners(&self) -> usize {
+        self.document.listeners.borrow().len()
+    }
+}
+
+impl SelectionBackend for MockSelection {
+    fn selection(&self, _: &[NodeRef]) -> Option<Selection> {
+        self.current()
+    }
+
+    fn select(&self, target: &NodeRef, offsets: Range<usize>) -> bool {
+        self.set(Some(Selection {
+            collapsed: offsets.start == offsets.end,
+            anchor: Some(target.clone()),
+            focus: Some(target.clone()),
+            offsets: Some(offsets),
+            ..Selection::default()
+        }));
+        true
+    }
+
+    fn clear(&self) {
+        self.set(None);
+    }
+
+    fn listen(&self, callback: Box<FnMut()>) -> Box<Task> {
+        let id = self.document.next_id.get();
+        self.document.next_id.set(id + 1);
+        self.document.listeners.borrow_mut().push((id, Rc::new(RefCell::new(callback))));
+        Box::new(MockSelectionListener { document: self.document.clone(), id })
+    }
+}
+
+struct MockSelectionListener {
+    document: Rc<Selecting>,
+    id: usize,
+}
+
+impl Task for MockSelectionListener {
+    fn is_active(&self) -> bool {
+        self.document.listeners.borrow().iter().any(|&(id, _)| id == self.id)
+    }
+
+    fn cancel(&mut self) {
+        let id = self.id;
+        self.document.listeners.borrow_mut().retain(|&(listener, _)| listener != id);
+    }
+}
+
+/// A head of the document which records inserted hints.
+#[derive(Clone, Default)]
+pub struct MockHead {
+    hints: Rc<RefCell<Vec<Hint>>>,
+}
+
+impl MockHead {
+    /// Creates a head without hints.
+    pub fn new() -> Self {
+        MockHead::default()
+    }
+
+    /// Sets the head as the backend of hints inserted later.
+    pub fn install(&self) -> Installed {
+        let previous = RefCell::new(Some(backend::set_head(Rc::new(self.clone()))));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some(previous) = previous.borrow_mut().take() {
+                    backend::set_head(previous);
+                }
+            })),
+        }
+    }
+
+    /// Returns inserted hints in order.
+    pub fn hints(&self) -> Vec<Hint> {
+        self.hints.borrow().clone()
+    }
+}
+
+impl HeadBackend for MockHead {
+    fn insert_hint(&self, hint: &Hint) {
+        self.hints.borrow_mut().push(hint.clone());
+    }
+}
+
+type LongTaskObserver = Rc<RefCell<Box<FnMut(LongTask)>>>;
+
+#[derive(Default)]
+struct MainThread {
+    next_id: Cell<usize>,
+    observers: RefCell<Vec<(usize, LongTaskObserver)>>,
+}
+
+/// A main thread which a test stalls like a slow update.
+#[derive(Clone, Default)]
+pub struct MockLongTasks {
+    thread: Rc<MainThread>,
+}
+
+impl MockLongTasks {
+    /// Creates a thread without observers.
+    pub fn new() -> Self {
+        MockLongTasks::default()
+    }
+
+    /// Sets the thread as the backend of long tasks for services created later.
+    pub fn install(&self) -> Installed {
+        let previous = RefCell::new(Some(backend::set_long_tasks(Rc::new(self.clone()))));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some(previous) = previous.borrow_mut().take() {
+                    backend::set_long_tasks(previous);
+                }
+            })),
+        }
+    }
+
+    /// Reports a long task of the page since the time origin, which is
+    /// the monotonic time of `MockClock`, to observers.
+    pub fn stall(&self, start: Duration, duration: Duration) {
+        let observers: Vec<_> = self.thread.observers.borrow().iter()
+            .map(|(_, observer)| observer.clone())
+            .collect();
+        for observer in observers {
+            (*observer.borrow_mut())(LongTask {
+                start,
+                duration,
+                source: "self".into(),
+                container: None,
+                activities: Vec::new(),
+            });
+        }
+    }
+
+    /// Returns the number of observers.
+    pub fn observers(&self) -> usize {
+        self.thread.observers.borrow().len()
+    }
+}
+
+impl LongTaskBackend for MockLongTasks {
+    fn observe(&self, callback: Box<FnMut(LongTask)>) -> Box<Task> {
+        let id = self.thread.next_id.get();
+        self.thread.next_id.set(id + 1);
+        self.thread.observers.borrow_mut().push((id, Rc::new(RefCell::new(callback))));
+        Box::new(MockLongTaskObserver { thread: self.thread.clone(), id })
+    }
+}
+
+struct MockLongTaskObserver {
+    thread: Rc<MainThread>,
+    id: usize,
+}
+
+impl Task for MockLongTaskObserver {
+    fn is_active(&self) -> bool {
+        self.thread.observers.borrow().iter().any(|&(observer, _)| observer == self.id)
+    }
+
+    fn cancel(&mut self) {
+        self.thread.observers.borrow_mut().retain(|&(observer, _)| observer != self.id);
+    }
+}
+
+type DocumentListener = Rc<RefCell<Box<FnMut(DocumentEvent)>>>;
+
+#[derive(Default)]
+struct Document {
+    next_id: Cell<usize>,
+    listeners: RefCell<Vec<(usize, &'static str, DocumentListener)>>,
+}
+
+/// A document which a test presses keys and pointers on.
+#[derive(Clone, Default)]
+pub struct MockDocument {
+    document: Rc<Document>,
+}
+
+impl MockDocument {
+    /// Creates a document without listeners.
+    pub fn new() -> Self {
+        MockDocument::default()
+    }
+
+    /// Sets the document as the backend of listeners of the document for services created later.
+    pub fn install(&self) -> Installed {
+        let previous = RefCell::new(Some(backend::set_document(Rc::new(self.clone()))));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some(previous) = previous.borrow_mut().take() {
+                    backend::set_document(previous);
+                }
+            })),
+        }
+    }
+
+    /// Presses the key, like `Escape`.
+    pub fn press_key(&self, key: &str) {
+        self.dispatch("keydown", key, None);
+    }
+
+    /// Presses a pointer on the element of the reference, or outside of
+    /// all elements if it's `None`.
+    pub fn press(&self, target: Option<&NodeRef>) {
+        self.dispatch("pointerdown", "", target.cloned());
+    }
+
+    /// Returns the number of listeners of the kind of events, like `keydown`.
+    pub fn listeners(&self, kind: &str) -> usize {
+        self.document.listeners.borrow().iter().filter(|&&(_, listener, _)| listener == kind).count()
+    }
+
+    fn dispatch(&self, kind: &'static str, key: &str, target: Option<NodeRef>) {
+        let listeners: Vec<_> = self.document.listeners.borrow().iter()
+            .filter(|&&(_, listener, _)| listener == kind)
+            .map(|(_, _, listener)| listener.clone())
+            .collect();
+        for listener in listeners {
+            let target = target.clone();
+            let event = DocumentEvent::new(kind, key, move |node| target.as_ref() == Some(node));
+            (*listener.borrow_mut())(event);
+        }
+    }
+}
+
+impl DocumentBackend for MockDocument {
+    fn listen(&self, kind: &'static str, callback: Box<FnMut(DocumentEvent)>) -> Box<Task> {
+        let id = self.document.next_id.get();
+        self.document.next_id.set(id + 1);
+        self.document.listeners.borrow_mut().push((id, kind, Rc::new(RefCell::new(callback))));
+        Box::new(MockDocumentListener { document: self.document.clone(), id })
+    }
+}
+
+struct MockDocumentListener {
+    document: Rc<Document>,
+    id: usize,
+}
+
+impl Task for MockDocumentListener {
+    fn is_active(&self) -> bool {
+        self.document.listeners.borrow().iter().any(|&(listener, _, _)| listener == self.id)
+    }
+
+    fn cancel(&mut self) {
+        self.document.listeners.borrow_mut().retain(|&(listener, _, _)| listener != self.id);
+    }
+}
+
+#[cfg(feature = "devtools")]
+type InspectorListener = Rc<RefCell<Box<FnMut(String)>>>;
+
+#[cfg(feature = "devtools")]
+#[derive(Default)]
+struct Inspector {
+    next_id: Cell<usize>,
+    events: RefCell<Vec<(String, ::serde_json::Value)>>,
+    listeners: RefCell<Vec<(usize, InspectorListener)>>,
+}
+
+/// An inspector of the `devtools` feature which keeps posted events.
+#[cfg(feature = "devtools")]
+#[derive(Clone, Default)]
+pub struct MockDevtools {
+    inspector: Rc<Inspector>,
+}
+
+#[cfg(feature = "devtools")]
+impl MockDevtools {
+    /// Creates an inspector without events.
+    pub fn new() -> Self {
+        MockDevtools::default()
+    }
+
+    /// Sets the inspector as the backend of the bridge for the thread.
+    pub fn install(&self) -> Installed {
+        let previous = RefCell::new(Some(backend::set_devtools(Rc::new(self.clone()))));
+        Installed {
+            restore: Some(Box::new(move || {
+                if let Some(previous) = previous.borrow_mut().take() {
+                    backend::set_devtools(previous);
+                }
+            })),
+        }
+    }
+
+    /// Returns posted events with their types, like `mount`, in order.
+    pub fn events(&self) -> Vec<(String, ::serde_json::Value)> {
+        self.inspector.events.borrow().clone()
+    }
+
+    /// Returns payloads of posted events of the type.
+    pub fn payloads(&self, kind: &str) -> Vec<::serde_json::Value> {
+        self.inspector.events.borrow().iter()
+            .filter(|(posted, _)| posted == kind)
+            .map(|(_, payload)| payload.clone())
+            .collect()
+    }
+
+    /// Sends a request of an extension, like `inspect`.
+    pub fn request(&self, kind: &str) {
+        let listeners: Vec<_> = self.inspector.listeners.borrow().iter()
+            .map(|(_, listener)| listener.clone())
+            .collect();
+        for listener in listeners {
+            (*listener.borrow_mut())(kind.to_owned());
+        }
+    }
+}
+
+#[cfg(feature = "devtools")]
+impl DevtoolsBackend for MockDevtools {
+    fn post(&self, kind: &str, payload: &::serde_json::Value) {
+        self.inspector.events.borrow_mut().push((kind.to_owned(), payload.clone()));
+    }
+
+    fn listen(&self, callback: Box<FnMut(String)>) -> Box<Task> {
+        let id = self.inspector.next_id.get();
+        self.inspector.next_id.set(id + 1);
+        self.inspector.listeners.borrow_mut().push((id, Rc::new(RefCell::new(callback))));
+        Box::new(MockInspectorListener { inspector: self.inspector.clone(), id })
+    }
+}
+
+#[cfg(feature = "devtools")]
+struct MockInspectorListener {
+    inspector: Rc<Inspector>,
+    id: usize,
+}
+
+#[cfg(feature = "devtools")]
+impl Task for MockInspectorListener {
+    fn is_active(&self) -> bool {
+        self.inspector.listeners.borrow().iter().any(|&(listener, _)| listener == self.id)
+    }
+
+    fn cancel(&mut self) {
+        self.inspector.listeners.borrow_mut().retain(|&(listener, _)| listener != self.id);
+    }
+}
@@ -5,22 +5,118 @@
 
 pub mod timeout;
 pub mod interval;
+pub mod rate_limit;
 pub mod storage;
 pub mod dialog;
 pub mod console;
 pub mod fetch;
+pub mod interceptor;
 pub mod websocket;
+pub mod script;
+pub mod history;
+pub mod scroll;
+pub mod resize;
+pub mod announcer;
+pub mod intl;
+pub mod interop;
+pub mod promise;
+pub mod messaging;
+pub mod poll;
+pub mod file_system;
+pub mod image;
+pub mod scanner;
+pub mod intersection;
+pub mod layout;
+pub mod unsaved;
+pub mod beacon;
+pub mod idle;
+pub mod install;
+pub mod capture;
+pub mod recorder;
+pub mod media;
+pub mod midi;
+pub mod eyedropper;
+pub mod text;
+pub mod print;
+pub mod selection;
+pub mod long_task;
+pub mod document;
+#[cfg(feature = "bluetooth")]
+pub mod bluetooth;
+#[cfg(feature = "serial")]
+pub mod serial;
+#[cfg(feature = "usb")]
+pub mod usb;
+pub mod backend;
+#[cfg(feature = "testing")]
+pub mod mock;
+#[cfg(feature = "web_sys")]
+pub mod websys;
 
 use std::time::Duration;
+use stdweb::Value;
 
 /// An universal interface to service's routine. At least could be canceled.
+/// Handles of services cancel their routines when they're dropped.
 pub trait Task {
+    /// Returns `true` if the routine is still running.
+    fn is_active(&self) -> bool;
     /// Cancel current service's routine.
     fn cancel(&mut self);
 }
 
+/// A set of tasks which are canceled together when it's cleared or dropped.
+/// Keep it in a component or a context to tie tasks to its lifetime.
+#[derive(Default)]
+pub struct TaskBag {
+    tasks: Vec<Box<Task>>,
+}
+
+impl TaskBag {
+    /// Creates an empty set of tasks.
+    pub fn new() -> Self {
+        TaskBag {
+            tasks: Vec::new(),
+        }
+    }
+
+    /// Adds the task to the set. Finished tasks are removed from it.
+    pub fn push<T: Task + 'static>(&mut self, task: T) {
+        self.tasks.retain(|task| task.is_active());
+        self.tasks.push(Box::new(task));
+    }
+
+    /// Returns how many tasks of the set are still running.
+    pub fn active(&self) -> usize {
+        self.tasks.iter().filter(|task| task.is_active()).count()
+    }
+
+    /// Cancels all running tasks of the set.
+    pub fn clear(&mut self) {
+        for mut task in self.tasks.drain(..) {
+            if task.is_active() {
+                task.cancel();
+            }
+        }
+    }
+}
+
+impl Drop for TaskBag {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// Returns `true` if a routine marked its handle as finished.
+fn is_done(handle: &Value) -> bool {
+    let done: Value = js! {
+        return @{handle}.done === true;
+    };
+    done == Value::Bool(true)
+}
+
 #[doc(hidden)]
-fn to_ms(duration: Duration) -> u32 {
+pub(crate) fn to_ms(duration: Duration) -> u32 {
     let ms = duration.subsec_nanos() / 1_000_000;
     ms + duration.as_secs() as u32 * 1000
 }
@@ -0,0 +1,247 @@
+//! This module contains a service which repeats a fetch or another task
+//! with a period. Polls are paused while the page is hidden, a poll isn't
+//! started while the previous one is running, and everything is canceled
+//! when the handle is dropped:
+//!
+//! ```rust,ignore
+//! let options = PollOptions::every(Duration::from_secs(30)).jitter(0.2);
+//! self.poll = Some(self.poller.fetch(options, || {
+//!     Request::get("/notifications").body(Nothing).unwrap()
+//! }, |response: Response<Json<Result<Vec<Notification>, Error>>>| {
+//!     Msg::Notifications(response.into_body().0)
+//! }));
+//! ```
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+use std::time::Duration;
+use stdweb::Value;
+use stdweb::unstable::TryFrom;
+use format::{Storable, Restorable};
+use html::AppSender;
+use super::Task;
+use super::backend::{self, FetchBackend, TimerBackend, VisibilityBackend};
+use super::fetch::{FetchService, Request, Response};
+
+/// Options of polls.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PollOptions {
+    /// The time between polls.
+    pub period: Duration,
+    /// A fraction of the period which is added or subtracted at random,
+    /// so clients which started together don't poll at once.
+    pub jitter: f64,
+    /// Polls are skipped while the page is hidden and one is made
+    /// when the page is shown again.
+    pub pause_hidden: bool,
+    /// The first poll is made at once instead of after a period.
+    pub immediate: bool,
+}
+
+impl PollOptions {
+    /// Polls with the period, at once and without jitter, paused while the page is hidden.
+    pub fn every(period: Duration) -> Self {
+        PollOptions {
+            period,
+            jitter: 0.0,
+            pause_hidden: true,
+            immediate: true,
+        }
+    }
+
+    /// Sets the fraction of random jitter of the period, like `0.1` for ±10%.
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Keeps polling while the page is hidden.
+    pub fn in_background(mut self) -> Self {
+        self.pause_hidden = false;
+        self
+    }
+
+    /// Makes the first poll after a period.
+    pub fn delayed(mut self) -> Self {
+        self.immediate = false;
+        self
+    }
+}
+
+/// A handle of polls which stops them when it's canceled or dropped.
+pub struct PollHandle(Option<Rc<RefCell<Poll>>>);
+
+/// A service which repeats tasks with a period.
+pub struct PollService<MSG> {
+    sender: AppSender<MSG>,
+    timers: Rc<TimerBackend>,
+    fetch: Rc<FetchBackend>,
+    visibility: Rc<VisibilityBackend>,
+}
+
+impl<MSG: 'static> PollService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self::with_backends(sender, backend::timers(), backend::fetch(), backend::visibility())
+    }
+
+    /// Creates a new service instance which uses the backends.
+    pub fn with_backends(
+        sender: AppSender<MSG>,
+        timers: Rc<TimerBackend>,
+        fetch: Rc<FetchBackend>,
+        visibility: Rc<VisibilityBackend>,
+    ) -> Self {
+        Self { sender, timers, fetch, visibility }
+    }
+
+    /// Starts a task returned by `start` on every poll. The task sends its
+    /// messages itself, like a handle of `FetchService`.
+    pub fn spawn<F, T>(&mut self, options: PollOptions, start: F) -> PollHandle
+    where
+        F: FnMut() -> T + 'static,
+        T: Task + 'static,
+    {
+        let mut start = start;
+        let poll = Rc::new(RefCell::new(Poll {
+            options,
+            start: Box::new(move || Box::new(start()) as Box<Task>),
+            timers: self.timers.clone(),
+            visibility: self.visibility.clone(),
+            timer: None,
+            subscription: None,
+            running: None,
+            missed: false,
+        }));
+        let immediate = {
+            let mut state = poll.borrow_mut();
+            if state.options.pause_hidden {
+                let weak = Rc::downgrade(&poll);
+                let callback = move |hidden: bool| {
+                    if let Some(poll) = weak.upgrade() {
+                        let missed = !hidden && poll.borrow().missed;
+                        if missed {
+                            tick(&poll);
+                        }
+                    }
+                };
+                state.subscription = Some(state.visibility.listen(Box::new(callback)));
+            }
+            state.options.immediate
+        };
+        if immediate {
+            tick(&poll);
+        } else {
+            schedule(&poll);
+        }
+        PollHandle(Some(poll))
+    }
+
+    /// Sends a request built by `request` on every poll and a message
+    /// from a `converter` for every response.
+    pub fn fetch<R, F, IN, OUT>(&mut self, options: PollOptions, request: R, converter: F) -> PollHandle
+    where
+        R: Fn() -> Request<IN> + 'static,
+        IN: Into<Storable>,
+        OUT: From<Restorable> + 'static,
+        F: Fn(Response<OUT>) -> MSG + 'static,
+    {
+        let mut fetch = FetchService::with_backend(self.sender.clone(), self.fetch.clone());
+        let converter = Rc::new(converter);
+        self.spawn(options, move || {
+            let converter = converter.clone();
+            fetch.fetch(request(), move |response| (*converter)(response))
+        })
+    }
+}
+
+struct Poll {
+    options: PollOptions,
+    start: Box<FnMut() -> Box<Task>>,
+    timers: Rc<TimerBackend>,
+    visibility: Rc<VisibilityBackend>,
+    timer: Option<Box<Task>>,
+    subscription: Option<Box<Task>>,
+    running: Option<Box<Task>>,
+    // The page was hidden when a poll was due
+    missed: bool,
+}
+
+impl Drop for Poll {
+    fn drop(&mut self) {
+        let tasks = vec![self.timer.take(), self.subscription.take(), self.running.take()];
+        for mut task in tasks.into_iter().flatten() {
+            if task.is_active() {
+                task.cancel();
+            }
+        }
+    }
+}
+
+/// Makes a poll and schedules the next one, or waits until the page is shown.
+fn tick(poll: &Rc<RefCell<Poll>>) {
+    {
+        let mut state = poll.borrow_mut();
+        if let Some(mut timer) = state.timer.take() {
+            if timer.is_active() {
+                timer.cancel();
+            }
+        }
+        if state.options.pause_hidden && state.visibility.is_hidden() {
+            state.missed = true;
+            return;
+        }
+        state.missed = false;
+        let busy = state.running.as_ref().map_or(false, |task| task.is_active());
+        if busy {
+            debug!("skipped a poll: the previous one is still running");
+        } else {
+            let task = (state.start)();
+            state.running = Some(task);
+        }
+    }
+    schedule(poll);
+}
+
+fn schedule(poll: &Rc<RefCell<Poll>>) {
+    let weak: Weak<RefCell<Poll>> = Rc::downgrade(poll);
+    let callback = move || {
+        if let Some(poll) = weak.upgrade() {
+            tick(&poll);
+        }
+    };
+    let mut state = poll.borrow_mut();
+    let delay = jittered(state.options.period, state.options.jitter);
+    let timer = state.timers.timeout(delay, Box::new(callback));
+    state.timer = Some(timer);
+}
+
+/// Returns the period with a random jitter.
+fn jittered(period: Duration, jitter: f64) -> Duration {
+    if jitter <= 0.0 {
+        return period;
+    }
+    let random: Value = js! {
+        return Math.random();
+    };
+    let random = f64::try_from(random).unwrap_or(0.5);
+    period.mul_f64(1.0 + jitter * (random * 2.0 - 1.0))
+}
+
+impl Task for PollHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        self.0.take().expect("tried to cancel polls twice");
+    }
+}
+
+impl Drop for PollHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
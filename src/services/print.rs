@@ -0,0 +1,201 @@
+//! This module contains a service which prints the page, a subtree of it or
+//! a separate document, like an invoice. The print dialog of a browser also
+//! saves a PDF:
+//!
+//! ```rust,ignore
+//! self.handle = Some(self.print.listen(Msg::Print));
+//!
+//! Msg::PrintInvoice => self.print.print_element(&self.invoice),
+//! Msg::Print(PrintEvent::Before) => self.printing = true,
+//! Msg::Print(PrintEvent::After) => self.printing = false,
+//! ```
+//!
+//! `print_element` hides the rest of the page with a print stylesheet, so
+//! the element keeps styles of the page. `print_document` prints markup in
+//! a hidden frame with its own styles instead, with the `ssr` feature
+//! `PrintDocument::render` renders a virtual tree for it.
+
+use std::rc::Rc;
+use html::AppSender;
+use virtual_dom::NodeRef;
+#[cfg(feature = "ssr")]
+use virtual_dom::VNode;
+use super::Task;
+use super::backend::{self, PrintBackend};
+
+/// An event of printing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrintEvent {
+    /// The print dialog is about to open, views could change for paper.
+    Before,
+    /// The dialog is closed, the document is printed or canceled.
+    After,
+}
+
+/// What a `PrintBackend` prints.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrintJob {
+    /// The whole page.
+    Page,
+    /// The element only, the rest of the page is hidden.
+    Element(NodeRef),
+    /// An HTML document in a hidden frame.
+    Document(String),
+}
+
+/// A document which is printed apart from the page.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PrintDocument {
+    title: String,
+    stylesheets: Vec<String>,
+    style: String,
+    body: String,
+}
+
+impl PrintDocument {
+    /// Creates an empty document with the title, which browsers suggest
+    /// as a name of a saved PDF.
+    pub fn new(title: &str) -> Self {
+        PrintDocument {
+            title: title.to_owned(),
+            ..PrintDocument::default()
+        }
+    }
+
+    /// Links a stylesheet by the URL.
+    pub fn stylesheet(mut self, url: &str) -> Self {
+        self.stylesheets.push(url.to_owned());
+        self
+    }
+
+    /// Adds CSS rules, like `@page { size: A4; margin: 2cm }`.
+    pub fn style(mut self, css: &str) -> Self {
+        self.style.push_str(css);
+        self
+    }
+
+    /// Adds markup to the body.
+    pub fn body(mut self, html: &str) -> Self {
+        self.body.push_str(html);
+        self
+    }
+
+    /// Renders a virtual tree to the body, like `html! { <Invoice: order=order, /> }`.
+    #[cfg(feature = "ssr")]
+    pub fn render<MSG, T: Into<VNode<MSG>>>(self, node: T) -> Self {
+        let html = ::ssr::render_to_string(node);
+        self.body(&html)
+    }
+
+    /// Returns the HTML of the document.
+    pub fn to_html(&self) -> String {
+        let mut html = String::from("<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>");
+        escape(&self.title, &mut html);
+        html.push_str("</title>");
+        for url in &self.stylesheets {
+            html.push_str("<link rel=\"stylesheet\" href=\"");
+            escape(url, &mut html);
+            html.push_str("\">");
+        }
+        if !self.style.is_empty() {
+            // A frame of a document inherits the policy of the page
+            #[cfg(feature = "csp")]
+            let nonce = ::csp::nonce();
+            #[cfg(not(feature = "csp"))]
+            let nonce: Option<String> = None;
+            match nonce {
+                Some(nonce) => {
+                    html.push_str("<style nonce=\"");
+                    escape(&nonce, &mut html);
+                    html.push_str("\">");
+                }
+                None => html.push_str("<style>"),
+            }
+            html.push_str(&self.style);
+            html.push_str("</style>");
+        }
+        html.push_str("</head><body>");
+        html.push_str(&self.body);
+        html.push_str("</body></html>");
+        html
+    }
+}
+
+fn escape(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// A handle of a listener of printing. It's canceled when dropped.
+pub struct PrintHandle(Option<Box<Task>>);
+
+impl Task for PrintHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let mut listener = self.0.take().expect("tried to cancel a print listener twice");
+        listener.cancel();
+    }
+}
+
+impl Drop for PrintHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+/// A service of printing.
+pub struct PrintService<MSG> {
+    sender: AppSender<MSG>,
+    backend: Rc<PrintBackend>,
+}
+
+impl<MSG: 'static> PrintService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self::with_backend(sender, backend::print())
+    }
+
+    /// Creates a new service instance which uses the backend.
+    pub fn with_backend(sender: AppSender<MSG>, backend: Rc<PrintBackend>) -> Self {
+        Self { sender, backend }
+    }
+
+    /// Sends a message before the print dialog opens and after it's closed,
+    /// also when a user prints with a menu of the browser.
+    pub fn listen<F>(&mut self, converter: F) -> PrintHandle
+    where
+        F: Fn(PrintEvent) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let callback = move |event| tx.send(converter(event));
+        PrintHandle(Some(self.backend.listen(Box::new(callback))))
+    }
+
+    /// Opens the print dialog for the page.
+    pub fn print_page(&mut self) {
+        self.backend.print(PrintJob::Page);
+    }
+
+    /// Opens the print dialog for the element of the reference and hides
+    /// the rest of the page on paper. Returns `false` if it's not rendered.
+    pub fn print_element(&mut self, target: &NodeRef) -> bool {
+        self.backend.print(PrintJob::Element(target.clone()))
+    }
+
+    /// Opens the print dialog for the document.
+    pub fn print_document(&mut self, document: &PrintDocument) {
+        self.backend.print(PrintJob::Document(document.to_html()));
+    }
+}
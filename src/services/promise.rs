@@ -0,0 +1,165 @@
+//! This module contains a service which turns JS promises into messages,
+//! so async browser APIs which aren't wrapped by services are still used
+//! from `update`:
+//!
+//! ```rust,ignore
+//! let promise = js! { return navigator.getBattery().then(function(battery) { return battery.level; }); };
+//! let handle = self.promises.spawn(promise, Msg::Battery, |error| Msg::Failed(error.to_string()));
+//! ```
+
+use std::fmt;
+use serde::de::DeserializeOwned;
+use serde_json;
+use stdweb::Value;
+use stdweb::unstable::TryFrom;
+use html::AppSender;
+use super::{Task, is_done};
+
+/// An error of a spawned promise.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromiseError {
+    /// The promise was rejected. It has the message of an `Error`
+    /// or the reason converted to a string.
+    Rejected(String),
+    /// The promise resolved to a value of another type.
+    Conversion(String),
+}
+
+impl fmt::Display for PromiseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PromiseError::Rejected(ref reason) => write!(f, "promise rejected: {}", reason),
+            PromiseError::Conversion(ref reason) => write!(f, "can't convert a value of a promise: {}", reason),
+        }
+    }
+}
+
+/// A handle of a spawned promise. The promise can't be stopped,
+/// but the callback won't be called after it's canceled.
+pub struct PromiseHandle(Option<Value>);
+
+/// Calls the callback with `true` and the value when the promise is resolved,
+/// or with `false` and the reason when it's rejected. Values which aren't
+/// promises are treated as resolved ones.
+pub(crate) fn settle<F>(promise: Value, callback: F) -> PromiseHandle
+where
+    F: FnMut(bool, Value) + 'static,
+{
+    let handle = js! {
+        var callback = @{callback};
+        var handle = {
+            interrupted: false,
+            callback,
+        };
+        Promise.resolve(@{promise}).then(function(value) {
+            return [true, value];
+        }, function(reason) {
+            return [false, reason];
+        }).then(function(outcome) {
+            if (handle.interrupted != true) {
+                handle.done = true;
+                callback(outcome[0], outcome[1]);
+                callback.drop();
+            }
+        });
+        return handle;
+    };
+    PromiseHandle(Some(handle))
+}
+
+/// Returns the message of an `Error` or the reason converted to a string.
+pub(crate) fn reason(value: Value) -> String {
+    let reason: Value = js! {
+        var reason = @{value};
+        return reason instanceof Error ? reason.message : String(reason);
+    };
+    reason.into_string().unwrap_or_default()
+}
+
+/// A service to send messages when promises are settled.
+pub struct PromiseService<MSG> {
+    sender: AppSender<MSG>,
+}
+
+impl<MSG: 'static> PromiseService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self { sender }
+    }
+
+    /// Sends a message from `ok` with the value of the promise converted from
+    /// a JS value, or a message from `err` if it's rejected or has another type.
+    pub fn spawn<T, F, E>(&mut self, promise: Value, ok: F, err: E) -> PromiseHandle
+    where
+        T: TryFrom<Value> + 'static,
+        F: Fn(T) -> MSG + 'static,
+        E: Fn(PromiseError) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        settle(promise, move |resolved, value| {
+            let msg = if resolved {
+                match T::try_from(value) {
+                    Ok(value) => ok(value),
+                    Err(_) => err(PromiseError::Conversion("unexpected type of a value".into())),
+                }
+            } else {
+                err(PromiseError::Rejected(reason(value)))
+            };
+            tx.send(msg);
+        })
+    }
+
+    /// Like `spawn`, but converts the value with `serde` through JSON,
+    /// so it could be a structure.
+    pub fn spawn_json<T, F, E>(&mut self, promise: Value, ok: F, err: E) -> PromiseHandle
+    where
+        T: DeserializeOwned + 'static,
+        F: Fn(T) -> MSG + 'static,
+        E: Fn(PromiseError) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        settle(promise, move |resolved, value| {
+            let msg = if resolved {
+                match from_json(value) {
+                    Ok(value) => ok(value),
+                    Err(error) => err(PromiseError::Conversion(error)),
+                }
+            } else {
+                err(PromiseError::Rejected(reason(value)))
+            };
+            tx.send(msg);
+        })
+    }
+}
+
+/// Converts a JS value with `serde` through JSON. `undefined` is `null`.
+pub(crate) fn from_json<T: DeserializeOwned>(value: Value) -> Result<T, String> {
+    let json: Value = js! {
+        return JSON.stringify(@{value}) || "null";
+    };
+    let json = json.into_string().unwrap_or_default();
+    serde_json::from_str(&json).map_err(|error| error.to_string())
+}
+
+impl Task for PromiseHandle {
+    fn is_active(&self) -> bool {
+        self.0.as_ref().map_or(false, |handle| !is_done(handle))
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel promise twice");
+        js! { @(no_return)
+            var handle = @{handle};
+            handle.interrupted = true;
+            handle.callback.drop();
+        }
+    }
+}
+
+impl Drop for PromiseHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
@@ -0,0 +1,199 @@
+//! This module contains `debounce` and `throttle` which wrap a callback
+//! with timers, so handlers of frequent events like typing or resizing
+//! are called less often. Timers are managed by the returned callback and
+//! canceled when its last clone is dropped, keep it in a component:
+//!
+//! ```rust,ignore
+//! fn create(_: Self::Properties, sender: AppSender<Msg>) -> Self {
+//!     let mut limits = RateLimitService::new(sender);
+//!     Search {
+//!         search: limits.debounce(Duration::from_millis(300), Msg::Search),
+//!         resize: limits.throttle(Duration::from_millis(100), |_| Msg::Resize),
+//!     }
+//! }
+//!
+//! fn update(&mut self, msg: Msg) -> ShouldRender {
+//!     match msg {
+//!         Msg::Input(query) => self.search.emit(query),
+//!         ...
+//!     }
+//! }
+//! ```
+//!
+//! Callbacks of parents are wrapped with `rate_limit::debounce(delay, callback)`.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+use callback::Callback;
+use html::AppSender;
+use super::Task;
+use super::backend::{self, TimerBackend};
+
+struct Limited<IN> {
+    callback: Callback<IN>,
+    delay: Duration,
+    timers: Rc<TimerBackend>,
+    value: Option<IN>,
+    task: Option<Box<Task>>,
+}
+
+impl<IN> Limited<IN> {
+    fn new(delay: Duration, callback: Callback<IN>, timers: Rc<TimerBackend>) -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Limited {
+            callback,
+            delay,
+            timers,
+            value: None,
+            task: None,
+        }))
+    }
+
+    fn cancel(&mut self) {
+        if let Some(mut task) = self.task.take() {
+            if task.is_active() {
+                task.cancel();
+            }
+        }
+    }
+}
+
+impl<IN> Drop for Limited<IN> {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// Sets a timer which calls `elapsed` with the state. Timers don't keep
+/// the state alive, so dropped callbacks don't fire later.
+fn start<IN: 'static, F>(state: &Rc<RefCell<Limited<IN>>>, elapsed: F)
+where
+    F: Fn(&Rc<RefCell<Limited<IN>>>) + 'static,
+{
+    let weak = Rc::downgrade(state);
+    let fire = move || {
+        if let Some(state) = weak.upgrade() {
+            state.borrow_mut().task = None;
+            elapsed(&state);
+        }
+    };
+    let mut limited = state.borrow_mut();
+    limited.cancel();
+    let task = limited.timers.timeout(limited.delay, Box::new(fire));
+    limited.task = Some(task);
+}
+
+/// Emits the value which is kept in the state.
+fn emit_pending<IN>(state: &Rc<RefCell<Limited<IN>>>) -> bool {
+    let (callback, value) = {
+        let mut limited = state.borrow_mut();
+        (limited.callback.clone(), limited.value.take())
+    };
+    match value {
+        Some(value) => {
+            callback.emit(value);
+            true
+        }
+        None => false,
+    }
+}
+
+/// A service which creates rate limited callbacks which send messages.
+pub struct RateLimitService<MSG> {
+    sender: AppSender<MSG>,
+    backend: Rc<TimerBackend>,
+}
+
+impl<MSG: 'static> RateLimitService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self::with_backend(sender, backend::timers())
+    }
+
+    /// Creates a new service instance which uses the `backend` for timers.
+    pub fn with_backend(sender: AppSender<MSG>, backend: Rc<TimerBackend>) -> Self {
+        Self { sender, backend }
+    }
+
+    /// Returns a callback which sends a message from a `converter` with the
+    /// last value when `delay` passed without new values.
+    pub fn debounce<IN, F>(&mut self, delay: Duration, converter: F) -> Callback<IN>
+    where
+        IN: 'static,
+        F: Fn(IN) -> MSG + 'static,
+    {
+        let state = Limited::new(delay, self.callback(converter), self.backend.clone());
+        debounced(state)
+    }
+
+    /// Returns a callback which sends a message from a `converter` at once
+    /// and then at most once per `period`.
+    pub fn throttle<IN, F>(&mut self, period: Duration, converter: F) -> Callback<IN>
+    where
+        IN: 'static,
+        F: Fn(IN) -> MSG + 'static,
+    {
+        let state = Limited::new(period, self.callback(converter), self.backend.clone());
+        throttled(state)
+    }
+
+    fn callback<IN, F>(&self, converter: F) -> Callback<IN>
+    where
+        F: Fn(IN) -> MSG + 'static,
+    {
+        let tx = RefCell::new(self.sender.clone());
+        Callback::from(move |value| {
+            let msg = converter(value);
+            tx.borrow_mut().send(msg);
+        })
+    }
+}
+
+/// Returns a callback which calls the `callback` with the last value
+/// when `delay` passed without new values.
+pub fn debounce<IN, C>(delay: Duration, callback: C) -> Callback<IN>
+where
+    IN: 'static,
+    C: Into<Callback<IN>>,
+{
+    debounced(Limited::new(delay, callback.into(), backend::timers()))
+}
+
+fn debounced<IN: 'static>(state: Rc<RefCell<Limited<IN>>>) -> Callback<IN> {
+    Callback::from(move |value| {
+        state.borrow_mut().value = Some(value);
+        start(&state, |state| {
+            emit_pending(state);
+        });
+    })
+}
+
+/// Returns a callback which calls the `callback` at once and then at most
+/// once per `period`. The last value which came during a period is passed
+/// when the period ends.
+pub fn throttle<IN, C>(period: Duration, callback: C) -> Callback<IN>
+where
+    IN: 'static,
+    C: Into<Callback<IN>>,
+{
+    throttled(Limited::new(period, callback.into(), backend::timers()))
+}
+
+fn throttled<IN: 'static>(state: Rc<RefCell<Limited<IN>>>) -> Callback<IN> {
+    Callback::from(move |value| {
+        if state.borrow().task.is_some() {
+            state.borrow_mut().value = Some(value);
+            return;
+        }
+        let callback = state.borrow().callback.clone();
+        callback.emit(value);
+        start(&state, close_period);
+    })
+}
+
+/// Ends a period of `throttle` and starts the next one if a value was passed.
+fn close_period<IN: 'static>(state: &Rc<RefCell<Limited<IN>>>) {
+    if emit_pending(state) {
+        start(state, close_period);
+    }
+}
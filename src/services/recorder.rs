@@ -0,0 +1,371 @@
+//! This module contains a service which records streams of the capture
+//! service with `MediaRecorder`, like voice memos of the microphone or
+//! recordings of a screen share:
+//!
+//! ```rust,ignore
+//! Msg::Devices(ShareEvent::Started(stream)) => {
+//!     let options = RecordOptions::new().timeslice(Duration::from_secs(1));
+//!     self.recording = Some(self.recorder.record(&stream, options, Msg::Record));
+//! }
+//! Msg::Record(RecordEvent::Chunk(chunk)) => self.upload.push(chunk.data),
+//! Msg::Record(RecordEvent::Stopped(memo)) => self.memos.push(memo),
+//! Msg::Stop => if let Some(ref recording) = self.recording { recording.stop(); },
+//! ```
+//!
+//! A recording doesn't stop the stream, drop its `ShareHandle` after it.
+
+use std::fmt;
+use std::time::Duration;
+use stdweb::Value;
+use stdweb::unstable::TryInto;
+use stdweb::web::ArrayBuffer;
+use html::AppSender;
+use super::{Task, is_done, to_ms};
+use super::capture::MediaStream;
+
+/// Options of a recording.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecordOptions {
+    /// The MIME type of the recording, like `audio/webm;codecs=opus`.
+    /// The browser chooses if it's `None`.
+    pub mime: Option<String>,
+    /// Sends chunks of the recording every period if it's set.
+    pub timeslice: Option<Duration>,
+    /// The bitrate of the audio and the video together.
+    pub bits_per_second: Option<u32>,
+}
+
+impl RecordOptions {
+    /// Creates options with defaults of the browser.
+    pub fn new() -> Self {
+        RecordOptions::default()
+    }
+
+    /// Records with the MIME type, check `is_type_supported` first.
+    pub fn mime(mut self, mime: &str) -> Self {
+        self.mime = Some(mime.to_owned());
+        self
+    }
+
+    /// Sends chunks of the recording every period.
+    pub fn timeslice(mut self, timeslice: Duration) -> Self {
+        self.timeslice = Some(timeslice);
+        self
+    }
+
+    /// Sets the bitrate.
+    pub fn bits_per_second(mut self, bits_per_second: u32) -> Self {
+        self.bits_per_second = Some(bits_per_second);
+        self
+    }
+}
+
+/// A part of a recording which is sent while it's recorded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    /// The index of the chunk from 0.
+    pub index: usize,
+    /// Encoded data of the chunk. Chunks are parts of one file.
+    pub data: Vec<u8>,
+}
+
+/// A finished recording.
+#[derive(Debug, PartialEq)]
+pub struct Recording {
+    /// An object url to use in `src` of an `<audio>` or a `<video>`.
+    /// It's revoked when the recording is dropped.
+    pub url: String,
+    /// The MIME type of the recording.
+    pub mime: String,
+    /// The size of the recording in bytes.
+    pub size: u64,
+    /// How long it was recorded, without pauses.
+    pub duration: Duration,
+    blob: Value,
+}
+
+impl Recording {
+    #[doc(hidden)]
+    pub fn raw(&self) -> &Value {
+        &self.blob
+    }
+}
+
+impl Drop for Recording {
+    fn drop(&mut self) {
+        js! { @(no_return)
+            URL.revokeObjectURL(@{&self.url});
+        }
+    }
+}
+
+/// An error of a recording.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordError {
+    /// The browser doesn't have `MediaRecorder` or can't record the type.
+    Unsupported,
+    /// A stream has no running tracks.
+    Inactive,
+    /// Another error with its message.
+    Failed(String),
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RecordError::Unsupported => write!(f, "the browser can't record the stream"),
+            RecordError::Inactive => write!(f, "the stream is stopped"),
+            RecordError::Failed(ref reason) => write!(f, "recording error: {}", reason),
+        }
+    }
+}
+
+/// Events of a recording.
+#[derive(Debug, PartialEq)]
+pub enum RecordEvent {
+    /// Recording started.
+    Started,
+    /// Recording was paused.
+    Paused,
+    /// Recording continues after a pause.
+    Resumed,
+    /// A chunk is recorded. It's sent every timeslice and before `Stopped`.
+    Chunk(Chunk),
+    /// Recording stopped, by `stop` or because the stream ended, after all chunks.
+    Stopped(Recording),
+    /// Recording stopped with the error.
+    Failed(RecordError),
+}
+
+/// A handle of a recording. The recording is discarded when it's canceled,
+/// call `stop` to finish it.
+pub struct RecorderHandle(Option<Value>);
+
+impl RecorderHandle {
+    /// Pauses the recording.
+    pub fn pause(&self) {
+        self.call("pause");
+    }
+
+    /// Continues the paused recording.
+    pub fn resume(&self) {
+        self.call("resume");
+    }
+
+    /// Finishes the recording, `Stopped` is sent after the last chunk.
+    pub fn stop(&self) {
+        self.call("stop");
+    }
+
+    fn call(&self, method: &str) {
+        if let Some(ref handle) = self.0 {
+            js! { @(no_return)
+                var handle = @{handle};
+                var method = @{method};
+                if (handle.done !== true && handle.recorder !== null) {
+                    try {
+                        handle.recorder[method]();
+                    } catch (error) {
+                        // A recorder throws if it's in another state, like a pause of a pause
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns `true` if the browser records the MIME type.
+pub fn is_type_supported(mime: &str) -> bool {
+    let supported: Value = js! {
+        return typeof MediaRecorder === "function" && MediaRecorder.isTypeSupported(@{mime});
+    };
+    supported == Value::Bool(true)
+}
+
+/// A service to record streams.
+pub struct RecorderService<MSG> {
+    sender: AppSender<MSG>,
+}
+
+impl<MSG: 'static> RecorderService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self { sender }
+    }
+
+    /// Starts recording the stream and sends events until it's stopped.
+    pub fn record<F>(&mut self, stream: &MediaStream, options: RecordOptions, converter: F) -> RecorderHandle
+    where
+        F: Fn(RecordEvent) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let callback = move |kind: String, value: Value| {
+            let event = match kind.as_str() {
+                "started" => RecordEvent::Started,
+                "paused" => RecordEvent::Paused,
+                "resumed" => RecordEvent::Resumed,
+                "chunk" => {
+                    let index: f64 = js!( return @{&value}.index; ).try_into().unwrap_or(0.0);
+                    let data: Option<ArrayBuffer> = js!( return @{&value}.data; ).try_into().ok();
+                    RecordEvent::Chunk(Chunk {
+                        index: index as usize,
+                        data: data.map(Vec::from).unwrap_or_default(),
+                    })
+                }
+                "stopped" => {
+                    let size: f64 = js!( return @{&value}.blob.size; ).try_into().unwrap_or(0.0);
+                    let duration: f64 = js!( return @{&value}.duration; ).try_into().unwrap_or(0.0);
+                    RecordEvent::Stopped(Recording {
+                        url: js!( return @{&value}.url; ).try_into().unwrap_or_default(),
+                        mime: js!( return @{&value}.blob.type; ).try_into().unwrap_or_default(),
+                        size: size as u64,
+                        duration: Duration::from_millis(duration as u64),
+                        blob: js!( return @{&value}.blob; ),
+                    })
+                }
+                "NotSupportedError" => RecordEvent::Failed(RecordError::Unsupported),
+                "InvalidStateError" => RecordEvent::Failed(RecordError::Inactive),
+                _ => {
+                    let message: String = value.try_into().unwrap_or_default();
+                    RecordEvent::Failed(RecordError::Failed(message))
+                }
+            };
+            tx.send(converter(event));
+        };
+        let timeslice = options.timeslice.map(to_ms);
+        let handle = js! {
+            var callback = @{callback};
+            var stream = @{stream.raw()};
+            var mime = @{options.mime};
+            var bits = @{options.bits_per_second};
+            var timeslice = @{timeslice};
+            var handle = {
+                recorder: null,
+                callback,
+            };
+            handle.stop = function() {
+                handle.done = true;
+                if (handle.recorder !== null && handle.recorder.state !== "inactive") {
+                    handle.recorder.ondataavailable = null;
+                    handle.recorder.onstop = null;
+                    handle.recorder.stop();
+                }
+                callback.drop();
+            };
+            function fail(error) {
+                if (handle.done !== true) {
+                    callback(error && error.name || "failed", String(error && error.message || error));
+                    handle.stop();
+                }
+            }
+            if (typeof MediaRecorder !== "function" || (mime !== null && !MediaRecorder.isTypeSupported(mime))) {
+                Promise.resolve().then(function() { fail(new DOMException("", "NotSupportedError")); });
+                return handle;
+            }
+            var options = {};
+            if (mime !== null) {
+                options.mimeType = mime;
+            }
+            if (bits !== null) {
+                options.bitsPerSecond = bits;
+            }
+            var recorder;
+            try {
+                recorder = new MediaRecorder(stream, options);
+            } catch (error) {
+                Promise.resolve().then(function() { fail(error); });
+                return handle;
+            }
+            handle.recorder = recorder;
+            var blobs = [];
+            var index = 0;
+            // Chunks are read in order, so the final blob waits for the last one
+            var queue = Promise.resolve();
+            var elapsed = 0;
+            var since = null;
+            function pause() {
+                if (since !== null) {
+                    elapsed += performance.now() - since;
+                    since = null;
+                }
+            }
+            recorder.ondataavailable = function(event) {
+                if (event.data.size === 0) {
+                    return;
+                }
+                var blob = event.data;
+                blobs.push(blob);
+                if (timeslice === null) {
+                    return;
+                }
+                var chunk = index++;
+                queue = queue.then(function() {
+                    return blob.arrayBuffer();
+                }).then(function(data) {
+                    if (handle.done !== true) {
+                        callback("chunk", { index: chunk, data: data });
+                    }
+                });
+            };
+            recorder.onstart = function() {
+                since = performance.now();
+                callback("started", null);
+            };
+            recorder.onpause = function() {
+                pause();
+                callback("paused", null);
+            };
+            recorder.onresume = function() {
+                since = performance.now();
+                callback("resumed", null);
+            };
+            recorder.onerror = function(event) {
+                fail(event.error);
+            };
+            recorder.onstop = function() {
+                pause();
+                queue.then(function() {
+                    if (handle.done === true) {
+                        return;
+                    }
+                    var blob = new Blob(blobs, { type: recorder.mimeType });
+                    callback("stopped", { blob: blob, url: URL.createObjectURL(blob), duration: elapsed });
+                    handle.done = true;
+                    callback.drop();
+                }).catch(fail);
+            };
+            try {
+                if (timeslice === null) {
+                    recorder.start();
+                } else {
+                    recorder.start(timeslice);
+                }
+            } catch (error) {
+                Promise.resolve().then(function() { fail(error); });
+            }
+            return handle;
+        };
+        RecorderHandle(Some(handle))
+    }
+}
+
+impl Task for RecorderHandle {
+    fn is_active(&self) -> bool {
+        self.0.as_ref().map_or(false, |handle| !is_done(handle))
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel a recording twice");
+        js! { @(no_return)
+            @{handle}.stop();
+        }
+    }
+}
+
+impl Drop for RecorderHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
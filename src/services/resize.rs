@@ -0,0 +1,100 @@
+//! This module contains the implementation of a service which
+//! notifies about changes of the size of the window.
+
+use stdweb::Value;
+use stdweb::unstable::TryInto;
+use html::AppSender;
+use super::Task;
+
+/// An inner size of the window in pixels.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct WindowSize {
+    /// The width of the viewport.
+    pub width: f64,
+    /// The height of the viewport.
+    pub height: f64,
+}
+
+/// A handle of a listener of the `resize` event of the window.
+pub struct ResizeHandle(Option<Value>);
+
+/// A service which sends messages when the window is resized.
+pub struct ResizeService<MSG> {
+    sender: AppSender<MSG>,
+}
+
+impl<MSG: 'static> ResizeService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self { sender }
+    }
+
+    /// Returns the current size of the window.
+    pub fn size(&self) -> WindowSize {
+        let value: Value = js! {
+            return [window.innerWidth, window.innerHeight];
+        };
+        let size: Vec<f64> = value.try_into().unwrap_or_default();
+        WindowSize {
+            width: size.first().cloned().unwrap_or(0.0),
+            height: size.get(1).cloned().unwrap_or(0.0),
+        }
+    }
+
+    /// Sends a message returned by the converter with a new size every time
+    /// the window is resized. Notifications are throttled to animation frames.
+    pub fn register<F>(&mut self, converter: F) -> ResizeHandle
+    where
+        F: Fn(WindowSize) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let callback = move |width: f64, height: f64| {
+            let msg = converter(WindowSize { width, height });
+            tx.send(msg);
+        };
+        let handle = js! {
+            var callback = @{callback};
+            var handle = {
+                frame: null,
+                callback,
+            };
+            handle.listener = function() {
+                if (handle.frame === null) {
+                    handle.frame = requestAnimationFrame(function() {
+                        handle.frame = null;
+                        callback(window.innerWidth, window.innerHeight);
+                    });
+                }
+            };
+            window.addEventListener("resize", handle.listener);
+            return handle;
+        };
+        ResizeHandle(Some(handle))
+    }
+}
+
+impl Task for ResizeHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel resize listener twice");
+        js! { @(no_return)
+            var handle = @{handle};
+            window.removeEventListener("resize", handle.listener);
+            if (handle.frame !== null) {
+                cancelAnimationFrame(handle.frame);
+            }
+            handle.callback.drop();
+        }
+    }
+}
+
+impl Drop for ResizeHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
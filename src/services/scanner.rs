@@ -0,0 +1,263 @@
+//! This module contains a service which scans QR codes and barcodes with
+//! a camera. It plays the camera in a rendered `<video>` element, so a user
+//! sees what's scanned, and detects codes in frames with `BarcodeDetector`:
+//!
+//! ```rust,ignore
+//! html! { <video ref=&self.video, muted=true, playsinline=true,/> }
+//!
+//! let options = ScanOptions::new().formats(&["qr_code", "ean_13"]);
+//! self.scan = Some(self.scanner.start(&self.video, options, Msg::Scan));
+//!
+//! Msg::Scan(ScanEvent::Detected(code)) => self.ticket = Some(code.value),
+//! ```
+//!
+//! The camera is stopped when the handle is dropped. Browsers without
+//! `BarcodeDetector` send `ScanError::Unsupported`.
+
+use std::time::Duration;
+use stdweb::Value;
+use html::AppSender;
+use virtual_dom::NodeRef;
+use super::{Task, is_done, to_ms};
+
+/// A camera to scan with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Facing {
+    /// A camera on the back of a phone.
+    Environment,
+    /// A camera which faces a user.
+    User,
+}
+
+/// Options of scanning.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanOptions {
+    /// Formats of codes to detect, like `qr_code` or `ean_13`. All formats
+    /// of the browser are detected if it's empty.
+    pub formats: Vec<String>,
+    /// The camera to scan with.
+    pub facing: Facing,
+    /// How often frames are checked.
+    pub period: Duration,
+    /// A code which was seen is reported again only after this pause,
+    /// so a code in front of the camera isn't reported every frame.
+    pub repeat_after: Duration,
+}
+
+impl ScanOptions {
+    /// Creates options which detect all formats with the back camera 5 times a second.
+    pub fn new() -> Self {
+        ScanOptions {
+            formats: Vec::new(),
+            facing: Facing::Environment,
+            period: Duration::from_millis(200),
+            repeat_after: Duration::from_secs(2),
+        }
+    }
+
+    /// Detects the formats only.
+    pub fn formats(mut self, formats: &[&str]) -> Self {
+        self.formats = formats.iter().map(|format| format.to_string()).collect();
+        self
+    }
+
+    /// Scans with the camera.
+    pub fn facing(mut self, facing: Facing) -> Self {
+        self.facing = facing;
+        self
+    }
+
+    /// Checks frames with the period.
+    pub fn every(mut self, period: Duration) -> Self {
+        self.period = period;
+        self
+    }
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        ScanOptions::new()
+    }
+}
+
+/// A detected code.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Barcode {
+    /// The format of the code, like `qr_code`.
+    pub format: String,
+    /// The decoded contents of the code.
+    pub value: String,
+}
+
+/// An error of scanning.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScanError {
+    /// The browser can't detect codes or use cameras.
+    Unsupported,
+    /// A user didn't allow to use the camera.
+    Denied,
+    /// There is no camera or the video element isn't rendered.
+    NoCamera,
+    /// Another error with its message.
+    Failed(String),
+}
+
+/// Events of scanning.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScanEvent {
+    /// The camera plays and frames are checked.
+    Started,
+    /// A code was detected.
+    Detected(Barcode),
+    /// Scanning stopped with the error.
+    Failed(ScanError),
+}
+
+/// A handle of scanning. The camera is stopped when it's canceled.
+pub struct ScanHandle(Option<Value>);
+
+/// Returns `true` if the browser detects codes and has cameras.
+pub fn is_supported() -> bool {
+    let supported: Value = js! {
+        return typeof BarcodeDetector === "function" && !!(navigator.mediaDevices && navigator.mediaDevices.getUserMedia);
+    };
+    supported == Value::Bool(true)
+}
+
+/// A service to scan codes with a camera.
+pub struct ScannerService<MSG> {
+    sender: AppSender<MSG>,
+}
+
+impl<MSG: 'static> ScannerService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self { sender }
+    }
+
+    /// Asks for the camera, plays it in the video element and sends events
+    /// until the handle is canceled or an error happens.
+    pub fn start<F>(&mut self, video: &NodeRef, options: ScanOptions, converter: F) -> ScanHandle
+    where
+        F: Fn(ScanEvent) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let callback = move |kind: String, first: String, second: String| {
+            let event = match kind.as_str() {
+                "started" => ScanEvent::Started,
+                "detected" => ScanEvent::Detected(Barcode { format: first, value: second }),
+                "unsupported" => ScanEvent::Failed(ScanError::Unsupported),
+                "NotAllowedError" | "SecurityError" => ScanEvent::Failed(ScanError::Denied),
+                "missing" | "NotFoundError" | "OverconstrainedError" => ScanEvent::Failed(ScanError::NoCamera),
+                _ => ScanEvent::Failed(ScanError::Failed(first)),
+            };
+            tx.send(converter(event));
+        };
+        let facing = match options.facing {
+            Facing::Environment => "environment",
+            Facing::User => "user",
+        };
+        let video = video.get();
+        let handle = js! {
+            var callback = @{callback};
+            var video = @{video};
+            var formats = @{options.formats};
+            var period = @{to_ms(options.period)};
+            var repeatAfter = @{to_ms(options.repeat_after)};
+            var handle = {
+                stream: null,
+                timer: null,
+                seen: {},
+                callback,
+            };
+            handle.stop = function() {
+                handle.done = true;
+                if (handle.timer !== null) {
+                    clearInterval(handle.timer);
+                }
+                if (handle.stream !== null) {
+                    handle.stream.getTracks().forEach(function(track) { track.stop(); });
+                }
+                if (video) {
+                    video.srcObject = null;
+                }
+                callback.drop();
+            };
+            function fail(kind, message) {
+                if (handle.done !== true) {
+                    callback(kind, message, "");
+                    handle.stop();
+                }
+            }
+            if (typeof BarcodeDetector !== "function" || !(navigator.mediaDevices && navigator.mediaDevices.getUserMedia)) {
+                Promise.resolve().then(function() { fail("unsupported", ""); });
+                return handle;
+            }
+            if (!video) {
+                Promise.resolve().then(function() { fail("missing", ""); });
+                return handle;
+            }
+            var detector = formats.length > 0 ? new BarcodeDetector({ formats: formats }) : new BarcodeDetector();
+            navigator.mediaDevices.getUserMedia({ video: { facingMode: @{facing} }, audio: false }).then(function(stream) {
+                if (handle.done === true) {
+                    stream.getTracks().forEach(function(track) { track.stop(); });
+                    return;
+                }
+                handle.stream = stream;
+                video.srcObject = stream;
+                return video.play().then(function() {
+                    if (handle.done === true) {
+                        return;
+                    }
+                    callback("started", "", "");
+                    var busy = false;
+                    handle.timer = setInterval(function() {
+                        if (busy || video.readyState < 2) {
+                            return;
+                        }
+                        busy = true;
+                        detector.detect(video).then(function(codes) {
+                            busy = false;
+                            var now = Date.now();
+                            codes.forEach(function(code) {
+                                var key = code.format + ":" + code.rawValue;
+                                if (handle.done !== true && !(now - handle.seen[key] < repeatAfter)) {
+                                    callback("detected", code.format, code.rawValue);
+                                }
+                                handle.seen[key] = now;
+                            });
+                        }, function(error) {
+                            busy = false;
+                            fail("failed", String(error && error.message || error));
+                        });
+                    }, period);
+                });
+            }).catch(function(error) {
+                fail(error && error.name || "failed", String(error && error.message || error));
+            });
+            return handle;
+        };
+        ScanHandle(Some(handle))
+    }
+}
+
+impl Task for ScanHandle {
+    fn is_active(&self) -> bool {
+        self.0.as_ref().map_or(false, |handle| !is_done(handle))
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel scanning twice");
+        js! { @(no_return)
+            @{handle}.stop();
+        }
+    }
+}
+
+impl Drop for ScanHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! This module contains the implementation of a service to
+//! load scripts (chunks of `Lazy` and routes) on demand.
+
+use stdweb::Value;
+use html::AppSender;
+use prefetch::Destination;
+use super::{Task, is_done};
+
+/// A handle to cancel a loading of a script. The script
+/// can't be unloaded, but the callback won't be called.
+pub struct ScriptHandle(Option<Value>);
+
+/// A service to load scripts by inserting `script` tags into `head`.
+pub struct ScriptService<MSG> {
+    sender: AppSender<MSG>,
+}
+
+impl<MSG: 'static> ScriptService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self { sender }
+    }
+
+    /// Loads a script from the `url` and sends a message returned by a converter
+    /// when the script loaded or failed. Scripts which were loaded (or started loading)
+    /// before aren't inserted twice.
+    pub fn load<F>(&mut self, url: &str, converter: F) -> ScriptHandle
+    where
+        F: Fn(Result<(), String>) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let callback = move |success: bool, reason: String| {
+            let result = if success { Ok(()) } else { Err(reason) };
+            let msg = converter(result);
+            tx.send(msg);
+        };
+        #[cfg(feature = "csp")]
+        let nonce = ::csp::nonce();
+        #[cfg(not(feature = "csp"))]
+        let nonce: Option<String> = None;
+        let handle = js! {
+            var url = @{url};
+            var nonce = @{nonce};
+            var callback = @{callback};
+            var handle = {
+                interrupted: false,
+                callback,
+            };
+            var scripts = window.__yew_scripts || (window.__yew_scripts = {});
+            var loading = scripts[url];
+            if (loading === undefined) {
+                loading = new Promise(function(resolve, reject) {
+                    var script = document.createElement("script");
+                    script.src = url;
+                    script.async = true;
+                    if (nonce) {
+                        script.nonce = nonce;
+                    }
+                    script.onload = function() {
+                        var loaded = window.__yew_loaded_scripts || (window.__yew_loaded_scripts = {});
+                        loaded[url] = true;
+                        resolve();
+                    };
+                    script.onerror = function() {
+                        delete scripts[url];
+                        reject("can't load script: " + url);
+                    };
+                    document.head.appendChild(script);
+                });
+                scripts[url] = loading;
+            }
+            loading.then(function() {
+                if (handle.interrupted != true) {
+                    handle.done = true;
+                    callback(true, "");
+                    callback.drop();
+                }
+            }, function(reason) {
+                if (handle.interrupted != true) {
+                    handle.done = true;
+                    callback(false, String(reason));
+                    callback.drop();
+                }
+            });
+            return handle;
+        };
+        ScriptHandle(Some(handle))
+    }
+
+    /// Returns `true` if a script from the `url` was loaded by the service.
+    pub fn is_loaded(&self, url: &str) -> bool {
+        let loaded: Value = js! {
+            var loaded = window.__yew_loaded_scripts || {};
+            return loaded[@{url}] === true;
+        };
+        loaded == Value::Bool(true)
+    }
+
+    /// Asks a browser to download a script from the `url` in the background
+    /// without running it, so a later `load` gets it from the cache.
+    pub fn prefetch(&mut self, url: &str) {
+        if !is_requested(url) {
+            ::prefetch::prefetch(url, Destination::Script);
+        }
+    }
+}
+
+/// Returns `true` if a script from the `url` was loaded or started loading.
+pub(crate) fn is_requested(url: &str) -> bool {
+    let requested: Value = js! {
+        var scripts = window.__yew_scripts || {};
+        return scripts[@{url}] !== undefined;
+    };
+    requested == Value::Bool(true)
+}
+
+impl Task for ScriptHandle {
+    fn is_active(&self) -> bool {
+        self.0.as_ref().map_or(false, |handle| !is_done(handle))
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to cancel script loading twice");
+        js! { @(no_return)
+            var handle = @{handle};
+            handle.interrupted = true;
+            handle.callback.drop();
+        }
+    }
+}
+
+impl Drop for ScriptHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
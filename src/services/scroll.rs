@@ -0,0 +1,66 @@
+//! This module contains the implementation of a service
+//! to read and change the scroll position of a page.
+
+use stdweb::Value;
+use stdweb::unstable::TryInto;
+
+/// A position of the scroll: horizontal and vertical offsets in pixels.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ScrollPosition {
+    /// The horizontal offset.
+    pub x: f64,
+    /// The vertical offset.
+    pub y: f64,
+}
+
+/// A scroll service.
+pub struct ScrollService;
+
+impl ScrollService {
+    /// Returns the current scroll position of the window.
+    pub fn position(&self) -> ScrollPosition {
+        let value: Value = js! {
+            return [window.pageXOffset, window.pageYOffset];
+        };
+        let offsets: Vec<f64> = value.try_into().unwrap_or_default();
+        ScrollPosition {
+            x: offsets.first().cloned().unwrap_or(0.0),
+            y: offsets.get(1).cloned().unwrap_or(0.0),
+        }
+    }
+
+    /// Scrolls the window to the position.
+    pub fn scroll_to(&mut self, position: ScrollPosition) {
+        js! { @(no_return) window.scrollTo(@{position.x}, @{position.y}); }
+    }
+
+    /// Scrolls the window to the top left corner.
+    pub fn scroll_to_top(&mut self) {
+        self.scroll_to(ScrollPosition::default());
+    }
+
+    /// Scrolls to an element with the `id` or with the `name` of an anchor.
+    /// Returns `false` if there is no such element.
+    pub fn scroll_to_fragment(&mut self, id: &str) -> bool {
+        let found: Value = js! {
+            var id = @{id};
+            var element = document.getElementById(id) || document.getElementsByName(id)[0];
+            if (!element) {
+                return false;
+            }
+            element.scrollIntoView();
+            return true;
+        };
+        found == Value::Bool(true)
+    }
+
+    /// Turns off the restoration of a browser, because it restores the scroll
+    /// before an app renders a page of the entry.
+    pub fn set_manual_restoration(&mut self) {
+        js! { @(no_return)
+            if ("scrollRestoration" in history) {
+                history.scrollRestoration = "manual";
+            }
+        }
+    }
+}
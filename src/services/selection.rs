@@ -0,0 +1,111 @@
+//! This module contains a service of the selection of the document, like to
+//! comment on selected text or to show a custom context menu for it:
+//!
+//! ```rust,ignore
+//! let targets = [self.article.clone()];
+//! self.handle = Some(self.selection.listen(&targets, Msg::Selected));
+//!
+//! Msg::Selected(Some(ref selection)) if !selection.collapsed => self.comment = selection.offsets.clone(),
+//! Msg::Selected(_) => self.comment = None,
+//! ```
+//!
+//! Nodes of the selection are reported as one of the target references
+//! which contains them, and offsets are counted in characters of the text
+//! of the target, so they're stable across renders of the same text.
+
+use std::ops::Range;
+use std::rc::Rc;
+use html::AppSender;
+use position::Rect;
+use virtual_dom::NodeRef;
+use super::Task;
+use super::backend::{self, SelectionBackend};
+
+/// The selection of the document.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Selection {
+    /// The selected text.
+    pub text: String,
+    /// `true` if nothing is selected, only the caret is placed.
+    pub collapsed: bool,
+    /// The innermost target which contains the node where the selection starts,
+    /// at the point a user pressed.
+    pub anchor: Option<NodeRef>,
+    /// The innermost target which contains the node where the selection ends.
+    pub focus: Option<NodeRef>,
+    /// Offsets of the selected text in characters of the text of the target
+    /// if the selection is within one target.
+    pub offsets: Option<Range<usize>>,
+    /// The rectangle of the selected text, like to place a menu over it.
+    pub rect: Option<Rect>,
+}
+
+/// A handle of a listener of the selection. It's canceled when dropped.
+pub struct SelectionHandle(Option<Box<Task>>);
+
+impl Task for SelectionHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let mut listener = self.0.take().expect("tried to cancel a selection listener twice");
+        listener.cancel();
+    }
+}
+
+impl Drop for SelectionHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+/// A service of the selection of the document.
+pub struct SelectionService<MSG> {
+    sender: AppSender<MSG>,
+    backend: Rc<SelectionBackend>,
+}
+
+impl<MSG: 'static> SelectionService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self::with_backend(sender, backend::selection())
+    }
+
+    /// Creates a new service instance which uses the backend.
+    pub fn with_backend(sender: AppSender<MSG>, backend: Rc<SelectionBackend>) -> Self {
+        Self { sender, backend }
+    }
+
+    /// Returns the selection with nodes mapped to the targets,
+    /// or `None` if the document has no selection.
+    pub fn get(&self, targets: &[NodeRef]) -> Option<Selection> {
+        self.backend.selection(targets)
+    }
+
+    /// Sends a message with the selection every time it's changed.
+    pub fn listen<F>(&mut self, targets: &[NodeRef], converter: F) -> SelectionHandle
+    where
+        F: Fn(Option<Selection>) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let backend = self.backend.clone();
+        let targets = targets.to_vec();
+        let callback = move || tx.send(converter(backend.selection(&targets)));
+        SelectionHandle(Some(self.backend.listen(Box::new(callback))))
+    }
+
+    /// Selects characters of the text of the target, like to show which
+    /// text a comment is about. Returns `false` if the target isn't rendered
+    /// or the offsets are out of its text.
+    pub fn select(&mut self, target: &NodeRef, offsets: Range<usize>) -> bool {
+        self.backend.select(target, offsets)
+    }
+
+    /// Removes the selection.
+    pub fn clear(&mut self) {
+        self.backend.clear();
+    }
+}
@@ -0,0 +1,375 @@
+//! This module contains a service of Web Serial, so configurators talk
+//! to boards and other devices with serial ports:
+//!
+//! ```rust,ignore
+//! self.task = Some(self.serial.request(&[0x2341], Msg::Chosen));
+//!
+//! Msg::Chosen(Ok(port)) => self.port = Some(self.serial.open(&port, SerialOptions::baud_rate(115_200), Msg::Serial)),
+//! Msg::Serial(SerialEvent::Data(bytes)) => self.log.extend(bytes),
+//! Msg::Send(line) => if let Some(ref port) = self.port { port.write(line.as_bytes()); },
+//! ```
+//!
+//! A chooser needs a user gesture, so it's opened from messages of click
+//! events. Enable the `serial` feature.
+
+use std::fmt;
+use stdweb::Value;
+use stdweb::unstable::TryFrom;
+use html::AppSender;
+use super::{Task, is_done};
+use super::promise::{self, PromiseHandle};
+
+/// An error of a serial port.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SerialError {
+    /// The browser doesn't have Web Serial.
+    Unsupported,
+    /// A user or a policy of the page didn't allow serial ports.
+    Denied,
+    /// A user closed the chooser without choosing a port.
+    NotFound,
+    /// The port is opened by another page or app.
+    Busy,
+    /// The device was unplugged or the connection broke.
+    Lost,
+    /// Another error with its message.
+    Failed(String),
+}
+
+impl fmt::Display for SerialError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SerialError::Unsupported => write!(f, "Web Serial isn't supported"),
+            SerialError::Denied => write!(f, "an access to serial ports was denied"),
+            SerialError::NotFound => write!(f, "no serial port was chosen"),
+            SerialError::Busy => write!(f, "a serial port is already open"),
+            SerialError::Lost => write!(f, "a serial device was lost"),
+            SerialError::Failed(ref reason) => write!(f, "serial error: {}", reason),
+        }
+    }
+}
+
+/// A parity check of a port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parity {
+    /// No parity bit.
+    None,
+    /// An even parity bit.
+    Even,
+    /// An odd parity bit.
+    Odd,
+}
+
+/// Settings of an opened port.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SerialOptions {
+    /// The speed in bits a second.
+    pub baud_rate: u32,
+    /// Bits of a frame, 7 or 8.
+    pub data_bits: u8,
+    /// Stop bits of a frame, 1 or 2.
+    pub stop_bits: u8,
+    /// A parity check.
+    pub parity: Parity,
+    /// Uses RTS and CTS to control the flow.
+    pub hardware_flow_control: bool,
+}
+
+impl SerialOptions {
+    /// Creates settings with the speed and 8 data bits, 1 stop bit and no parity.
+    pub fn baud_rate(baud_rate: u32) -> Self {
+        SerialOptions {
+            baud_rate,
+            data_bits: 8,
+            stop_bits: 1,
+            parity: Parity::None,
+            hardware_flow_control: false,
+        }
+    }
+
+    /// Sets bits of a frame.
+    pub fn frame(mut self, data_bits: u8, parity: Parity, stop_bits: u8) -> Self {
+        self.data_bits = data_bits;
+        self.parity = parity;
+        self.stop_bits = stop_bits;
+        self
+    }
+
+    /// Turns hardware flow control on.
+    pub fn hardware_flow_control(mut self) -> Self {
+        self.hardware_flow_control = true;
+        self
+    }
+}
+
+/// A port which a user allowed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SerialPort(Value);
+
+impl SerialPort {
+    /// Returns the USB vendor id and product id of the device if it has them.
+    pub fn usb_ids(&self) -> Option<(u16, u16)> {
+        let info: Value = js! {
+            var info = @{&self.0}.getInfo();
+            return info.usbVendorId === undefined ? null : [info.usbVendorId, info.usbProductId || 0];
+        };
+        let ids = Vec::<u32>::try_from(info).ok()?;
+        match ids.as_slice() {
+            [vendor, product] => Some((*vendor as u16, *product as u16)),
+            _ => None,
+        }
+    }
+}
+
+/// Events of an opened port.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SerialEvent {
+    /// The port is open.
+    Opened,
+    /// The device sent the bytes.
+    Data(Vec<u8>),
+    /// The port is closed by the device.
+    Closed,
+    /// The port didn't open or broke with the error.
+    Failed(SerialError),
+}
+
+/// A handle of an opened port. The port is closed when it's canceled.
+pub struct SerialHandle(Option<Value>);
+
+impl SerialHandle {
+    /// Writes the bytes to the port after previous writes.
+    /// Returns `false` if the port isn't open yet.
+    pub fn write(&self, data: &[u8]) -> bool {
+        let handle = match self.0 {
+            Some(ref handle) => handle,
+            None => return false,
+        };
+        let queued: Value = js! {
+            var handle = @{handle};
+            if (handle.done === true || handle.writer === null) {
+                return false;
+            }
+            var data = new Uint8Array(@{data.to_vec()});
+            handle.writes = handle.writes.then(function() {
+                return handle.writer.write(data);
+            }).catch(handle.fail);
+            return true;
+        };
+        queued == Value::Bool(true)
+    }
+}
+
+/// Returns `true` if the browser has Web Serial.
+pub fn is_supported() -> bool {
+    let supported: Value = js! {
+        return !!(navigator.serial && navigator.serial.requestPort);
+    };
+    supported == Value::Bool(true)
+}
+
+/// A service of serial ports.
+pub struct SerialService<MSG> {
+    sender: AppSender<MSG>,
+}
+
+impl<MSG: 'static> SerialService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self { sender }
+    }
+
+    /// Shows a chooser of ports of devices with the USB vendor ids, or of all
+    /// ports if it's empty, and sends the chosen one.
+    pub fn request<F>(&mut self, vendors: &[u16], converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<SerialPort, SerialError>) -> MSG + 'static,
+    {
+        let vendors: Vec<u32> = vendors.iter().map(|&vendor| u32::from(vendor)).collect();
+        let promise = js! {
+            if (!(navigator.serial && navigator.serial.requestPort)) {
+                return Promise.reject(new DOMException("", "UnsupportedError"));
+            }
+            var filters = @{vendors}.map(function(vendor) { return { usbVendorId: vendor }; });
+            return navigator.serial.requestPort({ filters: filters });
+        };
+        self.spawn(promise, |port| Some(SerialPort(port)), converter)
+    }
+
+    /// Sends ports which a user allowed before, so they're opened without a chooser.
+    pub fn ports<F>(&mut self, converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<Vec<SerialPort>, SerialError>) -> MSG + 'static,
+    {
+        let promise = js! {
+            if (!(navigator.serial && navigator.serial.getPorts)) {
+                return Promise.reject(new DOMException("", "UnsupportedError"));
+            }
+            return navigator.serial.getPorts();
+        };
+        self.spawn(promise, |ports| {
+            let ports = Vec::<Value>::try_from(ports).ok()?;
+            Some(ports.into_iter().map(SerialPort).collect())
+        }, converter)
+    }
+
+    /// Opens the port and sends its data until the handle is canceled
+    /// or the port is closed.
+    pub fn open<F>(&mut self, port: &SerialPort, options: SerialOptions, converter: F) -> SerialHandle
+    where
+        F: Fn(SerialEvent) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let callback = move |kind: String, value: Value| {
+            let event = match kind.as_str() {
+                "opened" => SerialEvent::Opened,
+                "data" => SerialEvent::Data(Vec::<u8>::try_from(value).unwrap_or_default()),
+                "closed" => SerialEvent::Closed,
+                _ => SerialEvent::Failed(error_of(&kind, value.into_string().unwrap_or_default())),
+            };
+            tx.send(converter(event));
+        };
+        let parity = match options.parity {
+            Parity::None => "none",
+            Parity::Even => "even",
+            Parity::Odd => "odd",
+        };
+        let handle = js! {
+            var callback = @{callback};
+            var port = @{&port.0};
+            var settings = {
+                baudRate: @{options.baud_rate},
+                dataBits: @{u32::from(options.data_bits)},
+                stopBits: @{u32::from(options.stop_bits)},
+                parity: @{parity},
+                flowControl: @{options.hardware_flow_control} ? "hardware" : "none",
+            };
+            var handle = {
+                opened: false,
+                reader: null,
+                writer: null,
+                writes: Promise.resolve(),
+                callback,
+            };
+            handle.finish = function(kind, value) {
+                if (handle.done === true) {
+                    return;
+                }
+                handle.done = true;
+                callback(kind, value);
+                callback.drop();
+                handle.close();
+            };
+            handle.fail = function(error) {
+                handle.finish(error && error.name || "failed", String(error && error.message || error));
+            };
+            handle.close = function() {
+                var reader = handle.reader;
+                var writer = handle.writer;
+                handle.reader = null;
+                handle.writer = null;
+                // Locks are released before the port is closed
+                var released = reader !== null ? reader.cancel().catch(function() {}) : Promise.resolve();
+                released.then(function() {
+                    if (reader !== null) {
+                        reader.releaseLock();
+                    }
+                    if (writer !== null) {
+                        writer.releaseLock();
+                    }
+                    if (handle.opened) {
+                        return port.close();
+                    }
+                }).catch(function() {});
+            };
+            handle.stop = function() {
+                if (handle.done !== true) {
+                    handle.done = true;
+                    callback.drop();
+                    handle.close();
+                }
+            };
+            if (typeof port.open !== "function") {
+                Promise.resolve().then(function() { handle.finish("UnsupportedError", ""); });
+                return handle;
+            }
+            port.open(settings).then(function() {
+                handle.opened = true;
+                if (handle.done === true) {
+                    return port.close();
+                }
+                handle.writer = port.writable.getWriter();
+                handle.reader = port.readable.getReader();
+                callback("opened", null);
+                var read = function() {
+                    if (handle.reader === null) {
+                        return;
+                    }
+                    handle.reader.read().then(function(result) {
+                        if (result.done) {
+                            handle.finish("closed", null);
+                        } else if (handle.done !== true) {
+                            callback("data", Array.from(result.value));
+                            read();
+                        }
+                    }).catch(handle.fail);
+                };
+                read();
+            }).catch(handle.fail);
+            return handle;
+        };
+        SerialHandle(Some(handle))
+    }
+
+    fn spawn<T, C, F>(&mut self, promise: Value, convert: C, converter: F) -> PromiseHandle
+    where
+        C: Fn(Value) -> Option<T> + 'static,
+        F: Fn(Result<T, SerialError>) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        promise::settle(promise, move |resolved, value| {
+            let result = if resolved {
+                convert(value).ok_or_else(|| SerialError::Failed("unexpected value".into()))
+            } else {
+                let name: Value = js! {
+                    var reason = @{&value};
+                    return reason && reason.name || "";
+                };
+                Err(error_of(&name.into_string().unwrap_or_default(), promise::reason(value)))
+            };
+            tx.send(converter(result));
+        })
+    }
+}
+
+fn error_of(name: &str, message: String) -> SerialError {
+    match name {
+        "UnsupportedError" => SerialError::Unsupported,
+        "NotAllowedError" | "SecurityError" => SerialError::Denied,
+        "NotFoundError" => SerialError::NotFound,
+        "InvalidStateError" => SerialError::Busy,
+        "NetworkError" | "BreakError" | "BufferOverrunError" | "FramingError" | "ParityError" => SerialError::Lost,
+        _ => SerialError::Failed(message),
+    }
+}
+
+impl Task for SerialHandle {
+    fn is_active(&self) -> bool {
+        self.0.as_ref().map_or(false, |handle| !is_done(handle))
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to close a serial port twice");
+        js! { @(no_return)
+            @{handle}.stop();
+        }
+    }
+}
+
+impl Drop for SerialHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
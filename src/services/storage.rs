@@ -1,10 +1,12 @@
 //! This module contains the implementation of a service to
 //! use local and session storage of a browser.
 
-use stdweb::Value;
+use std::rc::Rc;
 use format::{Storable, Restorable};
+use super::backend::{self, StorageBackend};
 
 /// A scope to keep the data in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Scope {
     /// Use `localStorage` of a browser.
     Local,
@@ -15,13 +17,19 @@ pub enum Scope {
 /// A storage service attached to a context.
 pub struct StorageService {
     scope: Scope,
+    backend: Rc<StorageBackend>,
 }
 
 impl StorageService {
 
     /// Creates a new storage service instance with specified storate scope.
     pub fn new(scope: Scope) -> Self {
-        StorageService { scope }
+        StorageService::with_backend(scope, backend::storage())
+    }
+
+    /// Creates a new storage service instance which keeps data in the `backend`.
+    pub fn with_backend(scope: Scope, backend: Rc<StorageBackend>) -> Self {
+        StorageService { scope, backend }
     }
 
     /// Stores value to the storage.
@@ -30,14 +38,7 @@ impl StorageService {
         T: Into<Storable>
     {
         if let Some(data) = value.into() {
-            match self.scope {
-                Scope::Local => { js! { @(no_return)
-                    localStorage.setItem(@{key}, @{data});
-                } },
-                Scope::Session => { js! { @(no_return)
-                    sessionStorage.setItem(@{key}, @{data});
-                } },
-            }
+            self.backend.set(self.scope, key, &data);
         }
     }
 
@@ -46,27 +47,13 @@ impl StorageService {
     where
         T : From<Restorable>
     {
-        let value: Value = {
-            match self.scope {
-                Scope::Local => js! { return localStorage.getItem(@{key}); },
-                Scope::Session => js! { return sessionStorage.getItem(@{key}); },
-            }
-        };
-        let data = value.into_string().ok_or_else(|| "can't read string from storage".into());
+        let data = self.backend.get(self.scope, key)
+            .ok_or_else(|| "can't read string from storage".into());
         T::from(data)
     }
 
     /// Removes value from the storage.
     pub fn remove(&mut self, key: &str) {
-        {
-            match self.scope {
-                Scope::Local => js! { @(no_return)
-                    localStorage.removeItem(@{key});
-                },
-                Scope::Session => js! { @(no_return)
-                    sessionStorage.removeItem(@{key});
-                },
-            }
-        };
+        self.backend.remove(self.scope, key);
     }
 }
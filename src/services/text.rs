@@ -0,0 +1,153 @@
+//! This module contains a service which measures text with a font before
+//! it's rendered, so virtualized lists know heights of rows and editors
+//! wrap lines themselves:
+//!
+//! ```rust,ignore
+//! let text = TextService::new("14px Inter, sans-serif");
+//! let heights = messages.iter().map(|message| text.height(&message.body, 320.0, 20.0)).collect::<Vec<_>>();
+//! props.heights = RowHeights::Variable(Rc::new(move |index| heights[index]));
+//! ```
+//!
+//! Fonts are CSS shorthands like in `font` of a style, and they have to be
+//! loaded before text is measured. Widths of words are cached by the service.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use position::Rect;
+use virtual_dom::NodeRef;
+use super::backend::{self, TextBackend};
+
+/// Sizes of measured text in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TextMetrics {
+    /// The advance width of the text.
+    pub width: f64,
+    /// The height above the baseline of the font.
+    pub ascent: f64,
+    /// The height below the baseline of the font.
+    pub descent: f64,
+}
+
+impl TextMetrics {
+    /// Returns the height of the font, from the ascent to the descent.
+    pub fn height(&self) -> f64 {
+        self.ascent + self.descent
+    }
+}
+
+/// A wrapped line of text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line {
+    /// The text of the line without spaces at its edges.
+    pub text: String,
+    /// The width of the line in pixels.
+    pub width: f64,
+}
+
+/// A service to measure text.
+pub struct TextService {
+    font: String,
+    backend: Rc<TextBackend>,
+    widths: RefCell<HashMap<String, f64>>,
+}
+
+impl TextService {
+    /// Creates a new service instance which measures with the CSS font.
+    pub fn new(font: &str) -> Self {
+        TextService::with_backend(font, backend::text())
+    }
+
+    /// Creates a new service instance which measures with the `backend`.
+    pub fn with_backend(font: &str, backend: Rc<TextBackend>) -> Self {
+        TextService {
+            font: font.to_owned(),
+            backend,
+            widths: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the font of measured text.
+    pub fn font(&self) -> &str {
+        &self.font
+    }
+
+    /// Changes the font of measured text and clears cached widths.
+    pub fn set_font(&mut self, font: &str) {
+        self.font = font.to_owned();
+        self.widths.borrow_mut().clear();
+    }
+
+    /// Measures the text on one line.
+    pub fn metrics(&self, text: &str) -> TextMetrics {
+        self.backend.measure(&self.font, text)
+    }
+
+    /// Returns the width of the text on one line.
+    pub fn width(&self, text: &str) -> f64 {
+        if let Some(width) = self.widths.borrow().get(text) {
+            return *width;
+        }
+        let width = self.metrics(text).width;
+        self.widths.borrow_mut().insert(text.to_owned(), width);
+        width
+    }
+
+    /// Wraps the text to lines which fit the width, like `white-space: normal`
+    /// does: spaces are collapsed, lines break between words and on newlines,
+    /// and words which are longer than a line are broken between characters.
+    pub fn wrap(&self, text: &str, max_width: f64) -> Vec<Line> {
+        let space = self.width(" ");
+        let mut lines = Vec::new();
+        for paragraph in text.split('\n') {
+            let mut line = Line { text: String::new(), width: 0.0 };
+            for word in paragraph.split_whitespace() {
+                let width = self.width(word);
+                if line.text.is_empty() {
+                    line = self.start(word, width, max_width, &mut lines);
+                } else if line.width + space + width <= max_width {
+                    line.text.push(' ');
+                    line.text.push_str(word);
+                    line.width += space + width;
+                } else {
+                    lines.push(line);
+                    line = self.start(word, width, max_width, &mut lines);
+                }
+            }
+            lines.push(line);
+        }
+        lines
+    }
+
+    /// Returns the height of the text wrapped to the width with the height of lines.
+    pub fn height(&self, text: &str, max_width: f64, line_height: f64) -> f64 {
+        self.wrap(text, max_width).len() as f64 * line_height
+    }
+
+    /// Returns the rectangle of the rendered contents of the element, like the
+    /// size of its text without padding, or `None` if it isn't rendered.
+    pub fn content_rect(&self, target: &NodeRef) -> Option<Rect> {
+        self.backend.content_rect(target)
+    }
+
+    /// Starts a line with the word, pushing full lines of a long word before it.
+    fn start(&self, word: &str, width: f64, max_width: f64, lines: &mut Vec<Line>) -> Line {
+        if width <= max_width {
+            return Line { text: word.to_owned(), width };
+        }
+        let mut line = Line { text: String::new(), width: 0.0 };
+        for c in word.chars() {
+            let mut text = line.text.clone();
+            text.push(c);
+            let width = self.width(&text);
+            if width > max_width && !line.text.is_empty() {
+                lines.push(line);
+                text = c.to_string();
+                line = Line { width: self.width(&text), text };
+            } else {
+                line = Line { text, width };
+            }
+        }
+        line
+    }
+}
@@ -1,23 +1,31 @@
 //! This module contains the implementation of a service to
 //! send a messages when timeout elapsed.
 
+use std::rc::Rc;
 use std::time::Duration;
-use stdweb::Value;
 use html::AppSender;
-use super::{Task, to_ms};
+use progress::{self, Kind};
+use super::Task;
+use super::backend::{self, TimerBackend};
 
 /// A handle to cancel a timeout task.
-pub struct TimeoutHandle(Option<Value>);
+pub struct TimeoutHandle(Box<Task>);
 
 /// An service to set a timeout.
 pub struct TimeoutService<MSG> {
     sender: AppSender<MSG>,
+    backend: Rc<TimerBackend>,
 }
 
 impl<MSG: 'static> TimeoutService<MSG> {
     /// Creates a new service instance connected to `App` by provided `sender`.
     pub fn new(sender: AppSender<MSG>) -> Self {
-        Self { sender }
+        Self::with_backend(sender, backend::timers())
+    }
+
+    /// Creates a new service instance which uses the `backend` for timers.
+    pub fn with_backend(sender: AppSender<MSG>, backend: Rc<TimerBackend>) -> Self {
+        Self { sender, backend }
     }
 
     /// Sets timeout which send a messages from a `converter` after `duration`.
@@ -26,34 +34,30 @@ impl<MSG: 'static> TimeoutService<MSG> {
         F: Fn() -> MSG + 'static,
     {
         let mut tx = self.sender.clone();
+        let mut pending = progress::pending(Kind::Timeout);
         let callback = move || {
+            pending.take();
             let msg = converter();
             tx.send(msg);
         };
-        let ms = to_ms(duration);
-        let handle = js! {
-            var callback = @{callback};
-            let action = function() {
-                callback();
-                callback.drop();
-            };
-            let delay = @{ms};
-            return {
-                timeout_id: setTimeout(action, delay),
-                callback,
-            };
-        };
-        TimeoutHandle(Some(handle))
+        TimeoutHandle(self.backend.timeout(duration, Box::new(callback)))
     }
 }
 
 impl Task for TimeoutHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_active()
+    }
+
     fn cancel(&mut self) {
-        let handle = self.0.take().expect("tried to cancel timeout twice");
-        js! { @(no_return)
-            var handle = @{handle};
-            clearTimeout(handle.timeout_id);
-            handle.callback.drop();
+        self.0.cancel();
+    }
+}
+
+impl Drop for TimeoutHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
         }
     }
 }
@@ -0,0 +1,133 @@
+//! This module contains a service which protects unsaved changes. Components
+//! register predicates which return `true` while they have unsaved changes,
+//! like an edited form:
+//!
+//! ```rust,ignore
+//! let form = self.form.clone();
+//! self.unsaved = Some(self.unsaved_service.register(move || form.borrow().is_dirty()));
+//! ```
+//!
+//! A browser asks a user to confirm reloads and closes of the page meanwhile,
+//! and a router with `unsaved::guard` asks before it leaves the route.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use router::{Guard, Navigation};
+use super::Task;
+use super::backend::{self, UnloadBackend};
+use super::dialog::DialogService;
+
+type Dirty = Rc<Fn() -> bool>;
+
+thread_local! {
+    static PREDICATES: RefCell<(usize, Vec<(usize, Dirty)>)> = RefCell::new((0, Vec::new()));
+}
+
+/// Returns `true` if a registered predicate has unsaved changes.
+pub fn is_unsaved() -> bool {
+    let predicates: Vec<Dirty> = PREDICATES.with(|predicates| {
+        predicates.borrow().1.iter().map(|(_, dirty)| dirty.clone()).collect()
+    });
+    predicates.iter().any(|dirty| dirty())
+}
+
+/// Returns a guard of a router which asks a user to confirm leaving with
+/// the message while there are unsaved changes, with a `confirm` dialog.
+pub fn guard<R>(message: &str) -> Guard<R> {
+    let message = message.to_owned();
+    guard_with(move |navigation: Navigation| {
+        if DialogService.confirm(&message) {
+            navigation.allow();
+        } else {
+            navigation.block();
+        }
+    })
+}
+
+/// Returns a guard of a router which calls `confirm` while there are unsaved
+/// changes, to allow or block the navigation later, like with a modal of
+/// the app. Other navigations are allowed at once.
+pub fn guard_with<R, F>(confirm: F) -> Guard<R>
+where
+    F: Fn(Navigation) + 'static,
+{
+    Guard::from(move |_: &R, navigation: Navigation| {
+        if is_unsaved() {
+            confirm(navigation);
+        } else {
+            navigation.allow();
+        }
+    })
+}
+
+/// A handle of a registered predicate which unregisters it when it's
+/// canceled or dropped.
+pub struct UnsavedHandle {
+    id: usize,
+    listener: Option<Box<Task>>,
+}
+
+impl Task for UnsavedHandle {
+    fn is_active(&self) -> bool {
+        self.listener.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let mut listener = self.listener.take().expect("tried to cancel an unregistered predicate");
+        listener.cancel();
+        let id = self.id;
+        PREDICATES.with(|predicates| predicates.borrow_mut().1.retain(|&(other, _)| other != id));
+    }
+}
+
+impl Drop for UnsavedHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+/// A service which registers predicates of unsaved changes.
+pub struct UnsavedService {
+    backend: Rc<UnloadBackend>,
+}
+
+impl Default for UnsavedService {
+    fn default() -> Self {
+        UnsavedService::new()
+    }
+}
+
+impl UnsavedService {
+    /// Creates a new service instance.
+    pub fn new() -> Self {
+        UnsavedService::with_backend(backend::unload())
+    }
+
+    /// Creates a new service instance which uses the backend.
+    pub fn with_backend(backend: Rc<UnloadBackend>) -> Self {
+        UnsavedService { backend }
+    }
+
+    /// Registers a predicate which returns `true` while there are
+    /// unsaved changes. It's called only when the page is about to be left.
+    pub fn register<F>(&mut self, dirty: F) -> UnsavedHandle
+    where
+        F: Fn() -> bool + 'static,
+    {
+        let dirty: Dirty = Rc::new(dirty);
+        let id = PREDICATES.with(|predicates| {
+            let mut predicates = predicates.borrow_mut();
+            predicates.0 += 1;
+            let id = predicates.0;
+            predicates.1.push((id, dirty.clone()));
+            id
+        });
+        let listener = self.backend.listen(Box::new(move || dirty()));
+        UnsavedHandle {
+            id,
+            listener: Some(listener),
+        }
+    }
+}
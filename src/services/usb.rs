@@ -0,0 +1,424 @@
+//! This module contains a service of WebUSB, so tools like firmware
+//! flashers talk to devices with their own USB protocols:
+//!
+//! ```rust,ignore
+//! self.task = Some(self.usb.request(&[UsbFilter::vendor(0x2e8a)], Msg::Chosen));
+//!
+//! Msg::Chosen(Ok(device)) => self.task = Some(self.usb.open(&device, 1, 0, Msg::Opened)),
+//! Msg::Opened(Ok(())) => self.task = Some(self.usb.transfer_out(&device, 1, &block, Msg::Written)),
+//! Msg::Written(Ok(_)) => self.task = Some(self.usb.transfer_in(&device, 1, 64, Msg::Status)),
+//! ```
+//!
+//! A chooser needs a user gesture, so it's opened from messages of click
+//! events. Enable the `usb` feature.
+
+use std::fmt;
+use stdweb::Value;
+use stdweb::unstable::TryFrom;
+use html::AppSender;
+use super::promise::{self, PromiseHandle};
+
+/// An error of a USB device.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UsbError {
+    /// The browser doesn't have WebUSB.
+    Unsupported,
+    /// A user or a policy of the page didn't allow the device, or the
+    /// interface has a protected class, like a keyboard.
+    Denied,
+    /// A user closed the chooser, or the device doesn't have the
+    /// configuration, the interface or the endpoint.
+    NotFound,
+    /// The device isn't opened or the interface is claimed by another driver.
+    Busy,
+    /// The device was unplugged or a transfer broke.
+    Disconnected,
+    /// The device stalled the endpoint, it didn't accept the transfer.
+    Stall,
+    /// Another error with its message.
+    Failed(String),
+}
+
+impl fmt::Display for UsbError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            UsbError::Unsupported => write!(f, "WebUSB isn't supported"),
+            UsbError::Denied => write!(f, "an access to a USB device was denied"),
+            UsbError::NotFound => write!(f, "a device, an interface or an endpoint wasn't found"),
+            UsbError::Busy => write!(f, "a USB device isn't opened or is busy"),
+            UsbError::Disconnected => write!(f, "a USB device was disconnected"),
+            UsbError::Stall => write!(f, "a USB endpoint stalled"),
+            UsbError::Failed(ref reason) => write!(f, "USB error: {}", reason),
+        }
+    }
+}
+
+/// A filter of a chooser of devices.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UsbFilter {
+    /// The vendor id of a device.
+    pub vendor_id: Option<u16>,
+    /// The product id of a device, it needs a vendor id.
+    pub product_id: Option<u16>,
+    /// The class of a device or of one of its interfaces.
+    pub class_code: Option<u8>,
+}
+
+impl UsbFilter {
+    /// Creates a filter of devices of the vendor.
+    pub fn vendor(vendor_id: u16) -> Self {
+        UsbFilter {
+            vendor_id: Some(vendor_id),
+            ..UsbFilter::default()
+        }
+    }
+
+    /// Creates a filter of the product of the vendor.
+    pub fn product(vendor_id: u16, product_id: u16) -> Self {
+        UsbFilter {
+            vendor_id: Some(vendor_id),
+            product_id: Some(product_id),
+            ..UsbFilter::default()
+        }
+    }
+
+    /// Creates a filter of devices with the class.
+    pub fn class(class_code: u8) -> Self {
+        UsbFilter {
+            class_code: Some(class_code),
+            ..UsbFilter::default()
+        }
+    }
+}
+
+/// A device which a user allowed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsbDevice(Value);
+
+impl UsbDevice {
+    /// Returns the vendor id of the device.
+    pub fn vendor_id(&self) -> u16 {
+        self.number("vendorId") as u16
+    }
+
+    /// Returns the product id of the device.
+    pub fn product_id(&self) -> u16 {
+        self.number("productId") as u16
+    }
+
+    /// Returns the name of the product if the device has it.
+    pub fn product_name(&self) -> Option<String> {
+        self.text("productName")
+    }
+
+    /// Returns the name of the manufacturer if the device has it.
+    pub fn manufacturer_name(&self) -> Option<String> {
+        self.text("manufacturerName")
+    }
+
+    /// Returns the serial number if the device has it.
+    pub fn serial_number(&self) -> Option<String> {
+        self.text("serialNumber")
+    }
+
+    /// Returns `true` if the device is opened.
+    pub fn is_opened(&self) -> bool {
+        let opened: Value = js! { return @{&self.0}.opened; };
+        opened == Value::Bool(true)
+    }
+
+    fn number(&self, key: &str) -> f64 {
+        let value: Value = js! { return @{&self.0}[@{key}]; };
+        f64::try_from(value).unwrap_or(0.0)
+    }
+
+    fn text(&self, key: &str) -> Option<String> {
+        let value: Value = js! { return @{&self.0}[@{key}] || null; };
+        value.into_string()
+    }
+}
+
+/// A type of a control transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestType {
+    /// A request of the USB specification.
+    Standard,
+    /// A request of the class of the device.
+    Class,
+    /// A request of the vendor, most protocols of tools use it.
+    Vendor,
+}
+
+/// A recipient of a control transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recipient {
+    /// The device.
+    Device,
+    /// An interface with the number in `index`.
+    Interface,
+    /// An endpoint with the number in `index`.
+    Endpoint,
+    /// Another recipient.
+    Other,
+}
+
+/// The setup of a control transfer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControlSetup {
+    /// The type of the request.
+    pub request_type: RequestType,
+    /// The recipient of the request.
+    pub recipient: Recipient,
+    /// The code of the request.
+    pub request: u8,
+    /// The value of the request.
+    pub value: u16,
+    /// The index of the request, like the number of an interface.
+    pub index: u16,
+}
+
+impl ControlSetup {
+    /// Creates a vendor request to the interface.
+    pub fn vendor(request: u8, value: u16, interface: u16) -> Self {
+        ControlSetup {
+            request_type: RequestType::Vendor,
+            recipient: Recipient::Interface,
+            request,
+            value,
+            index: interface,
+        }
+    }
+
+    fn to_js(&self) -> Value {
+        let request_type = match self.request_type {
+            RequestType::Standard => "standard",
+            RequestType::Class => "class",
+            RequestType::Vendor => "vendor",
+        };
+        let recipient = match self.recipient {
+            Recipient::Device => "device",
+            Recipient::Interface => "interface",
+            Recipient::Endpoint => "endpoint",
+            Recipient::Other => "other",
+        };
+        let setup: Value = js! {
+            return {
+                requestType: @{request_type},
+                recipient: @{recipient},
+                request: @{u32::from(self.request)},
+                value: @{u32::from(self.value)},
+                index: @{u32::from(self.index)},
+            };
+        };
+        setup
+    }
+}
+
+/// Returns `true` if the browser has WebUSB.
+pub fn is_supported() -> bool {
+    let supported: Value = js! {
+        return !!(navigator.usb && navigator.usb.requestDevice);
+    };
+    supported == Value::Bool(true)
+}
+
+/// A service of USB devices.
+pub struct UsbService<MSG> {
+    sender: AppSender<MSG>,
+}
+
+impl<MSG: 'static> UsbService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self { sender }
+    }
+
+    /// Shows a chooser of devices which match one of the filters, or of
+    /// all devices if it's empty, and sends the chosen one.
+    pub fn request<F>(&mut self, filters: &[UsbFilter], converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<UsbDevice, UsbError>) -> MSG + 'static,
+    {
+        let filters: Vec<Value> = filters.iter().map(|filter| {
+            let filter: Value = js! {
+                var filter = {};
+                var vendor = @{filter.vendor_id.map(u32::from)};
+                var product = @{filter.product_id.map(u32::from)};
+                var class = @{filter.class_code.map(u32::from)};
+                if (vendor !== null) {
+                    filter.vendorId = vendor;
+                }
+                if (product !== null) {
+                    filter.productId = product;
+                }
+                if (class !== null) {
+                    filter.classCode = class;
+                }
+                return filter;
+            };
+            filter
+        }).collect();
+        let promise = js! {
+            if (!(navigator.usb && navigator.usb.requestDevice)) {
+                return Promise.reject(new DOMException("", "UnsupportedError"));
+            }
+            return navigator.usb.requestDevice({ filters: @{filters} });
+        };
+        self.spawn(promise, |device| Some(UsbDevice(device)), converter)
+    }
+
+    /// Sends devices which a user allowed before, so they're opened without a chooser.
+    pub fn devices<F>(&mut self, converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<Vec<UsbDevice>, UsbError>) -> MSG + 'static,
+    {
+        let promise = js! {
+            if (!(navigator.usb && navigator.usb.getDevices)) {
+                return Promise.reject(new DOMException("", "UnsupportedError"));
+            }
+            return navigator.usb.getDevices();
+        };
+        self.spawn(promise, |devices| {
+            let devices = Vec::<Value>::try_from(devices).ok()?;
+            Some(devices.into_iter().map(UsbDevice).collect())
+        }, converter)
+    }
+
+    /// Opens the device, selects the configuration and claims the interface,
+    /// so transfers are sent to its endpoints.
+    pub fn open<F>(&mut self, device: &UsbDevice, configuration: u8, interface: u8, converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<(), UsbError>) -> MSG + 'static,
+    {
+        let promise = js! {
+            var device = @{&device.0};
+            var configuration = @{u32::from(configuration)};
+            var interface = @{u32::from(interface)};
+            return (device.opened ? Promise.resolve() : device.open()).then(function() {
+                var current = device.configuration;
+                if (current === null || current.configurationValue !== configuration) {
+                    return device.selectConfiguration(configuration);
+                }
+            }).then(function() {
+                return device.claimInterface(interface);
+            });
+        };
+        self.spawn(promise, |_| Some(()), converter)
+    }
+
+    /// Releases the interface and closes the device.
+    pub fn close<F>(&mut self, device: &UsbDevice, interface: u8, converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<(), UsbError>) -> MSG + 'static,
+    {
+        let promise = js! {
+            var device = @{&device.0};
+            if (!device.opened) {
+                return Promise.resolve();
+            }
+            // A released interface is fine, the device is closed anyway
+            return device.releaseInterface(@{u32::from(interface)}).catch(function() {}).then(function() {
+                return device.close();
+            });
+        };
+        self.spawn(promise, |_| Some(()), converter)
+    }
+
+    /// Sends a control transfer which reads up to `length` bytes.
+    pub fn control_in<F>(&mut self, device: &UsbDevice, setup: &ControlSetup, length: u16, converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<Vec<u8>, UsbError>) -> MSG + 'static,
+    {
+        let promise = js! {
+            return @{&device.0}.controlTransferIn(@{setup.to_js()}, @{u32::from(length)});
+        };
+        self.spawn(promise, read_of, converter)
+    }
+
+    /// Sends a control transfer with the bytes and sends how many were written.
+    pub fn control_out<F>(&mut self, device: &UsbDevice, setup: &ControlSetup, data: &[u8], converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<usize, UsbError>) -> MSG + 'static,
+    {
+        let promise = js! {
+            return @{&device.0}.controlTransferOut(@{setup.to_js()}, new Uint8Array(@{data.to_vec()}));
+        };
+        self.spawn(promise, written_of, converter)
+    }
+
+    /// Reads up to `length` bytes from the bulk or interrupt endpoint.
+    pub fn transfer_in<F>(&mut self, device: &UsbDevice, endpoint: u8, length: u32, converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<Vec<u8>, UsbError>) -> MSG + 'static,
+    {
+        let promise = js! {
+            return @{&device.0}.transferIn(@{u32::from(endpoint)}, @{length});
+        };
+        self.spawn(promise, read_of, converter)
+    }
+
+    /// Writes the bytes to the bulk or interrupt endpoint and sends how many were written.
+    pub fn transfer_out<F>(&mut self, device: &UsbDevice, endpoint: u8, data: &[u8], converter: F) -> PromiseHandle
+    where
+        F: Fn(Result<usize, UsbError>) -> MSG + 'static,
+    {
+        let promise = js! {
+            return @{&device.0}.transferOut(@{u32::from(endpoint)}, new Uint8Array(@{data.to_vec()}));
+        };
+        self.spawn(promise, written_of, converter)
+    }
+
+    fn spawn<T, C, F>(&mut self, promise: Value, convert: C, converter: F) -> PromiseHandle
+    where
+        C: Fn(Value) -> Option<T> + 'static,
+        F: Fn(Result<T, UsbError>) -> MSG + 'static,
+    {
+        let mut tx = self.sender.clone();
+        let promise = js! {
+            // A stalled transfer resolves, it's rejected to be an error
+            return @{promise}.then(function(result) {
+                if (result && result.status && result.status !== "ok") {
+                    throw new DOMException(result.status, result.status === "stall" ? "StallError" : "BabbleError");
+                }
+                return result;
+            });
+        };
+        promise::settle(promise, move |resolved, value| {
+            let result = if resolved {
+                convert(value).ok_or_else(|| UsbError::Failed("unexpected value".into()))
+            } else {
+                let name: Value = js! {
+                    var reason = @{&value};
+                    return reason && reason.name || "";
+                };
+                Err(error_of(&name.into_string().unwrap_or_default(), promise::reason(value)))
+            };
+            tx.send(converter(result));
+        })
+    }
+}
+
+fn read_of(result: Value) -> Option<Vec<u8>> {
+    let data: Value = js! {
+        var view = @{result}.data;
+        return view ? Array.from(new Uint8Array(view.buffer, view.byteOffset, view.byteLength)) : [];
+    };
+    Vec::<u8>::try_from(data).ok()
+}
+
+fn written_of(result: Value) -> Option<usize> {
+    let written: Value = js! { return @{result}.bytesWritten; };
+    f64::try_from(written).ok().map(|written| written as usize)
+}
+
+fn error_of(name: &str, message: String) -> UsbError {
+    match name {
+        "UnsupportedError" => UsbError::Unsupported,
+        "NotAllowedError" | "SecurityError" => UsbError::Denied,
+        "NotFoundError" => UsbError::NotFound,
+        "InvalidStateError" => UsbError::Busy,
+        "NetworkError" => UsbError::Disconnected,
+        "StallError" => UsbError::Stall,
+        _ => UsbError::Failed(message),
+    }
+}
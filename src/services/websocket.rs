@@ -1,12 +1,15 @@
 //! Service to connect to a servers by
 //! [WebSocket Protocol](https://tools.ietf.org/html/rfc6455).
 
-use stdweb::Value;
+use std::rc::Rc;
 use html::AppSender;
 use format::{Storable, Restorable};
-use super::Task;
+use progress::{self, Kind};
+use super::{interceptor, Task};
+use super::backend::{self, Connection, WebSocketBackend};
 
 /// A status of a websocket connection. Used for status notification.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum WebSocketStatus {
     /// Fired when a websocket connection was opened.
     Opened,
@@ -15,17 +18,23 @@ pub enum WebSocketStatus {
 }
 
 /// A handle to control current websocket connection. Implements `Task` and could be canceled.
-pub struct WebSocketHandle(Option<Value>);
+pub struct WebSocketHandle(Option<Box<Connection>>);
 
 /// A websocket service attached to a user context.
 pub struct  WebSocketService<MSG> {
     sender: AppSender<MSG>,
+    backend: Rc<WebSocketBackend>,
 }
 
 impl<MSG: 'static> WebSocketService<MSG> {
     /// Creates a new service instance connected to `App` by provided `sender`.
     pub fn new(sender: AppSender<MSG>) -> Self {
-        Self { sender }
+        Self::with_backend(sender, backend::websocket())
+    }
+
+    /// Creates a new service instance which opens connections with the `backend`.
+    pub fn with_backend(sender: AppSender<MSG>, backend: Rc<WebSocketBackend>) -> Self {
+        Self { sender, backend }
     }
 
     /// Connects to a server by a weboscket connection. Needs two functions to generate
@@ -44,39 +53,16 @@ impl<MSG: 'static> WebSocketService<MSG> {
             tx.send(msg);
         };
         let mut tx = self.sender.clone();
-        let notify_callback = move |code: u32| {
-            let code = {
-                match code {
-                    1 => WebSocketStatus::Opened,
-                    0 => WebSocketStatus::Closed,
-                    x => panic!("unknown code of websocket notification: {}", x),
-                }
-            };
-            let msg = notification(code);
+        // A connection is in flight until it's opened or fails
+        let mut pending = progress::pending(Kind::WebSocket);
+        let notify_callback = move |status: WebSocketStatus| {
+            pending.take();
+            let msg = notification(status);
             tx.send(msg);
         };
-        let handle = js! {
-            var socket = new WebSocket(@{url});
-            var callback = @{callback};
-            var notify_callback = @{notify_callback};
-            socket.onopen = function(event) {
-                notify_callback(1);
-            };
-            socket.onclose = function(event) {
-                callback.drop();
-                notify_callback(0);
-                notify_callback.drop();
-            };
-            socket.onerror = function(event) {
-            };
-            socket.onmessage = function(event) {
-                callback(event.data);
-            };
-            return {
-                socket,
-            };
-        };
-        WebSocketHandle(Some(handle))
+        let url = interceptor::connect(url);
+        let connection = self.backend.connect(&url, Box::new(callback), Box::new(notify_callback));
+        WebSocketHandle(Some(connection))
     }
 }
 
@@ -86,12 +72,9 @@ impl WebSocketHandle {
     where
         IN: Into<Storable>
     {
-        if let WebSocketHandle(Some(ref handle)) = *self {
+        if let WebSocketHandle(Some(ref mut connection)) = *self {
             if let Some(body) = data.into() {
-                js! { @(no_return)
-                    var handle = @{handle};
-                    handle.socket.send(@{body});
-                }
+                connection.send(body);
             }
         } else {
             panic!("can't send data to the closed websocket connection");
@@ -100,11 +83,20 @@ impl WebSocketHandle {
 }
 
 impl Task for WebSocketHandle {
+    fn is_active(&self) -> bool {
+        self.0.as_ref().map_or(false, |connection| connection.is_active())
+    }
+
     fn cancel(&mut self) {
-        let handle = self.0.take().expect("tried to close websocket twice");
-        js! { @(no_return)
-            var handle = @{handle};
-            handle.socket.close();
+        let mut connection = self.0.take().expect("tried to close websocket twice");
+        connection.cancel();
+    }
+}
+
+impl Drop for WebSocketHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
         }
     }
 }
@@ -0,0 +1,134 @@
+//! This module contains backends which reach a browser through `web-sys`
+//! and `wasm-bindgen` instead of `stdweb`. Enable the `web_sys` feature
+//! and install them before an app is mounted:
+//!
+//! ```rust,ignore
+//! yew::initialize();
+//! yew::services::websys::install();
+//! ```
+//!
+//! They implement timers, storages and random bytes, so services of them
+//! work with `wasm-bindgen` apps. Other services and the renderer of the
+//! virtual DOM still use `stdweb`.
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
+use web_sys::{Storage, Window};
+use super::{Task, to_ms};
+use super::backend::{self, RandomBackend, StorageBackend, TimerBackend};
+use super::storage::Scope;
+
+/// Backends which use APIs of a browser through `web-sys`.
+pub struct WebSys;
+
+/// Replaces backends of timers, storages and random bytes of the thread
+/// with `WebSys`.
+pub fn install() {
+    backend::set_timers(Rc::new(WebSys));
+    backend::set_storage(Rc::new(WebSys));
+    backend::set_random(Rc::new(WebSys));
+}
+
+fn window() -> Window {
+    ::web_sys::window().expect("web-sys backends need a window")
+}
+
+impl TimerBackend for WebSys {
+    fn timeout(&self, delay: Duration, callback: Box<FnMut()>) -> Box<Task> {
+        let done = Rc::new(Cell::new(false));
+        let fired = done.clone();
+        let mut callback = callback;
+        let closure = Closure::wrap(Box::new(move || {
+            fired.set(true);
+            callback();
+        }) as Box<FnMut()>);
+        let id = window()
+            .set_timeout_with_callback_and_timeout_and_arguments_0(closure.as_ref().unchecked_ref(), to_ms(delay) as i32)
+            .expect("can't set a timeout");
+        Box::new(WebSysTimer { id, interval: false, done, closure: Some(closure) })
+    }
+
+    fn interval(&self, period: Duration, callback: Box<FnMut()>) -> Box<Task> {
+        let closure = Closure::wrap(callback);
+        let id = window()
+            .set_interval_with_callback_and_timeout_and_arguments_0(closure.as_ref().unchecked_ref(), to_ms(period) as i32)
+            .expect("can't set an interval");
+        Box::new(WebSysTimer { id, interval: true, done: Rc::new(Cell::new(false)), closure: Some(closure) })
+    }
+
+    fn monotonic(&self) -> Duration {
+        let ms = window().performance().map_or(0.0, |performance| performance.now());
+        Duration::from_micros((ms * 1000.0) as u64)
+    }
+}
+
+struct WebSysTimer {
+    id: i32,
+    interval: bool,
+    done: Rc<Cell<bool>>,
+    closure: Option<Closure<FnMut()>>,
+}
+
+impl Task for WebSysTimer {
+    fn is_active(&self) -> bool {
+        self.closure.is_some() && !self.done.get()
+    }
+
+    fn cancel(&mut self) {
+        self.closure.take().expect("tried to cancel timer twice");
+        if self.interval {
+            window().clear_interval_with_handle(self.id);
+        } else {
+            window().clear_timeout_with_handle(self.id);
+        }
+    }
+}
+
+impl Drop for WebSysTimer {
+    fn drop(&mut self) {
+        // A browser still calls the closure of a timer which wasn't canceled,
+        // like timers of `stdweb`, so it's kept
+        if self.is_active() {
+            if let Some(closure) = self.closure.take() {
+                closure.forget();
+            }
+        }
+    }
+}
+
+fn storage(scope: Scope) -> Option<Storage> {
+    let storage = match scope {
+        Scope::Local => window().local_storage(),
+        Scope::Session => window().session_storage(),
+    };
+    storage.ok().and_then(|storage| storage)
+}
+
+impl StorageBackend for WebSys {
+    fn get(&self, scope: Scope, key: &str) -> Option<String> {
+        storage(scope).and_then(|storage| storage.get_item(key).ok().and_then(|value| value))
+    }
+
+    fn set(&self, scope: Scope, key: &str, value: &str) {
+        if let Some(storage) = storage(scope) {
+            storage.set_item(key, value).expect("can't set an item of a storage");
+        }
+    }
+
+    fn remove(&self, scope: Scope, key: &str) {
+        if let Some(storage) = storage(scope) {
+            storage.remove_item(key).expect("can't remove an item of a storage");
+        }
+    }
+}
+
+impl RandomBackend for WebSys {
+    fn fill(&self, bytes: &mut [u8]) {
+        window().crypto()
+            .and_then(|crypto| crypto.get_random_values_with_u8_array(bytes))
+            .expect("crypto.getRandomValues isn't available");
+    }
+}
@@ -0,0 +1,277 @@
+//! This module contains typed settings of an app, like preferences of a user,
+//! which are kept in the local storage between visits. `settings!` declares
+//! a struct with defaults, and migrations upgrade values stored by older
+//! versions of the app when the schema changes:
+//!
+//! ```rust,ignore
+//! settings! {
+//!     pub struct Preferences in "preferences", version 2 {
+//!         pub theme: String = "light".into(),
+//!         pub font_size: u32 = 14,
+//!     }
+//!     migrations {
+//!         // Version 1 kept a `dark` flag
+//!         1 => |value| settings::rename(value, "dark", "theme"),
+//!     }
+//! }
+//!
+//! let preferences: Preferences = self.settings.load();
+//! self.settings.update(|preferences: &mut Preferences| preferences.font_size += 2);
+//! ```
+//!
+//! Fields which are missing or can't be parsed get defaults, so adding
+//! a field doesn't need a migration, only renames and changes of types do.
+//! A value stored without the module, like with `StorageService`, has
+//! the version 0.
+
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json;
+pub use serde_json::{Map, Value};
+use html::AppSender;
+use services::Task;
+use services::backend::{self, StorageBackend};
+use services::storage::Scope;
+
+/// Settings which are kept in the local storage. Use `settings!` macro to implement it.
+pub trait Settings: Default + 'static {
+    /// The key in the storage.
+    const KEY: &'static str;
+    /// The version of the schema, which is increased with every migration.
+    const VERSION: u32;
+    /// Converts the settings to an object of fields.
+    fn to_json(&self) -> Value;
+    /// Reads fields from an object, other fields get defaults.
+    fn from_json(value: &Value) -> Self;
+    /// Upgrades a value from the version to the next one.
+    fn migrate(from: u32, value: &mut Value);
+}
+
+/// Declares a struct of settings with defaults of fields and implements `Settings`
+/// for it. Fields have to implement `Serialize` and `Deserialize`. A migration
+/// from a version gets the stored object and changes it for the next version.
+#[macro_export]
+macro_rules! settings {
+    ($(#[$attr:meta])* $vis:vis struct $name:ident in $key:tt { $($body:tt)* } $($rest:tt)*) => {
+        settings! { @migrations [$(#[$attr])* $vis struct $name in $key, version 1 { $($body)* }] $($rest)* }
+    };
+    ($(#[$attr:meta])* $vis:vis struct $name:ident in $key:tt, version $version:tt { $($body:tt)* } $($rest:tt)*) => {
+        settings! { @migrations [$(#[$attr])* $vis struct $name in $key, version $version { $($body)* }] $($rest)* }
+    };
+    (@migrations $head:tt) => {
+        settings! { @impl $head [] }
+    };
+    (@migrations $head:tt migrations { $($from:tt => $migration:expr),* $(,)* }) => {
+        settings! { @impl $head [$(($from $migration))*] }
+    };
+    (@impl [$(#[$attr:meta])* $vis:vis struct $name:ident in $key:tt, version $version:tt {
+        $($(#[$fattr:meta])* $fvis:vis $field:ident : $ty:ty = $default:expr),* $(,)*
+    }] [$(($from:tt $migration:expr))*]) => {
+        $(#[$attr])* $vis struct $name {
+            $($(#[$fattr])* $fvis $field: $ty,)*
+        }
+
+        impl ::std::default::Default for $name {
+            fn default() -> Self {
+                $name {
+                    $($field: $default,)*
+                }
+            }
+        }
+
+        impl $crate::settings::Settings for $name {
+            const KEY: &'static str = $key;
+            const VERSION: u32 = $version;
+
+            fn to_json(&self) -> $crate::settings::Value {
+                let mut object = $crate::settings::Map::new();
+                $(object.insert(stringify!($field).to_owned(), $crate::settings::to_value(&self.$field));)*
+                $crate::settings::Value::Object(object)
+            }
+
+            fn from_json(value: &$crate::settings::Value) -> Self {
+                let mut settings = <Self as ::std::default::Default>::default();
+                $(if let Some(field) = $crate::settings::field(value, stringify!($field)) {
+                    settings.$field = field;
+                })*
+                settings
+            }
+
+            #[allow(unused_variables)]
+            fn migrate(from: u32, value: &mut $crate::settings::Value) {
+                $(if from == $from {
+                    $crate::settings::apply(value, $migration);
+                })*
+            }
+        }
+    };
+}
+
+/// Converts a field to a value for `settings!`.
+#[doc(hidden)]
+pub fn to_value<T: Serialize>(field: &T) -> Value {
+    serde_json::to_value(field).unwrap_or(Value::Null)
+}
+
+/// Reads a field for `settings!`.
+#[doc(hidden)]
+pub fn field<T: DeserializeOwned>(value: &Value, name: &str) -> Option<T> {
+    value.get(name).and_then(|field| serde_json::from_value(field.clone()).ok())
+}
+
+/// Runs a migration of `settings!`.
+#[doc(hidden)]
+pub fn apply<F: FnOnce(&mut Value)>(value: &mut Value, migration: F) {
+    migration(value);
+}
+
+/// Renames a field of a stored object in a migration.
+pub fn rename(value: &mut Value, from: &str, to: &str) {
+    if let Some(object) = value.as_object_mut() {
+        if let Some(field) = object.remove(from) {
+            object.insert(to.to_owned(), field);
+        }
+    }
+}
+
+type Listener = Rc<Fn()>;
+
+#[derive(Default)]
+struct Listeners {
+    next_id: usize,
+    listeners: Vec<(usize, &'static str, Listener)>,
+}
+
+thread_local! {
+    static LISTENERS: RefCell<Listeners> = RefCell::new(Listeners::default());
+}
+
+fn changed(key: &str) {
+    let listeners: Vec<Listener> = LISTENERS.with(|listeners| {
+        listeners.borrow().listeners.iter()
+            .filter(|&&(_, listened, _)| listened == key)
+            .map(|(_, _, listener)| listener.clone())
+            .collect()
+    });
+    for listener in listeners {
+        listener();
+    }
+}
+
+fn read<S: Settings>(backend: &StorageBackend) -> S {
+    let stored = backend.get(Scope::Local, S::KEY).and_then(|data| serde_json::from_str::<Value>(&data).ok());
+    let (version, mut value) = match stored {
+        Some(Value::Object(ref object)) if is_versioned(object) => {
+            (object["version"].as_u64().unwrap_or(0) as u32, object["settings"].clone())
+        }
+        Some(value) => (0, value),
+        None => return S::default(),
+    };
+    if version < S::VERSION {
+        for from in version..S::VERSION {
+            S::migrate(from, &mut value);
+        }
+        write(backend, S::KEY, S::VERSION, value.clone());
+    }
+    S::from_json(&value)
+}
+
+fn is_versioned(object: &Map<String, Value>) -> bool {
+    object.get("version").map_or(false, Value::is_u64) && object.contains_key("settings")
+}
+
+fn write(backend: &StorageBackend, key: &str, version: u32, value: Value) {
+    let mut object = Map::new();
+    object.insert("version".to_owned(), Value::from(version));
+    object.insert("settings".to_owned(), value);
+    backend.set(Scope::Local, key, &Value::Object(object).to_string());
+}
+
+/// A handle of a listener of settings. It's canceled when dropped.
+pub struct SettingsHandle(Option<usize>);
+
+impl Task for SettingsHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let id = self.0.take().expect("tried to cancel a settings listener twice");
+        LISTENERS.with(|listeners| listeners.borrow_mut().listeners.retain(|&(listener, _, _)| listener != id));
+    }
+}
+
+impl Drop for SettingsHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+/// A service which loads and saves settings of the type.
+pub struct SettingsService<S, MSG> {
+    sender: AppSender<MSG>,
+    backend: Rc<StorageBackend>,
+    settings: PhantomData<S>,
+}
+
+impl<S: Settings, MSG: 'static> SettingsService<S, MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self::with_backend(sender, backend::storage())
+    }
+
+    /// Creates a new service instance which keeps settings in the `backend`.
+    pub fn with_backend(sender: AppSender<MSG>, backend: Rc<StorageBackend>) -> Self {
+        Self { sender, backend, settings: PhantomData }
+    }
+
+    /// Returns the stored settings or defaults. A value of an older version
+    /// is migrated and stored again.
+    pub fn load(&self) -> S {
+        read(&*self.backend)
+    }
+
+    /// Stores the settings and notifies listeners.
+    pub fn save(&mut self, settings: &S) {
+        write(&*self.backend, S::KEY, S::VERSION, settings.to_json());
+        changed(S::KEY);
+    }
+
+    /// Changes the stored settings with `update` and returns them.
+    pub fn update<F: FnOnce(&mut S)>(&mut self, update: F) -> S {
+        let mut settings = self.load();
+        update(&mut settings);
+        self.save(&settings);
+        settings
+    }
+
+    /// Removes the stored settings, so defaults are used, and notifies listeners.
+    pub fn reset(&mut self) {
+        self.backend.remove(Scope::Local, S::KEY);
+        changed(S::KEY);
+    }
+
+    /// Sends a message with the settings every time they're saved or reset
+    /// by any service of the app, like a settings page.
+    pub fn listen<F>(&mut self, converter: F) -> SettingsHandle
+    where
+        F: Fn(S) -> MSG + 'static,
+    {
+        let tx = self.sender.clone();
+        let backend = self.backend.clone();
+        let listener = move || tx.clone().send(converter(read(&*backend)));
+        let id = LISTENERS.with(|listeners| {
+            let mut listeners = listeners.borrow_mut();
+            let id = listeners.next_id;
+            listeners.next_id += 1;
+            listeners.listeners.push((id, S::KEY, Rc::new(listener)));
+            id
+        });
+        SettingsHandle(Some(id))
+    }
+}
@@ -0,0 +1,109 @@
+//! This module contains names of elements and attributes of HTML, SVG and
+//! MathML which `html!` checks at compile time, so typos like `<dvi>` or
+//! `clas=` fail the build instead of rendering nothing.
+//!
+//! Names with dashes aren't checked: tags of custom elements like `<my-widget>`
+//! and attributes like `data-id`, `aria-label` or `stroke-width`.
+//! Set an attribute which isn't known with a computed name: `["x-custom"]=value`.
+
+/// Elements of HTML, SVG and MathML.
+pub const ELEMENTS: &[&str] = &[
+    // HTML
+    "a", "abbr", "address", "area", "article", "aside", "audio", "b", "base", "bdi", "bdo",
+    "blockquote", "body", "br", "button", "canvas", "caption", "cite", "code", "col", "colgroup",
+    "data", "datalist", "dd", "del", "details", "dfn", "dialog", "div", "dl", "dt", "em", "embed",
+    "fieldset", "figcaption", "figure", "footer", "form", "h1", "h2", "h3", "h4", "h5", "h6",
+    "head", "header", "hgroup", "hr", "html", "i", "iframe", "img", "input", "ins", "kbd", "label",
+    "legend", "li", "link", "main", "map", "mark", "menu", "meta", "meter", "nav", "noscript",
+    "object", "ol", "optgroup", "option", "output", "p", "param", "picture", "pre", "progress",
+    "q", "rp", "rt", "ruby", "s", "samp", "script", "search", "section", "select", "slot", "small",
+    "source", "span", "strong", "style", "sub", "summary", "sup", "table", "tbody", "td",
+    "template", "textarea", "tfoot", "th", "thead", "time", "title", "tr", "track", "u", "ul",
+    "var", "video", "wbr",
+    // SVG
+    "animate", "animateMotion", "animateTransform", "circle", "clipPath", "defs", "desc",
+    "ellipse", "feBlend", "feColorMatrix", "feComposite", "feDropShadow", "feFlood",
+    "feGaussianBlur", "feMerge", "feMergeNode", "feOffset", "filter", "foreignObject", "g",
+    "image", "line", "linearGradient", "marker", "mask", "metadata", "mpath", "path", "pattern",
+    "polygon", "polyline", "radialGradient", "rect", "set", "stop", "svg", "switch", "symbol",
+    "text", "textPath", "tspan", "use", "view",
+    // MathML
+    "math", "mfrac", "mi", "mn", "mo", "mover", "mroot", "mrow", "ms", "mspace", "msqrt",
+    "msub", "msubsup", "msup", "mtable", "mtd", "mtext", "mtr", "munder", "munderover", "semantics",
+];
+
+/// Attributes of HTML, SVG and MathML without dashes.
+pub const ATTRIBUTES: &[&str] = &[
+    // HTML
+    "accept", "accesskey", "action", "allow", "allowfullscreen", "alt", "as", "async",
+    "autocapitalize", "autocomplete", "autofocus", "autoplay", "capture", "charset", "checked",
+    "cite", "class", "cols", "colspan", "content", "contenteditable", "controls", "coords",
+    "crossorigin", "data", "datetime", "decoding", "default", "defer", "dir", "dirname",
+    "disabled", "download", "draggable", "enctype", "enterkeyhint", "for", "form", "formaction",
+    "formenctype", "formmethod", "formnovalidate", "formtarget", "headers", "height", "hidden",
+    "high", "href", "hreflang", "id", "inert", "inputmode", "integrity", "is", "ismap", "itemid",
+    "itemprop", "itemref", "itemscope", "itemtype", "kind", "label", "lang", "list", "loading",
+    "loop", "low", "max", "maxlength", "media", "method", "min", "minlength", "multiple", "muted",
+    "name", "nonce", "novalidate", "open", "optimum", "pattern", "ping", "placeholder",
+    "playsinline", "popover", "popovertarget", "poster", "preload", "readonly", "referrerpolicy",
+    "rel", "required", "reversed", "role", "rows", "rowspan", "sandbox", "scope", "selected",
+    "shape", "size", "sizes", "slot", "span", "spellcheck", "src", "srcdoc", "srclang", "srcset",
+    "start", "step", "style", "tabindex", "target", "title", "translate", "type", "usemap",
+    "value", "width", "wrap", "xmlns",
+    // SVG
+    "cx", "cy", "d", "dx", "dy", "fill", "filter", "focusable", "fr", "fx", "fy",
+    "gradientTransform", "gradientUnits", "markerHeight", "markerUnits", "markerWidth", "mask",
+    "offset", "opacity", "orient", "pathLength", "patternTransform", "patternUnits", "points",
+    "preserveAspectRatio", "r", "refX", "refY", "rotate", "rx", "ry", "spreadMethod",
+    "stdDeviation", "stroke", "transform", "version", "viewBox", "visibility", "x", "x1", "x2",
+    "y", "y1", "y2",
+    // MathML
+    "columnalign", "display", "displaystyle", "mathvariant", "scriptlevel", "stretchy",
+];
+
+const fn equal(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+pub(crate) const fn contains(names: &[&str], name: &str) -> bool {
+    let mut i = 0;
+    while i < names.len() {
+        if equal(names[i].as_bytes(), name.as_bytes()) {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+const fn has_dash(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'-' {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Returns `true` if the tag is a known element or a custom element with a dash.
+pub const fn is_element(name: &str) -> bool {
+    has_dash(name) || contains(ELEMENTS, name)
+}
+
+/// Returns `true` if the attribute is known or has a dash.
+pub const fn is_attribute(name: &str) -> bool {
+    has_dash(name) || contains(ATTRIBUTES, name)
+}
@@ -0,0 +1,566 @@
+//! This module contains server-side rendering of virtual DOM trees to HTML strings.
+//! It doesn't touch a browser: components are created with a detached loop,
+//! which ignores messages, and rendered once. Nothing calls JS, so it works
+//! in native binaries.
+//!
+//! ```rust,ignore
+//! let html = ssr::render_to_string(view(&model));
+//! ```
+//!
+//! Use `Stream` to send a page while its data is loading: the shell of the page with
+//! fallbacks of `await_node` is sent first, and every subtree is sent as a chunk
+//! when its data is resolved.
+//!
+//! Listeners and JS properties aren't rendered. Portals render a placeholder
+//! only, their children are rendered by a browser. Empty text nodes and
+//! boundaries between adjacent text nodes are rendered as `<!---->` comments
+//! to keep the same structure of nodes after parsing.
+
+use std::any::{Any, TypeId};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{self, Write};
+use std::marker::PhantomData;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::web::{document, INode, Node};
+use html::Html;
+use virtual_dom::{VNode, VTag, VComp};
+use virtual_dom::vcomp::Mount;
+use virtual_dom::vtag::BOOLEAN_ATTRIBUTES;
+
+/// Elements which have no content and closing tags.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input",
+    "link", "meta", "param", "source", "track", "wbr",
+];
+
+/// Elements which content is not escaped, only closing tags in it are broken.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style"];
+
+/// A marker of empty text nodes and boundaries between text nodes.
+pub(crate) const TEXT_MARKER: &str = "<!---->";
+
+/// Renders a virtual tree to an HTML string.
+pub fn render_to_string<MSG, T: Into<VNode<MSG>>>(node: T) -> String {
+    let mut node = node.into();
+    let mut out = String::new();
+    write_node(&mut node, &mut out);
+    out
+}
+
+/// A placeholder in a template of pages which is replaced with rendered markup.
+pub const PLACEHOLDER: &str = "<!--yew-->";
+
+/// A page rendered at build time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page {
+    /// A path of the route, like `/docs/intro`.
+    pub path: String,
+    /// Rendered markup of the view.
+    pub html: String,
+}
+
+impl Page {
+    /// Returns a relative path of the file of the page:
+    /// `/` becomes `index.html` and `/docs/intro` becomes `docs/intro/index.html`.
+    /// Returns `None` if the path leaves the output directory.
+    pub fn file_path(&self) -> Option<PathBuf> {
+        let mut file = PathBuf::new();
+        for part in Path::new(self.path.trim_start_matches('/')).components() {
+            match part {
+                Component::Normal(part) => file.push(part),
+                Component::CurDir => { }
+                _ => return None,
+            }
+        }
+        file.push("index.html");
+        Some(file)
+    }
+
+    /// Puts the markup into the template instead of the `PLACEHOLDER`.
+    pub fn to_document(&self, template: &str) -> String {
+        template.replacen(PLACEHOLDER, &self.html, 1)
+    }
+}
+
+/// Renders pages of static routes at build time. The `model` function returns
+/// the initial state of a route, so a browser could hydrate the page with the same state.
+///
+/// ```rust,ignore
+/// let pages = ssr::prerender(&["/", "/about"], |path| Model::for_path(path), view);
+/// ssr::write_pages(Path::new("dist"), include_str!("index.html"), &pages)?;
+/// ```
+pub fn prerender<R, MOD, MSG, M, V>(routes: R, model: M, view: V) -> Vec<Page>
+where
+    R: IntoIterator,
+    R::Item: AsRef<str>,
+    M: Fn(&str) -> MOD,
+    V: Fn(&MOD) -> Html<MSG>,
+{
+    routes.into_iter().map(|path| {
+        let path = path.as_ref();
+        let model = model(path);
+        Page {
+            path: path.to_owned(),
+            html: render_to_string(view(&model)),
+        }
+    }).collect()
+}
+
+/// Writes pages to the directory as documents made from the template.
+pub fn write_pages(dir: &Path, template: &str, pages: &[Page]) -> io::Result<()> {
+    if !template.contains(PLACEHOLDER) {
+        let msg = format!("template has no placeholder {}", PLACEHOLDER);
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, msg));
+    }
+    for page in pages {
+        let file = page.file_path().ok_or_else(|| {
+            let msg = format!("wrong path of a page: {}", page.path);
+            io::Error::new(io::ErrorKind::InvalidInput, msg)
+        })?;
+        let file = dir.join(file);
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(file, page.to_document(template))?;
+    }
+    Ok(())
+}
+
+/// Appends the HTML of a node to the `out` string.
+pub(crate) fn write_node<MSG>(node: &mut VNode<MSG>, out: &mut String) {
+    match *node {
+        VNode::VTag { ref mut vtag, .. } => {
+            write_tag(vtag, out);
+        }
+        VNode::VText { ref vtext, .. } => {
+            if vtext.text.is_empty() {
+                out.push_str(TEXT_MARKER);
+            } else {
+                escape(&vtext.text, false, out);
+            }
+        }
+        VNode::VPortal { .. } => {
+            out.push_str(TEXT_MARKER);
+        }
+        VNode::VRaw { ref vraw, .. } => {
+            out.push('<');
+            out.push_str(vraw.tag());
+            out.push('>');
+            out.push_str(&vraw.inserted_html());
+            out.push_str("</");
+            out.push_str(vraw.tag());
+            out.push('>');
+        }
+        VNode::VComp { ref mut vcomp } => {
+            vcomp.render_to_string(out);
+        }
+    }
+}
+
+fn write_tag<MSG>(vtag: &mut VTag<MSG>, out: &mut String) {
+    let tag = vtag.tag().to_owned();
+    out.push('<');
+    out.push_str(&tag);
+
+    if !vtag.classes.is_empty() {
+        let mut classes = vtag.classes.iter().map(String::as_str).collect::<Vec<_>>();
+        classes.sort();
+        write_attribute("class", &classes.join(" "), out);
+    }
+    if let Some(ref kind) = vtag.kind {
+        write_attribute("type", kind, out);
+    }
+    let mut attributes = vtag.attributes.iter().collect::<Vec<_>>();
+    attributes.sort();
+    for (name, value) in attributes {
+        if value == "false" && BOOLEAN_ATTRIBUTES.contains(&name.as_str()) {
+            continue;
+        }
+        #[cfg(feature = "csp")]
+        {
+            ::csp::check_attribute(name, value);
+            if name == "style" {
+                panic!("the `style` attribute of <{}> is inline, which a Content Security Policy blocks, use a class instead", tag);
+            }
+        }
+        write_attribute(name, value, out);
+    }
+    if vtag.checked {
+        write_attribute("checked", "", out);
+    }
+    if tag != "textarea" && tag != "select" {
+        if let Some(ref value) = vtag.value {
+            write_attribute("value", value, out);
+        }
+    }
+    out.push('>');
+
+    if VOID_ELEMENTS.contains(&tag.as_str()) {
+        return;
+    }
+    if tag == "textarea" {
+        if let Some(ref value) = vtag.value {
+            // Parsers drop a line break right after the start tag
+            if value.starts_with('\n') {
+                out.push('\n');
+            }
+            escape(value, false, out);
+        }
+    }
+    let raw = RAW_TEXT_ELEMENTS.contains(&tag.as_str());
+    let selected = if tag == "select" { vtag.value.clone() } else { None };
+    let mut after_text = false;
+    for child in vtag.childs.iter_mut() {
+        let is_text = match *child {
+            VNode::VText { ref vtext, .. } => !vtext.text.is_empty(),
+            _ => false,
+        };
+        if is_text && after_text && !raw {
+            out.push_str(TEXT_MARKER);
+        }
+        after_text = is_text;
+        match *child {
+            VNode::VText { ref vtext, .. } if raw => {
+                escape_raw(&vtext.text, out);
+            }
+            VNode::VTag { ref mut vtag, .. } if selected.is_some() && vtag.tag() == "option" => {
+                // `select` keeps its value, but the markup selects an option
+                if vtag.value == selected {
+                    vtag.add_attribute("selected", "");
+                }
+                write_tag(vtag, out);
+            }
+            _ => {
+                write_node(child, out);
+            }
+        }
+    }
+
+    out.push_str("</");
+    out.push_str(&tag);
+    out.push('>');
+}
+
+fn write_attribute(name: &str, value: &str, out: &mut String) {
+    out.push(' ');
+    out.push_str(name);
+    out.push_str("=\"");
+    escape(value, true, out);
+    out.push('"');
+}
+
+/// Writes text of a raw text element. A parser ends the element at any `</`
+/// followed by its name, like `</script>` in embedded JSON, so `</` is written
+/// as `<\/`, which is the same in strings of JS and CSS.
+fn escape_raw(text: &str, out: &mut String) {
+    out.push_str(&text.replace("</", "<\\/"));
+}
+
+fn escape(text: &str, attribute: bool, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' if attribute => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// A script which replaces a fallback between `<!--yew-await-N-->` comments
+/// with the content of the `<template id="yew-chunk-N">`.
+const SWAP_SCRIPT: &str = "window.__yewSwap = function(id) {\
+var walker = document.createTreeWalker(document, NodeFilter.SHOW_COMMENT);\
+var start = null;\
+while (walker.nextNode()) { if (walker.currentNode.data === 'yew-await-' + id) { start = walker.currentNode; break; } }\
+var chunk = document.getElementById('yew-chunk-' + id);\
+if (start) {\
+var end = start.nextSibling;\
+while (end && !(end.nodeType === 8 && end.data === '/yew-await-' + id)) { var next = end.nextSibling; end.parentNode.removeChild(end); end = next; }\
+start.parentNode.insertBefore(chunk.content, end);\
+start.parentNode.removeChild(start);\
+if (end) { end.parentNode.removeChild(end); }\
+}\
+chunk.parentNode.removeChild(chunk);\
+};</script>";
+
+/// Returns the opening tag of an inline script, which has the nonce of
+/// the policy with the `csp` feature if it's set.
+fn script_tag() -> String {
+    #[cfg(feature = "csp")]
+    {
+        if let Some(nonce) = ::csp::nonce() {
+            let mut tag = String::from("<script nonce=\"");
+            escape(&nonce, true, &mut tag);
+            tag.push_str("\">");
+            return tag;
+        }
+    }
+    "<script>".to_owned()
+}
+
+/// Data which is resolved by a thread spawned by `Stream::spawn`.
+pub struct Pending<T> {
+    stream: usize,
+    id: usize,
+    slot: Arc<Mutex<Option<T>>>,
+}
+
+/// Notifies the stream when a thread finished, even if it panicked.
+struct Resolved {
+    id: usize,
+    notify: Sender<usize>,
+}
+
+impl Drop for Resolved {
+    fn drop(&mut self) {
+        let _ = self.notify.send(self.id);
+    }
+}
+
+/// A subtree which will be rendered when its data is resolved.
+/// Returns `false` if there is no data.
+type Resume = Box<FnMut(&mut String) -> bool>;
+
+thread_local! {
+    /// Subtrees of rendered streams which wait for data, by streams and ids of nodes.
+    static AWAITING: RefCell<HashMap<(usize, usize), Resume>> = RefCell::new(HashMap::new());
+    static NEXT_STREAM: Cell<usize> = Cell::new(0);
+}
+
+/// Removes subtrees of a stream which wasn't finished, like when a client disconnected.
+struct Awaiting(usize);
+
+impl Drop for Awaiting {
+    fn drop(&mut self) {
+        let stream = self.0;
+        AWAITING.with(|awaiting| awaiting.borrow_mut().retain(|&(owner, _), _| owner != stream));
+    }
+}
+
+/// A streaming renderer which writes a page in chunks.
+///
+/// ```rust,ignore
+/// let mut stream = ssr::Stream::new(response);
+/// let user = stream.spawn(move || db.load_user(id));
+/// let page: Html<Msg> = html! {
+///     <div>
+///         { ssr::await_node(user, html! { <p>{ "Loading..." }</p> }, |user: User| html! {
+///             <p>{ user.name }</p>
+///         }) }
+///     </div>
+/// };
+/// stream.render(page)?;
+/// stream.finish()?;
+/// ```
+pub struct Stream<W: Write> {
+    out: W,
+    awaiting: Awaiting,
+    next_id: usize,
+    notify: Sender<usize>,
+    resolved: Receiver<usize>,
+    completed: HashSet<usize>,
+}
+
+impl<W: Write> Stream<W> {
+    /// Creates a stream which writes to the `out`.
+    pub fn new(out: W) -> Self {
+        let (notify, resolved) = channel();
+        let stream = NEXT_STREAM.with(|next| {
+            let stream = next.get();
+            next.set(stream + 1);
+            stream
+        });
+        Stream {
+            out,
+            awaiting: Awaiting(stream),
+            next_id: 0,
+            notify,
+            resolved,
+            completed: HashSet::new(),
+        }
+    }
+
+    /// Resolves data with the `resolver` in a separate thread.
+    pub fn spawn<T, F>(&mut self, resolver: F) -> Pending<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let id = self.next_id;
+        self.next_id += 1;
+        let slot = Arc::new(Mutex::new(None));
+        let resolved = Resolved {
+            id,
+            notify: self.notify.clone(),
+        };
+        let target = slot.clone();
+        thread::spawn(move || {
+            let _resolved = resolved;
+            let value = resolver();
+            *target.lock().expect("slot of pending data poisoned") = Some(value);
+        });
+        Pending { stream: self.awaiting.0, id, slot }
+    }
+
+    /// Writes the shell of the page with fallbacks of pending subtrees.
+    pub fn render<MSG, T: Into<VNode<MSG>>>(&mut self, node: T) -> io::Result<()> {
+        let html = render_to_string(node);
+        self.out.write_all(html.as_bytes())?;
+        write!(self.out, "{}{}", script_tag(), SWAP_SCRIPT)?;
+        self.out.flush()
+    }
+
+    /// Writes subtrees in the order of their resolution and returns
+    /// the writer when all of them are written.
+    pub fn finish(mut self) -> io::Result<W> {
+        let stream = self.awaiting.0;
+        loop {
+            let ready = AWAITING.with(|awaiting| {
+                awaiting.borrow().keys()
+                    .find(|&&(owner, id)| owner == stream && self.completed.contains(&id))
+                    .map(|&(_, id)| id)
+            });
+            match ready {
+                Some(id) => {
+                    let resume = AWAITING.with(|awaiting| awaiting.borrow_mut().remove(&(stream, id)));
+                    let mut html = String::new();
+                    let resolved = resume.map(|mut resume| resume(&mut html)).unwrap_or(false);
+                    if resolved {
+                        write!(self.out, "<template id=\"yew-chunk-{}\">{}</template>", id, html)?;
+                        write!(self.out, "{}__yewSwap({});</script>", script_tag(), id)?;
+                        self.out.flush()?;
+                    }
+                }
+                None => {
+                    let waiting = AWAITING.with(|awaiting| awaiting.borrow().keys().any(|&(owner, _)| owner == stream));
+                    if !waiting {
+                        break;
+                    }
+                    let id = self.resolved.recv().expect("stream keeps a sender");
+                    self.completed.insert(id);
+                }
+            }
+        }
+        Ok(self.out)
+    }
+}
+
+/// Creates a node which renders the `fallback` and then the subtree made
+/// by the `render` function when the data is resolved. If the resolver panicked
+/// the fallback stays. Browsers render nothing for this node, use `Suspense` there.
+pub fn await_node<MSG, T, F>(pending: Pending<T>, fallback: Html<MSG>, render: F) -> VNode<MSG>
+where
+    MSG: 'static,
+    T: 'static,
+    F: Fn(T) -> Html<MSG> + 'static,
+{
+    let node = Await {
+        pending: Some(pending),
+        fallback: Some(VNode::from(fallback)),
+        render: Some(render),
+        #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+        placeholder: None,
+        _msg: PhantomData,
+    };
+    VNode::from(VComp::from_mount(Box::new(node)))
+}
+
+struct Await<MSG, T, F> {
+    pending: Option<Pending<T>>,
+    fallback: Option<VNode<MSG>>,
+    render: Option<F>,
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    placeholder: Option<Node>,
+    _msg: PhantomData<MSG>,
+}
+
+impl<MSG, T, F> Mount for Await<MSG, T, F>
+where
+    MSG: 'static,
+    T: 'static,
+    F: Fn(T) -> Html<MSG> + 'static,
+{
+    fn kind(&self) -> TypeId {
+        TypeId::of::<Await<MSG, T, F>>()
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn mount(&mut self, parent: &Node) {
+        warn!("server-side await node rendered in a browser");
+        let placeholder = Node::from(document().create_text_node(""));
+        parent.append_child(&placeholder);
+        self.placeholder = Some(placeholder);
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn patch(&mut self, _: &Node, mut last: Box<Mount>) {
+        self.placeholder = last.unmount();
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn hydrate(&mut self, _: &Node, _: &mut Option<Node>) -> Result<(), String> {
+        Err("server-side await node can't be hydrated".to_owned())
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn reference(&self) -> Option<Node> {
+        self.placeholder.clone()
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn unmount(&mut self) -> Option<Node> {
+        self.placeholder.take()
+    }
+
+    fn render_to_string(&mut self, out: &mut String) {
+        let pending = self.pending.take().expect("tried to render an await node twice");
+        let render = self.render.take().expect("tried to render an await node twice");
+        let (stream, id) = (pending.stream, pending.id);
+        out.push_str(&format!("<!--yew-await-{}-->", id));
+        if let Some(ref mut fallback) = self.fallback {
+            write_node(fallback, out);
+        }
+        out.push_str(&format!("<!--/yew-await-{}-->", id));
+        let slot = pending.slot;
+        let resume = move |out: &mut String| {
+            let value = slot.lock().ok().and_then(|mut slot| slot.take());
+            match value {
+                Some(value) => {
+                    let mut node = VNode::from(render(value));
+                    write_node(&mut node, out);
+                    true
+                }
+                None => {
+                    warn!("data of the await node {} wasn't resolved", id);
+                    false
+                }
+            }
+        };
+        AWAITING.with(|awaiting| awaiting.borrow_mut().insert((stream, id), Box::new(resume)));
+    }
+
+    #[cfg(feature = "testing")]
+    fn render_to_test(&mut self) -> ::testing::TestNode {
+        match self.fallback {
+            Some(ref mut fallback) => ::testing::convert(fallback),
+            None => ::testing::TestNode::Text(String::new()),
+        }
+    }
+
+    #[cfg(feature = "a11y")]
+    fn audit(&self, _: &mut ::a11y::Audit) {
+        // Nothing is rendered in a browser
+    }
+
+    fn into_any(self: Box<Self>) -> Box<Any> {
+        self
+    }
+}
@@ -0,0 +1,223 @@
+//! This module contains a strict mode of development builds which is
+//! compiled with the `strict` feature. It warns about common mistakes:
+//!
+//! * a message is sent during `view`, which has to be a pure function of the state;
+//! * a task sends a message after its component or app was destroyed, because
+//!   it wasn't canceled or its handle was leaked;
+//! * items of a list use the same key, the `data-flip-key` attribute, so they
+//!   can't be matched between renders;
+//! * `view` returns another tree when it's called twice for the same state.
+//!
+//! Every render calls `view` twice to compare trees, so the mode isn't meant
+//! for release builds. Each mistake is printed to the console once and all
+//! of them are kept for tests:
+//!
+//! ```rust,ignore
+//! let mut renderer = TestRenderer::<Counter>::new(CounterProps::default());
+//! renderer.click("button");
+//! assert!(strict::warnings().is_empty(), "{:?}", strict::warnings());
+//! ```
+
+use std::any;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use flip::KEY_ATTRIBUTE;
+use virtual_dom::{VNode, VTag};
+
+/// A kind of a mistake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mistake {
+    /// A message was sent during `view`.
+    SentInView,
+    /// A message was sent to a destroyed component or app.
+    LeakedTask,
+    /// Siblings use the same key.
+    DuplicateKey,
+    /// Two calls of `view` returned different trees.
+    UnstableView,
+}
+
+/// A mistake found by the strict mode.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Warning {
+    /// A kind of the mistake.
+    pub mistake: Mistake,
+    /// The type name of the component, or of the message of a leaked task.
+    pub source: String,
+    /// Details like the key or the element which differ.
+    pub detail: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.mistake {
+            Mistake::SentInView => write!(f, "{} sends a message during view: {}", self.source, self.detail),
+            Mistake::LeakedTask => write!(f, "a task sends {} after its component was destroyed, cancel it", self.source),
+            Mistake::DuplicateKey => write!(f, "{} renders siblings with the same key: {}", self.source, self.detail),
+            Mistake::UnstableView => write!(f, "{} renders another tree for the same state: {}", self.source, self.detail),
+        }
+    }
+}
+
+thread_local! {
+    /// Components which are in `view` now.
+    static VIEWING: RefCell<Vec<&'static str>> = RefCell::new(Vec::new());
+    static WARNINGS: RefCell<Vec<Warning>> = RefCell::new(Vec::new());
+    static REPORTED: RefCell<HashSet<Warning>> = RefCell::new(HashSet::new());
+}
+
+/// Returns all mistakes found since the last `clear`.
+pub fn warnings() -> Vec<Warning> {
+    WARNINGS.with(|warnings| warnings.borrow().clone())
+}
+
+/// Forgets found mistakes, so they're printed again.
+pub fn clear() {
+    WARNINGS.with(|warnings| warnings.borrow_mut().clear());
+    REPORTED.with(|reported| reported.borrow_mut().clear());
+}
+
+fn report(mistake: Mistake, source: &str, detail: String) {
+    let warning = Warning {
+        mistake,
+        source: source.to_owned(),
+        detail,
+    };
+    let new = REPORTED.with(|reported| reported.borrow_mut().insert(warning.clone()));
+    if new {
+        warn!("strict mode: {}", warning);
+        WARNINGS.with(|warnings| warnings.borrow_mut().push(warning));
+    }
+}
+
+/// A guard of a call of `view`.
+pub(crate) struct Viewing(());
+
+impl Drop for Viewing {
+    fn drop(&mut self) {
+        VIEWING.with(|viewing| viewing.borrow_mut().pop());
+    }
+}
+
+/// Marks `view` of the component as running until the guard is dropped.
+pub(crate) fn viewing(name: &'static str) -> Viewing {
+    VIEWING.with(|viewing| viewing.borrow_mut().push(name));
+    Viewing(())
+}
+
+/// Checks a message which is sent now.
+pub(crate) fn sent<MSG>() {
+    if let Some(name) = VIEWING.with(|viewing| viewing.borrow().last().cloned()) {
+        report(Mistake::SentInView, name, any::type_name::<MSG>().to_owned());
+    }
+}
+
+/// Reports a message which was sent to a destroyed loop.
+pub(crate) fn leaked<MSG>() {
+    report(Mistake::LeakedTask, any::type_name::<MSG>(), String::new());
+}
+
+/// Checks the rendered frame and compares it with a frame of the second call of `view`.
+pub(crate) fn check<MSG, F>(name: &'static str, frame: &VNode<MSG>, view: F)
+where
+    F: FnOnce() -> VNode<MSG>,
+{
+    keys(name, frame);
+    let again = {
+        let _viewing = viewing(name);
+        view()
+    };
+    if let Some(detail) = differ(frame, &again) {
+        report(Mistake::UnstableView, name, detail);
+    }
+}
+
+/// Checks keys of children of every element of the tree. Nested components check their own trees.
+fn keys<MSG>(name: &'static str, node: &VNode<MSG>) {
+    let childs = match *node {
+        VNode::VTag { ref vtag, .. } => &vtag.childs,
+        VNode::VPortal { ref vportal, .. } => &vportal.childs,
+        _ => return,
+    };
+    let mut seen = HashSet::new();
+    for child in childs {
+        if let VNode::VTag { ref vtag, .. } = *child {
+            if let Some(key) = vtag.attributes.get(KEY_ATTRIBUTE) {
+                if !seen.insert(key) {
+                    report(Mistake::DuplicateKey, name, format!("{} `{}`", describe(vtag), key));
+                }
+            }
+        }
+        keys(name, child);
+    }
+}
+
+/// Returns a description of the first node which differs between trees.
+fn differ<MSG>(left: &VNode<MSG>, right: &VNode<MSG>) -> Option<String> {
+    match (left, right) {
+        (VNode::VTag { vtag: left, .. }, VNode::VTag { vtag: right, .. }) => {
+            let same = left.tag() == right.tag()
+                && sorted(&left.attributes) == sorted(&right.attributes)
+                && left.classes == right.classes
+                && left.value == right.value
+                && left.kind == right.kind
+                && left.checked == right.checked
+                && left.childs.len() == right.childs.len();
+            if !same {
+                return Some(describe(left));
+            }
+            children(&left.childs, &right.childs)
+        }
+        (VNode::VText { vtext: left, .. }, VNode::VText { vtext: right, .. }) => {
+            if left.text == right.text {
+                None
+            } else {
+                Some(format!("text `{}`", left.text))
+            }
+        }
+        (VNode::VRaw { vraw: left, .. }, VNode::VRaw { vraw: right, .. }) => {
+            if left.tag() == right.tag() && left.html() == right.html() {
+                None
+            } else {
+                Some(format!("raw {}", left.tag()))
+            }
+        }
+        (VNode::VPortal { vportal: left, .. }, VNode::VPortal { vportal: right, .. }) => {
+            if left.childs.len() == right.childs.len() {
+                children(&left.childs, &right.childs)
+            } else {
+                Some("portal".to_owned())
+            }
+        }
+        (VNode::VComp { vcomp: left }, VNode::VComp { vcomp: right }) => {
+            if left.kind() == right.kind() {
+                None
+            } else {
+                Some("component".to_owned())
+            }
+        }
+        _ => Some("node".to_owned()),
+    }
+}
+
+fn children<MSG>(left: &[VNode<MSG>], right: &[VNode<MSG>]) -> Option<String> {
+    left.iter().zip(right).filter_map(|(left, right)| differ(left, right)).next()
+}
+
+fn sorted(attributes: &HashMap<String, String>) -> Vec<(&String, &String)> {
+    let mut attributes: Vec<_> = attributes.iter().collect();
+    attributes.sort();
+    attributes
+}
+
+fn describe<MSG>(vtag: &VTag<MSG>) -> String {
+    let mut selector = vtag.tag().to_lowercase();
+    let mut classes = vtag.classes.iter().collect::<Vec<_>>();
+    classes.sort();
+    for class in classes {
+        selector.push('.');
+        selector.push_str(class);
+    }
+    selector
+}
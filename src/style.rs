@@ -0,0 +1,129 @@
+//! This module contains a `Style` builder for inline styles.
+//!
+//! ```rust,ignore
+//! let style = Style::new()
+//!     .set("width", format!("{}px", model.width))
+//!     .set_if(model.hidden, "display", "none");
+//! html! {
+//!     <div style=style,></div>
+//! }
+//! ```
+
+use std::fmt;
+use direction;
+
+/// An ordered list of CSS declarations for the `style` attribute.
+/// Setting a property again replaces its value.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Style {
+    declarations: Vec<(String, String)>,
+}
+
+impl Style {
+    /// Creates an empty style.
+    pub fn new() -> Self {
+        Style {
+            declarations: Vec::new(),
+        }
+    }
+
+    /// Sets a value of the property.
+    pub fn set<P, V>(mut self, property: P, value: V) -> Self
+    where
+        P: Into<String>,
+        V: ToString,
+    {
+        self.insert(property.into(), value.to_string());
+        self
+    }
+
+    /// Sets a value of the property if the flag is `true`.
+    pub fn set_if<P, V>(self, flag: bool, property: P, value: V) -> Self
+    where
+        P: Into<String>,
+        V: ToString,
+    {
+        if flag {
+            self.set(property, value)
+        } else {
+            self
+        }
+    }
+
+    /// Sets a value of the property if it's `Some`.
+    pub fn set_opt<P, V>(self, property: P, value: Option<V>) -> Self
+    where
+        P: Into<String>,
+        V: ToString,
+    {
+        match value {
+            Some(value) => self.set(property, value),
+            None => self,
+        }
+    }
+
+    /// Sets a property with logical sides, like `padding-inline-start`, or a value
+    /// `start` or `end` of `text-align` and `float`. They're replaced with
+    /// physical sides of the current direction.
+    pub fn set_logical<V>(self, property: &str, value: V) -> Self
+    where
+        V: ToString,
+    {
+        let direction = direction::direction();
+        let value = value.to_string();
+        let value = direction.physical_value(property, &value).to_owned();
+        self.set(direction.physical_property(property), value)
+    }
+
+    /// Adds declarations of another style. They replace values
+    /// of the same properties.
+    pub fn merge(mut self, other: Style) -> Self {
+        for (property, value) in other.declarations {
+            self.insert(property, value);
+        }
+        self
+    }
+
+    /// Returns a value of the property.
+    pub fn get(&self, property: &str) -> Option<&str> {
+        self.declarations.iter()
+            .find(|(name, _)| name == property)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns `true` if there are no declarations.
+    pub fn is_empty(&self) -> bool {
+        self.declarations.is_empty()
+    }
+
+    fn insert(&mut self, property: String, value: String) {
+        let is_valid_name = !property.is_empty() && property.chars().all(|c| {
+            c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-'
+        });
+        if !is_valid_name {
+            warn!("wrong name of style property: '{}'", property);
+            return;
+        }
+        if value.contains(';') || value.contains('{') || value.contains('}') {
+            warn!("wrong value of style property '{}': '{}'", property, value);
+            return;
+        }
+        if let Some(item) = self.declarations.iter_mut().find(|item| item.0 == property) {
+            item.1 = value;
+            return;
+        }
+        self.declarations.push((property, value));
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (idx, (property, value)) in self.declarations.iter().enumerate() {
+            if idx > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}: {};", property, value)?;
+        }
+        Ok(())
+    }
+}
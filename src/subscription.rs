@@ -0,0 +1,269 @@
+//! This module contains subscriptions to long-running sources of messages.
+//! A program declares the subscriptions it needs for a model and
+//! `Subscriptions` starts new ones and stops ones which aren't declared
+//! anymore, so handles of tasks aren't kept in a context.
+//!
+//! ```rust,ignore
+//! fn subscriptions(model: &Model) -> Vec<Subscription<Msg>> {
+//!     let mut subscriptions = vec![Subscription::window("resize", |_| Msg::Resized)];
+//!     if model.running {
+//!         subscriptions.push(Subscription::interval(Duration::from_secs(1), || Msg::Tick));
+//!     }
+//!     subscriptions
+//! }
+//! ```
+//!
+//! Subscriptions are matched by keys. A subscription which is still
+//! declared keeps running and uses the converter of the last declaration.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::mem;
+use std::rc::Rc;
+use std::time::Duration;
+use stdweb::Value;
+use format::Restorable;
+use html::AppSender;
+use services::Task;
+use services::interval::IntervalService;
+use services::websocket::{WebSocketService, WebSocketStatus};
+
+type Converter<IN, MSG> = Rc<RefCell<Box<Fn(IN) -> MSG>>>;
+
+/// What a subscription listens to.
+enum Source<MSG> {
+    Interval(Duration, Converter<(), MSG>),
+    Window(&'static str, Converter<Value, MSG>),
+    WebSocket(String, Converter<Restorable, MSG>, Converter<WebSocketStatus, MSG>),
+}
+
+/// A declaration of a source of messages.
+pub struct Subscription<MSG> {
+    key: String,
+    source: Source<MSG>,
+}
+
+fn converter<IN, MSG, F>(converter: F) -> Converter<IN, MSG>
+where
+    F: Fn(IN) -> MSG + 'static,
+{
+    Rc::new(RefCell::new(Box::new(converter)))
+}
+
+impl<MSG: 'static> Subscription<MSG> {
+    /// Sends a message from the `converter` on every elapsed `duration`.
+    pub fn interval<F>(duration: Duration, converter: F) -> Self
+    where
+        F: Fn() -> MSG + 'static,
+    {
+        Subscription {
+            key: format!("interval:{:?}", duration),
+            source: Source::Interval(duration, self::converter(move |_| converter())),
+        }
+    }
+
+    /// Sends a message for every event of the `kind` fired on the window.
+    pub fn window<F>(kind: &'static str, converter: F) -> Self
+    where
+        F: Fn(Value) -> MSG + 'static,
+    {
+        Subscription {
+            key: format!("window:{}", kind),
+            source: Source::Window(kind, self::converter(converter)),
+        }
+    }
+
+    /// Sends messages for data and notifications of a websocket connection
+    /// to the `url`. Use `WebSocketService` to send data to a server.
+    pub fn websocket<F, N, OUT>(url: &str, converter: F, notification: N) -> Self
+    where
+        OUT: From<Restorable>,
+        F: Fn(OUT) -> MSG + 'static,
+        N: Fn(WebSocketStatus) -> MSG + 'static,
+    {
+        let data = self::converter(move |data| converter(OUT::from(data)));
+        Subscription {
+            key: format!("websocket:{}", url),
+            source: Source::WebSocket(url.to_owned(), data, self::converter(notification)),
+        }
+    }
+
+    /// Adds an id to the key, so a few subscriptions of the same source
+    /// could run at once.
+    pub fn keyed(mut self, id: &str) -> Self {
+        self.key.push('#');
+        self.key.push_str(id);
+        self
+    }
+
+    /// Returns the key which identifies the subscription between updates.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    fn start(&self, sender: &AppSender<MSG>) -> Box<Task> {
+        match self.source {
+            Source::Interval(duration, ref converter) => {
+                let converter = converter.clone();
+                let mut interval = IntervalService::new(sender.clone());
+                Box::new(interval.spawn(duration, move || (*converter.borrow())(())))
+            }
+            Source::Window(kind, ref converter) => {
+                let converter = converter.clone();
+                let mut tx = sender.clone();
+                let callback = move |event: Value| {
+                    let msg = (*converter.borrow())(event);
+                    tx.send(msg);
+                };
+                let handle = js! {
+                    var callback = @{callback};
+                    var listener = function(event) {
+                        callback(event);
+                    };
+                    window.addEventListener(@{kind}, listener);
+                    return {
+                        kind: @{kind},
+                        listener,
+                        callback,
+                    };
+                };
+                Box::new(WindowHandle(Some(handle)))
+            }
+            Source::WebSocket(ref url, ref converter, ref notification) => {
+                let converter = converter.clone();
+                let notification = notification.clone();
+                let mut websocket = WebSocketService::new(sender.clone());
+                let handle = websocket.connect(
+                    url,
+                    move |data: Restorable| (*converter.borrow())(data),
+                    move |status| (*notification.borrow())(status),
+                );
+                Box::new(handle)
+            }
+        }
+    }
+
+    /// Moves converters of the declaration to the running subscription.
+    /// Returns `false` if sources are different.
+    fn refresh(&self, declared: &Subscription<MSG>) -> bool {
+        match (&self.source, &declared.source) {
+            (Source::Interval(duration, running), Source::Interval(other, declared))
+                if duration == other =>
+            {
+                mem::swap(&mut *running.borrow_mut(), &mut *declared.borrow_mut());
+                true
+            }
+            (Source::Window(kind, running), Source::Window(other, declared))
+                if kind == other =>
+            {
+                mem::swap(&mut *running.borrow_mut(), &mut *declared.borrow_mut());
+                true
+            }
+            (Source::WebSocket(url, data, status), Source::WebSocket(other, declared_data, declared_status))
+                if url == other =>
+            {
+                mem::swap(&mut *data.borrow_mut(), &mut *declared_data.borrow_mut());
+                mem::swap(&mut *status.borrow_mut(), &mut *declared_status.borrow_mut());
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A handle of a listener of window events.
+struct WindowHandle(Option<Value>);
+
+impl Task for WindowHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let handle = self.0.take().expect("tried to remove a window listener twice");
+        js! { @(no_return)
+            var handle = @{handle};
+            window.removeEventListener(handle.kind, handle.listener);
+            handle.callback.drop();
+        }
+    }
+}
+
+impl Drop for WindowHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+/// A running subscription.
+struct Active<MSG> {
+    subscription: Subscription<MSG>,
+    handle: Box<Task>,
+}
+
+/// Keeps subscriptions of a program running.
+pub struct Subscriptions<MSG> {
+    sender: AppSender<MSG>,
+    active: HashMap<String, Active<MSG>>,
+}
+
+impl<MSG: 'static> Subscriptions<MSG> {
+    /// Creates a set of subscriptions connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Subscriptions {
+            sender,
+            active: HashMap::new(),
+        }
+    }
+
+    /// Starts declared subscriptions which aren't running and stops
+    /// running subscriptions which aren't declared.
+    pub fn update<I>(&mut self, declared: I)
+    where
+        I: IntoIterator<Item = Subscription<MSG>>,
+    {
+        let mut running = mem::take(&mut self.active);
+        for subscription in declared {
+            if self.active.contains_key(&subscription.key) {
+                warn!("subscription declared twice: {}", subscription.key);
+                continue;
+            }
+            let key = subscription.key.clone();
+            let active = match running.remove(&key) {
+                Some(mut active) => {
+                    if active.subscription.refresh(&subscription) {
+                        active
+                    } else {
+                        // The key was used for another source
+                        active.handle.cancel();
+                        self.start(subscription)
+                    }
+                }
+                None => self.start(subscription),
+            };
+            self.active.insert(key, active);
+        }
+        for (_, mut active) in running {
+            active.handle.cancel();
+        }
+    }
+
+    fn start(&self, subscription: Subscription<MSG>) -> Active<MSG> {
+        let handle = subscription.start(&self.sender);
+        Active { subscription, handle }
+    }
+
+    /// Stops all subscriptions.
+    pub fn clear(&mut self) {
+        self.update(Vec::new());
+    }
+
+    /// Returns keys of running subscriptions.
+    pub fn keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self.active.keys().map(String::as_str).collect();
+        keys.sort();
+        keys
+    }
+}
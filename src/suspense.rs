@@ -0,0 +1,224 @@
+//! This module contains a pattern for "loading" states of views
+//! which depend on asynchronous resources.
+//!
+//! Keep a `Resource` in a model, spawn a task which will deliver the data
+//! (fetch or any other service) and resolve the resource with a message.
+//! Use `Suspense` in a `view` to render a fallback content until the
+//! resource is ready:
+//!
+//! ```rust,ignore
+//! fn update(context: &mut Context, model: &mut Model, msg: Msg) {
+//!     match msg {
+//!         Msg::Load => {
+//!             let request = Request::get("/user.json").body(Nothing).unwrap();
+//!             let task = context.fetch.fetch(request, |response| {
+//!                 let (_, Json(data)) = response.into_parts();
+//!                 Msg::Loaded(data)
+//!             });
+//!             model.user.load(task);
+//!             let timeout = context.timeout.spawn(Duration::from_secs(5), || Msg::TimedOut);
+//!             model.user.deadline(timeout);
+//!         }
+//!         Msg::Loaded(data) => {
+//!             model.user.resolve(data);
+//!         }
+//!         Msg::TimedOut => {
+//!             model.user.time_out();
+//!         }
+//!     }
+//! }
+//!
+//! fn view(model: &Model) -> Html<Msg> {
+//!     Suspense::new(&model.user, || html! { <p>{ "Loading..." }</p> })
+//!         .failed(|_| html! { <p>{ "Can't load the user" }</p> })
+//!         .view(|user| html! { <p>{ &user.name }</p> })
+//! }
+//! ```
+
+use html::Html;
+use services::Task;
+use services::timeout::TimeoutHandle;
+
+/// A state of a `Resource`.
+pub enum State<T, E> {
+    /// Nothing requested yet.
+    Idle,
+    /// The resource is loading now.
+    Pending,
+    /// The resource was loaded successfully.
+    Ready(T),
+    /// The resource wasn't loaded because of an error.
+    Failed(E),
+    /// The resource wasn't loaded in time.
+    TimedOut,
+}
+
+/// A container for a value which will be delivered asynchronously.
+/// Keeps a task which loads the value and cancels it when
+/// the resource reloaded or timed out.
+pub struct Resource<T, E = ()> {
+    state: State<T, E>,
+    task: Option<Box<Task>>,
+    timeout: Option<TimeoutHandle>,
+}
+
+impl<T, E> Default for Resource<T, E> {
+    fn default() -> Self {
+        Resource::new()
+    }
+}
+
+impl<T, E> Resource<T, E> {
+    /// Creates an idle resource.
+    pub fn new() -> Self {
+        Resource {
+            state: State::Idle,
+            task: None,
+            timeout: None,
+        }
+    }
+
+    /// Returns the current state of the resource.
+    pub fn state(&self) -> &State<T, E> {
+        &self.state
+    }
+
+    /// Returns `true` if the resource waits for a value.
+    pub fn is_pending(&self) -> bool {
+        matches!(self.state, State::Pending)
+    }
+
+    /// Returns a reference to a loaded value if it's ready.
+    pub fn ready(&self) -> Option<&T> {
+        match self.state {
+            State::Ready(ref value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Starts loading of the resource with a task which will send
+    /// a message to resolve the resource. An unfinished task of
+    /// the previous loading will be canceled.
+    pub fn load<TSK: Task + 'static>(&mut self, task: TSK) {
+        self.cancel();
+        self.task = Some(Box::new(task));
+        self.state = State::Pending;
+    }
+
+    /// Sets a timeout task which have to send a message to call
+    /// `time_out` if the resource can't be loaded in time.
+    pub fn deadline(&mut self, timeout: TimeoutHandle) {
+        if let Some(mut previous) = self.timeout.take() {
+            previous.cancel();
+        }
+        self.timeout = Some(timeout);
+    }
+
+    /// Resolves the resource with a loaded value or an error.
+    /// Does nothing if the resource doesn't wait for a value anymore.
+    pub fn resolve(&mut self, result: Result<T, E>) {
+        if !self.is_pending() {
+            return;
+        }
+        // The task is finished and can't be canceled
+        self.task.take();
+        if let Some(mut timeout) = self.timeout.take() {
+            timeout.cancel();
+        }
+        self.state = match result {
+            Ok(value) => State::Ready(value),
+            Err(err) => State::Failed(err),
+        };
+    }
+
+    /// Marks the resource as timed out and cancels a loading task.
+    pub fn time_out(&mut self) {
+        if !self.is_pending() {
+            return;
+        }
+        // Timeout is elapsed already and can't be canceled
+        self.timeout.take();
+        if let Some(mut task) = self.task.take() {
+            task.cancel();
+        }
+        self.state = State::TimedOut;
+    }
+
+    /// Cancels unfinished tasks and resets the resource to an idle state.
+    pub fn cancel(&mut self) {
+        if self.is_pending() {
+            if let Some(mut task) = self.task.take() {
+                task.cancel();
+            }
+            if let Some(mut timeout) = self.timeout.take() {
+                timeout.cancel();
+            }
+        }
+        self.task = None;
+        self.timeout = None;
+        self.state = State::Idle;
+    }
+}
+
+type Render<'a, MSG> = Box<Fn() -> Html<MSG> + 'a>;
+type RenderError<'a, E, MSG> = Box<Fn(&E) -> Html<MSG> + 'a>;
+
+/// A builder of a view which renders a fallback content until
+/// the `Resource` resolved and swaps it with the real content later.
+pub struct Suspense<'a, T: 'a, E: 'a, MSG> {
+    resource: &'a Resource<T, E>,
+    fallback: Render<'a, MSG>,
+    failed: Option<RenderError<'a, E, MSG>>,
+    timed_out: Option<Render<'a, MSG>>,
+}
+
+impl<'a, T: 'a, E: 'a, MSG> Suspense<'a, T, E, MSG> {
+    /// Creates a suspense for the `resource` which renders the `fallback`
+    /// while the resource is idle or pending.
+    pub fn new<F>(resource: &'a Resource<T, E>, fallback: F) -> Self
+    where
+        F: Fn() -> Html<MSG> + 'a,
+    {
+        Suspense {
+            resource,
+            fallback: Box::new(fallback),
+            failed: None,
+            timed_out: None,
+        }
+    }
+
+    /// Sets a view for a failed resource. The fallback used if not set.
+    pub fn failed<F>(mut self, view: F) -> Self
+    where
+        F: Fn(&E) -> Html<MSG> + 'a,
+    {
+        self.failed = Some(Box::new(view));
+        self
+    }
+
+    /// Sets a view for a timed out resource. The fallback used if not set.
+    pub fn timed_out<F>(mut self, view: F) -> Self
+    where
+        F: Fn() -> Html<MSG> + 'a,
+    {
+        self.timed_out = Some(Box::new(view));
+        self
+    }
+
+    /// Renders the state of the resource using `view` for a ready value.
+    pub fn view<F>(self, view: F) -> Html<MSG>
+    where
+        F: FnOnce(&T) -> Html<MSG>,
+    {
+        match self.resource.state {
+            State::Ready(ref value) => view(value),
+            State::Failed(ref err) if self.failed.is_some() => {
+                (self.failed.as_ref().unwrap())(err)
+            }
+            State::TimedOut if self.timed_out.is_some() => {
+                (self.timed_out.as_ref().unwrap())()
+            }
+            _ => (self.fallback)(),
+        }
+    }
+}
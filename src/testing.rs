@@ -0,0 +1,927 @@
+//! This module contains a renderer of components for tests which doesn't
+//! need a browser. A view is converted to a tree of `TestNode` which could
+//! be queried with simple selectors, and messages are handled by the test:
+//!
+//! ```rust,ignore
+//! let mut counter = TestRenderer::<Counter>::new(CounterProps::default());
+//! assert_eq!(counter.root().find("button").unwrap().text(), "0");
+//! counter.send(Msg::Increment);
+//! assert_eq!(counter.root().find("button").unwrap().text(), "1");
+//! assert!(counter.root().find_component::<Badge>().is_some());
+//! ```
+//!
+//! Events are simulated with data which listeners get, like
+//! `counter.click("button")` or `form.type_text("input.name", "Ann")`.
+//! An event bubbles up to listeners of parent elements unless a listener
+//! stops its propagation, and messages are handled after every event.
+//!
+//! A tree is printed as HTML-like markup with sorted attributes, so
+//! `assert_snapshot` compares it with a golden file kept with tests:
+//!
+//! ```rust,ignore
+//! testing::assert_snapshot("tests/snapshots/counter.snap", counter.root());
+//! ```
+//!
+//! Nested components are created with their properties and rendered once
+//! on every render of the tested component. Their messages are ignored,
+//! test them with own renderers, and their listeners aren't called.
+//!
+//! With `html::set_time_slicing` a render slices like in a browser: new nodes
+//! which don't fit in the budget are empty text placeholders until a test
+//! calls `run_frame`, and `MockClock` controls the time of the budget.
+
+use std::any::{self, Any, TypeId};
+use std::collections::{BTreeMap, VecDeque};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::slice;
+use component::{Component, ShouldRender};
+use html::{AppSender, InputData, KeyData, Lanes, MouseData};
+use middleware::{self, Middleware};
+use virtual_dom::{VNode, VTag};
+use virtual_dom::slicing;
+
+/// How many messages a renderer handles in a single flush.
+/// A component which sends messages to itself forever fails a test.
+const MAX_MESSAGES: usize = 10_000;
+
+/// Listeners of these events aren't called for events of child elements.
+const NOT_BUBBLING: &[&str] = &[
+    "onfocus", "onblur", "onmouseenter", "onmouseleave",
+    "onpointerenter", "onpointerleave", "onscroll",
+];
+
+/// A rendered node of a tree.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TestNode {
+    /// An element with its children.
+    Element(TestElement),
+    /// A text node.
+    Text(String),
+    /// Raw HTML inside a wrapper element. The markup isn't parsed.
+    Raw {
+        /// A tag of the wrapper element.
+        tag: String,
+        /// The markup.
+        html: String,
+    },
+    /// A nested component with its rendered view.
+    Component {
+        /// A type name of the component.
+        name: &'static str,
+        /// A type id of the component.
+        kind: TypeId,
+        /// The root node of the view of the component.
+        root: Box<TestNode>,
+    },
+    /// Children of a portal which are rendered into another node.
+    Portal {
+        /// A selector of the host node.
+        selector: String,
+        /// Nodes rendered into the host.
+        children: Vec<TestNode>,
+    },
+}
+
+/// A rendered element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestElement {
+    /// A tag of the element.
+    pub tag: String,
+    /// Sorted classes of the element.
+    pub classes: Vec<String>,
+    /// Attributes of the element.
+    pub attributes: BTreeMap<String, String>,
+    /// A value of an input.
+    pub value: Option<String>,
+    /// A type of an input.
+    pub kind: Option<String>,
+    /// A checked state of an input.
+    pub checked: bool,
+    /// Kinds of listeners of the element, like `onclick`.
+    pub listeners: Vec<&'static str>,
+    /// Children of the element.
+    pub children: Vec<TestNode>,
+}
+
+impl TestElement {
+    /// Returns the value of an attribute.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(String::as_str)
+    }
+
+    /// Returns `true` if the element has the class.
+    pub fn has_class(&self, class: &str) -> bool {
+        self.classes.iter().any(|item| item == class)
+    }
+
+    /// Returns `true` if the element has a listener of the kind, like `onclick`.
+    pub fn listens(&self, kind: &str) -> bool {
+        self.listeners.contains(&kind)
+    }
+}
+
+impl TestNode {
+    /// Returns the element if this node is an element.
+    pub fn element(&self) -> Option<&TestElement> {
+        match *self {
+            TestNode::Element(ref element) => Some(element),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of an attribute if this node is an element.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.element().and_then(|element| element.attribute(name))
+    }
+
+    /// Returns child nodes. A component has the root node of its view as a child.
+    pub fn children(&self) -> &[TestNode] {
+        match *self {
+            TestNode::Element(ref element) => &element.children,
+            TestNode::Portal { ref children, .. } => children,
+            TestNode::Component { ref root, .. } => slice::from_ref(root),
+            TestNode::Text(_) | TestNode::Raw { .. } => &[],
+        }
+    }
+
+    /// Returns text content of the node and all its descendants.
+    /// The markup of raw nodes is returned as is.
+    pub fn text(&self) -> String {
+        let mut out = String::new();
+        self.write_text(&mut out);
+        out
+    }
+
+    fn write_text(&self, out: &mut String) {
+        match *self {
+            TestNode::Text(ref text) => out.push_str(text),
+            TestNode::Raw { ref html, .. } => out.push_str(html),
+            _ => {
+                for child in self.children() {
+                    child.write_text(out);
+                }
+            }
+        }
+    }
+
+    /// Returns the first node which matches the selector, including this node.
+    /// Selectors are compound selectors like `li.item.done`, `#name`, `[type=text]`
+    /// separated by spaces for descendants: `ul.todos li`.
+    pub fn find(&self, selector: &str) -> Option<&TestNode> {
+        self.find_all(selector).into_iter().next()
+    }
+
+    /// Returns all nodes which match the selector in the document order.
+    pub fn find_all(&self, selector: &str) -> Vec<&TestNode> {
+        let parts = parse(selector);
+        let mut found = Vec::new();
+        self.walk(&mut Vec::new(), &mut |node, ancestors| {
+            if let Some(element) = node.element() {
+                if matches(&parts, element, ancestors) {
+                    found.push(node);
+                }
+            }
+        });
+        found
+    }
+
+    /// Returns the first nested component of the type.
+    pub fn find_component<COMP: Component>(&self) -> Option<&TestNode> {
+        self.find_components::<COMP>().into_iter().next()
+    }
+
+    /// Returns all nested components of the type.
+    pub fn find_components<COMP: Component>(&self) -> Vec<&TestNode> {
+        let kind = TypeId::of::<COMP>();
+        let mut found = Vec::new();
+        self.walk(&mut Vec::new(), &mut |node, _| {
+            if let TestNode::Component { kind: ref node_kind, .. } = *node {
+                if *node_kind == kind {
+                    found.push(node);
+                }
+            }
+        });
+        found
+    }
+
+    /// Returns indexes of children on the way to the `target` node.
+    fn path_to(&self, target: &TestNode, path: &mut Vec<usize>) -> bool {
+        if ::std::ptr::eq(self, target) {
+            return true;
+        }
+        for (index, child) in self.children().iter().enumerate() {
+            path.push(index);
+            if child.path_to(target, path) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+
+    /// Visits this node and its descendants with elements which contain them.
+    fn walk<'a, F>(&'a self, ancestors: &mut Vec<&'a TestElement>, visit: &mut F)
+    where
+        F: FnMut(&'a TestNode, &[&'a TestElement]),
+    {
+        visit(self, ancestors);
+        let element = self.element();
+        if let Some(element) = element {
+            ancestors.push(element);
+        }
+        for child in self.children() {
+            child.walk(ancestors, visit);
+        }
+        if element.is_some() {
+            ancestors.pop();
+        }
+    }
+}
+
+/// A compound selector of a single element.
+#[derive(Debug, Default)]
+struct Compound {
+    tag: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+    attributes: Vec<(String, Option<String>)>,
+}
+
+impl Compound {
+    fn matches(&self, element: &TestElement) -> bool {
+        if let Some(ref tag) = self.tag {
+            if !tag.eq_ignore_ascii_case(&element.tag) {
+                return false;
+            }
+        }
+        if let Some(ref id) = self.id {
+            if element.attribute("id") != Some(id.as_str()) {
+                return false;
+            }
+        }
+        if !self.classes.iter().all(|class| element.has_class(class)) {
+            return false;
+        }
+        self.attributes.iter().all(|(name, value)| {
+            let actual = match name.as_str() {
+                "value" => element.value.as_deref(),
+                "type" => element.kind.as_deref(),
+                _ => element.attribute(name),
+            };
+            match (actual, value) {
+                (Some(actual), Some(value)) => actual == value,
+                (Some(_), None) => true,
+                (None, _) => false,
+            }
+        })
+    }
+}
+
+/// Checks the last part of a selector against the element and
+/// the other parts against its ancestors.
+fn matches(parts: &[Compound], element: &TestElement, ancestors: &[&TestElement]) -> bool {
+    let (last, rest) = match parts.split_last() {
+        Some(split) => split,
+        None => return false,
+    };
+    if !last.matches(element) {
+        return false;
+    }
+    let mut rest = rest.iter().rev().peekable();
+    for ancestor in ancestors.iter().rev() {
+        if let Some(part) = rest.peek() {
+            if part.matches(ancestor) {
+                rest.next();
+            }
+        }
+    }
+    rest.peek().is_none()
+}
+
+/// Splits a selector to compound selectors. Panics on a malformed selector.
+fn parse(selector: &str) -> Vec<Compound> {
+    let mut parts = Vec::new();
+    let mut start = None;
+    let mut in_brackets = false;
+    for (index, c) in selector.char_indices() {
+        match c {
+            '[' => in_brackets = true,
+            ']' => in_brackets = false,
+            c if c.is_whitespace() && !in_brackets => {
+                if let Some(from) = start.take() {
+                    parts.push(parse_compound(&selector[from..index], selector));
+                }
+                continue;
+            }
+            _ => { }
+        }
+        if start.is_none() {
+            start = Some(index);
+        }
+    }
+    if let Some(from) = start {
+        parts.push(parse_compound(&selector[from..], selector));
+    }
+    if parts.is_empty() {
+        panic!("empty selector");
+    }
+    parts
+}
+
+fn parse_compound(text: &str, selector: &str) -> Compound {
+    let mut compound = Compound::default();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let (prefix, body) = match rest.chars().next() {
+            Some('#') | Some('.') | Some('[') => rest.split_at(1),
+            _ => ("", rest),
+        };
+        if prefix == "[" {
+            let end = body.find(']')
+                .unwrap_or_else(|| panic!("unclosed attribute in selector: {}", selector));
+            let inner = &body[..end];
+            rest = &body[end + 1..];
+            let attribute = match inner.find('=') {
+                Some(eq) => {
+                    let value = inner[eq + 1..].trim().trim_matches(|c| c == '"' || c == '\'');
+                    (inner[..eq].trim().to_owned(), Some(value.to_owned()))
+                }
+                None => (inner.trim().to_owned(), None),
+            };
+            compound.attributes.push(attribute);
+            continue;
+        }
+        let end = body.find(|c| c == '#' || c == '.' || c == '[').unwrap_or(body.len());
+        let name = &body[..end];
+        rest = &body[end..];
+        if name.is_empty() {
+            panic!("wrong selector: {}", selector);
+        }
+        match prefix {
+            "#" => compound.id = Some(name.to_owned()),
+            "." => compound.classes.push(name.to_owned()),
+            _ if name == "*" => { }
+            _ => compound.tag = Some(name.to_owned()),
+        }
+    }
+    compound
+}
+
+/// Renders a tree, like the result of a `view` function, without a renderer.
+pub fn render<MSG, T: Into<VNode<MSG>>>(node: T) -> TestNode {
+    convert(&mut node.into())
+}
+
+/// Converts a virtual node to a rendered test node.
+pub(crate) fn convert<MSG>(node: &mut VNode<MSG>) -> TestNode {
+    match *node {
+        VNode::VTag { ref mut vtag, .. } => TestNode::Element(convert_tag(vtag, |_, node| convert(node))),
+        VNode::VText { ref vtext, .. } => TestNode::Text(vtext.text.clone()),
+        VNode::VRaw { ref vraw, .. } => TestNode::Raw {
+            tag: vraw.tag().to_owned(),
+            html: vraw.html().to_owned(),
+        },
+        VNode::VPortal { ref mut vportal, .. } => TestNode::Portal {
+            selector: vportal.selector().to_owned(),
+            children: vportal.childs.iter_mut().map(convert).collect(),
+        },
+        VNode::VComp { ref mut vcomp } => vcomp.render_to_test(),
+    }
+}
+
+/// Converts a node of a sliced render. A child which has no node at the same
+/// place of the previous tree is new: it's left as a placeholder and its path
+/// is queued if the budget is spent, like `slicing::apply_new` does.
+fn convert_sliced<MSG>(
+    node: &mut VNode<MSG>,
+    previous: Option<&TestNode>,
+    path: &mut Vec<usize>,
+    pending: &mut VecDeque<Vec<usize>>,
+) -> TestNode {
+    let previous = previous.map(TestNode::children).unwrap_or(&[]);
+    let mut child = |index: usize, child: &mut VNode<MSG>| {
+        path.push(index);
+        let ancestor = previous.get(index);
+        let node = if ancestor.is_none() && slicing::expired() {
+            pending.push_back(path.clone());
+            TestNode::Text(String::new())
+        } else {
+            convert_sliced(child, ancestor, path, pending)
+        };
+        path.pop();
+        node
+    };
+    match *node {
+        VNode::VTag { ref mut vtag, .. } => TestNode::Element(convert_tag(vtag, child)),
+        VNode::VPortal { ref mut vportal, .. } => TestNode::Portal {
+            selector: vportal.selector().to_owned(),
+            children: vportal.childs.iter_mut().enumerate().map(|(index, node)| child(index, node)).collect(),
+        },
+        _ => convert(node),
+    }
+}
+
+fn convert_tag<MSG, F>(vtag: &mut VTag<MSG>, mut child: F) -> TestElement
+where
+    F: FnMut(usize, &mut VNode<MSG>) -> TestNode,
+{
+    let mut classes = vtag.classes.iter().cloned().collect::<Vec<_>>();
+    classes.sort();
+    TestElement {
+        tag: vtag.tag().to_owned(),
+        classes,
+        attributes: vtag.attributes.iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect(),
+        value: vtag.value.clone(),
+        kind: vtag.kind.clone(),
+        checked: vtag.checked,
+        listeners: vtag.listeners.iter().map(|listener| listener.kind()).collect(),
+        children: vtag.childs.iter_mut().enumerate().map(|(index, node)| child(index, node)).collect(),
+    }
+}
+
+/// Returns the tag at the path of children. Returns `None` if the path
+/// goes through a nested component, which listeners belong to another loop.
+fn tag_at<'a, MSG>(node: &'a mut VNode<MSG>, path: &[usize]) -> Option<&'a mut VTag<MSG>> {
+    match node_at(node, path) {
+        Some(&mut VNode::VTag { ref mut vtag, .. }) => Some(vtag),
+        _ => None,
+    }
+}
+
+/// Returns the node at the path of children of tags and portals.
+fn node_at<'a, MSG>(node: &'a mut VNode<MSG>, path: &[usize]) -> Option<&'a mut VNode<MSG>> {
+    let (first, rest) = match path.split_first() {
+        Some(split) => split,
+        None => return Some(node),
+    };
+    let child = match *node {
+        VNode::VTag { ref mut vtag, .. } => vtag.childs.get_mut(*first),
+        VNode::VPortal { ref mut vportal, .. } => vportal.childs.get_mut(*first),
+        _ => None,
+    };
+    child.and_then(|child| node_at(child, rest))
+}
+
+/// Returns the rendered node at the path of children of elements and portals.
+fn test_node_at<'a>(node: &'a mut TestNode, path: &[usize]) -> Option<&'a mut TestNode> {
+    let (first, rest) = match path.split_first() {
+        Some(split) => split,
+        None => return Some(node),
+    };
+    let child = match *node {
+        TestNode::Element(ref mut element) => element.children.get_mut(*first),
+        TestNode::Portal { ref mut children, .. } => children.get_mut(*first),
+        _ => None,
+    };
+    child.and_then(|child| test_node_at(child, rest))
+}
+
+/// Renders the view of a nested component which was created by the tree.
+pub(crate) fn component<COMP: Component>(frame: &mut VNode<COMP::Msg>) -> TestNode {
+    TestNode::Component {
+        name: any::type_name::<COMP>(),
+        kind: TypeId::of::<COMP>(),
+        root: Box::new(convert(frame)),
+    }
+}
+
+/// A component which is rendered without a browser. Messages are handled
+/// when a test sends them or calls `flush`.
+pub struct TestRenderer<COMP: Component> {
+    component: COMP,
+    sender: AppSender<COMP::Msg>,
+    lanes: Lanes<COMP::Msg>,
+    root: TestNode,
+    /// The last frame which keeps listeners for simulated events.
+    frame: Option<VNode<COMP::Msg>>,
+    /// Paths of nodes which a sliced render postponed to the next frame.
+    pending: VecDeque<Vec<usize>>,
+    renders: usize,
+    middleware: Vec<Box<Middleware<COMP, COMP::Msg>>>,
+    /// The id of the component in an inspector.
+    #[cfg(feature = "devtools")]
+    id: usize,
+}
+
+impl<COMP: Component> TestRenderer<COMP> {
+    /// Creates the component with properties and renders it.
+    /// Messages which the component sent to itself in `create` are handled too.
+    pub fn new(props: COMP::Properties) -> Self {
+        let (sender, high, low) = AppSender::captured();
+        let lanes = Lanes::new(high, low);
+        let component = COMP::create(props, sender.clone());
+        #[cfg(feature = "devtools")]
+        let id = ::devtools::mounted(any::type_name::<COMP>(), component.inspect());
+        let mut renderer = TestRenderer {
+            component,
+            sender,
+            lanes,
+            root: TestNode::Text(String::new()),
+            frame: None,
+            pending: VecDeque::new(),
+            renders: 0,
+            middleware: Vec::new(),
+            #[cfg(feature = "devtools")]
+            id,
+        };
+        renderer.render();
+        renderer.flush();
+        renderer
+    }
+
+    /// Returns the instance of the component.
+    pub fn component(&self) -> &COMP {
+        &self.component
+    }
+
+    /// Adds a middleware which wraps updates of the component like
+    /// middlewares of an app wrap updates of its model. With middlewares
+    /// a flush without messages renders the component too, because they
+    /// could change it before a batch.
+    pub fn middleware<M>(&mut self, middleware: M)
+    where
+        M: Middleware<COMP, COMP::Msg> + 'static,
+    {
+        self.middleware.push(Box::new(middleware));
+    }
+
+    /// Returns the root node of the last render.
+    pub fn root(&self) -> &TestNode {
+        &self.root
+    }
+
+    /// Returns how many times the component was rendered.
+    pub fn renders(&self) -> usize {
+        self.renders
+    }
+
+    /// Returns a sender of the component's loop. Messages sent with it
+    /// are handled with the next `send` or `flush` call.
+    pub fn sender(&self) -> AppSender<COMP::Msg> {
+        self.sender.clone()
+    }
+
+    /// Sends the message and handles it with all messages which
+    /// the component sent to itself. Returns `true` if it was rendered.
+    pub fn send(&mut self, msg: COMP::Msg) -> ShouldRender {
+        self.sender.send(msg);
+        self.flush()
+    }
+
+    /// Handles queued messages, messages of the high lane first, and renders
+    /// the component once if any update asked for it. Like in a browser,
+    /// messages of the low lane which don't fit in a slice are left for
+    /// the next flush, `MockClock` controls the time of slices.
+    pub fn flush(&mut self) -> ShouldRender {
+        let mut should_render = false;
+        let mut handled = 0;
+        // The span of updates ends before the render
+        {
+            let mut span = None;
+            let batch = self.lanes.batch();
+            // Like a loop which was woken to render a model changed by middlewares
+            let woken = batch.is_empty() && !self.middleware.is_empty();
+            middleware::before_batch(&mut self.middleware, &mut self.component);
+            for msg in batch {
+                if span.is_none() {
+                    span = Some(::trace::span("update", any::type_name::<COMP>()));
+                }
+                handled += 1;
+                if handled > MAX_MESSAGES {
+                    panic!("component sends messages to itself without an end");
+                }
+                middleware::dispatch(&mut self.middleware, &mut self.component, msg, &mut |component, msg| {
+                    should_render |= component.update(msg);
+                });
+            }
+            should_render |= woken;
+        }
+        if !self.middleware.is_empty() {
+            should_render &= middleware::should_render(&mut self.middleware, &self.component);
+        }
+        if should_render {
+            self.render();
+        }
+        #[cfg(feature = "devtools")]
+        {
+            if handled > 0 || should_render {
+                ::devtools::updated(self.id, handled, should_render, self.component.inspect());
+            }
+        }
+        should_render
+    }
+
+    /// Runs an animation frame of a sliced render: creates postponed nodes
+    /// in order until the budget of the frame is spent. Returns `true` if
+    /// nodes are left for the next frame.
+    pub fn run_frame(&mut self) -> bool {
+        let _slice = slicing::begin();
+        if let Some(ref mut frame) = self.frame {
+            while let Some(mut path) = self.pending.pop_front() {
+                if let Some(node) = node_at(frame, &path) {
+                    let created = convert_sliced(node, None, &mut path, &mut self.pending);
+                    if let Some(placeholder) = test_node_at(&mut self.root, &path) {
+                        *placeholder = created;
+                    }
+                }
+                if slicing::expired() {
+                    break;
+                }
+            }
+        }
+        !self.pending.is_empty()
+    }
+
+    /// Passes new properties to the component like a parent does.
+    /// Returns `true` if it was rendered.
+    pub fn change(&mut self, props: COMP::Properties) -> ShouldRender {
+        let should_render = self.component.change(props);
+        if should_render {
+            self.render();
+        }
+        self.flush();
+        should_render
+    }
+
+    /// Simulates the event of the `kind`, like `onclick`, on the first element
+    /// which matches the selector. Listeners get a clone of the `data`, custom
+    /// listeners get `detail` as a `Value`. The event bubbles up to parent
+    /// elements and queued messages are handled then. Returns `true` if the
+    /// component was rendered. Panics if there is no such element.
+    pub fn simulate<T: Any>(&mut self, selector: &str, kind: &str, data: T) -> ShouldRender {
+        let mut path = Vec::new();
+        {
+            let target = self.root.find(selector)
+                .unwrap_or_else(|| panic!("no element matches the selector: {}", selector));
+            self.root.path_to(target, &mut path);
+        }
+        let bubbles = kind.starts_with("on") && !NOT_BUBBLING.contains(&kind);
+        let mut messages = Vec::new();
+        if let Some(ref mut frame) = self.frame {
+            for depth in (0..path.len() + 1).rev() {
+                let mut stopped = false;
+                if let Some(vtag) = tag_at(frame, &path[..depth]) {
+                    for listener in vtag.listeners.iter_mut().filter(|listener| listener.kind() == kind) {
+                        match listener.simulate(&data) {
+                            Some(simulated) => {
+                                stopped |= simulated.stop_propagation;
+                                messages.push(simulated.msg);
+                            }
+                            None => {
+                                warn!("listener of {} doesn't accept data of the simulated event", kind);
+                            }
+                        }
+                    }
+                }
+                if stopped || !bubbles {
+                    break;
+                }
+            }
+        }
+        self.sender.send_all(messages);
+        self.flush()
+    }
+
+    /// Clicks the element with the left button.
+    pub fn click(&mut self, selector: &str) -> ShouldRender {
+        self.simulate(selector, "onclick", MouseData::default())
+    }
+
+    /// Sets the value of an input like a user changed it.
+    pub fn input(&mut self, selector: &str, value: &str) -> ShouldRender {
+        self.simulate(selector, "oninput", InputData { value: value.to_owned() })
+    }
+
+    /// Types the text into an input key by key: every character is pressed
+    /// and appended to the value, which is rendered before the next one.
+    pub fn type_text(&mut self, selector: &str, text: &str) -> ShouldRender {
+        let mut value = self.root.find(selector)
+            .and_then(TestNode::element)
+            .and_then(|element| element.value.clone())
+            .unwrap_or_default();
+        let mut should_render = false;
+        for c in text.chars() {
+            let key = c.to_string();
+            should_render |= self.key_down(selector, &key);
+            should_render |= self.key_press(selector, &key);
+            value.push(c);
+            should_render |= self.input(selector, &value);
+            should_render |= self.key_up(selector, &key);
+        }
+        should_render
+    }
+
+    /// Submits the form.
+    pub fn submit(&mut self, selector: &str) -> ShouldRender {
+        self.simulate(selector, "onsubmit", ())
+    }
+
+    /// Presses the key down, like `Enter` or `a`.
+    pub fn key_down(&mut self, selector: &str, key: &str) -> ShouldRender {
+        self.simulate(selector, "onkeydown", key_data(key))
+    }
+
+    /// Fires a key press of the key which produces a character.
+    pub fn key_press(&mut self, selector: &str, key: &str) -> ShouldRender {
+        self.simulate(selector, "onkeypress", key_data(key))
+    }
+
+    /// Releases the key.
+    pub fn key_up(&mut self, selector: &str, key: &str) -> ShouldRender {
+        self.simulate(selector, "onkeyup", key_data(key))
+    }
+
+    fn render(&mut self) {
+        let _span = ::trace::span("view", any::type_name::<COMP>());
+        let mut frame = {
+            #[cfg(feature = "profiler")]
+            let _measure = ::profiler::measure(any::type_name::<COMP>(), ::profiler::Phase::View);
+            #[cfg(feature = "strict")]
+            let _viewing = ::strict::viewing(any::type_name::<COMP>());
+            VNode::from(self.component.view())
+        };
+        #[cfg(feature = "strict")]
+        ::strict::check(any::type_name::<COMP>(), &frame, || VNode::from(self.component.view()));
+        // A conversion to test nodes is measured as a patch
+        #[cfg(feature = "profiler")]
+        let _measure = ::profiler::measure(any::type_name::<COMP>(), ::profiler::Phase::Patch);
+        // Nodes postponed by the previous render are rendered over
+        self.pending.clear();
+        let _slice = slicing::begin();
+        self.root = convert_sliced(&mut frame, Some(&self.root), &mut Vec::new(), &mut self.pending);
+        self.frame = Some(frame);
+        self.renders += 1;
+    }
+}
+
+/// A variable which makes `assert_snapshot` rewrite golden files.
+pub const UPDATE_VARIABLE: &str = "UPDATE_SNAPSHOTS";
+
+/// Compares the snapshot of the node with the golden file. The file is
+/// created if it doesn't exist, and rewritten if the `UPDATE_SNAPSHOTS`
+/// variable is set. Panics with a diff of lines if the snapshots differ.
+pub fn assert_snapshot<P: AsRef<Path>>(path: P, node: &TestNode) {
+    let path = path.as_ref();
+    let actual = node.to_string();
+    let update = env::var_os(UPDATE_VARIABLE).is_some();
+    match fs::read_to_string(path) {
+        Ok(ref expected) if !update => {
+            if expected.trim_end() != actual.trim_end() {
+                panic!("snapshot {} doesn't match, set {}=1 to update it:\n{}",
+                       path.display(), UPDATE_VARIABLE, diff(expected, &actual));
+            }
+        }
+        _ => {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).expect("can't create a directory of snapshots");
+            }
+            fs::write(path, actual).expect("can't write a snapshot");
+        }
+    }
+}
+
+/// Returns lines which were removed (with `-`) and added (with `+`).
+fn diff(expected: &str, actual: &str) -> String {
+    let old = expected.lines().collect::<Vec<_>>();
+    let new = actual.lines().collect::<Vec<_>>();
+    // Lengths of common subsequences of the tails of both texts
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            out.push_str(&format!("  {}\n", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            out.push_str(&format!("- {}\n", old[i]));
+            i += 1;
+        } else {
+            out.push_str(&format!("+ {}\n", new[j]));
+            j += 1;
+        }
+    }
+    out
+}
+
+/// Removes paths of modules from a type name: `app::List<app::Item>` becomes `List<Item>`.
+fn short_name(name: &str) -> String {
+    let mut out = String::new();
+    let mut segment = String::new();
+    let mut chars = name.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == ':' && chars.peek() == Some(&':') {
+            chars.next();
+            segment.clear();
+        } else if c.is_alphanumeric() || c == '_' {
+            segment.push(c);
+        } else {
+            out.push_str(&segment);
+            segment.clear();
+            out.push(c);
+        }
+    }
+    out.push_str(&segment);
+    out
+}
+
+impl TestNode {
+    fn write(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        let indent = "  ".repeat(depth);
+        match *self {
+            TestNode::Text(ref text) => writeln!(f, "{}{:?}", indent, text),
+            TestNode::Raw { ref tag, ref html } => {
+                writeln!(f, "{}<{}>", indent, tag)?;
+                writeln!(f, "{}  raw {:?}", indent, html)?;
+                writeln!(f, "{}</{}>", indent, tag)
+            }
+            TestNode::Component { name, ref root, .. } => {
+                let name = short_name(name);
+                writeln!(f, "{}<{}>", indent, name)?;
+                root.write(f, depth + 1)?;
+                writeln!(f, "{}</{}>", indent, name)
+            }
+            TestNode::Portal { ref selector, ref children } => {
+                writeln!(f, "{}<portal selector={:?}>", indent, selector)?;
+                for child in children {
+                    child.write(f, depth + 1)?;
+                }
+                writeln!(f, "{}</portal>", indent)
+            }
+            TestNode::Element(ref element) => {
+                let mut attributes = element.attributes.iter()
+                    .map(|(name, value)| (name.as_str(), Some(value.as_str())))
+                    .collect::<Vec<_>>();
+                let classes = element.classes.join(" ");
+                if !classes.is_empty() {
+                    attributes.push(("class", Some(&classes)));
+                }
+                if let Some(ref kind) = element.kind {
+                    attributes.push(("type", Some(kind)));
+                }
+                if let Some(ref value) = element.value {
+                    attributes.push(("value", Some(value)));
+                }
+                if element.checked {
+                    attributes.push(("checked", None));
+                }
+                for listener in &element.listeners {
+                    attributes.push((listener, None));
+                }
+                attributes.sort();
+                write!(f, "{}<{}", indent, element.tag)?;
+                for (name, value) in attributes {
+                    match value {
+                        Some(value) => write!(f, " {}={:?}", name, value)?,
+                        None => write!(f, " {}", name)?,
+                    }
+                }
+                if element.children.is_empty() {
+                    return writeln!(f, " />");
+                }
+                writeln!(f, ">")?;
+                for child in &element.children {
+                    child.write(f, depth + 1)?;
+                }
+                writeln!(f, "{}</{}>", indent, element.tag)
+            }
+        }
+    }
+}
+
+/// Prints the tree as indented HTML-like markup with sorted attributes.
+/// Listeners are printed as attributes without values and texts are quoted.
+impl fmt::Display for TestNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+fn key_data(key: &str) -> KeyData {
+    KeyData {
+        key: key.to_owned(),
+        ..KeyData::default()
+    }
+}
+
+impl<COMP: Component> Drop for TestRenderer<COMP> {
+    fn drop(&mut self) {
+        self.component.destroy();
+        #[cfg(feature = "devtools")]
+        ::devtools::unmounted(self.id);
+    }
+}
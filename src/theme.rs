@@ -0,0 +1,366 @@
+//! This module contains a typed theme of an app. Components read values of
+//! the current theme in `view`, and the same values are written as CSS custom
+//! properties to mount roots of apps, so style sheets use them too:
+//!
+//! ```rust,ignore
+//! theme::add_theme(Theme::light());
+//! theme::add_theme(Theme::dark());
+//! theme::set_theme(if theme::prefers_dark() { "dark" } else { "light" });
+//!
+//! let style = Style::new()
+//!     .set("color", &theme::current().colors.primary)
+//!     .set("padding", theme::var("spacing-unit"));
+//! ```
+//!
+//! Components which listen of the theme with `ThemeService` are rendered
+//! again when it's switched, other components are left as is.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use stdweb::{Reference, Value};
+use stdweb::web::Element;
+use html::AppSender;
+use services::Task;
+
+/// Colors of a theme.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Colors {
+    /// The color of primary actions and links.
+    pub primary: String,
+    /// The color of text on the primary color.
+    pub on_primary: String,
+    /// The color of a page.
+    pub background: String,
+    /// The color of cards, menus and dialogs.
+    pub surface: String,
+    /// The color of text.
+    pub text: String,
+    /// The color of secondary text.
+    pub muted: String,
+    /// The color of borders and dividers.
+    pub border: String,
+    /// The color of errors.
+    pub danger: String,
+}
+
+/// Spacing of a theme.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spacing {
+    /// The base unit in pixels. Gaps are multiples of it.
+    pub unit: u32,
+}
+
+impl Spacing {
+    /// Returns a gap of `steps` units in pixels, like `16px`.
+    pub fn get(&self, steps: u32) -> String {
+        format!("{}px", self.unit * steps)
+    }
+}
+
+/// Typography of a theme.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Typography {
+    /// The family of text.
+    pub font_family: String,
+    /// The family of code.
+    pub mono_family: String,
+    /// The size of text in pixels.
+    pub font_size: u32,
+    /// The height of lines relative to the size.
+    pub line_height: f64,
+}
+
+/// A named set of values of a design.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// The name to switch to the theme, like `dark`.
+    pub name: String,
+    /// Colors of the theme.
+    pub colors: Colors,
+    /// Spacing of the theme.
+    pub spacing: Spacing,
+    /// Typography of the theme.
+    pub typography: Typography,
+}
+
+impl Theme {
+    /// Returns a light theme named `light`.
+    pub fn light() -> Self {
+        Theme {
+            name: "light".into(),
+            colors: Colors {
+                primary: "#1f6feb".into(),
+                on_primary: "#ffffff".into(),
+                background: "#ffffff".into(),
+                surface: "#f6f8fa".into(),
+                text: "#1f2328".into(),
+                muted: "#656d76".into(),
+                border: "#d0d7de".into(),
+                danger: "#cf222e".into(),
+            },
+            spacing: Spacing { unit: 8 },
+            typography: Typography {
+                font_family: "system-ui, sans-serif".into(),
+                mono_family: "ui-monospace, monospace".into(),
+                font_size: 16,
+                line_height: 1.5,
+            },
+        }
+    }
+
+    /// Returns a dark theme named `dark`.
+    pub fn dark() -> Self {
+        Theme {
+            name: "dark".into(),
+            colors: Colors {
+                primary: "#4493f8".into(),
+                on_primary: "#0d1117".into(),
+                background: "#0d1117".into(),
+                surface: "#161b22".into(),
+                text: "#e6edf3".into(),
+                muted: "#8d96a0".into(),
+                border: "#30363d".into(),
+                danger: "#f85149".into(),
+            },
+            ..Theme::light()
+        }
+    }
+
+    /// Returns CSS custom properties of the theme, like `--color-primary`.
+    pub fn variables(&self) -> Vec<(String, String)> {
+        let colors = &self.colors;
+        let typography = &self.typography;
+        vec![
+            ("--color-primary".into(), colors.primary.clone()),
+            ("--color-on-primary".into(), colors.on_primary.clone()),
+            ("--color-background".into(), colors.background.clone()),
+            ("--color-surface".into(), colors.surface.clone()),
+            ("--color-text".into(), colors.text.clone()),
+            ("--color-muted".into(), colors.muted.clone()),
+            ("--color-border".into(), colors.border.clone()),
+            ("--color-danger".into(), colors.danger.clone()),
+            ("--spacing-unit".into(), self.spacing.get(1)),
+            ("--font-family".into(), typography.font_family.clone()),
+            ("--font-mono".into(), typography.mono_family.clone()),
+            ("--font-size".into(), format!("{}px", typography.font_size)),
+            ("--line-height".into(), typography.line_height.to_string()),
+        ]
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::light()
+    }
+}
+
+/// Returns a reference of a custom property of themes for CSS values,
+/// like `var(--color-primary)`.
+pub fn var(name: &str) -> String {
+    format!("var(--{})", name)
+}
+
+type Listener = Rc<Fn(&str)>;
+
+#[derive(Default)]
+struct Context {
+    themes: Vec<Rc<Theme>>,
+    current: Option<Rc<Theme>>,
+    // Increased when the theme is switched, roots keep the version they have
+    version: usize,
+    roots: Vec<(Element, usize)>,
+    listeners: Vec<(usize, Listener)>,
+    next_listener: usize,
+}
+
+thread_local! {
+    static CONTEXT: RefCell<Context> = RefCell::new(Context::default());
+}
+
+/// Adds a theme to switch to by its name. It replaces a theme with the same name.
+pub fn add_theme(theme: Theme) {
+    let replaced = CONTEXT.with(|context| {
+        let mut context = context.borrow_mut();
+        let theme = Rc::new(theme);
+        context.themes.retain(|old| old.name != theme.name);
+        context.themes.push(theme.clone());
+        let replaced = context.current.as_ref().map_or(false, |current| current.name == theme.name);
+        if replaced {
+            context.current = Some(theme);
+        }
+        replaced
+    });
+    if replaced {
+        changed();
+    }
+}
+
+/// Switches to the theme with the name. Returns `false` if it wasn't added.
+pub fn set_theme(name: &str) -> bool {
+    let found = CONTEXT.with(|context| {
+        let mut context = context.borrow_mut();
+        if context.current.as_ref().map_or(false, |current| current.name == name) {
+            return Some(false);
+        }
+        let theme = context.themes.iter().find(|theme| theme.name == name).cloned()?;
+        context.current = Some(theme);
+        Some(true)
+    });
+    match found {
+        Some(switched) => {
+            if switched {
+                changed();
+            }
+            true
+        }
+        None => {
+            warn!("the theme {} isn't added", name);
+            false
+        }
+    }
+}
+
+/// Returns the current theme. It's the light theme until another one is set.
+pub fn current() -> Rc<Theme> {
+    CONTEXT.with(|context| {
+        context.borrow().current.clone().unwrap_or_else(|| Rc::new(Theme::light()))
+    })
+}
+
+/// Returns names of added themes.
+pub fn themes() -> Vec<String> {
+    CONTEXT.with(|context| context.borrow().themes.iter().map(|theme| theme.name.clone()).collect())
+}
+
+/// Checks the user prefers dark colors in a browser.
+pub fn prefers_dark() -> bool {
+    let dark: Value = js! {
+        return !!(window.matchMedia && window.matchMedia("(prefers-color-scheme: dark)").matches);
+    };
+    dark == Value::Bool(true)
+}
+
+fn changed() {
+    let (name, listeners) = CONTEXT.with(|context| {
+        let mut context = context.borrow_mut();
+        context.version += 1;
+        let listeners: Vec<Listener> = context.listeners.iter().map(|(_, listener)| listener.clone()).collect();
+        (context.current.as_ref().map(|theme| theme.name.clone()).unwrap_or_default(), listeners)
+    });
+    for listener in listeners {
+        listener(&name);
+    }
+}
+
+/// Writes custom properties of the theme to the mount root of an app.
+pub(crate) fn register_root(root: &Element) {
+    CONTEXT.with(|context| context.borrow_mut().roots.push((root.clone(), 0)));
+    sync();
+}
+
+pub(crate) fn unregister_root(root: &Element) {
+    let root: &Reference = root.as_ref();
+    CONTEXT.with(|context| {
+        context.borrow_mut().roots.retain(|(element, _)| AsRef::<Reference>::as_ref(element) != root);
+    });
+}
+
+/// Writes custom properties to roots which have properties of a previous theme.
+/// Roots aren't touched until a theme is set.
+pub(crate) fn sync() {
+    CONTEXT.with(|context| {
+        let mut context = context.borrow_mut();
+        let context = &mut *context;
+        let theme = match context.current {
+            Some(ref theme) => theme.clone(),
+            None => return,
+        };
+        for &mut (ref root, ref mut version) in context.roots.iter_mut() {
+            if *version == context.version {
+                continue;
+            }
+            *version = context.version;
+            for (name, value) in theme.variables() {
+                js! { @(no_return)
+                    @{root}.style.setProperty(@{name}, @{value});
+                }
+            }
+            js! { @(no_return)
+                @{root}.setAttribute("data-theme", @{&theme.name});
+            }
+        }
+    });
+}
+
+/// A handle to stop listening of the theme.
+pub struct ThemeHandle(Option<usize>);
+
+impl Task for ThemeHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let id = self.0.take().expect("tried to cancel theme listener twice");
+        CONTEXT.with(|context| {
+            context.borrow_mut().listeners.retain(|&(listener, _)| listener != id);
+        });
+    }
+}
+
+impl Drop for ThemeHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+/// A service to switch themes at runtime and to listen of switches.
+pub struct ThemeService<MSG> {
+    sender: AppSender<MSG>,
+}
+
+impl<MSG: 'static> ThemeService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self { sender }
+    }
+
+    /// Sends a message from the `converter` with the name of a theme every time
+    /// the theme is switched. Return `true` from `update` to render with the new values.
+    pub fn listen<F>(&mut self, converter: F) -> ThemeHandle
+    where
+        F: Fn(String) -> MSG + 'static,
+    {
+        let tx = self.sender.clone();
+        let listener = move |name: &str| {
+            let msg = converter(name.to_owned());
+            tx.clone().send(msg);
+        };
+        let id = CONTEXT.with(|context| {
+            let mut context = context.borrow_mut();
+            let id = context.next_listener;
+            context.next_listener += 1;
+            context.listeners.push((id, Rc::new(listener)));
+            id
+        });
+        ThemeHandle(Some(id))
+    }
+
+    /// Returns the current theme.
+    pub fn current(&self) -> Rc<Theme> {
+        current()
+    }
+
+    /// Switches to the theme with the name for all apps.
+    pub fn set_theme(&mut self, name: &str) -> bool {
+        set_theme(name)
+    }
+
+    /// Switches between the `light` and `dark` themes.
+    pub fn toggle_dark(&mut self) {
+        let name = if current().name == "dark" { "light" } else { "dark" };
+        set_theme(name);
+    }
+}
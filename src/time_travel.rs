@@ -0,0 +1,262 @@
+//! This module contains a middleware which records messages of an app
+//! with snapshots of its model, so a developer could step back and forth
+//! through the history and replay messages to find how a view got broken.
+//!
+//! ```rust,ignore
+//! let mut app = App::new();
+//! let debugger = TimeTravel::new(app.sender(), 100);
+//! app.middleware(debugger.clone());
+//! app.mount(context, model, update, view);
+//!
+//! // Later, from a debug panel or a console binding:
+//! debugger.back();
+//! debugger.replay();
+//! ```
+//!
+//! The model has to be serializable and messages have to be cloneable.
+//! Messages sent while a past state is shown are dropped, call `resume`
+//! to return to the latest state.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::rc::Rc;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{self, Map, Value};
+use html::AppSender;
+use middleware::Middleware;
+
+/// A recorded state of an app.
+struct Entry<MSG> {
+    /// A message which led to the state. It's `None` for the initial state.
+    msg: Option<MSG>,
+    /// A snapshot of the model after the message.
+    snapshot: Value,
+}
+
+/// A change of the model requested by a debugger.
+enum Restore {
+    /// Shows the state with the index.
+    Show(usize),
+    /// Restores the state with the index and forgets later states,
+    /// because their messages will be sent again.
+    Replay(usize),
+}
+
+struct History<MSG> {
+    entries: VecDeque<Entry<MSG>>,
+    capacity: usize,
+    /// An index of the shown entry if it's not the latest one.
+    cursor: Option<usize>,
+    restore: Option<Restore>,
+}
+
+impl<MSG> History<MSG> {
+    fn shown(&self) -> Option<usize> {
+        match self.cursor {
+            Some(index) => Some(index),
+            None if self.entries.is_empty() => None,
+            None => Some(self.entries.len() - 1),
+        }
+    }
+
+    fn push(&mut self, msg: Option<MSG>, snapshot: Value) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(Entry { msg, snapshot });
+    }
+}
+
+/// A time-travel debugger of an app. It's a middleware and a handle
+/// to control it at once, clones share the history.
+pub struct TimeTravel<MSG> {
+    sender: AppSender<MSG>,
+    history: Rc<RefCell<History<MSG>>>,
+}
+
+impl<MSG> Clone for TimeTravel<MSG> {
+    fn clone(&self) -> Self {
+        TimeTravel {
+            sender: self.sender.clone(),
+            history: self.history.clone(),
+        }
+    }
+}
+
+impl<MSG: Clone + 'static> TimeTravel<MSG> {
+    /// Creates a debugger which keeps the `capacity` of last states
+    /// of the app connected by the `sender`.
+    pub fn new(sender: AppSender<MSG>, capacity: usize) -> Self {
+        let history = History {
+            entries: VecDeque::new(),
+            capacity: capacity.max(1),
+            cursor: None,
+            restore: None,
+        };
+        TimeTravel {
+            sender,
+            history: Rc::new(RefCell::new(history)),
+        }
+    }
+
+    /// Returns the count of recorded states.
+    pub fn len(&self) -> usize {
+        self.history.borrow().entries.len()
+    }
+
+    /// Returns `true` if nothing was recorded yet.
+    pub fn is_empty(&self) -> bool {
+        self.history.borrow().entries.is_empty()
+    }
+
+    /// Returns the index of the shown state.
+    pub fn position(&self) -> Option<usize> {
+        self.history.borrow().shown()
+    }
+
+    /// Returns `true` if a past state is shown and new messages are dropped.
+    pub fn is_paused(&self) -> bool {
+        self.history.borrow().cursor.is_some()
+    }
+
+    /// Returns recorded messages. The first state has no message.
+    pub fn messages(&self) -> Vec<Option<MSG>> {
+        self.history.borrow().entries.iter().map(|entry| entry.msg.clone()).collect()
+    }
+
+    /// Returns the snapshot of the state with the index.
+    pub fn snapshot(&self, index: usize) -> Option<Value> {
+        self.history.borrow().entries.get(index).map(|entry| entry.snapshot.clone())
+    }
+
+    /// Shows the state with the index.
+    pub fn jump(&self, index: usize) {
+        {
+            let mut history = self.history.borrow_mut();
+            if index >= history.entries.len() {
+                warn!("no state with index {} in the history", index);
+                return;
+            }
+            let last = history.entries.len() - 1;
+            history.cursor = if index == last { None } else { Some(index) };
+            history.restore = Some(Restore::Show(index));
+        }
+        self.sender.wake();
+    }
+
+    /// Shows the previous state.
+    pub fn back(&self) {
+        let shown = self.position();
+        if let Some(index) = shown {
+            if index > 0 {
+                self.jump(index - 1);
+            }
+        }
+    }
+
+    /// Shows the next state.
+    pub fn forward(&self) {
+        let shown = self.position();
+        if let Some(index) = shown {
+            self.jump(index + 1);
+        }
+    }
+
+    /// Shows the latest state and accepts new messages again.
+    pub fn resume(&self) {
+        let len = self.len();
+        if len > 0 {
+            self.jump(len - 1);
+        }
+    }
+
+    /// Restores the shown state and sends the messages recorded after it
+    /// again, so the way to the latest state is repeated with the current code.
+    pub fn replay(&self) {
+        let messages = {
+            let mut history = self.history.borrow_mut();
+            let index = match history.shown() {
+                Some(index) => index,
+                None => return,
+            };
+            let messages: Vec<MSG> = history.entries.iter()
+                .skip(index + 1)
+                .filter_map(|entry| entry.msg.clone())
+                .collect();
+            history.cursor = None;
+            history.restore = Some(Restore::Replay(index));
+            messages
+        };
+        self.sender.wake();
+        self.sender.clone().send_all(messages);
+    }
+}
+
+impl<MSG: Debug> TimeTravel<MSG> {
+    /// Exports the history as JSON with messages formatted by `Debug`.
+    pub fn export(&self) -> String {
+        let history = self.history.borrow();
+        let entries: Vec<Value> = history.entries.iter().map(|entry| {
+            let mut object = Map::new();
+            let msg = entry.msg.as_ref().map(|msg| format!("{:?}", msg));
+            object.insert("msg".into(), msg.map(Value::String).unwrap_or(Value::Null));
+            object.insert("snapshot".into(), entry.snapshot.clone());
+            Value::Object(object)
+        }).collect();
+        Value::Array(entries).to_string()
+    }
+}
+
+fn snapshot<MOD: Serialize>(model: &MOD) -> Option<Value> {
+    match serde_json::to_value(model) {
+        Ok(snapshot) => Some(snapshot),
+        Err(err) => {
+            warn!("can't take a snapshot of the model: {}", err);
+            None
+        }
+    }
+}
+
+impl<MOD, MSG> Middleware<MOD, MSG> for TimeTravel<MSG>
+where
+    MOD: Serialize + DeserializeOwned,
+    MSG: Clone,
+{
+    fn before_batch(&mut self, model: &mut MOD) {
+        let mut history = self.history.borrow_mut();
+        if history.entries.is_empty() {
+            if let Some(snapshot) = snapshot(model) {
+                history.push(None, snapshot);
+            }
+        }
+        let index = match history.restore.take() {
+            Some(Restore::Show(index)) => index,
+            Some(Restore::Replay(index)) => {
+                history.entries.truncate(index + 1);
+                index
+            }
+            None => return,
+        };
+        let restored = history.entries.get(index)
+            .map(|entry| serde_json::from_value(entry.snapshot.clone()));
+        match restored {
+            Some(Ok(restored)) => *model = restored,
+            Some(Err(err)) => warn!("can't restore a snapshot of the model: {}", err),
+            None => {}
+        }
+    }
+
+    fn update(&mut self, model: &mut MOD, msg: MSG, next: &mut FnMut(&mut MOD, MSG)) {
+        if self.history.borrow().cursor.is_some() {
+            debug!("a message dropped while a past state is shown");
+            return;
+        }
+        let recorded = msg.clone();
+        next(model, msg);
+        if let Some(snapshot) = snapshot(model) {
+            self.history.borrow_mut().push(Some(recorded), snapshot);
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! This module contains a queue of in-app notifications. Any component pushes
+//! toasts and a `Toaster` component which is rendered once in an app shows
+//! them and removes them when their time is out:
+//!
+//! ```rust,ignore
+//! toast::push(Toast::success("The file is saved"));
+//! toast::push(Toast::error("Can't save the file")
+//!     .action("Retry", self.link.callback(|_| Msg::Save)));
+//!
+//! html! { <Toaster: max=3,/> }
+//! ```
+//!
+//! Toasts are kept until they're dismissed if there is no `Toaster`.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+use callback::Callback;
+use html::AppSender;
+use services::Task;
+
+/// How important a toast is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    /// A neutral note.
+    Info,
+    /// A finished action.
+    Success,
+    /// Something which needs attention.
+    Warning,
+    /// A failed action. Errors are kept until they're dismissed.
+    Error,
+}
+
+impl Level {
+    /// Returns the name of the level, like `warning`.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Level::Info => "info",
+            Level::Success => "success",
+            Level::Warning => "warning",
+            Level::Error => "error",
+        }
+    }
+}
+
+/// A button of a toast.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Action {
+    /// The text of the button.
+    pub label: String,
+    /// Called when the button is clicked. The toast is dismissed then.
+    pub callback: Callback<()>,
+}
+
+/// A notification.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Toast {
+    /// How important the toast is.
+    pub level: Level,
+    /// The text of the toast.
+    pub message: String,
+    /// The toast is dismissed when the time is out. `None` keeps it.
+    pub timeout: Option<Duration>,
+    /// An optional button, like `Undo`.
+    pub action: Option<Action>,
+}
+
+/// How long toasts are shown by default.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+impl Toast {
+    /// Creates a toast of the level. Errors don't have a timeout.
+    pub fn new<T: Into<String>>(level: Level, message: T) -> Self {
+        let timeout = if level == Level::Error { None } else { Some(DEFAULT_TIMEOUT) };
+        Toast {
+            level,
+            message: message.into(),
+            timeout,
+            action: None,
+        }
+    }
+
+    /// Creates a toast of the `Info` level.
+    pub fn info<T: Into<String>>(message: T) -> Self {
+        Toast::new(Level::Info, message)
+    }
+
+    /// Creates a toast of the `Success` level.
+    pub fn success<T: Into<String>>(message: T) -> Self {
+        Toast::new(Level::Success, message)
+    }
+
+    /// Creates a toast of the `Warning` level.
+    pub fn warning<T: Into<String>>(message: T) -> Self {
+        Toast::new(Level::Warning, message)
+    }
+
+    /// Creates a toast of the `Error` level.
+    pub fn error<T: Into<String>>(message: T) -> Self {
+        Toast::new(Level::Error, message)
+    }
+
+    /// Dismisses the toast after the `timeout`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Keeps the toast until it's dismissed.
+    pub fn sticky(mut self) -> Self {
+        self.timeout = None;
+        self
+    }
+
+    /// Adds a button which calls the `callback`.
+    pub fn action<T: Into<String>>(mut self, label: T, callback: Callback<()>) -> Self {
+        self.action = Some(Action {
+            label: label.into(),
+            callback,
+        });
+        self
+    }
+}
+
+/// An id of a pushed toast.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ToastId(usize);
+
+type Listener = Rc<Fn()>;
+
+#[derive(Default)]
+struct Queue {
+    toasts: Vec<(ToastId, Rc<Toast>)>,
+    next_id: usize,
+    listeners: Vec<(usize, Listener)>,
+    next_listener: usize,
+}
+
+thread_local! {
+    static QUEUE: RefCell<Queue> = RefCell::new(Queue::default());
+}
+
+/// Adds a toast to the end of the queue.
+pub fn push(toast: Toast) -> ToastId {
+    let id = QUEUE.with(|queue| {
+        let mut queue = queue.borrow_mut();
+        let id = ToastId(queue.next_id);
+        queue.next_id += 1;
+        queue.toasts.push((id, Rc::new(toast)));
+        id
+    });
+    changed();
+    id
+}
+
+/// Removes a toast. Returns `false` if it was dismissed before.
+pub fn dismiss(id: ToastId) -> bool {
+    let removed = QUEUE.with(|queue| {
+        let mut queue = queue.borrow_mut();
+        let count = queue.toasts.len();
+        queue.toasts.retain(|&(toast, _)| toast != id);
+        queue.toasts.len() != count
+    });
+    if removed {
+        changed();
+    }
+    removed
+}
+
+/// Removes all toasts.
+pub fn clear() {
+    let removed = QUEUE.with(|queue| {
+        let mut queue = queue.borrow_mut();
+        let removed = !queue.toasts.is_empty();
+        queue.toasts.clear();
+        removed
+    });
+    if removed {
+        changed();
+    }
+}
+
+/// Returns toasts in the order they were pushed.
+pub fn toasts() -> Vec<(ToastId, Rc<Toast>)> {
+    QUEUE.with(|queue| queue.borrow().toasts.clone())
+}
+
+fn changed() {
+    let listeners: Vec<Listener> = QUEUE.with(|queue| {
+        queue.borrow().listeners.iter().map(|(_, listener)| listener.clone()).collect()
+    });
+    for listener in listeners {
+        listener();
+    }
+}
+
+/// A handle to stop listening of the queue.
+pub struct ToastHandle(Option<usize>);
+
+impl Task for ToastHandle {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        let id = self.0.take().expect("tried to cancel toast listener twice");
+        QUEUE.with(|queue| {
+            queue.borrow_mut().listeners.retain(|&(listener, _)| listener != id);
+        });
+    }
+}
+
+impl Drop for ToastHandle {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+/// A service to listen of changes of the queue of toasts.
+pub struct ToastService<MSG> {
+    sender: AppSender<MSG>,
+}
+
+impl<MSG: 'static> ToastService<MSG> {
+    /// Creates a new service instance connected to `App` by provided `sender`.
+    pub fn new(sender: AppSender<MSG>) -> Self {
+        Self { sender }
+    }
+
+    /// Sends a message from the `converter` every time a toast is pushed or dismissed.
+    pub fn listen<F>(&mut self, converter: F) -> ToastHandle
+    where
+        F: Fn() -> MSG + 'static,
+    {
+        let tx = self.sender.clone();
+        let listener = move || {
+            let msg = converter();
+            tx.clone().send(msg);
+        };
+        let id = QUEUE.with(|queue| {
+            let mut queue = queue.borrow_mut();
+            let id = queue.next_listener;
+            queue.next_listener += 1;
+            queue.listeners.push((id, Rc::new(listener)));
+            id
+        });
+        ToastHandle(Some(id))
+    }
+}
@@ -0,0 +1,27 @@
+//! Spans of phases of a loop. They're recorded by `tracing` with
+//! the `tracing` feature and cost nothing without it. Phases are recorded
+//! for long tasks too while a listener of them is active.
+
+use services::long_task;
+
+/// A guard which closes a span when it's dropped.
+#[cfg(feature = "tracing")]
+pub(crate) type Span = ::tracing::span::EnteredSpan;
+
+/// A guard which closes a span when it's dropped.
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct Span;
+
+/// Enters a span of the `phase` (`update`, `view` or `diff`) of an app or a component.
+#[cfg(feature = "tracing")]
+pub(crate) fn span(phase: &'static str, name: &'static str) -> Span {
+    long_task::record(phase, name);
+    ::tracing::debug_span!(target: "yew", "loop", phase, name).entered()
+}
+
+/// Enters a span of the `phase` (`update`, `view` or `diff`) of an app or a component.
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn span(phase: &'static str, name: &'static str) -> Span {
+    long_task::record(phase, name);
+    Span
+}
@@ -0,0 +1,309 @@
+//! This module contains TypeScript declarations of types which cross the JS
+//! boundary: properties and events of custom elements and messages of
+//! `postMessage` protocols. It's enabled with the `typescript` feature.
+//!
+//! Wrap a type with `typescript!` to implement `TypeScript` for it. Types
+//! are declared like `serde_json` writes them by default, but `serde`
+//! attributes like `rename` aren't followed:
+//!
+//! ```rust,ignore
+//! typescript! {
+//!     #[derive(Serialize, Deserialize)]
+//!     pub enum WidgetMsg {
+//!         Ready,
+//!         Resize { width: u32, height: u32 },
+//!         Select(Option<String>),
+//!     }
+//! }
+//! ```
+//!
+//! Collect declarations in a test or a build script and write them to a file
+//! which the JS host imports, so a changed type breaks the build of the host:
+//!
+//! ```rust,ignore
+//! let mut declarations = Declarations::new();
+//! declarations
+//!     .add::<WidgetMsg>()
+//!     .element::<Counter>("yew-counter", &[("counter-change", typescript::name::<u32>())]);
+//! declarations.write("js/widgets.d.ts").unwrap();
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::Path;
+use custom_element::CustomElement;
+
+/// A type which has a TypeScript declaration.
+pub trait TypeScript {
+    /// Returns the type as it's written in other declarations,
+    /// like `number` or `Settings`.
+    fn typescript() -> String;
+
+    /// Returns the declaration of a named type, like `export interface Settings { .. }`.
+    /// Builtin types don't have it.
+    fn declaration() -> Option<String> {
+        None
+    }
+}
+
+/// Returns the TypeScript name of the type.
+pub fn name<T: TypeScript>() -> String {
+    T::typescript()
+}
+
+macro_rules! impl_typescript {
+    ($name:expr => $($ty:ty),*) => {$(
+        impl TypeScript for $ty {
+            fn typescript() -> String {
+                $name.to_owned()
+            }
+        }
+    )*};
+}
+
+impl_typescript!("boolean" => bool);
+impl_typescript!("number" => u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64);
+impl_typescript!("string" => String, char);
+impl_typescript!("null" => ());
+
+impl TypeScript for &str {
+    fn typescript() -> String {
+        "string".to_owned()
+    }
+}
+
+impl<T: TypeScript> TypeScript for &T {
+    fn typescript() -> String {
+        T::typescript()
+    }
+}
+
+impl<T: TypeScript> TypeScript for Box<T> {
+    fn typescript() -> String {
+        T::typescript()
+    }
+}
+
+impl<T: TypeScript> TypeScript for Option<T> {
+    fn typescript() -> String {
+        format!("{} | null", T::typescript())
+    }
+}
+
+impl<T: TypeScript> TypeScript for Vec<T> {
+    fn typescript() -> String {
+        array(&T::typescript())
+    }
+}
+
+impl<T: TypeScript> TypeScript for &[T] {
+    fn typescript() -> String {
+        array(&T::typescript())
+    }
+}
+
+impl<T: TypeScript> TypeScript for HashMap<String, T> {
+    fn typescript() -> String {
+        format!("Record<string, {}>", T::typescript())
+    }
+}
+
+impl<T: TypeScript> TypeScript for BTreeMap<String, T> {
+    fn typescript() -> String {
+        format!("Record<string, {}>", T::typescript())
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: TypeScript),+> TypeScript for ($($name,)+) {
+            fn typescript() -> String {
+                let items: Vec<String> = vec![$($name::typescript()),+];
+                format!("[{}]", items.join(", "))
+            }
+        }
+    };
+}
+
+impl_tuple!(A);
+impl_tuple!(A, B);
+impl_tuple!(A, B, C);
+impl_tuple!(A, B, C, D);
+
+/// Returns an array type, with parens around unions: `(string | null)[]`.
+fn array(item: &str) -> String {
+    if item.contains(' ') {
+        format!("({})[]", item)
+    } else {
+        format!("{}[]", item)
+    }
+}
+
+/// Returns a line of a field of an interface. Optional values are optional fields.
+#[doc(hidden)]
+pub fn field(name: &str, ty: &str) -> String {
+    match ty.strip_suffix(" | null") {
+        Some(inner) if !inner.contains(" | ") => format!("  {}?: {} | null;\n", name, inner),
+        _ => format!("  {}: {};\n", name, ty),
+    }
+}
+
+/// Returns an object type of fields in one line: `{ x: number; y: number }`.
+#[doc(hidden)]
+pub fn object(fields: &[(&str, String)]) -> String {
+    let fields: Vec<String> = fields.iter().map(|(name, ty)| format!("{}: {}", name, ty)).collect();
+    format!("{{ {} }}", fields.join("; "))
+}
+
+/// Declares a struct or an enum and implements `TypeScript` for it.
+/// Fields and variants have to use types which implement `TypeScript`.
+/// Generic types aren't supported.
+#[macro_export]
+macro_rules! typescript {
+    ($(#[$attr:meta])* $vis:vis struct $name:ident {
+        $($(#[$fattr:meta])* $fvis:vis $field:ident : $ty:ty),* $(,)*
+    }) => {
+        $(#[$attr])* $vis struct $name {
+            $($(#[$fattr])* $fvis $field : $ty),*
+        }
+
+        impl $crate::typescript::TypeScript for $name {
+            fn typescript() -> String {
+                stringify!($name).to_owned()
+            }
+
+            fn declaration() -> Option<String> {
+                let mut fields = String::new();
+                $( fields.push_str(&$crate::typescript::field(
+                    stringify!($field),
+                    &<$ty as $crate::typescript::TypeScript>::typescript(),
+                )); )*
+                Some(format!("export interface {} {{\n{}}}\n", stringify!($name), fields))
+            }
+        }
+    };
+    ($(#[$attr:meta])* $vis:vis enum $name:ident { $($body:tt)* }) => {
+        $(#[$attr])* $vis enum $name {
+            $($body)*
+        }
+
+        impl $crate::typescript::TypeScript for $name {
+            fn typescript() -> String {
+                stringify!($name).to_owned()
+            }
+
+            fn declaration() -> Option<String> {
+                let mut variants: Vec<String> = Vec::new();
+                typescript!(@variants variants $($body)* ,);
+                Some(format!("export type {} =\n  | {};\n", stringify!($name), variants.join("\n  | ")))
+            }
+        }
+    };
+    (@variants $out:ident) => {};
+    (@variants $out:ident , $($rest:tt)*) => {
+        typescript!(@variants $out $($rest)*);
+    };
+    (@variants $out:ident $(#[$attr:meta])* $variant:ident ( $ty:ty ) , $($rest:tt)*) => {
+        $out.push(format!("{{ {}: {} }}", stringify!($variant),
+            <$ty as $crate::typescript::TypeScript>::typescript()));
+        typescript!(@variants $out $($rest)*);
+    };
+    (@variants $out:ident $(#[$attr:meta])* $variant:ident ( $($ty:ty),+ ) , $($rest:tt)*) => {
+        $out.push(format!("{{ {}: {} }}", stringify!($variant),
+            <($($ty,)+) as $crate::typescript::TypeScript>::typescript()));
+        typescript!(@variants $out $($rest)*);
+    };
+    (@variants $out:ident $(#[$attr:meta])* $variant:ident {
+        $($(#[$fattr:meta])* $field:ident : $ty:ty),* $(,)*
+    } , $($rest:tt)*) => {
+        $out.push(format!("{{ {}: {} }}", stringify!($variant), $crate::typescript::object(&[
+            $((stringify!($field), <$ty as $crate::typescript::TypeScript>::typescript())),*
+        ])));
+        typescript!(@variants $out $($rest)*);
+    };
+    (@variants $out:ident $(#[$attr:meta])* $variant:ident , $($rest:tt)*) => {
+        $out.push(format!("\"{}\"", stringify!($variant)));
+        typescript!(@variants $out $($rest)*);
+    };
+}
+
+/// A file of TypeScript declarations.
+#[derive(Debug, Default)]
+pub struct Declarations {
+    types: Vec<String>,
+    elements: Vec<(String, String)>,
+    out: String,
+}
+
+impl Declarations {
+    /// Creates an empty file.
+    pub fn new() -> Self {
+        Declarations::default()
+    }
+
+    /// Adds the declaration of a named type. Types are declared once.
+    pub fn add<T: TypeScript>(&mut self) -> &mut Self {
+        let name = T::typescript();
+        if let Some(declaration) = T::declaration() {
+            if !self.types.contains(&name) {
+                self.types.push(name);
+                self.out.push_str(&declaration);
+                self.out.push('\n');
+            }
+        }
+        self
+    }
+
+    /// Adds an interface of a custom element which was registered with
+    /// `custom_element::define`. Observed attributes are optional strings and
+    /// `events` are names of custom events with types of their `detail`.
+    pub fn element<COMP: CustomElement>(&mut self, tag: &str, events: &[(&str, String)]) -> &mut Self {
+        let interface: String = tag.split('-')
+            .flat_map(|part| {
+                let mut chars = part.chars();
+                chars.next().into_iter().flat_map(char::to_uppercase).chain(chars)
+            })
+            .collect::<String>() + "Element";
+        let mut attributes = String::new();
+        for attribute in COMP::observed_attributes() {
+            attributes.push_str(&format!("  \"{}\"?: string;\n", attribute));
+        }
+        self.out.push_str(&format!("export interface {}Attributes {{\n{}}}\n\n", interface, attributes));
+        let mut listeners = String::new();
+        for &(kind, ref detail) in events {
+            listeners.push_str(&format!(
+                "  addEventListener(type: \"{}\", listener: (event: CustomEvent<{}>) => void, options?: boolean | AddEventListenerOptions): void;\n",
+                kind, detail,
+            ));
+        }
+        listeners.push_str("  addEventListener(type: string, listener: EventListenerOrEventListenerObject, options?: boolean | AddEventListenerOptions): void;\n");
+        self.out.push_str(&format!("export interface {} extends HTMLElement {{\n{}}}\n\n", interface, listeners));
+        self.elements.push((tag.to_owned(), interface));
+        self
+    }
+
+    /// Returns the text of the file.
+    pub fn to_text(&self) -> String {
+        let mut text = String::from("// Generated by yew, don't edit it.\n\n");
+        text.push_str(&self.out);
+        if !self.elements.is_empty() {
+            text.push_str("declare global {\n  interface HTMLElementTagNameMap {\n");
+            for (tag, interface) in &self.elements {
+                text.push_str(&format!("    \"{}\": {};\n", tag, interface));
+            }
+            text.push_str("  }\n}\n");
+        }
+        text
+    }
+
+    /// Writes the file if its text changed, so watchers of the host
+    /// don't rebuild it for nothing.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let text = self.to_text();
+        if fs::read_to_string(path.as_ref()).ok().as_ref() == Some(&text) {
+            return Ok(());
+        }
+        fs::write(path, text)
+    }
+}
@@ -0,0 +1,228 @@
+//! This module contains `History`, a wrapper of a part of a model which
+//! records its changes, so they could be undone and redone:
+//!
+//! ```rust,ignore
+//! struct Model {
+//!     drawing: History<Drawing>,
+//! }
+//!
+//! fn update(&mut self, msg: Msg) -> ShouldRender {
+//!     match msg {
+//!         Msg::Clear => self.drawing.set(Drawing::default()),
+//!         Msg::Move(id, to) => self.drawing.update(|drawing| drawing.shapes[id].position = to),
+//!         Msg::History(msg) => return self.drawing.handle(msg),
+//!         Msg::Key(key) => match UndoMsg::from_key(&key) {
+//!             Some(msg) => return self.drawing.handle(msg),
+//!             None => return false,
+//!         },
+//!     }
+//!     true
+//! }
+//! ```
+//!
+//! Changes are recorded as snapshots of the previous value or as edits which
+//! know how to revert themselves, which is cheaper for large values.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::mem;
+use std::ops::Deref;
+use component::ShouldRender;
+use html::KeyData;
+
+/// A change which could be applied and reverted, like an inserted line.
+pub trait Edit<T> {
+    /// Makes the change.
+    fn apply(&self, target: &mut T);
+    /// Undoes the change which was made by `apply`.
+    fn revert(&self, target: &mut T);
+}
+
+/// Messages to move through a history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UndoMsg {
+    /// Undoes the last change.
+    Undo,
+    /// Makes the last undone change again.
+    Redo,
+}
+
+impl UndoMsg {
+    /// Returns a message for the usual shortcuts: Ctrl+Z, Ctrl+Shift+Z and Ctrl+Y,
+    /// or the same with Cmd on macOS.
+    pub fn from_key(key: &KeyData) -> Option<Self> {
+        let modifiers = &key.modifiers;
+        if !(modifiers.ctrl || modifiers.meta) || modifiers.alt {
+            return None;
+        }
+        match (key.key.to_lowercase().as_str(), modifiers.shift) {
+            ("z", false) => Some(UndoMsg::Undo),
+            ("z", true) | ("y", false) => Some(UndoMsg::Redo),
+            _ => None,
+        }
+    }
+}
+
+/// A recorded change: a value to swap with or an edit.
+enum Record<T> {
+    Snapshot(T),
+    Edit(Box<Edit<T>>),
+}
+
+/// A value with bounded lists of past and undone changes.
+pub struct History<T> {
+    present: T,
+    past: VecDeque<Record<T>>,
+    future: Vec<Record<T>>,
+    capacity: usize,
+}
+
+impl<T> History<T> {
+    /// Wraps the value and keeps at most `capacity` changes to undo.
+    pub fn new(value: T, capacity: usize) -> Self {
+        History {
+            present: value,
+            past: VecDeque::new(),
+            future: Vec::new(),
+            capacity,
+        }
+    }
+
+    /// Returns the current value.
+    pub fn get(&self) -> &T {
+        &self.present
+    }
+
+    /// Replaces the value and records the previous one.
+    pub fn set(&mut self, value: T) {
+        let previous = mem::replace(&mut self.present, value);
+        self.record(Record::Snapshot(previous));
+    }
+
+    /// Applies the edit and records it.
+    pub fn apply<E: Edit<T> + 'static>(&mut self, edit: E) {
+        edit.apply(&mut self.present);
+        self.record(Record::Edit(Box::new(edit)));
+    }
+
+    /// Changes the value without recording, like a selection which
+    /// shouldn't be undone.
+    pub fn untracked(&mut self) -> &mut T {
+        &mut self.present
+    }
+
+    /// Undoes the last change. Returns `false` if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.past.pop_back() {
+            Some(record) => {
+                let record = self.revert(record);
+                self.future.push(record);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Makes the last undone change again. Returns `false` if there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        match self.future.pop() {
+            Some(record) => {
+                let record = self.reapply(record);
+                self.past.push_back(record);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Handles a message and returns `true` if the value changed.
+    pub fn handle(&mut self, msg: UndoMsg) -> ShouldRender {
+        match msg {
+            UndoMsg::Undo => self.undo(),
+            UndoMsg::Redo => self.redo(),
+        }
+    }
+
+    /// Returns `true` if there is a change to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.past.is_empty()
+    }
+
+    /// Returns `true` if there is a change to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.future.is_empty()
+    }
+
+    /// Forgets all recorded changes, like after a document was saved.
+    pub fn clear(&mut self) {
+        self.past.clear();
+        self.future.clear();
+    }
+
+    /// Returns the current value and drops the history.
+    pub fn into_inner(self) -> T {
+        self.present
+    }
+
+    fn record(&mut self, record: Record<T>) {
+        self.future.clear();
+        self.past.push_back(record);
+        while self.past.len() > self.capacity {
+            self.past.pop_front();
+        }
+    }
+
+    /// Undoes the record and returns a record which makes the change again.
+    fn revert(&mut self, record: Record<T>) -> Record<T> {
+        match record {
+            Record::Snapshot(value) => Record::Snapshot(mem::replace(&mut self.present, value)),
+            Record::Edit(edit) => {
+                edit.revert(&mut self.present);
+                Record::Edit(edit)
+            }
+        }
+    }
+
+    fn reapply(&mut self, record: Record<T>) -> Record<T> {
+        match record {
+            Record::Snapshot(value) => Record::Snapshot(mem::replace(&mut self.present, value)),
+            Record::Edit(edit) => {
+                edit.apply(&mut self.present);
+                Record::Edit(edit)
+            }
+        }
+    }
+}
+
+impl<T: Clone> History<T> {
+    /// Changes a copy of the value and records the previous one.
+    pub fn update<F: FnOnce(&mut T)>(&mut self, change: F) {
+        let mut value = self.present.clone();
+        change(&mut value);
+        self.set(value);
+    }
+}
+
+impl<T: Default> Default for History<T> {
+    fn default() -> Self {
+        History::new(T::default(), 100)
+    }
+}
+
+impl<T> Deref for History<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.present
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for History<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("History")
+            .field("present", &self.present)
+            .field("past", &self.past.len())
+            .field("future", &self.future.len())
+            .finish()
+    }
+}
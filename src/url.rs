@@ -0,0 +1,196 @@
+//! This module contains a typed URL which is built and parsed by parts,
+//! so API requests and links of an app aren't concatenated from strings:
+//!
+//! ```rust,ignore
+//! let url = Url::with_origin("https://api.example.com")
+//!     .path("/v1/users")
+//!     .segment(&self.user_id)
+//!     .params(&Page { page: 2, per_page: 50 });
+//! let request = Request::get(url.to_string()).body(Nothing).unwrap();
+//!
+//! let link = Url::route(&Route::Post { id: 42 }).hash("comments");
+//! let search: Search = router::current_url().to_params()?;
+//! ```
+//!
+//! Segments and parameters are kept decoded and encoded with
+//! `router::encode` when the URL is printed.
+
+use std::fmt;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use http::Uri;
+use http::uri::InvalidUri;
+use router::{self, Routable};
+
+/// A URL of a location or a request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Url {
+    /// The scheme and the host, like `https://example.com:8080`.
+    /// It's `None` for a path of the same origin.
+    pub origin: Option<String>,
+    /// Decoded segments of the path.
+    pub segments: Vec<String>,
+    /// Ends the path with `/`.
+    pub trailing_slash: bool,
+    /// Decoded parameters of the query in order, they could repeat.
+    pub query: Vec<(String, String)>,
+    /// The hash without `#`. It's kept as is.
+    pub hash: Option<String>,
+}
+
+impl Url {
+    /// Creates the root path of the same origin, `/`.
+    pub fn new() -> Self {
+        Url::default()
+    }
+
+    /// Creates the root path of the origin, like `https://api.example.com`.
+    pub fn with_origin(origin: &str) -> Self {
+        Url {
+            origin: Some(origin.trim_end_matches('/').to_owned()),
+            ..Url::default()
+        }
+    }
+
+    /// Creates the path of the route.
+    pub fn route<R: Routable>(route: &R) -> Self {
+        Url::parse(&route.to_path()).expect("a route builds an encoded path")
+    }
+
+    /// Parses an absolute URL with an origin or a path with a query and a hash.
+    pub fn parse(location: &str) -> Result<Self, String> {
+        let (origin, rest) = match location.find("://") {
+            Some(scheme) => {
+                let host = scheme + 3;
+                let end = location[host..].find(&['/', '?', '#'][..])
+                    .map_or(location.len(), |end| host + end);
+                (Some(location[..end].to_owned()), &location[end..])
+            }
+            None => (None, location),
+        };
+        let path = router::path_of(rest);
+        let mut segments = Vec::new();
+        for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+            let decoded = router::decode(segment).ok_or_else(|| format!("can't decode path segment: {}", segment))?;
+            segments.push(decoded);
+        }
+        let mut query = Vec::new();
+        for pair in router::query_of(rest).split('&').filter(|pair| !pair.is_empty()) {
+            let mut parts = pair.splitn(2, '=');
+            let key = parts.next().unwrap_or_default().replace('+', " ");
+            let value = parts.next().unwrap_or_default().replace('+', " ");
+            let key = router::decode(&key).ok_or_else(|| format!("can't decode query parameter: {}", key))?;
+            let value = router::decode(&value).ok_or_else(|| format!("can't decode query value: {}", value))?;
+            query.push((key, value));
+        }
+        let hash = if rest.contains('#') {
+            Some(router::hash_of(rest).to_owned())
+        } else {
+            None
+        };
+        Ok(Url {
+            origin,
+            trailing_slash: path.len() > 1 && path.ends_with('/'),
+            segments,
+            query,
+            hash,
+        })
+    }
+
+    /// Appends segments of the path, like `/v1/users`. They're taken as is,
+    /// use `segment` for values which have to be encoded.
+    pub fn path(mut self, path: &str) -> Self {
+        self.segments.extend(path.split('/').filter(|segment| !segment.is_empty()).map(str::to_owned));
+        self.trailing_slash = path.len() > 1 && path.ends_with('/');
+        self
+    }
+
+    /// Appends a segment which is encoded, so `a/b` stays one segment.
+    pub fn segment<T: ToString>(mut self, value: T) -> Self {
+        self.segments.push(value.to_string());
+        self.trailing_slash = false;
+        self
+    }
+
+    /// Appends a parameter to the query.
+    pub fn query<T: ToString>(mut self, key: &str, value: T) -> Self {
+        self.query.push((key.to_owned(), value.to_string()));
+        self
+    }
+
+    /// Replaces parameters of the query with fields of the value like
+    /// `router::to_query` does: `None` fields are skipped and sequences
+    /// become repeated parameters.
+    pub fn params<T: Serialize>(mut self, value: &T) -> Self {
+        let query = router::to_query(value);
+        self.query = Url::parse(&format!("?{}", query)).map(|url| url.query).unwrap_or_default();
+        self
+    }
+
+    /// Sets the hash without `#`.
+    pub fn hash(mut self, hash: &str) -> Self {
+        self.hash = Some(hash.to_owned());
+        self
+    }
+
+    /// Returns the first value of the parameter of the query.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.query.iter().find(|(name, _)| name == key).map(|(_, value)| value.as_str())
+    }
+
+    /// Returns all values of the parameter of the query.
+    pub fn get_all(&self, key: &str) -> Vec<&str> {
+        self.query.iter().filter(|(name, _)| name == key).map(|(_, value)| value.as_str()).collect()
+    }
+
+    /// Parses the query to a value like `router::parse_query` does.
+    pub fn to_params<T: DeserializeOwned>(&self) -> Result<T, String> {
+        router::parse_query(&self.query_string())
+    }
+
+    /// Returns the encoded path, like `/users/a%2Fb`.
+    pub fn path_string(&self) -> String {
+        let mut path = String::new();
+        for segment in &self.segments {
+            path.push('/');
+            path.push_str(&router::encode(segment));
+        }
+        if path.is_empty() || self.trailing_slash {
+            path.push('/');
+        }
+        path
+    }
+
+    /// Returns the encoded query without `?`.
+    pub fn query_string(&self) -> String {
+        let pairs: Vec<String> = self.query.iter()
+            .map(|(key, value)| format!("{}={}", router::encode(key), router::encode(value)))
+            .collect();
+        pairs.join("&")
+    }
+
+    /// Parses the path and the query to a route. The origin and the hash are ignored.
+    pub fn to_route<R: Routable>(&self) -> Option<R> {
+        R::from_path(&router::with_query(&self.path_string(), &self.query_string()))
+    }
+
+    /// Converts the URL without the hash to a `Uri` of requests.
+    pub fn to_uri(&self) -> Result<Uri, InvalidUri> {
+        let mut url = self.clone();
+        url.hash = None;
+        url.to_string().parse()
+    }
+}
+
+impl fmt::Display for Url {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ref origin) = self.origin {
+            write!(f, "{}", origin)?;
+        }
+        write!(f, "{}", router::with_query(&self.path_string(), &self.query_string()))?;
+        if let Some(ref hash) = self.hash {
+            write!(f, "#{}", hash)?;
+        }
+        Ok(())
+    }
+}
@@ -1,16 +1,44 @@
 //! This module contains the implementation of reactive virtual dom concept.
+//!
+//! Native builds (servers and tests) have no DOM: virtual nodes are only
+//! rendered to strings or test trees there, so the parts which render nodes
+//! to a document are compiled for browsers only.
 
 pub mod vnode;
 pub mod vtag;
 pub mod vtext;
+pub mod vportal;
+pub mod vcomp;
+pub mod vraw;
+pub mod node_ref;
+pub(crate) mod slicing;
+mod pool;
 
+#[cfg(feature = "testing")]
+use std::any::Any;
 use std::fmt;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
-use stdweb::web::{Element, EventListenerHandle};
+use std::mem::ManuallyDrop;
+use std::ops::Deref;
+use stdweb::Value;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::unstable::TryFrom;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::web::Element;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::web::event::ConcreteEvent;
 
 pub use self::vnode::VNode;
 pub use self::vtag::VTag;
 pub use self::vtext::VText;
+pub use self::vportal::VPortal;
+pub use self::vcomp::VComp;
+pub use self::vraw::{VRaw, Sanitizer};
+pub use self::node_ref::NodeRef;
+pub use self::pool::pooled;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
 use html::AppSender;
 
 /// `Listener` trait is an universal implementation of an event listener
@@ -20,7 +48,23 @@ pub trait Listener<MSG> {
     fn kind(&self) -> &'static str;
     /// Attaches listener to the element and uses sender instance to send
     /// prepaired event back to the yew main loop.
-    fn attach(&mut self, element: &Element, sender: AppSender<MSG>) -> EventListenerHandle;
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn attach(&mut self, element: &Element, sender: AppSender<MSG>) -> ListenerHandle;
+    /// Calls the handler with data of an event simulated by a test renderer.
+    /// Returns `None` if the listener expects data of another type.
+    #[cfg(feature = "testing")]
+    fn simulate(&mut self, _data: &Any) -> Option<Simulated<MSG>> {
+        None
+    }
+}
+
+/// A message of a listener called by a test renderer.
+#[cfg(feature = "testing")]
+pub struct Simulated<MSG> {
+    /// The message which the handler returned.
+    pub msg: MSG,
+    /// The event doesn't bubble up to listeners of parent elements.
+    pub stop_propagation: bool,
 }
 
 impl<MSG> fmt::Debug for Listener<MSG> {
@@ -29,19 +73,426 @@ impl<MSG> fmt::Debug for Listener<MSG> {
     }
 }
 
+/// Options of a listener which are passed to `addEventListener`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ListenerOptions {
+    /// The listener never calls `prevent_default` and the browser
+    /// could scroll without waiting for it.
+    pub passive: bool,
+    /// The listener is called in the capture phase before
+    /// listeners of descendant elements.
+    pub capture: bool,
+    /// The listener is removed after the first call.
+    pub once: bool,
+}
+
+/// A handle to a listener attached to an element.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+pub struct ListenerHandle {
+    element: Element,
+    kind: &'static str,
+    options: ListenerOptions,
+    delegated: bool,
+    /// A JS object which keeps the handler in the `callback` field. DOM calls
+    /// its `dispatch` function, so the handler could be replaced in place.
+    slot: Value,
+}
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+thread_local! {
+    /// A handle of the previous render which the next attached listener
+    /// reuses if it listens the same events of the same element.
+    static REUSABLE: RefCell<Option<ListenerHandle>> = RefCell::new(None);
+}
+
+/// Events which bubble and are delegated to the roots of apps. All listeners
+/// of these events are delegated, so they're called in the same order as
+/// listeners of elements would be. Touch and wheel events are attached to
+/// elements, because only listeners of elements could be passive.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+const DELEGATED_EVENTS: &[&str] = &[
+    "click", "dblclick", "mousedown", "mouseup", "mousemove", "mouseover", "mouseout",
+    "contextmenu", "pointerdown", "pointerup", "pointermove", "pointercancel",
+    "keypress", "keydown", "keyup", "submit", "input", "change", "paste",
+];
+
+/// Returns the table of delegated handlers shared by all apps of a page.
+///
+/// Listeners of a root find the handlers of the target and its parents by
+/// internal ids of elements and call them like the DOM calls listeners:
+/// capturing handlers from the top in the capture phase and the others from
+/// the target in the bubble phase, until one of them stops propagation.
+/// `currentTarget` of the event is the element of the called handler.
+/// The first root which gets the event calls all handlers of the path,
+/// so roots inside of roots (like portals in the body) don't call them twice.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+fn delegation() -> Value {
+    let delegation: Value = js! {
+        if (window.__yew_delegation) {
+            return window.__yew_delegation;
+        }
+        var delegation = { next_id: 1, handlers: {} };
+        delegation.dispatch = function(event, capture) {
+            var mark = capture ? "__yew_captured" : "__yew_bubbled";
+            if (event[mark] === true) {
+                return;
+            }
+            event[mark] = true;
+            var handlers = delegation.handlers[event.type];
+            if (!handlers) {
+                return;
+            }
+            var path = [];
+            for (var node = event.target; node; node = node.parentNode) {
+                if (node.__yew_id !== undefined) {
+                    path.push(node);
+                }
+            }
+            if (capture) {
+                path.reverse();
+            }
+            var current = null;
+            Object.defineProperty(event, "currentTarget", {
+                configurable: true,
+                get: function() { return current; },
+            });
+            for (var i = 0; i < path.length && !event.cancelBubble; i++) {
+                var list = handlers[path[i].__yew_id];
+                if (!list) {
+                    continue;
+                }
+                current = path[i];
+                // Like the DOM, it calls all handlers of the element
+                // even if one of them stops propagation
+                list.slice().forEach(function(slot) {
+                    if (slot.capture !== capture) {
+                        return;
+                    }
+                    if (slot.once) {
+                        list.splice(list.indexOf(slot), 1);
+                    }
+                    slot.callback(event);
+                });
+            }
+            delete event.currentTarget;
+        };
+        window.__yew_delegation = delegation;
+        return delegation;
+    };
+    delegation
+}
+
+/// Adds listeners of delegated events to the element which an app, a portal
+/// or a custom element renders into. A root could be registered many times,
+/// its listeners are removed when it's unregistered as many times.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+pub(crate) fn register_root(root: &Element) {
+    js! { @(no_return)
+        var root = @{root};
+        var delegation = @{delegation()};
+        var kinds = @{DELEGATED_EVENTS.to_vec()};
+        root.__yew_roots = (root.__yew_roots || 0) + 1;
+        if (root.__yew_roots > 1) {
+            return;
+        }
+        root.__yew_dispatch = kinds.map(function(kind) {
+            var capture = function(event) { delegation.dispatch(event, true); };
+            var bubble = function(event) { delegation.dispatch(event, false); };
+            root.addEventListener(kind, capture, true);
+            root.addEventListener(kind, bubble, false);
+            return { kind: kind, capture: capture, bubble: bubble };
+        });
+    }
+}
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+pub(crate) fn unregister_root(root: &Element) {
+    js! { @(no_return)
+        var root = @{root};
+        root.__yew_roots -= 1;
+        if (root.__yew_roots > 0) {
+            return;
+        }
+        root.__yew_dispatch.forEach(function(listener) {
+            root.removeEventListener(listener.kind, listener.capture, true);
+            root.removeEventListener(listener.kind, listener.bubble, false);
+        });
+        delete root.__yew_roots;
+        delete root.__yew_dispatch;
+    }
+}
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+impl ListenerHandle {
+    /// Adds a listener of events of type `T` to the element.
+    ///
+    /// Listeners of bubbling events aren't added to elements: the root
+    /// of the app has a single listener per event type and phase, it finds
+    /// handlers of the target and its parents by internal ids of elements
+    /// and calls them until one stops propagation. It keeps big tables with
+    /// handlers of every row cheap to render.
+    pub fn attach<T, F>(element: &Element, options: ListenerOptions, listener: F) -> Self
+    where
+        T: ConcreteEvent,
+        F: FnMut(T) + 'static,
+    {
+        ListenerHandle::attach_named(element, T::EVENT_TYPE, options, listener)
+    }
+
+    /// Adds a listener of events with the `kind` name (like custom events
+    /// of web components) to the element. It's delegated like `attach`
+    /// if it listens a bubbling event.
+    pub fn attach_named<T, F>(element: &Element, kind: &'static str, options: ListenerOptions, listener: F) -> Self
+    where
+        T: TryFrom<Value>,
+        F: FnMut(T) + 'static,
+    {
+        if DELEGATED_EVENTS.contains(&kind) {
+            return ListenerHandle::delegate(element, kind, options, listener);
+        }
+        let callback = js!( return @{listener}; );
+        if let Some(handle) = ListenerHandle::reuse(element, kind, options, false, &callback) {
+            return handle;
+        }
+        let slot = js! {
+            var slot = { callback: @{callback} };
+            slot.dispatch = function(event) {
+                slot.callback(event);
+            };
+            var options = {
+                passive: @{options.passive},
+                capture: @{options.capture},
+                once: @{options.once},
+            };
+            @{element}.addEventListener(@{kind}, slot.dispatch, options);
+            return slot;
+        };
+        ListenerHandle {
+            element: element.clone(),
+            kind,
+            options,
+            delegated: false,
+            slot,
+        }
+    }
+
+    /// Registers the listener as a handler of the element for the delegated
+    /// listeners of roots. Passive handlers are called by the same listeners,
+    /// so `passive` is only a promise for them.
+    fn delegate<T, F>(element: &Element, kind: &'static str, options: ListenerOptions, listener: F) -> Self
+    where
+        T: TryFrom<Value>,
+        F: FnMut(T) + 'static,
+    {
+        let callback = js!( return @{listener}; );
+        if let Some(handle) = ListenerHandle::reuse(element, kind, options, true, &callback) {
+            return handle;
+        }
+        let slot = js! {
+            var slot = {
+                callback: @{callback},
+                capture: @{options.capture},
+                once: @{options.once},
+            };
+            var element = @{element};
+            var kind = @{kind};
+            var delegation = @{delegation()};
+            if (element.__yew_id === undefined) {
+                element.__yew_id = delegation.next_id++;
+            }
+            var handlers = delegation.handlers[kind];
+            if (!handlers) {
+                handlers = delegation.handlers[kind] = {};
+            }
+            var list = handlers[element.__yew_id];
+            if (!list) {
+                list = handlers[element.__yew_id] = [];
+            }
+            list.push(slot);
+            return slot;
+        };
+        ListenerHandle {
+            element: element.clone(),
+            kind,
+            options,
+            delegated: true,
+            slot,
+        }
+    }
+
+    /// Attaches the listener reusing the `previous` handle if the listener
+    /// attaches the same kind of a listener to the same element.
+    pub(crate) fn reattach<MSG>(
+        listener: &mut Listener<MSG>,
+        element: &Element,
+        sender: AppSender<MSG>,
+        previous: Option<ListenerHandle>,
+    ) -> ListenerHandle {
+        REUSABLE.with(|reusable| *reusable.borrow_mut() = previous);
+        let handle = listener.attach(element, sender);
+        if let Some(stale) = REUSABLE.with(|reusable| reusable.borrow_mut().take()) {
+            stale.remove();
+        }
+        handle
+    }
+
+    /// Replaces the handler of the reusable handle if it matches.
+    /// Listeners which are called once can't be reused, because they could be removed already.
+    fn reuse(element: &Element, kind: &str, options: ListenerOptions, delegated: bool, callback: &Value) -> Option<Self> {
+        let handle = REUSABLE.with(|reusable| {
+            let mut reusable = reusable.borrow_mut();
+            let matches = reusable.as_ref().map(|handle| {
+                handle.kind == kind && handle.options == options && !options.once
+                    && handle.delegated == delegated && handle.element.as_ref() == element.as_ref()
+            });
+            if matches == Some(true) {
+                reusable.take()
+            } else {
+                None
+            }
+        })?;
+        js! { @(no_return)
+            var slot = @{&handle.slot};
+            var stale = slot.callback;
+            slot.callback = @{callback};
+            stale.drop();
+        }
+        Some(handle)
+    }
+
+    /// Removes the listener from the element.
+    pub fn remove(self) {
+        let element = &self.element;
+        let slot = &self.slot;
+        if self.delegated {
+            js! { @(no_return)
+                var slot = @{slot};
+                var element = @{element};
+                var handlers = window.__yew_delegation.handlers[@{self.kind}];
+                var list = handlers[element.__yew_id] || [];
+                var index = list.indexOf(slot);
+                if (index >= 0) {
+                    list.splice(index, 1);
+                }
+                if (list.length === 0) {
+                    delete handlers[element.__yew_id];
+                }
+                slot.callback.drop();
+            }
+        } else {
+            js! { @(no_return)
+                var slot = @{slot};
+                @{element}.removeEventListener(@{self.kind}, slot.dispatch, @{self.options.capture});
+                slot.callback.drop();
+            }
+        }
+    }
+}
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+impl fmt::Debug for ListenerHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ListenerHandle {{ kind: {}, delegated: {} }}", self.kind, self.delegated)
+    }
+}
+
 /// A list of event listeners.
 type Listeners<MSG> = Vec<Box<Listener<MSG>>>;
 
 /// A map of attributes.
 type Attributes = HashMap<String, String>;
 
+/// A map of JS properties.
+type Properties = HashMap<String, Property>;
+
+/// A value of a JS property of an element.
+///
+/// Releasing a reference to a JS object calls JS, which native builds don't
+/// have. They can't make references either, so only strings are released there.
+pub struct Property(ManuallyDrop<Value>);
+
+impl From<Value> for Property {
+    fn from(value: Value) -> Self {
+        Property(ManuallyDrop::new(value))
+    }
+}
+
+impl Deref for Property {
+    type Target = Value;
+
+    fn deref(&self) -> &Value {
+        &self.0
+    }
+}
+
+impl Clone for Property {
+    fn clone(&self) -> Self {
+        Property::from(Value::clone(self))
+    }
+}
+
+impl PartialEq for Property {
+    fn eq(&self, other: &Property) -> bool {
+        **self == **other
+    }
+}
+
+impl fmt::Debug for Property {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl Drop for Property {
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn drop(&mut self) {
+        unsafe { ManuallyDrop::drop(&mut self.0) }
+    }
+
+    #[cfg(not(any(target_arch = "wasm32", target_os = "emscripten")))]
+    fn drop(&mut self) {
+        if let Value::String(ref mut text) = *self.0 {
+            *text = String::new();
+        }
+    }
+}
+
 /// A set of classes.
 type Classes = HashSet<String>;
 
-/// Patch for DOM node modification.
-enum Patch<ID, T> {
-    Add(ID, T),
-    Replace(ID, T),
-    Remove(ID),
+/// A change of an element which `VTag::diff` computes and a render applies.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Patch {
+    /// Adds the class to the element.
+    AddClass(String),
+    /// Removes the class from the element.
+    RemoveClass(String),
+    /// Sets the attribute with the name to the value.
+    SetAttribute(String, String),
+    /// Removes the attribute with the name.
+    RemoveAttribute(String),
+    /// Sets the JS property. The undefined value resets a removed property.
+    SetProperty(String, Value),
+    /// Sets `type` of an input. An empty kind resets it.
+    SetKind(String),
+    /// Sets the `value` attribute of an element which isn't controlled.
+    SetValue(String),
+    /// Removes the value: clears it for `input` and `textarea` elements
+    /// and removes the attribute of others.
+    RemoveValue,
 }
 
+impl Patch {
+    /// Returns the name of a changed class, attribute or property.
+    pub fn name(&self) -> &str {
+        match *self {
+            Patch::AddClass(ref name) |
+            Patch::RemoveClass(ref name) |
+            Patch::SetAttribute(ref name, _) |
+            Patch::RemoveAttribute(ref name) |
+            Patch::SetProperty(ref name, _) => name,
+            Patch::SetKind(_) => "type",
+            Patch::SetValue(_) | Patch::RemoveValue => "value",
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! This module contains `NodeRef` which keeps a reference to a rendered element.
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use stdweb::web::Element;
+
+/// A reference to the element of a tag. Put it to a tag with `ref=&self.input`
+/// and the reference is set when the tag is rendered and cleared when it's removed.
+/// Clones refer to the same element.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+#[derive(Clone, Default)]
+pub struct NodeRef(Rc<RefCell<Option<Element>>>);
+
+/// A reference to the element of a tag. Native builds never render elements,
+/// so it's always empty there.
+#[cfg(not(any(target_arch = "wasm32", target_os = "emscripten")))]
+#[derive(Clone, Default)]
+pub struct NodeRef(Rc<()>);
+
+impl NodeRef {
+    /// Returns the element if the tag is rendered now.
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    pub fn get(&self) -> Option<Element> {
+        self.0.borrow().clone()
+    }
+
+    /// Returns the element if the tag is rendered now.
+    #[cfg(not(any(target_arch = "wasm32", target_os = "emscripten")))]
+    pub fn get(&self) -> Option<Element> {
+        None
+    }
+
+    /// Moves focus to the element. Returns `false` if it's not rendered.
+    pub fn focus(&self) -> bool {
+        match self.get() {
+            Some(element) => {
+                js! { @(no_return)
+                    @{element}.focus();
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes focus from the element.
+    pub fn blur(&self) {
+        if let Some(element) = self.get() {
+            js! { @(no_return)
+                @{element}.blur();
+            }
+        }
+    }
+
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    pub(crate) fn set(&self, element: Option<Element>) {
+        *self.0.borrow_mut() = element;
+    }
+}
+
+impl PartialEq for NodeRef {
+    fn eq(&self, other: &NodeRef) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for NodeRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "NodeRef({})", if self.get().is_some() { "rendered" } else { "empty" })
+    }
+}
@@ -0,0 +1,124 @@
+//! This module contains a pool of collections of virtual tags. Every render
+//! builds a new tree, so maps of attributes, classes and properties of tags
+//! of the previous frame are cleared and reused by new tags instead of
+//! being freed and allocated again. Vectors of children and listeners are
+//! reused too, they're kept by the type of messages of their tags.
+//!
+//! Boxes of listeners aren't pooled, because they keep closures of different
+//! types. Children aren't stored inline like in a `SmallVec`, because a tag
+//! is stored inline in a node.
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use virtual_dom::{Attributes, Classes, Listeners, Properties, VNode};
+
+/// How many collections of every kind the pool keeps.
+const CAPACITY: usize = 4096;
+
+#[derive(Default)]
+struct Pool {
+    attributes: Vec<Attributes>,
+    classes: Vec<Classes>,
+    properties: Vec<Properties>,
+    /// `Vectors` of every type of messages.
+    vectors: HashMap<TypeId, Box<Any>>,
+    /// The count of vectors of all types.
+    pooled_vectors: usize,
+}
+
+/// Vectors of tags which send messages of the same type.
+struct Vectors<MSG> {
+    childs: Vec<Vec<VNode<MSG>>>,
+    listeners: Vec<Listeners<MSG>>,
+}
+
+impl Pool {
+    fn vectors<MSG: 'static>(&mut self) -> &mut Vectors<MSG> {
+        self.vectors.entry(TypeId::of::<MSG>())
+            .or_insert_with(|| Box::new(Vectors::<MSG> { childs: Vec::new(), listeners: Vec::new() }))
+            .downcast_mut()
+            .expect("vectors of another type of messages")
+    }
+}
+
+thread_local! {
+    static POOL: RefCell<Pool> = RefCell::new(Pool::default());
+}
+
+/// Takes an empty map of attributes.
+pub(crate) fn attributes() -> Attributes {
+    POOL.with(|pool| pool.borrow_mut().attributes.pop()).unwrap_or_default()
+}
+
+/// Takes an empty set of classes.
+pub(crate) fn classes() -> Classes {
+    POOL.with(|pool| pool.borrow_mut().classes.pop()).unwrap_or_default()
+}
+
+/// Takes an empty map of properties.
+pub(crate) fn properties() -> Properties {
+    POOL.with(|pool| pool.borrow_mut().properties.pop()).unwrap_or_default()
+}
+
+/// Takes empty vectors of children and listeners.
+pub(crate) fn vectors<MSG: 'static>() -> (Vec<VNode<MSG>>, Listeners<MSG>) {
+    POOL.with(|pool| {
+        let mut pool = pool.borrow_mut();
+        let (childs, listeners) = {
+            let vectors = pool.vectors::<MSG>();
+            (vectors.childs.pop(), vectors.listeners.pop())
+        };
+        pool.pooled_vectors -= childs.is_some() as usize + listeners.is_some() as usize;
+        (childs.unwrap_or_default(), listeners.unwrap_or_default())
+    })
+}
+
+/// Returns vectors to the pool. Children must be taken out of them already.
+pub(crate) fn put_vectors<MSG: 'static>(childs: Vec<VNode<MSG>>, mut listeners: Listeners<MSG>) {
+    debug_assert!(childs.is_empty(), "children of a recycled tag weren't taken");
+    POOL.with(|pool| {
+        let mut pool = pool.borrow_mut();
+        let mut added = 0;
+        {
+            let vectors = pool.vectors::<MSG>();
+            if childs.capacity() > 0 && vectors.childs.len() < CAPACITY {
+                vectors.childs.push(childs);
+                added += 1;
+            }
+            if listeners.capacity() > 0 && vectors.listeners.len() < CAPACITY {
+                listeners.clear();
+                vectors.listeners.push(listeners);
+                added += 1;
+            }
+        }
+        pool.pooled_vectors += added;
+    });
+}
+
+/// Returns collections to the pool. Empty ones without capacity aren't kept.
+pub(crate) fn put(mut attributes: Attributes, mut classes: Classes, mut properties: Properties) {
+    POOL.with(|pool| {
+        let mut pool = pool.borrow_mut();
+        if attributes.capacity() > 0 && pool.attributes.len() < CAPACITY {
+            attributes.clear();
+            pool.attributes.push(attributes);
+        }
+        if classes.capacity() > 0 && pool.classes.len() < CAPACITY {
+            classes.clear();
+            pool.classes.push(classes);
+        }
+        if properties.capacity() > 0 && pool.properties.len() < CAPACITY {
+            properties.clear();
+            pool.properties.push(properties);
+        }
+    });
+}
+
+/// Returns the count of collections which the pool keeps for new tags.
+pub fn pooled() -> usize {
+    POOL.with(|pool| {
+        let pool = pool.borrow();
+        pool.attributes.len() + pool.classes.len() + pool.properties.len() + pool.pooled_vectors
+    })
+}
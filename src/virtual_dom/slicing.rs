@@ -0,0 +1,290 @@
+//! This module contains time slicing of renders. When a render takes longer
+//! than a budget, new nodes which are left aren't created immediately:
+//! they're replaced with empty placeholders and created in the next
+//! animation frames, so a browser handles input events between frames.
+//! Native builds only count the budget for the test renderer.
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use std::any::{Any, TypeId};
+use std::cell::Cell;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use std::cell::RefCell;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use std::collections::VecDeque;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use std::mem;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use std::rc::Rc;
+use std::time::Duration;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::web::{INode, Node, TextNode, document};
+use html;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use html::AppSender;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use virtual_dom::{VNode, VText, VComp};
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use virtual_dom::vcomp::Mount;
+
+/// A postponed creation of a node.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+trait Job {
+    fn run(&self);
+}
+
+thread_local! {
+    static BUDGET: Cell<Option<f64>> = Cell::new(None);
+    static DEADLINE: Cell<Option<f64>> = Cell::new(None);
+}
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+thread_local! {
+    static PENDING: RefCell<VecDeque<Box<Job>>> = RefCell::new(VecDeque::new());
+    static SCHEDULED: Cell<bool> = Cell::new(false);
+}
+
+/// Sets the budget of a single render. Renders aren't sliced if it's `None`.
+pub(crate) fn set_budget(budget: Option<Duration>) {
+    let budget = budget.map(|budget| {
+        budget.as_secs() as f64 * 1000.0 + f64::from(budget.subsec_nanos()) / 1_000_000.0
+    });
+    BUDGET.with(|value| value.set(budget));
+}
+
+/// Starts counting the budget of a render until the guard is dropped.
+/// Nested renders (of components) share the budget of the outer one.
+pub(crate) fn begin() -> Slice {
+    let budget = BUDGET.with(|budget| budget.get());
+    let outer = DEADLINE.with(|deadline| deadline.get().is_some());
+    match budget {
+        Some(budget) if !outer => {
+            DEADLINE.with(|deadline| deadline.set(Some(html::now() + budget)));
+            Slice(true)
+        }
+        _ => Slice(false),
+    }
+}
+
+/// A guard of a counted budget.
+pub(crate) struct Slice(bool);
+
+impl Drop for Slice {
+    fn drop(&mut self) {
+        if self.0 {
+            DEADLINE.with(|deadline| deadline.set(None));
+        }
+    }
+}
+
+/// Returns `true` if the budget of the current render is spent.
+pub(crate) fn expired() -> bool {
+    DEADLINE.with(|deadline| deadline.get())
+        .map(|deadline| html::now() > deadline)
+        .unwrap_or(false)
+}
+
+/// Applies a new node which has no ancestor: creates it now or postpones
+/// it to the next frame if the budget of the render is spent.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+pub(crate) fn apply_new<T, MSG>(node: &mut VNode<MSG>, parent: &T, sender: AppSender<MSG>)
+where
+    T: INode,
+    MSG: 'static,
+{
+    if !expired() {
+        node.apply(parent, None, sender);
+        return;
+    }
+    let pending = mem::replace(node, VNode::from(VText::new("")));
+    let deferred = Deferred {
+        slot: Rc::new(RefCell::new(Slot {
+            node: Some(pending),
+            placeholder: None,
+            parent: None,
+            sender: sender.clone(),
+            mounted: false,
+        })),
+    };
+    *node = VNode::from(VComp::from_mount(Box::new(deferred)));
+    node.apply(parent, None, sender);
+}
+
+/// Unwraps a node which was postponed by a previous render. A created node
+/// is returned as is and a pending one is canceled and replaced with its placeholder.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+pub(crate) fn resolve<MSG: 'static>(node: VNode<MSG>) -> VNode<MSG> {
+    let vcomp = match node {
+        VNode::VComp { vcomp } => {
+            if vcomp.kind() != TypeId::of::<Deferred<MSG>>() {
+                return VNode::VComp { vcomp };
+            }
+            vcomp
+        }
+        node => return node,
+    };
+    let deferred = vcomp.into_mount().into_any().downcast::<Deferred<MSG>>()
+        .expect("deferred node has another type");
+    let mut slot = deferred.slot.borrow_mut();
+    if slot.mounted {
+        slot.node.take().expect("created node lost")
+    } else {
+        slot.node = None;
+        VNode::VText {
+            reference: slot.placeholder.take(),
+            vtext: VText::new(""),
+        }
+    }
+}
+
+/// Creates postponed nodes until the budget of the frame is spent.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+fn run_pending() {
+    SCHEDULED.with(|scheduled| scheduled.set(false));
+    let _slice = begin();
+    loop {
+        let job = PENDING.with(|pending| pending.borrow_mut().pop_front());
+        match job {
+            Some(job) => job.run(),
+            None => break,
+        }
+        if expired() {
+            break;
+        }
+    }
+    let left = PENDING.with(|pending| !pending.borrow().is_empty());
+    if left {
+        schedule();
+    }
+}
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+fn schedule() {
+    if SCHEDULED.with(|scheduled| scheduled.replace(true)) {
+        return;
+    }
+    let callback = run_pending;
+    js! { @(no_return)
+        var callback = @{callback};
+        requestAnimationFrame(function() {
+            callback();
+            callback.drop();
+        });
+    }
+}
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+struct Slot<MSG> {
+    /// A pending node or the node which was created.
+    node: Option<VNode<MSG>>,
+    /// Keeps the position of a pending node in the parent.
+    placeholder: Option<TextNode>,
+    parent: Option<Node>,
+    sender: AppSender<MSG>,
+    mounted: bool,
+}
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+impl<MSG: 'static> Job for Rc<RefCell<Slot<MSG>>> {
+    fn run(&self) {
+        let mut slot = self.borrow_mut();
+        let (parent, placeholder) = match (slot.parent.clone(), slot.placeholder.take()) {
+            (Some(parent), Some(placeholder)) => (parent, placeholder),
+            // The node was removed or rendered over before it was created
+            _ => return,
+        };
+        let mut node = match slot.node.take() {
+            Some(node) => node,
+            None => return,
+        };
+        node.apply(&parent, None, slot.sender.clone());
+        if let Some(reference) = node.reference() {
+            // The node was appended to the end and have to be moved
+            parent.replace_child(&reference, &placeholder);
+        }
+        slot.node = Some(node);
+        slot.mounted = true;
+    }
+}
+
+/// A node which is created in one of the next frames.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+pub(crate) struct Deferred<MSG> {
+    slot: Rc<RefCell<Slot<MSG>>>,
+}
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+impl<MSG: 'static> Mount for Deferred<MSG> {
+    fn kind(&self) -> TypeId {
+        TypeId::of::<Deferred<MSG>>()
+    }
+
+    fn mount(&mut self, parent: &Node) {
+        let placeholder = document().create_text_node("");
+        parent.append_child(&placeholder);
+        {
+            let mut slot = self.slot.borrow_mut();
+            slot.placeholder = Some(placeholder);
+            slot.parent = Some(parent.clone());
+        }
+        let job: Box<Job> = Box::new(self.slot.clone());
+        PENDING.with(|pending| pending.borrow_mut().push_back(job));
+        schedule();
+    }
+
+    fn patch(&mut self, parent: &Node, mut last: Box<Mount>) {
+        let wrong = last.unmount();
+        self.mount(parent);
+        if let (Some(wrong), Some(node)) = (wrong, self.reference()) {
+            parent.replace_child(&node, &wrong);
+        }
+    }
+
+    fn reference(&self) -> Option<Node> {
+        let slot = self.slot.borrow();
+        match slot.placeholder {
+            Some(ref placeholder) => Some(placeholder.clone().into()),
+            None => slot.node.as_ref().and_then(VNode::reference),
+        }
+    }
+
+    fn hydrate(&mut self, _: &Node, _: &mut Option<Node>) -> Result<(), String> {
+        Err("a postponed node can't be hydrated".into())
+    }
+
+    fn unmount(&mut self) -> Option<Node> {
+        let mut slot = self.slot.borrow_mut();
+        let node = slot.node.take();
+        match slot.placeholder.take() {
+            Some(placeholder) => Some(placeholder.into()),
+            None => node.and_then(VNode::into_reference),
+        }
+    }
+
+    #[cfg(feature = "ssr")]
+    fn render_to_string(&mut self, out: &mut String) {
+        if let Some(ref mut node) = self.slot.borrow_mut().node {
+            ::ssr::write_node(node, out);
+        }
+    }
+
+    #[cfg(feature = "testing")]
+    fn render_to_test(&mut self) -> ::testing::TestNode {
+        match self.slot.borrow_mut().node {
+            Some(ref mut node) => ::testing::convert(node),
+            None => ::testing::TestNode::Text(String::new()),
+        }
+    }
+
+    #[cfg(feature = "a11y")]
+    fn audit(&self, audit: &mut ::a11y::Audit) {
+        if let Ok(slot) = self.slot.try_borrow() {
+            if let Some(ref node) = slot.node {
+                audit.node(node);
+            }
+        }
+    }
+
+    fn into_any(self: Box<Self>) -> Box<Any> {
+        self
+    }
+}
@@ -0,0 +1,208 @@
+//! This module contains the implementation of a virtual component `VComp`.
+
+use std::any::{Any, TypeId};
+use std::fmt;
+use std::cmp::PartialEq;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::web::{INode, Node};
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use virtual_dom::VNode;
+use component::{Component, ComponentMount, Link};
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use html::AppSender;
+#[cfg(feature = "testing")]
+use testing::TestNode;
+
+/// An object-safe interface of a subtree which is rendered by another loop
+/// (a component or nodes of a parent passed as properties).
+/// Every subtree holds exactly one DOM node in the parent.
+pub(crate) trait Mount {
+    /// Returns type of the subtree to check that it can be patched by another one.
+    fn kind(&self) -> TypeId;
+    /// Renders the subtree first time and appends it to the `parent`.
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn mount(&mut self, parent: &Node);
+    /// Renders the subtree over the `last` subtree of the same kind.
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn patch(&mut self, parent: &Node, last: Box<Mount>);
+    /// Returns a reference to the root DOM node of the subtree.
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn reference(&self) -> Option<Node>;
+    /// Adopts the DOM nodes rendered by a server starting from the `next` node.
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn hydrate(&mut self, parent: &Node, next: &mut Option<Node>) -> Result<(), String>;
+    /// Destroys the subtree and returns a reference to its root DOM node.
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    fn unmount(&mut self) -> Option<Node>;
+    /// Takes a subtree which was kept by this one if it's a placeholder.
+    fn take_kept(&mut self) -> Option<Box<Mount>> {
+        None
+    }
+    /// Renders the subtree to an HTML string without a browser.
+    #[cfg(feature = "ssr")]
+    fn render_to_string(&mut self, out: &mut String);
+    /// Renders the subtree to a tree of a test renderer without a browser.
+    #[cfg(feature = "testing")]
+    fn render_to_test(&mut self) -> TestNode;
+    /// Walks the rendered subtree with an accessibility audit.
+    #[cfg(feature = "a11y")]
+    fn audit(&self, audit: &mut ::a11y::Audit);
+    /// Converts boxed subtree to `Any` for downcasting.
+    fn into_any(self: Box<Self>) -> Box<Any>;
+}
+
+/// A virtual component which is rendered with its own loop.
+/// The `link` is bound to the loop of a parent when the node rendered,
+/// and it makes callbacks from the properties able to send messages to the parent.
+pub struct VComp<MSG> {
+    link: Option<Link<MSG>>,
+    mount: Box<Mount>,
+}
+
+impl<MSG: 'static> VComp<MSG> {
+    /// Returns default properties for a component and a link to use it
+    /// to make callbacks for the properties.
+    pub fn lazy<COMP: Component>() -> (COMP::Properties, Link<MSG>) {
+        (Default::default(), Link::new())
+    }
+
+    /// Creates a virtual component with properties and a link which was
+    /// used to create callbacks for the properties.
+    pub fn new<COMP: Component>(props: COMP::Properties, link: Link<MSG>) -> Self {
+        VComp {
+            link: Some(link),
+            mount: Box::new(ComponentMount::<COMP>::new(props)),
+        }
+    }
+
+    /// Creates a memoized virtual component. It doesn't pass properties to
+    /// the component and skips rendering if they are equal to the properties
+    /// of the previous render. Keep in mind that callbacks are equal only if
+    /// they are clones of the same callback.
+    pub fn memo<COMP>(props: COMP::Properties, link: Link<MSG>) -> Self
+    where
+        COMP: Component,
+        COMP::Properties: Clone + PartialEq,
+    {
+        VComp {
+            link: Some(link),
+            mount: Box::new(ComponentMount::<COMP>::memo(props)),
+        }
+    }
+}
+
+impl<MSG> VComp<MSG> {
+    /// Wraps a subtree with a link which is bound to a parent's loop.
+    pub(crate) fn from_parts(link: Option<Link<MSG>>, mount: Box<Mount>) -> Self {
+        VComp { link, mount }
+    }
+
+    /// Wraps a subtree which doesn't need a parent's loop.
+    pub(crate) fn from_mount(mount: Box<Mount>) -> Self {
+        VComp {
+            link: None,
+            mount,
+        }
+    }
+
+    /// Returns the type of the subtree.
+    pub(crate) fn kind(&self) -> TypeId {
+        self.mount.kind()
+    }
+
+    /// Takes the subtree out of the node.
+    pub(crate) fn into_mount(self) -> Box<Mount> {
+        self.mount
+    }
+
+    /// Takes the link and the subtree out of the node.
+    pub(crate) fn into_parts(self) -> (Option<Link<MSG>>, Box<Mount>) {
+        (self.link, self.mount)
+    }
+
+    /// Returns a reference to the root DOM node of the component.
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    pub(crate) fn reference(&self) -> Option<Node> {
+        self.mount.reference()
+    }
+
+    /// Renders the component to an HTML string.
+    #[cfg(feature = "ssr")]
+    pub(crate) fn render_to_string(&mut self, out: &mut String) {
+        self.mount.render_to_string(out);
+    }
+
+    /// Renders the component to a tree of a test renderer.
+    #[cfg(feature = "testing")]
+    pub(crate) fn render_to_test(&mut self) -> TestNode {
+        self.mount.render_to_test()
+    }
+
+    /// Walks the rendered subtree of the component with an accessibility audit.
+    #[cfg(feature = "a11y")]
+    pub(crate) fn audit(&self, audit: &mut ::a11y::Audit) {
+        self.mount.audit(audit);
+    }
+
+    /// Destroys the component and returns a reference to its root DOM node.
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    pub(crate) fn unmount(mut self) -> Option<Node> {
+        self.mount.unmount()
+    }
+}
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+impl<MSG: 'static> VComp<MSG> {
+    /// Renders the component over the `last` node. Reuses the instance of
+    /// the component if the `last` node contains the same component.
+    pub(crate) fn apply(&mut self, parent: &Node, last: Option<VNode<MSG>>, sender: AppSender<MSG>) {
+        if let Some(ref link) = self.link {
+            link.bind(sender);
+        }
+        match last {
+            Some(VNode::VComp { vcomp }) => {
+                if vcomp.mount.kind() == self.mount.kind() {
+                    self.mount.patch(parent, vcomp.mount);
+                } else {
+                    let wrong = vcomp.unmount();
+                    self.replace(parent, wrong);
+                }
+            }
+            Some(wrong) => {
+                let wrong = wrong.into_reference();
+                self.replace(parent, wrong);
+            }
+            None => {
+                self.mount.mount(parent);
+            }
+        }
+    }
+
+    /// Adopts the markup of the component rendered by a server.
+    pub(crate) fn hydrate(&mut self, parent: &Node, next: &mut Option<Node>, sender: AppSender<MSG>) -> Result<(), String> {
+        if let Some(ref link) = self.link {
+            link.bind(sender);
+        }
+        self.mount.hydrate(parent, next)
+    }
+
+    fn replace(&mut self, parent: &Node, wrong: Option<Node>) {
+        self.mount.mount(parent);
+        if let (Some(wrong), Some(node)) = (wrong, self.mount.reference()) {
+            // Root node was appended to the end and have to be moved
+            parent.replace_child(&node, &wrong);
+        }
+    }
+}
+
+impl<MSG> fmt::Debug for VComp<MSG> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "VComp {{ kind: {:?} }}", self.mount.kind())
+    }
+}
+
+impl<MSG> PartialEq for VComp<MSG> {
+    fn eq(&self, other: &VComp<MSG>) -> bool {
+        self.mount.kind() == other.mount.kind()
+    }
+}
@@ -1,9 +1,19 @@
 //! This module contains the implementation of abstract virtual node.
 
 use std::fmt;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use std::mem;
 use std::cmp::PartialEq;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
 use stdweb::web::{INode, Node, Element, TextNode, document};
-use virtual_dom::{VTag, VText};
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::Value;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::unstable::TryInto;
+use virtual_dom::{VTag, VText, VPortal, VComp, VRaw};
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use virtual_dom::slicing;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
 use html::AppSender;
 
 /// Bind virtual element to a DOM reference.
@@ -11,6 +21,7 @@ pub enum VNode<MSG> {
     /// A bind between `VTag` and `Element`.
     VTag {
         /// A reference to the `Element`.
+        #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
         reference: Option<Element>,
         /// A virtual tag node which was applied.
         vtag: VTag<MSG>,
@@ -18,28 +29,111 @@ pub enum VNode<MSG> {
     /// A bind between `VText` and `TextNode`.
     VText {
         /// A reference to the `TextNode`.
+        #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
         reference: Option<TextNode>,
         /// A virtual text node which was applied.
         vtext: VText,
     },
+    /// A bind between `VPortal` and a placeholder `TextNode`.
+    VPortal {
+        /// A reference to the empty `TextNode` which keeps position of the portal.
+        #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+        reference: Option<TextNode>,
+        /// A virtual portal which was applied.
+        vportal: VPortal<MSG>,
+    },
+    /// A bind between `VRaw` and its wrapper `Element`.
+    VRaw {
+        /// A reference to the wrapper `Element`.
+        #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+        reference: Option<Element>,
+        /// A raw HTML node which was applied.
+        vraw: VRaw,
+    },
+    /// A virtual component which keeps a reference to its root node inside.
+    VComp {
+        /// A virtual component which was applied.
+        vcomp: VComp<MSG>,
+    },
 }
 
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+impl<MSG: 'static> VNode<MSG> {
+    /// Returns a reference to the DOM node of this virtual node if it was rendered.
+    pub(crate) fn reference(&self) -> Option<Node> {
+        match *self {
+            VNode::VTag { ref reference, .. } => reference.clone().map(Node::from),
+            VNode::VText { ref reference, .. } => reference.clone().map(Node::from),
+            VNode::VPortal { ref reference, .. } => reference.clone().map(Node::from),
+            VNode::VRaw { ref reference, .. } => reference.clone().map(Node::from),
+            VNode::VComp { ref vcomp } => vcomp.reference(),
+        }
+    }
 
-impl<MSG> VNode<MSG> {
-    fn remove<T: INode>(self, parent: &T) {
-        let opt_ref: Option<Node> = {
-            match self {
-                VNode::VTag { reference, .. } => reference.map(Node::from),
-                VNode::VText { reference, .. } => reference.map(Node::from),
+    /// Takes the reference to the DOM node of this virtual node and cleans up
+    /// everything which was rendered outside of it (children of portals, components).
+    pub(crate) fn into_reference(self) -> Option<Node> {
+        match self {
+            VNode::VTag { reference, mut vtag } => {
+                vtag.detach();
+                vtag.recycle();
+                reference.map(Node::from)
             }
-        };
-        if let Some(node) = opt_ref {
+            VNode::VText { reference, .. } => reference.map(Node::from),
+            VNode::VPortal { reference, vportal } => {
+                vportal.detach();
+                reference.map(Node::from)
+            }
+            VNode::VRaw { reference, .. } => reference.map(Node::from),
+            VNode::VComp { vcomp } => vcomp.unmount(),
+        }
+    }
+
+    pub(crate) fn remove<T: INode>(self, parent: &T) {
+        if let Some(node) = self.into_reference() {
             if let Err(_) = parent.remove_child(&node) {
                 warn!("Node not found to remove: {:?}", node);
             }
         }
     }
 
+}
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+impl<MSG: 'static> VNode<MSG> {
+    /// Applies children to the `parent` node pairing every child with a child
+    /// which was rendered before. Redundant old children will be removed.
+    pub(crate) fn apply_childs<T: INode>(parent: &T, lefts: &mut [VNode<MSG>], rights: Vec<VNode<MSG>>, sender: AppSender<MSG>) {
+        let mut rights = rights.into_iter().map(Some).collect::<Vec<_>>();
+        let mut lefts = lefts.iter_mut().map(Some).collect::<Vec<_>>();
+        let diff = lefts.len() as i32 - rights.len() as i32;
+        if diff > 0 {
+            for _ in 0..diff {
+                rights.push(None);
+            }
+        } else if diff < 0 {
+            for _ in 0..-diff {
+                lefts.push(None);
+            }
+        }
+        for pair in lefts.into_iter().zip(rights) {
+            match pair {
+                (Some(left), Some(right)) => {
+                    left.apply(parent, Some(slicing::resolve(right)), sender.clone());
+                }
+                (Some(left), None) => {
+                    slicing::apply_new(left, parent, sender.clone());
+                }
+                (None, Some(right)) => {
+                    right.remove(parent);
+                }
+                (None, None) => {
+                    panic!("redundant iterations during diff");
+                }
+            }
+        }
+    }
+
     /// Virtual rendering for the node. It uses parent node and existend children (virtual and DOM)
     /// to check the difference and apply patches to the actual DOM represenatation.
     pub fn apply<T: INode>(&mut self, parent: &T, last: Option<VNode<MSG>>, sender: AppSender<MSG>) {
@@ -60,59 +154,45 @@ impl<MSG> VNode<MSG> {
                             right = Some(vtag);
                             *reference = Some(element);
                         } else {
+                            let mut wrong_vtag = vtag;
+                            wrong_vtag.detach();
+                            wrong_vtag.recycle();
                             let wrong = element;
-                            let element = document().create_element(left.tag());
+                            let element = create_element(parent, left.tag());
                             parent.replace_child(&element, &wrong);
                             *reference = Some(element);
                         }
                     }
-                    Some(VNode::VText { reference: Some(wrong), .. }) => {
-                        let element = document().create_element(left.tag());
-                        parent.replace_child(&element, &wrong);
+                    Some(wrong) => {
+                        let element = create_element(parent, left.tag());
+                        if let Some(wrong) = wrong.into_reference() {
+                            parent.replace_child(&element, &wrong);
+                        } else {
+                            parent.append_child(&element);
+                        }
                         *reference = Some(element);
                     }
-                    Some(VNode::VTag { reference: None, .. }) |
-                    Some(VNode::VText { reference: None, .. }) |
                     None => {
-                        let element = document().create_element(left.tag());
+                        let element = create_element(parent, left.tag());
                         parent.append_child(&element);
                         *reference = Some(element);
                     }
                 }
                 let element_mut = reference.as_mut().expect("vtag must be here");
                 // Update parameters
-                let mut rights = {
+                let rights = {
                     if let Some(ref mut right) = right {
-                        right.childs.drain(..).map(Some).collect::<Vec<_>>()
+                        mem::take(&mut right.childs)
                     } else {
                         Vec::new()
                     }
                 };
                 left.render(element_mut, right, sender.clone());
-                let mut lefts = left.childs.iter_mut().map(Some).collect::<Vec<_>>();
                 // Process children
-                let diff = lefts.len() as i32 - rights.len() as i32;
-                if diff > 0 {
-                    for _ in 0..diff {
-                        rights.push(None);
-                    }
-                } else if diff < 0 {
-                    for _ in 0..-diff {
-                        lefts.push(None);
-                    }
-                }
-                for pair in lefts.into_iter().zip(rights) {
-                    match pair {
-                        (Some(left), right) => {
-                            left.apply(element_mut, right, sender.clone());
-                        }
-                        (None, Some(right)) => {
-                            right.remove(element_mut);
-                        }
-                        (None, None) => {
-                            panic!("redundant iterations during diff");
-                        }
-                    }
+                VNode::apply_childs(element_mut, &mut left.childs, rights, sender);
+                if left.tag() == "select" {
+                    // Value of `select` can be set only when options exist
+                    left.render_value(element_mut);
                 }
                 //vtag.apply(parent, reference, last, messages);
             }
@@ -130,13 +210,15 @@ impl<MSG> VNode<MSG> {
                         right = Some(vtext);
                         *reference = Some(element);
                     }
-                    Some(VNode::VTag { reference: Some(wrong), .. }) => {
+                    Some(wrong) => {
                         let element = document().create_text_node(&left.text);
-                        parent.replace_child(&element, &wrong);
+                        if let Some(wrong) = wrong.into_reference() {
+                            parent.replace_child(&element, &wrong);
+                        } else {
+                            parent.append_child(&element);
+                        }
                         *reference = Some(element);
                     }
-                    Some(VNode::VTag { reference: None, .. }) |
-                    Some(VNode::VText { reference: None, .. }) |
                     None => {
                         let element = document().create_text_node(&left.text);
                         parent.append_child(&element);
@@ -146,13 +228,269 @@ impl<MSG> VNode<MSG> {
                 let element_mut = reference.as_mut().expect("vtext must be here");
                 left.render(element_mut, right);
             }
+            VNode::VPortal {
+                ref mut vportal,
+                ref mut reference,
+            } => {
+                let left = vportal;
+                let mut right = None;
+                match last {
+                    Some(VNode::VPortal {
+                             vportal,
+                             reference: Some(anchor),
+                         }) => {
+                        right = Some(vportal);
+                        *reference = Some(anchor);
+                    }
+                    Some(wrong) => {
+                        // Portal keeps an empty text node in the parent to hold its position
+                        let anchor = document().create_text_node("");
+                        if let Some(wrong) = wrong.into_reference() {
+                            parent.replace_child(&anchor, &wrong);
+                        } else {
+                            parent.append_child(&anchor);
+                        }
+                        *reference = Some(anchor);
+                    }
+                    None => {
+                        let anchor = document().create_text_node("");
+                        parent.append_child(&anchor);
+                        *reference = Some(anchor);
+                    }
+                }
+                left.render(right, sender);
+            }
+            VNode::VRaw {
+                ref mut vraw,
+                ref mut reference,
+            } => {
+                let left = vraw;
+                let mut right = None;
+                match last {
+                    Some(VNode::VRaw {
+                             vraw,
+                             reference: Some(element),
+                         }) => {
+                        if left.tag() == vraw.tag() {
+                            right = Some(vraw);
+                            *reference = Some(element);
+                        } else {
+                            let wrong = element;
+                            let element = create_element(parent, left.tag());
+                            parent.replace_child(&element, &wrong);
+                            *reference = Some(element);
+                        }
+                    }
+                    Some(wrong) => {
+                        let element = create_element(parent, left.tag());
+                        if let Some(wrong) = wrong.into_reference() {
+                            parent.replace_child(&element, &wrong);
+                        } else {
+                            parent.append_child(&element);
+                        }
+                        *reference = Some(element);
+                    }
+                    None => {
+                        let element = create_element(parent, left.tag());
+                        parent.append_child(&element);
+                        *reference = Some(element);
+                    }
+                }
+                let element_mut = reference.as_mut().expect("vraw must be here");
+                left.render(element_mut, right);
+            }
+            VNode::VComp { ref mut vcomp } => {
+                vcomp.apply(parent.as_node(), last, sender);
+            }
         }
     }
 }
 
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+impl<MSG: 'static> VNode<MSG> {
+    /// Adopts DOM nodes rendered by a server (starting from the `next` node) instead
+    /// of creating them, and attaches listeners. Moves `next` to the node after
+    /// the adopted ones. Returns an error if the markup doesn't match this node.
+    pub(crate) fn hydrate<T: INode>(&mut self, parent: &T, next: &mut Option<Node>, sender: AppSender<MSG>) -> Result<(), String> {
+        match *self {
+            VNode::VTag {
+                ref mut vtag,
+                ref mut reference,
+            } => {
+                let element = take_element(next, vtag.tag())?;
+                vtag.render(&element, None, sender.clone());
+                *reference = Some(element.clone());
+                match vtag.value {
+                    // A server renders the value of a textarea as its text, which isn't a child
+                    Some(ref value) if vtag.tag() == "textarea" && vtag.childs.is_empty() => {
+                        expect_text(&element, value)?;
+                    }
+                    _ => VNode::hydrate_childs(&element, &mut vtag.childs, sender)?,
+                }
+                if vtag.tag() == "select" {
+                    vtag.render_value(&element);
+                }
+            }
+            VNode::VText {
+                ref mut vtext,
+                ref mut reference,
+            } => {
+                if vtext.text.is_empty() {
+                    // Server renders empty text nodes as markers
+                    let text = replace_marker(parent, next)?;
+                    *reference = Some(text);
+                } else {
+                    if next.as_ref().map(is_marker).unwrap_or(false) {
+                        // A boundary between adjacent text nodes
+                        let marker = next.take().expect("marker must be here");
+                        *next = marker.next_sibling();
+                        parent.remove_child(&marker).expect("can't remove a marker");
+                    }
+                    let node = next.take().ok_or_else(|| format!("no node for text {:?}", vtext.text))?;
+                    *next = node.next_sibling();
+                    let text: TextNode = node.try_into()
+                        .map_err(|_| format!("expected text {:?}", vtext.text))?;
+                    vtext.render(&text, None);
+                    *reference = Some(text);
+                }
+            }
+            VNode::VPortal {
+                ref mut vportal,
+                ref mut reference,
+            } => {
+                let anchor = replace_marker(parent, next)?;
+                *reference = Some(anchor);
+                vportal.render(None, sender);
+            }
+            VNode::VRaw {
+                ref mut vraw,
+                ref mut reference,
+            } => {
+                let element = take_element(next, vraw.tag())?;
+                vraw.render(&element, None);
+                *reference = Some(element);
+            }
+            VNode::VComp { ref mut vcomp } => {
+                vcomp.hydrate(parent.as_node(), next, sender)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Hydrates the only child of the `parent`.
+    pub(crate) fn hydrate_root<T: INode>(&mut self, parent: &T, sender: AppSender<MSG>) -> Result<(), String> {
+        let mut next = parent.first_child();
+        self.hydrate(parent, &mut next, sender)?;
+        expect_end(next)
+    }
+
+    /// Hydrates children of the `parent` and checks that there are no other nodes.
+    fn hydrate_childs<T: INode>(parent: &T, childs: &mut [VNode<MSG>], sender: AppSender<MSG>) -> Result<(), String> {
+        let mut next = parent.first_child();
+        for child in childs.iter_mut() {
+            child.hydrate(parent, &mut next, sender.clone())?;
+        }
+        expect_end(next)
+    }
+}
+
+/// Checks that there are no nodes after the `next` one except whitespace.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+fn expect_end(mut next: Option<Node>) -> Result<(), String> {
+    skip_whitespace(&mut next);
+    match next {
+        Some(node) => Err(format!("unexpected node {}", node.node_name())),
+        None => Ok(()),
+    }
+}
+
+/// Checks that the text of a textarea, which is its initial value, is the `value`.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+fn expect_text(element: &Element, value: &str) -> Result<(), String> {
+    let text: Value = js!( return @{element}.defaultValue; );
+    let text = text.into_string().unwrap_or_default();
+    // Parsers of markup normalize line breaks
+    if text == value.replace("\r\n", "\n").replace('\r', "\n") {
+        Ok(())
+    } else {
+        Err(format!("expected <textarea> with {:?}, found {:?}", value, text))
+    }
+}
+
+/// Takes the `next` node which has to be an element with the `tag`.
+/// Whitespace text nodes of formatted markup are skipped.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+fn take_element(next: &mut Option<Node>, tag: &str) -> Result<Element, String> {
+    skip_whitespace(next);
+    let node = next.take().ok_or_else(|| format!("no node for <{}>", tag))?;
+    *next = node.next_sibling();
+    let name: Value = js!( return @{&node}.localName; );
+    let name = name.into_string().unwrap_or_default();
+    if name != tag {
+        return Err(format!("expected <{}>, found {}", tag, node.node_name()));
+    }
+    Ok(node.try_into().expect("node with local name must be an element"))
+}
+
+/// Replaces the `next` node, which has to be a marker comment, with an empty text node.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+fn replace_marker<T: INode>(parent: &T, next: &mut Option<Node>) -> Result<TextNode, String> {
+    let node = next.take().ok_or_else(|| "no node for an empty text".to_owned())?;
+    *next = node.next_sibling();
+    if !is_marker(&node) {
+        return Err(format!("expected an empty text marker, found {}", node.node_name()));
+    }
+    let text = document().create_text_node("");
+    parent.replace_child(&text, &node);
+    Ok(text)
+}
+
+/// Checks the node is a comment which marks empty text nodes.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+fn is_marker(node: &Node) -> bool {
+    let marker: Value = js!( return @{node}.nodeType === 8; );
+    marker.try_into().unwrap_or(false)
+}
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+fn skip_whitespace(next: &mut Option<Node>) {
+    while let Some(node) = next.take() {
+        let whitespace: Value = js! {
+            var node = @{&node};
+            return node.nodeType === 3 && node.nodeValue.trim() === "";
+        };
+        if whitespace.try_into().unwrap_or(false) {
+            *next = node.next_sibling();
+        } else {
+            *next = Some(node);
+            break;
+        }
+    }
+}
+
+/// Creates an element for the `parent`. Elements inside of `svg` (but not inside
+/// of its `foreignObject`) are created with the SVG namespace, otherwise
+/// browsers render them as unknown HTML elements.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+fn create_element<T: INode>(parent: &T, tag: &str) -> Element {
+    let parent = parent.as_ref();
+    let element = js! {
+        var parent = @{parent};
+        var tag = @{tag};
+        var svg = "http://www.w3.org/2000/svg";
+        var inside = parent.namespaceURI === svg && parent.localName !== "foreignObject";
+        if (tag === "svg" || inside) {
+            return document.createElementNS(svg, tag);
+        }
+        return document.createElement(tag);
+    };
+    element.try_into().expect("can't create an element")
+}
+
 impl<MSG> From<VText> for VNode<MSG> {
     fn from(vtext: VText) -> Self {
         VNode::VText {
+            #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
             reference: None,
             vtext,
         }
@@ -162,15 +500,43 @@ impl<MSG> From<VText> for VNode<MSG> {
 impl<MSG> From<VTag<MSG>> for VNode<MSG> {
     fn from(vtag: VTag<MSG>) -> Self {
         VNode::VTag {
+            #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
             reference: None,
             vtag,
         }
     }
 }
 
+impl<MSG> From<VPortal<MSG>> for VNode<MSG> {
+    fn from(vportal: VPortal<MSG>) -> Self {
+        VNode::VPortal {
+            #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+            reference: None,
+            vportal,
+        }
+    }
+}
+
+impl<MSG> From<VRaw> for VNode<MSG> {
+    fn from(vraw: VRaw) -> Self {
+        VNode::VRaw {
+            #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+            reference: None,
+            vraw,
+        }
+    }
+}
+
+impl<MSG> From<VComp<MSG>> for VNode<MSG> {
+    fn from(vcomp: VComp<MSG>) -> Self {
+        VNode::VComp { vcomp }
+    }
+}
+
 impl<MSG, T: ToString> From<T> for VNode<MSG> {
     fn from(value: T) -> Self {
         VNode::VText {
+            #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
             reference: None,
             vtext: VText::new(value),
         }
@@ -179,9 +545,12 @@ impl<MSG, T: ToString> From<T> for VNode<MSG> {
 
 impl<MSG> fmt::Debug for VNode<MSG> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            &VNode::VTag { ref vtag, .. } => vtag.fmt(f),
-            &VNode::VText { ref vtext, .. } => vtext.fmt(f),
+        match *self {
+            VNode::VTag { ref vtag, .. } => vtag.fmt(f),
+            VNode::VText { ref vtext, .. } => vtext.fmt(f),
+            VNode::VPortal { ref vportal, .. } => vportal.fmt(f),
+            VNode::VRaw { ref vraw, .. } => vraw.fmt(f),
+            VNode::VComp { ref vcomp } => vcomp.fmt(f),
         }
     }
 }
@@ -204,6 +573,30 @@ impl<MSG> PartialEq for VNode<MSG> {
                     },
                     _ => false
                 }
+            },
+            VNode::VPortal { vportal: ref vportal_a, .. } => {
+                match *other {
+                    VNode::VPortal { vportal: ref vportal_b, .. } => {
+                        vportal_a == vportal_b
+                    },
+                    _ => false
+                }
+            },
+            VNode::VRaw { vraw: ref vraw_a, .. } => {
+                match *other {
+                    VNode::VRaw { vraw: ref vraw_b, .. } => {
+                        vraw_a == vraw_b
+                    },
+                    _ => false
+                }
+            },
+            VNode::VComp { vcomp: ref vcomp_a } => {
+                match *other {
+                    VNode::VComp { vcomp: ref vcomp_b } => {
+                        vcomp_a == vcomp_b
+                    },
+                    _ => false
+                }
             }
         }
     }
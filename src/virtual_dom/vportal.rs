@@ -0,0 +1,104 @@
+//! This module contains the implementation of a portal `VPortal`
+//! which renders its children into another DOM node.
+
+use std::fmt;
+use std::borrow::Cow;
+use std::cmp::PartialEq;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::web::{Element, document};
+use virtual_dom::VNode;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use virtual_dom;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use html::AppSender;
+
+/// A portal renders its children into an arbitrary DOM node (`body` by default)
+/// instead of the parent node. Children keep the sender of the subtree where
+/// the portal was declared, so listeners still send messages to the same loop.
+///
+/// It's useful for modals, tooltips and dropdowns which have to escape
+/// `overflow: hidden` ancestors.
+pub struct VPortal<MSG> {
+    /// A selector of the host node where children will be rendered.
+    selector: Cow<'static, str>,
+    /// The list of children nodes which are rendered into the host node.
+    pub childs: Vec<VNode<MSG>>,
+    /// _Service field_. Keeps the host node resolved by the `selector`.
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    host: Option<Element>,
+}
+
+impl<MSG> VPortal<MSG> {
+    /// Creates a new `VPortal` instance which renders children into a node
+    /// found by `selector`.
+    pub fn new<S: Into<Cow<'static, str>>>(selector: S) -> Self {
+        VPortal {
+            selector: selector.into(),
+            childs: Vec::new(),
+            #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+            host: None,
+        }
+    }
+
+    /// Creates a new `VPortal` instance which renders children into `body`.
+    pub fn body() -> Self {
+        VPortal::new("body")
+    }
+
+    /// Returns selector of the host node.
+    pub fn selector(&self) -> &str {
+        &self.selector
+    }
+
+    /// Add `VNode` child.
+    pub fn add_child(&mut self, child: VNode<MSG>) {
+        self.childs.push(child);
+    }
+}
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+impl<MSG: 'static> VPortal<MSG> {
+    /// Renders children into the host node, but it also compares them with
+    /// children of an opposite `VPortal` to patch only changed nodes.
+    pub fn render(&mut self, opposite: Option<Self>, sender: AppSender<MSG>) {
+        let mut rights = Vec::new();
+        if let Some(mut opposite) = opposite {
+            if opposite.selector == self.selector {
+                self.host = opposite.host.take();
+                rights = opposite.childs.drain(..).collect();
+            } else {
+                opposite.detach();
+            }
+        }
+        if self.host.is_none() {
+            let host = document().query_selector(&self.selector)
+                .unwrap_or_else(|| panic!("can't get host node with selector `{}` for portal", self.selector));
+            virtual_dom::register_root(&host);
+            self.host = Some(host);
+        }
+        let host = self.host.as_ref().expect("portal host must be here");
+        VNode::apply_childs(host, &mut self.childs, rights, sender);
+    }
+
+    /// Removes all rendered children from the host node.
+    pub fn detach(self) {
+        if let Some(host) = self.host {
+            for child in self.childs {
+                child.remove(&host);
+            }
+            virtual_dom::unregister_root(&host);
+        }
+    }
+}
+
+impl<MSG> fmt::Debug for VPortal<MSG> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "VPortal {{ selector: {} }}", self.selector)
+    }
+}
+
+impl<MSG> PartialEq for VPortal<MSG> {
+    fn eq(&self, other: &VPortal<MSG>) -> bool {
+        self.selector == other.selector && self.childs == other.childs
+    }
+}
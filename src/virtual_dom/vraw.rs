@@ -0,0 +1,149 @@
+//! This module contains the implementation of a raw HTML node `VRaw`.
+
+use std::fmt;
+use std::borrow::Cow;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use std::cell::RefCell;
+use std::cmp::PartialEq;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::Value;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::web::Element;
+
+/// A hook which cleans up an HTML string before it will be inserted into the DOM.
+/// Use a sanitizer for any HTML which wasn't produced by your app itself,
+/// because scripts and event handler attributes inside it will be executed.
+pub trait Sanitizer {
+    /// Returns a safe version of the `html`.
+    fn sanitize(&self, html: &str) -> String;
+}
+
+impl<F: Fn(&str) -> String> Sanitizer for F {
+    fn sanitize(&self, html: &str) -> String {
+        self(html)
+    }
+}
+
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+thread_local! {
+    // The Trusted Types policy of raw nodes, it's `Null` if a browser doesn't support them
+    static POLICY: RefCell<Option<Value>> = RefCell::new(None);
+}
+
+/// A node which inserts a pre-rendered HTML string into the tree.
+/// Because the string could contain any amount of nodes, it's rendered
+/// inside of a wrapper element (`div` by default) as its `innerHTML`.
+///
+/// If a browser supports Trusted Types, the HTML is inserted with the `yew`
+/// policy, so a page could allow only it with
+/// `require-trusted-types-for 'script'; trusted-types yew`.
+pub struct VRaw {
+    tag: Cow<'static, str>,
+    html: String,
+    sanitized: bool,
+}
+
+impl VRaw {
+    /// Creates a node from an HTML string which is inserted as is.
+    /// Use it only for trusted content, like a markup produced by your own code.
+    pub fn trusted<T: Into<String>>(html: T) -> Self {
+        VRaw {
+            tag: "div".into(),
+            html: html.into(),
+            sanitized: false,
+        }
+    }
+
+    /// Creates a node from an HTML string cleaned up by the `sanitizer`.
+    pub fn sanitized<S: Sanitizer + ?Sized>(html: &str, sanitizer: &S) -> Self {
+        VRaw {
+            sanitized: true,
+            ..VRaw::trusted(sanitizer.sanitize(html))
+        }
+    }
+
+    /// Sets a tag of the wrapper element.
+    pub fn with_tag<T: Into<Cow<'static, str>>>(mut self, tag: T) -> Self {
+        self.tag = tag.into();
+        self
+    }
+
+    /// Returns a tag of the wrapper element.
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Returns the HTML string of the node.
+    pub fn html(&self) -> &str {
+        &self.html
+    }
+
+    /// Returns `true` if the node was created with a sanitizer.
+    pub fn is_sanitized(&self) -> bool {
+        self.sanitized
+    }
+
+    /// Returns the HTML which is inserted. With the `sanitize` feature it's
+    /// cleaned if it wasn't sanitized and `sanitize::enforce` was called.
+    pub(crate) fn inserted_html(&self) -> Cow<'_, str> {
+        #[cfg(feature = "sanitize")]
+        {
+            if !self.sanitized && ::sanitize::is_enforced() {
+                return Cow::Owned(::sanitize::clean(&self.html));
+            }
+        }
+        Cow::Borrowed(&self.html)
+    }
+
+    /// Sets content of the wrapper element, but only if it was changed.
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    pub fn render(&mut self, subject: &Element, opposite: Option<Self>) {
+        let changed = opposite.map(|opposite| opposite.html != self.html).unwrap_or(true);
+        if changed {
+            let policy = policy();
+            let html = self.inserted_html();
+            let html = html.as_ref();
+            js! { @(no_return)
+                var policy = @{policy};
+                @{subject}.innerHTML = policy ? policy.createHTML(@{html}) : @{html};
+            }
+        }
+    }
+}
+
+/// Returns the Trusted Types policy of raw nodes. It's kept by Rust, so
+/// other scripts can't create HTML with it.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+fn policy() -> Value {
+    POLICY.with(|policy| {
+        policy.borrow_mut().get_or_insert_with(|| {
+            let policy: Value = js! {
+                if (!window.trustedTypes || !window.trustedTypes.createPolicy) {
+                    return null;
+                }
+                try {
+                    return window.trustedTypes.createPolicy("yew", {
+                        createHTML: function(html) { return html; },
+                    });
+                } catch (error) {
+                    // The policy of the page doesn't allow the name
+                    console.error("can't create the `yew` Trusted Types policy: " + error);
+                    return null;
+                }
+            };
+            policy
+        }).clone()
+    })
+}
+
+impl fmt::Debug for VRaw {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "VRaw {{ tag: {}, html: {} }}", self.tag, self.html)
+    }
+}
+
+impl PartialEq for VRaw {
+    fn eq(&self, other: &VRaw) -> bool {
+        self.tag == other.tag && self.html == other.html
+    }
+}
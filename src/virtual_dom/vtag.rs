@@ -1,14 +1,24 @@
 //! This module contains the implementation of a virtual element node `VTag`.
 
 use std::fmt;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use std::mem;
 use std::borrow::Cow;
-use std::collections::HashSet;
 use std::cmp::PartialEq;
-use stdweb::web::{IElement, Element, EventListenerHandle};
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use stdweb::web::{IElement, Element};
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
 use stdweb::web::html_element::InputElement;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
 use stdweb::unstable::TryFrom;
-use virtual_dom::{Listener, Listeners, Classes, Attributes, Patch, VNode};
+use stdweb::Value;
+use virtual_dom::{Listener, Listeners, Classes, Attributes, Properties, Property, Patch, VNode, NodeRef};
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+use virtual_dom::ListenerHandle;
+use virtual_dom::pool;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
 use html::AppSender;
+use classes::Classes as ClassList;
 
 /// A type for a virtual
 /// [Element](https://developer.mozilla.org/en-US/docs/Web/API/Element)
@@ -20,6 +30,9 @@ pub struct VTag<MSG> {
     pub listeners: Listeners<MSG>,
     /// List of attributes.
     pub attributes: Attributes,
+    /// JS properties of the element. Custom elements receive rich data
+    /// (arrays, objects, numbers) through properties instead of attributes.
+    pub properties: Properties,
     /// The list of children nodes. Which also could have own children.
     pub childs: Vec<VNode<MSG>>,
     /// List of attached classes.
@@ -37,29 +50,39 @@ pub struct VTag<MSG> {
     /// in original HTML it sets `defaultChecked` value of `InputElement`, but for reactive
     /// frameworks it's more useful to control `checked` value of an `InputElement`.
     pub checked: bool,
+    /// A reference which gets the element when the tag is rendered.
+    pub node_ref: Option<NodeRef>,
     /// _Service field_. Keeps handler for attached listeners
     /// to have an opportunity to drop them later.
-    captured: Vec<EventListenerHandle>,
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    captured: Vec<ListenerHandle>,
 }
 
-impl<MSG> VTag<MSG> {
+impl<MSG: 'static> VTag<MSG> {
     /// Creates a new `VTag` instance with `tag` name (cannot be changed later in DOM).
     pub fn new<S: Into<Cow<'static, str>>>(tag: S) -> Self {
+        let (childs, listeners) = pool::vectors();
         VTag {
             tag: tag.into(),
-            classes: Classes::new(),
-            attributes: Attributes::new(),
-            listeners: Vec::new(),
+            classes: pool::classes(),
+            attributes: pool::attributes(),
+            properties: pool::properties(),
+            listeners,
+            #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
             captured: Vec::new(),
-            childs: Vec::new(),
+            childs,
             value: None,
             kind: None,
             // In HTML node `checked` attribute sets `defaultChecked` parameter,
             // but we use own field to control real `checked` parameter
             checked: false,
+            node_ref: None,
         }
     }
 
+}
+
+impl<MSG> VTag<MSG> {
     /// Returns tag of an `Element`. In HTML tags are always uppercase.
     pub fn tag(&self) -> &str {
         &self.tag
@@ -74,8 +97,7 @@ impl<MSG> VTag<MSG> {
     /// [Element.classList.add](https://developer.mozilla.org/en-US/docs/Web/API/Element/classList)
     /// call later.
     pub fn add_classes(&mut self, class: &str) {
-        let class = class.trim();
-        if !class.is_empty() {
+        for class in class.split_whitespace() {
             self.classes.insert(class.into());
         }
     }
@@ -100,13 +122,27 @@ impl<MSG> VTag<MSG> {
         self.checked = value;
     }
 
+    /// Sets a reference which gets the element of the tag.
+    pub fn set_node_ref(&mut self, node_ref: NodeRef) {
+        self.node_ref = Some(node_ref);
+    }
+
     /// Adds attribute to a virtual node. Not every attribute works when
     /// it set as attribute. We use workarounds for:
     /// `class`, `type/kind`, `value` and `checked`.
+    /// Boolean attributes with the `false` value are removed, and `selected`
+    /// and `muted` are set as DOM properties too, because the attributes
+    /// set only default states of elements.
     pub fn add_attribute<T: ToString>(&mut self, name: &str, value: T) {
         self.attributes.insert(name.to_owned(), value.to_string());
     }
 
+    /// Sets a JS property of the element. It's compared with the value
+    /// of the previous render and assigned only when it changed.
+    pub fn add_property<T: Into<Value>>(&mut self, name: &str, value: T) {
+        self.properties.insert(name.to_owned(), Property::from(value.into()));
+    }
+
     /// Adds new listener to the node.
     /// It's boxed because we want to keep it in a single list.
     /// Lates `Listener::attach` called to attach actual listener to a DOM node.
@@ -114,121 +150,179 @@ impl<MSG> VTag<MSG> {
         self.listeners.push(listener);
     }
 
-    fn soakup_classes(&mut self, ancestor: &mut Option<Self>) -> Vec<Patch<String, ()>> {
-        let mut changes = Vec::new();
-        if let &mut Some(ref ancestor) = ancestor {
-            let to_add = self.classes.difference(&ancestor.classes).map(|class| {
-                Patch::Add(class.to_owned(), ())
-            });
-            changes.extend(to_add);
-            let to_remove = ancestor.classes.difference(&self.classes).map(|class| {
-                Patch::Remove(class.to_owned())
-            });
-            changes.extend(to_remove);
-        } else {
-            // Add everything
-            let to_add = self.classes.iter().map(|class| Patch::Add(class.to_owned(), ()));
-            changes.extend(to_add);
-        }
-        changes
-    }
-
-    fn soakup_attributes(&mut self, ancestor: &mut Option<Self>) -> Vec<Patch<String, String>> {
-        let mut changes = Vec::new();
-        if let &mut Some(ref mut ancestor) = ancestor {
-            let left_keys = self.attributes.keys().collect::<HashSet<_>>();
-            let right_keys = ancestor.attributes.keys().collect::<HashSet<_>>();
-            let to_add = left_keys.difference(&right_keys).map(|key| {
-                let value = self.attributes.get(*key).expect("attribute of vtag lost");
-                Patch::Add(key.to_string(), value.to_string())
-            });
-            changes.extend(to_add);
-            for key in left_keys.intersection(&right_keys) {
-                let left_value = self.attributes.get(*key).expect("attribute of the left side lost");
-                let right_value = ancestor.attributes.get(*key).expect("attribute of the right side lost");
-                if left_value != right_value {
-                    let mutator = Patch::Replace(key.to_string(), left_value.to_string());
-                    changes.push(mutator);
+    /// Adds an attribute and returns the tag to chain calls:
+    ///
+    /// ```rust,ignore
+    /// let link: VTag<Msg> = VTag::new("a")
+    ///     .attr("href", "/about")
+    ///     .class("nav-link active")
+    ///     .child("About");
+    /// ```
+    pub fn attr<T: ToString>(mut self, name: &str, value: T) -> Self {
+        self.add_attribute(name, value);
+        self
+    }
+
+    /// Adds classes separated by whitespace and returns the tag.
+    pub fn class<T: Into<ClassList>>(mut self, classes: T) -> Self {
+        let classes = classes.into();
+        for class in classes.iter() {
+            self.classes.insert(class.to_owned());
+        }
+        self
+    }
+
+    /// Sets a JS property and returns the tag.
+    pub fn property<T: Into<Value>>(mut self, name: &str, value: T) -> Self {
+        self.add_property(name, value);
+        self
+    }
+
+    /// Sets `value` of an element and returns the tag.
+    pub fn value<T: ToString>(mut self, value: T) -> Self {
+        self.set_value(&value);
+        self
+    }
+
+    /// Sets `type` of an element and returns the tag.
+    pub fn kind<T: ToString>(mut self, kind: T) -> Self {
+        self.set_kind(kind);
+        self
+    }
+
+    /// Sets `checked` state of an element and returns the tag.
+    pub fn checked(mut self, value: bool) -> Self {
+        self.set_checked(value);
+        self
+    }
+
+    /// Sets a reference to the element and returns the tag.
+    pub fn node_ref(mut self, node_ref: &NodeRef) -> Self {
+        self.set_node_ref(node_ref.clone());
+        self
+    }
+
+    /// Adds a listener and returns the tag.
+    pub fn listener<L: Listener<MSG> + 'static>(mut self, listener: L) -> Self {
+        self.add_listener(Box::new(listener));
+        self
+    }
+
+    /// Adds a child (a tag, a text, a component or anything which
+    /// converts to a text) and returns the tag.
+    pub fn child<T: Into<VNode<MSG>>>(mut self, child: T) -> Self {
+        self.add_child(child.into());
+        self
+    }
+
+    /// Adds children and returns the tag.
+    pub fn children<I>(mut self, children: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<VNode<MSG>>,
+    {
+        self.childs.extend(children.into_iter().map(Into::into));
+        self
+    }
+
+    /// Computes changes which turn the element of the `ancestor` into this one.
+    /// Changes are sorted by names, so the result could be compared in tests.
+    /// Values of `input` and `textarea` elements are compared with actual values
+    /// of elements when patches are applied, so they aren't included.
+    pub fn diff(&self, ancestor: Option<&Self>) -> Vec<Patch> {
+        let mut patches = Vec::new();
+
+        let empty = Classes::new();
+        let old_classes = ancestor.map(|ancestor| &ancestor.classes).unwrap_or(&empty);
+        let mut classes: Vec<Patch> = self.classes.difference(old_classes)
+            .map(|class| Patch::AddClass(class.to_owned()))
+            .chain(old_classes.difference(&self.classes).map(|class| Patch::RemoveClass(class.to_owned())))
+            .collect();
+        classes.sort_by(|a, b| a.name().cmp(b.name()));
+        patches.extend(classes);
+
+        let mut attributes = Vec::new();
+        for (name, value) in self.attributes.iter() {
+            let old_value = ancestor.and_then(|ancestor| ancestor.attributes.get(name));
+            if old_value == Some(value) {
+                continue;
+            }
+            if value == "false" && BOOLEAN_ATTRIBUTES.contains(&name.as_str()) {
+                // Any value of a boolean attribute enables it
+                attributes.push(Patch::RemoveAttribute(name.to_owned()));
+            } else {
+                attributes.push(Patch::SetAttribute(name.to_owned(), value.to_owned()));
+            }
+        }
+        if let Some(ancestor) = ancestor {
+            for name in ancestor.attributes.keys() {
+                if !self.attributes.contains_key(name) {
+                    attributes.push(Patch::RemoveAttribute(name.to_owned()));
                 }
             }
-            let to_remove = right_keys.difference(&left_keys).map(|key| {
-                Patch::Remove(key.to_string())
-            });
-            changes.extend(to_remove);
-        } else {
-            for (key, value) in self.attributes.iter() {
-                let mutator = Patch::Add(key.to_string(), value.to_string());
-                changes.push(mutator);
+        }
+        attributes.sort_by(|a, b| a.name().cmp(b.name()));
+        patches.extend(attributes);
+
+        let mut properties = Vec::new();
+        for (name, value) in self.properties.iter() {
+            let old_value = ancestor.and_then(|ancestor| ancestor.properties.get(name));
+            if old_value != Some(value) {
+                properties.push(Patch::SetProperty(name.to_owned(), Value::clone(value)));
             }
         }
-        changes
-    }
-
-    fn soakup_kind(&mut self, ancestor: &mut Option<Self>) -> Option<Patch<String, ()>> {
-        match (
-            &self.kind,
-            ancestor.as_mut().and_then(|anc| anc.kind.take()),
-        ) {
-            (&Some(ref left), Some(ref right)) => {
-                if left != right {
-                    Some(Patch::Replace(left.to_string(), ()))
-                } else {
-                    None
+        if let Some(ancestor) = ancestor {
+            for name in ancestor.properties.keys() {
+                if !self.properties.contains_key(name) {
+                    properties.push(Patch::SetProperty(name.to_owned(), Value::Undefined));
                 }
             }
-            (&Some(ref left), None) => Some(Patch::Add(left.to_string(), ())),
-            (&None, Some(right)) => Some(Patch::Remove(right)),
-            (&None, None) => None,
         }
-    }
+        properties.sort_by(|a, b| a.name().cmp(b.name()));
+        patches.extend(properties);
 
-    fn soakup_value(&mut self, ancestor: &mut Option<Self>) -> Option<Patch<String, ()>> {
-        match (
-            &self.value,
-            ancestor.as_mut().and_then(|anc| anc.value.take()),
-        ) {
-            (&Some(ref left), Some(ref right)) => {
-                if left != right {
-                    Some(Patch::Replace(left.to_string(), ()))
-                } else {
-                    None
-                }
+        let old_kind = ancestor.and_then(|ancestor| ancestor.kind.as_ref());
+        if self.kind.as_ref() != old_kind {
+            let kind = self.kind.clone().unwrap_or_default();
+            patches.push(Patch::SetKind(kind));
+        }
+
+        let old_value = ancestor.and_then(|ancestor| ancestor.value.as_ref());
+        if self.value.as_ref() != old_value && self.tag != "select" {
+            let controlled = self.tag == "input" || self.tag == "textarea";
+            match self.value {
+                Some(_) if controlled => { }
+                Some(ref value) => patches.push(Patch::SetValue(value.to_owned())),
+                None => patches.push(Patch::RemoveValue),
             }
-            (&Some(ref left), None) => Some(Patch::Add(left.to_string(), ())),
-            (&None, Some(right)) => Some(Patch::Remove(right)),
-            (&None, None) => None,
         }
+
+        patches
     }
 }
 
-impl<MSG> VTag<MSG> {
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+impl<MSG: 'static> VTag<MSG> {
     /// Renders virtual tag over DOM `Element`, but it also compares this with an opposite `VTag`
     /// to compute what to pach in the actual DOM nodes.
-    pub fn render(&mut self, subject: &Element, mut opposite: Option<Self>, sender: AppSender<MSG>) {
-        let changes = self.soakup_classes(&mut opposite);
-        for change in changes {
-            let list = subject.class_list();
-            match change {
-                Patch::Add(class, _) |
-                Patch::Replace(class, _) => {
-                    list.add(&class);
-                }
-                Patch::Remove(class) => {
-                    list.remove(&class);
-                }
-            }
+    pub fn render(&mut self, subject: &Element, opposite: Option<Self>, sender: AppSender<MSG>) {
+        let patches = self.diff(opposite.as_ref());
+        let input = InputElement::try_from(subject.clone()).ok();
+        for patch in patches {
+            self.apply_patch(subject, input.as_ref(), patch);
         }
 
-        let changes = self.soakup_attributes(&mut opposite);
-        for change in changes {
-            match change {
-                Patch::Add(key, value) |
-                Patch::Replace(key, value) => {
-                    set_attribute(&subject, &key, &value);
-                }
-                Patch::Remove(key) => {
-                    remove_attribute(&subject, &key);
-                }
+        // These attributes set a default state only and a user could
+        // change the actual state. Set properties every time like `checked`.
+        for name in DEFAULT_STATE_ATTRIBUTES {
+            let used = self.attributes.contains_key(*name) || opposite.as_ref()
+                .map(|opposite| opposite.attributes.contains_key(*name))
+                .unwrap_or(false);
+            if used {
+                let enabled = self.attributes.get(*name)
+                    .map(|value| value != "false")
+                    .unwrap_or(false);
+                set_property(subject, name, &Value::Bool(enabled));
             }
         }
 
@@ -236,48 +330,126 @@ impl<MSG> VTag<MSG> {
         // I override behavior of attributes to make it more clear
         // and useful in templates. For example I interpret `checked`
         // attribute as `checked` parameter, not `defaultChecked` as browsers do
-        if let Ok(input) = InputElement::try_from(subject.clone()) {
-            if let Some(change) = self.soakup_kind(&mut opposite) {
-                match change {
-                    Patch::Add(kind, _) |
-                    Patch::Replace(kind, _) => {
-                        input.set_kind(&kind);
-                    }
-                    Patch::Remove(_) => {
-                        input.set_kind("");
-                    }
-                }
-            }
-
-            if let Some(change) = self.soakup_value(&mut opposite) {
-                match change {
-                    Patch::Add(kind, _) |
-                    Patch::Replace(kind, _) => {
-                        input.set_value(&kind);
-                    }
-                    Patch::Remove(_) => {
-                        input.set_value("");
-                    }
-                }
-            }
-
+        if let Some(ref input) = input {
             // IMPORTANT! This parameters have to be set every time
             // to prevent strange behaviour in browser when DOM changed
-            set_checked(&input, self.checked);
+            set_checked(input, self.checked);
         }
 
-        // Every render it removes all listeners and attach it back later
-        // TODO Compare references of handler to do listeners update better
-        if let Some(mut opposite) = opposite {
-            for handle in opposite.captured.drain(..) {
-                handle.remove();
+        // Elements with a value are controlled: the value compared with the actual
+        // value of the element (not the previous render), because a user could change it.
+        // `select` gets its value later, when its options are rendered.
+        if self.tag == "input" || self.tag == "textarea" {
+            self.render_value(subject);
+        }
+
+        // Listeners are attached again every render, because handlers can't be
+        // compared, but a listener of the same kind at the same position reuses
+        // the DOM listener of the previous render and only replaces its handler.
+        if let Some(last) = opposite.as_ref().and_then(|opposite| opposite.node_ref.as_ref()) {
+            if self.node_ref.as_ref() != Some(last) {
+                last.set(None);
             }
         }
+        if let Some(ref node_ref) = self.node_ref {
+            node_ref.set(Some(subject.clone()));
+        }
 
+        let mut previous = opposite
+            .map(|mut opposite| {
+                let captured = mem::take(&mut opposite.captured);
+                opposite.recycle();
+                captured
+            })
+            .unwrap_or_default()
+            .into_iter();
         for mut listener in self.listeners.drain(..) {
-            let handle = listener.attach(&subject, sender.clone());
+            let handle = ListenerHandle::reattach(&mut *listener, subject, sender.clone(), previous.next());
             self.captured.push(handle);
         }
+        for handle in previous {
+            handle.remove();
+        }
+    }
+
+    fn apply_patch(&self, subject: &Element, input: Option<&InputElement>, patch: Patch) {
+        match patch {
+            Patch::AddClass(class) => {
+                subject.class_list().add(&class);
+            }
+            Patch::RemoveClass(class) => {
+                subject.class_list().remove(&class);
+            }
+            Patch::SetAttribute(name, value) => {
+                set_attribute(subject, &name, &value);
+            }
+            Patch::RemoveAttribute(name) => {
+                remove_attribute(subject, &name);
+            }
+            Patch::SetProperty(name, value) => {
+                set_property(subject, &name, &value);
+            }
+            Patch::SetKind(kind) => {
+                if let Some(input) = input {
+                    input.set_kind(&kind);
+                }
+            }
+            Patch::SetValue(value) => {
+                set_attribute(subject, "value", &value);
+            }
+            Patch::RemoveValue => {
+                if self.tag == "input" || self.tag == "textarea" {
+                    set_value(subject, "");
+                } else {
+                    remove_attribute(subject, "value");
+                }
+            }
+        }
+    }
+}
+
+impl<MSG: 'static> VTag<MSG> {
+    /// Returns maps of attributes, classes and properties and vectors of
+    /// children and listeners of the tag and its children to a pool which
+    /// new tags take them from. Renders recycle tags of previous frames,
+    /// so views built every frame allocate less.
+    pub fn recycle(self) {
+        let VTag { attributes, classes, properties, mut childs, listeners, .. } = self;
+        pool::put(attributes, classes, properties);
+        for child in childs.drain(..) {
+            if let VNode::VTag { vtag, .. } = child {
+                vtag.recycle();
+            }
+        }
+        pool::put_vectors(childs, listeners);
+    }
+}
+
+impl<MSG: 'static> VTag<MSG> {
+
+    /// Removes attached listeners and cleans up children which were rendered
+    /// outside of this element (portals, components). Called when the node is removed.
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    pub(crate) fn detach(&mut self) {
+        if let Some(node_ref) = self.node_ref.take() {
+            node_ref.set(None);
+        }
+        for handle in self.captured.drain(..) {
+            handle.remove();
+        }
+        for child in self.childs.drain(..) {
+            child.into_reference();
+        }
+    }
+
+    /// Sets `value` property of an element if it differs from the actual one.
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+    pub(crate) fn render_value(&self, subject: &Element) {
+        if let Some(ref value) = self.value {
+            if get_value(subject).as_ref() != Some(value) {
+                set_value(subject, value);
+            }
+        }
     }
 }
 
@@ -289,16 +461,73 @@ impl<MSG> fmt::Debug for VTag<MSG> {
 
 /// `stdweb` doesn't have methods to work with attributes now.
 /// this is workaround from: https://github.com/koute/stdweb/issues/16#issuecomment-325195854
+/// Attributes with `xlink:` prefix (legacy SVG links) are set with their namespace.
+/// With the `csp` feature `style` is set with CSSOM, which a policy allows.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
 fn set_attribute(element: &Element, name: &str, value: &str) {
-    js!( @(no_return) @{element}.setAttribute( @{name}, @{value} ); );
+    #[cfg(feature = "csp")]
+    {
+        ::csp::check_attribute(name, value);
+        if name == "style" {
+            js!( @(no_return) @{element}.style.cssText = @{value}; );
+            return;
+        }
+    }
+    if name.starts_with("xlink:") {
+        js!( @(no_return) @{element}.setAttributeNS( @{XLINK_NAMESPACE}, @{name}, @{value} ); );
+    } else {
+        js!( @(no_return) @{element}.setAttribute( @{name}, @{value} ); );
+    }
 }
 
 /// Removes attribute from a element by name.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
 fn remove_attribute(element: &Element, name: &str) {
-    js!( @(no_return) @{element}.removeAttribute( @{name} ); );
+    if let Some(local_name) = name.strip_prefix("xlink:") {
+        js!( @(no_return) @{element}.removeAttributeNS( @{XLINK_NAMESPACE}, @{local_name} ); );
+    } else {
+        js!( @(no_return) @{element}.removeAttribute( @{name} ); );
+    }
+}
+
+/// Attributes which are enabled by presence. The `false` value removes them.
+pub(crate) const BOOLEAN_ATTRIBUTES: &[&str] = &[
+    "allowfullscreen", "async", "autofocus", "autoplay", "checked", "controls",
+    "default", "defer", "disabled", "formnovalidate", "hidden", "ismap", "loop",
+    "multiple", "muted", "nomodule", "novalidate", "open", "readonly", "required",
+    "reversed", "selected",
+];
+
+/// Attributes which set only a default state of an element. The actual
+/// state is kept by a DOM property with the same name.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+const DEFAULT_STATE_ATTRIBUTES: &[&str] = &["selected", "muted"];
+
+/// Namespace of `xlink:` attributes.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+
+/// Sets a JS property of an element.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+fn set_property(element: &Element, name: &str, value: &Value) {
+    js!( @(no_return) @{element}[@{name}] = @{value}; );
+}
+
+/// Returns `value` property of an element (`input`, `textarea` or `select`).
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+fn get_value(element: &Element) -> Option<String> {
+    let value = js!( return @{element}.value; );
+    value.into_string()
+}
+
+/// Sets `value` property of an element (`input`, `textarea` or `select`).
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
+fn set_value(element: &Element, value: &str) {
+    js!( @(no_return) @{element}.value = @{value}; );
 }
 
 /// Set `checked` value for the `InputElement`.
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
 fn set_checked(input: &InputElement, value: bool) {
     js!( @(no_return) @{input}.checked = @{value}; );
 }
@@ -338,6 +567,10 @@ impl<MSG> PartialEq for VTag<MSG> {
             return false;
         }
 
+        if self.properties != other.properties {
+            return false;
+        }
+
         if self.classes != other.classes {
             return false;
         }
@@ -2,6 +2,7 @@
 
 use std::fmt;
 use std::cmp::PartialEq;
+#[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
 use stdweb::web::{INode, TextNode};
 
 /// A type for a virtual
@@ -20,6 +21,7 @@ impl VText {
 
     /// Renders virtual node over existent `TextNode`, but
     /// only if value of text had changed.
+    #[cfg(any(target_arch = "wasm32", target_os = "emscripten"))]
     pub fn render(&mut self, subject: &TextNode, opposite: Option<Self>) {
         if let Some(opposite) = opposite {
             if self.text != opposite.text {
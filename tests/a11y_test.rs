@@ -0,0 +1,70 @@
+#![cfg(feature = "a11y")]
+
+#[macro_use]
+extern crate yew;
+
+use yew::a11y::{self, Issue, Rule};
+use yew::html::Html;
+use yew::virtual_dom::VNode;
+
+fn audit(html: Html<()>) -> Vec<Issue> {
+    a11y::audit(&VNode::from(html))
+}
+
+fn issue(rule: Rule, element: &str) -> Issue {
+    Issue { rule, element: element.into() }
+}
+
+#[test]
+fn it_accepts_accessible_markup() {
+    let issues = audit(html! {
+        <form>
+            <img src="logo.png", alt="Yew", />
+            <img src="line.png", alt="", />
+            <label for="name",>{ "Name" }</label>
+            <input id="name", type="text", />
+            <label>{ "Age" }<input type="number", /></label>
+            <textarea aria-label="Notes",></textarea>
+            <input type="hidden", value="1", />
+            <input type="submit", />
+            <button>{ "Save" }</button>
+            <button title="Close",>{ "×" }</button>
+            <button><img src="trash.png", alt="Remove", /></button>
+        </form>
+    });
+    assert_eq!(issues, vec![]);
+}
+
+#[test]
+fn it_finds_issues() {
+    let issues = audit(html! {
+        <div>
+            <img class=("icon", "big"), src="logo.png", />
+            <button id="close",><span aria-hidden="true",>{ "×" }</span></button>
+            <input type="button", />
+            <input id="search", type="text", />
+            <select></select>
+            <span id="close",></span>
+        </div>
+    });
+    assert_eq!(issues, vec![
+        issue(Rule::MissingAlt, "img.big.icon"),
+        issue(Rule::UnnamedButton, "button#close"),
+        issue(Rule::UnnamedButton, "input"),
+        issue(Rule::UnlabeledField, "input#search"),
+        issue(Rule::UnlabeledField, "select"),
+        issue(Rule::DuplicateId, "#close"),
+    ]);
+    assert_eq!(issues[0].to_string(), "image without alt text: img.big.icon");
+}
+
+#[test]
+fn it_matches_labels_after_fields() {
+    let issues = audit(html! {
+        <div>
+            <input id="email", type="email", />
+            <p><label for="email",>{ "Email" }</label></p>
+        </div>
+    });
+    assert!(issues.is_empty(), "{:?}", issues);
+}
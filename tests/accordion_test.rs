@@ -0,0 +1,84 @@
+#![cfg(feature = "testing")]
+
+extern crate yew;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use yew::callback::Callback;
+use yew::components::accordion::{Accordion, AccordionProps};
+use yew::components::disclosure::{Disclosure, DisclosureProps};
+use yew::testing::TestRenderer;
+
+fn expanded(accordion: &TestRenderer<Accordion>) -> Vec<&str> {
+    accordion.root().find_all("button").iter().map(|button| button.attribute("aria-expanded").unwrap()).collect()
+}
+
+#[test]
+fn it_expands_sections() {
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let log = changes.clone();
+    let mut accordion = TestRenderer::<Accordion>::new(AccordionProps {
+        id: "faq".into(),
+        count: 3,
+        default_expanded: vec![0],
+        onchange: Callback::from(move |expanded| log.borrow_mut().push(expanded)),
+        ..AccordionProps::default()
+    });
+    assert_eq!(expanded(&accordion), vec!["true", "false", "false"]);
+    let heading = accordion.root().find("[role=heading]").unwrap();
+    assert_eq!(heading.attribute("aria-level"), Some("3"));
+    assert_eq!(accordion.root().find("#faq-panel-1").unwrap().attribute("hidden"), Some("true"));
+    assert_eq!(accordion.root().find("#faq-panel-1").unwrap().attribute("aria-labelledby"), Some("faq-header-1"));
+
+    // Only one section is expanded
+    accordion.click("#faq-header-1");
+    assert_eq!(expanded(&accordion), vec!["false", "true", "false"]);
+    assert_ne!(accordion.root().find("#faq-panel-1").unwrap().attribute("hidden"), Some("true"));
+    accordion.click("#faq-header-1");
+    assert_eq!(expanded(&accordion), vec!["false", "false", "false"]);
+    assert_eq!(*changes.borrow(), vec![vec![1], vec![]]);
+}
+
+#[test]
+fn it_keeps_sections_of_controlled_accordions() {
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let log = changes.clone();
+    let props = AccordionProps {
+        count: 3,
+        multiple: true,
+        collapsible: false,
+        expanded: Some(vec![2]),
+        onchange: Callback::from(move |expanded| log.borrow_mut().push(expanded)),
+        ..AccordionProps::default()
+    };
+    let mut accordion = TestRenderer::<Accordion>::new(props.clone());
+    accordion.click("#accordion-header-2");
+    assert!(changes.borrow().is_empty());
+    assert_eq!(accordion.root().find("#accordion-header-2").unwrap().attribute("aria-disabled"), Some("true"));
+
+    accordion.click("#accordion-header-0");
+    assert_eq!(*changes.borrow(), vec![vec![2, 0]]);
+    assert_eq!(expanded(&accordion), vec!["false", "false", "true"]);
+    accordion.change(AccordionProps { expanded: Some(vec![2, 0]), ..props });
+    assert_eq!(expanded(&accordion), vec!["true", "false", "true"]);
+}
+
+#[test]
+fn it_toggles_disclosures() {
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let log = changes.clone();
+    let mut disclosure = TestRenderer::<Disclosure>::new(DisclosureProps {
+        id: "shipping".into(),
+        onchange: Callback::from(move |open| log.borrow_mut().push(open)),
+        ..DisclosureProps::default()
+    });
+    let button = disclosure.root().find("button").unwrap();
+    assert_eq!(button.attribute("aria-expanded"), Some("false"));
+    assert_eq!(button.attribute("aria-controls"), Some("shipping"));
+    assert_eq!(disclosure.root().find("#shipping").unwrap().attribute("hidden"), Some("true"));
+
+    disclosure.click("button");
+    assert_eq!(disclosure.root().find("button").unwrap().attribute("aria-expanded"), Some("true"));
+    assert_ne!(disclosure.root().find("#shipping").unwrap().attribute("hidden"), Some("true"));
+    assert_eq!(*changes.borrow(), vec![true]);
+}
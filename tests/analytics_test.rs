@@ -0,0 +1,113 @@
+#![cfg(feature = "testing")]
+
+extern crate serde_json;
+extern crate yew;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+use serde_json::Value;
+use yew::analytics::{self, BeaconSink, CurrentRoute, Event, EventKind, Session};
+use yew::callback::Callback;
+use yew::services::mock::{MockBeacon, MockClock, MockConnectivity, MockNetwork, MockRandom, MockStorage,
+                             MockVisibility};
+
+fn collect() -> Rc<RefCell<Vec<Event>>> {
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let sink = events.clone();
+    analytics::add_sink(Callback::from(move |event| sink.borrow_mut().push(event)));
+    events
+}
+
+fn names(data: &str) -> Vec<String> {
+    let body: Value = serde_json::from_str(data).unwrap();
+    body["events"].as_array().unwrap().iter().map(|event| event["name"].as_str().unwrap().to_owned()).collect()
+}
+
+#[test]
+fn it_enriches_tracked_events() {
+    let storage = MockStorage::new();
+    let _installed = (storage.install(), MockRandom::new().install());
+    analytics::add_middleware(Session::new());
+    analytics::add_middleware(CurrentRoute);
+    analytics::add_middleware(|event: &mut Event| event.name != "secret");
+    let events = collect();
+
+    analytics::track(Event::page_view("/posts/1"));
+    let clicked = Rc::new(RefCell::new(0));
+    let count = clicked.clone();
+    let onclick = analytics::tracked(Event::interaction("like").property("id", 1),
+                                     Callback::from(move |_: ()| *count.borrow_mut() += 1));
+    onclick.emit(());
+    analytics::track(Event::custom("secret"));
+
+    assert_eq!(*clicked.borrow(), 1);
+    let events = events.borrow();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].kind, EventKind::PageView);
+    assert_eq!(events[1].properties["route"], "/posts/1");
+    assert_eq!(events[1].properties["id"], 1);
+    assert_eq!(events[1].properties["session"], "0001020304050607");
+    // The session outlives reloads of the tab
+    assert_eq!(Session::new().id(), "0001020304050607");
+    analytics::clear();
+}
+
+#[test]
+fn it_sends_batches_with_beacons() {
+    let (clock, beacon, network) = (MockClock::new(), MockBeacon::new(), MockNetwork::new());
+    let _installed = (clock.install(), beacon.install(), network.install());
+    let _page = (MockConnectivity::new().install(), MockVisibility::new().install());
+    analytics::add_sink(BeaconSink::new("/events").batch(2, Duration::from_secs(5)));
+
+    analytics::track(Event::custom("one"));
+    assert!(beacon.sent().is_empty());
+    clock.advance(Duration::from_secs(5));
+    assert_eq!(beacon.sent().len(), 1);
+    assert_eq!(beacon.sent()[0].0, "/events");
+    assert_eq!(names(&beacon.sent()[0].1), vec!["one"]);
+
+    analytics::track(Event::custom("two"));
+    analytics::track(Event::custom("three"));
+    assert_eq!(names(&beacon.sent()[1].1), vec!["two", "three"]);
+    assert_eq!(clock.pending(), 0);
+
+    // Refused beacons are fetched and failed fetches are sent again
+    beacon.set_refused(true);
+    analytics::track(Event::custom("four"));
+    analytics::flush();
+    assert_eq!(network.requests()[0].method, "POST");
+    assert!(network.respond("/events", 503, ""));
+    beacon.set_refused(false);
+    analytics::track(Event::custom("five"));
+    analytics::flush();
+    assert_eq!(names(&beacon.sent()[2].1), vec!["four", "five"]);
+    analytics::clear();
+}
+
+#[test]
+fn it_buffers_events_while_offline() {
+    let (beacon, connectivity, page) = (MockBeacon::new(), MockConnectivity::new(), MockVisibility::new());
+    let _installed = (MockClock::new().install(), beacon.install(), connectivity.install(), page.install());
+    connectivity.set_online(false);
+    analytics::add_sink(BeaconSink::new("/events").batch(1, Duration::from_secs(1)).capacity(2));
+
+    for name in &["one", "two", "three"] {
+        analytics::track(Event::custom(*name));
+    }
+    analytics::flush();
+    assert!(beacon.sent().is_empty());
+    connectivity.set_online(true);
+    assert_eq!(beacon.sent().len(), 1);
+    assert_eq!(names(&beacon.sent()[0].1), vec!["two", "three"]);
+
+    // A hidden page sends kept events at once
+    analytics::clear();
+    analytics::add_sink(BeaconSink::new("/events"));
+    analytics::track(Event::custom("four"));
+    analytics::track(Event::custom("five"));
+    assert_eq!(beacon.sent().len(), 1);
+    page.set_hidden(true);
+    assert_eq!(names(&beacon.sent()[1].1), vec!["four", "five"]);
+    analytics::clear();
+}
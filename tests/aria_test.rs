@@ -0,0 +1,55 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use yew::aria::{Aria, Popup, Role, Tristate};
+use yew::html::Html;
+use yew::testing;
+
+#[test]
+fn it_spreads_roles_with_states() {
+    let highlighted = Some("fruit-2");
+    let view: Html<()> = html! {
+        <div>
+            <input ..Aria::combobox(true).controls("fruits").has_popup(Popup::Listbox).active_descendant(highlighted),/>
+            <ul id="fruits", role=Role::Listbox,>
+                <li ..Aria::option(false),>{ "Apple" }</li>
+                <li ..Aria::option(true).selected(false).label("Pear"),>{ "Pear" }</li>
+            </ul>
+            <span ..Aria::checkbox(Tristate::Mixed).active_descendant(None::<String>),></span>
+        </div>
+    };
+    let node = testing::render(view);
+    let input = node.find("input").unwrap();
+    assert_eq!(input.attribute("role"), Some("combobox"));
+    assert_eq!(input.attribute("aria-expanded"), Some("true"));
+    assert_eq!(input.attribute("aria-controls"), Some("fruits"));
+    assert_eq!(input.attribute("aria-haspopup"), Some("listbox"));
+    assert_eq!(input.attribute("aria-activedescendant"), Some("fruit-2"));
+    assert_eq!(node.find("ul").unwrap().attribute("role"), Some("listbox"));
+
+    let options = node.find_all("li");
+    assert_eq!(options[0].attribute("aria-selected"), Some("false"));
+    // A state which is set again keeps the last value
+    assert_eq!(options[1].attribute("aria-selected"), Some("false"));
+    assert_eq!(options[1].attribute("aria-label"), Some("Pear"));
+
+    let checkbox = node.find("span").unwrap();
+    assert_eq!(checkbox.attribute("aria-checked"), Some("mixed"));
+    assert_eq!(checkbox.attribute("aria-activedescendant"), None);
+}
+
+#[test]
+fn it_requires_states_of_ranges() {
+    let slider = Aria::slider(3.0, 0.0, 5.0).value_text("3 of 5 stars");
+    assert_eq!(slider.get("role"), Some("slider"));
+    assert_eq!(slider.get("aria-valuenow"), Some("3"));
+    assert_eq!(slider.get("aria-valuemax"), Some("5"));
+    assert_eq!(slider.get("aria-valuetext"), Some("3 of 5 stars"));
+
+    let loading = Aria::progressbar(None, 0.0, 100.0);
+    assert_eq!(loading.get("aria-valuenow"), None);
+    assert_eq!(Aria::heading(0).get("aria-level"), Some("1"));
+    assert_eq!(Aria::tab(true, "panel-1").get("aria-controls"), Some("panel-1"));
+}
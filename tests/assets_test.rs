@@ -0,0 +1,36 @@
+extern crate yew;
+
+use yew::assets::{self, Asset};
+
+const LOGO: Asset = Asset::new("images/logo.svg", b"<svg></svg>");
+const ICON: Asset = Asset::hashed("images/icon.svg", assets::content_hash(b"<svg></svg>"));
+
+#[test]
+fn it_builds_urls_with_hashes() {
+    let hash = LOGO.hash();
+    assert_eq!(hash.len(), 8);
+    assert_ne!(hash, Asset::new("images/logo.svg", b"<svg />").hash());
+    assert_eq!(LOGO.url(), format!("/images/logo.svg?v={}", hash));
+
+    assets::set_base("https://cdn.example.com/app");
+    assert_eq!(LOGO.url(), format!("https://cdn.example.com/app/images/logo.svg?v={}", hash));
+    assets::set_base("/");
+}
+
+#[test]
+fn it_hashes_assets_at_build_time() {
+    assert_eq!(ICON.hash(), LOGO.hash());
+    assert_eq!(ICON.url(), format!("/images/icon.svg?v={}", LOGO.hash()));
+    assert_eq!(ICON.bytes(), None);
+    assert_eq!(ICON.text(), None);
+    assert_eq!(ICON.data_url(), None);
+}
+
+#[test]
+fn it_inlines_assets() {
+    assert_eq!(LOGO.mime(), "image/svg+xml");
+    assert_eq!(LOGO.text(), Some("<svg></svg>"));
+    assert_eq!(LOGO.data_url().unwrap(), "data:image/svg+xml;base64,PHN2Zz48L3N2Zz4=");
+    assert_eq!(Asset::new("fonts/Icons.WOFF2", b"ab").data_url().unwrap(), "data:font/woff2;base64,YWI=");
+    assert_eq!(Asset::new("data.bin", &[0xff]).mime(), "application/octet-stream");
+}
@@ -0,0 +1,121 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use std::time::{Duration, UNIX_EPOCH};
+use yew::auth::{self, AuthConfig, AuthEvent, AuthHandle, AuthService, Session};
+use yew::format::{Json, Nothing};
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::services::fetch::{FetchHandle, FetchService, Request, Response};
+use yew::services::mock::{MockClock, MockIntl, MockNetwork, MockStorage};
+use yew::services::storage::{Scope, StorageService};
+use yew::services::websocket::{WebSocketHandle, WebSocketService};
+use yew::testing::TestRenderer;
+
+struct Account {
+    events: Vec<AuthEvent>,
+    _auth: AuthHandle,
+}
+
+impl Component for Account {
+    type Msg = AuthEvent;
+    type Properties = ();
+
+    fn create(_: (), sender: AppSender<AuthEvent>) -> Self {
+        Account { events: Vec::new(), _auth: AuthService::new(sender).listen(|event| event) }
+    }
+
+    fn update(&mut self, event: AuthEvent) -> ShouldRender {
+        self.events.push(event);
+        true
+    }
+
+    fn view(&self) -> Html<AuthEvent> {
+        html! {
+            <p>{ if auth::is_logged_in() { "Log out" } else { "Log in" } }</p>
+        }
+    }
+}
+
+fn config() -> AuthConfig {
+    AuthConfig::new()
+        .attach_to("wss://api.example/")
+        .refresh(Duration::from_secs(10), |session: &Session| {
+            Request::post("/auth/refresh").body(session.refresh_token.clone()).unwrap()
+        }, |response: Response<Result<String, String>>| {
+            let token = response.into_body().ok().filter(|token| !token.is_empty())?;
+            Some(Session::new(token).expires_in(Duration::from_secs(60)))
+        })
+}
+
+fn fetch(service: &mut FetchService<AuthEvent>, uri: &str) -> FetchHandle {
+    service.fetch(Request::get(uri).body(Nothing).unwrap(), |_: Response<Nothing>| -> AuthEvent {
+        unreachable!("requests of the test aren't answered")
+    })
+}
+
+#[test]
+fn it_attaches_and_refreshes_sessions() {
+    let (network, clock, storage, intl) = (MockNetwork::new(), MockClock::new(), MockStorage::new(), MockIntl::new());
+    let _installed = (network.install(), clock.install(), storage.install(), intl.install());
+    auth::configure(config());
+    let mut account = TestRenderer::<Account>::new(());
+    auth::login(Session::new("abc").refresh_token("r1").expires_in(Duration::from_secs(60)));
+    account.flush();
+    assert_eq!(account.root().find("p").unwrap().text(), "Log out");
+
+    // Tokens go to the origin and to the prefixes only
+    let mut service = FetchService::new(account.sender());
+    let _requests = (fetch(&mut service, "/me"), fetch(&mut service, "https://cdn.example/logo.png"));
+    let requests = network.requests();
+    assert!(requests[0].headers.contains(&("authorization".into(), "Bearer abc".into())));
+    assert!(requests[1].headers.is_empty());
+    let _socket: WebSocketHandle = WebSocketService::new(account.sender())
+        .connect("wss://api.example/live", |_: Nothing| AuthEvent::LoggedIn, |_| AuthEvent::LoggedIn);
+    assert!(network.socket("wss://api.example/live?token=abc").is_some());
+    let Json(stored) = StorageService::new(Scope::Local).restore::<Json<Result<Session, ()>>>("session");
+    assert_eq!(stored.unwrap().token, "abc");
+
+    // A session is refreshed before it expires and keeps the refresh token
+    intl.set_now(UNIX_EPOCH + Duration::from_secs(50));
+    clock.advance(Duration::from_secs(50));
+    assert_eq!(network.requests().last().unwrap().body, Some("r1".into()));
+    assert!(network.respond("/auth/refresh", 200, "def"));
+    assert_eq!(auth::token(), Some("def".into()));
+    assert_eq!(auth::session().unwrap().refresh_token, Some("r1".into()));
+
+    intl.set_now(UNIX_EPOCH + Duration::from_secs(100));
+    clock.advance(Duration::from_secs(50));
+    assert!(network.respond("/auth/refresh", 401, ""));
+    account.flush();
+    assert_eq!(auth::token(), None);
+    assert_eq!(account.component().events, vec![AuthEvent::LoggedIn, AuthEvent::Refreshed, AuthEvent::Expired]);
+    assert_eq!(account.root().find("p").unwrap().text(), "Log in");
+    assert!(StorageService::new(Scope::Local).restore::<Result<String, String>>("session").is_err());
+}
+
+#[test]
+fn it_restores_sessions_from_the_storage() {
+    let (network, clock, storage, intl) = (MockNetwork::new(), MockClock::new(), MockStorage::new(), MockIntl::new());
+    let _installed = (network.install(), clock.install(), storage.install(), intl.install());
+    StorageService::new(Scope::Local).store("session", Json(&Session::new("abc")));
+    auth::configure(config());
+    assert_eq!(auth::token(), Some("abc".into()));
+
+    let mut account = TestRenderer::<Account>::new(());
+    auth::logout();
+    account.flush();
+    assert_eq!(account.component().events, vec![AuthEvent::LoggedOut]);
+    assert!(!auth::is_logged_in());
+
+    // An expired session is refreshed at once
+    StorageService::new(Scope::Local).store("session", Json(&Session::new("old").refresh_token("r1")
+        .expires_in(Duration::from_secs(5))));
+    intl.set_now(UNIX_EPOCH + Duration::from_secs(10));
+    auth::configure(config());
+    clock.advance(Duration::from_millis(1));
+    assert!(network.respond("/auth/refresh", 200, "new"));
+    assert_eq!(auth::token(), Some("new".into()));
+    assert_eq!(network.pending(), 0);
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "testing")]
+
+extern crate yew;
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+use yew::errors::{self, ErrorKind, ErrorReport, HttpSink};
+use yew::format::{Json, Nothing};
+use yew::services::Task;
+use yew::services::beacon::BeaconService;
+use yew::services::mock::{MockBeacon, MockClock, MockNetwork, MockVisibility};
+
+#[test]
+fn it_sends_beacons() {
+    let beacon = MockBeacon::new();
+    let _installed = (beacon.install(), MockVisibility::new().install());
+    let mut service = BeaconService::new();
+    assert!(service.send("/api/end", Json(&vec![1, 2])));
+    assert!(!service.send("/api/end", Nothing));
+    beacon.set_refused(true);
+    assert!(!service.send("/api/end", Json(&"late")));
+    assert_eq!(beacon.sent(), vec![("/api/end".to_owned(), "[1,2]".to_owned())]);
+}
+
+#[test]
+fn it_calls_hooks_when_the_page_is_left() {
+    let page = MockVisibility::new();
+    let _installed = (MockBeacon::new().install(), page.install());
+    let mut service = BeaconService::new();
+    let calls = Rc::new(Cell::new(0));
+    let count = calls.clone();
+    let mut handle = service.on_exit(move || count.set(count.get() + 1));
+
+    page.set_hidden(true);
+    page.set_hidden(false);
+    assert_eq!(calls.get(), 1);
+    handle.cancel();
+    page.set_hidden(true);
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn it_flushes_queued_errors_on_exit() {
+    let (network, page) = (MockNetwork::new(), MockVisibility::new());
+    let _installed = (MockBeacon::new().install(), page.install(), MockClock::new().install(), network.install());
+    errors::add_sink(HttpSink::new("/errors").batch(20, Duration::from_secs(60)));
+    let _exit = BeaconService::new().flush_on_exit();
+
+    errors::report(ErrorReport::new(ErrorKind::App, "cart", "a product is gone"));
+    assert_eq!(network.pending(), 0);
+    page.set_hidden(true);
+    assert_eq!(network.pending(), 1);
+    errors::clear_sinks();
+}
@@ -0,0 +1,34 @@
+extern crate yew;
+
+use std::cell::Cell;
+use std::rc::Rc;
+use yew::html::{Callback, Children};
+
+#[test]
+fn it_emits_values() {
+    let total = Rc::new(Cell::new(0));
+    let handle = total.clone();
+    let callback: Callback<u32> = Callback::from(move |value| {
+        handle.set(handle.get() + value);
+    });
+    callback.emit(2);
+    callback.clone().emit(3);
+    assert_eq!(total.get(), 5);
+}
+
+#[test]
+fn it_compares_callbacks() {
+    let a: Callback<()> = Callback::from(|_| ());
+    let b: Callback<()> = Callback::from(|_| ());
+    assert_eq!(a, a.clone());
+    assert_ne!(a, b);
+    assert!(Callback::<()>::default().is_noop());
+    assert_eq!(Callback::<()>::default(), Callback::default());
+}
+
+#[test]
+fn it_has_no_children_by_default() {
+    let children = Children::default();
+    assert!(children.is_empty());
+    assert_eq!(children, children.clone());
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "testing")]
+
+extern crate yew;
+
+use std::f64::consts::PI;
+use yew::components::chart::{
+    arc_path, format_tick, line_path, tick_step, BandScale, BarChart, BarChartProps, Datum, LineChart,
+    LineChartProps, LinearScale, PieChart, PieChartProps, Series, Sparkline, SparklineProps,
+};
+use yew::testing::TestRenderer;
+
+#[test]
+fn it_computes_nice_ticks() {
+    assert_eq!(tick_step(0.0, 100.0, 5), 20.0);
+    assert_eq!(tick_step(0.0, 1.0, 5), 0.2);
+    assert_eq!(tick_step(3.0, 3.0, 5), 0.0);
+
+    let scale = LinearScale::new((3.0, 97.0), (300.0, 0.0)).nice(5);
+    assert_eq!(scale.domain, (0.0, 100.0));
+    assert_eq!(scale.ticks(5), vec![0.0, 20.0, 40.0, 60.0, 80.0, 100.0]);
+    assert_eq!(scale.map(25.0), 225.0);
+    assert_eq!(LinearScale::new((1.0, 1.0), (0.0, 10.0)).map(1.0), 5.0);
+
+    assert_eq!(format_tick(0.6000000000000001, 0.2), "0.6");
+    assert_eq!(format_tick(-0.0001, 0.05), "0.00");
+    assert_eq!(format_tick(1500.0, 500.0), "1500");
+
+    let bands = BandScale::new(4, (0.0, 100.0));
+    assert_eq!((bands.step(), bands.bandwidth(), bands.start(1)), (25.0, 20.0, 27.5));
+}
+
+#[test]
+fn it_makes_paths() {
+    assert_eq!(line_path(&[(0.0, 1.5), (10.004, -0.001)]), "M0,1.5L10,0");
+    assert_eq!(arc_path((10.0, 10.0), 10.0, 0.0, 0.0, PI / 2.0), "M10,0A10,10 0 0 1 20,10L10,10Z");
+    assert_eq!(arc_path((10.0, 10.0), 10.0, 5.0, PI, 2.0 * PI), "M10,20A10,10 0 0 1 10,0L10,5A5,5 0 0 0 10,15Z");
+
+    let mut series = Series::values("load", &[1.0, 2.0, 3.0]);
+    series.push(3.0, 4.0);
+    series.keep_last(2);
+    assert_eq!(series.points, vec![(2.0, 3.0), (3.0, 4.0)]);
+}
+
+#[test]
+fn it_renders_charts() {
+    let line = TestRenderer::<LineChart>::new(LineChartProps {
+        series: vec![Series::values("cpu", &[10.0, 40.0, 20.0]), Series::values("memory", &[60.0, 70.0, 80.0])],
+        title: "Usage".into(),
+        ..LineChartProps::default()
+    });
+    assert_eq!(line.root().find("svg").unwrap().attribute("aria-label"), Some("Usage"));
+    assert_eq!(line.root().find_all("path.chart-series").len(), 2);
+    assert_eq!(line.root().find_all(".chart-legend li").len(), 2);
+    assert_eq!(line.root().find_all("g.chart-tick").iter().map(|tick| tick.text()).collect::<Vec<_>>(),
+               vec!["10", "20", "30", "40", "50", "60", "70", "80"]);
+
+    let bars = TestRenderer::<BarChart>::new(BarChartProps {
+        data: vec![Datum::new("Mon", 4.0), Datum::new("Tue", -2.0).color("red")],
+        ..BarChartProps::default()
+    });
+    let rects = bars.root().find_all("rect");
+    assert_eq!(rects.len(), 2);
+    // The negative bar starts at zero and goes down
+    assert_eq!(rects[1].attribute("y"), bars.root().find_all("g.chart-tick line")[2].attribute("y1"));
+    assert_eq!(rects[1].attribute("fill"), Some("red"));
+
+    let pie = TestRenderer::<PieChart>::new(PieChartProps {
+        data: vec![Datum::new("A", 1.0), Datum::new("B", 0.0), Datum::new("C", 3.0)],
+        ..PieChartProps::default()
+    });
+    assert_eq!(pie.root().find_all("path.chart-slice").len(), 2);
+    assert_eq!(pie.root().find_all(".chart-legend li").len(), 3);
+
+    let spark = TestRenderer::<Sparkline>::new(SparklineProps { values: vec![1.0, 3.0, 2.0], ..SparklineProps::default() });
+    assert_eq!(spark.root().find("path").unwrap().attribute("d"), Some("M2,22L50,2L98,12"));
+    assert_eq!(spark.root().find("circle").unwrap().attribute("cx"), Some("98"));
+}
@@ -0,0 +1,149 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use yew::html::{AppSender, ChildrenOf, Children, Component, Html, ShouldRender};
+use yew::testing::TestRenderer;
+
+#[derive(Default, Clone, PartialEq)]
+struct TabProps {
+    title: String,
+    active: bool,
+    children: Children,
+}
+
+struct Tab {
+    props: TabProps,
+}
+
+impl Component for Tab {
+    type Msg = ();
+    type Properties = TabProps;
+
+    fn create(props: TabProps, _: AppSender<()>) -> Self {
+        Tab { props }
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html<()> {
+        let class = if self.props.active { "tab active" } else { "tab" };
+        html! {
+            <section class=class,>{ for self.props.children.view() }</section>
+        }
+    }
+}
+
+#[derive(Default)]
+struct TabsProps {
+    active: usize,
+    children: ChildrenOf<Tab>,
+}
+
+struct Tabs {
+    props: TabsProps,
+}
+
+impl Component for Tabs {
+    type Msg = ();
+    type Properties = TabsProps;
+
+    fn create(props: TabsProps, _: AppSender<()>) -> Self {
+        Tabs { props }
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html<()> {
+        let active = self.props.active;
+        html! {
+            <div class="tabs",>
+                <nav>{ for self.props.children.iter().map(|tab| html! { <a>{ &tab.props().title }</a> }) }</nav>
+                { for self.props.children.view_with(|index, props| props.active = index == active) }
+            </div>
+        }
+    }
+}
+
+struct Page {
+    active: usize,
+}
+
+impl Component for Page {
+    type Msg = usize;
+    type Properties = ();
+
+    fn create(_: (), _: AppSender<usize>) -> Self {
+        Page { active: 1 }
+    }
+
+    fn update(&mut self, active: usize) -> ShouldRender {
+        self.active = active;
+        true
+    }
+
+    fn view(&self) -> Html<usize> {
+        html! {
+            <main>
+                <Tabs: active=self.active,>
+                    <Tab: title="One",>{ "first" }</Tab>
+                    <Tab: title="Two",><b>{ "second" }</b></Tab>
+                </Tabs>
+            </main>
+        }
+    }
+}
+
+#[test]
+fn it_passes_nested_children() {
+    let mut page = TestRenderer::<Page>::new(());
+    let titles: Vec<String> = page.root().find_all("a").iter().map(|title| title.text()).collect();
+    assert_eq!(titles, vec!["One", "Two"]);
+    let tabs = page.root().find_all("section");
+    assert_eq!(tabs.len(), 2);
+    assert_eq!(tabs[0].text(), "first");
+    assert!(!tabs[0].element().unwrap().has_class("active"));
+    assert_eq!(tabs[1].find("b").unwrap().text(), "second");
+    assert!(tabs[1].element().unwrap().has_class("active"));
+
+    page.send(0);
+    let tabs = page.root().find_all("section");
+    assert!(tabs[0].element().unwrap().has_class("active"));
+    assert!(!tabs[1].element().unwrap().has_class("active"));
+}
+
+struct Wrong;
+
+impl Component for Wrong {
+    type Msg = ();
+    type Properties = ();
+
+    fn create(_: (), _: AppSender<()>) -> Self {
+        Wrong
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html<()> {
+        html! {
+            <main>
+                <Tabs:>
+                    <p>{ "not a tab" }</p>
+                </Tabs>
+            </main>
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "children have to be")]
+fn it_rejects_children_of_other_types() {
+    TestRenderer::<Wrong>::new(());
+}
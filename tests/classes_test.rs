@@ -0,0 +1,35 @@
+#[macro_use]
+extern crate yew;
+
+use yew::html::Classes;
+use yew::virtual_dom::VTag;
+
+#[test]
+fn it_merges_classes() {
+    let extra: Option<&str> = None;
+    let classes = classes!("btn btn-primary", ("active", true), ("hidden", false), Some("large"), extra, "btn");
+    assert_eq!(classes.to_string(), "btn btn-primary active large");
+    assert!(classes.contains("active"));
+    assert!(!classes.contains("hidden"));
+    assert!(classes!().is_empty());
+}
+
+#[test]
+fn it_attaches_classes() {
+    let active = true;
+
+    let a: VTag<()> = html! {
+        <div class=classes!("item", ("active", active)),></div>
+    };
+
+    let b: VTag<()> = html! {
+        <div class=("item", "active"),></div>
+    };
+
+    let c: VTag<()> = html! {
+        <div class=Classes::from("item"),></div>
+    };
+
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
@@ -0,0 +1,110 @@
+#![cfg(all(feature = "collab", feature = "testing"))]
+
+#[macro_use]
+extern crate yew;
+extern crate serde_json;
+
+use yew::collab::{Crdt, LwwDelta, LwwMap, Shared, SyncMsg, Text};
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::services::mock::MockNetwork;
+use yew::services::websocket::WebSocketService;
+use yew::testing::TestRenderer;
+
+#[test]
+fn it_merges_concurrent_edits() {
+    let mut ann = Text::new(1);
+    let mut bob = Text::new(2);
+    bob.apply(ann.insert_str(0, "helo"));
+
+    // Both type at the same place, and Bob removes a letter
+    let from_ann = ann.insert_str(3, "l");
+    let mut from_bob = bob.insert_str(3, "x");
+    let removal = bob.remove(0);
+    from_bob.extend(removal.clone());
+    assert!(bob.apply(from_ann.clone()));
+    assert!(ann.apply(from_bob));
+    assert_eq!(ann.text(), "elxlo");
+    assert_eq!(bob.text(), "elxlo");
+
+    // Repeated and early deltas are applied once
+    let mut late = Text::new(3);
+    assert!(!late.apply(removal));
+    assert!(late.apply(ann.state()));
+    assert!(!late.apply(from_ann));
+    assert_eq!(late.text(), ann.text());
+
+    let mut first = LwwMap::new(1);
+    let mut second = LwwMap::new(2);
+    let title = first.set("title", "Draft".to_string());
+    second.apply(title.clone());
+    let renamed = second.set("title", "Notes".to_string());
+    let removed = first.remove("title");
+    first.apply(renamed.clone());
+    second.apply(removed.clone());
+    assert_eq!(first.get(&"title"), second.get(&"title"));
+    assert!(!second.apply(title));
+
+    let json = serde_json::to_string(&renamed).unwrap();
+    assert_eq!(serde_json::from_str::<LwwDelta<&str, String>>(&json).unwrap(), renamed);
+}
+
+struct Editor {
+    text: Shared<Text>,
+    _sockets: WebSocketService<Msg>,
+}
+
+enum Msg {
+    Type(usize, String),
+    Sync(SyncMsg<<Text as Crdt>::Delta>),
+}
+
+impl Component for Editor {
+    type Msg = Msg;
+    type Properties = ();
+
+    fn create(_: (), sender: AppSender<Msg>) -> Self {
+        let mut sockets = WebSocketService::new(sender);
+        let mut text = Shared::new(Text::new(7));
+        text.connect(&mut sockets, "ws://docs/1", Msg::Sync);
+        Editor { text, _sockets: sockets }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Type(index, chunk) => self.text.edit(|text| text.insert_str(index, &chunk)),
+            Msg::Sync(msg) => self.text.handle(msg),
+        }
+    }
+
+    fn view(&self) -> Html<Msg> {
+        html! { <p>{ self.text.get().text() }</p> }
+    }
+}
+
+#[test]
+fn it_syncs_shared_values_over_websockets() {
+    let network = MockNetwork::new();
+    let _network = network.install();
+    let mut editor = TestRenderer::<Editor>::new(());
+    let socket = network.socket("ws://docs/1").unwrap();
+
+    // Offline edits are sent as the whole state when the connection opens
+    editor.send(Msg::Type(0, "hi".into()));
+    assert!(socket.sent().is_empty());
+    socket.open();
+    editor.flush();
+    assert_eq!(socket.sent().len(), 1);
+
+    let mut peer = Text::new(8);
+    peer.apply(serde_json::from_str(&socket.sent()[0]).unwrap());
+    let delta = peer.insert_str(2, "!");
+    socket.receive(&serde_json::to_string(&delta).unwrap());
+    socket.receive("not a delta");
+    editor.flush();
+    assert_eq!(editor.root().find("p").unwrap().text(), "hi!");
+
+    editor.send(Msg::Type(0, ">".into()));
+    assert_eq!(socket.sent().len(), 2);
+    peer.apply(serde_json::from_str(&socket.sent()[1]).unwrap());
+    assert_eq!(peer.text(), ">hi!");
+}
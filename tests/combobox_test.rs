@@ -0,0 +1,97 @@
+#![cfg(feature = "testing")]
+
+extern crate yew;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+use yew::callback::Callback;
+use yew::components::combobox::{highlight, Combobox, ComboboxProps};
+use yew::services::mock::MockClock;
+use yew::testing::TestRenderer;
+
+fn props(queries: &Rc<RefCell<Vec<String>>>, picked: &Rc<RefCell<Vec<String>>>) -> ComboboxProps<String> {
+    let (queries, picked) = (queries.clone(), picked.clone());
+    ComboboxProps {
+        id: "city".into(),
+        onquery: Callback::from(move |query| queries.borrow_mut().push(query)),
+        onselect: Callback::from(move |city| picked.borrow_mut().push(city)),
+        ..ComboboxProps::default()
+    }
+}
+
+#[test]
+fn it_highlights_matches_ignoring_case() {
+    assert_eq!(highlight("Amsterdam", "am"), vec![
+        ("Am".to_string(), true),
+        ("sterd".to_string(), false),
+        ("am".to_string(), true),
+    ]);
+    assert_eq!(highlight("Zürich", "RI"), vec![
+        ("Zü".to_string(), false),
+        ("ri".to_string(), true),
+        ("ch".to_string(), false),
+    ]);
+    assert_eq!(highlight("Oslo", ""), vec![("Oslo".to_string(), false)]);
+}
+
+#[test]
+fn it_queries_when_typing_pauses() {
+    let clock = MockClock::new();
+    let _clock = clock.install();
+    let (queries, picked) = (Rc::new(RefCell::new(Vec::new())), Rc::new(RefCell::new(Vec::new())));
+    let mut combobox = TestRenderer::<Combobox<String>>::new(props(&queries, &picked));
+
+    combobox.input("input", "b");
+    clock.advance(Duration::from_millis(100));
+    combobox.flush();
+    combobox.input("input", "be");
+    clock.advance(Duration::from_millis(299));
+    combobox.flush();
+    assert!(queries.borrow().is_empty());
+
+    clock.advance(Duration::from_millis(1));
+    combobox.flush();
+    assert_eq!(*queries.borrow(), vec!["be".to_string()]);
+
+    // Queries shorter than the minimum aren't sent
+    combobox.input("input", "");
+    clock.advance(Duration::from_millis(300));
+    combobox.flush();
+    assert_eq!(queries.borrow().len(), 1);
+}
+
+#[test]
+fn it_picks_suggestions_with_keys() {
+    let (queries, picked) = (Rc::new(RefCell::new(Vec::new())), Rc::new(RefCell::new(Vec::new())));
+    let _clock = MockClock::new().install();
+    let mut combobox = TestRenderer::<Combobox<String>>::new(props(&queries, &picked));
+    combobox.input("input", "be");
+    combobox.change(ComboboxProps {
+        suggestions: vec!["Berlin".into(), "Bern".into()],
+        ..props(&queries, &picked)
+    });
+    assert_eq!(combobox.root().find("input").unwrap().attribute("aria-expanded"), Some("true"));
+    assert_eq!(combobox.root().find("input").unwrap().attribute("aria-activedescendant"), None);
+    assert_eq!(combobox.root().find_all("mark").len(), 2);
+
+    combobox.key_down("input", "ArrowUp");
+    combobox.key_down("input", "ArrowDown");
+    let input = combobox.root().find("input").unwrap();
+    assert_eq!(input.attribute("aria-activedescendant"), Some("city-option-0"));
+    assert_eq!(combobox.root().find("#city-option-0").unwrap().attribute("aria-selected"), Some("true"));
+
+    combobox.key_down("input", "Enter");
+    assert_eq!(*picked.borrow(), vec!["Berlin".to_string()]);
+    assert_eq!(combobox.root().find("input").unwrap().attribute("aria-expanded"), Some("false"));
+
+    // The first Escape closes the list and the second one clears the input
+    let value = |combobox: &TestRenderer<Combobox<String>>| combobox.root().find("input").unwrap().element().unwrap().value.clone();
+    assert_eq!(value(&combobox), Some("Berlin".to_string()));
+    combobox.input("input", "Ber");
+    combobox.key_down("input", "Escape");
+    assert_eq!(combobox.root().find("input").unwrap().attribute("aria-expanded"), Some("false"));
+    assert_eq!(value(&combobox), Some("Ber".to_string()));
+    combobox.key_down("input", "Escape");
+    assert_eq!(value(&combobox), Some(String::new()));
+}
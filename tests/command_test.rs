@@ -0,0 +1,65 @@
+extern crate yew;
+
+use std::time::Duration;
+use yew::command::Command;
+use yew::format::Nothing;
+use yew::services::fetch::{Request, Response};
+
+#[derive(Debug, PartialEq)]
+enum Msg {
+    Fire,
+    Timeout,
+    Loaded(bool),
+}
+
+#[derive(Default)]
+struct Model {
+    fired: bool,
+}
+
+fn update(model: &mut Model, msg: Msg) -> Vec<Command<Msg>> {
+    match msg {
+        Msg::Fire => vec![Command::delay(Duration::from_secs(5), Msg::Timeout)],
+        Msg::Timeout => {
+            model.fired = true;
+            let request = Request::get("/status").body(Nothing).unwrap();
+            vec![
+                Command::fetch(request, |response: Response<Nothing>| Msg::Loaded(response.status().is_success())),
+                Command::Navigate("/done".into()),
+            ]
+        }
+        Msg::Loaded(_) => Vec::new(),
+    }
+}
+
+#[test]
+fn it_returns_commands_without_effects() {
+    let mut model = Model::default();
+    let commands = update(&mut model, Msg::Fire);
+    assert!(!model.fired);
+    match commands.as_slice() {
+        [Command::Delay(duration, Msg::Timeout)] => {
+            assert_eq!(*duration, Duration::from_secs(5));
+        }
+        _ => panic!("unexpected commands: {:?}", commands),
+    }
+}
+
+#[test]
+fn it_converts_responses_of_fetch_commands() {
+    let mut model = Model::default();
+    let commands = update(&mut model, Msg::Timeout);
+    assert!(model.fired);
+    assert_eq!(format!("{:?}", commands), r#"[Fetch(GET /status), Navigate("/done")]"#);
+    match commands.into_iter().next() {
+        Some(Command::Fetch(request, converter)) => {
+            assert_eq!(request.body(), &None);
+            let response = Response::builder()
+                .status(200)
+                .body(Ok("ok".to_owned()))
+                .unwrap();
+            assert_eq!(converter(response), Msg::Loaded(true));
+        }
+        _ => panic!("expected a fetch command"),
+    }
+}
@@ -0,0 +1,100 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+extern crate serde_json;
+
+use serde_json::Value;
+use yew::config::{self, ConfigHandle, ConfigService, Flag, CACHE_KEY};
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::services::backend::StorageBackend;
+use yew::services::mock::{MockNetwork, MockStorage};
+use yew::services::storage::Scope;
+use yew::testing::TestRenderer;
+
+const NEW_CHECKOUT: Flag<bool> = Flag::new("new-checkout", false);
+const PAGE_SIZE: Flag<u32> = Flag::new("page-size", 20);
+
+struct Checkout {
+    config: ConfigService<()>,
+    _loading: ConfigHandle,
+    _listening: ConfigHandle,
+}
+
+impl Component for Checkout {
+    type Msg = ();
+    type Properties = ();
+
+    fn create(_: (), sender: AppSender<()>) -> Self {
+        let mut config = ConfigService::new(sender);
+        let _loading = config.load("/api/flags");
+        let _listening = config.listen(|| ());
+        Checkout { config, _loading, _listening }
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        true
+    }
+
+    fn view(&self) -> Html<()> {
+        html! {
+            <p>{ if self.config.get(&NEW_CHECKOUT) { "new" } else { "old" } }{ self.config.get(&PAGE_SIZE) }</p>
+        }
+    }
+}
+
+#[test]
+fn it_renders_consumers_with_loaded_flags() {
+    let (network, storage) = (MockNetwork::new(), MockStorage::new());
+    let _installed = (network.install(), storage.install());
+    storage.set(Scope::Local, CACHE_KEY, r#"{"page-size": 50}"#);
+    let mut checkout = TestRenderer::<Checkout>::new(());
+    assert_eq!(checkout.root().text(), "old50");
+
+    assert!(network.respond("/api/flags", 200, r#"{"new-checkout": true, "page-size": "wrong"}"#));
+    checkout.flush();
+    assert_eq!(checkout.root().text(), "new20");
+    assert!(storage.get(Scope::Local, CACHE_KEY).unwrap().contains("new-checkout"));
+    let renders = checkout.renders();
+
+    // Equal values and failed loads don't render consumers
+    let mut service = ConfigService::new(checkout.sender());
+    let _same = service.load("/api/flags");
+    assert!(network.respond("/api/flags", 200, r#"{"new-checkout": true, "page-size": "wrong"}"#));
+    let _failed = service.load("/api/flags");
+    assert!(network.respond("/api/flags", 500, "oops"));
+    checkout.flush();
+    assert_eq!(checkout.renders(), renders);
+    assert!(NEW_CHECKOUT.get());
+}
+
+#[test]
+fn it_merges_streamed_values() {
+    let network = MockNetwork::new();
+    let _installed = (network.install(), MockStorage::new().install());
+    let mut checkout = TestRenderer::<Checkout>::new(());
+    let mut service = ConfigService::new(checkout.sender());
+    let _stream = service.stream("wss://flags.example/");
+    let socket = network.socket("wss://flags.example/").expect("a stream");
+    socket.open();
+    socket.receive(r#"{"page-size": 30}"#);
+    socket.receive(r#"{"new-checkout": true}"#);
+    checkout.flush();
+    assert_eq!(checkout.root().text(), "new30");
+}
+
+#[test]
+fn it_overrides_values_deterministically() {
+    let network = MockNetwork::new();
+    let _installed = (network.install(), MockStorage::new().install());
+    config::set_overrides(vec![("new-checkout", Value::Bool(true))]);
+    let mut checkout = TestRenderer::<Checkout>::new(());
+    assert!(network.respond("/api/flags", 200, r#"{"new-checkout": false, "page-size": 10}"#));
+    checkout.flush();
+    assert_eq!(checkout.root().text(), "new20");
+    assert_eq!(config::value("page-size"), None);
+
+    config::clear_overrides();
+    checkout.flush();
+    assert_eq!(checkout.root().text(), "old10");
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "csp")]
+
+extern crate yew;
+
+use yew::csp;
+use yew::services::interop;
+use yew::services::print::PrintDocument;
+
+#[test]
+fn it_puts_the_nonce_to_inline_styles() {
+    csp::set_nonce("r4nd0m");
+    assert_eq!(csp::nonce(), Some("r4nd0m".to_owned()));
+    let html = PrintDocument::new("Invoice").style("@page { size: A4; }").to_html();
+    assert!(html.contains("<style nonce=\"r4nd0m\">@page { size: A4; }</style>"));
+}
+
+#[test]
+fn it_renders_inline_styles_without_a_nonce_if_it_isnt_set() {
+    assert_eq!(csp::nonce(), None);
+    let html = PrintDocument::new("Invoice").style("@page { size: A4; }").to_html();
+    assert!(html.contains("<style>@page { size: A4; }</style>"));
+}
+
+#[test]
+#[should_panic(expected = "interop::register evaluates code")]
+fn it_forbids_evaluated_sources() {
+    interop::register("add", "function(a, b) { return a + b; }");
+}
+
+#[test]
+#[cfg(feature = "ssr")]
+#[should_panic(expected = "`javascript:` URL")]
+fn it_rejects_inline_code_in_attributes() {
+    use yew::ssr;
+    use yew::virtual_dom::VTag;
+
+    let mut link = VTag::<()>::new("a");
+    link.add_attribute("href", " JavaScript:alert(1)");
+    ssr::render_to_string(link);
+}
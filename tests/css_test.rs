@@ -0,0 +1,43 @@
+extern crate yew;
+
+use yew::css::{self, Css, Scoped};
+use yew::style::Style;
+
+fn card() -> Css {
+    Css::new()
+        .rule("&", Style::new().set("padding", "8px"))
+        .rule("&:hover, &.active", Style::new().set("background", "#eee"))
+        .rule(".title", Style::new().set("font-weight", "bold"))
+        .media("(max-width: 600px)", Css::new().rule("&", Style::new().set("padding", "0")))
+}
+
+#[test]
+fn it_scopes_selectors_by_the_class() {
+    assert_eq!(card().to_css("card"), "\
+.card { padding: 8px; }
+.card:hover, .card.active { background: #eee; }
+.card .title { font-weight: bold; }
+@media (max-width: 600px) {
+.card { padding: 0; }
+}
+");
+}
+
+#[test]
+fn it_removes_rules_of_the_last_consumer() {
+    let first = Scoped::new("card", &card());
+    let second = Scoped::new("card", &card());
+    let other = Scoped::new("badge", &Css::new().rule("&", Style::new().set("color", "red")));
+    assert_eq!(first.class(), second.class());
+    assert!(first.class().starts_with("card-"));
+    assert_eq!(css::classes(), vec![first.class().to_owned(), other.class().to_owned()]);
+    assert!(css::sheet().contains(&format!(".{}:hover", first.class())));
+    let class = first.class().to_owned();
+    drop(first);
+    assert_eq!(css::classes().len(), 2);
+    let clone = second.clone();
+    drop(second);
+    assert!(css::classes().contains(&class));
+    drop(clone);
+    assert_eq!(css::classes(), vec![other.class().to_owned()]);
+}
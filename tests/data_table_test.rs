@@ -0,0 +1,120 @@
+#![cfg(feature = "testing")]
+
+extern crate yew;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use yew::callback::Callback;
+use yew::components::data_table::{Column, DataTable, DataTableProps, Direction, Pagination, Selection, Sort, TableQuery};
+use yew::testing::TestRenderer;
+
+#[derive(Clone, PartialEq)]
+struct User {
+    name: &'static str,
+    age: u32,
+}
+
+fn props() -> DataTableProps<User> {
+    DataTableProps {
+        rows: vec![
+            User { name: "Carol", age: 35 },
+            User { name: "Alice", age: 28 },
+            User { name: "Bob", age: 42 },
+        ],
+        columns: vec![
+            Column::new("name", "Name").class("name").text(|user: &User| user.name.to_owned())
+                .sort_by_key(|user| user.name),
+            Column::new("age", "Age").class("age").text(|user: &User| user.age.to_string())
+                .sort_by_key(|user| user.age),
+        ],
+        ..DataTableProps::default()
+    }
+}
+
+fn names(table: &TestRenderer<DataTable<User>>) -> Vec<String> {
+    table.root().find_all("tbody td.name").iter().map(|cell| cell.text()).collect()
+}
+
+#[test]
+fn it_sorts_rows_by_clicked_columns() {
+    let sorts = Rc::new(RefCell::new(Vec::new()));
+    let log = sorts.clone();
+    let mut table = TestRenderer::<DataTable<User>>::new(DataTableProps {
+        onsort: Callback::from(move |sort| log.borrow_mut().push(sort)),
+        ..props()
+    });
+    assert_eq!(names(&table), vec!["Carol", "Alice", "Bob"]);
+    assert_eq!(table.root().find("th.name").unwrap().attribute("aria-sort"), Some("none"));
+
+    table.click("th.name button");
+    assert_eq!(names(&table), vec!["Alice", "Bob", "Carol"]);
+    assert_eq!(table.root().find("th.name").unwrap().attribute("aria-sort"), Some("ascending"));
+    table.click("th.name button");
+    assert_eq!(names(&table), vec!["Carol", "Bob", "Alice"]);
+    table.click("th.name button");
+    assert_eq!(names(&table), vec!["Carol", "Alice", "Bob"]);
+    table.click("th.age button");
+    assert_eq!(names(&table), vec!["Alice", "Carol", "Bob"]);
+    assert_eq!(*sorts.borrow(), vec![
+        Some(Sort::ascending("name")),
+        Some(Sort::descending("name")),
+        None,
+        Some(Sort::ascending("age")),
+    ]);
+
+    // Rows which a server sorted are kept in order
+    table.change(DataTableProps { server: true, sort: Some(Sort::descending("age")), ..props() });
+    assert_eq!(names(&table), vec!["Carol", "Alice", "Bob"]);
+    assert_eq!(table.root().find("th.age").unwrap().attribute("aria-sort"), Some("descending"));
+}
+
+#[test]
+fn it_selects_rows_and_pages() {
+    let selections = Rc::new(RefCell::new(Vec::new()));
+    let pages = Rc::new(RefCell::new(Vec::new()));
+    let (log, opened) = (selections.clone(), pages.clone());
+    let props = move |selected: Vec<usize>| DataTableProps {
+        selection: Selection::Multiple,
+        selected,
+        sort: Some(Sort::ascending("name")),
+        pagination: Some(Pagination { page: 0, per_page: 2, total: 3 }),
+        onselect: Callback::from({ let log = log.clone(); move |selected| log.borrow_mut().push(selected) }),
+        onpage: Callback::from({ let opened = opened.clone(); move |page| opened.borrow_mut().push(page) }),
+        ..props()
+    };
+    let mut table = TestRenderer::<DataTable<User>>::new(props(Vec::new()));
+    assert_eq!(names(&table), vec!["Alice", "Bob"]);
+    assert_eq!(table.root().find(".data-table-page").unwrap().text(), "1 / 2");
+
+    table.click("tbody tr input");
+    assert_eq!(selections.borrow().last(), Some(&vec![1]));
+    table.change(props(vec![1]));
+    assert!(table.root().find("tbody tr").unwrap().element().unwrap().has_class("selected"));
+
+    // The header selects rows of the shown page
+    table.click("thead input");
+    assert_eq!(selections.borrow().last(), Some(&vec![1, 2]));
+    table.change(props(vec![1, 2]));
+    table.click("thead input");
+    assert_eq!(selections.borrow().last(), Some(&Vec::new()));
+
+    table.click(".data-table-next");
+    assert_eq!(*pages.borrow(), vec![1]);
+}
+
+#[test]
+fn it_writes_and_reads_table_queries() {
+    let mut query = TableQuery::new(20);
+    query.page = 3;
+    assert_eq!(query.to_query(), "page=4&per_page=20");
+    query.sort(Some(Sort::descending("created at")));
+    assert_eq!(query.page, 0);
+    assert_eq!(query.to_query(), "sort=created%20at&order=desc&page=1&per_page=20");
+
+    let parsed = TableQuery::new(10).parse("?sort=created%20at&order=desc&page=2&per_page=50");
+    assert_eq!(parsed.sort.as_ref().map(|sort| sort.direction), Some(Direction::Descending));
+    assert_eq!(parsed.sort.unwrap().column, "created at");
+    assert_eq!((parsed.page, parsed.per_page), (1, 50));
+    assert_eq!(TableQuery::new(10).parse("page=0&per_page=x"), TableQuery::new(10));
+    assert_eq!(query.pagination(41).pages(), 3);
+}
@@ -0,0 +1,185 @@
+#![cfg(feature = "testing")]
+
+extern crate yew;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use yew::callback::Callback;
+use yew::components::calendar::{Calendar, CalendarProps, Msg};
+use yew::components::date_picker::{self, DatePicker, DatePickerProps};
+use yew::components::time_picker::{TimePicker, TimePickerProps};
+use yew::date::{Date, DateRange, Time};
+use yew::html::{ChangeData, KeyData, Modifiers};
+use yew::services::mock::{MockIntl, MockLayout};
+use yew::testing::TestRenderer;
+
+fn date(year: i32, month: u32, day: u32) -> Date {
+    Date::new(year, month, day).expect("a valid date")
+}
+
+fn intl_at(today: Date) -> MockIntl {
+    let intl = MockIntl::new();
+    intl.set_now(today.to_time());
+    intl
+}
+
+fn focused(calendar: &TestRenderer<Calendar>) -> Option<&str> {
+    calendar.root().find("[tabindex=\"0\"]").and_then(|day| day.attribute("id"))
+}
+
+#[test]
+fn it_does_date_arithmetic() {
+    let leap = date(2024, 1, 31);
+    assert_eq!(leap.add_months(1), date(2024, 2, 29));
+    assert_eq!(leap.add_months(-2), date(2023, 11, 30));
+    assert_eq!(date(2024, 2, 29).add_years(1), date(2025, 2, 28));
+    assert_eq!(leap.add_days(1), date(2024, 2, 1));
+    assert_eq!(date(1969, 12, 31).days(), -1);
+    assert_eq!(Date::from_days(-1), date(1969, 12, 31));
+    assert_eq!(Date::from_time(date(1960, 5, 4).to_time()), date(1960, 5, 4));
+    assert_eq!(leap.weekday(), 3);
+    assert_eq!(leap.start_of_week(7), date(2024, 1, 28));
+    assert_eq!(Date::new(2023, 2, 29), None);
+    assert_eq!(Date::parse("2024-03-09"), Some(date(2024, 3, 9)));
+    assert_eq!(date(2024, 3, 9).to_string(), "2024-03-09");
+    assert_eq!(date(2024, 1, 5).clamp_to(Some(date(2024, 1, 10)), None), date(2024, 1, 10));
+    assert_eq!(DateRange::new(leap, date(2024, 1, 1)).days(), 31);
+    assert_eq!(Time::parse("9:05"), Time::new(9, 5));
+    assert_eq!(Time::from_minutes(25 * 60 + 1).to_string(), "01:01");
+}
+
+#[test]
+fn it_moves_through_calendars_with_keys() {
+    let intl = intl_at(date(2024, 1, 15));
+    let _intl = intl.install();
+    let mut calendar = TestRenderer::<Calendar>::new(CalendarProps {
+        id: "due".into(),
+        max: Some(date(2025, 1, 20)),
+        ..CalendarProps::default()
+    });
+    assert_eq!(calendar.root().find("h2").unwrap().text(), "January 2024");
+    assert_eq!(calendar.root().find("th").unwrap().text(), "Mon");
+    assert_eq!(calendar.root().find("th").unwrap().attribute("abbr"), Some("Monday"));
+    assert_eq!(calendar.root().find("button.calendar-day").unwrap().attribute("id"), Some("due-2024-01-01"));
+    assert_eq!(focused(&calendar), Some("due-2024-01-15"));
+    assert_eq!(calendar.root().find("#due-2024-01-15").unwrap().attribute("aria-current"), Some("date"));
+    assert_eq!(calendar.root().find("#due-2024-01-15").unwrap().attribute("aria-label"), Some("2024-01-15"));
+
+    calendar.key_down("#due-2024-01-15", "ArrowRight");
+    calendar.key_down("#due-2024-01-16", "ArrowDown");
+    assert_eq!(focused(&calendar), Some("due-2024-01-23"));
+    calendar.key_down("#due-2024-01-23", "End");
+    assert_eq!(focused(&calendar), Some("due-2024-01-28"));
+    calendar.key_down("#due-2024-01-28", "PageDown");
+    calendar.flush();
+    assert_eq!(focused(&calendar), Some("due-2024-02-28"));
+    assert_eq!(calendar.root().find("h2").unwrap().text(), "February 2024");
+    let shift = KeyData {
+        key: "PageDown".into(),
+        modifiers: Modifiers { shift: true, ..Modifiers::default() },
+        ..KeyData::default()
+    };
+    calendar.simulate("#due-2024-02-28", "onkeydown", shift);
+    // The latest day stops the move
+    assert_eq!(focused(&calendar), Some("due-2025-01-20"));
+    assert_eq!(calendar.root().find(".calendar-next").unwrap().attribute("disabled"), Some("true"));
+}
+
+#[test]
+fn it_selects_ranges_in_calendars() {
+    let intl = intl_at(date(2024, 1, 15));
+    intl.set_first_weekday(7);
+    let _intl = intl.install();
+    let selected = Rc::new(RefCell::new(Vec::new()));
+    let onselect = selected.clone();
+    let mut calendar = TestRenderer::<Calendar>::new(CalendarProps {
+        id: "stay".into(),
+        range: true,
+        min: Some(date(2024, 1, 5)),
+        onselect: Callback::from(move |range| onselect.borrow_mut().push(range)),
+        ..CalendarProps::default()
+    });
+    assert_eq!(calendar.root().find("th").unwrap().text(), "Sun");
+    assert_eq!(calendar.root().find("button.calendar-day").unwrap().attribute("id"), Some("stay-2023-12-31"));
+    assert_eq!(calendar.root().find("#stay-2024-01-04").unwrap().attribute("aria-disabled"), Some("true"));
+
+    calendar.click("#stay-2024-01-04");
+    calendar.click("#stay-2024-01-10");
+    calendar.send(Msg::Hover(Some(date(2024, 1, 12))));
+    let cell = |calendar: &TestRenderer<Calendar>, id: &str| {
+        calendar.root().find_all("td").into_iter()
+            .find(|cell| cell.find(id).is_some())
+            .and_then(|cell| cell.attribute("aria-selected"))
+            .map(str::to_owned)
+    };
+    assert_eq!(cell(&calendar, "#stay-2024-01-11"), Some("true".to_owned()));
+    assert_eq!(cell(&calendar, "#stay-2024-01-13"), Some("false".to_owned()));
+    assert!(calendar.root().find("#stay-2024-01-10").unwrap().element().unwrap().has_class("range-start"));
+    assert!(selected.borrow().is_empty());
+
+    calendar.click("#stay-2024-01-08");
+    assert_eq!(*selected.borrow(), vec![DateRange::new(date(2024, 1, 8), date(2024, 1, 10))]);
+}
+
+#[test]
+fn it_picks_dates_in_dialogs() {
+    let intl = intl_at(date(2024, 1, 15));
+    let layout = MockLayout::new();
+    let _intl = intl.install();
+    let _layout = layout.install();
+    let picked = Rc::new(RefCell::new(Vec::new()));
+    let onchange = picked.clone();
+    let mut picker = TestRenderer::<DatePicker>::new(DatePickerProps {
+        id: "due".into(),
+        label: "Due date".into(),
+        onchange: Callback::from(move |date| onchange.borrow_mut().push(date)),
+        ..DatePickerProps::default()
+    });
+    assert_eq!(picker.root().find("#due-button").unwrap().text(), "Choose a date");
+    assert_ne!(picker.root().find("#due-dialog").unwrap().attribute("hidden"), Some("false"));
+    assert!(picker.root().find_component::<Calendar>().is_none());
+
+    picker.click("#due-button");
+    assert_eq!(picker.root().find("#due-button").unwrap().attribute("aria-expanded"), Some("true"));
+    assert_ne!(picker.root().find("#due-dialog").unwrap().attribute("hidden"), Some("true"));
+    assert!(picker.root().find("#due-calendar-2024-01-15").is_some());
+    assert_eq!(layout.anchor(0), Some("due-button".to_owned()));
+
+    picker.send(date_picker::Msg::Select(date(2024, 1, 20)));
+    assert_eq!(*picked.borrow(), vec![date(2024, 1, 20)]);
+    assert!(!layout.is_tracked(0));
+    assert_eq!(picker.root().find("#due-button").unwrap().attribute("aria-expanded"), Some("false"));
+
+    picker.change(DatePickerProps {
+        id: "due".into(),
+        label: "Due date".into(),
+        value: Some(date(2024, 1, 20)),
+        ..DatePickerProps::default()
+    });
+    assert_eq!(picker.root().find("#due-button").unwrap().text(), "2024-01-20");
+    assert_eq!(picker.root().find("#due-button").unwrap().attribute("aria-label"), Some("Due date, 2024-01-20"));
+}
+
+#[test]
+fn it_lists_times_between_bounds() {
+    let _intl = MockIntl::new().install();
+    let picked = Rc::new(RefCell::new(Vec::new()));
+    let onchange = picked.clone();
+    let mut picker = TestRenderer::<TimePicker>::new(TimePickerProps {
+        value: Time::new(9, 45),
+        min: Time::new(9, 0).unwrap(),
+        max: Time::new(10, 0).unwrap(),
+        step: 30,
+        onchange: Callback::from(move |time| onchange.borrow_mut().push(time)),
+        ..TimePickerProps::default()
+    });
+    let values: Vec<_> = picker.root().find_all("option").into_iter()
+        .map(|option| option.attribute("value").unwrap_or_default().to_owned())
+        .collect();
+    assert_eq!(values, vec!["", "09:00", "09:30", "09:45", "10:00"]);
+    assert_eq!(picker.root().find_all("option")[2].text(), "09:30");
+
+    let change = ChangeData { value: "09:30".into(), ..ChangeData::default() };
+    picker.simulate("select", "onchange", change);
+    assert_eq!(*picked.borrow(), vec![Time::new(9, 30).unwrap()]);
+}
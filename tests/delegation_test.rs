@@ -0,0 +1,82 @@
+#![cfg(all(feature = "web_test", any(target_arch = "wasm32", target_os = "emscripten")))]
+
+#[macro_use]
+extern crate yew;
+#[macro_use]
+extern crate stdweb;
+
+use std::cell::RefCell;
+use stdweb::web::{document, Element};
+use yew::html::{App, Html};
+
+thread_local! {
+    static CALLS: RefCell<Vec<&'static str>> = RefCell::new(Vec::new());
+}
+
+fn call(name: &'static str) {
+    CALLS.with(|calls| calls.borrow_mut().push(name));
+}
+
+fn calls() -> Vec<&'static str> {
+    CALLS.with(|calls| calls.borrow_mut().drain(..).collect())
+}
+
+/// Mounts the view to a new element of the body and clicks its button.
+fn click(view: fn(&()) -> Html<()>) -> Element {
+    yew::initialize();
+    let root = document().create_element("div");
+    js! { @(no_return)
+        document.body.appendChild(@{&root});
+    }
+    let mut app = App::<()>::new();
+    app.mount_to_element(root.clone(), (), (), |_, _, _| (), view);
+    js! { @(no_return)
+        @{&root}.querySelector("button").click();
+    }
+    root
+}
+
+#[test]
+fn it_calls_listeners_in_the_order_of_the_dom() {
+    fn view(_: &()) -> Html<()> {
+        html! {
+            <div onclick.propagate=|_| call("outer"),>
+                <section onclick.capture.propagate=|_| call("capture"),>
+                    <p onclick.once.propagate=|_| call("once"),>
+                        <button onclick.propagate=|_| call("button"),>{ "Go" }</button>
+                    </p>
+                </section>
+            </div>
+        }
+    }
+    let root = click(view);
+    assert_eq!(calls(), vec!["capture", "button", "once", "outer"]);
+
+    js! { @(no_return)
+        @{&root}.querySelector("button").click();
+    }
+    assert_eq!(calls(), vec!["capture", "button", "outer"]);
+}
+
+#[test]
+fn it_stops_propagation_at_the_first_listener() {
+    fn view(_: &()) -> Html<()> {
+        html! {
+            <div onclick.once=|_| call("outer"),>
+                <p onclick=|_| call("inner"),>
+                    <button>{ "Go" }</button>
+                </p>
+            </div>
+        }
+    }
+    js! { @(no_return)
+        window.bodyClicked = false;
+        document.body.addEventListener("click", function() {
+            window.bodyClicked = true;
+        });
+    }
+    click(view);
+    assert_eq!(calls(), vec!["inner"]);
+    let body_clicked = js!( return window.bodyClicked; );
+    assert_eq!(body_clicked, false);
+}
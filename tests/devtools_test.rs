@@ -0,0 +1,87 @@
+#![cfg(all(feature = "devtools", feature = "testing"))]
+
+#[macro_use]
+extern crate yew;
+#[macro_use]
+extern crate serde_json;
+
+use yew::devtools::DevTools;
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::services::mock::{MockClock, MockDevtools};
+use yew::testing::TestRenderer;
+
+#[derive(Debug)]
+enum Msg {
+    Add(i32),
+}
+
+#[derive(Debug)]
+struct Counter {
+    value: i32,
+}
+
+impl Component for Counter {
+    type Msg = Msg;
+    type Properties = ();
+
+    fn create(_: (), _: AppSender<Msg>) -> Self {
+        Counter { value: 0 }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Add(amount) => self.value += amount,
+        }
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        html! { <p>{ self.value }</p> }
+    }
+
+    fn inspect(&self) -> Option<String> {
+        Some(format!("{:?}", self))
+    }
+}
+
+#[test]
+fn it_publishes_snapshots_of_components() {
+    let inspector = MockDevtools::new();
+    let _installed = inspector.install();
+    let mut counter = TestRenderer::<Counter>::new(());
+    counter.send(Msg::Add(1));
+    inspector.request("inspect");
+
+    assert_eq!(inspector.payloads("mount"), vec![json!({
+        "id": 1, "name": "devtools_test::Counter", "parent": null, "state": "Counter { value: 0 }",
+    })]);
+    assert_eq!(inspector.payloads("update"), vec![json!({
+        "id": 1, "messages": 1, "rendered": true, "state": "Counter { value: 1 }",
+    })]);
+    assert_eq!(inspector.payloads("tree"), vec![json!([{
+        "id": 1, "name": "devtools_test::Counter", "parent": null, "state": "Counter { value: 1 }",
+    }])]);
+
+    drop(counter);
+    inspector.request("inspect");
+    assert_eq!(inspector.payloads("unmount"), vec![json!({ "id": 1 })]);
+    assert_eq!(inspector.payloads("tree")[1], json!([]));
+}
+
+#[test]
+fn it_publishes_the_log_of_messages() {
+    let clock = MockClock::new();
+    let inspector = MockDevtools::new();
+    let _installed = (clock.install(), inspector.install());
+    let mut counter = TestRenderer::<Counter>::new(());
+    counter.middleware(DevTools::new("counter"));
+    counter.send(Msg::Add(2));
+    counter.send(Msg::Add(3));
+
+    assert_eq!(inspector.payloads("message"), vec![
+        json!({ "app": "counter", "msg": "Add(2)", "model": "Counter { value: 2 }", "elapsed": 0.0 }),
+        json!({ "app": "counter", "msg": "Add(3)", "model": "Counter { value: 5 }", "elapsed": 0.0 }),
+    ]);
+    let kinds: Vec<String> = inspector.events().into_iter().map(|(kind, _)| kind).collect();
+    assert_eq!(kinds, vec!["mount", "message", "update", "message", "update"]);
+}
@@ -0,0 +1,49 @@
+#[macro_use]
+extern crate yew;
+
+use yew::direction::{self, Direction};
+use yew::style::Style;
+use yew::virtual_dom::VTag;
+
+#[test]
+fn it_detects_direction_of_locales() {
+    assert_eq!(Direction::of_locale("ar-EG"), Direction::Rtl);
+    assert_eq!(Direction::of_locale("he"), Direction::Rtl);
+    assert_eq!(Direction::of_locale("fa_IR"), Direction::Rtl);
+    assert_eq!(Direction::of_locale("en-US"), Direction::Ltr);
+    assert_eq!(Direction::Rtl.to_string(), "rtl");
+}
+
+#[test]
+fn it_sets_dir_attribute() {
+    let a: VTag<()> = html! {
+        <blockquote dir=Direction::Rtl,></blockquote>
+    };
+    let b: VTag<()> = html! {
+        <blockquote dir="rtl",></blockquote>
+    };
+    assert_eq!(a, b);
+}
+
+#[test]
+fn it_maps_logical_sides() {
+    assert_eq!(Direction::Ltr.physical_property("margin-inline-start"), "margin-left");
+    assert_eq!(Direction::Rtl.physical_property("margin-inline-start"), "margin-right");
+    assert_eq!(Direction::Rtl.physical_property("border-inline-end-width"), "border-left-width");
+    assert_eq!(Direction::Rtl.physical_property("inset-inline-start"), "right");
+    assert_eq!(Direction::Rtl.physical_property("margin-top"), "margin-top");
+    assert_eq!(Direction::Rtl.physical_value("text-align", "start"), "right");
+    assert_eq!(Direction::Rtl.physical_value("display", "start"), "start");
+}
+
+#[test]
+fn it_sets_logical_styles_in_the_current_direction() {
+    let style = || Style::new().set_logical("padding-inline-start", "8px").set_logical("float", "end");
+    assert_eq!(style().to_string(), "padding-left: 8px; float: right;");
+    direction::set_direction(Direction::Rtl);
+    assert_eq!(style().to_string(), "padding-right: 8px; float: left;");
+    let nested = direction::with_direction(Direction::Ltr, || style().to_string());
+    assert_eq!(nested, "padding-left: 8px; float: right;");
+    assert_eq!(direction::direction(), Direction::Rtl);
+    assert_eq!(direction::start(), "right");
+}
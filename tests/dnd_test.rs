@@ -0,0 +1,91 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use yew::dnd::{self, DragEvent, DropAction, DropEvent};
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::testing::TestRenderer;
+
+struct Board {
+    columns: Vec<Vec<u32>>,
+    dragging: Option<u32>,
+    hover: Option<(usize, usize)>,
+}
+
+enum Msg {
+    Drag(u32, DragEvent),
+    Column(usize, DropEvent<u32>),
+}
+
+impl Component for Board {
+    type Msg = Msg;
+    type Properties = ();
+
+    fn create(_: (), _: AppSender<Msg>) -> Self {
+        Board {
+            columns: vec![vec![1, 2], vec![3]],
+            dragging: None,
+            hover: None,
+        }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Drag(card, DragEvent::Start) => self.dragging = Some(card),
+            Msg::Drag(_, DragEvent::End { .. }) => self.dragging = None,
+            Msg::Column(column, DropEvent::Over { index, .. }) => self.hover = Some((column, index)),
+            Msg::Column(_, DropEvent::Leave) => self.hover = None,
+            Msg::Column(column, DropEvent::Drop { payload, index }) => {
+                for cards in &mut self.columns {
+                    cards.retain(|card| *card != payload);
+                }
+                let cards = &mut self.columns[column];
+                let index = index.min(cards.len());
+                cards.insert(index, payload);
+                self.hover = None;
+            }
+        }
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        html! {
+            <div>
+                { for self.columns.iter().enumerate().map(|(column, cards)| html! {
+                    <ul id=format!("column-{}", column), listener=dnd::target(move |event| Msg::Column(column, event)),>
+                        { for cards.iter().map(|&card| html! {
+                            <li id=format!("card-{}", card), listener=dnd::source(card, move |event| Msg::Drag(card, event)),>
+                                { card }
+                            </li>
+                        }) }
+                    </ul>
+                }) }
+            </div>
+        }
+    }
+}
+
+#[test]
+fn it_moves_payloads_between_targets() {
+    let mut board = TestRenderer::<Board>::new(());
+    board.simulate("#card-1", "ondrag", DragEvent::Start);
+    assert_eq!(board.component().dragging, Some(1));
+    assert_eq!(dnd::dragged::<u32>(), Some(1));
+    assert_eq!(dnd::dragged::<String>(), None);
+
+    board.simulate("#column-1", "ondrop", DropAction::Over(1));
+    assert_eq!(board.component().hover, Some((1, 1)));
+    board.simulate("#column-1", "ondrop", DropAction::Drop(1));
+    board.simulate("#card-1", "ondrag", DragEvent::End { dropped: true });
+    assert_eq!(board.component().columns, vec![vec![2], vec![3, 1]]);
+    assert_eq!(board.component().dragging, None);
+    assert_eq!(dnd::dragged::<u32>(), None);
+}
+
+#[test]
+fn it_ignores_drops_without_a_drag() {
+    let mut board = TestRenderer::<Board>::new(());
+    assert!(!board.simulate("#column-0", "ondrop", DropAction::Drop(0)));
+    assert_eq!(board.component().columns, vec![vec![1, 2], vec![3]]);
+}
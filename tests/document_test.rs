@@ -0,0 +1,97 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::services::document::{DocumentHandle, DocumentService};
+use yew::services::mock::MockDocument;
+use yew::testing::TestRenderer;
+use yew::virtual_dom::NodeRef;
+
+enum Msg {
+    Close,
+}
+
+struct Dropdown {
+    panel: NodeRef,
+    open: bool,
+    _escape: Option<DocumentHandle>,
+    _outside: Option<DocumentHandle>,
+}
+
+impl Component for Dropdown {
+    type Msg = Msg;
+    type Properties = ();
+
+    fn create(_: (), sender: AppSender<Msg>) -> Self {
+        let panel = NodeRef::default();
+        let mut document = DocumentService::new(sender);
+        let escape = document.on_escape(|| Msg::Close);
+        let outside = document.on_outside_press(std::slice::from_ref(&panel), || Msg::Close);
+        Dropdown { panel, open: true, _escape: Some(escape), _outside: Some(outside) }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Close => {
+                self.open = false;
+                self._escape = None;
+                self._outside = None;
+                true
+            }
+        }
+    }
+
+    fn view(&self) -> Html<Msg> {
+        html! { <div ref=&self.panel,></div> }
+    }
+}
+
+#[test]
+fn it_shares_one_listener_of_the_document() {
+    let document = MockDocument::new();
+    let _installed = document.install();
+    let mut first = TestRenderer::<Dropdown>::new(());
+    let mut second = TestRenderer::<Dropdown>::new(());
+    assert_eq!(document.listeners("keydown"), 1);
+    assert_eq!(document.listeners("pointerdown"), 1);
+
+    // A press inside of the second dropdown closes the first one only
+    let panel = second.component().panel.clone();
+    document.press(Some(&panel));
+    first.flush();
+    second.flush();
+    assert!(!first.component().open);
+    assert!(second.component().open);
+    assert_eq!(document.listeners("pointerdown"), 1);
+
+    document.press(None);
+    second.flush();
+    assert!(!second.component().open);
+    assert_eq!(document.listeners("keydown"), 0);
+    assert_eq!(document.listeners("pointerdown"), 0);
+}
+
+#[test]
+fn it_sends_escape_to_the_latest_request() {
+    let document = MockDocument::new();
+    let _installed = document.install();
+    let mut menu = TestRenderer::<Dropdown>::new(());
+    let mut submenu = TestRenderer::<Dropdown>::new(());
+
+    document.press_key("Escape");
+    menu.flush();
+    submenu.flush();
+    assert!(menu.component().open);
+    assert!(!submenu.component().open);
+
+    document.press_key("Enter");
+    menu.flush();
+    assert!(menu.component().open);
+
+    document.press_key("Escape");
+    menu.flush();
+    assert!(!menu.component().open);
+    assert_eq!(document.listeners("keydown"), 0);
+}
@@ -0,0 +1,82 @@
+extern crate serde_json;
+extern crate yew;
+
+use std::rc::Rc;
+use yew::entity::{Entity, Ref, Selector, Store};
+
+#[derive(Clone, Debug, PartialEq)]
+struct User {
+    id: u32,
+    name: String,
+}
+
+impl Entity for User {
+    type Id = u32;
+
+    fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Post {
+    id: u32,
+    title: &'static str,
+    author: Ref<User>,
+}
+
+impl Entity for Post {
+    type Id = u32;
+
+    fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+fn user(id: u32, name: &str) -> User {
+    User { id, name: name.into() }
+}
+
+#[test]
+fn it_resolves_references() {
+    let mut store = Store::new();
+    let ann = store.insert(user(1, "Ann"));
+    store.insert_all(vec![
+        Post { id: 10, title: "Hello", author: ann.clone() },
+        Post { id: 11, title: "Again", author: ann.clone() },
+        Post { id: 12, title: "Lost", author: Ref::new(2) },
+    ]);
+    assert_eq!(store.all::<Post>().len(), 3);
+    let post = store.get::<Post>(&12).unwrap();
+    assert_eq!(store.resolve(&post.author), None);
+
+    // An update is seen through every reference
+    assert!(store.update::<User, _>(&1, |user| user.name = "Ann Lee".into()));
+    let authors: Vec<String> = store.filter::<Post, _>(|post| post.author == ann).iter()
+        .filter_map(|post| store.resolve(&post.author))
+        .map(|user| user.name.clone())
+        .collect();
+    assert_eq!(authors, vec!["Ann Lee", "Ann Lee"]);
+
+    assert!(store.remove::<Post>(&10).is_some());
+    let titles: Vec<&str> = store.all::<Post>().iter().map(|post| post.title).collect();
+    assert_eq!(titles, vec!["Again", "Lost"]);
+    assert_eq!(serde_json::to_string(&ann).unwrap(), "1");
+    let parsed: Ref<User> = serde_json::from_str("1").unwrap();
+    assert_eq!(parsed, ann);
+}
+
+#[test]
+fn it_computes_selectors_when_the_store_changes() {
+    let mut store = Store::new();
+    store.insert(user(1, "Ann"));
+    let names = Selector::new(|store: &Store| {
+        store.all::<User>().iter().map(|user| user.name.clone()).collect::<Vec<_>>()
+    });
+    let first = names.get(&store);
+    assert!(Rc::ptr_eq(&first, &names.get(&store)));
+
+    store.insert(user(2, "Bob"));
+    assert_eq!(*names.get(&store), vec!["Ann", "Bob"]);
+    assert_eq!(*names.get(&Store::new()), Vec::<String>::new());
+}
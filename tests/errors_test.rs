@@ -0,0 +1,106 @@
+#![cfg(feature = "testing")]
+
+extern crate serde_json;
+#[macro_use]
+extern crate yew;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+use serde_json::Value;
+use yew::callback::Callback;
+use yew::components::error_boundary::{ErrorBoundary, ErrorBoundaryProps};
+use yew::errors::{self, ErrorKind, ErrorReport, HttpSink};
+use yew::format::{Json, Restorable};
+use yew::html::Link;
+use yew::services::mock::{MockClock, MockNetwork};
+use yew::testing::TestRenderer;
+
+fn collect() -> Rc<RefCell<Vec<ErrorReport>>> {
+    let reports = Rc::new(RefCell::new(Vec::new()));
+    let sink = reports.clone();
+    errors::add_sink(Callback::from(move |report| sink.borrow_mut().push(report)));
+    reports
+}
+
+#[test]
+fn it_publishes_errors_to_sinks() {
+    let reports = collect();
+    errors::report(ErrorReport::new(ErrorKind::App, "cart", "a product is gone").tag("id", "7"));
+    let restored: Restorable = Ok("[1, 2".into());
+    let Json(data): Json<Result<Vec<u32>, ()>> = Json::from(restored);
+    assert!(data.is_err());
+
+    let reports = reports.borrow();
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[0].to_string(), "app error of cart: a product is gone");
+    assert_eq!(reports[0].tags, vec![("id".to_owned(), "7".to_owned())]);
+    assert_eq!(reports[1].kind, ErrorKind::Decode);
+    assert_eq!(reports[1].origin, "json");
+    errors::clear_sinks();
+}
+
+#[test]
+fn it_posts_errors_in_batches() {
+    let clock = MockClock::new();
+    let network = MockNetwork::new();
+    let _installed = (clock.install(), network.install());
+    errors::add_sink(HttpSink::new("/errors").release("1.2.0").batch(3, Duration::from_secs(2)));
+    let error = ErrorReport::new(ErrorKind::Service, "fetch", "GET /api failed");
+
+    errors::report(error.clone());
+    errors::report(error.clone());
+    assert_eq!(network.pending(), 0);
+    clock.advance(Duration::from_secs(2));
+    let requests = network.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "POST");
+    let body: Value = serde_json::from_str(requests[0].body.as_ref().unwrap()).unwrap();
+    assert_eq!(body["events"].as_array().unwrap().len(), 2);
+    assert_eq!(body["events"][0]["logger"], "fetch");
+    assert_eq!(body["events"][0]["release"], "1.2.0");
+    assert_eq!(body["events"][0]["tags"]["kind"], "service");
+
+    // A full batch is sent at once and its timer is canceled
+    for _ in 0..3 {
+        errors::report(error.clone());
+    }
+    assert_eq!(network.pending(), 2);
+    assert_eq!(clock.pending(), 0);
+
+    errors::report(ErrorReport::new(ErrorKind::Panic, "src/main.rs:1:1", "boom"));
+    let requests = network.requests();
+    assert_eq!(requests.len(), 3);
+    let body: Value = serde_json::from_str(requests[2].body.as_ref().unwrap()).unwrap();
+    assert_eq!(body["events"][0]["level"], "fatal");
+    errors::clear_sinks();
+}
+
+#[test]
+fn it_replaces_failed_content_with_an_alert() {
+    let reports = collect();
+    let fail = Rc::new(RefCell::new(None));
+    let keep = fail.clone();
+    let link = Link::<()>::new();
+    let props = ErrorBoundaryProps {
+        name: "recommendations".into(),
+        render: link.render(move |callback: Callback<String>| {
+            *keep.borrow_mut() = Some(callback);
+            html! { <p class="content",>{ "Picks" }</p> }
+        }),
+        ..ErrorBoundaryProps::default()
+    };
+    let mut boundary = TestRenderer::<ErrorBoundary>::new(props);
+    assert!(boundary.root().find(".content").is_some());
+
+    fail.borrow().clone().unwrap().emit("the service is down".into());
+    boundary.flush();
+    let alert = boundary.root().find("[role=alert]").expect("an alert");
+    assert_eq!(alert.find(".error-boundary-message").unwrap().text(), "the service is down");
+    assert_eq!(reports.borrow()[0].kind, ErrorKind::Component);
+    assert_eq!(reports.borrow()[0].origin, "recommendations");
+
+    boundary.click(".error-boundary-retry");
+    assert!(boundary.root().find(".content").is_some());
+    errors::clear_sinks();
+}
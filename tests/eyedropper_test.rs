@@ -0,0 +1,24 @@
+extern crate yew;
+
+use yew::services::eyedropper::Srgb;
+
+#[test]
+fn it_parses_and_prints_hex_colors() {
+    assert_eq!(Srgb::from_hex("#1e90ff"), Some(Srgb::new(0x1e, 0x90, 0xff)));
+    assert_eq!(Srgb::from_hex("FFF"), Some(Srgb::new(255, 255, 255)));
+    assert_eq!(Srgb::from_hex("#12345"), None);
+    assert_eq!(Srgb::from_hex("#gg0000"), None);
+    assert_eq!(Srgb::new(0, 128, 255).to_hex(), "#0080ff");
+    assert_eq!(Srgb::new(10, 20, 30).to_string(), "#0a141e");
+}
+
+#[test]
+fn it_measures_contrast() {
+    let black = Srgb::new(0, 0, 0);
+    let white = Srgb::new(255, 255, 255);
+    assert_eq!(black.luminance(), 0.0);
+    assert!((white.luminance() - 1.0).abs() < 1e-9);
+    assert!((black.contrast(&white) - 21.0).abs() < 1e-9);
+    assert_eq!(white.contrast(&black), black.contrast(&white));
+    assert!(Srgb::new(0x77, 0x77, 0x77).contrast(&white) < 4.5);
+}
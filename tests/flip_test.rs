@@ -0,0 +1,33 @@
+extern crate yew;
+
+use yew::flip::{self, Flip, Move, Position};
+
+fn at(key: &str, left: f64, top: f64) -> (String, Position) {
+    (key.to_owned(), Position { left, top })
+}
+
+#[test]
+fn it_inverts_moved_items() {
+    let first = vec![at("a", 0.0, 0.0), at("b", 0.0, 40.0), at("c", 0.0, 80.0)];
+    let last = vec![at("c", 0.0, 0.0), at("a", 0.0, 40.0), at("b", 0.0, 80.0), at("d", 0.0, 120.0)];
+    assert_eq!(flip::moves(&first, &last), vec![
+        Move { key: "c".into(), dx: 0.0, dy: 80.0 },
+        Move { key: "a".into(), dx: 0.0, dy: -40.0 },
+        Move { key: "b".into(), dx: 0.0, dy: -40.0 },
+    ]);
+}
+
+#[test]
+fn it_skips_items_in_place() {
+    let first = vec![at("a", 10.0, 0.0), at("b", 10.0, 40.0)];
+    let last = vec![at("a", 10.4, 0.0), at("b", 10.0, 40.0)];
+    assert!(flip::moves(&first, &last).is_empty());
+}
+
+#[test]
+fn it_plays_nothing_without_a_container() {
+    let mut flip = Flip::new();
+    flip.first();
+    flip.play();
+    flip.play();
+}
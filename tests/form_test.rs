@@ -0,0 +1,66 @@
+#[macro_use]
+extern crate yew;
+
+use yew::form::{Fields, Form};
+
+#[derive(Default)]
+struct Signup {
+    email: String,
+    age: u32,
+}
+
+form_fields!(Signup { email, age });
+
+fn signup_form() -> Form<Signup> {
+    Form::new(Signup::default())
+        .validator("email", |signup| {
+            if signup.email.contains('@') {
+                Ok(())
+            } else {
+                Err("wrong email".into())
+            }
+        })
+}
+
+#[test]
+fn it_maps_fields() {
+    let mut signup = Signup::default();
+    assert_eq!(Signup::names(), &["email", "age"]);
+    signup.set("age", "18".into()).unwrap();
+    assert_eq!(signup.age, 18);
+    assert_eq!(signup.get("age"), Some("18".into()));
+    assert!(signup.set("age", "old".into()).is_err());
+    assert!(signup.set("name", "John".into()).is_err());
+}
+
+#[test]
+fn it_validates_fields() {
+    let mut form = signup_form();
+    assert!(!form.is_dirty());
+    form.set("email", "john".into());
+    assert!(form.is_field_dirty("email"));
+    assert_eq!(form.error("email"), Some("wrong email"));
+    assert!(!form.is_touched("email"));
+    form.set("email", "john@example.com".into());
+    assert_eq!(form.error("email"), None);
+    assert!(form.validate());
+}
+
+#[test]
+fn it_keeps_raw_values() {
+    let mut form = signup_form();
+    form.set("age", "old".into());
+    assert_eq!(form.value("age"), "old");
+    assert!(form.error("age").is_some());
+    form.set("age", "18".into());
+    assert_eq!(form.value("age"), "18");
+    assert_eq!(form.error("age"), None);
+}
+
+#[test]
+fn it_validates_untouched_fields() {
+    let mut form = signup_form();
+    assert!(!form.validate());
+    assert!(form.is_touched("email"));
+    assert_eq!(form.error("email"), Some("wrong email"));
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+extern crate stdweb;
+
+use std::cell::Cell;
+use std::rc::Rc;
+use stdweb::Value;
+use yew::html::{Component, ShouldRender, AppSender, Html, HostAttributes, Callback};
+use yew::virtual_dom::VTag;
+use yew::testing::TestRenderer;
+
+#[derive(Default)]
+struct ButtonProps {
+    label: String,
+    host: HostAttributes,
+}
+
+struct Button {
+    props: ButtonProps,
+}
+
+impl Component for Button {
+    type Msg = ();
+    type Properties = ButtonProps;
+
+    fn create(props: Self::Properties, _: AppSender<Self::Msg>) -> Self {
+        Button { props }
+    }
+
+    fn update(&mut self, _: Self::Msg) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html<Self::Msg> {
+        html! {
+            <button class="button", type="button", ..&self.props.host,>{ &self.props.label }</button>
+        }
+    }
+}
+
+#[test]
+fn it_spreads_host_attributes() {
+    let focused = Rc::new(Cell::new(0));
+    let counter = focused.clone();
+    let host = HostAttributes::new()
+        .attr("id", "save")
+        .attr("aria-describedby", "save-hint")
+        .attr("type", "submit")
+        .class("primary")
+        .listen("focus", Callback::from(move |_| counter.set(counter.get() + 1)));
+    let mut button = TestRenderer::<Button>::new(ButtonProps { label: "Save".into(), host });
+
+    {
+        let element = button.root().find("button").and_then(|node| node.element()).unwrap();
+        assert_eq!(element.attribute("id"), Some("save"));
+        assert_eq!(element.attribute("aria-describedby"), Some("save-hint"));
+        assert_eq!(element.attribute("type"), Some("submit"));
+        assert!(element.has_class("button"));
+        assert!(element.has_class("primary"));
+        assert!(element.listens("focus"));
+    }
+
+    button.simulate("button", "focus", Value::Null);
+    assert_eq!(focused.get(), 1);
+}
+
+#[test]
+fn it_spreads_pairs_and_host_attributes_alike() {
+    let host = HostAttributes::new().attr("role", "tab").class("active");
+    let pairs = vec![("role", "tab")];
+
+    let a: VTag<()> = html! { <li class="tab", ..host,></li> };
+    let b: VTag<()> = html! { <li class=("tab", "active"), ..pairs,></li> };
+
+    assert_eq!(a, b);
+    assert!(HostAttributes::new().is_empty());
+}
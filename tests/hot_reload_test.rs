@@ -0,0 +1,51 @@
+#![cfg(all(feature = "testing", feature = "hot_reload"))]
+
+extern crate yew;
+
+use yew::hot_reload::HotReload;
+use yew::middleware::Middleware;
+use yew::services::backend::StorageBackend;
+use yew::services::mock::{MockStorage, MockUnload};
+use yew::services::storage::Scope;
+
+type Model = (u32, String);
+
+fn updated(hot: &mut HotReload, model: &Model) {
+    assert!(Middleware::<Model, ()>::should_render(hot, model));
+}
+
+#[test]
+fn it_keeps_the_model_across_reloads() {
+    let storage = MockStorage::new();
+    let page = MockUnload::new();
+    let _storage = storage.install();
+    let _page = page.install();
+
+    let mut hot = HotReload::new("todos");
+    let model = hot.restore((0, String::new()));
+    assert_eq!(model, (0, String::new()));
+    updated(&mut hot, &(3, "draft".into()));
+    assert!(!page.unload());
+    drop(hot);
+
+    let mut hot = HotReload::new("todos");
+    assert_eq!(hot.restore((0, String::new())), (3, "draft".to_owned()));
+    // A snapshot is restored once, but the model is kept for the next reload
+    assert_eq!(storage.get(Scope::Session, "yew-hot-reload:todos"), None);
+    assert!(!page.unload());
+    drop(hot);
+    assert_eq!(HotReload::new("todos").restore((0, String::new())), (3, "draft".to_owned()));
+}
+
+#[test]
+fn it_starts_over_if_the_model_changed() {
+    let storage = MockStorage::new();
+    let page = MockUnload::new();
+    let _storage = storage.install();
+    let _page = page.install();
+
+    storage.set(Scope::Session, "yew-hot-reload:todos", "[1, 2, 3]");
+    let mut hot = HotReload::new("todos");
+    assert_eq!(hot.restore((0, "new".to_owned())), (0, "new".to_owned()));
+    assert_eq!(HotReload::new("other").restore((7, String::new())), (7, String::new()));
+}
@@ -0,0 +1,49 @@
+#![cfg(all(feature = "web_test", feature = "ssr", any(target_arch = "wasm32", target_os = "emscripten")))]
+
+#[macro_use]
+extern crate yew;
+#[macro_use]
+extern crate stdweb;
+
+use stdweb::web::{document, Element};
+use yew::html::{App, Html};
+use yew::ssr;
+
+struct Model {
+    notes: String,
+}
+
+fn view(model: &Model) -> Html<()> {
+    html! {
+        <form>
+            <textarea value=&model.notes,></textarea>
+        </form>
+    }
+}
+
+fn server_markup(notes: &str) -> Element {
+    let root = document().create_element("div");
+    let markup = ssr::render_to_string(view(&Model { notes: notes.to_owned() }));
+    js! { @(no_return)
+        var root = @{&root};
+        root.innerHTML = @{markup};
+        document.body.appendChild(root);
+    }
+    root
+}
+
+#[test]
+fn it_adopts_filled_textareas() {
+    yew::initialize();
+    let root = server_markup("\nFirst line\nsecond line");
+    js! { @(no_return) window.serverTextarea = @{&root}.querySelector("textarea"); }
+
+    let mut app = App::<()>::new();
+    app.hydrate_to_element(root.clone(), (), Model { notes: "\nFirst line\nsecond line".into() }, |_, _, _| (), view);
+
+    // A mismatch would render the view again with a new textarea
+    let adopted = js!( return @{&root}.querySelector("textarea") === window.serverTextarea; );
+    assert_eq!(adopted, true);
+    let value = js!( return @{&root}.querySelector("textarea").value; );
+    assert_eq!(value.into_string().unwrap(), "\nFirst line\nsecond line");
+}
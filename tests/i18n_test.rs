@@ -0,0 +1,150 @@
+#![cfg(all(feature = "i18n", feature = "testing"))]
+
+#[macro_use]
+extern crate yew;
+
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::direction::{self, Direction};
+use yew::i18n::{self, plural, Args, Bundle, LocaleHandle, LocaleService};
+use yew::testing::TestRenderer;
+
+const EN: &str = r#"
+# Messages of the inbox
+-brand = Yew Mail
+    .gender = feminine
+
+greeting = Hello, { $name }!
+title = Welcome to { -brand }
+emails = { $count ->
+    [0] No emails
+    [one] One email
+   *[other] { $count } emails
+}
+about =
+    First line
+    of a paragraph.
+search = Search
+    .placeholder = Type to search { -brand }
+    .title = { search }
+"#;
+
+const RU: &str = r#"
+emails = { $count ->
+    [one] { $count } письмо
+    [few] { $count } письма
+   *[many] { $count } писем
+}
+"#;
+
+#[test]
+fn it_formats_messages() {
+    let bundle = Bundle::parse("en", EN).unwrap();
+    let format = |id, args: Args| bundle.format(id, &args).unwrap();
+    assert_eq!(format("greeting", Args::new().with("name", "Ann")), "Hello, Ann!");
+    assert_eq!(format("title", Args::new()), "Welcome to Yew Mail");
+    assert_eq!(format("about", Args::new()), "First line\nof a paragraph.");
+    assert_eq!(format("search.placeholder", Args::new()), "Type to search Yew Mail");
+    assert_eq!(format("search.title", Args::new()), "Search");
+    assert_eq!(format("greeting", Args::new()), "Hello, {$name}!");
+    assert_eq!(bundle.format("missing", &Args::new()), None);
+}
+
+#[test]
+fn it_selects_plural_variants() {
+    let en = Bundle::parse("en", EN).unwrap();
+    let emails = |bundle: &Bundle, count| bundle.format("emails", &Args::new().with("count", count)).unwrap();
+    assert_eq!(emails(&en, 0), "No emails");
+    assert_eq!(emails(&en, 1), "One email");
+    assert_eq!(emails(&en, 5), "5 emails");
+    let ru = Bundle::parse("ru", RU).unwrap();
+    assert_eq!(emails(&ru, 21), "21 письмо");
+    assert_eq!(emails(&ru, 3), "3 письма");
+    assert_eq!(emails(&ru, 11), "11 писем");
+}
+
+#[test]
+fn it_applies_plural_rules() {
+    assert_eq!(plural::category("en-US", 1.0), "one");
+    assert_eq!(plural::category("en-US", 1.5), "other");
+    assert_eq!(plural::category("fr", 0.0), "one");
+    assert_eq!(plural::category("pl", 22.0), "few");
+    assert_eq!(plural::category("pl", 25.0), "many");
+    assert_eq!(plural::category("ar", 2.0), "two");
+    assert_eq!(plural::category("ar", 105.0), "few");
+    assert_eq!(plural::category("ja", 1.0), "other");
+}
+
+#[test]
+fn it_reports_syntax_errors() {
+    let error = Bundle::parse("en", "ok = Fine\nbroken = { $count ->\n    [one] One\n}\n").err().unwrap();
+    assert_eq!(error.line, 4);
+    assert!(Bundle::parse("en", "= value").is_err());
+    assert!(Bundle::parse("en", "text = { \"unterminated }").is_err());
+}
+
+#[test]
+fn it_translates_with_fallbacks() {
+    i18n::add_bundle(Bundle::parse("en", EN).unwrap());
+    i18n::add_bundle(Bundle::parse("ru", RU).unwrap());
+    i18n::set_fallback("en");
+    i18n::set_locale("ru-RU");
+    assert_eq!(t!("emails", count = 2), "2 письма");
+    assert_eq!(t!("greeting", name = "Ann"), "Hello, Ann!");
+    assert_eq!(t!("unknown"), "unknown");
+    assert_eq!(i18n::with_locale("en", || t!("emails", count = 2)), "2 emails");
+    assert_eq!(i18n::locale(), "ru-RU");
+    assert_eq!(i18n::negotiate(&["de-DE", "ru-UA"]), Some("ru".to_owned()));
+}
+
+struct Inbox {
+    unread: u32,
+    _locale: LocaleHandle,
+}
+
+enum Msg {
+    LocaleChanged,
+}
+
+impl Component for Inbox {
+    type Msg = Msg;
+    type Properties = ();
+
+    fn create(_: (), sender: AppSender<Msg>) -> Self {
+        let handle = LocaleService::new(sender).listen(|_| Msg::LocaleChanged);
+        Inbox { unread: 3, _locale: handle }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::LocaleChanged => true,
+        }
+    }
+
+    fn view(&self) -> Html<Msg> {
+        html! {
+            <p>{ t!("emails", count = self.unread) }</p>
+        }
+    }
+}
+
+#[test]
+fn it_renders_again_when_locale_changes() {
+    i18n::add_bundle(Bundle::parse("en", EN).unwrap());
+    i18n::add_bundle(Bundle::parse("ru", RU).unwrap());
+    i18n::set_locale("en");
+    let mut inbox = TestRenderer::<Inbox>::new(());
+    assert_eq!(inbox.root().find("p").unwrap().text(), "3 emails");
+    i18n::set_locale("ru");
+    inbox.flush();
+    assert_eq!(inbox.root().find("p").unwrap().text(), "3 письма");
+}
+
+#[test]
+fn it_follows_direction_of_the_locale() {
+    assert_eq!(direction::direction(), Direction::Ltr);
+    i18n::add_bundle(Bundle::parse("ar", "title = مرحبا").unwrap());
+    i18n::set_locale("ar-EG");
+    assert_eq!(direction::direction(), Direction::Rtl);
+    i18n::set_locale("en");
+    assert_eq!(direction::direction(), Direction::Ltr);
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use std::time::Duration;
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::services::idle::{IdleEvent, IdleHandle, IdleService};
+use yew::services::mock::{MockActivity, MockClock};
+use yew::testing::TestRenderer;
+
+struct Presence {
+    events: Vec<IdleEvent>,
+    _idle: IdleHandle,
+}
+
+impl Component for Presence {
+    type Msg = IdleEvent;
+    type Properties = ();
+
+    fn create(_: (), sender: AppSender<IdleEvent>) -> Self {
+        let thresholds = [Duration::from_secs(600), Duration::from_secs(60)];
+        let handle = IdleService::new(sender).watch(&thresholds, |event| event);
+        Presence { events: Vec::new(), _idle: handle }
+    }
+
+    fn update(&mut self, event: IdleEvent) -> ShouldRender {
+        self.events.push(event);
+        true
+    }
+
+    fn view(&self) -> Html<IdleEvent> {
+        html! { <p>{ self.events.len() }</p> }
+    }
+}
+
+#[test]
+fn it_sends_idle_and_active_transitions() {
+    let (clock, user) = (MockClock::new(), MockActivity::new());
+    let _installed = (clock.install(), user.install());
+    let mut presence = TestRenderer::<Presence>::new(());
+
+    clock.advance(Duration::from_secs(50));
+    user.act();
+    clock.advance(Duration::from_secs(50));
+    presence.flush();
+    assert!(presence.component().events.is_empty());
+
+    clock.advance(Duration::from_secs(10));
+    presence.flush();
+    assert_eq!(presence.component().events, vec![IdleEvent::Idle(Duration::from_secs(60))]);
+    clock.advance(Duration::from_secs(540));
+    user.act();
+    user.act();
+    presence.flush();
+    assert_eq!(presence.component().events, vec![
+        IdleEvent::Idle(Duration::from_secs(60)),
+        IdleEvent::Idle(Duration::from_secs(600)),
+        IdleEvent::Active,
+    ]);
+}
+
+#[test]
+fn it_stops_watching_when_canceled() {
+    let (clock, user) = (MockClock::new(), MockActivity::new());
+    let _installed = (clock.install(), user.install());
+    let presence = TestRenderer::<Presence>::new(());
+    assert_eq!(user.listeners(), 1);
+    assert_eq!(clock.pending(), 1);
+    drop(presence);
+    assert_eq!(user.listeners(), 0);
+    assert_eq!(clock.pending(), 0);
+}
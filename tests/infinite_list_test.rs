@@ -0,0 +1,102 @@
+#![cfg(feature = "testing")]
+
+extern crate yew;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use yew::callback::Callback;
+use yew::components::infinite_list::{Edge, EdgeState, InfiniteList, InfiniteListProps, Page, Pages};
+use yew::services::mock::MockIntersection;
+use yew::testing::TestRenderer;
+
+fn props(requests: &Rc<RefCell<Vec<Edge>>>) -> InfiniteListProps {
+    let requests = requests.clone();
+    InfiniteListProps {
+        count: 10,
+        onload: Callback::from(move |edge| requests.borrow_mut().push(edge)),
+        ..InfiniteListProps::default()
+    }
+}
+
+#[test]
+fn it_loads_pages_while_the_end_is_visible() {
+    let viewport = MockIntersection::new();
+    let _installed = viewport.install();
+    let requests = Rc::new(RefCell::new(Vec::new()));
+    let mut list = TestRenderer::<InfiniteList>::new(props(&requests));
+    assert_eq!(list.root().find_all(".infinite-list-sentinel").len(), 1);
+
+    viewport.set_visible(0, true);
+    list.flush();
+    viewport.set_visible(0, true);
+    list.flush();
+    assert_eq!(*requests.borrow(), vec![Edge::End]);
+
+    // A short page leaves the sentinel visible, so the next one is asked for
+    list.change(InfiniteListProps { end: EdgeState::Loading, ..props(&requests) });
+    list.change(InfiniteListProps { count: 20, ..props(&requests) });
+    assert_eq!(*requests.borrow(), vec![Edge::End, Edge::End]);
+
+    viewport.set_visible(0, false);
+    list.flush();
+    list.change(InfiniteListProps { end: EdgeState::Failed, ..props(&requests) });
+    list.change(InfiniteListProps { count: 30, ..props(&requests) });
+    list.change(InfiniteListProps { end: EdgeState::Done, ..props(&requests) });
+    viewport.set_visible(0, true);
+    list.flush();
+    assert_eq!(requests.borrow().len(), 2);
+}
+
+#[test]
+fn it_loads_both_ends_of_bidirectional_lists() {
+    let viewport = MockIntersection::new();
+    let _installed = viewport.install();
+    let requests = Rc::new(RefCell::new(Vec::new()));
+    let mut list = TestRenderer::<InfiniteList>::new(InfiniteListProps { bidirectional: true, ..props(&requests) });
+    assert_eq!(list.root().find_all(".infinite-list-sentinel").len(), 2);
+
+    viewport.set_visible(1, true);
+    list.flush();
+    assert_eq!(*requests.borrow(), vec![Edge::Start]);
+
+    list.change(props(&requests));
+    assert!(!viewport.is_observed(1));
+    drop(list);
+    assert!(!viewport.is_observed(0));
+}
+
+#[test]
+fn it_keeps_pages_of_both_ends() {
+    let mut pages: Pages<u32, String> = Pages::new();
+    pages.load(Edge::End, Noop);
+    assert_eq!(pages.state(Edge::End), EdgeState::Loading);
+    pages.resolve(Edge::End, Ok(Page { items: vec![3, 4], more: true }));
+    pages.load(Edge::Start, Noop);
+    pages.resolve(Edge::Start, Ok(Page { items: vec![1, 2], more: false }));
+    assert_eq!(pages.items(), &[1, 2, 3, 4]);
+    assert_eq!(pages.state(Edge::Start), EdgeState::Done);
+    assert_eq!(pages.state(Edge::End), EdgeState::Idle);
+
+    pages.load(Edge::End, Noop);
+    pages.resolve(Edge::End, Err("offline".into()));
+    assert_eq!(pages.state(Edge::End), EdgeState::Failed);
+    assert_eq!(pages.error(Edge::End), Some(&"offline".to_string()));
+    pages.retry(Edge::End);
+    assert_eq!(pages.state(Edge::End), EdgeState::Idle);
+
+    // Pages of a reset list are ignored
+    pages.load(Edge::End, Noop);
+    pages.reset();
+    pages.resolve(Edge::End, Ok(Page { items: vec![5], more: true }));
+    assert!(pages.is_empty());
+}
+
+struct Noop;
+
+impl yew::services::Task for Noop {
+    fn is_active(&self) -> bool {
+        false
+    }
+
+    fn cancel(&mut self) {}
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::services::install::{InstallChoice, InstallEvent, InstallHandle, InstallService};
+use yew::services::mock::MockInstall;
+use yew::testing::TestRenderer;
+
+#[derive(Debug, PartialEq)]
+enum Msg {
+    Install(InstallEvent),
+    Pressed,
+    Chosen(InstallChoice),
+}
+
+struct Banner {
+    install: InstallService<Msg>,
+    installable: bool,
+    choices: Vec<InstallChoice>,
+    _listener: InstallHandle,
+}
+
+impl Component for Banner {
+    type Msg = Msg;
+    type Properties = ();
+
+    fn create(_: (), sender: AppSender<Msg>) -> Self {
+        let mut install = InstallService::new(sender);
+        let listener = install.listen(Msg::Install);
+        Banner {
+            installable: install.is_installable(),
+            install,
+            choices: Vec::new(),
+            _listener: listener,
+        }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Install(InstallEvent::Installable) => self.installable = true,
+            Msg::Install(InstallEvent::Installed) => self.installable = false,
+            Msg::Pressed => self.installable = !self.install.prompt(Msg::Chosen),
+            Msg::Chosen(choice) => self.choices.push(choice),
+        }
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        if self.installable {
+            html! { <button onclick=|_| Msg::Pressed,>{ "Install" }</button> }
+        } else {
+            html! { <p>{ "Installed or not installable" }</p> }
+        }
+    }
+}
+
+#[test]
+fn it_shows_the_prompt_after_an_offer() {
+    let browser = MockInstall::new();
+    let _installed = browser.install();
+    let mut banner = TestRenderer::<Banner>::new(());
+    assert!(!banner.component().installable);
+
+    browser.offer();
+    banner.flush();
+    assert!(banner.component().installable);
+    banner.click("button");
+    banner.flush();
+    assert!(browser.is_prompted());
+    assert!(!banner.component().installable);
+
+    assert!(browser.choose(InstallChoice::Dismissed));
+    banner.flush();
+    assert_eq!(banner.component().choices, vec![InstallChoice::Dismissed]);
+    // An offer is only used once
+    banner.sender().send(Msg::Pressed);
+    banner.flush();
+    assert!(!browser.is_prompted());
+}
+
+#[test]
+fn it_reports_installs() {
+    let browser = MockInstall::new();
+    let _installed = browser.install();
+    browser.offer();
+    let mut banner = TestRenderer::<Banner>::new(());
+    assert!(banner.component().installable);
+
+    browser.complete();
+    banner.flush();
+    assert!(!banner.component().installable);
+    assert!(!banner.component().install.is_installable());
+    drop(banner);
+    assert_eq!(browser.listeners(), 0);
+}
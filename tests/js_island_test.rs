@@ -0,0 +1,32 @@
+#![cfg(feature = "testing")]
+
+extern crate yew;
+#[macro_use]
+extern crate serde_json;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use yew::callback::Callback;
+use yew::components::JsIsland;
+use yew::components::js_island::{JsIslandProps, Msg};
+use yew::testing::TestRenderer;
+
+#[test]
+fn it_renders_a_wrapper_and_forwards_events() {
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let sink = events.clone();
+    let mut island = TestRenderer::<JsIsland>::new(JsIslandProps {
+        adapter: "chart".into(),
+        props: json!({ "data": [1, 2, 3] }),
+        tag: "canvas".into(),
+        class: "sales".into(),
+        onevent: Callback::from(move |data| sink.borrow_mut().push(data)),
+    });
+    let element = island.root().element().unwrap().clone();
+    assert_eq!(element.tag, "canvas");
+    assert!(element.has_class("sales"));
+    assert!(element.children.is_empty());
+
+    island.send(Msg::Event(json!({ "index": 2 })));
+    assert_eq!(*events.borrow(), vec![json!({ "index": 2 })]);
+}
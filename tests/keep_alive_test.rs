@@ -0,0 +1,43 @@
+#![cfg(feature = "testing")]
+
+extern crate yew;
+
+use yew::components::keep_alive::{KeepAlive, KeepAliveProps};
+use yew::testing::{TestNode, TestRenderer};
+
+fn props(current: u32) -> KeepAliveProps<u32> {
+    KeepAliveProps {
+        current: Some(current),
+        max: 2,
+        ..KeepAliveProps::default()
+    }
+}
+
+/// Returns `Some(hidden)` of every place of views, `None` for empty places.
+fn places(keep: &TestRenderer<KeepAlive<u32>>) -> Vec<Option<bool>> {
+    let root = keep.root().find(".keep-alive").expect("a root");
+    root.children().iter().map(|child| match *child {
+        TestNode::Element(ref view) => Some(view.attribute("hidden") == Some("true")),
+        _ => None,
+    }).collect()
+}
+
+#[test]
+fn it_keeps_views_of_recent_keys() {
+    let mut keep = TestRenderer::<KeepAlive<u32>>::new(props(1));
+    assert_eq!(places(&keep), vec![Some(false)]);
+
+    keep.change(props(2));
+    assert_eq!(places(&keep), vec![Some(true), Some(false)]);
+    keep.change(props(1));
+    assert_eq!(places(&keep), vec![Some(false), Some(true)]);
+
+    // The least recently shown view is destroyed and its place stays empty once
+    keep.change(props(3));
+    assert_eq!(places(&keep), vec![Some(true), None, Some(false)]);
+    keep.change(props(2));
+    assert_eq!(places(&keep), vec![None, Some(false), Some(true)]);
+
+    keep.change(KeepAliveProps { current: None, ..props(2) });
+    assert_eq!(places(&keep), vec![None, Some(true), Some(true)]);
+}
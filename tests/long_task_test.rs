@@ -0,0 +1,103 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use std::time::Duration;
+use yew::analytics::Event;
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::services::long_task::{Activity, LongTask, LongTaskHandle, LongTaskService};
+use yew::services::mock::{MockClock, MockLongTasks};
+use yew::testing::TestRenderer;
+
+enum Msg {
+    Filter,
+    Stalled(LongTask),
+}
+
+struct Search {
+    long_tasks: LongTaskService<Msg>,
+    stalls: Vec<LongTask>,
+    _handle: LongTaskHandle,
+}
+
+impl Component for Search {
+    type Msg = Msg;
+    type Properties = ();
+
+    fn create(_: (), sender: AppSender<Msg>) -> Self {
+        let mut long_tasks = LongTaskService::new(sender);
+        let handle = long_tasks.listen(Msg::Stalled);
+        Search { long_tasks, stalls: Vec::new(), _handle: handle }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Filter => true,
+            Msg::Stalled(task) => {
+                self.stalls.push(task);
+                false
+            }
+        }
+    }
+
+    fn view(&self) -> Html<Msg> {
+        html! { <ul></ul> }
+    }
+}
+
+const SEARCH: &str = "long_task_test::Search";
+
+#[test]
+fn it_attributes_long_tasks_to_updates() {
+    let clock = MockClock::new();
+    let thread = MockLongTasks::new();
+    let _installed = (clock.install(), thread.install());
+    let mut search = TestRenderer::<Search>::new(());
+    assert_eq!(thread.observers(), 1);
+
+    clock.advance(Duration::from_millis(1000));
+    search.send(Msg::Filter);
+    thread.stall(Duration::from_millis(990), Duration::from_millis(120));
+    search.flush();
+    let stall = &search.component().stalls[0];
+    assert_eq!(stall.blocking(), Duration::from_millis(70));
+    assert_eq!(stall.activities, vec![
+        Activity { phase: "update", name: SEARCH },
+        Activity { phase: "view", name: SEARCH },
+    ]);
+
+    // A task which didn't run updates of the app has no activities
+    thread.stall(Duration::from_millis(2000), Duration::from_millis(60));
+    search.flush();
+    assert!(search.component().stalls[1].activities.is_empty());
+
+    drop(search);
+    assert_eq!(thread.observers(), 0);
+}
+
+#[test]
+fn it_aggregates_long_tasks_to_a_report() {
+    let clock = MockClock::new();
+    let thread = MockLongTasks::new();
+    let _installed = (clock.install(), thread.install());
+    let mut search = TestRenderer::<Search>::new(());
+
+    clock.advance(Duration::from_millis(500));
+    search.send(Msg::Filter);
+    thread.stall(Duration::from_millis(480), Duration::from_millis(250));
+    thread.stall(Duration::from_millis(900), Duration::from_millis(80));
+    search.flush();
+
+    let report = search.component().long_tasks.report();
+    assert_eq!(report.tasks, 2);
+    assert_eq!(report.blocking, Duration::from_millis(230));
+    assert_eq!(report.longest, Duration::from_millis(250));
+    assert_eq!(report.culprits.len(), 1);
+    assert_eq!(report.culprits[0].name, SEARCH);
+    assert_eq!(report.culprits[0].blocking, Duration::from_millis(200));
+
+    let event = Event::from(report);
+    assert_eq!(event.name, "jank");
+    assert_eq!(event.properties["blocking_ms"], 230);
+}
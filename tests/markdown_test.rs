@@ -0,0 +1,67 @@
+#![cfg(feature = "markdown")]
+
+#[macro_use]
+extern crate yew;
+
+use yew::markdown;
+use yew::virtual_dom::VTag;
+
+#[test]
+fn it_renders_blocks() {
+    let a: VTag<()> = markdown::render("# Title\n\nSome *text* and `code`.\n\n- one\n- two\n\n---");
+
+    let b: VTag<()> = html! {
+        <div class="markdown",>
+            <h1>{ "Title" }</h1>
+            <p>{ "Some " }<em>{ "text" }</em>{ " and " }<code>{ "code" }</code>{ "." }</p>
+            <ul>
+                <li>{ "one" }</li>
+                <li>{ "two" }</li>
+            </ul>
+            <hr />
+        </div>
+    };
+
+    assert_eq!(a, b);
+}
+
+#[test]
+fn it_renders_links_and_code() {
+    let a: VTag<()> = markdown::render("[**Yew**](https://example.com)\n\n```rust\nlet x = 1;\n```");
+
+    let b: VTag<()> = html! {
+        <div class="markdown",>
+            <p><a href="https://example.com",><strong>{ "Yew" }</strong></a></p>
+            <pre><code class="language-rust",>{ "let x = 1;" }</code></pre>
+        </div>
+    };
+
+    assert_eq!(a, b);
+}
+
+#[test]
+fn it_sanitizes_markup() {
+    let a: VTag<()> = markdown::render("<script>alert(1)</script> [click](javascript:alert(1))");
+
+    let b: VTag<()> = html! {
+        <div class="markdown",>
+            <p>{ "<script>alert(1)</script> " }{ "click" }</p>
+        </div>
+    };
+
+    assert_eq!(a, b);
+}
+
+#[test]
+fn it_keeps_multibyte_whitespace_as_text() {
+    let a: VTag<()> = markdown::render("\u{3000}\u{3000}hello\n\n    let x = 1;\n");
+
+    let b: VTag<()> = html! {
+        <div class="markdown",>
+            <p>{ "hello" }</p>
+            <pre><code>{ "let x = 1;" }</code></pre>
+        </div>
+    };
+
+    assert_eq!(a, b);
+}
@@ -0,0 +1,117 @@
+#![cfg(feature = "testing")]
+
+extern crate yew;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use yew::callback::Callback;
+use yew::components::masked_input::{MaskedInput, MaskedInputProps};
+use yew::mask::{Mask, Masked, NumberMask};
+use yew::services::intl::IntlService;
+use yew::services::mock::MockIntl;
+use yew::testing::TestRenderer;
+
+#[test]
+fn it_formats_patterns_and_cards() {
+    let phone = Mask::phone();
+    assert_eq!(phone.raw("(555) 12a3-4567 89"), "5551234567");
+    assert_eq!(phone.format("5551"), "(555) 1");
+    assert!(phone.is_complete("5551234567"));
+    assert!(!phone.is_complete("555123"));
+    assert_eq!(phone.input_mode(), "numeric");
+
+    let uk = Mask::Pattern("+44 #### ######".into());
+    assert_eq!(uk.raw("+44 7911"), "7911");
+    assert_eq!(uk.format("7911123456"), "+44 7911 123456");
+    let plate = Mask::Pattern("AA-**".into());
+    assert_eq!(plate.format(&plate.raw("ab1c")), "AB-1C");
+
+    let card = Mask::CardNumber;
+    assert_eq!(card.format("4111111111111111"), "4111 1111 1111 1111");
+    assert_eq!(card.format("378282246310005"), "3782 822463 10005");
+    assert!(card.is_complete("4111111111111111"));
+    assert!(card.is_complete("378282246310005"));
+    assert!(!card.is_complete("4111111111111112"));
+}
+
+#[test]
+fn it_keeps_carets_while_reformatting() {
+    let phone = Mask::phone();
+    // A digit typed in the middle
+    let masked = phone.edit("(555) 123", "(5559) 123", 5);
+    assert_eq!(masked.formatted, "(555) 912-3");
+    assert_eq!(masked.caret, 7);
+    assert_eq!(masked.raw, "5559123");
+    // Backspace after a literal deletes the digit before it
+    let masked = phone.edit("(555) 123", "(555 123", 4);
+    assert_eq!(masked.raw, "55123");
+    assert_eq!(masked.formatted, "(551) 23");
+    assert_eq!(masked.caret, 3);
+    // A rejected character leaves the caret in place
+    let masked = phone.edit("(555) 1", "(55x5) 1", 4);
+    assert_eq!(masked.formatted, "(555) 1");
+    assert_eq!(masked.caret, 3);
+
+    let card = Mask::CardNumber;
+    let masked = card.edit("4111 1111", "4111 11119", 10);
+    assert_eq!(masked.formatted, "4111 1111 9");
+    assert_eq!(masked.caret, 11);
+}
+
+#[test]
+fn it_formats_numbers_with_locale_separators() {
+    let _intl = MockIntl::new().install();
+    let intl = IntlService::new();
+    let number = Mask::Number(NumberMask::new(&intl, 2));
+    assert_eq!(number.raw("1,234.567"), "1234.56");
+    assert_eq!(number.format("1234567.5"), "1,234,567.5");
+    let masked = number.edit("123", "1234", 4);
+    assert_eq!(masked.formatted, "1,234");
+    assert_eq!(masked.caret, 5);
+    assert_eq!(number.input_mode(), "decimal");
+
+    let euro = NumberMask::currency(&intl, "EUR");
+    assert_eq!(euro.prefix, "EUR ");
+    assert_eq!(euro.fraction_digits, 2);
+    assert_eq!(Mask::Number(euro.clone()).format("1234.5"), "EUR 1,234.5");
+    assert_eq!(euro.value("1234.5"), Some(1234.5));
+
+    let german = Mask::Number(NumberMask {
+        decimal: ',',
+        group: Some('.'),
+        fraction_digits: 2,
+        negative: true,
+        suffix: " €".into(),
+        ..NumberMask::default()
+    });
+    assert_eq!(german.raw("-1.234,5 €"), "-1234.5");
+    assert_eq!(german.format("-1234.5"), "-1.234,5 €");
+    assert_eq!(german.edit("", "7", 1).caret, 1);
+}
+
+#[test]
+fn it_sends_raw_and_formatted_values() {
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let onchange = changes.clone();
+    let props = MaskedInputProps {
+        id: "phone".into(),
+        mask: Mask::phone(),
+        value: "555".into(),
+        onchange: Callback::from(move |masked: Masked| onchange.borrow_mut().push(masked)),
+        ..MaskedInputProps::default()
+    };
+    let mut input = TestRenderer::<MaskedInput>::new(props.clone());
+    let value = |input: &TestRenderer<MaskedInput>| input.root().find("#phone").unwrap().element().unwrap().value.clone();
+    assert_eq!(value(&input), Some("(555".to_owned()));
+    assert_eq!(input.root().find("#phone").unwrap().attribute("inputmode"), Some("numeric"));
+
+    input.input("#phone", "(5551");
+    input.flush();
+    assert_eq!(changes.borrow()[0].raw, "5551");
+    assert_eq!(changes.borrow()[0].formatted, "(555) 1");
+    assert_eq!(value(&input), Some("(555) 1".to_owned()));
+
+    // The parent keeps the old value
+    input.change(MaskedInputProps { disabled: true, ..props });
+    assert_eq!(value(&input), Some("(555".to_owned()));
+}
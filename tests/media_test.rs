@@ -0,0 +1,103 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use std::time::Duration;
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::services::media::{MediaCommand, MediaError, MediaEvent, MediaHandle, MediaService};
+use yew::services::mock::MockMedia;
+use yew::testing::TestRenderer;
+use yew::virtual_dom::NodeRef;
+
+enum Msg {
+    Toggle,
+    Skip,
+    Media(MediaEvent),
+}
+
+struct Player {
+    video: NodeRef,
+    player: MediaHandle,
+    playing: bool,
+    position: Duration,
+    blocked: bool,
+}
+
+impl Component for Player {
+    type Msg = Msg;
+    type Properties = ();
+
+    fn create(_: (), sender: AppSender<Msg>) -> Self {
+        let video = NodeRef::default();
+        let player = MediaService::new(sender).attach(&video, Msg::Media);
+        Player { video, player, playing: false, position: Duration::from_secs(0), blocked: false }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Toggle => {
+                if self.playing {
+                    self.player.pause();
+                } else {
+                    self.player.play();
+                }
+            }
+            Msg::Skip => {
+                self.player.seek(self.position + Duration::from_secs(10));
+            }
+            Msg::Media(MediaEvent::Playing) => self.playing = true,
+            Msg::Media(MediaEvent::Paused) | Msg::Media(MediaEvent::Ended) => self.playing = false,
+            Msg::Media(MediaEvent::Time(time)) => self.position = time,
+            Msg::Media(MediaEvent::Failed(MediaError::Blocked)) => self.blocked = true,
+            Msg::Media(_) => return false,
+        }
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        html! {
+            <div>
+                <video ref=&self.video, src="/intro.mp4",></video>
+                <button class="toggle", onclick=|_| Msg::Toggle,>{ if self.playing { "Pause" } else { "Play" } }</button>
+                <button class="skip", onclick=|_| Msg::Skip,>{ "+10s" }</button>
+            </div>
+        }
+    }
+}
+
+#[test]
+fn it_controls_playback_with_a_handle() {
+    let media = MockMedia::new();
+    let _installed = media.install();
+    let mut player = TestRenderer::<Player>::new(());
+
+    player.click(".toggle");
+    player.flush();
+    assert!(player.component().playing);
+    media.emit(MediaEvent::Time(Duration::from_secs(5)));
+    player.click(".skip");
+    player.flush();
+    assert_eq!(player.component().position, Duration::from_secs(15));
+    assert_eq!(player.component().player.state().map(|state| state.paused), Some(false));
+
+    media.emit(MediaEvent::Ended);
+    player.flush();
+    assert!(!player.component().playing);
+    assert_eq!(media.commands(), vec![MediaCommand::Play, MediaCommand::Seek(Duration::from_secs(15))]);
+}
+
+#[test]
+fn it_reports_blocked_playback_and_detaches() {
+    let media = MockMedia::new();
+    let _installed = media.install();
+    media.set_blocked(true);
+    let mut player = TestRenderer::<Player>::new(());
+
+    player.click(".toggle");
+    player.flush();
+    assert!(player.component().blocked);
+    assert!(!player.component().playing);
+    drop(player);
+    assert_eq!(media.listeners(), 0);
+}
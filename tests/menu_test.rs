@@ -0,0 +1,184 @@
+#![cfg(feature = "testing")]
+
+extern crate yew;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use yew::callback::Callback;
+use yew::components::context_menu::{self, ContextMenu, ContextMenuProps};
+use yew::components::menu::{self, Menu, MenuItem, MenuProps};
+use yew::components::menu_button::{self, MenuButton, MenuButtonProps};
+use yew::focus::Orientation;
+use yew::position::{Layout, Rect};
+use yew::services::mock::MockLayout;
+use yew::testing::TestRenderer;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Command {
+    New,
+    Open,
+    Autosave,
+    Undo,
+    Redo,
+    Help,
+}
+
+fn items(autosave: bool) -> Vec<MenuItem<Command>> {
+    vec![
+        MenuItem::submenu("File", vec![
+            MenuItem::action("New", Command::New).shortcut("Ctrl+N"),
+            MenuItem::action("Open", Command::Open).disabled(true),
+            MenuItem::separator(),
+            MenuItem::check("Autosave", Command::Autosave, autosave),
+        ]),
+        MenuItem::submenu("Edit", vec![
+            MenuItem::action("Undo", Command::Undo),
+            MenuItem::action("Redo", Command::Redo),
+        ]),
+        MenuItem::action("Help", Command::Help),
+    ]
+}
+
+fn active(menu: &TestRenderer<Menu<Command>>) -> Option<&str> {
+    menu.root().find("#editor").unwrap().attribute("aria-activedescendant")
+}
+
+fn menubar(selected: &Rc<RefCell<Vec<Command>>>, closed: &Rc<RefCell<usize>>) -> TestRenderer<Menu<Command>> {
+    let (selected, closed) = (selected.clone(), closed.clone());
+    TestRenderer::new(MenuProps {
+        id: "editor".into(),
+        items: items(false),
+        label: "Editor".into(),
+        orientation: Orientation::Horizontal,
+        onselect: Callback::from(move |command| selected.borrow_mut().push(command)),
+        onclose: Callback::from(move |_| *closed.borrow_mut() += 1),
+        ..MenuProps::default()
+    })
+}
+
+#[test]
+fn it_moves_through_menubars_with_keys() {
+    let selected = Rc::new(RefCell::new(Vec::new()));
+    let closed = Rc::new(RefCell::new(0));
+    let mut bar = menubar(&selected, &closed);
+    assert_eq!(bar.root().find("#editor").unwrap().attribute("role"), Some("menubar"));
+    assert_eq!(active(&bar), None);
+
+    bar.key_down("#editor", "ArrowRight");
+    assert_eq!(active(&bar), Some("editor-item-0"));
+    assert_eq!(bar.root().find("#editor-item-0").unwrap().attribute("aria-expanded"), Some("false"));
+    bar.key_down("#editor", "ArrowDown");
+    assert_eq!(active(&bar), Some("editor-item-0-0"));
+    assert_eq!(bar.root().find("#editor-item-0").unwrap().attribute("aria-expanded"), Some("true"));
+    assert_ne!(bar.root().find("[aria-labelledby=editor-item-0]").unwrap().attribute("hidden"), Some("true"));
+    assert_eq!(bar.root().find("#editor-item-0-0").unwrap().attribute("aria-keyshortcuts"), Some("Ctrl+N"));
+
+    // Disabled items take focus and separators are skipped
+    bar.key_down("#editor", "ArrowDown");
+    assert_eq!(active(&bar), Some("editor-item-0-1"));
+    bar.key_down("#editor", "ArrowDown");
+    assert_eq!(active(&bar), Some("editor-item-0-3"));
+    bar.key_down("#editor", "ArrowDown");
+    assert_eq!(active(&bar), Some("editor-item-0-0"));
+
+    // The next menu of the menubar is opened from an item without a submenu
+    bar.key_down("#editor", "ArrowRight");
+    assert_eq!(active(&bar), Some("editor-item-1-0"));
+    bar.key_down("#editor", "ArrowLeft");
+    assert_eq!(active(&bar), Some("editor-item-0-0"));
+    bar.key_down("#editor", "Escape");
+    assert_eq!(active(&bar), Some("editor-item-0"));
+    assert_eq!(bar.root().find("[aria-labelledby=editor-item-0]").unwrap().attribute("hidden"), Some("true"));
+    bar.key_down("#editor", "ArrowLeft");
+    assert_eq!(active(&bar), Some("editor-item-2"));
+    bar.key_down("#editor", "Escape");
+    assert_eq!(active(&bar), None);
+    assert_eq!(*closed.borrow(), 1);
+    assert!(selected.borrow().is_empty());
+}
+
+#[test]
+fn it_selects_items_of_menus() {
+    let selected = Rc::new(RefCell::new(Vec::new()));
+    let closed = Rc::new(RefCell::new(0));
+    let mut bar = menubar(&selected, &closed);
+    let autosave = bar.root().find("#editor-item-0-3").unwrap();
+    assert_eq!(autosave.attribute("role"), Some("menuitemcheckbox"));
+    assert_eq!(autosave.attribute("aria-checked"), Some("false"));
+
+    // Hovering an item opens its submenu
+    bar.send(menu::Msg::Hover(vec![0]));
+    assert_eq!(bar.root().find("#editor-item-0").unwrap().attribute("aria-expanded"), Some("true"));
+    bar.send(menu::Msg::Activate(vec![0, 1]));
+    assert!(selected.borrow().is_empty());
+    bar.send(menu::Msg::Activate(vec![0, 3]));
+    assert_eq!(*selected.borrow(), vec![Command::Autosave]);
+    assert_eq!(*closed.borrow(), 1);
+    assert_eq!(active(&bar), None);
+
+    // A letter finds the next item with the label
+    bar.key_down("#editor", "ArrowRight");
+    bar.key_down("#editor", "h");
+    assert_eq!(active(&bar), Some("editor-item-2"));
+    bar.key_down("#editor", "e");
+    assert_eq!(active(&bar), Some("editor-item-1"));
+    bar.key_down("#editor", "Enter");
+    assert_eq!(active(&bar), Some("editor-item-1-0"));
+    bar.key_down("#editor", "End");
+    bar.key_down("#editor", "Enter");
+    assert_eq!(*selected.borrow(), vec![Command::Autosave, Command::Redo]);
+
+    // Changed items keep the active item
+    bar.key_down("#editor", "ArrowDown");
+    bar.key_down("#editor", "ArrowDown");
+    bar.change(MenuProps {
+        id: "editor".into(),
+        items: items(true),
+        orientation: Orientation::Horizontal,
+        ..MenuProps::default()
+    });
+    assert_eq!(active(&bar), Some("editor-item-0-0"));
+    assert_eq!(bar.root().find("#editor-item-0-3").unwrap().attribute("aria-checked"), Some("true"));
+}
+
+#[test]
+fn it_opens_menus_from_buttons_and_right_clicks() {
+    let layout = MockLayout::new();
+    let _layout = layout.install();
+    let mut button = TestRenderer::<MenuButton<Command>>::new(MenuButtonProps {
+        id: "actions".into(),
+        items: items(false),
+        ..MenuButtonProps::default()
+    });
+    assert!(button.root().find("#actions-menu").is_none());
+    button.send(menu_button::Msg::Key("ArrowUp".into()));
+    assert_eq!(layout.anchor(0), Some("actions-button".to_string()));
+    let opened = button.root().find("#actions-menu").unwrap();
+    assert_eq!(opened.attribute("role"), Some("menu"));
+    assert_eq!(opened.attribute("aria-activedescendant"), Some("actions-menu-item-2"));
+    assert!(layout.press_outside(0));
+    button.flush();
+    assert!(button.root().find("#actions-menu").is_none());
+    assert_eq!(button.root().find(".menu-popup").unwrap().attribute("hidden"), Some("true"));
+
+    let mut context = TestRenderer::<ContextMenu<Command>>::new(ContextMenuProps {
+        id: "file".into(),
+        items: items(false),
+        ..ContextMenuProps::default()
+    });
+    context.send(context_menu::Msg::Open(390.0, 290.0));
+    assert_eq!(layout.anchor(1), Some("file-target".to_string()));
+    layout.measure(1, Layout {
+        anchor: Rect::new(0.0, 0.0, 400.0, 300.0),
+        floating: Rect::new(0.0, 0.0, 80.0, 30.0),
+        boundary: Rect::new(0.0, 0.0, 400.0, 300.0),
+    });
+    context.flush();
+    // The menu flips above the pointer and shifts away from the edge
+    let popup = context.root().find(".context-menu").unwrap();
+    assert_eq!(popup.attribute("style"), Some("position: fixed; left: 312px; top: 260px; --arrow-offset: 78px;"));
+    context.send(context_menu::Msg::Open(10.0, 10.0));
+    let moved = context.root().find(".context-menu").unwrap();
+    assert_eq!(moved.attribute("style"), Some("position: fixed; left: 10px; top: 10px; --arrow-offset: 0px;"));
+    assert_eq!(layout.tracked(), 2);
+}
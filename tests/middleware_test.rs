@@ -0,0 +1,99 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::middleware::Middleware;
+use yew::testing::TestRenderer;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Msg {
+    Add(i32),
+    Spam,
+}
+
+struct Counter {
+    value: i32,
+    log: Vec<Msg>,
+}
+
+impl Component for Counter {
+    type Msg = Msg;
+    type Properties = ();
+
+    fn create(_: (), _: AppSender<Msg>) -> Self {
+        Counter { value: 0, log: Vec::new() }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        if let Msg::Add(amount) = msg {
+            self.value += amount;
+        }
+        self.log.push(msg);
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        html! { <p>{ self.value }</p> }
+    }
+}
+
+struct Trace {
+    name: &'static str,
+    calls: Rc<RefCell<Vec<String>>>,
+}
+
+impl Middleware<Counter, Msg> for Trace {
+    fn update(&mut self, model: &mut Counter, msg: Msg, next: &mut FnMut(&mut Counter, Msg)) {
+        self.calls.borrow_mut().push(format!("{} before {}", self.name, model.value));
+        next(model, msg);
+        self.calls.borrow_mut().push(format!("{} after {}", self.name, model.value));
+    }
+}
+
+/// Drops spam and doubles everything else.
+struct Filter;
+
+impl Middleware<Counter, Msg> for Filter {
+    fn update(&mut self, model: &mut Counter, msg: Msg, next: &mut FnMut(&mut Counter, Msg)) {
+        match msg {
+            Msg::Spam => {}
+            Msg::Add(amount) => next(model, Msg::Add(amount * 2)),
+        }
+    }
+}
+
+#[test]
+fn it_calls_middlewares_in_order() {
+    let calls = Rc::new(RefCell::new(Vec::new()));
+    let mut counter = TestRenderer::<Counter>::new(());
+    counter.middleware(Trace { name: "outer", calls: calls.clone() });
+    counter.middleware(Trace { name: "inner", calls: calls.clone() });
+    counter.send(Msg::Add(1));
+
+    assert_eq!(*calls.borrow(), vec![
+        "outer before 0",
+        "inner before 0",
+        "inner after 1",
+        "outer after 1",
+    ]);
+    assert_eq!(counter.root().text(), "1");
+}
+
+#[test]
+fn it_drops_and_rewrites_messages() {
+    let calls = Rc::new(RefCell::new(Vec::new()));
+    let mut counter = TestRenderer::<Counter>::new(());
+    counter.middleware(Filter);
+    counter.middleware(Trace { name: "inner", calls: calls.clone() });
+    counter.send(Msg::Spam);
+    counter.send(Msg::Add(2));
+
+    // The dropped message doesn't reach later middlewares
+    assert_eq!(*calls.borrow(), vec!["inner before 0", "inner after 4"]);
+    assert_eq!(counter.component().log, vec![Msg::Add(4)]);
+    assert_eq!(counter.root().text(), "4");
+}
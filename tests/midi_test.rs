@@ -0,0 +1,30 @@
+extern crate yew;
+
+use yew::services::midi::MidiMessage;
+
+#[test]
+fn it_parses_channel_messages() {
+    assert_eq!(MidiMessage::from_bytes(&[0x93, 60, 100]), MidiMessage::NoteOn { channel: 3, note: 60, velocity: 100 });
+    assert_eq!(MidiMessage::from_bytes(&[0x90, 60, 0]), MidiMessage::NoteOff { channel: 0, note: 60, velocity: 0 });
+    assert_eq!(MidiMessage::from_bytes(&[0xB0, 7, 90]), MidiMessage::ControlChange { channel: 0, controller: 7, value: 90 });
+    assert_eq!(MidiMessage::from_bytes(&[0xCF, 12]), MidiMessage::ProgramChange { channel: 15, program: 12 });
+    assert_eq!(MidiMessage::from_bytes(&[0xE0, 0, 0x40]), MidiMessage::PitchBend { channel: 0, value: 0 });
+    assert_eq!(MidiMessage::from_bytes(&[0xE1, 0, 0]), MidiMessage::PitchBend { channel: 1, value: -8192 });
+    assert_eq!(MidiMessage::from_bytes(&[0xF8]), MidiMessage::Other(vec![0xF8]));
+    assert_eq!(MidiMessage::from_bytes(&[0x90, 60]), MidiMessage::Other(vec![0x90, 60]));
+}
+
+#[test]
+fn it_encodes_messages_back() {
+    let messages = vec![
+        MidiMessage::NoteOff { channel: 2, note: 64, velocity: 30 },
+        MidiMessage::PolyAftertouch { channel: 0, note: 64, pressure: 10 },
+        MidiMessage::ChannelAftertouch { channel: 9, pressure: 127 },
+        MidiMessage::PitchBend { channel: 4, value: 8191 },
+        MidiMessage::Other(vec![0xF0, 0x7E, 0x7F, 0xF7]),
+    ];
+    for message in messages {
+        assert_eq!(MidiMessage::from_bytes(&message.to_bytes()), message);
+    }
+    assert_eq!(MidiMessage::NoteOn { channel: 17, note: 200, velocity: 1 }.to_bytes(), vec![0x91, 72, 1]);
+}
@@ -0,0 +1,554 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use std::time::{Duration, UNIX_EPOCH};
+use yew::format::Nothing;
+use yew::html::{AppSender, Component, Html, InputData, ShouldRender};
+use std::cell::RefCell;
+use std::rc::Rc;
+use yew::callback::Callback;
+use yew::services::Task;
+use yew::services::fetch::{FetchHandle, FetchService, Request, Response};
+use yew::services::interval::IntervalService;
+use yew::services::intl::{IntlService, NumberOptions, RelativeUnit};
+use yew::services::announcer::{AnnouncerService, Politeness};
+use yew::services::rate_limit::{self, RateLimitService};
+use yew::services::messaging::{Channel, MessagingHandle, MessagingService, Target};
+use yew::services::mock::{MockAnnouncer, MockClock, MockIntl, MockMessaging, MockNetwork, MockStorage, MockVisibility};
+use yew::services::poll::{PollHandle, PollOptions, PollService};
+use yew::services::storage::{Scope, StorageService};
+use yew::services::timeout::{TimeoutHandle, TimeoutService};
+use yew::services::websocket::{WebSocketHandle, WebSocketService, WebSocketStatus};
+use yew::testing::TestRenderer;
+
+struct Search {
+    query: String,
+    results: String,
+    failures: u32,
+    timeout: TimeoutService<SearchMsg>,
+    fetch: FetchService<SearchMsg>,
+    debounce: Option<TimeoutHandle>,
+    request: Option<FetchHandle>,
+}
+
+enum SearchMsg {
+    Input(String),
+    Search,
+    Found(String),
+    Failed,
+}
+
+impl Component for Search {
+    type Msg = SearchMsg;
+    type Properties = ();
+
+    fn create(_: (), sender: AppSender<SearchMsg>) -> Self {
+        Search {
+            query: String::new(),
+            results: String::new(),
+            failures: 0,
+            timeout: TimeoutService::new(sender.clone()),
+            fetch: FetchService::new(sender),
+            debounce: None,
+            request: None,
+        }
+    }
+
+    fn update(&mut self, msg: SearchMsg) -> ShouldRender {
+        match msg {
+            SearchMsg::Input(query) => {
+                self.query = query;
+                let handle = self.timeout.spawn(Duration::from_millis(300), || SearchMsg::Search);
+                self.debounce = Some(handle);
+                false
+            }
+            SearchMsg::Search => {
+                let request = Request::get(format!("/search?q={}", self.query))
+                    .body(Nothing)
+                    .unwrap();
+                let handle = self.fetch.fetch(request, |response: Response<Result<String, String>>| {
+                    let (meta, body) = response.into_parts();
+                    match body {
+                        Ok(body) if meta.status.is_success() => SearchMsg::Found(body),
+                        _ => SearchMsg::Failed,
+                    }
+                });
+                self.request = Some(handle);
+                false
+            }
+            SearchMsg::Found(results) => {
+                self.results = results;
+                true
+            }
+            SearchMsg::Failed => {
+                // Retries the same query once
+                self.failures += 1;
+                self.failures < 2 && self.update(SearchMsg::Search)
+            }
+        }
+    }
+
+    fn view(&self) -> Html<SearchMsg> {
+        html! {
+            <div>
+                <input oninput=|e: InputData| SearchMsg::Input(e.value), />
+                <p>{ &self.results }</p>
+            </div>
+        }
+    }
+}
+
+#[test]
+fn it_debounces_with_a_mock_clock() {
+    let clock = MockClock::new();
+    let network = MockNetwork::new();
+    let _clock = clock.install();
+    let _network = network.install();
+
+    let mut search = TestRenderer::<Search>::new(());
+    search.type_text("input", "yew");
+    assert_eq!(clock.pending(), 1);
+    clock.advance(Duration::from_millis(299));
+    search.flush();
+    assert!(network.requests().is_empty());
+
+    clock.advance(Duration::from_millis(1));
+    search.flush();
+    assert_eq!(clock.now(), Duration::from_millis(300));
+    let requests = network.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "GET");
+    assert_eq!(requests[0].uri, "/search?q=yew");
+    assert!(!network.respond("/search?q=other", 200, ""));
+    assert!(network.respond("/search?q=yew", 200, "found"));
+    search.flush();
+    assert_eq!(search.root().find("p").unwrap().text(), "found");
+}
+
+#[test]
+fn it_retries_failed_requests() {
+    let _clock = MockClock::new().install();
+    let network = MockNetwork::new();
+    let _network = network.install();
+    let mut search = TestRenderer::<Search>::new(());
+    search.send(SearchMsg::Input("a".into()));
+    search.send(SearchMsg::Search);
+    assert!(network.respond("/search?q=a", 500, ""));
+    search.flush();
+    assert_eq!(network.requests().len(), 1);
+    assert!(network.respond("/search?q=a", 503, ""));
+    search.flush();
+    assert!(network.requests().is_empty());
+    assert_eq!(search.component().failures, 2);
+}
+
+#[test]
+fn it_cancels_dropped_tasks() {
+    let clock = MockClock::new();
+    let network = MockNetwork::new();
+    let _clock = clock.install();
+    let _network = network.install();
+    let mut search = TestRenderer::<Search>::new(());
+    search.send(SearchMsg::Input("a".into()));
+    search.send(SearchMsg::Input("ab".into()));
+    assert_eq!(clock.pending(), 1);
+    search.send(SearchMsg::Search);
+    search.send(SearchMsg::Search);
+    assert_eq!(network.pending(), 1);
+    drop(search);
+    assert_eq!(clock.pending(), 0);
+    assert_eq!(network.pending(), 0);
+}
+
+struct Ticker {
+    events: Vec<&'static str>,
+    tasks: Vec<Box<Task>>,
+}
+
+impl Component for Ticker {
+    type Msg = &'static str;
+    type Properties = ();
+
+    fn create(_: (), sender: AppSender<&'static str>) -> Self {
+        let mut interval = IntervalService::new(sender.clone());
+        let mut timeout = TimeoutService::new(sender);
+        let tasks: Vec<Box<Task>> = vec![
+            Box::new(interval.spawn(Duration::from_millis(100), || "tick")),
+            Box::new(timeout.spawn(Duration::from_millis(250), || "timeout")),
+        ];
+        Ticker { events: Vec::new(), tasks }
+    }
+
+    fn update(&mut self, event: &'static str) -> ShouldRender {
+        self.events.push(event);
+        if self.events.len() == 4 {
+            for task in &mut self.tasks {
+                if task.is_active() {
+                    task.cancel();
+                }
+            }
+        }
+        false
+    }
+
+    fn view(&self) -> Html<&'static str> {
+        html! { <div></div> }
+    }
+}
+
+#[test]
+fn it_calls_timers_in_order() {
+    let clock = MockClock::new();
+    let _clock = clock.install();
+    let mut ticker = TestRenderer::<Ticker>::new(());
+    assert_eq!(clock.pending(), 2);
+    clock.advance(Duration::from_millis(350));
+    ticker.flush();
+    assert_eq!(ticker.component().events, vec!["tick", "tick", "timeout", "tick"]);
+    assert_eq!(clock.pending(), 0);
+    clock.advance(Duration::from_secs(1));
+    ticker.flush();
+    assert_eq!(ticker.component().events.len(), 4);
+}
+
+enum LimitMsg {
+    Type(&'static str),
+    Search(&'static str),
+    Scroll(u32),
+    Scrolled(u32),
+}
+
+struct Limits {
+    search: Callback<&'static str>,
+    scroll: Callback<u32>,
+    events: Vec<String>,
+}
+
+impl Component for Limits {
+    type Msg = LimitMsg;
+    type Properties = ();
+
+    fn create(_: (), sender: AppSender<LimitMsg>) -> Self {
+        let mut limits = RateLimitService::new(sender);
+        Limits {
+            search: limits.debounce(Duration::from_millis(300), LimitMsg::Search),
+            scroll: limits.throttle(Duration::from_millis(100), LimitMsg::Scrolled),
+            events: Vec::new(),
+        }
+    }
+
+    fn update(&mut self, msg: LimitMsg) -> ShouldRender {
+        match msg {
+            LimitMsg::Type(query) => self.search.emit(query),
+            LimitMsg::Scroll(top) => self.scroll.emit(top),
+            LimitMsg::Search(query) => self.events.push(format!("search {}", query)),
+            LimitMsg::Scrolled(top) => self.events.push(format!("scroll {}", top)),
+        }
+        false
+    }
+
+    fn view(&self) -> Html<LimitMsg> {
+        html! { <div></div> }
+    }
+}
+
+#[test]
+fn it_debounces_and_throttles_messages() {
+    let clock = MockClock::new();
+    let _clock = clock.install();
+    let mut limits = TestRenderer::<Limits>::new(());
+    limits.send(LimitMsg::Type("y"));
+    clock.advance(Duration::from_millis(200));
+    limits.send(LimitMsg::Type("ye"));
+    limits.send(LimitMsg::Type("yew"));
+    clock.advance(Duration::from_millis(299));
+    limits.flush();
+    assert!(limits.component().events.is_empty());
+    clock.advance(Duration::from_millis(1));
+    limits.flush();
+    assert_eq!(limits.component().events, vec!["search yew"]);
+
+    for top in 1..6 {
+        limits.send(LimitMsg::Scroll(top));
+        clock.advance(Duration::from_millis(30));
+    }
+    clock.advance(Duration::from_millis(200));
+    limits.flush();
+    assert_eq!(limits.component().events, vec!["search yew", "scroll 1", "scroll 4", "scroll 5"]);
+    assert_eq!(clock.pending(), 0);
+}
+
+#[test]
+fn it_cancels_timers_of_dropped_callbacks() {
+    let clock = MockClock::new();
+    let _clock = clock.install();
+    let calls = Rc::new(RefCell::new(Vec::new()));
+    let log = calls.clone();
+    let search = rate_limit::debounce(Duration::from_millis(100), move |query: &str| {
+        log.borrow_mut().push(query.to_owned());
+    });
+    search.emit("a");
+    assert_eq!(clock.pending(), 1);
+    drop(search);
+    assert_eq!(clock.pending(), 0);
+    clock.advance(Duration::from_secs(1));
+    assert!(calls.borrow().is_empty());
+}
+
+struct Inbox {
+    unread: Vec<String>,
+    poll: Option<PollHandle>,
+}
+
+impl Component for Inbox {
+    type Msg = Option<String>;
+    type Properties = ();
+
+    fn create(_: (), sender: AppSender<Option<String>>) -> Self {
+        let mut poller = PollService::new(sender);
+        let options = PollOptions::every(Duration::from_secs(10));
+        let poll = poller.fetch(options, || Request::get("/unread").body(Nothing).unwrap(),
+            |response: Response<Result<String, String>>| response.into_body().ok());
+        Inbox { unread: Vec::new(), poll: Some(poll) }
+    }
+
+    fn update(&mut self, msg: Option<String>) -> ShouldRender {
+        match msg {
+            Some(unread) => self.unread.push(unread),
+            None => self.poll = None,
+        }
+        true
+    }
+
+    fn view(&self) -> Html<Option<String>> {
+        html! { <div></div> }
+    }
+}
+
+#[test]
+fn it_polls_while_the_page_is_visible() {
+    let clock = MockClock::new();
+    let network = MockNetwork::new();
+    let page = MockVisibility::new();
+    let _installed = (clock.install(), network.install(), page.install());
+    let mut inbox = TestRenderer::<Inbox>::new(());
+    assert_eq!(network.pending(), 1);
+
+    // A poll isn't sent while the previous one is running
+    clock.advance(Duration::from_secs(10));
+    assert_eq!(network.pending(), 1);
+    assert!(network.respond("/unread", 200, "1"));
+    clock.advance(Duration::from_secs(10));
+    assert!(network.respond("/unread", 200, "2"));
+
+    page.set_hidden(true);
+    clock.advance(Duration::from_secs(60));
+    assert_eq!(network.pending(), 0);
+    assert_eq!(clock.pending(), 0);
+    page.set_hidden(false);
+    assert!(network.respond("/unread", 200, "3"));
+    inbox.flush();
+    assert_eq!(inbox.component().unread, vec!["1", "2", "3"]);
+
+    inbox.send(None);
+    assert_eq!(clock.pending(), 0);
+    clock.advance(Duration::from_secs(60));
+    page.set_hidden(true);
+    page.set_hidden(false);
+    assert_eq!(network.pending(), 0);
+}
+
+#[test]
+fn it_keeps_data_in_a_mock_storage() {
+    let storage = MockStorage::new();
+    let _storage = storage.install();
+    let mut local = StorageService::new(Scope::Local);
+    let mut session = StorageService::new(Scope::Session);
+    local.store("key", Some("value".to_string()));
+    let restored: Result<String, String> = local.restore("key");
+    assert_eq!(restored, Ok("value".into()));
+    let missing: Result<String, String> = session.restore("key");
+    assert!(missing.is_err());
+    local.remove("key");
+    let removed: Result<String, String> = local.restore("key");
+    assert!(removed.is_err());
+}
+
+struct Chat {
+    messages: Vec<String>,
+    opened: u32,
+    service: WebSocketService<ChatMsg>,
+    socket: Option<WebSocketHandle>,
+}
+
+enum ChatMsg {
+    Connect,
+    Received(Result<String, String>),
+    Status(WebSocketStatus),
+    Send(String),
+}
+
+impl Component for Chat {
+    type Msg = ChatMsg;
+    type Properties = ();
+
+    fn create(_: (), mut sender: AppSender<ChatMsg>) -> Self {
+        sender.send(ChatMsg::Connect);
+        Chat { messages: Vec::new(), opened: 0, service: WebSocketService::new(sender), socket: None }
+    }
+
+    fn update(&mut self, msg: ChatMsg) -> ShouldRender {
+        match msg {
+            ChatMsg::Connect => {
+                let handle = self.service.connect("ws://chat", ChatMsg::Received, ChatMsg::Status);
+                self.socket = Some(handle);
+            }
+            ChatMsg::Received(data) => self.messages.push(data.unwrap()),
+            ChatMsg::Status(WebSocketStatus::Opened) => self.opened += 1,
+            ChatMsg::Status(WebSocketStatus::Closed) => {
+                // Reconnects at once when a server closes the connection
+                self.socket = None;
+                return self.update(ChatMsg::Connect);
+            }
+            ChatMsg::Send(text) => {
+                if let Some(ref mut socket) = self.socket {
+                    socket.send(Some(text));
+                }
+            }
+        }
+        true
+    }
+
+    fn view(&self) -> Html<ChatMsg> {
+        html! {
+            <ul>{ for self.messages.iter().map(|message| html! { <li>{ message }</li> }) }</ul>
+        }
+    }
+}
+
+#[test]
+fn it_reconnects_websockets() {
+    let network = MockNetwork::new();
+    let _network = network.install();
+    let mut chat = TestRenderer::<Chat>::new(());
+    let socket = network.socket("ws://chat").unwrap();
+    socket.open();
+    socket.receive("hello");
+    chat.send(ChatMsg::Send("hi".into()));
+    assert_eq!(socket.sent(), vec!["hi".to_string()]);
+    assert_eq!(chat.root().find("li").unwrap().text(), "hello");
+
+    socket.close();
+    chat.flush();
+    assert_eq!(network.connections("ws://chat"), 2);
+    let socket = network.socket("ws://chat").unwrap();
+    socket.open();
+    chat.flush();
+    assert_eq!(chat.component().opened, 2);
+}
+
+#[test]
+fn it_records_announcements() {
+    let announcer = MockAnnouncer::new();
+    let _announcer = announcer.install();
+    let mut service = AnnouncerService::new();
+    service.announce("3 results loaded");
+    service.alert("Saving failed");
+    service.announce("3 results loaded");
+    assert_eq!(announcer.announcements(), vec![
+        (Politeness::Polite, "3 results loaded".to_string()),
+        (Politeness::Assertive, "Saving failed".to_string()),
+        (Politeness::Polite, "3 results loaded".to_string()),
+    ]);
+    assert_eq!(announcer.last(), Some("3 results loaded".into()));
+}
+
+#[test]
+fn it_formats_with_a_mock_intl() {
+    let intl = MockIntl::new();
+    let _intl = intl.install();
+    let service = IntlService::with_locale("de-DE");
+    assert_eq!(service.number(1234567.891), "1,234,567.891");
+    assert_eq!(service.number(-0.5), "-0.5");
+    assert_eq!(service.currency(1234.5, "EUR"), "EUR 1,234.50");
+    assert_eq!(service.percent(0.256), "26%");
+    let options = NumberOptions { minimum_fraction_digits: Some(1), grouping: false, ..NumberOptions::default() };
+    assert_eq!(service.number_with(1000.0, &options), "1000.0");
+    let posted = UNIX_EPOCH + Duration::from_secs(1_704_153_600 + 13 * 3600 + 45 * 60);
+    assert_eq!(service.date(posted), "2024-01-02");
+    assert_eq!(service.date_time(posted), "2024-01-02 13:45");
+    intl.set_now(posted + Duration::from_secs(180));
+    assert_eq!(service.relative(posted), "3 minutes ago");
+    assert_eq!(service.relative(posted + Duration::from_secs(2 * 86400 + 300)), "in 2 days");
+    assert_eq!(service.relative_with(1.0, RelativeUnit::Hour), "in 1 hour");
+}
+
+struct Widget {
+    configs: Vec<String>,
+    messaging: MessagingService<WidgetMsg>,
+    host: Option<MessagingHandle>,
+}
+
+enum WidgetMsg {
+    Configure(Result<String, String>),
+    Detach,
+}
+
+fn host() -> Channel {
+    Channel::new(Target::Parent, "https://shop.example", "cart")
+}
+
+impl Component for Widget {
+    type Msg = WidgetMsg;
+    type Properties = ();
+
+    fn create(_: (), sender: AppSender<WidgetMsg>) -> Self {
+        let mut messaging = MessagingService::new(sender);
+        let handle = messaging.listen(&host(), WidgetMsg::Configure);
+        messaging.post(&host(), Some("ready".to_owned()));
+        Widget { configs: Vec::new(), messaging, host: Some(handle) }
+    }
+
+    fn update(&mut self, msg: WidgetMsg) -> ShouldRender {
+        match msg {
+            WidgetMsg::Configure(data) => {
+                self.configs.push(data.unwrap());
+                self.messaging.post(&host(), Some("configured".to_owned()));
+            }
+            WidgetMsg::Detach => self.host = None,
+        }
+        true
+    }
+
+    fn view(&self) -> Html<WidgetMsg> {
+        html! { <span>{ self.configs.join(",") }</span> }
+    }
+}
+
+#[test]
+fn it_exchanges_messages_with_windows() {
+    let messaging = MockMessaging::new();
+    let _messaging = messaging.install();
+    let mut widget = TestRenderer::<Widget>::new(());
+    assert_eq!(messaging.posted(), vec![(host(), "ready".to_string())]);
+
+    assert!(messaging.receive(&host(), "blue"));
+    let stranger = Channel::new(Target::Parent, "https://evil.example", "cart");
+    assert!(!messaging.receive(&stranger, "red"));
+    let other = Channel::new(Target::Parent, "https://shop.example", "checkout");
+    assert!(!messaging.receive(&other, "green"));
+    widget.flush();
+    assert_eq!(widget.root().find("span").unwrap().text(), "blue");
+    assert_eq!(messaging.posted().len(), 2);
+
+    widget.send(WidgetMsg::Detach);
+    assert!(!messaging.receive(&host(), "blue"));
+}
+
+#[test]
+#[should_panic(expected = "exact origin")]
+fn it_rejects_channels_to_any_origin() {
+    Channel::new(Target::Parent, "*", "cart");
+}
@@ -0,0 +1,118 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use yew::callback::Callback;
+use yew::components::ModalHost;
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::modal::{self, Modal, ModalOptions};
+use yew::testing::TestRenderer;
+
+thread_local! {
+    static CLOSE: RefCell<Option<Callback<bool>>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone, Default)]
+struct ConfirmProps {
+    question: String,
+    onclose: Callback<bool>,
+}
+
+struct Confirm {
+    props: ConfirmProps,
+}
+
+impl Component for Confirm {
+    type Msg = ();
+    type Properties = ConfirmProps;
+
+    fn create(props: Self::Properties, _: AppSender<()>) -> Self {
+        Confirm { props }
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html<()> {
+        html! { <p class="question",>{ &self.props.question }</p> }
+    }
+}
+
+impl Modal for Confirm {
+    type Output = bool;
+
+    fn set_close(props: &mut ConfirmProps, close: Callback<bool>) {
+        // Buttons of nested components can't be clicked in tests
+        CLOSE.with(|last| *last.borrow_mut() = Some(close.clone()));
+        props.onclose = close;
+    }
+}
+
+fn confirm(question: &str) -> ConfirmProps {
+    ConfirmProps {
+        question: question.to_owned(),
+        ..ConfirmProps::default()
+    }
+}
+
+fn questions(host: &TestRenderer<ModalHost>) -> Vec<String> {
+    host.root().find_all(".modal-dialog .question").iter().map(|node| node.text()).collect()
+}
+
+#[test]
+fn it_stacks_modals_and_passes_results() {
+    let results = Rc::new(RefCell::new(Vec::new()));
+    let mut host = TestRenderer::<ModalHost>::new(());
+    let log = results.clone();
+    modal::open::<Confirm>(confirm("Save?"), Callback::from(move |answer| log.borrow_mut().push(answer)));
+    let close_first = CLOSE.with(|last| last.borrow_mut().take()).unwrap();
+    let log = results.clone();
+    modal::open::<Confirm>(confirm("Really?"), Callback::from(move |answer| log.borrow_mut().push(answer)));
+    let close_second = CLOSE.with(|last| last.borrow_mut().take()).unwrap();
+    host.flush();
+    assert_eq!(questions(&host), vec!["Save?", "Really?"]);
+    assert!(host.root().find(".modal-scroll-lock").is_some());
+
+    close_second.emit(true);
+    host.flush();
+    close_second.emit(false);
+    assert_eq!(*results.borrow(), vec![Some(true)]);
+    assert_eq!(questions(&host), vec!["Save?"]);
+
+    close_first.emit(false);
+    host.flush();
+    assert_eq!(*results.borrow(), vec![Some(true), Some(false)]);
+    assert_eq!(modal::count(), 0);
+    assert!(host.root().find(".modal-scroll-lock").is_none());
+}
+
+#[test]
+fn it_dismisses_the_top_modal() {
+    let results = Rc::new(RefCell::new(Vec::new()));
+    let mut host = TestRenderer::<ModalHost>::new(());
+    let log = results.clone();
+    let first = modal::open::<Confirm>(confirm("Save?"), Callback::from(move |answer| log.borrow_mut().push(answer)));
+    let options = ModalOptions {
+        dismissible: false,
+        class: "required".into(),
+    };
+    let log = results.clone();
+    let second = modal::open_with::<Confirm>(options, confirm("Name?"), Callback::from(move |answer| log.borrow_mut().push(answer)));
+    host.flush();
+
+    // Neither the covered modal nor the required one is dismissed by a click
+    host.click(".modal-backdrop");
+    host.click(".required .modal-backdrop");
+    assert_eq!(questions(&host), vec!["Save?", "Name?"]);
+
+    assert!(modal::dismiss(second));
+    host.flush();
+    host.click(".modal-backdrop");
+    assert_eq!(*results.borrow(), vec![None, None]);
+    assert!(!modal::dismiss(first));
+    assert!(questions(&host).is_empty());
+}
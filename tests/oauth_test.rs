@@ -0,0 +1,131 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::oauth::{self, OAuthConfig, OAuthError, OAuthHandle, OAuthService, Tokens};
+use yew::services::mock::{MockIntl, MockNetwork, MockRandom, MockStorage};
+use yew::testing::TestRenderer;
+
+const STATE: &str = "ICEiIyQlJicoKSorLC0uLw";
+const NONCE: &str = "MDEyMzQ1Njc4OTo7PD0-Pw";
+
+type Msg = Result<Tokens, OAuthError>;
+
+struct Login {
+    results: Vec<Msg>,
+}
+
+impl Component for Login {
+    type Msg = Msg;
+    type Properties = ();
+
+    fn create(_: (), _: AppSender<Msg>) -> Self {
+        Login { results: Vec::new() }
+    }
+
+    fn update(&mut self, result: Msg) -> ShouldRender {
+        self.results.push(result);
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        html! { <p>{ self.results.len() }</p> }
+    }
+}
+
+fn config() -> OAuthConfig {
+    OAuthConfig::new("https://id.example/authorize", "https://id.example/token", "app", "https://app.example/callback")
+        .scope("openid")
+        .scope("email")
+        .param("prompt", "login")
+}
+
+fn callback(oauth: &mut OAuthService<Msg>, location: &str) -> OAuthHandle {
+    oauth.callback(&config(), location, |result| result)
+}
+
+#[test]
+fn it_computes_code_challenges() {
+    // The example of RFC 7636
+    assert_eq!(oauth::code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+               "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM");
+    assert!(oauth::is_callback(&config(), "/callback?code=abc&state=xyz"));
+    assert!(!oauth::is_callback(&config(), "/callback/more"));
+}
+
+#[test]
+fn it_exchanges_codes_for_tokens() {
+    let (network, storage, random, intl) = (MockNetwork::new(), MockStorage::new(), MockRandom::new(), MockIntl::new());
+    let _installed = (network.install(), storage.install(), random.install(), intl.install());
+    let mut login = TestRenderer::<Login>::new(());
+    let mut oauth = OAuthService::new(login.sender());
+    let url = oauth.authorize_url(&config());
+    assert_eq!(url, format!("https://id.example/authorize?response_type=code&client_id=app\
+        &redirect_uri=https%3A%2F%2Fapp.example%2Fcallback&scope=openid%20email&state={}\
+        &code_challenge=6oZqdX5MOLq_qBJ8vppAnT4fk6AP8UiP9zX8-Rev_9A&code_challenge_method=S256\
+        &nonce={}&prompt=login", STATE, NONCE));
+
+    let _exchange = callback(&mut oauth, &format!("/callback?code=c0de&state={}", STATE));
+    let request = network.requests().pop().unwrap();
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.body, Some("grant_type=authorization_code&code=c0de\
+        &redirect_uri=https%3A%2F%2Fapp.example%2Fcallback&client_id=app\
+        &code_verifier=AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8".into()));
+    // {"nonce": NONCE}
+    let id_token = "e30.eyJub25jZSI6ICJNREV5TXpRMU5qYzRPVG83UEQwLVB3In0.sig";
+    assert!(network.respond("https://id.example/token", 200, &format!(
+        r#"{{"access_token":"abc","token_type":"Bearer","expires_in":60,"refresh_token":"r1","id_token":"{}"}}"#,
+        id_token)));
+    login.flush();
+    let tokens = login.component().results[0].clone().unwrap();
+    assert_eq!(tokens.access_token, "abc");
+    assert_eq!(tokens.id_token, Some(id_token.into()));
+    let session = tokens.into_session();
+    assert_eq!(session.refresh_token, Some("r1".into()));
+    assert!(session.expires_at.is_some());
+
+    // A flow is completed once
+    let _again = callback(&mut oauth, &format!("/callback?code=c0de&state={}", STATE));
+    login.flush();
+    assert_eq!(login.component().results[1], Err(OAuthError::UnknownFlow));
+    assert_eq!(network.pending(), 0);
+}
+
+#[test]
+fn it_rejects_wrong_redirects() {
+    let (network, storage, random) = (MockNetwork::new(), MockStorage::new(), MockRandom::new());
+    let _installed = (network.install(), storage.install(), random.install());
+    let mut login = TestRenderer::<Login>::new(());
+    let mut oauth = OAuthService::new(login.sender());
+
+    oauth.authorize_url(&config());
+    callback(&mut oauth, "/callback?code=c0de&state=forged");
+    oauth.authorize_url(&config());
+    callback(&mut oauth, "/callback?error=access_denied&error_description=No%20way");
+    assert_eq!(network.pending(), 0);
+
+    // The nonce of the ID token is checked
+    let _random = MockRandom::new().install();
+    let mut oauth = OAuthService::new(login.sender());
+    oauth.authorize_url(&config());
+    let _exchange = callback(&mut oauth, &format!("/callback?code=c0de&state={}", STATE));
+    // {"nonce": "other"}
+    assert!(network.respond("https://id.example/token", 200,
+        r#"{"access_token":"abc","id_token":"e30.eyJub25jZSI6ICJvdGhlciJ9.sig"}"#));
+
+    // Refused codes are reported with the error of the server
+    let _random = MockRandom::new().install();
+    let mut oauth = OAuthService::new(login.sender());
+    oauth.authorize_url(&config());
+    let _exchange = callback(&mut oauth, &format!("/callback?code=c0de&state={}", STATE));
+    assert!(network.respond("https://id.example/token", 400, r#"{"error":"invalid_grant"}"#));
+    login.flush();
+    assert_eq!(login.component().results, vec![
+        Err(OAuthError::StateMismatch),
+        Err(OAuthError::Denied { error: "access_denied".into(), description: Some("No way".into()) }),
+        Err(OAuthError::NonceMismatch),
+        Err(OAuthError::Exchange(400, Some("invalid_grant".into()))),
+    ]);
+}
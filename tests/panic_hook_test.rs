@@ -0,0 +1,19 @@
+extern crate yew;
+
+use yew::panic_hook::PanicReport;
+
+#[test]
+fn it_formats_reports() {
+    let report = PanicReport {
+        message: "index out of bounds".into(),
+        location: Some("src/main.rs:10:5".into()),
+        backtrace: None,
+    };
+    assert_eq!(report.to_string(), "panicked at src/main.rs:10:5: index out of bounds");
+    let report = PanicReport {
+        location: None,
+        backtrace: Some("0: main".into()),
+        ..report
+    };
+    assert_eq!(report.to_string(), "panicked: index out of bounds\n\n0: main");
+}
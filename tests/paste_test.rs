@@ -0,0 +1,71 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use yew::html::{AppSender, ClipboardFile, Component, Html, PasteData, ShouldRender};
+use yew::testing::TestRenderer;
+
+enum Msg {
+    Pasted(PasteData),
+}
+
+#[derive(Default)]
+struct Chat {
+    text: String,
+    images: Vec<String>,
+}
+
+impl Component for Chat {
+    type Msg = Msg;
+    type Properties = ();
+
+    fn create(_: (), _: AppSender<Msg>) -> Self {
+        Chat::default()
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Pasted(data) => {
+                self.text.push_str(&data.text);
+                self.images.extend(data.images().map(|image| image.name.clone()));
+            }
+        }
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        html! {
+            <div onpaste=|data| Msg::Pasted(data),>
+                <textarea onpaste.prevent_default=|data| Msg::Pasted(data),></textarea>
+                <p>{ &self.text }</p>
+            </div>
+        }
+    }
+}
+
+#[test]
+fn it_passes_pasted_images_to_handlers() {
+    let mut chat = TestRenderer::<Chat>::new(());
+    chat.simulate("textarea", "onpaste", PasteData {
+        files: vec![
+            ClipboardFile::new("image.png", "image/png", 2048),
+            ClipboardFile::new("notes.txt", "text/plain", 12),
+        ],
+        ..PasteData::default()
+    });
+    assert_eq!(chat.component().images, vec!["image.png".to_string()]);
+    assert!(chat.component().text.is_empty());
+}
+
+#[test]
+fn it_passes_pasted_text_and_markup() {
+    let mut chat = TestRenderer::<Chat>::new(());
+    chat.simulate("p", "onpaste", PasteData {
+        text: "Hello".into(),
+        html: Some("<b>Hello</b>".into()),
+        files: Vec::new(),
+    });
+    assert_eq!(chat.root().find("p").unwrap().text(), "Hello");
+    assert!(chat.component().images.is_empty());
+}
@@ -0,0 +1,66 @@
+extern crate yew;
+
+use yew::position::{compute, Align, Layout, Placement, PositionOptions, Rect, Side};
+
+fn layout(anchor: Rect) -> Layout {
+    Layout {
+        anchor,
+        floating: Rect::new(0.0, 0.0, 80.0, 30.0),
+        boundary: Rect::new(0.0, 0.0, 400.0, 300.0),
+    }
+}
+
+fn options(side: Side, align: Align) -> PositionOptions {
+    PositionOptions::new(Placement::new(side, align))
+}
+
+#[test]
+fn it_places_elements_at_sides_of_anchors() {
+    let anchor = layout(Rect::new(100.0, 100.0, 50.0, 20.0));
+    let below = compute(&anchor, &options(Side::Bottom, Align::Start));
+    assert_eq!((below.x, below.y), (100.0, 128.0));
+    assert_eq!(below.arrow, 25.0);
+    assert!(!below.detached);
+    let above = compute(&anchor, &options(Side::Top, Align::Center));
+    assert_eq!((above.x, above.y), (85.0, 62.0));
+    let right = compute(&anchor, &options(Side::Right, Align::Center));
+    assert_eq!((right.x, right.y), (158.0, 95.0));
+    assert_eq!(right.arrow, 15.0);
+    let before = compute(&anchor, &options(Side::Left, Align::End));
+    assert_eq!((before.x, before.y), (12.0, 90.0));
+    assert_eq!(below.style(), "position: fixed; left: 100px; top: 128px; --arrow-offset: 25px;");
+}
+
+#[test]
+fn it_flips_and_shifts_elements_into_boundaries() {
+    // There is no space below the anchor
+    let bottom = layout(Rect::new(100.0, 270.0, 50.0, 20.0));
+    let flipped = compute(&bottom, &options(Side::Bottom, Align::Start));
+    assert_eq!(flipped.placement, Placement::new(Side::Top, Align::Start));
+    assert_eq!(flipped.y, 232.0);
+    let mut fixed = options(Side::Bottom, Align::Start);
+    fixed.flip = false;
+    assert_eq!(compute(&bottom, &fixed).y, 298.0);
+
+    // The anchor is at the right edge, so the element moves left and the arrow follows the anchor
+    let edge = layout(Rect::new(380.0, 100.0, 20.0, 20.0));
+    let shifted = compute(&edge, &options(Side::Bottom, Align::Start));
+    assert_eq!((shifted.x, shifted.y), (312.0, 128.0));
+    assert_eq!(shifted.arrow, 78.0);
+    let mut unshifted = options(Side::Bottom, Align::Start);
+    unshifted.shift = false;
+    assert_eq!(compute(&edge, &unshifted).x, 380.0);
+}
+
+#[test]
+fn it_detaches_elements_of_hidden_anchors() {
+    assert_eq!(Rect::new(0.0, 0.0, 100.0, 100.0).intersect(&Rect::new(50.0, 80.0, 100.0, 100.0)),
+               Rect::new(50.0, 80.0, 50.0, 20.0));
+    assert_eq!(Rect::new(0.0, 0.0, 10.0, 10.0).intersect(&Rect::new(20.0, 20.0, 5.0, 5.0)).width, 0.0);
+
+    // A container scrolled the anchor out of the boundary
+    let scrolled = layout(Rect::new(100.0, -50.0, 50.0, 20.0));
+    let position = compute(&scrolled, &options(Side::Bottom, Align::Start));
+    assert!(position.detached);
+    assert!(position.style().ends_with("visibility: hidden;"));
+}
@@ -0,0 +1,107 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use yew::format::Nothing;
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::prefetch::{self, Destination, Hint, Rel};
+use yew::query::{self, QueryHandle, QueryOptions, QueryService};
+use yew::services::fetch::{Request, Response};
+use yew::services::mock::{MockConnectivity, MockHead, MockNetwork, MockVisibility};
+use yew::testing::TestRenderer;
+
+#[derive(Clone, Debug, PartialEq, Routable)]
+enum Route {
+    #[at("/")]
+    Home,
+    #[at("/posts/{id}")]
+    Post { id: u32 },
+    #[at("/reports")]
+    #[chunk("/chunks/reports.js")]
+    Reports,
+}
+
+fn register() {
+    prefetch::register(|route: &Route| {
+        if let Route::Post { id } = *route {
+            query::prefetch_fetch(format!("posts/{}", id), move || {
+                Request::get(format!("/posts/{}", id)).body(Nothing).unwrap()
+            }, parse);
+        }
+    });
+}
+
+struct Post {
+    post: QueryHandle<String, String>,
+}
+
+impl Component for Post {
+    type Msg = ();
+    type Properties = u32;
+
+    fn create(id: u32, sender: AppSender<()>) -> Self {
+        let mut queries = QueryService::new(sender);
+        let post = queries.fetch(format!("posts/{}", id), QueryOptions::default(), move || {
+            Request::get(format!("/posts/{}", id)).body(Nothing).unwrap()
+        }, parse, || ());
+        Post { post }
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        true
+    }
+
+    fn view(&self) -> Html<()> {
+        html! { <p></p> }
+    }
+}
+
+fn parse(response: Response<Result<String, String>>) -> Result<String, String> {
+    response.into_body()
+}
+
+#[test]
+fn it_prefetches_chunks_and_data_of_routes() {
+    let network = MockNetwork::new();
+    let head = MockHead::new();
+    let _installed = (network.install(), head.install());
+    register();
+
+    prefetch::route(&Route::Post { id: 1 });
+    prefetch::route(&Route::Post { id: 1 });
+    assert_eq!(network.pending(), 1);
+    assert!(network.respond("/posts/1", 200, "Hello"));
+    assert_eq!(query::data::<String>("posts/1").unwrap().as_str(), "Hello");
+    // Fresh data isn't fetched again
+    prefetch::route(&Route::Post { id: 1 });
+    assert_eq!(network.pending(), 0);
+
+    prefetch::route(&Route::Reports);
+    prefetch::route(&Route::Reports);
+    prefetch::preload("/fonts/inter.woff2", Destination::Font);
+    assert_eq!(head.hints(), vec![
+        Hint { rel: Rel::Prefetch, href: "/chunks/reports.js".into(), destination: Destination::Script },
+        Hint { rel: Rel::Preload, href: "/fonts/inter.woff2".into(), destination: Destination::Font },
+    ]);
+}
+
+#[test]
+fn it_shares_prefetched_queries_with_pages() {
+    let network = MockNetwork::new();
+    let _installed = (network.install(), MockVisibility::new().install(), MockConnectivity::new().install());
+    register();
+
+    prefetch::route(&Route::Post { id: 2 });
+    let page = TestRenderer::<Post>::new(2);
+    assert_eq!(network.pending(), 1);
+    assert!(network.respond("/posts/2", 200, "Second"));
+    assert_eq!(page.component().post.state().data.unwrap().as_str(), "Second");
+
+    // Data is kept until a page uses it
+    prefetch::route(&Route::Post { id: 3 });
+    assert!(network.respond("/posts/3", 200, "Third"));
+    let page = TestRenderer::<Post>::new(3);
+    assert_eq!(network.pending(), 0);
+    assert_eq!(page.component().post.state().data.unwrap().as_str(), "Third");
+}
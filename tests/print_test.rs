@@ -0,0 +1,99 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::services::mock::MockPrinter;
+use yew::services::print::{PrintDocument, PrintEvent, PrintHandle, PrintJob, PrintService};
+use yew::testing::TestRenderer;
+use yew::virtual_dom::NodeRef;
+
+#[derive(Debug, PartialEq)]
+enum Msg {
+    PrintInvoice,
+    PrintReceipt,
+    Print(PrintEvent),
+}
+
+struct Invoice {
+    print: PrintService<Msg>,
+    invoice: NodeRef,
+    events: Vec<PrintEvent>,
+    _listener: PrintHandle,
+}
+
+impl Component for Invoice {
+    type Msg = Msg;
+    type Properties = ();
+
+    fn create(_: (), sender: AppSender<Msg>) -> Self {
+        let mut print = PrintService::new(sender);
+        let listener = print.listen(Msg::Print);
+        Invoice {
+            print,
+            invoice: NodeRef::default(),
+            events: Vec::new(),
+            _listener: listener,
+        }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::PrintInvoice => {
+                self.print.print_element(&self.invoice);
+            }
+            Msg::PrintReceipt => {
+                let receipt = PrintDocument::new("Receipt <42>")
+                    .stylesheet("/print.css?v=1&lang=en")
+                    .style("@page { size: A5; }")
+                    .body("<p>Paid</p>");
+                self.print.print_document(&receipt);
+            }
+            Msg::Print(event) => self.events.push(event),
+        }
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        html! {
+            <div>
+                <section ref=&self.invoice,>{ "Invoice" }</section>
+                <button id="invoice", onclick=|_| Msg::PrintInvoice,>{ "Print invoice" }</button>
+                <button id="receipt", onclick=|_| Msg::PrintReceipt,>{ "Print receipt" }</button>
+            </div>
+        }
+    }
+}
+
+#[test]
+fn it_prints_elements_and_documents() {
+    let printer = MockPrinter::new();
+    let _installed = printer.install();
+    let mut invoice = TestRenderer::<Invoice>::new(());
+    invoice.click("#invoice");
+    invoice.flush();
+    assert_eq!(printer.jobs(), vec![PrintJob::Element(invoice.component().invoice.clone())]);
+    assert_eq!(invoice.component().events, vec![PrintEvent::Before, PrintEvent::After]);
+
+    invoice.click("#receipt");
+    invoice.flush();
+    let expected = "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>Receipt &lt;42&gt;</title>\
+                    <link rel=\"stylesheet\" href=\"/print.css?v=1&amp;lang=en\">\
+                    <style>@page { size: A5; }</style></head><body><p>Paid</p></body></html>";
+    assert_eq!(printer.jobs()[1], PrintJob::Document(expected.to_owned()));
+    assert_eq!(invoice.component().events.len(), 4);
+}
+
+#[test]
+fn it_reports_prints_from_the_browser() {
+    let printer = MockPrinter::new();
+    let _installed = printer.install();
+    let mut invoice = TestRenderer::<Invoice>::new(());
+    printer.notify(PrintEvent::Before);
+    invoice.flush();
+    assert_eq!(invoice.component().events, vec![PrintEvent::Before]);
+    assert!(printer.jobs().is_empty());
+    drop(invoice);
+    assert_eq!(printer.listeners(), 0);
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use std::time::Duration;
+use yew::html::{AppSender, Component, Html, Priority, ShouldRender};
+use yew::services::mock::MockClock;
+use yew::testing::TestRenderer;
+
+enum Msg {
+    Typed(&'static str),
+    Streamed(&'static str),
+}
+
+#[derive(Default)]
+struct FeedProps {
+    clock: MockClock,
+}
+
+struct Feed {
+    clock: MockClock,
+    handled: Vec<&'static str>,
+}
+
+impl Component for Feed {
+    type Msg = Msg;
+    type Properties = FeedProps;
+
+    fn create(props: FeedProps, _: AppSender<Msg>) -> Self {
+        Feed { clock: props.clock, handled: Vec::new() }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Typed(name) => self.handled.push(name),
+            Msg::Streamed(name) => {
+                // Every streamed update spends the whole slice
+                self.clock.advance(Duration::from_millis(10));
+                self.handled.push(name);
+            }
+        }
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        html! { <ul></ul> }
+    }
+}
+
+#[test]
+fn it_handles_the_high_lane_first() {
+    let clock = MockClock::new();
+    let _installed = clock.install();
+    let mut feed = TestRenderer::<Feed>::new(FeedProps { clock: clock.clone() });
+    let mut sender = feed.sender();
+    sender.send_with(Priority::Low, Msg::Streamed("row"));
+    sender.send(Msg::Typed("key"));
+    feed.flush();
+
+    assert_eq!(feed.component().handled, vec!["key", "row"]);
+}
+
+#[test]
+fn it_keeps_the_carried_message_when_the_high_lane_goes_first() {
+    let clock = MockClock::new();
+    let _installed = clock.install();
+    let mut feed = TestRenderer::<Feed>::new(FeedProps { clock: clock.clone() });
+    let mut sender = feed.sender();
+    sender.send_with(Priority::Low, Msg::Streamed("first"));
+    sender.send_with(Priority::Low, Msg::Streamed("second"));
+    feed.flush();
+    // The slice elapsed, the second message is carried to the next one
+    assert_eq!(feed.component().handled, vec!["first"]);
+
+    sender.send(Msg::Typed("key"));
+    sender.send_with(Priority::Low, Msg::Streamed("third"));
+    feed.flush();
+    feed.flush();
+    assert_eq!(feed.component().handled, vec!["first", "key", "second", "third"]);
+}
@@ -0,0 +1,97 @@
+#![cfg(all(feature = "testing", feature = "profiler"))]
+
+#[macro_use]
+extern crate yew;
+
+use std::cell::RefCell;
+use std::time::Duration;
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::profiler;
+use yew::services::mock::MockClock;
+use yew::testing::TestRenderer;
+
+thread_local! {
+    static CLOCK: RefCell<Option<MockClock>> = RefCell::new(None);
+}
+
+fn spend(ms: u64) {
+    CLOCK.with(|clock| {
+        if let Some(ref clock) = *clock.borrow() {
+            clock.advance(Duration::from_millis(ms));
+        }
+    });
+}
+
+struct Row;
+
+impl Component for Row {
+    type Msg = ();
+    type Properties = ();
+
+    fn create(_: (), _: AppSender<()>) -> Self {
+        Row
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html<()> {
+        spend(2);
+        html! { <li>{ "row" }</li> }
+    }
+}
+
+struct Table;
+
+impl Component for Table {
+    type Msg = ();
+    type Properties = ();
+
+    fn create(_: (), _: AppSender<()>) -> Self {
+        Table
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        true
+    }
+
+    fn view(&self) -> Html<()> {
+        spend(8);
+        html! {
+            <ul>
+                <Row: />
+                <Row: />
+                <Row: />
+            </ul>
+        }
+    }
+}
+
+#[test]
+fn it_aggregates_own_times_by_component_type() {
+    let clock = MockClock::new();
+    let _clock = clock.install();
+    CLOCK.with(|cell| *cell.borrow_mut() = Some(clock.clone()));
+    profiler::reset();
+
+    let mut table = TestRenderer::<Table>::new(());
+    table.send(());
+    let report = profiler::report();
+    assert_eq!(report.len(), 2);
+
+    // Rows are rendered inside of the patch of the table, but it doesn't count them
+    assert!(report[0].name.ends_with("Table"));
+    assert_eq!(report[0].renders, 2);
+    assert_eq!(report[0].view, Duration::from_millis(16));
+    assert_eq!(report[0].patch, Duration::from_millis(0));
+    assert_eq!(report[0].slowest, Duration::from_millis(8));
+
+    assert!(report[1].name.ends_with("Row"));
+    assert_eq!(report[1].renders, 6);
+    assert_eq!(report[1].total(), Duration::from_millis(12));
+    assert_eq!(report[1].average(), Duration::from_millis(2));
+
+    profiler::reset();
+    assert!(profiler::report().is_empty());
+}
@@ -0,0 +1,82 @@
+#![cfg(feature = "testing")]
+
+extern crate yew;
+
+use std::time::Duration;
+use yew::components::ProgressBar;
+use yew::components::progress_bar::{Msg, ProgressBarProps};
+use yew::format::Nothing;
+use yew::progress;
+use yew::services::Task;
+use yew::services::fetch::{FetchService, Request, Response};
+use yew::services::mock::{MockClock, MockNetwork};
+use yew::services::timeout::TimeoutService;
+use yew::testing::TestRenderer;
+
+#[test]
+fn it_counts_tracked_tasks() {
+    let clock = MockClock::new();
+    let network = MockNetwork::new();
+    let _clock = clock.install();
+    let _network = network.install();
+    let bar = TestRenderer::<ProgressBar>::new(ProgressBarProps::default());
+    let mut fetch = FetchService::new(bar.sender());
+    let mut timeout = TimeoutService::new(bar.sender());
+
+    // Tasks aren't counted unless they're tracked
+    let request = || Request::get("/users").body(Nothing).unwrap();
+    let _untracked = fetch.fetch(request(), |_: Response<Nothing>| Msg::Tick);
+    assert_eq!(progress::in_flight(), 0);
+
+    let _fetch = progress::track(|| fetch.fetch(request(), |_: Response<Nothing>| Msg::Tick));
+    let mut retry = progress::track(|| timeout.spawn(Duration::from_secs(1), || Msg::Tick));
+    let pending = progress::begin();
+    assert_eq!(progress::in_flight(), 3);
+
+    assert!(network.respond("/users", 200, ""));
+    assert!(network.respond("/users", 200, ""));
+    assert_eq!(progress::in_flight(), 2);
+    retry.cancel();
+    drop(pending);
+    assert_eq!(progress::in_flight(), 0);
+
+    progress::track_fetches(true);
+    let _fetch = fetch.fetch(request(), |_: Response<Nothing>| Msg::Tick);
+    assert_eq!(progress::in_flight(), 1);
+    assert!(network.respond("/users", 200, ""));
+    progress::track_fetches(false);
+    assert_eq!(progress::in_flight(), 0);
+}
+
+#[test]
+fn it_shows_the_bar_while_work_is_in_flight() {
+    let clock = MockClock::new();
+    let _clock = clock.install();
+    let mut bar = TestRenderer::<ProgressBar>::new(ProgressBarProps::default());
+    let hidden = |bar: &TestRenderer<ProgressBar>| {
+        bar.root().find(".progress-bar").and_then(|node| node.attribute("hidden")) == Some("true")
+    };
+    let percent = |bar: &TestRenderer<ProgressBar>| {
+        bar.root().find(".progress-bar").and_then(|node| node.attribute("aria-valuenow")).map(str::to_owned)
+    };
+    assert!(hidden(&bar));
+
+    let first = progress::begin();
+    let second = progress::begin();
+    bar.flush();
+    assert!(!hidden(&bar));
+    assert_eq!(percent(&bar), Some("10".into()));
+    clock.advance(Duration::from_millis(400));
+    bar.flush();
+    assert_eq!(percent(&bar), Some("25".into()));
+
+    drop(first);
+    bar.flush();
+    assert_eq!(percent(&bar), Some("25".into()));
+    drop(second);
+    bar.flush();
+    assert_eq!(percent(&bar), Some("100".into()));
+    clock.advance(Duration::from_millis(300));
+    bar.flush();
+    assert!(hidden(&bar));
+}
@@ -0,0 +1,94 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use yew::callback::Callback;
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::testing::TestRenderer;
+
+/// Properties of a dialog.
+#[derive(Clone, PartialEq, Properties)]
+pub struct DialogProps {
+    /// A required title.
+    pub title: String,
+    #[prop_or(true)]
+    pub closable: bool,
+    #[prop_or_default]
+    pub onclose: Option<Callback<()>>,
+    #[prop_or(3)]
+    pub level: u8
+}
+
+struct Dialog {
+    props: DialogProps,
+}
+
+impl Component for Dialog {
+    type Msg = ();
+    type Properties = DialogProps;
+
+    fn create(props: DialogProps, _: AppSender<()>) -> Self {
+        Dialog { props }
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html<()> {
+        let close = if self.props.closable { "closable" } else { "fixed" };
+        html! {
+            <section class=close,>
+                <h3>{ &self.props.title }</h3>
+                <span>{ self.props.level }</span>
+            </section>
+        }
+    }
+}
+
+struct Page;
+
+impl Component for Page {
+    type Msg = ();
+    type Properties = ();
+
+    fn create(_: (), _: AppSender<()>) -> Self {
+        Page
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html<()> {
+        html! {
+            <div>
+                <Dialog: title="Delete the file?", />
+                <Dialog: closable=false, level=1, title="Saved", />
+            </div>
+        }
+    }
+}
+
+#[test]
+fn it_fills_defaults_of_optional_properties() {
+    let props = DialogProps::default();
+    assert_eq!(props.title, "");
+    assert!(props.closable);
+    assert!(props.onclose.is_none());
+    assert_eq!(props.level, 3);
+}
+
+#[test]
+fn it_passes_set_properties() {
+    let page = TestRenderer::<Page>::new(());
+    let dialogs = page.root().find_all("section");
+    assert_eq!(dialogs.len(), 2);
+    assert_eq!(dialogs[0].find("h3").unwrap().text(), "Delete the file?");
+    assert!(dialogs[0].element().unwrap().has_class("closable"));
+    assert_eq!(dialogs[0].find("span").unwrap().text(), "3");
+    assert_eq!(dialogs[1].find("h3").unwrap().text(), "Saved");
+    assert!(dialogs[1].element().unwrap().has_class("fixed"));
+    assert_eq!(dialogs[1].find("span").unwrap().text(), "1");
+}
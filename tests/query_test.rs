@@ -0,0 +1,168 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use yew::format::Nothing;
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::query::{self, MutationHandle, QueryHandle, QueryOptions, QueryService};
+use yew::services::fetch::{Request, Response};
+use yew::services::mock::{MockConnectivity, MockNetwork, MockVisibility};
+use yew::testing::TestRenderer;
+
+struct Profile {
+    queries: QueryService<Msg>,
+    user: QueryHandle<String, String>,
+    rename: Option<MutationHandle>,
+    conflict: Option<String>,
+}
+
+enum Msg {
+    Changed,
+    Rename(String),
+    Renamed(Result<(), String>),
+}
+
+fn parse(response: Response<Result<String, String>>) -> Result<String, String> {
+    if response.status().is_success() {
+        response.into_body()
+    } else {
+        Err("failed".into())
+    }
+}
+
+impl Component for Profile {
+    type Msg = Msg;
+    type Properties = ();
+
+    fn create(_: (), sender: AppSender<Msg>) -> Self {
+        let mut queries = QueryService::new(sender);
+        let user = queries.fetch("users/1", QueryOptions::default(), || {
+            Request::get("/users/1").body(Nothing).unwrap()
+        }, parse, || Msg::Changed);
+        Profile { queries, user, rename: None, conflict: None }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Changed => {}
+            Msg::Rename(name) => {
+                let request = Request::put("/users/1").body(Some(name.clone())).unwrap();
+                let rename = self.queries.mutate("users/1", |_: Option<&String>| name, request, |response| {
+                    parse(response).map(|_| None)
+                }, Msg::Renamed);
+                self.rename = Some(rename);
+            }
+            Msg::Renamed(result) => {
+                self.conflict = result.err();
+            }
+        }
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let state = self.user.state();
+        let name = match state.data {
+            Some(ref name) => name.to_string(),
+            None => "loading".into(),
+        };
+        html! {
+            <p class=("user", if state.fetching { "fetching" } else { "" }),>{ name }</p>
+        }
+    }
+}
+
+fn name(profile: &TestRenderer<Profile>) -> String {
+    profile.root().find(".user").unwrap().text()
+}
+
+#[test]
+fn it_shares_queries_between_components() {
+    let network = MockNetwork::new();
+    let _installed = (network.install(), MockVisibility::new().install(), MockConnectivity::new().install());
+    let mut first = TestRenderer::<Profile>::new(());
+    let mut second = TestRenderer::<Profile>::new(());
+    assert_eq!(network.pending(), 1);
+    assert!(first.component().user.state().is_loading());
+    assert_eq!(name(&first), "loading");
+
+    assert!(network.respond("/users/1", 200, "Ann"));
+    first.flush();
+    second.flush();
+    assert_eq!(name(&first), "Ann");
+    assert_eq!(name(&second), "Ann");
+
+    // Stale data is shown until the query is fetched again
+    query::invalidate("users/1");
+    first.flush();
+    assert_eq!(network.pending(), 1);
+    assert!(first.root().find(".user.fetching").is_some());
+    assert!(network.respond("/users/1", 500, ""));
+    first.flush();
+    let state = first.component().user.state();
+    assert_eq!(state.error.as_ref().map(|error| error.as_str()), Some("failed"));
+    assert_eq!(name(&first), "Ann");
+
+    query::set_data("users/1", "Bob".to_string());
+    second.flush();
+    assert_eq!(name(&second), "Bob");
+    assert_eq!(query::data::<String>("users/1").unwrap().as_str(), "Bob");
+}
+
+#[test]
+fn it_refetches_queries_on_focus_and_reconnect() {
+    let network = MockNetwork::new();
+    let page = MockVisibility::new();
+    let connectivity = MockConnectivity::new();
+    let _installed = (network.install(), page.install(), connectivity.install());
+    let mut profile = TestRenderer::<Profile>::new(());
+    assert!(network.respond("/users/1", 200, "Ann"));
+
+    page.set_hidden(true);
+    page.set_hidden(false);
+    assert!(network.respond("/users/1", 200, "Ann Lee"));
+    connectivity.set_online(false);
+    connectivity.set_online(true);
+    assert!(network.respond("/users/1", 200, "Ann Smith"));
+    profile.flush();
+    assert_eq!(name(&profile), "Ann Smith");
+
+    // A request is canceled when nobody uses the query
+    query::invalidate("users/1");
+    assert_eq!(network.pending(), 1);
+    drop(profile);
+    assert_eq!(network.pending(), 0);
+
+    // The cached value is shown at once and fetched again, since it's stale
+    let profile = TestRenderer::<Profile>::new(());
+    assert_eq!(name(&profile), "Ann Smith");
+    assert_eq!(network.pending(), 1);
+}
+
+#[test]
+fn it_rolls_back_failed_mutations() {
+    let network = MockNetwork::new();
+    let _installed = (network.install(), MockVisibility::new().install(), MockConnectivity::new().install());
+    let mut profile = TestRenderer::<Profile>::new(());
+    assert!(network.respond("/users/1", 200, "Ann"));
+
+    // A refetch which started before is canceled and held until the server answers
+    query::invalidate("users/1");
+    profile.send(Msg::Rename("Bob".into()));
+    assert_eq!(name(&profile), "Bob");
+    assert_eq!(network.pending(), 1);
+    assert!(network.respond("/users/1", 409, ""));
+    profile.flush();
+    assert_eq!(name(&profile), "Ann");
+    assert_eq!(profile.component().conflict.as_deref(), Some("failed"));
+    assert!(network.respond("/users/1", 200, "Ann"));
+    assert_eq!(network.pending(), 0);
+
+    // A confirmed mutation fetches the query again
+    profile.send(Msg::Rename("Bob".into()));
+    assert!(network.respond("/users/1", 204, ""));
+    assert!(network.respond("/users/1", 200, "Bob"));
+    profile.flush();
+    assert_eq!(name(&profile), "Bob");
+    assert_eq!(profile.component().conflict, None);
+}
@@ -0,0 +1,52 @@
+#[macro_use]
+extern crate yew;
+
+use yew::html::{AppSender, Component, Html, Link, ShouldRender};
+use yew::registry::Registry;
+
+struct Label {
+    text: String,
+}
+
+#[derive(Default)]
+struct LabelProps {
+    text: String,
+}
+
+impl Component for Label {
+    type Msg = ();
+    type Properties = LabelProps;
+
+    fn create(props: LabelProps, _: AppSender<()>) -> Self {
+        Label { text: props.text }
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html<()> {
+        html! {
+            <span>{ &self.text }</span>
+        }
+    }
+}
+
+#[test]
+fn it_creates_registered_components() {
+    let mut registry: Registry<&'static str, ()> = Registry::new();
+    registry.register::<Label>("label");
+    assert!(registry.contains("label"));
+    assert!(!registry.contains("chart"));
+
+    let props = LabelProps { text: "registered".into() };
+    assert!(registry.create("label", Box::new(props), Link::new()).is_some());
+    assert!(registry.create("chart", Box::new(LabelProps::default()), Link::new()).is_none());
+}
+
+#[test]
+fn it_rejects_wrong_properties() {
+    let mut registry: Registry<String, ()> = Registry::new();
+    registry.register::<Label>("label".to_owned());
+    assert!(registry.create("label", Box::new(42u32), Link::new()).is_none());
+}
@@ -0,0 +1,112 @@
+#![cfg(feature = "testing")]
+
+extern crate serde_json;
+extern crate yew;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use yew::callback::Callback;
+use yew::components::rich_text_editor::{Msg, RichTextEditor, RichTextEditorProps};
+use yew::rich_text::{Block, BlockKind, Delta, Document, Mark, Marks, Op, Selection};
+use yew::testing::{self, TestRenderer};
+
+fn bold() -> Marks {
+    let mut marks = Marks::default();
+    marks.set(&Mark::Bold, true);
+    marks
+}
+
+#[test]
+fn it_applies_deltas_to_documents() {
+    let mut note = Document::from_text("Hello world");
+    assert_eq!(note.len(), 12);
+    assert!(note.apply(&Delta::new().retain(6).format(5, Mark::Bold, true)));
+    assert!(note.is_marked(6, 11, &Mark::Bold));
+    assert!(!note.is_marked(5, 11, &Mark::Bold));
+    assert_eq!(note.marks_at(11), bold());
+
+    assert!(note.apply(&Delta::new().set_block(12, BlockKind::Heading(2))));
+    assert!(note.apply(&Delta::new().retain(5).insert("\n", Marks::default()).delete(1)));
+    assert_eq!(note.text(), "Hello\nworld");
+    assert_eq!(note.blocks().len(), 2);
+    assert!(note.blocks().iter().all(|block| block.kind == BlockKind::Heading(2)));
+    assert!(!note.apply(&Delta::new().retain(3)));
+
+    let toggle = note.toggle_block(Selection::caret(8), BlockKind::Heading(2));
+    note.apply(&toggle);
+    assert_eq!(note.blocks()[0].kind, BlockKind::Heading(2));
+    assert_eq!(note.blocks()[1].kind, BlockKind::Paragraph);
+    let toggle = note.toggle_mark(Selection { anchor: 11, focus: 6 }, Mark::Bold);
+    assert_eq!(toggle.ops(), &[Op::Retain(6), Op::Format(5, Mark::Bold, false)]);
+    assert!(note.toggle_mark(Selection::caret(3), Mark::Bold).is_empty());
+}
+
+#[test]
+fn it_diffs_documents_and_moves_offsets() {
+    let old = Document::from_text("Hello world");
+    let mut new = Document::from_text("Hello, big world");
+    new.apply(&Delta::new().retain(7).format(3, Mark::Italic, true));
+    let delta = old.diff(&new);
+    let mut patched = old.clone();
+    patched.apply(&delta);
+    assert_eq!(patched, new);
+    assert_eq!(delta.ops()[0], Op::Retain(5));
+
+    let typed = Delta::new().retain(2).insert("ab", Marks::default()).delete(1);
+    assert_eq!(typed.transform_offset(1), 1);
+    assert_eq!(typed.transform_offset(2), 2);
+    assert_eq!(typed.transform_offset(4), 5);
+    let selection = Selection { anchor: 6, focus: 3 };
+    assert_eq!(selection.transform(&typed), Selection { anchor: 7, focus: 4 });
+    assert!(Document::new().diff(&Document::new()).is_empty());
+}
+
+#[test]
+fn it_stores_and_renders_documents() {
+    let mut note = Document::from_blocks(vec![
+        Block::new(BlockKind::Heading(1), "Plan"),
+        Block::new(BlockKind::Bullet, "Write"),
+        Block::new(BlockKind::Bullet, "Ship"),
+    ]);
+    note.apply(&Delta::new().retain(5).format(5, Mark::Link("/write".into()), true));
+    let json = serde_json::to_string(&note).expect("a stored document");
+    assert_eq!(serde_json::from_str::<Document>(&json).expect("a loaded document"), note);
+
+    let node = testing::render(note.view::<()>());
+    assert_eq!(node.find("h1").unwrap().text(), "Plan");
+    assert_eq!(node.find_all("ul").len(), 1);
+    assert_eq!(node.find_all("li").len(), 2);
+    assert_eq!(node.find("li a").unwrap().attribute("href"), Some("/write"));
+}
+
+#[test]
+fn it_sends_deltas_of_edits() {
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let onchange = changes.clone();
+    let props = RichTextEditorProps {
+        document: Document::from_text("Hi"),
+        label: "Comment".into(),
+        placeholder: "Write a comment".into(),
+        onchange: Callback::from(move |delta| onchange.borrow_mut().push(delta)),
+        ..RichTextEditorProps::default()
+    };
+    let mut editor = TestRenderer::<RichTextEditor>::new(props.clone());
+    editor.flush();
+    let element = editor.root().find("[role=\"textbox\"]").unwrap();
+    assert_eq!(element.attribute("contenteditable"), Some("true"));
+    assert_eq!(element.attribute("aria-label"), Some("Comment"));
+    assert!(!element.element().unwrap().has_class("empty"));
+
+    editor.send(Msg::Input(Document::from_text("Hi there")));
+    assert_eq!(*changes.borrow(), vec![Delta::new().retain(2).insert(" there", Marks::default())]);
+
+    // Commands emit deltas of the typed document until it's applied
+    editor.send(Msg::Select(Selection { anchor: 3, focus: 8 }));
+    editor.send(Msg::Command(Mark::Bold));
+    assert_eq!(changes.borrow()[1], Delta::new().retain(3).format(5, Mark::Bold, true));
+
+    editor.change(RichTextEditorProps { document: Document::new(), ..props });
+    let element = editor.root().find("[role=\"textbox\"]").unwrap();
+    assert!(element.element().unwrap().has_class("empty"));
+    assert_eq!(element.attribute("data-placeholder"), Some("Write a comment"));
+}
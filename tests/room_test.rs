@@ -0,0 +1,143 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+#[macro_use]
+extern crate serde_json;
+
+use std::collections::HashMap;
+use std::time::Duration;
+use serde_json::Value;
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::room::{Presence, RoomHandle, RoomMsg, RoomService};
+use yew::services::mock::{MockClock, MockNetwork};
+use yew::testing::TestRenderer;
+
+const URL: &str = "ws://chat/socket";
+
+type Meta = HashMap<String, String>;
+
+struct Chat {
+    room: RoomHandle,
+    presence: Presence<Meta>,
+    messages: Vec<String>,
+    events: Vec<String>,
+}
+
+enum Msg {
+    Send(String),
+    Room(RoomMsg<Value, Meta>),
+}
+
+#[derive(Clone, PartialEq, Default)]
+struct Props {
+    topic: String,
+}
+
+impl Component for Chat {
+    type Msg = Msg;
+    type Properties = Props;
+
+    fn create(props: Props, sender: AppSender<Msg>) -> Self {
+        let mut rooms = RoomService::new(sender);
+        let room = rooms.join(URL, &props.topic, &json!({ "token": "abc" }), Msg::Room);
+        Chat { room, presence: Presence::new(), messages: Vec::new(), events: Vec::new() }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Send(body) => self.room.push("new_msg", &json!({ "body": body })),
+            Msg::Room(RoomMsg::Message { payload, .. }) => {
+                self.messages.push(payload["body"].as_str().unwrap_or_default().to_owned());
+            }
+            Msg::Room(RoomMsg::Presence(diff)) => return self.presence.apply(diff),
+            Msg::Room(RoomMsg::Rejected(reason)) => self.events.push(format!("rejected: {}", reason)),
+            Msg::Room(msg) => self.events.push(format!("{:?}", msg)),
+        }
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        html! {
+            <ul class="members",>{ for self.presence.keys().into_iter().map(|key| html! { <li>{ key }</li> }) }</ul>
+        }
+    }
+}
+
+fn frames(data: Vec<String>) -> Vec<Value> {
+    data.iter().map(|frame| serde_json::from_str(frame).unwrap()).collect()
+}
+
+#[test]
+fn it_joins_rooms_and_tracks_presence() {
+    let network = MockNetwork::new();
+    let clock = MockClock::new();
+    let _installed = (network.install(), clock.install());
+    let mut chat = TestRenderer::<Chat>::new(Props { topic: "room:lobby".into() });
+    let socket = network.socket(URL).unwrap();
+
+    // Pushes wait until the room is joined
+    chat.send(Msg::Send("hi".into()));
+    socket.open();
+    assert_eq!(frames(socket.sent()), vec![json!(["1", "1", "room:lobby", "phx_join", { "token": "abc" }])]);
+    socket.receive(r#"["1", "1", "room:lobby", "phx_reply", { "status": "ok", "response": {} }]"#);
+    assert_eq!(frames(socket.sent())[1], json!(["1", "2", "room:lobby", "new_msg", { "body": "hi" }]));
+    assert!(chat.component().room.is_joined());
+
+    socket.receive(r#"["1", null, "room:lobby", "new_msg", { "body": "hello" }]"#);
+    socket.receive(r#"["1", null, "room:lobby", "presence_state", {
+        "ann": { "metas": [{ "phx_ref": "a1", "status": "away" }] }
+    }]"#);
+    socket.receive(r#"["1", null, "room:lobby", "presence_diff", {
+        "joins": { "bob": { "metas": [{ "phx_ref": "b1", "status": "online" }] } },
+        "leaves": { "ann": { "metas": [{ "phx_ref": "a1", "status": "away" }] } }
+    }]"#);
+    chat.flush();
+    assert_eq!(chat.component().messages, vec!["hello".to_string()]);
+    assert_eq!(chat.root().find(".members").unwrap().text(), "bob");
+    assert_eq!(chat.component().presence.get("bob")[0]["status"], "online");
+
+    clock.advance(Duration::from_secs(30));
+    assert_eq!(frames(socket.sent())[2], json!([null, "3", "phoenix", "heartbeat", {}]));
+
+    // The room is joined again after a reconnect
+    socket.close();
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(network.connections(URL), 2);
+    let socket = network.socket(URL).unwrap();
+    socket.open();
+    socket.receive(r#"["1", null, "room:lobby", "new_msg", { "body": "stale" }]"#);
+    socket.receive(r#"["4", "4", "room:lobby", "phx_reply", { "status": "ok", "response": {} }]"#);
+    chat.flush();
+    assert_eq!(chat.component().messages.len(), 1);
+    assert_eq!(chat.component().events, vec!["Joined", "Disconnected", "Joined"]);
+
+    drop(chat);
+    assert_eq!(frames(socket.sent())[1], json!(["4", "5", "room:lobby", "phx_leave", {}]));
+    assert!(socket.is_closed());
+}
+
+#[test]
+fn it_shares_a_connection_between_rooms() {
+    let network = MockNetwork::new();
+    let clock = MockClock::new();
+    let _installed = (network.install(), clock.install());
+    let mut lobby = TestRenderer::<Chat>::new(Props { topic: "room:lobby".into() });
+    let socket = network.socket(URL).unwrap();
+    socket.open();
+    let mut private = TestRenderer::<Chat>::new(Props { topic: "room:private".into() });
+    assert_eq!(network.connections(URL), 1);
+
+    socket.receive(r#"["2", "2", "room:private", "phx_reply", { "status": "error", "response": { "reason": "unauthorized" } }]"#);
+    socket.receive(r#"["1", "1", "room:lobby", "phx_reply", { "status": "ok", "response": {} }]"#);
+    lobby.flush();
+    private.flush();
+    assert_eq!(private.component().events, vec!["rejected: unauthorized"]);
+    assert_eq!(lobby.component().events, vec!["Joined"]);
+
+    // The connection is closed with the last room
+    drop(private);
+    assert!(!socket.is_closed());
+    drop(lobby);
+    assert!(socket.is_closed());
+}
@@ -0,0 +1,214 @@
+#[macro_use]
+extern crate yew;
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+use yew::components::{Link, Redirect, Router};
+use yew::html::{Callback, Html};
+use yew::router::{self, Decision, Guard, Navigation, Routable};
+
+#[derive(Clone, Debug, PartialEq, Routable)]
+enum Route {
+    #[at("/")]
+    Home,
+    #[at("/posts/{id}")]
+    Post { id: u32 },
+    #[at("/posts/{post}/comments/{slug}")]
+    Comment { post: u32, slug: String },
+    #[at("*")]
+    NotFound,
+}
+
+#[test]
+fn it_parses_routes() {
+    assert_eq!(Route::from_path("/"), Some(Route::Home));
+    assert_eq!(Route::from_path("/posts/42"), Some(Route::Post { id: 42 }));
+    assert_eq!(Route::from_path("/posts/42/"), Some(Route::Post { id: 42 }));
+    assert_eq!(
+        Route::from_path("/posts/1/comments/hello%20world"),
+        Some(Route::Comment { post: 1, slug: "hello world".into() })
+    );
+    assert_eq!(Route::from_path("/posts/abc"), Some(Route::NotFound));
+    assert_eq!(Route::from_path("/unknown/path"), Some(Route::NotFound));
+}
+
+#[test]
+fn it_builds_paths() {
+    assert_eq!(Route::Home.to_path(), "/");
+    assert_eq!(Route::Post { id: 7 }.to_path(), "/posts/7");
+    let comment = Route::Comment { post: 1, slug: "a/b c".into() };
+    assert_eq!(comment.to_path(), "/posts/1/comments/a%2Fb%20c");
+    assert_eq!(Route::from_path(&comment.to_path()), Some(comment));
+    assert_eq!(Route::NotFound.to_path(), "/");
+}
+
+#[test]
+fn it_strips_query_and_hash() {
+    assert_eq!(router::path_of("/posts/1?tab=2#top"), "/posts/1");
+    assert_eq!(router::path_of("/posts/1#top"), "/posts/1");
+}
+
+#[test]
+fn it_puts_links_and_routers_into_views() {
+    let view: Html<()> = html! {
+        <nav>
+            <Link<Route>: to=Route::Home, label="Home", />
+            <Link<Route>: to=Route::Post { id: 1 }, label="First", active_class="current", />
+            <Router<Route>: render=|route| match route {
+                Route::Post { id } => html! { <p>{ id }</p> },
+                _ => html! { <p>{ "Home" }</p> },
+            }, />
+        </nav>
+    };
+    assert_eq!(view.childs.len(), 3);
+}
+
+#[derive(Clone, Debug, PartialEq, Routable)]
+enum AdminRoute {
+    #[at("/")]
+    Dashboard,
+    #[at("/users/{id}")]
+    User { id: u32 },
+}
+
+#[derive(Clone, Debug, PartialEq, Routable)]
+enum AppRoute {
+    #[at("/")]
+    Home,
+    #[at("/admin")]
+    Admin(AdminRoute),
+}
+
+#[test]
+fn it_nests_routes() {
+    assert_eq!(AppRoute::from_path("/admin"), Some(AppRoute::Admin(AdminRoute::Dashboard)));
+    assert_eq!(
+        AppRoute::from_path("/admin/users/3"),
+        Some(AppRoute::Admin(AdminRoute::User { id: 3 }))
+    );
+    assert_eq!(AppRoute::from_path("/administrator"), None);
+    assert_eq!(AppRoute::Admin(AdminRoute::Dashboard).to_path(), "/admin");
+    assert_eq!(AppRoute::Admin(AdminRoute::User { id: 3 }).to_path(), "/admin/users/3");
+}
+
+#[test]
+fn it_resolves_paths_in_scopes() {
+    assert_eq!(router::strip_base("/admin", "/admin/users"), Some("/users"));
+    assert_eq!(router::strip_base("/admin/", "/admin"), Some("/"));
+    assert_eq!(router::strip_base("", "/users"), Some("/users"));
+    assert_eq!(router::strip_base("/admin", "/users"), None);
+    assert_eq!(router::join("/admin", "/users/3"), "/admin/users/3");
+    assert_eq!(router::join("/admin", "/"), "/admin");
+    assert_eq!(router::join("", "/"), "/");
+}
+
+fn decide(guard: &Guard<Route>, route: &Route) -> Vec<Decision> {
+    let decisions = Rc::new(RefCell::new(Vec::new()));
+    let collect = decisions.clone();
+    let callback = Callback::from(move |decision| collect.borrow_mut().push(decision));
+    guard.check(route, Navigation::new(callback));
+    let result = decisions.borrow().clone();
+    result
+}
+
+#[test]
+fn it_guards_navigations() {
+    let guard = Guard::from(|route: &Route, navigation: Navigation| {
+        match *route {
+            Route::Home => navigation.allow(),
+            Route::Post { .. } => navigation.redirect(&Route::Home),
+            // Dropped navigations are blocked
+            _ => {}
+        }
+    });
+    assert_eq!(decide(&guard, &Route::Home), vec![Decision::Allow]);
+    assert_eq!(decide(&guard, &Route::Post { id: 1 }), vec![Decision::Redirect("/".into())]);
+    assert_eq!(decide(&guard, &Route::NotFound), vec![Decision::Block]);
+    assert_eq!(decide(&Guard::default(), &Route::NotFound), vec![Decision::Allow]);
+}
+
+#[test]
+fn it_puts_redirects_into_views() {
+    let view: Html<()> = html! {
+        <main>
+            <Router<Route>: render=|route| match route {
+                Route::NotFound => html! { <div><Redirect<Route>: to=Route::Home, /></div> },
+                _ => html! { <p>{ "Page" }</p> },
+            }, guard=|_: &Route, navigation: Navigation| navigation.allow(), />
+        </main>
+    };
+    assert_eq!(view.childs.len(), 1);
+}
+
+#[derive(Clone, Debug, PartialEq, Routable)]
+enum ShopRoute {
+    #[at("/products/{category}")]
+    Products { category: String, #[query] filter: BTreeMap<String, u32> },
+    #[at("/search")]
+    Search { #[query] query: HashMap<String, String> },
+}
+
+#[test]
+fn it_binds_query_parameters() {
+    let mut filter = BTreeMap::new();
+    filter.insert("min".to_owned(), 10);
+    filter.insert("max".to_owned(), 20);
+    let route = ShopRoute::Products { category: "books".into(), filter };
+    assert_eq!(route.to_path(), "/products/books?max=20&min=10");
+    assert_eq!(ShopRoute::from_path("/products/books?min=10&max=20"), Some(route));
+    assert_eq!(
+        ShopRoute::from_path("/products/books"),
+        Some(ShopRoute::Products { category: "books".into(), filter: BTreeMap::new() })
+    );
+
+    let mut query = HashMap::new();
+    query.insert("q".to_owned(), "rust & wasm".to_owned());
+    let route = ShopRoute::Search { query };
+    assert_eq!(route.to_path(), "/search?q=rust%20%26%20wasm");
+    assert_eq!(ShopRoute::from_path("/search?q=rust+%26+wasm"), Some(route));
+}
+
+#[test]
+fn it_parses_queries() {
+    let query: BTreeMap<String, u32> = router::parse_query("page=2&page=3").unwrap();
+    assert_eq!(query.get("page"), Some(&3));
+    assert!(router::parse_query::<BTreeMap<String, u32>>("page=two").is_err());
+    assert_eq!(router::query_of("/list?page=2#top"), "page=2");
+    assert_eq!(router::hash_of("/list?page=2#top"), "top");
+    assert_eq!(router::to_query(&()), "");
+    assert_eq!(router::join("/shop", "/search?q=1"), "/shop/search?q=1");
+    assert_eq!(router::join("/shop", "/?q=1"), "/shop?q=1");
+}
+
+#[test]
+fn it_builds_hrefs_in_hash_mode() {
+    use yew::services::history::{self, HistoryMode};
+    assert_eq!(history::mode(), HistoryMode::Path);
+    assert_eq!(history::href("/posts/1"), "/posts/1");
+    history::set_mode(HistoryMode::Hash);
+    assert_eq!(history::href("/posts/1?tab=2"), "#/posts/1?tab=2");
+    history::set_mode(HistoryMode::Path);
+}
+
+#[derive(Clone, Debug, PartialEq, Routable)]
+enum SplitRoute {
+    #[at("/")]
+    Home,
+    #[at("/reports/{year}")]
+    #[chunk("/chunks/reports.js")]
+    Reports { year: u32 },
+    #[at("/admin")]
+    #[chunk("/chunks/admin.js")]
+    Admin(AdminRoute),
+}
+
+#[test]
+fn it_declares_chunks_of_routes() {
+    assert_eq!(SplitRoute::Home.chunk(), None);
+    assert_eq!(SplitRoute::Reports { year: 2018 }.chunk(), Some("/chunks/reports.js"));
+    assert_eq!(SplitRoute::Admin(AdminRoute::Dashboard).chunk(), Some("/chunks/admin.js"));
+    assert_eq!(SplitRoute::from_path("/reports/2018"), Some(SplitRoute::Reports { year: 2018 }));
+    assert_eq!(SplitRoute::Reports { year: 2018 }.to_path(), "/reports/2018");
+    assert_eq!(Route::Home.chunk(), None);
+}
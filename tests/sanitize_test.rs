@@ -0,0 +1,65 @@
+#![cfg(feature = "sanitize")]
+
+#[cfg_attr(feature = "markdown", macro_use)]
+extern crate yew;
+
+use yew::sanitize::{self, HtmlSanitizer};
+
+#[test]
+fn it_keeps_allowed_markup_only() {
+    let html = "<p onclick=\"steal()\" class=\"note\" title='A &quot;note&quot;'>Hi <b>there</b>\
+                <script>alert(1)</script><style>p { color: red }</style><!-- a comment --></p>\
+                <div><em>unclosed <svg><g>a < b & c &amp; d</p>";
+    assert_eq!(sanitize::clean(html),
+               "<p title=\"A &quot;note&quot;\">Hi <b>there</b></p><div><em>unclosed a &lt; b &amp; c &amp; d</em></div>");
+
+    let sanitizer = HtmlSanitizer::new()
+        .allow_attributes("span", &["class", "onclick", "style"])
+        .remove_tags(&["img"]);
+    assert_eq!(sanitizer.clean("<span class=\"tag\" onclick=\"x()\" style=\"color: red\">yew</span><img src=\"a.png\">"),
+               "<span class=\"tag\">yew</span>");
+}
+
+#[test]
+fn it_drops_urls_which_run_code() {
+    let html = "<a href=\"java&#x09;script:alert(1)\">one</a>\
+                <a href=\" &#106;avascript&colon;alert(1)\">two</a>\
+                <a href=\"/docs\" rel=\"opener\">three</a>\
+                <img src=\"https://example.com/a.png\" onerror=alert(1) alt=\"\">\
+                <a href=\"data:text/html,<script>alert(1)</script>\">four</a>";
+    assert_eq!(sanitize::clean(html),
+               "<a>one</a><a>two</a><a href=\"/docs\" rel=\"noopener noreferrer\">three</a>\
+                <img src=\"https://example.com/a.png\" alt=\"\"><a>four</a>");
+    let sanitizer = HtmlSanitizer::new().allow_schemes(&["data"]).link_rel(None);
+    assert_eq!(sanitizer.clean("<a href=\"data:,hello\" rel=\"opener\">x</a>"), "<a href=\"data:,hello\">x</a>");
+}
+
+#[test]
+#[cfg(feature = "markdown")]
+fn it_renders_html_blocks_of_markdown() {
+    use yew::markdown;
+    use yew::virtual_dom::{VRaw, VTag};
+
+    let sanitizer = HtmlSanitizer::new();
+    let a: VTag<()> = markdown::render_with_html("<details onclick=\"x()\">\n<summary>More</summary>\n</details>\n\nSome <b>text</b>", &sanitizer);
+    let b: VTag<()> = html! {
+        <div class="markdown",>
+            { VRaw::trusted("<details>\n<summary>More</summary>\n</details>") }
+            <p>{ "Some <b>text</b>" }</p>
+        </div>
+    };
+    assert_eq!(a, b);
+}
+
+#[test]
+#[cfg(feature = "ssr")]
+fn it_cleans_unsanitized_raw_nodes_when_enforced() {
+    use yew::ssr;
+    use yew::virtual_dom::{VNode, VRaw};
+
+    let raw = || VNode::<()>::from(VRaw::trusted("<b onclick=\"x()\">hi</b>"));
+    assert_eq!(ssr::render_to_string(raw()), "<div><b onclick=\"x()\">hi</b></div>");
+    sanitize::enforce();
+    assert!(sanitize::is_enforced());
+    assert_eq!(ssr::render_to_string(raw()), "<div><b>hi</b></div>");
+}
@@ -0,0 +1,124 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use std::ops::Range;
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::services::mock::MockSelection;
+use yew::services::selection::{Selection, SelectionHandle, SelectionService};
+use yew::testing::TestRenderer;
+use yew::virtual_dom::NodeRef;
+
+enum Msg {
+    Selected(Option<Selection>),
+    ShowComment,
+    Dismiss,
+}
+
+struct Article {
+    selection: SelectionService<Msg>,
+    article: NodeRef,
+    quote: Option<Range<usize>>,
+    _listener: SelectionHandle,
+}
+
+impl Component for Article {
+    type Msg = Msg;
+    type Properties = ();
+
+    fn create(_: (), sender: AppSender<Msg>) -> Self {
+        let article = NodeRef::default();
+        let mut selection = SelectionService::new(sender);
+        let targets = vec![article.clone()];
+        let listener = selection.listen(&targets, Msg::Selected);
+        Article {
+            selection,
+            article,
+            quote: None,
+            _listener: listener,
+        }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Selected(Some(ref selection)) if !selection.collapsed && selection.anchor == Some(self.article.clone()) => {
+                self.quote = selection.offsets.clone();
+            }
+            Msg::Selected(_) => self.quote = None,
+            Msg::ShowComment => {
+                let quote = self.quote.clone().expect("comment without a quote");
+                self.selection.select(&self.article, quote);
+            }
+            Msg::Dismiss => self.selection.clear(),
+        }
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        html! {
+            <div>
+                <article ref=&self.article,>{ "Lorem ipsum dolor" }</article>
+                { for self.quote.iter().map(|_| html! {
+                    <button id="comment", onclick=|_| Msg::ShowComment,>{ "Comment" }</button>
+                }) }
+                <button id="dismiss", onclick=|_| Msg::Dismiss,>{ "Dismiss" }</button>
+            </div>
+        }
+    }
+}
+
+#[test]
+fn it_sends_selections_of_targets() {
+    let document = MockSelection::new();
+    let _installed = document.install();
+    let mut article = TestRenderer::<Article>::new(());
+    assert!(article.root().find("#comment").is_none());
+
+    let target = article.component().article.clone();
+    document.set(Some(Selection {
+        text: "ipsum".to_owned(),
+        anchor: Some(target.clone()),
+        focus: Some(target.clone()),
+        offsets: Some(6..11),
+        ..Selection::default()
+    }));
+    article.flush();
+    assert_eq!(article.component().quote, Some(6..11));
+    assert!(article.root().find("#comment").is_some());
+
+    // Selections outside of targets aren't quoted
+    document.set(Some(Selection { text: "Comment".to_owned(), ..Selection::default() }));
+    article.flush();
+    assert_eq!(article.component().quote, None);
+    assert!(article.component().selection.get(&[target]).is_some());
+}
+
+#[test]
+fn it_selects_and_clears_text() {
+    let document = MockSelection::new();
+    let _installed = document.install();
+    let mut article = TestRenderer::<Article>::new(());
+    let target = article.component().article.clone();
+    article.sender().send(Msg::Selected(Some(Selection {
+        text: "Lorem".to_owned(),
+        anchor: Some(target.clone()),
+        focus: Some(target.clone()),
+        offsets: Some(0..5),
+        ..Selection::default()
+    })));
+    article.flush();
+    article.click("#comment");
+    article.flush();
+    let selection = document.current().unwrap();
+    assert_eq!(selection.anchor, Some(target));
+    assert_eq!(selection.offsets, Some(0..5));
+    assert_eq!(article.component().quote, Some(0..5));
+
+    article.click("#dismiss");
+    article.flush();
+    assert_eq!(document.current(), None);
+    assert_eq!(article.component().quote, None);
+    drop(article);
+    assert_eq!(document.listeners(), 0);
+}
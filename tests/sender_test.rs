@@ -0,0 +1,10 @@
+extern crate yew;
+
+use yew::html::ThreadSender;
+
+fn assert_thread_safe<T: Send + Sync + Clone>() {}
+
+#[test]
+fn thread_sender_is_thread_safe() {
+    assert_thread_safe::<ThreadSender<String>>();
+}
@@ -0,0 +1,113 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::services::backend::StorageBackend;
+use yew::services::mock::MockStorage;
+use yew::services::storage::Scope;
+use yew::settings::{self, SettingsHandle, SettingsService};
+use yew::testing::TestRenderer;
+
+settings! {
+    #[derive(Debug, Clone, PartialEq)]
+    struct Preferences in "preferences", version 2 {
+        theme: String = "light".into(),
+        font_size: u32 = 14,
+        compact: bool = false,
+    }
+    migrations {
+        0 => |value| settings::rename(value, "size", "font_size"),
+        1 => |value| settings::rename(value, "dark", "theme"),
+    }
+}
+
+enum Msg {
+    Enlarge,
+    Reset,
+    Changed(Preferences),
+}
+
+struct Page {
+    settings: SettingsService<Preferences, Msg>,
+    preferences: Preferences,
+    _listener: SettingsHandle,
+}
+
+impl Component for Page {
+    type Msg = Msg;
+    type Properties = ();
+
+    fn create(_: (), sender: AppSender<Msg>) -> Self {
+        let mut settings = SettingsService::new(sender);
+        let listener = settings.listen(Msg::Changed);
+        let preferences = settings.load();
+        Page { settings, preferences, _listener: listener }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Enlarge => {
+                self.settings.update(|preferences| {
+                    preferences.theme = "dark".into();
+                    preferences.font_size += 2;
+                });
+            }
+            Msg::Reset => self.settings.reset(),
+            Msg::Changed(preferences) => self.preferences = preferences,
+        }
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        html! { <p>{ &self.preferences.theme }</p> }
+    }
+}
+
+#[test]
+fn it_saves_settings_and_notifies_listeners() {
+    let storage = MockStorage::new();
+    let _installed = storage.install();
+    let mut page = TestRenderer::<Page>::new(());
+    let mut other = TestRenderer::<Page>::new(());
+    assert_eq!(page.component().preferences, Preferences::default());
+
+    page.send(Msg::Enlarge);
+    other.flush();
+    assert_eq!(other.component().preferences.font_size, 16);
+    assert_eq!(other.component().preferences, page.component().preferences);
+    assert_eq!(
+        storage.get(Scope::Local, "preferences").unwrap(),
+        r#"{"settings":{"compact":false,"font_size":16,"theme":"dark"},"version":2}"#
+    );
+
+    page.send(Msg::Reset);
+    other.flush();
+    assert_eq!(other.component().preferences, Preferences::default());
+    assert_eq!(storage.get(Scope::Local, "preferences"), None);
+}
+
+#[test]
+fn it_migrates_stored_settings() {
+    let storage = MockStorage::new();
+    let _installed = storage.install();
+
+    // A value stored before the settings had versions
+    storage.set(Scope::Local, "preferences", r#"{"dark":"dark","size":12,"compact":"yes"}"#);
+    let page = TestRenderer::<Page>::new(());
+    assert_eq!(page.component().preferences, Preferences {
+        theme: "dark".into(),
+        font_size: 12,
+        compact: false,
+    });
+    assert_eq!(
+        storage.get(Scope::Local, "preferences").unwrap(),
+        r#"{"settings":{"compact":"yes","font_size":12,"theme":"dark"},"version":2}"#
+    );
+
+    storage.set(Scope::Local, "preferences", r#"{"settings":{"dark":"light","font_size":18},"version":1}"#);
+    let page = TestRenderer::<Page>::new(());
+    assert_eq!(page.component().preferences.theme, "light");
+    assert_eq!(page.component().preferences.font_size, 18);
+}
@@ -0,0 +1,131 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use std::time::Duration;
+use yew::html::{self, AppSender, Component, Html, ShouldRender};
+use yew::services::mock::MockClock;
+use yew::testing::{TestNode, TestRenderer};
+
+#[derive(Default)]
+struct RowProps {
+    label: &'static str,
+    clock: MockClock,
+}
+
+/// A row which takes 4ms to create.
+struct Row {
+    label: &'static str,
+}
+
+impl Component for Row {
+    type Msg = ();
+    type Properties = RowProps;
+
+    fn create(props: RowProps, _: AppSender<()>) -> Self {
+        props.clock.advance(Duration::from_millis(4));
+        Row { label: props.label }
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html<()> {
+        html! { <span>{ self.label }</span> }
+    }
+}
+
+enum Msg {
+    Show(Vec<&'static str>),
+}
+
+#[derive(Default)]
+struct ListProps {
+    rows: Vec<&'static str>,
+    clock: MockClock,
+}
+
+struct List {
+    rows: Vec<&'static str>,
+    clock: MockClock,
+}
+
+impl Component for List {
+    type Msg = Msg;
+    type Properties = ListProps;
+
+    fn create(props: ListProps, _: AppSender<Msg>) -> Self {
+        List { rows: props.rows, clock: props.clock }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Show(rows) => self.rows = rows,
+        }
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let row = |label: &&'static str| html! {
+            <li><Row: label=*label, clock=self.clock.clone(), /></li>
+        };
+        html! { <ul>{ for self.rows.iter().map(row) }</ul> }
+    }
+}
+
+/// Returns labels of created rows and `_` for placeholders.
+fn labels(list: &TestRenderer<List>) -> Vec<String> {
+    list.root().children().iter()
+        .map(|node| match *node {
+            TestNode::Text(ref text) if text.is_empty() => "_".to_owned(),
+            ref row => row.text(),
+        })
+        .collect()
+}
+
+/// Renders rows with a budget of 10ms.
+fn sliced(clock: &MockClock, rows: Vec<&'static str>) -> TestRenderer<List> {
+    html::set_time_slicing(Some(Duration::from_millis(10)));
+    TestRenderer::<List>::new(ListProps { rows, clock: clock.clone() })
+}
+
+#[test]
+fn it_leaves_placeholders_when_the_budget_is_spent() {
+    let clock = MockClock::new();
+    let _installed = clock.install();
+    let list = sliced(&clock, vec!["a", "b", "c", "d", "e"]);
+
+    // Rows are created at 0, 4 and 8ms, the budget ends at 10ms
+    assert_eq!(labels(&list), vec!["a", "b", "c", "_", "_"]);
+}
+
+#[test]
+fn it_fills_placeholders_in_order_in_next_frames() {
+    let clock = MockClock::new();
+    let _installed = clock.install();
+    let mut list = sliced(&clock, vec!["a", "b", "c", "d", "e", "f", "g"]);
+    assert_eq!(labels(&list), vec!["a", "b", "c", "_", "_", "_", "_"]);
+
+    assert!(list.run_frame());
+    assert_eq!(labels(&list), vec!["a", "b", "c", "d", "e", "f", "_"]);
+    assert!(!list.run_frame());
+    assert_eq!(labels(&list), vec!["a", "b", "c", "d", "e", "f", "g"]);
+    assert_eq!(list.renders(), 1);
+}
+
+#[test]
+fn it_cancels_pending_nodes_which_are_rendered_over() {
+    let clock = MockClock::new();
+    let _installed = clock.install();
+    let mut list = sliced(&clock, vec!["a", "b", "c", "d", "e"]);
+    assert_eq!(labels(&list), vec!["a", "b", "c", "_", "_"]);
+
+    // The place of a pending row is taken by another row and the last one is removed.
+    // The item of it has an ancestor, but the new row in it is left for the frame.
+    list.send(Msg::Show(vec!["a", "b", "c", "x"]));
+    assert_eq!(labels(&list), vec!["a", "b", "c", ""]);
+    assert!(!list.run_frame());
+    assert_eq!(labels(&list), vec!["a", "b", "c", "x"]);
+}
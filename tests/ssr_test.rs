@@ -0,0 +1,140 @@
+#![cfg(feature = "ssr")]
+
+#[macro_use]
+extern crate yew;
+
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use std::path::PathBuf;
+use yew::ssr;
+use yew::virtual_dom::VTag;
+
+struct Badge {
+    label: String,
+}
+
+#[derive(Default)]
+struct BadgeProps {
+    label: String,
+}
+
+impl Component for Badge {
+    type Msg = ();
+    type Properties = BadgeProps;
+
+    fn create(props: BadgeProps, _: AppSender<()>) -> Self {
+        Badge { label: props.label }
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html<()> {
+        html! {
+            <span class="badge",>{ &self.label }</span>
+        }
+    }
+}
+
+#[test]
+fn it_renders_tags_to_string() {
+    let a: VTag<()> = html! {
+        <div class="card active", id="main",>
+            <input type="text", value="a \"quoted\" value", disabled=false, />
+            <p onclick=|_| (),>{ "1 < 2" }{ " & more" }</p>
+            <select value="b",>
+                <option value="a",>{ "A" }</option>
+                <option value="b",>{ "B" }</option>
+            </select>
+            <Badge: label="new", />
+        </div>
+    };
+
+    let html = ssr::render_to_string(a);
+    assert_eq!(html, concat!(
+        r#"<div class="active card" id="main">"#,
+        r#"<input type="text" value="a &quot;quoted&quot; value">"#,
+        r#"<p>1 &lt; 2<!----> &amp; more</p>"#,
+        r#"<select><option value="a">A</option><option selected="" value="b">B</option></select>"#,
+        r#"<span class="badge">new</span>"#,
+        r#"</div>"#,
+    ));
+}
+
+#[test]
+fn it_prerenders_pages() {
+    let pages = ssr::prerender(&["/", "/docs/intro"], |path| path.to_owned(), |path: &String| -> Html<()> {
+        html! { <h1>{ path }</h1> }
+    });
+
+    assert_eq!(pages[1].html, "<h1>/docs/intro</h1>");
+    assert_eq!(pages[0].file_path(), Some(PathBuf::from("index.html")));
+    assert_eq!(pages[1].file_path(), Some(PathBuf::from("docs/intro/index.html")));
+    assert_eq!(pages[1].to_document("<body><!--yew--></body>"), "<body><h1>/docs/intro</h1></body>");
+
+    let wrong = ssr::Page { path: "/../secret".into(), html: "".into() };
+    assert_eq!(wrong.file_path(), None);
+}
+
+#[test]
+fn it_streams_pending_subtrees() {
+    let mut stream = ssr::Stream::new(Vec::new());
+    let user = stream.spawn(|| "Alice".to_owned());
+    let page: Html<()> = html! {
+        <div>
+            { ssr::await_node(user, html! { <p>{ "Loading" }</p> }, |name: String| html! { <p>{ name }</p> }) }
+        </div>
+    };
+    stream.render(page).unwrap();
+    let out = String::from_utf8(stream.finish().unwrap()).unwrap();
+
+    assert!(out.starts_with("<div><!--yew-await-0--><p>Loading</p><!--/yew-await-0--></div><script>"));
+    assert!(out.ends_with(r#"<template id="yew-chunk-0"><p>Alice</p></template><script>__yewSwap(0);</script>"#));
+}
+
+#[test]
+fn it_breaks_closing_tags_in_scripts() {
+    let state: VTag<()> = html! {
+        <script type="application/json",>{ r#"{"bio":"</script><img src=x onerror=alert(1)>"}"# }</script>
+    };
+    assert_eq!(ssr::render_to_string(state), concat!(
+        r#"<script type="application/json">"#,
+        r#"{"bio":"<\/script><img src=x onerror=alert(1)>"}"#,
+        r#"</script>"#,
+    ));
+}
+
+#[test]
+fn it_renders_values_of_textareas_as_text() {
+    let notes: VTag<()> = html! { <textarea value="\nfirst & second",></textarea> };
+    // The leading line break would be dropped by a parser without the extra one
+    assert_eq!(ssr::render_to_string(notes), "<textarea>\n\nfirst &amp; second</textarea>");
+}
+
+#[test]
+fn it_streams_pages_after_a_dropped_stream() {
+    let mut dropped = ssr::Stream::new(Vec::new());
+    let first = dropped.spawn(|| "Alice".to_owned());
+    let second = dropped.spawn(|| "Bob".to_owned());
+    let page: Html<()> = html! {
+        <div>
+            { ssr::await_node(first, html! { <p>{ "Loading" }</p> }, |name: String| html! { <p>{ name }</p> }) }
+            { ssr::await_node(second, html! { <p>{ "Loading" }</p> }, |name: String| html! { <p>{ name }</p> }) }
+        </div>
+    };
+    dropped.render(page).unwrap();
+    drop(dropped);
+
+    let mut stream = ssr::Stream::new(Vec::new());
+    let user = stream.spawn(|| "Carol".to_owned());
+    let page: Html<()> = html! {
+        <div>
+            { ssr::await_node(user, html! { <p>{ "Loading" }</p> }, |name: String| html! { <p>{ name }</p> }) }
+        </div>
+    };
+    stream.render(page).unwrap();
+    let out = String::from_utf8(stream.finish().unwrap()).unwrap();
+
+    assert!(out.ends_with(r#"<template id="yew-chunk-0"><p>Carol</p></template><script>__yewSwap(0);</script>"#));
+    assert!(!out.contains("Alice") && !out.contains("Bob"));
+}
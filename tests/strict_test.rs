@@ -0,0 +1,99 @@
+#![cfg(all(feature = "testing", feature = "strict"))]
+
+#[macro_use]
+extern crate yew;
+
+use std::cell::Cell;
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::strict::{self, Mistake};
+use yew::testing::TestRenderer;
+
+struct List {
+    items: Vec<u32>,
+    sender: AppSender<Msg>,
+    renders: Cell<u32>,
+}
+
+enum Msg {
+    Set(Vec<u32>),
+    Notify,
+}
+
+impl Component for List {
+    type Msg = Msg;
+    type Properties = ();
+
+    fn create(_: (), sender: AppSender<Msg>) -> Self {
+        List {
+            items: Vec::new(),
+            sender,
+            renders: Cell::new(0),
+        }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Set(items) => self.items = items,
+            Msg::Notify => return false,
+        }
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        if self.items.contains(&0) {
+            self.sender.clone().send(Msg::Notify);
+        }
+        let renders = self.renders.get() + 1;
+        self.renders.set(renders);
+        let counter = if self.items.contains(&1) { renders.to_string() } else { String::new() };
+        html! {
+            <ul>
+                { for self.items.iter().map(|item| html! { <li data-flip-key=item,>{ item }</li> }) }
+                <li>{ counter }</li>
+            </ul>
+        }
+    }
+}
+
+#[test]
+fn it_warns_about_mistakes_of_views() {
+    strict::clear();
+    let mut list = TestRenderer::<List>::new(());
+    list.send(Msg::Set(vec![2, 3]));
+    assert!(strict::warnings().is_empty(), "{:?}", strict::warnings());
+
+    list.send(Msg::Set(vec![2, 3, 2]));
+    let warnings = strict::warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].mistake, Mistake::DuplicateKey);
+    assert!(warnings[0].source.ends_with("List"));
+    assert_eq!(warnings[0].detail, "li `2`");
+
+    strict::clear();
+    list.send(Msg::Set(vec![1]));
+    let mistakes: Vec<_> = strict::warnings().into_iter().map(|warning| warning.mistake).collect();
+    assert_eq!(mistakes, vec![Mistake::UnstableView]);
+
+    // A mistake is reported once until it's cleared
+    strict::clear();
+    list.send(Msg::Set(vec![0]));
+    list.send(Msg::Set(vec![0, 4]));
+    let mistakes: Vec<_> = strict::warnings().into_iter().map(|warning| warning.mistake).collect();
+    assert_eq!(mistakes, vec![Mistake::SentInView]);
+}
+
+#[test]
+fn it_warns_about_messages_of_destroyed_components() {
+    strict::clear();
+    let list = TestRenderer::<List>::new(());
+    let mut sender = list.sender();
+    sender.send(Msg::Notify);
+    assert!(strict::warnings().is_empty());
+
+    drop(list);
+    sender.send(Msg::Notify);
+    let warnings = strict::warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].mistake, Mistake::LeakedTask);
+    assert!(warnings[0].source.ends_with("Msg"));
+}
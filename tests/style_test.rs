@@ -0,0 +1,38 @@
+#[macro_use]
+extern crate yew;
+
+use yew::html::Style;
+use yew::virtual_dom::VTag;
+
+#[test]
+fn it_builds_styles() {
+    let style = Style::new()
+        .set("color", "red")
+        .set("width", format!("{}px", 10))
+        .set_if(false, "display", "none")
+        .set_opt("height", None::<&str>)
+        .merge(Style::new().set("color", "blue"));
+    assert_eq!(style.to_string(), "color: blue; width: 10px;");
+    assert_eq!(style.get("width"), Some("10px"));
+}
+
+#[test]
+fn it_skips_wrong_declarations() {
+    let style = Style::new()
+        .set("Color", "red")
+        .set("width", "10px; height: 0");
+    assert!(style.is_empty());
+}
+
+#[test]
+fn it_sets_style_attribute() {
+    let a: VTag<()> = html! {
+        <div style=Style::new().set("color", "red"),></div>
+    };
+
+    let b: VTag<()> = html! {
+        <div style="color: red;",></div>
+    };
+
+    assert_eq!(a, b);
+}
@@ -0,0 +1,43 @@
+extern crate yew;
+
+use std::time::Duration;
+use yew::format::Nothing;
+use yew::subscription::Subscription;
+
+enum Msg {
+    Tick,
+    Resized,
+    Received,
+    Status,
+}
+
+struct Model {
+    running: bool,
+}
+
+fn subscriptions(model: &Model) -> Vec<Subscription<Msg>> {
+    let mut subscriptions = vec![
+        Subscription::window("resize", |_| Msg::Resized),
+        Subscription::websocket("ws://localhost/feed", |_: Nothing| Msg::Received, |_| Msg::Status),
+    ];
+    if model.running {
+        subscriptions.push(Subscription::interval(Duration::from_secs(1), || Msg::Tick));
+        subscriptions.push(Subscription::interval(Duration::from_secs(1), || Msg::Tick).keyed("clock"));
+    }
+    subscriptions
+}
+
+fn keys(model: &Model) -> Vec<String> {
+    subscriptions(model).iter().map(|subscription| subscription.key().to_owned()).collect()
+}
+
+#[test]
+fn it_declares_subscriptions_of_a_model() {
+    assert_eq!(keys(&Model { running: false }), vec!["window:resize", "websocket:ws://localhost/feed"]);
+    assert_eq!(keys(&Model { running: true }), vec![
+        "window:resize",
+        "websocket:ws://localhost/feed",
+        "interval:1s",
+        "interval:1s#clock",
+    ]);
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "testing")]
+
+extern crate yew;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use yew::callback::Callback;
+use yew::components::tabs::{Activation, Tabs, TabsProps};
+use yew::focus::{roving_index, Orientation};
+use yew::testing::TestRenderer;
+
+fn selected(tabs: &TestRenderer<Tabs>) -> Vec<&str> {
+    tabs.root().find_all("[role=tab]").iter().map(|tab| tab.attribute("aria-selected").unwrap()).collect()
+}
+
+fn focusable(tabs: &TestRenderer<Tabs>) -> Vec<&str> {
+    tabs.root().find_all("[role=tab]").iter().map(|tab| tab.attribute("tabindex").unwrap()).collect()
+}
+
+#[test]
+fn it_moves_between_items_with_keys() {
+    let horizontal = Orientation::Horizontal;
+    assert_eq!(roving_index("ArrowRight", 0, 3, horizontal, &[]), Some(1));
+    assert_eq!(roving_index("ArrowRight", 2, 3, horizontal, &[]), Some(0));
+    assert_eq!(roving_index("ArrowLeft", 0, 3, horizontal, &[2]), Some(1));
+    assert_eq!(roving_index("ArrowRight", 0, 3, horizontal, &[1]), Some(2));
+    assert_eq!(roving_index("ArrowDown", 0, 3, horizontal, &[]), None);
+    assert_eq!(roving_index("ArrowDown", 0, 3, Orientation::Vertical, &[]), Some(1));
+    assert_eq!(roving_index("Home", 2, 3, horizontal, &[0]), Some(1));
+    assert_eq!(roving_index("End", 0, 3, horizontal, &[]), Some(2));
+    assert_eq!(roving_index("End", 0, 0, horizontal, &[]), None);
+}
+
+#[test]
+fn it_selects_tabs_of_uncontrolled_tabs() {
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let log = changes.clone();
+    let mut tabs = TestRenderer::<Tabs>::new(TabsProps {
+        id: "settings".into(),
+        count: 3,
+        disabled: vec![1],
+        onchange: Callback::from(move |index| log.borrow_mut().push(index)),
+        ..TabsProps::default()
+    });
+    assert_eq!(selected(&tabs), vec!["true", "false", "false"]);
+    let panel = tabs.root().find("[role=tabpanel]").unwrap();
+    assert_eq!(panel.attribute("aria-labelledby"), Some("settings-tab-0"));
+    assert_eq!(tabs.root().find("#settings-tab-0").unwrap().attribute("aria-controls"), Some("settings-panel-0"));
+
+    // The disabled tab is skipped
+    tabs.key_down("#settings-tab-0", "ArrowRight");
+    assert_eq!(selected(&tabs), vec!["false", "false", "true"]);
+    assert_eq!(focusable(&tabs), vec!["-1", "-1", "0"]);
+    tabs.click("#settings-tab-1");
+    tabs.click("#settings-tab-0");
+    assert_eq!(*changes.borrow(), vec![2, 0]);
+}
+
+#[test]
+fn it_asks_parents_of_controlled_tabs() {
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let log = changes.clone();
+    let props = TabsProps {
+        count: 3,
+        selected: Some(1),
+        activation: Activation::Manual,
+        onchange: Callback::from(move |index| log.borrow_mut().push(index)),
+        ..TabsProps::default()
+    };
+    let mut tabs = TestRenderer::<Tabs>::new(props.clone());
+    assert_eq!(selected(&tabs), vec!["false", "true", "false"]);
+
+    // Arrows only move focus with manual activation
+    tabs.key_down("#tabs-tab-1", "End");
+    assert_eq!(focusable(&tabs), vec!["-1", "-1", "0"]);
+    assert!(changes.borrow().is_empty());
+    tabs.key_down("#tabs-tab-2", "Enter");
+    assert_eq!(*changes.borrow(), vec![2]);
+    assert_eq!(selected(&tabs), vec!["false", "true", "false"]);
+
+    tabs.change(TabsProps { selected: Some(2), ..props });
+    assert_eq!(selected(&tabs), vec!["false", "false", "true"]);
+}
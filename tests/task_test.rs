@@ -0,0 +1,57 @@
+extern crate yew;
+
+use std::cell::Cell;
+use std::rc::Rc;
+use yew::services::{Task, TaskBag};
+
+struct FakeTask {
+    active: Rc<Cell<bool>>,
+    canceled: Rc<Cell<u32>>,
+}
+
+impl Task for FakeTask {
+    fn is_active(&self) -> bool {
+        self.active.get()
+    }
+
+    fn cancel(&mut self) {
+        self.active.set(false);
+        self.canceled.set(self.canceled.get() + 1);
+    }
+}
+
+fn fake_task(canceled: &Rc<Cell<u32>>) -> (FakeTask, Rc<Cell<bool>>) {
+    let active = Rc::new(Cell::new(true));
+    let task = FakeTask {
+        active: active.clone(),
+        canceled: canceled.clone(),
+    };
+    (task, active)
+}
+
+#[test]
+fn it_cancels_running_tasks_when_dropped() {
+    let canceled = Rc::new(Cell::new(0));
+    {
+        let mut tasks = TaskBag::new();
+        let (first, _) = fake_task(&canceled);
+        let (second, finished) = fake_task(&canceled);
+        tasks.push(first);
+        tasks.push(second);
+        finished.set(false);
+        assert_eq!(tasks.active(), 1);
+    }
+    assert_eq!(canceled.get(), 1);
+}
+
+#[test]
+fn it_cancels_tasks_once() {
+    let canceled = Rc::new(Cell::new(0));
+    let mut tasks = TaskBag::new();
+    let (task, _) = fake_task(&canceled);
+    tasks.push(task);
+    tasks.clear();
+    assert_eq!(tasks.active(), 0);
+    drop(tasks);
+    assert_eq!(canceled.get(), 1);
+}
@@ -0,0 +1,273 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use yew::html::{AppSender, Component, Html, InputData, KeyData, ShouldRender};
+use std::{env, fs, panic, process};
+use yew::testing::{self, TestNode, TestRenderer};
+
+struct Badge {
+    label: String,
+}
+
+#[derive(Default)]
+struct BadgeProps {
+    label: String,
+}
+
+impl Component for Badge {
+    type Msg = ();
+    type Properties = BadgeProps;
+
+    fn create(props: BadgeProps, _: AppSender<()>) -> Self {
+        Badge { label: props.label }
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html<()> {
+        html! {
+            <span class="badge",>{ &self.label }</span>
+        }
+    }
+}
+
+struct Counter {
+    value: u32,
+    title: String,
+}
+
+enum Msg {
+    Increment,
+    Twice,
+    Nothing,
+}
+
+#[derive(Default)]
+struct CounterProps {
+    title: String,
+}
+
+impl Component for Counter {
+    type Msg = Msg;
+    type Properties = CounterProps;
+
+    fn create(props: CounterProps, mut sender: AppSender<Msg>) -> Self {
+        sender.send(Msg::Increment);
+        Counter { value: 0, title: props.title }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Increment => {
+                self.value += 1;
+                true
+            }
+            Msg::Twice => {
+                self.value += 1;
+                true
+            }
+            Msg::Nothing => false,
+        }
+    }
+
+    fn change(&mut self, props: CounterProps) -> ShouldRender {
+        self.title = props.title;
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let done = self.value > 2;
+        html! {
+            <div id="counter", class=("counter", if done { "done" } else { "" }),>
+                <h1>{ &self.title }</h1>
+                <button onclick=|_| Msg::Increment,>{ self.value }</button>
+                <input type="text", value="fixed", />
+                <Badge: label="new", />
+            </div>
+        }
+    }
+}
+
+fn counter() -> TestRenderer<Counter> {
+    TestRenderer::new(CounterProps { title: "Clicks".into() })
+}
+
+#[test]
+fn it_renders_with_messages_of_create() {
+    let counter = counter();
+    assert_eq!(counter.component().value, 1);
+    assert_eq!(counter.renders(), 2);
+    assert_eq!(counter.root().find("button").unwrap().text(), "1");
+    assert_eq!(counter.root().text(), "Clicks1new");
+}
+
+#[test]
+fn it_renders_after_messages() {
+    let mut counter = counter();
+    assert!(counter.send(Msg::Increment));
+    assert_eq!(counter.root().find("button").unwrap().text(), "2");
+    assert!(!counter.send(Msg::Nothing));
+    assert_eq!(counter.renders(), 3);
+
+    let mut sender = counter.sender();
+    sender.send(Msg::Increment);
+    sender.send(Msg::Twice);
+    assert!(counter.flush());
+    assert_eq!(counter.renders(), 4);
+    assert_eq!(counter.root().find("button").unwrap().text(), "4");
+    assert!(counter.root().find("#counter.counter.done").is_some());
+}
+
+#[test]
+fn it_finds_nodes_with_selectors() {
+    let counter = counter();
+    let root = counter.root();
+    assert!(root.find("div.done").is_none());
+    assert!(root.find("div#counter h1").is_some());
+    assert!(root.find("span h1").is_none());
+    assert_eq!(root.find_all("div *").len(), 4);
+    assert!(root.find("input[type=text][value='fixed']").is_some());
+    assert!(root.find("input[value=other]").is_none());
+    let button = root.find("button").and_then(TestNode::element).unwrap();
+    assert!(button.listens("onclick"));
+    assert!(!button.listens("oninput"));
+}
+
+#[test]
+fn it_renders_nested_components() {
+    let counter = counter();
+    let badge = counter.root().find_component::<Badge>().unwrap();
+    assert_eq!(badge.text(), "new");
+    let span = badge.find("span").and_then(TestNode::element).unwrap();
+    assert!(span.has_class("badge"));
+    assert_eq!(counter.root().find_components::<Counter>().len(), 0);
+}
+
+#[test]
+fn it_changes_properties() {
+    let mut counter = counter();
+    assert!(counter.change(CounterProps { title: "Taps".into() }));
+    assert_eq!(counter.root().find("h1").unwrap().text(), "Taps");
+}
+
+struct Form {
+    name: String,
+    submitted: Vec<String>,
+    clicks: Vec<&'static str>,
+}
+
+enum FormMsg {
+    Input(String),
+    Key(String),
+    Submit,
+    Click(&'static str),
+}
+
+impl Component for Form {
+    type Msg = FormMsg;
+    type Properties = ();
+
+    fn create(_: (), _: AppSender<FormMsg>) -> Self {
+        Form { name: String::new(), submitted: Vec::new(), clicks: Vec::new() }
+    }
+
+    fn update(&mut self, msg: FormMsg) -> ShouldRender {
+        match msg {
+            FormMsg::Input(value) => self.name = value,
+            FormMsg::Key(ref key) if key == "Enter" => self.submitted.push(self.name.clone()),
+            FormMsg::Key(_) => return false,
+            FormMsg::Submit => self.submitted.push(self.name.clone()),
+            FormMsg::Click(target) => self.clicks.push(target),
+        }
+        true
+    }
+
+    fn view(&self) -> Html<FormMsg> {
+        html! {
+            <form onsubmit=|_| FormMsg::Submit, onclick=|_| FormMsg::Click("form"),>
+                <input class="name",
+                       value=&self.name,
+                       oninput=|e: InputData| FormMsg::Input(e.value),
+                       onkeypress=|e: KeyData| FormMsg::Key(e.key), />
+                <button class="bubbling", onclick.propagate=|_| FormMsg::Click("bubbling"),>{ "Bubbling" }</button>
+                <button class="stopping", onclick=|_| FormMsg::Click("stopping"),>{ "Stopping" }</button>
+                <p>{ &self.name }</p>
+            </form>
+        }
+    }
+}
+
+#[test]
+fn it_simulates_typing() {
+    let mut form = TestRenderer::<Form>::new(());
+    assert!(form.type_text("input.name", "Ann"));
+    assert_eq!(form.component().name, "Ann");
+    assert_eq!(form.root().find("input").and_then(TestNode::element).unwrap().value, Some("Ann".into()));
+    assert_eq!(form.root().find("p").unwrap().text(), "Ann");
+    form.key_press("input.name", "Enter");
+    form.input("input.name", "Bob");
+    form.submit("form");
+    assert_eq!(form.component().submitted, vec!["Ann".to_string(), "Bob".to_string()]);
+}
+
+#[test]
+fn it_simulates_bubbling_clicks() {
+    let mut form = TestRenderer::<Form>::new(());
+    form.click("button.bubbling");
+    form.click("button.stopping");
+    form.click("p");
+    assert_eq!(form.component().clicks, vec!["bubbling", "form", "stopping", "form"]);
+}
+
+#[test]
+#[should_panic(expected = "no element matches")]
+fn it_panics_without_target() {
+    let mut form = TestRenderer::<Form>::new(());
+    form.click("a.missing");
+}
+
+#[test]
+fn it_prints_snapshots() {
+    let node = testing::render(html! {
+        <ul class=("list", "dark"), id="items",>
+            <li onclick=|_| (), checked=true,>{ "first \"one\"" }</li>
+            <li></li>
+            <Badge: label="new", />
+        </ul>
+    });
+    let expected = "\
+<ul class=\"dark list\" id=\"items\">
+  <li checked onclick>
+    \"first \\\"one\\\"\"
+  </li>
+  <li />
+  <Badge>
+    <span class=\"badge\">
+      \"new\"
+    </span>
+  </Badge>
+</ul>
+";
+    assert_eq!(node.to_string(), expected);
+}
+
+#[test]
+fn it_compares_snapshots_with_golden_files() {
+    let counter = counter();
+    testing::assert_snapshot("tests/snapshots/counter.snap", counter.root());
+
+    let path = env::temp_dir().join(format!("yew-snapshot-{}.snap", process::id()));
+    let _ = fs::remove_file(&path);
+    testing::assert_snapshot(&path, counter.root());
+    testing::assert_snapshot(&path, counter.root());
+    let changed = TestRenderer::<Counter>::new(CounterProps { title: "Taps".into() });
+    let changed = changed.root().clone();
+    let result = panic::catch_unwind(|| testing::assert_snapshot(&path, &changed));
+    fs::remove_file(&path).unwrap();
+    let message = result.unwrap_err().downcast::<String>().unwrap();
+    assert!(message.contains("-     \"Clicks\"\n+     \"Taps\"\n"), "{}", message);
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "testing")]
+
+extern crate yew;
+
+use yew::services::mock::MockText;
+use yew::services::text::{Line, TextService};
+
+fn texts(lines: &[Line]) -> Vec<&str> {
+    lines.iter().map(|line| line.text.as_str()).collect()
+}
+
+#[test]
+fn it_wraps_words_to_the_width() {
+    let font = MockText::new();
+    let _font = font.install();
+    let text = TextService::new("16px monospace");
+    let lines = text.wrap("the quick  brown fox\njumps\n\nover", 80.0);
+    assert_eq!(texts(&lines), vec!["the quick", "brown fox", "jumps", "", "over"]);
+    assert_eq!(lines[0].width, 72.0);
+    assert_eq!(text.height("the quick brown fox", 80.0, 20.0), 40.0);
+
+    // A word longer than a line is broken between characters
+    let lines = text.wrap("an abcdefghijklmnop", 48.0);
+    assert_eq!(texts(&lines), vec!["an", "abcdef", "ghijkl", "mnop"]);
+}
+
+#[test]
+fn it_caches_widths_and_uses_the_font() {
+    let font = MockText::new();
+    font.set_width(' ', 4.0);
+    let _font = font.install();
+    let mut text = TextService::new("16px monospace");
+    assert_eq!(text.width("ab cd"), 36.0);
+    assert_eq!(text.width("ab cd"), 36.0);
+    assert_eq!(font.measured(), 1);
+    assert_eq!(text.metrics("ab").height(), 16.0);
+
+    font.set_char_width(10.0);
+    assert_eq!(text.width("ab cd"), 36.0);
+    text.set_font("20px monospace");
+    assert_eq!(text.width("ab cd"), 44.0);
+    assert_eq!(text.font(), "20px monospace");
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::style::Style;
+use yew::theme::{self, Theme, ThemeHandle, ThemeService};
+use yew::testing::TestRenderer;
+
+#[test]
+fn it_bridges_values_to_custom_properties() {
+    let theme = Theme::dark();
+    let variables = theme.variables();
+    assert!(variables.contains(&("--color-background".to_owned(), "#0d1117".to_owned())));
+    assert!(variables.contains(&("--spacing-unit".to_owned(), "8px".to_owned())));
+    assert_eq!(theme.spacing.get(3), "24px");
+    assert_eq!(theme::var("color-primary"), "var(--color-primary)");
+}
+
+struct Button {
+    theme: ThemeService<Msg>,
+    _listener: ThemeHandle,
+}
+
+enum Msg {
+    Toggle,
+    Switched,
+}
+
+impl Component for Button {
+    type Msg = Msg;
+    type Properties = ();
+
+    fn create(_: (), sender: AppSender<Msg>) -> Self {
+        let mut theme = ThemeService::new(sender);
+        let listener = theme.listen(|_| Msg::Switched);
+        Button { theme, _listener: listener }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Toggle => {
+                self.theme.toggle_dark();
+                false
+            }
+            Msg::Switched => true,
+        }
+    }
+
+    fn view(&self) -> Html<Msg> {
+        let current = theme::current();
+        html! {
+            <button style=Style::new().set("color", &current.colors.primary), onclick=|_| Msg::Toggle,>
+                { &current.name }
+            </button>
+        }
+    }
+}
+
+#[test]
+fn it_renders_listeners_again_when_theme_switches() {
+    theme::add_theme(Theme::light());
+    theme::add_theme(Theme::dark());
+    assert!(!theme::set_theme("sepia"));
+    let mut button = TestRenderer::<Button>::new(());
+    assert_eq!(button.root().find("button").unwrap().text(), "light");
+    button.click("button");
+    button.flush();
+    assert_eq!(theme::current().name, "dark");
+    let button = button.root().find("button").unwrap();
+    assert_eq!(button.text(), "dark");
+    assert_eq!(button.attribute("style"), Some("color: #4493f8;"));
+}
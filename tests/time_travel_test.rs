@@ -0,0 +1,109 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate yew;
+extern crate serde;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::testing::TestRenderer;
+use yew::time_travel::TimeTravel;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Msg {
+    Add(i64),
+}
+
+struct Counter {
+    value: i64,
+}
+
+// The model of a debugger is the component, only its value is recorded
+impl Serialize for Counter {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Counter {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        i64::deserialize(deserializer).map(|value| Counter { value })
+    }
+}
+
+impl Component for Counter {
+    type Msg = Msg;
+    type Properties = ();
+
+    fn create(_: (), _: AppSender<Msg>) -> Self {
+        Counter { value: 0 }
+    }
+
+    fn update(&mut self, msg: Msg) -> ShouldRender {
+        match msg {
+            Msg::Add(amount) => self.value += amount,
+        }
+        true
+    }
+
+    fn view(&self) -> Html<Msg> {
+        html! { <p>{ self.value }</p> }
+    }
+}
+
+fn record() -> (TestRenderer<Counter>, TimeTravel<Msg>) {
+    let mut counter = TestRenderer::<Counter>::new(());
+    let debugger = TimeTravel::new(counter.sender(), 10);
+    counter.middleware(debugger.clone());
+    for amount in 1..4 {
+        counter.send(Msg::Add(amount));
+    }
+    (counter, debugger)
+}
+
+#[test]
+fn it_records_messages_with_snapshots() {
+    let (counter, debugger) = record();
+
+    assert_eq!(debugger.len(), 4);
+    assert_eq!(debugger.messages(), vec![None, Some(Msg::Add(1)), Some(Msg::Add(2)), Some(Msg::Add(3))]);
+    assert_eq!(debugger.snapshot(2), Some(3.into()));
+    assert_eq!(debugger.position(), Some(3));
+    assert_eq!(counter.root().text(), "6");
+    assert_eq!(debugger.export(), r#"[{"msg":null,"snapshot":0},{"msg":"Add(1)","snapshot":1},{"msg":"Add(2)","snapshot":3},{"msg":"Add(3)","snapshot":6}]"#);
+}
+
+#[test]
+fn it_jumps_back_and_drops_messages_while_paused() {
+    let (mut counter, debugger) = record();
+    debugger.back();
+    counter.flush();
+    assert!(debugger.is_paused());
+    assert_eq!(counter.root().text(), "3");
+
+    counter.send(Msg::Add(100));
+    assert_eq!(counter.component().value, 3);
+    assert_eq!(debugger.len(), 4);
+
+    debugger.jump(0);
+    counter.flush();
+    assert_eq!(counter.root().text(), "0");
+    debugger.resume();
+    counter.flush();
+    assert!(!debugger.is_paused());
+    assert_eq!(counter.root().text(), "6");
+}
+
+#[test]
+fn it_replays_messages_after_the_shown_state() {
+    let (mut counter, debugger) = record();
+    debugger.jump(1);
+    counter.flush();
+    debugger.replay();
+    counter.flush();
+
+    assert!(!debugger.is_paused());
+    assert_eq!(counter.component().value, 6);
+    assert_eq!(debugger.len(), 4);
+    assert_eq!(debugger.messages()[3], Some(Msg::Add(3)));
+}
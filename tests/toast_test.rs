@@ -0,0 +1,63 @@
+#![cfg(feature = "testing")]
+
+extern crate yew;
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+use yew::callback::Callback;
+use yew::components::Toaster;
+use yew::components::toaster::ToasterProps;
+use yew::services::mock::MockClock;
+use yew::testing::TestRenderer;
+use yew::toast::{self, Toast};
+
+fn messages(toaster: &TestRenderer<Toaster>) -> Vec<String> {
+    toaster.root().find_all(".toast-message").iter().map(|node| node.text()).collect()
+}
+
+#[test]
+fn it_shows_and_expires_toasts() {
+    let clock = MockClock::new();
+    let _clock = clock.install();
+    let mut toaster = TestRenderer::<Toaster>::new(ToasterProps { max: 2, ..ToasterProps::default() });
+
+    toast::push(Toast::info("Saved").timeout(Duration::from_secs(1)));
+    toast::push(Toast::error("Offline"));
+    toast::push(Toast::success("Synced"));
+    toaster.flush();
+    assert_eq!(messages(&toaster), vec!["Offline", "Synced"]);
+    let alert = toaster.root().find(".toast-error").and_then(|node| node.element()).unwrap();
+    assert_eq!(alert.attribute("role"), Some("alert"));
+
+    // Only shown toasts expire, errors are kept
+    clock.advance(Duration::from_secs(1));
+    toaster.flush();
+    assert_eq!(toast::toasts().len(), 3);
+    clock.advance(Duration::from_secs(4));
+    toaster.flush();
+    assert_eq!(messages(&toaster), vec!["Saved", "Offline"]);
+    clock.advance(Duration::from_secs(1));
+    toaster.flush();
+    assert_eq!(messages(&toaster), vec!["Offline"]);
+
+    toaster.click(".toast-close");
+    assert!(messages(&toaster).is_empty());
+    assert!(toast::toasts().is_empty());
+}
+
+#[test]
+fn it_calls_actions_of_toasts() {
+    let _clock = MockClock::new().install();
+    let undone = Rc::new(Cell::new(false));
+    let flag = undone.clone();
+    let mut toaster = TestRenderer::<Toaster>::new(ToasterProps::default());
+    let id = toast::push(Toast::warning("Deleted").action("Undo", Callback::from(move |_| flag.set(true))));
+    toaster.flush();
+    assert_eq!(toaster.root().find(".toast-action").unwrap().text(), "Undo");
+
+    toaster.click(".toast-action");
+    assert!(undone.get());
+    assert!(!toast::dismiss(id));
+    assert!(messages(&toaster).is_empty());
+}
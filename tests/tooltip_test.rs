@@ -0,0 +1,139 @@
+#![cfg(feature = "testing")]
+
+extern crate yew;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+use yew::callback::Callback;
+use yew::components::popover::{self, Popover, PopoverProps, Trigger};
+use yew::components::tooltip::{self, Tooltip, TooltipProps};
+use yew::position::{Layout, Rect, UNMEASURED_STYLE};
+use yew::services::layout::LayoutEvent;
+use yew::services::mock::{MockClock, MockLayout};
+use yew::testing::TestRenderer;
+
+fn near_bottom() -> Layout {
+    Layout {
+        anchor: Rect::new(100.0, 270.0, 50.0, 20.0),
+        floating: Rect::new(0.0, 0.0, 80.0, 30.0),
+        boundary: Rect::new(0.0, 0.0, 400.0, 300.0),
+    }
+}
+
+#[test]
+fn it_shows_tooltips_after_hovering() {
+    let clock = MockClock::new();
+    let layout = MockLayout::new();
+    let _clock = clock.install();
+    let _layout = layout.install();
+    let mut hint = TestRenderer::<Tooltip>::new(TooltipProps {
+        id: "save-hint".into(),
+        text: "Saves the draft".into(),
+        ..TooltipProps::default()
+    });
+    assert_eq!(hint.root().find("[role=tooltip]").unwrap().attribute("hidden"), Some("true"));
+    assert_eq!(hint.root().find("#save-hint").unwrap().text(), "Saves the draft");
+
+    hint.send(tooltip::Msg::Enter);
+    clock.advance(Duration::from_millis(299));
+    hint.flush();
+    assert_eq!(layout.tracked(), 0);
+    clock.advance(Duration::from_millis(1));
+    hint.flush();
+    assert_eq!(layout.anchor(0), Some("save-hint-anchor".to_string()));
+    let shown = hint.root().find("#save-hint").unwrap();
+    assert_ne!(shown.attribute("hidden"), Some("true"));
+    assert_eq!(shown.attribute("style"), Some(UNMEASURED_STYLE));
+
+    assert!(layout.measure(0, near_bottom()));
+    hint.flush();
+    let measured = hint.root().find("#save-hint").unwrap();
+    assert_eq!(measured.attribute("style"), Some("position: fixed; left: 85px; top: 232px; --arrow-offset: 40px;"));
+    assert_eq!(measured.attribute("data-side"), Some("top"));
+
+    // Moving onto the tooltip keeps it shown
+    hint.send(tooltip::Msg::Leave);
+    hint.send(tooltip::Msg::Enter);
+    clock.advance(Duration::from_millis(200));
+    hint.flush();
+    assert!(layout.is_tracked(0));
+    hint.send(tooltip::Msg::Leave);
+    clock.advance(Duration::from_millis(100));
+    hint.flush();
+    assert!(!layout.is_tracked(0));
+    assert_eq!(hint.root().find("#save-hint").unwrap().attribute("hidden"), Some("true"));
+}
+
+#[test]
+fn it_shows_tooltips_on_focus_until_escape() {
+    let clock = MockClock::new();
+    let layout = MockLayout::new();
+    let _clock = clock.install();
+    let _layout = layout.install();
+    let mut hint = TestRenderer::<Tooltip>::new(TooltipProps::default());
+    hint.send(tooltip::Msg::Focus);
+    assert!(layout.is_tracked(0));
+    hint.send(tooltip::Msg::Key("Escape".into()));
+    assert!(!layout.is_tracked(0));
+
+    // Dismissed tooltips wait until the pointer leaves or focus moves
+    hint.send(tooltip::Msg::Enter);
+    clock.advance(Duration::from_millis(300));
+    hint.flush();
+    assert_eq!(layout.tracked(), 1);
+    hint.send(tooltip::Msg::Blur);
+    hint.send(tooltip::Msg::Focus);
+    assert!(layout.is_tracked(1));
+    // The pointer is still over the anchor
+    hint.send(tooltip::Msg::Blur);
+    assert!(layout.is_tracked(1));
+    hint.send(tooltip::Msg::Leave);
+    clock.advance(Duration::from_millis(100));
+    hint.flush();
+    assert!(!layout.is_tracked(1));
+}
+
+#[test]
+fn it_opens_and_closes_popovers() {
+    let layout = MockLayout::new();
+    let _layout = layout.install();
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let log = changes.clone();
+    let props = PopoverProps {
+        id: "filters".into(),
+        label: "Filters".into(),
+        trigger: Trigger::Click,
+        onchange: Callback::from(move |open| log.borrow_mut().push(open)),
+        ..PopoverProps::default()
+    };
+    let mut filters = TestRenderer::<Popover>::new(props.clone());
+    let dialog = filters.root().find("[role=dialog]").unwrap();
+    assert_eq!(dialog.attribute("id"), Some("filters"));
+    assert_eq!(dialog.attribute("aria-label"), Some("Filters"));
+    assert_eq!(dialog.attribute("hidden"), Some("true"));
+
+    filters.send(popover::Msg::Toggle);
+    assert_eq!(layout.anchor(0), Some("filters-anchor".to_string()));
+    layout.measure(0, near_bottom());
+    filters.flush();
+    // There is no space below the anchor, so it's flipped
+    assert_eq!(filters.root().find("#filters").unwrap().attribute("data-side"), Some("top"));
+    assert!(layout.press_outside(0));
+    filters.flush();
+    assert!(!layout.is_tracked(0));
+    filters.send(popover::Msg::Toggle);
+    filters.send(popover::Msg::Key("Escape".into()));
+    assert!(!layout.is_tracked(1));
+    assert_eq!(*changes.borrow(), vec![true, false, true, false]);
+
+    // A controlled popover waits for the parent
+    let mut controlled = TestRenderer::<Popover>::new(PopoverProps { open: Some(false), ..props.clone() });
+    controlled.send(popover::Msg::Toggle);
+    assert_eq!(layout.tracked(), 2);
+    controlled.change(PopoverProps { open: Some(true), ..props });
+    assert!(layout.is_tracked(2));
+    controlled.send(popover::Msg::Layout(LayoutEvent::Outside));
+    assert!(layout.is_tracked(2));
+    assert_eq!(*changes.borrow(), vec![true, false, true, false, true, false]);
+}
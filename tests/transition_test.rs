@@ -0,0 +1,57 @@
+#![cfg(feature = "testing")]
+
+extern crate yew;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use yew::callback::Callback;
+use yew::components::Transition;
+use yew::components::transition::TransitionProps;
+use yew::testing::TestRenderer;
+
+fn classes(renderer: &TestRenderer<Transition>) -> String {
+    renderer.root().element().unwrap().classes.join(" ")
+}
+
+#[test]
+fn it_adds_classes_of_phases() {
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let props = |show| {
+        let entered = events.clone();
+        let left = events.clone();
+        TransitionProps {
+            show,
+            name: "fade".into(),
+            class: "modal".into(),
+            onentered: Callback::from(move |_| entered.borrow_mut().push("entered")),
+            onleft: Callback::from(move |_| left.borrow_mut().push("left")),
+            ..TransitionProps::default()
+        }
+    };
+    let mut transition = TestRenderer::<Transition>::new(props(false));
+    assert_eq!(transition.root().element().unwrap().attribute("hidden"), Some(""));
+
+    transition.change(props(true));
+    assert_eq!(classes(&transition), "fade-enter-active fade-enter-to modal");
+    transition.simulate("div", "ontransitionend", ());
+    assert_eq!(classes(&transition), "modal");
+    assert_eq!(transition.root().element().unwrap().attribute("hidden"), None);
+
+    transition.change(props(false));
+    assert_eq!(classes(&transition), "fade-leave-active fade-leave-to modal");
+    transition.simulate("div", "onanimationend", ());
+    assert_eq!(transition.root().element().unwrap().attribute("hidden"), Some(""));
+    assert_eq!(*events.borrow(), vec!["entered", "left"]);
+}
+
+#[test]
+fn it_appears_only_when_asked() {
+    let shown = TestRenderer::<Transition>::new(TransitionProps { show: true, ..TransitionProps::default() });
+    assert_eq!(classes(&shown), "");
+    let appearing = TestRenderer::<Transition>::new(TransitionProps {
+        show: true,
+        appear: true,
+        ..TransitionProps::default()
+    });
+    assert_eq!(classes(&appearing), "transition-enter-active transition-enter-to");
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "typescript")]
+
+#[macro_use]
+extern crate yew;
+
+use std::collections::HashMap;
+use yew::custom_element::{Attributes, CustomElement, Host};
+use yew::html::{AppSender, Component, Html, ShouldRender};
+use yew::typescript::{self, Declarations, TypeScript};
+
+typescript! {
+    /// Settings of a widget.
+    #[derive(Debug, Clone)]
+    pub struct Settings {
+        pub title: String,
+        pub limit: Option<u32>,
+        pub tags: Vec<Option<String>>,
+        pub sizes: HashMap<String, (u32, u32)>,
+    }
+}
+
+typescript! {
+    #[allow(dead_code)]
+    enum WidgetMsg {
+        Ready,
+        Configure(Settings),
+        Move(i32, i32),
+        Resize { width: u32, height: u32 },
+    }
+}
+
+#[test]
+fn it_declares_structs() {
+    assert_eq!(Settings::typescript(), "Settings");
+    assert_eq!(Settings::declaration().unwrap(), "export interface Settings {\n  \
+        title: string;\n  \
+        limit?: number | null;\n  \
+        tags: (string | null)[];\n  \
+        sizes: Record<string, [number, number]>;\n}\n");
+}
+
+#[test]
+fn it_declares_enums_like_serde() {
+    assert_eq!(WidgetMsg::declaration().unwrap(), "export type WidgetMsg =\n  \
+        | \"Ready\"\n  \
+        | { Configure: Settings }\n  \
+        | { Move: [number, number] }\n  \
+        | { Resize: { width: number; height: number } };\n");
+}
+
+struct Counter;
+
+impl Component for Counter {
+    type Msg = ();
+    type Properties = ();
+
+    fn create(_: (), _: AppSender<()>) -> Self {
+        Counter
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html<()> {
+        html! { <span></span> }
+    }
+}
+
+impl CustomElement for Counter {
+    fn observed_attributes() -> &'static [&'static str] {
+        &["step"]
+    }
+
+    fn properties(_: &Attributes, _: &Host) {}
+}
+
+#[test]
+fn it_declares_custom_elements() {
+    let mut declarations = Declarations::new();
+    declarations
+        .add::<Settings>()
+        .add::<Settings>()
+        .add::<u32>()
+        .element::<Counter>("yew-counter", &[("counter-change", typescript::name::<u32>())]);
+    let text = declarations.to_text();
+    assert_eq!(text.matches("export interface Settings").count(), 1);
+    assert!(text.contains("export interface YewCounterElementAttributes {\n  \"step\"?: string;\n}"));
+    assert!(text.contains("addEventListener(type: \"counter-change\", listener: (event: CustomEvent<number>) => void"));
+    assert!(text.contains("    \"yew-counter\": YewCounterElement;\n"));
+}
@@ -0,0 +1,51 @@
+extern crate yew;
+
+use yew::html::{KeyData, Modifiers};
+use yew::undo::{Edit, History, UndoMsg};
+
+struct Push(&'static str);
+
+impl Edit<Vec<&'static str>> for Push {
+    fn apply(&self, lines: &mut Vec<&'static str>) {
+        lines.push(self.0);
+    }
+
+    fn revert(&self, lines: &mut Vec<&'static str>) {
+        lines.pop();
+    }
+}
+
+#[test]
+fn it_undoes_and_redoes_changes() {
+    let mut lines = History::new(Vec::new(), 2);
+    lines.apply(Push("one"));
+    lines.update(|lines| lines.push("two"));
+    lines.apply(Push("three"));
+    assert_eq!(*lines, vec!["one", "two", "three"]);
+
+    // Only two changes are kept
+    assert!(lines.undo());
+    assert!(lines.handle(UndoMsg::Undo));
+    assert!(!lines.undo());
+    assert_eq!(*lines, vec!["one"]);
+
+    assert!(lines.redo());
+    assert_eq!(*lines, vec!["one", "two"]);
+    lines.set(vec!["reset"]);
+    assert!(!lines.can_redo());
+    assert!(lines.undo());
+    assert_eq!(*lines, vec!["one", "two"]);
+}
+
+#[test]
+fn it_maps_shortcuts_to_messages() {
+    let key = |key: &str, ctrl: bool, shift: bool| KeyData {
+        key: key.into(),
+        modifiers: Modifiers { ctrl, shift, ..Modifiers::default() },
+        ..KeyData::default()
+    };
+    assert_eq!(UndoMsg::from_key(&key("z", true, false)), Some(UndoMsg::Undo));
+    assert_eq!(UndoMsg::from_key(&key("Z", true, true)), Some(UndoMsg::Redo));
+    assert_eq!(UndoMsg::from_key(&key("y", true, false)), Some(UndoMsg::Redo));
+    assert_eq!(UndoMsg::from_key(&key("z", false, false)), None);
+}
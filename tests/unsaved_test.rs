@@ -0,0 +1,70 @@
+#![cfg(feature = "testing")]
+
+extern crate yew;
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use yew::callback::Callback;
+use yew::router::{Decision, Guard, Navigation};
+use yew::services::Task;
+use yew::services::mock::MockUnload;
+use yew::services::unsaved::{self, UnsavedService};
+
+fn decide(guard: &Guard<()>) -> Vec<Decision> {
+    let decisions = Rc::new(RefCell::new(Vec::new()));
+    let collect = decisions.clone();
+    let callback = Callback::from(move |decision| collect.borrow_mut().push(decision));
+    guard.check(&(), Navigation::new(callback));
+    let result = decisions.borrow().clone();
+    result
+}
+
+#[test]
+fn it_prompts_on_unloads_while_dirty() {
+    let page = MockUnload::new();
+    let _page = page.install();
+    let dirty = Rc::new(Cell::new(false));
+    let mut service = UnsavedService::new();
+    let predicate = dirty.clone();
+    let mut handle = service.register(move || predicate.get());
+    assert_eq!(page.listeners(), 1);
+    assert!(!page.unload());
+    assert!(!unsaved::is_unsaved());
+
+    dirty.set(true);
+    assert!(page.unload());
+    assert!(unsaved::is_unsaved());
+
+    handle.cancel();
+    assert!(!handle.is_active());
+    assert_eq!(page.listeners(), 0);
+    assert!(!page.unload());
+    assert!(!unsaved::is_unsaved());
+
+    drop(service.register(|| true));
+    assert_eq!(page.listeners(), 0);
+    assert!(!unsaved::is_unsaved());
+}
+
+#[test]
+fn it_confirms_navigations_while_dirty() {
+    let _page = MockUnload::new().install();
+    let answer = Rc::new(Cell::new(false));
+    let asked = Rc::new(Cell::new(0));
+    let (confirm, count) = (answer.clone(), asked.clone());
+    let guard = unsaved::guard_with(move |navigation: Navigation| {
+        count.set(count.get() + 1);
+        if confirm.get() {
+            navigation.allow();
+        }
+    });
+    assert_eq!(decide(&guard), vec![Decision::Allow]);
+    assert_eq!(asked.get(), 0);
+
+    let mut service = UnsavedService::new();
+    let _handle = service.register(|| true);
+    assert_eq!(decide(&guard), vec![Decision::Block]);
+    answer.set(true);
+    assert_eq!(decide(&guard), vec![Decision::Allow]);
+    assert_eq!(asked.get(), 2);
+}
@@ -0,0 +1,55 @@
+#[macro_use]
+extern crate yew;
+
+use std::collections::BTreeMap;
+use yew::url::Url;
+
+#[derive(Clone, Debug, PartialEq, Routable)]
+enum Route {
+    #[at("/")]
+    Home,
+    #[at("/posts/{id}")]
+    Post { id: u32 },
+}
+
+#[test]
+fn it_builds_urls_by_parts() {
+    let url = Url::with_origin("https://api.example.com/")
+        .path("/v1/users")
+        .segment("a/b c")
+        .query("tag", "rust & wasm")
+        .query("tag", 2)
+        .hash("top");
+    assert_eq!(url.to_string(), "https://api.example.com/v1/users/a%2Fb%20c?tag=rust%20%26%20wasm&tag=2#top");
+    assert_eq!(url.to_uri().unwrap().path(), "/v1/users/a%2Fb%20c");
+    assert_eq!(Url::new().to_string(), "/");
+    assert_eq!(Url::new().path("/docs/").to_string(), "/docs/");
+
+    let mut params = BTreeMap::new();
+    params.insert("page", "2");
+    params.insert("q", "yew");
+    assert_eq!(Url::new().path("search").params(&params).to_string(), "/search?page=2&q=yew");
+}
+
+#[test]
+fn it_parses_urls_back() {
+    let url = Url::parse("http://localhost:8080/v1/users/a%2Fb?tag=x+y&tag=z&page=3#/settings").unwrap();
+    assert_eq!(url.origin, Some("http://localhost:8080".into()));
+    assert_eq!(url.segments, vec!["v1", "users", "a/b"]);
+    assert_eq!(url.get("tag"), Some("x y"));
+    assert_eq!(url.get_all("tag"), vec!["x y", "z"]);
+    assert_eq!(url.get("missing"), None);
+    assert_eq!(url.hash, Some("/settings".into()));
+    let params: BTreeMap<String, String> = url.to_params().unwrap();
+    assert_eq!(params.get("page"), Some(&"3".to_owned()));
+    assert_eq!(Url::parse(&url.to_string()), Ok(url));
+    assert!(Url::parse("/bad%FF").is_err());
+}
+
+#[test]
+fn it_converts_routes() {
+    let url = Url::route(&Route::Post { id: 42 }).hash("comments");
+    assert_eq!(url.to_string(), "/posts/42#comments");
+    assert_eq!(url.to_route::<Route>(), Some(Route::Post { id: 42 }));
+    assert_eq!(Url::new().to_route::<Route>(), Some(Route::Home));
+}
@@ -0,0 +1,59 @@
+#[macro_use]
+extern crate yew;
+
+use yew::html::{AppSender, Callback, Component, Html, Link, ShouldRender};
+use yew::virtual_dom::{VComp, VTag};
+
+struct Button {
+    props: ButtonProps,
+}
+
+#[derive(Default)]
+struct ButtonProps {
+    label: String,
+    count: u32,
+    onclick: Callback<()>,
+}
+
+impl Component for Button {
+    type Msg = ();
+    type Properties = ButtonProps;
+
+    fn create(props: ButtonProps, _: AppSender<()>) -> Self {
+        Button { props }
+    }
+
+    fn update(&mut self, _: ()) -> ShouldRender {
+        self.props.onclick.emit(());
+        false
+    }
+
+    fn view(&self) -> Html<()> {
+        html! {
+            <button onclick=|_| (),>{ &self.props.label }{ self.props.count }</button>
+        }
+    }
+}
+
+enum Msg {
+    Clicked,
+}
+
+#[test]
+fn it_renders_component_tags() {
+    let label = "Click".to_owned();
+
+    let a: VTag<Msg> = html! {
+        <div>
+            <Button: label=&label, count=1, onclick=|_| Msg::Clicked, />
+        </div>
+    };
+
+    let b: VTag<Msg> = html! {
+        <div>
+            { VComp::new::<Button>(ButtonProps::default(), Link::new()) }
+        </div>
+    };
+
+    assert_eq!(a, b);
+}
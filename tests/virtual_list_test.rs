@@ -0,0 +1,34 @@
+extern crate yew;
+
+use std::rc::Rc;
+use yew::components::virtual_list::{Layout, RowHeights};
+
+#[test]
+fn it_finds_visible_rows_of_fixed_height() {
+    let layout = Layout::new(100_000, &RowHeights::Fixed(20.0));
+
+    assert_eq!(layout.total_height(), 2_000_000.0);
+    assert_eq!(layout.visible(0.0, 100.0, 0), 0..6);
+    assert_eq!(layout.visible(1000.0, 100.0, 2), 48..58);
+    assert_eq!(layout.visible(1_999_990.0, 100.0, 2), 99_997..100_000);
+    assert_eq!(layout.offset(48), 960.0);
+}
+
+#[test]
+fn it_finds_visible_rows_of_variable_height() {
+    let heights = RowHeights::Variable(Rc::new(|index| if index % 2 == 0 { 10.0 } else { 30.0 }));
+    let layout = Layout::new(10, &heights);
+
+    assert_eq!(layout.total_height(), 200.0);
+    assert_eq!(layout.offset(3), 50.0);
+    assert_eq!(layout.visible(45.0, 30.0, 0), 2..4);
+    assert_eq!(layout.visible(45.0, 30.0, 1), 1..5);
+}
+
+#[test]
+fn it_renders_nothing_for_empty_lists() {
+    let layout = Layout::new(0, &RowHeights::default());
+
+    assert_eq!(layout.total_height(), 0.0);
+    assert_eq!(layout.visible(0.0, 500.0, 3), 0..0);
+}
@@ -0,0 +1,29 @@
+#[macro_use]
+extern crate yew;
+
+use yew::virtual_dom::{VNode, VPortal, VTag};
+
+#[test]
+fn it_compares_portals() {
+    let mut a: VPortal<()> = VPortal::body();
+    a.add_child(VNode::from(html! { <div class="modal",></div> }));
+
+    let mut b: VPortal<()> = VPortal::new("body");
+    b.add_child(VNode::from(html! { <div class="modal",></div> }));
+
+    let mut c: VPortal<()> = VPortal::new("#overlay");
+    c.add_child(VNode::from(html! { <div class="modal",></div> }));
+
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
+
+#[test]
+fn it_keeps_portals_in_the_tree() {
+    let a: VTag<()> = html! {
+        <div>
+            { VPortal::body() }
+        </div>
+    };
+    assert_eq!(a.childs[0], VNode::from(VPortal::body()));
+}
@@ -0,0 +1,30 @@
+#[macro_use]
+extern crate yew;
+
+use yew::virtual_dom::{VRaw, VTag};
+
+#[test]
+fn it_compares_raw_nodes() {
+    let a: VTag<()> = html! {
+        <div>{ VRaw::trusted("<b>bold</b>") }</div>
+    };
+
+    let b: VTag<()> = html! {
+        <div>{ VRaw::trusted("<b>bold</b>") }</div>
+    };
+
+    let c: VTag<()> = html! {
+        <div>{ VRaw::trusted("<b>bold</b>").with_tag("span") }</div>
+    };
+
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
+
+#[test]
+fn it_sanitizes_raw_html() {
+    let strip = |html: &str| html.replace("<script>", "").replace("</script>", "");
+    let raw = VRaw::sanitized("<p>text</p><script>alert(1)</script>", &strip);
+    assert_eq!(raw.html(), "<p>text</p>alert(1)");
+    assert_eq!(raw.tag(), "div");
+}
@@ -1,7 +1,8 @@
 #[macro_use]
 extern crate yew;
 
-use yew::virtual_dom::VTag;
+use yew::html::{InputData, CustomListener};
+use yew::virtual_dom::{VTag, Patch, NodeRef, pooled};
 
 #[test]
 fn it_compares_tags() {
@@ -42,15 +43,15 @@ fn it_compares_text() {
 #[test]
 fn it_compares_attributes() {
     let a: VTag<()> = html! {
-        <div a="test",></div>
+        <div title="test",></div>
     };
 
     let b: VTag<()> = html! {
-        <div a="test",></div>
+        <div title="test",></div>
     };
 
     let c: VTag<()> = html! {
-        <div a="fail",></div>
+        <div title="fail",></div>
     };
 
     assert_eq!(a, b);
@@ -117,6 +118,19 @@ fn it_compares_values() {
     assert_ne!(a, c);
 }
 
+#[test]
+fn it_binds_values() {
+    let a: VTag<String> = html! {
+        <input bind=("test", String::from),/>
+    };
+
+    let b: VTag<String> = html! {
+        <input value="test", oninput=|e: InputData| e.value,/>
+    };
+
+    assert_eq!(a, b);
+}
+
 #[test]
 fn it_compares_kinds() {
     let a: VTag<()> = html! {
@@ -179,3 +193,235 @@ fn it_allows_aria_attributes() {
     assert!(a.attributes.contains_key("aria-controls"));
     assert_eq!(a.attributes.get("aria-controls"), Some(&"it-works".into()));
 }
+
+#[test]
+fn it_renders_branches() {
+    let flag = true;
+    let value = Some("test");
+
+    let a: VTag<()> = html! {
+        <div>
+            if (flag) { <p></p> } else { <span></span> }
+            if let (Some(value) = value) { { value } }
+            if (!flag) { <p></p> }
+        </div>
+    };
+
+    let b: VTag<()> = html! {
+        <div>
+            <p></p>
+            { "test" }
+            { "" }
+        </div>
+    };
+
+    assert_eq!(a, b);
+}
+
+#[test]
+fn it_renders_iterations() {
+    let items = ["one", "two"];
+
+    let a: VTag<()> = html! {
+        <ul>
+            for item in (items.iter()) { <li>{ item }</li> }
+            { for vec![html! { <li>{ "three" }</li> }] }
+        </ul>
+    };
+
+    let b: VTag<()> = html! {
+        <ul>
+            <li>{ "one" }</li>
+            <li>{ "two" }</li>
+            <li>{ "three" }</li>
+        </ul>
+    };
+
+    assert_eq!(a, b);
+}
+
+#[test]
+fn it_accepts_svg_attributes() {
+    let a: VTag<()> = html! {
+        <svg viewBox="0 0 10 10", width=10,>
+            <circle cx=5, cy=5, r=4, stroke-width=1, fill="none",></circle>
+        </svg>
+    };
+
+    let mut b: VTag<()> = VTag::new("svg");
+    b.add_attribute("viewBox", "0 0 10 10");
+    b.add_attribute("width", 10);
+    let mut circle: VTag<()> = VTag::new("circle");
+    circle.add_attribute("cx", 5);
+    circle.add_attribute("cy", 5);
+    circle.add_attribute("r", 4);
+    circle.add_attribute("stroke-width", 1);
+    circle.add_attribute("fill", "none");
+    b.add_child(circle.into());
+
+    assert_eq!(a, b);
+}
+
+#[test]
+fn it_renders_custom_elements() {
+    let a: VTag<String> = html! {
+        <date-picker mode="range", .value=7, on("date-change")=|detail: String| detail,>
+        </date-picker>
+    };
+
+    let mut b: VTag<String> = VTag::new("date-picker");
+    b.add_attribute("mode", "range");
+    b.add_property("value", 7);
+    b.add_listener(Box::new(CustomListener::new("date-change", |detail: String| detail)));
+
+    assert_eq!(a, b);
+
+    let c: VTag<String> = html! {
+        <date-picker mode="range", .value=8, on("date-change")=|detail: String| detail,>
+        </date-picker>
+    };
+
+    assert_ne!(a, c);
+}
+
+#[test]
+fn it_accepts_dynamic_attributes() {
+    let field = "email";
+    let dataset = vec![("data-row", 3), ("data-column", 5)];
+    let a: VTag<()> = html! {
+        <td aria-describedby="hint", [format!("data-{}", field)]=true, ..dataset,
+            ["wrong name"]="skipped",>
+        </td>
+    };
+
+    let mut b: VTag<()> = VTag::new("td");
+    b.add_attribute("aria-describedby", "hint");
+    b.add_attribute("data-email", true);
+    b.add_attribute("data-row", 3);
+    b.add_attribute("data-column", 5);
+
+    assert_eq!(a, b);
+}
+
+#[test]
+fn it_builds_tags_without_macro() {
+    let items = ["one", "two"];
+    let a: VTag<()> = html! {
+        <ul class="list compact", role="list",>
+            <li><input type="checkbox", value="one", checked=true, /></li>
+            for item in (items.iter()) { <li>{ item }</li> }
+        </ul>
+    };
+
+    let b: VTag<()> = VTag::new("ul")
+        .class("list compact")
+        .attr("role", "list")
+        .child(VTag::new("li").child(VTag::new("input").kind("checkbox").value("one").checked(true)))
+        .children(items.iter().map(|item| VTag::new("li").child(item)));
+
+    assert_eq!(a, b);
+}
+
+#[test]
+fn it_diffs_only_changed_attributes() {
+    let a: VTag<()> = html! {
+        <div class="row selected", id="row-1", title="first", hidden=true,></div>
+    };
+    let b: VTag<()> = html! {
+        <div class="row", id="row-1", title="changed", hidden=false,></div>
+    };
+
+    assert_eq!(b.diff(Some(&a)), vec![
+        Patch::RemoveClass("selected".into()),
+        Patch::RemoveAttribute("hidden".into()),
+        Patch::SetAttribute("title".into(), "changed".into()),
+    ]);
+    assert_eq!(b.diff(Some(&b)), vec![]);
+}
+
+#[test]
+fn it_diffs_new_elements_completely() {
+    let a: VTag<()> = html! {
+        <a class="link", href="/next",>{ "Next" }</a>
+    };
+
+    assert_eq!(a.diff(None), vec![
+        Patch::AddClass("link".into()),
+        Patch::SetAttribute("href".into(), "/next".into()),
+    ]);
+}
+
+#[test]
+fn it_diffs_values_of_uncontrolled_elements() {
+    let a: VTag<()> = html! { <option value="one",></option> };
+    let b: VTag<()> = html! { <option value="two",></option> };
+    let c: VTag<()> = html! { <option></option> };
+    let d: VTag<()> = html! { <input value="two", /> };
+    let e: VTag<()> = html! { <input /> };
+
+    assert_eq!(b.diff(Some(&a)), vec![Patch::SetValue("two".into())]);
+    assert_eq!(c.diff(Some(&a)), vec![Patch::RemoveValue]);
+    assert_eq!(d.diff(Some(&e)), vec![]);
+    assert_eq!(e.diff(Some(&d)), vec![Patch::RemoveValue]);
+}
+
+#[test]
+fn it_reuses_collections_of_recycled_tags() {
+    let tag: VTag<()> = html! {
+        <ul class="list",>
+            <li class="item", data-id=1, onclick=|_| (),>{ "one" }</li>
+        </ul>
+    };
+    let before = pooled();
+    tag.recycle();
+    // Classes of both tags, attributes of the item, vectors of children
+    // of both tags and listeners of the item
+    assert_eq!(pooled(), before + 6);
+
+    let _tag: VTag<()> = html! { <li class="item",></li> };
+    assert!(pooled() < before + 6);
+}
+
+#[test]
+fn it_sets_node_refs() {
+    let input = NodeRef::default();
+    let tag: VTag<()> = html! {
+        <input ref=&input, type="text", />
+    };
+    assert_eq!(tag.node_ref, Some(input.clone()));
+    assert_ne!(tag.node_ref, Some(NodeRef::default()));
+    // Nothing is rendered without a document
+    assert!(input.get().is_none());
+    assert!(!input.focus());
+}
+
+#[test]
+fn it_knows_names_of_elements_and_attributes() {
+    use yew::spec::{is_attribute, is_element};
+    assert!(is_element("div"));
+    assert!(is_element("clipPath"));
+    assert!(is_element("date-picker"));
+    assert!(!is_element("dvi"));
+    assert!(is_attribute("viewBox"));
+    assert!(is_attribute("aria-label"));
+    assert!(!is_attribute("clas"));
+}
+
+#[test]
+fn it_omits_attributes_with_none_values() {
+    let selected: Option<bool> = None;
+    let label: Option<&str> = Some("Close");
+    let a: VTag<()> = html! {
+        <button aria-selected=selected, aria-label=label, title=None::<String>, disabled=Some(true),
+            value=None::<String>, ["data-id"]=Some(7),>
+        </button>
+    };
+
+    let mut b: VTag<()> = VTag::new("button");
+    b.add_attribute("aria-label", "Close");
+    b.add_attribute("disabled", "true");
+    b.add_attribute("data-id", 7);
+
+    assert_eq!(a, b);
+    assert_eq!(a.value, None);
+}
@@ -0,0 +1,36 @@
+//! Derive macros of Yew. They're re-exported by the `yew` crate, use them from it.
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+#[macro_use]
+extern crate syn;
+
+mod properties;
+mod routable;
+
+use proc_macro::TokenStream;
+use syn::DeriveInput;
+
+/// Implements `Default` and checks of required fields for properties of a component.
+/// Fields without `#[prop_or(value)]` or `#[prop_or_default]` are required.
+#[proc_macro_derive(Properties, attributes(prop_or, prop_or_default))]
+pub fn derive_properties(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    properties::derive(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Implements `Routable` for an enum. Every variant has a pattern of a path in
+/// `#[at("/posts/{id}")]`, and `#[chunk("/chunks/admin.js")]` names a chunk.
+/// Fields are parameters of the path, a field with `#[query]` gets parameters
+/// of a query and a tuple variant with a single field nests other routes.
+#[proc_macro_derive(Routable, attributes(at, chunk, query))]
+pub fn derive_routable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    routable::derive(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
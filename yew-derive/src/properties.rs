@@ -0,0 +1,96 @@
+//! The derive of `Properties`. It implements `Default` with defaults of
+//! fields and `Declared`, which lists markers of required fields, so
+//! `html!` checks that all of them are set.
+
+use proc_macro2::TokenStream;
+use syn::{Data, DeriveInput, Error, Expr, Field, Fields, Result};
+
+/// A default of a field.
+enum Default {
+    /// The field has to be set by a parent, `Default::default()` is used until then.
+    Required,
+    /// `#[prop_or_default]`
+    Optional,
+    /// `#[prop_or(value)]`
+    Value(Expr),
+}
+
+pub fn derive(input: DeriveInput) -> Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(&input.generics, "generic properties aren't supported"));
+    }
+    let fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => &fields.named,
+            _ => return Err(Error::new_spanned(&input.ident, "properties must have named fields")),
+        },
+        _ => return Err(Error::new_spanned(&input.ident, "properties must be a struct")),
+    };
+
+    let mut defaults = Vec::new();
+    let mut markers = Vec::new();
+    let mut required = Vec::new();
+    for field in fields {
+        let name = field.ident.as_ref().expect("fields are named");
+        let (value, marker) = match default_of(field)? {
+            Default::Required => {
+                required.push(name);
+                (quote!(::std::default::Default::default()), quote!(#name))
+            }
+            Default::Optional => (quote!(::std::default::Default::default()), quote!(::yew::properties::Optional)),
+            Default::Value(expr) => (quote!(#expr), quote!(::yew::properties::Optional)),
+        };
+        defaults.push(quote!(#name: #value));
+        markers.push(quote!(pub #name: #marker));
+    }
+    // Markers are types named like fields, so an error of `html!` names the missing field
+    let list = required.iter().rev().fold(quote!(::yew::properties::Nil), |tail, name| {
+        quote!(::yew::properties::Cons<#name, #tail>)
+    });
+
+    let name = &input.ident;
+    Ok(quote! {
+        impl ::std::default::Default for #name {
+            fn default() -> Self {
+                #name {
+                    #(#defaults,)*
+                }
+            }
+        }
+
+        #[allow(non_camel_case_types)]
+        const _: () = {
+            #(pub struct #required;)*
+
+            pub struct Fields {
+                #(#markers,)*
+            }
+
+            impl ::yew::properties::Declared for #name {
+                type Fields = Fields;
+                type Required = #list;
+            }
+        };
+    })
+}
+
+/// Reads the default of a field from its attributes.
+fn default_of(field: &Field) -> Result<Default> {
+    let mut default = Default::Required;
+    for attr in &field.attrs {
+        let next = if attr.path().is_ident("prop_or_default") {
+            attr.meta.require_path_only()?;
+            Default::Optional
+        } else if attr.path().is_ident("prop_or") {
+            Default::Value(attr.parse_args()?)
+        } else {
+            continue;
+        };
+        if let Default::Required = default {
+            default = next;
+        } else {
+            return Err(Error::new_spanned(attr, "a property can have only one default"));
+        }
+    }
+    Ok(default)
+}
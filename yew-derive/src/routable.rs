@@ -0,0 +1,202 @@
+//! The derive of `Routable`. Variants are matched in their order with
+//! patterns of `#[at(..)]` attributes, and `#[chunk(..)]` names a script
+//! which has to be loaded before a view of a route is rendered.
+
+use proc_macro2::TokenStream;
+use syn::{Attribute, Data, DeriveInput, Error, Fields, Ident, LitStr, Result, Variant};
+
+/// A variant of a route with its parsed attributes.
+struct Route<'a> {
+    variant: &'a Variant,
+    pattern: LitStr,
+    chunk: Option<LitStr>,
+    /// The field which takes parameters of a query.
+    query: Option<&'a Ident>,
+}
+
+pub fn derive(input: DeriveInput) -> Result<TokenStream> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(&input.generics, "generic routes aren't supported"));
+    }
+    let variants = match input.data {
+        Data::Enum(ref data) => &data.variants,
+        _ => return Err(Error::new_spanned(&input.ident, "routes must be an enum")),
+    };
+    let routes = variants.iter().map(route_of).collect::<Result<Vec<_>>>()?;
+
+    let name = &input.ident;
+    let from = routes.iter().map(|route| from_path(name, route));
+    let to = routes.iter().map(|route| to_path(name, route));
+    let chunk = routes.iter().map(|route| chunk(name, route));
+    Ok(quote! {
+        impl ::yew::router::Routable for #name {
+            fn from_path(location: &str) -> ::std::option::Option<Self> {
+                let path = ::yew::router::path_of(location);
+                let query = ::yew::router::query_of(location);
+                #(#from)*
+                ::std::option::Option::None
+            }
+
+            fn to_path(&self) -> ::std::string::String {
+                match *self {
+                    #(#to)*
+                }
+            }
+
+            fn chunk(&self) -> ::std::option::Option<&'static str> {
+                match *self {
+                    #(#chunk)*
+                }
+            }
+        }
+    })
+}
+
+/// Reads attributes of a variant and checks its fields.
+fn route_of<'a>(variant: &'a Variant) -> Result<Route<'a>> {
+    let pattern = literal(&variant.attrs, "at")?
+        .ok_or_else(|| Error::new_spanned(variant, "a route needs a pattern like `#[at(\"/posts/{id}\")]`"))?;
+    let chunk = literal(&variant.attrs, "chunk")?;
+    let mut query = None;
+    match variant.fields {
+        Fields::Named(ref fields) => {
+            for field in &fields.named {
+                if field.attrs.iter().any(|attr| attr.path().is_ident("query")) {
+                    if query.is_some() {
+                        return Err(Error::new_spanned(field, "a route can have only one field of a query"));
+                    }
+                    query = field.ident.as_ref();
+                }
+            }
+        }
+        Fields::Unnamed(ref fields) if fields.unnamed.len() != 1 => {
+            return Err(Error::new_spanned(variant, "a tuple variant nests a single route"));
+        }
+        _ => {}
+    }
+    Ok(Route { variant, pattern, chunk, query })
+}
+
+/// Returns a string of an attribute like `#[at("/")]`.
+fn literal(attrs: &[Attribute], name: &str) -> Result<Option<LitStr>> {
+    let mut found = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident(name)) {
+        if found.is_some() {
+            return Err(Error::new_spanned(attr, format!("a route can have only one `#[{}]`", name)));
+        }
+        found = Some(attr.parse_args()?);
+    }
+    Ok(found)
+}
+
+/// Names of fields which are parameters of a path.
+fn params<'a>(route: &Route<'a>) -> Vec<&'a Ident> {
+    match route.variant.fields {
+        Fields::Named(ref fields) => fields.named.iter()
+            .filter_map(|field| field.ident.as_ref())
+            .filter(|ident| Some(*ident) != route.query)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn from_path(name: &Ident, route: &Route) -> TokenStream {
+    let variant = &route.variant.ident;
+    let pattern = &route.pattern;
+    match route.variant.fields {
+        Fields::Unit => quote! {
+            if ::yew::router::match_path(#pattern, path).is_some() {
+                return ::std::option::Option::Some(#name::#variant);
+            }
+        },
+        Fields::Named(_) => {
+            let params = params(route);
+            let keys = params.iter().map(|param| param.to_string());
+            let query = route.query.map(|field| quote! {
+                #field: ::yew::router::parse_query(query).unwrap_or_default(),
+            });
+            quote! {
+                if let ::std::option::Option::Some(params) = ::yew::router::match_path(#pattern, path) {
+                    let route = (|| -> ::std::option::Option<#name> {
+                        ::std::option::Option::Some(#name::#variant {
+                            #(#params: params.get(#keys)?.parse().ok()?,)*
+                            #query
+                        })
+                    })();
+                    if route.is_some() {
+                        return route;
+                    }
+                }
+            }
+        }
+        Fields::Unnamed(ref fields) => {
+            let nested = &fields.unnamed[0].ty;
+            quote! {
+                if let ::std::option::Option::Some(rest) = ::yew::router::strip_base(#pattern, path) {
+                    let rest = ::yew::router::with_query(rest, query);
+                    if let ::std::option::Option::Some(nested) = <#nested as ::yew::router::Routable>::from_path(&rest) {
+                        return ::std::option::Option::Some(#name::#variant(nested));
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn to_path(name: &Ident, route: &Route) -> TokenStream {
+    let variant = &route.variant.ident;
+    let pattern = &route.pattern;
+    match route.variant.fields {
+        Fields::Unit => quote! {
+            #name::#variant => ::yew::router::build_path(#pattern, &[]),
+        },
+        Fields::Named(_) => {
+            let params = params(route);
+            let keys = params.iter().map(|param| param.to_string());
+            let build = quote! {
+                ::yew::router::build_path(#pattern, &[#((#keys, ::std::string::ToString::to_string(#params)),)*])
+            };
+            let params = params.iter();
+            match route.query {
+                Some(query) => quote! {
+                    #name::#variant { #(ref #params,)* ref #query } => {
+                        let path = #build;
+                        ::yew::router::with_query(&path, &::yew::router::to_query(#query))
+                    }
+                },
+                None => quote! {
+                    #name::#variant { #(ref #params,)* } => #build,
+                },
+            }
+        }
+        Fields::Unnamed(ref fields) => {
+            let nested = &fields.unnamed[0].ty;
+            quote! {
+                #name::#variant(ref nested) => {
+                    let path = <#nested as ::yew::router::Routable>::to_path(nested);
+                    ::yew::router::join(#pattern, &path)
+                }
+            }
+        }
+    }
+}
+
+fn chunk(name: &Ident, route: &Route) -> TokenStream {
+    let variant = &route.variant.ident;
+    let chunk = match route.chunk {
+        Some(ref chunk) => quote!(::std::option::Option::Some(#chunk)),
+        None => quote!(::std::option::Option::None),
+    };
+    match route.variant.fields {
+        Fields::Unit => quote!(#name::#variant => #chunk,),
+        Fields::Named(_) => quote!(#name::#variant { .. } => #chunk,),
+        Fields::Unnamed(ref fields) => {
+            let nested = &fields.unnamed[0].ty;
+            quote! {
+                #name::#variant(ref nested) => #chunk.or_else(|| {
+                    <#nested as ::yew::router::Routable>::chunk(nested)
+                }),
+            }
+        }
+    }
+}